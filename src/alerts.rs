@@ -0,0 +1,506 @@
+//! Alert rules from `[alerts]`, e.g. `error_rate > 5% over 2m as critical`
+//!
+//! A rule is `<metric> <op> <threshold> [over <window>] [as <severity>]`.
+//! Request, DB-share and exception metrics are computed over the window;
+//! `health_score` is a point-in-time reading that has to stay past the
+//! threshold for the whole window. Rules are checked every few seconds, and
+//! each one reports an event when it starts firing and when it clears.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::context::RequestContextTracker;
+use crate::database::DatabaseHealth;
+use crate::exception::ExceptionTracker;
+use crate::stats::{RECENT_TIMINGS_RETENTION, StatsCollector};
+use crate::time_range::parse_span;
+use crate::ui::formatting::{format_duration, format_ms, format_window};
+
+/// How often rules are checked
+pub const ALERT_EVAL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Window used when a rule doesn't give one
+const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// Percentage of requests with a 4xx/5xx status
+    ErrorRate,
+    AvgResponseTime,
+    P95ResponseTime,
+    /// p95 of the slowest endpoint (`Controller#action`)
+    EndpointP95,
+    /// Percentage of request time spent in SQL
+    DbShare,
+    /// Exceptions seen
+    Exceptions,
+    /// Database health score (0-100)
+    HealthScore,
+}
+
+const METRICS: [AlertMetric; 7] = [
+    AlertMetric::ErrorRate,
+    AlertMetric::AvgResponseTime,
+    AlertMetric::P95ResponseTime,
+    AlertMetric::EndpointP95,
+    AlertMetric::DbShare,
+    AlertMetric::Exceptions,
+    AlertMetric::HealthScore,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Percent,
+    Millis,
+    Count,
+}
+
+impl AlertMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::ErrorRate => "error_rate",
+            AlertMetric::AvgResponseTime => "avg_response_time",
+            AlertMetric::P95ResponseTime => "p95_response_time",
+            AlertMetric::EndpointP95 => "endpoint_p95",
+            AlertMetric::DbShare => "db_share",
+            AlertMetric::Exceptions => "exceptions",
+            AlertMetric::HealthScore => "health_score",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        METRICS.into_iter().find(|metric| metric.name() == name)
+    }
+
+    /// Whether the metric is computed over the window rather than read instantly
+    pub fn is_windowed(&self) -> bool {
+        !matches!(self, AlertMetric::HealthScore)
+    }
+
+    fn unit(&self) -> Unit {
+        match self {
+            AlertMetric::ErrorRate | AlertMetric::DbShare => Unit::Percent,
+            AlertMetric::AvgResponseTime
+            | AlertMetric::P95ResponseTime
+            | AlertMetric::EndpointP95 => Unit::Millis,
+            AlertMetric::Exceptions | AlertMetric::HealthScore => Unit::Count,
+        }
+    }
+
+    pub fn format_value(&self, value: f64) -> String {
+        match self.unit() {
+            Unit::Percent => format!("{:.1}%", value),
+            Unit::Millis => format_ms(value),
+            Unit::Count => format!("{:.0}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparator {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Comparator::Gt),
+            ">=" => Some(Comparator::Ge),
+            "<" => Some(Comparator::Lt),
+            "<=" => Some(Comparator::Le),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+        }
+    }
+
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::Gt => value > threshold,
+            Comparator::Ge => value >= threshold,
+            Comparator::Lt => value < threshold,
+            Comparator::Le => value <= threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [
+            AlertSeverity::Info,
+            AlertSeverity::Warning,
+            AlertSeverity::Critical,
+        ]
+        .into_iter()
+        .find(|severity| severity.label() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparator: Comparator,
+    /// In the metric's unit: percent, milliseconds or a count
+    pub threshold: f64,
+    pub window: Duration,
+    pub severity: AlertSeverity,
+}
+
+impl AlertRule {
+    /// Parse `<metric> <op> <threshold> [over <window>] [as <severity>]`
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let words: Vec<&str> = rule.split_whitespace().collect();
+        let [metric, op, threshold, rest @ ..] = words.as_slice() else {
+            return Err(format!(
+                "Alert rule '{}' should look like '<metric> <op> <threshold> [over 2m] [as critical]'",
+                rule
+            ));
+        };
+
+        let metric = AlertMetric::from_name(metric).ok_or_else(|| {
+            let known: Vec<&str> = METRICS.iter().map(AlertMetric::name).collect();
+            format!(
+                "Unknown alert metric '{}' in '{}' (known: {})",
+                metric,
+                rule,
+                known.join(", ")
+            )
+        })?;
+        let comparator = Comparator::parse(op).ok_or_else(|| {
+            format!(
+                "Unknown comparison '{}' in '{}' (use >, >=, < or <=)",
+                op, rule
+            )
+        })?;
+        let threshold = parse_threshold(metric, threshold)
+            .map_err(|reason| format!("{} in '{}'", reason, rule))?;
+
+        let default_window = if metric.is_windowed() {
+            DEFAULT_WINDOW
+        } else {
+            Duration::ZERO
+        };
+        let mut parsed = Self {
+            metric,
+            comparator,
+            threshold,
+            window: default_window,
+            severity: AlertSeverity::default(),
+        };
+
+        let mut rest = rest.iter();
+        while let Some(keyword) = rest.next() {
+            let value = rest
+                .next()
+                .ok_or_else(|| format!("Missing value after '{}' in '{}'", keyword, rule))?;
+            match *keyword {
+                "over" => {
+                    parsed.window = parse_span(value)
+                        .map_err(|e| format!("{} in '{}'", e, rule))?
+                        .to_std()
+                        .map_err(|_| format!("Invalid window '{}' in '{}'", value, rule))?;
+                }
+                "as" => {
+                    parsed.severity = AlertSeverity::parse(value).ok_or_else(|| {
+                        format!(
+                            "Unknown severity '{}' in '{}' (use info, warning or critical)",
+                            value, rule
+                        )
+                    })?;
+                }
+                _ => {
+                    return Err(format!(
+                        "Unexpected '{}' in '{}' (expected 'over' or 'as')",
+                        keyword, rule
+                    ));
+                }
+            }
+        }
+
+        if metric.is_windowed() && parsed.window > RECENT_TIMINGS_RETENTION {
+            return Err(format!(
+                "Window in '{}' is longer than the {} of history kept",
+                rule,
+                format_duration(RECENT_TIMINGS_RETENTION.as_secs())
+            ));
+        }
+        if metric.is_windowed() && parsed.window.is_zero() {
+            return Err(format!("Window in '{}' must be longer than 0s", rule));
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// A threshold in the metric's unit; `5%`, `1s`, `800ms` and bare numbers
+fn parse_threshold(metric: AlertMetric, text: &str) -> Result<f64, String> {
+    let (number, scale) = match metric.unit() {
+        Unit::Percent => (text.strip_suffix('%').unwrap_or(text), 1.0),
+        Unit::Millis => match text.strip_suffix("ms") {
+            Some(ms) => (ms, 1.0),
+            None => match text.strip_suffix('s') {
+                Some(secs) => (secs, 1000.0),
+                None => (text, 1.0),
+            },
+        },
+        Unit::Count => (text, 1.0),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .map(|n| n * scale)
+        .ok_or_else(|| format!("Invalid threshold '{}' for {}", text, metric.name()))
+}
+
+impl fmt::Display for AlertRule {
+    /// The rule in its canonical form, e.g. `endpoint_p95 > 1000ms over 5m`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.metric.unit() {
+            Unit::Percent => "%",
+            Unit::Millis => "ms",
+            Unit::Count => "",
+        };
+        write!(
+            f,
+            "{} {} {}{}",
+            self.metric.name(),
+            self.comparator.symbol(),
+            self.threshold,
+            unit
+        )?;
+        if !self.window.is_zero() {
+            write!(f, " over {}", format_window(self.window))?;
+        }
+        Ok(())
+    }
+}
+
+/// A metric's value when last checked
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertReading {
+    pub value: f64,
+    /// What the value belongs to, e.g. the endpoint for `endpoint_p95`
+    pub subject: Option<String>,
+}
+
+impl AlertReading {
+    fn new(value: f64) -> Self {
+        Self {
+            value,
+            subject: None,
+        }
+    }
+}
+
+/// The trackers rules are checked against
+pub struct AlertSources<'a> {
+    pub stats: &'a StatsCollector,
+    pub requests: &'a RequestContextTracker,
+    pub db_health: &'a DatabaseHealth,
+    pub exceptions: &'a ExceptionTracker,
+}
+
+impl AlertSources<'_> {
+    /// The metric over the `window` before `now`; `None` when there's no data
+    fn read(&self, metric: AlertMetric, window: Duration, now: Instant) -> Option<AlertReading> {
+        match metric {
+            AlertMetric::ErrorRate => self
+                .stats
+                .requests_at(now, window)
+                .map(|w| AlertReading::new(w.error_rate())),
+            AlertMetric::AvgResponseTime => self
+                .stats
+                .requests_at(now, window)
+                .map(|w| AlertReading::new(w.avg_duration)),
+            AlertMetric::P95ResponseTime => self
+                .stats
+                .requests_at(now, window)
+                .map(|w| AlertReading::new(w.p95_duration)),
+            AlertMetric::EndpointP95 => {
+                self.requests
+                    .slowest_endpoint_p95(now, window)
+                    .map(|(endpoint, p95)| AlertReading {
+                        value: p95,
+                        subject: Some(endpoint),
+                    })
+            }
+            AlertMetric::DbShare => self
+                .stats
+                .db_share_at(now, window)
+                .map(|share| AlertReading::new(share.db_percent())),
+            AlertMetric::Exceptions => Some(AlertReading::new(
+                self.exceptions.count_at(now, window) as f64,
+            )),
+            AlertMetric::HealthScore => (!self.db_health.is_empty())
+                .then(|| AlertReading::new(f64::from(self.db_health.calculate_health_score()))),
+        }
+    }
+}
+
+/// Where a rule stands as of the last check
+#[derive(Debug, Clone, Default)]
+pub struct AlertState {
+    pub reading: Option<AlertReading>,
+    /// When the condition started holding, for point-in-time metrics
+    breached_since: Option<Instant>,
+    pub firing_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEventKind {
+    Fired,
+    Resolved { after: Duration },
+}
+
+/// A rule starting or stopping firing
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub kind: AlertEventKind,
+    pub reading: Option<AlertReading>,
+}
+
+impl AlertEvent {
+    /// One-line summary, as written to the log
+    pub fn message(&self) -> String {
+        match &self.kind {
+            AlertEventKind::Fired => {
+                let mut message = format!(
+                    "[alert] {} {}",
+                    self.rule.severity.label().to_uppercase(),
+                    self.rule
+                );
+                if let Some(reading) = &self.reading {
+                    message.push_str(&format!(
+                        " - now {}",
+                        self.rule.metric.format_value(reading.value)
+                    ));
+                    if let Some(subject) = &reading.subject {
+                        message.push_str(&format!(" ({})", subject));
+                    }
+                }
+                message
+            }
+            AlertEventKind::Resolved { after } => format!(
+                "[alert] resolved {} after {}",
+                self.rule,
+                format_duration(after.as_secs())
+            ),
+        }
+    }
+}
+
+/// Checks rules on a timer and tracks which are firing
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: Vec<AlertState>,
+    evaluated_at: Option<Instant>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            states: vec![AlertState::default(); rules.len()],
+            rules,
+            evaluated_at: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check the rules if [`ALERT_EVAL_INTERVAL`] has passed since the last check
+    pub fn poll(&mut self, sources: &AlertSources) -> Vec<AlertEvent> {
+        let now = Instant::now();
+        let due = self
+            .evaluated_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= ALERT_EVAL_INTERVAL);
+        if self.rules.is_empty() || !due {
+            return Vec::new();
+        }
+        self.evaluate(now, sources)
+    }
+
+    /// Check every rule as of `now`, returning the ones that fired or resolved
+    pub fn evaluate(&mut self, now: Instant, sources: &AlertSources) -> Vec<AlertEvent> {
+        self.evaluated_at = Some(now);
+        let mut events = Vec::new();
+
+        for (rule, state) in self.rules.iter().zip(self.states.iter_mut()) {
+            let reading = sources.read(rule.metric, rule.window, now);
+            let breached = reading
+                .as_ref()
+                .is_some_and(|r| rule.comparator.holds(r.value, rule.threshold));
+            state.reading = reading;
+
+            if !breached {
+                state.breached_since = None;
+                if let Some(since) = state.firing_since.take() {
+                    events.push(AlertEvent {
+                        rule: rule.clone(),
+                        kind: AlertEventKind::Resolved {
+                            after: now.saturating_duration_since(since),
+                        },
+                        reading: state.reading.clone(),
+                    });
+                }
+                continue;
+            }
+
+            let breached_since = *state.breached_since.get_or_insert(now);
+            let sustained = rule.metric.is_windowed()
+                || now.saturating_duration_since(breached_since) >= rule.window;
+            if sustained && state.firing_since.is_none() {
+                state.firing_since = Some(now);
+                events.push(AlertEvent {
+                    rule: rule.clone(),
+                    kind: AlertEventKind::Fired,
+                    reading: state.reading.clone(),
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Every rule with its state, in config order
+    pub fn statuses(&self) -> impl Iterator<Item = (&AlertRule, &AlertState)> {
+        self.rules.iter().zip(self.states.iter())
+    }
+
+    /// Rules currently firing
+    pub fn firing(&self) -> Vec<(&AlertRule, &AlertState)> {
+        self.statuses()
+            .filter(|(_, state)| state.firing_since.is_some())
+            .collect()
+    }
+}
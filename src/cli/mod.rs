@@ -1,9 +1,57 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::process::DEFAULT_BACKLOG_LINES;
 
 #[derive(Parser, Debug)]
 #[command(name = "caboose")]
 #[command(about = "Rails development tool - process manager and monitoring", long_about = None)]
 pub struct Cli {
+    /// Project root to run in (defaults to $CABOOSE_PROJECT, then the current directory)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub project: Option<PathBuf>,
+
+    /// Save an auto-generated Procfile to Procfile.dev, with a comment per entry
+    #[arg(long, global = true)]
+    pub write_procfile: bool,
+
+    /// Overwrite an existing Procfile.dev when used with --write-procfile
+    #[arg(long, requires = "write_procfile")]
+    pub force: bool,
+
+    /// Run without the TUI, streaming process output to stdout
+    #[arg(long, global = true)]
+    pub no_tui: bool,
+
+    /// With --no-tui, start stdout with one JSON object describing the startup
+    #[arg(long, global = true, requires = "no_tui")]
+    pub startup_json: bool,
+
+    /// With --no-tui, write process output as one JSON event per line
+    #[arg(long, global = true, requires = "no_tui")]
+    pub json_logs: bool,
+
+    /// Adopt processes already listening on their port instead of starting them
+    #[arg(long, global = true)]
+    pub attach: bool,
+
+    /// Start on the default tab and filters instead of where the last run left off
+    #[arg(long, global = true)]
+    pub fresh: bool,
+
+    /// Reload the query stats, exceptions, requests and test runs the last run
+    /// kept in .caboose/session.json
+    #[arg(long, global = true)]
+    pub resume: bool,
+
+    /// Start the processes without running the `[setup]` commands first
+    #[arg(long, global = true)]
+    pub skip_setup: bool,
+
+    /// Print what frontend detection tried and how long it took
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -15,18 +63,49 @@ pub enum Commands {
         /// Optional process name to start
         process: Option<String>,
     },
-    /// Stop all processes
-    Stop,
+    /// Stop the session running in this project: SIGTERM each process, then
+    /// SIGKILL any still running after the grace period
+    Stop {
+        /// Seconds to wait after SIGTERM before SIGKILL
+        #[arg(long, value_name = "SECS", default_value_t = 5)]
+        grace: u64,
+        /// SIGKILL right away, without waiting
+        #[arg(long)]
+        force: bool,
+    },
     /// Restart a process
     Restart {
         /// Process name to restart
         process: String,
     },
-    /// Show logs for a process
+    /// Print a process's output, as kept in .caboose/logs/<process>.log
     Logs {
         /// Process name
         process: String,
+        /// Keep printing output as the process writes it
+        #[arg(long, short)]
+        follow: bool,
+        /// Lines of earlier output to print first
+        #[arg(long, short = 'n', value_name = "N", default_value_t = DEFAULT_BACKLOG_LINES)]
+        lines: usize,
+    },
+    /// Run a one-off command, e.g. `rails db:migrate`: in the running
+    /// session, where its output shows in the logs, or here when there's none
+    Run {
+        /// Command and its arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
     },
     /// List all processes
     Ps,
+    /// List recently used projects, or start one by its number
+    Recent {
+        /// Number from the list to start in dev mode
+        number: Option<usize>,
+    },
+    /// Browse a session saved with `/session save`, read-only
+    Open {
+        /// Session archive to open
+        file: PathBuf,
+    },
 }
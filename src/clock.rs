@@ -0,0 +1,222 @@
+//! The clock uptimes are read from, and noticing when the machine slept
+//!
+//! The UI loop ticks every ~100ms, so a gap of more than
+//! [`SUSPEND_THRESHOLD`] between two ticks means the machine was suspended
+//! (a closed laptop lid, or Caboose stopped with Ctrl-Z). Depending on the
+//! platform the monotonic clock either stops while the machine sleeps or keeps
+//! counting, while the wall clock always moves on, so a gap in either one
+//! counts. Uptimes spanning a suspension leave out any monotonic time it
+//! accounted for, and are marked as approximate.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::ui::formatting::format_duration;
+
+/// Gap between two ticks of the UI loop that counts as a suspension
+pub const SUSPEND_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Suspensions remembered; older ones no longer change an uptime shown
+const MAX_SUSPENSIONS: usize = 50;
+
+/// Where the time comes from
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Monotonic time, for uptimes and ages
+    fn now(&self) -> Instant;
+    /// Wall-clock time, for timestamps
+    fn wall(&self) -> DateTime<Local>;
+}
+
+/// The system's clocks
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock that only moves when told to, for tests
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<Instant>,
+    wall: Mutex<DateTime<Local>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Starts at the system's current time
+    pub fn new() -> Self {
+        Self {
+            now: Mutex::new(Instant::now()),
+            wall: Mutex::new(Local::now()),
+        }
+    }
+
+    /// Both clocks move on by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+        *self.wall.lock().unwrap() += TimeDelta::from_std(by).unwrap_or_default();
+    }
+
+    /// The machine sleeps for `by`: the wall clock moves on, the monotonic
+    /// clock doesn't, as on Linux and macOS
+    pub fn sleep(&self, by: Duration) {
+        *self.wall.lock().unwrap() += TimeDelta::from_std(by).unwrap_or_default();
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn wall(&self) -> DateTime<Local> {
+        *self.wall.lock().unwrap()
+    }
+}
+
+/// A stretch of time the machine spent suspended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suspension {
+    /// The last tick before it
+    pub from: DateTime<Local>,
+    /// The first tick after it
+    pub to: DateTime<Local>,
+    /// How long it lasted, by whichever clock saw more of it
+    pub duration: Duration,
+    /// Monotonic time that passed during it; near zero where that clock
+    /// stops while the machine sleeps
+    pub monotonic: Duration,
+    /// Monotonic time of the first tick after it
+    pub resumed_at: Instant,
+}
+
+impl Suspension {
+    /// `System suspended ~7h (22:14–05:31)`
+    pub fn message(&self) -> String {
+        format!(
+            "System suspended {} ({}–{})",
+            format_approx(self.duration),
+            self.from.format("%H:%M"),
+            self.to.format("%H:%M")
+        )
+    }
+}
+
+/// How long something has been up, and whether that spans a suspension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uptime {
+    /// Time the machine was awake for
+    pub awake: Duration,
+    pub spans_suspension: bool,
+}
+
+impl Uptime {
+    /// `12m 3s`, or `~12m 3s` when the machine slept in the meantime
+    pub fn label(&self) -> String {
+        let label = format_duration(self.awake.as_secs());
+        if self.spans_suspension {
+            format!("~{}", label)
+        } else {
+            label
+        }
+    }
+}
+
+/// Watches the gaps between ticks of the UI loop and keeps the suspensions found
+#[derive(Debug)]
+pub struct Suspensions {
+    threshold: Duration,
+    last_tick: Option<(Instant, DateTime<Local>)>,
+    /// Oldest first
+    seen: Vec<Suspension>,
+}
+
+impl Default for Suspensions {
+    fn default() -> Self {
+        Self::new(SUSPEND_THRESHOLD)
+    }
+}
+
+impl Suspensions {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            last_tick: None,
+            seen: Vec::new(),
+        }
+    }
+
+    /// Note a tick of the loop; returns the suspension that ended with it, if any
+    pub fn tick(&mut self, now: Instant, wall: DateTime<Local>) -> Option<Suspension> {
+        let (last_now, last_wall) = self.last_tick.replace((now, wall))?;
+        let monotonic = now.saturating_duration_since(last_now);
+        let wall_gap = (wall - last_wall).to_std().unwrap_or_default();
+        let duration = monotonic.max(wall_gap);
+        if duration < self.threshold {
+            return None;
+        }
+        let suspension = Suspension {
+            from: last_wall,
+            to: wall,
+            duration,
+            monotonic,
+            resumed_at: now,
+        };
+        self.seen.push(suspension);
+        if self.seen.len() > MAX_SUSPENSIONS {
+            self.seen.remove(0);
+        }
+        Some(suspension)
+    }
+
+    /// Suspensions found so far, oldest first
+    pub fn all(&self) -> &[Suspension] {
+        &self.seen
+    }
+
+    pub fn latest(&self) -> Option<&Suspension> {
+        self.seen.last()
+    }
+
+    /// Time between `since` and `now` the machine was awake for
+    pub fn uptime(&self, since: Instant, now: Instant) -> Uptime {
+        let mut uptime = Uptime {
+            awake: now.saturating_duration_since(since),
+            spans_suspension: false,
+        };
+        for suspension in &self.seen {
+            if suspension.resumed_at > since && suspension.resumed_at <= now {
+                uptime.awake = uptime.awake.saturating_sub(suspension.monotonic);
+                uptime.spans_suspension = true;
+            }
+        }
+        uptime
+    }
+}
+
+/// A duration rounded to its largest unit: `~7h`, `~45m`, `~40s`
+pub fn format_approx(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("~{}h", (secs + 1800) / 3600)
+    } else if secs >= 60 {
+        format!("~{}m", (secs + 30) / 60)
+    } else {
+        format!("~{}s", secs)
+    }
+}
@@ -1,7 +1,40 @@
+mod procfile_check;
+mod template;
+
+pub use procfile_check::{ProcfileFinding, check_procfile, check_project_procfile};
+pub use template::{PLACEHOLDERS, TemplateValues};
+
+use crate::alerts::AlertRule;
+use crate::context::{
+    DEFAULT_BURST_THRESHOLD, DEFAULT_BURST_WINDOW, DEFAULT_QUEUE_TIME_WARNING,
+    DEFAULT_REQUEST_CAPACITY, DEFAULT_TURBO_BURST_THRESHOLD, DEFAULT_TURBO_BURST_WINDOW,
+    DuplicateSettings, request_capacity_from_env,
+};
+use crate::diagnostics::{Limits, MAX_LOGS_LIMIT, MAX_STORE_LIMIT, validate_limit};
+use crate::disk::DiskLimits;
+use crate::exception::ExceptionGrouping;
+use crate::frontend::{DEFAULT_FRONTEND_PROCESS, FrontendApp};
+use crate::history::DEFAULT_RETENTION_DAYS;
+use crate::path_glob::PathGlob;
+use crate::privacy::Privacy;
+use crate::process::{
+    DEFAULT_MAX_LINE_LENGTH, DEFAULT_SETUP_TIMEOUT, LineTruncator, MAX_LINE_LENGTH_LIMIT,
+    RestartPolicy, RestartRule, SetupStep, default_spill_dir,
+};
+use crate::query::FingerprintOptions;
+use crate::rails::RailsApp;
+use crate::stats::{DEFAULT_CACHE_HIT_RATE_WARNING, DbShareThresholds};
+use crate::time_format::DisplayZone;
+use crate::ui::components::{LogColors, LogOrder};
+use crate::ui::layout::CompactThresholds;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// File name used when saving an auto-generated Procfile
+pub const GENERATED_PROCFILE_NAME: &str = "Procfile.dev";
 
 #[derive(Debug, Clone)]
 pub struct ProcessConfig {
@@ -16,6 +49,32 @@ pub struct CabooseConfig {
     #[serde(default)]
     pub rails: RailsConfig,
     #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub logs: LogsConfig,
+    #[serde(default)]
+    pub queries: QueriesConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub exceptions: ExceptionsConfig,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub templates: TemplatesConfig,
+    #[serde(default)]
+    pub setup: SetupConfig,
+    #[serde(default)]
     pub processes: HashMap<String, ProcessOverride>,
 }
 
@@ -46,6 +105,498 @@ pub struct RailsConfig {
     /// Disable Rails auto-detection
     #[serde(default)]
     pub disable_auto_detect: bool,
+
+    /// How background workers are split into Procfile entries
+    #[serde(default)]
+    pub workers: WorkerMode,
+
+    /// Puma threads per server, for estimating queueing when it isn't logged
+    /// (default: RAILS_MAX_THREADS, else 5)
+    pub max_threads: Option<usize>,
+}
+
+impl RailsConfig {
+    pub fn request_capacity(&self) -> usize {
+        self.max_threads
+            .filter(|threads| *threads > 0)
+            .or_else(request_capacity_from_env)
+            .unwrap_or(DEFAULT_REQUEST_CAPACITY)
+    }
+}
+
+/// Procfile layout for background job workers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkerMode {
+    /// One `worker` entry, regardless of the job config
+    Single,
+    /// One entry per process group found in the job config
+    #[default]
+    PerGroup,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StatsConfig {
+    /// DB share (% of request time) at which the header turns yellow (default: 50)
+    pub db_share_warning: Option<f64>,
+
+    /// DB share at which the header turns red (default: 75)
+    pub db_share_danger: Option<f64>,
+
+    /// Cache hit rate (%) below which the header turns yellow (default: 80)
+    pub cache_hit_rate_warning: Option<f64>,
+
+    /// Average request queue time (ms) over a minute that shows a capacity warning (default: 100)
+    pub queue_time_warning: Option<f64>,
+
+    /// 404s (or 5xx) from one path within the burst window that raise a warning (default: 20)
+    pub burst_threshold: Option<usize>,
+
+    /// Window error bursts are counted over, in seconds (default: 120)
+    pub burst_window_secs: Option<u64>,
+
+    /// Broadcasts to one Turbo Stream within the window that raise a warning (default: 50)
+    pub turbo_burst_threshold: Option<usize>,
+
+    /// Window Turbo Stream broadcasts are counted over, in seconds (default: 10)
+    pub turbo_burst_window_secs: Option<u64>,
+
+    /// Identical requests completing this close together are duplicates, in ms (default: 1000)
+    pub duplicate_window_ms: Option<u64>,
+
+    /// Requests in a run before duplicates are flagged (default: 2)
+    pub duplicate_min_count: Option<usize>,
+
+    /// Methods checked for duplicates (default: POST, PUT, PATCH, DELETE)
+    pub duplicate_methods: Option<Vec<String>>,
+
+    /// Path globs never flagged as duplicates, e.g. polling endpoints: `/api/*/status`, `/poll/**`
+    #[serde(default)]
+    pub duplicate_exclude: Vec<String>,
+}
+
+impl StatsConfig {
+    pub fn db_share_thresholds(&self) -> DbShareThresholds {
+        let defaults = DbShareThresholds::default();
+        DbShareThresholds {
+            warning: self.db_share_warning.unwrap_or(defaults.warning),
+            danger: self.db_share_danger.unwrap_or(defaults.danger),
+        }
+    }
+
+    pub fn cache_hit_rate_warning(&self) -> f64 {
+        self.cache_hit_rate_warning
+            .unwrap_or(DEFAULT_CACHE_HIT_RATE_WARNING)
+    }
+
+    pub fn queue_time_warning(&self) -> f64 {
+        self.queue_time_warning
+            .unwrap_or(DEFAULT_QUEUE_TIME_WARNING)
+    }
+
+    pub fn burst_threshold(&self) -> usize {
+        self.burst_threshold.unwrap_or(DEFAULT_BURST_THRESHOLD)
+    }
+
+    pub fn burst_window(&self) -> Duration {
+        self.burst_window_secs
+            .map_or(DEFAULT_BURST_WINDOW, Duration::from_secs)
+    }
+
+    pub fn turbo_burst_threshold(&self) -> usize {
+        self.turbo_burst_threshold
+            .unwrap_or(DEFAULT_TURBO_BURST_THRESHOLD)
+    }
+
+    pub fn turbo_burst_window(&self) -> Duration {
+        self.turbo_burst_window_secs
+            .map_or(DEFAULT_TURBO_BURST_WINDOW, Duration::from_secs)
+    }
+
+    /// Duplicate request settings, with a warning for each exclude pattern
+    /// that isn't a path glob
+    pub fn duplicate_settings(&self) -> (DuplicateSettings, Vec<String>) {
+        let defaults = DuplicateSettings::default();
+        let (exclude, errors) =
+            PathGlob::parse_all(self.duplicate_exclude.iter().map(String::as_str));
+        let warnings = errors
+            .into_iter()
+            .map(|e| format!("[stats] duplicate_exclude: {} - ignored", e))
+            .collect();
+        let settings = DuplicateSettings {
+            window: self
+                .duplicate_window_ms
+                .map_or(defaults.window, Duration::from_millis),
+            min_count: self.duplicate_min_count.unwrap_or(defaults.min_count),
+            methods: self
+                .duplicate_methods
+                .as_ref()
+                .map_or(defaults.methods, |methods| {
+                    methods
+                        .iter()
+                        .map(|method| method.trim().to_ascii_uppercase())
+                        .collect()
+                }),
+            exclude,
+        };
+        (settings, warnings)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UiConfig {
+    /// Use the compact layout below this many terminal rows (default: 25)
+    pub compact_height: Option<u16>,
+
+    /// Use the compact layout below this many terminal columns (default: 80)
+    pub compact_width: Option<u16>,
+
+    /// Zone times are shown and exported in: "local", "utc" or an IANA name (default: local)
+    pub timezone: Option<String>,
+
+    /// Seconds without a key press before `/autofocus` switches views again (default: 30)
+    pub autofocus_resume_secs: Option<u64>,
+
+    /// Reopen the last run's tab, filters and sorts (default: true; `--fresh` skips it)
+    pub restore_session: Option<bool>,
+}
+
+impl UiConfig {
+    pub fn compact_thresholds(&self) -> CompactThresholds {
+        let defaults = CompactThresholds::default();
+        CompactThresholds {
+            height: self.compact_height.unwrap_or(defaults.height),
+            width: self.compact_width.unwrap_or(defaults.width),
+        }
+    }
+
+    /// The configured display zone, with a warning when it isn't a known zone
+    pub fn display_zone(&self) -> (DisplayZone, Option<String>) {
+        DisplayZone::from_config(self.timezone.as_deref())
+    }
+
+    pub fn restores_session(&self) -> bool {
+        self.restore_session.unwrap_or(true)
+    }
+
+    pub fn autofocus_resume(&self) -> Duration {
+        self.autofocus_resume_secs
+            .map_or(crate::ui::autofocus::DEFAULT_RESUME, Duration::from_secs)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LogsConfig {
+    /// Show a line once when a process writes it to both stdout and stderr (NO_PTY mode)
+    #[serde(default)]
+    pub dedupe_streams: bool,
+
+    /// Longest line kept in full, in bytes; longer lines are cut (default: 16384)
+    pub max_line_length: Option<usize>,
+
+    /// Write the full text of cut lines to a temp file so export keeps them
+    #[serde(default)]
+    pub spill_long_lines: bool,
+
+    /// Logs view order at startup: "oldest" (tail style) or "newest" first
+    #[serde(default)]
+    pub order: LogOrder,
+
+    /// "theme" recolors log lines, "tool" keeps the colors processes print
+    #[serde(default)]
+    pub colors: LogColors,
+
+    /// Write samples of lines no parser understood to `.caboose/unparsed_samples.txt` on exit
+    #[serde(default)]
+    pub dump_unparsed: bool,
+}
+
+impl LogsConfig {
+    /// Truncator for overlong lines, with a warning if `max_line_length` fell back
+    pub fn line_truncator(&self) -> (LineTruncator, Vec<String>) {
+        let mut warnings = Vec::new();
+        let max_len = validate_limit(
+            "max_line_length",
+            self.max_line_length,
+            DEFAULT_MAX_LINE_LENGTH,
+            MAX_LINE_LENGTH_LIMIT,
+            &mut warnings,
+        );
+        let mut truncator = LineTruncator::new(max_len);
+        if self.spill_long_lines {
+            truncator = truncator.with_spill_dir(default_spill_dir());
+        }
+        (truncator, warnings)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct QueriesConfig {
+    /// Group queries that differ only in `LIMIT`/`OFFSET` (N+1 and slow-query grouping)
+    #[serde(default)]
+    pub ignore_limit_offset: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LimitsConfig {
+    /// Log lines kept in memory (default: 1000)
+    pub max_logs: Option<usize>,
+
+    /// Distinct slow queries kept (default: 50)
+    pub max_slow_queries: Option<usize>,
+
+    /// Recent exception samples kept (default: 100)
+    pub max_exceptions: Option<usize>,
+
+    /// Completed test runs kept (default: 10)
+    pub max_test_runs: Option<usize>,
+
+    /// Entries in the slowest-tests list (default: 20)
+    pub max_slowest_tests: Option<usize>,
+
+    /// Completed requests kept for N+1 analysis (default: 100)
+    pub max_completed_requests: Option<usize>,
+}
+
+impl LimitsConfig {
+    /// Effective limits, with a warning for each value that fell back to its default
+    pub fn limits(&self) -> (Limits, Vec<String>) {
+        let defaults = Limits::default();
+        let mut warnings = Vec::new();
+        let limits = Limits {
+            max_logs: validate_limit(
+                "max_logs",
+                self.max_logs,
+                defaults.max_logs,
+                MAX_LOGS_LIMIT,
+                &mut warnings,
+            ),
+            max_slow_queries: validate_limit(
+                "max_slow_queries",
+                self.max_slow_queries,
+                defaults.max_slow_queries,
+                MAX_STORE_LIMIT,
+                &mut warnings,
+            ),
+            max_exceptions: validate_limit(
+                "max_exceptions",
+                self.max_exceptions,
+                defaults.max_exceptions,
+                MAX_STORE_LIMIT,
+                &mut warnings,
+            ),
+            max_test_runs: validate_limit(
+                "max_test_runs",
+                self.max_test_runs,
+                defaults.max_test_runs,
+                MAX_STORE_LIMIT,
+                &mut warnings,
+            ),
+            max_slowest_tests: validate_limit(
+                "max_slowest_tests",
+                self.max_slowest_tests,
+                defaults.max_slowest_tests,
+                MAX_STORE_LIMIT,
+                &mut warnings,
+            ),
+            max_completed_requests: validate_limit(
+                "max_completed_requests",
+                self.max_completed_requests,
+                defaults.max_completed_requests,
+                MAX_STORE_LIMIT,
+                &mut warnings,
+            ),
+        };
+        (limits, warnings)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AlertsConfig {
+    /// Rules like "error_rate > 5% over 2m as critical"
+    #[serde(default)]
+    pub rules: Vec<String>,
+}
+
+impl AlertsConfig {
+    /// Parsed rules, or one error per rule that couldn't be parsed
+    pub fn rules(&self) -> Result<Vec<AlertRule>, Vec<String>> {
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        for rule in &self.rules {
+            match AlertRule::parse(rule) {
+                Ok(parsed) => rules.push(parsed),
+                Err(e) => errors.push(e),
+            }
+        }
+        if errors.is_empty() {
+            Ok(rules)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DiskConfig {
+    /// Watch log/*.log, tmp/cache and free disk space (default: true)
+    pub enabled: Option<bool>,
+
+    /// Size in MB at which a log file or tmp/cache gets a warning (default: 1024)
+    pub max_file_mb: Option<u64>,
+
+    /// Free space in MB below which the project's disk gets a warning (default: 2048)
+    pub min_free_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExceptionsConfig {
+    /// Group by the topmost project-local backtrace frame too, so the same
+    /// error raised from two places shows as two groups
+    #[serde(default)]
+    pub fingerprint_with_location: bool,
+
+    /// Keep the frame's line number in the grouping (default: true)
+    pub fingerprint_line_numbers: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PrivacyConfig {
+    /// Start with privacy mode on, masking personal data on screen and in exports
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Git branches to hide while it's on, `*` matching anything (e.g. "customer/*")
+    #[serde(default)]
+    pub branch_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HistoryConfig {
+    /// Keep request, slow query and health score history in .caboose/history.db
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Days of history kept; older rows are dropped on startup (default: 30)
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionConfig {
+    /// Reload the last run's query stats, exceptions, requests and test runs
+    /// from .caboose/session.json at startup, as `--resume` does once
+    #[serde(default)]
+    pub persist: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TemplatesConfig {
+    /// Also expand `{rails.port}` and the like in process commands and
+    /// Procfile lines; `[processes.x].env` values are always expanded
+    #[serde(default)]
+    pub commands: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SetupConfig {
+    /// One-shot commands run in order before any process starts
+    #[serde(default)]
+    pub commands: Vec<SetupCommand>,
+
+    /// Seconds a command may run before it's stopped and startup fails (default: 300)
+    pub timeout_secs: Option<u64>,
+}
+
+/// A `[setup]` command: just the command line, or a table with conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SetupCommand {
+    Command(String),
+    Detailed {
+        command: String,
+        /// Skip the command while this file exists; it's written once setup succeeds
+        unless_file: Option<PathBuf>,
+        /// Overrides `[setup] timeout_secs` for this command
+        timeout_secs: Option<u64>,
+    },
+}
+
+impl SetupConfig {
+    pub fn steps(&self) -> Vec<SetupStep> {
+        let default_timeout = self
+            .timeout_secs
+            .map_or(DEFAULT_SETUP_TIMEOUT, Duration::from_secs);
+        self.commands
+            .iter()
+            .map(|command| match command {
+                SetupCommand::Command(command) => {
+                    SetupStep::new(command).with_timeout(default_timeout)
+                }
+                SetupCommand::Detailed {
+                    command,
+                    unless_file,
+                    timeout_secs,
+                } => {
+                    let step = SetupStep::new(command)
+                        .with_timeout(timeout_secs.map_or(default_timeout, Duration::from_secs));
+                    match unless_file {
+                        Some(stamp) => step.with_unless_file(stamp),
+                        None => step,
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl HistoryConfig {
+    pub fn retention_days(&self) -> u32 {
+        self.retention_days.unwrap_or(DEFAULT_RETENTION_DAYS)
+    }
+}
+
+impl PrivacyConfig {
+    pub fn privacy(&self) -> Privacy {
+        Privacy::new(self.enabled, self.branch_patterns.clone())
+    }
+}
+
+impl ExceptionsConfig {
+    pub fn grouping(&self) -> ExceptionGrouping {
+        ExceptionGrouping {
+            with_location: self.fingerprint_with_location,
+            line_numbers: self
+                .fingerprint_line_numbers
+                .unwrap_or(ExceptionGrouping::default().line_numbers),
+        }
+    }
+}
+
+impl DiskConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn limits(&self) -> DiskLimits {
+        const MB: u64 = 1024 * 1024;
+        let defaults = DiskLimits::default();
+        DiskLimits {
+            max_file_size: self
+                .max_file_mb
+                .map_or(defaults.max_file_size, |mb| mb.saturating_mul(MB)),
+            min_free_space: self
+                .min_free_mb
+                .map_or(defaults.min_free_space, |mb| mb.saturating_mul(MB)),
+        }
+    }
+}
+
+impl QueriesConfig {
+    pub fn fingerprint_options(&self) -> FingerprintOptions {
+        FingerprintOptions {
+            ignore_limit_offset: self.ignore_limit_offset,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,18 +607,69 @@ pub struct ProcessOverride {
     /// Environment variables for this process
     #[serde(default)]
     pub env: HashMap<String, String>,
+
+    /// Don't warn when this process forks into the background (e.g. `rails s -d`)
+    #[serde(default)]
+    pub allow_daemonize: bool,
+
+    /// Start and stop this process (default: true); when false, the one
+    /// already running on its port is adopted instead
+    pub managed: Option<bool>,
+
+    /// Port to find an adopted process by, when the command doesn't give one
+    pub port: Option<u16>,
+
+    /// Log file to follow for an adopted process's output, relative to the
+    /// project (e.g. "log/development.log")
+    pub log_file: Option<PathBuf>,
+
+    /// Start the process again when it crashes: "never" (default) or "on-failure"
+    #[serde(default)]
+    pub restart: RestartPolicy,
+
+    /// Crash restarts in a row before giving up (default: 5)
+    pub max_restarts: Option<u32>,
+
+    /// Wait before the first crash restart, doubled for each one after (default: 1000)
+    pub backoff_ms: Option<u64>,
+}
+
+impl ProcessOverride {
+    pub fn managed(&self) -> bool {
+        self.managed.unwrap_or(true)
+    }
+
+    /// How to restart the process after a crash; `None` when it shouldn't be
+    pub fn restart_rule(&self) -> Option<RestartRule> {
+        if self.restart == RestartPolicy::Never {
+            return None;
+        }
+        let defaults = RestartRule::default();
+        Some(RestartRule {
+            max_restarts: self.max_restarts.unwrap_or(defaults.max_restarts),
+            backoff: self
+                .backoff_ms
+                .map_or(defaults.backoff, Duration::from_millis),
+        })
+    }
 }
 
 impl CabooseConfig {
     /// Load configuration from .caboose.toml
     pub fn load() -> Self {
-        Self::load_from(".caboose.toml")
-            .or_else(|| Self::load_from("caboose.toml"))
+        Self::load_in(".")
+    }
+
+    /// Load configuration from .caboose.toml (or caboose.toml) inside `root`
+    pub fn load_in<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref();
+        Self::load_from(&root.join(".caboose.toml"))
+            .or_else(|| Self::load_from(&root.join("caboose.toml")))
             .unwrap_or_default()
     }
 
-    fn load_from(path: &str) -> Option<Self> {
-        if !Path::new(path).exists() {
+    fn load_from(path: &Path) -> Option<Self> {
+        if !path.exists() {
             return None;
         }
 
@@ -75,6 +677,22 @@ impl CabooseConfig {
         toml::from_str(&content).ok()
     }
 
+    /// The config file `load_in` reads for `root`, if there is one
+    pub fn path_in<P: AsRef<Path>>(root: P) -> Option<PathBuf> {
+        let root = root.as_ref();
+        [".caboose.toml", "caboose.toml"]
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.exists())
+    }
+
+    /// Read a config file, reporting why it couldn't be used
+    pub fn parse_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+
     /// Create example configuration file
     pub fn create_example() -> String {
         r#"# Caboose Configuration File
@@ -103,19 +721,188 @@ impl CabooseConfig {
 # Disable Rails auto-detection
 # disable_auto_detect = false
 
+# Worker entries: "per-group" (one per sidekiq capsule / queue group) or "single"
+# workers = "per-group"
+
+# Puma threads per server, used to estimate request queueing when no middleware
+# logs it (default: RAILS_MAX_THREADS, else 5)
+# max_threads = 5
+
+[stats]
+# Header DB share (% of request time spent in SQL) thresholds
+# db_share_warning = 50
+# db_share_danger = 75
+# cache_hit_rate_warning = 80  # Cache hit rate (%) below which the header warns
+# queue_time_warning = 100     # Avg request queueing (ms) over a minute that warns of capacity
+# burst_threshold = 20         # 404s (or 5xx) from one path within the window that warn
+# burst_window_secs = 120
+# turbo_burst_threshold = 50   # Broadcasts to one Turbo Stream within the window that warn
+# turbo_burst_window_secs = 10
+# Identical requests completing within the window are flagged as double submits
+# duplicate_window_ms = 1000
+# duplicate_min_count = 2
+# duplicate_methods = ["POST", "PUT", "PATCH", "DELETE"]
+# duplicate_exclude = ["/api/*/status", "/poll/**"]  # Polling endpoints
+
+[ui]
+# Collapse the header and tab bar below this terminal size (tmux panes)
+# compact_height = 25
+# compact_width = 80
+# Show and export times in "local", "utc" or an IANA zone such as "Europe/Berlin"
+# timezone = "local"
+# With /autofocus on, seconds after a key press before it switches views again
+# autofocus_resume_secs = 30
+# Reopen the last run's tab, process filter, searches and sorts (skip once with --fresh)
+# restore_session = true
+
+[logs]
+# Show lines written to both stdout and stderr once (only without a PTY, NO_PTY=1)
+# dedupe_streams = false
+# Cut lines longer than this many bytes (huge bulk INSERTs) before they're stored
+# max_line_length = 16384
+# Keep the full text of cut lines in a temp file so /export still has it
+# spill_long_lines = false
+# Show the Logs view "oldest" first (tail style) or "newest" first (toggle with o)
+# order = "oldest"
+# Color lines with the Caboose "theme", or keep the "tool" colors (vite, next, ...)
+# colors = "theme"
+# On exit, write redacted samples of lines Caboose couldn't parse to
+# .caboose/unparsed_samples.txt (handy to attach to an issue)
+# dump_unparsed = false
+
+[queries]
+# Group queries that differ only in LIMIT/OFFSET for N+1 and slow-query stats
+# ignore_limit_offset = false
+
+[limits]
+# In-memory store sizes; edits are applied while caboose runs
+# max_logs = 1000
+# max_slow_queries = 50
+# max_exceptions = 100
+# max_test_runs = 10
+# max_slowest_tests = 20
+# max_completed_requests = 100
+
+[alerts]
+# "<metric> <op> <threshold> [over <window>] [as info|warning|critical]"
+# Metrics: error_rate, avg_response_time, p95_response_time, endpoint_p95,
+#          db_share, exceptions, health_score
+# rules = [
+#   "error_rate > 5% over 2m",
+#   "endpoint_p95 > 1s over 5m as critical",
+#   "health_score < 70 over 1m",
+# ]
+
+[disk]
+# Warn when a log/*.log file or tmp/cache passes max_file_mb, or free space on the
+# project's disk drops below min_free_mb; checked every few minutes
+# enabled = true
+# max_file_mb = 1024
+# min_free_mb = 2048
+
+[exceptions]
+# Also group by the first backtrace frame in the project (e.g. app/models/user.rb:12),
+# so one error raised from two call sites shows as two groups; applied while caboose runs
+# fingerprint_with_location = false
+# fingerprint_line_numbers = true    # false: any line in the same file groups together
+
+[history]
+# Keep request durations, slow queries and health scores in .caboose/history.db
+# across runs, for /trends GET /api/orders
+# enabled = false
+# retention_days = 30
+
+[session]
+# Caboose keeps query stats, slow queries, exception groups, requests and test
+# runs in .caboose/session.json; persist = true reloads them at startup
+# (--resume does it for one run)
+# persist = false
+
+[templates]
+# env values under [processes.x] may use {rails.port}, {frontend.port},
+# {frontend.path}, {project.root} and {env.NAME}; {{ and }} are literal braces.
+# commands = true also expands them in process commands and Procfile lines
+# commands = false
+
+[setup]
+# One-shot commands run in order before any process starts (skip with --skip-setup);
+# one that fails or times out stops startup. A table's command is skipped while its
+# unless_file exists, and the file is written once every command has succeeded
+# commands = [
+#   "bundle exec rails db:prepare",
+#   { command = "yarn install", unless_file = "tmp/caboose-setup-done", timeout_secs = 600 },
+# ]
+# timeout_secs = 300
+
 # Process-specific overrides
 # [processes.web]
 # command = "bundle exec puma -p 4000"
 # env = { RAILS_ENV = "development" }
+# allow_daemonize = false  # true silences the warning when it forks into the background
 
 # [processes.frontend]
 # command = "cd client && pnpm dev"
-# env = { NODE_ENV = "development" }
+# env = { NODE_ENV = "development", VITE_API_URL = "http://localhost:{rails.port}" }
 "#
         .to_string()
     }
 }
 
+/// Notices edits to the config file while Caboose is running
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    root: PathBuf,
+    /// Config file and its modification time as of the last load
+    seen: Option<(PathBuf, SystemTime)>,
+    checked_at: Instant,
+}
+
+impl ConfigWatcher {
+    /// How often the file's modification time is checked
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Watch the config file in `root`, treating its current contents as loaded
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref().to_path_buf();
+        Self {
+            seen: Self::current(&root),
+            root,
+            checked_at: Instant::now(),
+        }
+    }
+
+    fn current(root: &Path) -> Option<(PathBuf, SystemTime)> {
+        let path = CabooseConfig::path_in(root)?;
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        Some((path, modified))
+    }
+
+    /// The re-read config if the file changed since the last check
+    ///
+    /// Checks at most once per second. A deleted file reloads as the defaults.
+    pub fn poll(&mut self) -> Option<Result<CabooseConfig, String>> {
+        if self.checked_at.elapsed() < Self::INTERVAL {
+            return None;
+        }
+        self.check()
+    }
+
+    /// Like `poll`, without waiting for the interval
+    pub fn check(&mut self) -> Option<Result<CabooseConfig, String>> {
+        self.checked_at = Instant::now();
+        let current = Self::current(&self.root);
+        if current == self.seen {
+            return None;
+        }
+        self.seen = current;
+
+        Some(match &self.seen {
+            Some((path, _)) => CabooseConfig::parse_file(path),
+            None => Ok(CabooseConfig::default()),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct Procfile {
     pub processes: Vec<ProcessConfig>,
@@ -173,6 +960,116 @@ impl Procfile {
 
         Ok(Procfile { processes })
     }
+
+    /// Build a Procfile from generated entries
+    pub fn from_entries(entries: &[ProcfileEntry]) -> Result<Self, String> {
+        Self::parse_content(&render_procfile(entries, false))
+    }
+}
+
+// ============================================================================
+// PROCFILE GENERATION
+// ============================================================================
+
+/// A generated Procfile line together with why it was added
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcfileEntry {
+    pub name: String,
+    pub command: String,
+    pub reason: String,
+}
+
+impl ProcfileEntry {
+    pub fn new(
+        name: impl Into<String>,
+        command: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            command: command.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Generate Procfile entries for the detected Rails and frontend apps
+pub fn generate_procfile_entries(
+    rails_app: &RailsApp,
+    frontend_app: &FrontendApp,
+    config: &CabooseConfig,
+) -> Vec<ProcfileEntry> {
+    let mut entries = Vec::new();
+
+    // Rails processes (with port override from config)
+    if rails_app.detected {
+        entries.extend(rails_app.procfile_entries(config.rails.port, config.rails.workers));
+    }
+
+    // Frontend process (with dev_command override from config)
+    if frontend_app.detected
+        && let Some(command) =
+            frontend_app.generate_procfile_entry(config.frontend.dev_command.as_deref())
+    {
+        let name = config
+            .frontend
+            .process_name
+            .as_deref()
+            .unwrap_or(DEFAULT_FRONTEND_PROCESS);
+
+        let reason = if config.frontend.dev_command.is_some() {
+            "frontend dev_command set in .caboose.toml".to_string()
+        } else {
+            match frontend_app.framework {
+                Some(ref framework) => format!(
+                    "detected {} frontend in {}",
+                    framework.name(),
+                    frontend_app.path
+                ),
+                None => format!("detected frontend in {}", frontend_app.path),
+            }
+        };
+
+        entries.push(ProcfileEntry::new(name, command, reason));
+    }
+
+    entries
+}
+
+/// Render entries as Procfile text, optionally with a comment above each line
+pub fn render_procfile(entries: &[ProcfileEntry], annotate: bool) -> String {
+    let mut content = String::new();
+
+    if annotate {
+        content.push_str("# Generated by caboose from the detected project setup.\n");
+    }
+
+    for entry in entries {
+        if annotate {
+            content.push_str(&format!("\n# {}\n", entry.reason));
+        }
+        content.push_str(&format!("{}: {}\n", entry.name, entry.command));
+    }
+
+    content
+}
+
+/// Write annotated entries to `path`, refusing to replace an existing file unless `force`
+pub fn write_procfile<P: AsRef<Path>>(
+    path: P,
+    entries: &[ProcfileEntry],
+    force: bool,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    if path.exists() && !force {
+        return Err(format!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        ));
+    }
+
+    fs::write(path, render_procfile(entries, true))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
 }
 
 /// Load environment variables from .env file
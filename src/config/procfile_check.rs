@@ -0,0 +1,279 @@
+//! What an existing Procfile misses compared to the one Caboose would generate
+//!
+//! A hand-written Procfile is run as is, so a gem added later (sidekiq with no
+//! `worker:` line) or one removed (`bin/vite` without vite_rails) goes
+//! unnoticed. Startup and `/procfile-check` compare it against
+//! [`generate_procfile_entries`](super::generate_procfile_entries) and report
+//! the differences; the file itself is never changed.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use super::{
+    CabooseConfig, GENERATED_PROCFILE_NAME, ProcessConfig, Procfile, ProcfileEntry,
+    generate_procfile_entries,
+};
+use crate::frontend::{FrontendApp, PackageManager};
+use crate::parser::InstrumentationGems;
+use crate::process::port_from_command;
+use crate::rails::{JobConfig, RailsApp};
+
+/// Tools whose commands only work with a gem Caboose detects, and that gem
+const GEM_TOOLS: [(&str, &str); 4] = [
+    ("vite", "vite_rails"),
+    ("sidekiq", "sidekiq"),
+    ("good_job", "good_job"),
+    ("rake solid_queue:start", "solid_queue"),
+];
+
+/// One difference between the Procfile and the generated entries
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcfileFinding {
+    /// A generated entry nothing in the Procfile runs
+    Missing(ProcfileEntry),
+    /// An entry listening somewhere other than the port set in `.caboose.toml`
+    PortConflict {
+        name: String,
+        port: u16,
+        configured: u16,
+        /// The setting, e.g. `[rails] port`
+        setting: &'static str,
+    },
+    /// An entry running something the project no longer has
+    Stale {
+        name: String,
+        command: String,
+        /// Why it can't run, e.g. `bin/vite isn't in the project`
+        problem: String,
+    },
+}
+
+impl fmt::Display for ProcfileFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcfileFinding::Missing(entry) => write!(
+                f,
+                "{}, but the Procfile has no {} entry (`{}: {}`)",
+                entry.reason, entry.name, entry.name, entry.command
+            ),
+            ProcfileFinding::PortConflict {
+                name,
+                port,
+                configured,
+                setting,
+            } => write!(
+                f,
+                "'{}' listens on {}, but {} is {}",
+                name, port, setting, configured
+            ),
+            ProcfileFinding::Stale {
+                name,
+                command,
+                problem,
+            } => write!(f, "'{}' runs `{}`, but {}", name, command, problem),
+        }
+    }
+}
+
+/// Compare `procfile` with the `generated` entries for the same project
+///
+/// `root` is where `bin/...` commands are looked up.
+pub fn check_procfile(
+    procfile: &[ProcessConfig],
+    generated: &[ProcfileEntry],
+    config: &CabooseConfig,
+    root: &Path,
+) -> Vec<ProcfileFinding> {
+    let mut findings: Vec<ProcfileFinding> = generated
+        .iter()
+        .filter(|entry| {
+            !procfile
+                .iter()
+                .any(|process| covers(process, &entry.name, &entry.command))
+        })
+        .cloned()
+        .map(ProcfileFinding::Missing)
+        .collect();
+
+    for process in procfile {
+        let shape = CommandShape::of(&process.command);
+        if let Some(finding) = port_conflict(process, &shape, generated, config) {
+            findings.push(finding);
+        }
+        if let Some(problem) = stale(&shape, generated, root) {
+            findings.push(ProcfileFinding::Stale {
+                name: process.name.clone(),
+                command: process.command.clone(),
+                problem,
+            });
+        }
+    }
+    findings
+}
+
+/// [`check_procfile`] for the project at `root`, as startup would load it
+pub fn check_project_procfile(root: &Path) -> Result<Vec<ProcfileFinding>, String> {
+    let path = ["Procfile", GENERATED_PROCFILE_NAME]
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.exists())
+        .ok_or("No Procfile - Caboose generates its processes")?;
+    let procfile = Procfile::parse(&path)?;
+
+    let config = CabooseConfig::load_in(root);
+    let rails_app = if config.rails.disable_auto_detect {
+        RailsApp {
+            detected: false,
+            database: None,
+            background_job: None,
+            asset_pipeline: None,
+            jobs: JobConfig::default(),
+            instrumentation: InstrumentationGems::default(),
+        }
+    } else {
+        RailsApp::detect_in_path(root)
+    };
+    let frontend_app = if config.frontend.disable_auto_detect {
+        FrontendApp {
+            detected: false,
+            framework: None,
+            path: String::new(),
+            package_manager: PackageManager::Npm,
+        }
+    } else {
+        FrontendApp::detect_in_root(root, config.frontend.path.as_deref())
+    };
+    let generated = generate_procfile_entries(&rails_app, &frontend_app, &config);
+    Ok(check_procfile(
+        &procfile.processes,
+        &generated,
+        &config,
+        root,
+    ))
+}
+
+/// The parts of a command that say what it runs
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CommandShape {
+    /// Directory of a leading `cd dir &&`
+    dir: Option<String>,
+    /// First word of the command proper, as written (`bin/vite`)
+    executable: String,
+    /// What it runs, without `bundle exec` or `bin/`: `vite`, `rails server`,
+    /// `rake solid_queue:start`
+    program: String,
+}
+
+impl CommandShape {
+    fn of(command: &str) -> Self {
+        let mut dir = None;
+        let mut rest = command.trim();
+        while let Some((step, next)) = rest.split_once("&&") {
+            if let Some(path) = step.trim().strip_prefix("cd ") {
+                dir = Some(path.trim().to_string());
+            }
+            rest = next.trim();
+        }
+
+        let mut words = rest
+            .split_whitespace()
+            .skip_while(|word| word.contains('=') && !word.starts_with('-'))
+            .peekable();
+        if words.peek() == Some(&"bundle") {
+            words.next();
+            if words.peek() == Some(&"exec") {
+                words.next();
+            }
+        }
+        let Some(executable) = words.next() else {
+            return Self {
+                dir,
+                ..Self::default()
+            };
+        };
+        let name = executable.rsplit('/').next().unwrap_or(executable);
+        let program = match (name, words.next()) {
+            ("rails", Some("s" | "server")) => "rails server".to_string(),
+            ("rails" | "rake", Some(task)) => format!("{} {}", name, task),
+            _ => name.to_string(),
+        };
+        Self {
+            dir,
+            executable: executable.to_string(),
+            program,
+        }
+    }
+}
+
+/// Whether `process` runs what the generated `name: command` would
+fn covers(process: &ProcessConfig, name: &str, command: &str) -> bool {
+    if process.name == name {
+        return true;
+    }
+    let existing = CommandShape::of(&process.command);
+    let wanted = CommandShape::of(command);
+    match &wanted.dir {
+        // A frontend in its own directory, whatever package manager runs it
+        Some(dir) => existing.dir.as_ref() == Some(dir),
+        None => !wanted.program.is_empty() && existing.program == wanted.program,
+    }
+}
+
+/// A Rails server or frontend entry whose port isn't the one `.caboose.toml` sets
+fn port_conflict(
+    process: &ProcessConfig,
+    shape: &CommandShape,
+    generated: &[ProcfileEntry],
+    config: &CabooseConfig,
+) -> Option<ProcfileFinding> {
+    let (configured, setting) = if shape.program == "rails server" {
+        (config.rails.port?, "[rails] port")
+    } else {
+        let frontend = generated
+            .iter()
+            .find(|entry| CommandShape::of(&entry.command).dir.is_some())?;
+        if !covers(process, &frontend.name, &frontend.command) {
+            return None;
+        }
+        (config.frontend.port?, "[frontend] port")
+    };
+    let port = port_from_command(&process.command, &HashMap::new())?;
+    (port != configured).then(|| ProcfileFinding::PortConflict {
+        name: process.name.clone(),
+        port,
+        configured,
+        setting,
+    })
+}
+
+/// Why a command can't run any more, if it can't
+///
+/// A `bin/` script must exist. Gem tools (`bin/vite`, `sidekiq`) are stale
+/// once detection stops generating them, i.e. their gem left the Gemfile;
+/// without a detected Rails app there's nothing to compare against.
+fn stale(shape: &CommandShape, generated: &[ProcfileEntry], root: &Path) -> Option<String> {
+    if shape.executable.starts_with("bin/") || shape.executable.starts_with("./") {
+        let dir = shape
+            .dir
+            .as_deref()
+            .map_or(root.to_path_buf(), |dir| root.join(dir));
+        if !dir.join(&shape.executable).exists() {
+            return Some(format!("{} isn't in the project", shape.executable));
+        }
+    }
+
+    let rails_detected = generated
+        .iter()
+        .any(|entry| CommandShape::of(&entry.command).program == "rails server");
+    if !rails_detected || shape.dir.is_some() {
+        return None;
+    }
+    let (_, gem) = GEM_TOOLS
+        .iter()
+        .find(|(program, _)| *program == shape.program)?;
+    let generated_too = generated
+        .iter()
+        .any(|entry| CommandShape::of(&entry.command).program == shape.program);
+    (!generated_too).then(|| format!("the Gemfile has no {}", gem))
+}
@@ -0,0 +1,178 @@
+//! `{placeholder}` substitution in process env values and commands
+//!
+//! `[processes.x].env` values (and, with `[templates] commands = true`,
+//! process commands) can refer to detected values, so changing the Rails port
+//! once updates every URL built from it:
+//!
+//! ```toml
+//! [processes.frontend]
+//! env = { VITE_API_URL = "http://localhost:{rails.port}" }
+//! ```
+//!
+//! `{{` and `}}` stand for literal braces. An unknown placeholder is a startup
+//! error listing the valid ones.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Placeholders a template may use, for error messages
+pub const PLACEHOLDERS: [&str; 5] = [
+    "rails.port",
+    "frontend.port",
+    "frontend.path",
+    "project.root",
+    "env.NAME",
+];
+
+/// How deep `{env.X}` may chain through other templated env values
+const MAX_NESTING: usize = 8;
+
+/// Values the placeholders resolve to, once detection and config are done
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    rails_port: Option<u16>,
+    frontend_port: Option<u16>,
+    frontend_path: Option<String>,
+    project_root: Option<String>,
+}
+
+impl TemplateValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rails_port(mut self, port: u16) -> Self {
+        self.rails_port = Some(port);
+        self
+    }
+
+    pub fn with_frontend(mut self, port: Option<u16>, path: &str) -> Self {
+        self.frontend_port = port;
+        self.frontend_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_project_root(mut self, root: &Path) -> Self {
+        self.project_root = Some(root.display().to_string());
+        self
+    }
+
+    /// Expand `text`; `{env.X}` reads `env`, then Caboose's own environment
+    pub fn render(&self, text: &str, env: &HashMap<String, String>) -> Result<String, String> {
+        expand(text, &mut |name| match name.strip_prefix("env.") {
+            Some(var) => lookup_env(var, env),
+            None => self.value(name),
+        })
+    }
+
+    /// Expand each value of `overrides`, merged over `base`
+    ///
+    /// `{env.X}` may name another key of `overrides`, whose value is expanded
+    /// first; keys of `base` (the `.env` file) and the environment are used
+    /// as they are. Errors name the key whose value failed.
+    pub fn render_env(
+        &self,
+        overrides: &HashMap<String, String>,
+        base: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, String> {
+        let mut rendered = base.clone();
+        let mut keys: Vec<&String> = overrides.keys().collect();
+        keys.sort();
+        for key in keys {
+            let value = self
+                .render_override(key, overrides, base, &mut Vec::new())
+                .map_err(|err| format!("{}: {}", key, err))?;
+            rendered.insert(key.clone(), value);
+        }
+        Ok(rendered)
+    }
+
+    fn render_override(
+        &self,
+        key: &str,
+        overrides: &HashMap<String, String>,
+        base: &HashMap<String, String>,
+        stack: &mut Vec<String>,
+    ) -> Result<String, String> {
+        if stack.iter().any(|seen| seen == key) {
+            stack.push(key.to_string());
+            return Err(format!("{{env.*}} loop: {}", stack.join(" -> ")));
+        }
+        if stack.len() >= MAX_NESTING {
+            return Err(format!("{{env.*}} nested more than {} deep", MAX_NESTING));
+        }
+        stack.push(key.to_string());
+        let value = expand(
+            &overrides[key],
+            &mut |name| match name.strip_prefix("env.") {
+                Some(var) if overrides.contains_key(var) => {
+                    self.render_override(var, overrides, base, stack)
+                }
+                Some(var) => lookup_env(var, base),
+                None => self.value(name),
+            },
+        );
+        stack.pop();
+        value
+    }
+
+    fn value(&self, name: &str) -> Result<String, String> {
+        let value = match name {
+            "rails.port" => self.rails_port.map(|port| port.to_string()),
+            "frontend.port" => self.frontend_port.map(|port| port.to_string()),
+            "frontend.path" => self.frontend_path.clone(),
+            "project.root" => self.project_root.clone(),
+            _ => {
+                return Err(format!(
+                    "unknown placeholder {{{}}} (valid: {})",
+                    name,
+                    PLACEHOLDERS
+                        .iter()
+                        .map(|name| format!("{{{}}}", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        };
+        value.ok_or_else(|| match name {
+            "rails.port" => "{rails.port} has no value: no Rails app detected".to_string(),
+            _ => format!("{{{}}} has no value: no frontend detected", name),
+        })
+    }
+}
+
+fn lookup_env(var: &str, env: &HashMap<String, String>) -> Result<String, String> {
+    env.get(var)
+        .cloned()
+        .or_else(|| std::env::var(var).ok())
+        .ok_or_else(|| format!("{{env.{}}} is not set", var))
+}
+
+/// Replace each `{name}` with `resolve(name)`, `{{`/`}}` with single braces
+fn expand(
+    text: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if let Some(after) = tail.strip_prefix("{{").or(tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+        } else if let Some(inner) = tail.strip_prefix('{') {
+            let end = inner
+                .find('}')
+                .ok_or_else(|| format!("unclosed {{ in \"{}\" (write {{{{ for a brace)", text))?;
+            out.push_str(&resolve(inner[..end].trim())?);
+            rest = &inner[end + 1..];
+        } else {
+            // A lone `}` has nothing to close; keep it
+            out.push('}');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
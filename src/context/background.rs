@@ -0,0 +1,91 @@
+//! Queries logged outside any request: jobs, runners and `rails console`
+//!
+//! SQL with no request in flight, and everything a managed process queries
+//! once it has shown a console prompt, is collected here instead of being
+//! pinned to whichever request happens to be active.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::database::SLOW_QUERY_THRESHOLD;
+use crate::query::QueryInfo;
+
+/// How the pseudo-request is named in Query Analysis
+pub const BACKGROUND_LABEL: &str = "Background / console";
+
+/// Background queries kept for display, newest last
+pub const RECENT_BACKGROUND_QUERIES: usize = 100;
+
+/// SQL that ran outside any request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundQueries {
+    /// Queries seen this session, including those no longer in `recent`
+    pub queries: usize,
+    /// Of those, slower than [`SLOW_QUERY_THRESHOLD`]
+    pub slow: usize,
+    pub total_duration: f64,
+    /// The last [`RECENT_BACKGROUND_QUERIES`], newest last
+    pub recent: VecDeque<QueryInfo>,
+    /// Managed processes seen showing a console prompt
+    pub consoles: BTreeSet<String>,
+}
+
+impl BackgroundQueries {
+    pub fn record(&mut self, query: QueryInfo) {
+        self.queries += 1;
+        if query.duration > SLOW_QUERY_THRESHOLD {
+            self.slow += 1;
+        }
+        self.total_duration += query.duration;
+        self.recent.push_back(query);
+        if self.recent.len() > RECENT_BACKGROUND_QUERIES {
+            self.recent.pop_front();
+        }
+    }
+
+    /// `Background / console — 214 queries, 3 slow (console: console)`
+    pub fn summary(&self) -> String {
+        let consoles = if self.consoles.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<&str> = self.consoles.iter().map(String::as_str).collect();
+            format!(" (console: {})", names.join(", "))
+        };
+        format!(
+            "{} — {} queries, {} slow{}",
+            BACKGROUND_LABEL, self.queries, self.slow, consoles
+        )
+    }
+
+    /// The slowest of the recent queries, slowest first
+    pub fn slowest(&self, count: usize) -> Vec<&QueryInfo> {
+        let mut queries: Vec<&QueryInfo> = self.recent.iter().collect();
+        queries.sort_by(|a, b| b.duration.total_cmp(&a.duration));
+        queries.truncate(count);
+        queries
+    }
+}
+
+/// Whether `line` is an IRB or Pry prompt, as `rails console` prints
+///
+/// Matches `irb(main):001:0>`, `[1] pry(main)>` and the Rails 7.1+ prompt
+/// named after the app and environment, `shop(dev)>` or `shop(dev):001>`.
+pub fn is_console_prompt(line: &str) -> bool {
+    // Every line is checked, so skip the regex for the many that can't match
+    if !line.contains('>') {
+        return false;
+    }
+    static PROMPT: OnceLock<Regex> = OnceLock::new();
+    PROMPT
+        .get_or_init(|| {
+            Regex::new(
+                r"^\s*(?:irb\([^)]*\)(?::\d+)*[>*]|\[\d+\] pry\([^)]*\)[>*]|[\w-]+\((?:dev|development|test|prod|production|staging)\)(?::\d+)*[>*])",
+            )
+            .unwrap()
+        })
+        .is_match(line)
+}
@@ -0,0 +1,259 @@
+//! Error bursts: one endpoint answering 404 (or 5xx) over and over
+//!
+//! A missing route or a broken endpoint hit by a polling client fills the
+//! log with identical lines. Responses are counted per normalized path over
+//! a window; a path that passes the threshold raises one aggregated warning,
+//! which clears only once the count falls to half the threshold so a burst
+//! hovering around it doesn't flap. Once the app's routes are known, a path
+//! is counted under the pattern of the route it matches.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::rails::routes::RouteTable;
+
+use crate::ui::formatting::{format_duration, format_window};
+
+/// Responses per path within the window that make a burst
+pub const DEFAULT_BURST_THRESHOLD: usize = 20;
+
+/// Window error responses are counted over
+pub const DEFAULT_BURST_WINDOW: Duration = Duration::from_secs(120);
+
+/// How often bursts are re-counted
+pub const BURST_EVAL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Error responses kept for counting, however busy the app
+const MAX_ERROR_RESPONSES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BurstKind {
+    /// 404s, including `No route matches` routing errors
+    NotFound,
+    /// 5xx responses
+    ServerError,
+}
+
+impl BurstKind {
+    /// The kind of burst a response status counts towards
+    pub fn from_status(status: u16) -> Option<Self> {
+        match status {
+            404 => Some(Self::NotFound),
+            500..=599 => Some(Self::ServerError),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NotFound => "404",
+            Self::ServerError => "5xx",
+        }
+    }
+}
+
+/// A path currently answering with a burst of errors
+#[derive(Debug, Clone, PartialEq)]
+pub struct Burst {
+    pub kind: BurstKind,
+    /// Normalized path, e.g. `/users/:id`
+    pub path: String,
+    /// Responses within the window as of the last check
+    pub count: usize,
+    pub window: Duration,
+    pub since: Instant,
+}
+
+impl Burst {
+    /// One-line summary, e.g. `/api/v2/users returning 404 ×87 in 2m — route missing?`
+    pub fn message(&self) -> String {
+        let hint = match self.kind {
+            BurstKind::NotFound => "route missing?",
+            BurstKind::ServerError => "endpoint failing, check Exceptions",
+        };
+        format!(
+            "{} returning {} ×{} in {} — {}",
+            self.path,
+            self.kind.label(),
+            self.count,
+            format_window(self.window),
+            hint
+        )
+    }
+}
+
+/// A burst starting or dying down
+#[derive(Debug, Clone, PartialEq)]
+pub enum BurstEvent {
+    Started(Burst),
+    Cleared { burst: Burst, after: Duration },
+}
+
+impl BurstEvent {
+    /// One-line summary, as written to the log
+    pub fn message(&self) -> String {
+        match self {
+            Self::Started(burst) => format!("[burst] {}", burst.message()),
+            Self::Cleared { burst, after } => format!(
+                "[burst] {} {}s died down after {}",
+                burst.path,
+                burst.kind.label(),
+                format_duration(after.as_secs())
+            ),
+        }
+    }
+}
+
+/// Counts error responses per path and tracks which paths are bursting
+#[derive(Debug)]
+pub struct BurstDetector {
+    threshold: usize,
+    window: Duration,
+    responses: VecDeque<(Instant, BurstKind, String)>,
+    active: Vec<Burst>,
+    evaluated_at: Option<Instant>,
+    routes: Option<Arc<RouteTable>>,
+}
+
+impl Default for BurstDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_BURST_THRESHOLD, DEFAULT_BURST_WINDOW)
+    }
+}
+
+impl BurstDetector {
+    pub fn new(threshold: usize, window: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            window,
+            responses: VecDeque::new(),
+            active: Vec::new(),
+            evaluated_at: None,
+            routes: None,
+        }
+    }
+
+    /// Count paths under the pattern of the route they match, e.g.
+    /// `/users/:user_id/posts`; paths no route matches are normalized as before
+    pub fn set_routes(&mut self, routes: Arc<RouteTable>) {
+        self.routes = Some(routes);
+    }
+
+    /// Note a response; only 404s and 5xx are counted
+    pub fn record(&mut self, now: Instant, status: u16, path: &str) {
+        let Some(kind) = BurstKind::from_status(status) else {
+            return;
+        };
+        let path = self
+            .routes
+            .as_ref()
+            .and_then(|routes| routes.pattern_for(path))
+            .map_or_else(|| normalize_path(path), str::to_string);
+        self.responses.push_back((now, kind, path));
+        if self.responses.len() > MAX_ERROR_RESPONSES {
+            self.responses.pop_front();
+        }
+    }
+
+    /// Re-count if [`BURST_EVAL_INTERVAL`] has passed since the last check
+    pub fn poll(&mut self, now: Instant) -> Vec<BurstEvent> {
+        let due = self
+            .evaluated_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= BURST_EVAL_INTERVAL);
+        if !due {
+            return Vec::new();
+        }
+        self.evaluate(now)
+    }
+
+    /// Count responses within the window before `now`, returning the bursts
+    /// that started or cleared
+    pub fn evaluate(&mut self, now: Instant) -> Vec<BurstEvent> {
+        self.evaluated_at = Some(now);
+        while self
+            .responses
+            .front()
+            .is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > self.window)
+        {
+            self.responses.pop_front();
+        }
+
+        let mut counts: HashMap<(BurstKind, &str), usize> = HashMap::new();
+        for (_, kind, path) in &self.responses {
+            *counts.entry((*kind, path.as_str())).or_default() += 1;
+        }
+
+        let mut events = Vec::new();
+        let clear_at = self.threshold / 2;
+        self.active.retain_mut(|burst| {
+            burst.count = counts
+                .get(&(burst.kind, burst.path.as_str()))
+                .copied()
+                .unwrap_or(0);
+            if burst.count > clear_at {
+                return true;
+            }
+            events.push(BurstEvent::Cleared {
+                burst: burst.clone(),
+                after: now.saturating_duration_since(burst.since),
+            });
+            false
+        });
+
+        let mut started: Vec<Burst> = counts
+            .into_iter()
+            .filter(|((kind, path), count)| {
+                *count > self.threshold
+                    && !self
+                        .active
+                        .iter()
+                        .any(|burst| burst.kind == *kind && burst.path == *path)
+            })
+            .map(|((kind, path), count)| Burst {
+                kind,
+                path: path.to_string(),
+                count,
+                window: self.window,
+                since: now,
+            })
+            .collect();
+        started.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+        for burst in started {
+            events.push(BurstEvent::Started(burst.clone()));
+            self.active.push(burst);
+        }
+
+        events
+    }
+
+    /// Paths bursting as of the last check, in the order they started
+    pub fn active(&self) -> &[Burst] {
+        &self.active
+    }
+}
+
+/// Path with its query string dropped and id-like segments replaced by `:id`,
+/// so `/users/42?tab=1` and `/users/7` count as one endpoint
+pub fn normalize_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    path.split('/')
+        .map(|segment| if is_id(segment) { ":id" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Numbers, UUIDs and long hex digests
+fn is_id(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    if segment.bytes().all(|b| b.is_ascii_digit()) {
+        return true;
+    }
+    let hex_digits = segment.bytes().filter(u8::is_ascii_hexdigit).count();
+    let dashes = segment.bytes().filter(|b| *b == b'-').count();
+    let is_uuid = segment.len() == 36 && dashes == 4 && hex_digits == 32;
+    let is_digest = segment.len() >= 16 && hex_digits == segment.len();
+    is_uuid || is_digest
+}
@@ -0,0 +1,207 @@
+//! Duplicate requests: the same request completing again within moments
+//!
+//! A double-clicked submit button, or a frontend effect that fires its POST
+//! three times, shows up as identical requests finishing back to back.
+//! Requests match on method, path (query string dropped, ids kept - two
+//! records aren't a double submit) and parameters when they were logged.
+//! Each completion is checked against the ones in the window before it, so
+//! nothing is rescanned as history grows; a run of duplicates is reported
+//! once it's over.
+
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::path_glob::{self, PathGlob};
+
+/// Completions of the same request this close together count as duplicates
+pub const DEFAULT_DUPLICATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Requests in a run before it's flagged
+pub const DEFAULT_DUPLICATE_MIN_COUNT: usize = 2;
+
+/// Methods checked unless configured; repeated GETs are usually harmless
+pub const DEFAULT_DUPLICATE_METHODS: &[&str] = &["POST", "PUT", "PATCH", "DELETE"];
+
+/// Completions kept for matching, however busy the app
+const MAX_RECENT_COMPLETIONS: usize = 1_000;
+
+/// What counts as a duplicate
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateSettings {
+    pub window: Duration,
+    pub min_count: usize,
+    /// Upper-case methods to check
+    pub methods: Vec<String>,
+    /// Paths never flagged, e.g. polling endpoints
+    pub exclude: Vec<PathGlob>,
+}
+
+impl Default for DuplicateSettings {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_DUPLICATE_WINDOW,
+            min_count: DEFAULT_DUPLICATE_MIN_COUNT,
+            methods: DEFAULT_DUPLICATE_METHODS
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// How often a request was repeated, shown on each request of the run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Duplicates {
+    pub count: usize,
+    /// From the first completion of the run to the last
+    pub span: Duration,
+}
+
+impl Duplicates {
+    /// `×3 in 180ms`
+    pub fn badge(&self) -> String {
+        let span = if self.span < Duration::from_secs(1) {
+            format!("{}ms", self.span.as_millis())
+        } else {
+            format!("{:.1}s", self.span.as_secs_f64())
+        };
+        format!("×{} in {}", self.count, span)
+    }
+}
+
+/// A run of identical requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub method: String,
+    /// Path without its query string
+    pub path: String,
+    /// Ids of the requests in the run, oldest first
+    pub ids: Vec<u64>,
+    pub first: Instant,
+    pub last: Instant,
+}
+
+impl DuplicateGroup {
+    pub fn duplicates(&self) -> Duplicates {
+        Duplicates {
+            count: self.ids.len(),
+            span: self.last.saturating_duration_since(self.first),
+        }
+    }
+
+    /// One-line summary, as written to the log
+    pub fn message(&self) -> String {
+        format!(
+            "[duplicate] {} {} {} — double submit or retry loop?",
+            self.method,
+            self.path,
+            self.duplicates().badge()
+        )
+    }
+}
+
+/// Matches each completed request against the ones just before it
+#[derive(Debug, Default)]
+pub struct DuplicateDetector {
+    settings: DuplicateSettings,
+    /// Completion time, request key (hash of method, path and parameters) and id
+    recent: VecDeque<(Instant, u64, u64)>,
+    /// Runs that may still grow, by request key
+    open: Vec<(u64, DuplicateGroup)>,
+}
+
+impl DuplicateDetector {
+    pub fn new(settings: DuplicateSettings) -> Self {
+        Self {
+            settings: DuplicateSettings {
+                min_count: settings.min_count.max(2),
+                ..settings
+            },
+            ..Self::default()
+        }
+    }
+
+    /// Note a completed request; returns the run it belongs to once it's a
+    /// duplicate. `params` is a hash of its logged parameters, if any.
+    pub fn record(
+        &mut self,
+        now: Instant,
+        id: u64,
+        method: &str,
+        path: &str,
+        params: Option<u64>,
+    ) -> Option<&DuplicateGroup> {
+        let method = method.to_ascii_uppercase();
+        if !self.settings.methods.contains(&method)
+            || path_glob::matches_any(&self.settings.exclude, path)
+        {
+            return None;
+        }
+
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let mut hasher = DefaultHasher::new();
+        (&method, path, query, params).hash(&mut hasher);
+        let key = hasher.finish();
+
+        let window = self.settings.window;
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > window)
+        {
+            self.recent.pop_front();
+        }
+        let earlier: Vec<(Instant, u64)> = self
+            .recent
+            .iter()
+            .filter(|(_, recent_key, _)| *recent_key == key)
+            .map(|(at, _, id)| (*at, *id))
+            .collect();
+        self.recent.push_back((now, key, id));
+        if self.recent.len() > MAX_RECENT_COMPLETIONS {
+            self.recent.pop_front();
+        }
+
+        // A run keeps going while each repeat follows within the window
+        if let Some(index) = self.open.iter().position(|(open_key, group)| {
+            *open_key == key && now.saturating_duration_since(group.last) <= window
+        }) {
+            let group = &mut self.open[index].1;
+            group.ids.push(id);
+            group.last = now;
+            return Some(&self.open[index].1);
+        }
+
+        if earlier.len() + 1 < self.settings.min_count {
+            return None;
+        }
+        let group = DuplicateGroup {
+            method,
+            path: path.to_string(),
+            ids: earlier.iter().map(|(_, id)| *id).chain([id]).collect(),
+            first: earlier.first().map_or(now, |(at, _)| *at),
+            last: now,
+        };
+        self.open.push((key, group));
+        self.open.last().map(|(_, group)| group)
+    }
+
+    /// Runs with no repeat within the window before `now`; each is returned once
+    pub fn poll(&mut self, now: Instant) -> Vec<DuplicateGroup> {
+        let window = self.settings.window;
+        let mut finished = Vec::new();
+        self.open.retain(|(_, group)| {
+            if now.saturating_duration_since(group.last) <= window {
+                return true;
+            }
+            finished.push(group.clone());
+            false
+        });
+        finished
+    }
+}
@@ -1,36 +1,319 @@
-use crate::parser::{HttpRequest, LogEvent, SqlQuery};
+mod background;
+mod bursts;
+mod duplicates;
+mod n_plus_one;
+mod queueing;
+mod turbo;
+
+pub use background::{
+    BACKGROUND_LABEL, BackgroundQueries, RECENT_BACKGROUND_QUERIES, is_console_prompt,
+};
+pub use bursts::{
+    BURST_EVAL_INTERVAL, Burst, BurstDetector, BurstEvent, BurstKind, DEFAULT_BURST_THRESHOLD,
+    DEFAULT_BURST_WINDOW, normalize_path,
+};
+pub use duplicates::{
+    DEFAULT_DUPLICATE_METHODS, DEFAULT_DUPLICATE_MIN_COUNT, DEFAULT_DUPLICATE_WINDOW,
+    DuplicateDetector, DuplicateGroup, DuplicateSettings, Duplicates,
+};
+pub use n_plus_one::{AggregatedNPlusOne, aggregate_n_plus_one};
+pub use queueing::{
+    BACK_TO_BACK, DEFAULT_QUEUE_TIME_WARNING, DEFAULT_REQUEST_CAPACITY, QUEUE_TIME_KEYS,
+    QUEUEING_WINDOW, QueueEstimator, QueueTime, request_capacity_from_env,
+};
+pub use turbo::{
+    DEFAULT_TURBO_BURST_THRESHOLD, DEFAULT_TURBO_BURST_WINDOW, StreamStats, TurboBurst,
+    TurboStreams, describe_stream,
+};
+
+use crate::database::TableInfo;
+use crate::diagnostics::{Limits, StoreKind, StoreUsage, keep_last, string_bytes};
+use crate::parser::{
+    BulletWarning, HttpRequest, LogEvent, ProfilerTimings, REQUEST_ID_KEY, SqlQuery, ViewRender,
+};
 use crate::query::{
-    NPlusOneDetector, NPlusOneIssue, QueryFingerprint, QueryInfo, QueryType, RequestContext,
+    FingerprintOptions, NPlusOneDetector, NPlusOneIssue, NPlusOneSource, QueryFingerprint,
+    QueryInfo, QueryType, RequestContext,
 };
-use std::collections::VecDeque;
+use crate::rails::routes::RouteTable;
+use crate::search::SearchQuery;
+use crate::shared::{Shared, SnapshotCache};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// How soon after a request fails an exception logged is taken to be its
+pub const EXCEPTION_LINK_WINDOW: Duration = Duration::from_secs(2);
 
 /// Tracks request contexts and groups queries by request
 pub struct RequestContextTracker {
-    current_requests: Arc<Mutex<VecDeque<RequestContext>>>,
-    completed_requests: Arc<Mutex<Vec<CompletedRequest>>>,
-    max_completed: usize,
+    current_requests: Shared<VecDeque<RequestContext>>,
+    /// In-flight requests as last handed out, oldest first
+    in_flight: SnapshotCache<Vec<RequestContext>>,
+    completed_requests: Shared<Vec<CompletedRequest>>,
+    /// SQL logged with no request in flight, or by a console
+    background: Shared<BackgroundQueries>,
+    max_completed: AtomicUsize,
+    /// Requests completed this session, including those since evicted
+    completed_total: AtomicU64,
+    /// Id handed to the next request that starts
+    next_id: AtomicU64,
+    fingerprint_options: FingerprintOptions,
+    /// Render stats per template, across every request this session
+    view_stats: Arc<Mutex<HashMap<String, ViewStats>>>,
+    /// Queue time logged ahead of the next `Started` line (rack-timeout)
+    pending_queue_time: Mutex<Option<f64>>,
+    /// Set once the logs report queueing; estimates stop from then on
+    queueing_measured: AtomicBool,
+    queue_estimator: Mutex<QueueEstimator>,
+    /// Average queue time (ms) at which the header warns about capacity
+    queue_time_warning: f64,
+    /// 404 / 5xx responses per path, for the burst warning
+    bursts: Mutex<BurstDetector>,
+    /// Recent completions, for flagging double submits and retry storms
+    duplicates: Mutex<DuplicateDetector>,
+    /// Turbo Stream broadcasts per stream
+    turbo: Mutex<TurboStreams>,
+    /// Turbo Stream bursts not yet reported
+    turbo_bursts: Mutex<Vec<TurboBurst>>,
+    /// Tables from `db/schema.rb`, confirming N+1 `includes` suggestions
+    schema: Mutex<Arc<HashMap<String, TableInfo>>>,
+}
+
+/// A request found by its logged id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestIdMatch {
+    pub request_id: String,
+    /// `GET /orders`, or just the path when no method was logged
+    pub label: String,
+    pub target: RequestTarget,
+}
+
+impl RequestIdMatch {
+    fn new(context: &RequestContext, target: RequestTarget) -> Self {
+        let path = context.path.as_deref().unwrap_or("<unknown>");
+        Self {
+            request_id: context.request_id.clone().unwrap_or_default(),
+            label: match context.method.as_deref() {
+                Some(method) => format!("{} {}", method, path),
+                None => path.to_string(),
+            },
+            target,
+        }
+    }
+}
+
+/// Where a matched request lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestTarget {
+    /// Index into [`RequestContextTracker::get_recent_requests`]
+    Completed(usize),
+    /// Tracker id of a request still in flight
+    InFlight(u64),
+}
+
+/// What a saved session keeps of the tracker: completed requests, render
+/// stats and background queries. Requests still in flight are left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestSnapshot {
+    pub completed: Vec<CompletedRequest>,
+    pub view_stats: Vec<ViewStats>,
+    #[serde(default)]
+    pub background: BackgroundQueries,
+}
+
+/// Session-wide render stats for one template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewStats {
+    pub template: String,
+    /// `Rendered` lines logged for the template
+    pub renders: usize,
+    /// Partial instances, counting each item of a collection render
+    pub items: usize,
+    pub total_duration: f64,
+    pub max_duration: f64,
+}
+
+impl ViewStats {
+    fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+            renders: 0,
+            items: 0,
+            total_duration: 0.0,
+            max_duration: 0.0,
+        }
+    }
+
+    fn record(&mut self, view: &ViewRender) {
+        self.renders += 1;
+        self.items += view.items();
+        self.total_duration += view.duration;
+        self.max_duration = self.max_duration.max(view.duration);
+    }
+
+    pub fn avg_duration(&self) -> f64 {
+        if self.renders == 0 {
+            0.0
+        } else {
+            self.total_duration / self.renders as f64
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompletedRequest {
     pub context: RequestContext,
     pub n_plus_one_issues: Vec<NPlusOneIssue>,
     pub total_duration: Option<f64>,
     pub status: Option<u16>,
+    /// Extra fields from the completion line (`bytes`, `content_type`, `db`, ...)
+    pub extra: HashMap<String, String>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub completed_at: Instant,
+    /// Set when the same request completed repeatedly within moments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<Duplicates>,
+}
+
+impl CompletedRequest {
+    pub fn approx_bytes(&self) -> usize {
+        self.context.approx_bytes() + std::mem::size_of::<Self>()
+            - std::mem::size_of::<RequestContext>()
+            + self
+                .n_plus_one_issues
+                .iter()
+                .map(NPlusOneIssue::approx_bytes)
+                .sum::<usize>()
+            + self
+                .extra
+                .iter()
+                .map(|(key, value)| string_bytes(key) + string_bytes(value))
+                .sum::<usize>()
+    }
+}
+
+fn hash_params(params: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    params.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Position of the request logged with `request_id`
+fn position_of<'a>(
+    requests: impl IntoIterator<Item = &'a RequestContext>,
+    request_id: &str,
+) -> Option<usize> {
+    requests
+        .into_iter()
+        .position(|context| context.request_id.as_deref() == Some(request_id))
+}
+
+/// The path of a logged URL or path, without host or query string
+fn path_only(url: &str) -> &str {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |start| &rest[start..]),
+        None => url,
+    };
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+impl Default for RequestContextTracker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RequestContextTracker {
     pub fn new() -> Self {
         Self {
-            current_requests: Arc::new(Mutex::new(VecDeque::new())),
-            completed_requests: Arc::new(Mutex::new(Vec::new())),
-            max_completed: 100,
+            current_requests: Shared::default(),
+            in_flight: SnapshotCache::default(),
+            completed_requests: Shared::default(),
+            background: Shared::default(),
+            max_completed: AtomicUsize::new(Limits::default().max_completed_requests),
+            completed_total: AtomicU64::new(0),
+            next_id: AtomicU64::new(1),
+            fingerprint_options: FingerprintOptions::default(),
+            view_stats: Arc::new(Mutex::new(HashMap::new())),
+            pending_queue_time: Mutex::new(None),
+            queueing_measured: AtomicBool::new(false),
+            queue_estimator: Mutex::new(QueueEstimator::default()),
+            queue_time_warning: DEFAULT_QUEUE_TIME_WARNING,
+            bursts: Mutex::new(BurstDetector::default()),
+            duplicates: Mutex::new(DuplicateDetector::default()),
+            turbo: Mutex::default(),
+            turbo_bursts: Mutex::default(),
+            schema: Mutex::new(Arc::new(HashMap::new())),
         }
     }
 
+    /// Threads per server, used to estimate queueing when it isn't logged
+    pub fn with_request_capacity(self, capacity: usize) -> Self {
+        *self.queue_estimator.lock().unwrap() = QueueEstimator::new(capacity);
+        self
+    }
+
+    /// Use a custom average queue time (ms) for the capacity warning
+    pub fn with_queue_time_warning(mut self, ms: f64) -> Self {
+        self.queue_time_warning = ms;
+        self
+    }
+
+    /// Warn when a path answers more than `threshold` 404s (or 5xx) within `window`
+    pub fn with_burst_detection(self, threshold: usize, window: Duration) -> Self {
+        *self.bursts.lock().unwrap() = BurstDetector::new(threshold, window);
+        self
+    }
+
+    /// Warn when a Turbo Stream broadcasts more than `threshold` times within `window`
+    pub fn with_turbo_burst_detection(self, threshold: usize, window: Duration) -> Self {
+        *self.turbo.lock().unwrap() = TurboStreams::new(threshold, window);
+        self
+    }
+
+    /// Flag requests repeated as `settings` describes
+    pub fn with_duplicate_detection(self, settings: DuplicateSettings) -> Self {
+        *self.duplicates.lock().unwrap() = DuplicateDetector::new(settings);
+        self
+    }
+
+    pub fn with_limits(self, limits: Limits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Change how many completed requests are kept, dropping the oldest beyond it
+    pub fn set_limits(&self, limits: Limits) {
+        self.max_completed
+            .store(limits.max_completed_requests, Ordering::Relaxed);
+        self.trim(StoreKind::CompletedRequests, limits.max_completed_requests);
+    }
+
+    /// Set which differences are ignored when grouping queries for N+1 detection
+    pub fn with_fingerprint_options(mut self, options: FingerprintOptions) -> Self {
+        self.fingerprint_options = options;
+        self
+    }
+
+    /// Use tables parsed from `db/schema.rb` to confirm N+1 suggestions
+    pub fn with_schema(self, tables: HashMap<String, TableInfo>) -> Self {
+        self.set_schema(tables);
+        self
+    }
+
+    /// Replace the tables, e.g. after a migration rewrote `db/schema.rb`
+    pub fn set_schema(&self, tables: HashMap<String, TableInfo>) {
+        *self.schema.lock().unwrap() = Arc::new(tables);
+    }
+
     pub fn process_log_event(&self, event: &LogEvent) {
         match event {
             LogEvent::HttpRequest(req) => {
@@ -41,10 +324,11 @@ impl RequestContextTracker {
                     self.complete_request(req);
 
                     // Then start this new request (keeps it active to collect queries)
-                    self.start_request(req);
+                    // Lograge logs at completion, so start times can't show queueing
+                    self.start_request(req, false);
                 } else if req.status.is_none() {
                     // Traditional format: Request started
-                    self.start_request(req);
+                    self.start_request(req, true);
                 } else {
                     // Traditional format: Request completed (has status but no path)
                     self.complete_request(req);
@@ -53,81 +337,759 @@ impl RequestContextTracker {
             LogEvent::SqlQuery(query) => {
                 self.add_query_to_current_request(query);
             }
+            LogEvent::ViewRender(view) => {
+                self.record_view(view);
+            }
+            LogEvent::Cache(cache) => {
+                if let Some(context) = self.current_requests.write().back_mut() {
+                    context.cache.record(cache.operation);
+                }
+            }
+            LogEvent::RequestQueueing(ms) => {
+                self.queueing_measured.store(true, Ordering::Relaxed);
+                *self.pending_queue_time.lock().unwrap() = Some(*ms);
+            }
+            LogEvent::RequestParams(params) => {
+                if let Some(context) = self.current_requests.write().back_mut() {
+                    context.params_hash = Some(hash_params(params));
+                }
+            }
+            LogEvent::RouteNotFound { method, path } => {
+                self.complete_unrouted_request(method, path);
+            }
+            LogEvent::TurboStream(turbo) => {
+                // Broadcasts from callbacks run inside the request that saved
+                let request = self.current_requests.read().back().map(|context| {
+                    RequestIdMatch::new(context, RequestTarget::InFlight(context.id)).label
+                });
+                let burst = self
+                    .turbo
+                    .lock()
+                    .unwrap()
+                    .record(Instant::now(), turbo, request);
+                self.turbo_bursts.lock().unwrap().extend(burst);
+            }
+            LogEvent::Info(message) => {
+                if let Some(controller) = message.strip_prefix("Processing: ") {
+                    self.set_current_controller(controller);
+                }
+            }
             _ => {}
         }
     }
 
-    fn start_request(&self, req: &HttpRequest) {
+    fn start_request(&self, req: &HttpRequest, estimate_queueing: bool) {
         let path = req.path.clone();
         if path.is_empty() {
             return;
         }
 
-        let context = RequestContext::new(Some(path.clone()));
-        let mut requests = self.current_requests.lock().unwrap();
+        let mut context = RequestContext::new(Some(path.clone()));
+        context.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        context.method = Some(req.method.clone()).filter(|method| !method.is_empty());
+        if let (Some(controller), Some(action)) = (&req.controller, &req.action) {
+            context.controller = Some(format!("{}#{}", controller, action));
+        }
+        context.queue_time = self.queue_time_for(req, estimate_queueing);
+        context.request_id = req.extra.get(REQUEST_ID_KEY).cloned();
+        let mut requests = self.current_requests.write();
         requests.push_back(context);
     }
 
-    fn add_query_to_current_request(&self, sql_query: &SqlQuery) {
-        let mut requests = self.current_requests.lock().unwrap();
+    /// Logged queue time for a starting request, else an estimate if none is ever logged
+    fn queue_time_for(&self, req: &HttpRequest, estimate_queueing: bool) -> Option<QueueTime> {
+        let estimate = if estimate_queueing {
+            self.queue_estimator.lock().unwrap().started(Instant::now())
+        } else {
+            None
+        };
+
+        let logged = QUEUE_TIME_KEYS
+            .iter()
+            .find_map(|key| req.extra.get(*key))
+            .and_then(|value| value.trim_end_matches("ms").parse::<f64>().ok())
+            .filter(|ms| ms.is_finite() && *ms >= 0.0);
+        if logged.is_some() {
+            self.queueing_measured.store(true, Ordering::Relaxed);
+        }
+        if let Some(ms) = logged.or_else(|| self.pending_queue_time.lock().unwrap().take()) {
+            return Some(QueueTime {
+                ms,
+                estimated: false,
+            });
+        }
 
-        // Add query to the most recent (last) active request
-        // Queries typically belong to the most recently started request
+        estimate
+            .filter(|_| !self.queueing_measured.load(Ordering::Relaxed))
+            .map(|ms| QueueTime {
+                ms,
+                estimated: true,
+            })
+    }
+
+    fn set_current_controller(&self, controller: &str) {
+        let mut requests = self.current_requests.write();
         if let Some(context) = requests.back_mut() {
-            let query_info = QueryInfo {
-                raw_query: sql_query.query.clone(),
-                fingerprint: QueryFingerprint::new(&sql_query.query),
-                duration: sql_query.duration.unwrap_or(0.0),
-                rows: sql_query.rows,
-                query_type: QueryType::from_sql(&sql_query.query),
-            };
+            context.controller = Some(controller.to_string());
+        }
+    }
+
+    /// Like [`process_log_event`](Self::process_log_event), for a line
+    /// logged by `process`
+    ///
+    /// Queries from a process marked with [`mark_console`](Self::mark_console)
+    /// are background queries even while a request is in flight.
+    pub fn process_log_event_from(&self, process: &str, event: &LogEvent) {
+        match event {
+            LogEvent::SqlQuery(query) if self.is_console(process) => {
+                let query = self.query_info(query);
+                self.background.write().record(query);
+            }
+            _ => self.process_log_event(event),
+        }
+    }
+
+    /// Treat `process` as a `rails console` from now on; true the first time
+    pub fn mark_console(&self, process: &str) -> bool {
+        if self.is_console(process) {
+            return false;
+        }
+        self.background.write().consoles.insert(process.to_string())
+    }
+
+    pub fn is_console(&self, process: &str) -> bool {
+        self.background.read().consoles.contains(process)
+    }
+
+    /// Queries logged outside any request; shared until the next one
+    pub fn background_queries(&self) -> Arc<BackgroundQueries> {
+        self.background.snapshot()
+    }
+
+    fn query_info(&self, sql_query: &SqlQuery) -> QueryInfo {
+        QueryInfo {
+            raw_query: sql_query.query.clone(),
+            fingerprint: QueryFingerprint::with_options(&sql_query.query, self.fingerprint_options),
+            duration: sql_query.duration.unwrap_or(0.0),
+            rows: sql_query.rows,
+            query_type: QueryType::from_sql(&sql_query.query),
+            binds: sql_query.binds.clone(),
+            name: sql_query.name.clone(),
+            truncated: sql_query.truncated,
+        }
+    }
+
+    fn add_query_to_current_request(&self, sql_query: &SqlQuery) {
+        let query_info = self.query_info(sql_query);
+        let mut requests = self.current_requests.write();
+
+        // The request tagged with the query's id; without one, the most
+        // recently started request, which is right unless requests overlap.
+        // A tagged query whose request isn't in flight (its `Started` line
+        // was evicted or never seen) belongs to none of the others
+        let position = match sql_query.request_id.as_deref() {
+            Some(id) => position_of(requests.iter(), id),
+            None => requests.len().checked_sub(1),
+        };
+        match position.and_then(|index| requests.get_mut(index)) {
+            Some(context) => context.add_query(query_info),
+            // A job, runner or console, or a request no longer tracked
+            None => {
+                drop(requests);
+                self.background.write().record(query_info);
+            }
+        }
+    }
+
+    fn record_view(&self, view: &ViewRender) {
+        self.view_stats
+            .lock()
+            .unwrap()
+            .entry(view.template.clone())
+            .or_insert_with(|| ViewStats::new(&view.template))
+            .record(view);
 
-            context.add_query(query_info);
+        // Like queries, renders belong to the most recently started request
+        if let Some(context) = self.current_requests.write().back_mut() {
+            context.add_view(view.clone());
         }
     }
 
     fn complete_request(&self, req: &HttpRequest) {
-        let mut requests = self.current_requests.lock().unwrap();
-
-        // Use FIFO: pop the oldest request (first in, first out)
-        // Rails typically completes requests in the order they started
-        if let Some(context) = requests.pop_front() {
-            // Detect N+1 issues
-            let n_plus_one_issues = NPlusOneDetector::detect(&context);
-
-            let completed = CompletedRequest {
-                context,
-                n_plus_one_issues,
-                total_duration: req.duration,
-                status: req.status,
-                completed_at: Instant::now(),
+        let mut requests = self.current_requests.write();
+        self.queue_estimator
+            .lock()
+            .unwrap()
+            .completed(Instant::now(), requests.len());
+
+        // The request with the same id; without one, FIFO: Rails typically
+        // completes requests in the order they started. An id matching no
+        // request in flight can only be one whose `Started` line wasn't
+        // tagged (lograge logs the id on completion only), never a request
+        // tagged with another id; with none of those, the completion is dropped
+        let position = match req.extra.get(REQUEST_ID_KEY) {
+            Some(id) => position_of(requests.iter(), id).or_else(|| {
+                requests
+                    .iter()
+                    .position(|context| context.request_id.is_none())
+            }),
+            None => Some(0),
+        };
+        let context = position.and_then(|index| requests.remove(index));
+        if let Some(context) = context {
+            self.finish_request(context, req.duration, req.status, req.extra.clone());
+        }
+    }
+
+    /// A routing error ends its request without a `Completed` line; finish
+    /// the in-flight request for the path as a 404
+    fn complete_unrouted_request(&self, method: &str, path: &str) {
+        let mut requests = self.current_requests.write();
+        let position = requests.iter().position(|context| {
+            context.path.as_deref() == Some(path)
+                && context.method.as_deref().is_none_or(|m| m == method)
+        });
+        match position.and_then(|index| requests.remove(index)) {
+            Some(context) => self.finish_request(context, None, Some(404), HashMap::new()),
+            // Never started (or already evicted), but the 404 still counts
+            None => self
+                .bursts
+                .lock()
+                .unwrap()
+                .record(Instant::now(), 404, path),
+        }
+    }
+
+    fn finish_request(
+        &self,
+        mut context: RequestContext,
+        duration: Option<f64>,
+        status: Option<u16>,
+        mut extra: HashMap<String, String>,
+    ) {
+        let completed_at = Instant::now();
+        // The id may be on either line; keep it in both places so exports carry it
+        if context.request_id.is_none() {
+            context.request_id = extra.get(REQUEST_ID_KEY).cloned();
+        }
+        if let Some(id) = &context.request_id {
+            extra
+                .entry(REQUEST_ID_KEY.to_string())
+                .or_insert_with(|| id.clone());
+        }
+        if let (Some(status), Some(path)) = (status, context.path.as_deref()) {
+            self.bursts
+                .lock()
+                .unwrap()
+                .record(completed_at, status, path);
+        }
+
+        // Detect N+1 issues
+        let schema = self.schema.lock().unwrap().clone();
+        let n_plus_one_issues = NPlusOneDetector::detect(&context, &schema);
+
+        // Lograge logs parameters as a field rather than their own line
+        let params_hash = context
+            .params_hash
+            .or_else(|| extra.get("params").map(|params| hash_params(params)));
+        let mut completed_requests = self.completed_requests.write();
+        let mut duplicates = None;
+        if let (Some(method), Some(path)) = (context.method.as_deref(), context.path.as_deref()) {
+            let mut detector = self.duplicates.lock().unwrap();
+            if let Some(group) =
+                detector.record(completed_at, context.id, method, path, params_hash)
+            {
+                // Earlier requests of the run get the updated badge too
+                let marked = group.duplicates();
+                for req in completed_requests
+                    .iter_mut()
+                    .rev()
+                    .take_while(|req| req.completed_at >= group.first)
+                    .filter(|req| group.ids.contains(&req.context.id))
+                {
+                    req.duplicates = Some(marked);
+                }
+                duplicates = Some(marked);
+            }
+        }
+
+        let completed = CompletedRequest {
+            context,
+            n_plus_one_issues,
+            total_duration: duration,
+            status,
+            extra,
+            completed_at,
+            duplicates,
+        };
+        completed_requests.push(completed);
+        self.completed_total.fetch_add(1, Ordering::Relaxed);
+
+        // Keep only the most recent requests
+        if completed_requests.len() > self.max_completed.load(Ordering::Relaxed) {
+            completed_requests.remove(0);
+        }
+    }
+
+    /// Newest completed request for `method` and `path`, or simply the newest
+    /// when the line that refers back to it didn't say which
+    fn latest_completed<'a>(
+        completed: &'a mut [CompletedRequest],
+        method: Option<&str>,
+        path: Option<&str>,
+    ) -> Option<&'a mut CompletedRequest> {
+        let path = path.map(path_only);
+        completed.iter_mut().rev().find(|req| {
+            let context = &req.context;
+            path.is_none_or(|path| context.path.as_deref().map(path_only) == Some(path))
+                && method.is_none_or(|method| context.method.as_deref().is_none_or(|m| m == method))
+        })
+    }
+
+    /// Attach a Bullet report to the request it was logged for
+    ///
+    /// Bullet knows which association was lazily loaded, so its reports
+    /// replace the heuristic's guesses for that request. Returns false when
+    /// no completed request matches.
+    pub fn record_bullet_warning(&self, warning: &BulletWarning) -> bool {
+        let mut completed = self.completed_requests.write();
+        let Some(req) = Self::latest_completed(
+            &mut completed,
+            warning.method.as_deref(),
+            warning.path.as_deref(),
+        ) else {
+            return false;
+        };
+
+        let schema = self.schema.lock().unwrap().clone();
+        let issue = NPlusOneDetector::from_bullet(
+            &NPlusOneDetector::detect(&req.context, &schema),
+            warning,
+        );
+        req.n_plus_one_issues
+            .retain(|existing| existing.source == NPlusOneSource::Bullet);
+        if !req
+            .n_plus_one_issues
+            .iter()
+            .any(|existing| existing.suggestion == issue.suggestion)
+        {
+            req.n_plus_one_issues.push(issue);
+        }
+        true
+    }
+
+    /// Add rack-mini-profiler's timings to a request's breakdown as
+    /// `profiler_<phase>` fields; false when no completed request matches
+    pub fn record_profiler_timings(&self, timings: &ProfilerTimings) -> bool {
+        let mut completed = self.completed_requests.write();
+        let Some(req) = Self::latest_completed(
+            &mut completed,
+            timings.method.as_deref(),
+            timings.path.as_deref(),
+        ) else {
+            return false;
+        };
+        for (phase, ms) in &timings.timings {
+            req.extra
+                .insert(format!("profiler_{}", phase), format!("{:.1}", ms));
+        }
+        true
+    }
+
+    /// Count error bursts per route pattern from now on
+    pub fn set_routes(&self, routes: Arc<RouteTable>) {
+        self.bursts.lock().unwrap().set_routes(routes);
+    }
+
+    /// Re-count error bursts on their timer, returning those that started or cleared
+    pub fn poll_bursts(&self, now: Instant) -> Vec<BurstEvent> {
+        self.bursts.lock().unwrap().poll(now)
+    }
+
+    /// Runs of duplicate requests that have ended, each returned once
+    pub fn poll_duplicates(&self, now: Instant) -> Vec<DuplicateGroup> {
+        self.duplicates.lock().unwrap().poll(now)
+    }
+
+    /// Turbo Stream bursts that started since the last call, each returned once
+    pub fn poll_turbo_bursts(&self) -> Vec<TurboBurst> {
+        std::mem::take(&mut *self.turbo_bursts.lock().unwrap())
+    }
+
+    /// Turbo Streams by broadcast volume, busiest first
+    pub fn top_turbo_streams(&self, limit: usize) -> Vec<StreamStats> {
+        self.turbo.lock().unwrap().top(limit)
+    }
+
+    /// Paths answering a burst of 404s or 5xx as of the last check
+    pub fn active_bursts(&self) -> Vec<Burst> {
+        self.bursts.lock().unwrap().active().to_vec()
+    }
+
+    /// No request seen yet, completed or in flight, and no query outside one
+    pub fn is_empty(&self) -> bool {
+        self.completed_requests.read().is_empty()
+            && self.current_requests.read().is_empty()
+            && self.background.read().queries == 0
+    }
+
+    /// Completed requests, oldest first; shared until the next one completes
+    pub fn get_recent_requests(&self) -> Arc<Vec<CompletedRequest>> {
+        self.completed_requests.snapshot()
+    }
+
+    /// How many requests have completed, evicted ones included
+    pub fn completed_total(&self) -> u64 {
+        self.completed_total.load(Ordering::Relaxed)
+    }
+
+    /// The request completed last
+    pub fn last_completed(&self) -> Option<CompletedRequest> {
+        self.completed_requests.read().last().cloned()
+    }
+
+    /// The request an exception logged at `now` was raised in
+    ///
+    /// An exception line tagged with `request_id` belongs to that request,
+    /// in flight or completed, or to none still tracked. Untagged, it's the
+    /// last completed request if that failed (4xx or 5xx) within
+    /// [`EXCEPTION_LINK_WINDOW`], since Rails logs the error after the
+    /// `Completed` line, else the latest still in flight.
+    pub fn request_for_exception(
+        &self,
+        request_id: Option<&str>,
+        now: Instant,
+    ) -> Option<RequestIdMatch> {
+        let current = self.current_requests.read();
+        let completed = self.completed_requests.read();
+        if let Some(id) = request_id {
+            if let Some(index) = position_of(current.iter(), id) {
+                let context = &current[index];
+                return Some(RequestIdMatch::new(
+                    context,
+                    RequestTarget::InFlight(context.id),
+                ));
+            }
+            let index = position_of(completed.iter().map(|req| &req.context), id)?;
+            return Some(RequestIdMatch::new(
+                &completed[index].context,
+                RequestTarget::Completed(index),
+            ));
+        }
+
+        if let Some(index) = completed.len().checked_sub(1) {
+            let req = &completed[index];
+            let failed = req.status.is_some_and(|status| status >= 400);
+            let recent = now.saturating_duration_since(req.completed_at) <= EXCEPTION_LINK_WINDOW;
+            if failed && recent && req.context.path.is_some() {
+                return Some(RequestIdMatch::new(
+                    &req.context,
+                    RequestTarget::Completed(index),
+                ));
+            }
+        }
+        let context = current.back()?;
+        Some(RequestIdMatch::new(
+            context,
+            RequestTarget::InFlight(context.id),
+        ))
+    }
+
+    /// Completed requests whose path or controller contains `query`
+    pub fn get_recent_requests_matching(&self, query: &SearchQuery) -> Vec<CompletedRequest> {
+        let completed = self.completed_requests.read();
+        completed
+            .iter()
+            .filter(|req| {
+                query.matches_any(
+                    req.context
+                        .path
+                        .as_deref()
+                        .into_iter()
+                        .chain(req.context.controller.as_deref()),
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Endpoint with the highest p95 duration among requests completed in the
+    /// `window` before `now`, keyed by `Controller#action` (or path)
+    pub fn slowest_endpoint_p95(&self, now: Instant, window: Duration) -> Option<(String, f64)> {
+        let completed = self.completed_requests.read();
+        let mut durations: HashMap<&str, Vec<f64>> = HashMap::new();
+        for req in completed
+            .iter()
+            .filter(|req| now.saturating_duration_since(req.completed_at) <= window)
+        {
+            let (Some(endpoint), Some(duration)) = (
+                req.context
+                    .controller
+                    .as_deref()
+                    .or(req.context.path.as_deref()),
+                req.total_duration,
+            ) else {
+                continue;
             };
+            durations.entry(endpoint).or_default().push(duration);
+        }
 
-            let mut completed_requests = self.completed_requests.lock().unwrap();
-            completed_requests.push(completed);
+        durations
+            .into_iter()
+            .map(|(endpoint, mut durations)| {
+                durations.sort_by(|a, b| a.total_cmp(b));
+                let p95_index = ((durations.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+                (endpoint.to_string(), durations[p95_index])
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Average queue time of requests completed in the `window` before `now`
+    /// that have one, with how many that was
+    pub fn average_queue_time(&self, now: Instant, window: Duration) -> Option<(f64, usize)> {
+        let completed = self.completed_requests.read();
+        let queued: Vec<f64> = completed
+            .iter()
+            .filter(|req| now.saturating_duration_since(req.completed_at) <= window)
+            .filter_map(|req| req.context.queue_time.map(|queue| queue.ms))
+            .collect();
+        (!queued.is_empty()).then(|| {
+            (
+                queued.iter().sum::<f64>() / queued.len() as f64,
+                queued.len(),
+            )
+        })
+    }
+
+    /// Average queue time over [`QUEUEING_WINDOW`] when it's at or past the
+    /// warning level, i.e. requests are sustainedly waiting for a thread
+    pub fn queueing_warning(&self, now: Instant) -> Option<f64> {
+        self.average_queue_time(now, QUEUEING_WINDOW)
+            .map(|(average, _)| average)
+            .filter(|average| *average >= self.queue_time_warning)
+    }
+
+    /// In-flight requests, oldest first; shared until one changes
+    pub fn get_current_requests(&self) -> Arc<Vec<RequestContext>> {
+        self.current_requests
+            .derive(&self.in_flight, |current| current.iter().cloned().collect())
+    }
+
+    /// The in-flight request with `id`, as it stands so far
+    pub fn get_in_flight(&self, id: u64) -> Option<RequestContext> {
+        let current = self.current_requests.read();
+        current.iter().find(|context| context.id == id).cloned()
+    }
+
+    /// Requests whose logged id starts with `prefix` (case-insensitive)
+    ///
+    /// Completed requests come first, newest first, then those in flight. A
+    /// request whose id is exactly `prefix` is returned alone.
+    pub fn find_by_request_id(&self, prefix: &str) -> Vec<RequestIdMatch> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        let matches_prefix = |context: &RequestContext| {
+            context
+                .request_id
+                .as_deref()
+                .is_some_and(|id| id.to_lowercase().starts_with(&prefix))
+        };
+
+        let mut matches: Vec<RequestIdMatch> = {
+            let completed = self.completed_requests.read();
+            completed
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, req)| matches_prefix(&req.context))
+                .map(|(index, req)| {
+                    RequestIdMatch::new(&req.context, RequestTarget::Completed(index))
+                })
+                .collect()
+        };
+        let current = self.current_requests.read();
+        matches.extend(
+            current
+                .iter()
+                .filter(|context| matches_prefix(context))
+                .map(|context| RequestIdMatch::new(context, RequestTarget::InFlight(context.id))),
+        );
+
+        if let Some(exact) = matches
+            .iter()
+            .position(|m| m.request_id.to_lowercase() == prefix)
+        {
+            return vec![matches.swap_remove(exact)];
+        }
+        matches
+    }
 
-            // Keep only the most recent requests
-            if completed_requests.len() > self.max_completed {
-                completed_requests.remove(0);
+    /// Index of the request with `id` in [`Self::get_recent_requests`], once completed
+    pub fn completed_position(&self, id: u64) -> Option<usize> {
+        let completed = self.completed_requests.read();
+        completed.iter().rposition(|req| req.context.id == id)
+    }
+
+    /// Id of the request completed last
+    pub fn last_completed_id(&self) -> Option<u64> {
+        self.completed_requests
+            .read()
+            .last()
+            .map(|req| req.context.id)
+    }
+
+    /// Request id and index of the query added last, while its request is in flight
+    pub fn last_query_position(&self) -> Option<(u64, usize)> {
+        let current = self.current_requests.read();
+        let context = current.back()?;
+        Some((context.id, context.queries.len().checked_sub(1)?))
+    }
+
+    /// Duration and query count of the completed request with `id`
+    pub fn completed_summary(&self, id: u64) -> Option<(Option<f64>, usize)> {
+        let completed = self.completed_requests.read();
+        let req = completed.iter().rfind(|req| req.context.id == id)?;
+        Some((req.total_duration, req.context.queries.len()))
+    }
+
+    /// How many queries of request `id`, completed or in flight, share the
+    /// fingerprint of its query at `index`, and whether they were flagged as
+    /// an N+1
+    pub fn query_repeats(&self, id: u64, index: usize) -> Option<(usize, bool)> {
+        let repeats = |queries: &[QueryInfo]| {
+            let fingerprint = &queries.get(index)?.fingerprint;
+            Some(
+                queries
+                    .iter()
+                    .filter(|query| query.fingerprint == *fingerprint)
+                    .count(),
+            )
+        };
+        {
+            let completed = self.completed_requests.read();
+            if let Some(req) = completed.iter().rfind(|req| req.context.id == id) {
+                let count = repeats(&req.context.queries)?;
+                let fingerprint = &req.context.queries[index].fingerprint;
+                let n_plus_one = req
+                    .n_plus_one_issues
+                    .iter()
+                    .any(|issue| issue.fingerprint == *fingerprint);
+                return Some((count, n_plus_one));
             }
         }
+        let current = self.current_requests.read();
+        let context = current.iter().find(|context| context.id == id)?;
+        Some((repeats(&context.queries)?, false))
     }
 
-    pub fn get_recent_requests(&self) -> Vec<CompletedRequest> {
-        let completed = self.completed_requests.lock().unwrap();
-        completed.clone()
+    /// N+1 issues across completed requests, without cloning them
+    pub fn n_plus_one_count(&self) -> usize {
+        let completed = self.completed_requests.read();
+        completed
+            .iter()
+            .map(|req| req.n_plus_one_issues.len())
+            .sum()
     }
 
-    pub fn get_current_requests(&self) -> Vec<RequestContext> {
-        let current = self.current_requests.lock().unwrap();
-        current.iter().cloned().collect()
+    /// N+1 issues across completed requests, grouped by the query that
+    /// repeats, most total time first
+    pub fn get_aggregated_n_plus_one(&self) -> Vec<AggregatedNPlusOne> {
+        aggregate_n_plus_one(&self.completed_requests.read())
     }
 
     pub fn get_all_n_plus_one_issues(&self) -> Vec<NPlusOneIssue> {
-        let completed = self.completed_requests.lock().unwrap();
+        let completed = self.completed_requests.read();
         completed
             .iter()
             .flat_map(|req| req.n_plus_one_issues.clone())
             .collect()
     }
+
+    /// Per-template render stats, most total render time first
+    pub fn get_view_stats(&self) -> Vec<ViewStats> {
+        let mut stats: Vec<ViewStats> = self.view_stats.lock().unwrap().values().cloned().collect();
+        stats.sort_by(|a, b| b.total_duration.total_cmp(&a.total_duration));
+        stats
+    }
+
+    pub fn snapshot(&self) -> RequestSnapshot {
+        RequestSnapshot {
+            completed: self.get_recent_requests().to_vec(),
+            view_stats: self.get_view_stats(),
+            background: (*self.background_queries()).clone(),
+        }
+    }
+
+    /// Replace completed requests and render stats with a saved session's
+    pub fn restore(&self, snapshot: RequestSnapshot) {
+        let next_id = snapshot
+            .completed
+            .iter()
+            .map(|req| req.context.id + 1)
+            .max()
+            .unwrap_or(1);
+        self.next_id.fetch_max(next_id, Ordering::Relaxed);
+        *self.completed_requests.write() = snapshot.completed;
+        *self.background.write() = snapshot.background;
+        *self.view_stats.lock().unwrap() = snapshot
+            .view_stats
+            .into_iter()
+            .map(|stats| (stats.template.clone(), stats))
+            .collect();
+    }
+
+    /// Item counts and approximate sizes of the tracker's stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        let current = self.current_requests.read();
+        let completed = self.completed_requests.read();
+        let views = self.view_stats.lock().unwrap();
+        vec![
+            StoreUsage::new(
+                StoreKind::InFlightRequests,
+                current.len(),
+                current.iter().map(RequestContext::approx_bytes).sum(),
+            ),
+            StoreUsage::new(
+                StoreKind::CompletedRequests,
+                completed.len(),
+                completed.iter().map(CompletedRequest::approx_bytes).sum(),
+            )
+            .with_limit(self.max_completed.load(Ordering::Relaxed)),
+            StoreUsage::new(
+                StoreKind::ViewStats,
+                views.len(),
+                views
+                    .iter()
+                    .map(|(key, stats)| {
+                        key.len() + std::mem::size_of::<ViewStats>() + stats.template.len()
+                    })
+                    .sum(),
+            ),
+        ]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        crate::diagnostics::total_bytes(&self.store_usage())
+    }
+
+    /// Shrink a store to `keep` entries, returning how many were dropped
+    ///
+    /// Completed requests keep the newest; view stats keep the templates with
+    /// the most total render time.
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::CompletedRequests => keep_last(&mut self.completed_requests.write(), keep),
+            StoreKind::ViewStats => {
+                let mut views = self.view_stats.lock().unwrap();
+                let mut by_time: Vec<_> = views.drain().collect();
+                by_time.sort_by(|a, b| b.1.total_duration.total_cmp(&a.1.total_duration));
+                let removed = by_time.len().saturating_sub(keep);
+                *views = by_time.into_iter().take(keep).collect();
+                removed
+            }
+            _ => 0,
+        }
+    }
 }
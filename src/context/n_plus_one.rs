@@ -0,0 +1,87 @@
+//! N+1 issues across the session, grouped by the query that repeats
+//!
+//! Each completed request carries the N+1 issues found in it. Grouped by
+//! fingerprint, the same repeated query shows once with what it cost across
+//! every request it was found in, so the one wasting the most time overall
+//! comes first rather than the one logged last.
+
+use crate::query::{NPlusOneSource, QueryFingerprint};
+
+use super::CompletedRequest;
+
+/// One repeated query, across the requests it was found in
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedNPlusOne {
+    pub fingerprint: QueryFingerprint,
+    /// One of the repeated queries, as logged
+    pub sample_query: String,
+    /// Requests it was found in
+    pub requests: usize,
+    /// Times it ran, across those requests
+    pub occurrences: usize,
+    /// Time spent running it, across those requests
+    pub total_duration: f64,
+    /// Paths of those requests, query strings dropped, in the order first seen
+    pub paths: Vec<String>,
+    /// Position of the latest of those requests among the completed ones
+    pub latest_request: usize,
+    /// Bullet once it reported any of them
+    pub source: NPlusOneSource,
+}
+
+/// N+1 issues in `requests` (oldest first) grouped by fingerprint, most
+/// total time first
+pub fn aggregate_n_plus_one(requests: &[CompletedRequest]) -> Vec<AggregatedNPlusOne> {
+    let mut groups: Vec<AggregatedNPlusOne> = Vec::new();
+    for (index, request) in requests.iter().enumerate() {
+        let path = request
+            .context
+            .path
+            .as_deref()
+            .map(|path| path.split('?').next().unwrap_or(path).to_string());
+        for issue in &request.n_plus_one_issues {
+            let group = match groups
+                .iter_mut()
+                .position(|group| group.fingerprint == issue.fingerprint)
+            {
+                Some(position) => &mut groups[position],
+                None => {
+                    groups.push(AggregatedNPlusOne {
+                        fingerprint: issue.fingerprint.clone(),
+                        sample_query: String::new(),
+                        requests: 0,
+                        occurrences: 0,
+                        total_duration: 0.0,
+                        paths: Vec::new(),
+                        latest_request: index,
+                        source: NPlusOneSource::Heuristic,
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            if group.sample_query.is_empty() {
+                group.sample_query = issue.sample_query.clone();
+            }
+            if group.latest_request != index || group.requests == 0 {
+                group.requests += 1;
+            }
+            group.occurrences += issue.count;
+            group.total_duration += issue.total_duration;
+            group.latest_request = index;
+            if issue.source == NPlusOneSource::Bullet {
+                group.source = NPlusOneSource::Bullet;
+            }
+            if let Some(path) = &path
+                && !group.paths.contains(path)
+            {
+                group.paths.push(path.clone());
+            }
+        }
+    }
+    groups.sort_by(|a, b| {
+        b.total_duration
+            .total_cmp(&a.total_duration)
+            .then(b.occurrences.cmp(&a.occurrences))
+    });
+    groups
+}
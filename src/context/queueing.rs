@@ -0,0 +1,97 @@
+//! Request queueing: time a request waited (in puma's backlog, behind busy
+//! threads) before Rails started on it. Rails' own duration leaves it out.
+//!
+//! Measured values come from middleware that logs them (rack-timeout's
+//! `wait=`, `request_queueing` fields). Without any, it's estimated from
+//! `Started` lines that follow a completion while every thread was busy.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Threads assumed per server when neither config nor `RAILS_MAX_THREADS` says
+pub const DEFAULT_REQUEST_CAPACITY: usize = 5;
+
+/// Average queue time (ms) over [`QUEUEING_WINDOW`] that flags the server as saturated
+pub const DEFAULT_QUEUE_TIME_WARNING: f64 = 100.0;
+
+/// Window the queueing warning averages over
+pub const QUEUEING_WINDOW: Duration = Duration::from_secs(60);
+
+/// A start this soon after a completion took the thread that completion freed
+pub const BACK_TO_BACK: Duration = Duration::from_millis(5);
+
+/// Lograge / key=value fields that carry a measured queue time in ms
+pub const QUEUE_TIME_KEYS: &[&str] = &["queue_time", "request_queueing", "queueing"];
+
+/// How long a request waited before it was processed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QueueTime {
+    pub ms: f64,
+    /// Inferred from start and completion times rather than logged
+    pub estimated: bool,
+}
+
+/// Threads per server from `RAILS_MAX_THREADS`, when set
+pub fn request_capacity_from_env() -> Option<usize> {
+    std::env::var("RAILS_MAX_THREADS")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|threads| *threads > 0)
+}
+
+/// Estimates queueing when the logs don't report it
+///
+/// Rails logs `Started` only once a thread picks a request up. When every
+/// thread is busy and a request starts the moment another completes, it was
+/// waiting for that thread; it has been waiting at most since the previous
+/// `Started` line, which is the estimate.
+#[derive(Debug)]
+pub struct QueueEstimator {
+    capacity: usize,
+    last_start: Option<Instant>,
+    /// When a completion freed a thread while all of them were busy
+    freed_at: Option<Instant>,
+}
+
+impl Default for QueueEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUEST_CAPACITY)
+    }
+}
+
+impl QueueEstimator {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            last_start: None,
+            freed_at: None,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Note a completion; `in_flight` counts requests running just before it
+    pub fn completed(&mut self, now: Instant, in_flight: usize) {
+        if in_flight >= self.capacity {
+            self.freed_at = Some(now);
+        }
+    }
+
+    /// Note a start, returning its estimated queue time in ms if it queued
+    pub fn started(&mut self, now: Instant) -> Option<f64> {
+        let queued = self
+            .freed_at
+            .take()
+            .is_some_and(|freed| now.saturating_duration_since(freed) <= BACK_TO_BACK);
+        let previous = self.last_start.replace(now);
+
+        previous
+            .filter(|_| queued)
+            .map(|previous| now.saturating_duration_since(previous).as_secs_f64() * 1000.0)
+            .filter(|ms| *ms > 0.0)
+    }
+}
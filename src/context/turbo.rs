@@ -0,0 +1,293 @@
+//! Turbo Stream broadcasts per stream, and runaway ones
+//!
+//! Hotwire apps broadcast a Turbo Stream whenever a model changes, so an
+//! `after_commit` that touches its own record (or a parent that broadcasts
+//! its children) can loop and flood every subscriber. Broadcasts are counted
+//! per stream over the session, and a stream broadcasting more than the
+//! threshold within the window raises one warning, naming the request in
+//! flight when it crossed it. The warning re-arms once the stream's count
+//! falls to half the threshold.
+//!
+//! Each broadcast is logged once by the process that sent it and once per
+//! subscriber by the cable server. Only broadcasts count towards a burst;
+//! transmissions stand in for them on streams whose broadcasts never show
+//! up, e.g. when they're sent from a process Caboose doesn't run.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::parser::{TurboDelivery, TurboStream};
+use crate::ui::formatting::format_window;
+
+/// Broadcasts to one stream within the window that make a burst
+pub const DEFAULT_TURBO_BURST_THRESHOLD: usize = 50;
+
+/// Window Turbo Stream broadcasts are counted over
+pub const DEFAULT_TURBO_BURST_WINDOW: Duration = Duration::from_secs(10);
+
+/// Streams tracked at once; the least recently active is dropped beyond it
+const MAX_STREAMS: usize = 500;
+
+/// Session-wide counts for one stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Broadcasting name as logged
+    pub stream: String,
+    /// The name with signed global ids decoded, e.g. `Post/1:comments`
+    pub label: String,
+    /// Model of the record the stream belongs to, e.g. `Post`
+    pub model: Option<String>,
+    pub broadcasts: usize,
+    pub transmissions: usize,
+    /// Action of the latest stream, e.g. `replace`
+    pub last_action: Option<String>,
+    /// Broadcasts within the window as of the latest one
+    recent: VecDeque<Instant>,
+    last_seen: Instant,
+    bursting: bool,
+}
+
+impl StreamStats {
+    fn new(stream: &str, now: Instant) -> Self {
+        let (label, model) = describe_stream(stream);
+        Self {
+            stream: stream.to_string(),
+            label,
+            model,
+            broadcasts: 0,
+            transmissions: 0,
+            last_action: None,
+            recent: VecDeque::new(),
+            last_seen: now,
+            bursting: false,
+        }
+    }
+
+    /// Broadcasts, or transmissions while no broadcast has been seen
+    pub fn volume(&self) -> usize {
+        if self.broadcasts > 0 {
+            self.broadcasts
+        } else {
+            self.transmissions
+        }
+    }
+
+    /// Whether the stream is broadcasting past the threshold
+    pub fn is_bursting(&self) -> bool {
+        self.bursting
+    }
+}
+
+/// A stream broadcasting past the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurboBurst {
+    pub stream: String,
+    pub label: String,
+    /// Broadcasts within the window when it crossed the threshold
+    pub count: usize,
+    pub window: Duration,
+    /// `POST /posts/1/comments`, the request in flight at the time
+    pub request: Option<String>,
+}
+
+impl TurboBurst {
+    /// One-line summary, as written to the log, e.g.
+    /// `[turbo] Post/1:comments broadcast ×51 in 10s during PATCH /posts/1 — after_commit loop?`
+    pub fn message(&self) -> String {
+        let during = self
+            .request
+            .as_ref()
+            .map(|request| format!(" during {}", request))
+            .unwrap_or_default();
+        format!(
+            "[turbo] {} broadcast ×{} in {}{} — after_commit loop?",
+            self.label,
+            self.count,
+            format_window(self.window),
+            during
+        )
+    }
+}
+
+/// Counts Turbo Stream broadcasts per stream and flags bursts
+#[derive(Debug)]
+pub struct TurboStreams {
+    threshold: usize,
+    window: Duration,
+    streams: HashMap<String, StreamStats>,
+}
+
+impl Default for TurboStreams {
+    fn default() -> Self {
+        Self::new(DEFAULT_TURBO_BURST_THRESHOLD, DEFAULT_TURBO_BURST_WINDOW)
+    }
+}
+
+impl TurboStreams {
+    pub fn new(threshold: usize, window: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            window,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Count a broadcast or transmission; returns the burst it starts, if any
+    ///
+    /// `request` describes the request in flight, when there is one.
+    pub fn record(
+        &mut self,
+        now: Instant,
+        turbo: &TurboStream,
+        request: Option<String>,
+    ) -> Option<TurboBurst> {
+        if !self.streams.contains_key(&turbo.stream) && self.streams.len() >= MAX_STREAMS {
+            self.evict_least_recent();
+        }
+        let stats = self
+            .streams
+            .entry(turbo.stream.clone())
+            .or_insert_with(|| StreamStats::new(&turbo.stream, now));
+        stats.last_seen = now;
+        if turbo.action.is_some() {
+            stats.last_action = turbo.action.clone();
+        }
+        let counts = match turbo.delivery {
+            TurboDelivery::Broadcast => {
+                stats.broadcasts += 1;
+                // The first broadcast replaces transmissions' stand-in counts
+                if stats.broadcasts == 1 {
+                    stats.recent.clear();
+                }
+                true
+            }
+            TurboDelivery::Transmission => {
+                stats.transmissions += 1;
+                stats.broadcasts == 0
+            }
+        };
+        if !counts {
+            return None;
+        }
+
+        stats.recent.push_back(now);
+        while stats
+            .recent
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) > self.window)
+        {
+            stats.recent.pop_front();
+        }
+        // More than the threshold is never needed to decide
+        while stats.recent.len() > self.threshold + 1 {
+            stats.recent.pop_front();
+        }
+
+        let count = stats.recent.len();
+        if stats.bursting {
+            if count <= self.threshold / 2 {
+                stats.bursting = false;
+            }
+            return None;
+        }
+        if count <= self.threshold {
+            return None;
+        }
+        stats.bursting = true;
+        Some(TurboBurst {
+            stream: stats.stream.clone(),
+            label: stats.label.clone(),
+            count,
+            window: self.window,
+            request,
+        })
+    }
+
+    fn evict_least_recent(&mut self) {
+        if let Some(stream) = self
+            .streams
+            .values()
+            .min_by_key(|stats| stats.last_seen)
+            .map(|stats| stats.stream.clone())
+        {
+            self.streams.remove(&stream);
+        }
+    }
+
+    /// Streams by broadcast volume, busiest first
+    pub fn top(&self, limit: usize) -> Vec<StreamStats> {
+        let mut streams: Vec<&StreamStats> = self.streams.values().collect();
+        streams.sort_by(|a, b| {
+            b.volume()
+                .cmp(&a.volume())
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        streams.into_iter().take(limit).cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+}
+
+/// A broadcasting name made readable, and the model it belongs to
+///
+/// `broadcasts_to`/`broadcast_*_to` name a record's stream by its global id,
+/// base64-encoded (`Z2lkOi8vYmxvZy9Qb3N0LzE` for `gid://blog/Post/1`), with
+/// `:`-joined parts for arrays like `[post, :comments]`. Parts that aren't
+/// global ids are kept as they are.
+pub fn describe_stream(stream: &str) -> (String, Option<String>) {
+    let mut model = None;
+    let parts: Vec<String> = stream
+        .split(':')
+        .map(|part| match decode_gid(part) {
+            Some((name, id)) => {
+                let label = format!("{}/{}", name, id);
+                model.get_or_insert(name);
+                label
+            }
+            None => part.to_string(),
+        })
+        .collect();
+    (parts.join(":"), model)
+}
+
+/// Model and id of a base64url-encoded `gid://app/Model/id`
+fn decode_gid(part: &str) -> Option<(String, String)> {
+    // "gid://" encodes to "Z2lkOi8v"
+    if !part.starts_with("Z2lkOi8v") {
+        return None;
+    }
+    let decoded = String::from_utf8(decode_base64url(part)?).ok()?;
+    let path = decoded.strip_prefix("gid://")?;
+    let mut segments = path.splitn(3, '/');
+    let _app = segments.next()?;
+    let model = segments.next().filter(|model| !model.is_empty())?;
+    let id = segments.next().filter(|id| !id.is_empty())?;
+    Some((model.to_string(), id.to_string()))
+}
+
+/// Bytes of URL-safe (or standard) base64, padded or not
+fn decode_base64url(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,81 @@
+//! SQL dialect of the app's database, from the adapter in `config/database.yml`
+//!
+//! Decides how EXPLAIN is run and read, and what index migrations look like.
+//! An unknown or missing adapter gets [`SqlDialect::Generic`], which keeps the
+//! PostgreSQL-flavored defaults used before dialects were told apart.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SqlDialect {
+    Postgres,
+    MySql,
+    Sqlite,
+    #[default]
+    Generic,
+}
+
+impl SqlDialect {
+    /// Dialect for a Rails adapter name such as `postgresql`, `mysql2` or `sqlite3`
+    pub fn from_adapter(adapter: Option<&str>) -> Self {
+        let Some(adapter) = adapter else {
+            return SqlDialect::Generic;
+        };
+        match adapter.trim().to_ascii_lowercase().as_str() {
+            "postgresql" | "postgres" | "postgis" => SqlDialect::Postgres,
+            "mysql" | "mysql2" | "trilogy" => SqlDialect::MySql,
+            "sqlite" | "sqlite3" => SqlDialect::Sqlite,
+            _ => SqlDialect::Generic,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "PostgreSQL",
+            SqlDialect::MySql => "MySQL",
+            SqlDialect::Sqlite => "SQLite",
+            SqlDialect::Generic => "SQL",
+        }
+    }
+
+    /// Statement that asks the database for `query`'s plan
+    pub fn explain_statement(&self, query: &str) -> String {
+        match self {
+            SqlDialect::MySql => format!("EXPLAIN FORMAT=TREE {}", query),
+            SqlDialect::Sqlite => format!("EXPLAIN QUERY PLAN {}", query),
+            SqlDialect::Postgres | SqlDialect::Generic => format!("EXPLAIN {}", query),
+        }
+    }
+
+    /// Migration code adding an index on `table.column`
+    ///
+    /// PostgreSQL builds it concurrently and MySQL in place, so neither blocks
+    /// writes to the table while the index is built.
+    pub fn add_index_migration(&self, table: &str, column: &str) -> String {
+        let add_index = match self {
+            SqlDialect::Postgres => format!(
+                "disable_ddl_transaction!\n\ndef change\n  add_index :{}, :{}, algorithm: :concurrently\nend",
+                table, column
+            ),
+            SqlDialect::MySql => {
+                format!("add_index :{}, :{}, algorithm: :inplace", table, column)
+            }
+            SqlDialect::Sqlite | SqlDialect::Generic => {
+                format!("add_index :{}, :{}", table, column)
+            }
+        };
+        let class_name: String = table
+            .split('_')
+            .filter_map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            })
+            .collect();
+        format!(
+            "# Add to migration:\n{}\n\n# Or generate:\nrails g migration AddIndexTo{} {}:index",
+            add_index, class_name, column
+        )
+    }
+}
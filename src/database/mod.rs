@@ -1,21 +1,43 @@
+mod dialect;
+pub mod schema;
+mod score;
+
+pub use dialect::SqlDialect;
+pub use score::{
+    SCORE_HISTORY_LEN, SCORE_SAMPLE_INTERVAL, ScoreDrop, ScoreHistory, ScoreRange, ScoreSample,
+};
+
+use crate::diagnostics::{Limits, StoreKind, StoreUsage, keep_last, string_bytes, total_bytes};
+use crate::query::{FingerprintOptions, QueryFingerprint};
+use crate::search::SearchQuery;
+use crate::shared::{Shared, SnapshotCache};
 use ratatui::style::{Color, Style};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 // Memory management constants
 const MAX_TABLES_TRACKED: usize = 100;
 const TABLES_WARNING_THRESHOLD: usize = 90; // 90% of max
 
-#[derive(Debug, Clone)]
+/// Queries slower than this (ms) are tracked as slow
+pub const SLOW_QUERY_THRESHOLD: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableInfo {
     pub name: String,
     pub estimated_rows: usize,
     pub has_primary_key: bool,
+    /// Column names, in schema.rb order (the primary key isn't listed)
+    #[serde(default)]
+    pub columns: Vec<String>,
     pub indexes: Vec<IndexInfo>,
     pub foreign_keys: Vec<ForeignKeyInfo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexInfo {
     pub name: String,
     pub columns: Vec<String>,
@@ -23,22 +45,39 @@ pub struct IndexInfo {
     pub usage_count: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForeignKeyInfo {
     pub column: String,
     pub references_table: String,
     pub has_index: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlowQuery {
+    /// Text of the slowest query seen with this fingerprint
     pub query: String,
+    pub fingerprint: QueryFingerprint,
     pub duration: f64,
+    /// Summed duration of every execution
+    pub total_duration: f64,
     pub table: Option<String>,
     pub execution_count: usize,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub last_seen: std::time::Instant,
 }
 
+impl SlowQuery {
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.query.len()
+            + self.fingerprint.normalized.len()
+            + self.table.as_ref().map_or(0, |t| t.len())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum IssueType {
     MissingIndex,
@@ -79,13 +118,35 @@ impl IssueSeverity {
     }
 }
 
+/// Schema, slow queries and score trend, as kept in a saved session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DatabaseSnapshot {
+    pub dialect: SqlDialect,
+    pub tables: HashMap<String, TableInfo>,
+    pub slow_queries: Vec<SlowQuery>,
+    pub stats: QueryStats,
+    pub score_history: Vec<u32>,
+    /// The samples behind the scores, for the drops they show
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub score_samples: Vec<ScoreSample>,
+}
+
 pub struct DatabaseHealth {
-    _tables: Arc<Mutex<HashMap<String, TableInfo>>>,
-    slow_queries: Arc<Mutex<Vec<SlowQuery>>>,
-    query_stats: Arc<Mutex<QueryStats>>,
+    /// Tables from `db/schema.rb`, empty when it wasn't found
+    tables: Arc<Mutex<HashMap<String, TableInfo>>>,
+    slow_queries: Shared<Vec<SlowQuery>>,
+    /// Slow queries slowest first, as last handed out
+    slowest_first: SnapshotCache<Vec<SlowQuery>>,
+    query_stats: Shared<QueryStats>,
+    /// Issues as of the last stats and slow queries
+    issues: SnapshotCache<Vec<DatabaseIssue>>,
+    score_history: Mutex<ScoreHistory>,
+    fingerprint_options: FingerprintOptions,
+    max_slow_queries: AtomicUsize,
+    dialect: Mutex<SqlDialect>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QueryStats {
     pub total_queries: usize,
     pub slow_queries_count: usize,
@@ -94,46 +155,111 @@ pub struct QueryStats {
     pub tables_accessed: HashMap<String, usize>,
 }
 
+impl Default for DatabaseHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DatabaseHealth {
     pub fn new() -> Self {
         Self {
-            _tables: Arc::new(Mutex::new(HashMap::new())),
-            slow_queries: Arc::new(Mutex::new(Vec::new())),
-            query_stats: Arc::new(Mutex::new(QueryStats::default())),
+            tables: Arc::new(Mutex::new(HashMap::new())),
+            slow_queries: Shared::default(),
+            slowest_first: SnapshotCache::default(),
+            query_stats: Shared::default(),
+            issues: SnapshotCache::default(),
+            score_history: Mutex::new(ScoreHistory::new()),
+            fingerprint_options: FingerprintOptions::default(),
+            max_slow_queries: AtomicUsize::new(Limits::default().max_slow_queries),
+            dialect: Mutex::new(SqlDialect::Generic),
         }
     }
 
+    pub fn with_limits(self, limits: Limits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Change the slow-query cap, dropping the least recently seen beyond it
+    pub fn set_limits(&self, limits: Limits) {
+        self.max_slow_queries
+            .store(limits.max_slow_queries, Ordering::Relaxed);
+        self.trim(StoreKind::SlowQueries, limits.max_slow_queries);
+    }
+
+    /// Set which differences are ignored when grouping slow queries
+    pub fn with_fingerprint_options(mut self, options: FingerprintOptions) -> Self {
+        self.fingerprint_options = options;
+        self
+    }
+
+    /// Use tables parsed from `db/schema.rb` for index hints
+    pub fn with_schema(self, tables: HashMap<String, TableInfo>) -> Self {
+        self.set_schema(tables);
+        self
+    }
+
+    /// Replace the tables, e.g. after a migration rewrote `db/schema.rb`
+    pub fn set_schema(&self, tables: HashMap<String, TableInfo>) {
+        *self.tables.lock().unwrap() = tables;
+    }
+
+    /// Write index migrations for the app's database
+    pub fn with_dialect(self, dialect: SqlDialect) -> Self {
+        self.set_dialect(dialect);
+        self
+    }
+
+    /// Switch dialect, e.g. after `config/database.yml` names another adapter
+    pub fn set_dialect(&self, dialect: SqlDialect) {
+        *self.dialect.lock().unwrap() = dialect;
+        // Migrations in the issues are written for the dialect
+        self.issues.clear();
+    }
+
+    pub fn dialect(&self) -> SqlDialect {
+        *self.dialect.lock().unwrap()
+    }
+
     pub fn analyze_query(&self, query: &str, duration: f64) {
-        let mut stats = self.query_stats.lock().unwrap();
+        // Stats before slow queries, everywhere both are held
+        let mut stats = self.query_stats.write();
         stats.total_queries += 1;
 
-        // Track slow queries (>100ms)
-        if duration > 100.0 {
+        if duration > SLOW_QUERY_THRESHOLD {
             stats.slow_queries_count += 1;
 
             // Extract table name
             let table = Self::extract_table_name(query);
 
-            let mut slow_queries = self.slow_queries.lock().unwrap();
+            let mut slow_queries = self.slow_queries.write();
 
-            // Check if we already have this query
-            if let Some(existing) = slow_queries.iter_mut().find(|sq| sq.query == query) {
+            // Group by fingerprint so the same query with other values counts once
+            let fingerprint = QueryFingerprint::with_options(query, self.fingerprint_options);
+            if let Some(existing) = slow_queries
+                .iter_mut()
+                .find(|sq| sq.fingerprint == fingerprint)
+            {
                 existing.execution_count += 1;
+                existing.total_duration += duration;
                 existing.last_seen = std::time::Instant::now();
                 if duration > existing.duration {
+                    existing.query = query.to_string();
                     existing.duration = duration;
                 }
             } else {
                 slow_queries.push(SlowQuery {
                     query: query.to_string(),
+                    fingerprint,
                     duration,
+                    total_duration: duration,
                     table: table.clone(),
                     execution_count: 1,
                     last_seen: std::time::Instant::now(),
                 });
 
-                // Keep only last 50 slow queries
-                if slow_queries.len() > 50 {
+                if slow_queries.len() > self.max_slow_queries.load(Ordering::Relaxed) {
                     slow_queries.remove(0);
                 }
             }
@@ -176,7 +302,7 @@ impl DatabaseHealth {
         }
 
         // Check for SELECT *
-        if query.to_uppercase().contains("SELECT *") {
+        if crate::query::selects_star(query) {
             stats.select_star_count += 1;
         }
 
@@ -186,52 +312,100 @@ impl DatabaseHealth {
         }
     }
 
-    fn extract_table_name(query: &str) -> Option<String> {
-        let query_upper = query.to_uppercase();
-
-        // Try to find table name after FROM
-        if let Some(from_pos) = query_upper.find(" FROM ") {
-            let after_from = &query[from_pos + 6..];
-            let table = after_from
-                .split_whitespace()
-                .next()?
-                .trim_matches('"')
-                .trim_matches('`')
-                .to_string();
-            return Some(table);
+    /// Primary table a query reads or writes
+    ///
+    /// The first table after `FROM`, `UPDATE` or `INTO`, so `FROM a INNER JOIN b`
+    /// gives `a`. Quotes and schema qualifiers are stripped (`"public"."users"`
+    /// gives `users`), and tables outside subqueries win over those inside.
+    pub fn extract_table_name(query: &str) -> Option<String> {
+        let bytes = query.as_bytes();
+        let mut depth = 0usize;
+        let mut found: Option<(usize, String)> = None;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                quote @ (b'\'' | b'"' | b'`') => i = skip_quoted(bytes, i, quote),
+                b'(' => {
+                    depth += 1;
+                    i += 1;
+                }
+                b')' => {
+                    depth = depth.saturating_sub(1);
+                    i += 1;
+                }
+                c if is_word_byte(c) => {
+                    let start = i;
+                    while i < bytes.len() && is_word_byte(bytes[i]) {
+                        i += 1;
+                    }
+                    let word = &query[start..i];
+                    if ["FROM", "UPDATE", "INTO"]
+                        .iter()
+                        .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                        && found.as_ref().is_none_or(|(at, _)| depth < *at)
+                        && let Some(table) = qualified_name_at(query, i)
+                    {
+                        found = Some((depth, table));
+                    }
+                }
+                _ => i += 1,
+            }
         }
 
-        // Try UPDATE
-        if let Some(update_pos) = query_upper.find("UPDATE ") {
-            let after_update = &query[update_pos + 7..];
-            let table = after_update
-                .split_whitespace()
-                .next()?
-                .trim_matches('"')
-                .trim_matches('`')
-                .to_string();
-            return Some(table);
-        }
+        found.map(|(_, table)| table)
+    }
 
-        // Try INSERT INTO
-        if let Some(insert_pos) = query_upper.find("INSERT INTO ") {
-            let after_insert = &query[insert_pos + 12..];
-            let table = after_insert
-                .split_whitespace()
-                .next()?
-                .trim_matches('"')
-                .trim_matches('`')
-                .to_string();
-            return Some(table);
+    /// Columns compared in a query's WHERE clause, in order of appearance
+    pub fn where_columns(query: &str) -> Vec<String> {
+        static WHERE: OnceLock<Regex> = OnceLock::new();
+        static CLAUSE_END: OnceLock<Regex> = OnceLock::new();
+        static COMPARISON: OnceLock<Regex> = OnceLock::new();
+        let where_re = WHERE.get_or_init(|| Regex::new(r"(?i)\bwhere\b").unwrap());
+        let clause_end = CLAUSE_END.get_or_init(|| {
+            Regex::new(r"(?i)\b(?:order\s+by|group\s+by|having|limit|offset)\b").unwrap()
+        });
+        let comparison = COMPARISON.get_or_init(|| {
+            Regex::new(
+                r#"(?i)(?:[\w"`]+\.)?["`]?([a-z_]\w*)["`]?\s*(?:=|!=|<>|<=|>=|<|>|\bin\b|\bis\b|\blike\b|\bilike\b|\bbetween\b|\bnot\b)"#,
+            )
+            .unwrap()
+        });
+
+        let Some(start) = where_re.find(query) else {
+            return Vec::new();
+        };
+        let clause = &query[start.end()..];
+        let clause = clause_end
+            .find(clause)
+            .map_or(clause, |end| &clause[..end.start()]);
+
+        let mut columns: Vec<String> = Vec::new();
+        for caps in comparison.captures_iter(clause) {
+            let column = &caps[1];
+            if ["and", "or", "not", "null"]
+                .iter()
+                .any(|keyword| column.eq_ignore_ascii_case(keyword))
+            {
+                continue;
+            }
+            if !columns.iter().any(|c| c == column) {
+                columns.push(column.to_string());
+            }
         }
+        columns
+    }
 
-        None
+    /// Issues found so far; shared until the next query changes them
+    pub fn get_issues(&self) -> Arc<Vec<DatabaseIssue>> {
+        let generation = self.query_stats.generation() + self.slow_queries.generation();
+        self.issues.get_or_build(generation, || self.find_issues())
     }
 
-    pub fn get_issues(&self) -> Vec<DatabaseIssue> {
+    fn find_issues(&self) -> Vec<DatabaseIssue> {
         let mut issues = Vec::new();
-        let stats = self.query_stats.lock().unwrap();
-        let slow_queries = self.slow_queries.lock().unwrap();
+        let stats = self.query_stats.read();
+        let slow_queries = self.slow_queries.read();
 
         // Issue: High slow query count
         if stats.slow_queries_count > 10 {
@@ -277,7 +451,7 @@ impl DatabaseHealth {
                 title: format!("{} queries may benefit from indexes", stats.missing_index_hints),
                 description: "Slow queries with WHERE clauses detected. Adding indexes may improve performance.".to_string(),
                 recommendation: "Analyze slow queries and add indexes on frequently filtered columns.".to_string(),
-                migration_code: Some("# Review slow queries to determine appropriate indexes\n# rails g migration AddIndexToTable column:index".to_string()),
+                migration_code: Some(self.index_migration(&slow_queries)),
             });
         }
 
@@ -313,15 +487,32 @@ impl DatabaseHealth {
         issues
     }
 
+    /// Index migration for the most executed slow query that filters a
+    /// known table, or a pointer to the generator when there's none
+    fn index_migration(&self, slow_queries: &[SlowQuery]) -> String {
+        slow_queries
+            .iter()
+            .filter_map(|sq| {
+                let table = sq.table.as_ref()?;
+                let column = Self::where_columns(&sq.query).into_iter().next()?;
+                Some((sq.execution_count, table, column))
+            })
+            .max_by_key(|(count, _, _)| *count)
+            .map_or_else(
+                || "# Review slow queries to determine appropriate indexes\n# rails g migration AddIndexToTable column:index".to_string(),
+                |(_, table, column)| self.dialect().add_index_migration(table, &column),
+            )
+    }
+
     pub fn calculate_health_score(&self) -> u32 {
         let issues = self.get_issues();
-        let stats = self.query_stats.lock().unwrap();
+        let stats = self.query_stats.read();
 
         // Start with perfect score
         let mut score = 100u32;
 
         // Deduct points for issues
-        for issue in &issues {
+        for issue in issues.iter() {
             score = score.saturating_sub(issue.severity.score());
         }
 
@@ -343,27 +534,215 @@ impl DatabaseHealth {
         score
     }
 
-    pub fn get_stats(&self) -> QueryStats {
-        self.query_stats.lock().unwrap().clone()
+    /// Add the current health score, with the issues and slow queries
+    /// behind it, to the trend history
+    pub fn record_health_score(&self) {
+        let sample = ScoreSample {
+            time: chrono::Local::now(),
+            score: self.calculate_health_score(),
+            issues: self.get_issues().len(),
+            slow_queries: self.slow_queries.read().len(),
+        };
+        self.score_history
+            .lock()
+            .unwrap()
+            .record(sample, std::time::Instant::now());
+    }
+
+    /// Recorded health scores, oldest first
+    pub fn get_score_history(&self) -> Vec<f64> {
+        self.score_history.lock().unwrap().scores()
+    }
+
+    /// Lowest, highest and latest recorded score; `None` before the first
+    pub fn get_score_range(&self) -> Option<ScoreRange> {
+        self.score_history.lock().unwrap().range()
+    }
+
+    /// The `limit` largest falls in the recorded score, largest first
+    pub fn get_score_drops(&self, limit: usize) -> Vec<ScoreDrop> {
+        self.score_history.lock().unwrap().worst_drops(limit)
+    }
+
+    pub fn get_stats(&self) -> Arc<QueryStats> {
+        self.query_stats.snapshot()
     }
 
-    pub fn get_slow_queries(&self) -> Vec<SlowQuery> {
-        let mut queries = self.slow_queries.lock().unwrap().clone();
+    /// No query analyzed yet, so no health score to speak of
+    pub fn is_empty(&self) -> bool {
+        self.query_stats.read().total_queries == 0
+    }
+
+    pub fn snapshot(&self) -> DatabaseSnapshot {
+        let history = self.score_history.lock().unwrap();
+        DatabaseSnapshot {
+            dialect: self.dialect(),
+            tables: self.tables.lock().unwrap().clone(),
+            slow_queries: self.slow_queries.read().clone(),
+            stats: self.query_stats.read().clone(),
+            score_history: history.scores().iter().map(|&score| score as u32).collect(),
+            score_samples: history.samples().cloned().collect(),
+        }
+    }
+
+    /// Replace everything analyzed so far with a saved session's data
+    pub fn restore(&self, snapshot: DatabaseSnapshot) {
+        self.set_dialect(snapshot.dialect);
+        self.set_schema(snapshot.tables);
+        *self.query_stats.write() = snapshot.stats;
+        *self.slow_queries.write() = snapshot.slow_queries;
+        self.score_history
+            .lock()
+            .unwrap()
+            .restore(snapshot.score_history, snapshot.score_samples);
+    }
+
+    /// Slow queries, slowest first; shared until the next slow query
+    pub fn get_slow_queries(&self) -> Arc<Vec<SlowQuery>> {
+        self.slow_queries.derive(&self.slowest_first, |queries| {
+            let mut queries = queries.clone();
+            queries.sort_by(|a, b| b.duration.partial_cmp(&a.duration).unwrap());
+            queries
+        })
+    }
+
+    /// Item counts and approximate sizes of the analyzer's stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        let stats = self.query_stats.read();
+        let slow = self.slow_queries.read();
+        vec![
+            StoreUsage::new(
+                StoreKind::SlowQueries,
+                slow.len(),
+                slow.iter().map(SlowQuery::approx_bytes).sum(),
+            )
+            .with_limit(self.max_slow_queries.load(Ordering::Relaxed)),
+            StoreUsage::new(
+                StoreKind::TablesAccessed,
+                stats.tables_accessed.len(),
+                stats
+                    .tables_accessed
+                    .keys()
+                    .map(|k| string_bytes(k) + std::mem::size_of::<usize>())
+                    .sum(),
+            )
+            .with_limit(MAX_TABLES_TRACKED),
+        ]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        total_bytes(&self.store_usage())
+    }
+
+    /// Shrink a store to `keep` entries, returning how many were dropped
+    ///
+    /// Slow queries keep the most recently seen; tables keep the most accessed.
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::SlowQueries => {
+                let mut slow = self.slow_queries.write();
+                slow.sort_by_key(|q| q.last_seen);
+                keep_last(&mut slow, keep)
+            }
+            StoreKind::TablesAccessed => {
+                let mut stats = self.query_stats.write();
+                let mut tables: Vec<_> = stats.tables_accessed.drain().collect();
+                tables.sort_by_key(|t| std::cmp::Reverse(t.1));
+                let removed = tables.len().saturating_sub(keep);
+                stats.tables_accessed = tables.into_iter().take(keep).collect();
+                removed
+            }
+            _ => 0,
+        }
+    }
+
+    /// Slow queries whose SQL contains `query`, slowest first
+    pub fn get_slow_queries_matching(&self, query: &SearchQuery) -> Vec<SlowQuery> {
+        let mut queries: Vec<SlowQuery> = self
+            .slow_queries
+            .read()
+            .iter()
+            .filter(|q| query.matches(&q.query))
+            .cloned()
+            .collect();
         queries.sort_by(|a, b| b.duration.partial_cmp(&a.duration).unwrap());
         queries
     }
 
     pub fn get_top_tables(&self) -> Vec<(String, usize)> {
-        let stats = self.query_stats.lock().unwrap();
+        let stats = self.query_stats.read();
         let mut tables: Vec<_> = stats
             .tables_accessed
             .iter()
             .map(|(k, v)| (k.clone(), *v))
             .collect();
-        tables.sort_by(|a, b| b.1.cmp(&a.1));
+        tables.sort_by_key(|t| std::cmp::Reverse(t.1));
         tables.into_iter().take(10).collect()
     }
 
+    /// Slow queries, timings, index coverage and issues for one table
+    pub fn get_table_detail(&self, table: &str) -> TableDetail {
+        let access_count = self
+            .query_stats
+            .read()
+            .tables_accessed
+            .get(table)
+            .copied()
+            .unwrap_or(0);
+
+        let slow_queries: Vec<SlowQuery> = self
+            .get_slow_queries()
+            .iter()
+            .filter(|q| q.table.as_deref() == Some(table))
+            .cloned()
+            .collect();
+
+        let schema = self.tables.lock().unwrap().get(table).cloned();
+        let mut filtered_columns: Vec<FilteredColumn> = Vec::new();
+        for query in &slow_queries {
+            for column in Self::where_columns(&query.query) {
+                if filtered_columns.iter().any(|c| c.name == column) {
+                    continue;
+                }
+                let indexed = schema.as_ref().map(|info| {
+                    info.indexes.iter().any(|index| {
+                        index
+                            .columns
+                            .first()
+                            .is_some_and(|first| first.eq_ignore_ascii_case(&column))
+                    })
+                });
+                filtered_columns.push(FilteredColumn {
+                    name: column,
+                    indexed,
+                });
+            }
+        }
+
+        let issues = self
+            .get_issues()
+            .iter()
+            .filter(|issue| {
+                mentions_table(&issue.title, table)
+                    || mentions_table(&issue.description, table)
+                    || issue
+                        .migration_code
+                        .as_ref()
+                        .is_some_and(|code| mentions_table(code, table))
+            })
+            .cloned()
+            .collect();
+
+        TableDetail {
+            name: table.to_string(),
+            access_count,
+            slow_queries,
+            filtered_columns,
+            in_schema: schema.is_some(),
+            issues,
+        }
+    }
+
     pub fn get_health_style(&self) -> Style {
         let score = self.calculate_health_score();
         let color = match score {
@@ -375,3 +754,115 @@ impl DatabaseHealth {
         Style::default().fg(color)
     }
 }
+
+/// Everything known about one table, for the Database Health drill-down
+#[derive(Debug, Clone)]
+pub struct TableDetail {
+    pub name: String,
+    /// Slow queries recorded against the table
+    pub access_count: usize,
+    /// Slow queries whose primary table this is, slowest first
+    pub slow_queries: Vec<SlowQuery>,
+    /// Columns those queries filter on
+    pub filtered_columns: Vec<FilteredColumn>,
+    /// Whether `db/schema.rb` defines the table
+    pub in_schema: bool,
+    pub issues: Vec<DatabaseIssue>,
+}
+
+impl TableDetail {
+    pub fn executions(&self) -> usize {
+        self.slow_queries.iter().map(|q| q.execution_count).sum()
+    }
+
+    pub fn total_slow_time(&self) -> f64 {
+        self.slow_queries.iter().map(|q| q.total_duration).sum()
+    }
+
+    pub fn avg_slow_time(&self) -> f64 {
+        match self.executions() {
+            0 => 0.0,
+            n => self.total_slow_time() / n as f64,
+        }
+    }
+}
+
+/// A column used in a WHERE clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilteredColumn {
+    pub name: String,
+    /// Whether an index in `db/schema.rb` leads with the column; `None` when
+    /// the table isn't in the schema
+    pub indexed: Option<bool>,
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
+}
+
+/// Index just past the quoted section starting at `start`
+///
+/// A doubled closing quote (`''`, `""`, or MySQL's ` `` `) is an escaped one
+/// and doesn't end the section.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while let Some(end) = bytes[i.min(bytes.len())..].iter().position(|&b| b == quote) {
+        i += end + 1;
+        if bytes.get(i) != Some(&quote) {
+            return i;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The last part of a possibly quoted, dot-qualified name starting at `pos`
+fn qualified_name_at(query: &str, pos: usize) -> Option<String> {
+    let bytes = query.as_bytes();
+    let mut i = pos;
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+
+    let mut name = None;
+    loop {
+        let (part, next) = match bytes.get(i)? {
+            quote @ (b'"' | b'`') => {
+                let end = skip_quoted(bytes, i, *quote);
+                let doubled = if *quote == b'"' { "\"\"" } else { "``" };
+                (
+                    query.get(i + 1..end - 1)?.replace(doubled, &doubled[1..]),
+                    end,
+                )
+            }
+            b'[' => {
+                let end = skip_quoted(bytes, i, b']');
+                (query.get(i + 1..end - 1)?.replace("]]", "]"), end)
+            }
+            _ => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|&b| !is_word_byte(b))
+                    .map_or(bytes.len(), |len| i + len);
+                (query[i..end].to_string(), end)
+            }
+        };
+        if part.is_empty() {
+            return name;
+        }
+        name = Some(part);
+        if bytes.get(next) != Some(&b'.') {
+            return name;
+        }
+        i = next + 1;
+    }
+}
+
+/// Whether `text` names `table` as a whole word
+fn mentions_table(text: &str, table: &str) -> bool {
+    text.match_indices(table).any(|(at, _)| {
+        let before = text[..at].bytes().next_back();
+        let after = text[at + table.len()..].bytes().next();
+        !before.is_some_and(is_word_byte) && !after.is_some_and(is_word_byte)
+    })
+}
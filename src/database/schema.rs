@@ -0,0 +1,138 @@
+//! Table and index definitions read from `db/schema.rb`
+//!
+//! Only what the index hints and N+1 suggestions need is parsed:
+//! `create_table` blocks with their column names and `t.index` lines,
+//! top-level `add_index` calls from older Rails versions and
+//! `add_foreign_key` calls. Column types and row counts are left out.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::{ForeignKeyInfo, IndexInfo, TableInfo};
+use crate::query::singularize;
+
+/// Where Rails dumps the schema, relative to the project root
+pub const SCHEMA_PATH: &str = "db/schema.rb";
+
+/// Read and parse `db/schema.rb` under `root`; `None` when there is none
+pub fn load_schema(root: &Path) -> Option<HashMap<String, TableInfo>> {
+    let content = std::fs::read_to_string(root.join(SCHEMA_PATH)).ok()?;
+    Some(parse_schema(&content))
+}
+
+/// Tables and their indexes, keyed by unqualified table name
+pub fn parse_schema(content: &str) -> HashMap<String, TableInfo> {
+    static CREATE_TABLE: OnceLock<Regex> = OnceLock::new();
+    static TABLE_INDEX: OnceLock<Regex> = OnceLock::new();
+    static ADD_INDEX: OnceLock<Regex> = OnceLock::new();
+    static COLUMN: OnceLock<Regex> = OnceLock::new();
+    static ADD_FOREIGN_KEY: OnceLock<Regex> = OnceLock::new();
+    let create_table = CREATE_TABLE
+        .get_or_init(|| Regex::new(r#"^\s*create_table\s+"([^"]+)"(.*?)\bdo\b"#).unwrap());
+    let table_index = TABLE_INDEX
+        .get_or_init(|| Regex::new(r#"^\s*t\.index\s+(\[[^\]]*\]|"[^"]*")(.*)$"#).unwrap());
+    let add_index = ADD_INDEX.get_or_init(|| {
+        Regex::new(r#"^\s*add_index\s+"([^"]+)",\s*(\[[^\]]*\]|"[^"]*")(.*)$"#).unwrap()
+    });
+    let column = COLUMN.get_or_init(|| Regex::new(r#"^\s*t\.(\w+)\s+"([^"]+)""#).unwrap());
+    let add_foreign_key = ADD_FOREIGN_KEY.get_or_init(|| {
+        Regex::new(r#"^\s*add_foreign_key\s+"([^"]+)",\s*"([^"]+)"(.*)$"#).unwrap()
+    });
+
+    let mut tables: HashMap<String, TableInfo> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if let Some(caps) = create_table.captures(line) {
+            let name = unqualified(&caps[1]);
+            tables.insert(
+                name.clone(),
+                TableInfo {
+                    name: name.clone(),
+                    estimated_rows: 0,
+                    has_primary_key: !caps[2].contains("id: false"),
+                    columns: Vec::new(),
+                    indexes: Vec::new(),
+                    foreign_keys: Vec::new(),
+                },
+            );
+            current = Some(name);
+        } else if line.trim() == "end" {
+            current = None;
+        } else if let Some(table) = &current
+            && let Some(caps) = table_index.captures(line)
+        {
+            let index = index_info(&caps[1], &caps[2]);
+            tables.get_mut(table).unwrap().indexes.push(index);
+        } else if let Some(table) = &current
+            && let Some(caps) = column.captures(line)
+        {
+            tables
+                .get_mut(table)
+                .unwrap()
+                .columns
+                .push(caps[2].to_string());
+        } else if let Some(caps) = add_index.captures(line)
+            && let Some(table) = tables.get_mut(&unqualified(&caps[1]))
+        {
+            table.indexes.push(index_info(&caps[2], &caps[3]));
+        } else if let Some(caps) = add_foreign_key.captures(line)
+            && let Some(table) = tables.get_mut(&unqualified(&caps[1]))
+        {
+            table
+                .foreign_keys
+                .push(foreign_key_info(table, &caps[2], &caps[3]));
+        }
+    }
+
+    tables
+}
+
+/// `"public.users"` → `users`
+fn unqualified(name: &str) -> String {
+    name.rsplit('.').next().unwrap_or(name).to_string()
+}
+
+/// `add_foreign_key "comments", "posts"` on `table`; the column is
+/// `post_id` unless the options name it
+fn foreign_key_info(table: &TableInfo, to_table: &str, options: &str) -> ForeignKeyInfo {
+    static COLUMN: OnceLock<Regex> = OnceLock::new();
+    let column_re = COLUMN.get_or_init(|| Regex::new(r#"column:\s*"([^"]+)""#).unwrap());
+    let references_table = unqualified(to_table);
+    let column = column_re
+        .captures(options)
+        .map(|caps| caps[1].to_string())
+        .unwrap_or_else(|| format!("{}_id", singularize(&references_table)));
+    let has_index = table
+        .indexes
+        .iter()
+        .any(|index| index.columns.first() == Some(&column));
+    ForeignKeyInfo {
+        column,
+        references_table,
+        has_index,
+    }
+}
+
+/// Build an index from its column list (`["a", "b"]` or `"a"`) and options
+fn index_info(columns: &str, options: &str) -> IndexInfo {
+    static QUOTED: OnceLock<Regex> = OnceLock::new();
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    let quoted = QUOTED.get_or_init(|| Regex::new(r#""([^"]*)""#).unwrap());
+    let name = NAME.get_or_init(|| Regex::new(r#"name:\s*"([^"]+)""#).unwrap());
+
+    IndexInfo {
+        name: name
+            .captures(options)
+            .map(|caps| caps[1].to_string())
+            .unwrap_or_default(),
+        columns: quoted
+            .captures_iter(columns)
+            .map(|caps| caps[1].to_string())
+            .collect(),
+        is_unique: options.contains("unique: true"),
+        usage_count: 0,
+    }
+}
@@ -0,0 +1,175 @@
+//! The health score over a session
+//!
+//! Sampled every [`SCORE_SAMPLE_INTERVAL`] while queries arrive. The scores
+//! go into a [`TimeSeries`] for the trend sparkline and its range; each
+//! sample also keeps how many issues and slow queries there were, so a drop
+//! can be put down to what appeared in between.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::TimeSeries;
+use crate::time_format::DisplayZone;
+
+/// How often the UI samples the score
+pub const SCORE_SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Samples kept, half an hour's worth
+pub const SCORE_HISTORY_LEN: usize = 120;
+
+/// The score and what it was made of at one sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreSample {
+    pub time: DateTime<Local>,
+    pub score: u32,
+    /// Database issues listed at the time
+    pub issues: usize,
+    /// Distinct slow queries tracked at the time
+    pub slow_queries: usize,
+}
+
+/// A fall in the score between two samples in a row
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreDrop {
+    /// When the lower score was sampled
+    pub time: DateTime<Local>,
+    pub from: u32,
+    pub to: u32,
+    pub new_slow_queries: usize,
+    pub new_issues: usize,
+}
+
+impl ScoreDrop {
+    pub fn points(&self) -> u32 {
+        self.from - self.to
+    }
+
+    /// `Score dropped 12 points at 14:32:05 when 3 new slow queries appeared`
+    pub fn describe(&self, zone: DisplayZone) -> String {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        let points = self.points();
+        let mut text = format!(
+            "Score dropped {} point{} at {}",
+            points,
+            plural(points as usize),
+            zone.clock(self.time)
+        );
+        if self.new_slow_queries > 0 {
+            text.push_str(&format!(
+                " when {} new slow quer{} appeared",
+                self.new_slow_queries,
+                if self.new_slow_queries == 1 {
+                    "y"
+                } else {
+                    "ies"
+                }
+            ));
+        } else if self.new_issues > 0 {
+            text.push_str(&format!(
+                " when {} new issue{} appeared",
+                self.new_issues,
+                plural(self.new_issues)
+            ));
+        }
+        text
+    }
+}
+
+/// Lowest, highest and latest score sampled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreRange {
+    pub min: u32,
+    pub max: u32,
+    pub current: u32,
+}
+
+/// Recent samples, the oldest dropped past [`SCORE_HISTORY_LEN`]
+#[derive(Debug, Clone)]
+pub struct ScoreHistory {
+    trend: TimeSeries,
+    samples: VecDeque<ScoreSample>,
+}
+
+impl Default for ScoreHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScoreHistory {
+    pub fn new() -> Self {
+        Self {
+            trend: TimeSeries::new(
+                SCORE_SAMPLE_INTERVAL * SCORE_HISTORY_LEN as u32,
+                SCORE_HISTORY_LEN,
+            ),
+            samples: VecDeque::with_capacity(SCORE_HISTORY_LEN),
+        }
+    }
+
+    /// Add a sample taken at `at`
+    pub fn record(&mut self, sample: ScoreSample, at: Instant) {
+        self.trend.add_at(at, f64::from(sample.score));
+        self.samples.push_back(sample);
+        while self.samples.len() > SCORE_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Scores, oldest first
+    pub fn scores(&self) -> Vec<f64> {
+        self.trend.values()
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &ScoreSample> {
+        self.samples.iter()
+    }
+
+    pub fn range(&self) -> Option<ScoreRange> {
+        let current = self.trend.latest()?;
+        Some(ScoreRange {
+            min: self.trend.min() as u32,
+            max: self.trend.max() as u32,
+            current: current as u32,
+        })
+    }
+
+    /// The `limit` largest falls between one sample and the next, largest
+    /// first and the latest of equal ones first
+    pub fn worst_drops(&self, limit: usize) -> Vec<ScoreDrop> {
+        let mut drops: Vec<ScoreDrop> = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .filter(|(before, after)| after.score < before.score)
+            .map(|(before, after)| ScoreDrop {
+                time: after.time,
+                from: before.score,
+                to: after.score,
+                new_slow_queries: after.slow_queries.saturating_sub(before.slow_queries),
+                new_issues: after.issues.saturating_sub(before.issues),
+            })
+            .collect();
+        drops.sort_by(|a, b| b.points().cmp(&a.points()).then(b.time.cmp(&a.time)));
+        drops.truncate(limit);
+        drops
+    }
+
+    /// Put back a saved session's scores and samples, as taken now
+    ///
+    /// Files saved before samples were kept have only the scores.
+    pub fn restore(&mut self, scores: Vec<u32>, samples: Vec<ScoreSample>) {
+        let now = Instant::now();
+        self.trend.clear();
+        for score in scores {
+            self.trend.add_at(now, f64::from(score));
+        }
+        self.samples = samples.into();
+        while self.samples.len() > SCORE_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Memory accounting for Caboose's own in-memory stores
+//!
+//! Each tracker reports its stores as [`StoreUsage`] rows and can trim a store
+//! down to its most recent entries. Byte counts are estimates: struct sizes plus
+//! the heap capacity of owned strings and vectors, ignoring allocator overhead.
+//!
+//! [`FrameTimings`] times the UI loop itself, so a slow terminal or render path
+//! can be told apart from a flood of logs.
+
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// An in-memory store that grows while Caboose runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StoreKind {
+    LogBuffer,
+    InFlightRequests,
+    CompletedRequests,
+    ViewStats,
+    SlowQueries,
+    TablesAccessed,
+    Exceptions,
+    ExceptionGroups,
+    TestRuns,
+    SlowestTests,
+    ResponseTimeHistory,
+    EndpointStats,
+}
+
+impl StoreKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StoreKind::LogBuffer => "Log buffer",
+            StoreKind::InFlightRequests => "In-flight requests",
+            StoreKind::CompletedRequests => "Completed requests",
+            StoreKind::ViewStats => "View templates",
+            StoreKind::SlowQueries => "Slow queries",
+            StoreKind::TablesAccessed => "Tables accessed",
+            StoreKind::Exceptions => "Exception samples",
+            StoreKind::ExceptionGroups => "Exception groups",
+            StoreKind::TestRuns => "Test runs",
+            StoreKind::SlowestTests => "Slowest tests",
+            StoreKind::ResponseTimeHistory => "Response time history",
+            StoreKind::EndpointStats => "Endpoint stats",
+        }
+    }
+
+    /// Whether trimming this store is meaningful
+    ///
+    /// In-flight requests are still collecting queries, so dropping them would
+    /// corrupt the request/query pairing.
+    pub fn is_trimmable(&self) -> bool {
+        !matches!(self, StoreKind::InFlightRequests)
+    }
+}
+
+/// Item count and approximate heap footprint of one store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreUsage {
+    pub kind: StoreKind,
+    pub items: usize,
+    pub bytes: usize,
+    /// Most entries the store keeps before dropping the oldest
+    pub limit: Option<usize>,
+}
+
+impl StoreUsage {
+    pub fn new(kind: StoreKind, items: usize, bytes: usize) -> Self {
+        Self {
+            kind,
+            items,
+            bytes,
+            limit: None,
+        }
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Size caps for the stores, set under `[limits]` in `.caboose.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_logs: usize,
+    pub max_slow_queries: usize,
+    pub max_exceptions: usize,
+    pub max_test_runs: usize,
+    pub max_slowest_tests: usize,
+    pub max_completed_requests: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_logs: 1000,
+            max_slow_queries: 50,
+            max_exceptions: 100,
+            max_test_runs: 10,
+            max_slowest_tests: 20,
+            max_completed_requests: 100,
+        }
+    }
+}
+
+/// Largest accepted log buffer; anything above is almost certainly a typo
+pub const MAX_LOGS_LIMIT: usize = 1_000_000;
+
+/// Largest accepted size for every other store
+pub const MAX_STORE_LIMIT: usize = 10_000;
+
+/// `value` if it lies in `1..=max`, otherwise `default` and a warning
+///
+/// `name` is the config key, used in the warning.
+pub fn validate_limit(
+    name: &str,
+    value: Option<usize>,
+    default: usize,
+    max: usize,
+    warnings: &mut Vec<String>,
+) -> usize {
+    match value {
+        None => default,
+        Some(value) if (1..=max).contains(&value) => value,
+        Some(value) => {
+            warnings.push(format!(
+                "limits.{} = {} is out of range (1-{}), using {}",
+                name, value, max, default
+            ));
+            default
+        }
+    }
+}
+
+/// Sum the byte estimates of several stores
+pub fn total_bytes(usage: &[StoreUsage]) -> usize {
+    usage.iter().map(|u| u.bytes).sum()
+}
+
+/// Approximate size of an owned string, including its inline header
+pub fn string_bytes(s: &str) -> usize {
+    size_of::<String>() + s.len()
+}
+
+/// Approximate size of a list of owned strings
+pub fn strings_bytes(items: &[String]) -> usize {
+    size_of::<Vec<String>>() + items.iter().map(|s| string_bytes(s)).sum::<usize>()
+}
+
+/// Drop the oldest entries of `items` so that at most `keep` remain
+///
+/// Returns the number of entries removed.
+pub fn keep_last<T>(items: &mut Vec<T>, keep: usize) -> usize {
+    let excess = items.len().saturating_sub(keep);
+    items.drain(..excess);
+    items.shrink_to_fit();
+    excess
+}
+
+/// Resident set size of the current process in bytes
+pub fn process_rss(system: &mut System) -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    system.refresh_process(pid);
+    system.process(pid).map(|p| p.memory())
+}
+
+/// Frames kept for the frame-time percentiles, about 12s at the loop's pace
+pub const FRAME_SAMPLES: usize = 120;
+
+/// p95 draw time above which the UI counts as slow
+pub const SLOW_FRAME_P95: Duration = Duration::from_millis(25);
+
+/// How long draws must stay slow before warning
+pub const SLOW_FRAME_SUSTAINED: Duration = Duration::from_secs(10);
+
+/// Fewest frames needed before the p95 means anything
+const MIN_FRAME_SAMPLES: usize = 20;
+
+/// Fixed-size ring of the most recent durations
+#[derive(Debug, Clone, Copy)]
+struct DurationRing {
+    samples: [Duration; FRAME_SAMPLES],
+    len: usize,
+    next: usize,
+}
+
+impl DurationRing {
+    const fn new() -> Self {
+        Self {
+            samples: [Duration::ZERO; FRAME_SAMPLES],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    fn push(&mut self, sample: Duration) {
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % FRAME_SAMPLES;
+        self.len = (self.len + 1).min(FRAME_SAMPLES);
+    }
+
+    /// Nearest-rank 95th percentile, sorted on a stack copy
+    fn p95(&self) -> Duration {
+        if self.len == 0 {
+            return Duration::ZERO;
+        }
+        let mut sorted = self.samples;
+        let sorted = &mut sorted[..self.len];
+        sorted.sort_unstable();
+        sorted[(self.len * 95).div_ceil(100) - 1]
+    }
+
+    fn max(&self) -> Duration {
+        self.samples[..self.len]
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// p95 and max of the recent draws and key handling
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub draw_p95: Duration,
+    pub draw_max: Duration,
+    pub input_p95: Duration,
+    pub input_max: Duration,
+}
+
+/// How long each frame of the UI loop took to draw and to handle input
+///
+/// Recording a frame is two stores into fixed arrays; nothing is allocated.
+#[derive(Debug, Clone)]
+pub struct FrameTimings {
+    draw: DurationRing,
+    input: DurationRing,
+    /// When the draw p95 last went over [`SLOW_FRAME_P95`]
+    slow_since: Option<Instant>,
+    /// Already warned about the current slow stretch
+    warned: bool,
+}
+
+impl FrameTimings {
+    pub fn new() -> Self {
+        Self {
+            draw: DurationRing::new(),
+            input: DurationRing::new(),
+            slow_since: None,
+            warned: false,
+        }
+    }
+
+    /// Record one frame; `input` is `None` when no key was handled
+    ///
+    /// Returns the draw p95 the first time it has stayed over
+    /// [`SLOW_FRAME_P95`] for [`SLOW_FRAME_SUSTAINED`]. Another warning needs
+    /// the UI to recover first.
+    pub fn record(
+        &mut self,
+        draw: Duration,
+        input: Option<Duration>,
+        now: Instant,
+    ) -> Option<Duration> {
+        self.draw.push(draw);
+        if let Some(input) = input {
+            self.input.push(input);
+        }
+        if self.draw.len < MIN_FRAME_SAMPLES {
+            return None;
+        }
+
+        let p95 = self.draw.p95();
+        if p95 <= SLOW_FRAME_P95 {
+            self.slow_since = None;
+            self.warned = false;
+            return None;
+        }
+        let since = *self.slow_since.get_or_insert(now);
+        if self.warned || now.duration_since(since) < SLOW_FRAME_SUSTAINED {
+            return None;
+        }
+        self.warned = true;
+        Some(p95)
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        FrameStats {
+            draw_p95: self.draw.p95(),
+            draw_max: self.draw.max(),
+            input_p95: self.input.p95(),
+            input_max: self.input.max(),
+        }
+    }
+}
+
+impl Default for FrameTimings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,204 @@
+//! Disk watcher: `log/*.log` files and `tmp/cache` that grow without bound,
+//! and the free space left on the project's disk
+//!
+//! A scan only reads sizes, so it's cheap; it runs every few minutes on a
+//! blocking thread of the tokio runtime. Paths that can't be read (missing,
+//! permission denied) are skipped rather than reported.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::Disks;
+use tokio::sync::watch;
+
+/// Size at which a log file or `tmp/cache` gets a warning
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Free space on the project's disk below which it gets a warning
+pub const DEFAULT_MIN_FREE_SPACE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Time between scans
+pub const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskLimits {
+    pub max_file_size: u64,
+    pub min_free_space: u64,
+}
+
+impl Default for DiskLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            min_free_space: DEFAULT_MIN_FREE_SPACE,
+        }
+    }
+}
+
+/// A file under `log/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    pub path: PathBuf,
+    /// Path relative to the project root, e.g. `log/development.log`
+    pub name: String,
+    pub size: u64,
+}
+
+/// Sizes found by one scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub log_files: Vec<LogFile>,
+    /// Total size of `tmp/cache`, when it exists
+    pub tmp_cache: Option<u64>,
+    /// Space available on the disk holding the project
+    pub free_space: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiskWarning {
+    /// A log file over the size limit; it can be truncated
+    LargeLog(LogFile),
+    /// `tmp/cache` over the size limit
+    LargeCache {
+        size: u64,
+    },
+    LowSpace {
+        free: u64,
+    },
+}
+
+impl DiskWarning {
+    /// Identifies what the warning is about, to warn about it once
+    pub fn key(&self) -> String {
+        match self {
+            DiskWarning::LargeLog(file) => format!("log:{}", file.name),
+            DiskWarning::LargeCache { .. } => "tmp/cache".to_string(),
+            DiskWarning::LowSpace { .. } => "free space".to_string(),
+        }
+    }
+}
+
+impl DiskUsage {
+    /// Measure `log/*.log`, `tmp/cache` and free space for the project at `root`
+    pub fn scan(root: &Path) -> Self {
+        let mut log_files: Vec<LogFile> = fs::read_dir(root.join("log"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+                Some(LogFile {
+                    path: entry.path(),
+                    name: format!("log/{}", entry.file_name().to_string_lossy()),
+                    size: metadata.len(),
+                })
+            })
+            .collect();
+        log_files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let cache = root.join("tmp").join("cache");
+        Self {
+            log_files,
+            tmp_cache: cache.is_dir().then(|| dir_size(&cache)),
+            free_space: free_space(root),
+        }
+    }
+
+    /// What exceeds `limits`, largest log files first
+    pub fn warnings(&self, limits: DiskLimits) -> Vec<DiskWarning> {
+        let mut large: Vec<&LogFile> = self
+            .log_files
+            .iter()
+            .filter(|file| file.size > limits.max_file_size)
+            .collect();
+        large.sort_by_key(|file| std::cmp::Reverse(file.size));
+
+        let mut warnings: Vec<DiskWarning> = large
+            .into_iter()
+            .map(|file| DiskWarning::LargeLog(file.clone()))
+            .collect();
+        if let Some(size) = self.tmp_cache.filter(|size| *size > limits.max_file_size) {
+            warnings.push(DiskWarning::LargeCache { size });
+        }
+        if let Some(free) = self.free_space.filter(|free| *free < limits.min_free_space) {
+            warnings.push(DiskWarning::LowSpace { free });
+        }
+        warnings
+    }
+}
+
+/// Total size of the files under `dir`, not following symlinks
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = fs::symlink_metadata(entry.path()).ok()?;
+            Some(if metadata.is_dir() {
+                dir_size(&entry.path())
+            } else if metadata.is_file() {
+                metadata.len()
+            } else {
+                0
+            })
+        })
+        .sum()
+}
+
+/// Available space on the disk whose mount point holds `root`
+fn free_space(root: &Path) -> Option<u64> {
+    let root = root.canonicalize().ok()?;
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| root.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Empty a log file in place, returning the bytes freed
+///
+/// Like `rails log:clear`, the file is truncated rather than deleted: a
+/// server that has it open keeps writing to it (Ruby's Logger appends), where
+/// a deleted file would hold its space until the server restarts.
+pub fn truncate_log(path: &Path) -> Result<u64, String> {
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    file.set_len(0)
+        .map_err(|e| format!("Failed to truncate {}: {}", path.display(), e))?;
+    Ok(size)
+}
+
+/// Scan the project at `root` every `interval`, publishing its warnings
+///
+/// Must be called from within the tokio runtime. The task ends once the
+/// receiver is dropped.
+pub fn spawn_disk_watcher(
+    root: PathBuf,
+    limits: DiskLimits,
+    interval: Duration,
+) -> watch::Receiver<Vec<DiskWarning>> {
+    let (tx, rx) = watch::channel(Vec::new());
+    tokio::spawn(async move {
+        loop {
+            let dir = root.clone();
+            if let Ok(usage) = tokio::task::spawn_blocking(move || DiskUsage::scan(&dir)).await
+                && tx.send(usage.warnings(limits)).is_err()
+            {
+                break;
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+    rx
+}
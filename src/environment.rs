@@ -4,7 +4,7 @@
 //! package managers, current path, etc.
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Environment information for the current project
@@ -28,36 +28,45 @@ pub struct PackageManagerInfo {
 impl EnvironmentInfo {
     /// Detect all environment information
     pub fn detect() -> Self {
+        match env::current_dir() {
+            Ok(path) => Self::detect_in(&path),
+            Err(_) => Self::detect_in(Path::new(".")),
+        }
+    }
+
+    /// Detect environment information for the project at `root`
+    ///
+    /// Version commands run inside `root` so per-project version managers
+    /// (`.ruby-version`, `.nvmrc`) are honoured.
+    pub fn detect_in(root: &Path) -> Self {
         Self {
-            current_path: Self::get_current_path(),
-            ruby_version: Self::detect_ruby_version(),
-            node_version: Self::detect_node_version(),
-            package_manager: Self::detect_package_manager(),
-            rails_version: Self::detect_rails_version(),
-            database: Self::detect_database(),
+            current_path: Self::get_current_path(root),
+            ruby_version: Self::detect_ruby_version(root),
+            node_version: Self::detect_node_version(root),
+            package_manager: Self::detect_package_manager(root),
+            rails_version: Self::detect_rails_version(root),
+            database: Self::detect_database(root),
         }
     }
 
-    /// Get current working directory (shortened)
-    fn get_current_path() -> String {
-        if let Ok(path) = env::current_dir() {
-            // Get the last 2 components of the path for brevity
-            let components: Vec<_> = path.components().collect();
-            if components.len() > 2 {
-                let last_two: PathBuf = components[components.len() - 2..].iter().collect();
-                format!(".../{}", last_two.display())
-            } else {
-                path.display().to_string()
-            }
+    /// Get the project path (shortened)
+    fn get_current_path(root: &Path) -> String {
+        let path = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        // Get the last 2 components of the path for brevity
+        let components: Vec<_> = path.components().collect();
+        if components.len() > 2 {
+            let last_two: PathBuf = components[components.len() - 2..].iter().collect();
+            format!(".../{}", last_two.display())
         } else {
-            "~".to_string()
+            path.display().to_string()
         }
     }
 
     /// Detect Ruby version
-    fn detect_ruby_version() -> Option<String> {
+    fn detect_ruby_version(root: &Path) -> Option<String> {
         Command::new("ruby")
             .arg("--version")
+            .current_dir(root)
             .output()
             .ok()
             .and_then(|output| {
@@ -75,9 +84,10 @@ impl EnvironmentInfo {
     }
 
     /// Detect Node.js version
-    fn detect_node_version() -> Option<String> {
+    fn detect_node_version(root: &Path) -> Option<String> {
         Command::new("node")
             .arg("--version")
+            .current_dir(root)
             .output()
             .ok()
             .and_then(|output| {
@@ -92,25 +102,25 @@ impl EnvironmentInfo {
     }
 
     /// Detect package manager and version
-    fn detect_package_manager() -> Option<PackageManagerInfo> {
+    fn detect_package_manager(root: &Path) -> Option<PackageManagerInfo> {
         // Check for lockfiles to determine package manager
-        if std::path::Path::new("pnpm-lock.yaml").exists() {
-            Self::get_pm_version("pnpm", "--version").map(|v| PackageManagerInfo {
+        if root.join("pnpm-lock.yaml").exists() {
+            Self::get_pm_version(root, "pnpm", "--version").map(|v| PackageManagerInfo {
                 name: "pnpm".to_string(),
                 version: v,
             })
-        } else if std::path::Path::new("yarn.lock").exists() {
-            Self::get_pm_version("yarn", "--version").map(|v| PackageManagerInfo {
+        } else if root.join("yarn.lock").exists() {
+            Self::get_pm_version(root, "yarn", "--version").map(|v| PackageManagerInfo {
                 name: "yarn".to_string(),
                 version: v,
             })
-        } else if std::path::Path::new("bun.lockb").exists() {
-            Self::get_pm_version("bun", "--version").map(|v| PackageManagerInfo {
+        } else if root.join("bun.lockb").exists() {
+            Self::get_pm_version(root, "bun", "--version").map(|v| PackageManagerInfo {
                 name: "bun".to_string(),
                 version: v,
             })
-        } else if std::path::Path::new("package-lock.json").exists() {
-            Self::get_pm_version("npm", "--version").map(|v| PackageManagerInfo {
+        } else if root.join("package-lock.json").exists() {
+            Self::get_pm_version(root, "npm", "--version").map(|v| PackageManagerInfo {
                 name: "npm".to_string(),
                 version: v,
             })
@@ -120,22 +130,28 @@ impl EnvironmentInfo {
     }
 
     /// Get package manager version
-    fn get_pm_version(cmd: &str, arg: &str) -> Option<String> {
-        Command::new(cmd).arg(arg).output().ok().and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|s| s.trim().to_string())
-            } else {
-                None
-            }
-        })
+    fn get_pm_version(root: &Path, cmd: &str, arg: &str) -> Option<String> {
+        Command::new(cmd)
+            .arg(arg)
+            .current_dir(root)
+            .output()
+            .ok()
+            .and_then(|output| {
+                if output.status.success() {
+                    String::from_utf8(output.stdout)
+                        .ok()
+                        .map(|s| s.trim().to_string())
+                } else {
+                    None
+                }
+            })
     }
 
     /// Detect Rails version
-    fn detect_rails_version() -> Option<String> {
+    fn detect_rails_version(root: &Path) -> Option<String> {
         Command::new("rails")
             .arg("--version")
+            .current_dir(root)
             .output()
             .ok()
             .and_then(|output| {
@@ -151,9 +167,9 @@ impl EnvironmentInfo {
     }
 
     /// Detect database from config/database.yml or Gemfile
-    fn detect_database() -> Option<String> {
+    fn detect_database(root: &Path) -> Option<String> {
         // Try to read database.yml
-        if let Ok(contents) = std::fs::read_to_string("config/database.yml") {
+        if let Ok(contents) = std::fs::read_to_string(root.join("config/database.yml")) {
             if contents.contains("postgresql") || contents.contains("adapter: postgresql") {
                 return Some("PostgreSQL".to_string());
             } else if contents.contains("mysql") {
@@ -164,7 +180,7 @@ impl EnvironmentInfo {
         }
 
         // Fallback to checking Gemfile
-        if let Ok(contents) = std::fs::read_to_string("Gemfile") {
+        if let Ok(contents) = std::fs::read_to_string(root.join("Gemfile")) {
             if contents.contains("pg") {
                 return Some("PostgreSQL".to_string());
             } else if contents.contains("mysql2") {
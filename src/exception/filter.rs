@@ -0,0 +1,68 @@
+//! Which exception groups the Exceptions view lists
+//!
+//! Noisy groups are narrowed away by severity (`v` raises the minimum), by how
+//! long ago they were last seen (`h`), or one at a time by dismissing them
+//! (`d`); a dismissed group is listed again once it happens again.
+
+use std::time::{Duration, Instant};
+
+use super::{ExceptionGroup, ExceptionSeverity};
+
+/// How recently a group must have been seen for `h` to keep it listed
+pub const DEFAULT_SEEN_WITHIN: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExceptionFilter {
+    /// Least severe groups listed; `None` lists every severity
+    pub min_severity: Option<ExceptionSeverity>,
+    /// Only groups last seen within this long
+    pub seen_within: Option<Duration>,
+    /// List dismissed groups too
+    pub show_dismissed: bool,
+}
+
+impl ExceptionFilter {
+    /// Whether `group` is listed at `now`
+    pub fn matches(&self, group: &ExceptionGroup, now: Instant) -> bool {
+        let severity = ExceptionSeverity::from_exception_type(&group.exception_type);
+        self.min_severity.is_none_or(|min| severity >= min)
+            && self
+                .seen_within
+                .is_none_or(|window| now.saturating_duration_since(group.last_seen) <= window)
+            && (self.show_dismissed || !group.dismissed)
+    }
+
+    /// Whether severity or recency narrow the list
+    pub fn is_narrowed(&self) -> bool {
+        self.min_severity.is_some() || self.seen_within.is_some()
+    }
+
+    /// The next minimum severity for `v`: all, medium, high, critical, then
+    /// all again (low and up is everything)
+    pub fn next_severity(&self) -> Option<ExceptionSeverity> {
+        match self.min_severity {
+            None | Some(ExceptionSeverity::Low) => Some(ExceptionSeverity::Medium),
+            Some(ExceptionSeverity::Medium) => Some(ExceptionSeverity::High),
+            Some(ExceptionSeverity::High) => Some(ExceptionSeverity::Critical),
+            Some(ExceptionSeverity::Critical) => None,
+        }
+    }
+
+    /// `high and up, seen in the last 15m`, or empty when nothing narrows
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(severity) = self.min_severity {
+            parts.push(match severity {
+                ExceptionSeverity::Critical => "critical only".to_string(),
+                _ => format!("{} and up", severity.label()),
+            });
+        }
+        if let Some(window) = self.seen_within {
+            parts.push(format!(
+                "seen in the last {}m",
+                window.as_secs().div_ceil(60)
+            ));
+        }
+        parts.join(", ")
+    }
+}
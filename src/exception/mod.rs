@@ -1,35 +1,182 @@
+mod filter;
+mod rate;
+
+pub use filter::{DEFAULT_SEEN_WITHIN, ExceptionFilter};
+pub use rate::{ExceptionRate, RATE_MINUTES, RateBucket};
+
+use crate::diagnostics::{Limits, StoreKind, StoreUsage, keep_last, string_bytes, total_bytes};
+use crate::search::SearchQuery;
+use crate::shared::{Shared, SnapshotCache};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 // Memory management constants
 const MAX_EXCEPTION_GROUPS: usize = 200;
 const EXCEPTION_GROUPS_WARNING_THRESHOLD: usize = 180; // 90% of max
 
-#[derive(Debug, Clone)]
+/// Occurrences kept per group
+const MAX_GROUP_OCCURRENCES: usize = 10;
+
+/// Distinct endpoints counted per group; further ones aren't tallied
+const MAX_GROUP_ENDPOINTS: usize = 20;
+
+/// What besides type and message tells exception groups apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionGrouping {
+    /// Group by the topmost project-local backtrace frame too, so the same
+    /// error raised from two call sites shows up twice
+    pub with_location: bool,
+    /// Keep the frame's line number; off, raises anywhere in a file group together
+    pub line_numbers: bool,
+}
+
+impl Default for ExceptionGrouping {
+    fn default() -> Self {
+        Self {
+            with_location: false,
+            line_numbers: true,
+        }
+    }
+}
+
+/// Whether a backtrace file belongs to the project rather than a gem or Ruby
+fn is_project_file(file: &str) -> bool {
+    const OUTSIDE: &[&str] = &["gems/", "vendor/", "/ruby/", "/rubies/", "<internal:"];
+    !file.is_empty() && !OUTSIDE.iter().any(|marker| file.contains(marker))
+}
+
+/// The topmost project-local frame, as `app/models/user.rb:12` (or without
+/// the line number)
+pub fn project_location(backtrace: &[String], line_numbers: bool) -> Option<String> {
+    backtrace.iter().find_map(|frame| {
+        let (file, line) = ExceptionTracker::parse_backtrace_location(frame)?;
+        if !is_project_file(&file) {
+            return None;
+        }
+        Some(if line_numbers {
+            format!("{}:{}", file, line)
+        } else {
+            file
+        })
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Exception {
     pub exception_type: String,
     pub message: String,
     pub backtrace: Vec<String>,
     pub file_path: Option<String>,
     pub line_number: Option<usize>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub timestamp: Instant,
     pub context: Option<String>, // HTTP request context if available
+    /// `X-Request-Id` of the request it was raised in, when logged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl Exception {
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.exception_type.len()
+            + self.message.len()
+            + self
+                .backtrace
+                .iter()
+                .map(|l| string_bytes(l))
+                .sum::<usize>()
+            + self.file_path.as_ref().map_or(0, |p| p.len())
+            + self.context.as_ref().map_or(0, |c| c.len())
+            + self.request_id.as_ref().map_or(0, |id| id.len())
+    }
+
+    /// `POST /orders/:id`: the request it was raised in, with ids in the
+    /// path normalized so requests to the same route count together
+    pub fn endpoint(&self) -> Option<String> {
+        self.context.as_deref().map(crate::context::normalize_path)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExceptionGroup {
     pub fingerprint: String,
     pub exception_type: String,
     pub message_pattern: String,
+    /// Project frame the group is keyed on, when grouping by location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
     pub count: usize,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub first_seen: Instant,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub last_seen: Instant,
     pub sample_exception: Exception,
+    #[serde(
+        serialize_with = "crate::serialization::instant_ages",
+        deserialize_with = "crate::serialization::instants_from_ages"
+    )]
     pub occurrences: Vec<Instant>,
+    /// Dismissed with `d`; cleared by the next occurrence
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dismissed: bool,
+    /// Occurrences by the endpoint they were raised in
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub endpoints: HashMap<String, usize>,
+}
+
+impl ExceptionGroup {
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() - std::mem::size_of::<Exception>()
+            + self.fingerprint.len()
+            + self.exception_type.len()
+            + self.message_pattern.len()
+            + self.location.as_ref().map_or(0, |l| l.len())
+            + self.sample_exception.approx_bytes()
+            + self.occurrences.capacity() * std::mem::size_of::<Instant>()
+            + self
+                .endpoints
+                .keys()
+                .map(|endpoint| endpoint.len() + std::mem::size_of::<(String, usize)>())
+                .sum::<usize>()
+    }
+
+    /// Endpoints most occurrences were raised in first
+    pub fn top_endpoints(&self) -> Vec<(&str, usize)> {
+        let mut endpoints: Vec<(&str, usize)> = self
+            .endpoints
+            .iter()
+            .map(|(endpoint, count)| (endpoint.as_str(), *count))
+            .collect();
+        endpoints.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        endpoints
+    }
+
+    fn record_endpoint(&mut self, exception: &Exception) {
+        let Some(endpoint) = exception.endpoint() else {
+            return;
+        };
+        if let Some(count) = self.endpoints.get_mut(&endpoint) {
+            *count += 1;
+        } else if self.endpoints.len() < MAX_GROUP_ENDPOINTS {
+            self.endpoints.insert(endpoint, 1);
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ExceptionSeverity {
     Low,      // Warnings, expected errors
     Medium,   // Handled exceptions
@@ -58,6 +205,35 @@ impl ExceptionSeverity {
         }
     }
 
+    /// `critical`, `high`, `medium` or `low`, any case
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "critical" => Some(Self::Critical),
+            "high" => Some(Self::High),
+            "medium" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+
+    /// How much an exception of this severity adds to the weighted rate
+    pub fn weight(&self) -> u32 {
+        match self {
+            Self::Critical => 5,
+            Self::High => 2,
+            Self::Medium | Self::Low => 1,
+        }
+    }
+
     pub fn icon(&self) -> &str {
         match self {
             Self::Critical => "✗",
@@ -68,7 +244,7 @@ impl ExceptionSeverity {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExceptionStats {
     pub total_exceptions: usize,
     pub unique_exceptions: usize,
@@ -78,26 +254,186 @@ pub struct ExceptionStats {
     pub low_count: usize,
 }
 
+/// Exceptions, groups and counts, as kept in a saved session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExceptionSnapshot {
+    pub exceptions: Vec<Exception>,
+    pub groups: Vec<ExceptionGroup>,
+    pub stats: ExceptionStats,
+}
+
 pub struct ExceptionTracker {
     exceptions: Arc<Mutex<Vec<Exception>>>,
-    grouped_exceptions: Arc<Mutex<HashMap<String, ExceptionGroup>>>,
-    stats: Arc<Mutex<ExceptionStats>>,
+    grouped_exceptions: Shared<HashMap<String, ExceptionGroup>>,
+    /// Groups most frequent first, as last handed out
+    sorted_groups: SnapshotCache<Vec<ExceptionGroup>>,
+    stats: Shared<ExceptionStats>,
     current_exception: Arc<Mutex<Option<Exception>>>,
     parsing_backtrace: Arc<Mutex<bool>>,
+    /// Exceptions whose first line has been seen, finished or not
+    started: AtomicUsize,
+    /// Group of the exception finished last
+    last_fingerprint: Mutex<Option<String>>,
+    max_exceptions: AtomicUsize,
+    grouping: Mutex<ExceptionGrouping>,
+    rate: Mutex<ExceptionRate>,
+}
+
+impl Default for ExceptionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ExceptionTracker {
     pub fn new() -> Self {
         Self {
             exceptions: Arc::new(Mutex::new(Vec::new())),
-            grouped_exceptions: Arc::new(Mutex::new(HashMap::new())),
-            stats: Arc::new(Mutex::new(ExceptionStats::default())),
+            grouped_exceptions: Shared::default(),
+            sorted_groups: SnapshotCache::default(),
+            stats: Shared::default(),
             current_exception: Arc::new(Mutex::new(None)),
             parsing_backtrace: Arc::new(Mutex::new(false)),
+            started: AtomicUsize::new(0),
+            last_fingerprint: Mutex::new(None),
+            max_exceptions: AtomicUsize::new(Limits::default().max_exceptions),
+            grouping: Mutex::new(ExceptionGrouping::default()),
+            rate: Mutex::new(ExceptionRate::new(Instant::now())),
         }
     }
 
-    pub fn parse_line(&self, line: &str) {
+    pub fn with_limits(self, limits: Limits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Change how many recent exceptions are kept, dropping the oldest beyond it
+    pub fn set_limits(&self, limits: Limits) {
+        self.max_exceptions
+            .store(limits.max_exceptions, Ordering::Relaxed);
+        self.trim(StoreKind::Exceptions, limits.max_exceptions);
+    }
+
+    pub fn with_grouping(self, options: ExceptionGrouping) -> Self {
+        self.set_grouping(options);
+        self
+    }
+
+    pub fn grouping(&self) -> ExceptionGrouping {
+        *self.grouping.lock().unwrap()
+    }
+
+    /// Change how exceptions are grouped, regrouping those seen so far;
+    /// returns whether anything changed
+    ///
+    /// Groups are split using the recent exceptions still kept. Occurrences
+    /// older than those stay with the group's sample.
+    pub fn set_grouping(&self, options: ExceptionGrouping) -> bool {
+        let previous = std::mem::replace(&mut *self.grouping.lock().unwrap(), options);
+        if previous == options {
+            return false;
+        }
+
+        // Same lock order as `finalize_current_exception`
+        let mut stats = self.stats.write();
+        let mut grouped = self.grouped_exceptions.write();
+        let exceptions = self.exceptions.lock().unwrap();
+        let old_groups = std::mem::take(&mut *grouped);
+        for group in old_groups.into_values() {
+            let members: Vec<&Exception> = exceptions
+                .iter()
+                .filter(|e| Self::generate_fingerprint(e, previous) == group.fingerprint)
+                .collect();
+            let earliest_member = members.iter().map(|e| e.timestamp).min();
+
+            let older = group.count.saturating_sub(members.len());
+            if older > 0 {
+                let mut endpoints = group.endpoints.clone();
+                for endpoint in members.iter().filter_map(|e| e.endpoint()) {
+                    if let Some(count) = endpoints.get_mut(&endpoint) {
+                        *count = count.saturating_sub(1);
+                    }
+                }
+                endpoints.retain(|_, count| *count > 0);
+                let occurrences = group
+                    .occurrences
+                    .iter()
+                    .copied()
+                    .filter(|at| earliest_member.is_none_or(|earliest| *at < earliest))
+                    .collect();
+                Self::merge_into(
+                    &mut grouped,
+                    options,
+                    ExceptionGroup {
+                        count: older,
+                        last_seen: earliest_member.unwrap_or(group.last_seen),
+                        occurrences,
+                        endpoints,
+                        ..group.clone()
+                    },
+                );
+            }
+            for exception in members {
+                let endpoints = exception.endpoint().into_iter().map(|e| (e, 1)).collect();
+                Self::merge_into(
+                    &mut grouped,
+                    options,
+                    ExceptionGroup {
+                        count: 1,
+                        first_seen: exception.timestamp,
+                        last_seen: exception.timestamp,
+                        occurrences: vec![exception.timestamp],
+                        sample_exception: exception.clone(),
+                        endpoints,
+                        ..group.clone()
+                    },
+                );
+            }
+        }
+        stats.unique_exceptions = grouped.len();
+        true
+    }
+
+    /// Add `part` of a regrouped group under its fingerprint for `options`
+    fn merge_into(
+        grouped: &mut HashMap<String, ExceptionGroup>,
+        options: ExceptionGrouping,
+        mut part: ExceptionGroup,
+    ) {
+        let fingerprint = Self::generate_fingerprint(&part.sample_exception, options);
+        match grouped.get_mut(&fingerprint) {
+            Some(group) => {
+                group.count += part.count;
+                group.dismissed &= part.dismissed;
+                group.first_seen = group.first_seen.min(part.first_seen);
+                if part.last_seen > group.last_seen {
+                    group.last_seen = part.last_seen;
+                    group.sample_exception = part.sample_exception;
+                }
+                group.occurrences.extend(part.occurrences);
+                group.occurrences.sort();
+                for (endpoint, count) in part.endpoints {
+                    *group.endpoints.entry(endpoint).or_default() += count;
+                }
+                keep_last(&mut group.occurrences, MAX_GROUP_OCCURRENCES);
+            }
+            None => {
+                part.location = Self::group_location(&part.sample_exception, options);
+                part.fingerprint = fingerprint.clone();
+                grouped.insert(fingerprint, part);
+            }
+        }
+    }
+
+    fn group_location(exception: &Exception, options: ExceptionGrouping) -> Option<String> {
+        if !options.with_location {
+            return None;
+        }
+        project_location(&exception.backtrace, options.line_numbers)
+    }
+
+    /// Feed a log line, returning whether it was part of an exception
+    pub fn parse_line(&self, line: &str) -> bool {
         // Check if we're currently parsing a backtrace
         let mut parsing = self.parsing_backtrace.lock().unwrap();
 
@@ -105,7 +441,7 @@ impl ExceptionTracker {
             // Check if this is a backtrace line
             if Self::is_backtrace_line(line) {
                 self.add_backtrace_line(line);
-                return;
+                return true;
             } else {
                 // End of backtrace, finalize exception
                 *parsing = false;
@@ -114,33 +450,66 @@ impl ExceptionTracker {
         }
 
         // Check for new exception
-        if let Some(exception) = Self::detect_exception(line) {
-            let mut current = self.current_exception.lock().unwrap();
-            *current = Some(exception);
-            *parsing = true;
+        let Some(exception) = Self::detect_exception(line) else {
+            return false;
+        };
+        let mut current = self.current_exception.lock().unwrap();
+        *current = Some(exception);
+        *parsing = true;
+        self.started.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Attribute the exception being read to the request it was raised in,
+    /// `POST /orders/123`; does nothing between exceptions
+    pub fn set_current_request_context(&self, context: Option<String>, request_id: Option<String>) {
+        if let Some(exception) = self.current_exception.lock().unwrap().as_mut() {
+            exception.context = context;
+            exception.request_id = request_id;
         }
     }
 
+    /// How many exceptions have started, counting one whose backtrace is
+    /// still being read
+    pub fn started_total(&self) -> usize {
+        self.started.load(Ordering::Relaxed)
+    }
+
+    /// Group key of the exception finished last
+    pub fn last_fingerprint(&self) -> Option<String> {
+        self.last_fingerprint.lock().unwrap().clone()
+    }
+
+    /// Occurrences of the group keyed `fingerprint`, if it's still tracked
+    pub fn group_count(&self, fingerprint: &str) -> Option<usize> {
+        self.grouped_exceptions
+            .read()
+            .get(fingerprint)
+            .map(|group| group.count)
+    }
+
     fn detect_exception(line: &str) -> Option<Exception> {
         // Rails exception format: "ExceptionType (message):"
-        // or "ExceptionType: message"
+        // or "ExceptionType: message", after any logger prefix and tags
+        let line = crate::parser::RailsLogParser::message(line);
 
         // Pattern 1: "NameError (undefined local variable or method)"
         if let Some(pos) = line.find(" (") {
             let exc_type = line[..pos].trim();
-            if Self::is_exception_type(exc_type) {
-                if let Some(end_pos) = line[pos..].find("):") {
-                    let message = &line[pos + 2..pos + end_pos];
-                    return Some(Exception {
-                        exception_type: exc_type.to_string(),
-                        message: message.to_string(),
-                        backtrace: Vec::new(),
-                        file_path: None,
-                        line_number: None,
-                        timestamp: Instant::now(),
-                        context: None,
-                    });
-                }
+            if Self::is_exception_type(exc_type)
+                && let Some(end_pos) = line[pos..].find("):")
+            {
+                let message = &line[pos + 2..pos + end_pos];
+                return Some(Exception {
+                    exception_type: exc_type.to_string(),
+                    message: message.to_string(),
+                    backtrace: Vec::new(),
+                    file_path: None,
+                    line_number: None,
+                    timestamp: Instant::now(),
+                    context: None,
+                    request_id: None,
+                });
             }
         }
 
@@ -157,6 +526,7 @@ impl ExceptionTracker {
                     line_number: None,
                     timestamp: Instant::now(),
                     context: None,
+                    request_id: None,
                 });
             }
         }
@@ -188,11 +558,11 @@ impl ExceptionTracker {
             exception.backtrace.push(cleaned_line.clone());
 
             // Extract file path and line number from first backtrace line if not set
-            if exception.file_path.is_none() {
-                if let Some((file, line_num)) = Self::parse_backtrace_location(&cleaned_line) {
-                    exception.file_path = Some(file);
-                    exception.line_number = Some(line_num);
-                }
+            if exception.file_path.is_none()
+                && let Some((file, line_num)) = Self::parse_backtrace_location(&cleaned_line)
+            {
+                exception.file_path = Some(file);
+                exception.line_number = Some(line_num);
             }
         }
     }
@@ -222,10 +592,12 @@ impl ExceptionTracker {
         let mut current = self.current_exception.lock().unwrap();
         if let Some(exception) = current.take() {
             // Generate fingerprint for grouping
-            let fingerprint = Self::generate_fingerprint(&exception);
+            let options = self.grouping();
+            let fingerprint = Self::generate_fingerprint(&exception, options);
+            *self.last_fingerprint.lock().unwrap() = Some(fingerprint.clone());
 
             // Update stats
-            let mut stats = self.stats.lock().unwrap();
+            let mut stats = self.stats.write();
             stats.total_exceptions += 1;
 
             let severity = ExceptionSeverity::from_exception_type(&exception.exception_type);
@@ -235,22 +607,28 @@ impl ExceptionTracker {
                 ExceptionSeverity::Medium => stats.medium_count += 1,
                 ExceptionSeverity::Low => stats.low_count += 1,
             }
+            self.rate.lock().unwrap().record(&severity, Instant::now());
 
             // Group exception
-            let mut grouped = self.grouped_exceptions.lock().unwrap();
+            let mut grouped = self.grouped_exceptions.write();
             if let Some(group) = grouped.get_mut(&fingerprint) {
                 group.count += 1;
                 group.last_seen = Instant::now();
+                group.dismissed = false;
+                group.record_endpoint(&exception);
                 group.occurrences.push(Instant::now());
-                // Keep only last 10 occurrences per group
-                if group.occurrences.len() > 10 {
+                // Keep only the last few occurrences per group
+                if group.occurrences.len() > MAX_GROUP_OCCURRENCES {
                     group.occurrences.remove(0);
                 }
             } else {
                 // Check if we're at capacity before adding new group
                 if grouped.len() >= MAX_EXCEPTION_GROUPS {
                     // Log warning when at capacity
-                    eprintln!("[WARN] Exception groups at capacity ({}), evicting oldest group", MAX_EXCEPTION_GROUPS);
+                    eprintln!(
+                        "[WARN] Exception groups at capacity ({}), evicting oldest group",
+                        MAX_EXCEPTION_GROUPS
+                    );
 
                     // Evict oldest group by first_seen timestamp
                     if let Some(oldest_key) = grouped
@@ -271,34 +649,44 @@ impl ExceptionTracker {
                 }
 
                 stats.unique_exceptions += 1;
-                grouped.insert(
-                    fingerprint.clone(),
-                    ExceptionGroup {
-                        fingerprint: fingerprint.clone(),
-                        exception_type: exception.exception_type.clone(),
-                        message_pattern: Self::normalize_message(&exception.message),
-                        count: 1,
-                        first_seen: Instant::now(),
-                        last_seen: Instant::now(),
-                        sample_exception: exception.clone(),
-                        occurrences: vec![Instant::now()],
-                    },
-                );
+                let mut group = ExceptionGroup {
+                    fingerprint: fingerprint.clone(),
+                    exception_type: exception.exception_type.clone(),
+                    message_pattern: Self::normalize_message(&exception.message),
+                    location: Self::group_location(&exception, options),
+                    count: 1,
+                    first_seen: Instant::now(),
+                    last_seen: Instant::now(),
+                    sample_exception: exception.clone(),
+                    occurrences: vec![Instant::now()],
+                    dismissed: false,
+                    endpoints: HashMap::new(),
+                };
+                group.record_endpoint(&exception);
+                grouped.insert(fingerprint.clone(), group);
             }
 
-            // Store in recent exceptions (keep last 100)
+            // Store in recent exceptions
             let mut exceptions = self.exceptions.lock().unwrap();
             exceptions.push(exception);
-            if exceptions.len() > 100 {
+            if exceptions.len() > self.max_exceptions.load(Ordering::Relaxed) {
                 exceptions.remove(0);
             }
         }
     }
 
-    fn generate_fingerprint(exception: &Exception) -> String {
+    fn generate_fingerprint(exception: &Exception, options: ExceptionGrouping) -> String {
         // Generate a fingerprint based on exception type and normalized message
         let normalized_msg = Self::normalize_message(&exception.message);
-        format!("{}:{}", exception.exception_type, normalized_msg)
+        // ...and where in the app it was raised, when asked; exceptions raised
+        // entirely outside the project keep the plain fingerprint
+        match Self::group_location(exception, options) {
+            Some(location) => format!(
+                "{}:{}@{}",
+                exception.exception_type, normalized_msg, location
+            ),
+            None => format!("{}:{}", exception.exception_type, normalized_msg),
+        }
     }
 
     fn normalize_message(message: &str) -> String {
@@ -336,61 +724,216 @@ impl ExceptionTracker {
         exceptions.iter().rev().take(limit).cloned().collect()
     }
 
-    pub fn get_grouped_exceptions(&self) -> Vec<ExceptionGroup> {
-        let grouped = self.grouped_exceptions.lock().unwrap();
-        let mut groups: Vec<ExceptionGroup> = grouped.values().cloned().collect();
+    /// Every group, most frequent first; shared until the next exception
+    pub fn get_grouped_exceptions(&self) -> Arc<Vec<ExceptionGroup>> {
+        self.grouped_exceptions
+            .derive(&self.sorted_groups, |grouped| {
+                let mut groups: Vec<ExceptionGroup> = grouped.values().cloned().collect();
+                groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+                groups
+            })
+    }
 
-        // Sort by count (most frequent first)
-        groups.sort_by(|a, b| b.count.cmp(&a.count));
+    /// Groups whose type or message contains `query`, most frequent first
+    pub fn get_grouped_exceptions_matching(&self, query: &SearchQuery) -> Vec<ExceptionGroup> {
+        let grouped = self.grouped_exceptions.read();
+        let mut groups: Vec<ExceptionGroup> = grouped
+            .values()
+            .filter(|g| {
+                query.matches_any([
+                    g.exception_type.as_str(),
+                    g.message_pattern.as_str(),
+                    g.sample_exception.message.as_str(),
+                ])
+            })
+            .cloned()
+            .collect();
 
+        groups.sort_by_key(|g| std::cmp::Reverse(g.count));
         groups
     }
 
-    pub fn get_stats(&self) -> ExceptionStats {
-        self.stats.lock().unwrap().clone()
+    /// Groups `filter` lists at `now` whose type or message contains
+    /// `query`, most frequent first
+    pub fn get_grouped_exceptions_filtered(
+        &self,
+        query: &SearchQuery,
+        filter: &ExceptionFilter,
+        now: Instant,
+    ) -> Vec<ExceptionGroup> {
+        let mut groups = self.get_grouped_exceptions_matching(query);
+        groups.retain(|group| filter.matches(group, now));
+        groups
+    }
+
+    /// Hide the group keyed `fingerprint` until it happens again; false when
+    /// it's not tracked
+    pub fn dismiss(&self, fingerprint: &str) -> bool {
+        self.set_dismissed(fingerprint, true)
+    }
+
+    /// List a dismissed group again; false when it's not tracked
+    pub fn undismiss(&self, fingerprint: &str) -> bool {
+        self.set_dismissed(fingerprint, false)
+    }
+
+    fn set_dismissed(&self, fingerprint: &str, dismissed: bool) -> bool {
+        let mut grouped = self.grouped_exceptions.write();
+        let Some(group) = grouped.get_mut(fingerprint) else {
+            return false;
+        };
+        group.dismissed = dismissed;
+        true
+    }
+
+    /// Groups dismissed and not seen since
+    pub fn dismissed_count(&self) -> usize {
+        self.grouped_exceptions
+            .read()
+            .values()
+            .filter(|group| group.dismissed)
+            .count()
+    }
+
+    pub fn get_stats(&self) -> Arc<ExceptionStats> {
+        self.stats.snapshot()
+    }
+
+    /// No exception seen yet
+    pub fn is_empty(&self) -> bool {
+        self.stats.read().total_exceptions == 0
+    }
+
+    pub fn snapshot(&self) -> ExceptionSnapshot {
+        ExceptionSnapshot {
+            exceptions: self.exceptions.lock().unwrap().clone(),
+            groups: self.get_grouped_exceptions().to_vec(),
+            stats: self.stats.read().clone(),
+        }
+    }
+
+    /// Replace everything tracked with a saved session's exceptions
+    pub fn restore(&self, snapshot: ExceptionSnapshot) {
+        *self.stats.write() = snapshot.stats;
+        *self.grouped_exceptions.write() = snapshot
+            .groups
+            .into_iter()
+            .map(|group| (group.fingerprint.clone(), group))
+            .collect();
+        *self.exceptions.lock().unwrap() = snapshot.exceptions;
+        *self.current_exception.lock().unwrap() = None;
     }
 
     pub fn get_top_exceptions(&self, limit: usize) -> Vec<ExceptionGroup> {
         let groups = self.get_grouped_exceptions();
-        groups.into_iter().take(limit).collect()
+        groups.iter().take(limit).cloned().collect()
     }
 
     pub fn get_critical_exceptions(&self) -> Vec<ExceptionGroup> {
         self.get_grouped_exceptions()
-            .into_iter()
+            .iter()
             .filter(|g| {
                 ExceptionSeverity::from_exception_type(&g.exception_type)
                     == ExceptionSeverity::Critical
             })
+            .cloned()
             .collect()
     }
 
+    /// Exceptions seen in the `window` before `now`
+    ///
+    /// Only the last few occurrences of each group are kept, so a group that
+    /// repeats rapidly is undercounted.
+    pub fn count_at(&self, now: Instant, window: Duration) -> usize {
+        self.grouped_exceptions
+            .read()
+            .values()
+            .flat_map(|group| &group.occurrences)
+            .filter(|occurrence| now.saturating_duration_since(**occurrence) <= window)
+            .count()
+    }
+
+    /// Exceptions in the last minute
     pub fn get_exception_rate(&self) -> f64 {
-        // Calculate exceptions per minute based on recent occurrences
-        let groups = self.get_grouped_exceptions();
-        let now = Instant::now();
-        let mut recent_count = 0;
-
-        for group in groups {
-            for occurrence in &group.occurrences {
-                let age = now.duration_since(*occurrence).as_secs();
-                if age < 60 {
-                    recent_count += 1;
+        self.rate.lock().unwrap().count_per_minute(Instant::now())
+    }
+
+    /// Severity-weighted exceptions in the minute before `now`
+    pub fn weighted_rate_at(&self, now: Instant) -> f64 {
+        self.rate.lock().unwrap().per_minute(now)
+    }
+
+    /// Weighted exceptions per minute for the last [`RATE_MINUTES`], oldest first
+    pub fn rate_history_at(&self, now: Instant) -> Vec<u32> {
+        self.rate.lock().unwrap().history(now)
+    }
+
+    /// Item counts and approximate sizes of the tracker's stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        // Groups before samples, the order exceptions are recorded in
+        let grouped = self.grouped_exceptions.read();
+        let exceptions = self.exceptions.lock().unwrap();
+        vec![
+            StoreUsage::new(
+                StoreKind::Exceptions,
+                exceptions.len(),
+                exceptions.iter().map(Exception::approx_bytes).sum(),
+            )
+            .with_limit(self.max_exceptions.load(Ordering::Relaxed)),
+            StoreUsage::new(
+                StoreKind::ExceptionGroups,
+                grouped.len(),
+                grouped
+                    .iter()
+                    .map(|(key, group)| key.len() + group.approx_bytes())
+                    .sum(),
+            )
+            .with_limit(MAX_EXCEPTION_GROUPS),
+        ]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        total_bytes(&self.store_usage())
+    }
+
+    /// Shrink a store to `keep` entries, returning how many were dropped
+    ///
+    /// Exception samples keep the newest; groups keep the most recently seen.
+    /// Aggregate stats are left untouched so totals stay accurate.
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::Exceptions => keep_last(&mut self.exceptions.lock().unwrap(), keep),
+            StoreKind::ExceptionGroups => {
+                let mut grouped = self.grouped_exceptions.write();
+                if grouped.len() <= keep {
+                    return 0;
                 }
+                let mut by_recency: Vec<(String, Instant)> = grouped
+                    .iter()
+                    .map(|(key, group)| (key.clone(), group.last_seen))
+                    .collect();
+                by_recency.sort_by_key(|(_, last_seen)| std::cmp::Reverse(*last_seen));
+                let removed = by_recency.len() - keep;
+                for (key, _) in by_recency.into_iter().skip(keep) {
+                    grouped.remove(&key);
+                }
+                removed
             }
+            _ => 0,
         }
-
-        recent_count as f64
     }
 
     pub fn clear_stats(&self) {
-        let mut exceptions = self.exceptions.lock().unwrap();
-        exceptions.clear();
+        // Same lock order as `finalize_current_exception`
+        let mut stats = self.stats.write();
+        *stats = ExceptionStats::default();
 
-        let mut grouped = self.grouped_exceptions.lock().unwrap();
+        let mut grouped = self.grouped_exceptions.write();
         grouped.clear();
 
-        let mut stats = self.stats.lock().unwrap();
-        *stats = ExceptionStats::default();
+        let mut exceptions = self.exceptions.lock().unwrap();
+        exceptions.clear();
+
+        *self.rate.lock().unwrap() = ExceptionRate::new(Instant::now());
     }
 }
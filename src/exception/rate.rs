@@ -0,0 +1,113 @@
+//! Exceptions per minute, weighted by severity, for the header
+//!
+//! Every exception lands in a one-minute bucket, so a burst is counted in full
+//! however many occurrences its group keeps. A critical exception weighs
+//! [`ExceptionSeverity::weight`] 5, a high one 2, anything else 1.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::ExceptionSeverity;
+
+/// Minutes of history kept, and shown in the header's sparkline
+pub const RATE_MINUTES: usize = 10;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// One minute of exceptions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateBucket {
+    /// Minutes since the rate's origin
+    pub minute: u64,
+    pub count: u32,
+    /// Count with each exception multiplied by its severity's weight
+    pub weighted: u32,
+}
+
+/// Per-minute buckets of the last [`RATE_MINUTES`]
+#[derive(Debug, Clone)]
+pub struct ExceptionRate {
+    origin: Instant,
+    /// Oldest first; minutes without exceptions have no bucket
+    buckets: VecDeque<RateBucket>,
+}
+
+impl ExceptionRate {
+    /// Buckets start at `origin`, on the minute
+    pub fn new(origin: Instant) -> Self {
+        Self {
+            origin,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn minute(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.origin).as_secs() / MINUTE.as_secs()
+    }
+
+    /// Count an exception of `severity` seen `at`
+    pub fn record(&mut self, severity: &ExceptionSeverity, at: Instant) {
+        let minute = self.minute(at);
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.minute == minute => {
+                bucket.count += 1;
+                bucket.weighted += severity.weight();
+            }
+            _ => self.buckets.push_back(RateBucket {
+                minute,
+                count: 1,
+                weighted: severity.weight(),
+            }),
+        }
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| bucket.minute + (RATE_MINUTES as u64) <= minute)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Weighted count per minute for the last [`RATE_MINUTES`] up to `now`,
+    /// oldest first, the current (partial) minute last
+    pub fn history(&self, now: Instant) -> Vec<u32> {
+        let current = self.minute(now);
+        (0..RATE_MINUTES as u64)
+            .rev()
+            .map(|ago| {
+                let minute = current.checked_sub(ago);
+                self.buckets
+                    .iter()
+                    .find(|bucket| Some(bucket.minute) == minute)
+                    .map_or(0, |bucket| bucket.weighted)
+            })
+            .collect()
+    }
+
+    /// Weighted exceptions in the minute before `now`
+    ///
+    /// The current bucket plus the share of the previous one still inside
+    /// the last 60 seconds, so the figure doesn't drop to 0 on the minute.
+    pub fn per_minute(&self, now: Instant) -> f64 {
+        self.over_last_minute(now, |bucket| bucket.weighted)
+    }
+
+    /// Unweighted [`per_minute`](Self::per_minute)
+    pub fn count_per_minute(&self, now: Instant) -> f64 {
+        self.over_last_minute(now, |bucket| bucket.count)
+    }
+
+    fn over_last_minute(&self, now: Instant, value: impl Fn(&RateBucket) -> u32) -> f64 {
+        let current = self.minute(now);
+        let into_minute = now.saturating_duration_since(self.origin).as_secs_f64()
+            % MINUTE.as_secs_f64()
+            / MINUTE.as_secs_f64();
+        let bucket = |minute: Option<u64>| {
+            self.buckets
+                .iter()
+                .find(|bucket| Some(bucket.minute) == minute)
+                .map_or(0.0, |bucket| value(bucket) as f64)
+        };
+        bucket(Some(current)) + bucket(current.checked_sub(1)) * (1.0 - into_minute)
+    }
+}
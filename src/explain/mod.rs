@@ -1,3 +1,7 @@
+mod mysql;
+mod sqlite;
+
+use crate::database::SqlDialect;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +11,9 @@ pub struct ExplainPlan {
     pub warnings: Vec<ExplainWarning>,
     pub cost: Option<f64>,
     pub rows: Option<usize>,
+    /// Dialect the plan was read as
+    #[serde(default)]
+    pub dialect: SqlDialect,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,15 +31,28 @@ pub enum WarningSeverity {
 
 pub struct ExplainExecutor {
     _database_url: Option<String>,
+    dialect: SqlDialect,
 }
 
 impl ExplainExecutor {
     pub fn new(database_url: Option<String>) -> Self {
         Self {
             _database_url: database_url,
+            dialect: SqlDialect::Generic,
         }
     }
 
+    /// Run and read EXPLAIN the way the app's database does
+    pub fn with_dialect(mut self, dialect: SqlDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Statement sent to the database for `query`
+    pub fn explain_statement(&self, query: &str) -> String {
+        self.dialect.explain_statement(query)
+    }
+
     pub fn explain_query(&self, query: &str) -> Result<ExplainPlan, String> {
         // For now, this is a placeholder that would connect to the database
         // In a real implementation, we'd use a database connection pool
@@ -42,20 +62,44 @@ impl ExplainExecutor {
     }
 
     fn simulate_explain(&self, _query: &str) -> ExplainPlan {
-        let raw_output = format!(
-            "Seq Scan on users  (cost=0.00..15.00 rows=500 width=32)\n  \
-            Filter: (active = true)"
-        );
+        let raw_output = match self.dialect {
+            SqlDialect::MySql => {
+                "-> Filter: (users.active = true)  (cost=50.25 rows=50)\n    \
+                -> Table scan on users  (cost=50.25 rows=500)"
+            }
+            SqlDialect::Sqlite => "QUERY PLAN\n`--SCAN users",
+            SqlDialect::Postgres | SqlDialect::Generic => {
+                "Seq Scan on users  (cost=0.00..15.00 rows=500 width=32)\n  \
+                Filter: (active = true)"
+            }
+        };
+
+        self.parse_plan(raw_output)
+    }
 
-        let formatted = self.format_explain(&raw_output);
-        let warnings = self.analyze_plan(&raw_output);
+    /// Read EXPLAIN output in this executor's dialect
+    pub fn parse_plan(&self, raw_output: &str) -> ExplainPlan {
+        let (warnings, cost, rows) = match self.dialect {
+            SqlDialect::MySql => (
+                mysql::analyze_plan(raw_output),
+                mysql::extract_cost(raw_output),
+                mysql::extract_rows(raw_output),
+            ),
+            SqlDialect::Sqlite => (sqlite::analyze_plan(raw_output), None, None),
+            SqlDialect::Postgres | SqlDialect::Generic => (
+                self.analyze_plan(raw_output),
+                self.extract_cost(raw_output),
+                self.extract_rows(raw_output),
+            ),
+        };
 
         ExplainPlan {
-            raw_output: raw_output.clone(),
-            formatted,
+            raw_output: raw_output.to_string(),
+            formatted: self.format_explain(raw_output),
             warnings,
-            cost: Some(15.0),
-            rows: Some(500),
+            cost,
+            rows,
+            dialect: self.dialect,
         }
     }
 
@@ -81,31 +125,10 @@ impl ExplainExecutor {
             });
         }
 
-        // Check for high cost
-        if let Some(cost) = self.extract_cost(plan) {
-            if cost > 1000.0 {
-                warnings.push(ExplainWarning {
-                    severity: WarningSeverity::Critical,
-                    message: format!("Very high query cost: {:.2}", cost),
-                });
-            } else if cost > 100.0 {
-                warnings.push(ExplainWarning {
-                    severity: WarningSeverity::Warning,
-                    message: format!("High query cost: {:.2}", cost),
-                });
-            }
-        }
-
-        // Check for large row estimates
-        if let Some(rows) = self.extract_rows(plan) {
-            if rows > 10000 {
-                warnings.push(ExplainWarning {
-                    severity: WarningSeverity::Warning,
-                    message: format!("Large result set estimated: {} rows", rows),
-                });
-            }
-        }
-
+        warnings.extend(estimate_warnings(
+            self.extract_cost(plan),
+            self.extract_rows(plan),
+        ));
         warnings
     }
 
@@ -136,15 +159,58 @@ impl ExplainExecutor {
     }
 }
 
+/// Warnings for a plan's estimated cost and row count, shared by every dialect
+fn estimate_warnings(cost: Option<f64>, rows: Option<usize>) -> Vec<ExplainWarning> {
+    let mut warnings = Vec::new();
+
+    // Check for high cost
+    if let Some(cost) = cost {
+        if cost > 1000.0 {
+            warnings.push(ExplainWarning {
+                severity: WarningSeverity::Critical,
+                message: format!("Very high query cost: {:.2}", cost),
+            });
+        } else if cost > 100.0 {
+            warnings.push(ExplainWarning {
+                severity: WarningSeverity::Warning,
+                message: format!("High query cost: {:.2}", cost),
+            });
+        }
+    }
+
+    // Check for large row estimates
+    if let Some(rows) = rows
+        && rows > 10000
+    {
+        warnings.push(ExplainWarning {
+            severity: WarningSeverity::Warning,
+            message: format!("Large result set estimated: {} rows", rows),
+        });
+    }
+
+    warnings
+}
+
 impl ExplainPlan {
     pub fn has_index_scan(&self) -> bool {
-        self.raw_output.contains("Index Scan")
-            || self.raw_output.contains("Index Only Scan")
-            || self.raw_output.contains("Bitmap Index Scan")
+        match self.dialect {
+            SqlDialect::MySql => mysql::has_index_access(&self.raw_output),
+            SqlDialect::Sqlite => sqlite::has_index_access(&self.raw_output),
+            SqlDialect::Postgres | SqlDialect::Generic => {
+                self.raw_output.contains("Index Scan")
+                    || self.raw_output.contains("Index Only Scan")
+                    || self.raw_output.contains("Bitmap Index Scan")
+            }
+        }
     }
 
+    /// Whether a table is read in full (Seq Scan, Table scan, SCAN)
     pub fn has_seq_scan(&self) -> bool {
-        self.raw_output.contains("Seq Scan")
+        match self.dialect {
+            SqlDialect::MySql => !mysql::table_scans(&self.raw_output).is_empty(),
+            SqlDialect::Sqlite => !sqlite::table_scans(&self.raw_output).is_empty(),
+            SqlDialect::Postgres | SqlDialect::Generic => self.raw_output.contains("Seq Scan"),
+        }
     }
 
     pub fn suggest_indexes(&self) -> Vec<String> {
@@ -154,12 +220,13 @@ impl ExplainPlan {
             suggestions.push("Consider adding an index to avoid sequential scans".to_string());
         }
 
-        if let Some(cost) = self.cost {
-            if cost > 100.0 && !self.has_index_scan() {
-                suggestions.push(
-                    "High cost without index usage - investigate index opportunities".to_string(),
-                );
-            }
+        if let Some(cost) = self.cost
+            && cost > 100.0
+            && !self.has_index_scan()
+        {
+            suggestions.push(
+                "High cost without index usage - investigate index opportunities".to_string(),
+            );
         }
 
         suggestions
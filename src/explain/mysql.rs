@@ -0,0 +1,92 @@
+//! MySQL `EXPLAIN FORMAT=TREE` output
+//!
+//! Each node is a `-> ` line, children indented below their parent:
+//!
+//! ```text
+//! -> Sort: users.name  (cost=50.25 rows=500)
+//!     -> Filter: (users.active = true)  (cost=50.25 rows=500)
+//!         -> Table scan on users  (cost=50.25 rows=500)
+//! ```
+//!
+//! Costs are a single number rather than PostgreSQL's `start..total`. The
+//! first node is the whole query, so its cost and rows are the query's.
+
+use super::{ExplainWarning, WarningSeverity, estimate_warnings};
+
+/// Plan nodes with the leading `-> ` removed
+fn nodes(plan: &str) -> impl Iterator<Item = &str> {
+    plan.lines().map(|line| {
+        let line = line.trim();
+        line.strip_prefix("->").unwrap_or(line).trim()
+    })
+}
+
+/// Tables read in full, in plan order; internal temporary tables are left out
+pub(super) fn table_scans(plan: &str) -> Vec<&str> {
+    nodes(plan)
+        .filter_map(|node| node.strip_prefix("Table scan on "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter(|table| !table.starts_with('<'))
+        .collect()
+}
+
+pub(super) fn has_index_access(plan: &str) -> bool {
+    nodes(plan).any(|node| {
+        [
+            "Index lookup on",
+            "Index range scan on",
+            "Index scan on",
+            "Single-row index lookup on",
+            "Covering index lookup on",
+            "Covering index range scan on",
+            "Covering index scan on",
+        ]
+        .iter()
+        .any(|access| node.starts_with(access))
+    })
+}
+
+pub(super) fn extract_cost(plan: &str) -> Option<f64> {
+    estimate(plan, "cost=")
+}
+
+pub(super) fn extract_rows(plan: &str) -> Option<usize> {
+    // Row estimates can be fractional (`rows=0.5`) or exponential (`rows=1e+6`)
+    estimate(plan, "rows=").map(|rows| rows.round() as usize)
+}
+
+/// First `key=<number>` in the plan
+fn estimate(plan: &str, key: &str) -> Option<f64> {
+    let start = plan.find(key)? + key.len();
+    plan[start..]
+        .split(|c: char| c.is_whitespace() || c == ')')
+        .next()
+        .and_then(|value| value.parse::<f64>().ok())
+}
+
+pub(super) fn analyze_plan(plan: &str) -> Vec<ExplainWarning> {
+    let mut warnings: Vec<ExplainWarning> = table_scans(plan)
+        .into_iter()
+        .map(|table| ExplainWarning {
+            severity: WarningSeverity::Warning,
+            message: format!("Full table scan on {} - consider adding an index", table),
+        })
+        .collect();
+
+    if nodes(plan).any(|node| node.to_ascii_lowercase().contains("temporary table")) {
+        warnings.push(ExplainWarning {
+            severity: WarningSeverity::Warning,
+            message: "Temporary table used - GROUP BY or DISTINCT can't use an index".to_string(),
+        });
+    }
+
+    if nodes(plan).any(|node| node.starts_with("Sort:")) {
+        warnings.push(ExplainWarning {
+            severity: WarningSeverity::Info,
+            message: "Filesort - ORDER BY isn't served by an index".to_string(),
+        });
+    }
+
+    warnings.extend(estimate_warnings(extract_cost(plan), extract_rows(plan)));
+    warnings
+}
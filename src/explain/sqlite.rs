@@ -0,0 +1,79 @@
+//! SQLite `EXPLAIN QUERY PLAN` output
+//!
+//! The sqlite3 shell prints a tree, older versions and drivers one
+//! `id|parent|notused|detail` row per step:
+//!
+//! ```text
+//! QUERY PLAN
+//! |--SEARCH users USING INDEX index_users_on_email (email=?)
+//! `--USE TEMP B-TREE FOR ORDER BY
+//! ```
+//!
+//! Before SQLite 3.36 tables were written `SCAN TABLE users`. There are no
+//! cost or row estimates.
+
+use super::{ExplainWarning, WarningSeverity};
+
+/// Detail text of each step, without tree drawing or row ids
+fn steps(plan: &str) -> impl Iterator<Item = &str> {
+    plan.lines().filter_map(|line| {
+        let line = line.rsplit('|').next().unwrap_or(line);
+        let step = line.trim_start_matches(['|', '`', '-', ' ']).trim();
+        (!step.is_empty() && step != "QUERY PLAN").then_some(step)
+    })
+}
+
+/// Table a `SCAN`/`SEARCH` step reads, and the rest of the step
+fn step_table<'a>(step: &'a str, verb: &str) -> Option<(&'a str, &'a str)> {
+    let rest = step.strip_prefix(verb)?.strip_prefix(' ')?;
+    let rest = rest.strip_prefix("TABLE ").unwrap_or(rest);
+    let mut parts = rest.splitn(2, ' ');
+    Some((parts.next()?, parts.next().unwrap_or("")))
+}
+
+/// Tables read in full, in plan order; a scan of a covering index doesn't count
+pub(super) fn table_scans(plan: &str) -> Vec<&str> {
+    steps(plan)
+        .filter_map(|step| step_table(step, "SCAN"))
+        .filter(|(_, rest)| !rest.contains("INDEX"))
+        .map(|(table, _)| table)
+        .collect()
+}
+
+pub(super) fn has_index_access(plan: &str) -> bool {
+    steps(plan).any(|step| {
+        (step.starts_with("SEARCH") || step.starts_with("SCAN"))
+            && (step.contains(" INDEX ") || step.contains("PRIMARY KEY"))
+    })
+}
+
+pub(super) fn analyze_plan(plan: &str) -> Vec<ExplainWarning> {
+    let mut warnings: Vec<ExplainWarning> = table_scans(plan)
+        .into_iter()
+        .map(|table| ExplainWarning {
+            severity: WarningSeverity::Warning,
+            message: format!("Full table scan on {} - consider adding an index", table),
+        })
+        .collect();
+
+    for step in steps(plan) {
+        if let Some((table, _)) = step_table(step, "SEARCH")
+            && step.contains("AUTOMATIC")
+        {
+            warnings.push(ExplainWarning {
+                severity: WarningSeverity::Warning,
+                message: format!(
+                    "Automatic index built on {} for this query - add a permanent one",
+                    table
+                ),
+            });
+        } else if let Some(purpose) = step.strip_prefix("USE TEMP B-TREE FOR ") {
+            warnings.push(ExplainWarning {
+                severity: WarningSeverity::Info,
+                message: format!("Temporary B-tree for {} - no index covers it", purpose),
+            });
+        }
+    }
+
+    warnings
+}
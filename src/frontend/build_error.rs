@@ -0,0 +1,369 @@
+//! Compile errors from vite/esbuild, webpack and `tsc --watch`, put back
+//! together from the lines they're printed over
+//!
+//! The line naming the error is parsed into a [`BuildError`] by
+//! [`FrontendLogParser`](super::FrontendLogParser); [`BuildErrors`] then adds
+//! the lines that follow it (location, code frame) until the bundler's
+//! "build failed" line, and forgets the failure once the process compiles
+//! again.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::FrontendLogEvent;
+
+/// Code frame lines kept per error
+pub const MAX_CODE_FRAME: usize = 12;
+
+/// Which tool printed the error, deciding how the lines after it read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTool {
+    /// Vite's dev server (`[vite] Internal server error: ...`)
+    Vite,
+    /// esbuild, as run by `vite build` (`✘ [ERROR] ...`)
+    Esbuild,
+    Webpack,
+    Tsc,
+}
+
+impl BuildTool {
+    pub fn label(self) -> &'static str {
+        match self {
+            BuildTool::Vite => "vite",
+            BuildTool::Esbuild => "esbuild",
+            BuildTool::Webpack => "webpack",
+            BuildTool::Tsc => "tsc",
+        }
+    }
+}
+
+/// One compile error
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildError {
+    pub tool: BuildTool,
+    /// As printed: relative to the frontend's directory, or absolute
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    /// The source excerpt the tool printed under the error, as printed
+    pub code_frame: Vec<String>,
+}
+
+impl BuildError {
+    fn new(tool: BuildTool, message: &str) -> Self {
+        Self {
+            tool,
+            file: None,
+            line: None,
+            column: None,
+            message: message.trim().to_string(),
+            code_frame: Vec::new(),
+        }
+    }
+
+    fn at(mut self, file: &str, line: Option<&str>, column: Option<&str>) -> Self {
+        self.set_location(file, line, column);
+        self
+    }
+
+    fn set_location(&mut self, file: &str, line: Option<&str>, column: Option<&str>) {
+        self.file = Some(file.strip_prefix("./").unwrap_or(file).to_string());
+        self.line = line.and_then(|line| line.parse().ok());
+        self.column = column.and_then(|column| column.parse().ok());
+    }
+
+    /// `src/App.tsx:12:8`, or as much of it as is known
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_deref()?;
+        Some(match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+            (Some(line), None) => format!("{}:{}", file, line),
+            _ => file.to_string(),
+        })
+    }
+
+    /// The error a line starts, if it starts one
+    pub fn parse_header(line: &str) -> Option<Self> {
+        if !line.contains("ERROR") && !line.contains("error") {
+            return None;
+        }
+        if let Some(caps) = vite_header().captures(line) {
+            return Some(Self::new(BuildTool::Vite, &caps[1]));
+        }
+        if let Some(caps) = esbuild_header().captures(line) {
+            return Some(Self::new(BuildTool::Esbuild, &caps[1]));
+        }
+        if let Some(caps) = webpack_header().captures(line) {
+            let error = Self::new(BuildTool::Webpack, "");
+            let line = caps.get(2).or(caps.get(4)).map(|m| m.as_str());
+            let column = caps.get(3).or(caps.get(5)).map(|m| m.as_str());
+            return Some(error.at(&caps[1], line, column));
+        }
+        if let Some(caps) = tsc_header().captures(line) {
+            let error = Self::new(BuildTool::Tsc, &caps[6]);
+            let line = caps.get(2).or(caps.get(4)).map(|m| m.as_str());
+            let column = caps.get(3).or(caps.get(5)).map(|m| m.as_str());
+            return Some(error.at(&caps[1], line, column));
+        }
+        None
+    }
+
+    /// Take a line printed after the header; false once the line is past
+    /// the error's details
+    fn add_detail(&mut self, line: &str) -> bool {
+        let indented = line.starts_with([' ', '\t']);
+        let blank = line.trim().is_empty();
+        match self.tool {
+            BuildTool::Vite => {
+                // `/path/App.tsx:12:8: ERROR: Expected ";" but found "x"` names the real error
+                if let Some(caps) = esbuild_message().captures(line) {
+                    self.message = caps[4].trim().to_string();
+                    self.set_location(&caps[1], Some(&caps[2]), Some(&caps[3]));
+                    return true;
+                }
+                if !indented && !blank {
+                    return false;
+                }
+                if let Some(caps) = vite_file().captures(line) {
+                    self.set_location(
+                        &caps[1],
+                        caps.get(2).map(|m| m.as_str()),
+                        caps.get(3).map(|m| m.as_str()),
+                    );
+                } else if pipe_frame().is_match(line) {
+                    self.push_frame(line);
+                }
+                true
+            }
+            BuildTool::Esbuild => {
+                if !indented && !blank {
+                    return false;
+                }
+                if let Some(caps) = esbuild_location().captures(line) {
+                    self.set_location(&caps[1], Some(&caps[2]), Some(&caps[3]));
+                } else if line.contains('│') || line.contains('╵') {
+                    self.push_frame(line);
+                }
+                true
+            }
+            BuildTool::Webpack => {
+                if blank {
+                    // A blank line after the frame ends the error
+                    return self.code_frame.is_empty();
+                }
+                if pipe_frame().is_match(line) {
+                    self.push_frame(line);
+                } else if self.message.is_empty() {
+                    self.message = line.trim().to_string();
+                }
+                true
+            }
+            BuildTool::Tsc => {
+                if blank {
+                    return self.code_frame.is_empty();
+                }
+                if tsc_frame().is_match(line) {
+                    self.push_frame(line);
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    fn push_frame(&mut self, line: &str) {
+        if self.code_frame.len() < MAX_CODE_FRAME {
+            self.code_frame.push(line.trim_end().to_string());
+        }
+    }
+}
+
+/// A failed compile: every error printed for it, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildFailure {
+    pub process: String,
+    pub errors: Vec<BuildError>,
+    /// The "build failed" line was seen; the next error starts a new failure
+    pub complete: bool,
+    /// Still reading the last error's details
+    open: bool,
+}
+
+/// What a line did to a process's build state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildChange {
+    /// A new failure started
+    Failed,
+    /// The current failure got another error or more detail
+    Updated,
+    /// The process compiled again
+    Fixed,
+}
+
+/// The current build failure of each frontend process
+#[derive(Debug, Default)]
+pub struct BuildErrors {
+    failures: HashMap<String, BuildFailure>,
+    /// Process whose build failed last
+    latest: Option<String>,
+}
+
+impl BuildErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Follow one line of `process`'s output, with what the frontend parser
+    /// made of it
+    pub fn observe(
+        &mut self,
+        process: &str,
+        line: &str,
+        event: Option<&FrontendLogEvent>,
+    ) -> Option<BuildChange> {
+        match event {
+            Some(FrontendLogEvent::CompileError(error)) => {
+                return Some(self.start_error(process, error.clone()));
+            }
+            Some(
+                FrontendLogEvent::CompileSuccess { .. }
+                | FrontendLogEvent::HotModuleReplacement { .. },
+            ) => return self.fixed(process),
+            _ => {}
+        }
+
+        let failure = self.failures.get_mut(process)?;
+        if failure.complete {
+            return None;
+        }
+        if is_build_failed_line(line) {
+            failure.complete = true;
+            failure.open = false;
+            return Some(BuildChange::Updated);
+        }
+        if !failure.open {
+            return None;
+        }
+        let error = failure.errors.last_mut()?;
+        let before = error.clone();
+        if !error.add_detail(line) {
+            failure.open = false;
+            return None;
+        }
+        (*error != before).then_some(BuildChange::Updated)
+    }
+
+    fn start_error(&mut self, process: &str, error: BuildError) -> BuildChange {
+        self.latest = Some(process.to_string());
+        match self.failures.get_mut(process) {
+            Some(failure) if !failure.complete => {
+                failure.errors.push(error);
+                failure.open = true;
+                BuildChange::Updated
+            }
+            _ => {
+                self.failures.insert(
+                    process.to_string(),
+                    BuildFailure {
+                        process: process.to_string(),
+                        errors: vec![error],
+                        complete: false,
+                        open: true,
+                    },
+                );
+                BuildChange::Failed
+            }
+        }
+    }
+
+    fn fixed(&mut self, process: &str) -> Option<BuildChange> {
+        self.failures.remove(process)?;
+        if self.latest.as_deref() == Some(process) {
+            self.latest = self.failures.keys().next().cloned();
+        }
+        Some(BuildChange::Fixed)
+    }
+
+    /// The failure reported last, if its process hasn't compiled since
+    pub fn latest(&self) -> Option<&BuildFailure> {
+        self.failures.get(self.latest.as_deref()?)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// The line a bundler prints once it gave up on a build
+fn is_build_failed_line(line: &str) -> bool {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| {
+            Regex::new(
+                r"error during build:|Build failed with \d+ errors?|compiled with \d+ errors?|Failed to compile|Found [1-9]\d* errors?",
+            )
+            .unwrap()
+        })
+        .is_match(line)
+}
+
+/// `4:12:09 PM [vite] Internal server error: Transform failed with 1 error:`
+fn vite_header() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\[vite\] Internal server error: (.+)$").unwrap())
+}
+
+/// `  File: /home/dev/shop/frontend/src/App.tsx:12:8`
+fn vite_file() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s+File: (\S+?)(?::(\d+)(?::(\d+))?)?\s*$").unwrap())
+}
+
+/// `✘ [ERROR] Expected ";" but found "x"`
+fn esbuild_header() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s*✘ \[ERROR\] (.+)$").unwrap())
+}
+
+/// `/home/dev/shop/frontend/src/App.tsx:12:8: ERROR: Expected ";" but found "x"`
+fn esbuild_message() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^(\S+?):(\d+):(\d+): ERROR: (.+)$").unwrap())
+}
+
+/// `    src/App.tsx:12:8:`
+fn esbuild_location() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s+(\S+?):(\d+):(\d+):\s*$").unwrap())
+}
+
+/// `ERROR in ./src/App.tsx 12:8` or `ERROR in /abs/src/api.ts:7:3`
+fn webpack_header() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^ERROR in (\S+?)(?::(\d+):(\d+)| (\d+):(\d+)(?:-\d+)?)?\s*$").unwrap()
+    })
+}
+
+/// `src/api.ts:7:3 - error TS2322: ...` or `src/api.ts(7,3): error TS2322: ...`
+fn tsc_header() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(\S+?)(?::(\d+):(\d+) - |\((\d+),(\d+)\): )error (TS\d+: .+)$").unwrap()
+    })
+}
+
+/// Vite and webpack frames: `  12 |    let y x`, `     |          ^`, `>   let y x`
+fn pipe_frame() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s*(?:>\s*)?(?:\d+\s*)?\||^>").unwrap())
+}
+
+/// tsc frames: `7   return value;` and the `~~~~` under it
+fn tsc_frame() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\d+\s|^\s+~+\s*$").unwrap())
+}
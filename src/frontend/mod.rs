@@ -1,4 +1,22 @@
+mod build_error;
+mod scan;
+mod tracker;
+
+pub use build_error::{
+    BuildChange, BuildError, BuildErrors, BuildFailure, BuildTool, MAX_CODE_FRAME,
+};
+pub use scan::{
+    DetectionTrace, SKIPPED_DIRS, ScanCap, ScanLimits, is_git_ignored, scan_package_dirs,
+};
+pub use tracker::{
+    BuildState, DEFAULT_FRONTEND_PROCESS, FrontendTracker, HMR_WINDOW, MAX_BUILD_ERRORS,
+    MAX_BUILD_TIMES,
+};
+
+use regex::Regex;
 use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FrontendFramework {
@@ -110,10 +128,55 @@ impl FrontendApp {
     }
 
     pub fn detect_with_config(config_path: Option<&str>) -> Self {
+        Self::detect_in_root(Path::new("."), config_path)
+    }
+
+    /// Detect a frontend app, probing candidate directories relative to `root`
+    ///
+    /// The returned `path` stays relative to `root` (unless the configured path
+    /// is absolute), since generated Procfile commands run from the project root.
+    pub fn detect_in_root(root: &Path, config_path: Option<&str>) -> Self {
+        Self::detect_traced(root, config_path, &ScanLimits::default()).0
+    }
+
+    /// [`detect_in_root`](Self::detect_in_root), saying what was tried
+    ///
+    /// The configured path is tried first, then the usual directory names
+    /// (skipping any the project's `.gitignore` ignores), then a search of
+    /// the project bounded by `limits`.
+    pub fn detect_traced(
+        root: &Path,
+        config_path: Option<&str>,
+        limits: &ScanLimits,
+    ) -> (Self, DetectionTrace) {
+        let started = Instant::now();
+        let (app, mut trace) = Self::detect_steps(root, config_path, limits);
+        trace.elapsed = started.elapsed();
+        (app, trace)
+    }
+
+    fn detect_steps(
+        root: &Path,
+        config_path: Option<&str>,
+        limits: &ScanLimits,
+    ) -> (Self, DetectionTrace) {
+        let mut steps = Vec::new();
+        let found = |steps: Vec<String>, app: FrontendApp| {
+            let trace = DetectionTrace {
+                steps,
+                ..DetectionTrace::default()
+            };
+            (app, trace)
+        };
+
         // If explicit path provided, try that first
         if let Some(path) = config_path {
-            if let Some(app) = Self::detect_in_path(path) {
-                return app;
+            match Self::detect_in_path(root, path) {
+                Some(app) => {
+                    steps.push(format!("{}: configured path", path));
+                    return found(steps, app);
+                }
+                None => steps.push(format!("{}: configured path, no frontend app", path)),
             }
         }
 
@@ -131,31 +194,58 @@ impl FrontendApp {
         ];
 
         for dir in &frontend_dirs {
-            if let Some(app) = Self::detect_in_path(dir) {
-                return app;
+            if is_git_ignored(root, Path::new(dir)) {
+                if root.join(dir).is_dir() {
+                    steps.push(format!("{}: ignored by .gitignore", dir));
+                }
+                continue;
+            }
+            if let Some(app) = Self::detect_in_path(root, dir) {
+                steps.push(format!("{}: found", dir));
+                return found(steps, app);
             }
         }
 
-        FrontendApp {
+        // Anywhere else, within limits
+        let (candidates, mut trace) = scan_package_dirs(root, limits);
+        steps.push(format!(
+            "searched {} entries in {}ms, {} package.json found",
+            trace.visited,
+            trace.elapsed.as_millis(),
+            candidates.len()
+        ));
+        let app = candidates.iter().find_map(|dir| {
+            let dir = dir.to_string_lossy();
+            let app = Self::detect_in_path(root, &dir);
+            steps.push(match &app {
+                Some(_) => format!("{}: found", dir),
+                None => format!("{}: no known framework", dir),
+            });
+            app
+        });
+        trace.steps = steps;
+        let app = app.unwrap_or(FrontendApp {
             detected: false,
             framework: None,
             path: String::new(),
             package_manager: PackageManager::Npm,
-        }
+        });
+        (app, trace)
     }
 
-    fn detect_in_path(path: &str) -> Option<FrontendApp> {
-        let package_json = format!("{}/package.json", path);
+    fn detect_in_path(root: &Path, path: &str) -> Option<FrontendApp> {
+        let dir = root.join(path);
+        let dir = dir.to_string_lossy();
 
-        if !Path::new(&package_json).exists() {
+        if !Path::new(&format!("{}/package.json", dir)).exists() {
             return None;
         }
 
         // Read package.json to detect framework
-        let framework = Self::detect_framework(path);
+        let framework = Self::detect_framework(&dir);
 
         if framework.is_some() {
-            let package_manager = PackageManager::detect(path);
+            let package_manager = PackageManager::detect(&dir);
 
             return Some(FrontendApp {
                 detected: true,
@@ -221,10 +311,10 @@ impl FrontendApp {
         }
 
         // Create React App (check for react-scripts in package.json)
-        if let Ok(content) = std::fs::read_to_string(format!("{}/package.json", path)) {
-            if content.contains("react-scripts") {
-                return Some(FrontendFramework::CreateReactApp);
-            }
+        if let Ok(content) = std::fs::read_to_string(format!("{}/package.json", path))
+            && content.contains("react-scripts")
+        {
+            return Some(FrontendFramework::CreateReactApp);
         }
 
         None
@@ -268,9 +358,8 @@ pub enum FrontendLogEvent {
     CompileSuccess {
         duration: f64,
     },
-    CompileError {
-        message: String,
-    },
+    /// The first line of a compile error; see [`BuildErrors`] for the rest
+    CompileError(BuildError),
     HotModuleReplacement {
         file: String,
     },
@@ -291,29 +380,43 @@ pub struct FrontendLogParser;
 
 impl FrontendLogParser {
     pub fn parse_line(line: &str) -> Option<FrontendLogEvent> {
+        if let Some(error) = BuildError::parse_header(line) {
+            return Some(FrontendLogEvent::CompileError(error));
+        }
+
         // Vite patterns
-        if line.contains("Local:") && line.contains("http://") {
-            if let Some(port) = Self::extract_port(line) {
-                return Some(FrontendLogEvent::ServerStart { port });
-            }
+        if line.contains("Local:")
+            && line.contains("http://")
+            && let Some(port) = Self::extract_port(line)
+        {
+            return Some(FrontendLogEvent::ServerStart { port });
         }
 
         // Next.js patterns
-        if line.contains("ready - started server on") || line.contains("Ready in") {
-            if let Some(port) = Self::extract_port(line) {
-                return Some(FrontendLogEvent::ServerStart { port });
-            }
+        if (line.contains("ready - started server on") || line.contains("Ready in"))
+            && let Some(port) = Self::extract_port(line)
+        {
+            return Some(FrontendLogEvent::ServerStart { port });
         }
 
         // Compile start
-        if line.contains("Compiling") || line.contains("building...") {
+        if line.contains("Compiling")
+            || line.contains("building...")
+            || line.contains("building for production")
+            || line.contains("Starting incremental compilation")
+        {
             return Some(FrontendLogEvent::CompileStart);
         }
 
         // Compile success
         if line.contains("Compiled successfully")
+            || line.contains("compiled successfully")
+            || line.contains("Found 0 errors")
             || line.contains("✓ Compiled")
             || line.contains("built in")
+            // The dev server's first build: `VITE v5.0.10  ready in 312 ms`, `✓ Ready in 2.3s`
+            || line.contains("ready in")
+            || line.contains("Ready in")
         {
             if let Some(duration) = Self::extract_build_duration(line) {
                 return Some(FrontendLogEvent::CompileSuccess { duration });
@@ -322,17 +425,21 @@ impl FrontendLogParser {
         }
 
         // HMR
-        if line.contains("hmr update")
+        if (line.contains("hmr update")
             || line.contains("hot updated")
-            || line.contains("[vite] hmr")
+            || line.contains("[vite] hmr"))
+            && let Some(file) = Self::extract_file_path(line)
         {
-            if let Some(file) = Self::extract_file_path(line) {
-                return Some(FrontendLogEvent::HotModuleReplacement { file });
-            }
+            return Some(FrontendLogEvent::HotModuleReplacement { file });
         }
 
         // Errors
-        if line.contains("ERROR") || line.contains("Failed to compile") || line.contains("✘") {
+        // Next.js marks errors with ⨯
+        if line.contains("ERROR")
+            || line.contains("Failed to compile")
+            || line.contains("✘")
+            || line.contains("⨯")
+        {
             return Some(FrontendLogEvent::Error {
                 message: line.to_string(),
             });
@@ -349,47 +456,27 @@ impl FrontendLogParser {
     }
 
     fn extract_port(line: &str) -> Option<u16> {
-        // Extract port from URLs like "http://localhost:5173"
-        if let Some(pos) = line.find("localhost:") {
-            let after = &line[pos + 10..];
-            if let Some(end) = after.find(|c: char| !c.is_numeric()) {
-                return after[..end].parse().ok();
-            }
-        }
-
-        // Extract from "port 3000" pattern
-        if let Some(pos) = line.find("port ") {
-            let after = &line[pos + 5..];
-            if let Some(end) = after.find(|c: char| !c.is_numeric()) {
-                return after[..end].parse().ok();
-            }
-        }
-
-        None
+        // Extract port from URLs like "http://localhost:5173", or from a
+        // "port 3000" pattern
+        ["localhost:", "port "].iter().find_map(|marker| {
+            let after = &line[line.find(marker)? + marker.len()..];
+            let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            after[..digits].parse().ok()
+        })
     }
 
+    /// Milliseconds from "in 1234ms", "in 312 ms" or "in 1.23s"
     fn extract_build_duration(line: &str) -> Option<f64> {
-        // Pattern: "in 123ms" or "123ms"
-        if let Some(pos) = line.find("ms") {
-            let before = &line[..pos];
-            if let Some(num_start) = before.rfind(|c: char| !c.is_numeric() && c != '.') {
-                let num_str = &before[num_start + 1..];
-                return num_str.parse().ok();
-            }
-        }
-
-        // Pattern: "in 1.23s"
-        if let Some(pos) = line.find("s ") {
-            let before = &line[..pos];
-            if let Some(num_start) = before.rfind(|c: char| !c.is_numeric() && c != '.') {
-                let num_str = &before[num_start + 1..];
-                if let Ok(seconds) = num_str.parse::<f64>() {
-                    return Some(seconds * 1000.0);
-                }
-            }
-        }
-
-        None
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        let captures = PATTERN
+            .get_or_init(|| Regex::new(r"(\d+(?:\.\d+)?) ?(ms|s)\b").unwrap())
+            .captures(line)?;
+        let value: f64 = captures[1].parse().ok()?;
+        Some(if &captures[2] == "s" {
+            value * 1000.0
+        } else {
+            value
+        })
     }
 
     fn extract_file_path(line: &str) -> Option<String> {
@@ -0,0 +1,129 @@
+//! Bounded search of the project for frontend apps the fixed candidates miss
+//!
+//! The walk honours the project's `.gitignore`, never enters `node_modules`,
+//! `vendor`, `tmp` or hidden directories, and stops at [`ScanLimits`]: a
+//! depth, a number of entries and a time budget. A repo with a vendored
+//! package cache can hold thousands of `package.json` files, and walking all
+//! of them would hold up startup for seconds.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ignore::WalkBuilder;
+use ignore::gitignore::Gitignore;
+
+/// Directories never searched, ignored or not
+pub const SKIPPED_DIRS: &[&str] = &["node_modules", "vendor", "tmp"];
+
+/// How far the search may go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanLimits {
+    /// Directory levels below the project root
+    pub max_depth: usize,
+    /// Files and directories visited
+    pub max_entries: usize,
+    pub time_budget: Duration,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_entries: 10_000,
+            time_budget: Duration::from_secs(2),
+        }
+    }
+}
+
+/// The limit a search stopped at, before it covered the project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanCap {
+    Entries(usize),
+    Time(Duration),
+}
+
+impl ScanCap {
+    pub fn warning(&self) -> String {
+        let stopped = match self {
+            ScanCap::Entries(entries) => format!("after {} files and directories", entries),
+            ScanCap::Time(budget) => format!("after {:.1}s", budget.as_secs_f64()),
+        };
+        format!(
+            "Frontend detection stopped {}; set [frontend] path in .caboose.toml to point at the app",
+            stopped
+        )
+    }
+}
+
+/// How frontend detection went, for `--verbose`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DetectionTrace {
+    /// What was tried, in order
+    pub steps: Vec<String>,
+    /// Entries the search visited, if it ran
+    pub visited: usize,
+    pub capped: Option<ScanCap>,
+    pub elapsed: Duration,
+}
+
+/// Directories under `root` holding a `package.json`, shallowest first
+///
+/// `root` itself isn't one of them: a root `package.json` belongs to the
+/// Rails app's own asset bundling.
+pub fn scan_package_dirs(root: &Path, limits: &ScanLimits) -> (Vec<PathBuf>, DetectionTrace) {
+    let started = Instant::now();
+    let mut trace = DetectionTrace::default();
+    let mut found: Vec<(usize, PathBuf)> = Vec::new();
+
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(limits.max_depth + 1))
+        .hidden(true)
+        .parents(false)
+        .git_global(false)
+        .require_git(false)
+        .follow_links(false)
+        .filter_entry(|entry| {
+            !(entry.file_type().is_some_and(|kind| kind.is_dir())
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name)))
+        })
+        .build();
+
+    for entry in walker {
+        if trace.visited >= limits.max_entries {
+            trace.capped = Some(ScanCap::Entries(limits.max_entries));
+            break;
+        }
+        if started.elapsed() >= limits.time_budget {
+            trace.capped = Some(ScanCap::Time(limits.time_budget));
+            break;
+        }
+        trace.visited += 1;
+
+        let Ok(entry) = entry else { continue };
+        if entry.depth() >= 2
+            && entry.file_name() == "package.json"
+            && let Some(dir) = entry.path().parent()
+            && let Ok(relative) = dir.strip_prefix(root)
+        {
+            found.push((entry.depth(), relative.to_path_buf()));
+        }
+    }
+
+    found.sort();
+    trace.elapsed = started.elapsed();
+    (found.into_iter().map(|(_, dir)| dir).collect(), trace)
+}
+
+/// Whether the project's `.gitignore` ignores `relative`, a directory under `root`
+pub fn is_git_ignored(root: &Path, relative: &Path) -> bool {
+    if relative.is_absolute() || relative.starts_with("..") {
+        return false;
+    }
+    let (gitignore, _) = Gitignore::new(root.join(".gitignore"));
+    gitignore
+        .matched_path_or_any_parents(relative, true)
+        .is_ignore()
+}
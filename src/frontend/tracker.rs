@@ -0,0 +1,216 @@
+//! How the frontend dev server's builds are going, from its output
+//!
+//! Fed the lines of the configured frontend process along with what
+//! [`FrontendLogParser`](super::FrontendLogParser) made of them, the tracker
+//! keeps the last build's time and a rolling average, the errors of recent
+//! failed builds, how often modules are hot-replaced, and where the dev server
+//! listens. The header sums it up as `vite ✓ 320ms`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::{BuildError, FrontendLogEvent};
+use crate::diagnostics::keep_last;
+
+/// Process the frontend dev server runs as unless `[frontend] process_name` says
+pub const DEFAULT_FRONTEND_PROCESS: &str = "frontend";
+
+/// Build times averaged over
+pub const MAX_BUILD_TIMES: usize = 20;
+
+/// Build errors kept, newest last
+pub const MAX_BUILD_ERRORS: usize = 10;
+
+/// Window hot updates are counted over
+pub const HMR_WINDOW: Duration = Duration::from_secs(60);
+
+/// Where the last build left things
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildState {
+    /// Nothing seen yet
+    Unknown,
+    Building,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct FrontendTracker {
+    /// Process whose output is followed
+    process: String,
+    /// `vite`, `next` or `webpack`, once the output gives it away
+    tool: Option<&'static str>,
+    state: BuildState,
+    /// Recent build times, oldest first
+    build_times: VecDeque<Duration>,
+    /// Messages of recent build errors, with their location when printed
+    errors: Vec<String>,
+    /// Recent hot updates, oldest first
+    hot_updates: VecDeque<Instant>,
+    last_hot_update: Option<String>,
+    port: Option<u16>,
+    url: Option<String>,
+}
+
+impl FrontendTracker {
+    pub fn new(process: impl Into<String>) -> Self {
+        Self {
+            process: process.into(),
+            tool: None,
+            state: BuildState::Unknown,
+            build_times: VecDeque::new(),
+            errors: Vec::new(),
+            hot_updates: VecDeque::new(),
+            last_hot_update: None,
+            port: None,
+            url: None,
+        }
+    }
+
+    pub fn process(&self) -> &str {
+        &self.process
+    }
+
+    /// Follow one line of the frontend process's output, with what the
+    /// frontend parser made of it
+    pub fn observe(&mut self, line: &str, event: Option<&FrontendLogEvent>, now: Instant) {
+        if self.tool.is_none() {
+            self.tool = detect_tool(line);
+        }
+        match event {
+            Some(FrontendLogEvent::ServerStart { port }) => {
+                self.port = Some(*port);
+                self.url = find_url(line).or(self.url.take());
+            }
+            Some(FrontendLogEvent::CompileStart) => self.state = BuildState::Building,
+            Some(FrontendLogEvent::CompileSuccess { duration }) => {
+                self.state = BuildState::Succeeded;
+                if *duration > 0.0 {
+                    self.build_times
+                        .push_back(Duration::from_secs_f64(duration / 1000.0));
+                    if self.build_times.len() > MAX_BUILD_TIMES {
+                        self.build_times.pop_front();
+                    }
+                }
+            }
+            Some(FrontendLogEvent::CompileError(error)) => self.fail(describe(error)),
+            // A bare error line fails the build only while one is compiling;
+            // otherwise it's the app's own error
+            Some(FrontendLogEvent::Error { message })
+                if self.state == BuildState::Building || message.contains("Failed to compile") =>
+            {
+                self.fail(message.trim().trim_start_matches("⨯ ").to_string());
+            }
+            Some(FrontendLogEvent::HotModuleReplacement { file }) => {
+                self.state = BuildState::Succeeded;
+                self.hot_updates.push_back(now);
+                self.last_hot_update = Some(file.clone());
+            }
+            _ => {}
+        }
+        while self
+            .hot_updates
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) > HMR_WINDOW)
+        {
+            self.hot_updates.pop_front();
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        self.state = BuildState::Failed;
+        self.errors.push(error);
+        keep_last(&mut self.errors, MAX_BUILD_ERRORS);
+    }
+
+    /// Whether anything about a build has been seen
+    pub fn is_active(&self) -> bool {
+        self.state != BuildState::Unknown || self.port.is_some()
+    }
+
+    pub fn state(&self) -> BuildState {
+        self.state
+    }
+
+    /// `vite`, `next`, `webpack`, or the process name when the output hasn't
+    /// said
+    pub fn tool(&self) -> &str {
+        self.tool.unwrap_or(&self.process)
+    }
+
+    pub fn last_build_time(&self) -> Option<Duration> {
+        self.build_times.back().copied()
+    }
+
+    /// Mean of the last [`MAX_BUILD_TIMES`] build times
+    pub fn average_build_time(&self) -> Option<Duration> {
+        let count = self.build_times.len() as u32;
+        (count > 0).then(|| self.build_times.iter().sum::<Duration>() / count)
+    }
+
+    /// Errors of recent failed builds, oldest first
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Hot updates in the [`HMR_WINDOW`] before `now`
+    pub fn hot_updates_per_minute(&self, now: Instant) -> usize {
+        self.hot_updates
+            .iter()
+            .filter(|at| now.saturating_duration_since(**at) <= HMR_WINDOW)
+            .count()
+    }
+
+    pub fn last_hot_update(&self) -> Option<&str> {
+        self.last_hot_update.as_deref()
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// The dev server's local URL, as printed
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// `vite ✓ 320ms`, `vite ✘ build failed` or `vite … building`
+    pub fn summary(&self) -> String {
+        let tool = self.tool();
+        match self.state {
+            BuildState::Failed => format!("{} ✘ build failed", tool),
+            BuildState::Building => format!("{} … building", tool),
+            _ => match self.last_build_time() {
+                Some(time) => format!("{} ✓ {}ms", tool, time.as_millis()),
+                None => format!("{} ✓", tool),
+            },
+        }
+    }
+}
+
+/// `src/App.tsx:12:8 Expected ";" but found "x"`
+fn describe(error: &BuildError) -> String {
+    match error.location() {
+        Some(location) => format!("{} {}", location, error.message),
+        None => error.message.clone(),
+    }
+}
+
+fn detect_tool(line: &str) -> Option<&'static str> {
+    if line.contains("VITE v") || line.contains("[vite]") {
+        Some("vite")
+    } else if line.contains("Next.js") || line.contains("next dev") {
+        Some("next")
+    } else if line.contains("webpack") {
+        Some("webpack")
+    } else {
+        None
+    }
+}
+
+/// `http://localhost:5173/` out of `➜  Local:   http://localhost:5173/`
+fn find_url(line: &str) -> Option<String> {
+    let start = line.find("http://").or_else(|| line.find("https://"))?;
+    let url = line[start..].split_whitespace().next()?;
+    Some(url.trim_end_matches(',').to_string())
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::process::Command;
 
 #[derive(Debug, Clone, Default)]
@@ -10,40 +11,49 @@ pub struct GitInfo {
 
 impl GitInfo {
     pub fn get() -> Self {
+        Self::get_in(".")
+    }
+
+    /// Read git state for the repository containing `root`
+    pub fn get_in<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref();
         let mut info = GitInfo::default();
 
         // Get current branch
         if let Ok(output) = Command::new("git")
             .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(root)
             .output()
+            && output.status.success()
         {
-            if output.status.success() {
-                info.branch = String::from_utf8(output.stdout)
-                    .ok()
-                    .map(|s| s.trim().to_string());
-            }
+            info.branch = String::from_utf8(output.stdout)
+                .ok()
+                .map(|s| s.trim().to_string());
         }
 
         // Check for uncommitted changes
-        if let Ok(output) = Command::new("git").args(["status", "--porcelain"]).output() {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                info.has_changes = !stdout.trim().is_empty();
-            }
+        if let Ok(output) = Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(root)
+            .output()
+            && output.status.success()
+        {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            info.has_changes = !stdout.trim().is_empty();
         }
 
         // Get ahead/behind counts
         if let Ok(output) = Command::new("git")
             .args(["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])
+            .current_dir(root)
             .output()
+            && output.status.success()
         {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = stdout.trim().split_whitespace().collect();
-                if parts.len() == 2 {
-                    info.ahead = parts[0].parse().unwrap_or(0);
-                    info.behind = parts[1].parse().unwrap_or(0);
-                }
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<&str> = stdout.split_whitespace().collect();
+            if parts.len() == 2 {
+                info.ahead = parts[0].parse().unwrap_or(0);
+                info.behind = parts[1].parse().unwrap_or(0);
             }
         }
 
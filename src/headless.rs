@@ -0,0 +1,350 @@
+//! Running without the TUI (`--no-tui`), for scripts that wrap Caboose
+//!
+//! With `--startup-json`, stdout starts with one JSON object describing what
+//! was detected and started; with `--json-logs`, every line after it is a JSON
+//! event. The human startup messages go to stderr whenever stdout is for a
+//! machine, so the two never mix.
+//!
+//! Exit codes are stable:
+//! - 0: processes ran (and have all exited, or Caboose was stopped)
+//! - 2: nothing to run was detected
+//! - 3: the Rails health check failed (e.g. `bundle install` needed)
+//! - 4: a `[setup]` command failed or timed out
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::frontend::{FrontendApp, PackageManager};
+use crate::process::{LogLine, LogStream, ProcessExit, port_from_command};
+use crate::rails::{RailsApp, RailsHealthIssue};
+
+/// Version of the startup report's layout; bumped on incompatible changes
+pub const STARTUP_SCHEMA_VERSION: u32 = 1;
+
+pub const EXIT_DETECTION_FAILED: i32 = 2;
+pub const EXIT_HEALTH_FAILED: i32 = 3;
+pub const EXIT_SETUP_FAILED: i32 = 4;
+
+/// How startup went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupStatus {
+    Running,
+    DetectionFailed,
+    HealthFailed,
+    SetupFailed,
+}
+
+impl StartupStatus {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StartupStatus::Running => 0,
+            StartupStatus::DetectionFailed => EXIT_DETECTION_FAILED,
+            StartupStatus::HealthFailed => EXIT_HEALTH_FAILED,
+            StartupStatus::SetupFailed => EXIT_SETUP_FAILED,
+        }
+    }
+}
+
+/// Startup stopped before any process ran; the process exits with its status' code
+#[derive(Debug, Clone)]
+pub struct StartupFailure {
+    pub status: StartupStatus,
+    pub message: String,
+}
+
+impl StartupFailure {
+    pub fn detection(message: impl Into<String>) -> Self {
+        Self {
+            status: StartupStatus::DetectionFailed,
+            message: message.into(),
+        }
+    }
+
+    pub fn health(message: impl Into<String>) -> Self {
+        Self {
+            status: StartupStatus::HealthFailed,
+            message: message.into(),
+        }
+    }
+
+    pub fn setup(message: impl Into<String>) -> Self {
+        Self {
+            status: StartupStatus::SetupFailed,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StartupFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for StartupFailure {}
+
+/// The `--startup-json` object
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub schema: u32,
+    pub status: StartupStatus,
+    pub project: String,
+    pub rails: Option<RailsReport>,
+    pub frontend: Option<FrontendReport>,
+    /// Processes started, after `.caboose.toml` overrides
+    pub processes: Vec<ProcessReport>,
+    /// Rails health check findings; empty when there's no Rails app
+    pub health: Vec<HealthReport>,
+    /// Why startup failed, when it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RailsReport {
+    pub database: Option<String>,
+    pub background_job: Option<String>,
+    pub asset_pipeline: Option<String>,
+    pub instrumentation: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrontendReport {
+    pub framework: Option<String>,
+    pub path: String,
+    pub package_manager: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessReport {
+    pub name: String,
+    pub command: String,
+    /// Names of the environment variables it gets; values are left out
+    pub env: Vec<String>,
+    pub port: Option<u16>,
+    /// Why it couldn't be started; the other processes still run
+    pub error: Option<String>,
+    /// Already running, so adopted instead of started; Caboose won't stop it
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub external: bool,
+    /// PID of an adopted process, when it could be looked up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// `bundle_outdated`, `pending_migrations`, `database_not_created` or
+    /// `database_connection`
+    pub kind: &'static str,
+    pub message: String,
+    /// Whether it stops Caboose from starting
+    pub fatal: bool,
+}
+
+impl StartupReport {
+    pub fn new(project: impl Into<String>) -> Self {
+        Self {
+            schema: STARTUP_SCHEMA_VERSION,
+            status: StartupStatus::Running,
+            project: project.into(),
+            rails: None,
+            frontend: None,
+            processes: Vec::new(),
+            health: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn with_rails(mut self, rails: &RailsApp) -> Self {
+        self.rails = rails.detected.then(|| RailsReport {
+            database: rails.database.clone(),
+            background_job: rails.background_job.clone(),
+            asset_pipeline: rails.asset_pipeline.clone(),
+            instrumentation: rails.instrumentation.names(),
+        });
+        self
+    }
+
+    pub fn with_frontend(mut self, frontend: &FrontendApp) -> Self {
+        self.frontend = frontend.detected.then(|| FrontendReport {
+            framework: frontend
+                .framework
+                .as_ref()
+                .map(|framework| framework.name().to_string()),
+            path: frontend.path.clone(),
+            package_manager: package_manager_name(&frontend.package_manager),
+        });
+        self
+    }
+
+    pub fn with_health(mut self, issues: &[RailsHealthIssue]) -> Self {
+        self.health = issues.iter().map(HealthReport::from).collect();
+        self
+    }
+
+    /// Add a started process; the port comes from its command or `PORT`
+    pub fn add_process(&mut self, name: &str, command: &str, env: &HashMap<String, String>) {
+        let mut keys: Vec<String> = env.keys().cloned().collect();
+        keys.sort();
+        self.processes.push(ProcessReport {
+            name: name.to_string(),
+            command: command.to_string(),
+            env: keys,
+            port: port_from_command(command, env),
+            error: None,
+            external: false,
+            pid: None,
+        });
+    }
+
+    /// Record that the process `name` was adopted rather than started
+    pub fn adopted(&mut self, name: &str, port: Option<u16>, pid: Option<u32>) {
+        if let Some(process) = self.processes.iter_mut().find(|p| p.name == name) {
+            process.external = true;
+            process.port = port;
+            process.pid = pid;
+        }
+    }
+
+    /// Record that the process `name` failed to start
+    pub fn spawn_failed(&mut self, name: &str, error: &str) {
+        if let Some(process) = self.processes.iter_mut().find(|p| p.name == name) {
+            process.error = Some(error.to_string());
+        }
+    }
+
+    /// The report for a startup that stopped with `failure`
+    pub fn failed(mut self, failure: &StartupFailure) -> Self {
+        self.status = failure.status;
+        self.error = Some(failure.message.clone());
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+impl From<&RailsHealthIssue> for HealthReport {
+    fn from(issue: &RailsHealthIssue) -> Self {
+        let (kind, message, fatal) = match issue {
+            RailsHealthIssue::BundleOutdated(message) => (
+                "bundle_outdated",
+                message
+                    .lines()
+                    .next()
+                    .unwrap_or("Dependencies missing")
+                    .to_string(),
+                true,
+            ),
+            RailsHealthIssue::PendingMigrations(migrations) => (
+                "pending_migrations",
+                format!("{} pending migration(s)", migrations.len()),
+                false,
+            ),
+            RailsHealthIssue::DatabaseNotCreated => (
+                "database_not_created",
+                "Database does not exist".to_string(),
+                false,
+            ),
+            RailsHealthIssue::DatabaseConnectionError(err) => {
+                ("database_connection", err.clone(), false)
+            }
+        };
+        Self {
+            kind,
+            message,
+            fatal,
+        }
+    }
+}
+
+fn package_manager_name(package_manager: &PackageManager) -> &'static str {
+    match package_manager {
+        PackageManager::Npm => "npm",
+        PackageManager::Yarn => "yarn",
+        PackageManager::Pnpm => "pnpm",
+        PackageManager::Bun => "bun",
+    }
+}
+
+fn stream_name(stream: LogStream) -> &'static str {
+    match stream {
+        LogStream::Combined => "combined",
+        LogStream::Stdout => "stdout",
+        LogStream::Stderr => "stderr",
+        LogStream::Both => "both",
+    }
+}
+
+/// What `--no-tui` writes to stdout after startup
+#[derive(Debug, Clone)]
+pub enum LogOutput {
+    /// `web    | Started GET "/"`, names padded to the longest
+    Text { name_width: usize },
+    /// One JSON event per line
+    Json,
+}
+
+impl LogOutput {
+    pub fn text<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        let name_width = names
+            .into_iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        LogOutput::Text { name_width }
+    }
+
+    /// `{"event":"log","process":"web","stream":"stdout","time":"...","content":"..."}`
+    pub fn log_line(&self, line: &LogLine) -> String {
+        match self {
+            LogOutput::Text { name_width } => format!(
+                "{:<width$} | {}",
+                line.process_name,
+                line.content,
+                width = *name_width
+            ),
+            LogOutput::Json => serde_json::json!({
+                "event": "log",
+                "process": line.process_name,
+                "stream": stream_name(line.stream),
+                "time": line.time.to_rfc3339(),
+                "content": line.content,
+            })
+            .to_string(),
+        }
+    }
+
+    /// `{"event":"exit","process":"web","success":false,"code":1}`
+    pub fn process_exit(&self, name: &str, exit: Option<&ProcessExit>) -> String {
+        let success = exit.map(|exit| exit.success);
+        let code = exit.and_then(|exit| exit.code);
+        match self {
+            LogOutput::Text { name_width } => {
+                let how = match code {
+                    Some(code) => format!("exited with code {}", code),
+                    None if success == Some(false) => "was killed".to_string(),
+                    None => "exited".to_string(),
+                };
+                format!(
+                    "{:<width$} | {} {}",
+                    "caboose",
+                    name,
+                    how,
+                    width = *name_width
+                )
+            }
+            LogOutput::Json => serde_json::json!({
+                "event": "exit",
+                "process": name,
+                "success": success,
+                "code": code,
+            })
+            .to_string(),
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Long-term history: request, slow query and health score trends across days
+//!
+//! Opt-in with `[history] enabled = true`. Completed requests, slow query
+//! fingerprints and database health scores are appended to
+//! `.caboose/history.db` (SQLite), so `/trends GET /api/orders` can show
+//! whether an endpoint got slower this week.
+//!
+//! Rows are written by a background thread fed over a channel, a batch per
+//! transaction, so recording never waits on the disk. A file that can't be
+//! opened or written (corrupt, or locked by another caboose) turns history
+//! off with a warning instead.
+
+use crate::context::{CompletedRequest, normalize_path};
+use crate::time_format::DisplayZone;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta};
+use rusqlite::{Connection, params};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// History file inside the project's `.caboose/` directory
+pub const HISTORY_FILE_NAME: &str = "history.db";
+
+/// Days of history kept when `[history] retention_days` isn't set
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Days `/trends` looks back over
+pub const TREND_DAYS: i64 = 30;
+
+/// How long a write waits for another connection to release the file
+const BUSY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Most records written in one transaction
+const MAX_BATCH: usize = 500;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS requests (
+        at INTEGER NOT NULL,
+        method TEXT NOT NULL,
+        path TEXT NOT NULL,
+        status INTEGER,
+        duration REAL NOT NULL,
+        queries INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS requests_by_path ON requests (path, at);
+    CREATE TABLE IF NOT EXISTS slow_queries (
+        at INTEGER NOT NULL,
+        fingerprint TEXT NOT NULL,
+        duration REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS slow_queries_by_time ON slow_queries (at);
+    CREATE TABLE IF NOT EXISTS health_scores (
+        at INTEGER NOT NULL,
+        score INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS health_scores_by_time ON health_scores (at);
+";
+
+/// Tables pruned by age on startup
+const TABLES: [&str; 3] = ["requests", "slow_queries", "health_scores"];
+
+/// A completed request as it's kept in history
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestSummary {
+    pub method: String,
+    /// Path with ids replaced by `:id`, so `/orders/5` and `/orders/6` trend together
+    pub path: String,
+    pub status: Option<u16>,
+    pub duration: f64,
+    pub queries: usize,
+    pub at: DateTime<Local>,
+}
+
+impl RequestSummary {
+    /// Summary of a request that logged its path and duration
+    pub fn from_completed(req: &CompletedRequest, at: DateTime<Local>) -> Option<Self> {
+        Some(Self {
+            method: req.context.method.clone().unwrap_or_default(),
+            path: normalize_path(req.context.path.as_deref()?),
+            status: req.status,
+            duration: req.total_duration?,
+            queries: req.context.queries.len(),
+            at,
+        })
+    }
+}
+
+/// One row appended to history
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryRecord {
+    Request(RequestSummary),
+    SlowQuery {
+        fingerprint: String,
+        duration: f64,
+        at: DateTime<Local>,
+    },
+    HealthScore {
+        score: u32,
+        at: DateTime<Local>,
+    },
+}
+
+/// Which requests `/trends` looks at: `GET /api/orders`, or `/api/orders`
+/// for every method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub method: Option<String>,
+    pub path: String,
+}
+
+impl Endpoint {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let (method, path) = match (words.next(), words.next(), words.next()) {
+            (Some(path), None, None) => (None, path),
+            (Some(method), Some(path), None) => (Some(method.to_ascii_uppercase()), path),
+            _ => {
+                return Err(format!(
+                    "Expected an endpoint like 'GET /api/orders', got '{}'",
+                    input.trim()
+                ));
+            }
+        };
+        if !path.starts_with('/') {
+            return Err(format!("'{}' isn't a path (it should start with /)", path));
+        }
+        Ok(Self {
+            method,
+            path: normalize_path(path),
+        })
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.method {
+            Some(method) => write!(f, "{} {}", method, self.path),
+            None => write!(f, "{}", self.path),
+        }
+    }
+}
+
+/// Request durations for one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyTrend {
+    pub date: NaiveDate,
+    pub requests: usize,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+/// An endpoint's days with requests, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointTrend {
+    pub endpoint: Endpoint,
+    pub days: Vec<DailyTrend>,
+}
+
+/// The history database
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open (or create) the history file and drop rows older than
+    /// `retention_days`, returning the store and how many rows went
+    ///
+    /// Fails on a file that isn't a database or that another process keeps locked.
+    pub fn open(path: &Path, retention_days: u32) -> Result<(Self, usize), String> {
+        let fail = |e: rusqlite::Error| format!("{}: {}", path.display(), e);
+        let conn = Connection::open(path).map_err(fail)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(fail)?;
+        conn.execute_batch(SCHEMA).map_err(fail)?;
+
+        let store = Self { conn };
+        let cutoff = Local::now() - TimeDelta::days(i64::from(retention_days));
+        let pruned = store.prune(cutoff).map_err(fail)?;
+        Ok((store, pruned))
+    }
+
+    /// Delete rows from before `cutoff`
+    fn prune(&self, cutoff: DateTime<Local>) -> rusqlite::Result<usize> {
+        let mut deleted = 0;
+        for table in TABLES {
+            deleted += self.conn.execute(
+                &format!("DELETE FROM {} WHERE at < ?1", table),
+                params![cutoff.timestamp_millis()],
+            )?;
+        }
+        Ok(deleted)
+    }
+
+    /// Append `records` in one transaction
+    pub fn append(&mut self, records: &[HistoryRecord]) -> Result<(), String> {
+        let insert = |conn: &Connection| -> rusqlite::Result<()> {
+            for record in records {
+                match record {
+                    HistoryRecord::Request(req) => {
+                        conn.prepare_cached(
+                            "INSERT INTO requests (at, method, path, status, duration, queries)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        )?
+                        .execute(params![
+                            req.at.timestamp_millis(),
+                            req.method,
+                            req.path,
+                            req.status,
+                            req.duration,
+                            req.queries as i64,
+                        ])?;
+                    }
+                    HistoryRecord::SlowQuery {
+                        fingerprint,
+                        duration,
+                        at,
+                    } => {
+                        conn.prepare_cached(
+                            "INSERT INTO slow_queries (at, fingerprint, duration) VALUES (?1, ?2, ?3)",
+                        )?
+                        .execute(params![at.timestamp_millis(), fingerprint, duration])?;
+                    }
+                    HistoryRecord::HealthScore { score, at } => {
+                        conn.prepare_cached(
+                            "INSERT INTO health_scores (at, score) VALUES (?1, ?2)",
+                        )?
+                        .execute(params![at.timestamp_millis(), score])?;
+                    }
+                }
+            }
+            Ok(())
+        };
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to write history: {}", e))?;
+        insert(&tx)
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Daily p50/p95 for `endpoint` since `since`, days in `zone`
+    pub fn endpoint_trend(
+        &self,
+        endpoint: &Endpoint,
+        since: DateTime<Local>,
+        zone: DisplayZone,
+    ) -> Result<EndpointTrend, String> {
+        let fail = |e: rusqlite::Error| format!("Failed to read history: {}", e);
+        let mut statement = self
+            .conn
+            .prepare_cached(
+                "SELECT at, duration FROM requests
+                 WHERE path = ?1 AND (?2 IS NULL OR method = ?2) AND at >= ?3",
+            )
+            .map_err(fail)?;
+        let rows = statement
+            .query_map(
+                params![endpoint.path, endpoint.method, since.timestamp_millis()],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?)),
+            )
+            .map_err(fail)?;
+
+        let mut by_day: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
+        for row in rows {
+            let (at, duration) = row.map_err(fail)?;
+            let Some(at) = DateTime::from_timestamp_millis(at) else {
+                continue;
+            };
+            by_day
+                .entry(zone.date(at.with_timezone(&Local)))
+                .or_default()
+                .push(duration);
+        }
+
+        let days = by_day
+            .into_iter()
+            .map(|(date, mut durations)| {
+                durations.sort_by(|a, b| a.total_cmp(b));
+                DailyTrend {
+                    date,
+                    requests: durations.len(),
+                    p50: percentile(&durations, 0.50),
+                    p95: percentile(&durations, 0.95),
+                }
+            })
+            .collect();
+        Ok(EndpointTrend {
+            endpoint: endpoint.clone(),
+            days,
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`
+fn percentile(values: &[f64], p: f64) -> f64 {
+    let rank = ((values.len() as f64 * p).ceil() as usize).saturating_sub(1);
+    values[rank.min(values.len() - 1)]
+}
+
+pub type TrendResult = Result<EndpointTrend, String>;
+
+enum Message {
+    Record(HistoryRecord),
+    Trend {
+        endpoint: Endpoint,
+        zone: DisplayZone,
+        reply: oneshot::Sender<TrendResult>,
+    },
+}
+
+/// Sends records to the history thread
+///
+/// Once a write fails the thread stops, and every call after reports why.
+pub struct HistoryWriter {
+    tx: Sender<Message>,
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl HistoryWriter {
+    /// Hand `store` to a background thread that writes what it's sent
+    pub fn spawn(store: HistoryStore) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let failure = Arc::new(Mutex::new(None));
+        let thread_failure = failure.clone();
+        std::thread::Builder::new()
+            .name("caboose-history".to_string())
+            .spawn(move || {
+                if let Err(e) = write_batches(store, rx) {
+                    *thread_failure.lock().unwrap() = Some(e);
+                }
+            })
+            .expect("failed to spawn the history thread");
+        Self { tx, failure }
+    }
+
+    /// Queue a row; never waits on the disk
+    pub fn record(&self, record: HistoryRecord) -> Result<(), String> {
+        self.send(Message::Record(record))
+    }
+
+    /// Ask for `endpoint`'s daily trend over the last [`TREND_DAYS`], read
+    /// after every row queued before it is written
+    pub fn trend(
+        &self,
+        endpoint: Endpoint,
+        zone: DisplayZone,
+    ) -> Result<oneshot::Receiver<TrendResult>, String> {
+        let (reply, rx) = oneshot::channel();
+        self.send(Message::Trend {
+            endpoint,
+            zone,
+            reply,
+        })?;
+        Ok(rx)
+    }
+
+    fn send(&self, message: Message) -> Result<(), String> {
+        self.tx.send(message).map_err(|_| {
+            self.failure
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_else(|| "history writer stopped".to_string())
+        })
+    }
+}
+
+/// Write records as they arrive, as many per transaction as are waiting,
+/// answering trend queries in between; ends on the first failed write
+fn write_batches(mut store: HistoryStore, rx: Receiver<Message>) -> Result<(), String> {
+    let mut batch = Vec::new();
+    while let Ok(first) = rx.recv() {
+        let mut next = Some(first);
+        while let Some(message) = next.take() {
+            match message {
+                Message::Record(record) => batch.push(record),
+                Message::Trend {
+                    endpoint,
+                    zone,
+                    reply,
+                } => {
+                    store.append(&batch)?;
+                    batch.clear();
+                    let since = Local::now() - TimeDelta::days(TREND_DAYS);
+                    let _ = reply.send(store.endpoint_trend(&endpoint, since, zone));
+                }
+            }
+            if batch.len() < MAX_BATCH {
+                next = rx.try_recv().ok();
+            }
+        }
+        store.append(&batch)?;
+        batch.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("caboose_history_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(HISTORY_FILE_NAME)
+    }
+
+    fn request(method: &str, path: &str, duration: f64, at: DateTime<Local>) -> HistoryRecord {
+        HistoryRecord::Request(RequestSummary {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: Some(200),
+            duration,
+            queries: 3,
+            at,
+        })
+    }
+
+    fn day(days_ago: i64, hour: u32) -> DateTime<Local> {
+        let date = Local::now().date_naive() - TimeDelta::days(days_ago);
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_trend_has_daily_percentiles_for_the_endpoint() {
+        let path = temp_db("trend");
+        let (mut store, _) = HistoryStore::open(&path, 30).unwrap();
+        let mut records: Vec<HistoryRecord> = (1..=20)
+            .map(|ms| request("GET", "/orders", f64::from(ms) * 10.0, day(1, 10)))
+            .collect();
+        records.push(request("GET", "/orders", 40.0, day(0, 9)));
+        records.push(request("POST", "/orders", 900.0, day(0, 9)));
+        records.push(request("GET", "/users", 900.0, day(0, 9)));
+        store.append(&records).unwrap();
+
+        let get = Endpoint::parse("get /orders").unwrap();
+        let trend = store
+            .endpoint_trend(&get, day(7, 0), DisplayZone::Local)
+            .unwrap();
+        assert_eq!(trend.days.len(), 2);
+        assert_eq!(trend.days[0].date, day(1, 10).date_naive());
+        assert_eq!(trend.days[0].requests, 20);
+        assert_eq!(trend.days[0].p50, 100.0);
+        assert_eq!(trend.days[0].p95, 190.0);
+        assert_eq!((trend.days[1].requests, trend.days[1].p95), (1, 40.0));
+
+        // Without a method every method counts
+        let any = Endpoint::parse("/orders").unwrap();
+        let trend = store
+            .endpoint_trend(&any, day(7, 0), DisplayZone::Local)
+            .unwrap();
+        assert_eq!((trend.days[1].requests, trend.days[1].p95), (2, 900.0));
+    }
+
+    #[test]
+    fn test_rows_past_retention_are_pruned_on_open() {
+        let path = temp_db("prune");
+        let (mut store, _) = HistoryStore::open(&path, 30).unwrap();
+        store
+            .append(&[
+                request("GET", "/orders", 10.0, day(40, 12)),
+                request("GET", "/orders", 20.0, day(2, 12)),
+                HistoryRecord::HealthScore {
+                    score: 90,
+                    at: day(40, 12),
+                },
+                HistoryRecord::SlowQuery {
+                    fingerprint: "SELECT * FROM orders WHERE id = ?".to_string(),
+                    duration: 150.0,
+                    at: day(2, 12),
+                },
+            ])
+            .unwrap();
+        drop(store);
+
+        let (_, pruned) = HistoryStore::open(&path, 30).unwrap();
+        assert_eq!(pruned, 2);
+        let (_, pruned) = HistoryStore::open(&path, 1).unwrap();
+        assert_eq!(pruned, 2);
+    }
+
+    #[test]
+    fn test_corrupt_or_locked_file_is_an_error() {
+        let path = temp_db("corrupt");
+        std::fs::write(&path, "not a database, just some text that is long enough").unwrap();
+        assert!(HistoryStore::open(&path, 30).is_err());
+
+        let path = temp_db("locked");
+        let (_store, _) = HistoryStore::open(&path, 30).unwrap();
+        let other = Connection::open(&path).unwrap();
+        other.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        assert!(HistoryStore::open(&path, 30).is_err());
+    }
+
+    #[test]
+    fn test_writer_answers_trends_after_earlier_records() {
+        let path = temp_db("writer");
+        let (store, _) = HistoryStore::open(&path, 30).unwrap();
+        let writer = HistoryWriter::spawn(store);
+        for ms in [10.0, 20.0, 30.0] {
+            writer
+                .record(request("GET", "/orders/:id", ms, Local::now()))
+                .unwrap();
+        }
+
+        let endpoint = Endpoint::parse("GET /orders/42").unwrap();
+        assert_eq!(endpoint.to_string(), "GET /orders/:id");
+        let trend = writer
+            .trend(endpoint, DisplayZone::Local)
+            .unwrap()
+            .blocking_recv()
+            .unwrap()
+            .unwrap();
+        assert_eq!(trend.days.len(), 1);
+        assert_eq!((trend.days[0].requests, trend.days[0].p50), (3, 20.0));
+    }
+
+    #[test]
+    fn test_endpoint_parsing() {
+        assert_eq!(
+            Endpoint::parse("post /api/users/5/orders").unwrap(),
+            Endpoint {
+                method: Some("POST".to_string()),
+                path: "/api/users/:id/orders".to_string(),
+            }
+        );
+        assert!(Endpoint::parse("orders").is_err());
+        assert!(Endpoint::parse("GET /a /b").is_err());
+        assert!(Endpoint::parse("").is_err());
+    }
+
+    #[test]
+    fn test_percentile_is_nearest_rank() {
+        assert_eq!(percentile(&[5.0], 0.95), 5.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0], 0.50), 2.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0], 0.95), 4.0);
+    }
+}
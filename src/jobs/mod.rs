@@ -0,0 +1,486 @@
+//! Background jobs from worker output: ActiveJob and Sidekiq lines
+//!
+//! ActiveJob logs `Performing` / `Performed` / `Error performing` around each
+//! job and `Retrying` when one is rescheduled; Sidekiq logs `start`, `done`
+//! and `fail` with the job's class and jid, then the error as a `WARN` line.
+//! Both are folded into stats per job class and a list of recent failures.
+//!
+//! An ActiveJob job run by Sidekiq is logged by both. Once a class has
+//! ActiveJob lines, its Sidekiq `done` and `fail` only end the run rather
+//! than counting it again.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use regex::Regex;
+
+use crate::diagnostics::keep_last;
+use crate::parser::RailsLogParser;
+
+/// Failures kept for the recent failures list
+pub const MAX_RECENT_FAILURES: usize = 50;
+
+/// Jobs tracked as running; a worker killed mid-job never logs their end
+const MAX_RUNNING: usize = 500;
+
+/// Failed Sidekiq jids remembered, to count a later `start` as a retry
+const MAX_FAILED_JIDS: usize = 200;
+
+/// Which log format a job line came in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobSource {
+    ActiveJob,
+    Sidekiq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobEventKind {
+    Started,
+    Performed,
+    Failed,
+    /// ActiveJob rescheduled a failed job
+    Retrying,
+    /// The error a Sidekiq job failed with, logged after its `fail`
+    Error,
+}
+
+/// One job line
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobEvent {
+    pub kind: JobEventKind,
+    pub source: JobSource,
+    /// Job class; a Sidekiq error line without its job payload has none
+    pub class: Option<String>,
+    /// ActiveJob's Job ID or Sidekiq's jid
+    pub job_id: Option<String>,
+    /// Queue, from ActiveJob's `from Sidekiq(default)`
+    pub queue: Option<String>,
+    pub duration_ms: Option<f64>,
+    /// `RuntimeError: boom`
+    pub error: Option<String>,
+}
+
+impl JobEvent {
+    fn new(kind: JobEventKind, source: JobSource, class: Option<String>) -> Self {
+        Self {
+            kind,
+            source,
+            class,
+            job_id: None,
+            queue: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+}
+
+/// Runs of one job class
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobClassStats {
+    pub class: String,
+    /// Queue it last ran from, when logged
+    pub queue: Option<String>,
+    /// Finished runs, failed ones included
+    pub runs: usize,
+    pub failures: usize,
+    pub retries: usize,
+    /// Time spent in the runs that logged their duration
+    pub total_duration_ms: f64,
+    pub timed_runs: usize,
+    pub last_error: Option<String>,
+    pub last_seen: Instant,
+}
+
+impl JobClassStats {
+    fn new(class: &str, now: Instant) -> Self {
+        Self {
+            class: class.to_string(),
+            queue: None,
+            runs: 0,
+            failures: 0,
+            retries: 0,
+            total_duration_ms: 0.0,
+            timed_runs: 0,
+            last_error: None,
+            last_seen: now,
+        }
+    }
+
+    pub fn avg_duration_ms(&self) -> Option<f64> {
+        (self.timed_runs > 0).then(|| self.total_duration_ms / self.timed_runs as f64)
+    }
+}
+
+/// A failed run
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobFailure {
+    pub class: String,
+    pub job_id: Option<String>,
+    pub queue: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: Option<f64>,
+    pub at: Instant,
+}
+
+#[derive(Default)]
+struct JobState {
+    classes: HashMap<String, JobClassStats>,
+    /// Newest last
+    failures: Vec<JobFailure>,
+    /// Started and not yet finished, by job id
+    running: HashMap<String, Instant>,
+    /// Classes seen in ActiveJob lines
+    active_job_classes: HashSet<String>,
+    failed_jids: VecDeque<String>,
+    /// Position in `failures` of a Sidekiq failure whose error line is due
+    awaiting_error: Option<usize>,
+}
+
+/// Job runs and failures seen in any process's output
+#[derive(Default)]
+pub struct JobTracker {
+    state: Mutex<JobState>,
+}
+
+impl JobTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a log line, returning whether it was a job line
+    pub fn parse_line(&self, line: &str) -> bool {
+        parse_job_line(line).is_some_and(|event| self.record(&event, Instant::now()))
+    }
+
+    /// Count `event`; false for an error line no failure was waiting for
+    pub fn record(&self, event: &JobEvent, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        if event.kind != JobEventKind::Error {
+            state.awaiting_error = None;
+        }
+        let counted = match (&event.class, event.source) {
+            (Some(class), JobSource::ActiveJob) => {
+                state.active_job_classes.insert(class.clone());
+                true
+            }
+            (Some(class), JobSource::Sidekiq) => !state.active_job_classes.contains(class),
+            (None, _) => true,
+        };
+
+        match event.kind {
+            JobEventKind::Started => {
+                let Some(id) = &event.job_id else {
+                    return true;
+                };
+                if state.running.len() >= MAX_RUNNING {
+                    let oldest = state
+                        .running
+                        .iter()
+                        .min_by_key(|(_, started)| **started)
+                        .map(|(id, _)| id.clone());
+                    if let Some(oldest) = oldest {
+                        state.running.remove(&oldest);
+                    }
+                }
+                state.running.insert(id.clone(), now);
+                // Sidekiq retries a job under the jid it failed with
+                if event.source == JobSource::Sidekiq
+                    && counted
+                    && let Some(position) = state.failed_jids.iter().position(|jid| jid == id)
+                {
+                    state.failed_jids.remove(position);
+                    if let Some(class) = &event.class {
+                        Self::class_stats(state, class, now).retries += 1;
+                    }
+                }
+                if let Some(class) = &event.class {
+                    let stats = Self::class_stats(state, class, now);
+                    if event.queue.is_some() {
+                        stats.queue = event.queue.clone();
+                    }
+                }
+            }
+            JobEventKind::Performed | JobEventKind::Failed => {
+                if let Some(id) = &event.job_id {
+                    state.running.remove(id);
+                }
+                let Some(class) = &event.class else {
+                    return true;
+                };
+                if !counted {
+                    return true;
+                }
+                let failed = event.kind == JobEventKind::Failed;
+                let stats = Self::class_stats(state, class, now);
+                stats.runs += 1;
+                if let Some(ms) = event.duration_ms {
+                    stats.total_duration_ms += ms;
+                    stats.timed_runs += 1;
+                }
+                if event.queue.is_some() {
+                    stats.queue = event.queue.clone();
+                }
+                if !failed {
+                    return true;
+                }
+                stats.failures += 1;
+                if event.error.is_some() {
+                    stats.last_error = event.error.clone();
+                }
+                let queue = stats.queue.clone();
+                state.failures.push(JobFailure {
+                    class: class.clone(),
+                    job_id: event.job_id.clone(),
+                    queue,
+                    error: event.error.clone(),
+                    duration_ms: event.duration_ms,
+                    at: now,
+                });
+                keep_last(&mut state.failures, MAX_RECENT_FAILURES);
+                if event.source == JobSource::Sidekiq {
+                    if let Some(id) = &event.job_id {
+                        state.failed_jids.push_back(id.clone());
+                        if state.failed_jids.len() > MAX_FAILED_JIDS {
+                            state.failed_jids.pop_front();
+                        }
+                    }
+                    state.awaiting_error = Some(state.failures.len() - 1);
+                }
+            }
+            JobEventKind::Retrying => {
+                let Some(class) = &event.class else {
+                    return true;
+                };
+                let stats = Self::class_stats(state, class, now);
+                stats.retries += 1;
+                if event.error.is_some() {
+                    stats.last_error = event.error.clone();
+                }
+            }
+            JobEventKind::Error => {
+                let Some(error) = &event.error else {
+                    return false;
+                };
+                let position = match &event.job_id {
+                    Some(id) => state
+                        .failures
+                        .iter()
+                        .rposition(|failure| failure.job_id.as_ref() == Some(id)),
+                    None => state.awaiting_error,
+                };
+                let Some(failure) = position.and_then(|index| state.failures.get_mut(index)) else {
+                    return false;
+                };
+                if failure.error.is_none() {
+                    failure.error = Some(error.clone());
+                }
+                let class = failure.class.clone();
+                Self::class_stats(state, &class, now).last_error = Some(error.clone());
+                state.awaiting_error = None;
+            }
+        }
+        true
+    }
+
+    fn class_stats<'a>(
+        state: &'a mut JobState,
+        class: &str,
+        now: Instant,
+    ) -> &'a mut JobClassStats {
+        let stats = state
+            .classes
+            .entry(class.to_string())
+            .or_insert_with(|| JobClassStats::new(class, now));
+        stats.last_seen = now;
+        stats
+    }
+
+    /// Stats per job class, most runs first
+    pub fn get_class_stats(&self) -> Vec<JobClassStats> {
+        let mut classes: Vec<JobClassStats> = self
+            .state
+            .lock()
+            .unwrap()
+            .classes
+            .values()
+            .cloned()
+            .collect();
+        classes.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.class.cmp(&b.class)));
+        classes
+    }
+
+    /// Up to `limit` failures, newest first
+    pub fn recent_failures(&self, limit: usize) -> Vec<JobFailure> {
+        let state = self.state.lock().unwrap();
+        state.failures.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Jobs started and not finished yet
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().running.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.classes.is_empty() && state.running.is_empty()
+    }
+}
+
+/// `Performing MyJob (Job ID: 5f2c...) from Sidekiq(default) enqueued at ...`
+fn active_job_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"^(Performing|Performed|Error performing) ([A-Z][\w:]*) \(Job ID: ([^)]+)\) from \w+\(([^)]*)\)(?: in (\d+(?:\.\d+)?)ms)?(?::\s+(.+?):?)?(?:\s+enqueued at.*|\s+with arguments.*)?$",
+        )
+        .unwrap()
+    })
+}
+
+/// `Retrying MyJob (Job ID: 5f2c...) after 1 attempts in 3 seconds, due to a
+/// RuntimeError (boom).`, and Rails 6's `Retrying MyJob in 3 seconds, due to
+/// a RuntimeError.`
+fn active_job_retry_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^Retrying ([A-Z][\w:]*)(?: \(Job ID: ([^)]+)\))?.*?, due to an? (.+?)\.?$")
+            .unwrap()
+    })
+}
+
+/// `INFO: done` (Sidekiq 6+, `elapsed=` before it) or `INFO: done: 0.152 sec`
+/// (Sidekiq 5)
+fn sidekiq_state_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\bINFO: (start|done|fail)(?:: (\d+(?:\.\d+)?) sec)?\s*$").unwrap()
+    })
+}
+
+/// Sidekiq 5's `TID-ouy7z HardWorker JID-b4a577edbccf1d805744efa9 INFO:`
+fn sidekiq_legacy_job_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b([A-Z][\w:]*) JID-(\w+) INFO:").unwrap())
+}
+
+fn sidekiq_field_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b(class|jid|elapsed)=(\S+)").unwrap())
+}
+
+/// `WARN: RuntimeError: boom`, the line Sidekiq logs a job's error on
+fn sidekiq_error_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\bWARN: ([A-Z]\w*(?:::[A-Z]\w*)*): (.+)$").unwrap())
+}
+
+/// The job event a line logs, if any
+pub fn parse_job_line(line: &str) -> Option<JobEvent> {
+    let message = RailsLogParser::message(line).trim();
+    parse_active_job(message).or_else(|| parse_sidekiq(line))
+}
+
+fn parse_active_job(message: &str) -> Option<JobEvent> {
+    if let Some(caps) = active_job_pattern().captures(message) {
+        let kind = match &caps[1] {
+            "Performing" => JobEventKind::Started,
+            "Performed" => JobEventKind::Performed,
+            _ => JobEventKind::Failed,
+        };
+        let mut event = JobEvent::new(kind, JobSource::ActiveJob, Some(caps[2].to_string()));
+        event.job_id = Some(caps[3].to_string());
+        event.queue = Some(caps[4].to_string()).filter(|queue| !queue.is_empty());
+        event.duration_ms = caps.get(5).and_then(|ms| ms.as_str().parse().ok());
+        if kind == JobEventKind::Failed {
+            event.error = caps.get(6).map(|error| error_text(error.as_str()));
+        }
+        return Some(event);
+    }
+
+    let caps = active_job_retry_pattern().captures(message)?;
+    let mut event = JobEvent::new(
+        JobEventKind::Retrying,
+        JobSource::ActiveJob,
+        Some(caps[1].to_string()),
+    );
+    event.job_id = caps.get(2).map(|id| id.as_str().to_string());
+    event.error = Some(error_text(&caps[3]));
+    Some(event)
+}
+
+/// `RuntimeError (boom)` as `RuntimeError: boom`
+fn error_text(error: &str) -> String {
+    match error.split_once(" (") {
+        Some((class, message)) if message.ends_with(')') => {
+            format!("{}: {}", class, &message[..message.len() - 1])
+        }
+        _ => error.to_string(),
+    }
+}
+
+fn parse_sidekiq(line: &str) -> Option<JobEvent> {
+    if let Some(caps) = sidekiq_state_pattern().captures(line) {
+        let kind = match &caps[1] {
+            "start" => JobEventKind::Started,
+            "done" => JobEventKind::Performed,
+            _ => JobEventKind::Failed,
+        };
+        let mut event = JobEvent::new(kind, JobSource::Sidekiq, None);
+        let mut elapsed = caps.get(2).map(|secs| secs.as_str());
+        for field in sidekiq_field_pattern().captures_iter(line) {
+            let value = field.get(2).map(|value| value.as_str());
+            match &field[1] {
+                "class" => event.class = value.map(str::to_string),
+                "jid" => event.job_id = value.map(str::to_string),
+                _ => elapsed = elapsed.or(value),
+            }
+        }
+        if event.class.is_none()
+            && let Some(legacy) = sidekiq_legacy_job_pattern().captures(line)
+        {
+            event.class = Some(legacy[1].to_string());
+            event.job_id = Some(legacy[2].to_string());
+        }
+        event.class.as_ref()?;
+        event.duration_ms = elapsed
+            .and_then(|secs| secs.parse::<f64>().ok())
+            .map(|secs| secs * 1000.0);
+        return Some(event);
+    }
+
+    if let Some(json) = line
+        .split_once("WARN: {")
+        .map(|(_, rest)| format!("{{{}", rest))
+    {
+        return sidekiq_error_payload(&json);
+    }
+    let caps = sidekiq_error_pattern().captures(line)?;
+    let mut event = JobEvent::new(JobEventKind::Error, JobSource::Sidekiq, None);
+    event.error = Some(format!("{}: {}", &caps[1], &caps[2]));
+    Some(event)
+}
+
+/// `{"context":"Job raised exception","job":{"class":...,"jid":...}}`; the
+/// error class and message are only in it once the job has been retried
+fn sidekiq_error_payload(json: &str) -> Option<JobEvent> {
+    let payload: serde_json::Value = serde_json::from_str(json).ok()?;
+    if payload.get("context")?.as_str()? != "Job raised exception" {
+        return None;
+    }
+    let job = payload.get("job")?;
+    let field = |key: &str| job.get(key).and_then(|value| value.as_str());
+    let error_class = field("error_class")?;
+    let mut event = JobEvent::new(
+        JobEventKind::Error,
+        JobSource::Sidekiq,
+        field("wrapped").or(field("class")).map(str::to_string),
+    );
+    event.job_id = field("jid").map(str::to_string);
+    event.error = Some(match field("error_message") {
+        Some(message) => format!("{}: {}", error_class, message),
+        None => error_class.to_string(),
+    });
+    Some(event)
+}
@@ -1,17 +1,36 @@
+pub mod alerts;
 pub mod cli;
+pub mod clock;
 pub mod config;
 pub mod context;
 pub mod database;
+pub mod diagnostics;
+pub mod disk;
 pub mod environment;
 pub mod exception;
 pub mod explain;
 pub mod frontend;
 pub mod git;
+pub mod headless;
+pub mod history;
+pub mod jobs;
+pub mod log_export;
+pub mod memory;
 pub mod metrics;
 pub mod parser;
+pub mod path_glob;
+pub mod persistence;
+pub mod privacy;
 pub mod process;
+pub mod project;
 pub mod query;
 pub mod rails;
+pub mod search;
+pub mod serialization;
+pub mod session;
+pub mod shared;
 pub mod stats;
 pub mod test;
+pub mod time_format;
+pub mod time_range;
 pub mod ui;
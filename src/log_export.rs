@@ -0,0 +1,126 @@
+//! Logs written out as newline-delimited JSON by `/export json`
+//!
+//! One object per line: the process, the wall-clock time the line was read
+//! (RFC 3339, in the `[ui] timezone` zone), the raw content, its level and,
+//! when [`RailsLogParser`] understands the line, what it made of it. Lines
+//! are written one at a time through a buffer, so exporting a full log
+//! buffer never holds more than one of them in memory.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{LogEvent, RailsLogParser};
+use crate::privacy::Privacy;
+use crate::process::{LogLevel, LogLine};
+use crate::query::QueryFingerprint;
+use crate::time_format::DisplayZone;
+
+/// One exported line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonLogRecord {
+    pub process: String,
+    pub time: DateTime<FixedOffset>,
+    /// The whole line, spilled tail included, masked while privacy mode is on
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LogLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<ParsedLine>,
+}
+
+/// What the Rails parser made of a line, for the lines worth querying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParsedLine {
+    /// A `Started` line (method and path) or a `Completed` one (status and
+    /// duration); Lograge lines have all four
+    HttpRequest {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        method: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<u16>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<f64>,
+    },
+    Sql {
+        /// `User Load`, when logged
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<f64>,
+        /// The query with its values replaced by `?`, as Query Analysis
+        /// groups it
+        fingerprint: String,
+    },
+}
+
+impl ParsedLine {
+    /// The parsed form of `content`, if it's a request or query line
+    pub fn parse(content: &str, privacy: &Privacy) -> Option<Self> {
+        let non_empty = |text: String| (!text.is_empty()).then_some(text);
+        match RailsLogParser::parse_line(content)? {
+            LogEvent::HttpRequest(request) => Some(Self::HttpRequest {
+                method: non_empty(request.method),
+                path: non_empty(privacy.text(&request.path).into_owned()),
+                status: request.status,
+                duration_ms: request.duration,
+            }),
+            LogEvent::SqlQuery(query) => Some(Self::Sql {
+                name: query.name,
+                duration_ms: query.duration,
+                fingerprint: QueryFingerprint::new(&query.query).normalized,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl JsonLogRecord {
+    pub fn from_log(log: &LogLine, zone: DisplayZone, privacy: &Privacy) -> Self {
+        let content = log.full_content();
+        Self {
+            process: log.process_name.clone(),
+            time: zone.fixed(log.time),
+            parsed: ParsedLine::parse(&content, privacy),
+            content: privacy.log_line(&content).into_owned(),
+            level: log.level,
+        }
+    }
+}
+
+/// Write `logs` to `writer` as JSON lines; returns how many were written
+pub fn write_json_lines<'a, W: Write>(
+    writer: W,
+    logs: impl IntoIterator<Item = &'a LogLine>,
+    zone: DisplayZone,
+    privacy: &Privacy,
+) -> io::Result<usize> {
+    let mut writer = BufWriter::new(writer);
+    let mut written = 0;
+    for log in logs {
+        serde_json::to_writer(&mut writer, &JsonLogRecord::from_log(log, zone, privacy))?;
+        writer.write_all(b"\n")?;
+        written += 1;
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Export `logs` to the file at `path` as JSON lines
+pub fn export_json<'a>(
+    path: &Path,
+    logs: impl IntoIterator<Item = &'a LogLine>,
+    zone: DisplayZone,
+    privacy: &Privacy,
+) -> Result<usize, String> {
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    write_json_lines(file, logs, zone, privacy)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -17,7 +17,9 @@
 //!   frontend (Vite, Next.js, Nuxt, SvelteKit, Remix, Astro, CRA, Vue CLI,
 //!   Angular).
 //! - Works for monorepos (`frontend/`, `client/`, `web/`, `app/`, `ui/`, `www/`)
-//!   and sibling layouts (`../frontend`, `../client`, `../web`).
+//!   and sibling layouts (`../frontend`, `../client`, `../web`); failing those,
+//!   a bounded search that skips `node_modules`, `vendor`, `tmp` and whatever
+//!   `.gitignore` ignores (`--verbose` shows what was tried).
 //! - If no Procfile is present, Caboose generates one with Rails, worker, and
 //!   frontend entries using the detected package manager.
 //!
@@ -26,8 +28,17 @@
 //! # From Rails project root
 //! cargo run
 //! ./target/release/caboose
+//! # From anywhere
+//! caboose --project ~/code/my-rails-app
+//! caboose recent
+//! caboose open caboose_session_20250101_120000.json.gz
+//! # Without the TUI: startup report as JSON, then one JSON event per line
+//! caboose --no-tui --startup-json --json-logs
 //! ```
-//! - Coming soon CLI shims: `caboose dev [process]`, `caboose stop`, `caboose restart`, `caboose logs`, `caboose ps`.
+//! - Exit codes: 0 ran, 2 nothing detected, 3 health check failed, 4 a `[setup]`
+//!   command failed (see `caboose::headless`).
+//! - Coming soon CLI shims: `caboose dev [process]`, `caboose stop`, `caboose restart`, `caboose logs`, `caboose ps`,
+//!   `caboose run <command...>`.
 //! - Keyboard inside the TUI: `q` quit, `t` cycles views, `/` search, `Esc` go back,
 //!   `↑/↓` scroll, `PageUp/PageDown` page scroll, `c` clear filters, `:` command mode.
 //!
@@ -59,8 +70,11 @@
 //!
 //! [processes.frontend]
 //! command = "cd client && pnpm dev -- --port 3001"
-//! env = { NODE_ENV = "development", VITE_API_URL = "http://localhost:4000" }
+//! env = { NODE_ENV = "development", VITE_API_URL = "http://localhost:{rails.port}" }
 //! ```
+//! - Env values may use `{rails.port}`, `{frontend.port}`, `{frontend.path}`,
+//!   `{project.root}` and `{env.NAME}` (`{{` for a literal brace); with
+//!   `[templates] commands = true`, so may commands and Procfile lines.
 //! - Use `.caboose.toml` to set non-standard frontend locations, custom package
 //!   managers (pnpm/bun/yarn), custom ports, and custom process names.
 //! - Disable auto-detect when multiple frontends exist, then drive everything via
@@ -122,6 +136,8 @@
 //!   `.env` ingestion.
 //! - `process` – PTY-backed process spawning, environment merging, lifecycle
 //!   management, and log channel fan-out (`LogLine`).
+//! - `project` – `ProjectRoot` resolution (`--project`, `CABOOSE_PROJECT`, cwd),
+//!   the per-project `.caboose/` data dir, and the recent-projects list.
 //! - `parser` – Rails log parsing (HTTP requests, SQL statements), color coding,
 //!   and extraction into structured events.
 //! - `query` – SQL fingerprinting, N+1 detection, query recommendations, and
@@ -135,6 +151,12 @@
 //!   debugger detection and status.
 //! - `exception` – Exception detection, fingerprinting, severity classification,
 //!   grouping, and recent exception store.
+//! - `session` – Saving a whole session to one archive (`/session save`) and
+//!   loading it back for the read-only viewer (`caboose open`).
+//! - `history` – Opt-in SQLite store (`.caboose/history.db`) of request,
+//!   slow query and health score history behind `/trends`.
+//! - `time_format` – The `[ui] timezone` display zone used for on-screen and
+//!   exported wall-clock times.
 //! - `frontend` – Frontend framework and package manager detection plus Procfile
 //!   entry generation.
 //! - `rails` – Rails project detection and Procfile scaffolding for web/worker
@@ -163,7 +185,8 @@
 //! - Wrong package manager used → ensure the correct lockfile exists (`yarn.lock`
 //!   vs `package-lock.json` vs `pnpm-lock.yaml` vs `bun.lockb`).
 //! - Port conflict → set `[rails].port` and `[frontend].port` or override in
-//!   Procfile commands.
+//!   Procfile commands. Two processes set up for the same port stop startup
+//!   with both named (see `process::ports`).
 //! - Need custom commands → override in `[processes.<name>]` or in Procfile.
 //!
 //! ## Development & Testing
@@ -173,55 +196,430 @@
 //! - The UI refactor is modularized (see `src/ui/*`) with reusable widgets,
 //!   theming, and formatting utilities to ease further contributions.
 use caboose::cli::{Cli, Commands};
-use caboose::config::{CabooseConfig, Procfile, load_env};
+use caboose::config::{
+    CabooseConfig, ConfigWatcher, GENERATED_PROCFILE_NAME, Procfile, TemplateValues,
+    check_procfile, generate_procfile_entries, load_env, render_procfile, write_procfile,
+};
 use caboose::context::RequestContextTracker;
 use caboose::database::DatabaseHealth;
+use caboose::database::schema::load_schema;
+use caboose::disk::{DISK_CHECK_INTERVAL, spawn_disk_watcher};
+use caboose::environment::EnvironmentInfo;
 use caboose::exception::ExceptionTracker;
-use caboose::frontend::{FrontendApp, PackageManager};
+use caboose::frontend::{DEFAULT_FRONTEND_PROCESS, FrontendApp, PackageManager, ScanLimits};
 use caboose::git::GitInfo;
-use caboose::process::{LogLine, ProcessManager};
-use caboose::rails::RailsApp;
+use caboose::headless::{LogOutput, StartupFailure, StartupReport};
+use caboose::history::{HISTORY_FILE_NAME, HistoryStore, HistoryWriter};
+use caboose::parser::{InstrumentationGems, UNPARSED_SAMPLES_FILE};
+use caboose::persistence::{PERSISTED_SESSION_FILE, PersistedAnalytics};
+use caboose::process::{
+    LOG_FILE_POLL, LogFileFollower, LogLine, PortClaim, ProcessInfo, ProcessLogFiles,
+    ProcessManager, ProcessSpawn, ProcessStatus, RESTART_WAIT, SETUP_PROCESS_NAME,
+    STATE_WRITE_INTERVAL, STOP_REQUEST_WAIT, SessionState, SetupError, SetupStep,
+    ask_session_to_exit, find_session, format_ps, is_port_listening, is_process_alive,
+    logged_processes, parse_ansi, port_collisions, process_log_path, process_logs_dir,
+    read_backlog, request_restart, request_run, request_stop, restart_requests_path,
+    run_requests_path, session_state_path, shell_join, stop_processes, stop_request_path,
+    stop_targets, task_name,
+};
+use caboose::project::{self, ProjectRoot, RecentProjects};
+use caboose::rails::routes::RouteTable;
+use caboose::rails::watch::{WATCH_DEBOUNCE, spawn_project_watcher};
+use caboose::rails::{JobConfig, RailsApp};
+use caboose::session::{SessionArchive, SessionSources};
 use caboose::stats::StatsCollector;
 use caboose::test::TestTracker;
+use caboose::ui::ui_state::{UI_STATE_FILE, UiState};
 use caboose::ui::{self, App};
 use clap::Parser;
+use std::collections::HashSet;
+use std::io::Write;
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
 };
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+/// Set when stdout is for a machine (`--startup-json`, `--json-logs`)
+static BANNER_ON_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Startup messages for people: stdout, or stderr when stdout carries JSON
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if BANNER_ON_STDERR.load(Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let options = DevOptions {
+        write_procfile: cli.write_procfile,
+        force: cli.force,
+        no_tui: cli.no_tui,
+        startup_json: cli.startup_json,
+        json_logs: cli.json_logs,
+        attach: cli.attach,
+        fresh: cli.fresh,
+        resume: cli.resume,
+        skip_setup: cli.skip_setup,
+        verbose: cli.verbose,
+    };
+    BANNER_ON_STDERR.store(cli.startup_json || cli.json_logs, Ordering::Relaxed);
 
     match cli.command {
         Some(Commands::Dev { process: _ }) | None => {
-            run_dev_mode().await?;
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            exit_on_startup_failure(run_dev_mode(project, options).await)?;
+        }
+        Some(Commands::Recent { number }) => {
+            if let Some(project) = select_recent_project(number)? {
+                exit_on_startup_failure(run_dev_mode(project, options).await)?;
+            }
         }
-        Some(Commands::Stop) => {
-            println!("Stop command not yet implemented");
+        Some(Commands::Stop { grace, force }) => {
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            let grace = if force {
+                Duration::ZERO
+            } else {
+                Duration::from_secs(grace)
+            };
+            if !stop_session(&project, grace) {
+                std::process::exit(1);
+            }
         }
         Some(Commands::Restart { process }) => {
-            println!("Restart '{}' not yet implemented", process);
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            if let Err(err) = restart_in_session(&project, &process) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
         }
-        Some(Commands::Logs { process }) => {
-            println!("Logs for '{}' not yet implemented", process);
+        Some(Commands::Logs {
+            process,
+            follow,
+            lines,
+        }) => {
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            if let Err(err) = print_logs(&project, &process, lines, follow) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Run { command }) => {
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            match run_task(&project, &command) {
+                Ok(code) => std::process::exit(code),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
         }
         Some(Commands::Ps) => {
-            println!("Ps command not yet implemented");
+            let project = ProjectRoot::resolve(cli.project.as_deref())?;
+            print_processes(&project);
+        }
+        Some(Commands::Open { file }) => {
+            run_session_viewer(&file).await?;
         }
     }
 
     Ok(())
 }
 
-async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
+/// Startup failures exit with their documented code (see `caboose::headless`)
+fn exit_on_startup_failure(
+    result: Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Err(e) = &result
+        && let Some(failure) = e.downcast_ref::<StartupFailure>()
+    {
+        eprintln!("Error: {}", failure);
+        std::process::exit(failure.status.exit_code());
+    }
+    result
+}
+
+/// `caboose ps`: the processes of the session running in `project`
+fn print_processes(project: &ProjectRoot) {
+    match find_session(project) {
+        Some(state) => print!(
+            "{}",
+            format_ps(&state, chrono::Local::now(), is_process_alive)
+        ),
+        None => println!(
+            "No Caboose session is running in {}. Start one with `caboose`.",
+            project
+        ),
+    }
+}
+
+/// `caboose stop`: stop the processes of the session running in `project`,
+/// then ask the session to exit; false when there was none
+///
+/// The session is told first, so it doesn't take the exits for crashes and
+/// restart what's being stopped.
+fn stop_session(project: &ProjectRoot, grace: Duration) -> bool {
+    let Some(state) = find_session(project) else {
+        eprintln!("No Caboose session is running in {}.", project);
+        return false;
+    };
+    if let Err(err) = request_stop(&stop_request_path(project), STOP_REQUEST_WAIT) {
+        eprintln!(
+            "Could not tell the session its processes are stopping: {}",
+            err
+        );
+    }
+    for result in stop_processes(&stop_targets(&state), grace) {
+        println!("{}", result.describe());
+    }
+    match ask_session_to_exit(state.pid) {
+        Ok(()) => println!("Asked the session (pid {}) to exit.", state.pid),
+        Err(err) => eprintln!("Could not ask the session to exit: {}", err),
+    }
+    true
+}
+
+/// `caboose restart`: have the session running in `project` restart
+/// `process`, and wait to see it start again
+fn restart_in_session(project: &ProjectRoot, process: &str) -> Result<(), String> {
+    let Some(state) = find_session(project) else {
+        return Err(format!("No Caboose session is running in {}.", project));
+    };
+    // A new run has a new start time; stopping the old one doesn't change it
+    let started_at = |state: &SessionState| {
+        state
+            .processes
+            .iter()
+            .find(|p| p.name == process)
+            .and_then(|p| p.started_at)
+    };
+    let before = started_at(&state);
+    request_restart(&restart_requests_path(project), &state, process)?;
+
+    let deadline = Instant::now() + RESTART_WAIT;
+    while Instant::now() < deadline {
+        std::thread::sleep(STATE_WRITE_INTERVAL);
+        let Some(state) = find_session(project) else {
+            return Err("The session exited before restarting it.".to_string());
+        };
+        let Some(info) = state.processes.iter().find(|p| p.name == process) else {
+            continue;
+        };
+        if started_at(&state) == before {
+            continue;
+        }
+        match (&info.status, info.pid) {
+            (ProcessStatus::Running, Some(pid)) => {
+                println!("Restarted {} (pid {})", process, pid);
+                return Ok(());
+            }
+            (ProcessStatus::Crashed | ProcessStatus::Stopped, _) => {
+                return Err(format!(
+                    "{} didn't start again; see its logs in the session",
+                    process
+                ));
+            }
+            _ => {}
+        }
+    }
+    Err(format!(
+        "Asked the session to restart {}, but it hasn't yet.",
+        process
+    ))
+}
+
+/// `caboose run`: hand `command` to the session running in `project`, or
+/// run it here with the project's `.env` when there's none; the exit code
+/// to leave with
+fn run_task(project: &ProjectRoot, command: &[String]) -> Result<i32, String> {
+    if find_session(project).is_some() {
+        request_run(&run_requests_path(project), command)?;
+        println!(
+            "Running `{}` in the session; its output is under {} in the logs.",
+            shell_join(command),
+            task_name(command)
+        );
+        return Ok(0);
+    }
+    let env = load_env(project.join(".env")).unwrap_or_default();
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(shell_join(command))
+        .current_dir(project.path())
+        .envs(env)
+        .status()
+        .map_err(|err| format!("Failed to run {}: {}", shell_join(command), err))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// `caboose logs`: print the last `lines` lines `process` wrote, then with
+/// `follow` the ones it goes on to write, until interrupted
+fn print_logs(
+    project: &ProjectRoot,
+    process: &str,
+    lines: usize,
+    follow: bool,
+) -> Result<(), String> {
+    let dir = process_logs_dir(project);
+    let path = process_log_path(&dir, process);
+    if !path.exists() {
+        // Known from an earlier run's logs, or the running session
+        let mut known = logged_processes(&dir);
+        if let Some(state) = find_session(project) {
+            known.extend(state.processes.into_iter().map(|p| p.name));
+        }
+        known.sort();
+        known.dedup();
+        if !known.contains(&process.to_string()) {
+            return Err(if known.is_empty() {
+                format!("No process output is kept in {} yet.", dir.display())
+            } else {
+                format!(
+                    "No process named '{}' (known processes: {})",
+                    process,
+                    known.join(", ")
+                )
+            });
+        }
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    // Started before the backlog is read, so nothing written in between is missed
+    let mut follower = follow.then(|| LogFileFollower::new(&path));
+    if path.exists() {
+        let backlog = read_backlog(&path, lines)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        for line in backlog {
+            if writeln!(stdout, "{}", line).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    let Some(follower) = follower.as_mut() else {
+        return Ok(());
+    };
+    loop {
+        for line in follower.read_lines() {
+            // The reader went away (e.g. `| head`)
+            if writeln!(stdout, "{}", line).is_err() {
+                return Ok(());
+            }
+        }
+        if stdout.flush().is_err() {
+            return Ok(());
+        }
+        std::thread::sleep(LOG_FILE_POLL);
+    }
+}
+
+/// List recent projects, or resolve the one picked by number
+fn select_recent_project(number: Option<usize>) -> Result<Option<ProjectRoot>, String> {
+    let recent = RecentProjects::default_path()
+        .map(RecentProjects::load_from)
+        .unwrap_or_default();
+
+    if let Some(number) = number {
+        let entry = recent.get(number).ok_or_else(|| {
+            format!(
+                "No recent project #{} (run `caboose recent` to see the list)",
+                number
+            )
+        })?;
+        return ProjectRoot::new(&entry.path).map(Some);
+    }
+
+    if recent.projects.is_empty() {
+        println!("No recent projects yet. Run `caboose` inside a project to add one.");
+        return Ok(None);
+    }
+
+    println!("Recent projects:");
+    for (idx, entry) in recent.projects.iter().enumerate() {
+        let missing = if entry.path.is_dir() {
+            ""
+        } else {
+            "  (missing)"
+        };
+        println!("  {:>2}. {}{}", idx + 1, entry.path.display(), missing);
+    }
+    println!("\nStart one with: caboose recent <number>");
+
+    Ok(None)
+}
+
+/// Startup flags that affect dev mode
+#[derive(Debug, Clone, Copy)]
+struct DevOptions {
+    /// Save an auto-generated Procfile to disk
+    write_procfile: bool,
+    /// Allow replacing an existing generated Procfile
+    force: bool,
+    /// Stream process output to stdout instead of running the TUI
+    no_tui: bool,
+    /// Start stdout with the startup report as JSON
+    startup_json: bool,
+    /// Write process output as JSON events
+    json_logs: bool,
+    /// Adopt processes already listening on their port instead of starting them
+    attach: bool,
+    /// Skip restoring the last run's UI state
+    fresh: bool,
+    /// Reload the last run's analytics, as `[session] persist` does
+    resume: bool,
+    /// Don't run the `[setup]` commands
+    skip_setup: bool,
+    /// Say how detection went
+    verbose: bool,
+}
+
+/// Print the failed startup report for `--startup-json`, then hand the failure back
+fn startup_failed(
+    report: StartupReport,
+    failure: StartupFailure,
+    options: DevOptions,
+) -> Box<dyn std::error::Error> {
+    if options.startup_json {
+        println!("{}", report.failed(&failure).to_json());
+    }
+    failure.into()
+}
+
+async fn run_dev_mode(
+    project: ProjectRoot,
+    mut options: DevOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Detect terminal capabilities for icon rendering (must be first)
     caboose::ui::icon_manager::IconManager::detect();
 
+    // Settle on the TUI before anything starts, so a terminal that can't show
+    // it fails here rather than with processes already running
+    if !options.no_tui
+        && let Err(problem) = ui::terminal::check_terminal()
+    {
+        eprintln!(
+            "[WARN] Can't show the TUI: {}. Streaming plain logs instead, as with --no-tui",
+            problem
+        );
+        options.no_tui = true;
+    }
+
+    say!("Project: {}", project);
+    project::remember_project(&project);
+    if let Err(e) = project.ensure_data_dir() {
+        eprintln!("[WARN] {}", e);
+    }
+
     // Load configuration
-    let caboose_config = CabooseConfig::load();
+    let caboose_config = CabooseConfig::load_in(&project);
+
+    let mut report = StartupReport::new(project.to_string());
 
     // Detect Rails application
     let rails_app = if caboose_config.rails.disable_auto_detect {
@@ -230,73 +628,93 @@ async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
             database: None,
             background_job: None,
             asset_pipeline: None,
+            jobs: JobConfig::default(),
+            instrumentation: InstrumentationGems::default(),
         }
     } else {
-        RailsApp::detect()
+        RailsApp::detect_in_path(&project)
     };
 
+    report = report.with_rails(&rails_app);
     if rails_app.detected {
-        println!("✓ Rails application detected");
+        say!("✓ Rails application detected");
         if let Some(ref db) = rails_app.database {
-            println!("  Database: {}", db);
+            say!("  Database: {}", db);
         }
         if let Some(ref job) = rails_app.background_job {
-            println!("  Background jobs: {}", job);
+            say!("  Background jobs: {}", job);
+            if rails_app.jobs.groups.len() > 1 {
+                say!("  Worker groups: {}", rails_app.jobs.groups.len());
+            }
+        }
+        if let Some(ref warning) = rails_app.jobs.warning {
+            eprintln!("[WARN] {} (using a single worker entry)", warning);
         }
         if let Some(ref assets) = rails_app.asset_pipeline {
-            println!("  Assets: {}", assets);
+            say!("  Assets: {}", assets);
+        }
+        if !rails_app.instrumentation.is_empty() {
+            say!(
+                "  Instrumentation: {}",
+                rails_app.instrumentation.names().join(", ")
+            );
         }
 
         // Check Rails health (migrations, database connectivity)
-        println!("\nChecking Rails health...");
-        let health_issues = rails_app.check_health();
+        say!("\nChecking Rails health...");
+        let health_issues = rails_app.check_health(&project);
+        report = report.with_health(&health_issues);
         if health_issues.is_empty() {
-            println!("✓ No issues detected");
+            say!("✓ No issues detected");
         } else {
             for issue in &health_issues {
                 match issue {
                     caboose::rails::RailsHealthIssue::BundleOutdated(message) => {
-                        println!("\n❌ ERROR: Bundler dependencies not satisfied!");
-                        println!(
+                        say!("\n❌ ERROR: Bundler dependencies not satisfied!");
+                        say!(
                             "   {}",
                             message.lines().next().unwrap_or("Dependencies missing")
                         );
-                        println!("   Run: bundle install");
-                        println!("\n   Caboose cannot start until dependencies are installed.");
+                        say!("   Run: bundle install");
+                        say!("\n   Caboose cannot start until dependencies are installed.");
                     }
                     caboose::rails::RailsHealthIssue::PendingMigrations(migrations) => {
-                        println!(
+                        say!(
                             "\n⚠️  WARNING: {} pending migration(s) detected!",
                             migrations.len()
                         );
-                        println!("   Run: bundle exec rails db:migrate");
+                        say!("   Run: bundle exec rails db:migrate");
                         if migrations.len() <= 5 {
                             for migration in migrations {
-                                println!("   - {}", migration);
+                                say!("   - {}", migration);
                             }
                         }
                     }
                     caboose::rails::RailsHealthIssue::DatabaseNotCreated => {
-                        println!("\n❌ ERROR: Database does not exist!");
-                        println!("   Run: bundle exec rails db:create");
+                        say!("\n❌ ERROR: Database does not exist!");
+                        say!("   Run: bundle exec rails db:create");
                     }
                     caboose::rails::RailsHealthIssue::DatabaseConnectionError(err) => {
-                        println!("\n❌ ERROR: Cannot connect to database!");
-                        println!("   {}", err);
-                        println!(
+                        say!("\n❌ ERROR: Cannot connect to database!");
+                        say!("   {}", err);
+                        say!(
                             "   Check your database.yml configuration and ensure the database server is running."
                         );
                     }
                 }
             }
-            println!();
+            say!();
 
             // Exit if bundle install is needed
             if health_issues
                 .iter()
                 .any(|issue| matches!(issue, caboose::rails::RailsHealthIssue::BundleOutdated(_)))
             {
-                return Err("Please run 'bundle install' before starting Caboose".into());
+                return Err(startup_failed(
+                    report,
+                    StartupFailure::health("Please run 'bundle install' before starting Caboose"),
+                    options,
+                ));
             }
         }
     }
@@ -309,83 +727,223 @@ async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
             path: String::new(),
             package_manager: PackageManager::Npm,
         }
-    } else if let Some(ref path) = caboose_config.frontend.path {
-        println!("Using configured frontend path: {}", path);
-        FrontendApp::detect_with_config(Some(path))
     } else {
-        FrontendApp::detect()
+        let configured = caboose_config.frontend.path.as_deref();
+        if let Some(path) = configured {
+            say!("Using configured frontend path: {}", path);
+        }
+        let (app, trace) =
+            FrontendApp::detect_traced(project.path(), configured, &ScanLimits::default());
+        if let Some(cap) = trace.capped {
+            eprintln!("[WARN] {}", cap.warning());
+        }
+        if options.verbose {
+            say!("Frontend detection ({}ms):", trace.elapsed.as_millis());
+            for step in &trace.steps {
+                say!("  {}", step);
+            }
+        }
+        app
     };
 
+    report = report.with_frontend(&frontend_app);
     if frontend_app.detected {
-        println!("✓ Frontend application detected");
+        say!("✓ Frontend application detected");
         if let Some(ref framework) = frontend_app.framework {
-            println!("  Framework: {}", framework.name());
-            println!("  Path: {}", frontend_app.path);
-            println!("  Package manager: {:?}", frontend_app.package_manager);
+            say!("  Framework: {}", framework.name());
+            say!("  Path: {}", frontend_app.path);
+            say!("  Package manager: {:?}", frontend_app.package_manager);
         }
     }
 
-    // Load or generate Procfile
-    let mut procfile = if std::path::Path::new("Procfile").exists() {
-        println!("Loading Procfile...");
-        Procfile::parse("Procfile").map_err(|e| format!("Failed to load Procfile: {}", e))?
+    // Load or generate Procfile. A previously saved Procfile.dev is reused unless
+    // --write-procfile asks for a fresh one.
+    let candidates: &[&str] = if options.write_procfile {
+        &["Procfile"]
+    } else {
+        &["Procfile", GENERATED_PROCFILE_NAME]
+    };
+    let procfile_path = candidates
+        .iter()
+        .map(|name| project.join(name))
+        .find(|path| path.exists());
+    let mut procfile = if let Some(ref procfile_path) = procfile_path {
+        say!("Loading {}...", procfile_path.display());
+        let procfile = Procfile::parse(procfile_path)
+            .map_err(|e| format!("Failed to load Procfile: {}", e))?;
+        // Run as is, but say what detection would have added or dropped
+        if rails_app.detected || frontend_app.detected {
+            let generated = generate_procfile_entries(&rails_app, &frontend_app, &caboose_config);
+            let findings = check_procfile(
+                &procfile.processes,
+                &generated,
+                &caboose_config,
+                project.path(),
+            );
+            for finding in &findings {
+                eprintln!("[WARN] Procfile: {}", finding);
+            }
+            if !findings.is_empty() {
+                say!("💡 /procfile-check lists these again; the Procfile is left as it is");
+            }
+        }
+        procfile
     } else if rails_app.detected || frontend_app.detected {
-        println!("No Procfile found, auto-generating...");
-        let procfile_content =
-            generate_multi_project_procfile(&rails_app, &frontend_app, &caboose_config);
-        println!("{}", procfile_content);
-        Procfile::parse_content(&procfile_content)?
+        say!("No Procfile found, auto-generating...");
+        let entries = generate_procfile_entries(&rails_app, &frontend_app, &caboose_config);
+        say!("{}", render_procfile(&entries, true));
+
+        let generated_path = project.join(GENERATED_PROCFILE_NAME);
+        if options.write_procfile {
+            write_procfile(&generated_path, &entries, options.force)?;
+            say!("✓ Saved {}", generated_path.display());
+        } else {
+            say!(
+                "💡 Run with --write-procfile to save this as {}",
+                GENERATED_PROCFILE_NAME
+            );
+        }
+
+        Procfile::from_entries(&entries)?
     } else {
         eprintln!("\n❌ No processes to run!");
-        eprintln!("\nCaboose couldn't detect any Rails or Frontend applications in the current directory.");
+        eprintln!(
+            "\nCaboose couldn't detect any Rails or Frontend applications in the project directory."
+        );
         eprintln!("\n💡 Possible solutions:");
-        eprintln!("   1. Run caboose from your Rails project root (where Gemfile exists)");
+        eprintln!(
+            "   1. Run caboose from your Rails project root (where Gemfile exists), or pass --project <path>"
+        );
         eprintln!("   2. Create a .caboose.toml to specify frontend path:");
         eprintln!("      [frontend]");
         eprintln!("      path = \"path/to/frontend\"");
         eprintln!("   3. Create a Procfile to manually define processes:");
         eprintln!("      web: bundle exec rails server");
         eprintln!("      frontend: cd frontend && npm start");
-        eprintln!("\n📖 Current directory: {}", std::env::current_dir().unwrap_or_default().display());
+        eprintln!("\n📖 Project directory: {}", project);
         eprintln!("   Looking for: Gemfile, config/application.rb (Rails)");
         eprintln!("                package.json, angular.json (Frontend)");
-        return Err("No Procfile, Rails app, or Frontend app detected".into());
+        return Err(startup_failed(
+            report,
+            StartupFailure::detection("No Procfile, Rails app, or Frontend app detected"),
+            options,
+        ));
     };
 
     // Apply process-specific overrides from .caboose.toml
     apply_process_overrides(&mut procfile, &caboose_config);
 
-    println!("Starting {} processes", procfile.processes.len());
+    say!("Starting {} processes", procfile.processes.len());
 
     // Load .env
-    let env_vars = load_env(".env").unwrap_or_default();
+    let env_vars = load_env(project.join(".env")).unwrap_or_default();
     if !env_vars.is_empty() {
-        println!("Loaded {} environment variables", env_vars.len());
+        say!("Loaded {} environment variables", env_vars.len());
     }
 
     // Get Git info
-    let git_info = GitInfo::get();
+    let git_info = GitInfo::get_in(&project);
+
+    // Detect language/tooling versions for the header
+    let environment_info = EnvironmentInfo::detect_in(project.path());
 
     // Create stats collector
-    let stats_collector = StatsCollector::new();
+    let stats_collector = StatsCollector::new()
+        .with_db_share_thresholds(caboose_config.stats.db_share_thresholds())
+        .with_cache_hit_rate_warning(caboose_config.stats.cache_hit_rate_warning());
+
+    let fingerprint_options = caboose_config.queries.fingerprint_options();
+    let (limits, limit_warnings) = caboose_config.limits.limits();
+    for warning in &limit_warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    let (line_truncator, line_warnings) = caboose_config.logs.line_truncator();
+    for warning in &line_warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    let (duplicate_settings, duplicate_warnings) = caboose_config.stats.duplicate_settings();
+    for warning in &duplicate_warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    let (display_zone, zone_warning) = caboose_config.ui.display_zone();
+    if let Some(warning) = &zone_warning {
+        eprintln!("[WARN] {}", warning);
+    }
+    let alert_rules = caboose_config
+        .alerts
+        .rules()
+        .map_err(|errors| format!("Invalid [alerts] rules:\n  {}", errors.join("\n  ")))?;
+
+    let schema = load_schema(project.path()).unwrap_or_default();
 
     // Create request context tracker
-    let context_tracker = Arc::new(RequestContextTracker::new());
+    let context_tracker = Arc::new(
+        RequestContextTracker::new()
+            .with_limits(limits)
+            .with_fingerprint_options(fingerprint_options)
+            .with_request_capacity(caboose_config.rails.request_capacity())
+            .with_queue_time_warning(caboose_config.stats.queue_time_warning())
+            .with_burst_detection(
+                caboose_config.stats.burst_threshold(),
+                caboose_config.stats.burst_window(),
+            )
+            .with_turbo_burst_detection(
+                caboose_config.stats.turbo_burst_threshold(),
+                caboose_config.stats.turbo_burst_window(),
+            )
+            .with_duplicate_detection(duplicate_settings)
+            .with_schema(schema.clone()),
+    );
 
     // Create database health tracker
-    let db_health = Arc::new(DatabaseHealth::new());
+    let db_health = Arc::new(
+        DatabaseHealth::new()
+            .with_limits(limits)
+            .with_fingerprint_options(fingerprint_options)
+            .with_dialect(rails_app.sql_dialect())
+            .with_schema(schema),
+    );
 
     // Create test tracker
-    let test_tracker = Arc::new(TestTracker::new());
+    let test_tracker = Arc::new(TestTracker::new().with_limits(limits));
 
     // Create exception tracker
-    let exception_tracker = Arc::new(ExceptionTracker::new());
+    let exception_tracker = Arc::new(
+        ExceptionTracker::new()
+            .with_limits(limits)
+            .with_grouping(caboose_config.exceptions.grouping()),
+    );
 
     // Create log channel
     let (log_tx, log_rx) = mpsc::unbounded_channel::<LogLine>();
 
     // Create process manager
-    let process_manager = Arc::new(ProcessManager::new(log_tx));
+    let daemonize_allowed = caboose_config
+        .processes
+        .iter()
+        .filter(|(_, process)| process.allow_daemonize)
+        .map(|(name, _)| name.clone());
+    let restart_rules = caboose_config
+        .processes
+        .iter()
+        .filter_map(|(name, process)| Some((name.clone(), process.restart_rule()?)));
+    // Setup output is kept for the Logs view too, under its own name
+    let setup_log = log_tx.clone();
+    let process_manager = Arc::new(
+        ProcessManager::new(log_tx)
+            .with_project_root(project.clone())
+            .with_daemonize_allowed(daemonize_allowed)
+            .with_restart_rules(restart_rules)
+            .with_stream_dedupe(caboose_config.logs.dedupe_streams)
+            .with_log_files(ProcessLogFiles::new(process_logs_dir(&project)))
+            .with_task_env(env_vars.clone()),
+    );
+    process_manager.keep_state_file(session_state_path(&project));
+    process_manager.follow_restart_requests(restart_requests_path(&project));
+    process_manager.follow_restart_policies();
+    process_manager.follow_run_requests(run_requests_path(&project));
+    process_manager.follow_stop_request(stop_request_path(&project));
+    process_manager.follow_task_cleanup();
     let shutdown_flag = Arc::new(AtomicBool::new(false));
 
     // Handle Ctrl+C to trigger graceful shutdown
@@ -399,37 +957,290 @@ async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
         });
     }
 
-    // Spawn processes
-    for proc_config in procfile.processes {
-        println!("  → Starting: {}", proc_config.name);
+    // `caboose stop` from another terminal sends SIGTERM; nobody's at this
+    // one to answer questions about daemonized processes
+    let stopped_externally = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let process_manager = process_manager.clone();
+        let shutdown_flag = shutdown_flag.clone();
+        let stopped_externally = stopped_externally.clone();
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::spawn(async move {
+                terminate.recv().await;
+                stopped_externally.store(true, Ordering::SeqCst);
+                shutdown_flag.store(true, Ordering::SeqCst);
+                process_manager.stop_all();
+            });
+        }
+    }
+
+    let process_names: Vec<String> = procfile
+        .processes
+        .iter()
+        .map(|process| process.name.clone())
+        .collect();
+    // `{rails.port}` and friends in env values (and commands, when enabled)
+    let mut templates = TemplateValues::new().with_project_root(project.path());
+    if rails_app.detected {
+        templates = templates.with_rails_port(caboose_config.rails.port.unwrap_or(3000));
+    }
+    if frontend_app.detected {
+        let port = caboose_config
+            .frontend
+            .port
+            .or(frontend_app.framework.as_ref().map(|f| f.default_port()));
+        templates = templates.with_frontend(port, &frontend_app.path);
+    }
 
+    let mut planned = Vec::new();
+    for mut proc_config in procfile.processes {
         // Merge global env vars with process-specific env vars from config
         let mut process_env = env_vars.clone();
-        if let Some(override_config) = caboose_config.processes.get(&proc_config.name) {
-            for (key, value) in &override_config.env {
-                process_env.insert(key.clone(), value.clone());
+        let override_config = caboose_config.processes.get(&proc_config.name);
+        if let Some(override_config) = override_config {
+            process_env = templates
+                .render_env(&override_config.env, &env_vars)
+                .map_err(|err| format!("[processes.{}] env {}", proc_config.name, err))?;
+        }
+        if caboose_config.templates.commands {
+            proc_config.command = templates
+                .render(&proc_config.command, &process_env)
+                .map_err(|err| format!("[processes.{}] command: {}", proc_config.name, err))?;
+        }
+
+        report.add_process(&proc_config.name, &proc_config.command, &process_env);
+        let claim = PortClaim::for_process(
+            &proc_config.name,
+            &proc_config.command,
+            &process_env,
+            override_config.and_then(|o| o.port),
+        );
+        planned.push((proc_config, process_env, claim));
+    }
+
+    // Two processes set up for one port: the second would only crash on it
+    let claims: Vec<PortClaim> = planned
+        .iter()
+        .filter_map(|(_, _, claim)| claim.clone())
+        .collect();
+    let collisions = port_collisions(&claims);
+    if !collisions.is_empty() {
+        for collision in &collisions {
+            eprintln!("\n❌ {}", collision);
+        }
+        eprintln!(
+            "\n💡 Give one of them another port: its -p/--port flag, PORT, or [rails] port for a generated Procfile"
+        );
+        return Err(startup_failed(
+            report,
+            StartupFailure::health(collisions.join("; ")),
+            options,
+        ));
+    }
+
+    // One-shot setup, in order, before anything is spawned
+    let setup_steps = caboose_config.setup.steps();
+    if options.skip_setup {
+        if !setup_steps.is_empty() {
+            say!(
+                "Skipping {} setup command(s) (--skip-setup)",
+                setup_steps.len()
+            );
+        }
+    } else if let Err(err) = run_setup(
+        &project,
+        &setup_steps,
+        &env_vars,
+        (!options.no_tui).then_some(&setup_log),
+    ) {
+        eprintln!("\n❌ Setup failed: {}", err.command());
+        eprintln!("   {}", err);
+        eprintln!("\n💡 Fix it and start again, or run with --skip-setup to start without it");
+        return Err(startup_failed(
+            report,
+            StartupFailure::setup(err.to_string()),
+            options,
+        ));
+    }
+    drop(setup_log);
+
+    // Spawn processes, all at once; one that can't start is shown as crashed
+    let mut spawns = Vec::new();
+    for (proc_config, process_env, claim) in planned {
+        let override_config = caboose_config.processes.get(&proc_config.name);
+
+        // Already running elsewhere: observe it rather than fail on its port
+        let port = claim.map(|claim| claim.port);
+        let managed = override_config.is_none_or(|o| o.managed());
+        if !managed || (options.attach && port.is_some_and(is_port_listening)) {
+            say!("  → Adopting: {}", proc_config.name);
+            let log_file = override_config.and_then(|o| o.log_file.clone());
+            match process_manager.adopt_process(
+                proc_config.name.clone(),
+                proc_config.command,
+                port,
+                log_file,
+            ) {
+                Ok(pid) => report.adopted(&proc_config.name, port, pid),
+                Err(err) => {
+                    eprintln!("[WARN] '{}' can't be adopted: {}", proc_config.name, err);
+                    report.spawn_failed(&proc_config.name, &err);
+                }
             }
+            continue;
         }
 
-        process_manager.spawn_process(
-            proc_config.name.clone(),
-            proc_config.command.clone(),
-            process_env,
-        )?;
+        say!("  → Starting: {}", proc_config.name);
+        spawns.push(ProcessSpawn {
+            name: proc_config.name,
+            command: proc_config.command,
+            env: process_env,
+        });
+    }
+    for (name, err) in process_manager.spawn_all(spawns).await {
+        eprintln!("[WARN] '{}' failed to start: {}", name, err);
+        report.spawn_failed(&name, &err);
+    }
+
+    if options.startup_json {
+        println!("{}", report.to_json());
+        let _ = std::io::stdout().flush();
     }
 
-    // Wait a bit for processes to start
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    if options.no_tui {
+        let output = if options.json_logs {
+            LogOutput::Json
+        } else {
+            LogOutput::text(process_names.iter().map(String::as_str).chain(["caboose"]))
+        };
+        stream_headless(log_rx, &process_manager, &shutdown_flag, &output).await;
+        // Daemonized processes are left running; there's no one to ask
+        for info in process_manager.unmanaged_processes() {
+            if let Some(pid) = info.pid {
+                eprintln!(
+                    "[WARN] '{}' daemonized and is still running (pid {})",
+                    info.name, pid
+                );
+            }
+        }
+        process_manager.stop_all();
+        return Ok(());
+    }
+
+    // Pick up the last run's analytics before any new lines reach the trackers
+    let analytics_path = project.data_dir().join(PERSISTED_SESSION_FILE);
+    if caboose_config.session.persist || options.resume {
+        match PersistedAnalytics::load(&analytics_path) {
+            Ok(Some(analytics)) => {
+                say!(
+                    "✓ Resumed {} saved {}",
+                    analytics.summary(),
+                    analytics.saved_at.format("%Y-%m-%d %H:%M")
+                );
+                analytics.restore_into(&SessionSources {
+                    processes: &[],
+                    logs: &[],
+                    stats: &stats_collector,
+                    requests: &context_tracker,
+                    exceptions: &exception_tracker,
+                    tests: &test_tracker,
+                    database: &db_health,
+                });
+            }
+            Ok(None) => say!("No saved session to resume in {}", analytics_path.display()),
+            Err(err) => eprintln!("[WARN] Not resuming: {}", err),
+        }
+    }
 
     // Run TUI
     let app = App::new(
         git_info,
+        environment_info,
         stats_collector.clone(),
         context_tracker.clone(),
         db_health.clone(),
         test_tracker.clone(),
         exception_tracker.clone(),
+    )
+    .with_compact_thresholds(caboose_config.ui.compact_thresholds())
+    .with_autofocus_resume(caboose_config.ui.autofocus_resume())
+    .with_limits(limits)
+    .with_alert_rules(alert_rules)
+    .with_line_truncator(line_truncator)
+    .with_log_order(caboose_config.logs.order)
+    .with_log_colors(caboose_config.logs.colors)
+    .with_instrumentation(rails_app.instrumentation)
+    .with_display_zone(display_zone)
+    .with_privacy(caboose_config.privacy.privacy())
+    .with_project(project.path())
+    .with_process_manager(process_manager.clone())
+    .with_config_watcher(ConfigWatcher::new(project.path()));
+    let ui_state_path = project.data_dir().join(UI_STATE_FILE);
+    let app = match UiState::load(&ui_state_path) {
+        Some(state) if caboose_config.ui.restores_session() && !options.fresh => {
+            let processes: Vec<String> = process_manager
+                .get_processes()
+                .iter()
+                .map(|process| process.name.clone())
+                .collect();
+            app.with_ui_state(state, &processes)
+        }
+        _ => app,
+    }
+    .with_ui_state_file(ui_state_path)
+    .with_analytics_file(analytics_path)
+    .with_frontend_process(
+        caboose_config
+            .frontend
+            .process_name
+            .as_deref()
+            .unwrap_or(DEFAULT_FRONTEND_PROCESS),
     );
+    let app = if frontend_app.detected {
+        app.with_frontend_dir(project.path().join(&frontend_app.path))
+    } else {
+        app
+    };
+    let app = if caboose_config.logs.dump_unparsed {
+        app.with_unparsed_dump(project.data_dir().join(UNPARSED_SAMPLES_FILE))
+    } else {
+        app
+    };
+    let app = if caboose_config.history.enabled {
+        let path = project.data_dir().join(HISTORY_FILE_NAME);
+        match HistoryStore::open(&path, caboose_config.history.retention_days()) {
+            Ok((store, _)) => app.with_history(HistoryWriter::spawn(store)),
+            Err(err) => {
+                eprintln!("[WARN] History disabled: {}", err);
+                app
+            }
+        }
+    } else {
+        app
+    };
+    let app = if caboose_config.disk.enabled() {
+        app.with_disk_watcher(spawn_disk_watcher(
+            project.path().to_path_buf(),
+            caboose_config.disk.limits(),
+            DISK_CHECK_INTERVAL,
+        ))
+    } else {
+        app
+    };
+    let app = if rails_app.detected {
+        let app = app.with_routes(RouteTable::load(&project.data_dir()));
+        match spawn_project_watcher(project.path().to_path_buf(), WATCH_DEBOUNCE) {
+            Ok(watcher) => app.with_project_watcher(watcher),
+            Err(err) => {
+                eprintln!("[WARN] {} (schema.rb changes won't be picked up)", err);
+                app
+            }
+        }
+    } else {
+        app
+    };
     let process_manager_for_ui = process_manager.clone();
     let ui_result = ui::run_ui(
         app,
@@ -444,6 +1255,11 @@ async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await;
 
+    // Daemonized processes survive stop_all unless the user agrees to stop them
+    if !stopped_externally.load(Ordering::SeqCst) {
+        confirm_stop_unmanaged(&process_manager);
+    }
+
     // Ensure all child processes are torn down when leaving the UI
     process_manager.stop_all();
 
@@ -453,48 +1269,221 @@ async fn run_dev_mode() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn apply_process_overrides(procfile: &mut Procfile, config: &CabooseConfig) {
-    // Apply process-specific command overrides from [processes.xxx] sections
-    for process in &mut procfile.processes {
-        if let Some(override_config) = config.processes.get(&process.name) {
-            if let Some(ref custom_command) = override_config.command {
-                println!("  Overriding '{}' command from .caboose.toml", process.name);
-                process.command = custom_command.clone();
+/// Run the `[setup]` commands in order, echoing their output and, for the
+/// TUI, sending it to the logs under "setup"; stamps are written once all
+/// have succeeded
+fn run_setup(
+    project: &ProjectRoot,
+    steps: &[SetupStep],
+    env: &std::collections::HashMap<String, String>,
+    log_tx: Option<&mpsc::UnboundedSender<LogLine>>,
+) -> Result<(), SetupError> {
+    let mut ran = Vec::new();
+    for step in steps {
+        if step.is_done(project.path()) {
+            say!("  ✓ Setup: {} (already done)", step.command);
+            continue;
+        }
+        say!("  → Setup: {}", step.command);
+        step.run(project.path(), env, |line| {
+            let (content, markup) = parse_ansi(line);
+            say!("    {}", content);
+            if let Some(log_tx) = log_tx {
+                let _ = log_tx.send(LogLine {
+                    markup: markup.map(Box::new),
+                    ..LogLine::new(SETUP_PROCESS_NAME, content)
+                });
             }
+        })?;
+        ran.push(step);
+    }
+    for step in ran {
+        if let Err(err) = step.touch_stamp(project.path()) {
+            eprintln!(
+                "[WARN] Setup stamp for `{}` not written: {}",
+                step.command, err
+            );
         }
     }
+    Ok(())
 }
 
-fn generate_multi_project_procfile(
-    rails_app: &RailsApp,
-    frontend_app: &FrontendApp,
-    config: &CabooseConfig,
-) -> String {
-    let mut procfile_content = String::new();
+/// Browse a saved session: the trackers are filled from the archive and no
+/// process is started
+async fn run_session_viewer(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    caboose::ui::icon_manager::IconManager::detect();
+    ui::terminal::check_terminal().map_err(|problem| {
+        format!(
+            "Can't show the session: {}. Open it from an interactive terminal",
+            problem
+        )
+    })?;
 
-    // Add Rails processes if detected (with port override from config)
-    if rails_app.detected {
-        procfile_content.push_str(&rails_app.generate_procfile(config.rails.port));
+    let mut archive = SessionArchive::load(path)?;
+    // Times are shown in the viewer's own `[ui] timezone`
+    let (display_zone, zone_warning) = CabooseConfig::load().ui.display_zone();
+    if let Some(warning) = &zone_warning {
+        eprintln!("[WARN] {}", warning);
     }
+    println!(
+        "Opening session saved {}{}",
+        display_zone.date_time(archive.metadata.saved_at),
+        archive
+            .metadata
+            .project
+            .as_ref()
+            .map_or_else(String::new, |project| format!(" in {}", project))
+    );
 
-    // Add frontend process if detected (with dev_command override from config)
-    if frontend_app.detected {
-        if let Some(frontend_entry) =
-            frontend_app.generate_procfile_entry(config.frontend.dev_command.as_deref())
-        {
-            if !procfile_content.is_empty() {
-                procfile_content.push('\n');
+    let stats_collector = StatsCollector::new();
+    let context_tracker = Arc::new(RequestContextTracker::new());
+    let db_health = Arc::new(DatabaseHealth::new());
+    let test_tracker = Arc::new(TestTracker::new());
+    let exception_tracker = Arc::new(ExceptionTracker::new());
+
+    // The header shows where the session ran rather than where it's viewed
+    let environment_info = EnvironmentInfo {
+        current_path: archive.metadata.project.clone().unwrap_or_default(),
+        ruby_version: None,
+        node_version: None,
+        package_manager: None,
+        rails_version: None,
+        database: Some(archive.database.dialect.name().to_string()),
+    };
+    let metadata = archive.metadata.clone();
+    let processes = std::mem::take(&mut archive.processes);
+    let logs = std::mem::take(&mut archive.logs);
+    archive.restore_into(&SessionSources {
+        processes: &[],
+        logs: &[],
+        stats: &stats_collector,
+        requests: &context_tracker,
+        exceptions: &exception_tracker,
+        tests: &test_tracker,
+        database: &db_health,
+    });
+
+    // Nothing is spawned, so nothing ever sends on the log channel
+    let (log_tx, log_rx) = mpsc::unbounded_channel::<LogLine>();
+    let process_manager = Arc::new(ProcessManager::new(log_tx));
+
+    let app = App::new(
+        GitInfo::default(),
+        environment_info,
+        stats_collector.clone(),
+        context_tracker.clone(),
+        db_health.clone(),
+        test_tracker.clone(),
+        exception_tracker.clone(),
+    )
+    .with_display_zone(display_zone)
+    .with_session(metadata, processes, logs);
+
+    ui::run_ui(
+        app,
+        log_rx,
+        process_manager,
+        stats_collector,
+        context_tracker,
+        db_health,
+        test_tracker,
+        exception_tracker,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .await
+}
+
+/// Write process output to stdout until every process has exited or Caboose
+/// is stopped
+async fn stream_headless(
+    mut log_rx: mpsc::UnboundedReceiver<LogLine>,
+    process_manager: &ProcessManager,
+    shutdown_flag: &AtomicBool,
+    output: &LogOutput,
+) {
+    let mut stdout = std::io::stdout();
+    let mut exited = HashSet::new();
+    let mut ticker = tokio::time::interval(tokio::time::Duration::from_millis(250));
+    loop {
+        tokio::select! {
+            Some(line) = log_rx.recv() => {
+                // Stdout went away (e.g. `| head`); nothing left to stream to
+                if writeln!(stdout, "{}", output.log_line(&line)).is_err() {
+                    break;
+                }
+                let _ = stdout.flush();
+            }
+            _ = ticker.tick() => {
+                // Output ends before the exit code is known; wait for both
+                let stopping = shutdown_flag.load(Ordering::SeqCst);
+                let processes = process_manager.get_processes();
+                // A process that never started is done, without an exit to
+                // report; one waiting to be restarted after a crash isn't
+                let has_exited = |info: &ProcessInfo| {
+                    info.spawn_error.is_some()
+                        || (info.status != ProcessStatus::Running
+                            && (info.last_exit.is_some() || stopping)
+                            && (stopping || !process_manager.is_restart_pending(&info.name)))
+                };
+                // Let lines already read from a process that exited come first
+                while let Ok(line) = log_rx.try_recv() {
+                    let _ = writeln!(stdout, "{}", output.log_line(&line));
+                }
+                for info in processes.iter() {
+                    // Started again, so its next exit is news
+                    if info.status == ProcessStatus::Running {
+                        exited.remove(&info.name);
+                    }
+                    if has_exited(info)
+                        && info.spawn_error.is_none()
+                        && !process_manager.is_adopted(&info.name)
+                        && exited.insert(info.name.clone())
+                    {
+                        let _ = writeln!(
+                            stdout,
+                            "{}",
+                            output.process_exit(&info.name, info.last_exit.as_ref())
+                        );
+                    }
+                }
+                let _ = stdout.flush();
+                if stopping || processes.iter().all(has_exited) {
+                    break;
+                }
             }
+        }
+    }
+}
 
-            // Use custom process name if configured
-            let process_name = config
-                .frontend
-                .process_name
-                .as_deref()
-                .unwrap_or("frontend");
-            procfile_content.push_str(&format!("{}: {}", process_name, frontend_entry));
+/// Ask whether to stop processes that daemonized out of Caboose's control
+fn confirm_stop_unmanaged(process_manager: &ProcessManager) {
+    for info in process_manager.unmanaged_processes() {
+        let Some(pid) = info.pid else { continue };
+        print!(
+            "'{}' daemonized and is still running (pid {}). Stop it? [y/N] ",
+            info.name, pid
+        );
+        let _ = std::io::stdout().flush();
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim(), "y" | "Y" | "yes")
+        {
+            process_manager.confirm_stop_detached(&info.name);
+        } else {
+            println!("  Leaving pid {} running.", pid);
         }
     }
+}
 
-    procfile_content
+fn apply_process_overrides(procfile: &mut Procfile, config: &CabooseConfig) {
+    // Apply process-specific command overrides from [processes.xxx] sections
+    for process in &mut procfile.processes {
+        if let Some(override_config) = config.processes.get(&process.name)
+            && let Some(ref custom_command) = override_config.command
+        {
+            say!("  Overriding '{}' command from .caboose.toml", process.name);
+            process.command = custom_command.clone();
+        }
+    }
 }
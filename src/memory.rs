@@ -0,0 +1,413 @@
+//! Memory reports printed by memory_profiler and derailed_benchmarks
+//!
+//! `MemoryProfiler.report { ... }.pretty_print` (also what `derailed exec
+//! perf:objects` prints) opens with `Total allocated:`, and `derailed bundle:mem`
+//! with `TOP: 84.2 MiB`. From there, the lines a process prints are read as
+//! part of its report for as long as they look like one: totals, table titles,
+//! `-----` rules, rows and blank lines. The first other line closes the report.
+//! Each process is followed on its own, so other processes' output arriving
+//! in between doesn't cut a report short.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+
+use crate::time_format::DisplayZone;
+use crate::ui::formatting::{format_bytes, format_number};
+
+/// Rows kept per table; memory_profiler prints 50
+pub const MAX_TABLE_ROWS: usize = 10;
+
+/// Which tool printed a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    MemoryProfiler,
+    /// `derailed bundle:mem`: memory taken by requiring each gem
+    DerailedBundleMem,
+}
+
+impl ReportKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportKind::MemoryProfiler => "memory_profiler",
+            ReportKind::DerailedBundleMem => "derailed bundle:mem",
+        }
+    }
+}
+
+/// `Total allocated: 30185764 bytes (291567 objects)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryTotal {
+    /// `allocated`, `retained` or `required`
+    pub label: &'static str,
+    pub bytes: u64,
+    pub objects: Option<u64>,
+}
+
+impl MemoryTotal {
+    /// `28.79 MB allocated (291,567 objects)`
+    pub fn describe(&self) -> String {
+        match self.objects {
+            Some(objects) => format!(
+                "{} {} ({} objects)",
+                format_bytes(self.bytes),
+                self.label,
+                format_number(objects as usize)
+            ),
+            None => format!("{} {}", format_bytes(self.bytes), self.label),
+        }
+    }
+}
+
+/// What a table's numbers count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Bytes,
+    Objects,
+}
+
+impl Measure {
+    pub fn format(self, amount: u64) -> String {
+        match self {
+            Measure::Bytes => format_bytes(amount),
+            Measure::Objects => format_number(amount as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRow {
+    pub amount: u64,
+    /// Gem, file, location or class
+    pub name: String,
+}
+
+/// `allocated memory by gem` and its first [`MAX_TABLE_ROWS`] rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryTable {
+    pub title: String,
+    pub measure: Measure,
+    pub rows: Vec<MemoryRow>,
+}
+
+/// One report, as far as it has been printed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryReport {
+    pub process: String,
+    pub kind: ReportKind,
+    pub time: DateTime<Local>,
+    pub totals: Vec<MemoryTotal>,
+    pub tables: Vec<MemoryTable>,
+    /// A line that isn't part of the report was seen after it
+    pub complete: bool,
+}
+
+impl MemoryReport {
+    fn new(process: &str, kind: ReportKind, time: DateTime<Local>, total: MemoryTotal) -> Self {
+        Self {
+            process: process.to_string(),
+            kind,
+            time,
+            totals: vec![total],
+            tables: Vec::new(),
+            complete: false,
+        }
+    }
+
+    pub fn total(&self, label: &str) -> Option<&MemoryTotal> {
+        self.totals.iter().find(|total| total.label == label)
+    }
+
+    pub fn table(&self, title: &str) -> Option<&MemoryTable> {
+        self.tables.iter().find(|table| table.title == title)
+    }
+
+    /// `28.79 MB allocated, 1.78 MB retained`
+    pub fn summary(&self) -> String {
+        let totals: Vec<String> = self
+            .totals
+            .iter()
+            .map(|total| format!("{} {}", format_bytes(total.bytes), total.label))
+            .collect();
+        totals.join(", ")
+    }
+
+    /// The report as plain text, for `/memory export`
+    pub fn to_text(&self, zone: DisplayZone) -> String {
+        let mut text = format!(
+            "{} report from {} at {}\n",
+            self.kind.label(),
+            self.process,
+            zone.date_time(self.time)
+        );
+        for total in &self.totals {
+            text.push_str(&format!("Total {}\n", total.describe()));
+        }
+        for table in &self.tables {
+            text.push_str(&format!("\n{}\n", table.title));
+            for row in &table.rows {
+                text.push_str(&format!(
+                    "{:>12}  {}\n",
+                    table.measure.format(row.amount),
+                    row.name
+                ));
+            }
+        }
+        text
+    }
+
+    /// Take one more line of the report; `false` if it isn't one
+    fn add_line(&mut self, section: &mut Section, line: &str) -> bool {
+        if line.trim().is_empty() {
+            return true;
+        }
+        match self.kind {
+            ReportKind::MemoryProfiler => self.add_profiler_line(section, line),
+            ReportKind::DerailedBundleMem => self.add_require_line(line),
+        }
+    }
+
+    fn add_profiler_line(&mut self, section: &mut Section, line: &str) -> bool {
+        let line = line.trim_end();
+        if let Some(total) = profiler_total(line) {
+            self.totals.push(total);
+            return true;
+        }
+        if is_rule(line) {
+            return true;
+        }
+        if let Some(caps) = table_title().captures(line) {
+            self.tables.push(MemoryTable {
+                title: line.trim().to_string(),
+                measure: if &caps[1] == "objects" {
+                    Measure::Objects
+                } else {
+                    Measure::Bytes
+                },
+                rows: Vec::new(),
+            });
+            *section = Section::Table;
+            return true;
+        }
+        if string_report_title().is_match(line) {
+            // Strings and where they were allocated; too long to be worth keeping
+            *section = Section::Skipped;
+            return true;
+        }
+        let Some(row) = parse_row(line) else {
+            return false;
+        };
+        match section {
+            Section::Table => {
+                if let Some(table) = self.tables.last_mut()
+                    && table.rows.len() < MAX_TABLE_ROWS
+                {
+                    table.rows.push(row);
+                }
+                true
+            }
+            Section::Skipped => true,
+            Section::Totals => false,
+        }
+    }
+
+    /// `  rails/all: 31.4414 MiB`; only gems required from the Gemfile
+    /// (the first level) make the table
+    fn add_require_line(&mut self, line: &str) -> bool {
+        let Some(caps) = require_row().captures(line) else {
+            return false;
+        };
+        if caps[1].len() == 2 {
+            if self.tables.is_empty() {
+                self.tables.push(MemoryTable {
+                    title: "memory by require".to_string(),
+                    measure: Measure::Bytes,
+                    rows: Vec::new(),
+                });
+            }
+            let table = &mut self.tables[0];
+            if table.rows.len() < MAX_TABLE_ROWS {
+                table.rows.push(MemoryRow {
+                    amount: mib(&caps[3]),
+                    name: caps[2].to_string(),
+                });
+            }
+        }
+        true
+    }
+}
+
+/// What a line did to a process's report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportChange {
+    Started,
+    Finished,
+}
+
+/// Where in a memory_profiler report the last line was
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Before the first table
+    Totals,
+    Table,
+    /// The string reports at the end
+    Skipped,
+}
+
+/// The last memory report of each process
+#[derive(Debug, Default)]
+pub struct MemoryReports {
+    reports: HashMap<String, MemoryReport>,
+    /// Processes still printing their report
+    open: HashMap<String, Section>,
+    /// Process whose report started last
+    latest: Option<String>,
+}
+
+impl MemoryReports {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Follow one line of `process`'s output, printed at `time`
+    pub fn observe(
+        &mut self,
+        process: &str,
+        line: &str,
+        time: DateTime<Local>,
+    ) -> Option<ReportChange> {
+        if let Some((kind, total)) = report_start(line) {
+            self.reports.insert(
+                process.to_string(),
+                MemoryReport::new(process, kind, time, total),
+            );
+            self.open.insert(process.to_string(), Section::Totals);
+            self.latest = Some(process.to_string());
+            return Some(ReportChange::Started);
+        }
+
+        let section = self.open.get_mut(process)?;
+        let report = self.reports.get_mut(process)?;
+        if report.add_line(section, line) {
+            return None;
+        }
+        report.complete = true;
+        self.open.remove(process);
+        Some(ReportChange::Finished)
+    }
+
+    /// The report started last, finished or not
+    pub fn latest(&self) -> Option<&MemoryReport> {
+        self.reports.get(self.latest.as_deref()?)
+    }
+
+    pub fn get(&self, process: &str) -> Option<&MemoryReport> {
+        self.reports.get(process)
+    }
+}
+
+/// The first line of a report, and the total it gives
+fn report_start(line: &str) -> Option<(ReportKind, MemoryTotal)> {
+    let line = line.trim();
+    if line.starts_with("Total allocated:") {
+        return Some((ReportKind::MemoryProfiler, profiler_total(line)?));
+    }
+    let caps = derailed_top().captures(line)?;
+    Some((
+        ReportKind::DerailedBundleMem,
+        MemoryTotal {
+            label: "required",
+            bytes: mib(&caps[1]),
+            objects: None,
+        },
+    ))
+}
+
+/// `Total retained:  1864296 bytes (16243 objects)` or `Total retained: 3.07 MB (21904 objects)`
+fn profiler_total(line: &str) -> Option<MemoryTotal> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let caps = PATTERN
+        .get_or_init(|| {
+            Regex::new(
+                r"^\s*Total (allocated|retained):\s+([\d.]+)\s*(bytes|B|kB|KB|MB|GB|TB)?(?:\s+\((\d+) objects\))?",
+            )
+            .unwrap()
+        })
+        .captures(line)?;
+    Some(MemoryTotal {
+        label: if &caps[1] == "allocated" {
+            "allocated"
+        } else {
+            "retained"
+        },
+        bytes: scaled_bytes(&caps[2], caps.get(3).map(|unit| unit.as_str())),
+        objects: caps
+            .get(4)
+            .and_then(|objects| objects.as_str().parse().ok()),
+    })
+}
+
+/// `   6120392  actionpack-7.1.3` or `  9.83 MB  sprockets-4.2.1`
+fn parse_row(line: &str) -> Option<MemoryRow> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let caps = PATTERN
+        .get_or_init(|| Regex::new(r"^\s*([\d.]+)(?:\s?(B|kB|KB|MB|GB|TB))?\s+(\S.*)$").unwrap())
+        .captures(line)?;
+    Some(MemoryRow {
+        amount: scaled_bytes(&caps[1], caps.get(2).map(|unit| unit.as_str())),
+        name: caps[3].trim().to_string(),
+    })
+}
+
+/// memory_profiler scales by 1000 when asked to (`scale_bytes: true`)
+fn scaled_bytes(value: &str, unit: Option<&str>) -> u64 {
+    let value: f64 = value.parse().unwrap_or(0.0);
+    let scale = match unit {
+        Some("kB" | "KB") => 1e3,
+        Some("MB") => 1e6,
+        Some("GB") => 1e9,
+        Some("TB") => 1e12,
+        _ => 1.0,
+    };
+    (value * scale).round() as u64
+}
+
+fn mib(value: &str) -> u64 {
+    (value.parse::<f64>().unwrap_or(0.0) * 1024.0 * 1024.0).round() as u64
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && line.chars().all(|c| c == '-')
+}
+
+/// `allocated memory by gem`, `retained objects by class`
+fn table_title() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:allocated|retained) (memory|objects) by (?:gem|file|location|class)\s*$",
+        )
+        .unwrap()
+    })
+}
+
+/// `Allocated String Report`
+fn string_report_title() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s*(?:Allocated|Retained) String Report\s*$").unwrap())
+}
+
+/// `TOP: 84.2148 MiB`
+fn derailed_top() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^TOP: ([\d.]+) MiB$").unwrap())
+}
+
+/// `    rails: 11.4102 MiB (Also required by: ...)`
+fn require_row() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^( +)(\S[^:]*): ([\d.]+) MiB").unwrap())
+}
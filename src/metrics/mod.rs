@@ -1,3 +1,4 @@
+use crate::diagnostics::{StoreKind, StoreUsage, total_bytes};
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -32,8 +33,11 @@ impl TimeSeries {
     }
 
     pub fn add(&mut self, value: f64) {
-        let now = Instant::now();
+        self.add_at(Instant::now(), value);
+    }
 
+    /// Add a point taken at `now`, dropping those older than `max_age` then
+    pub fn add_at(&mut self, now: Instant, value: f64) {
         // Remove old data points
         while let Some(point) = self.data.front() {
             if now.duration_since(point.timestamp) > self.max_age {
@@ -64,10 +68,35 @@ impl TimeSeries {
             .collect()
     }
 
+    pub fn approx_bytes(&self) -> usize {
+        self.data.capacity() * std::mem::size_of::<DataPoint>()
+    }
+
     pub fn get_all(&self) -> Vec<DataPoint> {
         self.data.iter().cloned().collect()
     }
 
+    /// The values alone, oldest first
+    pub fn values(&self) -> Vec<f64> {
+        self.data.iter().map(|p| p.value).collect()
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.data.back().map(|p| p.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
     pub fn average(&self) -> f64 {
         if self.data.is_empty() {
             return 0.0;
@@ -96,7 +125,10 @@ impl TimeSeries {
         if self.data.is_empty() {
             return 0.0;
         }
-        self.data.iter().map(|p| p.value).fold(f64::INFINITY, f64::min)
+        self.data
+            .iter()
+            .map(|p| p.value)
+            .fold(f64::INFINITY, f64::min)
     }
 }
 
@@ -136,6 +168,12 @@ impl EndpointStats {
         }
     }
 
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.path.len()
+            + self.durations.capacity() * std::mem::size_of::<f64>()
+    }
+
     pub fn avg_duration(&self) -> f64 {
         if self.count == 0 {
             0.0
@@ -177,6 +215,12 @@ pub struct AdvancedMetrics {
     total_errors: Arc<Mutex<u64>>,
 }
 
+impl Default for AdvancedMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AdvancedMetrics {
     pub fn new() -> Self {
         let retention = Duration::from_secs(3600); // 1 hour
@@ -244,7 +288,8 @@ impl AdvancedMetrics {
                 );
             }
 
-            stats.entry(path.clone())
+            stats
+                .entry(path.clone())
                 .or_insert_with(|| EndpointStats::new(path))
                 .add_request(duration);
         }
@@ -319,10 +364,52 @@ impl AdvancedMetrics {
     pub fn get_endpoint_stats(&self) -> Vec<EndpointStats> {
         let stats = self.endpoint_stats.lock().unwrap();
         let mut result: Vec<EndpointStats> = stats.values().cloned().collect();
-        result.sort_by(|a, b| b.count.cmp(&a.count));
+        result.sort_by_key(|s| std::cmp::Reverse(s.count));
         result
     }
 
+    /// Item counts and approximate sizes of the metrics stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        let stats = self.endpoint_stats.lock().unwrap();
+        vec![StoreUsage::new(
+            StoreKind::EndpointStats,
+            stats.len(),
+            stats
+                .iter()
+                .map(|(path, endpoint)| path.len() + endpoint.approx_bytes())
+                .sum(),
+        )]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        let series_bytes: usize = [
+            &self.request_rate,
+            &self.response_time,
+            &self.error_rate,
+            &self.cpu_usage,
+            &self.memory_usage,
+        ]
+        .iter()
+        .map(|series| series.lock().unwrap().approx_bytes())
+        .sum();
+        total_bytes(&self.store_usage()) + series_bytes
+    }
+
+    /// Keep only the `keep` most requested endpoints, returning how many were dropped
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::EndpointStats => {
+                let mut stats = self.endpoint_stats.lock().unwrap();
+                let mut endpoints: Vec<_> = stats.drain().collect();
+                endpoints.sort_by_key(|(_, e)| std::cmp::Reverse(e.count));
+                let removed = endpoints.len().saturating_sub(keep);
+                *stats = endpoints.into_iter().take(keep).collect();
+                removed
+            }
+            _ => 0,
+        }
+    }
+
     pub fn get_cpu_trend(&self, duration: Duration) -> Vec<DataPoint> {
         let series = self.cpu_usage.lock().unwrap();
         series.get_recent(duration)
@@ -0,0 +1,169 @@
+//! How many log lines the parsers understand, and samples of those they don't
+//!
+//! Every process line is counted as understood (a request, query, test,
+//! exception, frontend or instrumentation line, or noise like a blank line)
+//! or not. Lines nobody understood are kept as short, redacted *shapes* -
+//! digits collapsed so timestamps and ids don't make every line distinct -
+//! to show which log formats Caboose is missing.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::privacy::redact_secrets;
+
+/// File in the project's data directory that `[logs] dump_unparsed` writes
+pub const UNPARSED_SAMPLES_FILE: &str = "unparsed_samples.txt";
+
+/// Distinct unparsed shapes kept; later new shapes are only counted
+pub const UNPARSED_SAMPLES: usize = 50;
+
+/// Characters of a line kept in its shape
+pub const SHAPE_LENGTH: usize = 80;
+
+/// Lines seen from one process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessCoverage {
+    pub lines: u64,
+    pub unparsed: u64,
+}
+
+/// An unparsed line shape and how often it was seen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnparsedSample {
+    pub process: String,
+    pub shape: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseCoverage {
+    processes: BTreeMap<String, ProcessCoverage>,
+    samples: Vec<UnparsedSample>,
+}
+
+impl ParseCoverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a line of `process`, sampling it when no parser `understood` it
+    pub fn record(&mut self, process: &str, line: &str, understood: bool) {
+        let coverage = self.processes.entry(process.to_string()).or_default();
+        coverage.lines += 1;
+        if understood || is_noise(line) {
+            return;
+        }
+        coverage.unparsed += 1;
+
+        let shape = line_shape(line);
+        if let Some(sample) = self
+            .samples
+            .iter_mut()
+            .find(|sample| sample.process == process && sample.shape == shape)
+        {
+            sample.count += 1;
+        } else if self.samples.len() < UNPARSED_SAMPLES {
+            self.samples.push(UnparsedSample {
+                process: process.to_string(),
+                shape,
+                count: 1,
+            });
+        }
+    }
+
+    /// Lines counted, over all processes
+    pub fn lines(&self) -> u64 {
+        self.processes.values().map(|c| c.lines).sum()
+    }
+
+    /// Lines no parser understood, over all processes
+    pub fn unparsed(&self) -> u64 {
+        self.processes.values().map(|c| c.unparsed).sum()
+    }
+
+    /// Percent of lines understood, rounded down; `None` before any line
+    pub fn percent(&self) -> Option<u8> {
+        let lines = self.lines();
+        (lines > 0).then(|| ((lines - self.unparsed()) * 100 / lines) as u8)
+    }
+
+    /// Per-process counts, by process name
+    pub fn processes(&self) -> impl Iterator<Item = (&str, ProcessCoverage)> {
+        self.processes
+            .iter()
+            .map(|(name, coverage)| (name.as_str(), *coverage))
+    }
+
+    /// Sampled shapes, most frequent first
+    pub fn samples(&self) -> Vec<&UnparsedSample> {
+        let mut samples: Vec<&UnparsedSample> = self.samples.iter().collect();
+        samples.sort_by_key(|sample| std::cmp::Reverse(sample.count));
+        samples
+    }
+
+    /// Plain-text report of the coverage and every sample, for an issue
+    pub fn report(&self) -> String {
+        let mut out = match self.percent() {
+            Some(percent) => format!(
+                "Parse coverage: {}% ({} of {} lines unparsed)\n",
+                percent,
+                self.unparsed(),
+                self.lines()
+            ),
+            None => "Parse coverage: no lines yet\n".to_string(),
+        };
+        for (name, coverage) in self.processes() {
+            out.push_str(&format!(
+                "  {}: {} of {} lines unparsed\n",
+                name, coverage.unparsed, coverage.lines
+            ));
+        }
+        out.push_str(&format!(
+            "\nUnparsed line shapes (digits shown as 0, at most {}):\n",
+            UNPARSED_SAMPLES
+        ));
+        for sample in self.samples() {
+            out.push_str(&format!(
+                "{:>6}x  [{}] {}\n",
+                sample.count, sample.process, sample.shape
+            ));
+        }
+        out
+    }
+
+    /// Write [`report`](Self::report) to `path`
+    pub fn dump(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.report())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Lines with nothing to parse: blank, or only separators and box drawing
+pub fn is_noise(line: &str) -> bool {
+    !line.chars().any(char::is_alphanumeric)
+}
+
+/// A line's redacted start, with each run of digits collapsed to `0`
+pub fn line_shape(line: &str) -> String {
+    let redacted = redact_secrets(line.trim());
+    let mut shape = String::with_capacity(SHAPE_LENGTH);
+    let mut kept = 0;
+    let mut in_digits = false;
+    for c in redacted.chars() {
+        if c.is_ascii_digit() {
+            if in_digits {
+                continue;
+            }
+            in_digits = true;
+            shape.push('0');
+        } else {
+            in_digits = false;
+            shape.push(c);
+        }
+        kept += 1;
+        if kept == SHAPE_LENGTH {
+            break;
+        }
+    }
+    shape
+}
@@ -0,0 +1,317 @@
+//! Lines printed by APM / instrumentation gems found in the Gemfile
+//!
+//! Each parser only runs when its gem is in the Gemfile:
+//! - bullet: `USE eager loading detected` reports, which name the model and
+//!   association, become N+1 issues on the request they were logged for
+//! - rack-mini-profiler: timing summaries add to the request's breakdown
+//! - scout_apm / skylight: agent errors (bad key, failed start) become a
+//!   configuration warning
+//!
+//! Bullet and mini-profiler both log after Rails' `Completed` line, so their
+//! events refer back to a request that has just finished.
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::RailsLogParser;
+
+/// Instrumentation gems declared in the Gemfile
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstrumentationGems {
+    pub bullet: bool,
+    pub rack_mini_profiler: bool,
+    pub scout_apm: bool,
+    pub skylight: bool,
+}
+
+impl InstrumentationGems {
+    /// Gems from a Gemfile's contents; commented-out lines don't count
+    pub fn from_gemfile(gemfile: &str) -> Self {
+        let declares = |gem: &str| crate::rails::declares_gem(gemfile, gem);
+        Self {
+            bullet: declares("bullet"),
+            rack_mini_profiler: declares("rack-mini-profiler"),
+            scout_apm: declares("scout_apm"),
+            skylight: declares("skylight"),
+        }
+    }
+
+    /// Names of the gems found, in Gemfile spelling
+    pub fn names(&self) -> Vec<&'static str> {
+        [
+            (self.bullet, "bullet"),
+            (self.rack_mini_profiler, "rack-mini-profiler"),
+            (self.scout_apm, "scout_apm"),
+            (self.skylight, "skylight"),
+        ]
+        .into_iter()
+        .filter_map(|(found, name)| found.then_some(name))
+        .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names().is_empty()
+    }
+}
+
+/// A Bullet `USE eager loading detected` report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulletWarning {
+    /// Request line Bullet printed above the report, e.g. `GET /posts`
+    pub method: Option<String>,
+    pub path: Option<String>,
+    /// Model whose association was lazily loaded, e.g. `Post`
+    pub model: String,
+    /// Associations to eager load, e.g. `[:comments]`
+    pub associations: Vec<String>,
+}
+
+impl BulletWarning {
+    /// Bullet's own advice: `.includes([:comments])`
+    pub fn suggestion(&self) -> String {
+        let associations = self
+            .associations
+            .iter()
+            .map(|association| format!(":{}", association))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "Bullet: USE eager loading on {} => [{}]\n  Add to your query: .includes([{}])",
+            self.model, associations, associations
+        )
+    }
+}
+
+/// Timings from a rack-mini-profiler summary line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilerTimings {
+    pub method: Option<String>,
+    pub path: Option<String>,
+    /// Phase (`total`, `sql`, `render`, ...) to milliseconds
+    pub timings: HashMap<String, f64>,
+}
+
+/// An APM agent reporting that it couldn't start or report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentError {
+    /// Gem whose agent failed, e.g. `scout_apm`
+    pub gem: &'static str,
+    pub message: String,
+}
+
+impl AgentError {
+    pub fn warning(&self) -> String {
+        format!(
+            "{}: {} - check its configuration (key, environment)",
+            self.gem, self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstrumentationEvent {
+    Bullet(BulletWarning),
+    Profiler(ProfilerTimings),
+    AgentError(AgentError),
+}
+
+/// Parses the instrumentation gems' lines; stateful, since a Bullet report
+/// spans several lines
+#[derive(Debug, Default)]
+pub struct InstrumentationParser {
+    gems: InstrumentationGems,
+    /// Request line seen at the top of a Bullet report
+    bullet_request: Option<(String, String)>,
+    /// Inside a `USE eager loading detected` report, waiting for `Model => [...]`
+    bullet_pending: bool,
+    /// Agents already warned about; they retry and repeat themselves
+    warned_agents: HashSet<&'static str>,
+}
+
+fn bare_request_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| Regex::new(r"^(GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS) (/\S*)$").unwrap())
+}
+
+fn bullet_association_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // `  Post => [:comments]`, `  Admin::User => [:roles, :profile]`
+        Regex::new(r"^\s*([A-Z][\w:]*) => \[([^\]]*)\]\s*$").unwrap()
+    })
+}
+
+fn profiler_marker_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)\b(?:rack[-_ ]?)?mini[-_ ]?profiler\b").unwrap())
+}
+
+fn profiler_request_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| Regex::new(r"\b(GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS) (/\S*)").unwrap())
+}
+
+fn profiler_timing_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // `total: 182.3ms`, `sql=41.2ms`, `render: 96 ms`
+        Regex::new(r"\b(\w+)\s*[:=]\s*(\d+(?:\.\d+)?)\s*ms\b").unwrap()
+    })
+}
+
+fn agent_failure_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:error|failed|unable|invalid|missing|required|not (?:starting|started|configured))\b",
+        )
+        .unwrap()
+    })
+}
+
+fn agent_prefix_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // Agent log prefixes: `ERROR : [Scout] `, `[SKYLIGHT] [6.0.4] `
+        Regex::new(r"^(?:(?:DEBUG|INFO|WARN|ERROR|FATAL)\s*:\s*|\[[^\]]*\]\s*)+").unwrap()
+    })
+}
+
+fn scout_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)\[scout\]|scout[ _]?apm").unwrap())
+}
+
+fn skylight_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)\[skylight\]|skylight::").unwrap())
+}
+
+impl InstrumentationParser {
+    pub fn new(gems: InstrumentationGems) -> Self {
+        Self {
+            gems,
+            ..Self::default()
+        }
+    }
+
+    /// Feed one log line; returns an event once a report is complete
+    pub fn parse_line(&mut self, line: &str) -> Option<InstrumentationEvent> {
+        if self.gems.is_empty() {
+            return None;
+        }
+        let message = RailsLogParser::message(line);
+
+        if self.gems.bullet
+            && let Some(warning) = self.parse_bullet(message)
+        {
+            return Some(InstrumentationEvent::Bullet(warning));
+        }
+        if self.gems.rack_mini_profiler
+            && let Some(timings) = Self::parse_profiler(line)
+        {
+            return Some(InstrumentationEvent::Profiler(timings));
+        }
+        self.parse_agent_error(line, message)
+            .map(InstrumentationEvent::AgentError)
+    }
+
+    /// Bullet reports arrive a line at a time:
+    ///
+    /// ```text
+    /// user: alice
+    /// GET /posts
+    /// USE eager loading detected
+    ///   Post => [:comments]
+    ///   Add to your query: .includes([:comments])
+    /// Call stack
+    /// ```
+    fn parse_bullet(&mut self, message: &str) -> Option<BulletWarning> {
+        if let Some(caps) = bare_request_pattern().captures(message) {
+            self.bullet_request = Some((caps[1].to_string(), caps[2].to_string()));
+            return None;
+        }
+        // Bullet 4+ says `USE eager loading`; older versions `N+1 Query detected`
+        if message.contains("USE eager loading detected") || message.contains("N+1 Query detected")
+        {
+            self.bullet_pending = true;
+            return None;
+        }
+        // `AVOID eager loading` and counter cache reports aren't N+1s
+        if message.contains("AVOID eager loading detected")
+            || message.contains("Need Counter Cache")
+        {
+            self.bullet_pending = false;
+            return None;
+        }
+        if !self.bullet_pending {
+            return None;
+        }
+
+        let caps = bullet_association_pattern().captures(message)?;
+        self.bullet_pending = false;
+        let associations = caps[2]
+            .split(',')
+            .map(|association| association.trim().trim_start_matches(':').to_string())
+            .filter(|association| !association.is_empty())
+            .collect();
+        let (method, path) = self.bullet_request.take().unzip();
+        Some(BulletWarning {
+            method,
+            path,
+            model: caps[1].to_string(),
+            associations,
+        })
+    }
+
+    /// `[MiniProfiler] GET /posts total: 182.3ms sql: 41.2ms render: 96.0ms`
+    ///
+    /// Takes the whole line, since the marker is often a `[tag]`.
+    fn parse_profiler(line: &str) -> Option<ProfilerTimings> {
+        let marker = profiler_marker_pattern().find(line)?;
+        let rest = &line[marker.end()..];
+        let request = profiler_request_pattern().captures(rest);
+        let timings_from = request
+            .as_ref()
+            .map_or(0, |caps| caps.get(0).unwrap().end());
+
+        let timings: HashMap<String, f64> = profiler_timing_pattern()
+            .captures_iter(&rest[timings_from..])
+            .filter_map(|caps| {
+                let ms: f64 = caps[2].parse().ok()?;
+                Some((caps[1].to_lowercase(), ms))
+            })
+            .collect();
+        if timings.is_empty() {
+            return None;
+        }
+
+        Some(ProfilerTimings {
+            method: request.as_ref().map(|caps| caps[1].to_string()),
+            path: request.as_ref().map(|caps| caps[2].to_string()),
+            timings,
+        })
+    }
+
+    /// First failure each agent reports
+    fn parse_agent_error(&mut self, line: &str, message: &str) -> Option<AgentError> {
+        let gem = if self.gems.scout_apm && scout_pattern().is_match(line) {
+            "scout_apm"
+        } else if self.gems.skylight && skylight_pattern().is_match(line) {
+            "skylight"
+        } else {
+            return None;
+        };
+        if !agent_failure_pattern().is_match(message) || !self.warned_agents.insert(gem) {
+            return None;
+        }
+        let message = agent_prefix_pattern().replace(message.trim(), "");
+        Some(AgentError {
+            gem,
+            message: message.to_string(),
+        })
+    }
+}
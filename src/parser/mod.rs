@@ -1,7 +1,23 @@
+mod coverage;
+mod instrumentation;
+
+pub use coverage::{
+    ParseCoverage, ProcessCoverage, SHAPE_LENGTH, UNPARSED_SAMPLES, UNPARSED_SAMPLES_FILE,
+    UnparsedSample, is_noise, line_shape,
+};
+
+pub use instrumentation::{
+    AgentError, BulletWarning, InstrumentationEvent, InstrumentationGems, InstrumentationParser,
+    ProfilerTimings,
+};
+
+use crate::query::split_binds;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HttpRequest {
     pub method: String,
     pub path: String,
@@ -9,26 +25,155 @@ pub struct HttpRequest {
     pub duration: Option<f64>,
     pub controller: Option<String>,
     pub action: Option<String>,
+    /// Any other fields logged with the request, e.g. `bytes`, `content_type`, `db`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, String>,
+}
+
+/// `extra` key holding a request's id, from a Lograge field or a log tag
+pub const REQUEST_ID_KEY: &str = "request_id";
+
+/// The tag that looks like a request id: a UUID (Rails' default), a run of
+/// 16+ hex digits, or a `req-`/`req_` prefixed token
+pub fn request_id_tag(tags: &[String]) -> Option<&str> {
+    tags.iter().map(String::as_str).find(|tag| {
+        let hex_or_dash = tag.bytes().all(|b| b.is_ascii_hexdigit() || b == b'-');
+        let is_uuid = tag.len() == 36 && tag.matches('-').count() == 4 && hex_or_dash;
+        let is_hex = tag.len() >= 16 && tag.bytes().all(|b| b.is_ascii_hexdigit());
+        let is_prefixed = (tag.starts_with("req-") || tag.starts_with("req_"))
+            && tag.len() > 4
+            && tag
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        is_uuid || is_hex || is_prefixed
+    })
 }
 
-#[derive(Debug, Clone)]
+/// Keys that map onto `HttpRequest`'s own fields rather than `extra`
+const CORE_REQUEST_KEYS: &[&str] = &[
+    "method",
+    "path",
+    "status",
+    "duration",
+    "controller",
+    "action",
+];
+
+#[derive(Debug, Clone, Serialize)]
 pub struct SqlQuery {
     pub query: String,
     pub duration: Option<f64>,
     pub rows: Option<usize>,
     pub name: Option<String>, // e.g., "User Load"
+    /// Bind `(name, value)` pairs logged after the query, see [`split_binds`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub binds: Vec<(String, String)>,
+    /// Length in bytes of the line the query was cut from, when it was too
+    /// long to keep whole (see `max_line_length`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<usize>,
+    /// Id of the request that ran it, from the line's tags (see
+    /// [`request_id_tag`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// A `Rendered ...` line for a template, partial or layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewRender {
+    /// Template path as logged, e.g. `users/_row.html.erb`
+    pub template: String,
+    pub duration: f64,
+    pub allocations: Option<u64>,
+    /// Items rendered, for `Rendered collection of ... [50 times]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+impl ViewRender {
+    /// Partial instances this line stands for: the collection size, or 1
+    pub fn items(&self) -> usize {
+        self.count.unwrap_or(1)
+    }
+}
+
+/// What a `Cache ...` line did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CacheOperation {
+    Hit,
+    Miss,
+    Write,
+    Delete,
+}
+
+/// A Rails cache store line, e.g. `Cache read: views/users/1 (hit)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheEvent {
+    pub operation: CacheOperation,
+    pub key: String,
+}
+
+impl CacheEvent {
+    /// First path segment of the key, e.g. `views` for `views/users/1`
+    pub fn prefix(&self) -> &str {
+        self.key.split('/').next().unwrap_or(&self.key)
+    }
+}
+
+/// How a Turbo Stream showed up in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TurboDelivery {
+    /// `[ActionCable] Broadcasting to <stream>: ...`, once per broadcast
+    Broadcast,
+    /// `Turbo::StreamsChannel transmitting ... (via streamed from <stream>)`,
+    /// once per subscriber it reached
+    Transmission,
+}
+
+/// A `<turbo-stream>` sent over ActionCable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TurboStream {
+    /// Broadcasting name, e.g. `Z2lkOi8vYmxvZy9Qb3N0LzE:comments` for
+    /// `broadcast_append_to [post, :comments]`
+    pub stream: String,
+    /// `append`, `replace`, `remove`, ... when the payload shows it
+    pub action: Option<String>,
+    pub delivery: TurboDelivery,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum LogEvent {
     HttpRequest(HttpRequest),
     SqlQuery(SqlQuery),
+    ViewRender(ViewRender),
+    Cache(CacheEvent),
+    TurboStream(TurboStream),
+    /// Time the next request waited before Rails started on it, in ms
+    RequestQueueing(f64),
+    /// The `Parameters: {...}` line Rails logs for a request, as logged
+    RequestParams(String),
+    /// `ActionController::RoutingError (No route matches [GET] "/path")`
+    RouteNotFound {
+        method: String,
+        path: String,
+    },
     Error(String),
     RailsStartupError(RailsError),
     Info(String),
 }
 
-#[derive(Debug, Clone)]
+/// A parsed line with the `[tag]` groups that prefixed it
+///
+/// Tags come from `Rails.logger.tagged(...)` / `config.log_tags` (e.g. a
+/// request id) and semantic_logger, outermost first.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaggedEvent {
+    pub event: LogEvent,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub enum RailsError {
     PendingMigrations,
     DatabaseNotFound(String),
@@ -67,31 +212,95 @@ impl RailsLogParser {
         }
     }
 
-    fn http_start_pattern() -> &'static Regex {
+    /// semantic_logger's prefix, with any tags in the first group:
+    /// `2024-01-15 10:30:45.123456 I [12345:puma srv tp 001] [Payments] (1.2ms) ClassName -- `
+    fn semantic_logger_prefix() -> &'static Regex {
         static PATTERN: OnceLock<Regex> = OnceLock::new();
         PATTERN.get_or_init(|| {
-            // Match Rails 6/7 format: Started METHOD "PATH" for IP at TIMESTAMP
-            // Supports both quoted and unquoted paths
-            Regex::new(r#"Started (GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\s+"([^"]+)"|Started (GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\s+([^\s]+)"#).unwrap()
+            // Time, level letter, [pid:thread], tags, optional {named: tags},
+            // optional (duration), logger name, then " -- "
+            Regex::new(
+                r"^\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}\S*\s+[TDIWEF]\s+\[[^\]]*\]\s+((?:\[[^\[\]]+\]\s+)*)(?:\{[^}]*\}\s+)?(?:\([^)]*\)\s+)?[\w:]+\s+--\s+",
+            )
+            .unwrap()
         })
     }
 
-    fn http_start_keyvalue_pattern() -> &'static Regex {
+    /// Split the timestamp prefix and any `[tag]` groups off a line
+    ///
+    /// Returns the tags, the line without its timestamp prefix (tags kept) and
+    /// the bare message.
+    fn split_prefix(line: &str) -> (Vec<String>, &str, &str) {
+        if let Some(caps) = Self::semantic_logger_prefix().captures(line) {
+            let (mut tags, _) = Self::split_tags(caps.get(1).map_or("", |m| m.as_str()));
+            let message = &line[caps.get(0).unwrap().end()..];
+            let (inner, clean) = Self::split_tags(message);
+            tags.extend(inner);
+            return (tags, message, clean);
+        }
+
+        let untimed = Self::strip_timestamp_prefix(line);
+        let (tags, clean) = Self::split_tags(untimed);
+        (tags, untimed, clean)
+    }
+
+    /// Take leading `[tag]` groups off a line, e.g. `[Payments] [req-abc] Started GET ...`
+    ///
+    /// A tag must be non-empty, contain no brackets and be followed by a
+    /// space, so `[["id", 1]]` binds and the like are left alone.
+    fn split_tags(line: &str) -> (Vec<String>, &str) {
+        let mut tags = Vec::new();
+        let mut rest = line;
+        while let Some(inner) = rest.strip_prefix('[')
+            && let Some(end) = inner.find(']')
+            && end > 0
+            && !inner[..end].contains('[')
+            && let Some(after) = inner[end + 1..].strip_prefix(' ')
+        {
+            tags.push(inner[..end].to_string());
+            rest = after;
+        }
+        (tags, rest)
+    }
+
+    /// The `[tag]` groups prefixing a line, outermost first
+    pub fn tags(line: &str) -> Vec<String> {
+        Self::split_prefix(line).0
+    }
+
+    /// A line without its timestamp prefix and tags
+    pub fn message(line: &str) -> &str {
+        Self::split_prefix(line).2
+    }
+
+    fn logged_severity_pattern() -> &'static Regex {
         static PATTERN: OnceLock<Regex> = OnceLock::new();
         PATTERN.get_or_init(|| {
-            // Match key-value format: method=POST path=/users format=html
-            // Captures method and path from anywhere in the line
-            Regex::new(r"method=([A-Z]+).*?path=([^\s]+)").unwrap()
+            // An error or fatal severity in the logger's prefix:
+            // - E, [2024-01-15T10:30:45.043111 #6322] ERROR -- :
+            // - [FATAL 2018-07-01 11:55:04 65048] :
+            // - 2024-01-15 10:30:45.123456 E [12345:puma srv tp 001] (semantic_logger)
+            // - 2024-01-15 10:30:45 ERROR
+            Regex::new(
+                r"^(?:[EF],\s*\[[^\]]*\]\s+(?:ERROR|FATAL)\s+--|\[(?:ERROR|FATAL)\s[^\]]*\]\s*:|\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}\S*\s+(?:[EF]\s+\[|(?:ERROR|FATAL)\b))",
+            )
+            .unwrap()
         })
     }
 
-    fn lograge_pattern() -> &'static Regex {
+    fn leading_severity_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        // A message opening with a severity word: `ERROR: relation "widgets" does not exist`,
+        // `FATAL -- Exception in thread` (but not `ERRORS_TO_IGNORE` or `ExceptionNotifier`)
+        PATTERN.get_or_init(|| Regex::new(r"^\s*(?:ERROR|FATAL|Exception)\b").unwrap())
+    }
+
+    fn http_start_pattern() -> &'static Regex {
         static PATTERN: OnceLock<Regex> = OnceLock::new();
         PATTERN.get_or_init(|| {
-            // Match Lograge single-line format:
-            // method=GET path=/users ... status=200 duration=123.45
-            // Must have method, path, status, and duration to be a complete request
-            Regex::new(r"method=([A-Z]+).*?path=([^\s]+).*?status=(\d+).*?duration=(\d+(?:\.\d+)?)").unwrap()
+            // Match Rails 6/7 format: Started METHOD "PATH" for IP at TIMESTAMP
+            // Supports both quoted and unquoted paths
+            Regex::new(r#"Started (GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\s+"([^"]+)"|Started (GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\s+([^\s]+)"#).unwrap()
         })
     }
 
@@ -107,7 +316,43 @@ impl RailsLogParser {
             // - Completed 200 OK in 45ms (Views: 32.1ms | ActiveRecord: 8.9ms)
             // - Completed 302 Found in 25ms (ActiveRecord: 6.6ms | Allocations: 2809)
             // - Completed 200 OK in 104ms (Views: 90.8ms | ActiveRecord: 0.4ms)
-            Regex::new(r"Completed (\d+)\s+\w+\s+in\s+(\d+(?:\.\d+)?)ms").unwrap()
+            // - Completed 500 Internal Server Error in 12ms
+            Regex::new(r"Completed (\d+)\s+(?:[\w'-]+\s+)*?in\s+(\d+(?:\.\d+)?)ms").unwrap()
+        })
+    }
+
+    fn route_not_found_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN.get_or_init(|| {
+            // Dev and production both log the unmatched route:
+            // - ActionController::RoutingError (No route matches [GET] "/api/v2/users"):
+            Regex::new(r#"No route matches \[(\w+)\] "([^"]*)""#).unwrap()
+        })
+    }
+
+    fn completed_breakdown_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN.get_or_init(|| {
+            // Entries of the breakdown after the duration:
+            // (Views: 32.1ms | ActiveRecord: 8.9ms | Allocations: 2809)
+            Regex::new(r"(Views|ActiveRecord|Allocations): (\d+(?:\.\d+)?)").unwrap()
+        })
+    }
+
+    fn rendered_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN.get_or_init(|| {
+            // Match template render lines (Rails 5 logs a bare duration):
+            // - Rendered users/_row.html.erb (Duration: 1.2ms | Allocations: 312)
+            // - Rendered posts/index.html.erb within layouts/application (Duration: 12.3ms | ...)
+            // - Rendered layout layouts/application.html.erb (Duration: 8.4ms | Allocations: 3120)
+            // - Rendered collection of users/_row.html.erb [50 times] (Duration: 30.1ms | ...)
+            // - Rendered collection of posts/_post.html.erb [8 / 10 cache hits] (Duration: ...)
+            // - Rendered users/_row.html.erb (1.2ms)
+            Regex::new(
+                r"Rendered (?:layout |collection of )?(\S+)(?: within \S+)?(?: \[(\d+) times?\]| \[\d+ / (\d+) cache hits\])? \((?:Duration: )?(\d+(?:\.\d+)?)ms(?:[^)]*?Allocations: (\d+))?",
+            )
+            .unwrap()
         })
     }
 
@@ -130,35 +375,129 @@ impl RailsLogParser {
         })
     }
 
+    fn queueing_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN.get_or_init(|| {
+            // Queue time printed by APM / queueing middleware:
+            // - [request_queueing] 12.3ms
+            // - request_queueing: 12.3ms
+            // - request_queueing=12.3ms
+            Regex::new(r"\[?request_queueing\]?(?::\s*|=|\s+)(\d+(?:\.\d+)?)\s*ms\b").unwrap()
+        })
+    }
+
     pub fn parse_line(line: &str) -> Option<LogEvent> {
-        // Strip timestamp prefixes for Rails 6/7 compatibility
-        let clean_line = Self::strip_timestamp_prefix(line);
+        Self::parse_tagged(line).map(|tagged| tagged.event)
+    }
+
+    /// Parse a line, keeping the tags that prefixed it
+    pub fn parse_tagged(line: &str) -> Option<TaggedEvent> {
+        // Strip timestamp prefixes (Rails 6/7, semantic_logger) and tags
+        let (tags, untagged, clean_line) = Self::split_prefix(line);
+        let mut event = Self::parse_message(untagged, clean_line).or_else(|| {
+            Self::is_error(line, &tags, clean_line).then(|| LogEvent::Error(untagged.to_string()))
+        })?;
+        // `config.log_tags = [:request_id]` puts the id in a tag rather than a field
+        if let Some(id) = request_id_tag(&tags) {
+            match &mut event {
+                LogEvent::HttpRequest(req) if !req.extra.contains_key(REQUEST_ID_KEY) => {
+                    req.extra.insert(REQUEST_ID_KEY.to_string(), id.to_string());
+                }
+                LogEvent::SqlQuery(query) => query.request_id = Some(id.to_string()),
+                _ => {}
+            }
+        }
+        Some(TaggedEvent { event, tags })
+    }
+
+    /// Whether a line nothing more specific matched reports an error
+    ///
+    /// Only a severity in the logger's prefix (`E, [...] ERROR -- :`), an
+    /// `[ERROR]` / `[FATAL]` tag, or `ERROR`, `FATAL` or `Exception` as the
+    /// first word of the message count; the same words inside a message
+    /// (`ExceptionNotifier loaded`, an `error_logs` table) don't. SQL and
+    /// request lines never get here.
+    fn is_error(line: &str, tags: &[String], message: &str) -> bool {
+        let flagged = Self::logged_severity_pattern().is_match(line)
+            || tags.iter().any(|tag| tag == "ERROR" || tag == "FATAL")
+            || Self::leading_severity_pattern().is_match(message);
+        flagged && !Self::is_benign(message)
+    }
+
+    /// Summaries that carry a severity word without reporting a failure
+    ///
+    /// Phrases match as whole words, so `0 errors` isn't found in `10 errors`.
+    fn is_benign(message: &str) -> bool {
+        const BENIGN_PHRASES: &[&str] = &[
+            "0 errors",
+            "no errors",
+            "errors: 0",
+            "without errors",
+            "exception notifier loaded",
+            "exceptionnotifier loaded",
+        ];
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN
+            .get_or_init(|| {
+                let phrases: Vec<String> = BENIGN_PHRASES
+                    .iter()
+                    .map(|phrase| regex::escape(phrase))
+                    .collect();
+                Regex::new(&format!(r"(?i)\b(?:{})\b", phrases.join("|"))).unwrap()
+            })
+            .is_match(message)
+    }
 
+    /// Parse a line's message; `line` still has its tags, `clean_line` doesn't
+    fn parse_message(line: &str, clean_line: &str) -> Option<LogEvent> {
         // Check for Rails-specific startup errors first
-        if let Some(rails_error) = Self::detect_rails_error(clean_line) {
+        if let Some(rails_error) = Self::detect_rails_error(line) {
             return Some(LogEvent::RailsStartupError(rails_error));
         }
 
         // Check for Lograge single-line format FIRST (has status + duration)
         // This takes priority because it's a complete request in one line
-        if let Some(caps) = Self::lograge_pattern().captures(clean_line) {
-            let method = caps[1].to_string();
-            let path = caps[2].to_string();
-            let status: u16 = caps[3].parse().unwrap_or(0);
-            let duration: f64 = caps[4].parse().unwrap_or(0.0);
-
+        let fields = Self::parse_key_values(clean_line);
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        let keyvalue_start = match (field("method"), field("path")) {
+            (Some(method), Some(path))
+                if !method.is_empty()
+                    && method.bytes().all(|b| b.is_ascii_uppercase())
+                    && !path.is_empty() =>
+            {
+                Some((method.to_string(), path.to_string()))
+            }
+            _ => None,
+        };
+
+        if let Some((ref method, ref path)) = keyvalue_start
+            && let Some(status) = field("status").and_then(|s| s.parse::<u16>().ok())
+            && let Some(duration) = field("duration")
+                .and_then(|d| d.parse::<f64>().ok())
+                .filter(|d| d.is_finite() && *d >= 0.0)
+        {
             // For Lograge, we create a complete request immediately
-            // First emit a "start" event
             return Some(LogEvent::HttpRequest(HttpRequest {
                 method: method.clone(),
                 path: path.clone(),
                 status: Some(status),
                 duration: Some(duration),
-                controller: None,
-                action: None,
+                controller: field("controller").map(String::from),
+                action: field("action").map(String::from),
+                extra: Self::extra_fields(&fields),
             }));
         }
 
+        // Check for Turbo Streams first: their HTML payload can hold anything
+        if let Some(turbo) = Self::parse_turbo_stream(clean_line) {
+            return Some(LogEvent::TurboStream(turbo));
+        }
+
         // Check for HTTP request start (traditional format)
         if let Some(caps) = Self::http_start_pattern().captures(clean_line) {
             // Handle both quoted and unquoted path formats
@@ -172,25 +511,30 @@ impl RailsLogParser {
                 duration: None,
                 controller: None,
                 action: None,
+                extra: HashMap::new(),
             }));
         }
 
         // Check for HTTP request start (key-value format: method=POST path=/users)
         // Only if it doesn't have status/duration (otherwise Lograge would catch it)
-        if let Some(caps) = Self::http_start_keyvalue_pattern().captures(clean_line) {
-            let method = caps[1].to_string();
-            let path = caps[2].to_string();
-
+        if let Some((method, path)) = keyvalue_start {
             return Some(LogEvent::HttpRequest(HttpRequest {
                 method,
                 path,
                 status: None,
                 duration: None,
-                controller: None,
-                action: None,
+                controller: field("controller").map(String::from),
+                action: field("action").map(String::from),
+                extra: Self::extra_fields(&fields),
             }));
         }
 
+        // Check for queue time logged by middleware ahead of the request
+        // (`[request_queueing] 12ms` reads as a tag, so match the tagged line)
+        if let Some(ms) = Self::parse_queueing(line, &fields) {
+            return Some(LogEvent::RequestQueueing(ms));
+        }
+
         // Check for processing (controller#action)
         if let Some(caps) = Self::processing_pattern().captures(clean_line) {
             return Some(LogEvent::Info(format!(
@@ -199,10 +543,29 @@ impl RailsLogParser {
             )));
         }
 
+        // Check for the request's parameters (before SQL, which they could contain)
+        if let Some(params) = clean_line.trim_start().strip_prefix("Parameters: ") {
+            return Some(LogEvent::RequestParams(params.trim_end().to_string()));
+        }
+
         // Check for completed request
         if let Some(caps) = Self::completed_pattern().captures(clean_line) {
             let status: u16 = caps[1].parse().unwrap_or(0);
             let duration: f64 = caps[2].parse().unwrap_or(0.0);
+
+            // Use the same keys as Lograge so both formats display alike
+            let extra = Self::completed_breakdown_pattern()
+                .captures_iter(clean_line)
+                .map(|caps| {
+                    let key = match &caps[1] {
+                        "Views" => "view",
+                        "ActiveRecord" => "db",
+                        _ => "allocations",
+                    };
+                    (key.to_string(), caps[2].to_string())
+                })
+                .collect();
+
             return Some(LogEvent::HttpRequest(HttpRequest {
                 method: String::new(),
                 path: String::new(),
@@ -210,49 +573,223 @@ impl RailsLogParser {
                 duration: Some(duration),
                 controller: None,
                 action: None,
+                extra,
+            }));
+        }
+
+        // Check for routing errors before the generic error fallback
+        if let Some(caps) = Self::route_not_found_pattern().captures(clean_line) {
+            return Some(LogEvent::RouteNotFound {
+                method: caps[1].to_string(),
+                path: caps[2].to_string(),
+            });
+        }
+
+        // Check for template renders
+        if let Some(caps) = Self::rendered_pattern().captures(clean_line) {
+            return Some(LogEvent::ViewRender(ViewRender {
+                template: caps[1].to_string(),
+                duration: caps[4].parse().unwrap_or(0.0),
+                allocations: caps.get(5).and_then(|m| m.as_str().parse().ok()),
+                count: caps
+                    .get(2)
+                    .or_else(|| caps.get(3))
+                    .and_then(|m| m.as_str().parse().ok()),
             }));
         }
 
+        // Check for cache store lines before SQL, which keys could resemble
+        if let Some(cache) = Self::parse_cache_line(clean_line) {
+            return Some(LogEvent::Cache(cache));
+        }
+
         // Check for SQL query (Rails format with timing)
         if let Some(caps) = Self::sql_pattern().captures(clean_line) {
             let name = caps[1].trim().to_string();
             let duration: f64 = caps[2].parse().unwrap_or(0.0);
             // Strip Rails 7 query comments from the query text
             let query = Self::strip_query_comments(caps[0].to_string());
+            let (query, binds) = split_binds(&query);
 
             return Some(LogEvent::SqlQuery(SqlQuery {
-                query,
+                query: query.to_string(),
                 duration: Some(duration),
                 rows: None,
                 name: Some(name),
+                binds,
+                truncated: None,
+                request_id: None,
             }));
         }
 
         // Fallback to simple SQL pattern
         if let Some(_caps) = Self::sql_simple_pattern().captures(clean_line) {
             let query = Self::strip_query_comments(clean_line.to_string());
+            let (query, binds) = split_binds(&query);
             return Some(LogEvent::SqlQuery(SqlQuery {
-                query,
+                query: query.to_string(),
                 duration: None,
                 rows: None,
                 name: None,
+                binds,
+                truncated: None,
+                request_id: None,
             }));
         }
 
-        // Check for generic errors
-        if clean_line.contains("ERROR") || clean_line.contains("FATAL") || clean_line.contains("Exception") {
-            return Some(LogEvent::Error(clean_line.to_string()));
+        // Anything else is only an error if its severity says so; see `is_error`
+        None
+    }
+
+    /// Queue time in ms from rack-timeout or a `request_queueing` line
+    ///
+    /// rack-timeout logs `wait=` on every state change of a request; only the
+    /// `ready` line, written before the request reaches Rails, is used.
+    fn parse_queueing(line: &str, fields: &[(String, String)]) -> Option<f64> {
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        let ms = if field("source") == Some("rack-timeout") {
+            if field("state").is_some_and(|state| state != "ready") {
+                return None;
+            }
+            field("wait")?.strip_suffix("ms")?.parse::<f64>().ok()?
+        } else {
+            Self::queueing_pattern().captures(line)?[1].parse().ok()?
+        };
+        Some(ms).filter(|ms| ms.is_finite() && *ms >= 0.0)
+    }
+
+    /// Parse `Cache <operation>: <key> [...]`
+    ///
+    /// The key is the first token after the colon; a read's outcome is only
+    /// taken from a separate trailing `(hit)` / `(miss)`, so keys that contain
+    /// those words are not mistaken for outcomes. Reads with no outcome and
+    /// other operations (`exist?`, `read_multi`, ...) are not cache events.
+    fn parse_cache_line(line: &str) -> Option<CacheEvent> {
+        let (operation, rest) = line.trim_start().strip_prefix("Cache ")?.split_once(": ")?;
+        let mut tokens = rest.split_whitespace();
+        let key = tokens.next()?;
+        let outcome = tokens.last();
+
+        let operation = match (operation, outcome) {
+            ("read", Some("(hit)")) | ("fetch_hit", _) => CacheOperation::Hit,
+            ("read", Some("(miss)")) | ("generate", _) => CacheOperation::Miss,
+            ("write", _) => CacheOperation::Write,
+            ("delete", _) => CacheOperation::Delete,
+            _ => return None,
+        };
+
+        Some(CacheEvent {
+            operation,
+            key: key.to_string(),
+        })
+    }
+
+    /// A Turbo Stream broadcast or transmission, from its line without the
+    /// `[ActionCable]` tag:
+    ///
+    /// - `Broadcasting to Z2lk...: "<turbo-stream action=\"replace\" ...>"`
+    /// - `Turbo::StreamsChannel transmitting "<turbo-stream ..." (via streamed from Z2lk...)`
+    ///
+    /// Other ActionCable traffic (chat messages, JSON payloads) isn't one.
+    fn parse_turbo_stream(line: &str) -> Option<TurboStream> {
+        static BROADCAST: OnceLock<Regex> = OnceLock::new();
+        static TRANSMISSION: OnceLock<Regex> = OnceLock::new();
+        static ACTION: OnceLock<Regex> = OnceLock::new();
+        if !line.contains("turbo-stream") {
+            return None;
+        }
+        let line = line.trim();
+        let broadcast =
+            BROADCAST.get_or_init(|| Regex::new(r"^Broadcasting to (\S+): (.*)$").unwrap());
+        let transmission = TRANSMISSION.get_or_init(|| {
+            Regex::new(r"^[\w:]+ transmitting (.*) \(via streamed from (\S+)\)$").unwrap()
+        });
+        let (stream, payload, delivery) = if let Some(caps) = broadcast.captures(line) {
+            (caps.get(1)?, caps.get(2)?, TurboDelivery::Broadcast)
+        } else if let Some(caps) = transmission.captures(line) {
+            (caps.get(2)?, caps.get(1)?, TurboDelivery::Transmission)
+        } else {
+            return None;
+        };
+        let payload = payload.as_str();
+        if !payload.contains("turbo-stream") {
+            return None;
+        }
+        // The payload is inspected (`\"`) or JSON-escaped (`\\\"`) HTML
+        let action = ACTION.get_or_init(|| Regex::new(r#"action=\\*"?([\w-]+)"#).unwrap());
+        Some(TurboStream {
+            stream: stream.as_str().to_string(),
+            action: action.captures(payload).map(|caps| caps[1].to_string()),
+            delivery,
+        })
+    }
+
+    /// Split a Lograge-style line into its `key=value` pairs
+    ///
+    /// Values may be double-quoted to include spaces. Tokens that are not
+    /// `key=value` pairs (timestamps, tags, free text) are skipped.
+    fn parse_key_values(line: &str) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        let mut rest = line;
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+
+            let key_len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+                .unwrap_or(rest.len());
+            let key = &rest[..key_len];
+            let after_key = &rest[key_len..];
+
+            let Some(value_start) = after_key.strip_prefix('=').filter(|_| key_len > 0) else {
+                // Not a pair: skip to the next whitespace
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                rest = &rest[end..];
+                continue;
+            };
+
+            let (value, remaining) = match value_start.strip_prefix('"') {
+                Some(quoted) => match quoted.find('"') {
+                    Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                    None => (quoted, ""),
+                },
+                None => {
+                    let end = value_start
+                        .find(char::is_whitespace)
+                        .unwrap_or(value_start.len());
+                    (&value_start[..end], &value_start[end..])
+                }
+            };
+
+            pairs.push((key.to_string(), value.to_string()));
+            rest = remaining;
         }
 
-        None
+        pairs
+    }
+
+    /// Fields other than the core request fields
+    fn extra_fields(fields: &[(String, String)]) -> HashMap<String, String> {
+        fields
+            .iter()
+            .filter(|(key, _)| !CORE_REQUEST_KEYS.contains(&key.as_str()))
+            .cloned()
+            .collect()
     }
 
     /// Strip Rails 7 query comments like /*application='Blog',controller='articles'*/
     fn strip_query_comments(query: String) -> String {
         static QUERY_COMMENT: OnceLock<Regex> = OnceLock::new();
-        let re = QUERY_COMMENT.get_or_init(|| {
-            Regex::new(r"/\*.*?\*/").unwrap()
-        });
+        let re = QUERY_COMMENT.get_or_init(|| Regex::new(r"/\*.*?\*/").unwrap());
         re.replace_all(&query, "").trim().to_string()
     }
 
@@ -371,10 +908,11 @@ impl RailsLogParser {
         // Try to extract port number from error message
         let words: Vec<&str> = line.split_whitespace().collect();
         for (i, word) in words.iter().enumerate() {
-            if word.to_lowercase().contains("port") && i + 1 < words.len() {
-                if let Ok(port) = words[i + 1].trim_matches(|c: char| !c.is_numeric()).parse() {
-                    return port;
-                }
+            if word.to_lowercase().contains("port")
+                && i + 1 < words.len()
+                && let Ok(port) = words[i + 1].trim_matches(|c: char| !c.is_numeric()).parse()
+            {
+                return port;
             }
         }
         3000 // Default Rails port
@@ -0,0 +1,110 @@
+//! Path globs for picking endpoints out in config, e.g. `/api/*/status` or `/admin/**`
+//!
+//! `*` matches within a single segment and `**` any number of whole segments,
+//! none included. The query string and a trailing slash are ignored, so
+//! `/poll/*` matches `/poll/42?since=3` but not `/poll/42/events`.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// `**`
+    Any,
+    /// A literal segment, possibly with `*` wildcards inside
+    Pattern(String),
+}
+
+/// A parsed path glob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathGlob {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+impl PathGlob {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim();
+        if !pattern.starts_with('/') {
+            return Err(format!(
+                "Path pattern '{}' should start with / (e.g. /api/*/status)",
+                pattern
+            ));
+        }
+        let segments = segments(pattern)
+            .map(|segment| match segment {
+                "**" => Segment::Any,
+                _ => Segment::Pattern(segment.to_string()),
+            })
+            .collect();
+        Ok(Self {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    /// Parse each pattern, keeping the valid ones and an error for each other
+    pub fn parse_all<'a>(patterns: impl IntoIterator<Item = &'a str>) -> (Vec<Self>, Vec<String>) {
+        let mut globs = Vec::new();
+        let mut errors = Vec::new();
+        for pattern in patterns {
+            match Self::parse(pattern) {
+                Ok(glob) => globs.push(glob),
+                Err(e) => errors.push(e),
+            }
+        }
+        (globs, errors)
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let path: Vec<&str> = segments(path).collect();
+        matches_segments(&self.segments, &path)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl fmt::Display for PathGlob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+/// Whether any of `globs` matches `path`
+pub fn matches_any(globs: &[PathGlob], path: &str) -> bool {
+    globs.iter().any(|glob| glob.matches(path))
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+fn matches_segments(pattern: &[Segment], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((Segment::Any, rest)) => {
+            (0..=path.len()).any(|skip| matches_segments(rest, &path[skip..]))
+        }
+        Some((Segment::Pattern(segment), rest)) => {
+            path.split_first().is_some_and(|(first, path_rest)| {
+                matches_wildcards(segment, first) && matches_segments(rest, path_rest)
+            })
+        }
+    }
+}
+
+/// `*` matches any run of characters within the segment
+pub(crate) fn matches_wildcards(pattern: &str, text: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(text) = text.strip_prefix(prefix) else {
+        return false;
+    };
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .any(|start| matches_wildcards(rest, &text[start..]))
+}
@@ -0,0 +1,155 @@
+//! Analytics carried between runs in `.caboose/session.json`
+//!
+//! The TUI writes the query stats and health score history, slow queries,
+//! exception groups, completed requests (what the endpoint stats are built
+//! from) and test runs on exit and every [`SAVE_INTERVAL`]. With
+//! `[session] persist = true` or `--resume` they are read back at startup, so
+//! a restart, or a crash, doesn't lose them. Logs and processes are left out:
+//! the new run has its own.
+//!
+//! Timestamps are kept as ages at `saved_at` and aged by the time Caboose
+//! wasn't running when read back, so an exception last seen "3 mins ago"
+//! before an hour-long break shows as "1 hr ago" after it.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::context::RequestSnapshot;
+use crate::database::DatabaseSnapshot;
+use crate::exception::ExceptionSnapshot;
+use crate::serialization;
+use crate::session::SessionSources;
+use crate::stats::PerformanceStats;
+use crate::test::TestSnapshot;
+
+/// File in the project's data directory the analytics are kept in
+pub const PERSISTED_SESSION_FILE: &str = "session.json";
+
+/// Bumped when a change to the layout would misread older files
+pub const PERSISTED_FORMAT_VERSION: u32 = 1;
+
+/// How often the TUI saves while running
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistedAnalytics {
+    pub version: u32,
+    pub saved_at: DateTime<Local>,
+    #[serde(default)]
+    pub requests: RequestSnapshot,
+    #[serde(default)]
+    pub exceptions: ExceptionSnapshot,
+    #[serde(default)]
+    pub tests: TestSnapshot,
+    #[serde(default)]
+    pub database: DatabaseSnapshot,
+    #[serde(default)]
+    pub stats: PerformanceStats,
+}
+
+impl PersistedAnalytics {
+    /// The trackers' analytics as of now; `processes` and `logs` are ignored
+    pub fn capture(sources: &SessionSources) -> Self {
+        Self {
+            version: PERSISTED_FORMAT_VERSION,
+            saved_at: Local::now(),
+            requests: sources.requests.snapshot(),
+            exceptions: sources.exceptions.snapshot(),
+            tests: sources.tests.snapshot(),
+            database: sources.database.snapshot(),
+            stats: sources.stats.get_stats(),
+        }
+    }
+
+    /// Replace the trackers' analytics with the saved ones
+    ///
+    /// The schema and SQL dialect are the running project's, not the saved
+    /// ones: `db/schema.rb` may have changed in between.
+    pub fn restore_into(mut self, sources: &SessionSources) {
+        let live = sources.database.snapshot();
+        self.database.tables = live.tables;
+        self.database.dialect = live.dialect;
+
+        sources.requests.restore(self.requests);
+        sources.exceptions.restore(self.exceptions);
+        sources.tests.restore(self.tests);
+        sources.database.restore(self.database);
+        sources.stats.restore(self.stats);
+    }
+
+    /// Nothing was logged yet: saving it would only wipe the last run's file
+    pub fn is_empty(&self) -> bool {
+        self.stats.total_requests == 0
+            && self.database.stats.total_queries == 0
+            && self.exceptions.groups.is_empty()
+            && self.requests.completed.is_empty()
+            && self.tests.recent_runs.is_empty()
+            && self.tests.current_run.is_none()
+    }
+
+    /// Exception groups, completed requests and test runs, for the
+    /// "Resumed ..." line at startup
+    pub fn summary(&self) -> String {
+        let groups = self.exceptions.groups.len();
+        let requests = self.requests.completed.len();
+        let runs = self.tests.recent_runs.len();
+        format!(
+            "{} exception group{}, {} request{}, {} test run{}",
+            groups,
+            if groups == 1 { "" } else { "s" },
+            requests,
+            if requests == 1 { "" } else { "s" },
+            runs,
+            if runs == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Write to `path`, through a temporary file so a crash mid-write leaves
+    /// the previous save in place
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_vec(self)
+            .map_err(|e| format!("Failed to serialize session analytics: {}", e))?;
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, content)
+            .and_then(|_| fs::rename(&partial, path))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Read the analytics saved at `path`; `Ok(None)` when nothing was saved
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        Self::from_json(&content, Local::now()).map(Some)
+    }
+
+    /// Parse a saved file as read back at `now`
+    pub fn from_json(json: &[u8], now: DateTime<Local>) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+            saved_at: DateTime<Local>,
+        }
+
+        let invalid = |e: serde_json::Error| format!("Not a saved Caboose session: {}", e);
+        let header: Header = serde_json::from_slice(json).map_err(invalid)?;
+        if header.version > PERSISTED_FORMAT_VERSION {
+            return Err(format!(
+                "Saved session is format version {}, this Caboose reads up to {}",
+                header.version, PERSISTED_FORMAT_VERSION
+            ));
+        }
+        let downtime = (now - header.saved_at).to_std().unwrap_or_default();
+        serialization::aged_by(downtime, || serde_json::from_slice(json)).map_err(invalid)
+    }
+}
@@ -0,0 +1,187 @@
+//! Privacy mode: masking of personal data on screen, for screen sharing
+//!
+//! Masking is presentation-only - trackers and the log buffer keep the real
+//! values, and turning privacy off shows them again. While it's on, string
+//! literals in SQL, email-like and UUID-like tokens in paths, params and log
+//! lines, and git branches matching the configured patterns are replaced with
+//! [`MASK`]. Exports made meanwhile go through the same masking. Request ids
+//! look like UUIDs but identify a request, not a person, so they're kept.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::parser::{LogEvent, REQUEST_ID_KEY, RailsLogParser, request_id_tag};
+use crate::path_glob::matches_wildcards;
+use crate::query::{is_quoted_bind, mask_bind_values, mask_string_literals};
+use crate::session::SessionArchive;
+
+/// What a masked value is shown as
+pub const MASK: &str = "•••";
+
+#[derive(Debug, Clone, Default)]
+pub struct Privacy {
+    enabled: bool,
+    /// Branch names to hide, `*` matching any run of characters
+    branch_patterns: Vec<String>,
+}
+
+impl Privacy {
+    pub fn new(enabled: bool, branch_patterns: Vec<String>) -> Self {
+        Self {
+            enabled,
+            branch_patterns,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// A query with its string literals, string bind values and personal
+    /// tokens masked
+    pub fn sql<'a>(&self, sql: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(sql);
+        }
+        let masked = mask_string_literals(&mask_bind_values(sql, MASK), MASK);
+        Cow::Owned(mask_tokens(&masked).into_owned())
+    }
+
+    /// A bind value as logged: strings masked whole, others for personal tokens
+    pub fn bind_value<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(value);
+        }
+        if is_quoted_bind(value) {
+            return Cow::Owned(format!("\"{}\"", MASK));
+        }
+        mask_tokens(value)
+    }
+
+    /// A path, params or other text with email-like and UUID-like tokens masked
+    pub fn text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(text);
+        }
+        mask_tokens(text)
+    }
+
+    /// A log line, masked as SQL when it logs a query
+    pub fn log_line<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(content);
+        }
+        let tagged = RailsLogParser::parse_tagged(content);
+        let tags = match &tagged {
+            Some(tagged) => tagged.tags.clone(),
+            None => RailsLogParser::tags(content),
+        };
+        let request_id = match tagged.as_ref().map(|tagged| &tagged.event) {
+            Some(LogEvent::HttpRequest(req)) => req.extra.get(REQUEST_ID_KEY).map(String::as_str),
+            _ => request_id_tag(&tags),
+        };
+        match tagged.as_ref().map(|tagged| &tagged.event) {
+            Some(LogEvent::SqlQuery(_)) => {
+                let masked = mask_string_literals(&mask_bind_values(content, MASK), MASK);
+                Cow::Owned(mask_tokens_except(&masked, request_id).into_owned())
+            }
+            _ => mask_tokens_except(content, request_id),
+        }
+    }
+
+    /// The branch name, or [`MASK`] when it matches a configured pattern
+    pub fn branch<'a>(&self, branch: &'a str) -> Cow<'a, str> {
+        if self.enabled
+            && self
+                .branch_patterns
+                .iter()
+                .any(|pattern| matches_wildcards(pattern, branch))
+        {
+            Cow::Borrowed(MASK)
+        } else {
+            Cow::Borrowed(branch)
+        }
+    }
+
+    /// Mask what a saved session shows of logs, requests and queries
+    pub fn archive(&self, archive: &mut SessionArchive) {
+        if !self.enabled {
+            return;
+        }
+        for log in &mut archive.logs {
+            log.content = self.log_line(&log.content).into_owned();
+        }
+        for request in &mut archive.requests.completed {
+            if let Some(path) = request.context.path.as_mut() {
+                *path = self.text(path).into_owned();
+            }
+            for query in &mut request.context.queries {
+                query.raw_query = self.sql(&query.raw_query).into_owned();
+                for (_, value) in &mut query.binds {
+                    *value = self.bind_value(value).into_owned();
+                }
+            }
+            for issue in &mut request.n_plus_one_issues {
+                issue.sample_query = self.sql(&issue.sample_query).into_owned();
+            }
+            for (key, value) in request.extra.iter_mut() {
+                if key != REQUEST_ID_KEY {
+                    *value = self.text(value).into_owned();
+                }
+            }
+        }
+        for query in &mut archive.database.slow_queries {
+            query.query = self.sql(&query.query).into_owned();
+        }
+    }
+}
+
+/// A log line with secrets and personal data masked whether or not privacy
+/// mode is on, for text Caboose keeps aside or writes to disk
+///
+/// Values of password-, secret-, token- and key-like fields, bearer tokens
+/// and email/UUID-like tokens are replaced with [`MASK`].
+pub fn redact_secrets(content: &str) -> Cow<'_, str> {
+    static SECRET: OnceLock<Regex> = OnceLock::new();
+    let secret = SECRET.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(\b[\w-]*(?:password|passwd|secret|token|api_?key|access_?key|private_?key|authorization|cookie)[\w-]*["']?\s*(?:=>|[:=])\s*)((?:bearer|basic)\s+[^\s,;&}\]]+|"[^"]*"|'[^']*'|[^\s,;&}\]]+)|(\bbearer\s+)[\w.~+/=-]+"#,
+        )
+        .unwrap()
+    });
+    let redacted = secret.replace_all(content, |caps: &regex::Captures| {
+        let key = caps.get(1).or(caps.get(3)).map_or("", |m| m.as_str());
+        format!("{}{}", key, MASK)
+    });
+    Cow::Owned(mask_tokens(&redacted).into_owned())
+}
+
+/// Replace email-like and UUID-like tokens, URL-encoded `@` included
+fn mask_tokens(text: &str) -> Cow<'_, str> {
+    mask_tokens_except(text, None)
+}
+
+/// [`mask_tokens`], leaving `keep` (a request id) as it is
+fn mask_tokens_except<'a>(text: &'a str, keep: Option<&str>) -> Cow<'a, str> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)[a-z0-9._%+-]+(?:@|%40)[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}|\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+        )
+        .unwrap()
+    });
+    pattern.replace_all(text, |caps: &regex::Captures| {
+        let token = &caps[0];
+        if keep == Some(token) {
+            token.to_string()
+        } else {
+            MASK.to_string()
+        }
+    })
+}
@@ -0,0 +1,329 @@
+//! ANSI escapes in process output: keep what's useful, drop what's harmful
+//!
+//! SGR colors become style runs over the cleaned text, so the Logs view can
+//! honor a tool's own colors, and OSC 8 hyperlinks (file paths from vite,
+//! next, ...) become link spans. Cursor movement, screen clears, titles and
+//! anything unrecognized are dropped; forward cursor moves some CLIs use to
+//! line up columns turn into spaces so the alignment survives. Malformed or
+//! unterminated sequences are dropped, never a panic.
+
+use serde::{Deserialize, Serialize};
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{7}';
+
+/// Widest gap a forward cursor move is expanded to
+const MAX_CURSOR_SKIP: usize = 256;
+
+/// A terminal color: the 256-color palette (0-15 being the basic and bright
+/// colors) or 24-bit RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnsiColor {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// SGR attributes in effect for a stretch of text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnsiStyle {
+    pub fg: Option<AnsiColor>,
+    pub bg: Option<AnsiColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl AnsiStyle {
+    fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Styled byte range of the cleaned line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyledRun {
+    pub start: usize,
+    pub end: usize,
+    pub style: AnsiStyle,
+}
+
+/// OSC 8 hyperlink over a byte range of the cleaned line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hyperlink {
+    pub start: usize,
+    pub end: usize,
+    pub url: String,
+}
+
+/// What the escapes in a line said, relative to its cleaned text
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogMarkup {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub styles: Vec<StyledRun>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Hyperlink>,
+}
+
+impl LogMarkup {
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty() && self.links.is_empty()
+    }
+
+    /// Drop everything past `len` bytes, e.g. after a line was cut
+    pub fn clip(&mut self, len: usize) {
+        self.styles.retain_mut(|run| {
+            run.end = run.end.min(len);
+            run.start < run.end
+        });
+        self.links.retain_mut(|link| {
+            link.end = link.end.min(len);
+            link.start < link.end
+        });
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.styles.len() * std::mem::size_of::<StyledRun>()
+            + self
+                .links
+                .iter()
+                .map(|link| std::mem::size_of::<Hyperlink>() + link.url.len())
+                .sum::<usize>()
+    }
+}
+
+/// Strip escapes from `line`, returning the text and, when there was any,
+/// the colors and links they described
+pub fn parse_ansi(line: &str) -> (String, Option<LogMarkup>) {
+    if !line.contains(|c: char| c.is_control() && c != '\t') {
+        return (line.to_string(), None);
+    }
+
+    let mut parser = Parser::default();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            parser.push_char(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                let mut params = String::new();
+                let mut final_byte = None;
+                for c in chars.by_ref() {
+                    if ('\u{40}'..='\u{7e}').contains(&c) {
+                        final_byte = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if let Some(final_byte) = final_byte {
+                    parser.csi(&params, final_byte);
+                }
+            }
+            Some(']') => {
+                let mut body = String::new();
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC {
+                        // String terminator `ESC \`
+                        chars.next_if_eq(&'\\');
+                        break;
+                    }
+                    body.push(c);
+                }
+                parser.osc(&body);
+            }
+            // Character set designation: `ESC ( B`
+            Some('(' | ')' | '*' | '+') => {
+                chars.next();
+            }
+            // Any other two-character escape (`ESC 7`, `ESC c`, ...)
+            _ => {}
+        }
+    }
+    parser.finish()
+}
+
+#[derive(Default)]
+struct Parser {
+    text: String,
+    markup: LogMarkup,
+    style: AnsiStyle,
+    style_start: usize,
+    link: Option<(usize, String)>,
+}
+
+impl Parser {
+    fn push_char(&mut self, c: char) {
+        // Tabs stay; other control characters (\r, backspace, ...) would
+        // only corrupt the TUI
+        if c == '\t' || !c.is_control() {
+            self.text.push(c);
+        }
+    }
+
+    fn csi(&mut self, params: &str, final_byte: char) {
+        match final_byte {
+            'm' => {
+                let mut style = self.style;
+                apply_sgr(&mut style, params);
+                self.set_style(style);
+            }
+            // Cursor forward: keep the gap it leaves
+            'C' => {
+                let skip = first_param(params).unwrap_or(1).max(1);
+                self.pad(skip);
+            }
+            // Cursor to column (1-based): pad out to it
+            'G' => {
+                let column = first_param(params).unwrap_or(1).max(1) - 1;
+                let width = self.text.chars().count();
+                self.pad(column.saturating_sub(width));
+            }
+            // Cursor up/down/back, erase, scroll, modes, ... are dropped
+            _ => {}
+        }
+    }
+
+    fn pad(&mut self, spaces: usize) {
+        self.text
+            .extend(std::iter::repeat_n(' ', spaces.min(MAX_CURSOR_SKIP)));
+    }
+
+    fn osc(&mut self, body: &str) {
+        // `8;params;uri` opens a link, `8;;` closes it; titles etc. are dropped
+        let Some(rest) = body.strip_prefix("8;") else {
+            return;
+        };
+        let url = rest.split_once(';').map_or("", |(_, url)| url);
+        self.close_link();
+        if !url.is_empty() {
+            self.link = Some((self.text.len(), url.to_string()));
+        }
+    }
+
+    fn set_style(&mut self, style: AnsiStyle) {
+        if style == self.style {
+            return;
+        }
+        self.close_run();
+        self.style = style;
+        self.style_start = self.text.len();
+    }
+
+    fn close_run(&mut self) {
+        let end = self.text.len();
+        if !self.style.is_plain() && self.style_start < end {
+            self.markup.styles.push(StyledRun {
+                start: self.style_start,
+                end,
+                style: self.style,
+            });
+        }
+    }
+
+    fn close_link(&mut self) {
+        if let Some((start, url)) = self.link.take()
+            && start < self.text.len()
+        {
+            self.markup.links.push(Hyperlink {
+                start,
+                end: self.text.len(),
+                url,
+            });
+        }
+    }
+
+    fn finish(mut self) -> (String, Option<LogMarkup>) {
+        self.close_run();
+        self.close_link();
+        let markup = (!self.markup.is_empty()).then_some(self.markup);
+        (self.text, markup)
+    }
+}
+
+fn first_param(params: &str) -> Option<usize> {
+    params.split(';').next()?.parse().ok()
+}
+
+/// Apply a `ESC [ ... m` parameter list to `style`
+fn apply_sgr(style: &mut AnsiStyle, params: &str) {
+    let groups: Vec<&str> = params.split(';').collect();
+    let mut i = 0;
+    while i < groups.len() {
+        let group = groups[i];
+        i += 1;
+
+        // Colon sub-parameters carry a whole colour: `38:5:n`, `38:2::r:g:b`
+        if let Some((code, rest)) = group.split_once(':') {
+            let parts: Vec<&str> = rest.split(':').collect();
+            match code {
+                "38" => style.fg = color_from(&parts).or(style.fg),
+                "48" => style.bg = color_from(&parts).or(style.bg),
+                // `4:0` turns off the underline, `4:3` (curly) and the rest set it
+                "4" => style.underline = rest != "0",
+                _ => {}
+            }
+            continue;
+        }
+
+        let code: u16 = if group.is_empty() {
+            0
+        } else {
+            match group.parse() {
+                Ok(code) => code,
+                Err(_) => continue,
+            }
+        };
+        match code {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            30..=37 => style.fg = Some(AnsiColor::Indexed((code - 30) as u8)),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(AnsiColor::Indexed((code - 40) as u8)),
+            49 => style.bg = None,
+            90..=97 => style.fg = Some(AnsiColor::Indexed((code - 90 + 8) as u8)),
+            100..=107 => style.bg = Some(AnsiColor::Indexed((code - 100 + 8) as u8)),
+            // `38;5;n` / `38;2;r;g;b` take the following groups
+            38 | 48 => {
+                let taken = match groups.get(i) {
+                    Some(&"5") => 2,
+                    Some(&"2") => 4,
+                    _ => 0,
+                };
+                let parts = &groups[i..(i + taken).min(groups.len())];
+                let color = color_from(parts);
+                if code == 38 {
+                    style.fg = color.or(style.fg);
+                } else {
+                    style.bg = color.or(style.bg);
+                }
+                i += parts.len();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `5, n` or `2, r, g, b` (with an optional colour-space id before r)
+fn color_from(parts: &[&str]) -> Option<AnsiColor> {
+    let byte = |part: &str| part.parse::<u8>().ok();
+    match parts {
+        ["5", n] => byte(n).map(AnsiColor::Indexed),
+        ["2", r, g, b] | ["2", _, r, g, b] => Some(AnsiColor::Rgb(byte(r)?, byte(g)?, byte(b)?)),
+        _ => None,
+    }
+}
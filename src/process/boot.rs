@@ -0,0 +1,187 @@
+//! Boot-time tracking: how long each process takes from spawn to ready
+//!
+//! A process counts as ready at the first line that a server or bundler
+//! prints once it can take work (Puma's "Listening on", webpack's "compiled
+//! successfully", ...). Each new start time begins another measurement.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::{ProcessInfo, ProcessStatus, Readiness};
+
+/// Boot times kept per process
+pub const BOOT_HISTORY_LEN: usize = 20;
+
+/// How long a process with no recorded boots may take to become ready
+pub const DEFAULT_BOOT_BUDGET: Duration = Duration::from_secs(90);
+
+/// Shortest budget, however quickly a process booted before
+pub const MIN_BOOT_BUDGET: Duration = Duration::from_secs(30);
+
+/// Budget as a multiple of the slowest recorded boot
+const BOOT_BUDGET_FACTOR: f64 = 3.0;
+
+/// Commands that print one of the ready markers once they're up
+const READY_COMMANDS: [&str; 10] = [
+    "rails s",
+    "rails server",
+    "puma",
+    "unicorn",
+    "sidekiq",
+    "vite",
+    "webpack",
+    "ng serve",
+    "next dev",
+    "npm run dev",
+];
+
+/// Lines (matched case-insensitively) that mean a process finished booting
+const READY_MARKERS: [&str; 6] = [
+    "listening on",
+    "use ctrl-c to stop",
+    "compiled successfully",
+    "webpack compiled",
+    "ready in",
+    "starting processing",
+];
+
+/// Whether a log line says its process is ready
+pub fn is_ready_line(content: &str) -> bool {
+    let lower = content.to_lowercase();
+    READY_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+/// Whether a command is a server or bundler known to print a ready line
+///
+/// Other processes (asset watchers, scripts) may never print one, so they
+/// aren't held to a boot budget until they have.
+pub fn expects_ready_line(command: &str) -> bool {
+    READY_COMMANDS.iter().any(|known| command.contains(known))
+}
+
+/// A process's current run
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    started: Instant,
+    ready: bool,
+    /// Monotonic time the machine spent suspended while it booted
+    asleep: Duration,
+}
+
+impl Run {
+    fn boot_time(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+            .saturating_sub(self.asleep)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BootTimes {
+    /// Each process's current run
+    runs: HashMap<String, Run>,
+    /// Boot durations in seconds, oldest first
+    history: HashMap<String, VecDeque<f64>>,
+}
+
+impl BootTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note the current start time of each running process
+    pub fn track(&mut self, processes: &[ProcessInfo]) {
+        for process in processes {
+            if process.status != ProcessStatus::Running {
+                continue;
+            }
+            let Some(started) = process.start_time else {
+                continue;
+            };
+            let is_new_run = self
+                .runs
+                .get(&process.name)
+                .is_none_or(|run| run.started != started);
+            if is_new_run {
+                self.runs.insert(
+                    process.name.clone(),
+                    Run {
+                        started,
+                        ready: false,
+                        asleep: Duration::ZERO,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Record a boot time if `content` is the first ready line of the current run
+    pub fn observe(&mut self, process: &str, content: &str, at: Instant) {
+        let Some(run) = self.runs.get_mut(process) else {
+            return;
+        };
+        if run.ready || !is_ready_line(content) {
+            return;
+        }
+        run.ready = true;
+
+        let history = self.history.entry(process.to_string()).or_default();
+        history.push_back(run.boot_time(at).as_secs_f64());
+        if history.len() > BOOT_HISTORY_LEN {
+            history.pop_front();
+        }
+    }
+
+    /// Leave the time the machine slept out of the boots it interrupted
+    ///
+    /// Where the monotonic clock keeps counting through a suspension, a
+    /// process booting when the lid closed would otherwise come back slow.
+    pub fn resume(&mut self, asleep: Duration) {
+        for run in self.runs.values_mut().filter(|run| !run.ready) {
+            run.asleep += asleep;
+        }
+    }
+
+    /// Boot durations in seconds for `process`, oldest first
+    pub fn history(&self, process: &str) -> Vec<f64> {
+        self.history
+            .get(process)
+            .map(|h| h.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// How long `process` may take to boot before it counts as slow
+    pub fn boot_budget(&self, process: &str) -> Duration {
+        let slowest = self
+            .history
+            .get(process)
+            .and_then(|h| h.iter().copied().reduce(f64::max));
+        match slowest {
+            Some(secs) => Duration::from_secs_f64(secs * BOOT_BUDGET_FACTOR).max(MIN_BOOT_BUDGET),
+            None => DEFAULT_BOOT_BUDGET,
+        }
+    }
+
+    /// Readiness of each running process's current run at `now`
+    pub fn readiness(&self, processes: &[ProcessInfo], now: Instant) -> HashMap<String, Readiness> {
+        processes
+            .iter()
+            .filter(|process| process.status == ProcessStatus::Running)
+            .filter_map(|process| {
+                let run = self.runs.get(&process.name)?;
+                let readiness = if run.ready {
+                    Readiness::Ready
+                } else if self.history.contains_key(&process.name)
+                    || expects_ready_line(&process.command)
+                {
+                    Readiness::Booting {
+                        elapsed: run.boot_time(now),
+                        budget: self.boot_budget(&process.name),
+                    }
+                } else {
+                    Readiness::Unknown
+                };
+                Some((process.name.clone(), readiness))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,81 @@
+//! What a log line is about, for the Logs view's filter chips
+//!
+//! Decided once when the line is added, from what the parsers already made
+//! of it, and kept on the [`LogLine`](super::LogLine).
+
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::FrontendLogEvent;
+use crate::parser::LogEvent;
+
+/// A Logs view filter chip, and the lines it toggles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogCategory {
+    Sql,
+    Http,
+    Error,
+    Frontend,
+    #[default]
+    Other,
+}
+
+impl LogCategory {
+    /// Chip order; chip `n` is toggled with key `n`
+    pub const ALL: [LogCategory; 5] = [
+        LogCategory::Sql,
+        LogCategory::Http,
+        LogCategory::Error,
+        LogCategory::Frontend,
+        LogCategory::Other,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogCategory::Sql => "SQL",
+            LogCategory::Http => "HTTP",
+            LogCategory::Error => "Errors",
+            LogCategory::Frontend => "Frontend",
+            LogCategory::Other => "Other",
+        }
+    }
+
+    /// Position in [`ALL`](Self::ALL)
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Category of a line from its Rails `event`, whether the exception
+    /// tracker took it (`exception`) and its `frontend` event
+    ///
+    /// SQL and request lines win over everything else, so a query with
+    /// `'ERROR'` in a literal stays SQL; frontend compile errors are errors.
+    pub fn classify(
+        event: Option<&LogEvent>,
+        exception: bool,
+        frontend: Option<&FrontendLogEvent>,
+    ) -> Self {
+        match event {
+            Some(LogEvent::SqlQuery(_)) => return LogCategory::Sql,
+            Some(LogEvent::HttpRequest(_) | LogEvent::RequestParams(_)) => {
+                return LogCategory::Http;
+            }
+            Some(
+                LogEvent::Error(_)
+                | LogEvent::RailsStartupError(_)
+                | LogEvent::RouteNotFound { .. },
+            ) => return LogCategory::Error,
+            _ => {}
+        }
+        if exception {
+            return LogCategory::Error;
+        }
+        match frontend {
+            Some(FrontendLogEvent::CompileError(_) | FrontendLogEvent::Error { .. }) => {
+                LogCategory::Error
+            }
+            Some(_) => LogCategory::Frontend,
+            None => LogCategory::Other,
+        }
+    }
+}
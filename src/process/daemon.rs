@@ -0,0 +1,134 @@
+//! Detection of processes that daemonize
+//!
+//! `rails server -d` and some npm wrappers fork and exit right away, leaving the
+//! real server running outside Caboose. We notice this when the spawned child
+//! exits cleanly soon after starting and its port then starts accepting
+//! connections, and look up the PID that owns the listening socket so it can
+//! still be stopped.
+
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use sysinfo::{Pid, Signal, System};
+
+/// A clean exit within this long after spawning looks like a fork-and-exit
+pub const DAEMONIZE_WINDOW: Duration = Duration::from_secs(5);
+
+/// How long to wait for the port to be bound after the child exits
+pub const PORT_WAIT: Duration = Duration::from_secs(10);
+
+/// Whether something accepts connections on `port` locally
+pub fn is_port_listening(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()
+}
+
+/// PID of the process listening on `port`, if it can be found
+pub fn find_listening_pid(port: u16) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    if let Some(pid) = find_listening_pid_in_proc(port) {
+        return Some(pid);
+    }
+
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-t"])
+        .output()
+        .ok()?;
+    parse_lsof_pids(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .next()
+}
+
+/// Match the port's listening socket inode against every process's open fds
+#[cfg(target_os = "linux")]
+fn find_listening_pid_in_proc(port: u16) -> Option<u32> {
+    use std::fs;
+
+    let mut inodes = Vec::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(content) = fs::read_to_string(table) {
+            inodes.extend(listening_inodes(&content, port));
+        }
+    }
+    if inodes.is_empty() {
+        return None;
+    }
+
+    let targets: Vec<String> = inodes.iter().map(|i| format!("socket:[{}]", i)).collect();
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(link) = fs::read_link(fd.path())
+                && targets.iter().any(|t| link.as_os_str() == t.as_str())
+            {
+                return Some(pid);
+            }
+        }
+    }
+    None
+}
+
+/// Socket inodes in LISTEN state on `port`, from `/proc/net/tcp` or `tcp6`
+pub fn listening_inodes(proc_net_tcp: &str, port: u16) -> Vec<u64> {
+    const TCP_LISTEN: &str = "0A";
+
+    proc_net_tcp
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit_once(':')?.1;
+            let state = fields.get(3)?;
+            if u16::from_str_radix(local_port, 16).ok()? != port || *state != TCP_LISTEN {
+                return None;
+            }
+            fields.get(9)?.parse().ok()
+        })
+        .filter(|inode| *inode != 0)
+        .collect()
+}
+
+/// PIDs from `lsof -t` output, one per line
+pub fn parse_lsof_pids(output: &str) -> Vec<u32> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+/// Warning shown when a process appears to have daemonized
+pub fn daemonize_warning(name: &str, port: u16, pid: Option<u32>) -> String {
+    let owner = match pid {
+        Some(pid) => format!("pid {} on port {}", pid, port),
+        None => format!("port {}", port),
+    };
+    format!(
+        "'{}' appears to have daemonized ({}) - caboose cannot manage it; \
+         remove -d / set daemonize: false, or set allow_daemonize = true in [processes.{}]",
+        name, owner, name
+    )
+}
+
+/// Whether a process with `pid` is running (not exited, nor a zombie)
+pub fn is_process_alive(pid: u32) -> bool {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    system.refresh_process(pid)
+        && system
+            .process(pid)
+            .is_some_and(|process| process.status() != sysinfo::ProcessStatus::Zombie)
+}
+
+/// Ask a process outside Caboose's process tree to stop
+pub fn terminate(pid: u32) -> Result<(), String> {
+    super::stop::signal(pid, Signal::Term)
+}
@@ -0,0 +1,93 @@
+//! Suppression of lines a tool writes to both stdout and stderr
+//!
+//! Without a PTY the two streams are read separately, so a tool that logs each
+//! message to both shows every line twice. Lines are held for a short window;
+//! if the same text arrives on the other stream meanwhile, the held line is
+//! tagged [`LogStream::Both`] and the copy is dropped. Repeats on the same
+//! stream are real output and are always kept.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::{LogLine, LogStream};
+
+/// How long a line waits for its copy on the other stream
+pub const DEDUPE_WINDOW: Duration = Duration::from_millis(50);
+
+/// Per-process buffer that merges stdout/stderr copies of the same line
+#[derive(Debug)]
+pub struct StreamDeduper {
+    window: Duration,
+    pending: VecDeque<LogLine>,
+    open_streams: usize,
+}
+
+impl StreamDeduper {
+    /// Buffer for a process whose stdout and stderr are both being read
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: VecDeque::new(),
+            open_streams: 2,
+        }
+    }
+
+    /// Offer a line read at `now`; returns the lines that are ready to send
+    pub fn push(&mut self, line: LogLine, now: Instant) -> Vec<LogLine> {
+        let mut ready = self.flush_expired(now);
+
+        let copy_of = self
+            .pending
+            .iter_mut()
+            .find(|held| held.content == line.content && is_other_stream(held.stream, line.stream));
+        match copy_of {
+            Some(held) => held.stream = LogStream::Both,
+            None => self.pending.push_back(line),
+        }
+
+        // A line that can't be merged any more has nothing to wait for
+        while self
+            .pending
+            .front()
+            .is_some_and(|held| held.stream == LogStream::Both)
+        {
+            ready.extend(self.pending.pop_front());
+        }
+        ready
+    }
+
+    /// Lines whose window has passed without a copy arriving
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<LogLine> {
+        let mut ready = Vec::new();
+        while self
+            .pending
+            .front()
+            .is_some_and(|held| now.duration_since(held.timestamp) >= self.window)
+        {
+            ready.extend(self.pending.pop_front());
+        }
+        ready
+    }
+
+    /// Note that one stream hit EOF; once both have, everything held is released
+    pub fn close_stream(&mut self) -> Vec<LogLine> {
+        self.open_streams = self.open_streams.saturating_sub(1);
+        if self.open_streams == 0 {
+            self.pending.drain(..).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Both streams closed and nothing is held
+    pub fn is_finished(&self) -> bool {
+        self.open_streams == 0 && self.pending.is_empty()
+    }
+}
+
+fn is_other_stream(held: LogStream, incoming: LogStream) -> bool {
+    matches!(
+        (held, incoming),
+        (LogStream::Stdout, LogStream::Stderr) | (LogStream::Stderr, LogStream::Stdout)
+    )
+}
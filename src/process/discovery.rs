@@ -0,0 +1,90 @@
+//! Finding the Caboose session running in a project, from another terminal
+//!
+//! A running session keeps its processes in the project's
+//! `.caboose/state.json` (see [`SessionState`]); `caboose ps` and
+//! `caboose stop` look there, trusting the file only while the Caboose
+//! process that wrote it is alive. `caboose restart` asks the session to
+//! restart a process by adding its name to `.caboose/restart-requests`, which
+//! the session picks up (see
+//! [`ProcessManager::follow_restart_requests`](super::ProcessManager::follow_restart_requests)).
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{ProcessStatus, STATE_FILE, SessionState, is_process_alive, running_session};
+use crate::project::ProjectRoot;
+
+/// File in the project's data directory restart requests are left in, one
+/// process name per line
+pub const RESTART_REQUESTS_FILE: &str = "restart-requests";
+
+/// Time `caboose restart` waits to see the process start again
+pub const RESTART_WAIT: Duration = Duration::from_secs(10);
+
+/// Where a session running in `project` keeps its state
+pub fn session_state_path(project: &ProjectRoot) -> PathBuf {
+    project.data_dir().join(STATE_FILE)
+}
+
+/// The session running in `project`, if there is one
+///
+/// A state file left behind by a session that's no longer running is removed.
+pub fn find_session(project: &ProjectRoot) -> Option<SessionState> {
+    running_session(&session_state_path(project), is_process_alive)
+}
+
+/// Where `caboose restart` leaves requests for the session running in `project`
+pub fn restart_requests_path(project: &ProjectRoot) -> PathBuf {
+    project.data_dir().join(RESTART_REQUESTS_FILE)
+}
+
+/// Check `process` can be restarted in `state`, and ask the session to
+///
+/// Adopted processes weren't started by Caboose, so it can't start them again.
+pub fn request_restart(path: &Path, state: &SessionState, process: &str) -> Result<(), String> {
+    let Some(found) = state.processes.iter().find(|p| p.name == process) else {
+        let names: Vec<&str> = state.processes.iter().map(|p| p.name.as_str()).collect();
+        return Err(format!(
+            "No process named '{}' (the session has: {})",
+            process,
+            names.join(", ")
+        ));
+    };
+    if found.status == ProcessStatus::External {
+        return Err(format!(
+            "{} was started outside Caboose, so Caboose can't restart it",
+            process
+        ));
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", process))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The processes asked to be restarted since the last look, in the order
+/// asked; the requests are removed
+pub fn take_restart_requests(path: &Path) -> Vec<String> {
+    // Moved aside first, so a request added while reading isn't lost
+    let taken = path.with_extension("taken");
+    if fs::rename(path, &taken).is_err() {
+        return Vec::new();
+    }
+    let content = fs::read_to_string(&taken).unwrap_or_default();
+    let _ = fs::remove_file(&taken);
+    let mut names: Vec<String> = Vec::new();
+    for name in content
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !names.iter().any(|taken| taken == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
@@ -0,0 +1,79 @@
+//! Adopted processes: ones already running when Caboose started (a
+//! teammate's script, `bin/dev` in another terminal)
+//!
+//! Nothing is spawned. The process is found by the PID listening on its port
+//! and watched there, so the panel shows when it goes away or comes back. Its
+//! output comes from following a log file, when one is configured; otherwise
+//! only the other processes are observed. Caboose never stops it.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Time between checks of an adopted process's port
+pub const ADOPT_POLL: Duration = Duration::from_secs(2);
+
+/// Time between reads of a followed log file
+pub const LOG_FILE_POLL: Duration = Duration::from_millis(250);
+
+/// Reads the lines appended to a log file since the last read
+///
+/// A file that shrinks (truncated by `rails log:clear`, or rotated) is read
+/// again from the start, as is one that didn't exist when following began.
+/// A line still being written is held back until its newline arrives.
+#[derive(Debug)]
+pub struct LogFileFollower {
+    path: PathBuf,
+    /// Bytes already read; `None` until the file has been seen
+    offset: Option<u64>,
+    partial: Vec<u8>,
+}
+
+impl LogFileFollower {
+    /// Follow `path` from its current end: what's already there isn't replayed
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let offset = std::fs::metadata(&path).ok().map(|meta| meta.len());
+        Self {
+            path,
+            offset,
+            partial: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Complete lines written since the last call, without their line endings
+    pub fn read_lines(&mut self) -> Vec<String> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let len = file.metadata().map_or(0, |meta| meta.len());
+        let start = match self.offset {
+            Some(offset) if offset <= len => offset,
+            _ => {
+                self.partial.clear();
+                0
+            }
+        };
+        if file.seek(SeekFrom::Start(start)).is_err() {
+            return Vec::new();
+        }
+        let mut appended = Vec::new();
+        let read = file.read_to_end(&mut appended).unwrap_or(0);
+        self.offset = Some(start + read as u64);
+        self.partial.extend_from_slice(&appended[..read]);
+
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.partial.drain(..=last_newline).collect();
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
@@ -0,0 +1,96 @@
+//! Which processes the Logs view shows (`/filter`)
+//!
+//! A filter is a comma-separated list of process names: `web,worker` shows
+//! just those, `!frontend` everything but it. Names may use `*` wildcards
+//! (`web*`). Exclusions win over inclusions whatever the order, so
+//! `web*,!web-2` shows every `web` process except `web-2`.
+
+use std::fmt;
+
+use crate::path_glob::matches_wildcards;
+
+/// One entry of a [`ProcessFilter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessPattern {
+    /// `web`, `web*`
+    Include(String),
+    /// `!frontend`
+    Exclude(String),
+}
+
+/// The processes whose lines the Logs view keeps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessFilter {
+    patterns: Vec<ProcessPattern>,
+}
+
+impl ProcessFilter {
+    /// Just `process`
+    pub fn only(process: impl Into<String>) -> Self {
+        Self {
+            patterns: vec![ProcessPattern::Include(process.into())],
+        }
+    }
+
+    /// Parse `web,worker`, `!frontend` or `web*`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+        for entry in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let pattern = match entry.strip_prefix('!') {
+                Some(name) if name.trim().is_empty() => {
+                    return Err("'!' needs a process name after it, e.g. !frontend".to_string());
+                }
+                Some(name) => ProcessPattern::Exclude(name.trim().to_string()),
+                None => ProcessPattern::Include(entry.to_string()),
+            };
+            patterns.push(pattern);
+        }
+        if patterns.is_empty() {
+            return Err("Name a process, e.g. web, web,worker, !frontend or web*".to_string());
+        }
+        Ok(Self { patterns })
+    }
+
+    pub fn patterns(&self) -> &[ProcessPattern] {
+        &self.patterns
+    }
+
+    /// Whether `process`'s lines are shown
+    ///
+    /// Excluded if any exclusion matches; otherwise shown if there are no
+    /// inclusions or one of them matches.
+    pub fn matches(&self, process: &str) -> bool {
+        let mut includes = false;
+        let mut included = false;
+        for pattern in &self.patterns {
+            match pattern {
+                ProcessPattern::Exclude(name) if matches_wildcards(name, process) => return false,
+                ProcessPattern::Exclude(_) => {}
+                ProcessPattern::Include(name) => {
+                    includes = true;
+                    included |= matches_wildcards(name, process);
+                }
+            }
+        }
+        !includes || included
+    }
+}
+
+/// The filter as typed, `web,worker` or `!frontend`
+impl fmt::Display for ProcessFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .patterns
+            .iter()
+            .map(|pattern| match pattern {
+                ProcessPattern::Include(name) => name.clone(),
+                ProcessPattern::Exclude(name) => format!("!{}", name),
+            })
+            .collect();
+        f.write_str(&entries.join(","))
+    }
+}
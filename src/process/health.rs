@@ -0,0 +1,108 @@
+//! Project health: one answer to "is everything up?" across all processes
+//!
+//! Computed from process status plus readiness (whether each run has printed
+//! its ready line yet, and how long it may take). A crash outranks a slow
+//! boot, which outranks a clean stop, which outranks a boot still in budget.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{ProcessInfo, ProcessStatus};
+
+/// Whether a running process has finished booting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Readiness {
+    /// Printed a ready line this run
+    Ready,
+    /// No ready line yet; past `budget` it counts as slow
+    Booting { elapsed: Duration, budget: Duration },
+    /// Not known to print a ready line, so readiness can't be told
+    Unknown,
+}
+
+impl Readiness {
+    pub fn is_overdue(&self) -> bool {
+        matches!(self, Readiness::Booting { elapsed, budget } if elapsed > budget)
+    }
+}
+
+/// Overall health of the project's processes, worst first
+///
+/// Each variant but `AllGo` and `NoProcesses` names the processes behind it,
+/// sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectHealth {
+    /// A process exited with an error
+    Down(Vec<String>),
+    /// A process hasn't become ready within its boot budget
+    SlowBoot(Vec<String>),
+    /// A process exited cleanly and isn't running
+    Stopped(Vec<String>),
+    /// A process is still booting, within its budget
+    Starting(Vec<String>),
+    /// Every process is running, and ready where that can be told
+    AllGo,
+    NoProcesses,
+}
+
+impl ProjectHealth {
+    /// Short text for the header, e.g. `Crashed: worker`
+    pub fn summary(&self) -> String {
+        let (label, names) = match self {
+            ProjectHealth::AllGo => return "All systems go".to_string(),
+            ProjectHealth::NoProcesses => return "No processes".to_string(),
+            ProjectHealth::Down(names) => ("Crashed", names),
+            ProjectHealth::SlowBoot(names) => ("Not ready", names),
+            ProjectHealth::Stopped(names) => ("Stopped", names),
+            ProjectHealth::Starting(names) => ("Starting", names),
+        };
+        format!("{}: {}", label, names.join(", "))
+    }
+}
+
+/// Combine every process's status and readiness into one health value
+///
+/// A detached or adopted process counts as up: it's still serving. Tasks
+/// don't count, since they're meant to finish. Processes missing from
+/// `readiness` are treated as [`Readiness::Unknown`].
+pub fn project_health(
+    processes: &[ProcessInfo],
+    readiness: &HashMap<String, Readiness>,
+) -> ProjectHealth {
+    if processes.is_empty() {
+        return ProjectHealth::NoProcesses;
+    }
+
+    let mut down = Vec::new();
+    let mut slow = Vec::new();
+    let mut stopped = Vec::new();
+    let mut starting = Vec::new();
+    for process in processes.iter().filter(|p| !super::is_task(&p.name)) {
+        let name = process.name.clone();
+        match process.status {
+            ProcessStatus::Crashed => down.push(name),
+            ProcessStatus::Stopped => stopped.push(name),
+            ProcessStatus::Detached | ProcessStatus::External => {}
+            ProcessStatus::Running => match readiness.get(&process.name) {
+                Some(state) if state.is_overdue() => slow.push(name),
+                Some(Readiness::Booting { .. }) => starting.push(name),
+                _ => {}
+            },
+        }
+    }
+
+    for names in [&mut down, &mut slow, &mut stopped, &mut starting] {
+        names.sort();
+    }
+    if !down.is_empty() {
+        ProjectHealth::Down(down)
+    } else if !slow.is_empty() {
+        ProjectHealth::SlowBoot(slow)
+    } else if !stopped.is_empty() {
+        ProjectHealth::Stopped(stopped)
+    } else if !starting.is_empty() {
+        ProjectHealth::Starting(starting)
+    } else {
+        ProjectHealth::AllGo
+    }
+}
@@ -0,0 +1,157 @@
+//! Severity of a log line, for the Logs view's `/level` filter and gutter
+//!
+//! Read once when the line is added and kept on the
+//! [`LogLine`](super::LogLine). The logger's own prefix wins where there is
+//! one (`W, [...] WARN -- :`, `[WARN ...] :`, semantic_logger's level
+//! letter); otherwise frontend tool markers (`✘`, `⚠`, `warn  -`) and
+//! severity words in the message decide, then what the line is about.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::LogCategory;
+
+/// How severe a line is, least first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    /// Parse `debug`, `info`, `warn` or `error` (as used by `/level`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "debug" | "trace" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "err" | "fatal" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+
+    /// Whether a line at `level` is shown with this as the minimum; lines
+    /// that show no level count as info
+    pub fn admits(self, level: Option<LogLevel>) -> bool {
+        level.unwrap_or(LogLevel::Info) >= self
+    }
+
+    /// Level of a line from its text and the `category` it was put in
+    ///
+    /// Queries are logged at debug by Rails, so a `'ERROR'` in a literal
+    /// doesn't make one an error; exceptions are errors and requests and
+    /// frontend output without a marker are info. Anything else with no
+    /// marker has no level.
+    pub fn detect(content: &str, category: LogCategory) -> Option<Self> {
+        if let Some(level) = logger_prefix_level(content) {
+            return Some(level);
+        }
+        match category {
+            LogCategory::Sql => return Some(Self::Debug),
+            LogCategory::Error => return Some(Self::Error),
+            _ => {}
+        }
+        if let Some(level) = marker_level(content).or_else(|| word_level(content)) {
+            return Some(level);
+        }
+        match category {
+            LogCategory::Http | LogCategory::Frontend => Some(Self::Info),
+            _ => None,
+        }
+    }
+
+    /// A severity word or a logger's one-letter level
+    fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "T" | "D" => Some(Self::Debug),
+            "I" => Some(Self::Info),
+            "W" => Some(Self::Warn),
+            "E" | "F" => Some(Self::Error),
+            "ERR!" => Some(Self::Error),
+            token => Self::parse(token),
+        }
+    }
+}
+
+/// The level in a Ruby Logger or semantic_logger prefix:
+/// - `W, [2024-01-15T10:30:45.043111 #6322]  WARN -- : ...` (Rails 6/7)
+/// - `[WARN 2018-07-01 11:55:04 65048] : ...`
+/// - `2024-01-15 10:30:45.123456 W [12345:puma srv tp 001] ...` (semantic_logger)
+/// - `2024-01-15 10:30:45 WARN ...`
+fn logger_prefix_level(content: &str) -> Option<LogLevel> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:[DIWEFA],\s*\[[^\]]*\]\s+(?P<ruby>DEBUG|INFO|WARN|ERROR|FATAL)\s+--|\[(?P<bracketed>DEBUG|INFO|WARN|ERROR|FATAL)\s[^\]]*\]\s*:|\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}\S*\s+(?:(?P<letter>[TDIWEF])\s+\[|(?P<word>DEBUG|INFO|WARN|WARNING|ERROR|FATAL)\b))",
+        )
+        .unwrap()
+    });
+    let caps = pattern.captures(content)?;
+    ["ruby", "bracketed", "letter", "word"]
+        .into_iter()
+        .find_map(|name| caps.name(name))
+        .and_then(|token| LogLevel::from_token(token.as_str()))
+}
+
+/// The status symbol or prefix frontend tools open a line with:
+/// - `✘ [ERROR] Could not resolve "react"` / `▲ [WARNING] ...` (esbuild, Vite)
+/// - ` ⨯ ./app/page.tsx` / ` ⚠ Fast Refresh ...` / ` ✓ Compiled` (Next.js 13+)
+/// - `error - ./pages/index.js` / `warn  - ...` / `event - compiled` (Next.js 12)
+/// - `(!) Some chunks are larger than 500 kB` (Rollup)
+fn marker_level(content: &str) -> Option<LogLevel> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        // Vite and webpack put a clock time before some of these
+        Regex::new(
+            r"^\s*(?:\d{1,2}:\d{2}:\d{2}\s*(?:[AP]M\s+)?)?(?:(?P<error>[✘✖⨯×]|error\s+-\s)|(?P<warn>[⚠▲]|\(!\)|warn\s+-\s)|(?P<info>[✓✔○➜]|(?:info|event|wait|ready)\s+-\s))",
+        )
+        .unwrap()
+    });
+    let caps = pattern.captures(content)?;
+    if caps.name("error").is_some() {
+        Some(LogLevel::Error)
+    } else if caps.name("warn").is_some() {
+        Some(LogLevel::Warn)
+    } else {
+        Some(LogLevel::Info)
+    }
+}
+
+/// The first severity word in a line: `WARN: Redis reconnecting`, `npm ERR!`,
+/// `[warning]`, `warning: ...`, `level=error` (but not `ERRORS_TO_IGNORE`,
+/// `NoMethodError` or `no warnings`)
+fn word_level(content: &str) -> Option<LogLevel> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"\b(?P<upper>DEBUG|INFO|WARN|WARNING|ERROR|FATAL|ERR!)(?:[^\w]|$)|\[(?P<bracketed>(?i:debug|info|warn|warning|error|fatal))\]|\b(?P<lower>warning|error):|\blevel=(?P<logfmt>(?i:debug|info|warn|warning|error|fatal))\b",
+        )
+        .unwrap()
+    });
+    let caps = pattern.captures(content)?;
+    ["upper", "bracketed", "lower", "logfmt"]
+        .into_iter()
+        .find_map(|name| caps.name(name))
+        .and_then(|token| LogLevel::from_token(token.as_str()))
+}
@@ -0,0 +1,163 @@
+//! Each process's output kept in `.caboose/logs/<process>.log`, for
+//! `caboose logs`
+//!
+//! The TUI holds the only copy of what a process prints, so the session also
+//! appends every line (ANSI escapes stripped, one per line) to a file per
+//! process. A file that reaches [`MAX_LOG_FILE_BYTES`] is rolled over to
+//! `<process>.log.1`, replacing the one before, so a process keeps at most
+//! twice that on disk.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use tokio::sync::mpsc;
+
+use super::LogLine;
+use crate::project::ProjectRoot;
+
+/// Directory in the project's data directory the log files are kept in
+pub const LOGS_DIR: &str = "logs";
+
+/// Size a log file is rolled over at
+pub const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Lines `caboose logs` prints before following, unless `-n` says otherwise
+pub const DEFAULT_BACKLOG_LINES: usize = 100;
+
+/// Where a session running in `project` keeps its processes' output
+pub fn process_logs_dir(project: &ProjectRoot) -> PathBuf {
+    project.data_dir().join(LOGS_DIR)
+}
+
+/// `<dir>/web.log`; a name that isn't a plain file name can't leave `dir`
+pub fn process_log_path(dir: &Path, process: &str) -> PathBuf {
+    let file_name: String = process
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') { '_' } else { c })
+        .collect();
+    dir.join(format!("{}.log", file_name))
+}
+
+/// `web.log.1`, where `web.log` goes once it's full
+pub fn rolled_over_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+/// Processes with output kept in `dir`, sorted
+pub fn logged_processes(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_suffix(".log").map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// The last `n` lines of `text`
+pub fn last_lines(text: &str, n: usize) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(n)..].to_vec()
+}
+
+/// The last `n` lines kept for the process logging to `path`, reaching into
+/// the rolled over file when the current one is shorter
+pub fn read_backlog(path: &Path, n: usize) -> io::Result<Vec<String>> {
+    let current = fs::read_to_string(path)?;
+    let mut lines: Vec<String> = last_lines(&current, n)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    if lines.len() < n
+        && let Ok(older) = fs::read_to_string(rolled_over_path(path))
+    {
+        let mut backlog: Vec<String> = last_lines(&older, n - lines.len())
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        backlog.append(&mut lines);
+        lines = backlog;
+    }
+    Ok(lines)
+}
+
+/// Appends each process's lines to its own file in a directory
+pub struct ProcessLogFiles {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Open files and their size, by process
+    open: HashMap<String, (File, u64)>,
+}
+
+impl ProcessLogFiles {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: MAX_LOG_FILE_BYTES,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Roll files over at `max_bytes` instead of [`MAX_LOG_FILE_BYTES`]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Append `content` as a line of `process`'s file, rolling it over first
+    /// if the line would take it past the limit
+    pub fn append(&mut self, process: &str, content: &str) -> io::Result<()> {
+        let path = process_log_path(&self.dir, process);
+        let line_len = content.len() as u64 + 1;
+        if let Some((_, size)) = self.open.get(process)
+            && *size > 0
+            && size + line_len > self.max_bytes
+        {
+            self.open.remove(process);
+            fs::rename(&path, rolled_over_path(&path))?;
+        }
+        let (file, size) = match self.open.entry(process.to_string()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                fs::create_dir_all(&self.dir)?;
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                let size = file.metadata()?.len();
+                entry.insert((file, size))
+            }
+        };
+        writeln!(file, "{}", content)?;
+        *size += line_len;
+        Ok(())
+    }
+
+    /// Pass the lines sent on the returned channel on to `log_tx`, appending
+    /// each to its process's file on the way
+    ///
+    /// Writes happen on a thread of their own. A file that can't be written
+    /// only costs its copy on disk; the lines still reach `log_tx`.
+    pub fn tee(mut self, log_tx: mpsc::UnboundedSender<LogLine>) -> mpsc::UnboundedSender<LogLine> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<LogLine>();
+        let forward = log_tx.clone();
+        let spawned = std::thread::Builder::new()
+            .name("caboose-log-files".into())
+            .spawn(move || {
+                while let Some(line) = rx.blocking_recv() {
+                    let _ = self.append(&line.process_name, &line.content);
+                    if forward.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        match spawned {
+            Ok(_) => tx,
+            Err(_) => log_tx,
+        }
+    }
+}
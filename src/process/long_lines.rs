@@ -0,0 +1,157 @@
+//! Very long log lines: a bulk INSERT or a query with a huge IN list can be
+//! megabytes on one line. Such lines are cut to a maximum length before they
+//! are stored or parsed, with a marker saying how much was dropped. The full
+//! line can optionally be written to a temp file so export still has it.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use super::LogLine;
+
+/// Longest line kept in full, in bytes
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 16 * 1024;
+
+/// Largest accepted `max_line_length`
+pub const MAX_LINE_LENGTH_LIMIT: usize = 16 * 1024 * 1024;
+
+/// How a stored line was cut
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Truncation {
+    /// Length of the original line in bytes
+    pub original_len: usize,
+    /// Bytes of the original kept at the start of `content`, before the marker
+    pub kept_len: usize,
+    /// File holding the full line, when spilling is enabled
+    pub spill: Option<PathBuf>,
+}
+
+impl LogLine {
+    /// The line as logged, up to any truncation marker
+    ///
+    /// Parsers and trackers read this rather than `content` so the marker
+    /// never looks like part of the log.
+    pub fn kept_content(&self) -> &str {
+        match &self.truncated {
+            Some(truncation) => &self.content[..truncation.kept_len],
+            None => &self.content,
+        }
+    }
+
+    /// The whole line, read back from its spill file if it was truncated
+    ///
+    /// Falls back to the stored (truncated) content when there is no spill
+    /// file or it can no longer be read.
+    pub fn full_content(&self) -> Cow<'_, str> {
+        self.truncated
+            .as_ref()
+            .and_then(|truncation| truncation.spill.as_ref())
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map_or(Cow::Borrowed(self.content.as_str()), Cow::Owned)
+    }
+}
+
+/// Cuts lines longer than the maximum, optionally spilling them to disk
+#[derive(Debug)]
+pub struct LineTruncator {
+    max_len: usize,
+    spill_dir: Option<PathBuf>,
+    spilled: usize,
+}
+
+impl Default for LineTruncator {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LINE_LENGTH)
+    }
+}
+
+impl LineTruncator {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            max_len,
+            spill_dir: None,
+            spilled: 0,
+        }
+    }
+
+    /// Write the full text of each truncated line to a file in `dir`
+    ///
+    /// The directory is created on first use and removed when the truncator
+    /// is dropped.
+    pub fn with_spill_dir(mut self, dir: PathBuf) -> Self {
+        self.spill_dir = Some(dir);
+        self
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Cut `line` to the maximum length if it is longer
+    pub fn apply(&mut self, line: &mut LogLine) {
+        let original_len = line.content.len();
+        if original_len <= self.max_len {
+            return;
+        }
+
+        let spill = self.spill(&line.content);
+        let kept_len = floor_char_boundary(&line.content, self.max_len);
+        line.content.truncate(kept_len);
+        line.content.shrink_to_fit();
+        if let Some(markup) = &mut line.markup {
+            markup.clip(kept_len);
+        }
+        line.content
+            .push_str(&format!(" … truncated ({})", format_size(original_len)));
+        line.truncated = Some(Truncation {
+            original_len,
+            kept_len,
+            spill,
+        });
+    }
+
+    fn spill(&mut self, content: &str) -> Option<PathBuf> {
+        let dir = self.spill_dir.as_ref()?;
+        std::fs::create_dir_all(dir).ok()?;
+        self.spilled += 1;
+        let path = dir.join(format!("line-{}.log", self.spilled));
+        std::fs::write(&path, content).ok()?;
+        Some(path)
+    }
+}
+
+impl Drop for LineTruncator {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.spill_dir
+            && self.spilled > 0
+        {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Per-run directory for spilled lines
+pub fn default_spill_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("caboose-{}", std::process::id()))
+}
+
+/// Largest index `<= index` that falls on a char boundary of `text`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    (0..=index.min(text.len()))
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// `2.1MB`, `16.0KB`, `512B`
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f >= KB * KB {
+        format!("{:.1}MB", bytes_f / (KB * KB))
+    } else if bytes_f >= KB {
+        format!("{:.1}KB", bytes_f / KB)
+    } else {
+        format!("{}B", bytes)
+    }
+}
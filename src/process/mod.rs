@@ -1,12 +1,97 @@
+mod ansi;
+mod boot;
+mod category;
+mod daemon;
+mod dedupe;
+mod discovery;
+mod external;
+mod filter;
+mod health;
+mod level;
+mod log_files;
+mod long_lines;
+mod ports;
+mod restart;
+mod setup;
+mod state;
+mod stop;
+mod task;
+
+pub use ansi::{AnsiColor, AnsiStyle, Hyperlink, LogMarkup, StyledRun, parse_ansi};
+pub use boot::{
+    BOOT_HISTORY_LEN, BootTimes, DEFAULT_BOOT_BUDGET, MIN_BOOT_BUDGET, expects_ready_line,
+    is_ready_line,
+};
+pub use category::LogCategory;
+pub use daemon::{
+    DAEMONIZE_WINDOW, daemonize_warning, is_port_listening, is_process_alive, listening_inodes,
+    parse_lsof_pids,
+};
+pub use dedupe::{DEDUPE_WINDOW, StreamDeduper};
+pub use discovery::{
+    RESTART_REQUESTS_FILE, RESTART_WAIT, find_session, request_restart, restart_requests_path,
+    session_state_path, take_restart_requests,
+};
+pub use external::{ADOPT_POLL, LOG_FILE_POLL, LogFileFollower};
+pub use filter::{ProcessFilter, ProcessPattern};
+pub use health::{ProjectHealth, Readiness, project_health};
+pub use level::LogLevel;
+pub use log_files::{
+    DEFAULT_BACKLOG_LINES, LOGS_DIR, MAX_LOG_FILE_BYTES, ProcessLogFiles, last_lines,
+    logged_processes, process_log_path, process_logs_dir, read_backlog, rolled_over_path,
+};
+pub use long_lines::{
+    DEFAULT_MAX_LINE_LENGTH, LineTruncator, MAX_LINE_LENGTH_LIMIT, Truncation, default_spill_dir,
+};
+pub use ports::{PortClaim, PortSource, port_collisions, port_from_command, port_intent};
+pub use restart::{
+    DEFAULT_MAX_RESTARTS, DEFAULT_RESTART_BACKOFF, MAX_RESTART_BACKOFF, RestartPolicy, RestartRule,
+    STABLE_RUN, exit_description, gave_up_note, restart_note,
+};
+pub use setup::{DEFAULT_SETUP_TIMEOUT, SETUP_PROCESS_NAME, SetupError, SetupStep};
+pub use state::{
+    ProcessState, STATE_FILE, STATE_WRITE_INTERVAL, SessionState, format_ps, running_session,
+};
+pub use stop::{
+    DEFAULT_STOP_GRACE, STOP_REQUEST_FILE, STOP_REQUEST_WAIT, StopOutcome, StopResult,
+    ask_session_to_exit, request_stop, stop_processes, stop_request_path, stop_targets,
+};
+pub use task::{
+    FINISHED_TASK_TTL, RUN_REQUESTS_FILE, TASK_PREFIX, is_expired_task, is_task, request_run,
+    run_requests_path, shell_join, split_args, take_run_requests, task_name, unique_task_name,
+};
+
+use crate::project::ProjectRoot;
+use crate::shared::{Shared, SnapshotCache};
+use chrono::{DateTime, Local};
 use portable_pty::{ChildKiller, CommandBuilder, PtySize, native_pty_system};
-use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::mpsc;
+use tokio::task::JoinSet;
 use tokio::time::{Duration, sleep};
 
+/// Longest line [`send_line`](ProcessManager::send_line) writes; a PTY in
+/// line mode drops input past about 4 KB, so this leaves room to spare
+pub const MAX_SEND_LEN: usize = 1024;
+
+/// Time a stopped process gets to exit before a restart goes ahead anyway
+pub const STOP_PROCESS_WAIT: Duration = Duration::from_secs(2);
+
+/// How often finished tasks are looked at to drop the expired ones
+pub const TASK_CLEANUP_POLL: Duration = Duration::from_secs(5);
+
+/// Input side of a process's PTY, shared by everything that writes to it
+///
+/// Writers hold the lock for a whole line, so concurrent sends never
+/// interleave their bytes.
+type PtyWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
 enum ChildHandle {
     Pty {
         killer: Box<dyn ChildKiller + Send + Sync>,
@@ -32,6 +117,27 @@ impl Clone for ChildHandle {
 }
 
 impl ChildHandle {
+    /// Whether the child has exited; its exit monitor still reports how
+    fn has_exited(&self) -> bool {
+        match self {
+            ChildHandle::Pty { child, .. } => child
+                .lock()
+                .is_ok_and(|mut child| !matches!(child.try_wait(), Ok(None))),
+            ChildHandle::Plain { child } => child
+                .lock()
+                .is_ok_and(|mut child| !matches!(child.try_wait(), Ok(None))),
+        }
+    }
+
+    /// Whether this is the handle of `child`, and not of a later run
+    fn is_child<T>(&self, child: &Arc<Mutex<T>>) -> bool {
+        let ours = match self {
+            ChildHandle::Pty { child, .. } => Arc::as_ptr(child) as *const (),
+            ChildHandle::Plain { child } => Arc::as_ptr(child) as *const (),
+        };
+        std::ptr::eq(ours, Arc::as_ptr(child) as *const ())
+    }
+
     fn kill(&self) -> Result<(), String> {
         match self {
             ChildHandle::Pty { killer, child } => {
@@ -60,43 +166,402 @@ impl ChildHandle {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProcessStatus {
     Running,
     Stopped,
     Crashed,
+    /// The spawned child exited but left a daemon running on its port
+    Detached,
+    /// Already running when Caboose started and adopted rather than spawned
+    /// (`--attach`, `managed = false`); Caboose never stops it
+    External,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub name: String,
     pub command: String,
     pub status: ProcessStatus,
+    #[serde(
+        serialize_with = "crate::serialization::optional_instant_age",
+        deserialize_with = "crate::serialization::optional_instant_from_age"
+    )]
     pub start_time: Option<Instant>,
     pub pid: Option<u32>,
+    /// How the previous run ended, if the process has exited before
+    pub last_exit: Option<ProcessExit>,
+    /// Times the process was started again after its first run
+    pub restarts: u32,
+    /// Times a run exited with a non-zero code or was killed; the code is in
+    /// [`last_exit`](Self::last_exit)
+    #[serde(default)]
+    pub crash_count: u32,
+    /// Why the last start failed (e.g. the program doesn't exist); the
+    /// process is marked crashed and never ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_error: Option<String>,
 }
 
+/// A process to start: its Procfile name, command and environment
 #[derive(Debug, Clone)]
+pub struct ProcessSpawn {
+    pub name: String,
+    pub command: String,
+    pub env: HashMap<String, String>,
+}
+
+/// How a process run ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessExit {
+    pub success: bool,
+    /// Exit code, when the process wasn't killed by a signal
+    pub code: Option<i32>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
+    pub at: Instant,
+}
+
+/// Which output stream a log line was read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogStream {
+    /// PTY output (stdout and stderr share one stream) or a Caboose message
+    #[default]
+    Combined,
+    Stdout,
+    Stderr,
+    /// Written to both stdout and stderr, merged by stream de-duplication
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogLine {
     pub process_name: String,
     pub content: String,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub timestamp: Instant,
+    /// Wall-clock time the line was read, for `/around`
+    pub time: DateTime<Local>,
+    /// Position in the log buffer, assigned when the line is added; 0 until then
+    pub seq: u64,
+    pub stream: LogStream,
+    /// Set when the line was cut to the maximum stored length
+    pub truncated: Option<Truncation>,
+    /// Colors and hyperlinks from the escapes stripped out of `content`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markup: Option<Box<LogMarkup>>,
+    /// Filter chip the line counts towards, set when the line is added
+    #[serde(default)]
+    pub category: LogCategory,
+    /// Severity, read when the line is added; `None` when it shows none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LogLevel>,
+}
+
+impl LogLine {
+    /// `content` read from `process_name` just now, on the combined stream
+    ///
+    /// The rest is filled in as the line is added to the log buffer; set
+    /// other fields with `..LogLine::new(..)`.
+    pub fn new(process_name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            process_name: process_name.into(),
+            content: content.into(),
+            timestamp: Instant::now(),
+            time: Local::now(),
+            seq: 0,
+            stream: LogStream::Combined,
+            truncated: None,
+            markup: None,
+            category: LogCategory::Other,
+            level: None,
+        }
+    }
+
+    /// Approximate heap footprint of this line in the log buffer
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.process_name.len()
+            + self.content.len()
+            + self
+                .markup
+                .as_ref()
+                .map_or(0, |markup| markup.approx_bytes())
+    }
 }
 
 pub struct ProcessManager {
-    processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    processes: Arc<Shared<HashMap<String, ProcessInfo>>>,
+    /// Processes as last handed out, by name
+    listed: SnapshotCache<Vec<ProcessInfo>>,
     child_handles: Arc<Mutex<HashMap<String, ChildHandle>>>,
+    /// Input of each process running in a PTY
+    pty_writers: Arc<Mutex<HashMap<String, PtyWriter>>>,
     log_tx: mpsc::UnboundedSender<LogLine>,
     use_pty: bool,
+    project_root: Option<ProjectRoot>,
+    /// Processes allowed to daemonize without a warning
+    daemonize_allowed: HashSet<String>,
+    /// Detached processes the user agreed to stop along with the rest
+    detached_stop_confirmed: Arc<Mutex<HashSet<String>>>,
+    /// Processes adopted rather than spawned, which are never stopped
+    adopted: Mutex<HashSet<String>>,
+    /// Port each spawned process is expected to listen on, when known
+    ports: Mutex<HashMap<String, u16>>,
+    /// How each process was last spawned, to restart it the same way
+    spawns: Mutex<HashMap<String, ProcessSpawn>>,
+    /// Processes restarted after a crash (`restart = "on-failure"`)
+    restart_rules: HashMap<String, RestartRule>,
+    /// Crash restarts so far, by process; see [`RestartRule`]
+    restart_attempts: Arc<Mutex<HashMap<String, u32>>>,
+    /// Processes waiting out their backoff before a crash restart
+    restarts_pending: Arc<Mutex<HashSet<String>>>,
+    /// Processes whose backoff is over, for
+    /// [`follow_restart_policies`](Self::follow_restart_policies) to start again
+    restart_tx: mpsc::UnboundedSender<String>,
+    restart_rx: Mutex<Option<mpsc::UnboundedReceiver<String>>>,
+    /// Where the session state is kept for `caboose ps`, once asked to
+    state_file: Mutex<Option<PathBuf>>,
+    /// Merge lines written to both stdout and stderr (NO_PTY only)
+    dedupe_streams: bool,
+    /// Environment one-off tasks run with, the project's `.env`
+    task_env: HashMap<String, String>,
+    /// Set by [`stop_all`](ProcessManager::stop_all)
+    stopping: Arc<AtomicBool>,
+}
+
+/// What the exit monitor needs to notice a daemonizing child
+struct ExitWatch {
+    name: String,
+    /// The child's pid; a process showing another one has been stopped or
+    /// restarted since, and this run's exit is no longer news
+    pid: Option<u32>,
+    port: Option<u16>,
+    allow_daemonize: bool,
+    processes: Arc<Shared<HashMap<String, ProcessInfo>>>,
+    log_tx: mpsc::UnboundedSender<LogLine>,
+    /// Set once Caboose is stopping everything, so a kill isn't a crash
+    stopping: Arc<AtomicBool>,
+    /// How to restart the process after a crash, if it should be
+    restart: Option<CrashRestart>,
+}
+
+/// What the exit monitor needs to restart a crashed process
+struct CrashRestart {
+    rule: RestartRule,
+    attempts: Arc<Mutex<HashMap<String, u32>>>,
+    pending: Arc<Mutex<HashSet<String>>>,
+    restart_tx: mpsc::UnboundedSender<String>,
+}
+
+impl ExitWatch {
+    /// Count the crash as an attempt, then wait out its backoff and hand the
+    /// process on to be started again; past the last attempt it stays crashed
+    async fn restart_after_crash(
+        &self,
+        restart: &CrashRestart,
+        code: Option<i32>,
+        started_at: Option<Instant>,
+    ) {
+        let attempt = {
+            let mut attempts = restart.attempts.lock().unwrap();
+            let attempt = attempts.entry(self.name.clone()).or_insert(0);
+            if started_at.is_some_and(|started| started.elapsed() >= STABLE_RUN) {
+                *attempt = 0;
+            }
+            *attempt += 1;
+            *attempt
+        };
+        if attempt > restart.rule.max_restarts {
+            send_note(
+                &self.log_tx,
+                &self.name,
+                gave_up_note(&self.name, code, &restart.rule),
+            );
+            return;
+        }
+        restart.pending.lock().unwrap().insert(self.name.clone());
+        send_note(
+            &self.log_tx,
+            &self.name,
+            restart_note(&self.name, code, attempt, &restart.rule),
+        );
+        sleep(restart.rule.delay(attempt)).await;
+        let _ = restart.restart_tx.send(self.name.clone());
+    }
+
+    /// Mark the process stopped (or crashed), then check whether it left a daemon behind
+    async fn child_exited(self, success: bool, code: Option<i32>) {
+        let (crashed, started_at) = {
+            let mut procs = self.processes.write();
+            let Some(info) = procs.get_mut(&self.name) else {
+                return;
+            };
+            if info.pid != self.pid {
+                return;
+            }
+            info.status = if success || self.stopping.load(Ordering::Relaxed) {
+                ProcessStatus::Stopped
+            } else {
+                info.crash_count += 1;
+                ProcessStatus::Crashed
+            };
+            info.last_exit = Some(ProcessExit {
+                success,
+                code,
+                at: Instant::now(),
+            });
+            (info.status == ProcessStatus::Crashed, info.start_time)
+        };
+        if task::is_task(&self.name) {
+            let took = started_at.map_or(String::new(), |started| {
+                format!(" after {:.1}s", started.elapsed().as_secs_f64())
+            });
+            let note = if success {
+                format!("Finished{}", took)
+            } else {
+                format!("Failed: {}{}", exit_description(code), took)
+            };
+            send_note(&self.log_tx, &self.name, note);
+            return;
+        }
+        if crashed && let Some(restart) = &self.restart {
+            self.restart_after_crash(restart, code, started_at).await;
+            return;
+        }
+
+        // Only a clean, quick exit looks like fork-and-exit; a crash is a crash
+        let Some(port) = self.port else {
+            return;
+        };
+        if !success || started_at.is_none_or(|t| t.elapsed() > daemon::DAEMONIZE_WINDOW) {
+            return;
+        }
+
+        let deadline = Instant::now() + daemon::PORT_WAIT;
+        loop {
+            let listening = tokio::task::spawn_blocking(move || daemon::is_port_listening(port))
+                .await
+                .unwrap_or(false);
+            if listening {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return;
+            }
+            sleep(Duration::from_millis(250)).await;
+        }
+
+        let pid = tokio::task::spawn_blocking(move || daemon::find_listening_pid(port))
+            .await
+            .ok()
+            .flatten();
+
+        {
+            let mut procs = self.processes.write();
+            if let Some(info) = procs.get_mut(&self.name) {
+                // Stopped by the user in the meantime
+                if info.status != ProcessStatus::Stopped {
+                    return;
+                }
+                info.status = ProcessStatus::Detached;
+                info.pid = pid;
+            }
+        }
+
+        if !self.allow_daemonize {
+            let _ = self.log_tx.send(LogLine::new(
+                self.name.clone(),
+                format!(
+                    "[WARN] {}",
+                    daemon::daemonize_warning(&self.name, port, pid)
+                ),
+            ));
+        }
+    }
 }
 
 impl ProcessManager {
     pub fn new(log_tx: mpsc::UnboundedSender<LogLine>) -> Self {
+        let (restart_tx, restart_rx) = mpsc::unbounded_channel();
         Self {
-            processes: Arc::new(Mutex::new(HashMap::new())),
+            processes: Arc::default(),
+            listed: SnapshotCache::default(),
             child_handles: Arc::new(Mutex::new(HashMap::new())),
+            pty_writers: Arc::new(Mutex::new(HashMap::new())),
             log_tx,
             use_pty: std::env::var("NO_PTY").is_err(),
+            project_root: None,
+            daemonize_allowed: HashSet::new(),
+            detached_stop_confirmed: Arc::new(Mutex::new(HashSet::new())),
+            adopted: Mutex::new(HashSet::new()),
+            ports: Mutex::new(HashMap::new()),
+            spawns: Mutex::new(HashMap::new()),
+            restart_rules: HashMap::new(),
+            restart_attempts: Arc::new(Mutex::new(HashMap::new())),
+            restarts_pending: Arc::new(Mutex::new(HashSet::new())),
+            restart_tx,
+            restart_rx: Mutex::new(Some(restart_rx)),
+            state_file: Mutex::new(None),
+            dedupe_streams: false,
+            task_env: HashMap::new(),
+            stopping: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Don't warn when these processes daemonize (`allow_daemonize = true`)
+    pub fn with_daemonize_allowed<I: IntoIterator<Item = String>>(mut self, names: I) -> Self {
+        self.daemonize_allowed.extend(names);
+        self
+    }
+
+    /// Restart these processes after a crash (`restart = "on-failure"`), once
+    /// [`follow_restart_policies`](Self::follow_restart_policies) is running
+    pub fn with_restart_rules<I: IntoIterator<Item = (String, RestartRule)>>(
+        mut self,
+        rules: I,
+    ) -> Self {
+        self.restart_rules.extend(rules);
+        self
+    }
+
+    /// Merge lines a process writes to both stdout and stderr (`[logs] dedupe_streams`)
+    ///
+    /// Only affects NO_PTY mode; a PTY already delivers both streams as one.
+    pub fn with_stream_dedupe(mut self, enabled: bool) -> Self {
+        self.dedupe_streams = enabled;
+        self
+    }
+
+    /// Run one-off tasks ([`run_task`](Self::run_task)) with `env` on top of
+    /// Caboose's own environment
+    pub fn with_task_env(mut self, env: HashMap<String, String>) -> Self {
+        self.task_env = env;
+        self
+    }
+
+    /// Also append each process's output to its own file in `files`, for
+    /// `caboose logs`
+    pub fn with_log_files(mut self, files: ProcessLogFiles) -> Self {
+        self.log_tx = files.tee(self.log_tx);
+        self
+    }
+
+    /// Run spawned processes inside `root` instead of the current directory
+    pub fn with_project_root(mut self, root: ProjectRoot) -> Self {
+        self.project_root = Some(root);
+        self
+    }
+
+    fn working_dir(&self) -> Option<PathBuf> {
+        match &self.project_root {
+            Some(root) => Some(root.path().to_path_buf()),
+            None => std::env::current_dir().ok(),
         }
     }
 
@@ -106,9 +571,22 @@ impl ProcessManager {
         command: String,
         env_vars: HashMap<String, String>,
     ) -> Result<(), String> {
+        self.spawns.lock().unwrap().insert(
+            name.clone(),
+            ProcessSpawn {
+                name: name.clone(),
+                command: command.clone(),
+                env: env_vars.clone(),
+            },
+        );
+
         // Pre-register process so UI shows it even if spawn fails
         {
-            let mut processes = self.processes.lock().unwrap();
+            let mut processes = self.processes.write();
+            let previous = processes.get(&name);
+            let last_exit = previous.and_then(|info| info.last_exit.clone());
+            let restarts = previous.map_or(0, |info| info.restarts + 1);
+            let crash_count = previous.map_or(0, |info| info.crash_count);
             processes.insert(
                 name.clone(),
                 ProcessInfo {
@@ -117,15 +595,319 @@ impl ProcessManager {
                     status: ProcessStatus::Running,
                     start_time: Some(Instant::now()),
                     pid: None,
+                    last_exit,
+                    restarts,
+                    crash_count,
+                    spawn_error: None,
                 },
             );
         }
 
-        if self.use_pty {
-            self.spawn_with_pty(name, command, env_vars)
+        // A port that is already taken can't tell us anything about this child
+        let port = ports::port_from_command(&command, &env_vars)
+            .filter(|port| !daemon::is_port_listening(*port));
+        match port {
+            Some(port) => self.ports.lock().unwrap().insert(name.clone(), port),
+            None => self.ports.lock().unwrap().remove(&name),
+        };
+        let watch = ExitWatch {
+            name: name.clone(),
+            pid: None,
+            port,
+            allow_daemonize: self.daemonize_allowed.contains(&name),
+            processes: self.processes.clone(),
+            log_tx: self.log_tx.clone(),
+            stopping: self.stopping.clone(),
+            restart: self.restart_rules.get(&name).map(|rule| CrashRestart {
+                rule: *rule,
+                attempts: self.restart_attempts.clone(),
+                pending: self.restarts_pending.clone(),
+                restart_tx: self.restart_tx.clone(),
+            }),
+        };
+
+        let result = if self.use_pty {
+            self.spawn_with_pty(name.clone(), command, env_vars, watch)
         } else {
-            self.spawn_without_pty(name, command, env_vars)
+            self.spawn_without_pty(name.clone(), command, env_vars, watch)
+        };
+        if let Err(err) = &result {
+            self.spawn_failed(&name, err);
+        }
+        result
+    }
+
+    /// Run `argv` once as a task, returning the process name it runs under
+    ///
+    /// It runs through the shell with the `.env` environment; its output is
+    /// logged under its name and it stays listed with its exit code until
+    /// [`remove_finished_tasks`](Self::remove_finished_tasks) drops it.
+    pub fn run_task(&self, argv: &[String]) -> Result<String, String> {
+        if argv.is_empty() {
+            return Err("Nothing to run".to_string());
+        }
+        let name = unique_task_name(&task_name(argv), |name| {
+            self.get_process(name)
+                .is_some_and(|info| info.status == ProcessStatus::Running)
+        });
+        let command = shell_join(argv);
+        send_note(&self.log_tx, &name, format!("Running {}", command));
+        self.spawn_process(name.clone(), command, self.task_env.clone())?;
+        Ok(name)
+    }
+
+    /// Drop tasks that finished at least [`FINISHED_TASK_TTL`] before `now`,
+    /// returning their names
+    pub fn remove_finished_tasks(&self, now: Instant) -> Vec<String> {
+        let removed: Vec<String> = {
+            let mut processes = self.processes.write();
+            let expired: Vec<String> = processes
+                .values()
+                .filter(|info| is_expired_task(info, now))
+                .map(|info| info.name.clone())
+                .collect();
+            for name in &expired {
+                processes.remove(name);
+            }
+            expired
+        };
+        if !removed.is_empty() {
+            let mut spawns = self.spawns.lock().unwrap();
+            let mut ports = self.ports.lock().unwrap();
+            for name in &removed {
+                spawns.remove(name);
+                ports.remove(name);
+            }
+        }
+        removed
+    }
+
+    /// Drop finished tasks as they expire, every [`TASK_CLEANUP_POLL`] until
+    /// [`stop_all`](Self::stop_all)
+    pub fn follow_task_cleanup(self: &Arc<Self>) {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                sleep(TASK_CLEANUP_POLL).await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if manager.stopping.load(Ordering::Relaxed) {
+                    break;
+                }
+                manager.remove_finished_tasks(Instant::now());
+            }
+        });
+    }
+
+    /// Run the tasks `caboose run` asks for in `path`
+    ///
+    /// Looked at every [`STATE_WRITE_INTERVAL`] until
+    /// [`stop_all`](Self::stop_all); requests left over from an earlier
+    /// session are dropped. A task that fails to start is noted in its log.
+    pub fn follow_run_requests(self: &Arc<Self>, path: PathBuf) {
+        let _ = std::fs::remove_file(&path);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            while !manager.stopping.load(Ordering::Relaxed) {
+                for argv in take_run_requests(&path) {
+                    let running = manager.clone();
+                    // A failure to start is logged under the task's name
+                    let _ = tokio::task::spawn_blocking(move || running.run_task(&argv)).await;
+                }
+                sleep(STATE_WRITE_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Start every process at once, each on a blocking thread: opening a PTY
+    /// and spawning take long enough to add up over a long Procfile
+    ///
+    /// A process that fails to start doesn't stop the others; the failures
+    /// are returned with their errors, in `spawns` order.
+    pub async fn spawn_all(self: &Arc<Self>, spawns: Vec<ProcessSpawn>) -> Vec<(String, String)> {
+        let mut tasks = JoinSet::new();
+        for (index, spawn) in spawns.into_iter().enumerate() {
+            let manager = self.clone();
+            tasks.spawn_blocking(move || {
+                let result = manager.spawn_process(spawn.name.clone(), spawn.command, spawn.env);
+                (index, spawn.name, result)
+            });
+        }
+
+        let mut failures = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((index, name, Err(err))) = joined {
+                failures.push((index, name, err));
+            }
+        }
+        failures.sort_by_key(|(index, _, _)| *index);
+        failures
+            .into_iter()
+            .map(|(_, name, err)| (name, err))
+            .collect()
+    }
+
+    /// Mark a process that couldn't be started as crashed, and say why in its log
+    fn spawn_failed(&self, name: &str, err: &str) {
+        if let Some(info) = self.processes.write().get_mut(name) {
+            info.status = ProcessStatus::Crashed;
+            info.start_time = None;
+            info.spawn_error = Some(err.to_string());
+        }
+        let _ = self.log_tx.send(LogLine {
+            stream: LogStream::Stderr,
+            ..LogLine::new(name, format!("Failed to start: {}", err))
+        });
+    }
+
+    /// Show a process that's already running as external instead of starting it
+    ///
+    /// It's found by what listens on `port`, checked again every [`ADOPT_POLL`]
+    /// until Caboose stops: external while something does, stopped while
+    /// nothing does. `log_file` (relative to the project root) is followed for
+    /// its output. Fails without a port, as there's then no way to find it.
+    pub fn adopt_process(
+        &self,
+        name: String,
+        command: String,
+        port: Option<u16>,
+        log_file: Option<PathBuf>,
+    ) -> Result<Option<u32>, String> {
+        self.adopted.lock().unwrap().insert(name.clone());
+        let listening = port.is_some_and(daemon::is_port_listening);
+        let pid = port
+            .filter(|_| listening)
+            .and_then(daemon::find_listening_pid);
+        self.processes.write().insert(
+            name.clone(),
+            ProcessInfo {
+                name: name.clone(),
+                command,
+                status: if listening {
+                    ProcessStatus::External
+                } else {
+                    ProcessStatus::Stopped
+                },
+                start_time: None,
+                pid,
+                last_exit: None,
+                restarts: 0,
+                crash_count: 0,
+                spawn_error: None,
+            },
+        );
+        let Some(port) = port else {
+            let err = format!(
+                "no port to find the running '{}' by - set port in [processes.{}]",
+                name, name
+            );
+            self.spawn_failed(&name, &err);
+            return Err(err);
+        };
+
+        let found = match pid {
+            Some(pid) => format!("running on port {} (pid {})", port, pid),
+            None if listening => format!("running on port {}", port),
+            None => format!("nothing on port {} yet, watching for it", port),
+        };
+        send_note(
+            &self.log_tx,
+            &name,
+            format!(
+                "Adopted external process: {} - Caboose won't stop it",
+                found
+            ),
+        );
+
+        let log_file = log_file.map(|path| match self.working_dir() {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        });
+        if let Some(path) = log_file {
+            send_note(&self.log_tx, &name, format!("Following {}", path.display()));
+            self.follow_log_file(name.clone(), LogFileFollower::new(path));
         }
+        self.watch_external(name, port);
+        Ok(pid)
+    }
+
+    /// Track an adopted process as it stops and starts outside Caboose
+    fn watch_external(&self, name: String, port: u16) {
+        let processes = self.processes.clone();
+        let log_tx = self.log_tx.clone();
+        let stopping = self.stopping.clone();
+        tokio::spawn(async move {
+            loop {
+                sleep(ADOPT_POLL).await;
+                if stopping.load(Ordering::Relaxed) || log_tx.is_closed() {
+                    break;
+                }
+                let (listening, pid) = tokio::task::spawn_blocking(move || {
+                    let listening = daemon::is_port_listening(port);
+                    (
+                        listening,
+                        listening
+                            .then(|| daemon::find_listening_pid(port))
+                            .flatten(),
+                    )
+                })
+                .await
+                .unwrap_or((false, None));
+
+                let was_listening = {
+                    let mut procs = processes.write();
+                    let Some(info) = procs.get_mut(&name) else {
+                        break;
+                    };
+                    let was_listening = info.status == ProcessStatus::External;
+                    if listening {
+                        info.status = ProcessStatus::External;
+                        // Its owner can't always be looked up; keep what we had
+                        info.pid = pid.or(info.pid.filter(|_| was_listening));
+                    } else {
+                        info.status = ProcessStatus::Stopped;
+                        info.pid = None;
+                    }
+                    was_listening
+                };
+                match (was_listening, listening) {
+                    (true, false) => send_note(
+                        &log_tx,
+                        &name,
+                        format!("No longer listening on port {}", port),
+                    ),
+                    (false, true) => send_note(
+                        &log_tx,
+                        &name,
+                        match pid {
+                            Some(pid) => format!("Now listening on port {} (pid {})", port, pid),
+                            None => format!("Now listening on port {}", port),
+                        },
+                    ),
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    /// Send the lines appended to a log file as `name`'s output
+    fn follow_log_file(&self, name: String, mut follower: LogFileFollower) {
+        let log_tx = self.log_tx.clone();
+        let stopping = self.stopping.clone();
+        tokio::spawn(async move {
+            while !stopping.load(Ordering::Relaxed) && !log_tx.is_closed() {
+                for content in follower.read_lines() {
+                    // Rails colors its development log; keep the colors as markup
+                    let (cleaned_content, markup) = parse_ansi(&content);
+                    let _ = log_tx.send(LogLine {
+                        markup: markup.map(Box::new),
+                        ..LogLine::new(name.clone(), cleaned_content)
+                    });
+                }
+                sleep(LOG_FILE_POLL).await;
+            }
+        });
     }
 
     fn spawn_with_pty(
@@ -133,19 +915,20 @@ impl ProcessManager {
         name: String,
         command: String,
         env_vars: HashMap<String, String>,
+        mut watch: ExitWatch,
     ) -> Result<(), String> {
         let pty_system = native_pty_system();
 
-        let (program, args) = parse_command(&command)?;
+        let (program, args) = parse_command(&command, is_task(&name))?;
 
         let mut cmd = CommandBuilder::new(&program);
         for arg in args {
             cmd.arg(arg);
         }
 
-        // Set working directory to current directory
-        if let Ok(current_dir) = std::env::current_dir() {
-            cmd.cwd(current_dir);
+        // Run from the project root
+        if let Some(dir) = self.working_dir() {
+            cmd.cwd(dir);
         }
 
         // Add environment variables
@@ -170,12 +953,13 @@ impl ProcessManager {
             .map_err(|e| format!("Failed to spawn process: {}", e))?;
 
         let pid = child.process_id();
+        watch.pid = pid;
         let killer = child.clone_killer();
         let child = Arc::new(Mutex::new(child));
 
         // Update process info
         {
-            let mut processes = self.processes.lock().unwrap();
+            let mut processes = self.processes.write();
             if let Some(info) = processes.get_mut(&name) {
                 info.pid = pid;
             }
@@ -193,6 +977,17 @@ impl ProcessManager {
             );
         }
 
+        // Keep the PTY's input for `/send`
+        let writer: PtyWriter = Arc::new(Mutex::new(
+            pair.master
+                .take_writer()
+                .map_err(|e| format!("Failed to take PTY writer: {}", e))?,
+        ));
+        self.pty_writers
+            .lock()
+            .unwrap()
+            .insert(name.clone(), writer.clone());
+
         // Read from PTY and send to log channel
         let reader = pair
             .master
@@ -203,60 +998,71 @@ impl ProcessManager {
         let process_name = name.clone();
         let processes = self.processes.clone();
 
-        tokio::spawn(async move {
+        // Reads block, so they get a thread of their own rather than one of
+        // the runtime's workers
+        tokio::task::spawn_blocking(move || {
             let buf_reader = BufReader::new(reader);
             for line in buf_reader.lines() {
                 match line {
                     Ok(content) => {
-                        // Strip ANSI escape codes (colors, cursor movement, spinners, etc.)
-                        // to prevent them from bleeding into the TUI
-                        let bytes = strip_ansi_escapes::strip(&content);
-                        let cleaned_content = String::from_utf8_lossy(&bytes).to_string();
+                        // Strip ANSI escapes so they can't bleed into the TUI,
+                        // keeping colors and hyperlinks as markup
+                        let (cleaned_content, markup) = parse_ansi(&content);
 
                         let _ = log_tx.send(LogLine {
-                            process_name: process_name.clone(),
-                            content: cleaned_content,
-                            timestamp: Instant::now(),
+                            markup: markup.map(Box::new),
+                            ..LogLine::new(process_name.clone(), cleaned_content)
                         });
                     }
                     Err(_) => break,
                 }
             }
 
-            // Process ended
-            let mut procs = processes.lock().unwrap();
-            if let Some(info) = procs.get_mut(&process_name) {
+            // Process ended (the exit monitor may already have marked it
+            // detached, or it was restarted)
+            let mut procs = processes.write();
+            if let Some(info) = procs.get_mut(&process_name)
+                && info.status == ProcessStatus::Running
+                && info.pid == pid
+            {
                 info.status = ProcessStatus::Stopped;
             }
         });
 
         // Monitor child process
         let process_name = name.clone();
-        let processes = self.processes.clone();
         let child_handles = self.child_handles.clone();
+        let pty_writers = self.pty_writers.clone();
         let child_for_monitor = child.clone();
         tokio::spawn(async move {
-            loop {
-                let done = {
+            let (success, code) = loop {
+                let exited = {
                     let mut guard = child_for_monitor.lock().unwrap();
                     match guard.try_wait() {
-                        Ok(Some(_)) => true,
-                        Ok(None) => false,
-                        Err(_) => true,
+                        Ok(Some(status)) => {
+                            Some((status.success(), Some(status.exit_code() as i32)))
+                        }
+                        Ok(None) => None,
+                        Err(_) => Some((false, None)),
                     }
                 };
-                if done {
-                    break;
+                if let Some(exited) = exited {
+                    break exited;
                 }
                 sleep(Duration::from_millis(100)).await;
-            }
+            };
 
-            let mut procs = processes.lock().unwrap();
-            if let Some(info) = procs.get_mut(&process_name) {
-                info.status = ProcessStatus::Stopped;
+            forget_child(&child_handles, &process_name, &child_for_monitor);
+            {
+                let mut writers = pty_writers.lock().unwrap();
+                if writers
+                    .get(&process_name)
+                    .is_some_and(|current| Arc::ptr_eq(current, &writer))
+                {
+                    writers.remove(&process_name);
+                }
             }
-            let mut handles = child_handles.lock().unwrap();
-            handles.remove(&process_name);
+            watch.child_exited(success, code).await;
         });
 
         Ok(())
@@ -267,15 +1073,16 @@ impl ProcessManager {
         name: String,
         command: String,
         env_vars: HashMap<String, String>,
+        mut watch: ExitWatch,
     ) -> Result<(), String> {
-        let (program, args) = parse_command(&command)?;
+        let (program, args) = parse_command(&command, is_task(&name))?;
 
         let mut cmd = std::process::Command::new(&program);
         cmd.args(&args);
 
-        // Set working directory to current directory
-        if let Ok(current_dir) = std::env::current_dir() {
-            cmd.current_dir(current_dir);
+        // Run from the project root
+        if let Some(dir) = self.working_dir() {
+            cmd.current_dir(dir);
         }
 
         cmd.envs(env_vars);
@@ -286,12 +1093,13 @@ impl ProcessManager {
             .spawn()
             .map_err(|e| format!("Failed to spawn process: {}", e))?;
         let pid = child.id();
+        watch.pid = Some(pid);
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
         let child = Arc::new(Mutex::new(child));
 
         {
-            let mut processes = self.processes.lock().unwrap();
+            let mut processes = self.processes.write();
             if let Some(info) = processes.get_mut(&name) {
                 info.pid = Some(pid);
             }
@@ -308,92 +1116,481 @@ impl ProcessManager {
             );
         }
 
-        // stdout
-        if let Some(stdout) = stdout {
+        // Tools that log to both streams are merged before lines reach the channel
+        let deduper = self
+            .dedupe_streams
+            .then(|| Arc::new(Mutex::new(StreamDeduper::new(DEDUPE_WINDOW))));
+        if let Some(deduper) = deduper.clone() {
             let log_tx = self.log_tx.clone();
-            let process_name = name.clone();
             tokio::spawn(async move {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(content) = line {
-                        // Strip ANSI escape codes to prevent TUI bleeding
-                        let bytes = strip_ansi_escapes::strip(&content);
-                        let cleaned_content = String::from_utf8_lossy(&bytes).to_string();
-
-                        let _ = log_tx.send(LogLine {
-                            process_name: process_name.clone(),
-                            content: cleaned_content,
-                            timestamp: Instant::now(),
-                        });
+                loop {
+                    sleep(DEDUPE_WINDOW).await;
+                    let (ready, finished) = {
+                        let mut deduper = deduper.lock().unwrap();
+                        (deduper.flush_expired(Instant::now()), deduper.is_finished())
+                    };
+                    for line in ready {
+                        let _ = log_tx.send(line);
+                    }
+                    if finished {
+                        break;
                     }
                 }
             });
         }
 
-        // stderr
+        if let Some(stdout) = stdout {
+            spawn_stream_reader(
+                stdout,
+                LogStream::Stdout,
+                name.clone(),
+                self.log_tx.clone(),
+                deduper.clone(),
+            );
+        }
         if let Some(stderr) = stderr {
-            let log_tx = self.log_tx.clone();
-            let process_name = name.clone();
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    if let Ok(content) = line {
-                        // Strip ANSI escape codes to prevent TUI bleeding
-                        let bytes = strip_ansi_escapes::strip(&content);
-                        let cleaned_content = String::from_utf8_lossy(&bytes).to_string();
-
-                        let _ = log_tx.send(LogLine {
-                            process_name: process_name.clone(),
-                            content: cleaned_content,
-                            timestamp: Instant::now(),
-                        });
-                    }
-                }
-            });
+            spawn_stream_reader(
+                stderr,
+                LogStream::Stderr,
+                name.clone(),
+                self.log_tx.clone(),
+                deduper,
+            );
         }
 
         // Monitor child process
-        let processes = self.processes.clone();
         let process_name = name.clone();
         let child_handles = self.child_handles.clone();
         let child = child.clone();
         tokio::spawn(async move {
-            loop {
-                let done = {
+            let (success, code) = loop {
+                let exited = {
                     let mut guard = child.lock().unwrap();
                     match guard.try_wait() {
-                        Ok(Some(_)) => true,
-                        Ok(None) => false,
-                        Err(_) => true,
+                        Ok(Some(status)) => Some((status.success(), status.code())),
+                        Ok(None) => None,
+                        Err(_) => Some((false, None)),
                     }
                 };
-                if done {
-                    break;
+                if let Some(exited) = exited {
+                    break exited;
                 }
                 sleep(Duration::from_millis(100)).await;
+            };
+
+            forget_child(&child_handles, &process_name, &child);
+            watch.child_exited(success, code).await;
+        });
+
+        Ok(())
+    }
+
+    /// Every process; shared until one changes
+    pub fn get_processes(&self) -> Arc<Vec<ProcessInfo>> {
+        self.processes.derive(&self.listed, |processes| {
+            processes.values().cloned().collect()
+        })
+    }
+
+    pub fn get_process(&self, name: &str) -> Option<ProcessInfo> {
+        let processes = self.processes.read();
+        processes.get(name).cloned()
+    }
+
+    /// Write `text` and a newline to a running process's PTY, as if typed
+    ///
+    /// The line is echoed to the process's log marked `[SENT]`. Processes
+    /// started without a PTY (NO_PTY) or adopted from outside Caboose have no
+    /// input to write to.
+    pub fn send_line(&self, name: &str, text: &str) -> Result<(), String> {
+        if text.len() > MAX_SEND_LEN {
+            return Err(format!(
+                "Too long to send: {} bytes (at most {})",
+                text.len(),
+                MAX_SEND_LEN
+            ));
+        }
+        if text.contains(['\n', '\r']) {
+            return Err("Send one line at a time".to_string());
+        }
+        let Some(info) = self.get_process(name) else {
+            return Err(format!("No process named '{}'", name));
+        };
+        if self.is_adopted(name) {
+            return Err(format!(
+                "{} was started outside Caboose, so its input isn't connected",
+                name
+            ));
+        }
+        if info.status != ProcessStatus::Running {
+            return Err(format!("{} isn't running", name));
+        }
+        let writer = self.pty_writers.lock().unwrap().get(name).cloned();
+        let Some(writer) = writer else {
+            return Err(if self.use_pty {
+                format!("{} has no input to write to", name)
+            } else {
+                format!(
+                    "{} runs without a PTY (NO_PTY is set), so its input isn't connected; \
+                     unset NO_PTY to send to it",
+                    name
+                )
+            });
+        };
+
+        {
+            let mut writer = writer
+                .lock()
+                .map_err(|_| format!("Failed to lock input of {}", name))?;
+            writer
+                .write_all(format!("{}\n", text).as_bytes())
+                .and_then(|_| writer.flush())
+                .map_err(|e| format!("Failed to write to {}: {}", name, e))?;
+        }
+
+        let _ = self
+            .log_tx
+            .send(LogLine::new(name, format!("[SENT] {}", text)));
+        Ok(())
+    }
+
+    /// Whether `name` was adopted with [`adopt_process`](Self::adopt_process)
+    pub fn is_adopted(&self, name: &str) -> bool {
+        self.adopted.lock().unwrap().contains(name)
+    }
+
+    /// Check every spawned process is still alive, after the machine slept
+    ///
+    /// Exit monitors poll on timers that don't run while the machine is
+    /// suspended, so each child is reaped now rather than whenever they get to
+    /// it. Spawned processes with a known port are probed: a detached daemon
+    /// that stopped listening is marked stopped, and a process that was ready
+    /// but no longer listens is called out in its log. Adopted processes are
+    /// left to their own watcher. Returns the processes found exited or gone
+    /// quiet, by name.
+    pub fn recheck_liveness(&self, readiness: &HashMap<String, Readiness>) -> Vec<String> {
+        let exited: Vec<String> = {
+            let handles = self.child_handles.lock().unwrap();
+            handles
+                .iter()
+                .filter(|(_, handle)| handle.has_exited())
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+        for name in &exited {
+            send_note(
+                &self.log_tx,
+                name,
+                "Exited while the system was suspended".to_string(),
+            );
+        }
+
+        let ports: Vec<(String, u16)> = self
+            .ports
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, port)| (name.clone(), *port))
+            .collect();
+        let mut quiet = Vec::new();
+        for (name, port) in ports {
+            let Some(status) = self.get_process(&name).map(|info| info.status) else {
+                continue;
+            };
+            let was_serving = match status {
+                ProcessStatus::Detached => true,
+                ProcessStatus::Running => {
+                    !exited.contains(&name) && readiness.get(&name) == Some(&Readiness::Ready)
+                }
+                _ => false,
+            };
+            if !was_serving || daemon::is_port_listening(port) {
+                continue;
             }
-            let mut procs = processes.lock().unwrap();
-            if let Some(info) = procs.get_mut(&process_name) {
+            if status == ProcessStatus::Detached
+                && let Some(info) = self.processes.write().get_mut(&name)
+            {
                 info.status = ProcessStatus::Stopped;
+                info.pid = None;
+            }
+            send_note(
+                &self.log_tx,
+                &name,
+                format!(
+                    "No longer listening on port {} since the system was suspended",
+                    port
+                ),
+            );
+            quiet.push(name);
+        }
+
+        exited.into_iter().chain(quiet).collect()
+    }
+
+    /// Keep `path` up to date with the processes, for `caboose ps`
+    ///
+    /// The processes are looked at every [`STATE_WRITE_INTERVAL`] and written
+    /// out when one was spawned or changed status, until
+    /// [`stop_all`](Self::stop_all) removes the file.
+    pub fn keep_state_file(&self, path: PathBuf) {
+        *self.state_file.lock().unwrap() = Some(path.clone());
+        let processes = self.processes.clone();
+        let stopping = self.stopping.clone();
+        let started_at = Local::now();
+        tokio::spawn(async move {
+            let mut written = None;
+            while !stopping.load(Ordering::Relaxed) {
+                let infos: Vec<ProcessInfo> = processes.read().values().cloned().collect();
+                let mut seen: Vec<_> = infos
+                    .iter()
+                    .map(|info| {
+                        (
+                            info.name.clone(),
+                            info.status.clone(),
+                            info.pid,
+                            info.start_time,
+                        )
+                    })
+                    .collect();
+                seen.sort_by(|a, b| a.0.cmp(&b.0));
+                if written.as_ref() != Some(&seen) {
+                    let state = SessionState::capture(
+                        std::process::id(),
+                        started_at,
+                        &infos,
+                        Instant::now(),
+                        Local::now(),
+                    );
+                    if state.save(&path).is_ok() {
+                        written = Some(seen);
+                    }
+                    // Stopped while writing: don't leave it behind
+                    if stopping.load(Ordering::Relaxed) {
+                        let _ = std::fs::remove_file(&path);
+                    }
+                }
+                sleep(STATE_WRITE_INTERVAL).await;
             }
-            let mut handles = child_handles.lock().unwrap();
-            handles.remove(&process_name);
         });
+    }
 
+    /// Stop one process, leaving the others running
+    ///
+    /// The process is marked stopped rather than crashed, however it exits.
+    /// Returns once it has exited, or after [`STOP_PROCESS_WAIT`]. A detached
+    /// process's daemon is stopped too: asking for it by name is the
+    /// confirmation [`stop_all`](Self::stop_all) would ask for. Adopted
+    /// processes weren't started by Caboose and aren't stopped.
+    pub fn stop_process(&self, name: &str) -> Result<(), String> {
+        if self.is_adopted(name) {
+            return Err(format!(
+                "{} was started outside Caboose, so Caboose won't stop it",
+                name
+            ));
+        }
+        let detached = {
+            let mut processes = self.processes.write();
+            let Some(info) = processes.get_mut(name) else {
+                return Err(format!("No process named '{}'", name));
+            };
+            let detached = (info.status == ProcessStatus::Detached)
+                .then_some(info.pid)
+                .flatten();
+            // Its exit monitor only updates the run it's watching
+            info.status = ProcessStatus::Stopped;
+            info.pid = None;
+            detached
+        };
+
+        // Stopped on purpose: a crash restart waiting to happen doesn't
+        self.restarts_pending.lock().unwrap().remove(name);
+        let handle = self.child_handles.lock().unwrap().remove(name);
+        self.pty_writers.lock().unwrap().remove(name);
+        if let Some(handle) = handle {
+            handle.kill()?;
+            let deadline = Instant::now() + STOP_PROCESS_WAIT;
+            while !handle.has_exited() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
+        if let Some(pid) = detached {
+            daemon::terminate(pid)?;
+            let deadline = Instant::now() + STOP_PROCESS_WAIT;
+            while daemon::is_process_alive(pid) && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
         Ok(())
     }
 
-    pub fn get_processes(&self) -> Vec<ProcessInfo> {
-        let processes = self.processes.lock().unwrap();
-        processes.values().cloned().collect()
+    /// Stop a process and start it again with the same command and
+    /// environment
+    ///
+    /// Works whatever state the process is in; a crashed one is started
+    /// again and shows as running. Its output goes on to the same log.
+    pub fn restart_process(&self, name: &str) -> Result<(), String> {
+        let spawn = self.spawns.lock().unwrap().get(name).cloned();
+        let Some(spawn) = spawn else {
+            return Err(if self.is_adopted(name) {
+                format!(
+                    "{} was started outside Caboose, so Caboose can't restart it",
+                    name
+                )
+            } else {
+                format!("No process named '{}'", name)
+            });
+        };
+        self.stop_process(name)?;
+        // Restarted by hand, crash restarts count from the first attempt again
+        self.restart_attempts.lock().unwrap().remove(name);
+        send_note(&self.log_tx, name, "Restarting".to_string());
+        self.spawn_process(spawn.name, spawn.command, spawn.env)
     }
 
-    pub fn get_process(&self, name: &str) -> Option<ProcessInfo> {
-        let processes = self.processes.lock().unwrap();
-        processes.get(name).cloned()
+    /// Start a stopped or crashed process again with the same command and
+    /// environment
+    ///
+    /// Unlike [`restart_process`](Self::restart_process) it leaves a running
+    /// process alone and says so.
+    pub fn start_process(&self, name: &str) -> Result<(), String> {
+        let spawn = self.spawns.lock().unwrap().get(name).cloned();
+        let Some(spawn) = spawn else {
+            return Err(if self.is_adopted(name) {
+                format!(
+                    "{} was started outside Caboose, so Caboose can't start it",
+                    name
+                )
+            } else {
+                format!("No process named '{}'", name)
+            });
+        };
+        let status = self.get_process(name).map(|info| info.status);
+        if !matches!(
+            status,
+            Some(ProcessStatus::Stopped | ProcessStatus::Crashed)
+        ) {
+            return Err(format!("{} is already running", name));
+        }
+        // Started by hand, a crash restart waiting to happen doesn't
+        self.restarts_pending.lock().unwrap().remove(name);
+        self.restart_attempts.lock().unwrap().remove(name);
+        send_note(&self.log_tx, name, "Starting".to_string());
+        self.spawn_process(spawn.name, spawn.command, spawn.env)
+    }
+
+    /// Start crashed processes again by their restart rules, as their backoff
+    /// runs out
+    ///
+    /// Call once; until then crashed processes wait. A process stopped,
+    /// restarted by hand or already running again by the time its backoff is
+    /// over is left as it is.
+    pub fn follow_restart_policies(self: &Arc<Self>) {
+        let Some(mut due) = self.restart_rx.lock().unwrap().take() else {
+            return;
+        };
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            while let Some(name) = due.recv().await {
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                tokio::task::spawn_blocking(move || manager.restart_crashed(&name));
+            }
+        });
+    }
+
+    /// Start `name` again after a crash, if it's still waiting for that
+    fn restart_crashed(&self, name: &str) {
+        let waiting = self.restarts_pending.lock().unwrap().remove(name);
+        let crashed = self
+            .get_process(name)
+            .is_some_and(|info| info.status == ProcessStatus::Crashed);
+        if !waiting || !crashed || self.stopping.load(Ordering::Relaxed) {
+            return;
+        }
+        let spawn = self.spawns.lock().unwrap().get(name).cloned();
+        if let Some(spawn) = spawn {
+            // A failure to start is logged and leaves it crashed
+            let _ = self.spawn_process(spawn.name, spawn.command, spawn.env);
+        }
+    }
+
+    /// Whether `name` crashed and is waiting out its backoff to be restarted
+    pub fn is_restart_pending(&self, name: &str) -> bool {
+        self.restarts_pending.lock().unwrap().contains(name)
+    }
+
+    /// Restart the processes `caboose restart` asks for in `path`
+    ///
+    /// Looked at every [`STATE_WRITE_INTERVAL`] until
+    /// [`stop_all`](Self::stop_all); requests left over from an earlier
+    /// session are dropped. A request that fails is noted in the process's log.
+    pub fn follow_restart_requests(self: &Arc<Self>, path: PathBuf) {
+        let _ = std::fs::remove_file(&path);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            while !manager.stopping.load(Ordering::Relaxed) {
+                for name in take_restart_requests(&path) {
+                    let restarting = manager.clone();
+                    let result = tokio::task::spawn_blocking(move || {
+                        restarting.restart_process(&name).map_err(|err| (name, err))
+                    })
+                    .await;
+                    if let Ok(Err((name, err))) = result {
+                        send_note(&manager.log_tx, &name, format!("Restart failed: {}", err));
+                    }
+                }
+                sleep(STATE_WRITE_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Take the stop request `caboose stop` leaves in `path` before stopping
+    /// the processes, from then on marking them stopped as they exit and
+    /// restarting none
+    ///
+    /// Looked at every [`STATE_WRITE_INTERVAL`] until
+    /// [`stop_all`](Self::stop_all); a request left over from an earlier
+    /// session is dropped. The file is removed to say it was taken.
+    pub fn follow_stop_request(self: &Arc<Self>, path: PathBuf) {
+        let _ = std::fs::remove_file(&path);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            while !manager.stopping.load(Ordering::Relaxed) {
+                if path.exists() {
+                    manager.stopping.store(true, Ordering::Relaxed);
+                    let _ = std::fs::remove_file(&path);
+                    break;
+                }
+                sleep(STATE_WRITE_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Detached processes that daemonized without `allow_daemonize`
+    pub fn unmanaged_processes(&self) -> Vec<ProcessInfo> {
+        let processes = self.processes.read();
+        processes
+            .values()
+            .filter(|info| {
+                info.status == ProcessStatus::Detached
+                    && info.pid.is_some()
+                    && !self.daemonize_allowed.contains(&info.name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Let [`stop_all`](Self::stop_all) terminate a detached process by its discovered PID
+    pub fn confirm_stop_detached(&self, name: &str) {
+        self.detached_stop_confirmed
+            .lock()
+            .unwrap()
+            .insert(name.to_string());
     }
 
     pub fn stop_all(&self) {
+        self.stopping.store(true, Ordering::Relaxed);
         let handles: Vec<(String, ChildHandle)> = {
             let handles = self.child_handles.lock().unwrap();
             handles
@@ -409,23 +1606,105 @@ impl ProcessManager {
         }
 
         {
-            let mut processes = self.processes.lock().unwrap();
+            let confirmed = self.detached_stop_confirmed.lock().unwrap();
+            let adopted = self.adopted.lock().unwrap();
+            let mut processes = self.processes.write();
             for info in processes.values_mut() {
+                if adopted.contains(&info.name) {
+                    // Not ours to stop
+                    continue;
+                }
+                if info.status == ProcessStatus::Detached {
+                    if !confirmed.contains(&info.name) {
+                        // Still running outside Caboose; leave it as it is
+                        continue;
+                    }
+                    if let Some(pid) = info.pid
+                        && let Err(err) = daemon::terminate(pid)
+                    {
+                        eprintln!("Failed to stop detached process {}: {}", info.name, err);
+                    }
+                }
                 info.status = ProcessStatus::Stopped;
             }
         }
 
         let mut handles = self.child_handles.lock().unwrap();
         handles.clear();
+
+        if let Some(path) = self.state_file.lock().unwrap().take() {
+            let _ = std::fs::remove_file(path);
+        }
     }
 }
 
-fn parse_command(command: &str) -> Result<(String, Vec<String>), String> {
+/// Drop the handle of `name` once its child has exited, unless it's already
+/// that of a later run
+fn forget_child<T>(
+    handles: &Mutex<HashMap<String, ChildHandle>>,
+    name: &str,
+    child: &Arc<Mutex<T>>,
+) {
+    let mut handles = handles.lock().unwrap();
+    if handles
+        .get(name)
+        .is_some_and(|handle| handle.is_child(child))
+    {
+        handles.remove(name);
+    }
+}
+
+/// A line from Caboose about `process_name`, shown in its log
+fn send_note(log_tx: &mpsc::UnboundedSender<LogLine>, process_name: &str, content: String) {
+    let _ = log_tx.send(LogLine::new(process_name, content));
+}
+
+/// Forward one of a plain child's output streams to the log channel
+fn spawn_stream_reader<R: std::io::Read + Send + 'static>(
+    output: R,
+    stream: LogStream,
+    process_name: String,
+    log_tx: mpsc::UnboundedSender<LogLine>,
+    deduper: Option<Arc<Mutex<StreamDeduper>>>,
+) {
+    // Reads block; see the PTY reader
+    tokio::task::spawn_blocking(move || {
+        let reader = BufReader::new(output);
+        for content in reader.lines().map_while(Result::ok) {
+            // Strip ANSI escapes to prevent TUI bleeding, keeping colors and links
+            let (cleaned_content, markup) = parse_ansi(&content);
+
+            let line = LogLine {
+                stream,
+                markup: markup.map(Box::new),
+                ..LogLine::new(process_name.clone(), cleaned_content)
+            };
+            let ready = match &deduper {
+                Some(deduper) => deduper.lock().unwrap().push(line, Instant::now()),
+                None => vec![line],
+            };
+            for line in ready {
+                let _ = log_tx.send(line);
+            }
+        }
+
+        if let Some(deduper) = deduper {
+            for line in deduper.lock().unwrap().close_stream() {
+                let _ = log_tx.send(line);
+            }
+        }
+    });
+}
+
+/// Program and arguments to run `command` with; `shell` runs it through the
+/// shell whatever it looks like, as tasks are, their arguments quoted by
+/// [`shell_join`]
+fn parse_command(command: &str, shell: bool) -> Result<(String, Vec<String>), String> {
     if command.trim().is_empty() {
         return Err("Empty command".to_string());
     }
 
-    if should_use_shell(command) {
+    if shell || should_use_shell(command) {
         let shell = preferred_shell();
         return Ok((
             shell.to_string(),
@@ -0,0 +1,164 @@
+//! Ports processes are set up to listen on, read before anything starts
+//!
+//! Two processes configured for the same port (a frontend passing
+//! `--port 3000` next to the rails server's default) would leave the second to
+//! crash on `EADDRINUSE`. Reading each command and its config up front lets
+//! startup name both processes, and where each port came from, instead.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Where a process's port came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortSource {
+    /// A flag in the command, as written: `-p 3000`, `--port=5173`,
+    /// `-b tcp://0.0.0.0:3000`
+    Flag(String),
+    /// A `PORT=` assignment in the command, as written
+    Assignment(String),
+    /// `PORT` in the process's environment (`.env` or `[processes.<name>] env`)
+    Env,
+    /// `rails server` without a port listens on 3000
+    RailsDefault,
+    /// `port` under `[processes.<name>]`
+    Config(String),
+}
+
+impl fmt::Display for PortSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortSource::Flag(flag) | PortSource::Assignment(flag) => {
+                write!(f, "`{}` in its command", flag)
+            }
+            PortSource::Env => write!(f, "PORT in its environment"),
+            PortSource::RailsDefault => write!(f, "the rails server default"),
+            PortSource::Config(name) => write!(f, "port in [processes.{}]", name),
+        }
+    }
+}
+
+/// A port a process is set up to listen on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortClaim {
+    pub process: String,
+    pub port: u16,
+    pub source: PortSource,
+}
+
+impl PortClaim {
+    /// The port `process` will listen on; `configured` is its
+    /// `[processes.<name>] port`, which wins over the command
+    pub fn for_process(
+        process: &str,
+        command: &str,
+        env: &HashMap<String, String>,
+        configured: Option<u16>,
+    ) -> Option<Self> {
+        let (port, source) = match configured {
+            Some(port) => (port, PortSource::Config(process.to_string())),
+            None => port_intent(command, env)?,
+        };
+        Some(Self {
+            process: process.to_string(),
+            port,
+            source,
+        })
+    }
+}
+
+/// Port a command is expected to listen on
+///
+/// Looks at `-p` / `--port` flags, puma's `-b` / `--bind` URLs and a `PORT=`
+/// prefix, then the `PORT` env var. `$PORT` as a flag value is read from `env`.
+/// `rails server` without an explicit port listens on 3000.
+pub fn port_from_command(command: &str, env: &HashMap<String, String>) -> Option<u16> {
+    port_intent(command, env).map(|(port, _)| port)
+}
+
+/// [`port_from_command`], along with where in the command the port came from
+pub fn port_intent(command: &str, env: &HashMap<String, String>) -> Option<(u16, PortSource)> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+
+    for (i, word) in words.iter().enumerate() {
+        let (value, source) = match *word {
+            "-p" | "--port" | "-b" | "--bind" => match words.get(i + 1) {
+                Some(value) => (*value, PortSource::Flag(format!("{} {}", word, value))),
+                None => continue,
+            },
+            _ => {
+                if let Some(value) = word.strip_prefix("PORT=") {
+                    (value, PortSource::Assignment(word.to_string()))
+                } else if let Some(value) = ["--port=", "-p=", "--bind="]
+                    .iter()
+                    .find_map(|flag| word.strip_prefix(flag))
+                {
+                    (value, PortSource::Flag(word.to_string()))
+                } else {
+                    continue;
+                }
+            }
+        };
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        let port = match *word {
+            "-b" | "--bind" => bind_url_port(value),
+            _ if word.starts_with("--bind=") => bind_url_port(value),
+            _ => port_value(value, env),
+        };
+        if let Some(port) = port {
+            return Some((port, source));
+        }
+    }
+
+    if let Some(port) = env.get("PORT").and_then(|p| p.parse().ok()) {
+        return Some((port, PortSource::Env));
+    }
+
+    let runs_rails_server = words.windows(2).any(|pair| {
+        pair[0].rsplit('/').next() == Some("rails") && matches!(pair[1], "s" | "server")
+    });
+    runs_rails_server.then_some((3000, PortSource::RailsDefault))
+}
+
+/// A port number, or `$PORT` / `${PORT}` looked up in `env`
+fn port_value(value: &str, env: &HashMap<String, String>) -> Option<u16> {
+    let value = match value {
+        "$PORT" | "${PORT}" => env.get("PORT")?.as_str(),
+        _ => value,
+    };
+    value.parse().ok().filter(|&port| port != 0)
+}
+
+/// The port of a puma bind URL such as `tcp://0.0.0.0:3000`; a plain address
+/// (`rails server -b 0.0.0.0`) has none
+fn bind_url_port(value: &str) -> Option<u16> {
+    let (_, rest) = value.split_once("://")?;
+    let authority = rest.split(['?', '/']).next()?;
+    let (_, port) = authority.rsplit_once(':')?;
+    port.parse().ok().filter(|&port| port != 0)
+}
+
+/// One message per port claimed by more than one process, naming each
+/// process and where its port came from, lowest port first
+pub fn port_collisions(claims: &[PortClaim]) -> Vec<String> {
+    let mut by_port: BTreeMap<u16, Vec<&PortClaim>> = BTreeMap::new();
+    for claim in claims {
+        by_port.entry(claim.port).or_default().push(claim);
+    }
+    by_port
+        .into_iter()
+        .filter(|(_, claims)| claims.len() > 1)
+        .map(|(port, claims)| {
+            let names: Vec<String> = claims
+                .iter()
+                .map(|claim| format!("'{}' ({})", claim.process, claim.source))
+                .collect();
+            let (last, rest) = names.split_last().expect("at least two claims");
+            format!(
+                "Port {} is claimed by {} and {}",
+                port,
+                rest.join(", "),
+                last
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+//! Restarting crashed processes by their `restart` policy
+//!
+//! A process with `restart = "on-failure"` under `[processes.<name>]` is
+//! started again after a non-zero exit, waiting `backoff_ms` before the first
+//! attempt and twice as long before each one after, up to `max_restarts`
+//! attempts. A run that stays up for [`STABLE_RUN`] counts as recovered, so
+//! its next crash starts again from the first attempt.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Attempts before giving up, unless `max_restarts` says otherwise
+pub const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+/// Wait before the first attempt, unless `backoff_ms` says otherwise
+pub const DEFAULT_RESTART_BACKOFF: Duration = Duration::from_millis(1000);
+
+/// Longest wait between attempts, however many there have been
+pub const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long a run has to stay up for its crash to start counting attempts afresh
+pub const STABLE_RUN: Duration = Duration::from_secs(60);
+
+/// When a process that exited is started again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Left crashed until restarted by hand
+    #[default]
+    Never,
+    /// Started again after a non-zero exit
+    OnFailure,
+}
+
+/// How a process with `restart = "on-failure"` is restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartRule {
+    pub max_restarts: u32,
+    /// Wait before the first attempt
+    pub backoff: Duration,
+}
+
+impl Default for RestartRule {
+    fn default() -> Self {
+        Self {
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            backoff: DEFAULT_RESTART_BACKOFF,
+        }
+    }
+}
+
+impl RestartRule {
+    /// Wait before attempt `attempt` (counting from 1): the backoff, doubled
+    /// for each attempt before it, at most [`MAX_RESTART_BACKOFF`]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31);
+        self.backoff
+            .saturating_mul(1 << doublings)
+            .min(MAX_RESTART_BACKOFF)
+    }
+}
+
+/// `exited with code 1`, or `was killed` when a signal ended it
+pub fn exit_description(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exited with code {}", code),
+        None => "was killed".to_string(),
+    }
+}
+
+/// `web exited with code 1, restarting (attempt 2/5)`
+pub fn restart_note(name: &str, code: Option<i32>, attempt: u32, rule: &RestartRule) -> String {
+    format!(
+        "{} {}, restarting (attempt {}/{})",
+        name,
+        exit_description(code),
+        attempt,
+        rule.max_restarts
+    )
+}
+
+/// `web exited with code 1, not restarting after 5 attempts`
+pub fn gave_up_note(name: &str, code: Option<i32>, rule: &RestartRule) -> String {
+    format!(
+        "{} {}, not restarting after {} attempts",
+        name,
+        exit_description(code),
+        rule.max_restarts
+    )
+}
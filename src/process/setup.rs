@@ -0,0 +1,217 @@
+//! One-shot setup commands (`[setup]`) run before any process starts
+//!
+//! Each runs to completion from the project root, in order, with its output
+//! handed over line by line. One that exits non-zero or runs past its timeout
+//! stops startup. A command with an `unless_file` stamp is skipped while the
+//! stamp exists; the stamp is written once every command has succeeded.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use super::preferred_shell;
+
+/// How long a setup command may run when neither it nor `[setup]` says
+pub const DEFAULT_SETUP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Name setup output is logged under
+pub const SETUP_PROCESS_NAME: &str = "setup";
+
+/// A command to run before the processes start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetupStep {
+    pub command: String,
+    /// Stamp file, relative to the project root, that skips the command
+    pub unless_file: Option<PathBuf>,
+    pub timeout: Duration,
+}
+
+/// Why a setup command stopped startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupError {
+    /// Couldn't be started at all
+    Spawn {
+        command: String,
+        error: String,
+    },
+    /// Exited non-zero, or was killed by a signal (`code` is `None`)
+    Failed {
+        command: String,
+        code: Option<i32>,
+    },
+    TimedOut {
+        command: String,
+        timeout: Duration,
+    },
+}
+
+impl SetupError {
+    pub fn command(&self) -> &str {
+        match self {
+            SetupError::Spawn { command, .. }
+            | SetupError::Failed { command, .. }
+            | SetupError::TimedOut { command, .. } => command,
+        }
+    }
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetupError::Spawn { command, error } => {
+                write!(f, "setup command `{}` couldn't start: {}", command, error)
+            }
+            SetupError::Failed {
+                command,
+                code: Some(code),
+            } => write!(f, "setup command `{}` exited with code {}", command, code),
+            SetupError::Failed {
+                command,
+                code: None,
+            } => {
+                write!(f, "setup command `{}` was killed", command)
+            }
+            SetupError::TimedOut { command, timeout } => write!(
+                f,
+                "setup command `{}` was stopped after {}s",
+                command,
+                timeout.as_secs()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SetupError {}
+
+impl SetupStep {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            unless_file: None,
+            timeout: DEFAULT_SETUP_TIMEOUT,
+        }
+    }
+
+    pub fn with_unless_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unless_file = Some(path.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the stamp file exists, so the command can be skipped
+    pub fn is_done(&self, root: &Path) -> bool {
+        self.unless_file
+            .as_ref()
+            .is_some_and(|stamp| root.join(stamp).exists())
+    }
+
+    /// Write the stamp file, with its directories, if the command has one
+    pub fn touch_stamp(&self, root: &Path) -> std::io::Result<()> {
+        let Some(stamp) = &self.unless_file else {
+            return Ok(());
+        };
+        let path = root.join(stamp);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, "")
+    }
+
+    /// Run the command from `root` with `env` added, passing each line it
+    /// prints on stdout or stderr to `on_line` as it arrives
+    ///
+    /// Blocks until it exits; past the timeout it's killed.
+    pub fn run(
+        &self,
+        root: &Path,
+        env: &HashMap<String, String>,
+        mut on_line: impl FnMut(&str),
+    ) -> Result<(), SetupError> {
+        let spawn_error = |error: String| SetupError::Spawn {
+            command: self.command.clone(),
+            error,
+        };
+        // Always through the shell: setup lines are written like shell lines
+        let mut child = Command::new(preferred_shell())
+            .args(["-c", &self.command])
+            .current_dir(root)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(e.to_string()))?;
+
+        let (tx, rx) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, tx);
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        // Until both streams close, then until the command exits
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match rx.recv_timeout(left) {
+                Ok(line) => on_line(&line),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(self.timed_out());
+                }
+            }
+        }
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(20))
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(self.timed_out());
+                }
+                Err(e) => return Err(spawn_error(e.to_string())),
+            }
+        };
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(SetupError::Failed {
+                command: self.command.clone(),
+                code: status.code(),
+            })
+        }
+    }
+
+    fn timed_out(&self) -> SetupError {
+        SetupError::TimedOut {
+            command: self.command.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Send each line of `output` to `tx` from a thread of its own
+fn forward_lines<R: Read + Send + 'static>(output: R, tx: mpsc::Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,191 @@
+//! The running session's processes in `.caboose/state.json`, for `caboose ps`
+//!
+//! While a session runs, the process manager writes what it knows about each
+//! process whenever one is spawned or changes status, and removes the file
+//! when it stops them all. `caboose ps` in another terminal reads it back. A
+//! session that ended without cleaning up (killed, or the machine rebooted)
+//! leaves a file whose owner is gone; that one is stale and removed on read.
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use super::{ProcessInfo, ProcessStatus};
+use crate::ui::formatting::format_duration;
+
+/// File in the project's data directory the session state is kept in
+pub const STATE_FILE: &str = "state.json";
+
+/// Time between checks for process changes to write out
+pub const STATE_WRITE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A running session, as written for `caboose ps`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// The Caboose process running the session
+    pub pid: u32,
+    pub started_at: DateTime<Local>,
+    pub processes: Vec<ProcessState>,
+}
+
+/// One process of a running session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessState {
+    pub name: String,
+    pub command: String,
+    pub status: ProcessStatus,
+    pub pid: Option<u32>,
+    /// When the current run started, if it's running
+    pub started_at: Option<DateTime<Local>>,
+}
+
+impl SessionState {
+    /// The session's processes as of `now`, sorted by name
+    ///
+    /// Start times are kept as [`Instant`]s, which mean nothing to another
+    /// process, so they're written as wall-clock times.
+    pub fn capture(
+        pid: u32,
+        started_at: DateTime<Local>,
+        processes: &[ProcessInfo],
+        now: Instant,
+        wall: DateTime<Local>,
+    ) -> Self {
+        let mut processes: Vec<ProcessState> = processes
+            .iter()
+            .map(|info| ProcessState {
+                name: info.name.clone(),
+                command: info.command.clone(),
+                status: info.status.clone(),
+                pid: info.pid,
+                started_at: info.start_time.map(|start| {
+                    wall - TimeDelta::from_std(now.saturating_duration_since(start))
+                        .unwrap_or_default()
+                }),
+            })
+            .collect();
+        processes.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            pid,
+            started_at,
+            processes,
+        }
+    }
+
+    /// Read the state at `path`; `None` when missing or unreadable
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Write the state to `path`, replacing what's there in one step so a
+    /// reader never sees half of it
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize session state: {}", e))?;
+        let partial = path.with_extension("json.tmp");
+        fs::write(&partial, content)
+            .and_then(|_| fs::rename(&partial, path))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// The session running in the project whose state file is `path`
+///
+/// `None` when there's no file, or when the Caboose process that wrote it is
+/// no longer alive; a stale file is removed.
+pub fn running_session(path: &Path, is_alive: impl Fn(u32) -> bool) -> Option<SessionState> {
+    if !path.exists() {
+        return None;
+    }
+    match SessionState::load(path) {
+        Some(state) if is_alive(state.pid) => Some(state),
+        _ => {
+            let _ = fs::remove_file(path);
+            None
+        }
+    }
+}
+
+/// The `caboose ps` table for `state` at `now`
+///
+/// ```text
+/// Session pid 4100, up 1h 2m
+/// NAME    PID    STATUS   UPTIME  COMMAND
+/// web     4242   running  12m 3s  bin/rails server -p 3000
+/// worker  -      crashed  -       bundle exec sidekiq
+/// ```
+///
+/// A process whose pid is no longer alive shows as `exited`: the session
+/// hasn't noticed yet, or can't (it's not one of its children).
+pub fn format_ps(
+    state: &SessionState,
+    now: DateTime<Local>,
+    is_alive: impl Fn(u32) -> bool,
+) -> String {
+    let uptime = |since: DateTime<Local>| {
+        format_duration((now - since).to_std().unwrap_or_default().as_secs())
+    };
+    let rows: Vec<[String; 5]> = state
+        .processes
+        .iter()
+        .map(|process| {
+            let alive = process.pid.is_none_or(&is_alive);
+            let status = match process.status {
+                ProcessStatus::Running | ProcessStatus::Detached | ProcessStatus::External
+                    if !alive =>
+                {
+                    "exited"
+                }
+                ProcessStatus::Running => "running",
+                ProcessStatus::Stopped => "stopped",
+                ProcessStatus::Crashed => "crashed",
+                ProcessStatus::Detached => "detached",
+                ProcessStatus::External => "external",
+            };
+            let uptime = match (&process.status, process.started_at) {
+                (ProcessStatus::Running, Some(since)) if alive => uptime(since),
+                _ => "-".to_string(),
+            };
+            [
+                process.name.clone(),
+                process.pid.map_or("-".to_string(), |pid| pid.to_string()),
+                status.to_string(),
+                uptime,
+                process.command.clone(),
+            ]
+        })
+        .collect();
+
+    let header = ["NAME", "PID", "STATUS", "UPTIME", "COMMAND"].map(str::to_string);
+    let mut widths = [0; 4];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = format!(
+        "Session pid {}, up {}\n",
+        state.pid,
+        uptime(state.started_at)
+    );
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+        for (width, cell) in widths.iter().zip(row) {
+            line.push_str(&format!("{:<width$}  ", cell, width = width));
+        }
+        line.push_str(&row[4]);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,188 @@
+//! Stopping a running session from another terminal, for `caboose stop`
+//!
+//! The session is told first, by leaving `.caboose/stop-request` for it (see
+//! [`ProcessManager::follow_stop_request`](super::ProcessManager::follow_stop_request)),
+//! so it doesn't take the exits that follow for crashes. Then each process
+//! the session started gets SIGTERM and the grace period to exit; any still
+//! running after it gets SIGKILL. Adopted processes were started elsewhere
+//! and are left alone, as the session itself would.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, Signal, System};
+
+use super::{ProcessStatus, SessionState, is_process_alive};
+use crate::project::ProjectRoot;
+
+/// File in the project's data directory `caboose stop` leaves for the
+/// session before stopping its processes
+pub const STOP_REQUEST_FILE: &str = "stop-request";
+
+/// Time `caboose stop` waits for the session to take its stop request
+pub const STOP_REQUEST_WAIT: Duration = Duration::from_secs(2);
+
+/// Time processes get to exit after SIGTERM, unless `--grace` says otherwise
+pub const DEFAULT_STOP_GRACE: Duration = Duration::from_secs(5);
+
+/// Time between checks on processes that were signalled
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+/// Time a process gets to go after SIGKILL before it's reported as failed
+const KILL_WAIT: Duration = Duration::from_secs(2);
+
+/// How stopping one process went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// Exited after SIGTERM
+    Stopped,
+    /// Still running after the grace period, so killed
+    Killed,
+    /// Killed without a grace period (`--force`)
+    ForceKilled,
+    /// Already gone before it was signalled
+    NotRunning,
+    Failed(String),
+}
+
+/// A process `caboose stop` tried to stop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StopResult {
+    pub name: String,
+    pub pid: u32,
+    pub outcome: StopOutcome,
+}
+
+impl StopResult {
+    /// `web: stopped`, `frontend: killed after timeout`
+    pub fn describe(&self) -> String {
+        let outcome = match &self.outcome {
+            StopOutcome::Stopped => "stopped".to_string(),
+            StopOutcome::Killed => "killed after timeout".to_string(),
+            StopOutcome::ForceKilled => "killed".to_string(),
+            StopOutcome::NotRunning => "not running".to_string(),
+            StopOutcome::Failed(err) => format!("failed: {}", err),
+        };
+        format!("{}: {}", self.name, outcome)
+    }
+}
+
+pub fn stop_request_path(project: &ProjectRoot) -> PathBuf {
+    project.data_dir().join(STOP_REQUEST_FILE)
+}
+
+/// Tell the session following `path` its processes are about to be stopped,
+/// and wait up to `wait` for it to take the request
+///
+/// The session removes the file once it has; a request it didn't take is
+/// removed, so a later session doesn't find it.
+pub fn request_stop(path: &Path, wait: Duration) -> Result<(), String> {
+    std::fs::write(path, "").map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let deadline = Instant::now() + wait;
+    while path.exists() {
+        if Instant::now() >= deadline {
+            let _ = std::fs::remove_file(path);
+            return Err("the session didn't answer".to_string());
+        }
+        std::thread::sleep(STOP_POLL);
+    }
+    Ok(())
+}
+
+/// The processes of `state` that `caboose stop` stops, by name and pid
+///
+/// Ones without a pid never started or have already exited.
+pub fn stop_targets(state: &SessionState) -> Vec<(String, u32)> {
+    state
+        .processes
+        .iter()
+        .filter(|process| process.status != ProcessStatus::External)
+        .filter_map(|process| Some((process.name.clone(), process.pid?)))
+        .collect()
+}
+
+/// SIGTERM each of `targets`, then SIGKILL those still running after `grace`
+///
+/// All of them are signalled at once and given the same grace period, so
+/// stopping takes at most `grace` however many there are. A zero grace
+/// period kills them right away.
+pub fn stop_processes(targets: &[(String, u32)], grace: Duration) -> Vec<StopResult> {
+    let mut results: Vec<StopResult> = targets
+        .iter()
+        .map(|(name, pid)| StopResult {
+            name: name.clone(),
+            pid: *pid,
+            outcome: StopOutcome::Stopped,
+        })
+        .collect();
+
+    let force = grace.is_zero();
+    let first_signal = if force { Signal::Kill } else { Signal::Term };
+    let mut waiting = Vec::new();
+    for (index, result) in results.iter_mut().enumerate() {
+        if !is_process_alive(result.pid) {
+            result.outcome = StopOutcome::NotRunning;
+            continue;
+        }
+        match signal(result.pid, first_signal) {
+            Ok(()) => waiting.push(index),
+            Err(err) => result.outcome = StopOutcome::Failed(err),
+        }
+    }
+    if force {
+        for &index in &waiting {
+            results[index].outcome = StopOutcome::ForceKilled;
+        }
+    } else {
+        let deadline = Instant::now() + grace;
+        wait_for_exit(&results, &mut waiting, deadline);
+
+        for &index in &waiting {
+            let result = &mut results[index];
+            result.outcome = match signal(result.pid, Signal::Kill) {
+                Ok(()) => StopOutcome::Killed,
+                Err(err) => StopOutcome::Failed(err),
+            };
+        }
+    }
+    let deadline = Instant::now() + KILL_WAIT;
+    wait_for_exit(&results, &mut waiting, deadline);
+    for index in waiting {
+        results[index].outcome = StopOutcome::Failed("still running after SIGKILL".to_string());
+    }
+
+    results
+}
+
+/// Wait until the processes at `waiting` in `results` have exited, or `deadline`;
+/// those still running are left in `waiting`
+fn wait_for_exit(results: &[StopResult], waiting: &mut Vec<usize>, deadline: Instant) {
+    loop {
+        waiting.retain(|&index| is_process_alive(results[index].pid));
+        if waiting.is_empty() || Instant::now() >= deadline {
+            return;
+        }
+        std::thread::sleep(STOP_POLL);
+    }
+}
+
+/// Ask the Caboose process running a session to exit, as Ctrl+C would
+pub fn ask_session_to_exit(pid: u32) -> Result<(), String> {
+    signal(pid, Signal::Term)
+}
+
+/// Send `signal` to `pid`
+pub(super) fn signal(pid: u32, signal: Signal) -> Result<(), String> {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    system.refresh_process(pid);
+    let process = system
+        .process(pid)
+        .ok_or_else(|| format!("process {} is no longer running", pid))?;
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        // Only SIGKILL is available on every platform
+        None if process.kill() => Ok(()),
+        _ => Err(format!("failed to signal process {}", pid)),
+    }
+}
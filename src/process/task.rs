@@ -0,0 +1,229 @@
+//! One-off tasks: `rails db:migrate`, `yarn build` and the like, run once
+//!
+//! `/run` and `caboose run` start a task alongside the Procfile processes,
+//! named after what it runs (`task:db-migrate`) so its output has a log of
+//! its own. It runs through the shell from the project root with the `.env`
+//! environment, shows in the Processes panel with its exit code once done,
+//! and is dropped [`FINISHED_TASK_TTL`] after that.
+//!
+//! `caboose run` hands the task to the session running in the project
+//! through `.caboose/run-requests`, one shell-quoted command per line (see
+//! [`ProcessManager::follow_run_requests`](super::ProcessManager::follow_run_requests)).
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::{ProcessInfo, ProcessStatus};
+use crate::project::ProjectRoot;
+
+/// Start of every task's process name
+pub const TASK_PREFIX: &str = "task:";
+
+/// How long a finished task stays in the Processes panel
+pub const FINISHED_TASK_TTL: Duration = Duration::from_secs(60);
+
+/// File in the project's data directory `caboose run` leaves tasks in
+pub const RUN_REQUESTS_FILE: &str = "run-requests";
+
+/// Longest name a task gets, after its prefix
+const MAX_TASK_NAME_LEN: usize = 24;
+
+/// Programs that run the task rather than being it: `rails db:migrate` is
+/// `db-migrate`, `bundle exec rspec` is `rspec`
+const RUNNERS: &[&str] = &[
+    "bundle",
+    "exec",
+    "bin/rails",
+    "rails",
+    "bin/rake",
+    "rake",
+    "yarn",
+    "npm",
+    "pnpm",
+    "bun",
+    "npx",
+    "run",
+    "bin/dev",
+];
+
+/// Whether `name` is a task's process name
+pub fn is_task(name: &str) -> bool {
+    name.starts_with(TASK_PREFIX)
+}
+
+/// `task:db-migrate` for `rails db:migrate`: the first word that isn't a
+/// runner, or the program itself
+pub fn task_name(argv: &[String]) -> String {
+    let word = argv
+        .iter()
+        .find(|arg| !RUNNERS.contains(&arg.as_str()) && !arg.starts_with('-'))
+        .or(argv.first())
+        .map(String::as_str)
+        .unwrap_or("task");
+    let word = word.rsplit('/').next().unwrap_or(word);
+    let mut slug = String::new();
+    for c in word.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug
+        .trim_end_matches('-')
+        .chars()
+        .take(MAX_TASK_NAME_LEN)
+        .collect();
+    let slug = slug.trim_end_matches('-');
+    format!(
+        "{}{}",
+        TASK_PREFIX,
+        if slug.is_empty() { "task" } else { slug }
+    )
+}
+
+/// `name`, or `name-2`, `name-3`... when a task of that name is still running
+pub fn unique_task_name(name: &str, running: impl Fn(&str) -> bool) -> String {
+    if !running(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !running(candidate))
+        .unwrap()
+}
+
+/// The arguments as one shell command, each quoted when it needs to be;
+/// `&&`, `||`, `|` and `;` are left for the shell to act on
+pub fn shell_join(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn shell_quote(arg: &str) -> String {
+    if matches!(arg, "&&" | "||" | "|" | ";") {
+        return arg.to_string();
+    }
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Split a command line the way a shell would: words on whitespace,
+/// `'single'` quotes taken literally, `"double"` quotes and `\` escapes
+pub fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // A word was started, even if only by empty quotes
+    let mut in_word = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unclosed ' quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unclosed \" quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unclosed \" quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some(c) => current.push(c),
+                    None => return Err("Nothing after \\ to escape".to_string()),
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Whether `info` is a task that finished at least [`FINISHED_TASK_TTL`]
+/// before `now`
+pub fn is_expired_task(info: &ProcessInfo, now: Instant) -> bool {
+    is_task(&info.name)
+        && info.status != ProcessStatus::Running
+        && info
+            .last_exit
+            .as_ref()
+            .is_some_and(|exit| now.saturating_duration_since(exit.at) >= FINISHED_TASK_TTL)
+}
+
+/// Where `caboose run` leaves tasks for the session running in `project`
+pub fn run_requests_path(project: &ProjectRoot) -> PathBuf {
+    project.data_dir().join(RUN_REQUESTS_FILE)
+}
+
+/// Ask the session reading `path` to run `argv`
+pub fn request_run(path: &Path, argv: &[String]) -> Result<(), String> {
+    if argv.is_empty() {
+        return Err("Nothing to run".to_string());
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", shell_join(argv)))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The tasks asked for since the last look, in the order asked; the requests
+/// are removed and lines that can't be split are skipped
+pub fn take_run_requests(path: &Path) -> Vec<Vec<String>> {
+    // Moved aside first, so a request added while reading isn't lost
+    let taken = path.with_extension("taken");
+    if fs::rename(path, &taken).is_err() {
+        return Vec::new();
+    }
+    let content = fs::read_to_string(&taken).unwrap_or_default();
+    let _ = fs::remove_file(&taken);
+    content
+        .lines()
+        .filter_map(|line| split_args(line).ok())
+        .filter(|argv| !argv.is_empty())
+        .collect()
+}
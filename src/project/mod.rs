@@ -0,0 +1,203 @@
+//! Project root resolution and per-project data storage
+//!
+//! Every detection step (Rails, frontend, git, `.env`, config) and every
+//! spawned process resolves paths against a single [`ProjectRoot`]. It comes
+//! from `--project <path>`, then `CABOOSE_PROJECT`, then the current directory.
+//!
+//! Runtime data for a project lives in `<root>/.caboose/`. The list of recently
+//! used roots is shared across projects and kept in the XDG data directory.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variable that selects the project root when `--project` is absent
+pub const PROJECT_ENV_VAR: &str = "CABOOSE_PROJECT";
+
+/// Name of the per-project data directory inside the project root
+pub const DATA_DIR_NAME: &str = ".caboose";
+
+/// Maximum number of entries kept in the recent projects list
+const MAX_RECENT_PROJECTS: usize = 20;
+
+/// Absolute path to the project Caboose is managing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRoot(PathBuf);
+
+impl ProjectRoot {
+    /// Resolve the project root from an explicit path, `CABOOSE_PROJECT`, or the cwd
+    pub fn resolve(explicit: Option<&Path>) -> Result<Self, String> {
+        if let Some(path) = explicit {
+            return Self::new(path);
+        }
+
+        if let Ok(path) = std::env::var(PROJECT_ENV_VAR)
+            && !path.trim().is_empty()
+        {
+            return Self::new(path.trim());
+        }
+
+        let cwd = std::env::current_dir()
+            .map_err(|e| format!("Failed to read current directory: {}", e))?;
+        Self::new(cwd)
+    }
+
+    /// Create a project root from a path, which must be an existing directory
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Project path '{}' is not accessible: {}", path.display(), e))?;
+
+        if !canonical.is_dir() {
+            return Err(format!(
+                "Project path '{}' is not a directory",
+                canonical.display()
+            ));
+        }
+
+        Ok(ProjectRoot(canonical))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Resolve a path relative to the project root
+    pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.0.join(path)
+    }
+
+    /// Directory for per-project runtime data (`<root>/.caboose`)
+    pub fn data_dir(&self) -> PathBuf {
+        self.0.join(DATA_DIR_NAME)
+    }
+
+    /// Create the data directory if needed and return its path
+    ///
+    /// A `.gitignore` is written inside so runtime files never show up in VCS.
+    pub fn ensure_data_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.data_dir();
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n")
+                .map_err(|e| format!("Failed to write {}: {}", gitignore.display(), e))?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Short display name (the final path component)
+    pub fn name(&self) -> String {
+        self.0
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.0.display().to_string())
+    }
+}
+
+impl AsRef<Path> for ProjectRoot {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ProjectRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+// ============================================================================
+// RECENT PROJECTS
+// ============================================================================
+
+/// A project root that Caboose has been started in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub path: PathBuf,
+    /// Unix timestamp (seconds) of the last run
+    pub last_used: u64,
+}
+
+/// Recently used project roots, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentProjects {
+    pub projects: Vec<RecentProject>,
+}
+
+impl RecentProjects {
+    /// Location of the shared recent-projects file
+    ///
+    /// Uses `$XDG_DATA_HOME/caboose/recent_projects.json`, falling back to
+    /// `~/.local/share/caboose/recent_projects.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })?;
+        Some(base.join("caboose").join("recent_projects.json"))
+    }
+
+    /// Load the list from a file; a missing or unreadable file yields an empty list
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Move `root` to the front of the list, dropping the oldest entries past the limit
+    pub fn record(&mut self, root: &ProjectRoot) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.projects.retain(|p| p.path != root.path());
+        self.projects.insert(
+            0,
+            RecentProject {
+                path: root.path().to_path_buf(),
+                last_used: now,
+            },
+        );
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Look up an entry by its 1-based position as shown by `caboose recent`
+    pub fn get(&self, number: usize) -> Option<&RecentProject> {
+        number.checked_sub(1).and_then(|idx| self.projects.get(idx))
+    }
+}
+
+/// Record `root` in the shared recent-projects file (best effort)
+pub fn remember_project(root: &ProjectRoot) {
+    let Some(path) = RecentProjects::default_path() else {
+        return;
+    };
+
+    let mut recent = RecentProjects::load_from(&path);
+    recent.record(root);
+    if let Err(e) = recent.save_to(&path) {
+        eprintln!("[WARN] Could not update recent projects: {}", e);
+    }
+}
@@ -0,0 +1,304 @@
+//! Which association an N+1's repeated query loads, for an exact `includes`
+//!
+//! The repeated (child) query names its table and, in its `WHERE`, the key it
+//! is loaded by; the request's main (parent) table is the other side. Rails
+//! naming conventions then give the association:
+//!
+//! - `comments.post_id = ?` with parent `posts`: `Post.includes(:comments)`
+//! - `users.id = ?` with parent `posts` (a belongs_to): `Post.includes(:user)`
+//! - `tags` joined through `taggings.post_id = ?`: `Post.includes(:tags)`
+//! - `blog_comments.post_id = ?` with parent `blog_posts`: a namespaced
+//!   model, `Blog::Post.includes(:comments)`
+//!
+//! Columns and foreign keys from `db/schema.rb`, when loaded, confirm the key
+//! (or name a belongs_to that doesn't follow the convention, like `author_id`).
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::database::{DatabaseHealth, TableInfo};
+
+/// How sure an inferred association is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// schema.rb doesn't have the key the names suggest
+    Low,
+    /// Follows Rails naming; nothing to check it against
+    Medium,
+    /// The key is a column or foreign key in schema.rb
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        })
+    }
+}
+
+/// An `includes` that would load a repeated query's rows up front
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EagerLoad {
+    /// Parent model, e.g. `Post` or `Blog::Post`
+    pub model: String,
+    pub association: String,
+    /// Join table of a has_many :through
+    pub through: Option<String>,
+    pub confidence: Confidence,
+    /// What the association was inferred from, e.g. `comments.post_id`
+    pub evidence: String,
+}
+
+impl EagerLoad {
+    /// `Post.includes(:comments)`
+    pub fn code(&self) -> String {
+        format!("{}.includes(:{})", self.model, self.association)
+    }
+}
+
+/// Infer what `parent_table`'s records load one by one with `child_query`
+///
+/// `None` when the query isn't keyed in a way the names (or `schema`) explain.
+pub fn infer_eager_load(
+    parent_table: &str,
+    child_query: &str,
+    schema: &HashMap<String, TableInfo>,
+) -> Option<EagerLoad> {
+    let child = DatabaseHealth::extract_table_name(child_query)?;
+    let (key_table, key_column) = where_key(child_query)?;
+    if child == parent_table {
+        return None;
+    }
+
+    if key_table == child && key_column == "id" {
+        belongs_to(parent_table, &child, schema)
+    } else if key_table == child {
+        has_many(parent_table, &child, &key_column, schema)
+    } else {
+        has_many_through(parent_table, &child, &key_table, &key_column, schema)
+    }
+}
+
+/// `posts` loading `users` by id: `belongs_to :user` (or `:author` when
+/// schema.rb has `posts.author_id` referencing `users`)
+fn belongs_to(parent: &str, child: &str, schema: &HashMap<String, TableInfo>) -> Option<EagerLoad> {
+    let model = classify(parent);
+    let conventional = format!("{}_id", singularize(child));
+    let short =
+        strip_shared_prefix(child, parent).map(|short| format!("{}_id", singularize(short)));
+
+    if let Some(table) = schema.get(parent) {
+        let column = [Some(&conventional), short.as_ref()]
+            .into_iter()
+            .flatten()
+            .find(|column| table.columns.contains(column))
+            .cloned()
+            .or_else(|| {
+                table
+                    .foreign_keys
+                    .iter()
+                    .find(|fk| fk.references_table == child)
+                    .map(|fk| fk.column.clone())
+            });
+        return match column {
+            Some(column) => Some(EagerLoad {
+                model,
+                association: column.trim_end_matches("_id").to_string(),
+                through: None,
+                confidence: Confidence::High,
+                evidence: format!("{}.{} in schema.rb", parent, column),
+            }),
+            None => Some(EagerLoad {
+                model,
+                association: conventional.trim_end_matches("_id").to_string(),
+                through: None,
+                confidence: Confidence::Low,
+                evidence: format!("schema.rb has no {}.{}", parent, conventional),
+            }),
+        };
+    }
+
+    Some(EagerLoad {
+        model,
+        association: conventional.trim_end_matches("_id").to_string(),
+        through: None,
+        confidence: Confidence::Medium,
+        evidence: format!("{} loaded by id for each of {}", child, parent),
+    })
+}
+
+/// `comments.post_id` with parent `posts`: `has_many :comments`
+fn has_many(
+    parent: &str,
+    child: &str,
+    column: &str,
+    schema: &HashMap<String, TableInfo>,
+) -> Option<EagerLoad> {
+    let (model, association) = owner(parent, child, column).or_else(|| {
+        // A key off the convention is only believable as a foreign key
+        schema
+            .get(child)?
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.column == column && fk.references_table == parent)?;
+        Some((classify(parent), child.to_string()))
+    })?;
+    Some(EagerLoad {
+        model,
+        association,
+        through: None,
+        confidence: verify_key(child, column, parent, schema),
+        evidence: format!("{}.{}", child, column),
+    })
+}
+
+/// `tags` joined through `taggings.post_id`: `has_many :tags, through: :taggings`
+///
+/// A join table named after both sides (`posts_tags`) is has_and_belongs_to_many,
+/// which has no `through`.
+fn has_many_through(
+    parent: &str,
+    child: &str,
+    join: &str,
+    column: &str,
+    schema: &HashMap<String, TableInfo>,
+) -> Option<EagerLoad> {
+    let (model, association) = owner(parent, child, column)?;
+    let habtm = [
+        format!("{}_{}", parent, child),
+        format!("{}_{}", child, parent),
+    ]
+    .iter()
+    .any(|name| name == join);
+    Some(EagerLoad {
+        model,
+        association,
+        through: (!habtm).then(|| join.to_string()),
+        confidence: verify_key(join, column, parent, schema),
+        evidence: format!("{}.{}", join, column),
+    })
+}
+
+/// Parent model and association name when `column` is the parent's key
+///
+/// `blog_post_id` is the key of `BlogPost` (`blog_posts`); `post_id` on a
+/// `blog_` table is the key of `Blog::Post`, whose associations drop the prefix.
+fn owner(parent: &str, child: &str, column: &str) -> Option<(String, String)> {
+    let stem = column.strip_suffix("_id")?;
+    if stem == singularize(parent) {
+        return Some((classify(parent), child.to_string()));
+    }
+    let prefix = shared_prefix(parent, child)?;
+    let short_parent = &parent[prefix.len()..];
+    (stem == singularize(short_parent)).then(|| {
+        (
+            format!(
+                "{}::{}",
+                camelize(prefix.trim_end_matches('_')),
+                classify(short_parent)
+            ),
+            child[prefix.len()..].to_string(),
+        )
+    })
+}
+
+/// High when schema.rb has `table.column` (a foreign key to `parent` or just
+/// the column), low when it has the table without it, medium without schema.rb
+fn verify_key(
+    table: &str,
+    column: &str,
+    parent: &str,
+    schema: &HashMap<String, TableInfo>,
+) -> Confidence {
+    match schema.get(table) {
+        Some(info)
+            if info.columns.iter().any(|c| c == column)
+                || info
+                    .foreign_keys
+                    .iter()
+                    .any(|fk| fk.column == column && fk.references_table == parent) =>
+        {
+            Confidence::High
+        }
+        Some(_) => Confidence::Low,
+        None => Confidence::Medium,
+    }
+}
+
+/// Table and column of the first `WHERE` condition, e.g. `comments`, `post_id`
+fn where_key(query: &str) -> Option<(String, String)> {
+    static KEY: OnceLock<Regex> = OnceLock::new();
+    let key = KEY.get_or_init(|| {
+        Regex::new(r#"(?i)\bwhere\s+\(?\s*["`]?(\w+)["`]?\s*\.\s*["`]?(\w+)["`]?\s*(?:=|\bin\b)"#)
+            .unwrap()
+    });
+    let caps = key.captures(query)?;
+    Some((caps[1].to_string(), caps[2].to_string()))
+}
+
+/// Leading `word_` segments two table names share, e.g. `blog_`
+fn shared_prefix<'a>(a: &'a str, b: &str) -> Option<&'a str> {
+    let mut end = 0;
+    for (i, _) in a.match_indices('_') {
+        // Bytes: `b` may have a wider character where `a` has `_`
+        if b.as_bytes().get(..=i) == Some(&a.as_bytes()[..=i]) {
+            end = i + 1;
+        } else {
+            break;
+        }
+    }
+    (end > 0).then(|| &a[..end])
+}
+
+/// `child` without a prefix it shares with `parent`
+fn strip_shared_prefix<'a>(child: &'a str, parent: &str) -> Option<&'a str> {
+    shared_prefix(child, parent).map(|prefix| &child[prefix.len()..])
+}
+
+/// Rails' common singular forms: `categories` → `category`, `addresses` → `address`
+pub fn singularize(word: &str) -> String {
+    let (head, last) = match word.rfind('_') {
+        Some(i) => word.split_at(i + 1),
+        None => ("", word),
+    };
+    let singular = match last {
+        "people" => "person".to_string(),
+        "children" => "child".to_string(),
+        _ if last.ends_with("statuses") || last.ends_with("aliases") => {
+            last[..last.len() - 2].to_string()
+        }
+        _ if last.ends_with("ies") && last.len() > 3 => format!("{}y", &last[..last.len() - 3]),
+        _ if ["sses", "shes", "ches", "xes", "zes"]
+            .iter()
+            .any(|suffix| last.ends_with(suffix)) =>
+        {
+            last[..last.len() - 2].to_string()
+        }
+        _ if last.ends_with('s') && !last.ends_with("ss") => last[..last.len() - 1].to_string(),
+        _ => last.to_string(),
+    };
+    format!("{}{}", head, singular)
+}
+
+/// Model name of a table: `line_items` → `LineItem`
+fn classify(table: &str) -> String {
+    camelize(&singularize(table))
+}
+
+fn camelize(word: &str) -> String {
+    word.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -1,22 +1,59 @@
+mod association;
+mod sql_export;
+
+pub use association::{Confidence, EagerLoad, infer_eager_load, singularize};
+pub(crate) use sql_export::strip_log_prefix;
+pub use sql_export::{request_to_sql, requests_to_sql, statement_to_sql, substitute_binds};
+
+use crate::context::QueueTime;
+use crate::database::{DatabaseHealth, SqlDialect, TableInfo};
+use crate::parser::{BulletWarning, ViewRender};
+use crate::stats::CacheCounts;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct QueryFingerprint {
     pub normalized: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryInfo {
     pub raw_query: String,
     pub fingerprint: QueryFingerprint,
     pub duration: f64,
     pub rows: Option<usize>,
     pub query_type: QueryType,
+    /// Bind `(name, value)` pairs logged after the query, values as logged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binds: Vec<(String, String)>,
+    /// Name Rails logged the query under, e.g. `User Load`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Length in bytes of the line the query was cut from, when it was too
+    /// long to keep whole; `raw_query` is then incomplete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<usize>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl QueryInfo {
+    /// Approximate heap footprint, used by the diagnostics overlay
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.raw_query.len()
+            + self.fingerprint.normalized.len()
+            + self.name.as_ref().map_or(0, String::len)
+            + self
+                .binds
+                .iter()
+                .map(|(name, value)| name.len() + value.len())
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QueryType {
     Select,
     Insert,
@@ -29,8 +66,9 @@ pub enum QueryType {
 }
 
 impl QueryType {
+    /// The type of `sql`, read past the `User Load (0.4ms)` Rails logs in front
     pub fn from_sql(sql: &str) -> Self {
-        let sql_upper = sql.trim().to_uppercase();
+        let sql_upper = sql_export::strip_log_prefix(sql).trim().to_uppercase();
         if sql_upper.starts_with("SELECT") {
             QueryType::Select
         } else if sql_upper.starts_with("INSERT") {
@@ -51,57 +89,657 @@ impl QueryType {
     }
 }
 
+// ============================================================================
+// SELECT * DETECTION
+// ============================================================================
+
+/// Token produced by the lightweight SQL scanner used for `SELECT *` detection
+#[derive(Debug, Clone, PartialEq)]
+enum SqlToken {
+    /// Unquoted keyword or identifier, uppercased
+    Word(String),
+    Star,
+    Comma,
+    /// Quoted identifier, literal, operator or punctuation
+    Other,
+}
+
+/// Split SQL into tokens tagged with their parenthesis depth
+///
+/// String literals, quoted identifiers and comments collapse into single
+/// tokens, so a `*` inside them never counts.
+fn tokenize_sql(sql: &str) -> Vec<(SqlToken, usize)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                // Skip to the closing quote; doubled quotes are escapes
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                tokens.push((SqlToken::Other, depth));
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '*' => tokens.push((SqlToken::Star, depth)),
+            ',' => tokens.push((SqlToken::Comma, depth)),
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let start = i;
+                while i + 1 < chars.len()
+                    && (chars[i + 1].is_alphanumeric()
+                        || chars[i + 1] == '_'
+                        || chars[i + 1] == '$')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..=i].iter().collect();
+                tokens.push((SqlToken::Word(word.to_uppercase()), depth));
+            }
+            c if c.is_whitespace() => {}
+            _ => tokens.push((SqlToken::Other, depth)),
+        }
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Whether a query selects every column with a bare `*` in its top-level select list
+///
+/// Stars inside `COUNT(*)`, `EXISTS (SELECT * ...)` subqueries, string literals
+/// and comments are ignored, as are table-qualified stars such as `"users".*`
+/// that Rails generates for every model load.
+pub fn selects_star(sql: &str) -> bool {
+    let top_level: Vec<SqlToken> = tokenize_sql(sql)
+        .into_iter()
+        .filter(|(_, depth)| *depth == 0)
+        .map(|(token, _)| token)
+        .collect();
+
+    let mut in_select_list = false;
+    let mut item: Vec<&SqlToken> = Vec::new();
+
+    for token in &top_level {
+        match token {
+            SqlToken::Word(word) if word == "SELECT" => {
+                in_select_list = true;
+                item.clear();
+            }
+            _ if !in_select_list => {}
+            SqlToken::Comma => {
+                if item == [&SqlToken::Star] {
+                    return true;
+                }
+                item.clear();
+            }
+            SqlToken::Word(word) if matches!(word.as_str(), "FROM" | "INTO" | "UNION") => {
+                if item == [&SqlToken::Star] {
+                    return true;
+                }
+                in_select_list = false;
+            }
+            SqlToken::Word(word)
+                if item.is_empty() && matches!(word.as_str(), "DISTINCT" | "ALL" | "ON") => {}
+            _ => item.push(token),
+        }
+    }
+
+    in_select_list && item == [&SqlToken::Star]
+}
+
+// ============================================================================
+// QUERY FINGERPRINTS
+// ============================================================================
+
+/// Which differences between queries a fingerprint ignores
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FingerprintOptions {
+    /// Drop `LIMIT ?` and `OFFSET ?`, so paginated and unpaginated forms match
+    pub ignore_limit_offset: bool,
+}
+
+/// Bytes of a query the fingerprint looks at
+///
+/// Well past any stored log line, so it only bounds callers that hand in raw
+/// SQL. Queries that only differ beyond this (the tail of a huge bulk INSERT)
+/// share a fingerprint, which is what grouping wants anyway.
+pub const MAX_FINGERPRINT_INPUT: usize = 256 * 1024;
+
+/// Token produced by the fingerprint scanner
+#[derive(Debug, Clone, PartialEq)]
+enum FingerprintToken {
+    /// Keyword or identifier; lowercased unless quoted
+    Word(String),
+    /// Literal or bind placeholder, rendered as `?`
+    Value,
+    /// Operator or other punctuation
+    Op(String),
+    Open(char),
+    Close(char),
+    Comma,
+}
+
 impl QueryFingerprint {
     pub fn new(query: &str) -> Self {
+        Self::with_options(query, FingerprintOptions::default())
+    }
+
+    pub fn with_options(query: &str, options: FingerprintOptions) -> Self {
         Self {
-            normalized: Self::normalize_query(query),
+            normalized: Self::normalize_query(query, options),
         }
     }
 
     /// Normalize query by replacing values with placeholders
-    fn normalize_query(query: &str) -> String {
-        static NUMBER_PATTERN: OnceLock<Regex> = OnceLock::new();
-        static STRING_PATTERN: OnceLock<Regex> = OnceLock::new();
-        static PLACEHOLDER_PATTERN: OnceLock<Regex> = OnceLock::new();
+    ///
+    /// Lists of values (`IN (1, 2, 3)`, `ARRAY[1, 2]`, multi-row `VALUES`)
+    /// collapse to a single `?`, so their length doesn't split a fingerprint.
+    /// Keywords and unquoted identifiers are lowercased; comments and the
+    /// trailing bind array are dropped.
+    fn normalize_query(query: &str, options: FingerprintOptions) -> String {
+        // Bind values are the query's parameters, not part of its shape
+        let (query, _) = split_binds(query);
+        let mut end = query.len().min(MAX_FINGERPRINT_INPUT);
+        while !query.is_char_boundary(end) {
+            end -= 1;
+        }
+        let query = &query[..end];
 
-        let number_re = NUMBER_PATTERN.get_or_init(|| Regex::new(r"\b\d+\b").unwrap());
-        let string_re = STRING_PATTERN.get_or_init(|| Regex::new(r"'[^']*'").unwrap());
-        let placeholder_re = PLACEHOLDER_PATTERN.get_or_init(|| Regex::new(r"\$\d+").unwrap());
+        let mut tokens: Vec<(FingerprintToken, bool)> = Vec::new();
+        for (token, spaced) in fingerprint_tokens(query) {
+            push_fingerprint_token(&mut tokens, token, spaced, options);
+        }
 
-        let mut normalized = query.to_string();
+        let mut normalized = String::with_capacity(query.len());
+        let mut previous: Option<&FingerprintToken> = None;
+        for (token, spaced) in &tokens {
+            let space = match (previous, token) {
+                (None, _) => false,
+                (_, FingerprintToken::Close(_) | FingerprintToken::Comma) => false,
+                (Some(FingerprintToken::Open(_)), _) => false,
+                (Some(FingerprintToken::Comma), _) => true,
+                _ => *spaced,
+            };
+            if space {
+                normalized.push(' ');
+            }
+            match token {
+                FingerprintToken::Word(text) | FingerprintToken::Op(text) => {
+                    normalized.push_str(text)
+                }
+                FingerprintToken::Value => normalized.push('?'),
+                FingerprintToken::Open(c) | FingerprintToken::Close(c) => normalized.push(*c),
+                FingerprintToken::Comma => normalized.push(','),
+            }
+            previous = Some(token);
+        }
 
-        // Replace placeholders like $1, $2
-        normalized = placeholder_re.replace_all(&normalized, "?").to_string();
+        normalized
+    }
+}
 
-        // Replace string literals
-        normalized = string_re.replace_all(&normalized, "?").to_string();
+/// Index just past the quoted run opening at `start`; doubled quotes are escapes
+fn quoted_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == quote {
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            break;
+        }
+        i += 1;
+    }
+    i + 1
+}
 
-        // Replace numbers
-        normalized = number_re.replace_all(&normalized, "?").to_string();
+/// Replace the contents of each string literal with `mask`, keeping the quotes
+///
+/// Literals are found the way fingerprinting finds them, so `'O''Brien'` is one
+/// literal and quoted identifiers such as `"users"` are left alone.
+pub fn mask_string_literals(sql: &str, mask: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut masked = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' => {
+                let end = quoted_end(&chars, i);
+                masked.push('\'');
+                masked.push_str(mask);
+                if end <= chars.len() {
+                    masked.push('\'');
+                }
+                i = end;
+            }
+            '"' | '`' => {
+                let end = quoted_end(&chars, i).min(chars.len());
+                masked.extend(&chars[i..end]);
+                i = end;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == '\n')
+                    .map_or(chars.len(), |n| i + n);
+                masked.extend(&chars[i..end]);
+                i = end;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let end = chars[i + 2..]
+                    .windows(2)
+                    .position(|w| w == ['*', '/'])
+                    .map_or(chars.len(), |n| i + n + 4);
+                masked.extend(&chars[i..end]);
+                i = end;
+            }
+            _ => {
+                masked.push(c);
+                i += 1;
+            }
+        }
+    }
+    masked
+}
 
-        // Normalize whitespace
-        normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+/// A query without the bind array Rails logs after it, and its binds as
+/// `(name, value)` pairs
+///
+/// Rails appends binds as `  [["id", 1], ["LIMIT", 11]]`. Names lose their
+/// quotes; values are kept as logged, so `"42"` stays distinct from `42`.
+/// Without a well-formed array running to the end of the text (no binds, or a
+/// line cut off mid-array) the query comes back whole, with no binds.
+pub fn split_binds(sql: &str) -> (&str, Vec<(String, String)>) {
+    match bind_spans(sql) {
+        Some((start, spans)) => (
+            sql[..start].trim_end(),
+            spans
+                .into_iter()
+                .map(|span| {
+                    let name = &sql[span.name];
+                    let name = name
+                        .strip_prefix('"')
+                        .and_then(|name| name.strip_suffix('"'))
+                        .unwrap_or(name);
+                    (name.to_string(), sql[span.value].to_string())
+                })
+                .collect(),
+        ),
+        None => (sql, Vec::new()),
+    }
+}
 
-        normalized
+/// Replace the contents of each quoted bind value with `mask`, keeping the quotes
+pub fn mask_bind_values(sql: &str, mask: &str) -> String {
+    let Some((_, spans)) = bind_spans(sql) else {
+        return sql.to_string();
+    };
+    let mut masked = String::with_capacity(sql.len());
+    let mut copied = 0;
+    for span in spans {
+        if is_quoted_bind(&sql[span.value.clone()]) {
+            masked.push_str(&sql[copied..span.value.start]);
+            masked.push('"');
+            masked.push_str(mask);
+            masked.push('"');
+            copied = span.value.end;
+        }
     }
+    masked.push_str(&sql[copied..]);
+    masked
 }
 
-#[derive(Debug, Clone)]
+/// Whether a bind value as logged is a string, rather than a number, `nil` or a list
+pub fn is_quoted_bind(value: &str) -> bool {
+    value.len() >= 2 && value.starts_with('"') && value.ends_with('"')
+}
+
+/// Byte ranges of one bind's name and value
+struct BindSpan {
+    name: std::ops::Range<usize>,
+    value: std::ops::Range<usize>,
+}
+
+/// Where a trailing bind array starts, and its binds
+///
+/// Rails separates the array from the query with two spaces and never puts
+/// two in a row inside it, so only the last such run is a candidate.
+fn bind_spans(sql: &str) -> Option<(usize, Vec<BindSpan>)> {
+    let start = sql.rfind("  [[")?;
+    let spans = parse_bind_array(sql.as_bytes(), start + 2)?;
+    Some((start, spans))
+}
+
+/// Binds of the array opening at `start`, if it's well formed and nothing but
+/// whitespace follows it
+fn parse_bind_array(bytes: &[u8], start: usize) -> Option<Vec<BindSpan>> {
+    let mut spans = Vec::new();
+    let mut i = start + 1;
+    loop {
+        i = skip_spaces(bytes, i);
+        if bytes.get(i) != Some(&b'[') {
+            return None;
+        }
+        let name_start = skip_spaces(bytes, i + 1);
+        let name_end = bind_item_end(bytes, name_start, b',')?;
+        let value_start = skip_spaces(bytes, name_end + 1);
+        let value_end = bind_item_end(bytes, value_start, b']')?;
+        let name = name_start..trim_spaces_end(bytes, name_start, name_end);
+        let value = value_start..trim_spaces_end(bytes, value_start, value_end);
+        if name.is_empty() || value.is_empty() {
+            return None;
+        }
+        spans.push(BindSpan { name, value });
+
+        i = skip_spaces(bytes, value_end + 1);
+        match bytes.get(i) {
+            Some(b',') => i += 1,
+            Some(b']') => break,
+            _ => return None,
+        }
+    }
+    (skip_spaces(bytes, i + 1) == bytes.len()).then_some(spans)
+}
+
+/// Index of the `terminator` ending the item at `start`, stepping over quoted
+/// strings and nested brackets
+fn bind_item_end(bytes: &[u8], start: usize, terminator: u8) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= bytes.len() {
+                    return None;
+                }
+            }
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' if depth > 0 => depth -= 1,
+            c if c == terminator => return Some(i),
+            b']' | b'}' => return None,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn skip_spaces(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    i
+}
+
+fn trim_spaces_end(bytes: &[u8], start: usize, mut end: usize) -> usize {
+    while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    end
+}
+
+/// Split SQL into fingerprint tokens, each tagged with whether whitespace preceded it
+fn fingerprint_tokens(sql: &str) -> Vec<(FingerprintToken, bool)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens: Vec<(FingerprintToken, bool)> = Vec::new();
+    let mut spaced = false;
+    let mut i = 0;
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            c if c.is_whitespace() => {
+                spaced = true;
+                i += 1;
+                continue;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                spaced = true;
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                spaced = true;
+                continue;
+            }
+            '\'' | '"' | '`' => {
+                i = quoted_end(&chars, i);
+                if c == '\'' {
+                    FingerprintToken::Value
+                } else {
+                    // Quoted identifiers are case-sensitive, so kept verbatim
+                    FingerprintToken::Word(chars[start..i.min(chars.len())].iter().collect())
+                }
+            }
+            '(' | '[' => {
+                i += 1;
+                FingerprintToken::Open(c)
+            }
+            ')' | ']' => {
+                i += 1;
+                FingerprintToken::Close(c)
+            }
+            ',' => {
+                i += 1;
+                FingerprintToken::Comma
+            }
+            '?' => {
+                i += 1;
+                FingerprintToken::Value
+            }
+            '$' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                FingerprintToken::Value
+            }
+            // A sign directly before a number, where no operand precedes it
+            '-' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+                && !matches!(
+                    tokens.last(),
+                    Some((
+                        FingerprintToken::Word(_)
+                            | FingerprintToken::Value
+                            | FingerprintToken::Close(_),
+                        _
+                    ))
+                ) =>
+            {
+                i += 1;
+                while i < chars.len() && (is_word_char(chars[i]) || chars[i] == '.') {
+                    i += 1;
+                }
+                FingerprintToken::Value
+            }
+            c if c.is_ascii_digit() => {
+                while i < chars.len() && (is_word_char(chars[i]) || chars[i] == '.') {
+                    i += 1;
+                }
+                FingerprintToken::Value
+            }
+            c if is_word_char(c) => {
+                while i < chars.len() && is_word_char(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                FingerprintToken::Word(word.to_lowercase())
+            }
+            '.' => {
+                i += 1;
+                FingerprintToken::Op(".".to_string())
+            }
+            _ => {
+                // Multi-character operators such as `::`, `>=` and `<>`
+                i += 1;
+                while i < chars.len()
+                    && "<>=!:|&+-*/%~^@#".contains(chars[i])
+                    && !(chars[i] == '-' && chars.get(i + 1) == Some(&'-'))
+                    && !(chars[i] == '/' && chars.get(i + 1) == Some(&'*'))
+                {
+                    i += 1;
+                }
+                FingerprintToken::Op(chars[start..i].iter().collect())
+            }
+        };
+        tokens.push((token, spaced));
+        spaced = false;
+    }
+
+    tokens
+}
+
+/// Append a token, collapsing value lists and (optionally) pagination as they complete
+fn push_fingerprint_token(
+    tokens: &mut Vec<(FingerprintToken, bool)>,
+    token: FingerprintToken,
+    spaced: bool,
+    options: FingerprintOptions,
+) {
+    match &token {
+        FingerprintToken::Value
+            if options.ignore_limit_offset
+                && matches!(
+                    tokens.last(),
+                    Some((FingerprintToken::Word(word), _)) if word == "limit" || word == "offset"
+                ) =>
+        {
+            tokens.pop();
+            return;
+        }
+        FingerprintToken::Close(close) => {
+            // `(?, ?, ?)` -> `(?)`: walk back over `?`s separated by commas
+            let mut k = tokens.len();
+            let mut values = 0;
+            while k > 0 && tokens[k - 1].0 == FingerprintToken::Value {
+                values += 1;
+                k -= 1;
+                if k > 0 && tokens[k - 1].0 == FingerprintToken::Comma {
+                    k -= 1;
+                } else {
+                    break;
+                }
+            }
+            let open = if *close == ')' { '(' } else { '[' };
+            if values > 1 && k > 0 && tokens[k - 1].0 == FingerprintToken::Open(open) {
+                tokens.truncate(k + 1);
+            }
+        }
+        _ => {}
+    }
+    tokens.push((token, spaced));
+
+    // `(?), (?)` -> `(?)`, for multi-row VALUES and row-value IN lists
+    let group = [
+        FingerprintToken::Open('('),
+        FingerprintToken::Value,
+        FingerprintToken::Close(')'),
+    ];
+    let n = tokens.len();
+    if n >= 7
+        && tokens[n - 3..].iter().map(|(t, _)| t).eq(group.iter())
+        && tokens[n - 4].0 == FingerprintToken::Comma
+        && tokens[n - 7..n - 4].iter().map(|(t, _)| t).eq(group.iter())
+    {
+        tokens.truncate(n - 4);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestContext {
+    /// Assigned by `RequestContextTracker` when the request starts; 0 if untracked
+    pub id: u64,
     pub queries: Vec<QueryInfo>,
+    /// Templates, partials and layouts rendered, in log order
+    pub views: Vec<ViewRender>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub start_time: std::time::Instant,
+    /// HTTP method from the "Started" line
+    pub method: Option<String>,
     pub path: Option<String>,
+    /// `Controller#action` from the "Processing by" line, when logged
+    pub controller: Option<String>,
+    /// Cache store activity logged while the request was active
+    pub cache: CacheCounts,
+    /// Time spent queued before Rails started on it, when logged or estimated
+    pub queue_time: Option<QueueTime>,
+    /// Hash of the `Parameters:` line, for telling repeated requests apart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params_hash: Option<u64>,
+    /// Id the app logged for the request (`request_id` field or log tag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl RequestContext {
     pub fn new(path: Option<String>) -> Self {
         Self {
+            id: 0,
             queries: Vec::new(),
+            views: Vec::new(),
             start_time: std::time::Instant::now(),
+            method: None,
             path,
+            controller: None,
+            cache: CacheCounts::default(),
+            queue_time: None,
+            params_hash: None,
+            request_id: None,
         }
     }
 
+    /// First 8 characters of the request id, enough to tell requests apart
+    pub fn short_request_id(&self) -> Option<&str> {
+        self.request_id
+            .as_deref()
+            .map(|id| id.char_indices().nth(8).map_or(id, |(end, _)| &id[..end]))
+    }
+
     pub fn add_query(&mut self, query: QueryInfo) {
         self.queries.push(query);
     }
@@ -113,22 +751,78 @@ impl RequestContext {
     pub fn query_count(&self) -> usize {
         self.queries.len()
     }
+
+    pub fn add_view(&mut self, view: ViewRender) {
+        self.views.push(view);
+    }
+
+    /// Rendered views, slowest first
+    pub fn views_by_duration(&self) -> Vec<&ViewRender> {
+        let mut views: Vec<&ViewRender> = self.views.iter().collect();
+        views.sort_by(|a, b| b.duration.total_cmp(&a.duration));
+        views
+    }
+
+    /// Approximate heap footprint, including every collected query
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.method.as_ref().map_or(0, |m| m.len())
+            + self.path.as_ref().map_or(0, |p| p.len())
+            + self.request_id.as_ref().map_or(0, |id| id.len())
+            + self
+                .queries
+                .iter()
+                .map(QueryInfo::approx_bytes)
+                .sum::<usize>()
+            + self
+                .views
+                .iter()
+                .map(|view| std::mem::size_of::<ViewRender>() + view.template.len())
+                .sum::<usize>()
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Who flagged an N+1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NPlusOneSource {
+    /// The same query repeated within a request
+    #[default]
+    Heuristic,
+    /// Reported by the Bullet gem, which knows the association
+    Bullet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NPlusOneIssue {
     pub fingerprint: QueryFingerprint,
     pub count: usize,
     pub total_duration: f64,
     pub sample_query: String,
     pub suggestion: String,
+    #[serde(default)]
+    pub source: NPlusOneSource,
+}
+
+impl NPlusOneIssue {
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.fingerprint.normalized.len()
+            + self.sample_query.len()
+            + self.suggestion.len()
+    }
 }
 
 pub struct NPlusOneDetector;
 
 impl NPlusOneDetector {
     /// Detect N+1 queries in a request context
-    pub fn detect(context: &RequestContext) -> Vec<NPlusOneIssue> {
+    ///
+    /// Tables in `schema` (from schema.rb, may be empty) confirm the
+    /// association each suggestion eager loads.
+    pub fn detect(
+        context: &RequestContext,
+        schema: &HashMap<String, TableInfo>,
+    ) -> Vec<NPlusOneIssue> {
         let mut issues = Vec::new();
         let mut fingerprint_counts: HashMap<QueryFingerprint, Vec<&QueryInfo>> = HashMap::new();
 
@@ -138,7 +832,7 @@ impl NPlusOneDetector {
             if query.query_type == QueryType::Select {
                 fingerprint_counts
                     .entry(query.fingerprint.clone())
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(query);
             }
         }
@@ -150,7 +844,10 @@ impl NPlusOneDetector {
                 let total_duration: f64 = queries.iter().map(|q| q.duration).sum();
                 let sample_query = queries[0].raw_query.clone();
 
-                let suggestion = Self::generate_suggestion(&sample_query, queries.len());
+                let eager_load = Self::parent_table(context, &fingerprint, &sample_query)
+                    .and_then(|parent| infer_eager_load(&parent, &sample_query, schema));
+                let suggestion =
+                    Self::generate_suggestion(&sample_query, queries.len(), eager_load.as_ref());
 
                 issues.push(NPlusOneIssue {
                     fingerprint,
@@ -158,33 +855,118 @@ impl NPlusOneDetector {
                     total_duration,
                     sample_query,
                     suggestion,
+                    source: NPlusOneSource::Heuristic,
                 });
             }
         }
 
         // Sort by count (most repeated first)
-        issues.sort_by(|a, b| b.count.cmp(&a.count));
+        issues.sort_by_key(|i| std::cmp::Reverse(i.count));
         issues
     }
 
-    fn generate_suggestion(query: &str, count: usize) -> String {
-        // Try to extract table name
+    /// The issue for a Bullet report, taking the repeated query from the
+    /// heuristic's `candidates` when one reads the association's table
+    pub fn from_bullet(candidates: &[NPlusOneIssue], warning: &BulletWarning) -> NPlusOneIssue {
+        let matched = candidates.iter().find(|issue| {
+            Self::table_name(&issue.sample_query).is_some_and(|table| {
+                let table = table.to_lowercase();
+                warning.associations.iter().any(|association| {
+                    let stem = association.trim_end_matches('s').trim_end_matches('y');
+                    !stem.is_empty() && table.starts_with(&stem.to_lowercase())
+                })
+            })
+        });
+
+        let suggestion = warning.suggestion();
+        match matched {
+            Some(issue) => NPlusOneIssue {
+                suggestion,
+                source: NPlusOneSource::Bullet,
+                ..issue.clone()
+            },
+            None => NPlusOneIssue {
+                fingerprint: QueryFingerprint {
+                    normalized: format!(
+                        "{} => [{}]",
+                        warning.model,
+                        warning.associations.join(", ")
+                    ),
+                },
+                count: 0,
+                total_duration: 0.0,
+                sample_query: String::new(),
+                suggestion,
+                source: NPlusOneSource::Bullet,
+            },
+        }
+    }
+
+    fn table_name(query: &str) -> Option<&str> {
         static TABLE_PATTERN: OnceLock<Regex> = OnceLock::new();
         let table_re = TABLE_PATTERN.get_or_init(|| Regex::new(r#"FROM\s+"?(\w+)"?"#).unwrap());
+        Some(table_re.captures(query)?.get(1)?.as_str())
+    }
 
-        if let Some(caps) = table_re.captures(query) {
-            let table = &caps[1];
-            format!(
-                "Possible N+1 query detected ({} times). Consider using eager loading:\n  \
-                Model.includes(:{}) instead of lazy loading",
+    /// The request's main table: the one most of its other SELECTs read,
+    /// the earliest on a tie (usually the query that loaded the parents)
+    fn parent_table(
+        context: &RequestContext,
+        repeated: &QueryFingerprint,
+        sample_query: &str,
+    ) -> Option<String> {
+        let child = DatabaseHealth::extract_table_name(sample_query)?;
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for query in &context.queries {
+            if query.query_type != QueryType::Select || &query.fingerprint == repeated {
+                continue;
+            }
+            let Some(table) = DatabaseHealth::extract_table_name(&query.raw_query) else {
+                continue;
+            };
+            if table == child {
+                continue;
+            }
+            match counts.iter_mut().find(|(name, _)| *name == table) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((table, 1)),
+            }
+        }
+        // max_by_key keeps the last of equals; reversing keeps the earliest
+        counts
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(table, _)| table)
+    }
+
+    fn generate_suggestion(query: &str, count: usize, eager_load: Option<&EagerLoad>) -> String {
+        if let Some(eager_load) = eager_load {
+            let through = eager_load
+                .through
+                .as_ref()
+                .map(|join| format!("  # through :{}", join))
+                .unwrap_or_default();
+            return format!(
+                "Possible N+1 query detected ({} times). Eager load the association:\n  \
+                {}{}\n  Confidence: {} ({})",
                 count,
-                table.trim_end_matches('s') // Simple singularization
-            )
-        } else {
-            format!(
+                eager_load.code(),
+                through,
+                eager_load.confidence,
+                eager_load.evidence
+            );
+        }
+        match Self::table_name(query) {
+            Some(table) => format!(
+                "Possible N+1 query detected ({} times). Eager load the association that \
+                reads {} with .includes() or .preload()",
+                count, table
+            ),
+            None => format!(
                 "Possible N+1 query detected ({} times). Consider using eager loading with .includes() or .preload()",
                 count
-            )
+            ),
         }
     }
 }
@@ -218,10 +1000,15 @@ pub struct QueryAnalyzer;
 
 impl QueryAnalyzer {
     pub fn analyze(query: &QueryInfo) -> Vec<QueryRecommendation> {
+        Self::analyze_for(query, SqlDialect::Generic)
+    }
+
+    /// Like [`QueryAnalyzer::analyze`], with index migrations written for `dialect`
+    pub fn analyze_for(query: &QueryInfo, dialect: SqlDialect) -> Vec<QueryRecommendation> {
         let mut recommendations = Vec::new();
 
         // Check for SELECT *
-        if query.raw_query.contains("SELECT *") {
+        if selects_star(&query.raw_query) {
             recommendations.push(QueryRecommendation {
                 issue_type: PerformanceIssue::SelectStar,
                 severity: Severity::Medium,
@@ -244,49 +1031,38 @@ impl QueryAnalyzer {
                 },
                 message: format!("Slow query: {:.1}ms", query.duration),
                 suggestion: "Consider adding indexes or optimizing the query".to_string(),
-                migration_code: Self::suggest_index(&query.raw_query),
+                migration_code: Self::suggest_index(&query.raw_query, dialect),
             });
         }
 
         // Check for large result sets (if we have row count)
-        if let Some(rows) = query.rows {
-            if rows > 100 {
-                recommendations.push(QueryRecommendation {
-                    issue_type: PerformanceIssue::LargeResultSet,
-                    severity: if rows > 1000 {
-                        Severity::High
-                    } else {
-                        Severity::Medium
-                    },
-                    message: format!("Large result set: {} rows", rows),
-                    suggestion: "Consider using pagination (limit/offset) or find_each".to_string(),
-                    migration_code: None,
-                });
-            }
+        if let Some(rows) = query.rows
+            && rows > 100
+        {
+            recommendations.push(QueryRecommendation {
+                issue_type: PerformanceIssue::LargeResultSet,
+                severity: if rows > 1000 {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                message: format!("Large result set: {} rows", rows),
+                suggestion: "Consider using pagination (limit/offset) or find_each".to_string(),
+                migration_code: None,
+            });
         }
 
         recommendations
     }
 
-    fn suggest_index(query: &str) -> Option<String> {
+    fn suggest_index(query: &str, dialect: SqlDialect) -> Option<String> {
         // Simple index suggestion based on WHERE clause
         static WHERE_PATTERN: OnceLock<Regex> = OnceLock::new();
-        let where_re = WHERE_PATTERN
-            .get_or_init(|| Regex::new(r#"WHERE\s+"?(\w+)"?\."?(\w+)"?\s*="#).unwrap());
-
-        if let Some(caps) = where_re.captures(query) {
-            let table = &caps[1];
-            let column = &caps[2];
-
-            Some(format!(
-                "# Add to migration:\nadd_index :{}, :{}\n\n# Or generate:\nrails g migration AddIndexTo{} {}:index",
-                table,
-                column,
-                table.chars().next()?.to_uppercase().collect::<String>() + &table[1..],
-                column
-            ))
-        } else {
-            None
-        }
+        let where_re = WHERE_PATTERN.get_or_init(|| {
+            Regex::new(r#"(?i)WHERE\s+["`]?(\w+)["`]?\.["`]?(\w+)["`]?\s*="#).unwrap()
+        });
+
+        let caps = where_re.captures(query)?;
+        Some(dialect.add_index_migration(&caps[1], &caps[2]))
     }
 }
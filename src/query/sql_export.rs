@@ -0,0 +1,213 @@
+//! Requests' SQL as a `.sql` file, for replaying against a local database
+//!
+//! Statements come out in the order they ran, each under a comment with the
+//! name and duration Rails logged. Bind placeholders (`$1`, `?`) are filled
+//! in from the logged bind values, so the file runs as is; `BEGIN`, `COMMIT`
+//! and savepoints are kept where they were. A statement whose log line was
+//! too long to keep whole is commented out with a note rather than written
+//! half-finished.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::context::CompletedRequest;
+use crate::privacy::Privacy;
+
+use super::QueryInfo;
+
+/// `User Load (0.4ms)  SELECT ...`, `TRANSACTION (0.2ms)  BEGIN`: the name
+/// and duration Rails logs in front of a statement
+fn log_prefix_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^\s*([A-Za-z][\w:]*(?: [\w:]+)*) \((\d+(?:\.\d+)?)ms\)\s+(\S.*)$").unwrap()
+    })
+}
+
+/// The statement of a logged query, without the name and duration in front
+pub(crate) fn strip_log_prefix(query: &str) -> &str {
+    match log_prefix_pattern().captures(query) {
+        Some(caps) => caps.get(3).map_or("", |m| m.as_str()),
+        None => query,
+    }
+}
+
+/// `requests` as one `.sql` file, a header comment above each request's
+/// statements
+pub fn requests_to_sql(requests: &[CompletedRequest], privacy: &Privacy) -> String {
+    let statements: usize = requests.iter().map(|req| req.context.queries.len()).sum();
+    let mut sql = format!(
+        "-- {} statement{} from {} request{}, exported by Caboose\n",
+        statements,
+        if statements == 1 { "" } else { "s" },
+        requests.len(),
+        if requests.len() == 1 { "" } else { "s" }
+    );
+    for request in requests {
+        sql.push('\n');
+        sql.push_str(&request_to_sql(request, privacy));
+    }
+    sql
+}
+
+/// One request's statements under a `-- GET /users -> UsersController#index`
+/// comment
+pub fn request_to_sql(request: &CompletedRequest, privacy: &Privacy) -> String {
+    let context = &request.context;
+    let mut header = format!(
+        "-- {} {}",
+        context.method.as_deref().unwrap_or("?"),
+        privacy.text(context.path.as_deref().unwrap_or("?"))
+    );
+    if let Some(controller) = &context.controller {
+        header.push_str(&format!(" -> {}", controller));
+    }
+    if let Some(status) = request.status {
+        header.push_str(&format!(", {}", status));
+    }
+    if let Some(duration) = request.total_duration {
+        header.push_str(&format!(" in {:.1}ms", duration));
+    }
+    if let Some(id) = &context.request_id {
+        header.push_str(&format!(", request {}", id));
+    }
+
+    let mut sql = header + "\n";
+    if context.queries.is_empty() {
+        sql.push_str("-- (no queries)\n");
+    }
+    for query in &context.queries {
+        sql.push_str(&statement_to_sql(query, privacy));
+    }
+    sql
+}
+
+/// A statement with its comment, ending in `;`
+pub fn statement_to_sql(query: &QueryInfo, privacy: &Privacy) -> String {
+    let (name, duration, statement) = match log_prefix_pattern().captures(&query.raw_query) {
+        Some(caps) => (
+            query.name.clone().or_else(|| Some(caps[1].to_string())),
+            caps[2].parse().unwrap_or(query.duration),
+            caps.get(3).map_or("", |m| m.as_str()),
+        ),
+        None => (query.name.clone(), query.duration, query.raw_query.trim()),
+    };
+    let comment = match name {
+        Some(name) => format!("-- {} ({:.1}ms)\n", name, duration),
+        None => format!("-- ({:.1}ms)\n", duration),
+    };
+
+    if let Some(original_len) = query.truncated {
+        let commented: Vec<String> = privacy
+            .sql(statement)
+            .lines()
+            .map(|line| format!("-- {}", line))
+            .collect();
+        return format!(
+            "{}-- TRUNCATED: the logged line was {} bytes, longer than [logs] max_line_length, \
+             so only its start is below, commented out\n{}\n",
+            comment,
+            original_len,
+            commented.join("\n")
+        );
+    }
+
+    let (filled, unbound) = substitute_binds(statement, &query.binds);
+    let mut sql = comment;
+    if unbound > 0 {
+        sql.push_str(&format!(
+            "-- {} placeholder{} had no logged bind value\n",
+            unbound,
+            if unbound == 1 { "" } else { "s" }
+        ));
+    }
+    let filled = privacy.sql(&filled);
+    sql.push_str(filled.trim_end_matches(';'));
+    sql.push_str(";\n");
+    sql
+}
+
+/// `sql` with its `$1` / `?` placeholders replaced by `binds`' values as SQL
+/// literals, and how many placeholders were left without one
+///
+/// `$n` takes the n-th bind, each `?` the next. A statement using `$n` is
+/// left with its `?`s, PostgreSQL's JSON operator. Placeholders inside quoted
+/// strings and identifiers are left alone, and without binds `sql` comes
+/// back unchanged.
+pub fn substitute_binds<'a>(sql: &'a str, binds: &[(String, String)]) -> (Cow<'a, str>, usize) {
+    if binds.is_empty() {
+        return (Cow::Borrowed(sql), 0);
+    }
+
+    let numbered = sql
+        .as_bytes()
+        .windows(2)
+        .any(|pair| pair[0] == b'$' && pair[1].is_ascii_digit());
+    let mut filled = String::with_capacity(sql.len());
+    let mut unbound = 0;
+    let mut next = 0;
+    let mut quote: Option<char> = None;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '$') if chars.peek().is_some_and(|(_, d)| d.is_ascii_digit()) => {
+                let mut number = String::new();
+                while let Some(&(_, d)) = chars.peek().filter(|(_, d)| d.is_ascii_digit()) {
+                    number.push(d);
+                    chars.next();
+                }
+                match number
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|index| binds.get(index))
+                {
+                    Some((_, value)) => filled.push_str(&sql_literal(value)),
+                    None => {
+                        unbound += 1;
+                        filled.push('$');
+                        filled.push_str(&number);
+                    }
+                }
+                continue;
+            }
+            (None, '?') if !numbered => {
+                match binds.get(next) {
+                    Some((_, value)) => filled.push_str(&sql_literal(value)),
+                    None => {
+                        unbound += 1;
+                        filled.push('?');
+                    }
+                }
+                next += 1;
+                continue;
+            }
+            _ => {}
+        }
+        filled.push(c);
+    }
+    (Cow::Owned(filled), unbound)
+}
+
+/// A bind value as Rails logged it (Ruby `inspect`) as an SQL literal:
+/// `"O'Brien"` becomes `'O''Brien'`, `nil` becomes `NULL`
+fn sql_literal(value: &str) -> String {
+    if value == "nil" {
+        return "NULL".to_string();
+    }
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(string) => {
+            let unescaped = string.replace("\\\"", "\"").replace("\\\\", "\\");
+            format!("'{}'", unescaped.replace('\'', "''"))
+        }
+        None => value.to_string(),
+    }
+}
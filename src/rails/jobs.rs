@@ -0,0 +1,198 @@
+//! Worker layout from the background job framework's config
+//!
+//! Reads `config/sidekiq.yml` for Sidekiq, and `config/queue.yml` plus
+//! `config/recurring.yml` for Solid Queue. A config that cannot be parsed
+//! leaves the groups empty and records a warning, so the Procfile falls back
+//! to a single worker entry.
+
+use std::fs;
+use std::path::Path;
+
+use super::yaml::{self, Yaml};
+
+pub const SIDEKIQ_CONFIG: &str = "config/sidekiq.yml";
+pub const SOLID_QUEUE_CONFIG: &str = "config/queue.yml";
+pub const SOLID_QUEUE_RECURRING: &str = "config/recurring.yml";
+
+/// One worker process group
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkerGroup {
+    /// Capsule name, when the config names the group
+    pub name: Option<String>,
+    /// Queues in priority order; weighted queues are kept as `name,weight`
+    pub queues: Vec<String>,
+    /// Threads per process
+    pub concurrency: Option<u32>,
+    /// Number of processes (Solid Queue only)
+    pub processes: Option<u32>,
+}
+
+impl WorkerGroup {
+    /// Short identifier for Procfile names: the group name, else its first queue
+    pub fn label(&self) -> String {
+        let raw = self
+            .name
+            .as_deref()
+            .or_else(|| {
+                self.queues
+                    .first()
+                    .map(|q| q.split(',').next().unwrap_or(q))
+            })
+            .unwrap_or("default");
+
+        let label: String = raw
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let label = label.trim_matches('_');
+        if label.is_empty() {
+            "all".to_string()
+        } else {
+            label.to_lowercase()
+        }
+    }
+}
+
+/// Worker groups and recurring tasks found in the job config
+#[derive(Debug, Clone, Default)]
+pub struct JobConfig {
+    pub groups: Vec<WorkerGroup>,
+    pub recurring_tasks: usize,
+    /// Set when a config file exists but could not be read
+    pub warning: Option<String>,
+}
+
+impl JobConfig {
+    /// Read the config for `framework` ("sidekiq" or "solid_queue") inside `root`
+    pub fn detect(root: &Path, framework: &str) -> Self {
+        match framework {
+            "sidekiq" => Self::load_sidekiq(root),
+            "solid_queue" => Self::load_solid_queue(root),
+            _ => Self::default(),
+        }
+    }
+
+    fn load_sidekiq(root: &Path) -> Self {
+        let mut config = Self::default();
+        match read_yaml(root, SIDEKIQ_CONFIG) {
+            Ok(Some(doc)) => config.groups = sidekiq_groups(&doc),
+            Ok(None) => {}
+            Err(e) => config.warning = Some(e),
+        }
+        config
+    }
+
+    fn load_solid_queue(root: &Path) -> Self {
+        let mut config = Self::default();
+
+        match read_yaml(root, SOLID_QUEUE_CONFIG) {
+            Ok(Some(doc)) => config.groups = solid_queue_groups(&doc),
+            Ok(None) => {}
+            Err(e) => config.warning = Some(e),
+        }
+
+        match read_yaml(root, SOLID_QUEUE_RECURRING) {
+            Ok(Some(doc)) => {
+                // Only the development schedule runs under Caboose
+                config.recurring_tasks = doc
+                    .get("development")
+                    .and_then(Yaml::as_map)
+                    .map_or(0, |tasks| tasks.len());
+            }
+            Ok(None) => {}
+            Err(e) => {
+                config.warning.get_or_insert(e);
+            }
+        }
+
+        config
+    }
+}
+
+/// Parse a config file; `Ok(None)` when it does not exist
+fn read_yaml(root: &Path, relative: &str) -> Result<Option<Yaml>, String> {
+    let Ok(content) = fs::read_to_string(root.join(relative)) else {
+        return Ok(None);
+    };
+    yaml::parse(&content)
+        .map(Some)
+        .map_err(|e| format!("Could not parse {}: {}", relative, e))
+}
+
+/// Sidekiq accepts both `queues:` and `:queues:`
+fn sidekiq_key<'a>(doc: &'a Yaml, key: &str) -> Option<&'a Yaml> {
+    doc.get(key).or_else(|| doc.get(&format!(":{}", key)))
+}
+
+fn sidekiq_groups(doc: &Yaml) -> Vec<WorkerGroup> {
+    let env = sidekiq_key(doc, "development");
+    let setting = |key: &str| {
+        env.and_then(|e| sidekiq_key(e, key))
+            .or(sidekiq_key(doc, key))
+    };
+
+    let mut groups = vec![WorkerGroup {
+        name: None,
+        queues: setting("queues").map(queue_list).unwrap_or_default(),
+        concurrency: setting("concurrency").and_then(Yaml::as_u32),
+        processes: None,
+    }];
+
+    if let Some(capsules) = setting("capsules").and_then(Yaml::as_map) {
+        for (name, capsule) in capsules {
+            groups.push(WorkerGroup {
+                name: Some(name.trim_start_matches(':').to_string()),
+                queues: sidekiq_key(capsule, "queues")
+                    .map(queue_list)
+                    .unwrap_or_default(),
+                concurrency: sidekiq_key(capsule, "concurrency").and_then(Yaml::as_u32),
+                processes: None,
+            });
+        }
+    }
+
+    groups
+}
+
+fn solid_queue_groups(doc: &Yaml) -> Vec<WorkerGroup> {
+    let section = ["development", "default"]
+        .iter()
+        .find_map(|env| doc.get(env).filter(|s| s.get("workers").is_some()))
+        .unwrap_or(doc);
+
+    section
+        .get("workers")
+        .and_then(Yaml::as_seq)
+        .unwrap_or_default()
+        .iter()
+        .map(|worker| WorkerGroup {
+            name: None,
+            queues: worker.get("queues").map(queue_list).unwrap_or_default(),
+            concurrency: worker.get("threads").and_then(Yaml::as_u32),
+            processes: worker.get("processes").and_then(Yaml::as_u32),
+        })
+        .collect()
+}
+
+/// Queue names from a scalar, a list, or Sidekiq's `[name, weight]` pairs
+fn queue_list(value: &Yaml) -> Vec<String> {
+    match value {
+        Yaml::Scalar(name) => name
+            .split(',')
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty())
+            .collect(),
+        Yaml::Seq(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Yaml::Scalar(name) => Some(name.clone()),
+                Yaml::Seq(pair) => {
+                    let parts: Vec<&str> = pair.iter().filter_map(Yaml::as_str).collect();
+                    (!parts.is_empty()).then(|| parts.join(","))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
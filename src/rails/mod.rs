@@ -1,3 +1,13 @@
+mod jobs;
+pub mod routes;
+pub mod watch;
+mod yaml;
+
+pub use jobs::{JobConfig, WorkerGroup};
+
+use crate::config::{ProcfileEntry, WorkerMode, render_procfile};
+use crate::database::SqlDialect;
+use crate::parser::InstrumentationGems;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
@@ -8,6 +18,35 @@ pub struct RailsApp {
     pub database: Option<String>,
     pub background_job: Option<String>,
     pub asset_pipeline: Option<String>,
+    pub jobs: JobConfig,
+    /// APM / instrumentation gems whose log lines get their own parsers
+    pub instrumentation: InstrumentationGems,
+}
+
+/// Whether a Gemfile declares `gem`, ignoring commented-out lines
+pub fn declares_gem(gemfile: &str, gem: &str) -> bool {
+    gemfile.lines().any(|line| {
+        let Some(rest) = line.trim_start().strip_prefix("gem") else {
+            return false;
+        };
+        let rest = rest.trim_start();
+        [format!("\"{}\"", gem), format!("'{}'", gem)]
+            .iter()
+            .any(|quoted| rest.starts_with(quoted.as_str()))
+    })
+}
+
+/// Database named by a `config/database.yml`: `postgresql`, `mysql` or `sqlite`
+pub fn detect_adapter(database_yml: &str) -> Option<String> {
+    if database_yml.contains("postgresql") || database_yml.contains("adapter: postgresql") {
+        Some("postgresql".to_string())
+    } else if database_yml.contains("mysql") {
+        Some("mysql".to_string())
+    } else if database_yml.contains("sqlite") {
+        Some("sqlite".to_string())
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +68,8 @@ impl RailsApp {
             database: None,
             background_job: None,
             asset_pipeline: None,
+            jobs: JobConfig::default(),
+            instrumentation: InstrumentationGems::default(),
         };
 
         let root = root.as_ref();
@@ -42,13 +83,7 @@ impl RailsApp {
 
         // Detect database
         if let Ok(database_yml) = fs::read_to_string(root.join("config/database.yml")) {
-            if database_yml.contains("postgresql") || database_yml.contains("adapter: postgresql") {
-                app.database = Some("postgresql".to_string());
-            } else if database_yml.contains("mysql") {
-                app.database = Some("mysql".to_string());
-            } else if database_yml.contains("sqlite") {
-                app.database = Some("sqlite".to_string());
-            }
+            app.database = detect_adapter(&database_yml);
         }
 
         // Detect background job framework
@@ -64,6 +99,10 @@ impl RailsApp {
             }
         }
 
+        if let Some(ref framework) = app.background_job {
+            app.jobs = JobConfig::detect(root, framework);
+        }
+
         // Detect asset pipeline
         if let Ok(gemfile) = fs::read_to_string(root.join("Gemfile")) {
             if gemfile.contains("gem \"vite_rails\"") || gemfile.contains("gem 'vite_rails'") {
@@ -75,38 +114,148 @@ impl RailsApp {
             }
         }
 
+        // Detect APM / instrumentation gems
+        if let Ok(gemfile) = fs::read_to_string(root.join("Gemfile")) {
+            app.instrumentation = InstrumentationGems::from_gemfile(&gemfile);
+        }
+
         app
     }
 
-    pub fn generate_procfile(&self, port_override: Option<u16>) -> String {
-        let mut procfile = String::new();
+    /// SQL dialect of the detected database, generic when it wasn't found
+    pub fn sql_dialect(&self) -> SqlDialect {
+        SqlDialect::from_adapter(self.database.as_deref())
+    }
+
+    /// Procfile entries for this app, each with the reason it was added
+    pub fn procfile_entries(
+        &self,
+        port_override: Option<u16>,
+        workers: WorkerMode,
+    ) -> Vec<ProcfileEntry> {
+        let mut entries = Vec::new();
 
         // Web server with configurable port
         let port = port_override.unwrap_or(3000);
-        procfile.push_str(&format!("web: bundle exec rails server -p {}\n", port));
+        let web_reason = if port_override.is_some() {
+            format!("Rails app detected; port {} set in .caboose.toml", port)
+        } else {
+            "Rails app detected (Gemfile + config/application.rb)".to_string()
+        };
+        entries.push(ProcfileEntry::new(
+            "web",
+            format!("bundle exec rails server -p {}", port),
+            web_reason,
+        ));
 
         // Background job worker
         if let Some(ref job_framework) = self.background_job {
-            match job_framework.as_str() {
-                "sidekiq" => procfile.push_str("worker: bundle exec sidekiq\n"),
-                "good_job" => procfile.push_str("worker: bundle exec good_job start\n"),
-                "solid_queue" => procfile.push_str("worker: bundle exec rake solid_queue:start\n"),
-                _ => {}
-            }
+            entries.extend(self.worker_entries(job_framework, workers));
         }
 
         // Asset pipeline
-        if let Some(ref asset_pipeline) = self.asset_pipeline {
-            if asset_pipeline == "vite" {
-                procfile.push_str("vite: bin/vite dev\n");
-            }
+        if let Some(ref asset_pipeline) = self.asset_pipeline
+            && asset_pipeline == "vite"
+        {
+            entries.push(ProcfileEntry::new(
+                "vite",
+                "bin/vite dev",
+                "detected vite_rails in Gemfile",
+            ));
         }
 
-        procfile
+        entries
+    }
+
+    pub fn generate_procfile(&self, port_override: Option<u16>) -> String {
+        render_procfile(
+            &self.procfile_entries(port_override, WorkerMode::default()),
+            false,
+        )
+    }
+
+    /// Worker entries for the job framework
+    ///
+    /// Sidekiq gets one entry per queue group when its config defines more than
+    /// one. Solid Queue's supervisor forks its own workers, so it always gets a
+    /// single entry and the groups are only listed in the reason.
+    fn worker_entries(&self, job_framework: &str, workers: WorkerMode) -> Vec<ProcfileEntry> {
+        let gemfile_reason = format!("detected {} in Gemfile", job_framework);
+        let groups = &self.jobs.groups;
+
+        match job_framework {
+            "sidekiq" if workers == WorkerMode::PerGroup && groups.len() > 1 => {
+                let mut names: Vec<String> = Vec::new();
+                groups
+                    .iter()
+                    .map(|group| {
+                        let mut name = format!("worker_{}", group.label());
+                        if names.contains(&name) {
+                            name = format!("{}_{}", name, names.len() + 1);
+                        }
+                        names.push(name.clone());
+
+                        let mut command = "bundle exec sidekiq".to_string();
+                        for queue in &group.queues {
+                            command.push_str(&format!(" -q {}", queue));
+                        }
+                        if let Some(concurrency) = group.concurrency {
+                            command.push_str(&format!(" -c {}", concurrency));
+                        }
+
+                        let reason = match group.name {
+                            Some(ref capsule) => {
+                                format!("capsule '{}' in {}", capsule, jobs::SIDEKIQ_CONFIG)
+                            }
+                            None => format!("default queues in {}", jobs::SIDEKIQ_CONFIG),
+                        };
+                        ProcfileEntry::new(name, command, reason)
+                    })
+                    .collect()
+            }
+            "sidekiq" => vec![ProcfileEntry::new(
+                "worker",
+                "bundle exec sidekiq",
+                gemfile_reason,
+            )],
+            "good_job" => vec![ProcfileEntry::new(
+                "worker",
+                "bundle exec good_job start",
+                gemfile_reason,
+            )],
+            "solid_queue" => {
+                let mut reason = gemfile_reason;
+                if !groups.is_empty() {
+                    let queues: Vec<String> = groups.iter().map(|g| g.queues.join(",")).collect();
+                    reason.push_str(&format!(
+                        "; {} worker group(s) [{}] in {}",
+                        groups.len(),
+                        queues.join("] ["),
+                        jobs::SOLID_QUEUE_CONFIG
+                    ));
+                }
+                if self.jobs.recurring_tasks > 0 {
+                    reason.push_str(&format!(
+                        "; {} recurring task(s) in {}",
+                        self.jobs.recurring_tasks,
+                        jobs::SOLID_QUEUE_RECURRING
+                    ));
+                }
+                vec![ProcfileEntry::new(
+                    "worker",
+                    "bundle exec rake solid_queue:start",
+                    reason,
+                )]
+            }
+            _ => Vec::new(),
+        }
     }
 
     /// Check for Rails health issues (pending migrations, database connectivity)
-    pub fn check_health(&self) -> Vec<RailsHealthIssue> {
+    ///
+    /// Bundler and Rails commands run inside `root`.
+    pub fn check_health<P: AsRef<Path>>(&self, root: P) -> Vec<RailsHealthIssue> {
+        let root = root.as_ref();
         if !self.detected {
             return vec![];
         }
@@ -114,26 +263,30 @@ impl RailsApp {
         let mut issues = vec![];
 
         // Check if bundle install is needed
-        if let Ok(output) = Command::new("bundle").args(["check"]).output() {
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let message = if !stderr.is_empty() {
-                    stderr.to_string()
-                } else if !stdout.is_empty() {
-                    stdout.to_string()
-                } else {
-                    "Gemfile dependencies are not satisfied".to_string()
-                };
-                issues.push(RailsHealthIssue::BundleOutdated(message));
-                // If bundle check fails, skip other checks as they'll likely fail too
-                return issues;
-            }
+        if let Ok(output) = Command::new("bundle")
+            .args(["check"])
+            .current_dir(root)
+            .output()
+            && !output.status.success()
+        {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let message = if !stderr.is_empty() {
+                stderr.to_string()
+            } else if !stdout.is_empty() {
+                stdout.to_string()
+            } else {
+                "Gemfile dependencies are not satisfied".to_string()
+            };
+            issues.push(RailsHealthIssue::BundleOutdated(message));
+            // If bundle check fails, skip other checks as they'll likely fail too
+            return issues;
         }
 
         // Check for pending migrations
         if let Ok(output) = Command::new("bundle")
             .args(["exec", "rails", "db:migrate:status"])
+            .current_dir(root)
             .output()
         {
             if output.status.success() {
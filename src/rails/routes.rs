@@ -0,0 +1,304 @@
+//! Rails routes: a summary of `config/routes.rb`, and the full table from
+//! `rails routes`
+//!
+//! The summary (how many routes the file defines and where engines are
+//! mounted) is read line by line, without evaluating Ruby, so routes built in
+//! loops or helper methods aren't counted. The table comes from running
+//! `rails routes --expanded`, which boots the app, so it's read in the
+//! background and cached in `.caboose/routes.json`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use tokio::sync::oneshot;
+
+use crate::project::ProjectRoot;
+
+/// Where Rails defines routes, relative to the project root
+pub const ROUTES_PATH: &str = "config/routes.rb";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteSummary {
+    /// `get`, `post`, `resources`, `root`, ... lines
+    pub definitions: usize,
+    /// Paths engines are mounted at, e.g. `/sidekiq`, in file order
+    pub mounts: Vec<String>,
+}
+
+pub fn parse_routes(content: &str) -> RouteSummary {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    static MOUNT: OnceLock<Regex> = OnceLock::new();
+    let definition = DEFINITION.get_or_init(|| {
+        Regex::new(r"^\s*(?:get|post|put|patch|delete|match|resources?|root)\b[\s(]").unwrap()
+    });
+    let mount = MOUNT.get_or_init(|| {
+        Regex::new(r#"^\s*mount\s+[\w:.]+(?:\s*=>\s*|\s*,\s*at:\s*)["']([^"']+)["']"#).unwrap()
+    });
+
+    let mut summary = RouteSummary::default();
+    for line in content.lines() {
+        if let Some(caps) = mount.captures(line) {
+            summary.mounts.push(caps[1].to_string());
+        } else if definition.is_match(line) {
+            summary.definitions += 1;
+        }
+    }
+    summary
+}
+
+/// `rails routes` output is cached here, inside the project's `.caboose/`
+pub const ROUTES_CACHE_FILE: &str = "routes.json";
+
+/// One route from `rails routes --expanded`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    /// The `Prefix` column, e.g. `edit_user`; unnamed routes have none
+    pub name: Option<String>,
+    /// `GET`, `GET|POST`, or empty for a mount or a `match` on any verb
+    pub verb: String,
+    /// `/users/:id/edit(.:format)`
+    pub path: String,
+    /// `users#edit`, or the app mounted there, e.g. `Sidekiq::Web`
+    pub action: String,
+}
+
+impl Route {
+    /// The path without its optional `(.:format)`, e.g. `/users/:id/edit`
+    pub fn pattern(&self) -> &str {
+        self.path.strip_suffix("(.:format)").unwrap_or(&self.path)
+    }
+
+    /// Whether requests with `method` are routed here
+    pub fn accepts(&self, method: &str) -> bool {
+        self.verb.is_empty()
+            || self
+                .verb
+                .split('|')
+                .any(|verb| verb.eq_ignore_ascii_case(method))
+    }
+}
+
+/// Parse `rails routes --expanded`
+///
+/// ```text
+/// --[ Route 1 ]---------------------------------
+/// Prefix            | user
+/// Verb              | GET
+/// URI               | /users/:id(.:format)
+/// Controller#Action | users#show
+/// ```
+///
+/// The routes of mounted engines, listed after `Routes for <Engine>:`, are
+/// left out: their paths are relative to the mount.
+pub fn parse_expanded_routes(output: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    let mut current: Option<Route> = None;
+    for line in output.lines() {
+        if line.starts_with("Routes for ") {
+            break;
+        }
+        if line.starts_with("--[ Route ") {
+            routes.extend(current.take());
+            current = Some(Route {
+                name: None,
+                verb: String::new(),
+                path: String::new(),
+                action: String::new(),
+            });
+            continue;
+        }
+        let (Some(route), Some((key, value))) = (current.as_mut(), line.split_once('|')) else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Prefix" => route.name = Some(value).filter(|name| !name.is_empty()),
+            "Verb" => route.verb = value,
+            "URI" => route.path = value,
+            "Controller#Action" => route.action = value,
+            _ => {}
+        }
+    }
+    routes.extend(current);
+    routes.retain(|route| !route.path.is_empty());
+    routes
+}
+
+/// Routes in the order Rails tries them, each with its path compiled for matching
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<Route>,
+    matchers: Vec<Option<Regex>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoutesCache {
+    routes: Vec<Route>,
+}
+
+impl RouteTable {
+    pub fn new(routes: Vec<Route>) -> Self {
+        let matchers = routes
+            .iter()
+            .map(|route| path_matcher(&route.path))
+            .collect();
+        Self { routes, matchers }
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// The route a `method` request for `path` goes to: the first one whose
+    /// verb and path match, as in Rails
+    pub fn find(&self, method: &str, path: &str) -> Option<&Route> {
+        let path = request_path(path);
+        self.matching(path).find(|route| route.accepts(method))
+    }
+
+    /// The route name of a request, e.g. `user` for `PATCH /users/42`
+    ///
+    /// Rails names only the first route of each path; the others (`PATCH`
+    /// and `DELETE` after `GET /users/:id`) share its name, as their path
+    /// helper does.
+    pub fn name_for(&self, method: &str, path: &str) -> Option<&str> {
+        let route = self.find(method, path)?;
+        route.name.as_deref().or_else(|| {
+            self.routes
+                .iter()
+                .filter(|other| other.path == route.path)
+                .find_map(|other| other.name.as_deref())
+        })
+    }
+
+    /// The pattern of the first route `path` matches on any verb, e.g.
+    /// `/users/:id` for `/users/42?tab=1`
+    pub fn pattern_for(&self, path: &str) -> Option<&str> {
+        self.matching(request_path(path)).next().map(Route::pattern)
+    }
+
+    fn matching<'a>(&'a self, path: &str) -> impl Iterator<Item = &'a Route> {
+        self.routes
+            .iter()
+            .zip(&self.matchers)
+            .filter(move |(_, matcher)| matcher.as_ref().is_some_and(|m| m.is_match(path)))
+            .map(|(route, _)| route)
+    }
+
+    /// Routes whose name, verb, path or action contain `query` (any case)
+    pub fn filter<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a Route> + use<'a> {
+        let query = query.to_lowercase();
+        self.routes.iter().filter(move |route| {
+            query.is_empty()
+                || [
+                    route.name.as_deref().unwrap_or_default(),
+                    &route.verb,
+                    &route.path,
+                    &route.action,
+                ]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&query))
+        })
+    }
+
+    /// The table cached in `data_dir`, if there is one that can be read
+    pub fn load(data_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(data_dir.join(ROUTES_CACHE_FILE)).ok()?;
+        let cache: RoutesCache = serde_json::from_str(&content).ok()?;
+        Some(Self::new(cache.routes))
+    }
+
+    /// Cache the table in `data_dir`
+    pub fn save(&self, data_dir: &Path) -> Result<(), String> {
+        let path = data_dir.join(ROUTES_CACHE_FILE);
+        let cache = RoutesCache {
+            routes: self.routes.clone(),
+        };
+        let json = serde_json::to_string_pretty(&cache)
+            .map_err(|e| format!("Failed to serialize routes: {}", e))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// The path of a request line, without its query string or fragment
+fn request_path(path: &str) -> &str {
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+/// A regex for a route path: `:param` matches one segment, `*glob` the rest,
+/// and parenthesized parts are optional
+fn path_matcher(path: &str) -> Option<Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => pattern.push_str("(?:"),
+            ')' => pattern.push_str(")?"),
+            ':' | '*' => {
+                while chars
+                    .peek()
+                    .is_some_and(|next| next.is_ascii_alphanumeric() || *next == '_')
+                {
+                    chars.next();
+                }
+                pattern.push_str(if c == ':' { "[^/.?]+" } else { ".+?" });
+            }
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+/// Run `bundle exec rails routes --expanded` in `root`
+///
+/// On failure, the error is what the command printed, so a broken
+/// `routes.rb` can be fixed from it.
+pub fn read_routes(root: &Path) -> Result<RouteTable, String> {
+    let output = Command::new("bundle")
+        .args(["exec", "rails", "routes", "--expanded"])
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("Failed to run bundle exec rails routes: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let message = [stderr.trim(), stdout.trim()]
+            .into_iter()
+            .find(|text| !text.is_empty())
+            .map_or_else(
+                || format!("rails routes failed ({})", output.status),
+                str::to_string,
+            );
+        return Err(message);
+    }
+    Ok(RouteTable::new(parse_expanded_routes(
+        &String::from_utf8_lossy(&output.stdout),
+    )))
+}
+
+/// Read the routes of the project at `root` on a blocking thread, caching
+/// them in its `.caboose/` once read
+pub fn spawn_routes_refresh(root: ProjectRoot) -> oneshot::Receiver<Result<RouteTable, String>> {
+    let (tx, rx) = oneshot::channel();
+    tokio::task::spawn_blocking(move || {
+        let result = read_routes(root.path());
+        if let Ok(table) = &result {
+            // Without the cache the routes are only read again on the next change
+            let _ = root.ensure_data_dir().and_then(|dir| table.save(&dir));
+        }
+        let _ = tx.send(result);
+    });
+    rx
+}
@@ -0,0 +1,210 @@
+//! Watches `db/schema.rb`, `config/routes.rb` and `config/database.yml`
+//!
+//! Their directories are watched rather than the files, so a file replaced by
+//! an atomic write (written elsewhere, then renamed over) is still seen. A
+//! burst of events (a migration rewriting the schema, an editor saving twice)
+//! is reported once, after `debounce` without further events. The changed
+//! file is re-read on a blocking thread of the tokio runtime.
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::detect_adapter;
+use super::routes::{ROUTES_PATH, RouteSummary, parse_routes};
+use crate::database::TableInfo;
+use crate::database::schema::{SCHEMA_PATH, parse_schema};
+
+/// Quiet time after the last event before a change is reported
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How many times, and how far apart, a file missing after an event is
+/// looked for again before it counts as removed
+const REAPPEAR_TRIES: u32 = 5;
+const REAPPEAR_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProjectFile {
+    Schema,
+    Routes,
+    DatabaseConfig,
+}
+
+impl ProjectFile {
+    pub const ALL: [ProjectFile; 3] = [
+        ProjectFile::Schema,
+        ProjectFile::Routes,
+        ProjectFile::DatabaseConfig,
+    ];
+
+    /// Path relative to the project root
+    pub fn path(&self) -> &'static str {
+        match self {
+            ProjectFile::Schema => SCHEMA_PATH,
+            ProjectFile::Routes => ROUTES_PATH,
+            ProjectFile::DatabaseConfig => "config/database.yml",
+        }
+    }
+
+    /// File name, e.g. `schema.rb`
+    pub fn name(&self) -> &'static str {
+        self.path().rsplit('/').next().unwrap_or_default()
+    }
+
+    /// The watched file `path` is, if any
+    fn at(root: &Path, path: &Path) -> Option<Self> {
+        let relative = path.strip_prefix(root).ok()?;
+        Self::ALL
+            .into_iter()
+            .find(|file| relative == Path::new(file.path()))
+    }
+}
+
+/// A watched file's new contents, parsed
+#[derive(Debug, Clone)]
+pub enum ProjectChange {
+    Schema(HashMap<String, TableInfo>),
+    Routes(RouteSummary),
+    /// The adapter now in `config/database.yml`, if one is recognized
+    DatabaseConfig {
+        adapter: Option<String>,
+    },
+    /// The file is gone; what was read from it before still applies
+    Removed(ProjectFile),
+}
+
+impl ProjectChange {
+    /// One line for the log, e.g. `schema.rb changed — reloaded 42 tables`
+    pub fn message(&self) -> String {
+        match self {
+            ProjectChange::Schema(tables) => format!(
+                "schema.rb changed — reloaded {} table{}",
+                tables.len(),
+                if tables.len() == 1 { "" } else { "s" }
+            ),
+            ProjectChange::Routes(routes) => {
+                let mut message = format!(
+                    "routes.rb changed — {} route definition{}",
+                    routes.definitions,
+                    if routes.definitions == 1 { "" } else { "s" }
+                );
+                if !routes.mounts.is_empty() {
+                    message.push_str(&format!(", engines at {}", routes.mounts.join(", ")));
+                }
+                message
+            }
+            ProjectChange::DatabaseConfig { adapter } => format!(
+                "database.yml changed — adapter {}",
+                adapter.as_deref().unwrap_or("not recognized")
+            ),
+            ProjectChange::Removed(file) => {
+                format!("{} removed — keeping what was read from it", file.name())
+            }
+        }
+    }
+}
+
+/// Re-read `file` under `root`
+///
+/// A missing file is looked for again a few times, since an atomic write
+/// briefly removes it.
+pub fn read_change(root: &Path, file: ProjectFile) -> ProjectChange {
+    let path = root.join(file.path());
+    let mut content = fs::read_to_string(&path);
+    for _ in 1..REAPPEAR_TRIES {
+        if content.is_ok() {
+            break;
+        }
+        std::thread::sleep(REAPPEAR_INTERVAL);
+        content = fs::read_to_string(&path);
+    }
+    let Ok(content) = content else {
+        return ProjectChange::Removed(file);
+    };
+
+    match file {
+        ProjectFile::Schema => ProjectChange::Schema(parse_schema(&content)),
+        ProjectFile::Routes => ProjectChange::Routes(parse_routes(&content)),
+        ProjectFile::DatabaseConfig => ProjectChange::DatabaseConfig {
+            adapter: detect_adapter(&content),
+        },
+    }
+}
+
+/// Watch the project at `root`, sending each change once it has settled
+///
+/// Must be called from within the tokio runtime. Fails when the OS watcher
+/// can't be created or neither `db/` nor `config/` can be watched. The task
+/// ends once the receiver is dropped.
+pub fn spawn_project_watcher(
+    root: PathBuf,
+    debounce: Duration,
+) -> Result<mpsc::UnboundedReceiver<ProjectChange>, String> {
+    // Events carry absolute paths, so match them against an absolute root
+    let root = root.canonicalize().unwrap_or(root);
+    let (event_tx, mut events) = mpsc::unbounded_channel::<ProjectFile>();
+    let event_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for file in event
+            .paths
+            .iter()
+            .filter_map(|path| ProjectFile::at(&event_root, path))
+        {
+            let _ = event_tx.send(file);
+        }
+    })
+    .map_err(|e| format!("Failed to watch project files: {}", e))?;
+
+    let watched = ["db", "config"]
+        .into_iter()
+        .filter(|dir| {
+            watcher
+                .watch(&root.join(dir), RecursiveMode::NonRecursive)
+                .is_ok()
+        })
+        .count();
+    if watched == 0 {
+        return Err(format!(
+            "Failed to watch project files: no db/ or config/ in {}",
+            root.display()
+        ));
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        // Dropping the watcher stops the events
+        let _watcher = watcher;
+        while let Some(first) = events.recv().await {
+            let mut pending = BTreeSet::from([first]);
+            loop {
+                match tokio::time::timeout(debounce, events.recv()).await {
+                    Ok(Some(file)) => {
+                        pending.insert(file);
+                    }
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+
+            for file in pending {
+                let dir = root.clone();
+                if let Ok(change) =
+                    tokio::task::spawn_blocking(move || read_change(&dir, file)).await
+                    && tx.send(change).is_err()
+                {
+                    return;
+                }
+            }
+        }
+    });
+    Ok(rx)
+}
@@ -0,0 +1,379 @@
+//! Minimal YAML reader for Rails config files
+//!
+//! Covers the subset used by `config/sidekiq.yml`, `config/queue.yml` and
+//! `config/recurring.yml`: block mappings and sequences, flow sequences,
+//! quoted scalars, comments, and `&anchor` / `<<: *anchor` merges. ERB tags are
+//! kept as plain scalars. Anything outside the subset is reported as an error
+//! so callers can fall back to defaults.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Yaml {
+    Null,
+    Scalar(String),
+    Seq(Vec<Yaml>),
+    Map(Vec<(String, Yaml)>),
+}
+
+impl Yaml {
+    /// Look up a mapping key
+    pub fn get(&self, key: &str) -> Option<&Yaml> {
+        match self {
+            Yaml::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Yaml::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_seq(&self) -> Option<&[Yaml]> {
+        match self {
+            Yaml::Seq(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(String, Yaml)]> {
+        match self {
+            Yaml::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Read a number, using the fallback of `<%= ENV.fetch("X", 3) %>` for ERB values
+    pub fn as_u32(&self) -> Option<u32> {
+        let text = self.as_str()?.trim();
+        if let Ok(n) = text.parse() {
+            return Some(n);
+        }
+
+        let inner = text.strip_prefix("<%=")?.strip_suffix("%>")?.trim();
+        let args = inner.strip_prefix("ENV.fetch(")?.strip_suffix(')')?;
+        let (_, fallback) = args.rsplit_once(',')?;
+        fallback
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .parse()
+            .ok()
+    }
+}
+
+/// Parse a YAML document
+pub fn parse(source: &str) -> Result<Yaml, String> {
+    let mut lines = Vec::new();
+    for (idx, raw) in source.lines().enumerate() {
+        let text = strip_comment(raw);
+        if text.trim().is_empty() || text.trim() == "---" {
+            continue;
+        }
+
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        if text[indent..].starts_with('\t') {
+            return Err(format!(
+                "line {}: tabs are not allowed in indentation",
+                idx + 1
+            ));
+        }
+
+        lines.push(Line {
+            number: idx + 1,
+            indent,
+            text: text.trim().to_string(),
+        });
+    }
+
+    if lines.is_empty() {
+        return Ok(Yaml::Null);
+    }
+
+    let mut parser = Parser {
+        lines,
+        pos: 0,
+        anchors: HashMap::new(),
+    };
+    let indent = parser.lines[0].indent;
+    let doc = parser.parse_block(indent)?;
+
+    if let Some(line) = parser.lines.get(parser.pos) {
+        return Err(format!("line {}: unexpected indentation", line.number));
+    }
+    Ok(doc)
+}
+
+struct Line {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    pos: usize,
+    anchors: HashMap<String, Yaml>,
+}
+
+impl Parser {
+    fn parse_block(&mut self, indent: usize) -> Result<Yaml, String> {
+        let line = &self.lines[self.pos];
+        if line.indent != indent {
+            return Err(format!("line {}: unexpected indentation", line.number));
+        }
+
+        if is_seq_item(&line.text) {
+            self.parse_seq(indent)
+        } else {
+            self.parse_map(indent)
+        }
+    }
+
+    fn parse_seq(&mut self, indent: usize) -> Result<Yaml, String> {
+        let mut items = Vec::new();
+
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent != indent || !is_seq_item(&line.text) {
+                break;
+            }
+
+            let rest = line.text[1..].trim_start().to_string();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_nested(indent)?);
+            } else if split_key(&rest).is_some() && !rest.starts_with(['[', '{', '"', '\'']) {
+                // "- key: value" starts a mapping aligned with the text after the dash
+                let offset = line.text.len() - rest.len();
+                let line = &mut self.lines[self.pos];
+                line.indent += offset;
+                line.text = rest;
+                let child_indent = line.indent;
+                items.push(self.parse_map(child_indent)?);
+            } else {
+                let number = line.number;
+                self.pos += 1;
+                items.push(self.parse_inline(&rest, number)?);
+            }
+        }
+
+        Ok(Yaml::Seq(items))
+    }
+
+    fn parse_map(&mut self, indent: usize) -> Result<Yaml, String> {
+        let mut entries: Vec<(String, Yaml)> = Vec::new();
+        let mut merged: Vec<(String, Yaml)> = Vec::new();
+
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent {
+                return Err(format!("line {}: unexpected indentation", line.number));
+            }
+            if is_seq_item(&line.text) {
+                return Err(format!(
+                    "line {}: sequence item inside a mapping",
+                    line.number
+                ));
+            }
+
+            let number = line.number;
+            let (key, value) = split_key(&line.text)
+                .ok_or_else(|| format!("line {}: expected 'key: value'", number))?;
+            let key = unquote(key, number)?;
+            let value = value.to_string();
+            self.pos += 1;
+
+            let (anchor, value) = match value.strip_prefix('&') {
+                Some(rest) => {
+                    let (name, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                    (Some(name.to_string()), rest.trim().to_string())
+                }
+                None => (None, value),
+            };
+
+            let parsed = if value.is_empty() {
+                self.parse_value_block(indent)?
+            } else {
+                self.parse_inline(&value, number)?
+            };
+
+            if let Some(name) = anchor {
+                self.anchors.insert(name, parsed.clone());
+            }
+
+            if key == "<<" {
+                match parsed {
+                    Yaml::Map(base) => merged.extend(base),
+                    _ => return Err(format!("line {}: '<<' must merge a mapping", number)),
+                }
+            } else {
+                entries.push((key, parsed));
+            }
+        }
+
+        // Explicit keys win over merged ones
+        merged.retain(|(k, _)| !entries.iter().any(|(key, _)| key == k));
+        merged.extend(entries);
+        Ok(Yaml::Map(merged))
+    }
+
+    /// Value of a `key:` line with nothing after the colon
+    fn parse_value_block(&mut self, indent: usize) -> Result<Yaml, String> {
+        match self.lines.get(self.pos) {
+            // Sequences may sit at the same indentation as their key
+            Some(next) if next.indent == indent && is_seq_item(&next.text) => {
+                self.parse_seq(indent)
+            }
+            _ => self.parse_nested(indent),
+        }
+    }
+
+    fn parse_nested(&mut self, parent_indent: usize) -> Result<Yaml, String> {
+        match self.lines.get(self.pos) {
+            Some(next) if next.indent > parent_indent => {
+                let indent = next.indent;
+                self.parse_block(indent)
+            }
+            _ => Ok(Yaml::Null),
+        }
+    }
+
+    fn parse_inline(&self, text: &str, number: usize) -> Result<Yaml, String> {
+        if let Some(name) = text.strip_prefix('*') {
+            return self
+                .anchors
+                .get(name.trim())
+                .cloned()
+                .ok_or_else(|| format!("line {}: unknown alias '*{}'", number, name.trim()));
+        }
+        parse_flow(text, number)
+    }
+}
+
+fn parse_flow(text: &str, number: usize) -> Result<Yaml, String> {
+    let text = text.trim();
+
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| format!("line {}: unterminated '['", number))?;
+        let items = split_flow(inner, number)?
+            .into_iter()
+            .map(|item| parse_flow(item, number))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Yaml::Seq(items));
+    }
+
+    if let Some(inner) = text.strip_prefix('{') {
+        let inner = inner
+            .strip_suffix('}')
+            .ok_or_else(|| format!("line {}: unterminated '{{'", number))?;
+        let mut entries = Vec::new();
+        for item in split_flow(inner, number)? {
+            let (key, value) =
+                split_key(item).ok_or_else(|| format!("line {}: expected 'key: value'", number))?;
+            entries.push((unquote(key, number)?, parse_flow(value, number)?));
+        }
+        return Ok(Yaml::Map(entries));
+    }
+
+    match text {
+        "" | "~" | "null" => Ok(Yaml::Null),
+        _ => Ok(Yaml::Scalar(unquote(text, number)?)),
+    }
+}
+
+/// Split the inside of a flow collection on top-level commas
+fn split_flow(inner: &str, number: usize) -> Result<Vec<&str>, String> {
+    let mut items = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut start = 0;
+
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if quote.is_some() || depth != 0 {
+        return Err(format!("line {}: unbalanced flow collection", number));
+    }
+
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    Ok(items)
+}
+
+fn unquote(text: &str, number: usize) -> Result<String, String> {
+    let text = text.trim();
+    for q in ['"', '\''] {
+        if let Some(rest) = text.strip_prefix(q) {
+            return rest
+                .strip_suffix(q)
+                .map(str::to_string)
+                .ok_or_else(|| format!("line {}: unterminated quote", number));
+        }
+    }
+    Ok(text.to_string())
+}
+
+fn is_seq_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Split `key: value` on the first colon outside quotes and ERB tags
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    let mut in_erb = false;
+    let bytes = text.as_bytes();
+
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if i == 0 => quote = Some(c),
+            (None, '<') if text[i..].starts_with("<%") => in_erb = true,
+            (None, '%') if text[i..].starts_with("%>") => in_erb = false,
+            (None, ':') if !in_erb && (i + 1 == bytes.len() || bytes[i + 1] == b' ') => {
+                // Sidekiq's symbol keys (":queues:") keep their leading colon
+                if i == 0 {
+                    continue;
+                }
+                return Some((&text[..i], text[i + 1..].trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Drop a trailing `# comment` that is not inside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') if i == 0 || line[..i].ends_with(' ') => return line[..i].trim_end(),
+            _ => {}
+        }
+    }
+    line.trim_end()
+}
@@ -0,0 +1,175 @@
+//! Case-insensitive substring matching shared by the trackers' filtered accessors
+//!
+//! The query is lowercased once up front, so matching a record never allocates
+//! unless the query contains non-ASCII characters.
+//!
+//! The Logs view searches with a [`SearchFilter`] instead, which also takes
+//! `re:` regexes, can match case, and says where in a line it matched.
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::process::LogLine;
+
+/// Prefix that makes a log search a regex, as in `re:Completed (5\d\d)`
+pub const REGEX_PREFIX: &str = "re:";
+
+/// Compiled patterns are capped at this size, so a typo like `\w{1000}{1000}`
+/// fails to compile instead of eating memory
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// A lowercased search query
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    needle: String,
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> Self {
+        Self {
+            needle: query.trim().to_lowercase(),
+        }
+    }
+
+    /// An empty query matches everything
+    pub fn is_empty(&self) -> bool {
+        self.needle.is_empty()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.needle
+    }
+
+    /// Whether `haystack` contains the query, ignoring case
+    pub fn matches(&self, haystack: &str) -> bool {
+        if self.needle.is_empty() {
+            return true;
+        }
+
+        if self.needle.is_ascii() {
+            let needle = self.needle.as_bytes();
+            haystack
+                .as_bytes()
+                .windows(needle.len())
+                .any(|window| window.eq_ignore_ascii_case(needle))
+        } else {
+            haystack.to_lowercase().contains(&self.needle)
+        }
+    }
+
+    /// Whether any of the given fields matches
+    pub fn matches_any<'a>(&self, fields: impl IntoIterator<Item = &'a str>) -> bool {
+        self.is_empty() || fields.into_iter().any(|field| self.matches(field))
+    }
+}
+
+/// A log search: a substring, or a regex after `re:`, matched with or
+/// without case
+///
+/// Unless `case_sensitive`, letters match under Unicode simple case folding,
+/// so `ÜBER` finds `über`. A regex that doesn't compile matches nothing and
+/// keeps the reason in [`SearchFilter::error`].
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    query: String,
+    regex: Option<Regex>,
+    is_regex: bool,
+    case_sensitive: bool,
+    error: Option<String>,
+}
+
+impl SearchFilter {
+    pub fn new(query: &str, case_sensitive: bool) -> Self {
+        let query = query.trim();
+        let (pattern, is_regex) = match query.strip_prefix(REGEX_PREFIX) {
+            Some(pattern) => (pattern.to_string(), true),
+            None => (regex::escape(query), false),
+        };
+
+        let (regex, error) = if pattern.is_empty() {
+            (None, None)
+        } else {
+            match RegexBuilder::new(&pattern)
+                .case_insensitive(!case_sensitive)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+            {
+                Ok(regex) => (Some(regex), None),
+                Err(e) => (None, Some(regex_error_message(&e))),
+            }
+        };
+
+        Self {
+            query: query.to_string(),
+            regex,
+            is_regex,
+            case_sensitive,
+            error,
+        }
+    }
+
+    /// The query as typed, `re:` included
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Nothing to search for: every line matches
+    pub fn is_empty(&self) -> bool {
+        self.regex.is_none() && self.error.is_none()
+    }
+
+    pub fn is_regex(&self) -> bool {
+        self.is_regex
+    }
+
+    pub fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Why the regex didn't compile, in a line
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn matches(&self, log: &LogLine) -> bool {
+        self.matches_str(&log.content)
+    }
+
+    pub fn matches_str(&self, text: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(text),
+            None => self.error.is_none(),
+        }
+    }
+
+    /// Byte ranges of `text` the search matched, in order and not
+    /// overlapping; empty matches, which there's nothing to highlight of, are
+    /// left out
+    pub fn highlight_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex.as_ref().map_or_else(Vec::new, |regex| {
+            regex
+                .find_iter(text)
+                .map(|found| found.range())
+                .filter(|range| !range.is_empty())
+                .collect()
+        })
+    }
+}
+
+/// The line of a regex error that says what's wrong, without the pattern
+/// and caret the full message draws above it
+fn regex_error_message(error: &regex::Error) -> String {
+    match error {
+        regex::Error::CompiledTooBig(_) => "regex is too big".to_string(),
+        error => {
+            let message = error.to_string();
+            message
+                .lines()
+                .rev()
+                .find_map(|line| line.strip_prefix("error: "))
+                .unwrap_or_else(|| message.lines().next().unwrap_or_default())
+                .to_string()
+        }
+    }
+}
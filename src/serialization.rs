@@ -0,0 +1,81 @@
+//! Serde helpers for types that don't serialize on their own
+//!
+//! `Instant` has no absolute meaning outside the running process, so it is
+//! written as its age in milliseconds at serialization time. Consumers that
+//! need stable output (golden-file tests) replace these values afterwards.
+//! Reading an age back gives an `Instant` that long before now, so a loaded
+//! session shows the same "how long ago" as when it was saved; inside
+//! [`aged_by`] every age read is that much older, for data saved by an
+//! earlier run and read back after it stopped.
+
+use serde::{Deserialize, Deserializer, Serializer};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// Added to every age read on this thread, see [`aged_by`]
+    static AGE_OFFSET: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Run `read` with every age it deserializes `offset` older
+///
+/// Ages are written relative to the moment of saving; `offset` is the time
+/// that has passed since, so a restored timestamp lands back on its wall-clock
+/// moment rather than on the same distance from now.
+pub fn aged_by<T>(offset: Duration, read: impl FnOnce() -> T) -> T {
+    let previous = AGE_OFFSET.with(|cell| cell.replace(offset));
+    let result = read();
+    AGE_OFFSET.with(|cell| cell.set(previous));
+    result
+}
+
+fn age_ms(instant: &Instant) -> u64 {
+    instant.elapsed().as_millis() as u64
+}
+
+/// `age_ms` milliseconds before now, or now when the clock doesn't reach back that far
+fn instant_aged(age_ms: u64) -> Instant {
+    let now = Instant::now();
+    let age = Duration::from_millis(age_ms) + AGE_OFFSET.with(Cell::get);
+    now.checked_sub(age).unwrap_or(now)
+}
+
+/// Serialize an `Instant` as milliseconds elapsed since it was taken
+pub fn instant_age<S: Serializer>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(age_ms(instant))
+}
+
+/// Serialize an `Option<Instant>` as optional milliseconds elapsed
+pub fn optional_instant_age<S: Serializer>(
+    instant: &Option<Instant>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match instant {
+        Some(instant) => serializer.serialize_some(&age_ms(instant)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialize a list of `Instant`s as milliseconds elapsed for each entry
+pub fn instant_ages<S: Serializer>(instants: &[Instant], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(instants.iter().map(age_ms))
+}
+
+/// Deserialize an age written by [`instant_age`]
+pub fn instant_from_age<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Instant, D::Error> {
+    u64::deserialize(deserializer).map(instant_aged)
+}
+
+/// Deserialize an optional age written by [`optional_instant_age`]
+pub fn optional_instant_from_age<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Instant>, D::Error> {
+    Option::<u64>::deserialize(deserializer).map(|age| age.map(instant_aged))
+}
+
+/// Deserialize ages written by [`instant_ages`]
+pub fn instants_from_ages<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Instant>, D::Error> {
+    Vec::<u64>::deserialize(deserializer).map(|ages| ages.into_iter().map(instant_aged).collect())
+}
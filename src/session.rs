@@ -0,0 +1,157 @@
+//! Saved sessions: everything collected in a run, in one file a teammate can open
+//!
+//! `/session save` writes the archive as gzip-compressed JSON and
+//! `caboose open` loads it into a read-only viewer. The archive carries a
+//! format version: a file from a newer, incompatible Caboose is refused
+//! rather than misread, while sections missing from older files load empty.
+
+use crate::context::{RequestContextTracker, RequestSnapshot};
+use crate::database::{DatabaseHealth, DatabaseSnapshot};
+use crate::exception::{ExceptionSnapshot, ExceptionTracker};
+use crate::process::{LogLine, ProcessInfo};
+use crate::stats::{PerformanceStats, StatsCollector};
+use crate::test::{TestSnapshot, TestTracker};
+use crate::time_format::DisplayZone;
+use chrono::{DateTime, Local};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+/// Version written into new archives; bumped when old readers can't load them
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+/// The two bytes every gzip stream starts with
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Where and when a session was saved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    /// Version of Caboose that saved the session
+    pub caboose_version: String,
+    /// Project root the session ran in, when known
+    pub project: Option<String>,
+    pub saved_at: DateTime<Local>,
+}
+
+impl SessionMetadata {
+    pub fn new(project: Option<&Path>) -> Self {
+        Self {
+            caboose_version: env!("CARGO_PKG_VERSION").to_string(),
+            project: project.map(|path| path.display().to_string()),
+            saved_at: Local::now(),
+        }
+    }
+}
+
+/// Everything needed to look at a session again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionArchive {
+    pub version: u32,
+    pub metadata: SessionMetadata,
+    #[serde(default)]
+    pub processes: Vec<ProcessInfo>,
+    #[serde(default)]
+    pub logs: Vec<LogLine>,
+    #[serde(default)]
+    pub requests: RequestSnapshot,
+    #[serde(default)]
+    pub exceptions: ExceptionSnapshot,
+    #[serde(default)]
+    pub tests: TestSnapshot,
+    #[serde(default)]
+    pub database: DatabaseSnapshot,
+    #[serde(default)]
+    pub stats: PerformanceStats,
+}
+
+/// Where a running session's data lives
+pub struct SessionSources<'a> {
+    pub processes: &'a [ProcessInfo],
+    pub logs: &'a [LogLine],
+    pub stats: &'a StatsCollector,
+    pub requests: &'a RequestContextTracker,
+    pub exceptions: &'a ExceptionTracker,
+    pub tests: &'a TestTracker,
+    pub database: &'a DatabaseHealth,
+}
+
+impl SessionArchive {
+    /// Snapshot everything `sources` hold
+    pub fn capture(sources: &SessionSources, metadata: SessionMetadata) -> Self {
+        Self {
+            version: SESSION_FORMAT_VERSION,
+            metadata,
+            processes: sources.processes.to_vec(),
+            logs: sources.logs.to_vec(),
+            requests: sources.requests.snapshot(),
+            exceptions: sources.exceptions.snapshot(),
+            tests: sources.tests.snapshot(),
+            database: sources.database.snapshot(),
+            stats: sources.stats.get_stats(),
+        }
+    }
+
+    /// Load the saved data into freshly created trackers
+    pub fn restore_into(self, sources: &SessionSources) {
+        sources.requests.restore(self.requests);
+        sources.exceptions.restore(self.exceptions);
+        sources.tests.restore(self.tests);
+        sources.database.restore(self.database);
+        sources.stats.restore(self.stats);
+    }
+
+    /// Write the archive to `path` as gzip-compressed JSON
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        serde_json::to_writer(&mut encoder, self)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        encoder
+            .finish()
+            .and_then(|mut writer| writer.flush())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Read an archive written by [`Self::save`]; uncompressed JSON is accepted too
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let json = if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut json)
+                .map_err(|e| format!("{} is not a valid session archive: {}", path.display(), e))?;
+            json
+        } else {
+            bytes
+        };
+        Self::from_json(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parse an archive, checking its version before the rest of it
+    pub fn from_json(json: &[u8]) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_slice(json)
+            .map_err(|e| format!("not a valid session archive: {}", e))?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("not a session archive (no format version)")?;
+        if version > u64::from(SESSION_FORMAT_VERSION) {
+            return Err(format!(
+                "saved with session format {}, this Caboose reads up to {} - upgrade to open it",
+                version, SESSION_FORMAT_VERSION
+            ));
+        }
+        serde_json::from_value(value).map_err(|e| format!("not a valid session archive: {}", e))
+    }
+}
+
+/// Name used by `/session save` when none is given, stamped in `zone`
+pub fn default_file_name(now: DateTime<Local>, zone: DisplayZone) -> String {
+    format!("caboose_session_{}.json.gz", zone.file_stamp(now))
+}
@@ -0,0 +1,121 @@
+//! Tracker state written by the log ingestion and read by every UI frame
+//!
+//! A [`Shared`] value sits behind a `parking_lot` read-write lock and counts
+//! its writes. Readers ask for an [`Arc`] snapshot, which is only rebuilt when
+//! the count moved since the last one, so an idle frame neither allocates nor
+//! waits on the ingestion thread. Views derived from the value (sorted groups,
+//! issues) are cached the same way in a [`SnapshotCache`].
+//!
+//! Lock order: a snapshot never holds the value's lock and its cache's at the
+//! same time, and nothing calls into another tracker while holding a guard.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The last view built from a [`Shared`] value, and the write it reflects
+#[derive(Debug)]
+pub struct SnapshotCache<V> {
+    cached: Mutex<Option<(u64, Arc<V>)>>,
+}
+
+impl<V> Default for SnapshotCache<V> {
+    fn default() -> Self {
+        Self {
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+impl<V> SnapshotCache<V> {
+    /// The view cached for `generation`, or `build`'s, cached from now on
+    ///
+    /// For views of several [`Shared`] values, keyed by the sum of their
+    /// generations. Take the generation before building, so a write racing
+    /// the build only costs a rebuild on the next call.
+    pub fn get_or_build(&self, generation: u64, build: impl FnOnce() -> V) -> Arc<V> {
+        if let Some(view) = self.get(generation) {
+            return view;
+        }
+        let view = Arc::new(build());
+        self.store(generation, &view);
+        view
+    }
+
+    /// Drop the cached view, for changes no generation counts
+    pub fn clear(&self) {
+        *self.cached.lock() = None;
+    }
+
+    fn get(&self, generation: u64) -> Option<Arc<V>> {
+        match &*self.cached.lock() {
+            Some((cached, view)) if *cached == generation => Some(Arc::clone(view)),
+            _ => None,
+        }
+    }
+
+    fn store(&self, generation: u64, view: &Arc<V>) {
+        let mut cached = self.cached.lock();
+        // A slower reader may finish after one that saw a later write
+        if cached.as_ref().is_none_or(|(older, _)| *older < generation) {
+            *cached = Some((generation, Arc::clone(view)));
+        }
+    }
+}
+
+/// A value with cheap, cached snapshots for readers
+#[derive(Debug, Default)]
+pub struct Shared<T> {
+    value: RwLock<T>,
+    generation: AtomicU64,
+    snapshot: SnapshotCache<T>,
+}
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: RwLock::new(value),
+            generation: AtomicU64::new(0),
+            snapshot: SnapshotCache::default(),
+        }
+    }
+
+    /// Read in place, for lookups that don't need the whole value
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.value.read()
+    }
+
+    /// Write access; every snapshot taken before is stale from here on
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let value = self.value.write();
+        self.generation.fetch_add(1, Ordering::Release);
+        value
+    }
+
+    /// Writes so far
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// `build` applied to the value, reused until the next write
+    pub fn derive<V>(&self, cache: &SnapshotCache<V>, build: impl FnOnce(&T) -> V) -> Arc<V> {
+        if let Some(view) = cache.get(self.generation()) {
+            return view;
+        }
+        let (generation, view) = {
+            let value = self.value.read();
+            // No write can land while the read lock is held
+            (self.generation(), Arc::new(build(&value)))
+        };
+        cache.store(generation, &view);
+        view
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// The value as of the last write, shared with other readers
+    pub fn snapshot(&self) -> Arc<T> {
+        self.derive(&self.snapshot, T::clone)
+    }
+}
@@ -1,7 +1,33 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use crate::diagnostics::{StoreKind, StoreUsage, keep_last};
+use crate::parser::{CacheEvent, CacheOperation};
+use crate::shared::{Shared, SnapshotCache};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+mod timeline;
+
+pub use timeline::{
+    MAX_TIMELINE_BUCKETS, TIMELINE_BUCKET, Timeline, TimelineBucket, TimelineEvent, bucket_start,
+};
+
+/// How far back the DB share looks
+pub const DB_SHARE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Cache hit rate (%) below which the header turns yellow
+pub const DEFAULT_CACHE_HIT_RATE_WARNING: f64 = 80.0;
+
+/// Entries kept in the response time history
+const RESPONSE_TIME_HISTORY_LEN: usize = 100;
+
+/// How long per-request timings are kept, the longest window they can answer for
+pub const RECENT_TIMINGS_RETENTION: Duration = Duration::from_secs(15 * 60);
+
+/// Cap on kept per-request timings, however busy the window
+const MAX_RECENT_TIMINGS: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceStats {
     pub total_requests: usize,
     pub total_duration: f64,
@@ -10,6 +36,139 @@ pub struct PerformanceStats {
     pub sql_queries: usize,
     pub total_sql_duration: f64,
     pub response_time_history: Vec<u64>, // History of average response times
+    /// Timings of requests completed within [`RECENT_TIMINGS_RETENTION`], oldest first
+    pub recent_timings: VecDeque<RequestTiming>,
+    /// SQL time logged since the last completed request
+    pub pending_sql_duration: f64,
+    /// Cache store activity across the session
+    pub cache: CacheCounts,
+    /// Cache store activity per key prefix (first segment of the key)
+    pub cache_by_prefix: HashMap<String, CacheCounts>,
+    /// Request volume and notable events per [`TIMELINE_BUCKET`]
+    #[serde(default)]
+    pub timeline: Timeline,
+}
+
+/// Rails cache store activity
+///
+/// Reads are split into hits and misses; fragment writes and deletes are
+/// counted on their own and don't affect the hit rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheCounts {
+    pub hits: usize,
+    pub misses: usize,
+    pub writes: usize,
+    pub deletes: usize,
+}
+
+impl CacheCounts {
+    pub fn record(&mut self, operation: CacheOperation) {
+        match operation {
+            CacheOperation::Hit => self.hits += 1,
+            CacheOperation::Miss => self.misses += 1,
+            CacheOperation::Write => self.writes += 1,
+            CacheOperation::Delete => self.deletes += 1,
+        }
+    }
+
+    pub fn reads(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads() + self.writes + self.deletes == 0
+    }
+
+    /// Percentage of reads that hit; `None` before any read
+    pub fn hit_rate(&self) -> Option<f64> {
+        let reads = self.reads();
+        (reads > 0).then(|| (self.hits as f64 / reads as f64) * 100.0)
+    }
+}
+
+/// Total and database time of one completed request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RequestTiming {
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
+    pub completed_at: Instant,
+    pub status: u16,
+    pub duration: f64,
+    pub db_duration: f64,
+}
+
+/// Requests completed within a time window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestWindow {
+    pub requests: usize,
+    pub errors: usize,
+    pub avg_duration: f64,
+    pub p95_duration: f64,
+}
+
+impl RequestWindow {
+    /// Percentage of requests with a 4xx/5xx status
+    pub fn error_rate(&self) -> f64 {
+        (self.errors as f64 / self.requests as f64) * 100.0
+    }
+}
+
+/// Share of recent request time spent in the database
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DbShare {
+    pub requests: usize,
+    pub db_ms: f64,
+    pub app_ms: f64,
+}
+
+impl DbShare {
+    /// Database time as a percentage of total request time (0 when no time was logged)
+    pub fn db_percent(&self) -> f64 {
+        let total = self.db_ms + self.app_ms;
+        if total > 0.0 {
+            (self.db_ms / total) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbShareLevel {
+    Normal,
+    Warning,
+    Danger,
+}
+
+/// DB share percentages at which the header turns warning / danger
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DbShareThresholds {
+    pub warning: f64,
+    pub danger: f64,
+}
+
+impl Default for DbShareThresholds {
+    fn default() -> Self {
+        Self {
+            warning: 50.0,
+            danger: 75.0,
+        }
+    }
+}
+
+impl DbShareThresholds {
+    pub fn level(&self, share: &DbShare) -> DbShareLevel {
+        let percent = share.db_percent();
+        if percent >= self.danger {
+            DbShareLevel::Danger
+        } else if percent >= self.warning {
+            DbShareLevel::Warning
+        } else {
+            DbShareLevel::Normal
+        }
+    }
 }
 
 impl Default for PerformanceStats {
@@ -21,7 +180,12 @@ impl Default for PerformanceStats {
             status_codes: HashMap::new(),
             sql_queries: 0,
             total_sql_duration: 0.0,
-            response_time_history: Vec::with_capacity(100), // Pre-allocate capacity
+            response_time_history: Vec::with_capacity(RESPONSE_TIME_HISTORY_LEN),
+            recent_timings: VecDeque::new(),
+            pending_sql_duration: 0.0,
+            cache: CacheCounts::default(),
+            cache_by_prefix: HashMap::new(),
+            timeline: Timeline::default(),
         }
     }
 }
@@ -50,22 +214,170 @@ impl PerformanceStats {
             0.0
         }
     }
+
+    /// Per-prefix cache activity, most reads first
+    pub fn cache_prefixes(&self) -> Vec<(String, CacheCounts)> {
+        let mut prefixes: Vec<(String, CacheCounts)> = self
+            .cache_by_prefix
+            .iter()
+            .map(|(prefix, counts)| (prefix.clone(), *counts))
+            .collect();
+        prefixes.sort_by(|a, b| b.1.reads().cmp(&a.1.reads()).then_with(|| a.0.cmp(&b.0)));
+        prefixes
+    }
+
+    /// Timings of requests completed in the `window` before `now`
+    fn timings_in(&self, now: Instant, window: Duration) -> impl Iterator<Item = &RequestTiming> {
+        self.recent_timings
+            .iter()
+            .filter(move |t| now.saturating_duration_since(t.completed_at) <= window)
+    }
+
+    /// Request count, error count and durations in the `window` before `now`
+    ///
+    /// Returns `None` when no request completed in the window.
+    pub fn requests_at(&self, now: Instant, window: Duration) -> Option<RequestWindow> {
+        let mut durations: Vec<f64> = Vec::new();
+        let mut errors = 0;
+        for timing in self.timings_in(now, window) {
+            durations.push(timing.duration);
+            if timing.status >= 400 {
+                errors += 1;
+            }
+        }
+        if durations.is_empty() {
+            return None;
+        }
+
+        durations.sort_by(|a, b| a.total_cmp(b));
+        // Nearest-rank percentile
+        let p95_index = ((durations.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+        Some(RequestWindow {
+            requests: durations.len(),
+            errors,
+            avg_duration: durations.iter().sum::<f64>() / durations.len() as f64,
+            p95_duration: durations[p95_index],
+        })
+    }
+
+    /// DB share of requests completed in the `window` before `now`
+    ///
+    /// Returns `None` when no request completed in the window.
+    pub fn db_share_at(&self, now: Instant, window: Duration) -> Option<DbShare> {
+        let recent: Vec<&RequestTiming> = self.timings_in(now, window).collect();
+        if recent.is_empty() {
+            return None;
+        }
+
+        let db_ms: f64 = recent.iter().map(|t| t.db_duration).sum();
+        let total_ms: f64 = recent.iter().map(|t| t.duration).sum();
+        Some(DbShare {
+            requests: recent.len(),
+            db_ms,
+            app_ms: (total_ms - db_ms).max(0.0),
+        })
+    }
+}
+
+/// The header's stats line, built once per change to the stats rather than
+/// on every frame
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderStats {
+    pub total_requests: usize,
+    pub avg_response_time: f64,
+    pub error_rate: f64,
+    pub sql_queries: usize,
+    pub cache: CacheCounts,
+    /// The response time history, as the sparkline takes it
+    pub response_times: Vec<f64>,
+}
+
+impl HeaderStats {
+    pub fn new(stats: &PerformanceStats) -> Self {
+        Self {
+            total_requests: stats.total_requests,
+            avg_response_time: stats.avg_response_time(),
+            error_rate: stats.error_rate(),
+            sql_queries: stats.sql_queries,
+            cache: stats.cache,
+            response_times: stats
+                .response_time_history
+                .iter()
+                .map(|&ms| ms as f64)
+                .collect(),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct StatsCollector {
-    stats: Arc<Mutex<PerformanceStats>>,
+    stats: Arc<Shared<PerformanceStats>>,
+    db_share_thresholds: DbShareThresholds,
+    cache_hit_rate_warning: f64,
+}
+
+impl Default for StatsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StatsCollector {
     pub fn new() -> Self {
         Self {
-            stats: Arc::new(Mutex::new(PerformanceStats::default())),
+            stats: Arc::new(Shared::new(PerformanceStats::default())),
+            db_share_thresholds: DbShareThresholds::default(),
+            cache_hit_rate_warning: DEFAULT_CACHE_HIT_RATE_WARNING,
         }
     }
 
+    /// Use custom warning / danger thresholds for the DB share
+    pub fn with_db_share_thresholds(mut self, thresholds: DbShareThresholds) -> Self {
+        self.db_share_thresholds = thresholds;
+        self
+    }
+
+    pub fn db_share_thresholds(&self) -> DbShareThresholds {
+        self.db_share_thresholds
+    }
+
+    /// Use a custom hit rate below which the cache stat is shown as a warning
+    pub fn with_cache_hit_rate_warning(mut self, percent: f64) -> Self {
+        self.cache_hit_rate_warning = percent;
+        self
+    }
+
+    pub fn cache_hit_rate_warning(&self) -> f64 {
+        self.cache_hit_rate_warning
+    }
+
     pub fn record_request(&self, status: u16, duration: f64) {
-        let mut stats = self.stats.lock().unwrap();
+        self.record_request_with_db_time(status, duration, None);
+    }
+
+    /// Record a completed request with the ActiveRecord time Rails logged for it
+    ///
+    /// Without a logged DB time, the SQL time recorded since the previous
+    /// request is attributed to this one.
+    pub fn record_request_with_db_time(&self, status: u16, duration: f64, db_time: Option<f64>) {
+        let mut stats = self.stats.write();
+        let pending_sql = std::mem::take(&mut stats.pending_sql_duration);
+        let db_duration = db_time.unwrap_or(pending_sql).clamp(0.0, duration.max(0.0));
+
+        let now = Instant::now();
+        stats.recent_timings.push_back(RequestTiming {
+            completed_at: now,
+            status,
+            duration: duration.max(0.0),
+            db_duration,
+        });
+        while let Some(oldest) = stats.recent_timings.front()
+            && (now.saturating_duration_since(oldest.completed_at) > RECENT_TIMINGS_RETENTION
+                || stats.recent_timings.len() > MAX_RECENT_TIMINGS)
+        {
+            stats.recent_timings.pop_front();
+        }
+
         stats.total_requests += 1;
         stats.total_duration += duration;
 
@@ -78,27 +390,147 @@ impl StatsCollector {
         // Update response time history (rolling average)
         let current_avg = stats.avg_response_time().round() as u64;
         stats.response_time_history.push(current_avg);
-        if stats.response_time_history.len() > 100 {
-            stats.response_time_history.remove(0); // Keep history to last 100 entries
+        if stats.response_time_history.len() > RESPONSE_TIME_HISTORY_LEN {
+            stats.response_time_history.remove(0);
         }
     }
 
     pub fn record_sql_query(&self, duration: f64) {
-        let mut stats = self.stats.lock().unwrap();
+        let mut stats = self.stats.write();
         stats.sql_queries += 1;
         stats.total_sql_duration += duration;
+        stats.pending_sql_duration += duration;
+    }
+
+    pub fn record_cache(&self, event: &CacheEvent) {
+        let mut stats = self.stats.write();
+        stats.cache.record(event.operation);
+        stats
+            .cache_by_prefix
+            .entry(event.prefix().to_string())
+            .or_default()
+            .record(event.operation);
+    }
+
+    /// Count `event` on the session timeline at `at`
+    pub fn record_timeline(&self, at: chrono::DateTime<chrono::Local>, event: TimelineEvent) {
+        self.stats.write().timeline.record(at, event);
+    }
+
+    /// Mark the session timeline as asleep from `from` to `to`
+    pub fn record_suspension(
+        &self,
+        from: chrono::DateTime<chrono::Local>,
+        to: chrono::DateTime<chrono::Local>,
+    ) {
+        self.stats.write().timeline.record_suspension(from, to);
+    }
+
+    /// Nothing on the session timeline yet
+    pub fn timeline_is_empty(&self) -> bool {
+        self.stats.read().timeline.is_empty()
+    }
+
+    /// `count` timeline buckets `width` wide ending with the one holding `end`
+    pub fn timeline_window(
+        &self,
+        end: chrono::DateTime<chrono::Local>,
+        width: chrono::TimeDelta,
+        count: usize,
+    ) -> Vec<(chrono::DateTime<chrono::Local>, TimelineBucket)> {
+        self.stats.read().timeline.window(end, width, count)
+    }
+
+    /// Start of the oldest timeline bucket, if anything was recorded
+    pub fn timeline_earliest(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.stats.read().timeline.earliest()
+    }
+
+    /// Start of the newest timeline bucket, if anything was recorded
+    pub fn timeline_latest(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.stats.read().timeline.latest()
+    }
+
+    /// DB share over the last [`DB_SHARE_WINDOW`]
+    pub fn db_share(&self) -> Option<DbShare> {
+        self.stats
+            .read()
+            .db_share_at(Instant::now(), DB_SHARE_WINDOW)
+    }
+
+    /// Requests completed in the `window` before `now`
+    pub fn requests_at(&self, now: Instant, window: Duration) -> Option<RequestWindow> {
+        self.stats.read().requests_at(now, window)
+    }
+
+    /// DB share of requests completed in the `window` before `now`
+    pub fn db_share_at(&self, now: Instant, window: Duration) -> Option<DbShare> {
+        self.stats.read().db_share_at(now, window)
     }
 
     pub fn get_stats(&self) -> PerformanceStats {
-        self.stats.lock().unwrap().clone()
+        self.stats.read().clone()
+    }
+
+    /// Changes to the stats so far
+    pub fn generation(&self) -> u64 {
+        self.stats.generation()
+    }
+
+    /// The header's stats, rebuilt only if they changed since `cache` was filled
+    pub fn header_stats(&self, cache: &SnapshotCache<HeaderStats>) -> Arc<HeaderStats> {
+        self.stats.derive(cache, HeaderStats::new)
+    }
+
+    /// Replace the counters with a saved session's
+    pub fn restore(&self, stats: PerformanceStats) {
+        *self.stats.write() = stats;
     }
 
     pub fn get_response_time_history(&self) -> Vec<u64> {
-        self.stats.lock().unwrap().response_time_history.clone()
+        self.stats.read().response_time_history.clone()
+    }
+
+    /// Item counts and approximate sizes of the collector's stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        let stats = self.stats.read();
+        vec![
+            StoreUsage::new(
+                StoreKind::ResponseTimeHistory,
+                stats.response_time_history.len(),
+                stats.response_time_history.capacity() * std::mem::size_of::<u64>(),
+            )
+            .with_limit(RESPONSE_TIME_HISTORY_LEN),
+        ]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        let stats = self.stats.read();
+        std::mem::size_of::<PerformanceStats>()
+            + stats.response_time_history.capacity() * std::mem::size_of::<u64>()
+            + stats.status_codes.len() * std::mem::size_of::<(u16, usize)>()
+            + stats.recent_timings.capacity() * std::mem::size_of::<RequestTiming>()
+            + stats.timeline.len() * std::mem::size_of::<TimelineBucket>()
+            + stats
+                .cache_by_prefix
+                .keys()
+                .map(|prefix| prefix.len() + std::mem::size_of::<CacheCounts>())
+                .sum::<usize>()
+    }
+
+    /// Keep only the newest `keep` entries of a store, returning how many were dropped
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::ResponseTimeHistory => {
+                let mut stats = self.stats.write();
+                keep_last(&mut stats.response_time_history, keep)
+            }
+            _ => 0,
+        }
     }
 
     pub fn reset(&self) {
-        let mut stats = self.stats.lock().unwrap();
+        let mut stats = self.stats.write();
         *stats = PerformanceStats::default();
     }
 }
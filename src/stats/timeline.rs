@@ -0,0 +1,221 @@
+//! Session timeline: request volume and notable events over wall-clock time
+//!
+//! Events are counted into fixed [`TIMELINE_BUCKET`] buckets as they are
+//! recorded, so drawing the timeline only sums buckets; coarser zoom levels
+//! add up whole runs of them.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// Width of the buckets events are counted in; zoom levels are multiples of it
+pub const TIMELINE_BUCKET: TimeDelta = TimeDelta::seconds(10);
+
+/// Buckets kept: the last six hours
+pub const MAX_TIMELINE_BUCKETS: usize = 6 * 60 * 6;
+
+/// Something that happened at a point in the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineEvent {
+    /// A completed request; 5xx counts as an error
+    Request {
+        status: u16,
+    },
+    Exception,
+    /// A process that crashed
+    Crash,
+    /// A process restarted by Caboose
+    Restart,
+    /// Migrations run, routes or database.yml edited, `.caboose.toml` reloaded
+    Deploy,
+    /// A test run that finished
+    TestRun,
+}
+
+/// Counts for one stretch of time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub requests: u32,
+    pub errors: u32,
+    pub exceptions: u32,
+    pub crashes: u32,
+    pub restarts: u32,
+    pub deploys: u32,
+    pub test_runs: u32,
+    /// Buckets of it the machine spent suspended
+    #[serde(default)]
+    pub suspended: u32,
+}
+
+impl TimelineBucket {
+    fn record(&mut self, event: TimelineEvent) {
+        let count = match event {
+            TimelineEvent::Request { status } => {
+                if status >= 500 {
+                    self.errors += 1;
+                }
+                &mut self.requests
+            }
+            TimelineEvent::Exception => &mut self.exceptions,
+            TimelineEvent::Crash => &mut self.crashes,
+            TimelineEvent::Restart => &mut self.restarts,
+            TimelineEvent::Deploy => &mut self.deploys,
+            TimelineEvent::TestRun => &mut self.test_runs,
+        };
+        *count += 1;
+    }
+
+    fn add(&mut self, other: &TimelineBucket) {
+        self.requests += other.requests;
+        self.errors += other.errors;
+        self.exceptions += other.exceptions;
+        self.crashes += other.crashes;
+        self.restarts += other.restarts;
+        self.deploys += other.deploys;
+        self.test_runs += other.test_runs;
+        self.suspended += other.suspended;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == TimelineBucket::default()
+    }
+}
+
+/// Buckets of [`TIMELINE_BUCKET`] from the first event recorded onwards
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Timeline {
+    /// Index of the first bucket: Unix seconds / bucket seconds
+    first: i64,
+    buckets: VecDeque<TimelineBucket>,
+}
+
+impl Timeline {
+    /// Count `event` in the bucket holding `at`
+    ///
+    /// Events older than the kept buckets are dropped.
+    pub fn record(&mut self, at: DateTime<Local>, event: TimelineEvent) {
+        if let Some(bucket) = self.bucket_mut(bucket_index(at)) {
+            bucket.record(event);
+        }
+        self.trim();
+    }
+
+    /// Mark the buckets from `from` to `to` as time the machine was suspended
+    ///
+    /// Charts show these as asleep rather than as a quiet stretch. Only the
+    /// newest kept buckets' worth of a longer suspension is marked.
+    pub fn record_suspension(&mut self, from: DateTime<Local>, to: DateTime<Local>) {
+        let last = bucket_index(to);
+        let first = bucket_index(from).max(last - MAX_TIMELINE_BUCKETS as i64 + 1);
+        for index in first..=last {
+            if let Some(bucket) = self.bucket_mut(index) {
+                bucket.suspended += 1;
+            }
+        }
+        self.trim();
+    }
+
+    /// The bucket at `index`, added if it's missing
+    ///
+    /// Indexes older than the kept buckets have none; one far ahead of them
+    /// (the clock jumped) starts the timeline over.
+    fn bucket_mut(&mut self, index: i64) -> Option<&mut TimelineBucket> {
+        if self.buckets.is_empty() || index - self.first >= 2 * MAX_TIMELINE_BUCKETS as i64 {
+            self.first = index;
+            self.buckets.clear();
+            self.buckets.push_back(TimelineBucket::default());
+        }
+        if index < self.first {
+            let missing = (self.first - index) as usize;
+            if self.buckets.len() + missing > MAX_TIMELINE_BUCKETS {
+                return None;
+            }
+            for _ in 0..missing {
+                self.buckets.push_front(TimelineBucket::default());
+            }
+            self.first = index;
+        }
+        let offset = (index - self.first) as usize;
+        if offset >= self.buckets.len() {
+            self.buckets.resize(offset + 1, TimelineBucket::default());
+        }
+        self.buckets.get_mut(offset)
+    }
+
+    fn trim(&mut self) {
+        while self.buckets.len() > MAX_TIMELINE_BUCKETS {
+            self.buckets.pop_front();
+            self.first += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Start of the newest bucket
+    pub fn latest(&self) -> Option<DateTime<Local>> {
+        (!self.buckets.is_empty()).then(|| bucket_time(self.first + self.buckets.len() as i64 - 1))
+    }
+
+    /// Start of the oldest bucket
+    pub fn earliest(&self) -> Option<DateTime<Local>> {
+        (!self.buckets.is_empty()).then(|| bucket_time(self.first))
+    }
+
+    /// `count` buckets `width` wide, the last one holding `end`, oldest first
+    ///
+    /// `width` is rounded to a whole number of [`TIMELINE_BUCKET`]s and buckets
+    /// are aligned to it, so a bucket keeps its bounds as the window pans.
+    pub fn window(
+        &self,
+        end: DateTime<Local>,
+        width: TimeDelta,
+        count: usize,
+    ) -> Vec<(DateTime<Local>, TimelineBucket)> {
+        let step = (width.num_seconds() / TIMELINE_BUCKET.num_seconds()).max(1);
+        let last = bucket_index(end).div_euclid(step) * step;
+        (0..count as i64)
+            .rev()
+            .map(|back| {
+                let start = last - back * step;
+                let mut total = TimelineBucket::default();
+                for index in start..start + step {
+                    if let Some(bucket) = self.get(index) {
+                        total.add(bucket);
+                    }
+                }
+                (bucket_time(start), total)
+            })
+            .collect()
+    }
+
+    fn get(&self, index: i64) -> Option<&TimelineBucket> {
+        usize::try_from(index - self.first)
+            .ok()
+            .and_then(|offset| self.buckets.get(offset))
+    }
+}
+
+/// Start of the `width`-wide bucket holding `at`
+pub fn bucket_start(at: DateTime<Local>, width: TimeDelta) -> DateTime<Local> {
+    let step = width.num_seconds().max(1);
+    let start = at.timestamp().div_euclid(step) * step;
+    Local.timestamp_opt(start, 0).single().unwrap_or(at)
+}
+
+fn bucket_index(at: DateTime<Local>) -> i64 {
+    at.timestamp().div_euclid(TIMELINE_BUCKET.num_seconds())
+}
+
+fn bucket_time(index: i64) -> DateTime<Local> {
+    Local
+        .timestamp_opt(index * TIMELINE_BUCKET.num_seconds(), 0)
+        .single()
+        .unwrap_or_default()
+}
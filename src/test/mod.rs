@@ -1,8 +1,19 @@
+mod rspec;
+
+use crate::diagnostics::{Limits, StoreKind, StoreUsage, keep_last, strings_bytes, total_bytes};
+use crate::search::SearchQuery;
+use crate::shared::Shared;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Instant;
 
-#[derive(Debug, Clone, PartialEq)]
+use regex::Regex;
+
+use rspec::{RSpecEvent, RSpecParser};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TestFramework {
     RSpec,
     Minitest,
@@ -10,7 +21,7 @@ pub enum TestFramework {
     Unknown,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestResult {
     pub test_name: String,
     pub file_path: Option<String>,
@@ -19,10 +30,14 @@ pub struct TestResult {
     pub duration: Option<f64>,
     pub failure_message: Option<String>,
     pub backtrace: Option<Vec<String>>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub timestamp: Instant,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TestStatus {
     Passed,
     Failed,
@@ -30,10 +45,18 @@ pub enum TestStatus {
     Skipped,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestRun {
     pub framework: TestFramework,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub started_at: Instant,
+    #[serde(
+        serialize_with = "crate::serialization::optional_instant_age",
+        deserialize_with = "crate::serialization::optional_instant_from_age"
+    )]
     pub completed_at: Option<Instant>,
     pub total_tests: usize,
     pub passed: usize,
@@ -42,6 +65,52 @@ pub struct TestRun {
     pub skipped: usize,
     pub duration: Option<f64>,
     pub test_results: Vec<TestResult>,
+    /// Set while the run is stopped at a breakpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<DebuggerPause>,
+    /// Time spent at breakpoints, left out of `duration`
+    #[serde(default)]
+    pub paused_ms: f64,
+    /// Paused time not yet taken off the duration of the test it interrupted
+    #[serde(skip)]
+    pub unattributed_pause_ms: f64,
+}
+
+/// A run stopped at a breakpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebuggerPause {
+    pub file_path: Option<String>,
+    pub line_number: Option<usize>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
+    pub started_at: Instant,
+}
+
+impl DebuggerPause {
+    pub fn elapsed_ms(&self) -> f64 {
+        self.started_at.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// `app/models/user.rb:27`, or just the file when the line isn't known
+    pub fn location(&self) -> Option<String> {
+        let file = self.file_path.as_deref()?;
+        Some(match self.line_number {
+            Some(line) => format!("{}:{}", file, line),
+            None => file.to_string(),
+        })
+    }
+}
+
+impl TestResult {
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.test_name.len()
+            + self.file_path.as_ref().map_or(0, |p| p.len())
+            + self.failure_message.as_ref().map_or(0, |m| m.len())
+            + self.backtrace.as_ref().map_or(0, |b| strings_bytes(b))
+    }
 }
 
 impl TestRun {
@@ -57,23 +126,126 @@ impl TestRun {
             skipped: 0,
             duration: None,
             test_results: Vec::new(),
+            paused: None,
+            paused_ms: 0.0,
+            unattributed_pause_ms: 0.0,
+        }
+    }
+
+    /// Stop the clock at a breakpoint; a pause already under way only
+    /// learns its location
+    pub fn pause(&mut self, file_path: Option<String>, line_number: Option<usize>) {
+        match &mut self.paused {
+            Some(pause) => {
+                if file_path.is_some() {
+                    pause.file_path = file_path;
+                    pause.line_number = line_number;
+                }
+            }
+            None => {
+                self.paused = Some(DebuggerPause {
+                    file_path,
+                    line_number,
+                    started_at: Instant::now(),
+                })
+            }
+        }
+    }
+
+    /// Start the clock again, returning whether the run was paused
+    pub fn resume(&mut self) -> bool {
+        let Some(pause) = self.paused.take() else {
+            return false;
+        };
+        let elapsed = pause.elapsed_ms();
+        self.paused_ms += elapsed;
+        self.unattributed_pause_ms += elapsed;
+        true
+    }
+
+    /// Time spent at breakpoints so far, including a pause under way
+    pub fn total_paused_ms(&self) -> f64 {
+        self.paused_ms + self.paused.as_ref().map_or(0.0, DebuggerPause::elapsed_ms)
+    }
+
+    /// Record a result; time the run spent paused since the previous result
+    /// was spent inside this test, so it comes off the test's duration
+    pub fn add_result(&mut self, mut result: TestResult) {
+        self.resume();
+        if let Some(duration) = result.duration {
+            result.duration = Some((duration - self.unattributed_pause_ms).max(0.0));
         }
+        self.unattributed_pause_ms = 0.0;
+
+        self.count(&result.status);
+        self.test_results.push(result);
+    }
+
+    /// Count an example the output didn't name, like a progress dot
+    pub fn tally(&mut self, status: TestStatus) {
+        self.resume();
+        self.unattributed_pause_ms = 0.0;
+        self.count(&status);
     }
 
-    pub fn add_result(&mut self, result: TestResult) {
+    fn count(&mut self, status: &TestStatus) {
         self.total_tests += 1;
-        match result.status {
+        match status {
             TestStatus::Passed => self.passed += 1,
             TestStatus::Failed => self.failed += 1,
             TestStatus::Pending => self.pending += 1,
             TestStatus::Skipped => self.skipped += 1,
         }
-        self.test_results.push(result);
     }
 
+    /// Fill in the `number`th result with `detail`'s status from a failure
+    /// or pending list, taking its name, message and location
+    ///
+    /// Examples that were only tallied have no result to fill in, so the
+    /// detail is added without being counted again.
+    pub fn add_detail(&mut self, number: usize, detail: TestResult) {
+        let existing = self
+            .test_results
+            .iter_mut()
+            .filter(|result| result.status == detail.status)
+            .nth(number.saturating_sub(1));
+        match existing {
+            Some(result) => {
+                result.test_name = detail.test_name;
+                result.file_path = detail.file_path.or(result.file_path.take());
+                result.line_number = detail.line_number.or(result.line_number);
+                result.failure_message = detail.failure_message.or(result.failure_message.take());
+                result.backtrace = detail.backtrace.or(result.backtrace.take());
+            }
+            None => self.test_results.push(detail),
+        }
+    }
+
+    /// Take the counts from the framework's summary, which covers examples
+    /// the output didn't show one by one
+    pub fn set_totals(&mut self, total: usize, failed: usize, pending: usize) {
+        self.total_tests = total;
+        self.failed = failed;
+        self.pending = pending;
+        self.skipped = 0;
+        self.passed = total.saturating_sub(failed + pending);
+    }
+
+    /// Finish the run; `duration` is the framework's wall-clock time, so
+    /// time spent at breakpoints is taken off it
     pub fn complete(&mut self, duration: Option<f64>) {
+        self.resume();
         self.completed_at = Some(Instant::now());
-        self.duration = duration;
+        self.duration = duration.map(|duration| (duration - self.paused_ms).max(0.0));
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self
+                .test_results
+                .iter()
+                .map(TestResult::approx_bytes)
+                .sum::<usize>()
     }
 
     pub fn success_rate(&self) -> f64 {
@@ -91,7 +263,7 @@ impl TestRun {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TestStats {
     pub total_runs: usize,
     pub total_tests_run: usize,
@@ -102,25 +274,46 @@ pub struct TestStats {
     pub slowest_tests: Vec<TestResult>,
 }
 
+/// Test runs and totals, as kept in a saved session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestSnapshot {
+    pub framework: Option<TestFramework>,
+    pub current_run: Option<TestRun>,
+    pub recent_runs: Vec<TestRun>,
+    pub stats: TestStats,
+}
+
+/// A file and, once known, the line in it
+type SourceLine = (String, Option<usize>);
+
 pub struct TestTracker {
     framework: Arc<Mutex<Option<TestFramework>>>,
     current_run: Arc<Mutex<Option<TestRun>>>,
-    recent_runs: Arc<Mutex<Vec<TestRun>>>,
-    stats: Arc<Mutex<TestStats>>,
+    recent_runs: Shared<Vec<TestRun>>,
+    stats: Shared<TestStats>,
     debugger_active: Arc<Mutex<bool>>,
     debugger_info: Arc<Mutex<Option<DebuggerInfo>>>,
+    /// The file and line a debugger last listed, ahead of its prompt
+    debugger_source: Arc<Mutex<Option<SourceLine>>>,
+    rspec: Mutex<RSpecParser>,
+    max_test_runs: AtomicUsize,
+    max_slowest_tests: AtomicUsize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DebuggerInfo {
     pub debugger_type: DebuggerType,
     pub file_path: Option<String>,
     pub line_number: Option<usize>,
     pub variables: HashMap<String, String>,
+    #[serde(
+        serialize_with = "crate::serialization::instant_age",
+        deserialize_with = "crate::serialization::instant_from_age"
+    )]
     pub timestamp: Instant,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum DebuggerType {
     Pry,
     Byebug,
@@ -128,20 +321,45 @@ pub enum DebuggerType {
     Unknown,
 }
 
+impl Default for TestTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TestTracker {
     pub fn new() -> Self {
         Self {
             framework: Arc::new(Mutex::new(None)),
             current_run: Arc::new(Mutex::new(None)),
-            recent_runs: Arc::new(Mutex::new(Vec::new())),
-            stats: Arc::new(Mutex::new(TestStats::default())),
+            recent_runs: Shared::default(),
+            stats: Shared::default(),
             debugger_active: Arc::new(Mutex::new(false)),
             debugger_info: Arc::new(Mutex::new(None)),
+            debugger_source: Arc::new(Mutex::new(None)),
+            rspec: Mutex::new(RSpecParser::new()),
+            max_test_runs: AtomicUsize::new(Limits::default().max_test_runs),
+            max_slowest_tests: AtomicUsize::new(Limits::default().max_slowest_tests),
         }
     }
 
+    pub fn with_limits(self, limits: Limits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Change how many runs and slowest tests are kept, trimming to fit
+    pub fn set_limits(&self, limits: Limits) {
+        self.max_test_runs
+            .store(limits.max_test_runs, Ordering::Relaxed);
+        self.max_slowest_tests
+            .store(limits.max_slowest_tests, Ordering::Relaxed);
+        self.trim(StoreKind::TestRuns, limits.max_test_runs);
+        self.trim(StoreKind::SlowestTests, limits.max_slowest_tests);
+    }
+
     pub fn detect_framework(&self, line: &str) -> Option<TestFramework> {
-        if line.contains("RSpec") || line.contains("rspec") {
+        if line.contains("RSpec") || line.contains("rspec") || rspec::is_rspec_output(line) {
             Some(TestFramework::RSpec)
         } else if line.contains("Minitest") || line.contains("minitest") {
             Some(TestFramework::Minitest)
@@ -173,7 +391,7 @@ impl TestTracker {
             run.complete(duration);
 
             // Update stats
-            let mut stats = self.stats.lock().unwrap();
+            let mut stats = self.stats.write();
             stats.total_runs += 1;
             stats.total_tests_run += run.total_tests;
             stats.total_passed += run.passed;
@@ -192,21 +410,23 @@ impl TestTracker {
 
             // Update slowest tests
             for test in &run.test_results {
-                if let Some(test_dur) = test.duration {
-                    if test_dur > 100.0 {
-                        stats.slowest_tests.push(test.clone());
-                    }
+                if let Some(test_dur) = test.duration
+                    && test_dur > 100.0
+                {
+                    stats.slowest_tests.push(test.clone());
                 }
             }
             stats
                 .slowest_tests
                 .sort_by(|a, b| b.duration.partial_cmp(&a.duration).unwrap());
-            stats.slowest_tests.truncate(20);
+            stats
+                .slowest_tests
+                .truncate(self.max_slowest_tests.load(Ordering::Relaxed));
 
             // Store in recent runs
-            let mut recent = self.recent_runs.lock().unwrap();
+            let mut recent = self.recent_runs.write();
             recent.push(run.clone());
-            if recent.len() > 10 {
+            if recent.len() > self.max_test_runs.load(Ordering::Relaxed) {
                 recent.remove(0);
             }
         }
@@ -214,16 +434,19 @@ impl TestTracker {
         *current = None;
     }
 
-    pub fn parse_line(&self, line: &str) {
+    /// Feed a log line, returning whether it was test runner output
+    pub fn parse_line(&self, line: &str) -> bool {
         // Auto-detect framework if not set
-        if self.framework.lock().unwrap().is_none() {
-            if let Some(fw) = self.detect_framework(line) {
-                self.start_test_run(fw);
-            }
+        let mut recognized = Self::is_test_output(line);
+        if self.framework.lock().unwrap().is_none()
+            && let Some(fw) = self.detect_framework(line)
+        {
+            self.start_test_run(fw);
+            recognized = true;
         }
 
-        // Check for debugger activation
-        self.detect_debugger(line);
+        // Check for debugger activation, pausing the run it stopped
+        self.track_debugger_pause(line);
 
         // Parse test output based on framework
         let framework = self.framework.lock().unwrap().clone();
@@ -232,24 +455,40 @@ impl TestTracker {
             Some(TestFramework::Minitest) => self.parse_minitest_line(line),
             _ => {}
         }
+        recognized
     }
 
     fn parse_rspec_line(&self, line: &str) {
-        // RSpec example format: "  example description"
-        // Failure format: "  1) example description"
-        // Summary format: "1 example, 0 failures"
-
-        // Check for test completion summary
-        if line.contains("example") && (line.contains("failure") || line.contains("passed")) {
-            if let Some(duration) = Self::extract_duration_rspec(line) {
-                self.complete_test_run(Some(duration));
+        let events = self.rspec.lock().unwrap().parse_line(line);
+        for event in events {
+            if let RSpecEvent::Summary {
+                examples,
+                failures,
+                pending,
+                duration,
+            } = event
+            {
+                self.with_rspec_run(|run| run.set_totals(examples, failures, pending));
+                self.complete_test_run(duration);
+                continue;
             }
+            self.with_rspec_run(|run| match event {
+                RSpecEvent::Progress(statuses) => statuses.into_iter().for_each(|s| run.tally(s)),
+                RSpecEvent::Example(example) => run.add_result(example),
+                RSpecEvent::Detail { number, example } => run.add_detail(number, example),
+                RSpecEvent::Summary { .. } => {}
+            });
         }
+    }
 
-        // Check for failures
-        if line.trim().starts_with("1)") || line.trim().starts_with("2)") {
-            // This is a failure description line
-            // Next lines will contain details
+    /// Apply `update` to the run in progress, starting one if the output
+    /// began without anything that announced it
+    fn with_rspec_run(&self, update: impl FnOnce(&mut TestRun)) {
+        if !self.is_running() {
+            self.start_test_run(TestFramework::RSpec);
+        }
+        if let Some(run) = self.current_run.lock().unwrap().as_mut() {
+            update(run);
         }
     }
 
@@ -257,10 +496,10 @@ impl TestTracker {
         // Minitest format: "Finished in 0.123s"
         // Results: "1 runs, 2 assertions, 0 failures, 0 errors, 0 skips"
 
-        if line.contains("Finished in") {
-            if let Some(duration) = Self::extract_duration_minitest(line) {
-                self.complete_test_run(Some(duration * 1000.0)); // Convert to ms
-            }
+        if line.contains("Finished in")
+            && let Some(duration) = Self::extract_duration_minitest(line)
+        {
+            self.complete_test_run(Some(duration * 1000.0)); // Convert to ms
         }
 
         // Parse results line
@@ -275,41 +514,28 @@ impl TestTracker {
 
         for part in parts {
             let part = part.trim();
-            if part.contains("failure") {
-                if let Some(count) = part
+            if part.contains("failure")
+                && let Some(count) = part
                     .split_whitespace()
                     .next()
                     .and_then(|n| n.parse::<usize>().ok())
-                {
-                    for _ in 0..count {
-                        self.add_test_result(TestResult {
-                            test_name: "Unknown test".to_string(),
-                            file_path: None,
-                            line_number: None,
-                            status: TestStatus::Failed,
-                            duration: None,
-                            failure_message: None,
-                            backtrace: None,
-                            timestamp: Instant::now(),
-                        });
-                    }
+            {
+                for _ in 0..count {
+                    self.add_test_result(TestResult {
+                        test_name: "Unknown test".to_string(),
+                        file_path: None,
+                        line_number: None,
+                        status: TestStatus::Failed,
+                        duration: None,
+                        failure_message: None,
+                        backtrace: None,
+                        timestamp: Instant::now(),
+                    });
                 }
             }
         }
     }
 
-    fn extract_duration_rspec(line: &str) -> Option<f64> {
-        // Format: "Finished in 0.12345 seconds"
-        if let Some(pos) = line.find("Finished in") {
-            let after = &line[pos + 12..];
-            if let Some(end) = after.find("second") {
-                let duration_str = &after[..end].trim();
-                return duration_str.parse::<f64>().ok().map(|s| s * 1000.0);
-            }
-        }
-        None
-    }
-
     fn extract_duration_minitest(line: &str) -> Option<f64> {
         // Format: "Finished in 0.123456s"
         if let Some(pos) = line.find("Finished in") {
@@ -322,7 +548,102 @@ impl TestTracker {
         None
     }
 
-    fn detect_debugger(&self, line: &str) {
+    /// Pause the current run while a debugger has it stopped
+    ///
+    /// A `continue` at the prompt or the next test output resumes it. Outside
+    /// a run a debugger only shows in the header.
+    fn track_debugger_pause(&self, line: &str) {
+        let source = Self::debugger_source_line(line);
+        if let Some((file, line_number)) = &source {
+            let mut last = self.debugger_source.lock().unwrap();
+            match (last.as_mut(), file) {
+                // `=> 27:` marks the current line of the file listed above it
+                (Some(last), None) => last.1 = *line_number,
+                (_, Some(file)) => *last = Some((file.clone(), *line_number)),
+                (None, None) => {}
+            }
+        }
+
+        let mut current = self.current_run.lock().unwrap();
+        let Some(run) = current.as_mut() else {
+            drop(current);
+            self.detect_debugger(line);
+            return;
+        };
+
+        if Self::is_resume_command(line) || Self::is_test_output(line) {
+            if run.resume() {
+                *self.debugger_source.lock().unwrap() = None;
+                self.clear_debugger();
+            }
+            return;
+        }
+
+        let activated = self.detect_debugger(line);
+        if activated || (run.paused.is_some() && source.is_some()) {
+            let info = self.get_debugger_info();
+            let listed = self.debugger_source.lock().unwrap().clone();
+            let (file_path, line_number) = match info {
+                Some(DebuggerInfo {
+                    file_path: Some(file),
+                    line_number,
+                    ..
+                }) if activated => (Some(file), line_number),
+                _ => match listed {
+                    Some((file, line_number)) => (Some(file), line_number),
+                    None => (None, None),
+                },
+            };
+            run.pause(file_path, line_number);
+        }
+    }
+
+    /// The file (`From: app/models/user.rb:27`, `[22, 31] in app/models/user.rb`)
+    /// or current line (`=> 27:`) of a debugger's source listing
+    fn debugger_source_line(line: &str) -> Option<(Option<String>, Option<usize>)> {
+        static FILE: OnceLock<Regex> = OnceLock::new();
+        static CURRENT_LINE: OnceLock<Regex> = OnceLock::new();
+        let file = FILE.get_or_init(|| {
+            Regex::new(r"(?:From: |^\s*\[\d+, \d+\] in )(\S+?\.rb)(?::(\d+))?").unwrap()
+        });
+        let current_line =
+            CURRENT_LINE.get_or_init(|| Regex::new(r"^\s*=>\s*(\d+)\s*[:|]").unwrap());
+
+        if let Some(caps) = file.captures(line) {
+            let line_number = caps.get(2).and_then(|n| n.as_str().parse().ok());
+            return Some((Some(caps[1].to_string()), line_number));
+        }
+        let caps = current_line.captures(line)?;
+        Some((None, caps[1].parse().ok()))
+    }
+
+    /// `continue` (or a way out) typed at a pry, byebug or debug prompt
+    fn is_resume_command(line: &str) -> bool {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN
+            .get_or_init(|| {
+                Regex::new(
+                    r"(?:pry\(.*\)>|\((?:byebug|rdbg|ruby)\))\s*(?:c|continue|exit|exit!|exit-all|!!!|q|quit)\s*$",
+                )
+                .unwrap()
+            })
+            .is_match(line)
+    }
+
+    /// Progress dots or a summary: the tests are running again
+    fn is_test_output(line: &str) -> bool {
+        let trimmed = line.trim();
+        (!trimmed.is_empty()
+            && trimmed
+                .chars()
+                .all(|c| matches!(c, '.' | 'F' | 'E' | '*' | 'S')))
+            || trimmed.starts_with("Finished in")
+            || (trimmed.contains(" example") && trimmed.contains("failure"))
+            || (trimmed.contains("runs,") && trimmed.contains("assertions,"))
+    }
+
+    /// Note a debugger prompt for the header, returning whether the line was one
+    fn detect_debugger(&self, line: &str) -> bool {
         let mut active = self.debugger_active.lock().unwrap();
         let mut info = self.debugger_info.lock().unwrap();
 
@@ -358,7 +679,10 @@ impl TestTracker {
                 variables: HashMap::new(),
                 timestamp: Instant::now(),
             });
+        } else {
+            return false;
         }
+        true
     }
 
     fn extract_file_path(line: &str) -> Option<String> {
@@ -385,16 +709,131 @@ impl TestTracker {
         None
     }
 
+    /// Item counts and approximate sizes of the tracker's stores
+    pub fn store_usage(&self) -> Vec<StoreUsage> {
+        // Stats before runs, the order a finished run is recorded in
+        let stats = self.stats.read();
+        let runs = self.recent_runs.read();
+        vec![
+            StoreUsage::new(
+                StoreKind::TestRuns,
+                runs.len(),
+                runs.iter().map(TestRun::approx_bytes).sum(),
+            )
+            .with_limit(self.max_test_runs.load(Ordering::Relaxed)),
+            StoreUsage::new(
+                StoreKind::SlowestTests,
+                stats.slowest_tests.len(),
+                stats
+                    .slowest_tests
+                    .iter()
+                    .map(TestResult::approx_bytes)
+                    .sum(),
+            )
+            .with_limit(self.max_slowest_tests.load(Ordering::Relaxed)),
+        ]
+    }
+
+    pub fn approx_bytes(&self) -> usize {
+        let current = self
+            .current_run
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, TestRun::approx_bytes);
+        total_bytes(&self.store_usage()) + current
+    }
+
+    /// Shrink a store to `keep` entries, returning how many were dropped
+    ///
+    /// Test runs keep the newest; the slowest-tests list keeps the slowest.
+    pub fn trim(&self, kind: StoreKind, keep: usize) -> usize {
+        match kind {
+            StoreKind::TestRuns => keep_last(&mut self.recent_runs.write(), keep),
+            StoreKind::SlowestTests => {
+                let mut stats = self.stats.write();
+                let removed = stats.slowest_tests.len().saturating_sub(keep);
+                stats.slowest_tests.truncate(keep);
+                removed
+            }
+            _ => 0,
+        }
+    }
+
     pub fn get_current_run(&self) -> Option<TestRun> {
         self.current_run.lock().unwrap().clone()
     }
 
-    pub fn get_recent_runs(&self) -> Vec<TestRun> {
-        self.recent_runs.lock().unwrap().clone()
+    /// The run in progress, or else the last one to finish
+    pub fn latest_run(&self) -> Option<TestRun> {
+        self.get_current_run()
+            .or_else(|| self.recent_runs.read().last().cloned())
+    }
+
+    /// Finished runs, oldest first; shared until the next run finishes
+    pub fn get_recent_runs(&self) -> Arc<Vec<TestRun>> {
+        self.recent_runs.snapshot()
+    }
+
+    /// Results whose test name contains `query`, newest run first
+    ///
+    /// Includes the run in progress, so matches show up while tests execute.
+    pub fn get_test_results_matching(&self, query: &SearchQuery) -> Vec<TestResult> {
+        let current = self.current_run.lock().unwrap();
+        let recent = self.recent_runs.read();
+
+        current
+            .iter()
+            .chain(recent.iter().rev())
+            .flat_map(|run| run.test_results.iter().rev())
+            .filter(|result| query.matches(&result.test_name))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_stats(&self) -> Arc<TestStats> {
+        self.stats.snapshot()
+    }
+
+    /// Whether a test run has started and not finished
+    pub fn is_running(&self) -> bool {
+        self.current_run.lock().unwrap().is_some()
+    }
+
+    /// No test run completed or under way
+    pub fn is_empty(&self) -> bool {
+        self.total_runs() == 0 && !self.is_running()
+    }
+
+    /// Test runs completed, without cloning the rest of the stats
+    pub fn total_runs(&self) -> usize {
+        self.stats.read().total_runs
+    }
+
+    pub fn snapshot(&self) -> TestSnapshot {
+        TestSnapshot {
+            framework: self.framework.lock().unwrap().clone(),
+            current_run: self.get_current_run(),
+            recent_runs: self.recent_runs.read().clone(),
+            stats: self.stats.read().clone(),
+        }
+    }
+
+    /// Replace runs and totals with a saved session's
+    pub fn restore(&self, snapshot: TestSnapshot) {
+        *self.framework.lock().unwrap() = snapshot.framework;
+        *self.current_run.lock().unwrap() = snapshot.current_run;
+        *self.stats.write() = snapshot.stats;
+        *self.recent_runs.write() = snapshot.recent_runs;
     }
 
-    pub fn get_stats(&self) -> TestStats {
-        self.stats.lock().unwrap().clone()
+    /// Where and since when the current run has been stopped at a breakpoint
+    pub fn debugger_pause(&self) -> Option<DebuggerPause> {
+        self.current_run
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|run| run.paused.clone())
     }
 
     pub fn is_debugger_active(&self) -> bool {
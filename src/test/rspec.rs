@@ -0,0 +1,299 @@
+//! RSpec's progress and documentation formatter output, a line at a time
+//!
+//! The progress formatter's `..F.*` lines count examples as they run; the
+//! documentation formatter names the ones marked `(FAILED - 1)` or
+//! `(PENDING: reason)`. Passing examples of the documentation formatter
+//! can't be told apart from group names, or from other processes' output,
+//! so they're only counted by the summary line, which has the final say.
+//!
+//! The `Pending:` and `Failures:` lists that follow give each numbered
+//! example its full description, message and backtrace:
+//!
+//! ```text
+//!   1) User#full_name joins first and last name
+//!      Failure/Error: expect(user.full_name).to eq("Ada Lovelace")
+//!
+//!        expected: "Ada Lovelace"
+//!             got: "Ada"
+//!
+//!        (compared using ==)
+//!      # ./spec/models/user_spec.rb:12:in `block (3 levels) in <top (required)>'
+//! ```
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use regex::Regex;
+
+use super::{TestResult, TestStatus};
+
+/// What a line of RSpec output adds to the run
+#[derive(Debug, Clone)]
+pub(crate) enum RSpecEvent {
+    /// A progress formatter line, one status per character
+    Progress(Vec<TestStatus>),
+    /// A documentation formatter example marked failed or pending
+    Example(TestResult),
+    /// Entry `number` of the `Failures:` or `Pending:` list, numbered among
+    /// the examples with its status
+    Detail { number: usize, example: TestResult },
+    /// `5 examples, 1 failure, 1 pending`, with the `Finished in` time before it
+    Summary {
+        examples: usize,
+        failures: usize,
+        pending: usize,
+        duration: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Section {
+    #[default]
+    Examples,
+    Pending,
+    Failures,
+}
+
+/// A `Failures:` or `Pending:` entry read so far
+#[derive(Debug)]
+struct Block {
+    number: usize,
+    status: TestStatus,
+    description: String,
+    /// Indentation of the `Failure/Error:` line, taken off the message lines
+    indent: Option<usize>,
+    message: Vec<String>,
+    backtrace: Vec<String>,
+}
+
+impl Block {
+    fn finish(self) -> RSpecEvent {
+        let mut message = self.message;
+        while message.last().is_some_and(|line| line.is_empty()) {
+            message.pop();
+        }
+        let (file_path, line_number) = test_location(&self.backtrace).unzip();
+        RSpecEvent::Detail {
+            number: self.number,
+            example: TestResult {
+                test_name: self.description,
+                file_path,
+                line_number: line_number.flatten(),
+                status: self.status,
+                duration: None,
+                failure_message: (!message.is_empty()).then(|| message.join("\n")),
+                backtrace: (!self.backtrace.is_empty()).then_some(self.backtrace),
+                timestamp: Instant::now(),
+            },
+        }
+    }
+}
+
+/// Where the failing example is: the first backtrace frame in a spec file,
+/// otherwise the first frame
+fn test_location(backtrace: &[String]) -> Option<(String, Option<usize>)> {
+    let frames = || backtrace.iter().filter_map(|frame| split_location(frame));
+    frames()
+        .find(|(file, _)| file.ends_with("_spec.rb"))
+        .or_else(|| frames().next())
+}
+
+/// `./spec/models/user_spec.rb:12:in 'block'` into the file and line
+fn split_location(frame: &str) -> Option<(String, Option<usize>)> {
+    let caps = location_pattern().captures(frame)?;
+    Some((caps[1].to_string(), caps[2].parse().ok()))
+}
+
+fn location_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^(\S+?\.rb):(\d+)(?::in .*)?$").unwrap())
+}
+
+/// `10 examples, 2 failures, 1 pending`, optionally followed by
+/// `, 1 error occurred outside of examples`
+fn summary_counts(line: &str) -> Option<(usize, usize, usize)> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"^(\d+) examples?, (\d+) failures?(?:, (\d+) pending)?(?:,|\s*$)").unwrap()
+    });
+    let caps = pattern.captures(line.trim())?;
+    let count = |idx: usize| caps.get(idx).map_or(Some(0), |n| n.as_str().parse().ok());
+    Some((count(1)?, count(2)?, count(3)?))
+}
+
+/// Whether a line is output only RSpec prints, to tell which framework is
+/// running when nothing names it
+pub(crate) fn is_rspec_output(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with("Randomized with seed")
+        || trimmed.starts_with("Failure/Error:")
+        || summary_counts(trimmed).is_some()
+}
+
+/// Follows RSpec output across lines
+#[derive(Debug, Default)]
+pub(crate) struct RSpecParser {
+    section: Section,
+    block: Option<Block>,
+    /// From `Finished in`, for the summary on the next line
+    finished_ms: Option<f64>,
+}
+
+impl RSpecParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What `line` adds, if anything; a list entry is only complete once the
+    /// line after its last is read
+    pub fn parse_line(&mut self, line: &str) -> Vec<RSpecEvent> {
+        let mut events = Vec::new();
+        let trimmed = line.trim();
+
+        if let Some((examples, failures, pending)) = summary_counts(trimmed) {
+            events.extend(self.finish_block());
+            events.push(RSpecEvent::Summary {
+                examples,
+                failures,
+                pending,
+                duration: self.finished_ms.take(),
+            });
+            self.section = Section::Examples;
+            return events;
+        }
+        if let Some(duration) = finished_duration(trimmed) {
+            events.extend(self.finish_block());
+            self.finished_ms = Some(duration);
+            self.section = Section::Examples;
+            return events;
+        }
+        if line == "Failures:" || line.starts_with("Pending: (") || line == "Pending:" {
+            events.extend(self.finish_block());
+            self.section = if line == "Failures:" {
+                Section::Failures
+            } else {
+                Section::Pending
+            };
+            return events;
+        }
+
+        match self.section {
+            Section::Examples => events.extend(Self::example_line(line)),
+            Section::Pending | Section::Failures => {
+                if let Some((number, description)) = numbered_entry(line) {
+                    events.extend(self.finish_block());
+                    self.block = Some(Block {
+                        number,
+                        status: if self.section == Section::Failures {
+                            TestStatus::Failed
+                        } else {
+                            TestStatus::Pending
+                        },
+                        description,
+                        indent: None,
+                        message: Vec::new(),
+                        backtrace: Vec::new(),
+                    });
+                } else if let Some(block) = self.block.as_mut() {
+                    Self::block_line(block, line);
+                }
+            }
+        }
+        events
+    }
+
+    fn finish_block(&mut self) -> Option<RSpecEvent> {
+        self.block.take().map(Block::finish)
+    }
+
+    /// A progress line, or a documentation formatter example that failed or
+    /// is pending
+    fn example_line(line: &str) -> Option<RSpecEvent> {
+        static MARKED: OnceLock<Regex> = OnceLock::new();
+        let marked = MARKED
+            .get_or_init(|| Regex::new(r"^\s+(.+?) \((?:FAILED - \d+|PENDING: (.*))\)$").unwrap());
+
+        if !line.is_empty() && line.chars().all(|c| matches!(c, '.' | 'F' | '*')) {
+            let statuses = line
+                .chars()
+                .map(|c| match c {
+                    'F' => TestStatus::Failed,
+                    '*' => TestStatus::Pending,
+                    _ => TestStatus::Passed,
+                })
+                .collect();
+            return Some(RSpecEvent::Progress(statuses));
+        }
+
+        let caps = marked.captures(line)?;
+        let reason = caps.get(2).map(|reason| reason.as_str().to_string());
+        Some(RSpecEvent::Example(TestResult {
+            test_name: caps[1].to_string(),
+            file_path: None,
+            line_number: None,
+            status: if reason.is_some() {
+                TestStatus::Pending
+            } else {
+                TestStatus::Failed
+            },
+            duration: None,
+            failure_message: reason,
+            backtrace: None,
+            timestamp: Instant::now(),
+        }))
+    }
+
+    /// A line inside a list entry: the `Failure/Error:` line and the message
+    /// under it, a `# reason` for pending examples, or a backtrace frame
+    fn block_line(block: &mut Block, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if !block.message.is_empty() && block.message.last().is_some_and(|l| !l.is_empty()) {
+                block.message.push(String::new());
+            }
+            return;
+        }
+        let indent = line.len() - line.trim_start().len();
+        // Entries are indented; anything at the margin came from elsewhere
+        if indent == 0 {
+            return;
+        }
+
+        if let Some(comment) = trimmed.strip_prefix("# ") {
+            if location_pattern().is_match(comment) {
+                block.backtrace.push(comment.to_string());
+            } else if block.backtrace.is_empty() {
+                block.message.push(comment.to_string());
+            }
+            return;
+        }
+        let base = *block.indent.get_or_insert(indent);
+        let text = if indent >= base {
+            &line[base..]
+        } else {
+            trimmed
+        };
+        block.message.push(text.trim_end().to_string());
+    }
+}
+
+/// `1) User validates email` opening a list entry
+fn numbered_entry(line: &str) -> Option<(usize, String)> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| Regex::new(r"^\s{1,4}(\d+)\) (.+)$").unwrap());
+    let caps = pattern.captures(line)?;
+    Some((caps[1].parse().ok()?, caps[2].trim_end().to_string()))
+}
+
+/// `Finished in 0.31245 seconds (files took 1.82 seconds to load)` or
+/// `Finished in 1 minute 2.5 seconds`, in milliseconds
+fn finished_duration(line: &str) -> Option<f64> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"^Finished in (?:(\d+) minutes? )?(\d+(?:\.\d+)?) seconds?").unwrap()
+    });
+    let caps = pattern.captures(line)?;
+    let minutes: f64 = caps.get(1).map_or(Ok(0.0), |m| m.as_str().parse()).ok()?;
+    let seconds: f64 = caps[2].parse().ok()?;
+    Some((minutes * 60.0 + seconds) * 1000.0)
+}
@@ -0,0 +1,132 @@
+//! Wall-clock times in the zone picked by `[ui] timezone`
+//!
+//! `local` (the default), `utc`, or an IANA name such as `Europe/Berlin`.
+//! Log lines keep their local `DateTime`; times are converted only when
+//! shown or written out, and times typed in (`/around 14:32`) are read in
+//! the same zone they're displayed in.
+
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// `14:32:05`
+pub const CLOCK_FORMAT: &str = "%H:%M:%S";
+
+/// `2025-03-09 14:32:05`
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// `20250309_143205`, for generated file names
+pub const FILE_STAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// Zone times are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayZone {
+    /// The machine's zone
+    #[default]
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// `local`, `utc` or an IANA zone name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            Ok(Self::Local)
+        } else if name.eq_ignore_ascii_case("utc") {
+            Ok(Self::Utc)
+        } else {
+            name.parse::<Tz>().map(Self::Named).map_err(|_| {
+                format!(
+                    "Unknown time zone '{}' (use local, utc or an IANA name like Europe/Berlin)",
+                    name
+                )
+            })
+        }
+    }
+
+    /// The zone `[ui] timezone` names, or local time with a warning when
+    /// it names none
+    pub fn from_config(name: Option<&str>) -> (Self, Option<String>) {
+        match name.map(Self::parse) {
+            None => (Self::Local, None),
+            Some(Ok(zone)) => (zone, None),
+            Some(Err(e)) => (
+                Self::Local,
+                Some(format!("[ui] timezone: {} - showing local time", e)),
+            ),
+        }
+    }
+
+    /// `time` in this zone, formatted with a chrono format string
+    pub fn format(&self, time: DateTime<Local>, format: &str) -> String {
+        match self {
+            Self::Local => time.format(format).to_string(),
+            Self::Utc => time.with_timezone(&Utc).format(format).to_string(),
+            Self::Named(tz) => time.with_timezone(tz).format(format).to_string(),
+        }
+    }
+
+    /// `14:32:05`
+    pub fn clock(&self, time: DateTime<Local>) -> String {
+        self.format(time, CLOCK_FORMAT)
+    }
+
+    /// `2025-03-09 14:32:05`, followed by the zone's abbreviation unless
+    /// it's local time (`2025-03-09 13:32:05 UTC`)
+    pub fn date_time(&self, time: DateTime<Local>) -> String {
+        match self {
+            Self::Local => self.format(time, DATE_TIME_FORMAT),
+            _ => self.format(time, &format!("{} %Z", DATE_TIME_FORMAT)),
+        }
+    }
+
+    /// `20250309_143205`
+    pub fn file_stamp(&self, time: DateTime<Local>) -> String {
+        self.format(time, FILE_STAMP_FORMAT)
+    }
+
+    /// `time` with this zone's offset, for RFC 3339 stamps in exports
+    pub fn fixed(&self, time: DateTime<Local>) -> DateTime<FixedOffset> {
+        match self {
+            Self::Local => time.fixed_offset(),
+            Self::Utc => time.with_timezone(&Utc).fixed_offset(),
+            Self::Named(tz) => time.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    /// The calendar date `time` falls on in this zone
+    pub fn date(&self, time: DateTime<Local>) -> NaiveDate {
+        match self {
+            Self::Local => time.date_naive(),
+            Self::Utc => time.with_timezone(&Utc).date_naive(),
+            Self::Named(tz) => time.with_timezone(tz).date_naive(),
+        }
+    }
+
+    /// The moment a wall-clock time in this zone refers to; the earlier one
+    /// when clocks went back, `None` when they skipped over it
+    pub fn resolve(&self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+        let naive = date.and_time(time);
+        match self {
+            Self::Local => naive.and_local_timezone(Local).earliest(),
+            Self::Utc => Some(naive.and_utc().with_timezone(&Local)),
+            Self::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|time| time.with_timezone(&Local)),
+        }
+    }
+}
+
+impl fmt::Display for DisplayZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Utc => write!(f, "UTC"),
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Wall-clock windows over the log buffer, for `/around 14:32 ±30s`
+//!
+//! The log buffer is kept in time order, so the lines inside a window are
+//! found by binary search rather than a scan.
+
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+
+use crate::process::LogLine;
+use crate::time_format::DisplayZone;
+
+/// Window on each side of the requested time when none is given
+pub const DEFAULT_WINDOW: TimeDelta = TimeDelta::seconds(30);
+
+/// Longest accepted window or relative offset
+const MAX_SPAN: TimeDelta = TimeDelta::days(1);
+
+/// A time window the Logs view is constrained to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    /// The time asked for; the view jumps to the first line at or after it
+    pub center: DateTime<Local>,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+impl TimeRange {
+    pub fn around(center: DateTime<Local>, window: TimeDelta) -> Self {
+        Self {
+            center,
+            start: center - window,
+            end: center + window,
+        }
+    }
+
+    /// The window from `start` up to `end`, shown from its start
+    pub fn between(start: DateTime<Local>, end: DateTime<Local>) -> Self {
+        Self {
+            center: start,
+            start,
+            end,
+        }
+    }
+
+    /// Parse `/around` arguments: a time (read in `zone`) and an optional `±30s` window
+    pub fn parse(args: &[String], now: DateTime<Local>, zone: DisplayZone) -> Result<Self, String> {
+        let (time, window) = match args {
+            [time] => (time, DEFAULT_WINDOW),
+            [time, window] => (time, parse_window(window)?),
+            _ => return Err("Usage: /around <HH:MM[:SS] | -5m> [±30s]".to_string()),
+        };
+        Ok(Self::around(parse_time_spec(time, now, zone)?, window))
+    }
+
+    pub fn contains(&self, time: DateTime<Local>) -> bool {
+        self.start <= time && time <= self.end
+    }
+
+    /// The lines of a time-ordered buffer that fall inside the window
+    pub fn slice<'a>(&self, logs: &'a [LogLine]) -> &'a [LogLine] {
+        let from = first_at_or_after(logs, self.start);
+        let to = logs.partition_point(|log| log.time <= self.end);
+        &logs[from..to.max(from)]
+    }
+
+    /// "14:31:30–14:32:30", in `zone`
+    pub fn label(&self, zone: DisplayZone) -> String {
+        format!("{}–{}", zone.clock(self.start), zone.clock(self.end))
+    }
+
+    /// "Showing logs 14:31:30–14:32:30", noting when `logs` no longer reach back that far
+    pub fn showing_message(&self, logs: &[LogLine], zone: DisplayZone) -> String {
+        let message = format!("Showing logs {}", self.label(zone));
+        match logs.first() {
+            Some(oldest) if oldest.time > self.end => format!(
+                "{} - nothing retained, oldest line is from {}",
+                message,
+                zone.clock(oldest.time)
+            ),
+            Some(oldest) if oldest.time > self.start => {
+                format!("{} - buffer starts at {}", message, zone.clock(oldest.time))
+            }
+            _ => message,
+        }
+    }
+}
+
+/// Index of the first line at or after `time` in a time-ordered buffer
+pub fn first_at_or_after(logs: &[LogLine], time: DateTime<Local>) -> usize {
+    logs.partition_point(|log| log.time < time)
+}
+
+/// `HH:MM` / `HH:MM:SS` in `zone` (the most recent such time, today or
+/// yesterday) or `-5m`
+pub fn parse_time_spec(
+    spec: &str,
+    now: DateTime<Local>,
+    zone: DisplayZone,
+) -> Result<DateTime<Local>, String> {
+    if let Some(ago) = spec.strip_prefix('-') {
+        return Ok(now - parse_span(ago)?);
+    }
+
+    let time = NaiveTime::parse_from_str(spec, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(spec, "%H:%M"))
+        .map_err(|_| format!("Invalid time '{}' (use HH:MM, HH:MM:SS or -5m)", spec))?;
+
+    let today = zone
+        .resolve(zone.date(now), time)
+        .ok_or_else(|| format!("{} does not exist today", spec))?;
+    if today > now {
+        Ok(today - TimeDelta::days(1))
+    } else {
+        Ok(today)
+    }
+}
+
+/// `±30s`, `+-2m`, or a bare `30s`
+pub fn parse_window(spec: &str) -> Result<TimeDelta, String> {
+    let span = spec
+        .strip_prefix('±')
+        .or_else(|| spec.strip_prefix("+-"))
+        .unwrap_or(spec);
+    parse_span(span)
+}
+
+/// `30s`, `5m`, `1h`; a bare number is seconds
+pub fn parse_span(spec: &str) -> Result<TimeDelta, String> {
+    let invalid = || format!("Invalid duration '{}' (use e.g. 30s, 5m, 1h)", spec);
+    let (number, unit) = spec.split_at(
+        spec.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len()),
+    );
+    let amount: i64 = number.parse().map_err(|_| invalid())?;
+
+    let span = match unit {
+        "" | "s" => TimeDelta::seconds(amount),
+        "m" => TimeDelta::minutes(amount),
+        "h" => TimeDelta::hours(amount),
+        _ => return Err(invalid()),
+    };
+    if span > MAX_SPAN {
+        return Err(format!("Duration '{}' is longer than a day", spec));
+    }
+    Ok(span)
+}
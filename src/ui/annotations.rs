@@ -0,0 +1,181 @@
+//! Inline annotations (`/annotations`): what Caboose knows about a log line,
+//! shown muted at its right edge
+//!
+//! A completed request's line gets its duration and query count, an SQL line
+//! how often its statement ran in the same request, and an exception's first
+//! line how often its group has been seen. Only which request, query or group
+//! a line belongs to is remembered as it arrives; the text is looked up in
+//! the trackers at render time, so counts keep up with later lines. A line
+//! whose request or group has since been dropped simply has no annotation.
+
+use std::collections::BTreeMap;
+
+use crate::context::RequestContextTracker;
+use crate::exception::ExceptionTracker;
+
+/// What a log line was recognized as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineRef {
+    /// The line completing request `id`
+    Request(u64),
+    /// The `index`-th query of request `request`
+    Query { request: u64, index: usize },
+    /// The first line of an exception in the group keyed by this fingerprint
+    Exception(String),
+}
+
+/// Recognized log lines by sequence number, and whether to annotate them
+#[derive(Debug)]
+pub struct LogAnnotations {
+    enabled: bool,
+    refs: BTreeMap<u64, LineRef>,
+}
+
+impl Default for LogAnnotations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogAnnotations {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            refs: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turning annotations off only hides them; lines keep being recorded
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn record(&mut self, seq: u64, line: LineRef) {
+        self.refs.insert(seq, line);
+    }
+
+    /// Forget lines older than `first_seq`, the oldest line still buffered
+    pub fn evict_before(&mut self, first_seq: Option<u64>) {
+        match first_seq {
+            Some(first) => self.refs = self.refs.split_off(&first),
+            None => self.refs.clear(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.refs.clear();
+    }
+
+    /// Looks annotations up for rendering, or `None` while they're off
+    pub fn annotator<'a>(
+        &'a self,
+        requests: &'a RequestContextTracker,
+        exceptions: &'a ExceptionTracker,
+    ) -> Option<Annotator<'a>> {
+        self.enabled.then_some(Annotator {
+            refs: &self.refs,
+            requests,
+            exceptions,
+        })
+    }
+}
+
+/// Annotation text for log lines, from the trackers' current state
+pub struct Annotator<'a> {
+    refs: &'a BTreeMap<u64, LineRef>,
+    requests: &'a RequestContextTracker,
+    exceptions: &'a ExceptionTracker,
+}
+
+impl Annotator<'_> {
+    /// `● 245ms 3 queries`, `×7 N+1`, `×12 seen`; nothing for lines that
+    /// weren't recognized, or whose request or group is gone
+    ///
+    /// A query that ran once in its request isn't worth a note.
+    pub fn annotation(&self, seq: u64) -> Option<String> {
+        match self.refs.get(&seq)? {
+            LineRef::Request(id) => {
+                let (duration, queries) = self.requests.completed_summary(*id)?;
+                let queries = format!("{} quer{}", queries, if queries == 1 { "y" } else { "ies" });
+                Some(match duration {
+                    Some(duration) => format!("● {:.0}ms {}", duration, queries),
+                    None => format!("● {}", queries),
+                })
+            }
+            LineRef::Query { request, index } => {
+                let (count, n_plus_one) = self.requests.query_repeats(*request, *index)?;
+                match (count, n_plus_one) {
+                    (count, true) => Some(format!("×{} N+1", count)),
+                    (1, false) => None,
+                    (count, false) => Some(format!("×{}", count)),
+                }
+            }
+            LineRef::Exception(fingerprint) => {
+                let count = self.exceptions.group_count(fingerprint)?;
+                Some(format!("×{} seen", count))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::RailsLogParser;
+
+    fn feed(tracker: &RequestContextTracker, lines: &[&str]) {
+        for line in lines {
+            if let Some(event) = RailsLogParser::parse_line(line) {
+                tracker.process_log_event(&event);
+            }
+        }
+    }
+
+    #[test]
+    fn test_annotations_follow_the_trackers() {
+        let requests = RequestContextTracker::new();
+        let exceptions = ExceptionTracker::new();
+        let mut annotations = LogAnnotations::new();
+
+        feed(
+            &requests,
+            &[
+                r#"Started GET "/posts" for 127.0.0.1 at 2024-01-01 10:00:00 +0000"#,
+                r#"  Post Load (0.4ms)  SELECT "posts".* FROM "posts""#,
+            ],
+        );
+        let (request, index) = requests.last_query_position().unwrap();
+        annotations.record(1, LineRef::Query { request, index });
+        annotations.record(2, LineRef::Exception("gone".into()));
+
+        let annotator = annotations.annotator(&requests, &exceptions).unwrap();
+        // Ran once so far, and the group isn't tracked
+        assert_eq!(annotator.annotation(1), None);
+        assert_eq!(annotator.annotation(2), None);
+        assert_eq!(annotator.annotation(3), None);
+
+        feed(
+            &requests,
+            &[
+                r#"  Post Load (0.3ms)  SELECT "posts".* FROM "posts""#,
+                "Completed 200 OK in 52ms (Views: 10.0ms | ActiveRecord: 0.7ms)",
+            ],
+        );
+        annotations.record(3, LineRef::Request(requests.last_completed_id().unwrap()));
+        let annotator = annotations.annotator(&requests, &exceptions).unwrap();
+        assert_eq!(annotator.annotation(1).as_deref(), Some("×2"));
+        assert_eq!(annotator.annotation(3).as_deref(), Some("● 52ms 2 queries"));
+
+        annotations.evict_before(Some(3));
+        let annotator = annotations.annotator(&requests, &exceptions).unwrap();
+        assert_eq!(annotator.annotation(1), None);
+        assert!(annotator.annotation(3).is_some());
+
+        annotations.set_enabled(false);
+        assert!(annotations.annotator(&requests, &exceptions).is_none());
+    }
+}
@@ -0,0 +1,303 @@
+//! Auto focus (`/autofocus on`): switch to the view of the latest notable event
+//!
+//! Meant for demos, where Caboose should drive itself. Events rank
+//! unhandled exception > process crash > test run started or finished; the
+//! highest ranked new event picks the view. A view is kept for at least
+//! [`DWELL`] unless something more important happens, and Logs comes back
+//! once nothing happened for [`QUIET`]. Any key the user presses suspends
+//! switching until they've been idle for the resume period.
+//!
+//! [`decide`] holds the policy as a pure function of the recent events.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Shortest time a view picked by auto focus is kept
+pub const DWELL: Duration = Duration::from_secs(5);
+
+/// Without events for this long, auto focus returns to Logs
+pub const QUIET: Duration = Duration::from_secs(30);
+
+/// Idle time after a key press before auto focus switches again
+pub const DEFAULT_RESUME: Duration = Duration::from_secs(30);
+
+/// Events kept for [`decide`]; older ones can't outrank a newer switch anyway
+const RECENT_EVENTS: usize = 16;
+
+/// Something worth switching views for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusEvent {
+    /// An unhandled (high or critical severity) exception
+    Exception,
+    /// A process crashed
+    Crash(String),
+    TestRunStarted,
+    TestRunFinished,
+}
+
+impl FocusEvent {
+    fn priority(&self) -> u8 {
+        match self {
+            FocusEvent::Exception => 3,
+            FocusEvent::Crash(_) => 2,
+            FocusEvent::TestRunStarted | FocusEvent::TestRunFinished => 1,
+        }
+    }
+
+    fn target(&self) -> FocusTarget {
+        match self {
+            FocusEvent::Exception => FocusTarget::Exceptions,
+            FocusEvent::Crash(process) => FocusTarget::ProcessLogs(process.clone()),
+            FocusEvent::TestRunStarted | FocusEvent::TestRunFinished => FocusTarget::TestResults,
+        }
+    }
+}
+
+/// A view auto focus switches to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusTarget {
+    Exceptions,
+    /// Logs filtered to the crashed process
+    ProcessLogs(String),
+    TestResults,
+    /// Back to the unfiltered Logs after a quiet spell
+    Logs,
+}
+
+/// The view auto focus last switched to, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Focus {
+    pub target: FocusTarget,
+    priority: u8,
+    pub since: Instant,
+}
+
+/// Where to switch now, if anywhere
+///
+/// `recent` is oldest first. Only events after `current` was picked can
+/// switch away from it, and within [`DWELL`] only more important ones. With
+/// nothing new for [`QUIET`], a picked view gives way to Logs. Nothing
+/// switches while `suspended_until` is ahead of `now`.
+pub fn decide(
+    recent: &[(Instant, FocusEvent)],
+    current: Option<&Focus>,
+    suspended_until: Option<Instant>,
+    now: Instant,
+) -> Option<FocusTarget> {
+    if suspended_until.is_some_and(|until| now < until) {
+        return None;
+    }
+
+    let candidate = recent
+        .iter()
+        .filter(|(at, _)| current.is_none_or(|focus| *at > focus.since))
+        // max_by_key keeps the last of equals: the newest event of a rank
+        .max_by_key(|(_, event)| event.priority());
+
+    match (candidate, current) {
+        (Some((_, event)), None) => Some(event.target()),
+        (Some((_, event)), Some(focus)) => {
+            let dwelling = now.duration_since(focus.since) < DWELL;
+            if event.target() == focus.target || (dwelling && event.priority() <= focus.priority) {
+                None
+            } else {
+                Some(event.target())
+            }
+        }
+        (None, Some(focus)) => {
+            let last_event = recent.last().map_or(focus.since, |(at, _)| *at);
+            let quiet = now.duration_since(last_event.max(focus.since)) >= QUIET;
+            (focus.target != FocusTarget::Logs && quiet).then_some(FocusTarget::Logs)
+        }
+        (None, None) => None,
+    }
+}
+
+/// Auto focus state kept by the app
+#[derive(Debug, Clone)]
+pub struct AutoFocus {
+    enabled: bool,
+    resume_after: Duration,
+    recent: VecDeque<(Instant, FocusEvent)>,
+    current: Option<Focus>,
+    suspended_until: Option<Instant>,
+}
+
+impl Default for AutoFocus {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESUME)
+    }
+}
+
+impl AutoFocus {
+    pub fn new(resume_after: Duration) -> Self {
+        Self {
+            enabled: false,
+            resume_after,
+            recent: VecDeque::new(),
+            current: None,
+            suspended_until: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn auto focus on or off; turning it on starts from what's on screen
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.recent.clear();
+        self.current = None;
+        self.suspended_until = None;
+    }
+
+    /// Whether a key press is holding off switching as of `now`
+    pub fn is_suspended(&self, now: Instant) -> bool {
+        self.suspended_until.is_some_and(|until| now < until)
+    }
+
+    /// Note an event; ignored while auto focus is off
+    pub fn observe(&mut self, event: FocusEvent, now: Instant) {
+        if !self.enabled {
+            return;
+        }
+        if self.recent.len() == RECENT_EVENTS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((now, event));
+    }
+
+    /// The user pressed a key: stop switching until they've been idle a while,
+    /// and leave the view they pick alone
+    pub fn user_input(&mut self, now: Instant) {
+        if !self.enabled {
+            return;
+        }
+        self.suspended_until = Some(now + self.resume_after);
+        self.current = None;
+        self.recent.clear();
+    }
+
+    /// The view to switch to now, if any; the caller is expected to switch
+    pub fn poll(&mut self, now: Instant) -> Option<FocusTarget> {
+        if !self.enabled {
+            return None;
+        }
+        let recent: Vec<(Instant, FocusEvent)> = self.recent.iter().cloned().collect();
+        let target = decide(&recent, self.current.as_ref(), self.suspended_until, now)?;
+        let priority = recent
+            .iter()
+            .rev()
+            .find(|(_, event)| event.target() == target)
+            .map_or(0, |(_, event)| event.priority());
+        self.current = Some(Focus {
+            target: target.clone(),
+            priority,
+            since: now,
+        });
+        if target == FocusTarget::Logs {
+            self.recent.clear();
+        }
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focus(target: FocusTarget, priority: u8, since: Instant) -> Focus {
+        Focus {
+            target,
+            priority,
+            since,
+        }
+    }
+
+    #[test]
+    fn test_highest_priority_event_wins() {
+        let t0 = Instant::now();
+        let recent = [
+            (t0, FocusEvent::TestRunStarted),
+            (t0 + Duration::from_millis(10), FocusEvent::Exception),
+            (
+                t0 + Duration::from_millis(20),
+                FocusEvent::Crash("web".into()),
+            ),
+        ];
+
+        assert_eq!(
+            decide(&recent, None, None, t0 + Duration::from_secs(1)),
+            Some(FocusTarget::Exceptions)
+        );
+    }
+
+    #[test]
+    fn test_dwell_holds_the_view_against_equal_or_lower_events() {
+        let t0 = Instant::now();
+        let current = focus(FocusTarget::ProcessLogs("web".into()), 2, t0);
+        let test_run = [(t0 + Duration::from_secs(1), FocusEvent::TestRunFinished)];
+
+        let within = t0 + Duration::from_secs(2);
+        assert_eq!(decide(&test_run, Some(&current), None, within), None);
+
+        let after = t0 + DWELL + Duration::from_secs(1);
+        assert_eq!(
+            decide(&test_run, Some(&current), None, after),
+            Some(FocusTarget::TestResults)
+        );
+
+        // An exception outranks a crash even while dwelling
+        let exception = [(t0 + Duration::from_secs(1), FocusEvent::Exception)];
+        assert_eq!(
+            decide(&exception, Some(&current), None, within),
+            Some(FocusTarget::Exceptions)
+        );
+    }
+
+    #[test]
+    fn test_quiet_returns_to_logs() {
+        let t0 = Instant::now();
+        let current = focus(FocusTarget::TestResults, 1, t0);
+        let recent = [(t0 - Duration::from_secs(1), FocusEvent::TestRunStarted)];
+
+        assert_eq!(decide(&recent, Some(&current), None, t0 + QUIET / 2), None);
+        assert_eq!(
+            decide(&recent, Some(&current), None, t0 + QUIET),
+            Some(FocusTarget::Logs)
+        );
+
+        let logs = focus(FocusTarget::Logs, 0, t0);
+        assert_eq!(decide(&[], Some(&logs), None, t0 + QUIET * 2), None);
+    }
+
+    #[test]
+    fn test_suspension_blocks_switching_until_idle() {
+        let t0 = Instant::now();
+        let mut autofocus = AutoFocus::new(Duration::from_secs(10));
+        autofocus.set_enabled(true);
+
+        autofocus.user_input(t0);
+        autofocus.observe(FocusEvent::Exception, t0 + Duration::from_secs(1));
+        assert!(autofocus.is_suspended(t0 + Duration::from_secs(2)));
+        assert_eq!(autofocus.poll(t0 + Duration::from_secs(2)), None);
+
+        assert_eq!(
+            autofocus.poll(t0 + Duration::from_secs(10)),
+            Some(FocusTarget::Exceptions)
+        );
+        assert_eq!(autofocus.poll(t0 + Duration::from_secs(11)), None);
+    }
+
+    #[test]
+    fn test_off_ignores_events() {
+        let t0 = Instant::now();
+        let mut autofocus = AutoFocus::default();
+        autofocus.observe(FocusEvent::Exception, t0);
+        assert_eq!(autofocus.poll(t0), None);
+
+        autofocus.set_enabled(true);
+        assert_eq!(autofocus.poll(t0), None);
+    }
+}
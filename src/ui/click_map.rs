@@ -0,0 +1,241 @@
+//! Where the clickable parts of the screen were drawn in the last frame
+//!
+//! Rendering records the tab titles and selectable rows here as it lays them
+//! out, and a mouse click is looked up against what was actually on screen
+//! rather than by repeating the layout.
+
+use std::time::{Duration, Instant};
+
+use ratatui::layout::Rect;
+use ratatui::text::Span;
+
+/// Two clicks on the same target within this long are a double-click
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Log lines scrolled per wheel notch
+pub const WHEEL_SCROLL_LINES: isize = 3;
+
+/// What a click lands on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickTarget {
+    /// A tab, by its index in `ViewMode::all_variants`
+    Tab(usize),
+    /// A repeated query in Query Analysis's N+1 section
+    NPlusOne(usize),
+    /// An in-flight request in Query Analysis
+    InFlight(usize),
+    /// A completed request in Query Analysis, by its index in the (searched)
+    /// request list, oldest first
+    Request(usize),
+    /// An exception group, by its position in the listed order
+    Exception(usize),
+}
+
+#[derive(Debug, Default)]
+pub struct ClickMap {
+    regions: Vec<(Rect, ClickTarget)>,
+}
+
+impl ClickMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the last frame's regions, before drawing the next
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    pub fn add(&mut self, area: Rect, target: ClickTarget) {
+        if !area.is_empty() {
+            self.regions.push((area, target));
+        }
+    }
+
+    /// Line `line` of a paragraph drawn unwrapped and unscrolled in `inner`;
+    /// nothing when it fell below the bottom
+    pub fn add_line(&mut self, inner: Rect, line: usize, target: ClickTarget) {
+        if let Some(area) = line_rect(inner, line) {
+            self.add(area, target);
+        }
+    }
+
+    /// The target under the cell at `column`, `row`; regions added later were
+    /// drawn on top and win
+    pub fn target_at(&self, column: u16, row: u16) -> Option<ClickTarget> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(area, _)| contains(*area, column, row))
+            .map(|(_, target)| *target)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Whether the cell at `column`, `row` is inside `area`
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.right() && row >= area.y && row < area.bottom()
+}
+
+/// The row line `line` of `inner` is drawn in, if it fits
+pub fn line_rect(inner: Rect, line: usize) -> Option<Rect> {
+    let offset = u16::try_from(line).ok()?;
+    (offset < inner.height).then(|| Rect::new(inner.x, inner.y + offset, inner.width, 1))
+}
+
+/// Segments of `widths` cells laid out left to right on `area`'s top row
+/// with `gap` cells between them, cut off at its right edge
+pub fn row_segments(area: Rect, widths: impl IntoIterator<Item = u16>, gap: u16) -> Vec<Rect> {
+    let mut x = area.x;
+    let mut segments = Vec::new();
+    for width in widths {
+        let width = width.min(area.right().saturating_sub(x));
+        if width == 0 {
+            break;
+        }
+        segments.push(Rect::new(x, area.y, width, 1));
+        x = x.saturating_add(width).saturating_add(gap);
+    }
+    segments
+}
+
+/// Where ratatui's `Tabs` draws each of `titles` inside `inner`, its
+/// one-space padding on either side included: the one-cell dividers between
+/// them aren't part of any tab
+pub fn tab_rects(inner: Rect, titles: &[&str]) -> Vec<Rect> {
+    let widths = titles
+        .iter()
+        .map(|title| Span::raw(*title).width() as u16 + 2);
+    row_segments(inner, widths, 1)
+}
+
+/// The last click, to tell a double-click from two single ones
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    last: Option<(Instant, ClickTarget)>,
+}
+
+impl ClickTracker {
+    /// Note a click on `target` at `now`: true when it completes a
+    /// double-click, which then doesn't count towards the next one
+    pub fn click(&mut self, target: ClickTarget, now: Instant) -> bool {
+        let double = self.last.is_some_and(|(at, last)| {
+            last == target && now.saturating_duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        });
+        self.last = if double { None } else { Some((now, target)) };
+        double
+    }
+
+    /// A click on nothing, which ends a double-click in the making
+    pub fn miss(&mut self) {
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_later_regions_win_and_edges_are_exclusive() {
+        let mut map = ClickMap::new();
+        map.add(Rect::new(0, 0, 20, 10), ClickTarget::Tab(0));
+        map.add(Rect::new(5, 2, 4, 1), ClickTarget::Exception(3));
+        map.add(Rect::new(30, 0, 0, 5), ClickTarget::Tab(1));
+
+        assert_eq!(map.target_at(5, 2), Some(ClickTarget::Exception(3)));
+        assert_eq!(map.target_at(8, 2), Some(ClickTarget::Exception(3)));
+        assert_eq!(map.target_at(9, 2), Some(ClickTarget::Tab(0)));
+        assert_eq!(map.target_at(19, 9), Some(ClickTarget::Tab(0)));
+        assert_eq!(map.target_at(20, 9), None);
+        assert_eq!(map.target_at(19, 10), None);
+        assert_eq!(map.target_at(30, 0), None);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.target_at(5, 2), None);
+    }
+
+    #[test]
+    fn test_lines_below_the_area_are_not_clickable() {
+        let inner = Rect::new(2, 3, 40, 5);
+        assert_eq!(line_rect(inner, 0), Some(Rect::new(2, 3, 40, 1)));
+        assert_eq!(line_rect(inner, 4), Some(Rect::new(2, 7, 40, 1)));
+        assert_eq!(line_rect(inner, 5), None);
+        assert_eq!(line_rect(inner, usize::MAX), None);
+
+        let mut map = ClickMap::new();
+        map.add_line(inner, 2, ClickTarget::Request(7));
+        map.add_line(inner, 9, ClickTarget::Request(0));
+        assert_eq!(map.target_at(2, 5), Some(ClickTarget::Request(7)));
+        assert_eq!(map.target_at(41, 5), Some(ClickTarget::Request(7)));
+        assert_eq!(map.target_at(42, 5), None);
+        assert_eq!(map.target_at(2, 12), None);
+    }
+
+    #[test]
+    fn test_tabs_are_laid_out_like_ratatui_draws_them() {
+        // "│ Logs │ Query Analysis │ Jobs │" inside a one-cell border
+        let inner = Rect::new(1, 1, 60, 1);
+        let rects = tab_rects(inner, &["Logs", "Query Analysis", "Jobs"]);
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(1, 1, 6, 1),
+                Rect::new(8, 1, 16, 1),
+                Rect::new(25, 1, 6, 1),
+            ]
+        );
+
+        // Tabs past the right edge are cut off, then left out
+        let rects = tab_rects(Rect::new(0, 0, 12, 1), &["Logs", "Query Analysis", "Jobs"]);
+        assert_eq!(rects, vec![Rect::new(0, 0, 6, 1), Rect::new(7, 0, 5, 1)]);
+    }
+
+    #[test]
+    fn test_tab_rects_match_the_rendered_tabs() {
+        use ratatui::{Terminal, backend::TestBackend, widgets::Tabs};
+
+        let titles = ["Logs", "Query Analysis", "Database Health"];
+        let mut terminal = Terminal::new(TestBackend::new(50, 1)).unwrap();
+        terminal
+            .draw(|f| f.render_widget(Tabs::new(titles.to_vec()), f.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+
+        for (rect, title) in tab_rects(Rect::new(0, 0, 50, 1), &titles)
+            .iter()
+            .zip(titles)
+        {
+            let drawn: String = (rect.x..rect.right())
+                .map(|x| buffer[(x, 0)].symbol())
+                .collect();
+            assert_eq!(drawn, format!(" {} ", title));
+        }
+    }
+
+    #[test]
+    fn test_double_clicks_need_the_same_target_soon_after() {
+        let mut clicks = ClickTracker::default();
+        let start = Instant::now();
+        let target = ClickTarget::Exception(1);
+
+        assert!(!clicks.click(target, start));
+        assert!(clicks.click(target, start + Duration::from_millis(200)));
+        // A third click starts over
+        assert!(!clicks.click(target, start + Duration::from_millis(300)));
+
+        assert!(!clicks.click(
+            ClickTarget::Exception(2),
+            start + Duration::from_millis(350)
+        ));
+        assert!(!clicks.click(target, start + Duration::from_millis(400)));
+        assert!(!clicks.click(target, start + Duration::from_millis(900)));
+
+        clicks.miss();
+        assert!(!clicks.click(target, start + Duration::from_millis(1000)));
+    }
+}
@@ -86,7 +86,7 @@ impl AutocompleteEngine {
         }
 
         // Sort by score (descending) and take top results
-        suggestions.sort_by(|a, b| b.score.cmp(&a.score));
+        suggestions.sort_by_key(|s| std::cmp::Reverse(s.score));
         suggestions.truncate(max_results);
         suggestions
     }
@@ -168,10 +168,78 @@ impl AutocompleteEngine {
     }
 }
 
+/// Closest candidate to a mistyped name, for "did you mean" hints
+///
+/// Case and a leading `/` are ignored. A candidate matches when it is within
+/// a third of the input's length in edits (a swapped pair counts as one), or
+/// when the input is the candidate with a `-`/`_` suffix (`export-report`).
+/// Inputs shorter than three characters only match exactly. Ties go to the
+/// earlier candidate, so list primary names before aliases.
+pub fn closest_match<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let input = input.trim().trim_start_matches('/').to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    let max_distance = if input.chars().count() < 3 {
+        0
+    } else {
+        (input.chars().count() / 3).max(1)
+    };
+
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        let lower = candidate.to_lowercase();
+        let distance = if input
+            .strip_prefix(lower.as_str())
+            .is_some_and(|rest| rest.starts_with(['-', '_']))
+        {
+            1
+        } else {
+            edit_distance(&input, &lower)
+        };
+        if distance <= max_distance && best.is_none_or(|(best, _)| distance < best) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of
+/// adjacent characters as one edit each
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ui::command::registry::CommandMetadata;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
 
     fn create_test_engine() -> AutocompleteEngine {
         let commands = vec![
@@ -261,4 +329,114 @@ mod tests {
 
         assert_eq!(hints, vec!["error", "warn"]);
     }
+
+    #[test]
+    fn test_closest_match_near_miss() {
+        let names = ["view", "v", "switch", "export", "help"];
+
+        assert_eq!(closest_match("veiw", names), Some("view"));
+        assert_eq!(closest_match("/HEPL", names), Some("help"));
+        assert_eq!(closest_match("export-reprot", names), Some("export"));
+        assert_eq!(closest_match("swtich", names), Some("switch"));
+    }
+
+    #[test]
+    fn test_closest_match_exact_alias() {
+        let names = ["view", "v", "switch"];
+
+        assert_eq!(closest_match("/V", names), Some("v"));
+        assert_eq!(closest_match("Switch", names), Some("switch"));
+    }
+
+    #[test]
+    fn test_closest_match_hopeless() {
+        let names = ["view", "v", "quit", "q", "export"];
+
+        assert_eq!(closest_match("xyzzy", names), None);
+        assert_eq!(closest_match("x", names), None);
+        assert_eq!(closest_match("/", names), None);
+    }
+
+    #[test]
+    fn test_unknown_command_tab_fills_the_suggestion() {
+        let mut app = test_app();
+        app.enter_command_mode();
+        app.command_input = "/veiw".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message))
+                    if message == "Unknown command 'veiw'. Did you mean 'view'? (Tab to use it)"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.command_input, "/view");
+
+        app.command_input = "/view exceptoins".to_string();
+        app.execute_command();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.command_input, "/view exceptions");
+        app.execute_command();
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+
+        // Nothing close enough: no suggestion for Tab to take
+        app.enter_command_mode();
+        app.command_input = "/xyzzy".to_string();
+        app.execute_command();
+        assert!(app.command_fix.is_none());
+    }
+
+    #[test]
+    fn test_restart_completes_process_names() {
+        use crate::process::ProcessStatus;
+
+        let mut app = test_app();
+        let process = |name: &str| ProcessInfo {
+            name: name.into(),
+            command: "bin/dev".into(),
+            status: ProcessStatus::Running,
+            start_time: None,
+            pid: None,
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        };
+        app.update_processes(std::sync::Arc::new(vec![
+            process("worker"),
+            process("web"),
+            process("css"),
+        ]));
+        app.enter_command_mode();
+        for c in "restart w".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        let suggested: Vec<_> = app.command_suggestions.iter().map(|s| &s.text).collect();
+        assert_eq!(suggested, ["restart web", "restart worker"]);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.command_input, "/restart web");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message))
+                    if message == "Can't restart a saved session's processes"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+
+        // Asked for, the process name is offered from the same list
+        app.enter_command_mode();
+        for c in "restart".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        let suggested: Vec<_> = app.command_suggestions.iter().map(|s| &s.text).collect();
+        assert_eq!(suggested, ["css", "web", "worker"]);
+    }
 }
@@ -1,15 +1,57 @@
 /// Built-in commands for the application
 use super::registry::{Command, CommandContext, CommandResult};
+use crate::search::SearchFilter;
 use crate::ui::ViewMode;
 
 /// Command context implementation for the application
 pub struct AppContext<'a> {
     pub view_mode: &'a mut ViewMode,
     pub search_query: &'a mut String,
-    pub filter_process: &'a mut Option<String>,
+    /// Whether the search matches case when it's the Logs view's, which
+    /// takes `re:` regexes too; `None` for the other views' plain searches
+    pub search_case_sensitive: Option<bool>,
+    pub filter_process: &'a mut Option<crate::process::ProcessFilter>,
+    /// Least severe lines the Logs view shows
+    pub min_log_level: &'a mut Option<crate::process::LogLevel>,
+    /// Severity and recency narrowing the Exceptions list
+    pub exception_filter: &'a mut crate::exception::ExceptionFilter,
     pub auto_scroll: &'a mut bool,
+    pub log_order: &'a mut crate::ui::components::LogOrder,
     pub should_quit: &'a mut bool,
+    pub show_diagnostics: &'a mut bool,
+    pub show_alerts: &'a mut bool,
+    pub show_help: &'a mut bool,
+    pub show_status: &'a mut bool,
+    pub show_build_error: &'a mut bool,
+    pub show_memory: &'a mut bool,
+    /// The last memory report a process printed
+    pub memory_report: Option<&'a crate::memory::MemoryReport>,
+    /// Whether a frontend build is currently failing
+    pub build_failed: bool,
+    pub show_routes: &'a mut bool,
+    pub routes_query: &'a mut String,
+    /// Whether there's a Rails app to read routes from
+    pub routes_available: bool,
+    /// Endpoint `/trends` asked for; `None` until then
+    pub trends_endpoint: &'a mut Option<crate::history::Endpoint>,
+    /// Whether `[history]` is recording
+    pub history_available: bool,
     pub logs: &'a Vec<crate::process::LogLine>,
+    /// Query Analysis's search, which narrows the requests `/export-sql all` writes
+    pub request_search: &'a str,
+    pub time_range: &'a mut Option<crate::time_range::TimeRange>,
+    pub display_zone: crate::time_format::DisplayZone,
+    /// Masking of personal data on screen and in exports
+    pub privacy: &'a mut crate::privacy::Privacy,
+    pub autofocus: &'a mut crate::ui::autofocus::AutoFocus,
+    pub annotations: &'a mut crate::ui::annotations::LogAnnotations,
+    pub session: crate::session::SessionSources<'a>,
+    pub project: Option<&'a std::path::Path>,
+    /// Running processes, for `/send`, `/restart` and `/run`; `None` in a
+    /// saved session
+    pub process_manager: Option<&'a crate::process::ProcessManager>,
+    /// Lines for the logs, as Caboose's own, once the command returns
+    pub notices: &'a mut Vec<String>,
 }
 
 impl<'a> CommandContext for AppContext<'a> {}
@@ -62,7 +104,7 @@ impl Command for SearchCommand {
     }
 
     fn description(&self) -> &str {
-        "Search logs for a query"
+        "Search the current view for a query"
     }
 
     fn usage(&self) -> &str {
@@ -84,77 +126,1339 @@ impl Command for SearchCommand {
         let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
 
         let query = args.join(" ");
+        if let Some(case_sensitive) = ctx.search_case_sensitive
+            && let Some(error) = SearchFilter::new(&query, case_sensitive).error()
+        {
+            return Err(format!("Invalid regex: {}", error));
+        }
         *ctx.search_query = query.clone();
         *ctx.auto_scroll = false;
 
-        Ok(format!("Searching for: '{}'", query))
+        Ok(format!("Searching for: '{}'", query))
+    }
+}
+
+// ============================================================================
+// CLEAR COMMAND
+// ============================================================================
+
+pub struct ClearCommand;
+
+impl Command for ClearCommand {
+    fn name(&self) -> &str {
+        "clear"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["c", "reset"]
+    }
+
+    fn description(&self) -> &str {
+        "Clear search and filters"
+    }
+
+    fn usage(&self) -> &str {
+        "/clear"
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        ctx.search_query.clear();
+        *ctx.filter_process = None;
+        *ctx.min_log_level = None;
+        *ctx.time_range = None;
+        *ctx.auto_scroll = true;
+
+        Ok("Cleared all filters".to_string())
+    }
+}
+
+// ============================================================================
+// VIEW COMMAND
+// ============================================================================
+
+pub struct ViewCommand;
+
+/// Names `/view` accepts, main names first
+const VIEW_NAMES: [&str; 15] = [
+    "logs",
+    "query",
+    "db",
+    "tests",
+    "exceptions",
+    "jobs",
+    "log",
+    "queries",
+    "sql",
+    "database",
+    "health",
+    "test",
+    "errors",
+    "err",
+    "job",
+];
+
+impl ViewCommand {
+    fn parse_view(name: &str) -> Option<ViewMode> {
+        match name {
+            "logs" | "log" => Some(ViewMode::Logs),
+            "query" | "queries" | "sql" => Some(ViewMode::QueryAnalysis),
+            "db" | "database" | "health" => Some(ViewMode::DatabaseHealth),
+            "tests" | "test" => Some(ViewMode::TestResults),
+            "exceptions" | "errors" | "err" => Some(ViewMode::Exceptions),
+            "jobs" | "job" => Some(ViewMode::Jobs),
+            _ => None,
+        }
+    }
+
+    /// Closest view name to an unknown one
+    pub fn suggest_view(name: &str) -> Option<&'static str> {
+        if Self::parse_view(&name.to_lowercase()).is_some() {
+            return None;
+        }
+        super::autocomplete::closest_match(name, VIEW_NAMES)
+    }
+}
+
+impl Command for ViewCommand {
+    fn name(&self) -> &str {
+        "view"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["v", "switch"]
+    }
+
+    fn description(&self) -> &str {
+        "Switch to a different view"
+    }
+
+    fn usage(&self) -> &str {
+        "/view <logs|query|db|tests|exceptions|jobs>"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["logs", "query", "db", "tests", "exceptions", "jobs"]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let view_name = args[0].to_lowercase();
+
+        *ctx.view_mode = match Self::parse_view(&view_name) {
+            Some(view) => view,
+            None => {
+                return Err(match Self::suggest_view(&view_name) {
+                    Some(suggestion) => format!(
+                        "Unknown view '{}'. Did you mean '{}'? (Tab to use it)",
+                        view_name, suggestion
+                    ),
+                    None => format!(
+                        "Unknown view: '{}'. Available views: logs, query, db, tests, exceptions, jobs",
+                        view_name
+                    ),
+                });
+            }
+        };
+
+        Ok(format!("Switched to {} view", ctx.view_mode.as_str()))
+    }
+}
+
+// ============================================================================
+// FILTER COMMAND
+// ============================================================================
+
+pub struct FilterCommand;
+
+impl Command for FilterCommand {
+    fn name(&self) -> &str {
+        "filter"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["f"]
+    }
+
+    fn description(&self) -> &str {
+        "Filter logs by process: web,worker, !frontend or web*"
+    }
+
+    fn usage(&self) -> &str {
+        "/filter <process>[,<process>...] | !<process> | <glob*>"
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        // `web, worker` arrives as two arguments
+        let filter = crate::process::ProcessFilter::parse(&args.join(","))?;
+        let message = format!("Filtering by process: '{}'", filter);
+        *ctx.filter_process = Some(filter);
+        *ctx.auto_scroll = false;
+
+        Ok(message)
+    }
+}
+
+// ============================================================================
+// LEVEL COMMAND
+// ============================================================================
+
+pub struct LevelCommand;
+
+impl Command for LevelCommand {
+    fn name(&self) -> &str {
+        "level"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["lvl"]
+    }
+
+    fn description(&self) -> &str {
+        "Show only log lines at a level or above: debug, info, warn, error"
+    }
+
+    fn usage(&self) -> &str {
+        "/level <debug|info|warn|error|all>"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["debug", "info", "warn", "error", "all"]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        use crate::process::LogLevel;
+
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let arg = &args[0];
+        if matches!(arg.to_lowercase().as_str(), "all" | "off") {
+            *ctx.min_log_level = None;
+            return Ok("Showing log lines at every level".to_string());
+        }
+        let level = LogLevel::parse(arg)
+            .ok_or_else(|| "Invalid level. Use: debug, info, warn, error or all".to_string())?;
+        *ctx.min_log_level = Some(level);
+        Ok(format!("Showing log lines at {} and above", level.label()))
+    }
+}
+
+// ============================================================================
+// EXCEPTIONS COMMAND
+// ============================================================================
+
+pub struct ExceptionsCommand;
+
+impl Command for ExceptionsCommand {
+    fn name(&self) -> &str {
+        "exceptions"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["exc"]
+    }
+
+    fn description(&self) -> &str {
+        "List exceptions of a severity or above, or only those seen recently"
+    }
+
+    fn usage(&self) -> &str {
+        "/exceptions <critical|high|medium|low|recent [minutes]|dismissed|all>"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec![
+            "critical",
+            "high",
+            "medium",
+            "low",
+            "recent",
+            "dismissed",
+            "all",
+        ]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        use crate::exception::{DEFAULT_SEEN_WITHIN, ExceptionSeverity};
+
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let filter = &mut *ctx.exception_filter;
+        let message = match (args[0].to_lowercase().as_str(), args.get(1)) {
+            ("all", None) => {
+                filter.min_severity = None;
+                filter.seen_within = None;
+                "Listing every exception".to_string()
+            }
+            ("dismissed", None) => {
+                filter.show_dismissed = !filter.show_dismissed;
+                if filter.show_dismissed {
+                    "Listing dismissed exceptions too (d lists one as usual again)".to_string()
+                } else {
+                    "Hiding dismissed exceptions".to_string()
+                }
+            }
+            ("recent", Some(arg)) if arg == "off" => {
+                filter.seen_within = None;
+                "Listing exceptions however long ago they were seen".to_string()
+            }
+            ("recent", minutes) => {
+                let window = match minutes {
+                    Some(minutes) => match minutes.parse::<u64>() {
+                        Ok(minutes) if minutes > 0 => std::time::Duration::from_secs(minutes * 60),
+                        _ => return Err("Minutes must be a whole number above 0".to_string()),
+                    },
+                    None => DEFAULT_SEEN_WITHIN,
+                };
+                filter.seen_within = Some(window);
+                format!(
+                    "Listing exceptions seen in the last {}m",
+                    window.as_secs() / 60
+                )
+            }
+            (name, None) => {
+                let severity = ExceptionSeverity::parse(name).ok_or_else(|| {
+                    "Invalid severity. Use: critical, high, medium, low, recent, dismissed or all"
+                        .to_string()
+                })?;
+                filter.min_severity = Some(severity);
+                format!("Listing exceptions: {}", filter.describe())
+            }
+            _ => return Err(format!("Too many arguments.\nUsage: {}", self.usage())),
+        };
+        *ctx.view_mode = ViewMode::Exceptions;
+        Ok(message)
+    }
+}
+
+// ============================================================================
+// EXPORT COMMAND
+// ============================================================================
+
+/// `caboose_logs_20250309_143205.txt`, stamped in `zone`
+pub fn default_export_file_name(
+    now: chrono::DateTime<chrono::Local>,
+    zone: crate::time_format::DisplayZone,
+) -> String {
+    format!("caboose_logs_{}.txt", zone.file_stamp(now))
+}
+
+/// `caboose_logs_20250309_143205.jsonl`, stamped in `zone`, for `/export json`
+pub fn default_json_export_file_name(
+    now: chrono::DateTime<chrono::Local>,
+    zone: crate::time_format::DisplayZone,
+) -> String {
+    format!("caboose_logs_{}.jsonl", zone.file_stamp(now))
+}
+
+/// `caboose_memory_20250309_143205.txt`, stamped in `zone`
+pub fn default_memory_file_name(
+    now: chrono::DateTime<chrono::Local>,
+    zone: crate::time_format::DisplayZone,
+) -> String {
+    format!("caboose_memory_{}.txt", zone.file_stamp(now))
+}
+
+/// Write `report` as text to `path`
+pub fn export_memory_report(
+    report: &crate::memory::MemoryReport,
+    zone: crate::time_format::DisplayZone,
+    path: &str,
+) -> Result<String, String> {
+    std::fs::write(path, report.to_text(zone))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(format!(
+        "Exported {}'s memory report to '{}'",
+        report.process, path
+    ))
+}
+
+/// One exported line: `2025-03-09 14:32:05 [web] Started GET "/"`
+///
+/// The content is masked the way it's shown while privacy mode is on.
+pub fn export_line(
+    log: &crate::process::LogLine,
+    zone: crate::time_format::DisplayZone,
+    privacy: &crate::privacy::Privacy,
+) -> String {
+    format!(
+        "{} [{}] {}",
+        zone.date_time(log.time),
+        log.process_name,
+        privacy.log_line(&log.full_content())
+    )
+}
+
+pub struct ExportCommand;
+
+impl Command for ExportCommand {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["e", "save"]
+    }
+
+    fn description(&self) -> &str {
+        "Export logs to a file, as text or (with json) as JSON lines"
+    }
+
+    fn usage(&self) -> &str {
+        "/export [json] [filename]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["json", "logs.txt", "output.log"]
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        if args
+            .first()
+            .is_some_and(|arg| arg.eq_ignore_ascii_case("json"))
+        {
+            let filename = match args.get(1) {
+                Some(filename) => filename.clone(),
+                None => default_json_export_file_name(chrono::Local::now(), ctx.display_zone),
+            };
+            let written = crate::log_export::export_json(
+                std::path::Path::new(&filename),
+                ctx.logs,
+                ctx.display_zone,
+                ctx.privacy,
+            )?;
+            return Ok(format!(
+                "Exported {} logs as JSON lines to '{}'",
+                written, filename
+            ));
+        }
+        if args.len() > 1 {
+            return Err("Usage: /export [json] [filename]".to_string());
+        }
+
+        let filename = if args.is_empty() {
+            default_export_file_name(chrono::Local::now(), ctx.display_zone)
+        } else {
+            args[0].clone()
+        };
+
+        // Write logs to file
+        use std::fs::File;
+        use std::io::Write;
+
+        let mut file =
+            File::create(&filename).map_err(|e| format!("Failed to create file: {}", e))?;
+
+        for log in ctx.logs {
+            writeln!(file, "{}", export_line(log, ctx.display_zone, ctx.privacy))
+                .map_err(|e| format!("Failed to write to file: {}", e))?;
+        }
+
+        Ok(format!(
+            "Exported {} logs to '{}'",
+            ctx.logs.len(),
+            filename
+        ))
+    }
+}
+
+// ============================================================================
+// EXPORT SQL COMMAND
+// ============================================================================
+
+/// `caboose_sql_20250309_143205.sql`, stamped in `zone`
+pub fn default_sql_file_name(
+    now: chrono::DateTime<chrono::Local>,
+    zone: crate::time_format::DisplayZone,
+) -> String {
+    format!("caboose_sql_{}.sql", zone.file_stamp(now))
+}
+
+/// Requests whose SQL `S` / `/export-sql` writes: Request Detail's request,
+/// or with `all` (and from any other view) those Query Analysis lists for
+/// `search`
+pub fn sql_export_requests(
+    view_mode: &ViewMode,
+    requests: &crate::context::RequestContextTracker,
+    search: &str,
+    all: bool,
+) -> Vec<crate::context::CompletedRequest> {
+    match view_mode {
+        ViewMode::RequestDetail(index) if !all => requests
+            .get_recent_requests()
+            .get(*index)
+            .cloned()
+            .into_iter()
+            .collect(),
+        _ => requests.get_recent_requests_matching(&crate::search::SearchQuery::new(search)),
+    }
+}
+
+/// Write `requests`' SQL to `path`, masked the way it's shown
+pub fn export_sql(
+    requests: &[crate::context::CompletedRequest],
+    privacy: &crate::privacy::Privacy,
+    path: &str,
+) -> Result<String, String> {
+    let [first, ..] = requests else {
+        return Err("No requests to export SQL from".to_string());
+    };
+    std::fs::write(path, crate::query::requests_to_sql(requests, privacy))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    let statements: usize = requests.iter().map(|req| req.context.queries.len()).sum();
+    let from = match requests {
+        [_] => format!(
+            "{} {}",
+            first.context.method.as_deref().unwrap_or("?"),
+            privacy.text(first.context.path.as_deref().unwrap_or("?"))
+        ),
+        _ => format!("{} requests", requests.len()),
+    };
+    Ok(format!(
+        "Exported {} statement{} from {} to '{}'",
+        statements,
+        if statements == 1 { "" } else { "s" },
+        from,
+        path
+    ))
+}
+
+pub struct ExportSqlCommand;
+
+impl Command for ExportSqlCommand {
+    fn name(&self) -> &str {
+        "export-sql"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["sql"]
+    }
+
+    fn description(&self) -> &str {
+        "Write the SQL of the request in Request Detail, or of every listed request, to a .sql file"
+    }
+
+    fn usage(&self) -> &str {
+        "/export-sql [all] [file]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["all", "queries.sql"]
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let all = args.first().is_some_and(|arg| arg == "all");
+        let path = args
+            .get(usize::from(all))
+            .cloned()
+            .unwrap_or_else(|| default_sql_file_name(chrono::Local::now(), ctx.display_zone));
+        let requests =
+            sql_export_requests(ctx.view_mode, ctx.session.requests, ctx.request_search, all);
+        export_sql(&requests, ctx.privacy, &path)
+    }
+}
+
+// ============================================================================
+// SESSION COMMAND
+// ============================================================================
+
+pub struct SessionCommand;
+
+impl Command for SessionCommand {
+    fn name(&self) -> &str {
+        "session"
+    }
+
+    fn description(&self) -> &str {
+        "Save the session to hand off (caboose open <file>)"
+    }
+
+    fn usage(&self) -> &str {
+        "/session save [filename]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["save"]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        if args[0] != "save" {
+            return Err(format!(
+                "Unknown action '{}' (usage: {})",
+                args[0],
+                self.usage()
+            ));
+        }
+        let filename = args.get(1).cloned().unwrap_or_else(|| {
+            crate::session::default_file_name(chrono::Local::now(), ctx.display_zone)
+        });
+
+        let mut archive = crate::session::SessionArchive::capture(
+            &ctx.session,
+            crate::session::SessionMetadata::new(ctx.project),
+        );
+        ctx.privacy.archive(&mut archive);
+        archive.save(std::path::Path::new(&filename))?;
+
+        Ok(format!(
+            "Saved session ({} logs, {} requests) to '{}' - open it with: caboose open {}",
+            archive.logs.len(),
+            archive.requests.completed.len(),
+            filename,
+            filename
+        ))
+    }
+}
+
+// ============================================================================
+// AROUND COMMAND
+// ============================================================================
+
+pub struct AroundCommand;
+
+impl Command for AroundCommand {
+    fn name(&self) -> &str {
+        "around"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["at"]
+    }
+
+    fn description(&self) -> &str {
+        "Show logs around a time (c clears)"
+    }
+
+    fn usage(&self) -> &str {
+        "/around <HH:MM[:SS] | -5m> [±30s]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["-5m", "±30s"]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let range =
+            crate::time_range::TimeRange::parse(&args, chrono::Local::now(), ctx.display_zone)?;
+        *ctx.time_range = Some(range);
+        *ctx.view_mode = ViewMode::Logs;
+        *ctx.auto_scroll = false;
+
+        Ok(range.showing_message(ctx.logs, ctx.display_zone))
+    }
+}
+
+// ============================================================================
+// HELP COMMAND
+// ============================================================================
+
+pub struct HelpCommand;
+
+impl Command for HelpCommand {
+    fn name(&self) -> &str {
+        "help"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["h", "?"]
+    }
+
+    fn description(&self) -> &str {
+        "Show keys and commands"
+    }
+
+    fn usage(&self) -> &str {
+        "/help"
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        *ctx.show_help = true;
+        Ok("Help opened (Esc to close)".to_string())
+    }
+}
+
+// ============================================================================
+// STATUS COMMAND
+// ============================================================================
+
+pub struct StatusCommand;
+
+impl Command for StatusCommand {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["ps"]
+    }
+
+    fn description(&self) -> &str {
+        "Show each process's status, readiness and exits"
+    }
+
+    fn usage(&self) -> &str {
+        "/status"
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        *ctx.show_status = true;
+        Ok("Process status opened (Esc to close)".to_string())
+    }
+}
+
+// ============================================================================
+// BUILD ERROR COMMAND
+// ============================================================================
+
+pub struct BuildErrorCommand;
+
+impl Command for BuildErrorCommand {
+    fn name(&self) -> &str {
+        "builderr"
+    }
+
+    fn description(&self) -> &str {
+        "Show the failing frontend build's errors"
+    }
+
+    fn usage(&self) -> &str {
+        "/builderr"
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        if !ctx.build_failed {
+            return Err("No frontend build is failing".to_string());
+        }
+        *ctx.show_build_error = true;
+        Ok("Build errors opened (Esc to close)".to_string())
+    }
+}
+
+// ============================================================================
+// MEMORY COMMAND
+// ============================================================================
+
+pub struct MemoryCommand;
+
+impl Command for MemoryCommand {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["mem"]
+    }
+
+    fn description(&self) -> &str {
+        "Show the last memory_profiler / derailed report, or export it"
+    }
+
+    fn usage(&self) -> &str {
+        "/memory [export [file]]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["export"]
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let Some(report) = ctx.memory_report else {
+            return Err(
+                "No memory report yet: run memory_profiler or derailed in a process".to_string(),
+            );
+        };
+        match args.first().map(String::as_str) {
+            None => {
+                *ctx.show_memory = true;
+                Ok(format!(
+                    "Memory report from {} opened (Esc to close)",
+                    report.process
+                ))
+            }
+            Some("export") => {
+                let path = args.get(1).cloned().unwrap_or_else(|| {
+                    default_memory_file_name(chrono::Local::now(), ctx.display_zone)
+                });
+                export_memory_report(report, ctx.display_zone, &path)
+            }
+            Some(other) => Err(format!("Unknown argument '{}'. Use: export [file]", other)),
+        }
+    }
+}
+
+// ============================================================================
+// ROUTES COMMAND
+// ============================================================================
+
+pub struct RoutesCommand;
+
+impl Command for RoutesCommand {
+    fn name(&self) -> &str {
+        "routes"
+    }
+
+    fn description(&self) -> &str {
+        "Browse the app's routes (from rails routes)"
+    }
+
+    fn usage(&self) -> &str {
+        "/routes [filter]"
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        if !ctx.routes_available {
+            return Err("Routes are only available for a Rails app".to_string());
+        }
+        *ctx.routes_query = args.join(" ");
+        *ctx.show_routes = true;
+        Ok("Routes opened (Esc to close)".to_string())
+    }
+}
+
+// ============================================================================
+// TRENDS COMMAND
+// ============================================================================
+
+pub struct TrendsCommand;
+
+impl Command for TrendsCommand {
+    fn name(&self) -> &str {
+        "trends"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["trend"]
+    }
+
+    fn description(&self) -> &str {
+        "Daily p50/p95 of an endpoint from [history]"
+    }
+
+    fn usage(&self) -> &str {
+        "/trends [METHOD] <path>"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["GET", "POST"]
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        if !ctx.history_available {
+            return Err(
+                "History is off - set enabled = true under [history] in .caboose.toml".to_string(),
+            );
+        }
+        let endpoint = crate::history::Endpoint::parse(&args.join(" "))?;
+        let message = format!("Trends for {} (Esc to close)", endpoint);
+        *ctx.trends_endpoint = Some(endpoint);
+        Ok(message)
+    }
+}
+
+// ============================================================================
+// REQUEST COMMAND
+// ============================================================================
+
+/// Matches listed when a prefix is ambiguous
+const REQUEST_MATCHES_SHOWN: usize = 5;
+
+pub struct RequestCommand;
+
+impl Command for RequestCommand {
+    fn name(&self) -> &str {
+        "request"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["req"]
+    }
+
+    fn description(&self) -> &str {
+        "Open a request by its request id (or the start of it)"
+    }
+
+    fn usage(&self) -> &str {
+        "/request <id-or-prefix>"
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        use crate::context::RequestTarget;
+
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let prefix = &args[0];
+        let matches = ctx.session.requests.find_by_request_id(prefix);
+        match matches.as_slice() {
+            [] => Err(format!(
+                "No request id starts with '{}'. Only the most recent requests are kept \
+                 (max_completed_requests under [limits])",
+                prefix
+            )),
+            [found] => {
+                *ctx.view_mode = match found.target {
+                    RequestTarget::Completed(index) => ViewMode::RequestDetail(index),
+                    RequestTarget::InFlight(id) => ViewMode::LiveRequest(id),
+                };
+                Ok(format!(
+                    "Request {} {}",
+                    found.request_id,
+                    ctx.privacy.text(&found.label)
+                ))
+            }
+            several => {
+                let listed: Vec<String> = several
+                    .iter()
+                    .take(REQUEST_MATCHES_SHOWN)
+                    .map(|m| format!("{} {}", m.request_id, ctx.privacy.text(&m.label)))
+                    .collect();
+                let more = several.len().saturating_sub(REQUEST_MATCHES_SHOWN);
+                Err(format!(
+                    "'{}' matches {} requests - type more of the id:\n{}{}",
+                    prefix,
+                    several.len(),
+                    listed.join("\n"),
+                    if more > 0 {
+                        format!("\n... and {} more", more)
+                    } else {
+                        String::new()
+                    }
+                ))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// SEND COMMAND
+// ============================================================================
+
+pub struct SendCommand;
+
+impl Command for SendCommand {
+    fn name(&self) -> &str {
+        "send"
+    }
+
+    fn description(&self) -> &str {
+        "Type a line into a process (answer a prompt, run a console command)"
+    }
+
+    fn usage(&self) -> &str {
+        "/send <process> <text>"
+    }
+
+    fn min_args(&self) -> usize {
+        2
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let Some(manager) = ctx.process_manager else {
+            return Err("Can't send to a saved session's processes".to_string());
+        };
+        let process = &args[0];
+        let text = args[1..].join(" ");
+        manager.send_line(process, &text)?;
+        Ok(format!("Sent to {}: {}", process, text))
+    }
+}
+
+// ============================================================================
+// RESTART COMMAND
+// ============================================================================
+
+pub struct RestartCommand;
+
+impl Command for RestartCommand {
+    fn name(&self) -> &str {
+        "restart"
+    }
+
+    fn description(&self) -> &str {
+        "Stop a process and start it again, leaving the others running"
+    }
+
+    fn usage(&self) -> &str {
+        "/restart <process>"
+    }
+
+    fn completes_processes(&self) -> bool {
+        true
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let Some(manager) = ctx.process_manager else {
+            return Err("Can't restart a saved session's processes".to_string());
+        };
+        let process = &args[0];
+        manager.restart_process(process)?;
+        Ok(format!("Restarted {}", process))
+    }
+}
+
+// ============================================================================
+// RUN COMMAND
+// ============================================================================
+
+pub struct RunCommand;
+
+impl Command for RunCommand {
+    fn name(&self) -> &str {
+        "run"
+    }
+
+    fn description(&self) -> &str {
+        "Run a one-off command (rails db:migrate, yarn build) with its output in the logs"
+    }
+
+    fn usage(&self) -> &str {
+        "/run <command...>"
+    }
+
+    fn raw_args(&self) -> bool {
+        true
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let argv = crate::process::split_args(&args.join(" "))?;
+        if argv.is_empty() {
+            return Err(format!("Nothing to run.\nUsage: {}", self.usage()));
+        }
+        run_task(ctx, &argv)
     }
 }
 
+/// Start `argv` as a task in the running session
+fn run_task(ctx: &AppContext, argv: &[String]) -> CommandResult {
+    let Some(manager) = ctx.process_manager else {
+        return Err("Can't run tasks in a saved session".to_string());
+    };
+    let name = manager.run_task(argv)?;
+    Ok(format!(
+        "Running {} as {}",
+        crate::process::shell_join(argv),
+        name
+    ))
+}
+
 // ============================================================================
-// CLEAR COMMAND
+// MIGRATE COMMAND
 // ============================================================================
 
-pub struct ClearCommand;
+pub struct MigrateCommand;
 
-impl Command for ClearCommand {
+impl Command for MigrateCommand {
     fn name(&self) -> &str {
-        "clear"
+        "migrate"
     }
 
-    fn aliases(&self) -> Vec<&str> {
-        vec!["c", "reset"]
+    fn description(&self) -> &str {
+        "Run bin/rails db:migrate as a task"
+    }
+
+    fn usage(&self) -> &str {
+        "/migrate"
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+        run_task(ctx, &["bin/rails".to_string(), "db:migrate".to_string()])
+    }
+}
+
+pub struct RunRoutesCommand;
+
+impl Command for RunRoutesCommand {
+    fn name(&self) -> &str {
+        // `/routes` opens the routes overlay
+        "run-routes"
     }
 
     fn description(&self) -> &str {
-        "Clear search and filters"
+        "Run bin/rails routes as a task"
     }
 
     fn usage(&self) -> &str {
-        "/clear"
+        "/run-routes"
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
     }
 
     fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
         // Safety: We know this is always AppContext in our application
         let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+        run_task(ctx, &["bin/rails".to_string(), "routes".to_string()])
+    }
+}
 
-        ctx.search_query.clear();
-        *ctx.filter_process = None;
-        *ctx.auto_scroll = true;
+// ============================================================================
+// PRIVACY COMMAND
+// ============================================================================
 
-        Ok("Cleared all filters".to_string())
+pub struct PrivacyCommand;
+
+impl Command for PrivacyCommand {
+    fn name(&self) -> &str {
+        "privacy"
+    }
+
+    fn description(&self) -> &str {
+        "Mask SQL literals, emails, UUIDs and branches for screen sharing"
+    }
+
+    fn usage(&self) -> &str {
+        "/privacy [on|off]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["on", "off"]
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let enabled = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => !ctx.privacy.is_enabled(),
+            Some("on") => true,
+            Some("off") => false,
+            Some(_) => return Err("Invalid argument. Use: on or off".to_string()),
+        };
+        ctx.privacy.set_enabled(enabled);
+        Ok(if enabled {
+            "Privacy mode on - personal data is masked on screen and in exports".to_string()
+        } else {
+            "Privacy mode off".to_string()
+        })
     }
 }
 
 // ============================================================================
-// VIEW COMMAND
+// AUTOFOCUS COMMAND
 // ============================================================================
 
-pub struct ViewCommand;
+pub struct AutofocusCommand;
 
-impl Command for ViewCommand {
+impl Command for AutofocusCommand {
     fn name(&self) -> &str {
-        "view"
+        "autofocus"
     }
 
-    fn aliases(&self) -> Vec<&str> {
-        vec!["v", "switch"]
+    fn description(&self) -> &str {
+        "Switch views to exceptions, crashes and test runs as they happen"
+    }
+
+    fn usage(&self) -> &str {
+        "/autofocus [on|off]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["on", "off"]
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let enabled = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => !ctx.autofocus.is_enabled(),
+            Some("on") => true,
+            Some("off") => false,
+            Some(_) => return Err("Invalid argument. Use: on or off".to_string()),
+        };
+        ctx.autofocus.set_enabled(enabled);
+        Ok(if enabled {
+            "Auto focus on - pressing a key pauses it until you're idle".to_string()
+        } else {
+            "Auto focus off".to_string()
+        })
+    }
+}
+
+// ============================================================================
+// ANNOTATIONS COMMAND
+// ============================================================================
+
+pub struct AnnotationsCommand;
+
+impl Command for AnnotationsCommand {
+    fn name(&self) -> &str {
+        "annotations"
     }
 
     fn description(&self) -> &str {
-        "Switch to a different view"
+        "Show request timings, query repeats and exception counts beside log lines"
     }
 
     fn usage(&self) -> &str {
-        "/view <logs|query|db|tests|exceptions>"
+        "/annotations [on|off]"
     }
 
     fn arg_hints(&self) -> Vec<&str> {
-        vec!["logs", "query", "db", "tests", "exceptions"]
+        vec!["on", "off"]
     }
 
     fn min_args(&self) -> usize {
-        1
+        0
     }
 
     fn max_args(&self) -> Option<usize> {
@@ -165,90 +1469,159 @@ impl Command for ViewCommand {
         // Safety: We know this is always AppContext in our application
         let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
 
-        let view_name = args[0].to_lowercase();
-
-        *ctx.view_mode = match view_name.as_str() {
-            "logs" | "log" => ViewMode::Logs,
-            "query" | "queries" | "sql" => ViewMode::QueryAnalysis,
-            "db" | "database" | "health" => ViewMode::DatabaseHealth,
-            "tests" | "test" => ViewMode::TestResults,
-            "exceptions" | "errors" | "err" => ViewMode::Exceptions,
-            _ => {
-                return Err(format!(
-                    "Unknown view: '{}'. Available views: logs, query, db, tests, exceptions",
-                    view_name
-                ));
-            }
+        let enabled = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => !ctx.annotations.is_enabled(),
+            Some("on") => true,
+            Some("off") => false,
+            Some(_) => return Err("Invalid argument. Use: on or off".to_string()),
         };
+        ctx.annotations.set_enabled(enabled);
+        Ok(if enabled {
+            "Annotations on".to_string()
+        } else {
+            "Annotations off".to_string()
+        })
+    }
+}
 
-        Ok(format!("Switched to {} view", ctx.view_mode.as_str()))
+// ============================================================================
+// PROCFILE CHECK COMMAND
+// ============================================================================
+
+pub struct ProcfileCheckCommand;
+
+impl Command for ProcfileCheckCommand {
+    fn name(&self) -> &str {
+        "procfile-check"
+    }
+
+    fn description(&self) -> &str {
+        "Compare the Procfile with the processes Caboose detects"
+    }
+
+    fn usage(&self) -> &str {
+        "/procfile-check"
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let project = ctx.project.ok_or("No project to check")?;
+        let findings = crate::config::check_project_procfile(project)?;
+        if findings.is_empty() {
+            return Ok("The Procfile runs everything Caboose detects".to_string());
+        }
+        ctx.notices.extend(
+            findings
+                .iter()
+                .map(|finding| format!("Procfile: {}", finding)),
+        );
+        Ok(format!(
+            "{} Procfile finding{} - see the Caboose lines in Logs",
+            findings.len(),
+            if findings.len() == 1 { "" } else { "s" }
+        ))
     }
 }
 
 // ============================================================================
-// FILTER COMMAND
+// DIAGNOSTICS COMMAND
 // ============================================================================
 
-pub struct FilterCommand;
+pub struct DiagCommand;
 
-impl Command for FilterCommand {
+impl Command for DiagCommand {
     fn name(&self) -> &str {
-        "filter"
+        "diag"
     }
 
     fn aliases(&self) -> Vec<&str> {
-        vec!["f"]
+        vec!["diagnostics", "mem"]
     }
 
     fn description(&self) -> &str {
-        "Filter logs by process name"
+        "Show Caboose's own buffer sizes and memory usage"
     }
 
     fn usage(&self) -> &str {
-        "/filter <process>"
+        "/diag"
     }
 
-    fn min_args(&self) -> usize {
-        1
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        *ctx.show_diagnostics = true;
+        Ok("Diagnostics opened (Esc to close)".to_string())
     }
+}
 
-    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+// ============================================================================
+// ALERTS COMMAND
+// ============================================================================
+
+pub struct AlertsCommand;
+
+impl Command for AlertsCommand {
+    fn name(&self) -> &str {
+        "alerts"
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["alert"]
+    }
+
+    fn description(&self) -> &str {
+        "Show alert rules and which are firing"
+    }
+
+    fn usage(&self) -> &str {
+        "/alerts"
+    }
+
+    fn execute(&self, _args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
         // Safety: We know this is always AppContext in our application
         let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
 
-        let process = args[0].clone();
-        *ctx.filter_process = Some(process.clone());
-        *ctx.auto_scroll = false;
-
-        Ok(format!("Filtering by process: '{}'", process))
+        *ctx.show_alerts = true;
+        Ok("Alerts opened (Esc to close)".to_string())
     }
 }
 
 // ============================================================================
-// EXPORT COMMAND
+// ORDER COMMAND
 // ============================================================================
 
-pub struct ExportCommand;
+pub struct OrderCommand;
 
-impl Command for ExportCommand {
+impl Command for OrderCommand {
     fn name(&self) -> &str {
-        "export"
+        "order"
     }
 
     fn aliases(&self) -> Vec<&str> {
-        vec!["e", "save"]
+        vec!["sort"]
     }
 
     fn description(&self) -> &str {
-        "Export logs to a file"
+        "Show logs newest or oldest first"
     }
 
     fn usage(&self) -> &str {
-        "/export <filename>"
+        "/order [newest|oldest]"
     }
 
     fn arg_hints(&self) -> Vec<&str> {
-        vec!["logs.txt", "output.log"]
+        vec!["newest", "oldest"]
     }
 
     fn min_args(&self) -> usize {
@@ -260,74 +1633,116 @@ impl Command for ExportCommand {
     }
 
     fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        use crate::ui::components::LogOrder;
+
         // Safety: We know this is always AppContext in our application
         let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
 
-        let filename = if args.is_empty() {
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_err(|e| format!("Failed to get timestamp: {}", e))?
-                .as_secs();
-            format!("caboose_logs_{}.txt", timestamp)
-        } else {
-            args[0].clone()
+        let order = match args.first() {
+            Some(arg) => LogOrder::parse(arg)
+                .ok_or_else(|| "Invalid argument. Use: newest or oldest".to_string())?,
+            None => ctx.log_order.toggled(),
         };
-
-        // Write logs to file
-        use std::fs::File;
-        use std::io::Write;
-
-        let mut file =
-            File::create(&filename).map_err(|e| format!("Failed to create file: {}", e))?;
-
-        for log in ctx.logs {
-            writeln!(file, "[{}] {}", log.process_name, log.content)
-                .map_err(|e| format!("Failed to write to file: {}", e))?;
-        }
-
+        *ctx.log_order = order;
         Ok(format!(
-            "Exported {} logs to '{}'",
-            ctx.logs.len(),
-            filename
+            "Logs shown {} (set [logs] order in .caboose.toml to keep it)",
+            order.label()
         ))
     }
 }
 
 // ============================================================================
-// HELP COMMAND
+// OPEN COMMAND
 // ============================================================================
 
-pub struct HelpCommand;
+/// The `n`-th most recent hyperlink in the log buffer, 1 being the newest
+pub fn recent_link(logs: &[crate::process::LogLine], n: usize) -> Option<&str> {
+    logs.iter()
+        .rev()
+        .filter_map(|log| log.markup.as_deref())
+        .flat_map(|markup| markup.links.iter().rev())
+        .nth(n.checked_sub(1)?)
+        .map(|link| link.url.as_str())
+}
 
-impl Command for HelpCommand {
-    fn name(&self) -> &str {
-        "help"
+/// Hand a link to the system opener; only web and file links are opened
+fn open_link(url: &str) -> Result<(), String> {
+    if !["http://", "https://", "file://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        return Err(format!(
+            "Not opening '{}' - only http(s) and file links are opened",
+            url
+        ));
     }
 
-    fn aliases(&self) -> Vec<&str> {
-        vec!["h", "?"]
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+pub struct OpenCommand;
+
+impl Command for OpenCommand {
+    fn name(&self) -> &str {
+        "open"
     }
 
     fn description(&self) -> &str {
-        "Show available commands"
+        "Open a link from the logs (newest first)"
     }
 
     fn usage(&self) -> &str {
-        "/help"
+        "/open [n]"
+    }
+
+    fn arg_hints(&self) -> Vec<&str> {
+        vec!["1", "2"]
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
     }
 
-    fn execute(&self, _args: Vec<String>, _ctx: &mut dyn CommandContext) -> CommandResult {
-        Ok("Available commands:\n\
-            /quit (q, exit) - Exit the application\n\
-            /search <query> (s, find) - Search logs\n\
-            /clear (c, reset) - Clear filters\n\
-            /view <name> (v) - Switch views\n\
-            /filter <process> (f) - Filter by process\n\
-            /export [file] (e) - Export logs\n\
-            /theme <name> (color) - Change color theme\n\
-            /icons [on|off|toggle] - Toggle icon mode\n\
-            /help (h, ?) - Show this help"
-            .to_string())
+    fn execute(&self, args: Vec<String>, ctx: &mut dyn CommandContext) -> CommandResult {
+        // Safety: We know this is always AppContext in our application
+        let ctx = unsafe { &mut *(ctx as *mut dyn CommandContext as *mut AppContext) };
+
+        let n = match args.first() {
+            Some(arg) => arg
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("Invalid link number '{}'", arg))?,
+            None => 1,
+        };
+        let url = recent_link(ctx.logs, n).ok_or_else(|| match n {
+            1 => "No links in the logs yet".to_string(),
+            n => format!("No link #{} - the logs have fewer links", n),
+        })?;
+        open_link(url)?;
+        Ok(format!("Opened {}", url))
     }
 }
 
@@ -481,10 +1896,281 @@ pub fn build_command_registry() -> super::registry::CommandRegistry {
     registry.register(Box::new(ClearCommand));
     registry.register(Box::new(ViewCommand));
     registry.register(Box::new(FilterCommand));
+    registry.register(Box::new(LevelCommand));
+    registry.register(Box::new(AroundCommand));
+    registry.register(Box::new(OrderCommand));
     registry.register(Box::new(ExportCommand));
+    registry.register(Box::new(ExportSqlCommand));
+    registry.register(Box::new(OpenCommand));
+    registry.register(Box::new(SessionCommand));
     registry.register(Box::new(ThemeCommand));
     registry.register(Box::new(IconCommand));
+    registry.register(Box::new(DiagCommand));
+    registry.register(Box::new(AlertsCommand));
+    registry.register(Box::new(ExceptionsCommand));
     registry.register(Box::new(HelpCommand));
+    registry.register(Box::new(StatusCommand));
+    registry.register(Box::new(BuildErrorCommand));
+    registry.register(Box::new(MemoryCommand));
+    registry.register(Box::new(RoutesCommand));
+    registry.register(Box::new(TrendsCommand));
+    registry.register(Box::new(RequestCommand));
+    registry.register(Box::new(SendCommand));
+    registry.register(Box::new(RestartCommand));
+    registry.register(Box::new(RunCommand));
+    registry.register(Box::new(MigrateCommand));
+    registry.register(Box::new(RunRoutesCommand));
+    registry.register(Box::new(PrivacyCommand));
+    registry.register(Box::new(AutofocusCommand));
+    registry.register(Box::new(AnnotationsCommand));
+    registry.register(Box::new(ProcfileCheckCommand));
 
     registry
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_command_splits_its_arguments_like_a_shell() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = std::sync::Arc::new(crate::process::ProcessManager::new(tx));
+        let mut app = test_app().with_process_manager(manager.clone());
+
+        app.enter_command_mode();
+        app.command_input = "/run echo 'a  b' \"it's\"".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Success(message))
+                    if message == "Running echo 'a  b' 'it'\\''s' as task:echo"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+        // Generous: spawning is slow while the rest of the suite runs
+        let mut output = Vec::new();
+        while !output.contains(&"a  b it's".to_string())
+            && let Ok(Some(line)) = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await
+        {
+            output.push(line.content.trim().to_string());
+        }
+        assert!(output.contains(&"a  b it's".to_string()), "{:?}", output);
+
+        app.enter_command_mode();
+        app.command_input = "/run echo 'open".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message)) if message.contains("Unclosed ' quote")
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+
+        // `/routes` being the routes browser, its task has its own name
+        app.enter_command_mode();
+        app.command_input = "/run-routes".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Success(message))
+                    if message == "Running bin/rails routes as task:routes"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+        manager.stop_all();
+    }
+
+    #[test]
+    fn test_request_command_opens_a_request_by_id_prefix() {
+        let mut app = test_app();
+        let log = |app: &mut App, content: &str| {
+            app.add_log(LogLine::new("web", content.to_string()));
+        };
+        for (id, path) in [
+            ("ab12cd34-7b3d-4c55-9e21-6d8f0a4b2c10", "/orders"),
+            ("ab98ef01-7b3d-4c55-9e21-6d8f0a4b2c10", "/users"),
+        ] {
+            log(
+                &mut app,
+                &format!("[{}] Started GET \"{}\" for 127.0.0.1", id, path),
+            );
+            log(&mut app, &format!("[{}] Completed 200 OK in 12ms", id));
+        }
+
+        app.enter_command_mode();
+        app.command_input = "/request ab".to_string();
+        app.execute_command();
+        let Some(command::ExecutionResult::Error(message)) = &app.last_command_result else {
+            panic!("{:?}", app.last_command_result);
+        };
+        assert!(message.contains("matches 2 requests"), "{}", message);
+        assert!(
+            message.contains("ab98ef01-7b3d-4c55-9e21-6d8f0a4b2c10 GET /users"),
+            "{}",
+            message
+        );
+
+        app.command_input = "/request zz".to_string();
+        app.execute_command();
+        assert!(matches!(
+            &app.last_command_result,
+            Some(command::ExecutionResult::Error(message)) if message.contains("No request id starts with 'zz'")
+        ));
+
+        app.command_input = "/request AB98".to_string();
+        app.execute_command();
+        assert!(matches!(app.view_mode, ViewMode::RequestDetail(_)));
+        let screen = render_app(&app, 100, 30);
+        assert!(screen.contains("#ab98ef01"), "{}", screen);
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 120, 30);
+        assert!(screen.contains("#ab12cd34"), "{}", screen);
+    }
+
+    #[test]
+    fn test_filter_command_takes_process_sets_exclusions_and_globs() {
+        let mut app = test_app();
+        for process in ["worker", "frontend"] {
+            app.add_log(LogLine::new(process, format!("{} says hi", process)));
+        }
+        let shown = |app: &App| {
+            let mut processes: Vec<String> = app
+                .filtered_logs()
+                .iter()
+                .map(|log| log.process_name.clone())
+                .collect();
+            processes.dedup();
+            processes.sort();
+            processes
+        };
+
+        app.command_input = "/filter web, worker".to_string();
+        app.execute_command();
+        assert_eq!(shown(&app), ["web", "worker"]);
+        app.last_command_result = None;
+        assert!(render_app(&app, 160, 40).contains("Filter: web,worker"));
+
+        app.command_input = "/filter !frontend".to_string();
+        app.execute_command();
+        assert_eq!(shown(&app), ["web", "worker"]);
+
+        app.command_input = "/filter w*,!worker".to_string();
+        app.execute_command();
+        assert_eq!(shown(&app), ["web"]);
+
+        app.command_input = "/filter !".to_string();
+        app.execute_command();
+        assert!(!app.last_command_result.as_ref().unwrap().is_success());
+        assert_eq!(
+            app.filter_process.as_ref().unwrap().to_string(),
+            "w*,!worker"
+        );
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('c')));
+        assert_eq!(app.filter_process, None);
+        assert_eq!(shown(&app), ["frontend", "web", "worker"]);
+    }
+
+    #[test]
+    fn test_around_command_constrains_logs_and_c_clears_it() {
+        let mut app = test_app();
+        app.logs.clear();
+        let now = chrono::Local::now();
+        // One line a minute, from ten minutes ago until now
+        for minutes_ago in (0..=10).rev() {
+            app.add_log(LogLine {
+                time: now - chrono::TimeDelta::minutes(minutes_ago),
+                ..LogLine::new("web", format!("{} minutes ago", minutes_ago))
+            });
+        }
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        app.command_input = "/around -5m ±90s".to_string();
+        app.execute_command();
+
+        assert_eq!(app.view_mode, ViewMode::Logs);
+        assert_eq!(app.active_tab_index, 0);
+        let shown: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|log| log.content.as_str())
+            .collect();
+        assert_eq!(shown, ["6 minutes ago", "5 minutes ago", "4 minutes ago"]);
+
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("⏱"), "{}", screen);
+        assert!(screen.contains("5 minutes ago"));
+        assert!(!screen.contains("9 minutes ago"));
+
+        app.clear_filter();
+        assert!(app.time_range.is_none());
+        assert_eq!(app.filtered_logs().len(), app.logs.len());
+    }
+
+    #[test]
+    fn test_export_sql_writes_the_request_or_every_listed_request() {
+        let mut app = test_app();
+        let long_in: Vec<String> = (0..4000).map(|id| id.to_string()).collect();
+        for content in [
+            r#"Started GET "/orders" for 127.0.0.1"#.to_string(),
+            format!(
+                r#"  Order Load (3.1ms)  SELECT "orders".* FROM "orders" WHERE "orders"."id" IN ({})"#,
+                long_in.join(", ")
+            ),
+            "Completed 200 OK in 9ms".to_string(),
+        ] {
+            app.add_log(LogLine::new("web", content));
+        }
+
+        // From Request Detail, `S` writes just that request
+        app.view_mode = ViewMode::RequestDetail(1);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('S')));
+        let message = app.last_command_result.as_ref().unwrap().message().unwrap();
+        assert!(
+            message.starts_with("Exported 1 statement from GET /orders to '"),
+            "{}",
+            message
+        );
+        let path = message.split('\'').nth(1).unwrap().to_string();
+        let sql = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            sql.contains("-- Order Load (3.1ms)\n-- TRUNCATED: "),
+            "{}",
+            sql
+        );
+        assert!(!sql.contains("\nSELECT"), "{}", sql);
+
+        // `/export-sql all` writes every request Query Analysis lists
+        let path = std::env::temp_dir().join(format!("caboose_sql_{}.sql", std::process::id()));
+        app.command_input = format!("/export-sql all {}", path.display());
+        app.execute_command();
+        assert_eq!(
+            app.last_command_result.as_ref().unwrap().message(),
+            Some(
+                format!(
+                    "Exported 2 statements from 2 requests to '{}'",
+                    path.display()
+                )
+                .as_str()
+            )
+        );
+        let sql = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            sql.contains("-- GET /users -> UsersController#index, 200 in 52.0ms\n-- User Load (1.2ms)\nSELECT \"users\".* FROM \"users\";\n"),
+            "{}",
+            sql
+        );
+    }
+}
@@ -78,10 +78,9 @@ impl CommandHistory {
     /// Navigate to next command (newer)
     ///
     /// Returns the command at the new position, or the temporary buffer if at the end
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<String> {
-        if self.position.is_none() {
-            return None;
-        }
+        self.position?;
 
         let new_pos = self.position.unwrap() + 1;
 
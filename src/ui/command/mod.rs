@@ -2,6 +2,7 @@ pub mod autocomplete;
 pub mod commands;
 pub mod history;
 pub mod parser;
+pub mod prompt;
 /// Command system module - Claude CLI inspired command palette
 ///
 /// This module provides a modern, extensible command system with:
@@ -27,10 +28,13 @@ pub mod parser;
 /// ```
 pub mod registry;
 
-pub use autocomplete::AutocompleteEngine;
+pub use autocomplete::{AutocompleteEngine, Suggestion};
 pub use history::CommandHistory;
 pub use parser::CommandParser;
-pub use registry::{Command, CommandContext, CommandRegistry, CommandResult};
+pub use prompt::ArgPrompt;
+pub use registry::{
+    Command, CommandContext, CommandError, CommandRegistry, CommandResult, MissingArgs,
+};
 
 use crate::ui::ViewMode;
 
@@ -38,7 +42,7 @@ use crate::ui::ViewMode;
 pub struct AppCommandContext<'a> {
     pub view_mode: &'a mut ViewMode,
     pub search_query: &'a mut String,
-    pub filter_process: &'a mut Option<String>,
+    pub filter_process: &'a mut Option<crate::process::ProcessFilter>,
     pub auto_scroll: &'a mut bool,
     pub should_quit: &'a mut bool,
 }
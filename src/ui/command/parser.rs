@@ -61,6 +61,22 @@ impl CommandParser {
         }
     }
 
+    /// Everything after the command name, as typed
+    ///
+    /// ```
+    /// use caboose::ui::command::CommandParser;
+    ///
+    /// assert_eq!(CommandParser::rest("/run echo 'a  b'"), "echo 'a  b'");
+    /// assert_eq!(CommandParser::rest("/run"), "");
+    /// ```
+    pub fn rest(input: &str) -> &str {
+        let trimmed = input.trim();
+        let without_slash = trimmed.strip_prefix('/').unwrap_or(trimmed);
+        without_slash
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim())
+    }
+
     /// Tokenize input, respecting quoted strings
     ///
     /// # Examples
@@ -75,9 +91,9 @@ impl CommandParser {
         let mut current_token = String::new();
         let mut in_quotes = false;
         let mut quote_char = ' ';
-        let mut chars = input.chars().peekable();
+        let chars = input.chars().peekable();
 
-        while let Some(ch) = chars.next() {
+        for ch in chars {
             match ch {
                 '"' | '\'' if !in_quotes => {
                     in_quotes = true;
@@ -0,0 +1,223 @@
+/// Asking for a command's missing arguments one at a time
+///
+/// A command run with fewer arguments than it needs doesn't fail: the palette
+/// shows its usage and takes the rest one per Enter, then runs it. Each answer
+/// is one argument as typed, spaces and all, so nothing needs quoting.
+use super::autocomplete::Suggestion;
+use super::registry::MissingArgs;
+
+/// A command waiting for the rest of its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgPrompt {
+    command: String,
+    usage: String,
+    args: Vec<String>,
+    needed: usize,
+    hints: Vec<String>,
+}
+
+impl ArgPrompt {
+    pub fn new(missing: MissingArgs) -> Self {
+        Self {
+            command: missing.command,
+            usage: missing.usage,
+            args: missing.args,
+            needed: missing.needed,
+            hints: missing.hints,
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    /// The arguments collected so far, given ones first
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// `argument 2 of 2`, for the one being asked for
+    pub fn label(&self) -> String {
+        format!("argument {} of {}", self.args.len() + 1, self.needed)
+    }
+
+    /// The command's hints matching `partial`, prefix matches first
+    ///
+    /// Hints describe a command's first argument, so later ones get none.
+    pub fn suggestions(&self, partial: &str) -> Vec<Suggestion> {
+        if !self.args.is_empty() {
+            return Vec::new();
+        }
+        let partial = partial.trim().to_lowercase();
+        let mut matches: Vec<Suggestion> = self
+            .hints
+            .iter()
+            .filter_map(|hint| {
+                let lower = hint.to_lowercase();
+                let score = if lower.starts_with(&partial) {
+                    2
+                } else if lower.contains(&partial) {
+                    1
+                } else {
+                    return None;
+                };
+                Some(Suggestion::new(
+                    hint.clone(),
+                    format!("{} {}", self.command, self.label()),
+                    self.usage.clone(),
+                    score,
+                ))
+            })
+            .collect();
+        matches.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.score));
+        matches
+    }
+
+    /// Take the next argument; `true` once the command has all it needs
+    pub fn push(&mut self, arg: String) -> bool {
+        self.args.push(arg);
+        self.is_complete()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.args.len() >= self.needed
+    }
+
+    /// The command line it amounts to, for history
+    pub fn command_line(&self) -> String {
+        let args = self.args.iter().map(|arg| {
+            if arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        });
+        std::iter::once(format!("/{}", self.command))
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    fn prompt(args: &[&str], needed: usize) -> ArgPrompt {
+        ArgPrompt::new(MissingArgs {
+            command: "send".to_string(),
+            usage: "/send <process> <text>".to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            needed,
+            hints: vec!["web".to_string(), "worker".to_string(), "rails".to_string()],
+        })
+    }
+
+    #[test]
+    fn test_collects_missing_args_in_order() {
+        let mut prompt = prompt(&[], 2);
+        assert_eq!(prompt.label(), "argument 1 of 2");
+        assert!(!prompt.push("web".to_string()));
+        assert_eq!(prompt.label(), "argument 2 of 2");
+        assert!(prompt.push("binding.pry continue".to_string()));
+        assert_eq!(prompt.args(), ["web", "binding.pry continue"]);
+        assert_eq!(prompt.command_line(), "/send web \"binding.pry continue\"");
+    }
+
+    #[test]
+    fn test_hints_are_filtered_and_only_offered_for_the_first_argument() {
+        let mut prompt = prompt(&[], 2);
+        let texts = |prompt: &ArgPrompt, partial: &str| -> Vec<String> {
+            prompt
+                .suggestions(partial)
+                .into_iter()
+                .map(|s| s.text)
+                .collect()
+        };
+        assert_eq!(texts(&prompt, ""), ["web", "worker", "rails"]);
+        assert_eq!(texts(&prompt, "W"), ["web", "worker"]);
+        // Prefix matches come before substring ones
+        assert_eq!(texts(&prompt, "r"), ["rails", "worker"]);
+        assert!(texts(&prompt, "zzz").is_empty());
+
+        prompt.push("web".to_string());
+        assert!(prompt.suggestions("").is_empty());
+    }
+
+    #[test]
+    fn test_missing_arguments_are_prompted_for_one_at_a_time() {
+        fn type_in(app: &mut App, text: &str) {
+            for c in text.chars() {
+                handle_key_event(app, KeyEvent::from(KeyCode::Char(c)));
+            }
+            handle_key_event(app, KeyEvent::from(KeyCode::Enter));
+        }
+        let label = |app: &App| app.command_prompt.as_ref().map(|prompt| prompt.label());
+
+        let mut app = test_app();
+        app.enter_command_mode();
+        type_in(&mut app, "send");
+        assert!(app.command_mode);
+        assert!(
+            app.last_command_result.is_none(),
+            "{:?}",
+            app.last_command_result
+        );
+        assert_eq!(label(&app).as_deref(), Some("argument 1 of 2"));
+        assert_eq!(app.command_input, "");
+        let screen = render_app(&app, 120, 30);
+        assert!(
+            screen.contains("/send <process> <text> · argument 1 of 2"),
+            "{}",
+            screen
+        );
+
+        // Nothing typed: still asking; history isn't offered in place of an argument
+        type_in(&mut app, "");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert_eq!(app.command_input, "");
+        type_in(&mut app, "web");
+        assert_eq!(label(&app).as_deref(), Some("argument 2 of 2"));
+        type_in(&mut app, "hello there");
+        assert!(app.command_prompt.is_none());
+        // A saved session has nothing to send to, but the command did run
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message))
+                    if message == "Can't send to a saved session's processes"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+        app.navigate_command_history_prev();
+        assert_eq!(app.command_input, "/send web \"hello there\"");
+
+        // Esc gives up on the whole command
+        app.enter_command_mode();
+        type_in(&mut app, "send web");
+        assert_eq!(label(&app).as_deref(), Some("argument 2 of 2"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.command_mode);
+        assert!(app.command_prompt.is_none());
+
+        // The command's hints narrow to what's typed, and Tab takes one
+        app.enter_command_mode();
+        type_in(&mut app, "view");
+        assert_eq!(app.command_suggestions.len(), 6);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('q')));
+        let hints: Vec<_> = app.command_suggestions.iter().map(|s| &s.text).collect();
+        assert_eq!(hints, ["query"]);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.command_input, "query");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.view_mode, ViewMode::QueryAnalysis);
+        assert!(!app.command_mode);
+    }
+}
@@ -7,6 +7,49 @@ pub trait CommandContext {}
 /// Result of command execution
 pub type CommandResult = Result<String, String>;
 
+/// Why [`CommandRegistry::execute`] didn't run a command, or how it failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// Fewer arguments than the command needs; the palette can ask for the rest
+    MissingArgs(MissingArgs),
+    /// Unknown command, too many arguments, or the command's own error
+    Failed(String),
+}
+
+/// A command run without all of its required arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingArgs {
+    /// Primary name, even when run by an alias
+    pub command: String,
+    pub usage: String,
+    /// The arguments it was given
+    pub args: Vec<String>,
+    /// How many it needs at least
+    pub needed: usize,
+    pub hints: Vec<String>,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::MissingArgs(missing) => write!(
+                f,
+                "Too few arguments. Expected at least {}, got {}.\nUsage: {}",
+                missing.needed,
+                missing.args.len(),
+                missing.usage
+            ),
+            CommandError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Failed(message)
+    }
+}
+
 /// Trait for implementing commands
 ///
 /// # Example
@@ -47,6 +90,18 @@ pub trait Command: Send + Sync {
         vec![]
     }
 
+    /// Whether the first argument is a process name, completed from the
+    /// running processes
+    fn completes_processes(&self) -> bool {
+        false
+    }
+
+    /// Whether the command takes everything after its name as one argument,
+    /// to split itself (`/run` splits it the way a shell would)
+    fn raw_args(&self) -> bool {
+        false
+    }
+
     /// Minimum number of arguments required
     fn min_args(&self) -> usize {
         0
@@ -73,15 +128,15 @@ pub trait Command: Send + Sync {
             ));
         }
 
-        if let Some(max) = self.max_args() {
-            if arg_count > max {
-                return Err(format!(
-                    "Too many arguments. Expected at most {}, got {}.\nUsage: {}",
-                    max,
-                    arg_count,
-                    self.usage()
-                ));
-            }
+        if let Some(max) = self.max_args()
+            && arg_count > max
+        {
+            return Err(format!(
+                "Too many arguments. Expected at most {}, got {}.\nUsage: {}",
+                max,
+                arg_count,
+                self.usage()
+            ));
         }
 
         Ok(())
@@ -154,28 +209,63 @@ impl CommandRegistry {
             return self.commands.get(primary_name).map(|cmd| cmd.as_ref());
         }
 
+        // Names are lowercase; `/Help` still finds `help`
+        let lower = name.to_lowercase();
+        if lower != name {
+            return self.find(&lower);
+        }
+
         None
     }
 
+    /// Closest command name or alias to an unknown one
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        let names = self.metadata.iter().map(|m| m.name.as_str());
+        let aliases = self
+            .metadata
+            .iter()
+            .flat_map(|m| m.aliases.iter().map(String::as_str));
+        super::autocomplete::closest_match(name, names.chain(aliases))
+    }
+
     /// Execute a command by name with arguments
+    ///
+    /// Too few arguments is [`CommandError::MissingArgs`], so the caller can
+    /// ask for them rather than fail.
     pub fn execute(
         &self,
         name: &str,
         args: Vec<String>,
         ctx: &mut dyn CommandContext,
-    ) -> CommandResult {
+    ) -> Result<String, CommandError> {
         match self.find(name) {
             Some(cmd) => {
+                if args.len() < cmd.min_args() {
+                    return Err(CommandError::MissingArgs(MissingArgs {
+                        command: cmd.name().to_string(),
+                        usage: cmd.usage().to_string(),
+                        args,
+                        needed: cmd.min_args(),
+                        hints: cmd.arg_hints().iter().map(|s| s.to_string()).collect(),
+                    }));
+                }
+
                 // Validate arguments
                 cmd.validate_args(&args)?;
 
                 // Execute command
-                cmd.execute(args, ctx)
+                Ok(cmd.execute(args, ctx)?)
             }
-            None => Err(format!(
-                "Unknown command: '{}'. Type /help for available commands.",
-                name
-            )),
+            None => Err(CommandError::Failed(match self.suggest(name) {
+                Some(suggestion) => format!(
+                    "Unknown command '{}'. Did you mean '{}'? (Tab to use it)",
+                    name, suggestion
+                ),
+                None => format!(
+                    "Unknown command: '{}'. Type /help for available commands.",
+                    name
+                ),
+            })),
         }
     }
 
@@ -249,4 +339,80 @@ mod tests {
         let result = registry.execute("unknown", vec![], &mut ctx);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_unknown_command_suggests_closest() {
+        let mut registry = CommandRegistry::new();
+        registry.register(Box::new(TestCommand));
+        let mut ctx = MockContext;
+
+        let err = registry.execute("tset", vec![], &mut ctx).unwrap_err();
+        assert_eq!(
+            err,
+            CommandError::Failed(
+                "Unknown command 'tset'. Did you mean 'test'? (Tab to use it)".to_string()
+            )
+        );
+        assert!(registry.execute("TEST", vec![], &mut ctx).is_ok());
+        assert_eq!(registry.suggest("/T"), Some("t"));
+        assert_eq!(registry.suggest("deploy"), None);
+    }
+
+    struct PairCommand;
+    impl Command for PairCommand {
+        fn name(&self) -> &str {
+            "pair"
+        }
+        fn aliases(&self) -> Vec<&str> {
+            vec!["p"]
+        }
+        fn description(&self) -> &str {
+            "Two arguments"
+        }
+        fn usage(&self) -> &str {
+            "/pair <left> <right>"
+        }
+        fn arg_hints(&self) -> Vec<&str> {
+            vec!["one", "two"]
+        }
+        fn min_args(&self) -> usize {
+            2
+        }
+        fn max_args(&self) -> Option<usize> {
+            Some(2)
+        }
+        fn execute(&self, args: Vec<String>, _ctx: &mut dyn CommandContext) -> CommandResult {
+            Ok(args.join("+"))
+        }
+    }
+
+    #[test]
+    fn test_missing_args_are_told_apart_from_other_errors() {
+        let mut registry = CommandRegistry::new();
+        registry.register(Box::new(PairCommand));
+        let mut ctx = MockContext;
+
+        let err = registry
+            .execute("p", vec!["a".to_string()], &mut ctx)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            CommandError::MissingArgs(MissingArgs {
+                command: "pair".to_string(),
+                usage: "/pair <left> <right>".to_string(),
+                args: vec!["a".to_string()],
+                needed: 2,
+                hints: vec!["one".to_string(), "two".to_string()],
+            })
+        );
+        assert!(err.to_string().starts_with("Too few arguments"));
+
+        let args = ["a", "b", "c"].map(String::from).to_vec();
+        assert!(matches!(
+            registry.execute("pair", args, &mut ctx),
+            Err(CommandError::Failed(_))
+        ));
+        let args = ["a", "b"].map(String::from).to_vec();
+        assert_eq!(registry.execute("pair", args, &mut ctx).unwrap(), "a+b");
+    }
 }
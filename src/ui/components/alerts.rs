@@ -0,0 +1,157 @@
+/// Alerts overlay - every `[alerts]` rule with its current value and state
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Clear, Paragraph, Row, Table},
+};
+use std::time::Instant;
+
+use crate::alerts::{AlertEngine, AlertRule, AlertSeverity, AlertState};
+use crate::ui::formatting::format_duration;
+use crate::ui::theme::Theme;
+
+/// Render the alerts overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Alerts ─────────────────────────────────────────────────────┐
+/// │ State        Severity  Rule                        Now      │
+/// │ FIRING 2m 5s critical  error_rate > 5% over 1m     8.3%     │
+/// │ ok           warning   p95_response_time > 800ms   412ms    │
+/// └─────────────────────────────────────────────────────────────┘
+/// ```
+pub fn render_alerts(f: &mut Frame, area: Rect, engine: &AlertEngine, fade_progress: Option<f32>) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let block = Theme::block("Alerts", fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if engine.is_empty() {
+        let hint = Paragraph::new(" No rules configured. Add them under [alerts] in .caboose.toml")
+            .style(Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade)));
+        f.render_widget(hint, inner);
+        return;
+    }
+
+    let header = Row::new(vec!["State", "Severity", "Rule", "Now"]).style(
+        Style::default()
+            .fg(Theme::apply_fade_to_color(Theme::text_muted(), fade))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let now = Instant::now();
+    let rows: Vec<Row> = engine
+        .statuses()
+        .map(|(rule, state)| {
+            let (status, style) = match state.firing_since {
+                Some(since) => (
+                    format!(
+                        "FIRING {}",
+                        format_duration(now.saturating_duration_since(since).as_secs())
+                    ),
+                    Style::default()
+                        .fg(Theme::apply_fade_to_color(
+                            severity_color(rule.severity),
+                            fade,
+                        ))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                None if state.reading.is_none() => (
+                    "no data".to_string(),
+                    Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade)),
+                ),
+                None => (
+                    "ok".to_string(),
+                    Style::default().fg(Theme::apply_fade_to_color(Theme::text_primary(), fade)),
+                ),
+            };
+
+            Row::new(vec![
+                Cell::from(status),
+                Cell::from(rule.severity.label()),
+                Cell::from(rule.to_string()),
+                Cell::from(current_value(rule, state)),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(14),
+            Constraint::Length(9),
+            Constraint::Min(24),
+            Constraint::Length(24),
+        ],
+    )
+    .header(header);
+
+    f.render_widget(table, inner);
+}
+
+pub fn severity_color(severity: AlertSeverity) -> ratatui::style::Color {
+    match severity {
+        AlertSeverity::Critical => Theme::danger(),
+        AlertSeverity::Warning => Theme::warning(),
+        AlertSeverity::Info => Theme::info(),
+    }
+}
+
+/// The last reading, with what it belongs to when the metric has a subject
+fn current_value(rule: &AlertRule, state: &AlertState) -> String {
+    match &state.reading {
+        Some(reading) => match &reading.subject {
+            Some(subject) => format!("{} {}", rule.metric.format_value(reading.value), subject),
+            None => rule.metric.format_value(reading.value),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// Centered area for the overlay, sized to fit `rows` rules
+pub fn calculate_alerts_area(full_area: Rect, rows: usize) -> Rect {
+    let width = full_area.width.min(90);
+    // Borders + header + one line per rule (at least one for the empty hint)
+    let height = (rows.max(1) as u16 + 3).min(full_area.height);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_alerts_area_is_centered() {
+        let area = Rect::new(0, 0, 100, 40);
+        let overlay = calculate_alerts_area(area, 3);
+
+        assert_eq!(overlay.width, 90);
+        assert_eq!(overlay.height, 6);
+        assert_eq!(overlay.x, 5);
+        assert_eq!(overlay.y, 17);
+    }
+
+    #[test]
+    fn test_calculate_alerts_area_without_rules_fits_hint() {
+        let area = Rect::new(0, 0, 60, 20);
+        let overlay = calculate_alerts_area(area, 0);
+
+        assert_eq!(overlay.width, 60);
+        assert_eq!(overlay.height, 4);
+    }
+}
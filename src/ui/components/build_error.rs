@@ -0,0 +1,191 @@
+/// Build error overlay - a frontend process's failed compile, parsed into the
+/// file, line, message and code frame
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+use crate::frontend::{BuildError, BuildFailure};
+use crate::ui::theme::Theme;
+
+/// Render the overlay for error `selected` of `failure`
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Build failed: frontend (Esc to close) ───────────────┐
+/// │ esbuild - error 1 of 2 (↑↓)                          │
+/// │ src/App.tsx:12:8                                     │
+/// │ Expected ";" but found "x"                           │
+/// │                                                      │
+/// │       12 │   let y x                                 │
+/// │          │         ^                                 │
+/// └──────────────────────────────────────────────────────┘
+/// ```
+pub fn render_build_error(
+    f: &mut Frame,
+    area: Rect,
+    failure: &BuildFailure,
+    selected: usize,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let title = format!("Build failed: {} (Esc to close)", failure.process);
+    let block = Theme::block(title, fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+
+    let Some(error) = failure.errors.get(selected) else {
+        f.render_widget(block, area);
+        return;
+    };
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let danger = Style::default().fg(Theme::apply_fade_to_color(Theme::danger(), fade));
+
+    let mut lines = vec![Line::styled(error_heading(failure, selected), muted)];
+    lines.push(Line::styled(
+        error
+            .location()
+            .unwrap_or_else(|| "(no file named)".to_string()),
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+    lines.push(Line::styled(error.message.clone(), danger));
+    if !error.code_frame.is_empty() {
+        lines.push(Line::default());
+        lines.extend(error.code_frame.iter().map(|line| {
+            let style = if is_marker_line(line) { danger } else { muted };
+            Line::from(Span::styled(line.clone(), style))
+        }));
+    }
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// `webpack - error 1 of 2 (↑↓)`, or just the tool for a single error
+pub fn error_heading(failure: &BuildFailure, selected: usize) -> String {
+    let tool = failure
+        .errors
+        .get(selected)
+        .map_or("build", |error: &BuildError| error.tool.label());
+    match failure.errors.len() {
+        0 | 1 => tool.to_string(),
+        count => format!("{} - error {} of {} (↑↓)", tool, selected + 1, count),
+    }
+}
+
+/// The frame line pointing at the error: `>` in front, or `^` / `~` under it
+fn is_marker_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with('>')
+        || line.contains('^')
+        || trimmed.trim_start_matches('│').trim().starts_with('~')
+}
+
+/// Centered area for the overlay
+pub fn calculate_build_error_area(full_area: Rect) -> Rect {
+    let width = full_area.width.min(100);
+    let height = full_area.height.min(20);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::{BuildErrors, FrontendLogParser};
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_heading_counts_errors_only_when_there_are_several() {
+        let mut errors = BuildErrors::new();
+        for line in [
+            "src/api.ts:7:3 - error TS2322: Type 'string' is not assignable to type 'number'.",
+            "src/App.tsx(12,8): error TS1005: ';' expected.",
+        ] {
+            errors.observe(
+                "frontend",
+                line,
+                FrontendLogParser::parse_line(line).as_ref(),
+            );
+        }
+        let failure = errors.latest().unwrap();
+        assert_eq!(error_heading(failure, 1), "tsc - error 2 of 2 (↑↓)");
+
+        let mut single = failure.clone();
+        single.errors.truncate(1);
+        assert_eq!(error_heading(&single, 0), "tsc");
+    }
+
+    #[test]
+    fn test_marker_lines_point_at_the_error() {
+        assert!(is_marker_line("> 12 |   let y x"));
+        assert!(is_marker_line("     |          ^"));
+        assert!(is_marker_line("    ~~~~~~~~~~~~~"));
+        assert!(!is_marker_line("  11 |    const a = 1"));
+    }
+
+    #[test]
+    fn test_build_error_overlay_opens_on_failure_and_closes_once_fixed() {
+        let mut app = test_app().with_frontend_dir(std::path::PathBuf::from("/shop/frontend"));
+        let frontend = |content: &str| LogLine::new("frontend", content);
+        for line in std::fs::read_to_string("tests/fixtures/build_errors/tsc_watch.log")
+            .unwrap()
+            .lines()
+        {
+            app.add_log(frontend(line));
+        }
+        assert!(app.show_build_error);
+
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Build failed: frontend"), "{}", screen);
+        assert!(screen.contains("error 1 of 2"), "{}", screen);
+        assert!(screen.contains("src/api.ts:7:3"), "{}", screen);
+        assert!(screen.contains("Open in $EDITOR"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('o')));
+        assert_eq!(
+            app.editor_request.take(),
+            Some(editor::EditorTarget {
+                path: std::path::PathBuf::from("/shop/frontend/src/App.tsx"),
+                line: Some(12),
+                column: Some(8),
+            })
+        );
+
+        // Dismissed, the failure stays in the footer and `/builderr` reopens it
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_build_error);
+        assert!(render_app(&app, 160, 40).contains("Build failed (/builderr)"));
+        app.command_input = "/builderr".to_string();
+        app.execute_command();
+        assert!(app.show_build_error);
+
+        app.add_log(frontend(
+            "[4:13:02 PM] Found 0 errors. Watching for file changes.",
+        ));
+        assert!(!app.show_build_error);
+        assert!(app.build_errors.latest().is_none());
+        app.command_input = "/builderr".to_string();
+        app.execute_command();
+        assert!(!app.show_build_error);
+    }
+}
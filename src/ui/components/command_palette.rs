@@ -7,6 +7,7 @@ use ratatui::{
     widgets::{List, ListItem, Paragraph},
 };
 
+use crate::ui::command::ArgPrompt;
 use crate::ui::command::autocomplete::Suggestion;
 use crate::ui::theme::{Icons, Theme};
 
@@ -23,10 +24,15 @@ use crate::ui::theme::{Icons, Theme};
 /// │ clear  - Clear search and filters│
 /// └─────────────────────────────────┘
 /// ```
+///
+/// While `prompt` asks for a missing argument, the input is that argument
+/// and the title shows the command's usage and which argument it is.
+#[allow(clippy::too_many_arguments)]
 pub fn render_command_palette(
     f: &mut Frame,
     area: Rect,
     input: &str,
+    prompt: Option<&ArgPrompt>,
     suggestions: &[Suggestion],
     selected_suggestion: usize,
     error: Option<&str>,
@@ -42,7 +48,7 @@ pub fn render_command_palette(
         .split(area);
 
     // Render input field
-    render_input(f, chunks[0], input, error, fade_progress);
+    render_input(f, chunks[0], input, prompt, error, fade_progress);
 
     // Render suggestions if available
     if !suggestions.is_empty() {
@@ -61,6 +67,7 @@ fn render_input(
     f: &mut Frame,
     area: Rect,
     input: &str,
+    prompt: Option<&ArgPrompt>,
     error: Option<&str>,
     fade_progress: Option<f32>,
 ) {
@@ -125,8 +132,11 @@ fn render_input(
         ])
     };
 
-    let block =
-        Theme::block(" Command ", fade_progress).border_style(Style::default().fg(border_color));
+    let title = match prompt {
+        Some(prompt) => format!(" {} · {} ", prompt.usage(), prompt.label()),
+        None => " Command ".to_string(),
+    };
+    let block = Theme::block(title, fade_progress).border_style(Style::default().fg(border_color));
 
     let paragraph = Paragraph::new(text).block(block);
 
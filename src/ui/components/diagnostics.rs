@@ -0,0 +1,239 @@
+/// Diagnostics overlay - item counts and memory estimates for Caboose's own stores
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::diagnostics::{FrameStats, StoreUsage, total_bytes};
+use crate::parser::ParseCoverage;
+use crate::ui::formatting::{format_bytes, format_ms, format_number};
+use crate::ui::theme::Theme;
+
+/// Render the diagnostics overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Diagnostics ────────────────────────────────────┐
+/// │ RSS 48.20 MB · tracked ~3.10 MB · keep 100      │
+/// │ Frame p95 4.0ms · max 18.0ms · input p95 1.0ms  │
+/// │ Parse coverage 83% · 246 of 1,480 lines unparsed│
+/// │                                                 │
+/// │ Store                Items   Limit  Approx size │
+/// │ > Log buffer         1,000   1,000    212.40 KB │
+/// │   Completed requests   100     100      1.20 MB │
+/// │                                                 │
+/// │ Unparsed lines, most frequent                   │
+/// │     12x [worker] Sidekiq 0.0.0 connecting to ...│
+/// └─────────────────────────────────────────────────┘
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn render_diagnostics(
+    f: &mut Frame,
+    area: Rect,
+    usage: &[StoreUsage],
+    selected: usize,
+    keep: usize,
+    rss: Option<u64>,
+    frames: FrameStats,
+    coverage: &ParseCoverage,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let block = Theme::block("Diagnostics", fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Length(usage.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    let rss_text = rss.map_or_else(|| "unavailable".to_string(), format_bytes);
+    let summary = Line::from(vec![
+        Span::styled(
+            format!(" RSS {}", rss_text),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "  ·  tracked ~{}  ·  trim keeps last {}",
+                format_bytes(total_bytes(usage) as u64),
+                keep
+            ),
+            Style::default().fg(Theme::apply_fade_to_color(Theme::text_secondary(), fade)),
+        ),
+    ]);
+    let ms = |d: std::time::Duration| format_ms(d.as_secs_f64() * 1000.0);
+    let frame_line = Line::styled(
+        format!(
+            " Frame p95 {}  ·  max {}  ·  input p95 {}  ·  max {}",
+            ms(frames.draw_p95),
+            ms(frames.draw_max),
+            ms(frames.input_p95),
+            ms(frames.input_max)
+        ),
+        Style::default().fg(Theme::apply_fade_to_color(Theme::text_secondary(), fade)),
+    );
+    let coverage_line = match coverage.percent() {
+        Some(percent) => Line::from(vec![
+            Span::styled(
+                format!(" Parse coverage {}%", percent),
+                Style::default()
+                    .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "  ·  {} of {} lines unparsed",
+                    format_number(coverage.unparsed() as usize),
+                    format_number(coverage.lines() as usize)
+                ),
+                Style::default().fg(Theme::apply_fade_to_color(Theme::text_secondary(), fade)),
+            ),
+        ]),
+        None => Line::styled(
+            " Parse coverage: no lines yet",
+            Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade)),
+        ),
+    };
+    f.render_widget(
+        Paragraph::new(vec![summary, frame_line, coverage_line]),
+        chunks[0],
+    );
+
+    let header = Row::new(vec!["  Store", "Items", "Limit", "Approx size"]).style(
+        Style::default()
+            .fg(Theme::apply_fade_to_color(Theme::text_muted(), fade))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows: Vec<Row> = usage
+        .iter()
+        .enumerate()
+        .map(|(i, store)| {
+            let marker = if i == selected { "> " } else { "  " };
+            let style = if i == selected {
+                Style::default()
+                    .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+                    .add_modifier(Modifier::BOLD)
+            } else if store.kind.is_trimmable() {
+                Style::default().fg(Theme::apply_fade_to_color(Theme::text_primary(), fade))
+            } else {
+                Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade))
+            };
+
+            Row::new(vec![
+                Cell::from(format!("{}{}", marker, store.kind.label())),
+                Cell::from(format_number(store.items)),
+                Cell::from(store.limit.map_or_else(|| "-".to_string(), format_number)),
+                Cell::from(format_bytes(store.bytes as u64)),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(24),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header);
+
+    f.render_widget(table, chunks[1]);
+
+    let samples = coverage.samples();
+    if samples.is_empty() {
+        return;
+    }
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let mut lines = vec![
+        Line::default(),
+        Line::styled(
+            "  Unparsed lines, most frequent",
+            muted.add_modifier(Modifier::BOLD),
+        ),
+    ];
+    lines.extend(samples.iter().take(UNPARSED_SHOWN).map(|sample| {
+        Line::from(vec![
+            Span::styled(format!("  {:>5}x ", sample.count), muted),
+            Span::styled(
+                format!("[{}] {}", sample.process, sample.shape),
+                Style::default().fg(Theme::apply_fade_to_color(Theme::text_secondary(), fade)),
+            ),
+        ])
+    }));
+    f.render_widget(Paragraph::new(lines), chunks[2]);
+}
+
+/// Unparsed line shapes listed under the stores
+pub const UNPARSED_SHOWN: usize = 5;
+
+/// Centered area for the overlay, sized to fit `rows` stores and `samples`
+/// unparsed line shapes
+pub fn calculate_diagnostics_area(full_area: Rect, rows: usize, samples: usize) -> Rect {
+    let width = full_area.width.min(70);
+    // Borders + summary + frame times + coverage + header + one line per store
+    let samples = samples.min(UNPARSED_SHOWN);
+    let sample_lines = if samples > 0 { samples + 2 } else { 0 };
+    let height = (rows as u16 + 7 + sample_lines as u16).min(full_area.height);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_diagnostics_area_is_centered() {
+        let area = Rect::new(0, 0, 100, 40);
+        let overlay = calculate_diagnostics_area(area, 9, 0);
+
+        assert_eq!(overlay.width, 70);
+        assert_eq!(overlay.height, 16);
+        assert_eq!(overlay.x, 15);
+        assert_eq!(overlay.y, 12);
+    }
+
+    #[test]
+    fn test_calculate_diagnostics_area_fits_a_few_unparsed_samples() {
+        let area = Rect::new(0, 0, 100, 40);
+
+        assert_eq!(calculate_diagnostics_area(area, 9, 2).height, 20);
+        assert_eq!(calculate_diagnostics_area(area, 9, 50).height, 23);
+    }
+
+    #[test]
+    fn test_calculate_diagnostics_area_fits_small_terminal() {
+        let area = Rect::new(0, 0, 40, 8);
+        let overlay = calculate_diagnostics_area(area, 9, 0);
+
+        assert_eq!(overlay.width, 40);
+        assert_eq!(overlay.height, 8);
+    }
+}
@@ -0,0 +1,165 @@
+//! Empty state - what a view is waiting for before its first data arrives
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+use crate::ui::theme::Theme;
+
+/// A centered icon, title, hint lines and tip inside the view's block
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Query Analysis ──────────────────────────────────────────────┐
+/// │                                                              │
+/// │                            [sql]                             │
+/// │                        No requests yet                       │
+/// │                                                              │
+/// │ Waiting for Rails request logs - make an HTTP request to app │
+/// │                                                              │
+/// │        Tip: requests only show if Rails logs to STDOUT       │
+/// │                                                              │
+/// └──────────────────────────────────────────────────────────────┘
+/// ```
+pub struct EmptyState {
+    icon: String,
+    title: String,
+    hints: Vec<String>,
+    tip: Option<String>,
+}
+
+impl EmptyState {
+    pub fn new(icon: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            icon: icon.into(),
+            title: title.into(),
+            hints: Vec::new(),
+            tip: None,
+        }
+    }
+
+    /// What the view is waiting for, one line per call
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
+    /// Something to try, shown last as "Tip: ..."
+    pub fn with_tip(mut self, tip: impl Into<String>) -> Self {
+        self.tip = Some(tip.into());
+        self
+    }
+
+    /// The lines, before centering
+    pub fn lines(&self, fade: f32) -> Vec<Line<'static>> {
+        let color = |color| Theme::apply_fade_to_color(color, fade);
+        let mut lines = vec![
+            Line::styled(
+                self.icon.clone(),
+                Style::default().fg(color(Theme::primary())),
+            ),
+            Line::styled(
+                self.title.clone(),
+                Style::default()
+                    .fg(color(Theme::text_primary()))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if !self.hints.is_empty() {
+            lines.push(Line::default());
+            lines.extend(self.hints.iter().map(|hint| {
+                Line::styled(
+                    hint.clone(),
+                    Style::default().fg(color(Theme::text_secondary())),
+                )
+            }));
+        }
+        if let Some(tip) = &self.tip {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!("Tip: {}", tip),
+                Style::default().fg(color(Theme::info())),
+            ));
+        }
+        lines
+    }
+
+    /// Render in a block titled `title`, centered both ways
+    pub fn render(&self, f: &mut Frame, area: Rect, title: &str, fade_progress: Option<f32>) {
+        f.render_widget(Clear, area);
+        let block = Theme::block(title, fade_progress);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let lines = self.lines(fade_progress.unwrap_or(1.0));
+        let top = inner
+            .height
+            .saturating_sub(wrapped_height(&lines, inner.width))
+            / 2;
+        let body = Rect::new(inner.x, inner.y + top, inner.width, inner.height - top);
+        f.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            body,
+        );
+    }
+}
+
+/// Rows `lines` take once wrapped to `width`
+fn wrapped_height(lines: &[Line], width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(width) as u16)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn test_empty_state_is_centered_in_its_block() {
+        let state = EmptyState::new("[db]", "No queries yet")
+            .with_hint("Waiting for SQL in the Rails logs")
+            .with_tip("make a request");
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        terminal
+            .draw(|f| state.render(f, f.area(), "Database Health", None))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..12)
+            .map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        let text = |row: usize| rows[row].trim_matches(['│', ' ']).to_string();
+
+        assert!(rows[0].contains("Database Health"), "{:#?}", rows);
+        // 6 lines in 10 inner rows: two blank rows above
+        assert_eq!(text(2), "", "{:#?}", rows);
+        assert_eq!(text(3), "[db]");
+        assert_eq!(text(4), "No queries yet");
+        assert_eq!(text(6), "Waiting for SQL in the Rails logs");
+        assert_eq!(text(8), "Tip: make a request");
+
+        // As much space on each side, give or take a column
+        let title: Vec<char> = rows[4].chars().collect();
+        let left = title.iter().position(|&c| c == 'N').unwrap() - 1;
+        let right = title
+            .iter()
+            .rev()
+            .skip(1)
+            .take_while(|&&c| c == ' ')
+            .count();
+        assert!(left.abs_diff(right) <= 1, "{:?}", rows[4]);
+    }
+}
@@ -1,15 +1,29 @@
 /// Footer component builder for consistent keyboard shortcut display
 use ratatui::{
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::ui::theme::{Icons, Theme};
 
+/// How readily a footer hint is dropped when the terminal is too narrow
+///
+/// Lower priorities go first; `Essential` hints only go once nothing else is
+/// left to drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+    Essential,
+}
+
 /// Represents a keyboard shortcut
 pub struct KeyBinding {
     pub key: String,
     pub description: String,
+    pub priority: Priority,
 }
 
 impl KeyBinding {
@@ -17,13 +31,39 @@ impl KeyBinding {
         Self {
             key: key.into(),
             description: description.into(),
+            priority: Priority::Normal,
         }
     }
+
+    fn description_text(&self) -> String {
+        format!(" {} {}", Icons::SEPARATOR, self.description)
+    }
+
+    /// Display columns taken by the key and description
+    fn width(&self) -> usize {
+        self.key.width() + self.description_text().width()
+    }
+}
+
+/// Right-aligned state shown after the hints, e.g. auto-scroll off
+pub struct StatusSegment {
+    pub text: String,
+    pub color: Color,
 }
 
+/// Before the first hint
+const LEADING: &str = "  ";
+/// Between hints
+const GAP: &str = "   ";
+/// Between status segments, and after the last one
+const STATUS_GAP: &str = "  ";
+/// Shown when hints were dropped to fit
+pub const MORE_HINT: &str = "… ?:more";
+
 /// Builder for creating consistent footers with keyboard shortcuts
 pub struct FooterBuilder {
     bindings: Vec<KeyBinding>,
+    statuses: Vec<StatusSegment>,
 }
 
 impl FooterBuilder {
@@ -31,18 +71,40 @@ impl FooterBuilder {
     pub fn new() -> Self {
         Self {
             bindings: Vec::new(),
+            statuses: Vec::new(),
         }
     }
 
     /// Add a key binding
-    pub fn add_binding(mut self, key: impl Into<String>, description: impl Into<String>) -> Self {
-        self.bindings.push(KeyBinding::new(key, description));
+    pub fn add_binding(self, key: impl Into<String>, description: impl Into<String>) -> Self {
+        self.add_binding_with_priority(key, description, Priority::Normal)
+    }
+
+    /// Add a key binding that is dropped according to `priority` when space runs out
+    pub fn add_binding_with_priority(
+        mut self,
+        key: impl Into<String>,
+        description: impl Into<String>,
+        priority: Priority,
+    ) -> Self {
+        let mut binding = KeyBinding::new(key, description);
+        binding.priority = priority;
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Add a right-aligned status segment
+    pub fn add_status(mut self, text: impl Into<String>, color: Color) -> Self {
+        self.statuses.push(StatusSegment {
+            text: text.into(),
+            color,
+        });
         self
     }
 
     /// Add standard quit binding
     pub fn with_quit(self) -> Self {
-        self.add_binding("q", "Quit")
+        self.add_binding_with_priority("q", "Quit", Priority::Essential)
     }
 
     /// Add standard toggle binding
@@ -52,7 +114,7 @@ impl FooterBuilder {
 
     /// Add standard escape binding
     pub fn with_escape(self) -> Self {
-        self.add_binding("Esc", "Back")
+        self.add_binding_with_priority("Esc", "Back", Priority::Essential)
     }
 
     /// Add standard navigation bindings
@@ -65,13 +127,84 @@ impl FooterBuilder {
         self.add_binding("/", "Search")
     }
 
-    /// Build the footer line
+    /// Build the footer line with every hint, status segments last
     pub fn build(self) -> Line<'static> {
-        let mut spans = vec![Span::raw("  ")];
+        let kept: Vec<usize> = (0..self.bindings.len()).collect();
+        let pad = if self.statuses.is_empty() {
+            0
+        } else {
+            GAP.width()
+        };
+        self.line(&kept, false, self.statuses.len(), pad)
+    }
+
+    /// Build the footer line to fit in `width` columns
+    ///
+    /// Hints are dropped whole, lowest priority first (the last added among
+    /// equals), and a "… ?:more" hint is added when any were. Status segments
+    /// are right-aligned and only dropped once no hint but essential ones is left.
+    pub fn build_for_width(self, width: usize) -> Line<'static> {
+        let mut kept: Vec<usize> = (0..self.bindings.len()).collect();
+        let mut statuses = self.statuses.len();
+        let mut more = false;
+
+        while self.used_width(&kept, more, statuses) > width {
+            let droppable = kept
+                .iter()
+                .enumerate()
+                .filter(|(_, index)| self.bindings[**index].priority < Priority::Essential)
+                .min_by_key(|(position, index)| {
+                    (
+                        self.bindings[**index].priority,
+                        std::cmp::Reverse(*position),
+                    )
+                })
+                .map(|(position, _)| position);
+
+            if let Some(position) = droppable {
+                kept.remove(position);
+                more = true;
+            } else if statuses > 0 {
+                statuses -= 1;
+            } else if !kept.is_empty() {
+                kept.pop();
+                more = true;
+            } else {
+                // Not even the hint fits
+                more = false;
+                break;
+            }
+        }
+
+        let pad = width.saturating_sub(self.used_width(&kept, more, statuses));
+        let pad = if statuses > 0 { pad } else { 0 };
+        self.line(&kept, more, statuses, pad)
+    }
+
+    /// Columns used by the kept hints and the first `statuses` segments
+    fn used_width(&self, kept: &[usize], more: bool, statuses: usize) -> usize {
+        let hints: usize = kept.iter().map(|index| self.bindings[*index].width()).sum();
+        let gaps = kept.len().saturating_sub(1) * GAP.width();
+        let more = if more {
+            let gap = if kept.is_empty() { 0 } else { GAP.width() };
+            gap + MORE_HINT.width()
+        } else {
+            0
+        };
+        let status: usize = self.statuses[..statuses]
+            .iter()
+            .map(|status| status.text.width() + STATUS_GAP.width())
+            .sum();
+        LEADING.width() + hints + gaps + more + status
+    }
+
+    fn line(&self, kept: &[usize], more: bool, statuses: usize, pad: usize) -> Line<'static> {
+        let mut spans = vec![Span::raw(LEADING)];
 
-        for (idx, binding) in self.bindings.iter().enumerate() {
-            if idx > 0 {
-                spans.push(Span::raw("   "));
+        for (position, index) in kept.iter().enumerate() {
+            let binding = &self.bindings[*index];
+            if position > 0 {
+                spans.push(Span::raw(GAP));
             }
 
             spans.push(Span::styled(
@@ -82,11 +215,34 @@ impl FooterBuilder {
             ));
 
             spans.push(Span::styled(
-                format!(" {} {}", Icons::SEPARATOR, binding.description),
+                binding.description_text(),
                 Style::default().fg(Theme::text_secondary()),
             ));
         }
 
+        if more {
+            if !kept.is_empty() {
+                spans.push(Span::raw(GAP));
+            }
+            spans.push(Span::styled(
+                MORE_HINT,
+                Style::default().fg(Theme::text_muted()),
+            ));
+        }
+
+        if statuses > 0 {
+            spans.push(Span::raw(" ".repeat(pad)));
+            for status in &self.statuses[..statuses] {
+                spans.push(Span::styled(
+                    status.text.clone(),
+                    Style::default()
+                        .fg(status.color)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                spans.push(Span::raw(STATUS_GAP));
+            }
+        }
+
         Line::from(spans)
     }
 }
@@ -120,14 +276,16 @@ pub fn navigation_footer() -> Line<'static> {
 pub fn search_footer() -> Line<'static> {
     FooterBuilder::new()
         .add_binding("Type to search", "")
-        .add_binding("Esc", "Cancel")
-        .add_binding("Enter", "Apply")
+        .add_binding_with_priority("Esc", "Cancel", Priority::Essential)
+        .add_binding_with_priority("Enter", "Apply", Priority::Essential)
         .build()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
 
     #[test]
     fn test_footer_builder() {
@@ -147,4 +305,122 @@ mod tests {
         let footer = navigation_footer();
         assert!(!footer.spans.is_empty());
     }
+
+    fn text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    fn footer() -> FooterBuilder {
+        FooterBuilder::new()
+            .with_quit()
+            .add_binding_with_priority(":", "Command", Priority::High)
+            .add_binding("t/T", "Tab ←→")
+            .add_binding_with_priority("↑↓", "V-Scroll", Priority::Low)
+            .add_binding_with_priority("e", "Export", Priority::Low)
+            .add_binding_with_priority("End", "Follow", Priority::High)
+            .add_status("Auto-scroll OFF", Color::Yellow)
+    }
+
+    #[test]
+    fn test_footer_fits_every_width_without_cutting_a_hint() {
+        let hints = [
+            "q | Quit",
+            ": | Command",
+            "t/T | Tab ←→",
+            "↑↓ | V-Scroll",
+            "e | Export",
+            "End | Follow",
+        ];
+        for width in [10, 20, 30, 40, 50, 60, 80, 120] {
+            let line = footer().build_for_width(width);
+            let shown = text(&line);
+            assert!(
+                line.width() <= width,
+                "{} > {}: {:?}",
+                line.width(),
+                width,
+                shown
+            );
+
+            // Whatever is left of a hint is the whole hint
+            for hint in hints {
+                let key = hint.split(" |").next().unwrap();
+                if shown.contains(&format!("{} | ", key)) {
+                    assert!(shown.contains(hint), "{:?} cut in {:?}", hint, shown);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_footer_drops_lowest_priority_first_and_points_at_help() {
+        let wide = text(&footer().build_for_width(120));
+        assert!(!wide.contains(MORE_HINT));
+        assert!(wide.ends_with("Auto-scroll OFF  "), "{:?}", wide);
+        assert_eq!(wide.width(), 120);
+
+        // Room for all but the two low-priority hints: the later one goes first
+        let line = footer().build_for_width(99);
+        let shown = text(&line);
+        assert!(shown.contains("↑↓ | V-Scroll"), "{:?}", shown);
+        assert!(!shown.contains("e | Export"), "{:?}", shown);
+        assert!(shown.contains(MORE_HINT));
+
+        let shown = text(&footer().build_for_width(67));
+        assert!(
+            !shown.contains("V-Scroll") && !shown.contains("Tab"),
+            "{:?}",
+            shown
+        );
+        assert!(shown.contains(": | Command") && shown.contains("End | Follow"));
+        assert!(shown.contains("Auto-scroll OFF"));
+
+        // Essentials outlast the status segment
+        let shown = text(&footer().build_for_width(22));
+        assert!(shown.contains("q | Quit"), "{:?}", shown);
+        assert!(!shown.contains("Auto-scroll"), "{:?}", shown);
+    }
+
+    #[test]
+    fn test_footer_adapts_to_terminal_width() {
+        let mut app = test_app();
+        app.scroll_up();
+        assert!(!app.auto_scroll);
+        let footer = |app: &App, width| {
+            render_app(app, width, 30)
+                .lines()
+                .last()
+                .unwrap()
+                .trim_end()
+                .to_string()
+        };
+
+        let wide = footer(&app, 200);
+        assert!(wide.contains("e | Export"), "{}", wide);
+        assert!(wide.contains("⏸ Auto-scroll OFF"), "{}", wide);
+        assert!(!wide.contains("?:more"), "{}", wide);
+
+        // Scroll hints go before search and resuming auto-scroll
+        let narrow = footer(&app, 90);
+        assert!(!narrow.contains("H-Scroll"), "{}", narrow);
+        assert!(narrow.contains("/ | Search"), "{}", narrow);
+        assert!(narrow.contains("End | Follow"), "{}", narrow);
+        assert!(narrow.contains("… ?:more"), "{}", narrow);
+
+        let tiny = footer(&app, 30);
+        assert!(tiny.contains("q | Quit"), "{}", tiny);
+        assert!(tiny.contains("?:more"), "{}", tiny);
+        assert!(!tiny.contains(" | S"), "{}", tiny);
+
+        // ? opens the overlay the hint points at
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('?')));
+        let screen = render_app(&app, 120, 50);
+        assert!(screen.contains("Help (Esc to close)"), "{}", screen);
+        assert!(screen.contains("/alerts"), "{}", screen);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_help);
+    }
 }
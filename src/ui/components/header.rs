@@ -134,7 +134,7 @@ mod tests {
             .build();
 
         assert!(!header.is_empty());
-        assert!(header.len() >= 1);
+        assert!(!header.is_empty());
     }
 
     #[test]
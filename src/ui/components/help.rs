@@ -0,0 +1,162 @@
+/// Help overlay - every key binding and command, including those the footer
+/// had no room for
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::ui::command::registry::CommandMetadata;
+use crate::ui::theme::Theme;
+
+/// Keys as `(key, action)`, in the order they're listed
+pub const KEY_HELP: &[(&str, &str)] = &[
+    ("q", "Quit"),
+    (":", "Command palette"),
+    ("t / T", "Next / previous tab"),
+    ("/", "Search the current view"),
+    ("re: Ctrl+I", "Logs search: regex / match case"),
+    ("c", "Clear filters and search"),
+    ("↑↓", "Scroll logs / select"),
+    ("←→ Home", "Scroll logs sideways / back to column 0"),
+    ("PageUp PageDown", "Scroll logs / request timeline a page"),
+    ("End", "Follow new log lines (auto-scroll)"),
+    ("o", "Logs newest or oldest first"),
+    (
+        "1-5",
+        "Logs: show / hide SQL, HTTP, errors, frontend, other",
+    ),
+    ("e", "Export logs / extra request fields"),
+    ("Ctrl+E", "Export logs as JSON lines"),
+    (
+        "S",
+        "Export a request's SQL (all listed from Query Analysis)",
+    ),
+    ("p", "Process panel (compact layout)"),
+    ("Tab", "Logs: select processes in the panel (j/k)"),
+    ("s r S", "Selected process: stop / restart / start"),
+    ("s", "Process status"),
+    ("o ↑↓", "Build error: open the file in $EDITOR / next error"),
+    ("P", "Privacy mode: mask personal data"),
+    (
+        "E d v h",
+        "Exceptions; there: dismiss / min severity / recent only",
+    ),
+    ("+ - ←→", "Timeline: zoom / move between buckets"),
+    ("< > 1-9", "Sort exceptions / slow queries by column"),
+    ("n", "Query Analysis: collapse / expand N+1 queries"),
+    ("Enter", "Open the selected item / show a test failure"),
+    (
+        "Mouse",
+        "Click a tab or row, double-click to open, wheel to scroll",
+    ),
+    ("Esc", "Back / close"),
+    ("?", "This help"),
+];
+
+/// Width of the key column
+const KEY_COLUMN: usize = 18;
+
+/// Render the help overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Help (Esc to close) ─────────────────────────────┐
+/// │ Keys                                             │
+/// │   q                 Quit                         │
+/// │   ...                                            │
+/// │ Commands                                         │
+/// │   /filter <process> Filter by process (f)        │
+/// └──────────────────────────────────────────────────┘
+/// ```
+pub fn render_help(
+    f: &mut Frame,
+    area: Rect,
+    commands: &[CommandMetadata],
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let heading = Style::default()
+        .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+        .add_modifier(Modifier::BOLD);
+    let key_style = Style::default()
+        .fg(Theme::apply_fade_to_color(Theme::text_primary(), fade))
+        .add_modifier(Modifier::BOLD);
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+
+    let row = |key: &str, action: String, extra: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<width$} ", key, width = KEY_COLUMN), key_style),
+            Span::raw(action),
+            Span::styled(extra, muted),
+        ])
+    };
+
+    let mut lines = vec![Line::from(Span::styled("Keys", heading))];
+    lines.extend(
+        KEY_HELP
+            .iter()
+            .map(|(key, action)| row(key, action.to_string(), String::new())),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Commands", heading)));
+    lines.extend(commands.iter().map(|command| {
+        let aliases = if command.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", command.aliases.join(", "))
+        };
+        row(&command.usage, command.description.clone(), aliases)
+    }));
+
+    let block = Theme::block("Help (Esc to close)", fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Centered area for the overlay, tall enough for `commands` commands
+pub fn calculate_help_area(full_area: Rect, commands: usize) -> Rect {
+    let width = full_area.width.min(80);
+    // Borders + two headings + a blank line between the sections
+    let height = ((KEY_HELP.len() + commands + 5) as u16).min(full_area.height);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_help_area_fits_keys_and_commands() {
+        let area = Rect::new(0, 0, 120, 60);
+        let overlay = calculate_help_area(area, 10);
+
+        assert_eq!(overlay.width, 80);
+        assert_eq!(overlay.height, KEY_HELP.len() as u16 + 15);
+        assert_eq!(overlay.x, 20);
+    }
+
+    #[test]
+    fn test_calculate_help_area_is_clamped_to_the_screen() {
+        let area = Rect::new(0, 0, 60, 20);
+        let overlay = calculate_help_area(area, 10);
+
+        assert_eq!(overlay.width, 60);
+        assert_eq!(overlay.height, 20);
+        assert_eq!(overlay.y, 0);
+    }
+}
@@ -0,0 +1,184 @@
+/// Log chips - one-key category filters above the Logs view, with live counts
+///
+/// Counts cover every line in the buffer, hidden or not, so a chip still shows
+/// how many SQL lines arrived while SQL is switched off.
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::process::{LogCategory, LogLine};
+use crate::ui::theme::Theme;
+
+#[derive(Debug, Clone, Default)]
+pub struct LogChips {
+    hidden: [bool; LogCategory::ALL.len()],
+    counts: [usize; LogCategory::ALL.len()],
+}
+
+impl LogChips {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show or hide chip `index` (0-based, key `index + 1`), returning the
+    /// category it toggled
+    pub fn toggle(&mut self, index: usize) -> Option<LogCategory> {
+        let category = *LogCategory::ALL.get(index)?;
+        self.hidden[index] = !self.hidden[index];
+        Some(category)
+    }
+
+    pub fn hides(&self, category: LogCategory) -> bool {
+        self.hidden[category.index()]
+    }
+
+    /// Categories switched off, in chip order
+    pub fn hidden(&self) -> Vec<LogCategory> {
+        LogCategory::ALL
+            .into_iter()
+            .filter(|category| self.hides(*category))
+            .collect()
+    }
+
+    pub fn set_hidden(&mut self, hidden: &[LogCategory]) {
+        self.hidden = [false; LogCategory::ALL.len()];
+        for category in hidden {
+            self.hidden[category.index()] = true;
+        }
+    }
+
+    /// Whether any chip is switched off
+    pub fn is_filtering(&self) -> bool {
+        self.hidden.contains(&true)
+    }
+
+    /// Show every category again
+    pub fn show_all(&mut self) {
+        self.hidden = [false; LogCategory::ALL.len()];
+    }
+
+    pub fn count(&mut self, category: LogCategory) {
+        self.counts[category.index()] += 1;
+    }
+
+    /// Forget a line evicted from the buffer
+    pub fn uncount(&mut self, category: LogCategory) {
+        let count = &mut self.counts[category.index()];
+        *count = count.saturating_sub(1);
+    }
+
+    /// Count `logs` from scratch, for a replaced buffer
+    pub fn recount<'a>(&mut self, logs: impl IntoIterator<Item = &'a LogLine>) {
+        self.counts = [0; LogCategory::ALL.len()];
+        for log in logs {
+            self.count(log.category);
+        }
+    }
+
+    pub fn count_of(&self, category: LogCategory) -> usize {
+        self.counts[category.index()]
+    }
+
+    /// `Hiding: SQL, Other` for the footer, if anything is hidden
+    pub fn status(&self) -> Option<String> {
+        let hidden = self.hidden();
+        (!hidden.is_empty()).then(|| {
+            let labels: Vec<_> = hidden.iter().map(|category| category.label()).collect();
+            format!("Hiding: {}", labels.join(", "))
+        })
+    }
+
+    /// The chip bar, `1 SQL 12  2 HTTP 4 ...`, hidden chips struck out
+    pub fn line(&self) -> Line<'static> {
+        let mut spans = Vec::new();
+        for (index, category) in LogCategory::ALL.into_iter().enumerate() {
+            let hidden = self.hides(category);
+            let chip = if hidden {
+                Style::default()
+                    .fg(Theme::text_muted())
+                    .add_modifier(Modifier::CROSSED_OUT)
+            } else {
+                Style::default()
+                    .fg(chip_color(category))
+                    .add_modifier(Modifier::BOLD)
+            };
+            spans.push(Span::styled(
+                format!(" {} ", index + 1),
+                Style::default().fg(Theme::text_muted()),
+            ));
+            spans.push(Span::styled(category.label(), chip));
+            spans.push(Span::styled(
+                format!(" {} ", self.count_of(category)),
+                Style::default().fg(if hidden {
+                    Theme::text_muted()
+                } else {
+                    Theme::text_secondary()
+                }),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        f.render_widget(Paragraph::new(self.line()), area);
+    }
+}
+
+fn chip_color(category: LogCategory) -> ratatui::style::Color {
+    match category {
+        LogCategory::Sql => Theme::info(),
+        LogCategory::Http => Theme::primary(),
+        LogCategory::Error => Theme::danger(),
+        LogCategory::Frontend => Theme::accent(),
+        LogCategory::Other => Theme::text_primary(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_chips_still_count() {
+        let mut chips = LogChips::new();
+        chips.count(LogCategory::Sql);
+        chips.count(LogCategory::Sql);
+        chips.count(LogCategory::Error);
+
+        assert_eq!(chips.toggle(0), Some(LogCategory::Sql));
+        assert!(chips.hides(LogCategory::Sql));
+        chips.count(LogCategory::Sql);
+        assert_eq!(chips.count_of(LogCategory::Sql), 3);
+        assert_eq!(chips.status().as_deref(), Some("Hiding: SQL"));
+
+        chips.uncount(LogCategory::Error);
+        chips.uncount(LogCategory::Error);
+        assert_eq!(chips.count_of(LogCategory::Error), 0);
+
+        assert_eq!(chips.toggle(5), None);
+        chips.show_all();
+        assert!(!chips.is_filtering());
+        assert_eq!(chips.status(), None);
+    }
+
+    #[test]
+    fn test_chip_line_lists_every_category_with_its_key() {
+        let mut chips = LogChips::new();
+        chips.count(LogCategory::Http);
+        let text: String = chips
+            .line()
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+
+        assert_eq!(
+            text,
+            " 1 SQL 0  2 HTTP 1  3 Errors 0  4 Frontend 0  5 Other 0 "
+        );
+    }
+}
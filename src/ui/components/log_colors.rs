@@ -0,0 +1,166 @@
+/// Log colors - a line's content as spans, in Caboose's colors or the tool's own
+///
+/// Colors and hyperlinks come from the escapes stripped when the line was
+/// read (`LogLine::markup`). Links are underlined either way.
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::process::{AnsiColor, AnsiStyle, LogLine};
+use crate::ui::theme::Theme;
+
+/// Whose colors the Logs view uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogColors {
+    /// Recolor lines by what they contain (SQL, errors, completions)
+    #[default]
+    #[serde(rename = "theme")]
+    Themed,
+    /// Keep the colors the process printed; uncolored lines are themed
+    #[serde(rename = "tool")]
+    Tool,
+}
+
+fn color(color: AnsiColor) -> Color {
+    match color {
+        AnsiColor::Indexed(index) => Color::Indexed(index),
+        AnsiColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+fn tool_style(ansi: &AnsiStyle) -> Style {
+    let mut style = Style::default();
+    if let Some(fg) = ansi.fg {
+        style = style.fg(color(fg));
+    }
+    if let Some(bg) = ansi.bg {
+        style = style.bg(color(bg));
+    }
+    for (on, modifier) in [
+        (ansi.bold, Modifier::BOLD),
+        (ansi.dim, Modifier::DIM),
+        (ansi.italic, Modifier::ITALIC),
+        (ansi.underline, Modifier::UNDERLINED),
+    ] {
+        if on {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+/// Spans for `log`'s content; `themed` is the style Caboose would give it
+pub fn content_spans(log: &LogLine, themed: Style, colors: LogColors) -> Vec<Span<'static>> {
+    let content = log.content.as_str();
+    let Some(markup) = log.markup.as_deref() else {
+        return vec![Span::styled(content.to_string(), themed)];
+    };
+    let use_tool_colors = colors == LogColors::Tool && !markup.styles.is_empty();
+    let base = if use_tool_colors {
+        Style::default()
+    } else {
+        themed
+    };
+
+    // Cut wherever a run or link starts or ends; offsets that don't land on
+    // a character (e.g. from a hand-edited session file) are ignored
+    let mut cuts: Vec<usize> = markup
+        .styles
+        .iter()
+        .flat_map(|run| [run.start, run.end])
+        .chain(markup.links.iter().flat_map(|link| [link.start, link.end]))
+        .filter(|offset| *offset < content.len() && content.is_char_boundary(*offset))
+        .chain([0, content.len()])
+        .collect();
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    cuts.windows(2)
+        .map(|bounds| {
+            let (start, end) = (bounds[0], bounds[1]);
+            let covers = |from: usize, to: usize| from <= start && end <= to;
+            let mut style = base;
+            if use_tool_colors
+                && let Some(run) = markup.styles.iter().find(|run| covers(run.start, run.end))
+            {
+                style = style.patch(tool_style(&run.style));
+            }
+            if markup.links.iter().any(|link| covers(link.start, link.end)) {
+                if !use_tool_colors {
+                    style = style.fg(Theme::info());
+                }
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            Span::styled(content[start..end].to_string(), style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::parse_ansi;
+
+    fn line(raw: &str) -> LogLine {
+        let (content, markup) = parse_ansi(raw);
+        LogLine {
+            markup: markup.map(Box::new),
+            ..LogLine::new("vite", content)
+        }
+    }
+
+    #[test]
+    fn test_tool_colors_are_kept_or_themed() {
+        let log = line("\u{1b}[32m✓\u{1b}[0m built in \u{1b}[1m312ms\u{1b}[22m");
+        let themed = Style::default().fg(Theme::success());
+
+        let spans = content_spans(&log, themed, LogColors::Tool);
+        let texts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(texts, ["✓", " built in ", "312ms"]);
+        assert_eq!(spans[0].style.fg, Some(Color::Indexed(2)));
+        assert_eq!(spans[1].style, Style::default());
+        assert!(spans[2].style.add_modifier.contains(Modifier::BOLD));
+
+        let spans = content_spans(&log, themed, LogColors::Themed);
+        assert!(spans.iter().all(|span| span.style == themed));
+    }
+
+    #[test]
+    fn test_links_are_underlined() {
+        let log = line(
+            "error in \u{1b}]8;;file:///app/src/main.ts\u{7}src/main.ts:4\u{1b}]8;;\u{7} here",
+        );
+
+        let spans = content_spans(&log, Style::default(), LogColors::Themed);
+        assert_eq!(spans[1].content, "src/main.ts:4");
+        assert!(spans[1].style.add_modifier.contains(Modifier::UNDERLINED));
+        assert!(!spans[2].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn test_plain_lines_and_bad_offsets_stay_whole() {
+        let mut log = line("plain ✓ text");
+        let themed = Style::default().fg(Theme::info());
+        assert_eq!(
+            content_spans(&log, themed, LogColors::Tool),
+            vec![Span::styled("plain ✓ text", themed)]
+        );
+
+        // An offset inside the multi-byte ✓ must not split it
+        log.markup = Some(Box::new(crate::process::LogMarkup {
+            styles: vec![crate::process::StyledRun {
+                start: 7,
+                end: 99,
+                style: AnsiStyle::default(),
+            }],
+            links: Vec::new(),
+        }));
+        let text: String = content_spans(&log, themed, LogColors::Tool)
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "plain ✓ text");
+    }
+}
@@ -0,0 +1,240 @@
+/// Log viewport - keeps the scrolled-up log view anchored to a line, not an index
+///
+/// Lines are identified by `LogLine::seq`, which only ever increases. Anchoring
+/// to it means new lines arriving at the bottom and old ones being evicted from
+/// the top don't move what is on screen.
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+
+/// Which end of the log the Logs view shows at the top
+///
+/// The buffer itself is always oldest first; only rendering and the direction
+/// of scroll keys change. Auto-scroll pins to the newest line either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogOrder {
+    /// Tail style: oldest at the top, newest at the bottom
+    #[default]
+    #[serde(rename = "oldest")]
+    OldestFirst,
+    /// Feed style: newest at the top
+    #[serde(rename = "newest")]
+    NewestFirst,
+}
+
+impl LogOrder {
+    /// Parse `newest` or `oldest` (as used by `/order` and `[logs] order`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "newest" | "newest-first" => Some(Self::NewestFirst),
+            "oldest" | "oldest-first" => Some(Self::OldestFirst),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OldestFirst => "oldest first",
+            Self::NewestFirst => "newest first",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::OldestFirst => Self::NewestFirst,
+            Self::NewestFirst => Self::OldestFirst,
+        }
+    }
+
+    /// Turn an on-screen scroll (positive is down) into one through the buffer
+    pub fn buffer_delta(&self, screen_delta: isize) -> isize {
+        match self {
+            Self::OldestFirst => screen_delta,
+            Self::NewestFirst => -screen_delta,
+        }
+    }
+}
+
+/// Where the logs view is scrolled to while auto-scroll is off
+#[derive(Debug, Default)]
+pub struct LogViewport {
+    /// Sequence id of the top visible line; `None` shows the oldest line
+    anchor: Option<u64>,
+    /// Newest sequence id on screen when the view left the tail
+    seen_until: Option<u64>,
+    /// The anchored line was evicted and the view moved to the oldest line
+    anchor_evicted: bool,
+    /// Lines that fit on screen, as of the last render
+    height: Cell<usize>,
+}
+
+impl LogViewport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record how many lines the logs panel showed on the last render
+    pub fn set_height(&self, height: usize) {
+        self.height.set(height);
+    }
+
+    fn page(&self) -> usize {
+        self.height.get().max(1)
+    }
+
+    /// Index of the first visible line in `seqs` (the filtered lines' sequence ids)
+    pub fn start_index(&self, seqs: &[u64], following: bool) -> usize {
+        let last_page = seqs.len().saturating_sub(self.page());
+        if following {
+            return last_page;
+        }
+        match self.anchor {
+            Some(anchor) => seqs.partition_point(|seq| *seq < anchor).min(last_page),
+            None => 0,
+        }
+    }
+
+    /// Move the view by `delta` lines
+    ///
+    /// Returns whether the view should follow the tail afterwards, which is the
+    /// case once scrolling down reaches the last page.
+    pub fn scroll(&mut self, seqs: &[u64], following: bool, delta: isize) -> bool {
+        let last_page = seqs.len().saturating_sub(self.page());
+        let current = self.start_index(seqs, following);
+        let target = current.saturating_add_signed(delta).min(last_page);
+
+        if delta > 0 && target == last_page {
+            self.follow();
+            return true;
+        }
+
+        if following {
+            self.seen_until = seqs.last().copied();
+        }
+        self.anchor = seqs.get(target).copied();
+        self.anchor_evicted = false;
+        false
+    }
+
+    /// Put the line with sequence id `target` at the top of the view
+    pub fn jump_to(&mut self, seqs: &[u64], target: Option<u64>) {
+        self.anchor = target;
+        self.seen_until = seqs.last().copied();
+        self.anchor_evicted = false;
+    }
+
+    /// Jump back to the tail; the caller turns auto-scroll on
+    pub fn follow(&mut self) {
+        self.anchor = None;
+        self.seen_until = None;
+        self.anchor_evicted = false;
+    }
+
+    /// Note that lines were dropped from the front of the buffer
+    ///
+    /// `oldest` is the sequence id of the oldest line still retained. If the
+    /// anchored line is gone, the view moves to that line instead.
+    pub fn lines_evicted(&mut self, oldest: Option<u64>) {
+        if let Some(anchor) = self.anchor
+            && oldest.is_none_or(|oldest| anchor < oldest)
+        {
+            self.anchor = oldest;
+            self.anchor_evicted = true;
+        }
+    }
+
+    /// Whether the view was moved because its anchored line was evicted
+    pub fn anchor_evicted(&self) -> bool {
+        self.anchor_evicted
+    }
+
+    /// Lines in `seqs` that arrived after the view left the tail
+    pub fn new_lines_below(&self, seqs: &[u64]) -> usize {
+        self.seen_until.map_or(0, |seen| {
+            seqs.len() - seqs.partition_point(|seq| *seq <= seen)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A buffer of at most `max` lines, filled with sequence ids `from..to`
+    fn buffer(from: u64, to: u64, max: usize) -> Vec<u64> {
+        let seqs: Vec<u64> = (from..to).collect();
+        seqs[seqs.len().saturating_sub(max)..].to_vec()
+    }
+
+    fn visible(viewport: &LogViewport, seqs: &[u64]) -> Vec<u64> {
+        let start = viewport.start_index(seqs, false);
+        seqs[start..(start + viewport.page()).min(seqs.len())].to_vec()
+    }
+
+    #[test]
+    fn test_view_stays_put_while_lines_are_evicted() {
+        let mut viewport = LogViewport::new();
+        viewport.set_height(5);
+        let mut seqs = buffer(1, 101, 100);
+
+        assert!(!viewport.scroll(&seqs, true, -50));
+        let on_screen = visible(&viewport, &seqs);
+        assert_eq!(on_screen, [46, 47, 48, 49, 50]);
+
+        // 20 more lines arrive and the 20 oldest are evicted
+        seqs = buffer(1, 121, 100);
+        viewport.lines_evicted(seqs.first().copied());
+
+        assert_eq!(visible(&viewport, &seqs), on_screen);
+        assert!(!viewport.anchor_evicted());
+        assert_eq!(viewport.new_lines_below(&seqs), 20);
+    }
+
+    #[test]
+    fn test_evicted_anchor_moves_to_oldest_line() {
+        let mut viewport = LogViewport::new();
+        viewport.set_height(5);
+        let mut seqs = buffer(1, 101, 100);
+        viewport.scroll(&seqs, true, -90);
+        assert_eq!(visible(&viewport, &seqs)[0], 6);
+
+        seqs = buffer(1, 111, 100);
+        viewport.lines_evicted(seqs.first().copied());
+
+        assert!(viewport.anchor_evicted());
+        assert_eq!(visible(&viewport, &seqs)[0], 11);
+
+        // Scrolling clears the indicator
+        viewport.scroll(&seqs, false, 1);
+        assert!(!viewport.anchor_evicted());
+    }
+
+    #[test]
+    fn test_newest_first_scrolls_down_into_older_lines() {
+        let mut viewport = LogViewport::new();
+        viewport.set_height(5);
+        let seqs = buffer(1, 21, 100);
+        let order = LogOrder::NewestFirst;
+
+        // Down on screen moves away from the newest lines at the top
+        assert!(!viewport.scroll(&seqs, true, order.buffer_delta(3)));
+        assert_eq!(viewport.start_index(&seqs, false), 12);
+
+        // Back up to the top follows again
+        assert!(viewport.scroll(&seqs, false, order.buffer_delta(-3)));
+        assert_eq!(LogOrder::parse("Newest"), Some(order));
+        assert_eq!(order.toggled(), LogOrder::OldestFirst);
+    }
+
+    #[test]
+    fn test_scrolling_to_the_bottom_follows_again() {
+        let mut viewport = LogViewport::new();
+        viewport.set_height(5);
+        let seqs = buffer(1, 21, 100);
+
+        assert!(!viewport.scroll(&seqs, true, -3));
+        assert_eq!(viewport.start_index(&seqs, false), 12);
+        assert!(viewport.scroll(&seqs, false, 3));
+        assert_eq!(viewport.new_lines_below(&seqs), 0);
+    }
+}
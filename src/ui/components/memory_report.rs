@@ -0,0 +1,196 @@
+/// Memory report overlay - the last memory_profiler / derailed report a
+/// process printed, as totals and top-N tables
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::memory::MemoryReport;
+use crate::time_format::DisplayZone;
+use crate::ui::theme::Theme;
+
+/// Render the overlay, scrolled down `scroll` lines
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Memory report (↑↓ scroll, e export, Esc to close) ───────┐
+/// │ memory_profiler - web - 14:32:05                          │
+/// │ Total 28.79 MB allocated (291,567 objects)                │
+/// │ Total 1.78 MB retained (16,243 objects)                   │
+/// │                                                           │
+/// │ allocated memory by gem                                   │
+/// │    11.32 MB  activesupport-7.1.3                          │
+/// └───────────────────────────────────────────────────────────┘
+/// ```
+pub fn render_memory_report(
+    f: &mut Frame,
+    area: Rect,
+    report: &MemoryReport,
+    scroll: usize,
+    zone: DisplayZone,
+    fade_progress: Option<f32>,
+) {
+    f.render_widget(Clear, area);
+
+    let block = Theme::block(
+        "Memory report (↑↓ scroll, e export, Esc to close)",
+        fade_progress,
+    )
+    .style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let lines = report_lines(report, zone, fade_progress.unwrap_or(1.0));
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll.min(u16::MAX as usize) as u16, 0)),
+        area,
+    );
+}
+
+/// The report's lines, before scrolling
+pub fn report_lines(report: &MemoryReport, zone: DisplayZone, fade: f32) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let amount = Style::default().fg(Theme::apply_fade_to_color(Theme::warning(), fade));
+    let heading = Style::default()
+        .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+        .add_modifier(Modifier::BOLD);
+
+    let mut origin = format!(
+        "{} - {} - {}",
+        report.kind.label(),
+        report.process,
+        zone.clock(report.time)
+    );
+    if !report.complete {
+        origin.push_str(" (still printing)");
+    }
+    let mut lines = vec![Line::styled(origin, muted)];
+    lines.extend(report.totals.iter().map(|total| {
+        Line::styled(
+            format!("Total {}", total.describe()),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    }));
+
+    for table in &report.tables {
+        lines.push(Line::default());
+        lines.push(Line::styled(table.title.clone(), heading));
+        lines.extend(table.rows.iter().map(|row| {
+            Line::from(vec![
+                Span::styled(format!("{:>12}", table.measure.format(row.amount)), amount),
+                Span::raw(format!("  {}", row.name)),
+            ])
+        }));
+    }
+    lines
+}
+
+/// Centered area for the overlay
+pub fn calculate_memory_report_area(full_area: Rect) -> Rect {
+    let width = full_area.width.min(110);
+    let height = full_area
+        .height
+        .saturating_sub(4)
+        .max(full_area.height.min(8));
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryReports;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_report_lines_show_totals_then_each_table() {
+        let mut reports = MemoryReports::new();
+        for line in [
+            "Total allocated: 2048 bytes (20 objects)",
+            "allocated memory by gem",
+            "-----------------------------------",
+            "      2048  rack-3.0.9",
+        ] {
+            reports.observe("web", line, chrono::Local::now());
+        }
+        let text: Vec<String> = report_lines(reports.latest().unwrap(), DisplayZone::Utc, 1.0)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+
+        assert!(
+            text[0].starts_with("memory_profiler - web - "),
+            "{:?}",
+            text
+        );
+        assert!(text[0].ends_with("(still printing)"), "{:?}", text);
+        assert_eq!(text[1], "Total 2.00 KB allocated (20 objects)");
+        assert_eq!(text[3], "allocated memory by gem");
+        assert_eq!(text[4], "     2.00 KB  rack-3.0.9");
+    }
+
+    #[test]
+    fn test_memory_report_is_collected_and_shown_with_memory() {
+        let mut app = test_app();
+        app.command_input = "/memory".to_string();
+        app.execute_command();
+        assert!(!app.show_memory);
+
+        let report = std::fs::read_to_string("tests/fixtures/memory/memory_profiler.log").unwrap();
+        for line in report.lines() {
+            app.add_log(LogLine::new("worker", line.to_string()));
+        }
+        app.last_command_result = None;
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains("Memory report from worker (/memory)"),
+            "{}",
+            screen
+        );
+
+        app.command_input = "/memory".to_string();
+        app.execute_command();
+        app.last_command_result = None;
+        assert!(app.show_memory);
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("memory_profiler - worker"), "{}", screen);
+        assert!(screen.contains("Total 28.79 MB allocated"), "{}", screen);
+        assert!(
+            screen.contains("11.32 MB  activesupport-7.1.3"),
+            "{}",
+            screen
+        );
+        assert!(!screen.contains("(/memory)"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.memory_scroll, 1);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_memory);
+
+        let path = std::env::temp_dir().join(format!("caboose_memory_{}.txt", std::process::id()));
+        app.command_input = format!("/memory export {}", path.display());
+        app.execute_command();
+        assert!(app.last_command_result.as_ref().unwrap().is_success());
+        let exported = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(exported.contains("retained memory by file"), "{}", exported);
+    }
+}
@@ -1,9 +1,30 @@
+pub mod alerts;
+pub mod build_error;
 pub mod command_palette;
+pub mod diagnostics;
+pub mod empty_state;
 pub mod footer;
 /// Reusable UI components
 pub mod header;
+pub mod help;
+pub mod log_chips;
+pub mod log_colors;
+pub mod log_viewport;
+pub mod memory_report;
+pub mod replay_banner;
+pub mod request_fields;
+pub mod request_row;
+pub mod routes;
 pub mod scroll_indicator;
+pub mod search_bar;
+pub mod status;
+pub mod tab_indicator;
+pub mod trends;
 
-pub use footer::FooterBuilder;
+pub use empty_state::EmptyState;
+pub use footer::{FooterBuilder, Priority};
 pub use header::HeaderBuilder;
+pub use log_chips::LogChips;
+pub use log_colors::LogColors;
+pub use log_viewport::{LogOrder, LogViewport};
 pub use scroll_indicator::ScrollIndicator;
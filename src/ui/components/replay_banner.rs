@@ -0,0 +1,105 @@
+/// Replay banner - one line above every view while a saved session is open
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::session::SessionMetadata;
+use crate::time_format::DisplayZone;
+use crate::ui::theme::Theme;
+
+/// What the banner says about the session
+///
+/// ```text
+///  REPLAY  Session saved 2025-01-01 12:00:00 from ~/code/shop - read-only, nothing is running
+/// ```
+pub fn replay_banner_text(metadata: &SessionMetadata, zone: DisplayZone) -> String {
+    let from = metadata
+        .project
+        .as_ref()
+        .map_or_else(String::new, |project| format!(" from {}", project));
+    format!(
+        "Session saved {}{} - read-only, nothing is running",
+        zone.date_time(metadata.saved_at),
+        from
+    )
+}
+
+/// Render the banner
+pub fn render_replay_banner(
+    f: &mut Frame,
+    area: Rect,
+    metadata: &SessionMetadata,
+    zone: DisplayZone,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    let line = Line::from(vec![
+        Span::styled(
+            " REPLAY ",
+            Style::default()
+                .fg(Theme::apply_fade_to_color(Theme::surface(), fade))
+                .bg(Theme::apply_fade_to_color(Theme::warning(), fade))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" {}", replay_banner_text(metadata, zone)),
+            Style::default().fg(Theme::apply_fade_to_color(Theme::warning(), fade)),
+        ),
+    ]);
+    f.render_widget(Paragraph::new(line), area);
+}
+
+/// Split a content area into (banner, remaining content)
+pub fn split_replay_area(area: Rect) -> (Rect, Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    (chunks[0], chunks[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_banner_text_names_project_and_save_time() {
+        let mut metadata = SessionMetadata {
+            caboose_version: "0.1.0".to_string(),
+            project: Some("/code/shop".to_string()),
+            saved_at: Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap(),
+        };
+
+        assert_eq!(
+            replay_banner_text(&metadata, DisplayZone::Local),
+            "Session saved 2025-01-01 12:00:00 from /code/shop - read-only, nothing is running"
+        );
+        metadata.project = None;
+        assert_eq!(
+            replay_banner_text(&metadata, DisplayZone::Local),
+            "Session saved 2025-01-01 12:00:00 - read-only, nothing is running"
+        );
+
+        metadata.saved_at = chrono::Utc
+            .with_ymd_and_hms(2025, 1, 1, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        assert_eq!(
+            replay_banner_text(&metadata, DisplayZone::Utc),
+            "Session saved 2025-01-01 12:00:00 UTC - read-only, nothing is running"
+        );
+    }
+
+    #[test]
+    fn test_split_replay_area() {
+        let (banner, content) = split_replay_area(Rect::new(0, 5, 80, 20));
+
+        assert_eq!(banner, Rect::new(0, 5, 80, 1));
+        assert_eq!(content, Rect::new(0, 6, 80, 19));
+    }
+}
@@ -0,0 +1,120 @@
+/// Request fields - well-known extra fields from Lograge / `Completed` lines, humanized
+use std::collections::HashMap;
+
+use crate::ui::formatting::{format_bytes, format_ms, format_number};
+
+/// Well-known keys in display order: (keys as logged, label, kind)
+const KNOWN_FIELDS: &[(&[&str], &str, FieldKind)] = &[
+    (
+        &["bytes", "response_bytes", "size"],
+        "Size",
+        FieldKind::Bytes,
+    ),
+    (&["content_type"], "Content type", FieldKind::Text),
+    (&["format"], "Format", FieldKind::Text),
+    (&["db", "db_runtime"], "DB", FieldKind::Millis),
+    (&["view", "view_runtime"], "Views", FieldKind::Millis),
+    (&["allocations"], "Allocations", FieldKind::Count),
+    // rack-mini-profiler's own measurements, when the gem is in the Gemfile
+    (
+        &["profiler_total"],
+        "Total (mini-profiler)",
+        FieldKind::Millis,
+    ),
+    (&["profiler_sql"], "SQL (mini-profiler)", FieldKind::Millis),
+];
+
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Text,
+    Bytes,
+    Millis,
+    Count,
+}
+
+impl FieldKind {
+    /// Humanize `value`, keeping it as logged when it doesn't parse
+    fn format(self, value: &str) -> String {
+        match self {
+            FieldKind::Text => None,
+            FieldKind::Bytes => value.parse().ok().map(format_bytes),
+            FieldKind::Millis => value.parse().ok().map(format_ms),
+            FieldKind::Count => value.parse().ok().map(format_number),
+        }
+        .unwrap_or_else(|| value.to_string())
+    }
+}
+
+/// A request's extra fields, split for display
+#[derive(Debug, Default, PartialEq)]
+pub struct RequestFields<'a> {
+    /// Well-known fields as `(label, humanized value)`
+    pub known: Vec<(&'static str, String)>,
+    /// Everything else as `(key, value)`, sorted by key
+    pub other: Vec<(&'a str, &'a str)>,
+}
+
+/// Split extra fields into well-known fields and the rest
+///
+/// Unknown fields are sorted by key so the list is stable between renders.
+pub fn split_request_fields(extra: &HashMap<String, String>) -> RequestFields<'_> {
+    let mut known = Vec::new();
+    let mut used: Vec<&str> = Vec::new();
+
+    for (keys, label, kind) in KNOWN_FIELDS {
+        if let Some((key, value)) = keys.iter().find_map(|key| extra.get_key_value(*key)) {
+            known.push((*label, kind.format(value)));
+            used.push(key);
+        }
+    }
+
+    let mut other: Vec<(&str, &str)> = extra
+        .iter()
+        .filter(|(key, _)| !used.contains(&key.as_str()))
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    other.sort();
+
+    RequestFields { known, other }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_request_fields_humanizes_known_keys() {
+        let extra: HashMap<String, String> = [
+            ("bytes", "10432"),
+            ("content_type", "application/json"),
+            ("db", "12.01"),
+            ("allocations", "28090"),
+            ("request_id", "abc"),
+            ("host", "localhost"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let fields = split_request_fields(&extra);
+        assert_eq!(
+            fields.known,
+            [
+                ("Size", "10.19 KB".to_string()),
+                ("Content type", "application/json".to_string()),
+                ("DB", "12.0ms".to_string()),
+                ("Allocations", format_number(28090)),
+            ]
+        );
+        assert_eq!(fields.other, [("host", "localhost"), ("request_id", "abc")]);
+    }
+
+    #[test]
+    fn test_split_request_fields_keeps_unparsable_values() {
+        let extra: HashMap<String, String> = [("bytes".to_string(), "n/a".to_string())].into();
+
+        let fields = split_request_fields(&extra);
+        assert_eq!(fields.known, [("Size", "n/a".to_string())]);
+        assert!(fields.other.is_empty());
+    }
+}
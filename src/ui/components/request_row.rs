@@ -0,0 +1,332 @@
+/// Request row - one completed request as fixed columns for the Query Analysis list
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::context::CompletedRequest;
+use crate::privacy::Privacy;
+use crate::ui::formatting::{format_ms, truncate_middle};
+use crate::ui::theme::Theme;
+
+/// Requests slower than this get an over-budget badge; `Theme::duration_color`
+/// turns red at the same point
+pub const REQUEST_BUDGET_MS: f64 = 200.0;
+
+/// Columns taken by everything but the path:
+/// indent, method, status, queries, duration and badges plus the gaps between
+const FIXED_WIDTH: usize = 2 + 6 + 1 + 3 + 1 + 1 + 7 + 1 + 8 + 1 + 8;
+
+/// Narrowest path column, even when that overflows `width`
+const MIN_PATH_WIDTH: usize = 12;
+
+/// Column titles, aligned with [`request_row`] at the same `width`
+pub fn request_header(width: usize) -> Line<'static> {
+    let path_width = path_width(width);
+    Line::styled(
+        format!(
+            "  {:<6} {:>3} {:<path_width$} {:>7} {:>8} {:<8}",
+            "Method", "St", "Path", "Queries", "Duration", "Flags",
+        ),
+        Style::default()
+            .fg(Theme::text_muted())
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// A request as one line of `width` columns
+///
+/// ```text
+///   GET    200 /users/42 Users#show user   4   12.5ms
+///   POST   422 /orders Orders#create      31  250.0ms N+1 SLOW
+///   POST   201 /orders Orders#create       2   18.0ms ×3 in 180ms
+/// ```
+///
+/// The path is shortened from the middle so both its start and the id at its
+/// end stay visible; the controller follows when there's room for all of it,
+/// then the name of the route the request matched (`route`), if it has one,
+/// and the short form of its request id (`#1f0c9a2e`).
+/// Emails and UUIDs in the path are masked while `privacy` is on.
+pub fn request_row(
+    request: &CompletedRequest,
+    route: Option<&str>,
+    privacy: &Privacy,
+    width: usize,
+) -> Line<'static> {
+    let path_width = path_width(width);
+    let method = request.context.method.as_deref().unwrap_or("-");
+    let status = request
+        .status
+        .map_or_else(|| "-".to_string(), |status| status.to_string());
+    let duration = request.total_duration.unwrap_or(0.0);
+
+    let path = truncate_middle(
+        &privacy.text(request.context.path.as_deref().unwrap_or("<unknown>")),
+        path_width,
+    );
+    let path_len = path.chars().count();
+    let controller = request
+        .context
+        .controller
+        .as_deref()
+        .filter(|controller| path_len + 1 + controller.chars().count() <= path_width)
+        .map_or_else(String::new, |controller| format!(" {}", controller));
+    let used = path_len + controller.chars().count();
+    let route = route
+        .filter(|route| used + 1 + route.chars().count() <= path_width)
+        .map_or_else(String::new, |route| format!(" {}", route));
+    let used = used + route.chars().count();
+    let request_id = request
+        .context
+        .short_request_id()
+        .map(|id| format!(" #{}", id))
+        .filter(|id| used + id.chars().count() <= path_width)
+        .unwrap_or_default();
+    let padding = " ".repeat(path_width - used - request_id.chars().count());
+
+    let mut spans = vec![
+        Span::raw("  "),
+        Span::styled(
+            format!("{:<6}", method),
+            Style::default()
+                .fg(Theme::method_color(method))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            format!("{:>3}", status),
+            Style::default().fg(Theme::status_code_color(request.status.unwrap_or(0))),
+        ),
+        Span::raw(" "),
+        Span::styled(path, Style::default().fg(Theme::text_primary())),
+        Span::styled(controller, Style::default().fg(Theme::text_muted())),
+        Span::styled(route, Style::default().fg(Theme::info())),
+        Span::styled(request_id, Style::default().fg(Theme::text_muted())),
+        Span::raw(padding),
+        Span::styled(
+            format!(" {:>7}", request.context.query_count()),
+            Style::default().fg(Theme::text_secondary()),
+        ),
+        Span::styled(
+            format!(" {:>8}", format_ms(duration)),
+            Style::default().fg(Theme::duration_color(duration)),
+        ),
+        Span::raw(" "),
+    ];
+
+    let n_plus_one = !request.n_plus_one_issues.is_empty();
+    if n_plus_one {
+        spans.push(Span::styled(
+            "N+1",
+            Style::default()
+                .fg(Theme::danger())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    let slow = duration > REQUEST_BUDGET_MS;
+    if slow {
+        spans.push(Span::styled(
+            if n_plus_one { " SLOW" } else { "SLOW" },
+            Style::default()
+                .fg(Theme::warning())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(duplicates) = request.duplicates {
+        let gap = if n_plus_one || slow { " " } else { "" };
+        spans.push(Span::styled(
+            format!("{}{}", gap, duplicates.badge()),
+            Style::default()
+                .fg(Theme::warning())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    Line::from(spans)
+}
+
+fn path_width(width: usize) -> usize {
+    width.saturating_sub(FIXED_WIDTH).max(MIN_PATH_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Duplicates;
+    use crate::query::{NPlusOneIssue, NPlusOneSource, QueryFingerprint, RequestContext};
+    use ratatui::{Terminal, backend::TestBackend, widgets::Paragraph};
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    fn request(
+        method: &str,
+        path: &str,
+        controller: &str,
+        status: u16,
+        duration: f64,
+    ) -> CompletedRequest {
+        let mut context = RequestContext::new(Some(path.to_string()));
+        context.method = Some(method.to_string());
+        context.controller = Some(controller.to_string());
+        CompletedRequest {
+            context,
+            n_plus_one_issues: Vec::new(),
+            total_duration: Some(duration),
+            status: Some(status),
+            extra: HashMap::new(),
+            completed_at: Instant::now(),
+            duplicates: None,
+        }
+    }
+
+    fn requests() -> Vec<CompletedRequest> {
+        let mut slow = request("POST", "/orders", "Orders#create", 422, 250.0);
+        slow.n_plus_one_issues.push(NPlusOneIssue {
+            fingerprint: QueryFingerprint::new("SELECT 1"),
+            count: 3,
+            total_duration: 3.0,
+            sample_query: "SELECT 1".to_string(),
+            suggestion: String::new(),
+            source: NPlusOneSource::Heuristic,
+        });
+        vec![
+            request("GET", "/users/42", "Users#show", 200, 12.5),
+            slow,
+            request(
+                "DELETE",
+                "/admin/accounts/8f14e45f-ceea-467f-a0e6/sessions/12",
+                "Admin::SessionsController#destroy",
+                500,
+                1500.0,
+            ),
+        ]
+    }
+
+    fn screen(width: u16) -> Vec<String> {
+        let requests = requests();
+        let mut lines = vec![request_header(width as usize)];
+        let routes = [Some("user"), None, None];
+        lines.extend(
+            requests
+                .iter()
+                .zip(routes)
+                .map(|(r, route)| request_row(r, route, &Privacy::default(), width as usize)),
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(width, 4)).unwrap();
+        terminal
+            .draw(|f| f.render_widget(Paragraph::new(lines), f.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..4)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_align_at_narrow_width_shortening_paths() {
+        assert_eq!(
+            screen(60),
+            [
+                "  Method  St Path                  Queries Duration Flags",
+                "  GET    200 /users/42 Users#show        0   12.5ms",
+                "  POST   422 /orders Orders#create       0  250.0ms N+1 SLOW",
+                "  DELETE 500 /admin/acc…essions/12       0    1.50s SLOW",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_align_at_wide_width() {
+        assert_eq!(
+            screen(100),
+            [
+                "  Method  St Path                                                          Queries Duration Flags",
+                "  GET    200 /users/42 Users#show user                                           0   12.5ms",
+                "  POST   422 /orders Orders#create                                               0  250.0ms N+1 SLOW",
+                "  DELETE 500 /admin/accounts/8f14e45f-ceea-467f-a0e6/sessions/12                 0    1.50s SLOW",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_name_follows_the_controller_when_it_fits() {
+        let requests = requests();
+        let row = request_row(&requests[0], Some("user"), &Privacy::default(), 100);
+        let route = row.spans.iter().find(|span| span.content == " user");
+        assert_eq!(route.and_then(|span| span.style.fg), Some(Theme::info()));
+
+        // At 60 columns only the controller fits beside the path
+        let row = request_row(&requests[0], Some("user"), &Privacy::default(), 60);
+        assert!(row.spans.iter().all(|span| span.content != " user"));
+    }
+
+    #[test]
+    fn test_row_colors_method_status_and_duration() {
+        let row = request_row(&requests()[2], None, &Privacy::default(), 80);
+        let color = |text: &str| {
+            row.spans
+                .iter()
+                .find(|span| span.content.trim() == text)
+                .and_then(|span| span.style.fg)
+        };
+
+        assert_eq!(color("DELETE"), Some(Theme::method_color("DELETE")));
+        assert_eq!(color("500"), Some(Theme::danger()));
+        assert_eq!(color("1.50s"), Some(Theme::duration_color(1500.0)));
+    }
+
+    #[test]
+    fn test_duplicate_runs_get_a_badge_after_the_others() {
+        let mut repeated = request("POST", "/orders", "Orders#create", 201, 18.0);
+        repeated.duplicates = Some(Duplicates {
+            count: 3,
+            span: Duration::from_millis(180),
+        });
+        let text = |row: Line| {
+            row.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        };
+        assert!(
+            text(request_row(&repeated, None, &Privacy::default(), 80))
+                .ends_with("18.0ms ×3 in 180ms")
+        );
+
+        repeated.total_duration = Some(250.0);
+        assert!(
+            text(request_row(&repeated, None, &Privacy::default(), 80))
+                .ends_with("SLOW ×3 in 180ms")
+        );
+    }
+
+    #[test]
+    fn test_privacy_masks_emails_and_uuids_in_the_path() {
+        let request = request(
+            "GET",
+            "/invites/bob@example.com/accept/8f14e45f-ceea-467f-a0e6-1b2c3d4e5f60",
+            "Invites#accept",
+            200,
+            12.0,
+        );
+        let text = |privacy: &Privacy| {
+            request_row(&request, None, privacy, 120)
+                .spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect::<String>()
+        };
+
+        assert!(text(&Privacy::default()).contains("bob@example.com"));
+        let masked = text(&Privacy::new(true, Vec::new()));
+        assert!(masked.contains("/invites/•••/accept/•••"), "{}", masked);
+        assert!(masked.contains("Invites#accept"), "{}", masked);
+    }
+}
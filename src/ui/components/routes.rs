@@ -0,0 +1,345 @@
+/// Routes overlay - the app's routes from `rails routes`, filtered as you type
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::rails::routes::{Route, RouteTable};
+use crate::ui::formatting::truncate_middle;
+use crate::ui::theme::Theme;
+
+/// Frames of the spinner shown while `rails routes` runs
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+const VERB_COLUMN: usize = 7;
+const ACTION_COLUMN: usize = 28;
+const NAME_COLUMN: usize = 20;
+
+/// What the overlay shows besides the table
+pub struct RoutesStatus<'a> {
+    /// `rails routes` is running
+    pub loading: bool,
+    /// What it printed when it last failed
+    pub error: Option<&'a str>,
+    pub spinner_frame: usize,
+}
+
+/// Render the routes overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Routes (type to filter, Ctrl+R to refresh, Esc to close) ──────────┐
+/// │ Filter: users                                          3 of 42     │
+/// │ Verb    Path                       Action            Name          │
+/// │ GET     /users(.:format)           users#index       users         │
+/// │ GET     /users/:id(.:format)       users#show        user          │
+/// └────────────────────────────────────────────────────────────────────┘
+/// ```
+///
+/// The first `scroll` matching routes are skipped; it's clamped so the last
+/// page stays full. Without a table yet, a spinner (or the error output of
+/// the failed run) takes its place.
+pub fn render_routes(
+    f: &mut Frame,
+    area: Rect,
+    table: Option<&RouteTable>,
+    query: &str,
+    scroll: usize,
+    status: &RoutesStatus,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let danger = Style::default().fg(Theme::apply_fade_to_color(Theme::danger(), fade));
+    let spinner = SPINNER_FRAMES[status.spinner_frame % SPINNER_FRAMES.len()];
+
+    let title = if status.loading && table.is_some() {
+        format!("Routes {} refreshing (Esc to close)", spinner)
+    } else {
+        "Routes (type to filter, Ctrl+R to refresh, Esc to close)".to_string()
+    };
+    let block = Theme::block(title, fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let inner = block.inner(area);
+    let width = inner.width as usize;
+
+    let mut lines = Vec::new();
+    if let Some(error) = status.error {
+        lines.push(Line::styled(
+            "bundle exec rails routes failed (Ctrl+R to retry):",
+            danger.add_modifier(Modifier::BOLD),
+        ));
+        let limit = if table.is_some() { 3 } else { usize::MAX };
+        lines.extend(
+            error
+                .lines()
+                .take(limit)
+                .map(|line| Line::styled(line.to_string(), danger)),
+        );
+        lines.push(Line::from(""));
+    }
+
+    match table {
+        Some(table) => {
+            let matching: Vec<&Route> = table.filter(query).collect();
+            lines.push(Line::from(vec![
+                Span::styled("Filter: ", muted),
+                Span::raw(query.to_string()),
+                Span::styled(format!("   {} of {}", matching.len(), table.len()), muted),
+            ]));
+            lines.push(Line::styled(
+                route_columns(width, "Verb", "Path", "Action", "Name"),
+                muted.add_modifier(Modifier::BOLD),
+            ));
+
+            let rows = (inner.height as usize).saturating_sub(lines.len());
+            let scroll = scroll.min(matching.len().saturating_sub(rows));
+            lines.extend(matching.iter().skip(scroll).take(rows).map(|route| {
+                Line::styled(
+                    route_columns(
+                        width,
+                        &route.verb,
+                        &route.path,
+                        &route.action,
+                        route.name.as_deref().unwrap_or_default(),
+                    ),
+                    Style::default().fg(Theme::apply_fade_to_color(
+                        Theme::method_color(route.verb.split('|').next().unwrap_or_default()),
+                        fade,
+                    )),
+                )
+            }));
+            if matching.is_empty() {
+                lines.push(Line::styled("No routes match", muted));
+            }
+        }
+        None if status.loading => {
+            lines.push(Line::styled(
+                format!("{} Running bundle exec rails routes...", spinner),
+                muted,
+            ));
+        }
+        None if status.error.is_none() => {
+            lines.push(Line::styled(
+                "No routes read yet (Ctrl+R to read them)",
+                muted,
+            ));
+        }
+        None => {}
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// One row of fixed columns; the path gets what's left, shortened from the middle
+fn route_columns(width: usize, verb: &str, path: &str, action: &str, name: &str) -> String {
+    let path_width = width
+        .saturating_sub(VERB_COLUMN + ACTION_COLUMN + NAME_COLUMN + 4)
+        .max(12);
+    format!(
+        " {:<VERB_COLUMN$} {:<path_width$} {:<ACTION_COLUMN$} {}",
+        truncate_middle(verb, VERB_COLUMN),
+        truncate_middle(path, path_width),
+        truncate_middle(action, ACTION_COLUMN),
+        truncate_middle(name, NAME_COLUMN),
+    )
+}
+
+/// Centered area for the overlay, most of the screen
+pub fn calculate_routes_area(full_area: Rect) -> Rect {
+    let width = full_area.width.min(120);
+    let height = full_area
+        .height
+        .saturating_sub(4)
+        .max(full_area.height.min(8));
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rails::routes::parse_expanded_routes;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn table() -> RouteTable {
+        RouteTable::new(parse_expanded_routes(
+            "--[ Route 1 ]---\n\
+             Prefix            | users\n\
+             Verb              | GET\n\
+             URI               | /users(.:format)\n\
+             Controller#Action | users#index\n\
+             --[ Route 2 ]---\n\
+             Prefix            | user\n\
+             Verb              | GET\n\
+             URI               | /users/:id(.:format)\n\
+             Controller#Action | users#show\n\
+             --[ Route 3 ]---\n\
+             Prefix            | orders\n\
+             Verb              | POST\n\
+             URI               | /orders(.:format)\n\
+             Controller#Action | orders#create\n",
+        ))
+    }
+
+    fn screen(table: Option<&RouteTable>, query: &str, status: RoutesStatus) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal
+            .draw(|f| render_routes(f, f.area(), table, query, 0, &status, None))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..12)
+            .map(|y| {
+                (0..100)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn idle() -> RoutesStatus<'static> {
+        RoutesStatus {
+            loading: false,
+            error: None,
+            spinner_frame: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_routes_filters_the_table() {
+        let table = table();
+        let screen = screen(Some(&table), "users", idle());
+
+        assert!(screen.contains("Filter: users   2 of 3"), "{}", screen);
+        assert!(screen.contains("/users/:id(.:format)"), "{}", screen);
+        assert!(screen.contains("users#show"), "{}", screen);
+        assert!(!screen.contains("orders#create"), "{}", screen);
+    }
+
+    #[test]
+    fn test_render_routes_shows_a_spinner_then_the_error_output() {
+        let loading = RoutesStatus {
+            loading: true,
+            ..idle()
+        };
+        let screen_loading = screen(None, "", loading);
+        assert!(
+            screen_loading.contains("⠋ Running bundle exec rails routes..."),
+            "{}",
+            screen_loading
+        );
+
+        let failed = RoutesStatus {
+            error: Some("SyntaxError: config/routes.rb:4: syntax error"),
+            ..idle()
+        };
+        let screen_failed = screen(None, "", failed);
+        assert!(
+            screen_failed.contains("rails routes failed"),
+            "{}",
+            screen_failed
+        );
+        assert!(
+            screen_failed.contains("config/routes.rb:4: syntax error"),
+            "{}",
+            screen_failed
+        );
+    }
+
+    #[test]
+    fn test_calculate_routes_area_leaves_a_margin() {
+        let overlay = calculate_routes_area(Rect::new(0, 0, 140, 40));
+
+        assert_eq!(overlay.width, 120);
+        assert_eq!(overlay.height, 36);
+        assert_eq!(overlay.x, 10);
+    }
+
+    #[test]
+    fn test_routes_overlay_filters_and_requests_show_their_route() {
+        use crate::rails::routes::parse_expanded_routes;
+
+        let mut app = test_app();
+        app.command_input = "/routes".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message)) if message.contains("Rails app")
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+        app.exit_command_mode();
+
+        let table = RouteTable::new(parse_expanded_routes(
+            "--[ Route 1 ]---\n\
+             Prefix            | users\n\
+             Verb              | GET\n\
+             URI               | /users(.:format)\n\
+             Controller#Action | users#index\n\
+             --[ Route 2 ]---\n\
+             Prefix            | orders\n\
+             Verb              | POST\n\
+             URI               | /orders(.:format)\n\
+             Controller#Action | orders#create\n",
+        ));
+        let mut app = test_app().with_routes(Some(table));
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("UsersController#index users"), "{}", screen);
+
+        app.command_input = "/routes order".to_string();
+        app.execute_command();
+        assert!(app.show_routes);
+        // The cached table is shown; nothing is run
+        assert!(app.routes_loading.is_none());
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("Filter: order   1 of 2"), "{}", screen);
+        assert!(screen.contains("orders#create"), "{}", screen);
+        assert!(!screen.contains("users#index"), "{}", screen);
+
+        // Typing edits the filter rather than running keys
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Backspace));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('q')));
+        assert!(!app.should_quit());
+        assert_eq!(app.routes_query, "ordeq");
+
+        app.apply_routes_result(Err(
+            "rails aborted!\nSyntaxError: config/routes.rb:3: syntax error".to_string(),
+        ));
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains("config/routes.rb:3: syntax error"),
+            "{}",
+            screen
+        );
+        // The last good table stays in use
+        assert!(app.routes.is_some());
+        assert!(
+            app.logs
+                .iter()
+                .any(|log| log.content == "rails routes failed: rails aborted!")
+        );
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_routes);
+    }
+}
@@ -1,4 +1,4 @@
-/// Scroll indicator component for showing position in scrollable content
+//! Scroll indicator component for showing position in scrollable content
 
 /// Scroll indicator to show current position in a list
 pub struct ScrollIndicator {
@@ -0,0 +1,149 @@
+/// Search bar component - shown above any view with an active search
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::search::SearchFilter;
+use crate::ui::theme::{Icons, Theme};
+
+/// Height of the search bar including borders
+pub const SEARCH_BAR_HEIGHT: u16 = 3;
+
+/// Render the search bar
+///
+/// While `editing`, a cursor is drawn after the query; once applied the bar
+/// stays visible so it is clear the view below is filtered.
+///
+/// `filter` is the compiled search of a view that takes regexes and case
+/// matching (Logs): the bar then shows which modes are on, and why a regex
+/// doesn't compile in place of matching nothing without a word.
+pub fn render_search_bar(
+    f: &mut Frame,
+    area: Rect,
+    view_name: &str,
+    query: &str,
+    filter: Option<&SearchFilter>,
+    editing: bool,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    let error = filter.and_then(SearchFilter::error);
+    let accent = if error.is_some() {
+        Theme::danger()
+    } else if editing {
+        Theme::primary()
+    } else {
+        Theme::text_secondary()
+    };
+
+    let mut spans = vec![
+        Span::styled(
+            format!(" {} ", Icons::search()),
+            Style::default().fg(Theme::apply_fade_to_color(accent, fade)),
+        ),
+        Span::styled(
+            query.to_string(),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(Theme::text_primary(), fade))
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if editing {
+        spans.push(Span::styled(
+            "█",
+            Style::default().fg(Theme::apply_fade_to_color(Theme::primary(), fade)),
+        ));
+    }
+    if let Some(error) = error {
+        spans.push(Span::styled(
+            format!("  ✗ {}", error),
+            Style::default().fg(Theme::apply_fade_to_color(Theme::danger(), fade)),
+        ));
+    }
+
+    let title = format!(" Search {} ", view_name);
+    let mut block = Theme::block(title, fade_progress)
+        .border_style(Style::default().fg(Theme::apply_fade_to_color(accent, fade)));
+    if let Some(filter) = filter {
+        block = block.title_top(
+            Line::from(Span::styled(
+                mode_label(filter, editing),
+                Style::default().fg(Theme::apply_fade_to_color(accent, fade)),
+            ))
+            .right_aligned(),
+        );
+    }
+
+    f.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+}
+
+/// " regex · Aa match case · Ctrl+I " on the bar's top right
+fn mode_label(filter: &SearchFilter, editing: bool) -> String {
+    let mut modes = Vec::new();
+    if filter.is_regex() {
+        modes.push("regex");
+    }
+    modes.push(if filter.case_sensitive() {
+        "Aa match case"
+    } else {
+        "aa any case"
+    });
+    if editing {
+        modes.push("Ctrl+I");
+    }
+    format!(" {} ", modes.join(" · "))
+}
+
+/// Split a content area into (search bar, remaining content)
+pub fn split_search_area(area: Rect) -> (Rect, Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(SEARCH_BAR_HEIGHT), Constraint::Min(0)])
+        .split(area);
+    (chunks[0], chunks[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_search_area() {
+        let (bar, content) = split_search_area(Rect::new(0, 5, 80, 20));
+
+        assert_eq!(bar, Rect::new(0, 5, 80, 3));
+        assert_eq!(content, Rect::new(0, 8, 80, 17));
+    }
+
+    #[test]
+    fn test_modes_and_regex_errors_are_shown() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let draw = |query: &str, case_sensitive: bool| {
+            let filter = SearchFilter::new(query, case_sensitive);
+            let mut terminal = Terminal::new(TestBackend::new(80, 3)).unwrap();
+            terminal
+                .draw(|f| render_search_bar(f, f.area(), "Logs", query, Some(&filter), true, None))
+                .unwrap();
+            terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let screen = draw("users", false);
+        assert!(screen.contains("aa any case · Ctrl+I"), "{}", screen);
+        assert!(!screen.contains("regex"), "{}", screen);
+
+        let screen = draw("re:Completed (5", true);
+        assert!(screen.contains("regex · Aa match case"), "{}", screen);
+        assert!(screen.contains("✗ unclosed group"), "{}", screen);
+    }
+}
@@ -0,0 +1,251 @@
+/// Status overlay - every process with its status, readiness, last exit, restarts and crashes
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Cell, Clear, Row, Table},
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::clock::Suspensions;
+use crate::process::{ProcessExit, ProcessInfo, ProcessStatus, ProjectHealth, Readiness, is_task};
+use crate::ui::formatting::format_duration;
+use crate::ui::theme::{Icons, Theme};
+
+/// Icon and color for a project health summary
+pub fn health_style(health: &ProjectHealth) -> (&'static str, Color) {
+    match health {
+        ProjectHealth::AllGo => (Icons::success(), Theme::success()),
+        ProjectHealth::Down(_) => (Icons::error(), Theme::danger()),
+        ProjectHealth::SlowBoot(_) | ProjectHealth::Stopped(_) => {
+            (Icons::warning(), Theme::warning())
+        }
+        ProjectHealth::Starting(_) | ProjectHealth::NoProcesses => (Icons::info(), Theme::info()),
+    }
+}
+
+/// Render the status overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Status: Crashed: worker (Esc to close) ─────────────────────────────────┐
+/// │ Process   Status    Ready           Uptime   Last exit  Restarts Crashes│
+/// │ web       running   ready           12m 3s   -          0        0      │
+/// │ worker    crashed   -               -        exit 1 4s  2        3      │
+/// └─────────────────────────────────────────────────────────────────────────┘
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn render_status(
+    f: &mut Frame,
+    area: Rect,
+    processes: &[ProcessInfo],
+    readiness: &HashMap<String, Readiness>,
+    health: &ProjectHealth,
+    suspensions: &Suspensions,
+    now: Instant,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let title = format!("Status: {} (Esc to close)", health.summary());
+    let block = Theme::block(title, fade_progress).style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let header = Row::new(vec![
+        "Process",
+        "Status",
+        "Ready",
+        "Uptime",
+        "Last exit",
+        "Restarts",
+        "Crashes",
+    ])
+    .style(
+        Style::default()
+            .fg(Theme::apply_fade_to_color(Theme::text_muted(), fade))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows: Vec<Row> = processes
+        .iter()
+        .map(|process| {
+            let finished_task = is_task(&process.name) && process.status != ProcessStatus::Running;
+            let (status, color) = match process.status {
+                _ if finished_task && process.last_exit.as_ref().is_some_and(|e| e.success) => {
+                    ("done", Theme::success())
+                }
+                _ if finished_task => ("failed", Theme::danger()),
+                ProcessStatus::Running => ("running", Theme::success()),
+                ProcessStatus::Stopped => ("stopped", Theme::text_muted()),
+                ProcessStatus::Crashed => ("crashed", Theme::danger()),
+                ProcessStatus::Detached => ("detached", Theme::warning()),
+                ProcessStatus::External => ("external", Theme::info()),
+            };
+            let ready = match process.status {
+                ProcessStatus::Running => readiness_label(readiness.get(&process.name)),
+                _ => "-".to_string(),
+            };
+            let uptime = match (&process.status, process.start_time) {
+                (ProcessStatus::Running, Some(start)) => suspensions.uptime(start, now).label(),
+                _ => "-".to_string(),
+            };
+
+            Row::new(vec![
+                Cell::from(process.name.clone()),
+                Cell::from(status)
+                    .style(Style::default().fg(Theme::apply_fade_to_color(color, fade))),
+                Cell::from(ready),
+                Cell::from(uptime),
+                match &process.spawn_error {
+                    Some(_) => Cell::from("failed to start").style(
+                        Style::default().fg(Theme::apply_fade_to_color(Theme::danger(), fade)),
+                    ),
+                    None => Cell::from(exit_label(process.last_exit.as_ref(), suspensions, now)),
+                },
+                Cell::from(process.restarts.to_string()),
+                Cell::from(process.crash_count.to_string()),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(9),
+            Constraint::Length(22),
+            Constraint::Length(8),
+            Constraint::Length(16),
+            Constraint::Length(8),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header);
+
+    f.render_widget(table, inner);
+}
+
+/// `ready`, `booting 12s`, `slow 2m 5s (1m 30s budget)` or `-` when unknown
+pub fn readiness_label(readiness: Option<&Readiness>) -> String {
+    match readiness {
+        Some(Readiness::Ready) => "ready".to_string(),
+        Some(state @ Readiness::Booting { elapsed, budget }) => {
+            if state.is_overdue() {
+                format!(
+                    "slow {} ({} budget)",
+                    format_duration(elapsed.as_secs()),
+                    format_duration(budget.as_secs())
+                )
+            } else {
+                format!("booting {}", format_duration(elapsed.as_secs()))
+            }
+        }
+        Some(Readiness::Unknown) | None => "-".to_string(),
+    }
+}
+
+/// `ok 3m 2s`, `exit 1 4s`, `killed 10s` (how long ago), or `-` before any exit
+///
+/// How long ago is marked `~` when the machine slept since.
+pub fn exit_label(exit: Option<&ProcessExit>, suspensions: &Suspensions, now: Instant) -> String {
+    let Some(exit) = exit else {
+        return "-".to_string();
+    };
+    let ago = suspensions.uptime(exit.at, now).label();
+    match exit.code {
+        _ if exit.success => format!("ok {}", ago),
+        Some(code) => format!("exit {} {}", code, ago),
+        None => format!("killed {}", ago),
+    }
+}
+
+/// Centered area for the overlay, sized to fit `rows` processes
+pub fn calculate_status_area(full_area: Rect, rows: usize) -> Rect {
+    let width = full_area.width.min(90);
+    // Borders + header + one line per process
+    let height = (rows.max(1) as u16 + 3).min(full_area.height);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_readiness_label_shows_budget_only_when_overdue() {
+        let booting = Readiness::Booting {
+            elapsed: Duration::from_secs(12),
+            budget: Duration::from_secs(90),
+        };
+        let slow = Readiness::Booting {
+            elapsed: Duration::from_secs(125),
+            budget: Duration::from_secs(90),
+        };
+
+        assert_eq!(readiness_label(Some(&Readiness::Ready)), "ready");
+        assert_eq!(readiness_label(Some(&booting)), "booting 12s");
+        assert_eq!(readiness_label(Some(&slow)), "slow 2m 5s (1m 30s budget)");
+        assert_eq!(readiness_label(Some(&Readiness::Unknown)), "-");
+    }
+
+    #[test]
+    fn test_exit_label_describes_how_the_run_ended() {
+        let now = Instant::now();
+        let exit = |success, code| ProcessExit {
+            success,
+            code,
+            at: now - Duration::from_secs(4),
+        };
+
+        let awake = Suspensions::default();
+
+        assert_eq!(exit_label(None, &awake, now), "-");
+        assert_eq!(exit_label(Some(&exit(true, Some(0))), &awake, now), "ok 4s");
+        assert_eq!(
+            exit_label(Some(&exit(false, Some(1))), &awake, now),
+            "exit 1 4s"
+        );
+        assert_eq!(
+            exit_label(Some(&exit(false, None)), &awake, now),
+            "killed 4s"
+        );
+
+        // The machine slept for 7h, a second of which the monotonic clock saw
+        let mut slept = Suspensions::default();
+        let wall = chrono::Local::now();
+        slept.tick(now - Duration::from_secs(3), wall);
+        slept.tick(
+            now - Duration::from_secs(2),
+            wall + chrono::TimeDelta::hours(7),
+        );
+        assert_eq!(
+            exit_label(Some(&exit(true, Some(0))), &slept, now),
+            "ok ~3s"
+        );
+    }
+
+    #[test]
+    fn test_calculate_status_area_fits_processes() {
+        let area = Rect::new(0, 0, 100, 40);
+        let overlay = calculate_status_area(area, 3);
+
+        assert_eq!(overlay.width, 90);
+        assert_eq!(overlay.height, 6);
+        assert_eq!(overlay.x, 5);
+    }
+}
@@ -0,0 +1,43 @@
+/// Tab indicator - one character per view, used instead of the tab bar in compact mode
+use ratatui::{
+    style::{Modifier, Style},
+    text::Span,
+};
+
+use crate::ui::ViewMode;
+use crate::ui::theme::Theme;
+
+/// First letter of each view's name, with the active view highlighted
+pub fn tab_indicator_spans(views: &[ViewMode], active: usize, fade: f32) -> Vec<Span<'static>> {
+    views
+        .iter()
+        .enumerate()
+        .map(|(i, view)| {
+            let letter = view.as_str().chars().next().unwrap_or('?');
+            let style = if i == active {
+                Style::default()
+                    .fg(Theme::apply_fade_to_color(Theme::primary(), fade))
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade))
+            };
+            Span::styled(format!(" {} ", letter), style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_letter_per_view_with_active_highlighted() {
+        let views = ViewMode::all_variants();
+        let spans = tab_indicator_spans(&views, 2, 1.0);
+
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, " L  Q  D  T  E  T  J ");
+        assert!(spans[2].style.add_modifier.contains(Modifier::REVERSED));
+        assert!(!spans[0].style.add_modifier.contains(Modifier::REVERSED));
+    }
+}
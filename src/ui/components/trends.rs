@@ -0,0 +1,250 @@
+/// Trends overlay - an endpoint's daily p50/p95 from the `[history]` store
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::history::{EndpointTrend, TREND_DAYS};
+use crate::ui::formatting::{format_ms, format_number};
+use crate::ui::theme::Theme;
+use crate::ui::widgets::Sparkline;
+
+/// Frames of the spinner shown while the history is read
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// What the overlay shows instead of the table
+pub struct TrendsStatus<'a> {
+    /// The history is being read
+    pub loading: bool,
+    /// Why it couldn't be read
+    pub error: Option<&'a str>,
+    pub spinner_frame: usize,
+}
+
+/// Render the trends overlay
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Trends: GET /api/orders (Esc to close) ──────────┐
+/// │ p95  45ms ▁▂▂▃▅▇ 310ms                           │
+/// │ Day              Requests       p50       p95    │
+/// │ Mon 06 Oct            120      38ms     120ms    │
+/// │ Tue 07 Oct             98      45ms     310ms    │
+/// └──────────────────────────────────────────────────┘
+/// ```
+///
+/// Days are listed oldest first; when they don't all fit, the newest are kept.
+pub fn render_trends(
+    f: &mut Frame,
+    area: Rect,
+    endpoint: &str,
+    trend: Option<&EndpointTrend>,
+    status: &TrendsStatus,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    f.render_widget(Clear, area);
+
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let block = Theme::block(
+        format!("Trends: {} (Esc to close)", endpoint),
+        fade_progress,
+    )
+    .style(
+        Style::default()
+            .bg(Theme::surface())
+            .fg(Theme::text_primary()),
+    );
+    let inner = block.inner(area);
+
+    let mut lines = Vec::new();
+    if let Some(error) = status.error {
+        lines.push(Line::styled(
+            error.to_string(),
+            Style::default().fg(Theme::apply_fade_to_color(Theme::danger(), fade)),
+        ));
+    } else if status.loading {
+        lines.push(Line::styled(
+            format!(
+                "{} Reading history...",
+                SPINNER_FRAMES[status.spinner_frame % SPINNER_FRAMES.len()]
+            ),
+            muted,
+        ));
+    } else if let Some(trend) = trend.filter(|trend| !trend.days.is_empty()) {
+        let p95: Vec<f64> = trend.days.iter().map(|day| day.p95).collect();
+        let mut chart = vec![Span::styled("p95 ", muted)];
+        chart.extend(
+            Sparkline::new(&p95)
+                .width((inner.width as usize).saturating_sub(4))
+                .labels(format_ms)
+                .style(Style::default().fg(Theme::apply_fade_to_color(Theme::info(), fade)))
+                .line()
+                .spans,
+        );
+        lines.push(Line::from(chart));
+        lines.push(Line::styled(
+            format!(
+                "{:<14} {:>10} {:>10} {:>10}",
+                "Day", "Requests", "p50", "p95"
+            ),
+            muted.add_modifier(Modifier::BOLD),
+        ));
+
+        let rows = (inner.height as usize).saturating_sub(lines.len());
+        let skip = trend.days.len().saturating_sub(rows);
+        lines.extend(trend.days.iter().skip(skip).map(|day| {
+            Line::from(format!(
+                "{:<14} {:>10} {:>10} {:>10}",
+                day.date.format("%a %d %b"),
+                format_number(day.requests),
+                format_ms(day.p50),
+                format_ms(day.p95),
+            ))
+        }));
+    } else {
+        lines.push(Line::styled(
+            format!(
+                "No requests to {} recorded in the last {} days",
+                endpoint, TREND_DAYS
+            ),
+            muted,
+        ));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Centered area for the overlay, room for a couple of weeks of days
+pub fn calculate_trends_area(full_area: Rect) -> Rect {
+    let width = full_area.width.min(72);
+    let height = full_area.height.min(20);
+
+    Rect::new(
+        full_area.x + (full_area.width - width) / 2,
+        full_area.y + (full_area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{DailyTrend, Endpoint};
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+    use chrono::NaiveDate;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn screen(trend: Option<&EndpointTrend>, status: TrendsStatus) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(72, 8)).unwrap();
+        terminal
+            .draw(|f| render_trends(f, f.area(), "GET /orders", trend, &status, None))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..8)
+            .map(|y| (0..72).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn idle() -> TrendsStatus<'static> {
+        TrendsStatus {
+            loading: false,
+            error: None,
+            spinner_frame: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_trends_keeps_the_newest_days() {
+        let days = (1..=10)
+            .map(|day| DailyTrend {
+                date: NaiveDate::from_ymd_opt(2025, 10, day).unwrap(),
+                requests: 100 + day as usize,
+                p50: 40.0,
+                p95: f64::from(day) * 100.0,
+            })
+            .collect();
+        let trend = EndpointTrend {
+            endpoint: Endpoint::parse("GET /orders").unwrap(),
+            days,
+        };
+        let screen = screen(Some(&trend), idle());
+
+        assert!(screen.contains("Trends: GET /orders"), "{}", screen);
+        assert!(screen.contains("p95"), "{}", screen);
+        assert!(screen.contains("Fri 10 Oct"), "{}", screen);
+        assert!(screen.contains("110"), "{}", screen);
+        assert!(screen.contains("1.00s"), "{}", screen);
+        assert!(!screen.contains("Wed 01 Oct"), "{}", screen);
+    }
+
+    #[test]
+    fn test_render_trends_without_data() {
+        let screen_empty = screen(None, idle());
+        assert!(
+            screen_empty.contains("No requests to GET /orders recorded"),
+            "{}",
+            screen_empty
+        );
+
+        let loading = TrendsStatus {
+            loading: true,
+            ..idle()
+        };
+        assert!(screen(None, loading).contains("Reading history..."));
+    }
+
+    #[test]
+    fn test_history_records_requests_and_trends_shows_them() {
+        let mut app = test_app();
+        app.command_input = "/trends GET /users".to_string();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message)) if message.contains("[history]")
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+        app.exit_command_mode();
+
+        let dir = std::env::temp_dir().join(format!("caboose_ui_history_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let (store, _) = crate::history::HistoryStore::open(&dir.join("history.db"), 30).unwrap();
+        let mut app = test_app().with_history(HistoryWriter::spawn(store));
+        for ms in [30, 50, 70] {
+            for content in [
+                r#"Started GET "/users/7" for 127.0.0.1"#.to_string(),
+                format!("Completed 200 OK in {}ms", ms),
+            ] {
+                app.add_log(LogLine::new("web", content));
+            }
+        }
+
+        app.command_input = "/trends get /users/1".to_string();
+        app.execute_command();
+        assert!(app.show_trends);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while app.trends_loading.is_some() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            app.check_trends();
+        }
+        let trend = app.trends.as_ref().expect("trend read");
+        assert_eq!(trend.days.len(), 1);
+        assert_eq!((trend.days[0].requests, trend.days[0].p50), (3, 50.0));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Trends: GET /users/:id"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_trends);
+    }
+}
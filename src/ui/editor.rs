@@ -0,0 +1,134 @@
+//! Opening a file at a line in the user's editor
+//!
+//! `$VISUAL`, then `$EDITOR`, then `vi`. Terminal editors take over the
+//! screen, so the TUI steps aside while one runs; GUI editors return at once.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A file to open, and where in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorTarget {
+    pub path: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl EditorTarget {
+    /// `file` as printed by a tool run in `dir`, unless it's absolute
+    pub fn resolve(file: &str, dir: Option<&Path>, line: Option<u32>, column: Option<u32>) -> Self {
+        let path = Path::new(file);
+        let path = match dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        };
+        Self { path, line, column }
+    }
+}
+
+/// The editor to use, from the environment
+pub fn editor_from_env() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Program and arguments opening `target` in `editor`
+///
+/// `editor` may carry its own arguments (`code --wait`). VS Code, Sublime,
+/// Zed and Helix take `file:line:col`; everything else gets vi's `+line`.
+pub fn editor_command(editor: &str, target: &EditorTarget) -> (String, Vec<String>) {
+    let mut words = editor.split_whitespace().map(str::to_string);
+    let program = words.next().unwrap_or_else(|| "vi".to_string());
+    let mut args: Vec<String> = words.collect();
+
+    let name = Path::new(&program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let path = target.path.display().to_string();
+    match (name, target.line) {
+        ("code" | "code-insiders" | "codium" | "cursor", Some(line)) => {
+            args.push("--goto".to_string());
+            args.push(with_position(&path, line, target.column));
+        }
+        ("subl" | "zed" | "hx" | "helix", Some(line)) => {
+            args.push(with_position(&path, line, target.column));
+        }
+        (_, Some(line)) => {
+            args.push(format!("+{}", line));
+            args.push(path);
+        }
+        (_, None) => args.push(path),
+    }
+    (program, args)
+}
+
+fn with_position(path: &str, line: u32, column: Option<u32>) -> String {
+    match column {
+        Some(column) => format!("{}:{}:{}", path, line, column),
+        None => format!("{}:{}", path, line),
+    }
+}
+
+/// Run the editor on `target` and wait for it
+pub fn open_in_editor(target: &EditorTarget) -> Result<(), String> {
+    let editor = editor_from_env();
+    let (program, args) = editor_command(&editor, target);
+    let status = Command::new(&program)
+        .args(&args)
+        .status()
+        .map_err(|e| format!("Couldn't run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> EditorTarget {
+        EditorTarget::resolve(
+            "src/App.tsx",
+            Some(Path::new("/shop/frontend")),
+            Some(12),
+            Some(8),
+        )
+    }
+
+    #[test]
+    fn test_relative_files_resolve_against_the_tool_directory() {
+        assert_eq!(target().path, PathBuf::from("/shop/frontend/src/App.tsx"));
+        assert_eq!(
+            EditorTarget::resolve("/abs/App.tsx", Some(Path::new("/shop")), None, None).path,
+            PathBuf::from("/abs/App.tsx")
+        );
+    }
+
+    #[test]
+    fn test_editor_command_passes_the_line_the_way_each_editor_takes_it() {
+        assert_eq!(
+            editor_command("nvim", &target()),
+            (
+                "nvim".to_string(),
+                vec!["+12".to_string(), "/shop/frontend/src/App.tsx".to_string()]
+            )
+        );
+        assert_eq!(
+            editor_command("/usr/bin/code --wait", &target()).1,
+            vec!["--wait", "--goto", "/shop/frontend/src/App.tsx:12:8"]
+        );
+        assert_eq!(
+            editor_command("hx", &target()).1,
+            vec!["/shop/frontend/src/App.tsx:12:8"]
+        );
+
+        let no_line = EditorTarget::resolve("src/App.tsx", None, None, None);
+        assert_eq!(editor_command("vim", &no_line).1, vec!["src/App.tsx"]);
+    }
+}
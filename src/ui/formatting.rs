@@ -1,5 +1,9 @@
 /// Formatting utilities for consistent display of numbers, durations, and text
+use ratatui::style::Style;
+use ratatui::text::Span;
+use std::ops::Range;
 use std::time::Duration;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Format elapsed time in human-readable relative format
 ///
@@ -73,6 +77,27 @@ pub fn format_duration(seconds: u64) -> String {
     }
 }
 
+/// Format a configured window in its largest whole unit
+///
+/// # Examples
+/// ```rust
+/// use caboose::ui::formatting::format_window;
+/// use std::time::Duration;
+///
+/// assert_eq!(format_window(Duration::from_secs(120)), "2m");
+/// assert_eq!(format_window(Duration::from_secs(90)), "90s");
+/// ```
+pub fn format_window(window: Duration) -> String {
+    let secs = window.as_secs();
+    if secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
 /// Format milliseconds with appropriate precision
 ///
 /// # Examples
@@ -135,11 +160,173 @@ pub fn pad_or_truncate(text: &str, width: usize) -> String {
     }
 }
 
+/// Shorten text to `width` characters by replacing its middle with `…`
+///
+/// Keeps both ends of a path, e.g. `/admin/acc…sessions/12`.
+pub fn truncate_middle(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail_len = (width - 1) / 2;
+    let head: String = text.chars().take(width - 1 - tail_len).collect();
+    let tail: String = text.chars().skip(len - tail_len).collect();
+    format!("{}…{}", head, tail)
+}
+
 /// Format Duration to human-readable string
 pub fn format_rust_duration(duration: Duration) -> String {
     format_duration(duration.as_secs())
 }
 
+/// Total display width (terminal columns) of a list of spans
+pub fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(|s| s.content.width()).sum()
+}
+
+/// Drop the first `columns` display columns from a list of styled spans
+///
+/// Widths are measured in terminal cells, so CJK characters and emoji count
+/// as two columns. Each span keeps its style; spans that are skipped entirely
+/// are removed. If the cut lands in the middle of a wide character, the
+/// visible half is replaced with a space so the rest of the line stays aligned.
+pub fn skip_display_columns<'a>(spans: Vec<Span<'a>>, columns: usize) -> Vec<Span<'a>> {
+    if columns == 0 {
+        return spans;
+    }
+
+    let mut remaining = columns;
+    let mut result = Vec::with_capacity(spans.len());
+
+    for span in spans {
+        if remaining == 0 {
+            result.push(span);
+            continue;
+        }
+
+        let span_width = span.content.width();
+        if span_width <= remaining {
+            remaining -= span_width;
+            continue;
+        }
+
+        let mut kept = String::new();
+        for ch in span.content.chars() {
+            if remaining == 0 {
+                kept.push(ch);
+                continue;
+            }
+            let ch_width = ch.width().unwrap_or(0);
+            if ch_width <= remaining {
+                remaining -= ch_width;
+            } else {
+                // Wide character straddles the cut: pad the visible part
+                kept.push_str(&" ".repeat(ch_width - remaining));
+                remaining = 0;
+            }
+        }
+
+        result.push(Span::styled(kept, span.style));
+    }
+
+    result
+}
+
+/// Keep only the first `columns` display columns of a list of styled spans
+///
+/// The counterpart of [`skip_display_columns`]. A wide character that
+/// doesn't fit whole is replaced with a space, so the result is never wider
+/// than `columns`.
+pub fn take_display_columns<'a>(spans: Vec<Span<'a>>, columns: usize) -> Vec<Span<'a>> {
+    let mut remaining = columns;
+    let mut result = Vec::with_capacity(spans.len());
+
+    for span in spans {
+        if remaining == 0 {
+            break;
+        }
+
+        let span_width = span.content.width();
+        if span_width <= remaining {
+            remaining -= span_width;
+            result.push(span);
+            continue;
+        }
+
+        let mut kept = String::new();
+        for ch in span.content.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            if ch_width > remaining {
+                kept.push_str(&" ".repeat(remaining));
+                remaining = 0;
+                break;
+            }
+            remaining -= ch_width;
+            kept.push(ch);
+        }
+        result.push(Span::styled(kept, span.style));
+    }
+
+    result
+}
+
+/// Patch `style` onto the parts of a list of spans that fall in `ranges`
+///
+/// Ranges are byte offsets into the spans' text joined together, in order
+/// and not overlapping, as a search's matches are. Spans are split where a
+/// range starts or ends inside them.
+pub fn highlight_ranges<'a>(
+    spans: Vec<Span<'a>>,
+    ranges: &[Range<usize>],
+    style: Style,
+) -> Vec<Span<'a>> {
+    if ranges.is_empty() {
+        return spans;
+    }
+
+    let mut result = Vec::with_capacity(spans.len() + ranges.len() * 2);
+    let mut ranges = ranges.iter().peekable();
+    let mut offset = 0;
+
+    for span in spans {
+        let text = span.content.as_ref();
+        let end = offset + text.len();
+        let mut cut = 0;
+        while let Some(range) = ranges.peek() {
+            if range.start >= end {
+                break;
+            }
+            let start = range.start.clamp(offset, end) - offset;
+            let stop = range.end.min(end) - offset;
+            if start > cut {
+                result.push(Span::styled(text[cut..start].to_string(), span.style));
+            }
+            if stop > start {
+                result.push(Span::styled(
+                    text[start..stop].to_string(),
+                    span.style.patch(style),
+                ));
+            }
+            cut = cut.max(stop);
+            if range.end > end {
+                break;
+            }
+            ranges.next();
+        }
+        if cut == 0 {
+            result.push(span);
+        } else if cut < text.len() {
+            result.push(Span::styled(text[cut..].to_string(), span.style));
+        }
+        offset = end;
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,6 +348,15 @@ mod tests {
         assert_eq!(format_duration(3661), "1h 1m");
     }
 
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(truncate_middle("/users/42", 20), "/users/42");
+        assert_eq!(truncate_middle("/users/42/edit", 9), "/use…edit");
+        assert_eq!(truncate_middle("/users/42/edit", 8), "/use…dit");
+        assert_eq!(truncate_middle("/users", 1), "…");
+        assert_eq!(truncate_middle("/users", 0), "");
+    }
+
     #[test]
     fn test_format_ms() {
         assert_eq!(format_ms(0.5), "0.50ms");
@@ -183,9 +379,94 @@ mod tests {
         assert_eq!(truncate("hi", 1), "...");
     }
 
+    #[test]
+    fn test_skip_display_columns_preserves_styles() {
+        use ratatui::style::{Color, Style};
+
+        let red = Style::default().fg(Color::Red);
+        let blue = Style::default().fg(Color::Blue);
+        let spans = vec![Span::styled("abc", red), Span::styled("defgh", blue)];
+
+        let result = skip_display_columns(spans, 4);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].content, "efgh");
+        assert_eq!(result[0].style, blue);
+    }
+
+    #[test]
+    fn test_skip_display_columns_cjk() {
+        // Each CJK character occupies two columns
+        let spans = vec![Span::raw("日本語テキスト")];
+        assert_eq!(spans_width(&spans), 14);
+
+        let result = skip_display_columns(spans.clone(), 4);
+        assert_eq!(result[0].content, "語テキスト");
+
+        // Cutting through the middle of a character leaves a padding space
+        let result = skip_display_columns(spans, 3);
+        assert_eq!(result[0].content, " 語テキスト");
+        assert_eq!(spans_width(&result), 11);
+    }
+
+    #[test]
+    fn test_skip_display_columns_emoji() {
+        let spans = vec![Span::raw("🚀 deploy"), Span::raw(" ✅ done")];
+        assert_eq!(spans_width(&spans), 17);
+
+        let result = skip_display_columns(spans.clone(), 2);
+        assert_eq!(result[0].content, " deploy");
+
+        let result = skip_display_columns(spans.clone(), 1);
+        assert_eq!(result[0].content, "  deploy");
+
+        // Skipping past the end drops everything
+        assert!(skip_display_columns(spans, 40).is_empty());
+    }
+
+    #[test]
+    fn test_take_display_columns() {
+        let spans = vec![Span::raw("abc"), Span::raw("日本語")];
+        let result = take_display_columns(spans.clone(), 6);
+        assert_eq!(spans_width(&result), 6);
+        assert_eq!(result[1].content, "日 ");
+
+        // Shorter lines come back whole
+        assert_eq!(take_display_columns(spans.clone(), 40), spans);
+        assert!(take_display_columns(spans, 0).is_empty());
+    }
+
     #[test]
     fn test_pad_or_truncate() {
         assert_eq!(pad_or_truncate("hello", 10), "hello     ");
         assert_eq!(pad_or_truncate("hello world", 8), "hello...");
     }
+
+    #[test]
+    fn test_highlight_ranges_splits_spans_at_match_edges() {
+        use ratatui::style::{Color, Modifier};
+
+        let green = Style::default().fg(Color::Green);
+        let mark = Style::default().add_modifier(Modifier::REVERSED);
+        let spans = vec![Span::raw("Completed "), Span::styled("500 Error", green)];
+
+        // "ted 5" crosses the two spans, "Err" sits inside the second
+        let result = highlight_ranges(spans.clone(), &[6..11, 14..17], mark);
+        let parts: Vec<(&str, Style)> = result
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("Comple", Style::default()),
+                ("ted ", mark),
+                ("5", green.patch(mark)),
+                ("00 ", green),
+                ("Err", green.patch(mark)),
+                ("or", green),
+            ]
+        );
+
+        assert_eq!(highlight_ranges(spans.clone(), &[], mark), spans);
+    }
 }
@@ -63,14 +63,14 @@ impl IconManager {
 
         // Strategy 4: Check for WSL (Windows Subsystem for Linux)
         // Modern Windows Terminal supports Nerd Fonts well
-        if let Ok(wsl) = env::var("WSL_DISTRO_NAME") {
-            if !wsl.is_empty() {
-                // Check if using Windows Terminal
-                if let Ok(term_program) = env::var("WT_SESSION") {
-                    if !term_program.is_empty() {
-                        return true;
-                    }
-                }
+        if let Ok(wsl) = env::var("WSL_DISTRO_NAME")
+            && !wsl.is_empty()
+        {
+            // Check if using Windows Terminal
+            if let Ok(term_program) = env::var("WT_SESSION")
+                && !term_program.is_empty()
+            {
+                return true;
             }
         }
 
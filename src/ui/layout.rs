@@ -0,0 +1,81 @@
+/// Responsive layout - compact mode for small terminals and minimum-size guards
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::Style,
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+use crate::ui::theme::Theme;
+
+/// Smallest area a view is rendered into; anything smaller gets a placeholder
+pub const MIN_VIEW_WIDTH: u16 = 20;
+pub const MIN_VIEW_HEIGHT: u16 = 3;
+
+/// Terminal size below which the compact layout is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactThresholds {
+    /// Compact when the terminal has fewer rows than this
+    pub height: u16,
+    /// Compact when the terminal has fewer columns than this
+    pub width: u16,
+}
+
+impl Default for CompactThresholds {
+    fn default() -> Self {
+        Self {
+            height: 25,
+            width: 80,
+        }
+    }
+}
+
+impl CompactThresholds {
+    pub fn is_compact(&self, area: Rect) -> bool {
+        area.height < self.height || area.width < self.width
+    }
+}
+
+/// Whether `area` is too small to render a view into
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_VIEW_WIDTH || area.height < MIN_VIEW_HEIGHT
+}
+
+/// "Window too small" placeholder, shown instead of a view that wouldn't fit
+pub fn render_too_small(f: &mut Frame, area: Rect) {
+    f.render_widget(Clear, area);
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    let message = format!("Window too small ({}x{})", area.width, area.height);
+    let y = area.y + area.height.saturating_sub(1) / 2;
+    let line = Rect::new(area.x, y, area.width, area.bottom() - y);
+    f.render_widget(
+        Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Theme::warning())),
+        line,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_below_either_threshold() {
+        let thresholds = CompactThresholds::default();
+        assert!(!thresholds.is_compact(Rect::new(0, 0, 120, 40)));
+        assert!(thresholds.is_compact(Rect::new(0, 0, 80, 20)));
+        assert!(thresholds.is_compact(Rect::new(0, 0, 60, 40)));
+    }
+
+    #[test]
+    fn test_too_small_for_a_view() {
+        assert!(is_too_small(Rect::new(0, 0, 80, 2)));
+        assert!(is_too_small(Rect::new(0, 0, 10, 10)));
+        assert!(!is_too_small(Rect::new(0, 0, 60, 8)));
+    }
+}
@@ -1,7 +1,14 @@
+pub mod annotations;
+pub mod autofocus;
+pub mod click_map;
 pub mod command;
 pub mod components;
+pub mod editor;
 pub mod formatting;
 pub mod icon_manager;
+pub mod layout;
+pub mod process_control;
+pub mod terminal;
 /// UI Module - Terminal User Interface
 ///
 /// This module provides a modular, professional-grade terminal UI framework
@@ -9,27 +16,62 @@ pub mod icon_manager;
 // Public modules
 pub mod theme;
 pub mod themes;
+pub mod ui_state;
 pub mod views;
 pub mod widgets;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-exports for convenience
 pub use formatting::*;
 pub use theme::Theme;
 
-use crate::context::RequestContextTracker;
-use crate::database::DatabaseHealth;
-use crate::exception::ExceptionTracker;
+use crate::alerts::{AlertEngine, AlertRule, AlertSources};
+use crate::clock::{Clock, Suspensions, SystemClock};
+use crate::config::ConfigWatcher;
+use crate::context::{BurstKind, RequestContextTracker, is_console_prompt};
+use crate::database::{DatabaseHealth, SCORE_SAMPLE_INTERVAL, SLOW_QUERY_THRESHOLD, SqlDialect};
+use crate::exception::{ExceptionSeverity, ExceptionTracker};
+use crate::frontend::{
+    BuildChange, BuildErrors, BuildState, DEFAULT_FRONTEND_PROCESS, FrontendLogParser,
+    FrontendTracker,
+};
 use crate::git::GitInfo;
-use crate::parser::{LogEvent, RailsLogParser};
-use crate::process::{LogLine, ProcessInfo};
-use crate::stats::StatsCollector;
+use crate::history::{
+    Endpoint, EndpointTrend, HistoryRecord, HistoryWriter, RequestSummary, TrendResult,
+};
+use crate::jobs::JobTracker;
+use crate::memory::{MemoryReports, ReportChange};
+use crate::parser::{
+    InstrumentationEvent, InstrumentationGems, InstrumentationParser, LogEvent, ParseCoverage,
+    RailsLogParser, request_id_tag,
+};
+use crate::persistence::{self, PersistedAnalytics};
+use crate::privacy::Privacy;
+use crate::process::{
+    BootTimes, LineTruncator, LogCategory, LogLevel, LogLine, ProcessFilter, ProcessInfo,
+    ProcessStatus, ProjectHealth, Readiness, is_ready_line, project_health,
+};
+use crate::query::QueryFingerprint;
+use crate::shared::SnapshotCache;
+use crate::stats::{
+    CacheCounts, DbShare, DbShareLevel, DbShareThresholds, HeaderStats, StatsCollector,
+    TimelineEvent,
+};
 use crate::test::TestTracker;
-use crate::ui::components::FooterBuilder;
+use crate::ui::autofocus::{FocusEvent, FocusTarget};
+use crate::ui::click_map::{ClickMap, ClickTarget, ClickTracker};
+use crate::ui::components::{FooterBuilder, LogChips, LogColors, LogOrder, LogViewport};
 use crate::ui::theme::Icons;
-use crate::ui::widgets::Sparkline; // Import Sparkline
+use crate::ui::ui_state::UiState;
+use crate::ui::widgets::{Aggregation, SortChange, SortState, Sparkline, VisualBar};
 
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -43,24 +85,55 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Tabs},
 };
 
+use crate::diagnostics::{FrameTimings, Limits, StoreKind, StoreUsage};
+use crate::disk::DiskWarning;
+use crate::project::ProjectRoot;
+use crate::rails::routes::{RouteTable, spawn_routes_refresh};
+use crate::rails::watch::ProjectChange;
+
+use crate::search::{SearchFilter, SearchQuery};
+use crate::session::{SessionMetadata, SessionSources};
+use crate::time_format::DisplayZone;
+use crate::time_range::TimeRange;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::io;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant}; // Import Instant
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// Preset entry counts the diagnostics overlay can trim a store down to
+const DIAGNOSTICS_KEEP_STEPS: [usize; 6] = [10, 50, 100, 250, 500, 1000];
+
+/// Weighted exceptions per minute shown as a warning, and as danger (one
+/// critical exception is 5)
+const EXCEPTION_RATE_WARNING: f64 = 1.0;
+const EXCEPTION_RATE_DANGER: f64 = 5.0;
+
+/// How often a health score sample is also kept in `[history]`
+const HEALTH_HISTORY_INTERVAL: Duration = Duration::from_secs(60);
 
 // ============================================================================
 // VIEW MODE
 // ============================================================================
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ViewMode {
     Logs,
     QueryAnalysis,
     RequestDetail(usize),
+    /// Follows an in-flight request by its tracker id until it completes
+    LiveRequest(u64),
     DatabaseHealth,
+    /// Drill-down into one table from Database Health's top tables
+    TableDetail(String),
     TestResults,
     Exceptions,
     ExceptionDetail(usize),
+    /// Request volume and notable events over the session
+    Timeline,
+    /// Background job runs and failures, from ActiveJob and Sidekiq lines
+    Jobs,
 }
 
 impl ViewMode {
@@ -69,13 +142,29 @@ impl ViewMode {
             ViewMode::Logs => "Logs",
             ViewMode::QueryAnalysis => "Query Analysis",
             ViewMode::RequestDetail(_) => "Request Detail",
+            ViewMode::LiveRequest(_) => "Live Request",
             ViewMode::DatabaseHealth => "Database Health",
+            ViewMode::TableDetail(_) => "Table Detail",
             ViewMode::TestResults => "Test Results",
             ViewMode::Exceptions => "Exceptions",
             ViewMode::ExceptionDetail(_) => "Exception Detail",
+            ViewMode::Timeline => "Timeline",
+            ViewMode::Jobs => "Jobs",
         }
     }
 
+    /// Whether `/` opens a search bar in this view (detail views have none)
+    pub fn is_searchable(&self) -> bool {
+        !matches!(
+            self,
+            ViewMode::RequestDetail(_)
+                | ViewMode::LiveRequest(_)
+                | ViewMode::TableDetail(_)
+                | ViewMode::ExceptionDetail(_)
+                | ViewMode::Timeline
+        )
+    }
+
     pub fn all_variants() -> Vec<ViewMode> {
         vec![
             ViewMode::Logs,
@@ -83,6 +172,8 @@ impl ViewMode {
             ViewMode::DatabaseHealth,
             ViewMode::TestResults,
             ViewMode::Exceptions,
+            ViewMode::Timeline,
+            ViewMode::Jobs,
         ]
     }
 
@@ -93,6 +184,8 @@ impl ViewMode {
             2 => Some(ViewMode::DatabaseHealth),
             3 => Some(ViewMode::TestResults),
             4 => Some(ViewMode::Exceptions),
+            5 => Some(ViewMode::Timeline),
+            6 => Some(ViewMode::Jobs),
             _ => None,
         }
     }
@@ -105,9 +198,14 @@ impl ViewMode {
 /// Main application state
 pub struct App {
     // Process and log data
-    processes: Vec<ProcessInfo>,
+    processes: std::sync::Arc<Vec<ProcessInfo>>,
+    boot_times: BootTimes,
     logs: Vec<LogLine>,
     max_logs: usize,
+    /// Cuts overlong lines before they are stored or parsed
+    line_truncator: LineTruncator,
+    /// Parsers for the Gemfile's APM / instrumentation gems (Bullet, ...)
+    instrumentation: InstrumentationParser,
 
     // Application state
     should_quit: bool,
@@ -118,21 +216,80 @@ pub struct App {
     _git_info: GitInfo,
     environment_info: crate::environment::EnvironmentInfo,
     stats_collector: StatsCollector,
+    /// The header's stats line, rebuilt only when the stats change
+    header_stats: SnapshotCache<HeaderStats>,
     context_tracker: std::sync::Arc<RequestContextTracker>,
     db_health: std::sync::Arc<DatabaseHealth>,
     test_tracker: std::sync::Arc<TestTracker>,
     exception_tracker: std::sync::Arc<ExceptionTracker>,
+    job_tracker: JobTracker,
 
     // UI state
     search_mode: bool,
-    search_query: String,
-    log_scroll: usize,
+    /// Search query per view, so each tab keeps its own filter
+    search_queries: HashMap<ViewMode, String>,
+    /// The Logs search matches case; toggled with Ctrl+I while searching
+    search_case_sensitive: bool,
+    /// Next `LogLine::seq` to hand out
+    next_log_seq: u64,
+    log_viewport: LogViewport,
     horizontal_scroll: usize,
+    /// Longest scrollable offset seen in the last Logs render
+    max_horizontal_scroll: Cell<usize>,
     auto_scroll: bool,
-    _request_scroll: usize,
+    /// Which end of the log the Logs view shows at the top
+    log_order: LogOrder,
+    /// Whether log lines keep the colors their process printed
+    log_colors: LogColors,
+    /// Terminal size below which the compact layout is used
+    compact_thresholds: layout::CompactThresholds,
+    /// Process panel is hidden in the compact layout unless toggled on
+    show_compact_processes: bool,
+    /// Whether the Logs view's Processes panel has the keys (Tab)
+    processes_focused: bool,
+    /// Cursor in the Processes panel
+    selected_process: usize,
+    /// A stop or restart waiting for its key to be pressed again
+    pending_process_action: Option<process_control::PendingAction>,
+    /// Whether the last frame showed the Processes panel
+    process_panel_shown: Cell<bool>,
+    /// Tabs and rows the last frame drew, for mouse clicks
+    click_map: RefCell<ClickMap>,
+    clicks: ClickTracker,
+    /// Lines Request Detail's timeline is scrolled down
+    request_detail_scroll: usize,
     selected_request: usize,
+    /// Set while the selection is in Query Analysis's In Flight section
+    selected_in_flight: Option<usize>,
+    /// Set while the selection is in Query Analysis's N+1 section
+    selected_n_plus_one: Option<usize>,
+    /// Whether Query Analysis lists the N+1 queries or only counts them
+    n_plus_one_expanded: bool,
     selected_exception: usize,
-    filter_process: Option<String>,
+    /// Severity, recency and dismissal narrowing the Exceptions list
+    exception_filter: crate::exception::ExceptionFilter,
+    /// Failure selected in Test Results, and the one showing its message
+    selected_test_failure: usize,
+    expanded_test_failure: Option<usize>,
+    selected_table: usize,
+    /// Exception list order; the selection is a position in it
+    exception_sort: SortState,
+    slow_query_sort: SortState,
+    filter_process: Option<ProcessFilter>,
+    /// Least severe lines the Logs view shows, set with `/level`
+    min_log_level: Option<LogLevel>,
+    /// Category chips above the Logs view, and the categories they hide
+    log_chips: LogChips,
+    /// Logs view constrained to a time window by `/around`
+    time_range: Option<TimeRange>,
+    /// Index into [`views::timeline_view::ZOOM_LEVELS`]
+    timeline_zoom: usize,
+    /// Time inside the selected timeline bucket; `None` follows the latest
+    timeline_cursor: Option<chrono::DateTime<chrono::Local>>,
+    /// Zone wall-clock times are shown and exported in (`[ui] timezone`)
+    display_zone: DisplayZone,
+    /// Masking of personal data for screen sharing (`/privacy`, `P`)
+    privacy: Privacy,
 
     // Command system
     command_mode: bool,
@@ -143,6 +300,118 @@ pub struct App {
     command_suggestions: Vec<command::autocomplete::Suggestion>,
     selected_suggestion: usize,
     last_command_result: Option<command::ExecutionResult>,
+    /// Corrected command from the last "did you mean" error, filled by Tab
+    command_fix: Option<String>,
+    /// A command asking for its missing arguments; the input holds the next one
+    command_prompt: Option<command::ArgPrompt>,
+
+    // Processes that daemonized, and the warning shown until the next key press
+    // (also used for config reload problems)
+    warned_detached: Vec<String>,
+    process_warning: Option<String>,
+
+    // `[disk]` watcher results, the warning shown until the next key press
+    // (`x` truncates a large log) and what has been warned about while it lasts
+    disk_watcher: Option<watch::Receiver<Vec<DiskWarning>>>,
+    disk_warning: Option<DiskWarning>,
+    disk_warned: Vec<String>,
+
+    // Re-read schema.rb, routes.rb and database.yml, as they change
+    project_watcher: Option<mpsc::UnboundedReceiver<ProjectChange>>,
+
+    // Project root, recorded in saved sessions
+    project: Option<std::path::PathBuf>,
+    // Set when showing a saved session (`caboose open`): nothing runs and
+    // nothing may be changed on disk or in processes
+    viewing_session: Option<SessionMetadata>,
+
+    // Request Detail: whether the unrecognized extra fields are expanded
+    show_request_extra: bool,
+
+    // Diagnostics overlay
+    show_diagnostics: bool,
+    diagnostics_selected: usize,
+    diagnostics_keep: usize,
+    diagnostics_system: sysinfo::System,
+    diagnostics_rss: Option<u64>,
+    diagnostics_refreshed_at: Option<Instant>,
+    frame_timings: FrameTimings,
+    // Lines the parsers understood, and where `[logs] dump_unparsed` writes
+    // the samples of those they didn't on exit
+    parse_coverage: ParseCoverage,
+    unparsed_dump: Option<std::path::PathBuf>,
+    // Where the tab, filters and sorts are saved on exit for the next run
+    ui_state_file: Option<std::path::PathBuf>,
+    // Where the analytics are saved every `persistence::SAVE_INTERVAL` and
+    // on exit, and when they last were
+    analytics_file: Option<std::path::PathBuf>,
+    analytics_saved_at: Option<Instant>,
+
+    // `[alerts]` rules, and whether the `/alerts` overlay is open
+    alert_engine: AlertEngine,
+    show_alerts: bool,
+    /// Keys and commands overlay (`?` or `/help`)
+    show_help: bool,
+    /// Per-process status overlay (`s` or `/status`)
+    show_status: bool,
+
+    // Frontend compile failures, the overlay showing them (`/builderr`) and
+    // its selected error; relative paths in them resolve against the
+    // frontend directory. The editor to open once the terminal is released.
+    build_errors: BuildErrors,
+    show_build_error: bool,
+    build_error_selected: usize,
+    frontend_dir: Option<std::path::PathBuf>,
+    /// Builds, hot updates and the dev server of the frontend process
+    frontend: FrontendTracker,
+    editor_request: Option<editor::EditorTarget>,
+
+    // memory_profiler / derailed reports processes printed, the `/memory`
+    // overlay and its scroll, and whether the latest report was looked at
+    memory_reports: MemoryReports,
+    show_memory: bool,
+    memory_scroll: usize,
+    memory_seen: bool,
+
+    // `/routes` overlay: the table from `rails routes` (cached in `.caboose/`),
+    // the run in progress and what the last failed run printed
+    routes_available: bool,
+    routes: Option<std::sync::Arc<RouteTable>>,
+    routes_loading: Option<oneshot::Receiver<Result<RouteTable, String>>>,
+    routes_error: Option<String>,
+    show_routes: bool,
+    routes_query: String,
+    routes_scroll: usize,
+
+    // `[history]` writer, and the `/trends` overlay: the endpoint asked for,
+    // its trend once read and why it couldn't be
+    history: Option<HistoryWriter>,
+    // `/autofocus`: switches views on notable events; whether the Logs
+    // process filter is its own, so returning to Logs clears it
+    autofocus: autofocus::AutoFocus,
+    autofocus_filter: bool,
+    // `/annotations`: request, query and exception lines by sequence number,
+    // annotated at their right edge in the Logs view
+    log_annotations: annotations::LogAnnotations,
+    // Line that started the exception still being read, annotated once it's grouped
+    pending_exception_seq: Option<u64>,
+    // Processes `/send` writes to, in a live session
+    process_manager: Option<std::sync::Arc<crate::process::ProcessManager>>,
+    // Where uptimes and ages are read from, and the suspensions seen so far
+    clock: std::sync::Arc<dyn Clock>,
+    suspensions: Suspensions,
+    health_recorded_at: Option<Instant>,
+    show_trends: bool,
+    trends_endpoint: Option<Endpoint>,
+    trends: Option<EndpointTrend>,
+    trends_loading: Option<oneshot::Receiver<TrendResult>>,
+    trends_error: Option<String>,
+
+    // Re-applies `[limits]` when the config file changes
+    config_watcher: Option<ConfigWatcher>,
+
+    // When the database health score was last added to its trend
+    health_sampled_at: Option<Instant>,
 
     // Animation state
     spinner_frame: usize,
@@ -156,6 +425,7 @@ impl App {
     /// Create a new application instance
     pub fn new(
         git_info: GitInfo,
+        environment_info: crate::environment::EnvironmentInfo,
         stats_collector: StatsCollector,
         context_tracker: std::sync::Arc<RequestContextTracker>,
         db_health: std::sync::Arc<DatabaseHealth>,
@@ -168,28 +438,62 @@ impl App {
         let command_autocomplete = command::AutocompleteEngine::new(command_metadata);
 
         Self {
-            processes: Vec::new(),
+            processes: std::sync::Arc::default(),
+            boot_times: BootTimes::new(),
             logs: Vec::new(),
-            max_logs: 1000,
+            max_logs: Limits::default().max_logs,
+            line_truncator: LineTruncator::default(),
+            instrumentation: InstrumentationParser::default(),
             should_quit: false,
             _git_info: git_info,
-            environment_info: crate::environment::EnvironmentInfo::detect(),
+            environment_info,
             stats_collector,
+            header_stats: SnapshotCache::default(),
             context_tracker,
             db_health,
             test_tracker,
             exception_tracker,
+            job_tracker: JobTracker::new(),
             view_mode: ViewMode::Logs,
             active_tab_index: 0,
             search_mode: false,
-            search_query: String::new(),
-            log_scroll: 0,
+            search_queries: HashMap::new(),
+            search_case_sensitive: false,
+            next_log_seq: 1,
+            log_viewport: LogViewport::new(),
+            log_chips: LogChips::new(),
             horizontal_scroll: 0,
+            max_horizontal_scroll: Cell::new(0),
             auto_scroll: true,
-            _request_scroll: 0,
+            log_order: LogOrder::default(),
+            log_colors: LogColors::default(),
+            compact_thresholds: layout::CompactThresholds::default(),
+            show_compact_processes: false,
+            processes_focused: false,
+            selected_process: 0,
+            pending_process_action: None,
+            process_panel_shown: Cell::new(true),
+            click_map: RefCell::new(ClickMap::new()),
+            clicks: ClickTracker::default(),
+            request_detail_scroll: 0,
             selected_request: 0,
+            selected_in_flight: None,
+            selected_n_plus_one: None,
+            n_plus_one_expanded: true,
             selected_exception: 0,
+            exception_filter: crate::exception::ExceptionFilter::default(),
+            selected_test_failure: 0,
+            expanded_test_failure: None,
+            selected_table: 0,
+            exception_sort: views::exceptions_view::DEFAULT_SORT,
+            slow_query_sort: views::database_health_view::SLOW_QUERY_SORT,
             filter_process: None,
+            min_log_level: None,
+            time_range: None,
+            timeline_zoom: 0,
+            timeline_cursor: None,
+            display_zone: DisplayZone::default(),
+            privacy: Privacy::default(),
             command_mode: false,
             command_input: String::new(),
             command_registry,
@@ -198,32 +502,423 @@ impl App {
             command_suggestions: Vec::new(),
             selected_suggestion: 0,
             last_command_result: None,
+            command_fix: None,
+            command_prompt: None,
+            warned_detached: Vec::new(),
+            disk_watcher: None,
+            project_watcher: None,
+            project: None,
+            viewing_session: None,
+            disk_warning: None,
+            disk_warned: Vec::new(),
+            process_warning: None,
+            show_request_extra: false,
+            show_diagnostics: false,
+            diagnostics_selected: 0,
+            diagnostics_keep: DIAGNOSTICS_KEEP_STEPS[2],
+            diagnostics_system: sysinfo::System::new(),
+            diagnostics_rss: None,
+            diagnostics_refreshed_at: None,
+            frame_timings: FrameTimings::new(),
+            parse_coverage: ParseCoverage::new(),
+            unparsed_dump: None,
+            ui_state_file: None,
+            analytics_file: None,
+            analytics_saved_at: None,
+            config_watcher: None,
+            history: None,
+            health_recorded_at: None,
+            show_trends: false,
+            trends_endpoint: None,
+            autofocus: autofocus::AutoFocus::default(),
+            autofocus_filter: false,
+            log_annotations: annotations::LogAnnotations::new(),
+            pending_exception_seq: None,
+            process_manager: None,
+            clock: std::sync::Arc::new(SystemClock),
+            suspensions: Suspensions::default(),
+            trends: None,
+            trends_loading: None,
+            trends_error: None,
+            health_sampled_at: None,
+            alert_engine: AlertEngine::default(),
+            show_alerts: false,
+            show_help: false,
+            show_status: false,
+            build_errors: BuildErrors::new(),
+            show_build_error: false,
+            build_error_selected: 0,
+            frontend_dir: None,
+            frontend: FrontendTracker::new(DEFAULT_FRONTEND_PROCESS),
+            editor_request: None,
+            memory_reports: MemoryReports::new(),
+            show_memory: false,
+            memory_scroll: 0,
+            memory_seen: false,
+            routes_available: false,
+            routes: None,
+            routes_loading: None,
+            routes_error: None,
+            show_routes: false,
+            routes_query: String::new(),
+            routes_scroll: 0,
             spinner_frame: 0,
             previous_view_mode: None,
             last_view_change_time: None,
         }
     }
 
+    /// Switch to the compact layout below these terminal dimensions
+    pub fn with_compact_thresholds(mut self, thresholds: layout::CompactThresholds) -> Self {
+        self.compact_thresholds = thresholds;
+        self
+    }
+
+    /// Cap the log buffer; the trackers are given their limits when built
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.max_logs = limits.max_logs;
+        self
+    }
+
+    /// Cut log lines with this truncator instead of the default
+    pub fn with_log_order(mut self, order: LogOrder) -> Self {
+        self.log_order = order;
+        self
+    }
+
+    pub fn with_log_colors(mut self, colors: LogColors) -> Self {
+        self.log_colors = colors;
+        self
+    }
+
+    /// Parse the lines these gems print; none by default
+    pub fn with_instrumentation(mut self, gems: InstrumentationGems) -> Self {
+        self.instrumentation = InstrumentationParser::new(gems);
+        self
+    }
+
+    pub fn with_line_truncator(mut self, truncator: LineTruncator) -> Self {
+        self.line_truncator = truncator;
+        self
+    }
+
+    /// Show and export wall-clock times in `zone`
+    pub fn with_display_zone(mut self, zone: DisplayZone) -> Self {
+        self.display_zone = zone;
+        self
+    }
+
+    /// Mask personal data as `[privacy]` configures, starting masked if enabled
+    pub fn with_privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Check these `[alerts]` rules while running
+    pub fn with_alert_rules(mut self, rules: Vec<AlertRule>) -> Self {
+        self.alert_engine = AlertEngine::new(rules);
+        self
+    }
+
+    /// Reload `[limits]` whenever the watched config file changes
+    pub fn with_config_watcher(mut self, watcher: ConfigWatcher) -> Self {
+        self.config_watcher = Some(watcher);
+        self
+    }
+
+    /// Apply changes to schema.rb, routes.rb and database.yml as the watcher reports them
+    pub fn with_project_watcher(mut self, watcher: mpsc::UnboundedReceiver<ProjectChange>) -> Self {
+        self.project_watcher = Some(watcher);
+        self
+    }
+
+    /// Record the project root in sessions saved with `/session save`
+    pub fn with_project(mut self, root: &std::path::Path) -> Self {
+        self.project = Some(root.to_path_buf());
+        self
+    }
+
+    /// Where the frontend build runs, so files named in its errors can be opened
+    pub fn with_frontend_dir(mut self, dir: std::path::PathBuf) -> Self {
+        self.frontend_dir = Some(dir);
+        self
+    }
+
+    /// Follow the builds of `process` rather than of `frontend`
+    pub fn with_frontend_process(mut self, process: &str) -> Self {
+        self.frontend = FrontendTracker::new(process);
+        self
+    }
+
+    /// Offer `/routes` for this Rails app, starting from the table cached by
+    /// an earlier run, if any
+    pub fn with_routes(mut self, cached: Option<RouteTable>) -> Self {
+        self.routes_available = true;
+        if let Some(table) = cached {
+            self.set_routes(table);
+        }
+        self
+    }
+
+    /// Keep completed requests, slow queries and health scores in `[history]`
+    pub fn with_history(mut self, writer: HistoryWriter) -> Self {
+        self.history = Some(writer);
+        self
+    }
+
+    /// Write the parse coverage report to `path` on exit (`[logs] dump_unparsed`)
+    pub fn with_unparsed_dump(mut self, path: std::path::PathBuf) -> Self {
+        self.unparsed_dump = Some(path);
+        self
+    }
+
+    /// Save the UI state to `path` on exit
+    pub fn with_ui_state_file(mut self, path: std::path::PathBuf) -> Self {
+        self.ui_state_file = Some(path);
+        self
+    }
+
+    /// Save the analytics to `path` every `persistence::SAVE_INTERVAL` and on exit
+    pub fn with_analytics_file(mut self, path: std::path::PathBuf) -> Self {
+        self.analytics_file = Some(path);
+        self.analytics_saved_at = Some(Instant::now());
+        self
+    }
+
+    /// Pick up where the last run left off
+    ///
+    /// A filter for a process that isn't in `processes` is dropped with a
+    /// notice in the logs; sorts out of range fall back to the defaults.
+    /// Privacy mode is only ever turned on, never off.
+    pub fn with_ui_state(mut self, state: UiState, processes: &[String]) -> Self {
+        let views = ViewMode::all_variants();
+        if let Some(index) = state
+            .view
+            .as_deref()
+            .and_then(|name| views.iter().position(|view| view.as_str() == name))
+        {
+            self.view_mode = views[index].clone();
+            self.active_tab_index = index;
+        }
+        for (name, query) in state.searches {
+            if let Some(view) = views
+                .iter()
+                .find(|view| view.as_str() == name && view.is_searchable())
+                && !query.is_empty()
+            {
+                self.search_queries.insert(view.clone(), query);
+            }
+        }
+        self.log_chips.set_hidden(&state.hidden_log_categories);
+        self.min_log_level = state.min_log_level;
+        let filter = state
+            .filter_process
+            .map(|spec| (ProcessFilter::parse(&spec), spec));
+        match filter {
+            Some((Ok(filter), _)) if processes.iter().any(|process| filter.matches(process)) => {
+                self.filter_process = Some(filter);
+            }
+            Some((_, spec)) => {
+                self.add_log(LogLine::new(
+                    "caboose",
+                    format!(
+                        "Not restoring the Logs filter '{}': it matches no process",
+                        spec
+                    ),
+                ));
+            }
+            None => {}
+        }
+        if let Some(sort) = state.exception_sort {
+            self.exception_sort = views::exceptions_view::table(sort, &self.privacy).sort_state();
+        }
+        if let Some(sort) = state.slow_query_sort {
+            self.slow_query_sort =
+                views::database_health_view::slow_query_table(sort, &self.privacy).sort_state();
+        }
+        if let Some(order) = state.log_order {
+            self.log_order = order;
+        }
+        if let Some(colors) = state.log_colors {
+            self.log_colors = colors;
+        }
+        if state.privacy {
+            self.privacy.set_enabled(true);
+        }
+        self.show_compact_processes = state.compact_processes;
+        self.search_case_sensitive = state.search_case_sensitive;
+        self
+    }
+
+    /// Idle time after a key press before `/autofocus` switches views again
+    pub fn with_autofocus_resume(mut self, resume_after: Duration) -> Self {
+        self.autofocus = autofocus::AutoFocus::new(resume_after);
+        self
+    }
+
+    /// Let `/send` write to the running processes
+    pub fn with_process_manager(
+        mut self,
+        manager: std::sync::Arc<crate::process::ProcessManager>,
+    ) -> Self {
+        self.process_manager = Some(manager);
+        self
+    }
+
+    /// Read uptimes and ages from `clock` instead of the system's
+    pub fn with_clock(mut self, clock: std::sync::Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Show a saved session read-only: its processes as they were and its logs
+    ///
+    /// The logs are shown as saved; the trackers get their data from the
+    /// archive, so the lines aren't parsed again.
+    pub fn with_session(
+        mut self,
+        metadata: SessionMetadata,
+        processes: Vec<ProcessInfo>,
+        logs: Vec<LogLine>,
+    ) -> Self {
+        self.next_log_seq = logs.last().map_or(0, |log| log.seq + 1);
+        self.logs = logs;
+        self.log_chips.recount(&self.logs);
+        self.log_annotations.clear();
+        self.processes = std::sync::Arc::new(processes);
+        self.viewing_session = Some(metadata);
+        self
+    }
+
+    /// Whether this is a saved session rather than a live one
+    pub fn is_read_only(&self) -> bool {
+        self.viewing_session.is_some()
+    }
+
+    /// Warn about large logs and low disk space reported by the watcher
+    pub fn with_disk_watcher(mut self, watcher: watch::Receiver<Vec<DiskWarning>>) -> Self {
+        self.disk_watcher = Some(watcher);
+        self
+    }
+
+    /// Resize every store, dropping the oldest entries that no longer fit
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.max_logs = limits.max_logs;
+        if crate::diagnostics::keep_last(&mut self.logs, self.max_logs) > 0 {
+            self.log_chips.recount(&self.logs);
+            self.log_viewport
+                .lines_evicted(self.logs.first().map(|log| log.seq));
+            self.log_annotations
+                .evict_before(self.logs.first().map(|log| log.seq));
+        }
+        self.context_tracker.set_limits(limits);
+        self.db_health.set_limits(limits);
+        self.exception_tracker.set_limits(limits);
+        self.test_tracker.set_limits(limits);
+    }
+
+    /// Apply `[limits]` and `[exceptions]` grouping from the config file if it
+    /// changed on disk
+    ///
+    /// Other settings only take effect on restart.
+    pub fn reload_config_if_changed(&mut self) {
+        let Some(reloaded) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) else {
+            return;
+        };
+        self.apply_reloaded_config(reloaded);
+    }
+
+    fn apply_reloaded_config(&mut self, reloaded: Result<crate::config::CabooseConfig, String>) {
+        match reloaded {
+            Ok(config) => {
+                self.stats_collector
+                    .record_timeline(chrono::Local::now(), TimelineEvent::Deploy);
+                let (limits, warnings) = config.limits.limits();
+                self.set_limits(limits);
+                let regrouped = self
+                    .exception_tracker
+                    .set_grouping(config.exceptions.grouping());
+                if warnings.is_empty() {
+                    let applied = if regrouped {
+                        "limits applied, exceptions regrouped"
+                    } else {
+                        "limits applied"
+                    };
+                    self.last_command_result = Some(command::ExecutionResult::Success(format!(
+                        "Config reloaded, {}",
+                        applied
+                    )));
+                } else {
+                    self.process_warning = Some(format!(
+                        "Config reloaded with defaults for: {}",
+                        warnings.join("; ")
+                    ));
+                }
+            }
+            Err(err) => {
+                self.process_warning = Some(format!("{} (settings unchanged)", err));
+            }
+        }
+    }
+
     // ========================================================================
     // LOG MANAGEMENT
     // ========================================================================
 
     /// Add a log line and update trackers
-    pub fn add_log(&mut self, log: LogLine) {
+    pub fn add_log(&mut self, mut log: LogLine) {
+        log.seq = self.next_log_seq;
+        self.next_log_seq += 1;
+        // `/around` binary-searches by time, so never let a clock step back reorder the buffer
+        if let Some(last) = self.logs.last()
+            && log.time < last.time
+        {
+            log.time = last.time;
+        }
+
+        // Everything below only ever sees the kept prefix of an overlong line
+        self.line_truncator.apply(&mut log);
+        let content = log.kept_content();
+
+        // A console's queries belong to no request, from its first prompt on
+        if log.process_name != "caboose" && is_console_prompt(content) {
+            self.context_tracker.mark_console(&log.process_name);
+        }
+
         // Parse log for stats and context tracking
-        if let Some(event) = RailsLogParser::parse_line(&log.content) {
-            match &event {
+        let mut rails_event = RailsLogParser::parse_line(content);
+        // A query cut short can't be run as logged, so exports say so
+        if let Some(truncation) = &log.truncated
+            && let Some(LogEvent::SqlQuery(query)) = &mut rails_event
+        {
+            query.truncated = Some(truncation.original_len);
+        }
+        let mut understood = rails_event.is_some();
+        if let Some(event) = &rails_event {
+            match event {
                 LogEvent::HttpRequest(req) => {
                     if let (Some(status), Some(duration)) = (req.status, req.duration) {
-                        self.stats_collector.record_request(status, duration);
+                        let db_time = req.extra.get("db").and_then(|db| db.parse().ok());
+                        self.stats_collector
+                            .record_request_with_db_time(status, duration, db_time);
+                        self.stats_collector
+                            .record_timeline(log.time, TimelineEvent::Request { status });
                     }
                 }
                 LogEvent::SqlQuery(query) => {
                     if let Some(duration) = query.duration {
                         self.stats_collector.record_sql_query(duration);
                         self.db_health.analyze_query(&query.query, duration);
+                        if duration > SLOW_QUERY_THRESHOLD && self.history.is_some() {
+                            self.record_history(HistoryRecord::SlowQuery {
+                                fingerprint: QueryFingerprint::new(&query.query).normalized,
+                                duration,
+                                at: log.time,
+                            });
+                        }
                     }
                 }
+                LogEvent::Cache(cache) => self.stats_collector.record_cache(cache),
                 LogEvent::RailsStartupError(rails_error) => {
                     // Handle Rails errors - they're already logged, no additional action needed here
                     // The error will appear in the logs view with appropriate highlighting
@@ -235,28 +930,190 @@ impl App {
                         RailsError::DatabaseNotFound(_) => {
                             // Could show "Run db:create" suggestion
                         }
+                        RailsError::PortInUse(port) => {
+                            self.process_warning = Some(format!(
+                                "'{}' couldn't start: port {} is in use. Run with --attach, or set managed = false under [processes.{}], to adopt the server already running there",
+                                log.process_name, port, log.process_name
+                            ));
+                        }
                         _ => {}
                     }
                 }
                 _ => {}
             }
 
-            self.context_tracker.process_log_event(&event);
+            let completed = self.context_tracker.completed_total();
+            self.context_tracker
+                .process_log_event_from(&log.process_name, event);
+            let completed_now = self.context_tracker.completed_total() > completed;
+            let recognized = match event {
+                _ if completed_now => self
+                    .context_tracker
+                    .last_completed_id()
+                    .map(annotations::LineRef::Request),
+                LogEvent::SqlQuery(_) if !self.context_tracker.is_console(&log.process_name) => {
+                    self.context_tracker
+                        .last_query_position()
+                        .map(|(request, index)| annotations::LineRef::Query { request, index })
+                }
+                _ => None,
+            };
+            if let Some(line) = recognized {
+                self.log_annotations.record(log.seq, line);
+            }
+            if self.history.is_some()
+                && completed_now
+                && let Some(summary) = self
+                    .context_tracker
+                    .last_completed()
+                    .and_then(|req| RequestSummary::from_completed(&req, log.time))
+            {
+                self.record_history(HistoryRecord::Request(summary));
+            }
+        }
+
+        let instrumentation = self.instrumentation.parse_line(content);
+        understood |= instrumentation.is_some();
+        match instrumentation {
+            Some(InstrumentationEvent::Bullet(warning)) => {
+                self.context_tracker.record_bullet_warning(&warning);
+            }
+            Some(InstrumentationEvent::Profiler(timings)) => {
+                self.context_tracker.record_profiler_timings(&timings);
+            }
+            Some(InstrumentationEvent::AgentError(error)) => {
+                self.process_warning = Some(error.warning());
+            }
+            None => {}
         }
 
+        self.boot_times
+            .observe(&log.process_name, content, log.timestamp);
+
         // Feed to test tracker
-        self.test_tracker.parse_line(&log.content);
+        let test_runs = self.test_tracker.total_runs();
+        let test_running = self.test_tracker.is_running();
+        understood |= self.test_tracker.parse_line(content);
+        if self.test_tracker.total_runs() > test_runs {
+            self.stats_collector
+                .record_timeline(log.time, TimelineEvent::TestRun);
+            self.autofocus
+                .observe(FocusEvent::TestRunFinished, log.timestamp);
+        } else if !test_running && self.test_tracker.is_running() {
+            self.selected_test_failure = 0;
+            self.expanded_test_failure = None;
+            self.autofocus
+                .observe(FocusEvent::TestRunStarted, log.timestamp);
+        }
+
+        // Feed to job tracker
+        understood |= self.job_tracker.parse_line(content);
 
         // Feed to exception tracker
-        self.exception_tracker.parse_line(&log.content);
+        let exceptions = self.exception_tracker.get_stats().total_exceptions;
+        let started = self.exception_tracker.started_total();
+        let exception = self.exception_tracker.parse_line(content);
+        understood |= exception;
+        if self.exception_tracker.get_stats().total_exceptions > exceptions {
+            // Finished by this line; its first line is the one to annotate
+            if let Some(seq) = self.pending_exception_seq.take()
+                && let Some(fingerprint) = self.exception_tracker.last_fingerprint()
+            {
+                self.log_annotations
+                    .record(seq, annotations::LineRef::Exception(fingerprint));
+            }
+            self.stats_collector
+                .record_timeline(log.time, TimelineEvent::Exception);
+            if self.autofocus.is_enabled()
+                && self
+                    .exception_tracker
+                    .get_recent_exceptions(1)
+                    .first()
+                    .is_some_and(|exception| {
+                        ExceptionSeverity::from_exception_type(&exception.exception_type)
+                            >= ExceptionSeverity::High
+                    })
+            {
+                self.autofocus.observe(FocusEvent::Exception, log.timestamp);
+            }
+        }
+        if self.exception_tracker.started_total() > started {
+            self.pending_exception_seq = Some(log.seq);
+            // The line's request id tag, when logged with one, says which
+            // request it was raised in
+            let tags = RailsLogParser::tags(content);
+            let tagged = request_id_tag(&tags);
+            let request = self
+                .context_tracker
+                .request_for_exception(tagged, log.timestamp);
+            self.exception_tracker.set_current_request_context(
+                request.as_ref().map(|request| request.label.clone()),
+                request
+                    .map(|request| request.request_id)
+                    .filter(|id| !id.is_empty())
+                    .or(tagged.map(str::to_string)),
+            );
+        }
+
+        // Caboose's own messages aren't logs to parse
+        let mut frontend_event = None;
+        if log.process_name != "caboose" {
+            frontend_event = FrontendLogParser::parse_line(content);
+            understood |= is_ready_line(content) || frontend_event.is_some();
+            if log.process_name == self.frontend.process() {
+                self.frontend
+                    .observe(content, frontend_event.as_ref(), log.timestamp);
+            }
+            match self
+                .build_errors
+                .observe(&log.process_name, content, frontend_event.as_ref())
+            {
+                Some(BuildChange::Failed) => {
+                    self.show_build_error = true;
+                    self.build_error_selected = 0;
+                }
+                Some(BuildChange::Fixed) if self.build_errors.is_empty() => {
+                    self.show_build_error = false;
+                }
+                _ => {}
+            }
+            match self
+                .memory_reports
+                .observe(&log.process_name, content, log.time)
+            {
+                Some(ReportChange::Started) => {
+                    self.memory_seen = false;
+                    self.memory_scroll = 0;
+                    understood = true;
+                }
+                // The line closing the report isn't part of it
+                Some(ReportChange::Finished) => {}
+                None => {
+                    understood |= self
+                        .memory_reports
+                        .get(&log.process_name)
+                        .is_some_and(|report| !report.complete);
+                }
+            }
+            self.parse_coverage
+                .record(&log.process_name, content, understood);
+        }
+        log.category =
+            LogCategory::classify(rails_event.as_ref(), exception, frontend_event.as_ref());
+        log.level = LogLevel::detect(&log.content, log.category);
 
+        self.log_chips.count(log.category);
         self.logs.push(log);
         if self.logs.len() > self.max_logs {
-            self.logs.remove(0);
-            // If we removed a log and scroll is out of bounds, adjust it
-            if !self.auto_scroll && self.log_scroll > 0 {
-                self.log_scroll = self.log_scroll.saturating_sub(1);
+            let evicted = self.logs.remove(0);
+            self.log_chips.uncount(evicted.category);
+            if let Some(spill) = evicted.truncated.and_then(|t| t.spill) {
+                let _ = std::fs::remove_file(spill);
             }
+            self.log_viewport
+                .lines_evicted(self.logs.first().map(|log| log.seq));
+            self.log_annotations
+                .evict_before(self.logs.first().map(|log| log.seq));
         }
     }
 
@@ -264,6 +1121,52 @@ impl App {
     // VIEW MANAGEMENT
     // ========================================================================
 
+    /// Switch to the view auto focus picks, if it picks one now
+    pub fn check_autofocus(&mut self) {
+        let Some(target) = self.autofocus.poll(Instant::now()) else {
+            return;
+        };
+        let view = match target {
+            FocusTarget::Exceptions => ViewMode::Exceptions,
+            FocusTarget::TestResults => ViewMode::TestResults,
+            FocusTarget::ProcessLogs(process) => {
+                self.filter_process = Some(ProcessFilter::only(process));
+                self.autofocus_filter = true;
+                ViewMode::Logs
+            }
+            FocusTarget::Logs => {
+                if std::mem::take(&mut self.autofocus_filter) {
+                    self.filter_process = None;
+                }
+                ViewMode::Logs
+            }
+        };
+        if view == ViewMode::Logs {
+            self.auto_scroll = true;
+            self.log_viewport.follow();
+        }
+        self.set_view(view);
+    }
+
+    /// Note a key press: auto focus leaves the views alone until the user is idle
+    pub fn note_user_input(&mut self) {
+        self.autofocus.user_input(Instant::now());
+        self.autofocus_filter = false;
+    }
+
+    /// Show `view`, with the transition and tab bar following
+    fn set_view(&mut self, view: ViewMode) {
+        if view == self.view_mode {
+            return;
+        }
+        self.previous_view_mode = Some(self.view_mode.clone());
+        self.last_view_change_time = Some(Instant::now());
+        if let Some(index) = ViewMode::all_variants().iter().position(|v| v == &view) {
+            self.active_tab_index = index;
+        }
+        self.view_mode = view;
+    }
+
     /// Toggle to next view
     pub fn toggle_view(&mut self) {
         let variants = ViewMode::all_variants();
@@ -300,22 +1203,83 @@ impl App {
     // SEARCH MODE
     // ========================================================================
 
+    /// Search query for `view` (empty when none is set)
+    pub fn search_query_for(&self, view: &ViewMode) -> &str {
+        self.search_queries.get(view).map_or("", String::as_str)
+    }
+
+    /// Search query for the active view
+    pub fn current_search_query(&self) -> &str {
+        self.search_query_for(&self.view_mode)
+    }
+
     pub fn enter_search_mode(&mut self) {
+        if !self.view_mode.is_searchable() {
+            return;
+        }
         self.search_mode = true;
-        self.search_query.clear();
+        self.search_queries.remove(&self.view_mode);
+        self.reset_view_selection();
     }
 
+    /// Stop editing and drop the active view's query
     pub fn exit_search_mode(&mut self) {
         self.search_mode = false;
-        self.search_query.clear();
+        self.search_queries.remove(&self.view_mode);
+        self.reset_view_selection();
+    }
+
+    /// Stop editing but keep filtering the active view by its query
+    pub fn apply_search(&mut self) {
+        self.search_mode = false;
+        if self.current_search_query().is_empty() {
+            self.search_queries.remove(&self.view_mode);
+        }
     }
 
     pub fn add_search_char(&mut self, c: char) {
-        self.search_query.push(c);
+        self.search_queries
+            .entry(self.view_mode.clone())
+            .or_default()
+            .push(c);
+        self.reset_view_selection();
+    }
+
+    /// The Logs view's search, compiled: a substring or `re:` regex
+    pub fn log_search(&self) -> SearchFilter {
+        SearchFilter::new(
+            self.search_query_for(&ViewMode::Logs),
+            self.search_case_sensitive,
+        )
+    }
+
+    /// Switch the Logs search between matching and ignoring case
+    pub fn toggle_search_case(&mut self) {
+        if self.view_mode != ViewMode::Logs {
+            return;
+        }
+        self.search_case_sensitive = !self.search_case_sensitive;
+        self.reset_view_selection();
     }
 
     pub fn remove_search_char(&mut self) {
-        self.search_query.pop();
+        if let Some(query) = self.search_queries.get_mut(&self.view_mode) {
+            query.pop();
+        }
+        self.reset_view_selection();
+    }
+
+    /// Move the active view's selection back to the top after its results change
+    fn reset_view_selection(&mut self) {
+        match self.view_mode {
+            ViewMode::QueryAnalysis => {
+                self.selected_request = 0;
+                self.selected_in_flight = None;
+                self.selected_n_plus_one = None;
+            }
+            ViewMode::Exceptions => self.selected_exception = 0,
+            _ => {}
+        }
     }
 
     // ========================================================================
@@ -328,25 +1292,31 @@ impl App {
         self.command_suggestions.clear();
         self.selected_suggestion = 0;
         self.last_command_result = None;
+        self.command_fix = None;
+        self.command_prompt = None;
         self.update_command_suggestions(); // Show all commands initially
     }
 
     pub fn exit_command_mode(&mut self) {
         self.command_mode = false;
         self.command_input.clear();
+        self.command_fix = None;
+        self.command_prompt = None;
         self.command_suggestions.clear();
         self.selected_suggestion = 0;
     }
 
     pub fn add_command_char(&mut self, c: char) {
+        self.command_fix = None;
         self.command_input.push(c);
         self.update_command_suggestions();
         self.selected_suggestion = 0;
     }
 
     pub fn remove_command_char(&mut self) {
-        // Don't allow deleting the "/" prefix
-        if self.command_input.len() > 1 {
+        // Don't allow deleting the "/" prefix; an argument has none
+        let keep = if self.command_prompt.is_some() { 0 } else { 1 };
+        if self.command_input.len() > keep {
             self.command_input.pop();
             self.update_command_suggestions();
             self.selected_suggestion = 0;
@@ -354,10 +1324,64 @@ impl App {
     }
 
     pub fn update_command_suggestions(&mut self) {
+        if let Some(prompt) = &self.command_prompt {
+            self.command_suggestions = prompt.suggestions(&self.command_input);
+            return;
+        }
+        if let Some(suggestions) = self.process_suggestions() {
+            self.command_suggestions = suggestions;
+            return;
+        }
         let partial = command::CommandParser::extract_partial_command(&self.command_input);
         self.command_suggestions = self.command_autocomplete.get_suggestions(&partial, 5);
     }
 
+    /// Process names for a command that takes one, once its name is typed:
+    /// `/restart w` offers `restart web` and `restart worker`
+    fn process_suggestions(&self) -> Option<Vec<command::Suggestion>> {
+        let input = self.command_input.strip_prefix('/')?;
+        let (name, partial) = input.split_once(char::is_whitespace)?;
+        let partial = partial.trim_start();
+        if partial.contains(char::is_whitespace) {
+            return None;
+        }
+        let command = self.command_registry.find(name)?;
+        if !command.completes_processes() {
+            return None;
+        }
+        let partial = partial.to_lowercase();
+        let mut suggestions: Vec<command::Suggestion> = self
+            .process_names()
+            .into_iter()
+            .filter_map(|process| {
+                let lower = process.to_lowercase();
+                let score = if lower.starts_with(&partial) {
+                    2
+                } else if lower.contains(&partial) {
+                    1
+                } else {
+                    return None;
+                };
+                Some(command::Suggestion::new(
+                    format!("{} {}", command.name(), process),
+                    command.description().to_string(),
+                    command.usage().to_string(),
+                    score,
+                ))
+            })
+            .collect();
+        suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.score));
+        suggestions.truncate(5);
+        Some(suggestions)
+    }
+
+    /// Names of the session's processes, sorted
+    fn process_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.processes.iter().map(|p| p.name.clone()).collect();
+        names.sort();
+        names
+    }
+
     pub fn select_next_suggestion(&mut self) {
         if !self.command_suggestions.is_empty() {
             self.selected_suggestion =
@@ -376,13 +1400,54 @@ impl App {
     }
 
     pub fn autocomplete_selected(&mut self) {
+        // Right after a "did you mean" error, Tab takes the suggestion
+        if let Some(fix) = self.command_fix.take()
+            && self.command_input == "/"
+        {
+            self.command_input = fix;
+            self.last_command_result = None;
+            self.update_command_suggestions();
+            return;
+        }
         if let Some(suggestion) = self.command_suggestions.get(self.selected_suggestion) {
-            self.command_input = format!("/{}", suggestion.text);
+            self.command_input = if self.command_prompt.is_some() {
+                suggestion.text.clone()
+            } else {
+                format!("/{}", suggestion.text)
+            };
             self.update_command_suggestions();
         }
     }
 
+    /// The command line a "did you mean" error suggests, arguments kept
+    fn command_fix(&self, name: &str, args: Vec<String>) -> Option<String> {
+        let quote = |arg: String| {
+            if arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg
+            }
+        };
+        let (name, args) = match self.command_registry.find(name) {
+            None => (self.command_registry.suggest(name)?, args),
+            Some(command) if command.name() == "view" => {
+                let view = command::commands::ViewCommand::suggest_view(args.first()?)?;
+                ("view", vec![view.to_string()])
+            }
+            Some(_) => return None,
+        };
+        let line = std::iter::once(name.to_string())
+            .chain(args.into_iter().map(quote))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(format!("/{}", line))
+    }
+
     pub fn navigate_command_history_prev(&mut self) {
+        // History holds whole commands, not arguments
+        if self.command_prompt.is_some() {
+            return;
+        }
         if let Some(cmd) = self.command_history.prev(&self.command_input) {
             self.command_input = cmd;
             self.update_command_suggestions();
@@ -390,6 +1455,9 @@ impl App {
     }
 
     pub fn navigate_command_history_next(&mut self) {
+        if self.command_prompt.is_some() {
+            return;
+        }
         if let Some(cmd) = self.command_history.next() {
             self.command_input = cmd;
             self.update_command_suggestions();
@@ -397,6 +1465,21 @@ impl App {
     }
 
     pub fn execute_command(&mut self) {
+        if let Some(mut prompt) = self.command_prompt.take() {
+            let arg = self.command_input.trim().to_string();
+            if arg.is_empty() || !prompt.push(arg) {
+                // Still waiting on an argument
+                self.command_input.clear();
+                self.command_prompt = Some(prompt);
+                self.update_command_suggestions();
+                self.selected_suggestion = 0;
+                return;
+            }
+            self.command_history.add(prompt.command_line());
+            self.run_command(prompt.command(), prompt.args().to_vec());
+            return;
+        }
+
         if self.command_input.trim() == "/" || self.command_input.trim().is_empty() {
             self.exit_command_mode();
             return;
@@ -408,20 +1491,126 @@ impl App {
         // Add to history
         self.command_history.add(self.command_input.clone());
 
+        // Commands that split their own arguments get them as typed
+        let raw_args = self
+            .command_registry
+            .find(&parsed.name)
+            .is_some_and(|command| command.raw_args());
+        let args = match command::CommandParser::rest(&self.command_input) {
+            rest if raw_args && !rest.is_empty() => vec![rest.to_string()],
+            _ if raw_args => Vec::new(),
+            _ => parsed.args,
+        };
+        self.run_command(&parsed.name, args);
+    }
+
+    /// Run a command and keep the palette and views in step with the result
+    ///
+    /// Missing arguments are asked for rather than reported as an error.
+    fn run_command(&mut self, name: &str, args: Vec<String>) {
+        // Search commands act on the active view, or on Logs from a detail view
+        let search_view = if self.view_mode.is_searchable() {
+            self.view_mode.clone()
+        } else {
+            ViewMode::Logs
+        };
+
+        // Only the Logs search takes regexes and matches case
+        let search_case_sensitive =
+            (search_view == ViewMode::Logs).then_some(self.search_case_sensitive);
+
         // Create context
+        let routes_available = self.routes_available && !self.is_read_only();
+        let request_search = self.search_query_for(&ViewMode::QueryAnalysis).to_string();
+        let mut trends_endpoint = None;
+        let mut notices = Vec::new();
         let mut ctx = command::commands::AppContext {
             view_mode: &mut self.view_mode,
-            search_query: &mut self.search_query,
+            search_query: self.search_queries.entry(search_view).or_default(),
+            search_case_sensitive,
             filter_process: &mut self.filter_process,
+            min_log_level: &mut self.min_log_level,
+            exception_filter: &mut self.exception_filter,
             auto_scroll: &mut self.auto_scroll,
+            log_order: &mut self.log_order,
             should_quit: &mut self.should_quit,
+            show_diagnostics: &mut self.show_diagnostics,
+            show_alerts: &mut self.show_alerts,
+            show_help: &mut self.show_help,
+            show_status: &mut self.show_status,
+            show_build_error: &mut self.show_build_error,
+            show_memory: &mut self.show_memory,
+            memory_report: self.memory_reports.latest(),
+            build_failed: self.build_errors.latest().is_some(),
+            show_routes: &mut self.show_routes,
+            routes_query: &mut self.routes_query,
+            routes_available,
+            trends_endpoint: &mut trends_endpoint,
+            history_available: self.history.is_some(),
             logs: &self.logs,
+            request_search: &request_search,
+            time_range: &mut self.time_range,
+            display_zone: self.display_zone,
+            privacy: &mut self.privacy,
+            autofocus: &mut self.autofocus,
+            annotations: &mut self.log_annotations,
+            session: SessionSources {
+                processes: &self.processes,
+                logs: &self.logs,
+                stats: &self.stats_collector,
+                requests: &self.context_tracker,
+                exceptions: &self.exception_tracker,
+                tests: &self.test_tracker,
+                database: &self.db_health,
+            },
+            project: self.project.as_deref(),
+            process_manager: self.process_manager.as_deref(),
+            notices: &mut notices,
         };
 
         // Execute command
-        let result = self
+        let result = self.command_registry.execute(name, args.clone(), &mut ctx);
+        for notice in notices {
+            self.add_log(LogLine::new("caboose", notice));
+        }
+        if self.auto_scroll {
+            self.log_viewport.follow();
+        }
+        // Commands can switch views; keep the tab bar in sync
+        if let Some(index) = ViewMode::all_variants()
+            .iter()
+            .position(|view| view == &self.view_mode)
+        {
+            self.active_tab_index = index;
+        }
+        let jumps_to_time = self
             .command_registry
-            .execute(&parsed.name, parsed.args, &mut ctx);
+            .find(name)
+            .is_some_and(|command| command.name() == "around");
+        if result.is_ok() && jumps_to_time {
+            self.jump_to_time_range();
+        }
+        let opens_request = self
+            .command_registry
+            .find(name)
+            .is_some_and(|command| command.name() == "request");
+        if result.is_ok() && opens_request {
+            self.show_request_extra = false;
+            self.request_detail_scroll = 0;
+            self.follow_live_request();
+        }
+        if result.is_ok() && self.view_mode == ViewMode::Exceptions {
+            self.clamp_exception_selection();
+        }
+        if result.is_ok() && self.show_routes {
+            self.open_routes();
+        }
+        if let Some(endpoint) = trends_endpoint {
+            self.open_trends(endpoint);
+        }
+        if self.show_memory {
+            self.open_memory_report();
+        }
 
         // Store result and handle based on success/failure
         match result {
@@ -430,7 +1619,23 @@ impl App {
                 // Exit command mode on success
                 self.exit_command_mode();
             }
-            Err(err) => {
+            Err(command::CommandError::MissingArgs(mut missing)) => {
+                // Ask for the rest, one per Enter
+                if self
+                    .command_registry
+                    .find(&missing.command)
+                    .is_some_and(|command| command.completes_processes())
+                {
+                    missing.hints = self.process_names();
+                }
+                self.last_command_result = None;
+                self.command_prompt = Some(command::ArgPrompt::new(missing));
+                self.command_input.clear();
+                self.update_command_suggestions();
+                self.selected_suggestion = 0;
+            }
+            Err(command::CommandError::Failed(err)) => {
+                self.command_fix = self.command_fix(name, args);
                 self.last_command_result = Some(command::ExecutionResult::Error(err));
                 // Stay in command mode on error, clear input to try again
                 self.command_input = "/".to_string();
@@ -440,72 +1645,804 @@ impl App {
     }
 
     // ========================================================================
-    // NAVIGATION
+    // DIAGNOSTICS
     // ========================================================================
 
-    pub fn scroll_up(&mut self) {
-        if self.log_scroll > 0 {
-            self.log_scroll -= 1;
-        }
-        self.auto_scroll = false;
+    pub fn close_diagnostics(&mut self) {
+        self.show_diagnostics = false;
+        self.diagnostics_refreshed_at = None;
     }
 
-    pub fn scroll_down(&mut self) {
-        self.log_scroll += 1;
-        self.auto_scroll = false;
+    /// How many lines the parsers understood, with samples of the rest
+    pub fn parse_coverage(&self) -> &ParseCoverage {
+        &self.parse_coverage
+    }
 
-        // Re-enable auto-scroll if we scroll to near the bottom
-        let total_logs = self.filtered_logs().len();
-        if total_logs > 0 && self.log_scroll + 10 >= total_logs {
-            self.auto_scroll = true;
-            // Don't reset scroll position - let auto-scroll handle it
+    /// Write the parse coverage report if `[logs] dump_unparsed` asked for it
+    pub fn dump_unparsed(&self) -> Result<(), String> {
+        match &self.unparsed_dump {
+            Some(path) => self.parse_coverage.dump(path),
+            None => Ok(()),
         }
     }
 
-    pub fn scroll_left(&mut self) {
-        self.horizontal_scroll = self.horizontal_scroll.saturating_sub(10);
+    /// What's kept of the UI for the next run
+    pub fn ui_state(&self) -> UiState {
+        UiState {
+            view: ViewMode::from_index(self.active_tab_index).map(|view| view.as_str().to_string()),
+            filter_process: self.filter_process.as_ref().map(ProcessFilter::to_string),
+            min_log_level: self.min_log_level,
+            hidden_log_categories: self.log_chips.hidden(),
+            searches: self
+                .search_queries
+                .iter()
+                .filter(|(view, query)| view.is_searchable() && !query.is_empty())
+                .map(|(view, query)| (view.as_str().to_string(), query.clone()))
+                .collect(),
+            exception_sort: Some(self.exception_sort),
+            slow_query_sort: Some(self.slow_query_sort),
+            log_order: Some(self.log_order),
+            log_colors: Some(self.log_colors),
+            privacy: self.privacy.is_enabled(),
+            compact_processes: self.show_compact_processes,
+            search_case_sensitive: self.search_case_sensitive,
+        }
     }
 
-    pub fn scroll_right(&mut self) {
-        self.horizontal_scroll += 10;
+    /// Save the UI state if a file was given for it
+    pub fn save_ui_state(&self) -> Result<(), String> {
+        match &self.ui_state_file {
+            Some(path) => self.ui_state().save(path),
+            None => Ok(()),
+        }
     }
 
-    pub fn scroll_home(&mut self) {
-        self.horizontal_scroll = 0;
+    /// Save the analytics if a file was given for them and anything was logged
+    pub fn save_analytics(&mut self) -> Result<(), String> {
+        let Some(path) = &self.analytics_file else {
+            return Ok(());
+        };
+        self.analytics_saved_at = Some(Instant::now());
+        let analytics = PersistedAnalytics::capture(&SessionSources {
+            processes: &self.processes,
+            logs: &self.logs,
+            stats: &self.stats_collector,
+            requests: &self.context_tracker,
+            exceptions: &self.exception_tracker,
+            tests: &self.test_tracker,
+            database: &self.db_health,
+        });
+        if analytics.is_empty() {
+            return Ok(());
+        }
+        analytics.save(path)
     }
 
-    pub fn scroll_page_up(&mut self, page_size: usize) {
-        self.log_scroll = self.log_scroll.saturating_sub(page_size);
-        self.auto_scroll = false;
+    /// Save the analytics once `persistence::SAVE_INTERVAL` has passed since
+    /// the last time, so a crash loses at most that much
+    pub fn check_analytics_save(&mut self) {
+        let due = self
+            .analytics_saved_at
+            .is_some_and(|at| at.elapsed() >= persistence::SAVE_INTERVAL);
+        if due && let Err(err) = self.save_analytics() {
+            self.add_log(LogLine::new("caboose", format!("[WARN] {}", err)));
+        }
     }
 
-    pub fn scroll_page_down(&mut self, page_size: usize) {
-        self.log_scroll += page_size;
-        self.auto_scroll = false;
+    /// Item counts and size estimates for every store Caboose keeps in memory
+    pub fn diagnostics_usage(&self) -> Vec<StoreUsage> {
+        let log_bytes = self.logs.iter().map(LogLine::approx_bytes).sum::<usize>();
+
+        let mut usage = vec![
+            StoreUsage::new(StoreKind::LogBuffer, self.logs.len(), log_bytes)
+                .with_limit(self.max_logs),
+        ];
+        usage.extend(self.context_tracker.store_usage());
+        usage.extend(self.db_health.store_usage());
+        usage.extend(self.exception_tracker.store_usage());
+        usage.extend(self.test_tracker.store_usage());
+        usage.extend(self.stats_collector.store_usage());
+        usage
+    }
 
-        // Re-enable auto-scroll if we scroll to near the bottom
-        let total_logs = self.filtered_logs().len();
-        if total_logs > 0 && self.log_scroll + 10 >= total_logs {
-            self.auto_scroll = true;
-            // Don't reset scroll position - let auto-scroll handle it
+    /// Re-sample the process RSS at most once a second while the overlay is open
+    pub fn refresh_diagnostics(&mut self) {
+        if !self.show_diagnostics {
+            return;
+        }
+
+        let stale = self
+            .diagnostics_refreshed_at
+            .is_none_or(|at| at.elapsed() >= Duration::from_secs(1));
+        if stale {
+            self.diagnostics_rss = crate::diagnostics::process_rss(&mut self.diagnostics_system);
+            self.diagnostics_refreshed_at = Some(Instant::now());
+        }
+    }
+
+    /// Record how long a frame took to draw and to handle its key press
+    ///
+    /// Logs once when drawing stays slow, naming the view on screen.
+    pub fn record_frame(&mut self, draw: Duration, input: Option<Duration>, now: Instant) {
+        let Some(p95) = self.frame_timings.record(draw, input, now) else {
+            return;
+        };
+        self.add_log(LogLine::new(
+            "caboose",
+            format!(
+                "Caboose is slow to draw: p95 frame {} for the last {}s in the {} view. \
+                 Lower max_logs under [limits] in .caboose.toml, or filter the logs to fewer lines",
+                format_ms(p95.as_secs_f64() * 1000.0),
+                crate::diagnostics::SLOW_FRAME_SUSTAINED.as_secs(),
+                self.view_mode.as_str()
+            ),
+        ));
+    }
+
+    /// Add the database health score to its trend every
+    /// `SCORE_SAMPLE_INTERVAL` once queries arrive
+    pub fn sample_health_score(&mut self) {
+        if self.db_health.is_empty() {
+            return;
+        }
+        let due = self
+            .health_sampled_at
+            .is_none_or(|at| at.elapsed() >= SCORE_SAMPLE_INTERVAL);
+        if due {
+            self.db_health.record_health_score();
+            self.health_sampled_at = Some(Instant::now());
+        }
+
+        let keep = self.history.is_some()
+            && self
+                .health_recorded_at
+                .is_none_or(|at| at.elapsed() >= HEALTH_HISTORY_INTERVAL);
+        if keep {
+            self.record_history(HistoryRecord::HealthScore {
+                score: self.db_health.calculate_health_score(),
+                at: chrono::Local::now(),
+            });
+            self.health_recorded_at = Some(Instant::now());
+        }
+    }
+
+    // ========================================================================
+    // HISTORY
+    // ========================================================================
+
+    /// Queue a row for `[history]`, turning history off with a warning once
+    /// it can't be written
+    fn record_history(&mut self, record: HistoryRecord) {
+        let Some(writer) = &self.history else {
+            return;
+        };
+        if let Err(err) = writer.record(record) {
+            self.disable_history(err);
+        }
+    }
+
+    fn disable_history(&mut self, err: String) {
+        self.history = None;
+        self.process_warning = Some(format!("History disabled: {}", err));
+    }
+
+    /// Show the trends overlay for `endpoint`, reading it in the background
+    fn open_trends(&mut self, endpoint: Endpoint) {
+        let Some(writer) = &self.history else {
+            return;
+        };
+        match writer.trend(endpoint.clone(), self.display_zone) {
+            Ok(loading) => {
+                self.trends_loading = Some(loading);
+                self.trends = None;
+                self.trends_error = None;
+                self.trends_endpoint = Some(endpoint);
+                self.show_trends = true;
+            }
+            Err(err) => self.disable_history(err),
+        }
+    }
+
+    pub fn close_trends(&mut self) {
+        self.show_trends = false;
+        self.trends_loading = None;
+    }
+
+    /// Pick up the trend once the history thread has read it
+    pub fn check_trends(&mut self) {
+        let Some(loading) = self.trends_loading.as_mut() else {
+            return;
+        };
+        let result = match loading.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err("History stopped before the trend was read".to_string())
+            }
+        };
+        self.trends_loading = None;
+        match result {
+            Ok(trend) => self.trends = Some(trend),
+            Err(err) => self.trends_error = Some(err),
+        }
+    }
+
+    // ========================================================================
+    // ALERTS
+    // ========================================================================
+
+    /// Check alert rules on their timer and log each one that fires or resolves
+    pub fn check_alerts(&mut self) {
+        let sources = AlertSources {
+            stats: &self.stats_collector,
+            requests: &self.context_tracker,
+            db_health: &self.db_health,
+            exceptions: &self.exception_tracker,
+        };
+        for event in self.alert_engine.poll(&sources) {
+            self.add_log(LogLine::new("caboose", event.message()));
+        }
+    }
+
+    /// Log each 404 / 5xx burst as it starts and dies down
+    pub fn check_bursts(&mut self) {
+        for event in self.context_tracker.poll_bursts(Instant::now()) {
+            self.add_log(LogLine::new("caboose", event.message()));
+        }
+    }
+
+    /// Notice the machine having been suspended since the last tick
+    ///
+    /// The gap is marked on the timeline and logged, interrupted boots aren't
+    /// charged for it, and every process is checked again right away rather
+    /// than trusting what was known before the machine slept.
+    pub fn check_suspend(&mut self) {
+        let Some(suspension) = self.suspensions.tick(self.clock.now(), self.clock.wall()) else {
+            return;
+        };
+        self.stats_collector
+            .record_suspension(suspension.from, suspension.to);
+        self.boot_times.resume(suspension.monotonic);
+
+        let mut content = suspension.message();
+        if !self.is_read_only()
+            && let Some(manager) = &self.process_manager
+        {
+            let gone = manager.recheck_liveness(&self.process_readiness());
+            if gone.is_empty() {
+                content.push_str("; processes rechecked, all still up");
+            } else {
+                content.push_str(&format!(
+                    "; processes rechecked, down since: {}",
+                    gone.join(", ")
+                ));
+            }
+        }
+        self.add_log(LogLine::new("caboose", content));
+    }
+
+    /// Log each Turbo Stream that starts broadcasting in a burst
+    pub fn check_turbo_bursts(&mut self) {
+        for burst in self.context_tracker.poll_turbo_bursts() {
+            self.add_log(LogLine::new("caboose", burst.message()));
+        }
+    }
+
+    /// Log each run of duplicate requests once it's over
+    pub fn check_duplicates(&mut self) {
+        for group in self.context_tracker.poll_duplicates(Instant::now()) {
+            self.add_log(LogLine::new("caboose", group.message()));
+        }
+    }
+
+    pub fn close_alerts(&mut self) {
+        self.show_alerts = false;
+    }
+
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+    }
+
+    pub fn close_help(&mut self) {
+        self.show_help = false;
+    }
+
+    pub fn open_status(&mut self) {
+        self.show_status = true;
+    }
+
+    pub fn close_status(&mut self) {
+        self.show_status = false;
+    }
+
+    pub fn open_memory_report(&mut self) {
+        self.show_memory = true;
+        self.memory_seen = true;
+    }
+
+    pub fn close_memory_report(&mut self) {
+        self.show_memory = false;
+    }
+
+    fn scroll_memory_report(&mut self, delta: isize) {
+        let lines = self.memory_reports.latest().map_or(0, |report| {
+            components::memory_report::report_lines(report, self.display_zone, 1.0).len()
+        });
+        self.memory_scroll = self
+            .memory_scroll
+            .saturating_add_signed(delta)
+            .min(lines.saturating_sub(1));
+    }
+
+    /// Write the memory report on screen to a timestamped file
+    fn export_memory_report(&mut self) {
+        let Some(report) = self.memory_reports.latest() else {
+            return;
+        };
+        let path =
+            command::commands::default_memory_file_name(chrono::Local::now(), self.display_zone);
+        self.last_command_result = Some(
+            match command::commands::export_memory_report(report, self.display_zone, &path) {
+                Ok(message) => command::ExecutionResult::Success(message),
+                Err(err) => command::ExecutionResult::Error(err),
+            },
+        );
+    }
+
+    /// Write Request Detail's request, or the requests Query Analysis lists,
+    /// to a timestamped `.sql` file
+    pub fn export_sql(&mut self) {
+        let requests = command::commands::sql_export_requests(
+            &self.view_mode,
+            &self.context_tracker,
+            self.search_query_for(&ViewMode::QueryAnalysis),
+            false,
+        );
+        let path =
+            command::commands::default_sql_file_name(chrono::Local::now(), self.display_zone);
+        self.last_command_result = Some(
+            match command::commands::export_sql(&requests, &self.privacy, &path) {
+                Ok(message) => command::ExecutionResult::Success(message),
+                Err(err) => command::ExecutionResult::Error(err),
+            },
+        );
+    }
+
+    /// The header's stats, as of the last change to them
+    fn header_stats(&self) -> std::sync::Arc<HeaderStats> {
+        self.stats_collector.header_stats(&self.header_stats)
+    }
+
+    pub fn close_build_error(&mut self) {
+        self.show_build_error = false;
+    }
+
+    /// Move the build error overlay to the next or previous error
+    pub fn select_build_error(&mut self, forward: bool) {
+        let count = self
+            .build_errors
+            .latest()
+            .map_or(0, |failure| failure.errors.len());
+        self.build_error_selected = if forward {
+            (self.build_error_selected + 1).min(count.saturating_sub(1))
+        } else {
+            self.build_error_selected.saturating_sub(1)
+        };
+    }
+
+    /// Ask for the selected build error's file to be opened in `$EDITOR`
+    /// once the terminal can be handed over
+    pub fn open_build_error_in_editor(&mut self) {
+        let Some(error) = self
+            .build_errors
+            .latest()
+            .and_then(|failure| failure.errors.get(self.build_error_selected))
+        else {
+            return;
+        };
+        let Some(file) = &error.file else {
+            self.last_command_result = Some(command::ExecutionResult::Error(
+                "The build error doesn't name a file".to_string(),
+            ));
+            return;
+        };
+        let dir = self.frontend_dir.as_deref().or(self.project.as_deref());
+        self.editor_request = Some(editor::EditorTarget::resolve(
+            file,
+            dir,
+            error.line,
+            error.column,
+        ));
+    }
+
+    /// Show the routes overlay from the top, reading the routes the first time
+    fn open_routes(&mut self) {
+        self.show_routes = true;
+        self.routes_scroll = 0;
+        if self.routes.is_none() && self.routes_loading.is_none() {
+            self.refresh_routes();
+        }
+    }
+
+    pub fn close_routes(&mut self) {
+        self.show_routes = false;
+    }
+
+    /// Run `rails routes` again in the background; a run already going is dropped
+    pub fn refresh_routes(&mut self) {
+        if !self.routes_available || self.is_read_only() {
+            return;
+        }
+        let Some(root) = self
+            .project
+            .as_deref()
+            .and_then(|path| ProjectRoot::new(path).ok())
+        else {
+            return;
+        };
+        self.routes_loading = Some(spawn_routes_refresh(root));
+    }
+
+    /// Pick up the routes once `rails routes` has finished
+    pub fn check_routes(&mut self) {
+        let Some(loading) = self.routes_loading.as_mut() else {
+            return;
+        };
+        let result = match loading.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err("rails routes stopped before reporting".to_string())
+            }
+        };
+        self.routes_loading = None;
+        self.apply_routes_result(result);
+    }
+
+    /// Use a new table (or keep the old one and show why it couldn't be read), and log it
+    fn apply_routes_result(&mut self, result: Result<RouteTable, String>) {
+        let message = match result {
+            Ok(table) => {
+                let message = format!("Routes loaded - {} routes", table.len());
+                self.set_routes(table);
+                self.routes_error = None;
+                message
+            }
+            Err(err) => {
+                let message = format!(
+                    "rails routes failed: {}",
+                    err.lines().next().unwrap_or_default()
+                );
+                self.routes_error = Some(err);
+                message
+            }
+        };
+        self.add_log(LogLine::new("caboose", message));
+    }
+
+    /// Match requests and error bursts against `table`
+    fn set_routes(&mut self, table: RouteTable) {
+        let table = std::sync::Arc::new(table);
+        self.context_tracker.set_routes(table.clone());
+        self.routes = Some(table);
+    }
+
+    fn scroll_routes(&mut self, delta: isize) {
+        let matching = self
+            .routes
+            .as_ref()
+            .map_or(0, |routes| routes.filter(&self.routes_query).count());
+        self.routes_scroll = self
+            .routes_scroll
+            .saturating_add_signed(delta)
+            .min(matching.saturating_sub(1));
+    }
+
+    /// Readiness of each running process right now
+    fn process_readiness(&self) -> HashMap<String, Readiness> {
+        self.boot_times.readiness(&self.processes, self.clock.now())
+    }
+
+    /// Health of all processes together, for the header and `/status`
+    pub fn project_health(&self) -> ProjectHealth {
+        project_health(&self.processes, &self.process_readiness())
+    }
+
+    pub fn select_next_store(&mut self) {
+        let count = self.diagnostics_usage().len();
+        if self.diagnostics_selected + 1 < count {
+            self.diagnostics_selected += 1;
+        }
+    }
+
+    pub fn select_previous_store(&mut self) {
+        self.diagnostics_selected = self.diagnostics_selected.saturating_sub(1);
+    }
+
+    /// Step the trim size up or down through the preset values
+    pub fn adjust_diagnostics_keep(&mut self, larger: bool) {
+        let idx = DIAGNOSTICS_KEEP_STEPS
+            .iter()
+            .position(|&n| n == self.diagnostics_keep)
+            .unwrap_or(0);
+        let next = if larger {
+            (idx + 1).min(DIAGNOSTICS_KEEP_STEPS.len() - 1)
+        } else {
+            idx.saturating_sub(1)
+        };
+        self.diagnostics_keep = DIAGNOSTICS_KEEP_STEPS[next];
+    }
+
+    /// Trim the selected store down to its newest `diagnostics_keep` entries
+    pub fn trim_selected_store(&mut self) {
+        let Some(store) = self
+            .diagnostics_usage()
+            .get(self.diagnostics_selected)
+            .copied()
+        else {
+            return;
+        };
+        if !store.kind.is_trimmable() {
+            return;
+        }
+
+        let keep = self.diagnostics_keep;
+        let removed = match store.kind {
+            StoreKind::LogBuffer => {
+                let removed = crate::diagnostics::keep_last(&mut self.logs, keep);
+                self.log_chips.recount(&self.logs);
+                self.log_viewport
+                    .lines_evicted(self.logs.first().map(|log| log.seq));
+                self.log_annotations
+                    .evict_before(self.logs.first().map(|log| log.seq));
+                removed
+            }
+            kind => {
+                self.context_tracker.trim(kind, keep)
+                    + self.db_health.trim(kind, keep)
+                    + self.exception_tracker.trim(kind, keep)
+                    + self.test_tracker.trim(kind, keep)
+                    + self.stats_collector.trim(kind, keep)
+            }
+        };
+
+        self.last_command_result = Some(command::ExecutionResult::Success(format!(
+            "Trimmed {} entries from {}",
+            removed,
+            store.kind.label()
+        )));
+    }
+
+    // ========================================================================
+    // NAVIGATION
+    // ========================================================================
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_logs(-1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_logs(1);
+    }
+
+    /// Scroll the logs view by `delta` screen lines (positive is down)
+    ///
+    /// Auto-scroll comes back on when the newest line is reached: at the
+    /// bottom, or at the top in newest-first order.
+    fn scroll_logs(&mut self, delta: isize) {
+        let seqs: Vec<u64> = self.filtered_logs().iter().map(|log| log.seq).collect();
+        let delta = self.log_order.buffer_delta(delta);
+        self.auto_scroll = self.log_viewport.scroll(&seqs, self.auto_scroll, delta);
+    }
+
+    /// Switch the Logs view between oldest-first and newest-first
+    /// Show or hide the lines of log chip `index` (key `index + 1`)
+    pub fn toggle_log_chip(&mut self, index: usize) {
+        let Some(category) = self.log_chips.toggle(index) else {
+            return;
+        };
+        let shown = if self.log_chips.hides(category) {
+            "hidden"
+        } else {
+            "shown"
+        };
+        self.last_command_result = Some(command::ExecutionResult::Success(format!(
+            "{} lines {}",
+            category.label(),
+            shown
+        )));
+    }
+
+    pub fn toggle_log_order(&mut self) {
+        self.log_order = self.log_order.toggled();
+        self.last_command_result = Some(command::ExecutionResult::Success(format!(
+            "Logs shown {}",
+            self.log_order.label()
+        )));
+    }
+
+    /// Turn privacy mode on or off
+    pub fn toggle_privacy(&mut self) {
+        let enabled = !self.privacy.is_enabled();
+        self.privacy.set_enabled(enabled);
+        self.last_command_result = Some(command::ExecutionResult::Success(
+            if enabled {
+                "Privacy mode on - personal data is masked on screen and in exports"
+            } else {
+                "Privacy mode off"
+            }
+            .to_string(),
+        ));
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.horizontal_scroll = self
+            .horizontal_scroll
+            .min(self.max_horizontal_scroll.get())
+            .saturating_sub(10);
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.horizontal_scroll =
+            (self.horizontal_scroll + 10).min(self.max_horizontal_scroll.get());
+    }
+
+    pub fn scroll_home(&mut self) {
+        self.horizontal_scroll = 0;
+    }
+
+    pub fn scroll_page_up(&mut self, page_size: usize) {
+        self.scroll_logs(-(page_size as isize));
+    }
+
+    pub fn scroll_page_down(&mut self, page_size: usize) {
+        self.scroll_logs(page_size as isize);
+    }
+
+    /// One notch of the mouse wheel, `notches` negative for up: scrolls the
+    /// logs as the arrow keys do, or moves the selection in the list views
+    pub fn scroll_with_wheel(&mut self, notches: isize) {
+        let up = notches < 0;
+        match self.view_mode {
+            ViewMode::Logs => self.scroll_logs(notches * click_map::WHEEL_SCROLL_LINES),
+            ViewMode::RequestDetail(_) => {
+                self.scroll_request_detail(notches * click_map::WHEEL_SCROLL_LINES)
+            }
+            ViewMode::QueryAnalysis if up => self.select_previous_request(),
+            ViewMode::QueryAnalysis => self.select_next_request(),
+            ViewMode::DatabaseHealth if up => self.select_previous_table(),
+            ViewMode::DatabaseHealth => self.select_next_table(),
+            ViewMode::Exceptions if up => self.select_previous_exception(),
+            ViewMode::Exceptions => self.select_next_exception(),
+            ViewMode::TestResults if up => self.select_previous_test_failure(),
+            ViewMode::TestResults => self.select_next_test_failure(),
+            _ => {}
+        }
+    }
+
+    /// A left click on `target`: a tab switches to its view, a row is
+    /// selected, and a second click on the same row opens it as Enter would
+    pub fn click(&mut self, target: ClickTarget, now: Instant) {
+        let double = self.clicks.click(target, now);
+        match target {
+            ClickTarget::Tab(index) => {
+                if let Some(view) = ViewMode::from_index(index) {
+                    self.set_view(view);
+                }
+                return;
+            }
+            ClickTarget::NPlusOne(index) => {
+                self.selected_in_flight = None;
+                self.selected_n_plus_one = Some(index);
+            }
+            ClickTarget::InFlight(index) => {
+                self.selected_n_plus_one = None;
+                self.selected_in_flight = Some(index);
+            }
+            ClickTarget::Request(index) => {
+                self.selected_n_plus_one = None;
+                self.selected_in_flight = None;
+                self.selected_request = index;
+            }
+            ClickTarget::Exception(index) => self.selected_exception = index,
+        }
+        if double {
+            match target {
+                ClickTarget::Exception(_) => self.view_selected_exception(),
+                _ => self.view_selected_request(),
+            }
         }
     }
 
+    /// Whether a prompt, warning or overlay is up, which the mouse leaves alone
+    fn mouse_blocked(&self) -> bool {
+        self.process_warning.is_some()
+            || self.disk_warning.is_some()
+            || self.command_mode
+            || self.search_mode
+            || self.show_diagnostics
+            || self.show_alerts
+            || self.show_routes
+            || self.show_trends
+            || self.show_status
+            || self.show_help
+            || (self.show_memory && self.memory_reports.latest().is_some())
+            || (self.show_build_error && self.build_errors.latest().is_some())
+    }
+
+    /// Move down through In Flight, then on into the completed requests
     pub fn select_next_request(&mut self) {
-        let total = self.context_tracker.get_recent_requests().len();
+        if let Some(index) = self.selected_n_plus_one {
+            if index + 1 < self.n_plus_one_listed() {
+                self.selected_n_plus_one = Some(index + 1);
+                return;
+            }
+            self.selected_n_plus_one = None;
+            self.selected_request = 0;
+            if !self.context_tracker.get_current_requests().is_empty() {
+                self.selected_in_flight = Some(0);
+            }
+            return;
+        }
+        if let Some(index) = self.selected_in_flight {
+            let in_flight = self.context_tracker.get_current_requests().len();
+            self.selected_in_flight = (index + 1 < in_flight).then_some(index + 1);
+            return;
+        }
+        let total = self.matching_requests().len();
         if total > 0 {
             self.selected_request = (self.selected_request + 1).min(total - 1);
         }
     }
 
+    /// Move up through the completed requests, then into In Flight and the
+    /// N+1 section above them
     pub fn select_previous_request(&mut self) {
-        if self.selected_request > 0 {
-            self.selected_request -= 1;
+        if let Some(index) = self.selected_n_plus_one {
+            self.selected_n_plus_one = Some(index.saturating_sub(1));
+            return;
+        }
+        match self.selected_in_flight {
+            Some(0) => {
+                let listed = self.n_plus_one_listed();
+                if listed > 0 {
+                    self.selected_in_flight = None;
+                    self.selected_n_plus_one = Some(listed - 1);
+                }
+            }
+            Some(index) => self.selected_in_flight = Some(index - 1),
+            None if self.selected_request > 0 => self.selected_request -= 1,
+            None => {
+                let in_flight = self.context_tracker.get_current_requests().len();
+                let listed = self.n_plus_one_listed();
+                if in_flight > 0 {
+                    self.selected_in_flight = Some(in_flight - 1);
+                } else if listed > 0 {
+                    self.selected_n_plus_one = Some(listed - 1);
+                }
+            }
+        }
+    }
+
+    /// Repeated queries the N+1 section lists, none while it's collapsed
+    fn n_plus_one_listed(&self) -> usize {
+        if !self.n_plus_one_expanded {
+            return 0;
         }
+        self.context_tracker
+            .get_aggregated_n_plus_one()
+            .len()
+            .min(views::query_analysis_view::N_PLUS_ONE_LISTED)
+    }
+
+    /// Collapse Query Analysis's N+1 section to a count, or list it again
+    pub fn toggle_n_plus_one(&mut self) {
+        self.n_plus_one_expanded = !self.n_plus_one_expanded;
+        self.selected_n_plus_one = None;
     }
 
     pub fn select_next_exception(&mut self) {
-        let total = self.exception_tracker.get_grouped_exceptions().len();
+        let total = self.matching_exceptions().len();
         if total > 0 {
             self.selected_exception = (self.selected_exception + 1).min(total - 1);
         }
@@ -517,43 +2454,384 @@ impl App {
         }
     }
 
+    /// Keep the selection on the list after it shrinks
+    fn clamp_exception_selection(&mut self) {
+        let total = self.matching_exceptions().len();
+        self.selected_exception = self.selected_exception.min(total.saturating_sub(1));
+    }
+
+    /// Dismiss the selected exception group until it happens again, or list a
+    /// dismissed one as usual again
+    pub fn toggle_selected_dismissed(&mut self) {
+        let Some(group) = self
+            .matching_exceptions()
+            .into_iter()
+            .nth(self.selected_exception)
+        else {
+            return;
+        };
+        if group.dismissed {
+            self.exception_tracker.undismiss(&group.fingerprint);
+        } else {
+            self.exception_tracker.dismiss(&group.fingerprint);
+        }
+        self.clamp_exception_selection();
+    }
+
+    /// Raise the Exceptions list's minimum severity a step, back to all after
+    /// critical
+    pub fn cycle_exception_severity(&mut self) {
+        self.exception_filter.min_severity = self.exception_filter.next_severity();
+        self.clamp_exception_selection();
+    }
+
+    /// List only exception groups seen in the last
+    /// [`DEFAULT_SEEN_WITHIN`](crate::exception::DEFAULT_SEEN_WITHIN), or all again
+    pub fn toggle_recent_exceptions(&mut self) {
+        self.exception_filter.seen_within = match self.exception_filter.seen_within {
+            Some(_) => None,
+            None => Some(crate::exception::DEFAULT_SEEN_WITHIN),
+        };
+        self.clamp_exception_selection();
+    }
+
+    /// Failed tests of the latest run, as Test Results lists them
+    fn test_failures_listed(&self) -> usize {
+        self.test_tracker
+            .latest_run()
+            .map_or(0, |run| run.failed_tests().len())
+    }
+
+    pub fn select_next_test_failure(&mut self) {
+        let total = self.test_failures_listed();
+        if total > 0 {
+            self.selected_test_failure = (self.selected_test_failure + 1).min(total - 1);
+        }
+    }
+
+    pub fn select_previous_test_failure(&mut self) {
+        self.selected_test_failure = self
+            .selected_test_failure
+            .min(self.test_failures_listed().saturating_sub(1))
+            .saturating_sub(1);
+    }
+
+    /// Show the selected failure's message and backtrace, or hide them again
+    pub fn toggle_test_failure(&mut self) {
+        let total = self.test_failures_listed();
+        if total == 0 {
+            return;
+        }
+        let selected = self.selected_test_failure.min(total - 1);
+        self.expanded_test_failure = match self.expanded_test_failure {
+            Some(expanded) if expanded == selected => None,
+            _ => Some(selected),
+        };
+    }
+
+    pub fn select_next_table(&mut self) {
+        let total = self.db_health.get_top_tables().len();
+        if total > 0 {
+            self.selected_table = (self.selected_table + 1).min(total - 1);
+        }
+    }
+
+    pub fn select_previous_table(&mut self) {
+        if self.selected_table > 0 {
+            self.selected_table -= 1;
+        }
+    }
+
+    pub fn view_selected_table(&mut self) {
+        if let Some((table, _)) = self.db_health.get_top_tables().get(self.selected_table) {
+            self.view_mode = ViewMode::TableDetail(table.clone());
+        }
+    }
+
+    /// Requests shown in Query Analysis, narrowed by its search query
+    fn matching_requests(&self) -> Vec<crate::context::CompletedRequest> {
+        let query = SearchQuery::new(self.search_query_for(&ViewMode::QueryAnalysis));
+        self.context_tracker.get_recent_requests_matching(&query)
+    }
+
+    /// Exception groups shown in Exceptions, narrowed by its search query and
+    /// filter, in the order listed
+    fn matching_exceptions(&self) -> Vec<crate::exception::ExceptionGroup> {
+        let query = SearchQuery::new(self.search_query_for(&ViewMode::Exceptions));
+        let groups = self.exception_tracker.get_grouped_exceptions_filtered(
+            &query,
+            &self.exception_filter,
+            Instant::now(),
+        );
+        views::exceptions_view::table(self.exception_sort, &self.privacy)
+            .sorted(&groups)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Re-sort the active view's table, keeping the selected exception selected
+    pub fn change_sort(&mut self, change: SortChange) {
+        match self.view_mode {
+            ViewMode::Exceptions => {
+                let selected = self
+                    .matching_exceptions()
+                    .get(self.selected_exception)
+                    .map(|group| group.fingerprint.clone());
+                self.exception_sort =
+                    views::exceptions_view::table(self.exception_sort, &self.privacy).apply(change);
+                if let Some(fingerprint) = selected {
+                    self.selected_exception = self
+                        .matching_exceptions()
+                        .iter()
+                        .position(|group| group.fingerprint == fingerprint)
+                        .unwrap_or(0);
+                }
+            }
+            ViewMode::DatabaseHealth => {
+                self.slow_query_sort = views::database_health_view::slow_query_table(
+                    self.slow_query_sort,
+                    &self.privacy,
+                )
+                .apply(change);
+            }
+            _ => {}
+        }
+    }
+
     pub fn view_selected_request(&mut self) {
-        self.view_mode = ViewMode::RequestDetail(self.selected_request);
+        // A repeated query opens the latest request it was found in
+        if let Some(index) = self.selected_n_plus_one.take() {
+            if let Some(issue) = self.context_tracker.get_aggregated_n_plus_one().get(index) {
+                self.show_request_extra = false;
+                self.request_detail_scroll = 0;
+                self.view_mode = ViewMode::RequestDetail(issue.latest_request);
+            }
+            return;
+        }
+        if let Some(index) = self.selected_in_flight.take() {
+            // The request may have finished since it was selected
+            if let Some(context) = self.context_tracker.get_current_requests().get(index) {
+                self.view_mode = ViewMode::LiveRequest(context.id);
+                self.follow_live_request();
+                return;
+            }
+        }
+
+        // Detail views index the unfiltered list, so map a filtered selection back
+        let mut index = self.selected_request;
+        if !self.search_query_for(&ViewMode::QueryAnalysis).is_empty()
+            && let Some(selected) = self.matching_requests().get(self.selected_request)
+        {
+            index = self
+                .context_tracker
+                .get_recent_requests()
+                .iter()
+                .position(|req| req.completed_at == selected.completed_at)
+                .unwrap_or(index);
+        }
+        self.show_request_extra = false;
+        self.request_detail_scroll = 0;
+        self.view_mode = ViewMode::RequestDetail(index);
+    }
+
+    /// Swap a followed request for its Request Detail once it completes
+    pub fn follow_live_request(&mut self) {
+        if let ViewMode::LiveRequest(id) = self.view_mode
+            && let Some(index) = self.context_tracker.completed_position(id)
+        {
+            self.show_request_extra = false;
+            self.request_detail_scroll = 0;
+            self.view_mode = ViewMode::RequestDetail(index);
+        }
+    }
+
+    /// Scroll Request Detail's timeline, stopping at its last line
+    pub fn scroll_request_detail(&mut self, delta: isize) {
+        let ViewMode::RequestDetail(index) = self.view_mode else {
+            return;
+        };
+        let lines = self
+            .context_tracker
+            .get_recent_requests()
+            .get(index)
+            .map_or(0, |request| {
+                views::request_detail_view::timeline_lines(
+                    request,
+                    self.show_request_extra,
+                    &self.privacy,
+                    1.0,
+                )
+                .len()
+            });
+        self.request_detail_scroll = self
+            .request_detail_scroll
+            .saturating_add_signed(delta)
+            .min(lines.saturating_sub(1));
+    }
+
+    /// Expand or collapse the extra fields section in Request Detail
+    pub fn toggle_request_extra(&mut self) {
+        self.show_request_extra = !self.show_request_extra;
     }
 
+    /// Open the selected exception; the list may be filtered or re-sorted, so
+    /// it's found in Exception Detail's order by fingerprint
     pub fn view_selected_exception(&mut self) {
-        self.view_mode = ViewMode::ExceptionDetail(self.selected_exception);
+        let mut index = self.selected_exception;
+        if let Some(selected) = self.matching_exceptions().get(self.selected_exception) {
+            index = self
+                .exception_tracker
+                .get_grouped_exceptions()
+                .iter()
+                .position(|group| group.fingerprint == selected.fingerprint)
+                .unwrap_or(index);
+        }
+        self.view_mode = ViewMode::ExceptionDetail(index);
     }
 
     // ========================================================================
     // FILTERING
     // ========================================================================
 
+    /// Clear the process filter and the active view's search
     pub fn clear_filter(&mut self) {
         self.filter_process = None;
-        self.auto_scroll = true;
-        self.log_scroll = 0;
+        self.min_log_level = None;
+        self.exception_filter = crate::exception::ExceptionFilter::default();
+        self.time_range = None;
+        self.log_chips.show_all();
+        self.search_queries.remove(&self.view_mode);
+        self.reset_view_selection();
+        self.enable_auto_scroll();
+    }
+
+    /// Show or hide the process panel in the compact layout
+    pub fn toggle_process_panel(&mut self) {
+        self.show_compact_processes = !self.show_compact_processes;
+        if !self.show_compact_processes {
+            self.unfocus_processes();
+        }
     }
 
     pub fn enable_auto_scroll(&mut self) {
         self.auto_scroll = true;
-        self.log_scroll = 0;
+        self.log_viewport.follow();
+    }
+
+    // ========================================================================
+    // TIMELINE
+    // ========================================================================
+
+    /// Width of one timeline bucket at the current zoom
+    pub fn timeline_width(&self) -> chrono::TimeDelta {
+        views::timeline_view::ZOOM_LEVELS[self.timeline_zoom]
+    }
+
+    /// Where the timeline ends: now, or the newest recorded event when
+    /// replaying a file or a saved session
+    pub fn timeline_anchor(&self) -> chrono::DateTime<chrono::Local> {
+        if self.is_read_only() {
+            self.stats_collector
+                .timeline_latest()
+                .or(self.logs.last().map(|log| log.time))
+                .unwrap_or_else(chrono::Local::now)
+        } else {
+            chrono::Local::now()
+        }
+    }
+
+    /// Start of the selected timeline bucket
+    pub fn timeline_selected(&self) -> chrono::DateTime<chrono::Local> {
+        let at = self
+            .timeline_cursor
+            .unwrap_or_else(|| self.timeline_anchor());
+        crate::stats::bucket_start(at, self.timeline_width())
+    }
+
+    /// Wider (`true`) or narrower timeline buckets, keeping the selection
+    pub fn zoom_timeline(&mut self, wider: bool) {
+        let last = views::timeline_view::ZOOM_LEVELS.len() - 1;
+        self.timeline_zoom = if wider {
+            (self.timeline_zoom + 1).min(last)
+        } else {
+            self.timeline_zoom.saturating_sub(1)
+        };
+    }
+
+    /// Select the bucket `steps` buckets later (or earlier, when negative)
+    ///
+    /// Stepping past the newest bucket follows it again; stepping stops at the
+    /// oldest recorded one.
+    pub fn pan_timeline(&mut self, steps: i32) {
+        let width = self.timeline_width();
+        let anchor = crate::stats::bucket_start(self.timeline_anchor(), width);
+        let oldest = self
+            .stats_collector
+            .timeline_earliest()
+            .map(|earliest| crate::stats::bucket_start(earliest, width))
+            .unwrap_or(anchor)
+            .min(anchor);
+        let target = self.timeline_selected() + width * steps;
+        self.timeline_cursor = if target >= anchor {
+            None
+        } else {
+            Some(target.max(oldest))
+        };
+    }
+
+    /// Show the Logs view for the selected timeline bucket, as `/around` would
+    pub fn open_timeline_bucket(&mut self) {
+        let start = self.timeline_selected();
+        let range = TimeRange::between(start, start + self.timeline_width());
+        self.time_range = Some(range);
+        self.view_mode = ViewMode::Logs;
+        self.active_tab_index = 0;
+        self.auto_scroll = false;
+        self.jump_to_time_range();
+        self.last_command_result = Some(command::ExecutionResult::Success(
+            range.showing_message(&self.logs, self.display_zone),
+        ));
+    }
+
+    /// Move the Logs view to the first line at or after the `/around` time
+    fn jump_to_time_range(&mut self) {
+        let Some(range) = self.time_range else {
+            return;
+        };
+        let logs = self.filtered_logs();
+        let seqs: Vec<u64> = logs.iter().map(|log| log.seq).collect();
+        let target = logs
+            .iter()
+            .find(|log| log.time >= range.center)
+            .or(logs.last())
+            .map(|log| log.seq);
+        self.log_viewport.jump_to(&seqs, target);
     }
 
     pub fn filtered_logs(&self) -> Vec<&LogLine> {
+        let logs = match &self.time_range {
+            Some(range) => range.slice(&self.logs),
+            None => &self.logs,
+        };
         let mut logs: Vec<&LogLine> = if let Some(ref filter) = self.filter_process {
-            self.logs
-                .iter()
-                .filter(|log| &log.process_name == filter)
+            logs.iter()
+                .filter(|log| filter.matches(&log.process_name))
                 .collect()
         } else {
-            self.logs.iter().collect()
+            logs.iter().collect()
         };
+        if self.log_chips.is_filtering() {
+            logs.retain(|log| !self.log_chips.hides(log.category));
+        }
+        if let Some(min_level) = self.min_log_level {
+            logs.retain(|log| min_level.admits(log.level));
+        }
 
         // Apply search filter
-        if !self.search_query.is_empty() {
-            let query = self.search_query.to_lowercase();
-            logs.retain(|log| log.content.to_lowercase().contains(&query));
+        let search = self.log_search();
+        if !search.is_empty() {
+            logs.retain(|log| search.matches(log));
         }
 
         logs
@@ -569,19 +2847,255 @@ impl App {
 
         let mut file = File::create(path)?;
         for log in &self.logs {
-            writeln!(file, "[{}] {}", log.process_name, log.content)?;
+            writeln!(
+                file,
+                "{}",
+                command::commands::export_line(log, self.display_zone, &self.privacy)
+            )?;
         }
         Ok(())
     }
 
+    /// Write the log buffer to `path` as JSON lines, see [`crate::log_export`]
+    pub fn export_logs_json(&self, path: &str) -> Result<usize, String> {
+        crate::log_export::export_json(
+            std::path::Path::new(path),
+            &self.logs,
+            self.display_zone,
+            &self.privacy,
+        )
+    }
+
     // ========================================================================
     // PROCESS MANAGEMENT
     // ========================================================================
 
-    pub fn update_processes(&mut self, processes: Vec<ProcessInfo>) {
+    /// Take the process manager's latest list; the same list again is a no-op
+    pub fn update_processes(&mut self, processes: std::sync::Arc<Vec<ProcessInfo>>) {
+        if std::sync::Arc::ptr_eq(&processes, &self.processes) {
+            return;
+        }
+        self.boot_times.track(&processes);
+        let now = chrono::Local::now();
+        for process in processes.iter() {
+            let previous = self.processes.iter().find(|p| p.name == process.name);
+            if previous.is_some_and(|p| process.restarts > p.restarts) {
+                self.stats_collector
+                    .record_timeline(now, TimelineEvent::Restart);
+            }
+            // A task failing is its result, not a crash
+            if process.status == ProcessStatus::Crashed
+                && previous.is_none_or(|p| p.status != ProcessStatus::Crashed)
+                && !crate::process::is_task(&process.name)
+            {
+                self.stats_collector
+                    .record_timeline(now, TimelineEvent::Crash);
+                self.autofocus
+                    .observe(FocusEvent::Crash(process.name.clone()), Instant::now());
+            }
+        }
         self.processes = processes;
     }
 
+    /// Give the Logs view's Processes panel the keys, or take them back
+    ///
+    /// Only while the panel is on screen; the cursor starts where it was left.
+    pub fn toggle_process_focus(&mut self) {
+        if self.processes_focused {
+            self.unfocus_processes();
+        } else if matches!(self.view_mode, ViewMode::Logs)
+            && self.process_panel_shown.get()
+            && !self.processes.is_empty()
+        {
+            self.processes_focused = true;
+        }
+    }
+
+    fn unfocus_processes(&mut self) {
+        self.processes_focused = false;
+        self.pending_process_action = None;
+    }
+
+    /// Whether the Processes panel has the keys, and is on screen to show it
+    fn processes_have_focus(&self) -> bool {
+        self.processes_focused
+            && matches!(self.view_mode, ViewMode::Logs)
+            && self.process_panel_shown.get()
+    }
+
+    /// Cursor row in the Processes panel, while it has the keys
+    fn focused_process_index(&self) -> Option<usize> {
+        if !self.processes_focused || self.processes.is_empty() {
+            return None;
+        }
+        Some(self.selected_process.min(self.processes.len() - 1))
+    }
+
+    /// Name of the process under the Processes panel's cursor
+    fn selected_process_name(&self) -> Option<&str> {
+        self.focused_process_index()
+            .map(|index| self.processes[index].name.as_str())
+    }
+
+    /// Move the Processes panel's cursor, stopping at either end
+    pub fn move_process_selection(&mut self, down: bool) {
+        let Some(index) = self.focused_process_index() else {
+            return;
+        };
+        self.selected_process = if down {
+            (index + 1).min(self.processes.len() - 1)
+        } else {
+            index.saturating_sub(1)
+        };
+    }
+
+    /// Stop, restart or start the process under the Processes panel's cursor
+    ///
+    /// Stop and restart only happen when asked twice in a row for the same
+    /// process; the first time leaves a hint in the footer.
+    pub fn process_action(&mut self, action: process_control::ProcessAction) {
+        let pending = self.pending_process_action.take();
+        let Some(name) = self.selected_process_name().map(str::to_string) else {
+            return;
+        };
+        let confirmed = pending.is_some_and(|p| p.action == action && p.process == name);
+        if action.needs_confirmation() && !confirmed {
+            self.pending_process_action = Some(process_control::PendingAction {
+                action,
+                process: name,
+            });
+            return;
+        }
+
+        let Some(manager) = &self.process_manager else {
+            self.last_command_result = Some(command::ExecutionResult::Error(
+                "Can't control a saved session's processes".to_string(),
+            ));
+            return;
+        };
+        let result = match action {
+            process_control::ProcessAction::Stop => manager.stop_process(&name),
+            process_control::ProcessAction::Restart => manager.restart_process(&name),
+            process_control::ProcessAction::Start => manager.start_process(&name),
+        };
+        self.last_command_result = Some(match result {
+            Ok(()) => command::ExecutionResult::Success(action.done_message(&name)),
+            Err(err) => command::ExecutionResult::Error(err),
+        });
+    }
+
+    /// Pick up the disk watcher's latest scan, if there is a new one
+    pub fn check_disk(&mut self) {
+        let Some(watcher) = self.disk_watcher.as_mut() else {
+            return;
+        };
+        if !watcher.has_changed().unwrap_or(false) {
+            return;
+        }
+        let warnings = watcher.borrow_and_update().clone();
+        self.note_disk_warnings(&warnings);
+    }
+
+    /// Apply every change the project watcher has reported since the last check
+    pub fn check_project_files(&mut self) {
+        let Some(watcher) = self.project_watcher.as_mut() else {
+            return;
+        };
+        let mut changes = Vec::new();
+        while let Ok(change) = watcher.try_recv() {
+            changes.push(change);
+        }
+        for change in changes {
+            self.apply_project_change(change);
+        }
+    }
+
+    /// Refresh what depends on a changed project file, and log the change
+    ///
+    /// A new adapter in database.yml switches the SQL dialect, with a warning:
+    /// the running server keeps its connection until it restarts.
+    pub fn apply_project_change(&mut self, change: ProjectChange) {
+        let message = change.message();
+        // Migrations rewrite schema.rb; a new route or adapter is a change worth marking too
+        if !matches!(change, ProjectChange::Removed(_)) {
+            self.stats_collector
+                .record_timeline(chrono::Local::now(), TimelineEvent::Deploy);
+        }
+        match change {
+            ProjectChange::Schema(tables) => {
+                self.context_tracker.set_schema(tables.clone());
+                self.db_health.set_schema(tables);
+            }
+            ProjectChange::DatabaseConfig { adapter } => {
+                let previous = self.db_health.dialect();
+                let dialect = SqlDialect::from_adapter(adapter.as_deref());
+                if dialect != previous {
+                    self.db_health.set_dialect(dialect);
+                    self.process_warning = Some(format!(
+                        "database.yml adapter changed from {} to {} - restart the server to connect with it",
+                        previous.name(),
+                        dialect.name()
+                    ));
+                }
+            }
+            // The table comes from `rails routes`; the summary only counts lines
+            ProjectChange::Routes(_) => self.refresh_routes(),
+            ProjectChange::Removed(_) => {}
+        }
+
+        self.add_log(LogLine::new("caboose", message));
+    }
+
+    /// Warn once about each disk problem, again if it clears and comes back
+    pub fn note_disk_warnings(&mut self, warnings: &[DiskWarning]) {
+        let keys: Vec<String> = warnings.iter().map(DiskWarning::key).collect();
+        self.disk_warned.retain(|key| keys.contains(key));
+
+        if let Some(warning) = warnings
+            .iter()
+            .find(|warning| !self.disk_warned.contains(&warning.key()))
+        {
+            self.disk_warned.push(warning.key());
+            self.disk_warning = Some(warning.clone());
+        }
+    }
+
+    /// Truncate the log file the disk warning is about
+    pub fn truncate_warned_log(&mut self) {
+        let Some(DiskWarning::LargeLog(file)) = self.disk_warning.take() else {
+            return;
+        };
+        if self.is_read_only() {
+            return;
+        }
+        match crate::disk::truncate_log(&file.path) {
+            Ok(freed) => {
+                self.last_command_result = Some(command::ExecutionResult::Success(format!(
+                    "Truncated {}, freed {}",
+                    file.name,
+                    format_bytes(freed)
+                )));
+            }
+            Err(err) => self.process_warning = Some(err),
+        }
+    }
+
+    /// Warn once about each process that daemonized out of Caboose's control
+    pub fn note_unmanaged_processes(&mut self, unmanaged: &[ProcessInfo]) {
+        for info in unmanaged {
+            if self.warned_detached.contains(&info.name) {
+                continue;
+            }
+            self.warned_detached.push(info.name.clone());
+            self.process_warning = Some(format!(
+                "'{}' daemonized (pid {}) and runs outside caboose - you'll be asked whether to stop it on exit",
+                info.name,
+                info.pid
+                    .map_or_else(|| "?".to_string(), |pid| pid.to_string())
+            ));
+        }
+    }
+
     // ========================================================================
     // APPLICATION CONTROL
     // ========================================================================
@@ -600,6 +3114,7 @@ impl App {
 // ============================================================================
 
 /// Run the UI event loop
+#[allow(clippy::too_many_arguments)]
 pub async fn run_ui(
     mut app: App,
     mut log_rx: mpsc::UnboundedReceiver<LogLine>,
@@ -612,9 +3127,14 @@ pub async fn run_ui(
     shutdown_flag: std::sync::Arc<AtomicBool>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
+    terminal::install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    if let Err(err) = execute!(stdout, EnterAlternateScreen, EnableMouseCapture) {
+        let _ = terminal::restore();
+        return Err(err.into());
+    }
+    terminal::set_tui_active(true);
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -629,22 +3149,79 @@ pub async fn run_ui(
             app.quit();
         }
 
-        // Update process list
-        let processes = process_manager.get_processes();
-        app.update_processes(processes);
+        app.check_suspend();
+
+        // Update process list; a saved session keeps the one it was saved with
+        if !app.is_read_only() {
+            let processes = process_manager.get_processes();
+            app.update_processes(processes);
+            app.note_unmanaged_processes(&process_manager.unmanaged_processes());
+        }
+        app.check_disk();
+        app.check_project_files();
+        app.check_routes();
+        app.check_trends();
+
+        app.refresh_diagnostics();
+        app.reload_config_if_changed();
+        app.sample_health_score();
+        app.check_alerts();
+        app.check_bursts();
+        app.check_turbo_bursts();
+        app.check_duplicates();
+        app.check_analytics_save();
+        app.follow_live_request();
+        app.check_autofocus();
 
         // Update animation frame
         app.spinner_frame = app.spinner_frame.wrapping_add(1);
 
         // Draw UI using modular render function
+        let draw_started = Instant::now();
         terminal.draw(|f| render_ui(f, &app))?;
+        let draw = draw_started.elapsed();
 
         // Handle input (with timeout)
+        let mut input = None;
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                handle_key_event(&mut app, key);
+            let input_started = Instant::now();
+            let handled = match event::read()? {
+                Event::Key(key) => {
+                    handle_key_event(&mut app, key);
+                    true
+                }
+                Event::Mouse(mouse) => handle_mouse_event(&mut app, mouse),
+                _ => false,
+            };
+            if handled {
+                input = Some(input_started.elapsed());
             }
         }
+        app.record_frame(draw, input, Instant::now());
+
+        // A terminal editor needs the screen to itself until it exits
+        if let Some(target) = app.editor_request.take() {
+            disable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                DisableMouseCapture,
+                LeaveAlternateScreen
+            )?;
+            let opened = editor::open_in_editor(&target);
+            enable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                EnterAlternateScreen,
+                EnableMouseCapture
+            )?;
+            terminal.clear()?;
+            app.last_command_result = Some(match opened {
+                Ok(()) => {
+                    command::ExecutionResult::Success(format!("Opened {}", target.path.display()))
+                }
+                Err(err) => command::ExecutionResult::Error(err),
+            });
+        }
 
         if app.should_quit() {
             // Stop all managed processes immediately on quit
@@ -655,10 +3232,25 @@ pub async fn run_ui(
     }
 
     // Restore terminal
+    terminal::set_tui_active(false);
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    )?;
     terminal.show_cursor()?;
 
+    if let Err(err) = app.dump_unparsed() {
+        eprintln!("[WARN] {}", err);
+    }
+    if let Err(err) = app.save_ui_state() {
+        eprintln!("[WARN] {}", err);
+    }
+    if let Err(err) = app.save_analytics() {
+        eprintln!("[WARN] {}", err);
+    }
+
     Ok(())
 }
 
@@ -671,10 +3263,10 @@ pub async fn run_ui(
 // ============================================================================
 
 /// Main rendering dispatcher
-
 fn render_ui(f: &mut ratatui::Frame, app: &App) {
     // Clear the full frame to avoid artifacts bleeding between views/spinner frames
     f.render_widget(Clear, f.area());
+    app.click_map.borrow_mut().clear();
 
     let fade_progress = if let Some(last_change_time) = app.last_view_change_time {
         let elapsed = last_change_time.elapsed();
@@ -684,97 +3276,452 @@ fn render_ui(f: &mut ratatui::Frame, app: &App) {
         1.0
     };
 
-    let chunks = Layout::default()
-        .direction(ratatui::layout::Direction::Vertical)
-        .constraints([
-            Constraint::Length(4), // For header (with environment info)
-            Constraint::Length(3), // For tabs
-            Constraint::Min(0),    // For content
-            Constraint::Length(1), // For footer
-        ])
-        .split(f.area());
-
-    render_header(
-        f,
-        chunks[0],
-        &app._git_info,
-        &app.environment_info,
-        &app.stats_collector,
-        &app.test_tracker,
-        Some(fade_progress),
-    );
+    // Not even the compact layout fits
+    let area = f.area();
+    if area.width < layout::MIN_VIEW_WIDTH || area.height < layout::MIN_VIEW_HEIGHT + 2 {
+        layout::render_too_small(f, area);
+        return;
+    }
+
+    let compact = app.compact_thresholds.is_compact(area);
+    let (mut content_area, footer_area) = if compact {
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Tab indicator, environment, git and one stat
+                Constraint::Min(0),    // For content
+                Constraint::Length(1), // For footer
+            ])
+            .split(area);
+
+        render_compact_header(f, chunks[0], app, fade_progress);
+        (chunks[1], chunks[2])
+    } else {
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                Constraint::Length(5), // For header (environment, git, stats + borders)
+                Constraint::Length(3), // For tabs
+                Constraint::Min(0),    // For content
+                Constraint::Length(1), // For footer
+            ])
+            .split(area);
+
+        render_header(
+            f,
+            chunks[0],
+            &app._git_info,
+            &app.environment_info,
+            &app.stats_collector,
+            &app.header_stats(),
+            &app.test_tracker,
+            &app.context_tracker,
+            &app.alert_engine,
+            &app.exception_tracker,
+            &app.frontend,
+            &app.project_health(),
+            &app.privacy,
+            Some(fade_progress),
+        );
+
+        // Plain names, without counts, so unlike the header there's nothing
+        // to cache
+        let tab_titles: Vec<_> = ViewMode::all_variants()
+            .iter()
+            .map(|v| v.as_str())
+            .collect();
+
+        let block = Theme::block("Caboose", None) // Using Theme::block with no fade
+            .style(
+                Style::default()
+                    .fg(Theme::text_primary())
+                    .bg(Theme::surface()),
+            );
+        let mut click_map = app.click_map.borrow_mut();
+        for (index, rect) in click_map::tab_rects(block.inner(chunks[1]), &tab_titles)
+            .into_iter()
+            .enumerate()
+        {
+            click_map.add(rect, ClickTarget::Tab(index));
+        }
+        drop(click_map);
+
+        let tabs = Tabs::new(tab_titles)
+            .block(block)
+            .select(app.active_tab_index)
+            .style(Style::default().fg(Theme::text_secondary()))
+            .highlight_style(
+                Style::default()
+                    .fg(Theme::primary())
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        f.render_widget(tabs, chunks[1]);
+        (chunks[2], chunks[3])
+    };
+
+    // A saved session says so above every view
+    if let Some(metadata) = &app.viewing_session {
+        let (banner_area, rest) = components::replay_banner::split_replay_area(content_area);
+        components::replay_banner::render_replay_banner(
+            f,
+            banner_area,
+            metadata,
+            app.display_zone,
+            Some(fade_progress),
+        );
+        content_area = rest;
+    }
+
+    // Views with an active search get the shared search bar above their content
+    if app.view_mode.is_searchable() && (app.search_mode || !app.current_search_query().is_empty())
+    {
+        let (bar_area, rest) = components::search_bar::split_search_area(content_area);
+        components::search_bar::render_search_bar(
+            f,
+            bar_area,
+            app.view_mode.as_str(),
+            app.current_search_query(),
+            (app.view_mode == ViewMode::Logs)
+                .then(|| app.log_search())
+                .as_ref(),
+            app.search_mode,
+            Some(fade_progress),
+        );
+        content_area = rest;
+    }
+
+    if layout::is_too_small(content_area) {
+        layout::render_too_small(f, content_area);
+    } else {
+        render_active_view(f, content_area, app, compact, fade_progress);
+    }
+
+    render_footer(f, footer_area, app, compact, Some(fade_progress));
+
+    // Beneath the other overlays: it opens by itself, they only on request
+    if app.show_build_error
+        && let Some(failure) = app.build_errors.latest()
+    {
+        let area = components::build_error::calculate_build_error_area(f.area());
+        components::build_error::render_build_error(
+            f,
+            area,
+            failure,
+            app.build_error_selected,
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_memory
+        && let Some(report) = app.memory_reports.latest()
+    {
+        let area = components::memory_report::calculate_memory_report_area(f.area());
+        components::memory_report::render_memory_report(
+            f,
+            area,
+            report,
+            app.memory_scroll,
+            app.display_zone,
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_diagnostics {
+        let usage = app.diagnostics_usage();
+        let area = components::diagnostics::calculate_diagnostics_area(
+            f.area(),
+            usage.len(),
+            app.parse_coverage.samples().len(),
+        );
+        components::diagnostics::render_diagnostics(
+            f,
+            area,
+            &usage,
+            app.diagnostics_selected,
+            app.diagnostics_keep,
+            app.diagnostics_rss,
+            app.frame_timings.stats(),
+            &app.parse_coverage,
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_alerts {
+        let area = components::alerts::calculate_alerts_area(
+            f.area(),
+            app.alert_engine.statuses().count(),
+        );
+        components::alerts::render_alerts(f, area, &app.alert_engine, Some(fade_progress));
+    }
+
+    if app.show_status {
+        let area = components::status::calculate_status_area(f.area(), app.processes.len());
+        components::status::render_status(
+            f,
+            area,
+            &app.processes,
+            &app.process_readiness(),
+            &app.project_health(),
+            &app.suspensions,
+            app.clock.now(),
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_routes {
+        let area = components::routes::calculate_routes_area(f.area());
+        components::routes::render_routes(
+            f,
+            area,
+            app.routes.as_deref(),
+            &app.routes_query,
+            app.routes_scroll,
+            &components::routes::RoutesStatus {
+                loading: app.routes_loading.is_some(),
+                error: app.routes_error.as_deref(),
+                spinner_frame: app.spinner_frame,
+            },
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_trends {
+        let area = components::trends::calculate_trends_area(f.area());
+        let endpoint = app
+            .trends_endpoint
+            .as_ref()
+            .map(Endpoint::to_string)
+            .unwrap_or_default();
+        components::trends::render_trends(
+            f,
+            area,
+            &endpoint,
+            app.trends.as_ref(),
+            &components::trends::TrendsStatus {
+                loading: app.trends_loading.is_some(),
+                error: app.trends_error.as_deref(),
+                spinner_frame: app.spinner_frame,
+            },
+            Some(fade_progress),
+        );
+    }
+
+    if app.show_help {
+        let commands = app.command_registry.get_metadata();
+        let area = components::help::calculate_help_area(f.area(), commands.len());
+        components::help::render_help(f, area, commands, Some(fade_progress));
+    }
+
+    // Render command palette overlay if in command mode
+    if app.command_mode {
+        let palette_area = components::command_palette::calculate_palette_area(f.area());
+
+        // Get error message if in command mode with error
+        let error_msg = if let Some(ref result) = app.last_command_result {
+            if !result.is_success() {
+                result.message()
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        components::command_palette::render_command_palette(
+            f,
+            palette_area,
+            &app.command_input,
+            app.command_prompt.as_ref(),
+            &app.command_suggestions,
+            app.selected_suggestion,
+            error_msg,
+            Some(fade_progress),
+        );
+    } else if let Some(ref warning) = app.process_warning {
+        let warning_area = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(f.area())[1];
 
-    let tab_titles: Vec<_> = ViewMode::all_variants()
-        .iter()
-        .map(|v| v.as_str())
-        .collect();
-
-    let tabs = Tabs::new(tab_titles)
-        .block(
-            Theme::block("Caboose", None) // Using Theme::block with no fade
-                .style(
-                    Style::default()
-                        .fg(Theme::text_primary())
-                        .bg(Theme::surface()),
-                ),
-        )
-        .select(app.active_tab_index)
-        .style(Style::default().fg(Theme::text_secondary()))
-        .highlight_style(
-            Style::default()
-                .fg(Theme::primary())
-                .add_modifier(Modifier::BOLD),
+        components::command_palette::render_command_result(
+            f,
+            warning_area,
+            warning,
+            true,
+            Some(fade_progress),
         );
+    } else if let Some(ref warning) = app.disk_warning {
+        let warning_area = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(f.area())[1];
 
-    f.render_widget(tabs, chunks[1]);
+        components::command_palette::render_command_result(
+            f,
+            warning_area,
+            &disk_warning_message(warning),
+            true,
+            Some(fade_progress),
+        );
+    } else if let Some(ref result) = app.last_command_result {
+        // Only show success messages after command mode exits
+        if result.is_success()
+            && let Some(message) = result.message()
+        {
+            let result_area = Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)])
+                .split(f.area())[1];
+
+            components::command_palette::render_command_result(
+                f,
+                result_area,
+                message,
+                false,
+                Some(fade_progress),
+            );
+        }
+    }
+}
 
+/// Render the active view into `area`
+fn render_active_view(
+    f: &mut ratatui::Frame,
+    content_area: ratatui::layout::Rect,
+    app: &App,
+    compact: bool,
+    fade_progress: f32,
+) {
     match &app.view_mode {
         ViewMode::Logs => {
-            views::logs_view::render(
+            let show_processes = !compact || app.show_compact_processes;
+            app.process_panel_shown.set(show_processes);
+            let max_h_scroll = views::logs_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.processes,
+                &app.boot_times,
+                &app.frontend,
+                &app.suspensions,
+                app.clock.now(),
                 &app.logs,
                 app.search_mode,
-                &app.search_query,
-                app.log_scroll,
+                &app.log_search(),
+                &app.log_viewport,
+                app.log_order,
+                app.log_colors,
                 app.horizontal_scroll,
                 app.auto_scroll,
-                &app.filter_process,
+                app.filter_process.as_ref(),
+                app.min_log_level,
+                &app.log_chips,
+                app.time_range.as_ref(),
+                app.display_zone,
+                &app.privacy,
+                app.log_annotations
+                    .annotator(&app.context_tracker, &app.exception_tracker)
+                    .as_ref(),
+                show_processes,
+                app.focused_process_index(),
                 app.spinner_frame,
                 Some(fade_progress),
             );
+            app.max_horizontal_scroll.set(max_h_scroll);
         }
 
         ViewMode::QueryAnalysis => {
             views::query_analysis_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.context_tracker,
+                &app.stats_collector,
+                app.current_search_query(),
+                app.selected_in_flight,
+                app.n_plus_one_expanded,
+                app.selected_n_plus_one,
+                app.routes.as_deref(),
+                &app.privacy,
+                &mut app.click_map.borrow_mut(),
                 app.spinner_frame,
                 Some(fade_progress),
             );
         }
 
         ViewMode::RequestDetail(idx) => {
-            render_request_detail_view_fallback(f, chunks[2], app, *idx);
+            views::request_detail_view::render(
+                f,
+                content_area,
+                app.context_tracker.get_recent_requests().get(*idx),
+                app.show_request_extra,
+                app.request_detail_scroll,
+                &app.privacy,
+                Some(fade_progress),
+            );
+        }
+
+        ViewMode::LiveRequest(id) => {
+            if let Some(context) = app.context_tracker.get_in_flight(*id) {
+                views::request_detail_view::render_live(
+                    f,
+                    content_area,
+                    &context,
+                    &app.privacy,
+                    Some(fade_progress),
+                );
+            } else if let Some(idx) = app.context_tracker.completed_position(*id) {
+                // Completed since the last tick; the run loop switches views next
+                views::request_detail_view::render(
+                    f,
+                    content_area,
+                    app.context_tracker.get_recent_requests().get(idx),
+                    false,
+                    0,
+                    &app.privacy,
+                    Some(fade_progress),
+                );
+            } else {
+                let gone = Paragraph::new("This request is no longer tracked")
+                    .style(Style::default().fg(Theme::text_muted()))
+                    .block(Theme::block("Live Request", Some(fade_progress)));
+                f.render_widget(gone, content_area);
+            }
         }
 
         ViewMode::DatabaseHealth => {
             views::database_health_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.db_health,
+                app.current_search_query(),
+                app.selected_table,
+                app.slow_query_sort,
+                &app.privacy,
+                app.display_zone,
                 app.spinner_frame,
                 Some(fade_progress),
             );
         }
 
+        ViewMode::TableDetail(table) => {
+            views::table_detail_view::render(
+                f,
+                content_area,
+                &app.db_health,
+                table,
+                &app.privacy,
+                Some(fade_progress),
+            );
+        }
+
         ViewMode::TestResults => {
             views::test_results_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.test_tracker,
+                app.current_search_query(),
+                app.selected_test_failure,
+                app.expanded_test_failure,
                 app.spinner_frame,
                 Some(fade_progress),
             );
@@ -783,9 +3730,15 @@ fn render_ui(f: &mut ratatui::Frame, app: &App) {
         ViewMode::Exceptions => {
             views::exceptions_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.exception_tracker,
+                &app.context_tracker.active_bursts(),
+                app.current_search_query(),
+                &app.exception_filter,
                 app.selected_exception,
+                app.exception_sort,
+                &app.privacy,
+                &mut app.click_map.borrow_mut(),
                 app.spinner_frame,
                 Some(fade_progress),
             );
@@ -794,61 +3747,58 @@ fn render_ui(f: &mut ratatui::Frame, app: &App) {
         ViewMode::ExceptionDetail(exception_index) => {
             views::exception_detail_view::render(
                 f,
-                chunks[2],
+                content_area,
                 &app.exception_tracker,
                 *exception_index,
+                &app.privacy,
                 Some(fade_progress),
             );
         }
-    }
 
-    render_footer(f, chunks[3], app, Some(fade_progress));
-
-    // Render command palette overlay if in command mode
-    if app.command_mode {
-        let palette_area = components::command_palette::calculate_palette_area(f.area());
+        ViewMode::Timeline => {
+            views::timeline_view::render(
+                f,
+                content_area,
+                &app.stats_collector,
+                app.timeline_width(),
+                app.timeline_anchor(),
+                app.timeline_selected(),
+                app.display_zone,
+                Some(fade_progress),
+            );
+        }
 
-        // Get error message if in command mode with error
-        let error_msg = if let Some(ref result) = app.last_command_result {
-            if !result.is_success() {
-                result.message()
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        ViewMode::Jobs => {
+            views::jobs_view::render(
+                f,
+                content_area,
+                &app.job_tracker,
+                app.current_search_query(),
+                Some(fade_progress),
+            );
+        }
+    }
+}
 
-        components::command_palette::render_command_palette(
-            f,
-            palette_area,
-            &app.command_input,
-            &app.command_suggestions,
-            app.selected_suggestion,
-            error_msg,
-            Some(fade_progress),
-        );
-    } else if let Some(ref result) = app.last_command_result {
-        // Only show success messages after command mode exits
-        if result.is_success() {
-            if let Some(message) = result.message() {
-                let result_area = Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([Constraint::Min(0), Constraint::Length(3)])
-                    .split(f.area())[1];
-
-                components::command_palette::render_command_result(
-                    f,
-                    result_area,
-                    message,
-                    false,
-                    Some(fade_progress),
-                );
-            }
+/// Banner text for a disk warning
+fn disk_warning_message(warning: &DiskWarning) -> String {
+    match warning {
+        DiskWarning::LargeLog(file) => format!(
+            "{} has grown to {} - x truncates it (the server keeps logging), any other key dismisses",
+            file.name,
+            format_bytes(file.size)
+        ),
+        DiskWarning::LargeCache { size } => format!(
+            "tmp/cache has grown to {} - `bin/rails tmp:cache:clear` frees it",
+            format_bytes(*size)
+        ),
+        DiskWarning::LowSpace { free } => {
+            format!("Only {} free on the project's disk", format_bytes(*free))
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_header(
     f: &mut ratatui::Frame,
 
@@ -860,20 +3810,27 @@ fn render_header(
 
     stats_collector: &StatsCollector,
 
+    stats: &HeaderStats,
+
     test_tracker: &std::sync::Arc<crate::test::TestTracker>,
 
-    fade_progress: Option<f32>,
-) {
-    let stats = stats_collector.get_stats();
+    context_tracker: &RequestContextTracker,
+
+    alert_engine: &AlertEngine,
+
+    exception_tracker: &ExceptionTracker,
+
+    frontend: &FrontendTracker,
 
-    let error_rate = stats.error_rate();
+    project_health: &ProjectHealth,
 
-    let avg_time = stats.avg_response_time();
+    privacy: &Privacy,
 
-    let response_time_history = stats_collector.get_response_time_history();
-    // Convert u64 to f64 for Sparkline
-    let response_time_history_f64: Vec<f64> =
-        response_time_history.iter().map(|&x| x as f64).collect();
+    fade_progress: Option<f32>,
+) {
+    let error_rate = stats.error_rate;
+
+    let avg_time = stats.avg_response_time;
 
     // Define overall header layout
     let _header_layout = Layout::default()
@@ -918,38 +3875,47 @@ fn render_header(
         ])
         .split(inner_area);
 
-    // Environment segments (Powerlevel10k style)
+    // Project health leads the environment line, then the environment
+    // segments (Powerlevel10k style)
+    let mut env_spans = Vec::new();
+    if *project_health != ProjectHealth::NoProcesses {
+        let (icon, color) = components::status::health_style(project_health);
+        env_spans.push(Span::styled(
+            format!("{} {}", icon, project_health.summary()),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(
+                    color,
+                    fade_progress.unwrap_or(1.0),
+                ))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
     let env_segments = environment_info.format_segment();
-    let env_line = Line::from(
-        env_segments
-            .iter()
-            .enumerate()
-            .flat_map(|(i, segment)| {
-                let mut spans = Vec::new();
-
-                if i > 0 {
-                    spans.push(Span::styled(
-                        " │ ",
-                        Style::default().fg(Theme::apply_fade_to_color(
-                            Theme::text_muted(),
-                            fade_progress.unwrap_or(1.0),
-                        )),
-                    ));
-                }
+    let has_health = !env_spans.is_empty();
+    env_spans.extend(env_segments.iter().enumerate().flat_map(|(i, segment)| {
+        let mut spans = Vec::new();
+
+        if i > 0 || has_health {
+            spans.push(Span::styled(
+                " │ ",
+                Style::default().fg(Theme::apply_fade_to_color(
+                    Theme::text_muted(),
+                    fade_progress.unwrap_or(1.0),
+                )),
+            ));
+        }
 
-                spans.push(Span::styled(
-                    segment,
-                    Style::default().fg(Theme::apply_fade_to_color(
-                        Theme::text_secondary(),
-                        fade_progress.unwrap_or(1.0),
-                    )),
-                ));
+        spans.push(Span::styled(
+            segment,
+            Style::default().fg(Theme::apply_fade_to_color(
+                Theme::text_secondary(),
+                fade_progress.unwrap_or(1.0),
+            )),
+        ));
 
-                spans
-            })
-            .collect::<Vec<_>>(),
-    );
-    f.render_widget(Paragraph::new(env_line), inner_chunks[0]);
+        spans
+    }));
+    f.render_widget(Paragraph::new(Line::from(env_spans)), inner_chunks[0]);
 
     // Build git line with optional debugger indicator
     let mut git_spans = vec![
@@ -963,7 +3929,7 @@ fn render_header(
         ),
         Span::raw(" "),
         Span::styled(
-            git_info.format_short(),
+            git_summary(git_info, privacy),
             Style::default()
                 .fg(Theme::apply_fade_to_color(
                     Theme::primary(),
@@ -973,6 +3939,12 @@ fn render_header(
         ),
     ];
 
+    // Stays up while privacy mode masks what's on screen
+    if privacy.is_enabled() {
+        git_spans.push(Span::raw("   │   "));
+        git_spans.push(privacy_badge(fade_progress.unwrap_or(1.0)));
+    }
+
     // Add debugger indicator if active
     if test_tracker.is_debugger_active() {
         git_spans.push(Span::raw("   │   "));
@@ -1008,6 +3980,84 @@ fn render_header(
         }
     }
 
+    // Firing alerts badge, coloured by the most severe
+    let firing = alert_engine.firing();
+    if let Some(severity) = firing.iter().map(|(rule, _)| rule.severity).max() {
+        let color = components::alerts::severity_color(severity);
+        let label = if firing.len() == 1 { "alert" } else { "alerts" };
+        git_spans.push(Span::raw("   │   "));
+        git_spans.push(Span::styled(
+            format!("🔔 {} {} firing (/alerts)", firing.len(), label),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(
+                    color,
+                    fade_progress.unwrap_or(1.0),
+                ))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // One path answering 404s / 5xx over and over
+    let bursts = context_tracker.active_bursts();
+    if let Some(burst) = bursts.iter().max_by_key(|burst| burst.count) {
+        let more = match bursts.len() {
+            1 => String::new(),
+            n => format!(" +{} more", n - 1),
+        };
+        let color = match burst.kind {
+            BurstKind::NotFound => Theme::warning(),
+            BurstKind::ServerError => Theme::danger(),
+        };
+        git_spans.push(Span::raw("   │   "));
+        git_spans.push(Span::styled(
+            format!(
+                "⚠ {} {} ×{}{}",
+                privacy.text(&burst.path),
+                burst.kind.label(),
+                burst.count,
+                more
+            ),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(
+                    color,
+                    fade_progress.unwrap_or(1.0),
+                ))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Sustained request queueing means the server is short of threads
+    if let Some(average) = context_tracker.queueing_warning(Instant::now()) {
+        git_spans.push(Span::raw("   │   "));
+        git_spans.push(Span::styled(
+            format!("⏳ Queueing {} avg - at capacity", format_ms(average)),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(
+                    Theme::warning(),
+                    fade_progress.unwrap_or(1.0),
+                ))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // The frontend's last build, once its dev server has said anything
+    if frontend.is_active() {
+        let color = match frontend.state() {
+            BuildState::Failed => Theme::danger(),
+            BuildState::Building => Theme::info(),
+            _ => Theme::success(),
+        };
+        let mut style = Style::default().fg(Theme::apply_fade_to_color(
+            color,
+            fade_progress.unwrap_or(1.0),
+        ));
+        if frontend.state() == BuildState::Failed {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        git_spans.push(Span::raw("   │   "));
+        git_spans.push(Span::styled(frontend.summary(), style));
+    }
+
     let git_line = Line::from(git_spans);
     f.render_widget(Paragraph::new(git_line), inner_chunks[1]);
 
@@ -1017,8 +4067,10 @@ fn render_header(
         .constraints([
             Constraint::Length(18), // total requests
             Constraint::Length(15), // avg time
-            Constraint::Length(10), // sparkline
+            Constraint::Length(26), // sparkline
             Constraint::Length(15), // error rate
+            Constraint::Length(28), // exception rate + sparkline
+            Constraint::Length(20), // db share
             Constraint::Min(0),     // sql queries (flexible)
         ])
         .split(inner_chunks[2]);
@@ -1047,16 +4099,20 @@ fn render_header(
     );
     f.render_widget(Paragraph::new(avg_time_span), stats_layout[1]);
 
-    // Render Sparkline as text
-    let sparkline = Sparkline::new(&response_time_history_f64);
-    let sparkline_span = Span::styled(
-        sparkline.render(),
-        Style::default().fg(Theme::apply_fade_to_color(
+    // Render Sparkline, leaving a gap before the error rate
+    let sparkline = Sparkline::new(&stats.response_times)
+        .width(stats_layout[2].width.saturating_sub(2) as usize)
+        .aggregation(Aggregation::Max)
+        .labels(|ms| format!("{:.0}ms", ms))
+        .style(Style::default().fg(Theme::apply_fade_to_color(
             Theme::warning(),
             fade_progress.unwrap_or(1.0),
-        )),
-    );
-    f.render_widget(Paragraph::new(sparkline_span), stats_layout[2]);
+        )))
+        .highlight_last(Theme::apply_fade_to_color(
+            Theme::primary(),
+            fade_progress.unwrap_or(1.0),
+        ));
+    f.render_widget(Paragraph::new(sparkline.line()), stats_layout[2]);
 
     // Render error rate
     let error_rate_text = format_percentage(error_rate);
@@ -1082,64 +4138,429 @@ fn render_header(
     );
     f.render_widget(Paragraph::new(error_rate_span), stats_layout[3]);
 
-    // Render sql queries with emoji icon
-    let sql_queries_span = Span::styled(
-        format!(
-            " 🗄️ {} queries",
-            format_number(stats.sql_queries)
-        ),
+    // Render severity-weighted exceptions per minute, `E` jumps to them
+    let now = Instant::now();
+    let exception_rate_line = Line::from(exception_rate_spans(
+        exception_tracker.weighted_rate_at(now),
+        &exception_tracker.rate_history_at(now),
+        fade_progress.unwrap_or(1.0),
+    ));
+    f.render_widget(Paragraph::new(exception_rate_line), stats_layout[4]);
+
+    // Render DB vs app share of recent request time
+    let db_share_line = Line::from(db_share_spans(
+        stats_collector.db_share(),
+        stats_collector.db_share_thresholds(),
+        fade_progress.unwrap_or(1.0),
+    ));
+    f.render_widget(Paragraph::new(db_share_line), stats_layout[5]);
+
+    // Render sql queries with emoji icon, then the cache hit rate once there are reads
+    let mut sql_queries_spans = vec![Span::styled(
+        format!(" 🗄️ {} queries", format_number(stats.sql_queries)),
         Style::default().fg(Theme::apply_fade_to_color(
             Theme::info(),
             fade_progress.unwrap_or(1.0),
         )),
+    )];
+    sql_queries_spans.extend(cache_hit_rate_spans(
+        &stats.cache,
+        stats_collector.cache_hit_rate_warning(),
+        fade_progress.unwrap_or(1.0),
+    ));
+    f.render_widget(
+        Paragraph::new(Line::from(sql_queries_spans)),
+        stats_layout[6],
     );
-    f.render_widget(Paragraph::new(sql_queries_span), stats_layout[4]);
 
     f.render_widget(header_block, area); // This line was missing
 }
 
+/// Single-line header for the compact layout: views, project path, git and request count
+/// The header's git summary, with the branch hidden when privacy mode covers it
+fn git_summary(git_info: &GitInfo, privacy: &Privacy) -> String {
+    let mut git_info = git_info.clone();
+    if let Some(branch) = git_info.branch.as_mut() {
+        *branch = privacy.branch(branch).into_owned();
+    }
+    git_info.format_short()
+}
+
+/// Marks the screen as masked for as long as privacy mode is on
+fn privacy_badge(fade_progress: f32) -> Span<'static> {
+    Span::styled(
+        "🔒 Privacy on (P to show)",
+        Style::default()
+            .fg(Theme::apply_fade_to_color(Theme::warning(), fade_progress))
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn render_compact_header(
+    f: &mut ratatui::Frame,
+    area: ratatui::layout::Rect,
+    app: &App,
+    fade_progress: f32,
+) {
+    let separator = || {
+        Span::styled(
+            " │ ",
+            Style::default().fg(Theme::apply_fade_to_color(
+                Theme::text_muted(),
+                fade_progress,
+            )),
+        )
+    };
+
+    let mut spans = components::tab_indicator::tab_indicator_spans(
+        &ViewMode::all_variants(),
+        app.active_tab_index,
+        fade_progress,
+    );
+    let widths = spans.iter().map(|span| span.width() as u16);
+    let mut click_map = app.click_map.borrow_mut();
+    for (index, rect) in click_map::row_segments(area, widths, 0)
+        .into_iter()
+        .enumerate()
+    {
+        click_map.add(rect, ClickTarget::Tab(index));
+    }
+    drop(click_map);
+
+    if let Some(path) = app.environment_info.format_segment().into_iter().next() {
+        spans.push(separator());
+        spans.push(Span::styled(
+            path,
+            Style::default().fg(Theme::apply_fade_to_color(
+                Theme::text_secondary(),
+                fade_progress,
+            )),
+        ));
+    }
+
+    spans.push(separator());
+    spans.push(Span::styled(
+        format!(
+            "{} {}",
+            Icons::git(),
+            git_summary(&app._git_info, &app.privacy)
+        ),
+        Style::default()
+            .fg(Theme::apply_fade_to_color(Theme::primary(), fade_progress))
+            .add_modifier(Modifier::BOLD),
+    ));
+    if app.privacy.is_enabled() {
+        spans.push(separator());
+        spans.push(privacy_badge(fade_progress));
+    }
+
+    spans.push(separator());
+    spans.push(Span::styled(
+        format!(
+            "{} {} req",
+            Icons::success(),
+            format_number(app.header_stats().total_requests)
+        ),
+        Style::default().fg(Theme::apply_fade_to_color(Theme::success(), fade_progress)),
+    ));
+
+    f.render_widget(
+        Paragraph::new(Line::from(spans)).style(Style::default().bg(Theme::surface())),
+        area,
+    );
+}
+
+/// Width of the DB / app bar in the header
+const DB_SHARE_BAR_WIDTH: usize = 10;
+
+/// "DB ███░░░░░░░ 31%" - database segment colored by threshold, app segment muted
+fn db_share_spans(
+    share: Option<DbShare>,
+    thresholds: DbShareThresholds,
+    fade: f32,
+) -> Vec<Span<'static>> {
+    let Some(share) = share else {
+        return vec![Span::styled(
+            format!(" DB {} --", "░".repeat(DB_SHARE_BAR_WIDTH)),
+            Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade)),
+        )];
+    };
+
+    let percent = share.db_percent();
+    let color = match thresholds.level(&share) {
+        DbShareLevel::Normal => Theme::info(),
+        DbShareLevel::Warning => Theme::warning(),
+        DbShareLevel::Danger => Theme::danger(),
+    };
+    let db_bar = VisualBar::new(percent, 100.0, DB_SHARE_BAR_WIDTH)
+        .char('█')
+        .render_compact();
+    let app_bar = "░".repeat(DB_SHARE_BAR_WIDTH - db_bar.chars().count());
+
+    vec![
+        Span::styled(
+            " DB ",
+            Style::default().fg(Theme::apply_fade_to_color(Theme::text_secondary(), fade)),
+        ),
+        Span::styled(
+            db_bar,
+            Style::default().fg(Theme::apply_fade_to_color(color, fade)),
+        ),
+        Span::styled(
+            app_bar,
+            Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade)),
+        ),
+        Span::styled(
+            format!(" {:.0}%", percent),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(color, fade))
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]
+}
+
+/// " ⚠ 4.0 exc/min ▁▁▂▅" - the weighted exception rate and its last
+/// [`RATE_MINUTES`](crate::exception::RATE_MINUTES) minutes, warning colored
+/// from [`EXCEPTION_RATE_WARNING`] and danger from [`EXCEPTION_RATE_DANGER`]
+fn exception_rate_spans(rate: f64, history: &[u32], fade: f32) -> Vec<Span<'static>> {
+    let (color, icon) = if rate >= EXCEPTION_RATE_DANGER {
+        (Theme::danger(), Icons::error())
+    } else if rate >= EXCEPTION_RATE_WARNING {
+        (Theme::warning(), Icons::warning())
+    } else {
+        (Theme::success(), Icons::success())
+    };
+    let style = Style::default().fg(Theme::apply_fade_to_color(color, fade));
+    let history: Vec<f64> = history.iter().map(|&weighted| weighted as f64).collect();
+    let mut spans = vec![Span::styled(
+        format!(" {} {:.1} exc/min ", icon, rate),
+        if rate >= EXCEPTION_RATE_DANGER {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        },
+    )];
+    spans.extend(Sparkline::new(&history).style(style).line().spans);
+    spans
+}
+
+/// "  cache 92% hit" - warning colored below `warning`; nothing before any read
+fn cache_hit_rate_spans(cache: &CacheCounts, warning: f64, fade: f32) -> Vec<Span<'static>> {
+    let Some(rate) = cache.hit_rate() else {
+        return Vec::new();
+    };
+
+    let color = if rate < warning {
+        Theme::warning()
+    } else {
+        Theme::success()
+    };
+    let mut style = Style::default().fg(Theme::apply_fade_to_color(color, fade));
+    if rate < warning {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    vec![Span::styled(format!("   cache {:.0}% hit", rate), style)]
+}
+
+/// Key hints and status for what's on screen
+///
+/// Built every frame from the app's own state; it reads no tracker, so
+/// unlike the header there's no generation to cache it by.
 fn render_footer(
     f: &mut ratatui::Frame,
     area: ratatui::layout::Rect,
     app: &App,
+    compact: bool,
     fade_progress: Option<f32>,
 ) {
-    let footer = if app.search_mode {
+    use components::Priority::{Essential, High, Low};
+
+    let footer = if app.show_diagnostics && !app.command_mode {
+        FooterBuilder::new()
+            .add_binding_with_priority("↑↓", "Select", High)
+            .add_binding_with_priority("Enter", "Trim", High)
+            .add_binding("+/-", "Keep N")
+            .add_binding_with_priority("Esc", "Close", Essential)
+    } else if app.show_routes && !app.command_mode {
+        FooterBuilder::new()
+            .add_binding_with_priority("Type", "Filter", High)
+            .add_binding_with_priority("↑↓", "Scroll", High)
+            .add_binding("Ctrl+R", "Refresh")
+            .add_binding_with_priority("Esc", "Close", Essential)
+    } else if (app.show_alerts || app.show_help || app.show_status || app.show_trends)
+        && !app.command_mode
+    {
+        FooterBuilder::new().add_binding_with_priority("Esc", "Close", Essential)
+    } else if app.show_memory && app.memory_reports.latest().is_some() && !app.command_mode {
         FooterBuilder::new()
-            .add_binding("Type to search", "")
-            .add_binding("Esc", "Cancel")
-            .add_binding("Enter", "Apply")
-            .build()
+            .add_binding_with_priority("↑↓", "Scroll", High)
+            .add_binding_with_priority("e", "Export", High)
+            .add_binding_with_priority("Esc", "Close", Essential)
+    } else if app.show_build_error && app.build_errors.latest().is_some() && !app.command_mode {
+        FooterBuilder::new()
+            .add_binding_with_priority("o", "Open in $EDITOR", High)
+            .add_binding("↑↓", "Error")
+            .add_binding_with_priority("Esc", "Close", Essential)
+    } else if app.search_mode {
+        let mut footer = FooterBuilder::new().add_binding("Type to search", "");
+        if app.view_mode == ViewMode::Logs {
+            footer = footer
+                .add_binding("re:", "Regex")
+                .add_binding_with_priority("Ctrl+I", "Case", High);
+        }
+        footer
+            .add_binding_with_priority("Esc", "Cancel", Essential)
+            .add_binding_with_priority("Enter", "Apply", Essential)
+    } else if app.processes_have_focus() && !app.command_mode {
+        // A stop or restart waiting to be confirmed says so first
+        let mut footer = FooterBuilder::new();
+        if let Some(pending) = &app.pending_process_action {
+            footer = footer.add_status(
+                pending.action.confirmation_hint(&pending.process),
+                Theme::warning(),
+            );
+        }
+        footer
+            .with_quit()
+            .add_binding_with_priority("j/k", "Select", High)
+            .add_binding_with_priority("s", "Stop", High)
+            .add_binding_with_priority("r", "Restart", High)
+            .add_binding_with_priority("S", "Start", High)
+            .add_binding_with_priority("Tab", "Back to logs", Essential)
     } else {
-        let mut footer = FooterBuilder::new()
-            .add_binding("q", "Quit")
-            .add_binding(":", "Command")
+        let mut footer = FooterBuilder::new().with_quit();
+
+        // The process panel toggle only exists in the compact layout
+        if compact && matches!(app.view_mode, ViewMode::Logs) {
+            let label = if app.show_compact_processes {
+                "Hide processes"
+            } else {
+                "Processes"
+            };
+            footer = footer.add_binding_with_priority("p", label, High);
+        }
+
+        footer = footer
+            .add_binding_with_priority(":", "Command", High)
             .add_binding("t/T", "Tab ←→");
 
         // Add view-specific bindings
         if matches!(app.view_mode, ViewMode::Logs) {
+            let order = match app.log_order {
+                LogOrder::OldestFirst => "Newest first",
+                LogOrder::NewestFirst => "Oldest first",
+            };
             footer = footer
-                .add_binding("/", "Search")
-                .add_binding("↑↓", "V-Scroll")
-                .add_binding("←→", "H-Scroll");
+                .add_binding_with_priority("/", "Search", High)
+                .add_binding_with_priority("↑↓", "V-Scroll", Low)
+                .add_binding_with_priority("←→", "H-Scroll", Low)
+                .add_binding_with_priority("o", order, Low)
+                .add_binding_with_priority("e", "Export", Low);
+            if app.process_panel_shown.get() {
+                footer = footer.add_binding_with_priority("Tab", "Processes", Low);
+            }
 
-            // Show auto-scroll or Home hint
+            // Auto-scroll state goes on the right; the key to resume it stays a hint
             if !app.auto_scroll {
-                footer = footer.add_binding("End", "⚠️ Auto-scroll OFF");
+                footer = footer
+                    .add_binding_with_priority("End", "Follow", High)
+                    .add_status("⏸ Auto-scroll OFF", Theme::warning());
             } else if app.horizontal_scroll > 0 {
                 footer = footer.add_binding("Home", "Reset H-Scroll");
-            } else {
-                footer = footer.add_binding("c", "Clear");
             }
+            if let Some(process) = &app.filter_process {
+                footer = footer.add_status(format!("Filter: {}", process), Theme::info());
+            }
+            if let Some(level) = app.min_log_level {
+                footer = footer.add_status(
+                    format!("Level: {}+", level.label()),
+                    views::logs_view::level_color(Some(level)),
+                );
+            }
+            if let Some(hiding) = app.log_chips.status() {
+                footer = footer.add_status(hiding, Theme::info());
+            }
+            if let Some(range) = &app.time_range {
+                footer = footer.add_status(
+                    format!("⏱ {}", range.label(app.display_zone)),
+                    Theme::info(),
+                );
+            }
+            footer = footer.add_binding("c", "Clear");
+        } else if matches!(
+            app.view_mode,
+            ViewMode::TableDetail(_) | ViewMode::LiveRequest(_)
+        ) {
+            footer = footer.add_binding_with_priority("Esc", "Back", Essential);
+        } else if matches!(app.view_mode, ViewMode::RequestDetail(_)) {
+            let label = if app.show_request_extra {
+                "Hide extra fields"
+            } else {
+                "Extra fields"
+            };
+            footer = footer
+                .add_binding("↑↓", "Scroll")
+                .add_binding_with_priority("e", label, High)
+                .add_binding("S", "Export SQL")
+                .add_binding_with_priority("Esc", "Back", Essential);
+        } else if matches!(app.view_mode, ViewMode::Timeline) {
+            footer = footer
+                .add_binding_with_priority("←→", "Select", High)
+                .add_binding_with_priority("Enter", "Logs", High)
+                .add_binding("+/-", "Zoom")
+                .add_binding("End", "Latest");
+        } else if matches!(
+            app.view_mode,
+            ViewMode::Exceptions | ViewMode::DatabaseHealth
+        ) {
+            footer = footer
+                .add_binding_with_priority("/", "Search", High)
+                .add_binding("↑↓", "Scroll")
+                .add_binding("</>", "Sort")
+                .add_binding("c", "Clear");
+        } else if matches!(app.view_mode, ViewMode::QueryAnalysis) {
+            footer = footer
+                .add_binding_with_priority("/", "Search", High)
+                .add_binding("↑↓", "Scroll")
+                .add_binding("S", "Export SQL")
+                .add_binding_with_priority("n", "N+1", Low)
+                .add_binding("c", "Clear");
+        } else if matches!(app.view_mode, ViewMode::TestResults) {
+            footer = footer
+                .add_binding_with_priority("/", "Search", High)
+                .add_binding("↑↓", "Select")
+                .add_binding_with_priority("Enter", "Message", High)
+                .add_binding("c", "Clear");
         } else {
             footer = footer
-                .add_binding("/", "Search")
+                .add_binding_with_priority("/", "Search", High)
                 .add_binding("↑↓", "Scroll")
                 .add_binding("c", "Clear");
         }
 
-        footer.build()
-    };
+        if app.build_errors.latest().is_some() {
+            footer = footer.add_status("✘ Build failed (/builderr)", Theme::danger());
+        }
+        if !app.memory_seen
+            && let Some(report) = app.memory_reports.latest()
+        {
+            footer = footer.add_status(
+                format!("▦ Memory report from {} (/memory)", report.process),
+                Theme::info(),
+            );
+        }
+
+        if app.autofocus.is_enabled() {
+            footer = if app.autofocus.is_suspended(Instant::now()) {
+                footer.add_status("◎ Auto focus paused", Theme::warning())
+            } else {
+                footer.add_status("◎ Auto focus", Theme::info())
+            };
+        }
+
+        footer
+    }
+    .build_for_width(area.width as usize);
 
     let footer_widget = Paragraph::new(footer).style(
         Style::default()
@@ -1163,11 +4584,33 @@ fn render_footer(
 // ============================================================================
 
 fn handle_key_event(app: &mut App, key: KeyEvent) {
-    // Clear success messages on any key press
-    if let Some(ref result) = app.last_command_result {
-        if result.is_success() && !app.command_mode {
-            app.last_command_result = None;
+    // Whatever the user does, auto focus stops steering until they're idle
+    app.note_user_input();
+
+    // Any key dismisses the daemonize warning
+    if app.process_warning.take().is_some() {
+        return;
+    }
+
+    // `x` truncates the log a disk warning is about; any other key dismisses
+    // it. While the user is typing, keys go to what they type into and the
+    // warning waits
+    let typing = app.command_mode || app.search_mode || app.show_routes;
+    if app.disk_warning.is_some() && !typing {
+        if key.code == KeyCode::Char('x') {
+            app.truncate_warned_log();
+        } else {
+            app.disk_warning = None;
         }
+        return;
+    }
+
+    // Clear success messages on any key press
+    if let Some(ref result) = app.last_command_result
+        && result.is_success()
+        && !app.command_mode
+    {
+        app.last_command_result = None;
     }
 
     // Handle command mode first
@@ -1206,9 +4649,128 @@ fn handle_key_event(app: &mut App, key: KeyEvent) {
         return;
     }
 
+    // Diagnostics overlay captures keys while open
+    if app.show_diagnostics {
+        match key.code {
+            KeyCode::Esc => app.close_diagnostics(),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            KeyCode::Up => app.select_previous_store(),
+            KeyCode::Down => app.select_next_store(),
+            KeyCode::Enter | KeyCode::Char('x') => app.trim_selected_store(),
+            KeyCode::Char('+') | KeyCode::Char('=') => app.adjust_diagnostics_keep(true),
+            KeyCode::Char('-') => app.adjust_diagnostics_keep(false),
+            _ => {}
+        }
+        return;
+    }
+
+    // Alerts overlay captures keys while open
+    if app.show_alerts {
+        match key.code {
+            KeyCode::Esc => app.close_alerts(),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Routes overlay takes typing as its filter while open
+    if app.show_routes {
+        match key.code {
+            KeyCode::Esc => app.close_routes(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.refresh_routes()
+            }
+            KeyCode::Char(c) => {
+                app.routes_query.push(c);
+                app.routes_scroll = 0;
+            }
+            KeyCode::Backspace => {
+                app.routes_query.pop();
+                app.routes_scroll = 0;
+            }
+            KeyCode::Up => app.scroll_routes(-1),
+            KeyCode::Down => app.scroll_routes(1),
+            KeyCode::PageUp => app.scroll_routes(-10),
+            KeyCode::PageDown => app.scroll_routes(10),
+            _ => {}
+        }
+        return;
+    }
+
+    // Trends overlay captures keys while open
+    if app.show_trends {
+        match key.code {
+            KeyCode::Esc => app.close_trends(),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Status overlay captures keys while open
+    if app.show_status {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('s') => app.close_status(),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Help overlay captures keys while open
+    if app.show_help {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('?') => app.close_help(),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Memory report overlay captures keys while open
+    if app.show_memory && app.memory_reports.latest().is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_memory_report(),
+            KeyCode::Char('e') => app.export_memory_report(),
+            KeyCode::Up => app.scroll_memory_report(-1),
+            KeyCode::Down => app.scroll_memory_report(1),
+            KeyCode::PageUp => app.scroll_memory_report(-10),
+            KeyCode::PageDown => app.scroll_memory_report(10),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Build error overlay captures keys while open
+    if app.show_build_error && app.build_errors.latest().is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_build_error(),
+            KeyCode::Char('o') => app.open_build_error_in_editor(),
+            KeyCode::Up => app.select_build_error(false),
+            KeyCode::Down => app.select_build_error(true),
+            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char(':') => app.enter_command_mode(),
+            _ => {}
+        }
+        return;
+    }
+
     // Handle search mode separately
     if app.search_mode {
         match key.code {
+            // Terminals send Ctrl+I as Tab
+            KeyCode::Tab => app.toggle_search_case(),
+            KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.toggle_search_case()
+            }
             KeyCode::Char(c) => app.add_search_char(c),
             KeyCode::Backspace => app.remove_search_char(),
             KeyCode::Esc => {
@@ -1216,7 +4778,7 @@ fn handle_key_event(app: &mut App, key: KeyEvent) {
                 app.enable_auto_scroll();
             }
             KeyCode::Enter => {
-                app.exit_search_mode();
+                app.apply_search();
                 app.enable_auto_scroll();
             }
             _ => {}
@@ -1224,13 +4786,44 @@ fn handle_key_event(app: &mut App, key: KeyEvent) {
         return;
     }
 
+    // The Processes panel takes its keys while it has them; the rest work as usual
+    if app.processes_have_focus() {
+        match key.code {
+            KeyCode::Tab | KeyCode::Esc => {
+                app.unfocus_processes();
+                return;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.pending_process_action = None;
+                app.move_process_selection(true);
+                return;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.pending_process_action = None;
+                app.move_process_selection(false);
+                return;
+            }
+            KeyCode::Char(c @ ('s' | 'r' | 'S')) => {
+                if let Some(action) = process_control::ProcessAction::for_key(c) {
+                    app.process_action(action);
+                }
+                return;
+            }
+            _ => app.pending_process_action = None,
+        }
+    }
+
     // Normal mode key handling
     match key.code {
+        KeyCode::Tab if matches!(app.view_mode, ViewMode::Logs) => app.toggle_process_focus(),
         KeyCode::Char('q') => app.quit(),
         KeyCode::Esc => {
             // Esc only navigates back, doesn't quit
             match app.view_mode {
-                ViewMode::RequestDetail(_) => app.view_mode = ViewMode::QueryAnalysis,
+                ViewMode::RequestDetail(_) | ViewMode::LiveRequest(_) => {
+                    app.view_mode = ViewMode::QueryAnalysis
+                }
+                ViewMode::TableDetail(_) => app.view_mode = ViewMode::DatabaseHealth,
                 ViewMode::ExceptionDetail(_) => app.view_mode = ViewMode::Exceptions,
                 _ => {} // Do nothing in other views
             }
@@ -1238,62 +4831,125 @@ fn handle_key_event(app: &mut App, key: KeyEvent) {
         KeyCode::Char('t') => app.toggle_view(),
         KeyCode::Char('T') => app.toggle_view_backward(), // Shift+T for backward cycling
         KeyCode::Char(':') => app.enter_command_mode(),
-        KeyCode::Char('/') => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.enter_search_mode();
-            }
-        }
+        KeyCode::Char('/') => app.enter_search_mode(),
         KeyCode::Char('c') => app.clear_filter(),
+        KeyCode::Char('p') => app.toggle_process_panel(),
+        KeyCode::Char('P') => app.toggle_privacy(),
+        KeyCode::Char('E') => app.set_view(ViewMode::Exceptions),
+        KeyCode::Char('?') => app.open_help(),
+        KeyCode::Char('s') => app.open_status(),
+        KeyCode::Char('S')
+            if matches!(
+                app.view_mode,
+                ViewMode::RequestDetail(_) | ViewMode::QueryAnalysis
+            ) =>
+        {
+            app.export_sql()
+        }
+        KeyCode::Char('o') if matches!(app.view_mode, ViewMode::Logs) => app.toggle_log_order(),
+        KeyCode::Char('n') if matches!(app.view_mode, ViewMode::QueryAnalysis) => {
+            app.toggle_n_plus_one()
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') if matches!(app.view_mode, ViewMode::Timeline) => {
+            app.zoom_timeline(false)
+        }
+        KeyCode::Char('-') if matches!(app.view_mode, ViewMode::Timeline) => {
+            app.zoom_timeline(true)
+        }
+        KeyCode::End if matches!(app.view_mode, ViewMode::Timeline) => app.timeline_cursor = None,
+        KeyCode::Char('>') => app.change_sort(SortChange::Next),
+        KeyCode::Char('<') => app.change_sort(SortChange::Previous),
+        KeyCode::Char(c @ '1'..='5') if matches!(app.view_mode, ViewMode::Logs) => {
+            app.toggle_log_chip(c as usize - '1' as usize)
+        }
+        KeyCode::Char('d') if matches!(app.view_mode, ViewMode::Exceptions) => {
+            app.toggle_selected_dismissed()
+        }
+        KeyCode::Char('v') if matches!(app.view_mode, ViewMode::Exceptions) => {
+            app.cycle_exception_severity()
+        }
+        KeyCode::Char('h') if matches!(app.view_mode, ViewMode::Exceptions) => {
+            app.toggle_recent_exceptions()
+        }
+        KeyCode::Char(c @ '1'..='9')
+            if matches!(
+                app.view_mode,
+                ViewMode::Exceptions | ViewMode::DatabaseHealth
+            ) =>
+        {
+            app.change_sort(SortChange::Column(c as usize - '1' as usize))
+        }
         KeyCode::End => app.enable_auto_scroll(),
         KeyCode::Up => match app.view_mode {
             ViewMode::Logs => app.scroll_up(),
             ViewMode::QueryAnalysis => app.select_previous_request(),
+            ViewMode::RequestDetail(_) => app.scroll_request_detail(-1),
+            ViewMode::DatabaseHealth => app.select_previous_table(),
             ViewMode::Exceptions => app.select_previous_exception(),
+            ViewMode::TestResults => app.select_previous_test_failure(),
             _ => {}
         },
         KeyCode::Down => match app.view_mode {
             ViewMode::Logs => app.scroll_down(),
             ViewMode::QueryAnalysis => app.select_next_request(),
+            ViewMode::RequestDetail(_) => app.scroll_request_detail(1),
+            ViewMode::DatabaseHealth => app.select_next_table(),
             ViewMode::Exceptions => app.select_next_exception(),
+            ViewMode::TestResults => app.select_next_test_failure(),
+            _ => {}
+        },
+        KeyCode::Left => match app.view_mode {
+            ViewMode::Logs => app.scroll_left(),
+            ViewMode::Timeline => app.pan_timeline(-1),
+            _ => {}
+        },
+        KeyCode::Right => match app.view_mode {
+            ViewMode::Logs => app.scroll_right(),
+            ViewMode::Timeline => app.pan_timeline(1),
+            _ => {}
+        },
+        KeyCode::Home => match app.view_mode {
+            ViewMode::Logs => app.scroll_home(),
+            ViewMode::RequestDetail(_) => app.request_detail_scroll = 0,
+            _ => {}
+        },
+        KeyCode::PageUp => match app.view_mode {
+            ViewMode::Logs => app.scroll_page_up(10),
+            ViewMode::RequestDetail(_) => app.scroll_request_detail(-10),
+            _ => {}
+        },
+        KeyCode::PageDown => match app.view_mode {
+            ViewMode::Logs => app.scroll_page_down(10),
+            ViewMode::RequestDetail(_) => app.scroll_request_detail(10),
             _ => {}
         },
-        KeyCode::Left => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.scroll_left();
-            }
-        }
-        KeyCode::Right => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.scroll_right();
-            }
-        }
-        KeyCode::Home => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.scroll_home();
-            }
-        }
-        KeyCode::PageUp => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.scroll_page_up(10);
-            }
-        }
-        KeyCode::PageDown => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                app.scroll_page_down(10);
-            }
-        }
         KeyCode::Enter => match app.view_mode {
             ViewMode::QueryAnalysis => app.view_selected_request(),
+            ViewMode::DatabaseHealth => app.view_selected_table(),
             ViewMode::Exceptions => app.view_selected_exception(),
+            ViewMode::TestResults => app.toggle_test_failure(),
+            ViewMode::Timeline => app.open_timeline_bucket(),
             _ => {}
         },
+        // Ctrl+E: the same export as JSON lines
+        KeyCode::Char('e')
+            if key.modifiers.contains(KeyModifiers::CONTROL)
+                && matches!(app.view_mode, ViewMode::Logs) =>
+        {
+            let filename = command::commands::default_json_export_file_name(
+                chrono::Local::now(),
+                app.display_zone,
+            );
+            let _ = app.export_logs_json(&filename);
+        }
         KeyCode::Char('e') => {
-            if matches!(app.view_mode, ViewMode::Logs) {
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                let filename = format!("caboose_logs_{}.txt", timestamp);
+            if matches!(app.view_mode, ViewMode::RequestDetail(_)) {
+                app.toggle_request_extra();
+            } else if matches!(app.view_mode, ViewMode::Logs) {
+                let filename = command::commands::default_export_file_name(
+                    chrono::Local::now(),
+                    app.display_zone,
+                );
                 let _ = app.export_logs(&filename);
             }
         }
@@ -1301,42 +4957,806 @@ fn handle_key_event(app: &mut App, key: KeyEvent) {
     }
 }
 
-// ============================================================================
-// FALLBACK IMPLEMENTATIONS (to be migrated to views module)
-// ============================================================================
+/// Scroll with the wheel, and click tabs and rows where the last frame drew
+/// them; true when the event did something
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> bool {
+    let notches = match mouse.kind {
+        MouseEventKind::ScrollUp => -1,
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::Down(MouseButton::Left) => 0,
+        // Moves and drags come in constantly while capture is on
+        _ => return false,
+    };
+    if app.mouse_blocked() {
+        return false;
+    }
+    app.note_user_input();
+
+    if notches != 0 {
+        app.scroll_with_wheel(notches);
+        return true;
+    }
+    let target = app.click_map.borrow().target_at(mouse.column, mouse.row);
+    match target {
+        Some(target) => {
+            app.click(target, Instant::now());
+            true
+        }
+        None => {
+            app.clicks.miss();
+            false
+        }
+    }
+}
 
-// These are temporary fallback implementations using the original code
-// They will be gradually migrated to the views module
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+
+    fn all_views() -> Vec<ViewMode> {
+        let mut views = ViewMode::all_variants();
+        views.push(ViewMode::RequestDetail(0));
+        views.push(ViewMode::LiveRequest(1));
+        views.push(ViewMode::TableDetail("users".to_string()));
+        views.push(ViewMode::ExceptionDetail(0));
+        views
+    }
 
-fn render_request_detail_view_fallback(
-    f: &mut ratatui::Frame,
-    area: ratatui::layout::Rect,
-    app: &App,
-    idx: usize,
-) {
-    let requests = app.context_tracker.get_recent_requests();
-    let lines = if let Some(req) = requests.get(idx) {
-        let path = req
-            .context
-            .path
-            .clone()
-            .unwrap_or_else(|| "<unknown>".to_string());
-        let qcount = req.context.query_count();
-        let duration = req.total_duration.unwrap_or(0.0);
-        vec![
-            Line::raw("Request Detail (fallback)"),
-            Line::raw(format!("Path: {}", path)),
-            Line::raw(format!("Status: {:?}", req.status.unwrap_or(0))),
-            Line::raw(format!("Queries: {}", qcount)),
-            Line::raw(format!("Duration: {:.1}ms", duration)),
-        ]
-    } else {
-        vec![Line::raw("No request selected")]
-    };
+    #[test]
+    fn test_every_view_renders_in_small_terminals() {
+        for (width, height) in [(80, 15), (60, 10)] {
+            for view in all_views() {
+                let mut app = test_app();
+                app.view_mode = view;
+                let screen = render_app(&app, width, height);
+                assert!(screen.contains(" L  Q  D  T  E "), "{}", screen);
+            }
+        }
+    }
+
+    #[test]
+    fn test_views_show_what_they_wait_for_until_data_arrives() {
+        let mut app = empty_app();
+        for (view, title, tip) in [
+            (ViewMode::Logs, "No output yet", "/filter web"),
+            (
+                ViewMode::QueryAnalysis,
+                "No requests yet",
+                "RAILS_LOG_TO_STDOUT",
+            ),
+            (
+                ViewMode::DatabaseHealth,
+                "No queries yet",
+                ":debug log level",
+            ),
+            (
+                ViewMode::TestResults,
+                "No test runs yet",
+                "bundle exec guard",
+            ),
+            (ViewMode::Exceptions, "No exceptions", "/autofocus on"),
+            (ViewMode::Timeline, "Nothing on the timeline yet", "zoom"),
+            (ViewMode::Jobs, "No jobs yet", "bundle exec sidekiq"),
+        ] {
+            app.view_mode = view;
+            let screen = render_app(&app, 140, 30);
+            assert!(screen.contains(title), "{}", screen);
+            assert!(screen.contains(tip), "{}", screen);
+        }
+
+        app.db_health
+            .analyze_query(r#"SELECT * FROM "users" WHERE "users"."id" = 1"#, 2.0);
+        app.view_mode = ViewMode::DatabaseHealth;
+        let screen = render_app(&app, 140, 30);
+        assert!(!screen.contains("No queries yet"), "{}", screen);
+        assert!(screen.contains("Health"), "{}", screen);
+    }
+
+    #[test]
+    fn test_overlays_render_in_small_terminals() {
+        let mut app = test_app();
+        app.search_mode = true;
+        app.show_diagnostics = true;
+        app.command_mode = true;
+        render_app(&app, 60, 10);
+
+        app.command_mode = false;
+        app.process_warning = Some("'web' appears to have daemonized".into());
+        render_app(&app, 60, 10);
+    }
+
+    #[test]
+    fn test_tiny_terminal_shows_placeholder() {
+        let app = test_app();
+        assert!(render_app(&app, 40, 4).contains("Window too small"));
+        render_app(&app, 1, 1);
+        render_app(&app, 0, 0);
+    }
+
+    #[test]
+    fn test_process_panel_hidden_in_compact_layout_until_toggled() {
+        let mut app = test_app();
+        app.update_processes(std::sync::Arc::new(vec![ProcessInfo {
+            name: "web".into(),
+            command: "bin/rails s".into(),
+            status: crate::process::ProcessStatus::Running,
+            start_time: None,
+            pid: None,
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        }]));
+
+        assert!(render_app(&app, 120, 40).contains("  Processes  "));
+        let compact = render_app(&app, 80, 20);
+        assert!(!compact.contains("  Processes  "));
+        assert!(compact.contains("p | Processes"));
+
+        app.toggle_process_panel();
+        assert!(render_app(&app, 80, 20).contains("  Processes  "));
+    }
+
+    #[test]
+    fn test_frontend_builds_show_in_header_and_process_panel() {
+        let mut app = test_app().with_frontend_process("client");
+        app.update_processes(std::sync::Arc::new(vec![ProcessInfo {
+            name: "client".into(),
+            command: "cd client && npm run dev".into(),
+            status: crate::process::ProcessStatus::Running,
+            start_time: None,
+            pid: None,
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        }]));
+        let log = |app: &mut App, process: &str, content: &str| {
+            app.add_log(LogLine::new(process, content.to_string()));
+        };
+        // Only the configured frontend process is followed
+        log(&mut app, "web", "  VITE v5.0.10  ready in 999 ms");
+        log(&mut app, "client", "  VITE v5.0.10  ready in 312 ms");
+        log(&mut app, "client", "  ➜  Local:   http://localhost:5173/");
+        log(
+            &mut app,
+            "client",
+            "4:12:01 PM [vite] hmr update /src/App.tsx",
+        );
+
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("vite ✓ 312ms"), "{}", screen);
+        assert!(screen.contains("✓ 312.0ms  avg 312.0ms"), "{}", screen);
+        assert!(screen.contains("↻ 1 hmr/min  :5173"), "{}", screen);
+
+        log(
+            &mut app,
+            "client",
+            "4:12:09 PM [vite] Internal server error: Transform failed with 1 error:",
+        );
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("vite ✘ build failed"), "{}", screen);
+        assert!(screen.contains("✘ Transform failed"), "{}", screen);
+    }
+
+    #[test]
+    fn test_finished_tasks_show_their_exit_without_counting_as_crashes() {
+        use crate::process::{ProcessExit, ProcessStatus};
+
+        let mut app = test_app();
+        let task = |name: &str, code: i32| ProcessInfo {
+            name: name.into(),
+            command: "bin/rails db:migrate".into(),
+            status: if code == 0 {
+                ProcessStatus::Stopped
+            } else {
+                ProcessStatus::Crashed
+            },
+            start_time: None,
+            pid: None,
+            last_exit: Some(ProcessExit {
+                success: code == 0,
+                code: Some(code),
+                at: Instant::now(),
+            }),
+            restarts: 0,
+            crash_count: (code != 0) as u32,
+            spawn_error: None,
+        };
+        app.update_processes(std::sync::Arc::new(vec![
+            task("task:db-migrate", 1),
+            task("task:routes", 0),
+        ]));
+
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("db-migrate  exit 1"), "{}", screen);
+        assert!(screen.contains("routes          ok"), "{}", screen);
+        assert_eq!(app.project_health(), ProjectHealth::AllGo);
+
+        app.command_input = "/status".to_string();
+        app.execute_command();
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("failed"), "{}", screen);
+        assert!(screen.contains("done"), "{}", screen);
+    }
+
+    #[test]
+    fn test_project_health_leads_header_and_status_lists_processes() {
+        use crate::process::{ProcessExit, ProcessStatus};
+
+        let mut app = test_app();
+        let web = ProcessInfo {
+            name: "web".into(),
+            command: "bin/rails s".into(),
+            status: ProcessStatus::Running,
+            start_time: Some(Instant::now()),
+            pid: Some(42),
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        };
+        app.update_processes(std::sync::Arc::new(vec![web.clone()]));
+        assert!(render_app(&app, 120, 40).contains("Starting: web"));
+
+        let worker = ProcessInfo {
+            name: "worker".into(),
+            command: "bundle exec sidekiq".into(),
+            status: ProcessStatus::Crashed,
+            start_time: None,
+            pid: None,
+            last_exit: Some(ProcessExit {
+                success: false,
+                code: Some(1),
+                at: Instant::now(),
+            }),
+            restarts: 2,
+            crash_count: 0,
+            spawn_error: None,
+        };
+        app.update_processes(std::sync::Arc::new(vec![web, worker]));
+        app.add_log(LogLine::new("web", "* Listening on http://127.0.0.1:3000"));
+        assert_eq!(
+            app.project_health(),
+            ProjectHealth::Down(vec!["worker".to_string()])
+        );
+        assert!(render_app(&app, 120, 40).contains("Crashed: worker"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('s')));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Status: Crashed: worker"));
+        assert!(screen.contains("crashed"));
+        assert!(screen.contains("exit 1 0s"));
+        assert!(screen.contains("ready"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(!app.show_status);
+
+        app.command_input = "/status".to_string();
+        app.execute_command();
+        assert!(app.show_status);
+    }
+
+    #[test]
+    fn test_port_in_use_suggests_adopting_the_running_server() {
+        let mut app = test_app();
+        app.add_log(LogLine::new(
+            "web",
+            "Address already in use - bind(2) for \"127.0.0.1\" port 3001 (Errno::EADDRINUSE)",
+        ));
+
+        let warning = app.process_warning.as_deref().unwrap();
+        assert!(warning.contains("port 3001 is in use"), "{}", warning);
+        assert!(warning.contains("--attach"));
+        assert!(warning.contains("[processes.web]"));
+    }
+
+    #[test]
+    fn test_unparsed_lines_are_counted_and_sampled_per_process() {
+        let mut app = test_app();
+        for (process, content) in [
+            ("worker", "Sidekiq 7.2.1 starting pid=4242"),
+            ("worker", "Sidekiq 7.2.1 starting pid=5151"),
+            ("worker", "──────────"),
+            ("caboose", "Config reloaded"),
+        ] {
+            app.add_log(LogLine::new(process, content.to_string()));
+        }
 
-    let block = Block::default()
-        .title("Request Details")
-        .borders(Borders::ALL);
-    let para = Paragraph::new(lines).block(block);
-    f.render_widget(para, area);
+        let coverage = app.parse_coverage();
+        assert_eq!(coverage.lines(), 9);
+        assert_eq!(coverage.unparsed(), 2);
+        assert_eq!(coverage.percent(), Some(77));
+        let samples = coverage.samples();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].process, "worker");
+        assert_eq!(samples[0].shape, "Sidekiq 0.0.0 starting pid=0");
+        assert_eq!(samples[0].count, 2);
+    }
+
+    #[test]
+    fn test_sustained_slow_frames_log_a_warning_once() {
+        let mut app = test_app();
+        app.view_mode = ViewMode::QueryAnalysis;
+        let start = Instant::now();
+        let fast = Duration::from_millis(4);
+        let slow = Duration::from_millis(40);
+        let frame = Duration::from_millis(100);
+
+        // Quick frames, then a slow spike shorter than the sustain window
+        for i in 0..60 {
+            app.record_frame(fast, Some(fast), start + frame * i);
+        }
+        for i in 60..110 {
+            app.record_frame(slow, None, start + frame * i);
+        }
+        let warnings = |app: &App| {
+            app.logs
+                .iter()
+                .filter(|log| log.content.contains("slow to draw"))
+                .count()
+        };
+        assert_eq!(warnings(&app), 0);
+
+        // Still slow after the sustain window: one warning, naming the view
+        for i in 110..300 {
+            app.record_frame(slow, None, start + frame * i);
+        }
+        assert_eq!(warnings(&app), 1);
+        let warning = app
+            .logs
+            .iter()
+            .find(|log| log.content.contains("slow to draw"))
+            .unwrap();
+        assert!(warning.content.contains("40.0ms"), "{}", warning.content);
+        assert!(warning.content.contains("Query Analysis view"));
+        assert_eq!(app.frame_timings.stats().input_p95, fast);
+        assert_eq!(app.frame_timings.stats().draw_max, slow);
+    }
+
+    #[test]
+    fn test_process_panel_selects_and_confirms_before_stopping() {
+        use crate::process::ProcessStatus;
+
+        let mut app = test_app();
+        let process = |name: &str| ProcessInfo {
+            name: name.into(),
+            command: "bin/dev".into(),
+            status: ProcessStatus::Running,
+            start_time: None,
+            pid: None,
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        };
+        app.update_processes(std::sync::Arc::new(vec![process("web"), process("worker")]));
+        let key = |app: &mut App, c: char| handle_key_event(app, KeyEvent::from(KeyCode::Char(c)));
+
+        // `s` is the status overlay until Tab gives the panel the keys
+        key(&mut app, 's');
+        assert!(app.show_status);
+        app.close_status();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        key(&mut app, 'j');
+        key(&mut app, 'j');
+        assert_eq!(app.selected_process_name(), Some("worker"));
+
+        key(&mut app, 's');
+        assert!(!app.show_status);
+        let screen = render_app(&app, 140, 30);
+        assert!(
+            screen.contains("Press s again to stop worker"),
+            "{}",
+            screen
+        );
+        assert!(app.last_command_result.is_none());
+
+        // Any other key backs out; asked twice, it's done
+        key(&mut app, 'r');
+        key(&mut app, 's');
+        key(&mut app, 's');
+        assert!(app.pending_process_action.is_none());
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message))
+                    if message == "Can't control a saved session's processes"
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        key(&mut app, 's');
+        assert!(app.show_status);
+    }
+
+    #[test]
+    fn test_disk_warning_banner_truncates_log_on_x() {
+        use crate::disk::{DiskWarning, LogFile};
+
+        let dir = std::env::temp_dir().join(format!("caboose_ui_disk_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("development.log");
+        std::fs::write(&path, "x".repeat(2048)).unwrap();
+        let large = DiskWarning::LargeLog(LogFile {
+            path: path.clone(),
+            name: "log/development.log".to_string(),
+            size: 2048,
+        });
+        let low = DiskWarning::LowSpace { free: 1024 };
+
+        let mut app = test_app();
+        app.note_disk_warnings(&[large.clone(), low.clone()]);
+        assert!(render_app(&app, 140, 30).contains("log/development.log has grown to 2.00 KB"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        assert!(app.disk_warning.is_none());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        assert!(render_app(&app, 140, 30).contains("Truncated log/development.log"));
+
+        // Each problem is raised once while it lasts; the next one follows
+        app.note_disk_warnings(&[large.clone(), low.clone()]);
+        assert_eq!(app.disk_warning, Some(low.clone()));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(app.disk_warning.is_none());
+        app.note_disk_warnings(&[large.clone(), low.clone()]);
+        assert!(app.disk_warning.is_none());
+
+        // ... and again once it has cleared and come back
+        app.note_disk_warnings(&[low]);
+        app.note_disk_warnings(std::slice::from_ref(&large));
+        assert_eq!(app.disk_warning, Some(large.clone()));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+
+        // Typing isn't taken for the shortcut, even when the warning shows
+        // up mid-word
+        std::fs::write(&path, "x".repeat(2048)).unwrap();
+        app.note_disk_warnings(&[]);
+        app.enter_command_mode();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('e')));
+        app.note_disk_warnings(std::slice::from_ref(&large));
+        for c in "xport".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert!(
+            app.command_input.ends_with("export"),
+            "{}",
+            app.command_input
+        );
+        assert_eq!(app.disk_warning, Some(large));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 2048);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_project_changes_reload_schema_and_warn_on_adapter_switch() {
+        use crate::database::schema::parse_schema;
+
+        let mut app = test_app();
+        app.db_health.set_dialect(SqlDialect::Postgres);
+        let tables = parse_schema(
+            "  create_table \"users\", force: :cascade do |t|\n    t.index [\"email\"], name: \"index_users_on_email\"\n  end\n",
+        );
+        app.apply_project_change(ProjectChange::Schema(tables));
+        assert!(app.db_health.get_table_detail("users").in_schema);
+        assert_eq!(
+            app.logs.last().unwrap().content,
+            "schema.rb changed — reloaded 1 table"
+        );
+
+        // Same adapter: logged, no warning
+        app.apply_project_change(ProjectChange::DatabaseConfig {
+            adapter: Some("postgresql".to_string()),
+        });
+        assert!(app.process_warning.is_none());
+
+        app.apply_project_change(ProjectChange::DatabaseConfig {
+            adapter: Some("mysql".to_string()),
+        });
+        assert_eq!(app.db_health.dialect(), SqlDialect::MySql);
+        assert!(
+            render_app(&app, 140, 30)
+                .contains("database.yml adapter changed from PostgreSQL to MySQL")
+        );
+        assert_eq!(
+            app.logs.last().unwrap().content,
+            "database.yml changed — adapter mysql"
+        );
+    }
+
+    #[test]
+    fn test_saved_session_opens_read_only_behind_a_banner() {
+        use crate::disk::{DiskWarning, LogFile};
+        use crate::session::SessionArchive;
+
+        let dir = std::env::temp_dir().join(format!("caboose_ui_session_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive_path = dir.join("handoff.json.gz");
+
+        let mut live = test_app().with_project(std::path::Path::new("/code/shop"));
+        live.command_input = format!("/session save {}", archive_path.display());
+        live.execute_command();
+        assert!(
+            live.last_command_result
+                .as_ref()
+                .is_some_and(|result| result.is_success())
+        );
+
+        let mut archive = SessionArchive::load(&archive_path).unwrap();
+        let logs = std::mem::take(&mut archive.logs);
+        let mut app = test_app();
+        app.logs.clear();
+        let mut app = app.with_session(archive.metadata.clone(), Vec::new(), logs);
+        assert!(app.is_read_only());
+        assert_eq!(app.logs.len(), live.logs.len());
+
+        let screen = render_app(&app, 140, 30);
+        assert!(screen.contains("REPLAY"));
+        assert!(screen.contains("from /code/shop - read-only"));
+        assert!(screen.contains(r#"Started GET "/users""#));
+
+        // Truncating a large log would change the disk; it's left alone
+        let path = dir.join("development.log");
+        std::fs::write(&path, "x".repeat(2048)).unwrap();
+        app.note_disk_warnings(&[DiskWarning::LargeLog(LogFile {
+            path: path.clone(),
+            name: "log/development.log".to_string(),
+            size: 2048,
+        })]);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 2048);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_privacy_mode_masks_the_screen_and_shows_a_badge() {
+        let mut app = test_app().with_privacy(Privacy::new(false, vec!["customer/*".into()]));
+        app._git_info.branch = Some("customer/acme-export".to_string());
+        app.add_log(LogLine::new("web", r#"  User Load (0.8ms)  SELECT "users".* FROM "users" WHERE "users"."email" = 'jane@example.com'"#
+                .to_string()));
+
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("customer/acme-export"), "{}", screen);
+        assert!(screen.contains("'jane@example.com'"), "{}", screen);
+        assert!(!screen.contains("Privacy on"), "{}", screen);
+
+        app.command_input = "/privacy on".to_string();
+        app.execute_command();
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("Privacy on (P to show)"), "{}", screen);
+        assert!(screen.contains(r#""users"."email" = '•••'"#), "{}", screen);
+        assert!(!screen.contains("jane@example.com"), "{}", screen);
+        assert!(!screen.contains("customer/acme-export"), "{}", screen);
+
+        // The badge stays in the compact layout too
+        assert!(render_app(&app, 70, 20).contains("Privacy on"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('P')));
+        assert!(!app.privacy.is_enabled());
+        assert!(render_app(&app, 160, 40).contains("jane@example.com"));
+    }
+
+    #[test]
+    fn test_ui_state_restores_and_drops_filters_for_missing_processes() {
+        let mut app = test_app();
+        app.view_mode = ViewMode::QueryAnalysis;
+        app.active_tab_index = 1;
+        app.filter_process = Some(ProcessFilter::only("worker"));
+        app.search_queries
+            .insert(ViewMode::QueryAnalysis, "users".to_string());
+        app.log_order = LogOrder::NewestFirst;
+        app.exception_sort = SortState::new(0, false);
+        let state = app.ui_state();
+
+        let restored =
+            test_app().with_ui_state(state.clone(), &["web".to_string(), "worker".to_string()]);
+        assert_eq!(restored.view_mode, ViewMode::QueryAnalysis);
+        assert_eq!(restored.active_tab_index, 1);
+        assert_eq!(
+            restored
+                .filter_process
+                .as_ref()
+                .map(ProcessFilter::to_string),
+            Some("worker".to_string())
+        );
+        assert_eq!(restored.search_query_for(&ViewMode::QueryAnalysis), "users");
+        assert_eq!(restored.log_order, LogOrder::NewestFirst);
+        assert_eq!(restored.exception_sort, SortState::new(0, false));
+
+        // The worker is gone from the Procfile, and the sort column from the table
+        let stale = UiState {
+            exception_sort: Some(SortState::new(9, false)),
+            ..state
+        };
+        let restored = test_app().with_ui_state(stale, &["web".to_string()]);
+        assert_eq!(restored.filter_process, None);
+        assert!(restored.logs.iter().any(
+            |log| log.process_name == "caboose" && log.content.contains("Logs filter 'worker'")
+        ));
+        assert_eq!(restored.exception_sort.column, 0);
+    }
+
+    #[test]
+    fn test_autofocus_follows_exceptions_and_pauses_on_keys() {
+        let mut app = test_app();
+        app.command_input = "/autofocus on".to_string();
+        app.execute_command();
+        app.last_command_result = None;
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("◎ Auto focus"), "{}", screen);
+
+        app.add_log(LogLine::new(
+            "web",
+            "NoMethodError (undefined method `email' for nil:NilClass):",
+        ));
+        app.check_autofocus();
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+        assert_eq!(
+            ViewMode::from_index(app.active_tab_index),
+            Some(ViewMode::Exceptions)
+        );
+
+        // A key press hands the views back to the user
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
+        let view = app.view_mode.clone();
+        assert!(render_app(&app, 160, 40).contains("◎ Auto focus paused"));
+        app.update_processes(std::sync::Arc::new(vec![ProcessInfo {
+            name: "worker".into(),
+            command: "bin/jobs".into(),
+            status: crate::process::ProcessStatus::Crashed,
+            start_time: None,
+            pid: None,
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        }]));
+        app.check_autofocus();
+        assert_eq!(app.view_mode, view);
+    }
+
+    #[test]
+    fn test_reloaded_limits_resize_stores() {
+        let mut app = test_app();
+        assert_eq!(app.logs.len(), 6);
+
+        let config: crate::config::CabooseConfig =
+            toml::from_str("[limits]\nmax_logs = 4\n").unwrap();
+        app.apply_reloaded_config(Ok(config));
+
+        assert_eq!(app.logs.len(), 4);
+        assert!(app.process_warning.is_none());
+        assert!(app.last_command_result.as_ref().unwrap().is_success());
+        let log_buffer = app.diagnostics_usage()[0];
+        assert_eq!(log_buffer.kind, StoreKind::LogBuffer);
+        assert_eq!(log_buffer.limit, Some(4));
+
+        // Out-of-range values fall back to the default, with a warning
+        let config: crate::config::CabooseConfig =
+            toml::from_str("[limits]\nmax_logs = 0\n").unwrap();
+        app.apply_reloaded_config(Ok(config));
+        assert_eq!(app.max_logs, Limits::default().max_logs);
+        assert!(app.process_warning.as_ref().unwrap().contains("max_logs"));
+
+        app.process_warning = None;
+        app.apply_reloaded_config(Err("Invalid .caboose.toml".to_string()));
+        assert!(app.process_warning.as_ref().unwrap().contains("unchanged"));
+        assert_eq!(app.max_logs, Limits::default().max_logs);
+    }
+
+    #[test]
+    fn test_weighted_exception_rate_in_header_and_e_jumps_to_exceptions() {
+        let mut app = test_app();
+        for content in [
+            "SystemStackError: stack level too deep",
+            "NoMethodError: undefined method `foo' for nil:NilClass",
+            "Completed 500 Internal Server Error in 5ms",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        // test_app's NoMethodError (high, 2) + critical (5) + high (2)
+        let screen = render_app(&app, 200, 40);
+        assert!(screen.contains("9.0 exc/min"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('E')));
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+    }
+
+    fn click(app: &mut App, column: u16, row: u16) -> bool {
+        let left = MouseEventKind::Down(MouseButton::Left);
+        handle_mouse_event(app, mouse(left, column, row))
+    }
+
+    /// Column and row `text` was drawn at, searching from row `from`
+    fn position_of(screen: &str, text: &str, from: usize) -> (u16, u16) {
+        screen
+            .lines()
+            .enumerate()
+            .skip(from)
+            .find_map(|(row, line)| {
+                let at = line.find(text)?;
+                Some((line[..at].chars().count() as u16, row as u16))
+            })
+            .unwrap_or_else(|| panic!("{:?} not on screen:\n{}", text, screen))
+    }
+
+    #[test]
+    fn test_clicking_a_tab_switches_to_its_view() {
+        let mut app = test_app();
+        let screen = render_app(&app, 120, 30);
+        // The tab bar is below the five-line header
+        let (column, row) = position_of(&screen, "Exceptions", 5);
+        assert_eq!(row, 6);
+        assert!(click(&mut app, column + 3, row));
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+        assert_eq!(app.active_tab_index, 4);
+
+        // The divider between two tabs is neither
+        render_app(&app, 120, 30);
+        let (column, row) = position_of(&screen, "Logs", 5);
+        assert!(!click(&mut app, column + 5, row));
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+        assert!(click(&mut app, column - 1, row));
+        assert_eq!(app.view_mode, ViewMode::Logs);
+
+        // The compact layout's letters are tabs too: " L  Q  D ..."
+        render_app(&app, 80, 15);
+        assert!(click(&mut app, 7, 0));
+        assert_eq!(app.view_mode, ViewMode::DatabaseHealth);
+
+        // Nothing is clicked through an overlay
+        app.open_help();
+        render_app(&app, 80, 15);
+        assert!(!click(&mut app, 1, 0));
+        assert_eq!(app.view_mode, ViewMode::DatabaseHealth);
+    }
+
+    #[test]
+    fn test_clicking_rows_selects_them_and_a_double_click_opens_them() {
+        let mut app = test_app();
+        for exception in [
+            "ArgumentError (wrong number of arguments):",
+            "ArgumentError (wrong number of arguments):",
+            "NameError (uninitialized constant Foo):",
+        ] {
+            app.exception_tracker.parse_line(exception);
+            app.exception_tracker
+                .parse_line("Completed 500 Internal Server Error in 5ms");
+        }
+        app.view_mode = ViewMode::Exceptions;
+        let screen = render_app(&app, 120, 30);
+        let (column, row) = position_of(&screen, "NameError", 8);
+        assert!(click(&mut app, column, row));
+        assert_eq!(app.selected_exception, 1);
+        assert_eq!(app.view_mode, ViewMode::Exceptions);
+        click(&mut app, column, row);
+        let ViewMode::ExceptionDetail(index) = app.view_mode else {
+            panic!("expected Exception Detail, got {:?}", app.view_mode);
+        };
+        assert_eq!(
+            app.exception_tracker.get_grouped_exceptions()[index].exception_type,
+            "NameError"
+        );
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        app.selected_in_flight = Some(0);
+        let screen = render_app(&app, 120, 30);
+        let (_, row) = position_of(&screen, "/users", 8);
+        assert!(click(&mut app, 10, row));
+        assert_eq!(app.selected_in_flight, None);
+        assert_eq!(app.selected_request, 0);
+        // Below the list is nothing to click
+        assert!(!click(&mut app, 10, row + 1));
+        click(&mut app, 10, row);
+        assert_eq!(app.view_mode, ViewMode::QueryAnalysis);
+        click(&mut app, 10, row);
+        assert_eq!(app.view_mode, ViewMode::RequestDetail(0));
+    }
 }
@@ -0,0 +1,66 @@
+//! Stopping, starting and restarting the process selected in the Logs view's
+//! Processes panel
+//!
+//! Tab gives the panel the keys; `s` stops the selected process, `r` restarts
+//! it and `S` starts it again once stopped. Stop and restart end a run, so
+//! they wait for the same key a second time, with a hint in the footer.
+
+/// What can be done to the selected process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    Stop,
+    Restart,
+    Start,
+}
+
+impl ProcessAction {
+    /// The action bound to `c` while the Processes panel has the keys
+    pub fn for_key(c: char) -> Option<Self> {
+        match c {
+            's' => Some(Self::Stop),
+            'r' => Some(Self::Restart),
+            'S' => Some(Self::Start),
+            _ => None,
+        }
+    }
+
+    pub fn key(self) -> char {
+        match self {
+            Self::Stop => 's',
+            Self::Restart => 'r',
+            Self::Start => 'S',
+        }
+    }
+
+    /// Whether it ends a run, so waits for its key to be pressed again
+    pub fn needs_confirmation(self) -> bool {
+        !matches!(self, Self::Start)
+    }
+
+    /// `Press s again to stop worker`
+    pub fn confirmation_hint(self, name: &str) -> String {
+        let verb = match self {
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+            Self::Start => "start",
+        };
+        format!("Press {} again to {} {}", self.key(), verb, name)
+    }
+
+    /// `Stopped worker`
+    pub fn done_message(self, name: &str) -> String {
+        let verb = match self {
+            Self::Stop => "Stopped",
+            Self::Restart => "Restarted",
+            Self::Start => "Started",
+        };
+        format!("{} {}", verb, name)
+    }
+}
+
+/// A stop or restart waiting for its key to be pressed again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingAction {
+    pub action: ProcessAction,
+    pub process: String,
+}
@@ -0,0 +1,120 @@
+//! Whether the terminal can show the TUI, checked before any process starts
+//!
+//! Output piped into a VS Code task or CI log, `TERM=dumb` and terminals
+//! without raw mode would otherwise only fail once the processes are running.
+//! Dev mode falls back to plain logs (`--no-tui`) instead; `caboose open`
+//! exits with the reason.
+//!
+//! Once the TUI has the terminal, a panic puts it back (raw mode and mouse
+//! capture off, main screen) before the panic message is printed.
+
+use std::io::{self, IsTerminal};
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+
+/// Set while the TUI has the terminal in raw mode with mouse capture on
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Why the TUI can't run here, if it can't
+///
+/// Reads `$TERM` (on Unix) and whether stdout is a terminal, then briefly
+/// switches raw mode on and off.
+pub fn check_terminal() -> Result<(), String> {
+    let term = std::env::var("TERM").ok();
+    if let Some(problem) = unsupported_terminal(
+        cfg!(unix).then_some(term.as_deref()),
+        std::io::stdout().is_terminal(),
+    ) {
+        return Err(problem);
+    }
+    enable_raw_mode().map_err(|e| format!("the terminal has no raw mode ({})", e))?;
+    let _ = disable_raw_mode();
+    Ok(())
+}
+
+/// Hand the terminal back the way the shell expects it
+pub fn restore() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        Show
+    )
+}
+
+/// Note whether the TUI has the terminal, for the panic hook
+pub fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+/// Restore the terminal before printing a panic while the TUI has it; the
+/// panic is then reported as usual
+pub fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let report = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if TUI_ACTIVE.swap(false, Ordering::SeqCst) {
+                let _ = restore();
+            }
+            report(info);
+        }));
+    });
+}
+
+/// The problem with `term` (`None` when not checked, as on Windows) and
+/// stdout, before trying raw mode
+pub fn unsupported_terminal(
+    term: Option<Option<&str>>,
+    stdout_is_terminal: bool,
+) -> Option<String> {
+    if !stdout_is_terminal {
+        return Some("stdout isn't a terminal (output is piped or redirected)".to_string());
+    }
+    match term {
+        Some(None) | Some(Some("")) => Some("$TERM isn't set".to_string()),
+        Some(Some("dumb")) => Some("TERM=dumb has no cursor control".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piped_output_and_dumb_or_missing_term_are_unsupported() {
+        assert!(
+            unsupported_terminal(Some(Some("xterm-256color")), false)
+                .unwrap()
+                .contains("piped")
+        );
+        assert_eq!(
+            unsupported_terminal(Some(Some("dumb")), true).as_deref(),
+            Some("TERM=dumb has no cursor control")
+        );
+        assert_eq!(
+            unsupported_terminal(Some(None), true).as_deref(),
+            Some("$TERM isn't set")
+        );
+        assert_eq!(
+            unsupported_terminal(Some(Some("")), true).as_deref(),
+            Some("$TERM isn't set")
+        );
+
+        assert_eq!(
+            unsupported_terminal(Some(Some("xterm-256color")), true),
+            None
+        );
+        // Windows consoles have no $TERM to check
+        assert_eq!(unsupported_terminal(None, true), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! The app as the UI's tests drive it, and its screen as text
+
+use ratatui::{Terminal, backend::TestBackend};
+
+use super::*;
+
+/// A fresh session: nothing logged yet
+pub(super) fn empty_app() -> App {
+    let environment_info = crate::environment::EnvironmentInfo {
+        current_path: ".../apps/shop".to_string(),
+        ruby_version: Some("3.3.0".to_string()),
+        node_version: None,
+        package_manager: None,
+        rails_version: Some("7.1.2".to_string()),
+        database: Some("PostgreSQL".to_string()),
+    };
+    let exception_tracker = std::sync::Arc::new(ExceptionTracker::new());
+    App::new(
+        GitInfo::default(),
+        environment_info,
+        StatsCollector::new(),
+        std::sync::Arc::new(RequestContextTracker::new()),
+        std::sync::Arc::new(DatabaseHealth::new()),
+        std::sync::Arc::new(TestTracker::new()),
+        exception_tracker,
+    )
+}
+
+/// A session that has served one request, which raised an exception
+pub(super) fn test_app() -> App {
+    let mut app = empty_app();
+    for content in [
+        r#"Started GET "/users" for 127.0.0.1"#,
+        "Processing by UsersController#index as HTML",
+        r#"  User Load (1.2ms)  SELECT "users".* FROM "users""#,
+        "Completed 200 OK in 52ms (Views: 40.1ms | ActiveRecord: 9.5ms | Allocations: 1200)",
+        "NoMethodError (undefined method `name' for nil:NilClass):",
+        "  app/controllers/users_controller.rb:5:in `index'",
+    ] {
+        app.add_log(LogLine::new("web", content));
+    }
+    app
+}
+
+/// The screen `app` draws at `width` × `height`, one line per row
+pub(super) fn render_app(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| render_ui(f, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A mouse event with no modifiers held
+pub(super) fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
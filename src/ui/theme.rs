@@ -121,6 +121,17 @@ impl Theme {
         }
     }
 
+    /// Get color for an HTTP method badge
+    pub fn method_color(method: &str) -> Color {
+        match method {
+            "GET" | "HEAD" => Self::info(),
+            "POST" => Self::success(),
+            "PUT" | "PATCH" => Self::warning(),
+            "DELETE" => Self::danger(),
+            _ => Self::text_secondary(),
+        }
+    }
+
     /// Apply a fade effect to a color by blending it with the background.
     /// progress 0.0 = full background, 1.0 = full color
     pub fn apply_fade_to_color(color: Color, fade_progress: f32) -> Color {
@@ -135,7 +146,7 @@ impl Theme {
             _ => return color,
         };
 
-        let progress = fade_progress.max(0.0).min(1.0);
+        let progress = fade_progress.clamp(0.0, 1.0);
 
         let r = (r1 * progress + r2 * (1.0 - progress)) as u8;
         let g = (g1 * progress + g2 * (1.0 - progress)) as u8;
@@ -338,6 +349,14 @@ impl Icons {
         }
     }
 
+    pub fn job() -> &'static str {
+        if super::icon_manager::IconManager::using_nerd_fonts() {
+            "\u{f085}" // fa-cogs
+        } else {
+            "[job]"
+        }
+    }
+
     pub fn logs() -> &'static str {
         if super::icon_manager::IconManager::using_nerd_fonts() {
             "\u{f0f6}" // fa-file-text-o
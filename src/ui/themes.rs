@@ -38,6 +38,7 @@ impl ThemeName {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "material" | "material-design" | "md3" => Some(ThemeName::MaterialDesign),
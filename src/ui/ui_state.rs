@@ -0,0 +1,101 @@
+//! UI settings carried between runs in `.caboose/ui_state.json`
+//!
+//! Written on exit and, with `[ui] restore_session` (the default) and without
+//! `--fresh`, read back at startup: the tab that was open, the Logs process
+//! filter, minimum level and hidden category chips, each tab's search and
+//! whether the Logs search matches case, the table sorts and the log display
+//! toggles.
+//! A missing or corrupt file is the same as no saved state.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::{LogCategory, LogLevel};
+use crate::ui::components::{LogColors, LogOrder};
+use crate::ui::widgets::SortState;
+
+/// File in the project's data directory the UI state is kept in
+pub const UI_STATE_FILE: &str = "ui_state.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Tab open on exit, by its title (`Query Analysis`)
+    pub view: Option<String>,
+    /// The Logs view's process filter, as typed (`web,worker`, `!frontend`)
+    pub filter_process: Option<String>,
+    /// The Logs view's `/level` minimum
+    pub min_log_level: Option<LogLevel>,
+    /// Log categories switched off in the Logs view's chip bar
+    pub hidden_log_categories: Vec<LogCategory>,
+    /// Search per tab, by tab title
+    pub searches: BTreeMap<String, String>,
+    /// The Logs search matches case (Ctrl+I while searching)
+    pub search_case_sensitive: bool,
+    pub exception_sort: Option<SortState>,
+    pub slow_query_sort: Option<SortState>,
+    pub log_order: Option<LogOrder>,
+    pub log_colors: Option<LogColors>,
+    pub privacy: bool,
+    /// Process panel shown next to the logs in the compact layout
+    pub compact_processes: bool,
+}
+
+impl UiState {
+    /// Read the state saved at `path`; `None` when missing or unreadable
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize UI state: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trips_and_bad_files_load_as_none() {
+        let dir = std::env::temp_dir().join(format!("caboose_ui_state_{}", std::process::id()));
+        let path = dir.join(UI_STATE_FILE);
+        let state = UiState {
+            view: Some("Query Analysis".to_string()),
+            filter_process: Some("web".to_string()),
+            hidden_log_categories: vec![LogCategory::Sql, LogCategory::Other],
+            searches: BTreeMap::from([("Logs".to_string(), "users".to_string())]),
+            exception_sort: Some(SortState::new(0, false)),
+            log_order: Some(LogOrder::NewestFirst),
+            privacy: true,
+            ..UiState::default()
+        };
+
+        state.save(&path).unwrap();
+        assert_eq!(UiState::load(&path), Some(state));
+
+        fs::write(&path, "{\"view\": 3").unwrap();
+        assert_eq!(UiState::load(&path), None);
+        assert_eq!(UiState::load(&dir.join("missing.json")), None);
+
+        // Fields added later (or dropped by hand) fall back to their defaults
+        fs::write(&path, r#"{"view": "Exceptions"}"#).unwrap();
+        assert_eq!(
+            UiState::load(&path).and_then(|state| state.view),
+            Some("Exceptions".to_string())
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,27 +1,50 @@
-use ratatui::{Frame, layout::Rect, style::Style, widgets::Paragraph};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+};
 
-use crate::database::DatabaseHealth;
-use crate::ui::theme::Theme;
-use crate::ui::widgets::Gauge;
+use crate::database::{DatabaseHealth, SlowQuery};
+use crate::privacy::Privacy;
+use crate::search::SearchQuery;
+use crate::time_format::DisplayZone;
+use crate::ui::components::EmptyState;
+use crate::ui::formatting::format_ms;
+use crate::ui::theme::{Icons, Theme};
+use crate::ui::widgets::{Column, Gauge, SortKey, SortState, SortableTable, Sparkline};
 
+/// Render the database health view
+///
+/// A non-empty `search_query` replaces the issue list with the slow queries
+/// whose SQL contains it, sorted by `slow_query_sort`. The top tables list beside it highlights
+/// `selected_table`, which Enter opens in Table Detail. Query text is masked
+/// while `privacy` is on. The score's worst drops, if any, are listed under
+/// the issues with their times in `zone`.
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
     db_health: &DatabaseHealth,
+    search_query: &str,
+    selected_table: usize,
+    slow_query_sort: SortState,
+    privacy: &Privacy,
+    zone: DisplayZone,
     _spinner_frame: usize,
     fade_progress: Option<f32>,
 ) {
-    if db_health.get_stats().total_queries == 0 {
-        let block = Theme::block("Database Health", fade_progress);
-        let empty = Paragraph::new("Waiting for queries...")
-            .style(Style::default().fg(Theme::text_muted()))
-            .block(block);
-        f.render_widget(empty, area);
+    if db_health.is_empty() {
+        EmptyState::new(Icons::database(), "No queries yet")
+            .with_hint("Waiting for SQL in the Rails logs - the health score starts with the first query")
+            .with_hint("Queries are read from ActiveRecord's debug lines, e.g. 'User Load (0.4ms)  SELECT ...'")
+            .with_tip("SQL is only logged at the :debug log level, the development default")
+            .render(f, area, "Database Health", fade_progress);
         return;
     }
 
     let score = db_health.calculate_health_score();
-    let issues = db_health.get_issues();
 
     let gauge = Gauge::default()
         .block(Theme::block("Database Health Score", fade_progress))
@@ -29,27 +52,259 @@ pub fn render(
         .label(format!("{}%", score))
         .gradient(vec![Theme::danger(), Theme::warning(), Theme::success()]);
 
-    let issues_text: Vec<String> = issues
+    let search = SearchQuery::new(search_query);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(0)])
+        .split(area);
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[1]);
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(SCORE_TREND_WIDTH)])
+        .split(chunks[0]);
+    f.render_widget(gauge, top[0]);
+    render_score_trend(f, top[1], db_health, fade_progress);
+    if search.is_empty() {
+        let drops = db_health.get_score_drops(SCORE_DROPS_LISTED);
+        let [issues_area, drops_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(if drops.is_empty() {
+                0
+            } else {
+                drops.len() as u16 + 2
+            }),
+        ])
+        .areas(body[0]);
+        let issues_text: Vec<String> = db_health
+            .get_issues()
+            .iter()
+            .map(|issue| {
+                if issue.description.is_empty() {
+                    format!("• {}", issue.title)
+                } else {
+                    format!(
+                        "• {}\n  Query: {}",
+                        issue.title,
+                        privacy.sql(&issue.description)
+                    )
+                }
+            })
+            .collect();
+        f.render_widget(
+            Paragraph::new(issues_text.join("\n")).block(Theme::block("Issues", fade_progress)),
+            issues_area,
+        );
+        if !drops.is_empty() {
+            let lines: Vec<Line> = drops
+                .iter()
+                .map(|drop| {
+                    Line::styled(
+                        format!("▼ {}", drop.describe(zone)),
+                        Style::default().fg(Theme::warning()),
+                    )
+                })
+                .collect();
+            f.render_widget(
+                Paragraph::new(lines).block(Theme::block("Worst Score Drops", fade_progress)),
+                drops_area,
+            );
+        }
+    } else {
+        let slow_queries = db_health.get_slow_queries_matching(&search);
+        slow_query_table(slow_query_sort, privacy)
+            .header_style(Style::default().fg(Theme::warning()))
+            .block(Theme::block(
+                format!("Slow Queries ({}) - </> Sort", slow_queries.len()),
+                fade_progress,
+            ))
+            .render(f, body[0], &slow_queries, None);
+    }
+    render_top_tables(f, body[1], db_health, selected_table, fade_progress);
+}
+
+/// Slowest first, until `<` / `>` or a number key change it
+pub const SLOW_QUERY_SORT: SortState = SortState::new(0, true);
+
+/// Slow query columns, ties broken by the most executed
+pub fn slow_query_table<'a>(sort: SortState, privacy: &'a Privacy) -> SortableTable<'a, SlowQuery> {
+    SortableTable::new(
+        vec![
+            Column::new(
+                "Slowest",
+                Constraint::Length(10),
+                |q: &SlowQuery| Line::from(format_ms(q.duration)),
+                |q| SortKey::Number(q.duration),
+            )
+            .alignment(Alignment::Right)
+            .descending(),
+            Column::new(
+                "Count",
+                Constraint::Length(7),
+                |q: &SlowQuery| Line::from(format!("×{}", q.execution_count)),
+                |q| SortKey::Number(q.execution_count as f64),
+            )
+            .alignment(Alignment::Right)
+            .descending(),
+            Column::new(
+                "Total",
+                Constraint::Length(10),
+                |q: &SlowQuery| Line::from(format_ms(q.total_duration)),
+                |q| SortKey::Number(q.total_duration),
+            )
+            .alignment(Alignment::Right)
+            .descending(),
+            Column::new(
+                "Query",
+                Constraint::Min(20),
+                move |q: &SlowQuery| Line::from(privacy.sql(&q.query).into_owned()),
+                move |q| SortKey::Text(privacy.sql(&q.query).into_owned()),
+            ),
+        ],
+        sort,
+    )
+    .secondary(1)
+}
+
+/// Width of the health score trend panel, borders included
+const SCORE_TREND_WIDTH: u16 = 32;
+
+/// Largest falls in the score listed under the issues
+const SCORE_DROPS_LISTED: usize = 3;
+
+fn render_score_trend(
+    f: &mut Frame,
+    area: Rect,
+    db_health: &DatabaseHealth,
+    fade_progress: Option<f32>,
+) {
+    let history = db_health.get_score_history();
+    let block = Theme::block("Score Trend", fade_progress);
+    let inner = block.inner(area);
+    let trend = Sparkline::new(&history)
+        .width(inner.width as usize)
+        .labels(|score| format!("{:.0}", score))
+        .style(Style::default().fg(Theme::text_secondary()))
+        .highlight_last(Theme::primary());
+    let muted = Style::default().fg(Theme::text_muted());
+    let score_style = |score: u32| Style::default().fg(Theme::health_color(score.min(100) as u8));
+    let range = match db_health.get_score_range() {
+        Some(range) => Line::from(vec![
+            Span::styled("min ", muted),
+            Span::styled(range.min.to_string(), score_style(range.min)),
+            Span::styled("  max ", muted),
+            Span::styled(range.max.to_string(), score_style(range.max)),
+            Span::styled("  now ", muted),
+            Span::styled(
+                range.current.to_string(),
+                score_style(range.current).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        None => Line::styled("first sample soon", muted),
+    };
+    f.render_widget(Paragraph::new(vec![trend.line(), range]).block(block), area);
+}
+
+fn render_top_tables(
+    f: &mut Frame,
+    area: Rect,
+    db_health: &DatabaseHealth,
+    selected_table: usize,
+    fade_progress: Option<f32>,
+) {
+    let tables = db_health.get_top_tables();
+    if tables.is_empty() {
+        let empty = Paragraph::new("No slow queries yet")
+            .style(Style::default().fg(Theme::text_muted()))
+            .block(Theme::block("Top Tables", fade_progress));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let header = Row::new(vec![Cell::from("Table"), Cell::from("Slow")])
+        .style(Style::default().fg(Theme::warning()));
+    let rows: Vec<Row> = tables
         .iter()
-        .map(|issue| {
-            if issue.description.is_empty() {
-                format!("• {}", issue.title)
+        .enumerate()
+        .map(|(idx, (table, count))| {
+            let style = if idx == selected_table {
+                Style::default()
+                    .fg(Theme::text_primary())
+                    .bg(Theme::surface())
+                    .add_modifier(Modifier::BOLD)
             } else {
-                format!("• {}\n  Query: {}", issue.title, issue.description)
-            }
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(table.clone()),
+                Cell::from(count.to_string()),
+            ])
+            .style(style)
         })
         .collect();
-    let issues_list =
-        Paragraph::new(issues_text.join("\n")).block(Theme::block("Issues", fade_progress));
-
-    let chunks = ratatui::layout::Layout::default()
-        .direction(ratatui::layout::Direction::Vertical)
-        .constraints([
-            ratatui::layout::Constraint::Length(3),
-            ratatui::layout::Constraint::Min(0),
-        ])
-        .split(area);
 
-    f.render_widget(gauge, chunks[0]);
-    f.render_widget(issues_list, chunks[1]);
+    let mut table_state = TableState::default();
+    table_state.select(Some(selected_table));
+
+    let table = Table::new(rows, &[Constraint::Min(10), Constraint::Length(6)])
+        .header(header)
+        .block(Theme::block("Top Tables (Enter for detail)", fade_progress));
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_database_health_shows_the_score_range_and_its_worst_drops() {
+        let mut app = test_app();
+        app.sample_health_score();
+        for _ in 0..10 {
+            app.db_health.analyze_query(
+                r#"SELECT * FROM "orders" WHERE "orders"."state" = 'open'"#,
+                250.0,
+            );
+            app.db_health.analyze_query(
+                r#"SELECT * FROM "line_items" WHERE "line_items"."order_id" = 1"#,
+                300.0,
+            );
+        }
+        // Sampled again once the interval has passed
+        app.sample_health_score();
+        assert_eq!(app.db_health.get_score_history().len(), 1);
+        app.health_sampled_at = Some(Instant::now() - SCORE_SAMPLE_INTERVAL);
+        app.sample_health_score();
+        let range = app.db_health.get_score_range().unwrap();
+        assert!(range.current < range.max);
+
+        app.set_view(ViewMode::DatabaseHealth);
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains(&format!(
+                "min {}  max {}  now {}",
+                range.min, range.max, range.current
+            )),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("Worst Score Drops"), "{}", screen);
+        assert!(
+            screen.contains(&format!(
+                "▼ Score dropped {} points at",
+                range.max - range.current
+            )),
+            "{}",
+            screen
+        );
+        assert!(
+            screen.contains("when 2 new slow queries appeared"),
+            "{}",
+            screen
+        );
+    }
 }
@@ -7,14 +7,18 @@ use ratatui::{
 };
 
 use crate::exception::{ExceptionGroup, ExceptionSeverity, ExceptionTracker};
+use crate::privacy::Privacy;
 use crate::ui::formatting::format_relative_time;
 use crate::ui::theme::Theme;
 
+/// The selected exception group, its endpoints and sample request masked
+/// when `privacy` is on
 pub fn render(
     f: &mut Frame,
     area: Rect,
     exception_tracker: &ExceptionTracker,
     exception_index: usize,
+    privacy: &Privacy,
     fade_progress: Option<f32>,
 ) {
     let groups = exception_tracker.get_grouped_exceptions();
@@ -31,29 +35,33 @@ pub fn render(
     let exception = &group.sample_exception;
     let severity = ExceptionSeverity::from_exception_type(&group.exception_type);
 
+    let header = header_lines(group, severity, privacy, fade_progress);
+
     // Split area into sections
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(8), // Header info
-            Constraint::Min(10),   // Backtrace
+            Constraint::Length(header.len() as u16 + 2), // Header info
+            Constraint::Min(10),                         // Backtrace
         ])
         .split(area);
 
     // Header section with exception details
-    render_header(f, chunks[0], group, severity, fade_progress);
+    render_header(f, chunks[0], header, fade_progress);
 
     // Backtrace section
     render_backtrace(f, chunks[1], exception, fade_progress);
 }
 
-fn render_header(
-    f: &mut Frame,
-    area: Rect,
-    group: &ExceptionGroup,
+/// Endpoints listed in the header; the rest are counted
+const MAX_ENDPOINTS: usize = 4;
+
+fn header_lines<'a>(
+    group: &'a ExceptionGroup,
     severity: ExceptionSeverity,
+    privacy: &Privacy,
     fade_progress: Option<f32>,
-) {
+) -> Vec<Line<'a>> {
     let severity_color = match severity {
         ExceptionSeverity::Critical => Color::Red,
         ExceptionSeverity::High => Color::LightRed,
@@ -61,7 +69,7 @@ fn render_header(
         ExceptionSeverity::Low => Color::Blue,
     };
 
-    let header_text = vec![
+    let mut header_text = vec![
         Line::from(vec![
             Span::styled("Exception: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::styled(
@@ -116,6 +124,52 @@ fn render_header(
         ]),
     ];
 
+    let endpoints = group.top_endpoints();
+    if !endpoints.is_empty() {
+        let mut listed: Vec<String> = endpoints
+            .iter()
+            .take(MAX_ENDPOINTS)
+            .map(|(endpoint, count)| {
+                format!("{} — {}", privacy.text(endpoint), pluralize_times(*count))
+            })
+            .collect();
+        if endpoints.len() > MAX_ENDPOINTS {
+            listed.push(format!("+{} more", endpoints.len() - MAX_ENDPOINTS));
+        }
+        header_text.push(Line::from(vec![
+            Span::styled("Endpoints: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(listed.join(", ")),
+        ]));
+    }
+    let sample = &group.sample_exception;
+    if let Some(context) = &sample.context {
+        let mut request = vec![
+            Span::styled(
+                "Sample request: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(privacy.text(context).into_owned()),
+        ];
+        if let Some(request_id) = &sample.request_id {
+            request.push(Span::styled(
+                format!("  {}", request_id),
+                Style::default().fg(Theme::text_muted()),
+            ));
+        }
+        header_text.push(Line::from(request));
+    }
+    header_text
+}
+
+fn pluralize_times(count: usize) -> String {
+    if count == 1 {
+        "once".to_string()
+    } else {
+        format!("{} times", count)
+    }
+}
+
+fn render_header(f: &mut Frame, area: Rect, header_text: Vec<Line>, fade_progress: Option<f32>) {
     let paragraph = Paragraph::new(header_text)
         .block(
             Theme::block(" Exception Details ", fade_progress).border_style(Style::default().fg(
@@ -174,3 +228,149 @@ fn render_backtrace(
 
     f.render_widget(list, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_exceptions_show_the_endpoints_that_raised_them() {
+        let mut app = test_app();
+        let mut log = |content: &str| {
+            app.add_log(LogLine::new("web", content.to_string()));
+        };
+        for (id, request) in [
+            (
+                "4f1c2a90-7b3d-4c55-9e21-6d8f0a4b2c10",
+                r#"POST "/orders/14""#,
+            ),
+            (
+                "9a2e5b11-7b3d-4c55-9e21-6d8f0a4b2c10",
+                r#"POST "/orders/15""#,
+            ),
+            ("77b0c3d2-7b3d-4c55-9e21-6d8f0a4b2c10", r#"GET "/orders""#),
+        ] {
+            log(&format!("[{}] Started {} for 127.0.0.1", id, request));
+            log(&format!(
+                "[{}] Completed 500 Internal Server Error in 5ms",
+                id
+            ));
+            log("");
+            log("NoMethodError (undefined method `total' for nil):");
+            log("  app/controllers/orders_controller.rb:14:in `create'");
+            log("");
+        }
+
+        let groups = app.exception_tracker.get_grouped_exceptions();
+        let group = groups
+            .iter()
+            .find(|group| group.exception_type == "NoMethodError")
+            .unwrap();
+        assert_eq!(
+            group.top_endpoints(),
+            [("POST /orders/:id", 2), ("GET /orders", 1)]
+        );
+        assert_eq!(
+            group.sample_exception.context.as_deref(),
+            Some("POST /orders/14")
+        );
+        assert_eq!(
+            group.sample_exception.request_id.as_deref(),
+            Some("4f1c2a90-7b3d-4c55-9e21-6d8f0a4b2c10")
+        );
+
+        app.view_mode = ViewMode::Exceptions;
+        let screen = render_app(&app, 140, 30);
+        assert!(
+            screen.contains("NoMethodError  POST /orders/:id"),
+            "{}",
+            screen
+        );
+
+        let index = groups
+            .iter()
+            .position(|group| group.exception_type == "NoMethodError")
+            .unwrap();
+        app.view_mode = ViewMode::ExceptionDetail(index);
+        let screen = render_app(&app, 120, 40);
+        assert!(
+            screen.contains("Endpoints: POST /orders/:id — 2 times, GET /orders — once"),
+            "{}",
+            screen
+        );
+        assert!(
+            screen
+                .contains("Sample request: POST /orders/14  4f1c2a90-7b3d-4c55-9e21-6d8f0a4b2c10"),
+            "{}",
+            screen
+        );
+    }
+
+    #[test]
+    fn test_exceptions_link_to_the_request_their_line_is_tagged_with() {
+        let mut app = test_app();
+        for content in [
+            r#"[req-b] Started POST "/orders/7" for 127.0.0.1"#,
+            r#"[req-a] Started GET "/reports" for 127.0.0.1"#,
+            "[req-b] Completed 500 Internal Server Error in 5ms",
+            "[req-b] ArgumentError (wrong number of arguments):",
+            "",
+            "[req-a] Completed 200 OK in 40ms",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        let groups = app.exception_tracker.get_grouped_exceptions();
+        let group = groups
+            .iter()
+            .find(|group| group.exception_type == "ArgumentError")
+            .unwrap();
+        assert_eq!(
+            group.sample_exception.context.as_deref(),
+            Some("POST /orders/7")
+        );
+        assert_eq!(group.sample_exception.request_id.as_deref(), Some("req-b"));
+        assert_eq!(group.top_endpoints(), [("POST /orders/:id", 1)]);
+    }
+
+    #[test]
+    fn test_privacy_mode_masks_the_requests_exceptions_were_raised_in() {
+        let mut app = test_app().with_privacy(Privacy::new(true, Vec::new()));
+        for content in [
+            r#"Started POST "/users/jane@example.com/reset" for 127.0.0.1"#,
+            "Completed 500 Internal Server Error in 5ms",
+            "NoMethodError (undefined method `token' for nil):",
+            "",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        app.view_mode = ViewMode::Exceptions;
+        let screen = render_app(&app, 140, 30);
+        assert!(screen.contains("NoMethodError  POST /users/"), "{}", screen);
+        assert!(!screen.contains("jane@example.com"), "{}", screen);
+
+        let index = app
+            .exception_tracker
+            .get_grouped_exceptions()
+            .iter()
+            .position(|group| group.sample_exception.message.contains("`token'"))
+            .unwrap();
+        app.view_mode = ViewMode::ExceptionDetail(index);
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Endpoints: POST /users/"), "{}", screen);
+        assert!(
+            screen.contains("Sample request: POST /users/"),
+            "{}",
+            screen
+        );
+        assert!(!screen.contains("jane@example.com"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('P')));
+        assert!(
+            render_app(&app, 120, 40)
+                .contains("Sample request: POST /users/jane@example.com/reset")
+        );
+    }
+}
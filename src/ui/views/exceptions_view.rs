@@ -1,82 +1,469 @@
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Cell, Row, Table, TableState},
+    text::{Line, Span},
+    widgets::Paragraph,
 };
 
-use crate::exception::ExceptionTracker;
+use std::time::Instant;
+
+use crate::context::{Burst, BurstKind};
+use crate::exception::{ExceptionFilter, ExceptionGroup, ExceptionTracker};
+use crate::privacy::Privacy;
+use crate::search::SearchQuery;
+use crate::ui::click_map::{ClickMap, ClickTarget};
+use crate::ui::components::EmptyState;
 use crate::ui::formatting::format_relative_time;
-use crate::ui::theme::Theme;
+use crate::ui::theme::{Icons, Theme};
+use crate::ui::widgets::{Column, SortKey, SortState, SortableTable};
 
+/// Render the exception groups, recording each row on screen in `click_map`
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
     exception_tracker: &ExceptionTracker,
+    bursts: &[Burst],
+    search_query: &str,
+    filter: &ExceptionFilter,
     selected_exception: usize,
+    sort: SortState,
+    privacy: &Privacy,
+    click_map: &mut ClickMap,
     _spinner_frame: usize,
     fade_progress: Option<f32>,
 ) {
+    let area = if bursts.is_empty() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(bursts.len().min(MAX_BURST_LINES) as u16 + 2),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        render_bursts(f, chunks[0], bursts, fade_progress);
+        chunks[1]
+    };
+
     let stats = exception_tracker.get_stats();
-    let groups = exception_tracker.get_grouped_exceptions();
+    let search = SearchQuery::new(search_query);
+    let groups = exception_tracker.get_grouped_exceptions_filtered(&search, filter, Instant::now());
+    let hidden_dismissed = if filter.show_dismissed {
+        0
+    } else {
+        exception_tracker.dismissed_count()
+    };
+
+    if exception_tracker.is_empty() {
+        EmptyState::new(Icons::exception(), "No exceptions")
+            .with_hint("Waiting for Ruby errors in any process's output - none so far")
+            .with_hint("Unhandled errors and their backtraces are grouped by type and message")
+            .with_tip("`/autofocus on` switches here when one is raised")
+            .render(f, area, "Exceptions", fade_progress);
+        return;
+    }
 
     if groups.is_empty() {
         let block = Theme::block("Exceptions", fade_progress);
-        let empty = ratatui::widgets::Paragraph::new("Waiting for exceptions...")
+        let message = if !search.is_empty() {
+            format!("No exceptions matching '{}'", search_query)
+        } else if filter.is_narrowed() {
+            format!(
+                "No exceptions {} - v / h change the filter, c clears it",
+                filter.describe()
+            )
+        } else if hidden_dismissed > 0 {
+            format!(
+                "All {} exception groups dismissed - `/exceptions dismissed` lists them",
+                hidden_dismissed
+            )
+        } else {
+            "Waiting for exceptions...".to_string()
+        };
+        let empty = ratatui::widgets::Paragraph::new(message)
             .style(Style::default().fg(Theme::text_muted()))
             .block(block);
         f.render_widget(empty, area);
         return;
     }
 
-    let header = Row::new(vec![
-        Cell::from("Exception"),
-        Cell::from("Count"),
-        Cell::from("Last Seen"),
-    ])
-    .style(Style::default().fg(Theme::warning()));
+    let rows = table(sort, privacy)
+        .header_style(Style::default().fg(Theme::warning()))
+        .highlight_style(
+            Style::default()
+                .fg(Theme::text_primary())
+                .bg(Theme::surface())
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Theme::block(
+            format!(
+                "Exceptions ({}) - ↑/↓ Navigate, Enter View Details, </> Sort, d Dismiss",
+                listed_count(&groups, stats.total_exceptions, filter, hidden_dismissed)
+            ),
+            fade_progress,
+        ))
+        .render(f, area, &groups, Some(selected_exception));
+    for (position, row) in rows {
+        click_map.add(row, ClickTarget::Exception(position));
+    }
+}
+
+/// `42`, or with groups filtered away `7 of 42 · high and up · 2 dismissed`
+fn listed_count(
+    groups: &[ExceptionGroup],
+    total: usize,
+    filter: &ExceptionFilter,
+    hidden_dismissed: usize,
+) -> String {
+    if !filter.is_narrowed() && hidden_dismissed == 0 {
+        return total.to_string();
+    }
+    let listed: usize = groups.iter().map(|group| group.count).sum();
+    let mut parts = vec![format!("{} of {}", listed, total)];
+    if filter.is_narrowed() {
+        parts.push(filter.describe());
+    }
+    if hidden_dismissed > 0 {
+        parts.push(format!("{} dismissed", hidden_dismissed));
+    }
+    parts.join(" · ")
+}
+
+/// Most frequent first, until `<` / `>` or a number key change it
+pub const DEFAULT_SORT: SortState = SortState::new(1, true);
+
+/// The exception list's columns, ties broken by the most recently seen;
+/// endpoints are masked when `privacy` is on
+pub fn table<'a>(sort: SortState, privacy: &'a Privacy) -> SortableTable<'a, ExceptionGroup> {
+    SortableTable::new(
+        vec![
+            Column::new(
+                "Exception",
+                Constraint::Percentage(60),
+                |group: &ExceptionGroup| exception_cell(group, privacy),
+                |group: &ExceptionGroup| {
+                    SortKey::Text(match &group.location {
+                        Some(location) => format!("{} {}", group.exception_type, location),
+                        None => group.exception_type.clone(),
+                    })
+                },
+            ),
+            Column::new(
+                "Count",
+                Constraint::Percentage(20),
+                |group: &ExceptionGroup| Line::from(group.count.to_string()),
+                |group| SortKey::Number(group.count as f64),
+            )
+            .alignment(Alignment::Right)
+            .descending(),
+            Column::new(
+                "Last Seen",
+                Constraint::Percentage(20),
+                |group: &ExceptionGroup| {
+                    Line::from(format_relative_time(group.last_seen.elapsed()))
+                },
+                |group| SortKey::Number(group.last_seen.elapsed().as_secs_f64()),
+            ),
+        ],
+        sort,
+    )
+    .secondary(2)
+}
+
+/// Grouped by call site: show where, since the type alone repeats; a
+/// dismissed group, when listed, is dimmed
+fn exception_cell<'a>(group: &ExceptionGroup, privacy: &Privacy) -> Line<'a> {
+    let endpoint = group
+        .top_endpoints()
+        .first()
+        .map(|(endpoint, _)| format!("  {}", privacy.text(endpoint)))
+        .unwrap_or_default();
+    if group.dismissed {
+        let text = match &group.location {
+            Some(location) => format!("✓ {}  {}{}", group.exception_type, location, endpoint),
+            None => format!("✓ {}{}", group.exception_type, endpoint),
+        };
+        return Line::styled(text, Style::default().fg(Theme::text_muted()));
+    }
+    let mut spans = vec![Span::raw(group.exception_type.clone())];
+    if let Some(location) = &group.location {
+        spans.push(Span::styled(
+            format!("  {}", location),
+            Style::default()
+                .fg(Theme::info())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if !endpoint.is_empty() {
+        spans.push(Span::styled(
+            endpoint,
+            Style::default().fg(Theme::text_muted()),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Bursts listed above the exceptions; the rest are counted in the title
+const MAX_BURST_LINES: usize = 3;
 
-    let rows: Vec<Row> = groups
+/// Paths answering a burst of 404s / 5xx, as synthetic warnings
+fn render_bursts(f: &mut Frame, area: Rect, bursts: &[Burst], fade_progress: Option<f32>) {
+    let lines: Vec<Line> = bursts
         .iter()
-        .enumerate()
-        .map(|(idx, group)| {
-            let style = if idx == selected_exception {
-                Style::default()
-                    .fg(Theme::text_primary())
-                    .bg(Theme::surface())
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
+        .take(MAX_BURST_LINES)
+        .map(|burst| {
+            let color = match burst.kind {
+                BurstKind::NotFound => Theme::warning(),
+                BurstKind::ServerError => Theme::danger(),
             };
-
-            Row::new(vec![
-                Cell::from(group.exception_type.clone()),
-                Cell::from(group.count.to_string()),
-                Cell::from(format_relative_time(group.last_seen.elapsed())),
-            ])
-            .style(style)
+            Line::styled(
+                format!("⚠ {}", burst.message()),
+                Style::default().fg(Theme::apply_fade_to_color(
+                    color,
+                    fade_progress.unwrap_or(1.0),
+                )),
+            )
         })
         .collect();
+    let title = match bursts.len().saturating_sub(MAX_BURST_LINES) {
+        0 => format!("Warnings ({})", bursts.len()),
+        hidden => format!("Warnings ({}, {} more not shown)", bursts.len(), hidden),
+    };
+    f.render_widget(
+        Paragraph::new(lines).block(Theme::block(title, fade_progress)),
+        area,
+    );
+}
 
-    let mut table_state = TableState::default();
-    table_state.select(Some(selected_exception));
+#[cfg(test)]
+mod tests {
+    use crate::context::DEFAULT_BURST_THRESHOLD;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
 
-    let table = Table::new(
-        rows,
-        &[
-            ratatui::layout::Constraint::Percentage(60),
-            ratatui::layout::Constraint::Percentage(20),
-            ratatui::layout::Constraint::Percentage(20),
-        ],
-    )
-    .header(header)
-    .block(Theme::block(
-        format!(
-            "Exceptions ({}) - ↑/↓ Navigate, Enter View Details",
-            stats.total_exceptions
-        ),
-        fade_progress,
-    ));
-
-    f.render_stateful_widget(table, area, &mut table_state);
+    #[test]
+    fn test_reloaded_exception_grouping_regroups_and_shows_locations() {
+        let mut app = test_app();
+        // Groups of the `total` error raised below, by location
+        let totals = |app: &App| {
+            app.exception_tracker
+                .get_grouped_exceptions()
+                .iter()
+                .filter(|group| group.message_pattern.contains("`total'"))
+                .map(|group| (group.location.clone(), group.count))
+                .collect::<Vec<_>>()
+        };
+        for controller in ["orders", "invoices"] {
+            for content in [
+                "NoMethodError (undefined method `total' for nil):".to_string(),
+                format!(
+                    "  app/controllers/{}_controller.rb:14:in `show'",
+                    controller
+                ),
+                "Completed 500 Internal Server Error in 5ms".to_string(),
+            ] {
+                app.exception_tracker.parse_line(&content);
+            }
+        }
+        app.view_mode = ViewMode::Exceptions;
+        assert_eq!(totals(&app), [(None, 2)]);
+        assert!(!render_app(&app, 120, 30).contains("orders_controller.rb"));
+
+        let config: crate::config::CabooseConfig =
+            toml::from_str("[exceptions]\nfingerprint_with_location = true\n").unwrap();
+        app.apply_reloaded_config(Ok(config));
+        assert!(matches!(
+            &app.last_command_result,
+            Some(command::ExecutionResult::Success(message)) if message.contains("regrouped")
+        ));
+
+        assert_eq!(totals(&app).len(), 2);
+        let screen = render_app(&app, 120, 30);
+        assert!(
+            screen.contains("NoMethodError  app/controllers/orders_controller.rb:14"),
+            "{}",
+            screen
+        );
+        assert!(
+            screen.contains("app/controllers/invoices_controller.rb:14"),
+            "{}",
+            screen
+        );
+    }
+
+    #[test]
+    fn test_exceptions_resort_keeping_the_selection_and_drill_down() {
+        let mut app = test_app();
+        for (exception, times) in [
+            ("ArgumentError (wrong number of arguments):", 3),
+            ("ZeroDivisionError (divided by 0):", 1),
+            ("NameError (uninitialized constant Foo):", 2),
+        ] {
+            for _ in 0..times {
+                app.exception_tracker.parse_line(exception);
+                app.exception_tracker
+                    .parse_line("Completed 500 Internal Server Error in 5ms");
+            }
+        }
+        app.view_mode = ViewMode::Exceptions;
+        let order = |app: &App| {
+            app.matching_exceptions()
+                .into_iter()
+                .map(|group| group.exception_type)
+                .collect::<Vec<_>>()
+        };
+        // test_app's NoMethodError ties with ZeroDivisionError; the later seen goes first
+        assert_eq!(
+            order(&app),
+            [
+                "ArgumentError",
+                "NameError",
+                "ZeroDivisionError",
+                "NoMethodError"
+            ]
+        );
+        assert!(render_app(&app, 120, 30).contains("Count ▼"));
+
+        app.select_next_exception();
+        app.select_next_exception();
+        // By exception, then reversed; ZeroDivisionError stays selected
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('1')));
+        assert_eq!(app.selected_exception, 3);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('1')));
+        assert_eq!(
+            order(&app),
+            [
+                "ZeroDivisionError",
+                "NoMethodError",
+                "NameError",
+                "ArgumentError"
+            ]
+        );
+        assert_eq!(app.selected_exception, 0);
+        let screen = render_app(&app, 120, 30);
+        assert!(screen.contains("Exception ▼"), "{}", screen);
+        assert!(!screen.contains("Count ▼"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        let ViewMode::ExceptionDetail(index) = app.view_mode else {
+            panic!("expected Exception Detail, got {:?}", app.view_mode);
+        };
+        assert_eq!(
+            app.exception_tracker.get_grouped_exceptions()[index].exception_type,
+            "ZeroDivisionError"
+        );
+
+        app.view_mode = ViewMode::Exceptions;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('>')));
+        assert_eq!(order(&app)[0], "ArgumentError");
+        assert_eq!(app.selected_exception, 2);
+    }
+
+    #[test]
+    fn test_exceptions_filter_by_severity_and_dismissal() {
+        let mut app = test_app();
+        for exception in [
+            r#"ActionController::RoutingError (No route matches [GET] "/favicon.ico"):"#,
+            "SystemStackError (stack level too deep):",
+        ] {
+            app.exception_tracker.parse_line(exception);
+            app.exception_tracker.parse_line("");
+        }
+        app.view_mode = ViewMode::Exceptions;
+        let listed = |app: &App| {
+            let mut types: Vec<String> = app
+                .matching_exceptions()
+                .into_iter()
+                .map(|group| group.exception_type)
+                .collect();
+            types.sort();
+            types
+        };
+        assert_eq!(listed(&app).len(), 3);
+        assert!(render_app(&app, 140, 30).contains("Exceptions (3)"));
+
+        // Medium and up, then high and up
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('v')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('v')));
+        assert_eq!(listed(&app), ["NoMethodError", "SystemStackError"]);
+        let screen = render_app(&app, 140, 30);
+        assert!(
+            screen.contains("Exceptions (2 of 3 · high and up)"),
+            "{}",
+            screen
+        );
+
+        // Dismissing the last one listed moves the selection up
+        app.select_next_exception();
+        let dismissed = app.matching_exceptions()[1].exception_type.clone();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('d')));
+        assert_eq!(listed(&app).len(), 1);
+        assert_eq!(app.selected_exception, 0);
+        let screen = render_app(&app, 140, 30);
+        assert!(
+            screen.contains("Exceptions (1 of 3 · high and up · 1 dismissed)"),
+            "{}",
+            screen
+        );
+
+        // `c` clears severity and recency; the dismissed group stays hidden
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('h')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('c')));
+        assert_eq!(listed(&app).len(), 2);
+        assert!(!listed(&app).contains(&dismissed));
+
+        app.enter_command_mode();
+        app.command_input = "/exceptions dismissed".to_string();
+        app.execute_command();
+        assert!(listed(&app).contains(&dismissed));
+        app.command_input = "/exceptions critical".to_string();
+        app.execute_command();
+        assert_eq!(listed(&app), ["SystemStackError"]);
+        app.command_input = "/exceptions recent 0".to_string();
+        app.execute_command();
+        assert!(matches!(
+            &app.last_command_result,
+            Some(command::ExecutionResult::Error(message)) if message.contains("above 0")
+        ));
+    }
+
+    #[test]
+    fn test_404_burst_is_logged_once_and_shown_in_header_and_exceptions() {
+        let mut app = test_app();
+        for id in 0..=DEFAULT_BURST_THRESHOLD {
+            for content in [
+                format!(r#"Started GET "/api/v2/users/{}" for 127.0.0.1"#, id),
+                format!(
+                    r#"ActionController::RoutingError (No route matches [GET] "/api/v2/users/{}"):"#,
+                    id
+                ),
+            ] {
+                app.add_log(LogLine::new("web", content));
+            }
+        }
+        app.check_bursts();
+        app.check_bursts();
+
+        let warnings: Vec<_> = app
+            .logs
+            .iter()
+            .filter(|log| log.content.starts_with("[burst]"))
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].content,
+            "[burst] /api/v2/users/:id returning 404 ×21 in 2m — route missing?"
+        );
+
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("/api/v2/users/:id 404 ×21"), "{}", screen);
+
+        app.view_mode = ViewMode::Exceptions;
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("Warnings (1)"), "{}", screen);
+        assert!(screen.contains("route missing?"), "{}", screen);
+    }
 }
@@ -0,0 +1,245 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Cell, Paragraph, Row, Table},
+};
+
+use crate::jobs::{JobClassStats, JobFailure, JobTracker};
+use crate::search::SearchQuery;
+use crate::ui::components::EmptyState;
+use crate::ui::formatting::{format_ms, format_relative_time, truncate};
+use crate::ui::theme::{Icons, Theme};
+
+/// Failures listed under the overview
+pub const FAILURES_LISTED: usize = 8;
+
+/// Render the background jobs view: runs per job class, then the latest
+/// failures
+///
+/// A non-empty `search_query` keeps the classes and failures whose class,
+/// queue or error match.
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    job_tracker: &JobTracker,
+    search_query: &str,
+    fade_progress: Option<f32>,
+) {
+    if job_tracker.is_empty() {
+        EmptyState::new(Icons::job(), "No jobs yet")
+            .with_hint("Waiting for ActiveJob or Sidekiq lines from any process")
+            .with_hint("Runs, durations, retries and failures are counted per job class")
+            .with_tip("add a worker such as `worker: bundle exec sidekiq` to your Procfile")
+            .render(f, area, "Jobs", fade_progress);
+        return;
+    }
+
+    let search = SearchQuery::new(search_query);
+    let classes: Vec<JobClassStats> = job_tracker
+        .get_class_stats()
+        .into_iter()
+        .filter(|stats| {
+            search.matches_any(
+                [
+                    Some(stats.class.as_str()),
+                    stats.queue.as_deref(),
+                    stats.last_error.as_deref(),
+                ]
+                .into_iter()
+                .flatten(),
+            )
+        })
+        .collect();
+    let failures: Vec<JobFailure> = job_tracker
+        .recent_failures(crate::jobs::MAX_RECENT_FAILURES)
+        .into_iter()
+        .filter(|failure| {
+            search.matches_any(
+                [
+                    Some(failure.class.as_str()),
+                    failure.queue.as_deref(),
+                    failure.error.as_deref(),
+                ]
+                .into_iter()
+                .flatten(),
+            )
+        })
+        .take(FAILURES_LISTED)
+        .collect();
+
+    let (overview_area, failures_area) = if failures.is_empty() {
+        (area, None)
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(failures.len() as u16 + 2),
+            ])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    };
+
+    render_overview(
+        f,
+        overview_area,
+        &classes,
+        job_tracker.running(),
+        fade_progress,
+    );
+    if let Some(failures_area) = failures_area {
+        render_failures(f, failures_area, &failures, fade_progress);
+    }
+}
+
+/// "Jobs (3 classes, 120 runs, 4 failed, 1 running)" above a row per class
+fn render_overview(
+    f: &mut Frame,
+    area: Rect,
+    classes: &[JobClassStats],
+    running: usize,
+    fade_progress: Option<f32>,
+) {
+    let runs: usize = classes.iter().map(|stats| stats.runs).sum();
+    let failed: usize = classes.iter().map(|stats| stats.failures).sum();
+    let title = format!(
+        "Jobs ({} class{}, {} run{}, {} failed, {} running)",
+        classes.len(),
+        if classes.len() == 1 { "" } else { "es" },
+        runs,
+        if runs == 1 { "" } else { "s" },
+        failed,
+        running
+    );
+
+    let header = Row::new([
+        "Job",
+        "Queue",
+        "Runs",
+        "Avg",
+        "Failed",
+        "Retries",
+        "Last error",
+    ])
+    .style(
+        Style::default()
+            .fg(Theme::text_secondary())
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = classes
+        .iter()
+        .map(|stats| {
+            let failed_style = if stats.failures > 0 {
+                Style::default().fg(Theme::danger())
+            } else {
+                Style::default().fg(Theme::text_muted())
+            };
+            Row::new(vec![
+                Cell::from(stats.class.clone()),
+                Cell::from(stats.queue.clone().unwrap_or_default()),
+                Cell::from(stats.runs.to_string()),
+                Cell::from(stats.avg_duration_ms().map(format_ms).unwrap_or_default()),
+                Cell::from(stats.failures.to_string()).style(failed_style),
+                Cell::from(stats.retries.to_string()),
+                Cell::from(stats.last_error.clone().unwrap_or_default())
+                    .style(Style::default().fg(Theme::text_muted())),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(Theme::block(title, fade_progress));
+    f.render_widget(table, area);
+}
+
+/// "3 mins ago  HardWorker (default)  RuntimeError: boom", newest first
+fn render_failures(f: &mut Frame, area: Rect, failures: &[JobFailure], fade_progress: Option<f32>) {
+    let lines: Vec<Line> = failures
+        .iter()
+        .map(|failure| {
+            let queue = failure
+                .queue
+                .as_ref()
+                .map(|queue| format!(" ({})", queue))
+                .unwrap_or_default();
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>12}  ", format_relative_time(failure.at.elapsed())),
+                    Style::default().fg(Theme::text_muted()),
+                ),
+                Span::styled(
+                    format!("{}{}", failure.class, queue),
+                    Style::default().fg(Theme::danger()),
+                ),
+                Span::raw("  "),
+                Span::raw(truncate(
+                    failure.error.as_deref().unwrap_or("(no error logged)"),
+                    200,
+                )),
+            ])
+        })
+        .collect();
+
+    f.render_widget(
+        Paragraph::new(lines).block(Theme::block(
+            format!("Recent Failures ({})", failures.len()),
+            fade_progress,
+        )),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_jobs_view_lists_job_classes_and_recent_failures() {
+        let mut app = test_app();
+        for content in [
+            "[ActiveJob] [ReportJob] [a1] Performing ReportJob (Job ID: a1) from Sidekiq(reports)",
+            "[ActiveJob] [ReportJob] [a1] Performed ReportJob (Job ID: a1) from Sidekiq(reports) in 120.0ms",
+            "2024-01-15T10:30:46.000Z pid=1 tid=b class=HardWorker jid=c0ffee00 INFO: start",
+            "2024-01-15T10:30:46.010Z pid=1 tid=b class=HardWorker jid=c0ffee00 elapsed=0.01 INFO: fail",
+            "2024-01-15T10:30:46.011Z pid=1 tid=b WARN: RuntimeError: card declined",
+        ] {
+            app.add_log(LogLine::new("worker", content.to_string()));
+        }
+
+        app.command_input = "/view jobs".to_string();
+        app.execute_command();
+        assert_eq!(app.view_mode, ViewMode::Jobs);
+        let screen = render_app(&app, 140, 30);
+        assert!(
+            screen.contains("Jobs (2 classes, 2 runs, 1 failed, 0 running)"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("ReportJob"), "{}", screen);
+        assert!(screen.contains("120.0ms"), "{}", screen);
+        assert!(screen.contains("Recent Failures (1)"), "{}", screen);
+        assert!(screen.contains("HardWorker"), "{}", screen);
+        assert!(screen.contains("RuntimeError: card declined"), "{}", screen);
+
+        app.search_queries
+            .insert(ViewMode::Jobs, "report".to_string());
+        let screen = render_app(&app, 140, 30);
+        assert!(screen.contains("Jobs (1 class, 1 run"), "{}", screen);
+        assert!(!screen.contains("Recent Failures"), "{}", screen);
+    }
+}
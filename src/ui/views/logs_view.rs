@@ -1,66 +1,179 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Clear, List, ListItem, Paragraph},
+    widgets::{Clear, List, ListItem, ListState, Paragraph},
 };
 
-use crate::process::{LogLine, ProcessInfo, ProcessStatus};
-use crate::ui::components::ScrollIndicator;
-use crate::ui::formatting::format_duration;
+use std::borrow::Cow;
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
+
+use crate::clock::Suspensions;
+use crate::frontend::{BuildState, FrontendTracker};
+use crate::privacy::Privacy;
+use crate::process::{
+    BootTimes, LogLevel, LogLine, ProcessFilter, ProcessInfo, ProcessStatus, TASK_PREFIX, is_task,
+};
+use crate::search::SearchFilter;
+use crate::time_format::DisplayZone;
+use crate::time_range::TimeRange;
+use crate::ui::annotations::Annotator;
+use crate::ui::components::log_colors::content_spans;
+use crate::ui::components::{
+    EmptyState, LogChips, LogColors, LogOrder, LogViewport, ScrollIndicator,
+};
+use crate::ui::formatting::{
+    format_ms, highlight_ranges, skip_display_columns, spans_width, take_display_columns,
+};
 use crate::ui::theme::{Icons, Theme};
+use crate::ui::widgets::Sparkline;
 
 /// Render the logs view
+///
+/// Returns the maximum horizontal scroll offset for the lines currently on
+/// screen, so the caller can stop scrolling right once the longest line ends.
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: ratatui::layout::Rect,
     processes: &[ProcessInfo],
+    boot_times: &BootTimes,
+    frontend: &FrontendTracker,
+    suspensions: &Suspensions,
+    now: Instant,
     logs: &[LogLine],
     _search_mode: bool,
-    search_query: &str,
-    log_scroll: usize,
+    search: &SearchFilter,
+    viewport: &LogViewport,
+    order: LogOrder,
+    colors: LogColors,
     horizontal_scroll: usize,
     auto_scroll: bool,
-    filter_process: &Option<String>,
+    filter_process: Option<&ProcessFilter>,
+    min_level: Option<LogLevel>,
+    chips: &LogChips,
+    time_range: Option<&TimeRange>,
+    display_zone: DisplayZone,
+    privacy: &Privacy,
+    annotations: Option<&Annotator>,
+    show_processes: bool,
+    selected_process: Option<usize>,
     spinner_frame: usize,
     fade_progress: Option<f32>,
-) {
+) -> usize {
     // Clear full area to avoid artifacts bleeding between panels/spinner frames
     f.render_widget(Clear, area);
 
     // Split horizontally: processes panel (left) and logs panel (right)
     // Process panel is 30 chars wide (28 usable after borders)
     // Content must fit: Icon(1) + Space(1) + Name(10) + Space(1) + Uptime(7) = ~20 chars
+    // A second line per process charts its boot times once one is known
+    let logs_area = if show_processes {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Min(0)])
+            .split(area);
+        render_processes(
+            f,
+            chunks[0],
+            processes,
+            boot_times,
+            frontend,
+            suspensions,
+            now,
+            selected_process,
+        );
+        chunks[1]
+    } else {
+        area
+    };
+
+    // Chip bar on the first row, above the logs panel's border
     let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(30), Constraint::Min(0)])
-        .split(area);
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(logs_area);
+    chips.render(f, chunks[0]);
 
-    render_processes(f, chunks[0], processes);
     render_logs(
         f,
         chunks[1],
         logs,
-        log_scroll,
+        viewport,
+        order,
+        colors,
         horizontal_scroll,
         auto_scroll,
-        search_query,
+        search,
         filter_process,
+        min_level,
+        chips,
+        time_range,
+        display_zone,
+        privacy,
+        annotations,
         spinner_frame,
         fade_progress,
-    );
+    )
+}
+
+/// Gutter color of a line at `level`, and of the footer's `/level` status
+pub fn level_color(level: Option<LogLevel>) -> Color {
+    match level {
+        Some(LogLevel::Error) => Theme::danger(),
+        Some(LogLevel::Warn) => Theme::warning(),
+        Some(LogLevel::Info) => Theme::info(),
+        Some(LogLevel::Debug) | None => Theme::text_muted(),
+    }
 }
 
-fn render_processes(f: &mut Frame, area: ratatui::layout::Rect, processes: &[ProcessInfo]) {
+/// How a search's matches stand out in the lines around them
+fn match_style() -> Style {
+    Style::default()
+        .fg(Theme::background())
+        .bg(Theme::warning())
+        .add_modifier(Modifier::BOLD)
+}
+
+/// Width of the boot-time sparkline under each process, labels included
+const BOOT_SPARKLINE_WIDTH: usize = 20;
+
+/// Content columns a line keeps before its annotation is dropped instead
+const MIN_ANNOTATED_CONTENT: usize = 20;
+
+#[allow(clippy::too_many_arguments)]
+fn render_processes(
+    f: &mut Frame,
+    area: ratatui::layout::Rect,
+    processes: &[ProcessInfo],
+    boot_times: &BootTimes,
+    frontend: &FrontendTracker,
+    suspensions: &Suspensions,
+    now: Instant,
+    selected: Option<usize>,
+) {
     let process_items: Vec<ListItem> = processes
         .iter()
         .map(|p| {
+            // A finished task shows how it went rather than being stopped
+            let task_exit = p
+                .last_exit
+                .as_ref()
+                .filter(|_| is_task(&p.name) && p.status != ProcessStatus::Running);
+
             // Get status icon
             let (status_icon, status_color) = match p.status {
+                _ if task_exit.is_some_and(|exit| exit.success) => {
+                    (Icons::success(), Theme::success())
+                }
+                _ if task_exit.is_some() => (Icons::error(), Theme::danger()),
                 ProcessStatus::Running => (Icons::running(), Theme::success()),
                 ProcessStatus::Stopped => (Icons::stopped(), Theme::text_muted()),
                 ProcessStatus::Crashed => (Icons::error(), Theme::danger()),
+                ProcessStatus::Detached => (Icons::warning(), Theme::warning()),
+                ProcessStatus::External => (Icons::info(), Theme::info()),
             };
 
             // Get process type icon based on name
@@ -68,19 +181,32 @@ fn render_processes(f: &mut Frame, area: ratatui::layout::Rect, processes: &[Pro
                 "web" | "rails" => "🌐",
                 "angular" | "frontend" | "ui" => "⚡",
                 "worker" | "sidekiq" => "⚙️",
+                name if is_task(name) => "▶",
                 _ => "▪",
             };
 
-            let uptime = p.start_time.map_or("--".to_string(), |start| {
-                let elapsed = start.elapsed().as_secs();
-                format_duration(elapsed)
-            });
+            // Adopted processes were started elsewhere, at a time we don't know
+            let uptime = if let Some(exit) = task_exit {
+                match exit.code {
+                    _ if exit.success => "ok".to_string(),
+                    Some(code) => format!("exit {}", code),
+                    None => "killed".to_string(),
+                }
+            } else if p.status == ProcessStatus::External {
+                "ext".to_string()
+            } else {
+                p.start_time.map_or("--".to_string(), |start| {
+                    suspensions.uptime(start, now).label()
+                })
+            };
 
+            // Tasks are told apart by their icon, leaving room for the name
+            let name = p.name.strip_prefix(TASK_PREFIX).unwrap_or(&p.name);
             // Truncate process name if needed to fit in panel (max 10 chars)
-            let display_name = if p.name.len() > 10 {
-                format!("{}…", &p.name[..9])
+            let display_name = if name.len() > 10 {
+                format!("{}…", &name[..9])
             } else {
-                p.name.clone()
+                name.to_string()
             };
 
             // Compact layout with both status and process type icons
@@ -108,85 +234,185 @@ fn render_processes(f: &mut Frame, area: ratatui::layout::Rect, processes: &[Pro
                 ),
             ]);
 
-            ListItem::new(content)
+            let mut lines = vec![content];
+            if p.name == frontend.process() && frontend.is_active() {
+                lines.extend(frontend_lines(frontend, now));
+            }
+            let boots = boot_times.history(&p.name);
+            if boots.is_empty() {
+                return ListItem::new(lines);
+            }
+            let mut boot_line = Sparkline::new(&boots)
+                .width(BOOT_SPARKLINE_WIDTH)
+                .labels(|secs| format!("{:.1}s", secs))
+                .style(Style::default().fg(Theme::text_muted()))
+                .highlight_last(Theme::info())
+                .line();
+            boot_line.spans.insert(
+                0,
+                Span::styled("   boot ", Style::default().fg(Theme::text_muted())),
+            );
+            lines.push(boot_line);
+            ListItem::new(lines)
         })
         .collect();
 
-    let processes_widget = List::new(process_items).block(
-        Theme::block("  Processes  ", None) // No fade on process list for now
-            .border_style(Style::default().fg(Theme::text_muted())),
-    );
+    // With the keys (Tab), the border lights up and the cursor shows
+    let border = if selected.is_some() {
+        Theme::primary()
+    } else {
+        Theme::text_muted()
+    };
+    let processes_widget = List::new(process_items)
+        .block(
+            Theme::block("  Processes  ", None) // No fade on process list for now
+                .border_style(Style::default().fg(border)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Theme::text_primary())
+                .bg(Theme::surface())
+                .add_modifier(Modifier::BOLD),
+        );
+    let mut state = ListState::default().with_selected(selected);
 
     // Clear in case a spinner or other overlay was previously occupying this area
     f.render_widget(Clear, area);
-    f.render_widget(processes_widget, area);
+    f.render_stateful_widget(processes_widget, area, &mut state);
+}
+
+/// The frontend's builds under its process: how the last one went, then hot
+/// updates and where the dev server listens
+///
+/// ```text
+///    ✓ 312ms  avg 1.10s
+///    ↻ 4 hmr/min  :5173
+/// ```
+fn frontend_lines(frontend: &FrontendTracker, now: Instant) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(Theme::text_muted());
+    let build = match frontend.state() {
+        BuildState::Failed => Line::from(vec![
+            Span::styled("   ✘ ", Style::default().fg(Theme::danger())),
+            Span::styled(
+                frontend
+                    .errors()
+                    .last()
+                    .cloned()
+                    .unwrap_or_else(|| "build failed".to_string()),
+                Style::default().fg(Theme::danger()),
+            ),
+        ]),
+        BuildState::Building => Line::styled("   … building", Style::default().fg(Theme::info())),
+        _ => {
+            let mut spans = vec![Span::styled("   ✓", Style::default().fg(Theme::success()))];
+            if let Some(last) = frontend.last_build_time() {
+                spans.push(Span::styled(
+                    format!(" {}", format_ms(last.as_secs_f64() * 1000.0)),
+                    Style::default().fg(Theme::success()),
+                ));
+            }
+            if let Some(average) = frontend.average_build_time() {
+                spans.push(Span::styled(
+                    format!("  avg {}", format_ms(average.as_secs_f64() * 1000.0)),
+                    muted,
+                ));
+            }
+            Line::from(spans)
+        }
+    };
+    let mut lines = vec![build];
+    let hot_updates = frontend.hot_updates_per_minute(now);
+    let mut details = Vec::new();
+    if hot_updates > 0 {
+        details.push(format!("↻ {} hmr/min", hot_updates));
+    }
+    if let Some(port) = frontend.port() {
+        details.push(format!(":{}", port));
+    }
+    if !details.is_empty() {
+        lines.push(Line::styled(format!("   {}", details.join("  ")), muted));
+    }
+    lines
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_logs(
     f: &mut Frame,
     area: ratatui::layout::Rect,
     logs: &[LogLine],
-    log_scroll: usize,
+    viewport: &LogViewport,
+    order: LogOrder,
+    colors: LogColors,
     horizontal_scroll: usize,
     auto_scroll: bool,
-    search_query: &str,
-    filter_process: &Option<String>,
+    search: &SearchFilter,
+    filter_process: Option<&ProcessFilter>,
+    min_level: Option<LogLevel>,
+    chips: &LogChips,
+    time_range: Option<&TimeRange>,
+    display_zone: DisplayZone,
+    privacy: &Privacy,
+    annotations: Option<&Annotator>,
     _spinner_frame: usize,
     fade_progress: Option<f32>,
-) {
-    // If there are no logs at all, show a loading spinner
+) -> usize {
     if logs.is_empty() {
-        f.render_widget(Clear, area);
-        let block = Theme::block("Logs", fade_progress).border_style(Style::default().fg(
-            Theme::apply_fade_to_color(Theme::text_muted(), fade_progress.unwrap_or(1.0)),
-        ));
-        let empty = Paragraph::new("Waiting for logs...").block(block);
-        f.render_widget(empty, area);
-        return;
+        EmptyState::new(Icons::logs(), "No output yet")
+            .with_hint("Waiting for the processes in your Procfile to print something")
+            .with_hint("Every line they write to STDOUT or STDERR shows here as it arrives")
+            .with_tip("`/filter web` shows one process, `/filter !frontend` hides one")
+            .render(f, area, "Logs", fade_progress);
+        return 0;
     }
 
+    // Only the `/around` window, found by binary search on the time-ordered buffer
+    let logs = match time_range {
+        Some(range) => range.slice(logs),
+        None => logs,
+    };
+
     // Filter logs
     let mut filtered: Vec<&LogLine> = if let Some(filter) = filter_process {
         logs.iter()
-            .filter(|log| &log.process_name == filter)
+            .filter(|log| filter.matches(&log.process_name))
             .collect()
     } else {
         logs.iter().collect()
     };
+    if chips.is_filtering() {
+        filtered.retain(|log| !chips.hides(log.category));
+    }
+    if let Some(min_level) = min_level {
+        filtered.retain(|log| min_level.admits(log.level));
+    }
 
     // Apply search filter
-    if !search_query.is_empty() {
-        let query = search_query.to_lowercase();
-        filtered.retain(|log| log.content.to_lowercase().contains(&query));
+    if !search.is_empty() {
+        filtered.retain(|log| search.matches(log));
     }
 
     let total_logs = filtered.len();
     let visible_height = area.height.saturating_sub(2) as usize;
-    let start_idx = if auto_scroll {
-        total_logs.saturating_sub(visible_height.max(1))
-    } else {
-        log_scroll.min(total_logs.saturating_sub(visible_height))
-    };
+    viewport.set_height(visible_height);
+    let seqs: Vec<u64> = filtered.iter().map(|log| log.seq).collect();
+    let start_idx = viewport.start_index(&seqs, auto_scroll);
 
-    let h_scroll = horizontal_scroll; // Capture for use in closure
-    let log_lines: Vec<Line> = filtered
+    let visible_width = area.width.saturating_sub(2) as usize;
+    let mut visible_logs: Vec<&LogLine> = filtered
         .iter()
         .skip(start_idx)
         .take(visible_height.max(1))
+        .copied()
+        .collect();
+    // The viewport works on the chronological buffer; newest-first only flips the page
+    if order == LogOrder::NewestFirst {
+        visible_logs.reverse();
+    }
+
+    // Build prefix and content spans separately: only content scrolls horizontally
+    let rows: Vec<(Vec<Span>, Vec<Span>, Option<String>)> = visible_logs
+        .iter()
         .map(|log| {
-            // Apply horizontal scrolling to the content
-            // IMPORTANT: Use char-based operations to avoid UTF-8 boundary panics
-            let char_count = log.content.chars().count();
-            let scrolled_content: String = if h_scroll > 0 && h_scroll < char_count {
-                // Skip h_scroll characters safely
-                log.content.chars().skip(h_scroll).collect()
-            } else if h_scroll >= char_count {
-                // Scrolled past the end
-                String::new()
-            } else {
-                // No scroll
-                log.content.clone()
-            };
             // Check for Rails-specific errors first for prominent highlighting
             let is_rails_error = log.content.to_lowercase().contains("pending migration")
                 || (log.content.to_lowercase().contains("database")
@@ -228,30 +454,119 @@ fn render_logs(
                 "web" | "rails" => "🌐",
                 "angular" | "frontend" | "ui" => "⚡",
                 "worker" | "sidekiq" => "⚙️",
+                name if is_task(name) => "▶",
                 _ => "▪",
             };
 
-            Line::from(vec![
+            // A gutter colored by level; blank for lines that show none
+            let prefix = vec![
+                Span::styled(
+                    if log.level.is_some() { "▎" } else { " " },
+                    Style::default().fg(level_color(log.level)),
+                ),
                 Span::styled(
                     format!("[{}] ", log.process_name),
                     Style::default().fg(process_name_color(&log.process_name)),
                 ),
                 Span::raw(process_icon),
                 Span::raw(" "),
-                Span::styled(scrolled_content, content_style),
-            ])
+            ];
+            // Masking moves text around, so the tool's colors can't be kept
+            let content = match privacy.log_line(&log.content) {
+                Cow::Owned(masked) if masked != log.content => {
+                    vec![Span::styled(masked, content_style)]
+                }
+                _ => content_spans(log, content_style, colors),
+            };
+            // Matched on the text as shown, which masking may have changed
+            let content = if search.is_empty() {
+                content
+            } else {
+                let text: String = content.iter().map(|span| span.content.as_ref()).collect();
+                highlight_ranges(content, &search.highlight_ranges(&text), match_style())
+            };
+            let annotation = annotations.and_then(|annotations| annotations.annotation(log.seq));
+            (prefix, content, annotation)
+        })
+        .collect();
+
+    // Annotations sit at the right edge, a column clear of the content, which
+    // is clipped to make room for them; on a narrow panel they're left out
+    let rows: Vec<_> = rows
+        .into_iter()
+        .map(|(prefix, content, annotation)| {
+            let room = visible_width.saturating_sub(spans_width(&prefix));
+            let annotation = annotation
+                .filter(|annotation| room >= annotation.width() + 1 + MIN_ANNOTATED_CONTENT);
+            let content_room = match &annotation {
+                Some(annotation) => room - annotation.width() - 1,
+                None => room,
+            };
+            (prefix, content, annotation, content_room)
         })
         .collect();
 
-    let _scroll_indicator = ScrollIndicator::new(start_idx, total_logs, visible_height);
+    // Clamp to the longest visible line so scrolling right can't run into empty space
+    let max_h_scroll = rows
+        .iter()
+        .map(|(_, content, _, room)| spans_width(content).saturating_sub(*room))
+        .max()
+        .unwrap_or(0);
+    let h_scroll = horizontal_scroll.min(max_h_scroll);
+
+    let log_lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(mut prefix, content, annotation, room)| {
+            let content = skip_display_columns(content, h_scroll);
+            match annotation {
+                Some(annotation) => {
+                    let content = take_display_columns(content, room);
+                    let padding = room - spans_width(&content) + 1;
+                    prefix.extend(content);
+                    prefix.push(Span::raw(" ".repeat(padding)));
+                    prefix.push(Span::styled(
+                        annotation,
+                        Style::default().fg(Theme::text_muted()),
+                    ));
+                }
+                None => prefix.extend(content),
+            }
+            Line::from(prefix)
+        })
+        .collect();
 
-    let log_title = if let Some(filter) = filter_process {
+    let position = match order {
+        LogOrder::OldestFirst => start_idx,
+        LogOrder::NewestFirst => total_logs.saturating_sub(visible_height) - start_idx,
+    };
+    let _scroll_indicator = ScrollIndicator::new(position, total_logs, visible_height);
+
+    let mut log_title = if let Some(filter) = filter_process {
         format!(" Logs (Filtered by {})", filter)
-    } else if !search_query.is_empty() {
-        format!(" Logs (Search: {})", search_query)
+    } else if !search.query().is_empty() {
+        format!(" Logs (Search: {})", search.query())
     } else {
         " Logs ".to_string()
     };
+    if let Some(range) = time_range {
+        log_title = format!("{} ⏱ {} ", log_title.trim_end(), range.label(display_zone));
+    }
+    if h_scroll > 0 {
+        log_title = format!("{} ← col {} ", log_title.trim_end(), h_scroll);
+    }
+    if !auto_scroll {
+        if viewport.anchor_evicted() {
+            log_title = format!("{} ⚠ older lines dropped ", log_title.trim_end());
+        }
+        let new_lines = viewport.new_lines_below(&seqs);
+        if new_lines > 0 {
+            let note = match order {
+                LogOrder::OldestFirst => format!("↓ {} new lines below", new_lines),
+                LogOrder::NewestFirst => format!("↑ {} new lines above", new_lines),
+            };
+            log_title = format!("{} {} ", log_title.trim_end(), note);
+        }
+    }
 
     let logs_widget = Paragraph::new(log_lines).block(
         Theme::block(log_title, fade_progress).border_style(Style::default().fg(
@@ -265,6 +580,8 @@ fn render_logs(
     // Clear before rendering to prevent artifacts when content shrinks (e.g., spinner to list)
     f.render_widget(Clear, area);
     f.render_widget(logs_widget, area);
+
+    max_h_scroll
 }
 
 fn process_name_color(name: &str) -> ratatui::style::Color {
@@ -279,3 +596,418 @@ fn process_name_color(name: &str) -> ratatui::style::Color {
     let hash: usize = name.bytes().map(|b| b as usize).sum();
     colors[hash % colors.len()]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn line(seq: u64) -> LogLine {
+        LogLine {
+            seq,
+            ..LogLine::new("web", format!("line {}", seq))
+        }
+    }
+
+    fn screen(logs: &[LogLine], viewport: &LogViewport) -> String {
+        screen_in_order(logs, viewport, LogOrder::OldestFirst)
+    }
+
+    fn screen_in_order(logs: &[LogLine], viewport: &LogViewport, order: LogOrder) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(40, 7)).unwrap();
+        terminal
+            .draw(|f| {
+                render_logs(
+                    f,
+                    f.area(),
+                    logs,
+                    viewport,
+                    order,
+                    LogColors::Themed,
+                    0,
+                    false,
+                    &SearchFilter::default(),
+                    None,
+                    None,
+                    &LogChips::new(),
+                    None,
+                    DisplayZone::Local,
+                    &Privacy::default(),
+                    None,
+                    0,
+                    None,
+                );
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (1..6)
+            .map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_scrolled_view_keeps_content_while_lines_are_evicted() {
+        let mut logs: Vec<LogLine> = (1..=50).map(line).collect();
+        let mut viewport = LogViewport::new();
+        screen(&logs, &viewport);
+
+        let seqs: Vec<u64> = logs.iter().map(|log| log.seq).collect();
+        viewport.scroll(&seqs, true, -20);
+        let before = screen(&logs, &viewport);
+        assert!(before.contains("line 26"));
+
+        // Ten new lines arrive into a full buffer
+        for seq in 51..=60 {
+            logs.push(line(seq));
+            logs.remove(0);
+            viewport.lines_evicted(logs.first().map(|log| log.seq));
+        }
+
+        assert_eq!(screen(&logs, &viewport), before);
+    }
+
+    #[test]
+    fn test_newest_first_shows_the_page_upside_down() {
+        let logs: Vec<LogLine> = (1..=50).map(line).collect();
+        let mut viewport = LogViewport::new();
+        screen(&logs, &viewport);
+
+        let seqs: Vec<u64> = logs.iter().map(|log| log.seq).collect();
+        viewport.scroll(&seqs, true, LogOrder::NewestFirst.buffer_delta(20));
+        let rows = screen_in_order(&logs, &viewport, LogOrder::NewestFirst);
+        let rows: Vec<&str> = rows.lines().collect();
+
+        assert!(rows[0].contains("line 30"), "{:?}", rows);
+        assert!(rows[4].contains("line 26"), "{:?}", rows);
+    }
+
+    #[test]
+    fn test_search_matches_are_highlighted() {
+        let logs: Vec<LogLine> = (1..=3).map(line).collect();
+        let search = SearchFilter::new("re:line [23]", false);
+        let mut terminal = Terminal::new(TestBackend::new(40, 7)).unwrap();
+        terminal
+            .draw(|f| {
+                render_logs(
+                    f,
+                    f.area(),
+                    &logs,
+                    &LogViewport::new(),
+                    LogOrder::OldestFirst,
+                    LogColors::Themed,
+                    0,
+                    false,
+                    &search,
+                    None,
+                    None,
+                    &LogChips::new(),
+                    None,
+                    DisplayZone::Local,
+                    &Privacy::default(),
+                    None,
+                    0,
+                    None,
+                );
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..40).map(|x| buffer[(x, 1)].symbol()).collect();
+        assert!(row.contains("line 2"), "{}", row);
+        assert!(!row.contains("line 1"), "{}", row);
+
+        // "│[web] ▪ " comes before the content, one cell per character
+        let start = row[..row.find("line 2").unwrap()].chars().count() as u16;
+        let marked = |x: u16| buffer[(x, 1)].bg == Theme::warning();
+        assert!((start..start + 6).all(marked));
+        assert!(!marked(start - 1));
+        assert!(!marked(start + 6));
+    }
+
+    #[test]
+    fn test_log_search_takes_regexes_and_toggles_case() {
+        let mut app = test_app();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('/')));
+        for c in r"re:completed (2\d\d)".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        let matched: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|log| log.content.as_str())
+            .collect();
+        assert_eq!(matched.len(), 1);
+        assert!(matched[0].starts_with("Completed 200 OK"));
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains("regex · aa any case · Ctrl+I"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("Completed 200 OK"), "{}", screen);
+
+        // Ctrl+I arrives as Tab in most terminals
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert!(app.filtered_logs().is_empty());
+        assert!(render_app(&app, 160, 40).contains("Aa match case"));
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('i'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.filtered_logs().len(), 1);
+        assert_eq!(
+            app.search_query_for(&ViewMode::Logs),
+            r"re:completed (2\d\d)"
+        );
+
+        // A regex that doesn't compile says why, in the bar and from `/search`
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('(')));
+        assert!(app.filtered_logs().is_empty());
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("✗ unclosed group"), "{}", screen);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+
+        app.run_command("search", vec!["re:[a-".to_string()]);
+        assert!(matches!(
+            app.last_command_result.take(),
+            Some(command::ExecutionResult::Error(message)) if message.starts_with("Invalid regex")
+        ));
+        assert_eq!(app.search_query_for(&ViewMode::Logs), "");
+        app.run_command("search", vec!["re:^Started".to_string()]);
+        assert_eq!(app.filtered_logs().len(), 1);
+    }
+
+    #[test]
+    fn test_level_filter_composes_with_process_filter_and_search() {
+        let mut app = test_app();
+        for (process, content) in [
+            (
+                "worker",
+                "W, [2024-01-15T10:30:45.043111 #6322]  WARN -- : Redis is slow",
+            ),
+            (
+                "worker",
+                "E, [2024-01-15T10:30:46.043111 #6322] ERROR -- : Redis is gone",
+            ),
+            ("frontend", " ⚠ Fast Refresh had to perform a full reload"),
+        ] {
+            app.add_log(LogLine::new(process, content.to_string()));
+        }
+        let levels: Vec<Option<LogLevel>> = app.logs.iter().map(|log| log.level).collect();
+        assert_eq!(levels[0], Some(LogLevel::Info));
+        assert_eq!(levels[2], Some(LogLevel::Debug));
+
+        app.run_command("level", vec!["warn".to_string()]);
+        let shown = |app: &App| -> Vec<String> {
+            app.filtered_logs()
+                .iter()
+                .map(|log| log.content.clone())
+                .collect()
+        };
+        // The exception and its backtrace, and the three warnings and errors;
+        // requests and queries go
+        assert_eq!(shown(&app).len(), 5, "{:?}", shown(&app));
+        app.last_command_result = None;
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("Level: warn+"), "{}", screen);
+        assert!(screen.contains("Redis is gone"), "{}", screen);
+        assert!(!screen.contains("User Load"), "{}", screen);
+
+        app.filter_process = Some(ProcessFilter::only("worker"));
+        assert_eq!(shown(&app).len(), 2);
+        app.search_queries
+            .insert(ViewMode::Logs, "re:gone$".to_string());
+        assert_eq!(
+            shown(&app),
+            ["E, [2024-01-15T10:30:46.043111 #6322] ERROR -- : Redis is gone"]
+        );
+
+        app.run_command("level", vec!["loud".to_string()]);
+        assert!(matches!(
+            app.last_command_result.take(),
+            Some(command::ExecutionResult::Error(message)) if message.starts_with("Invalid level")
+        ));
+        app.clear_filter();
+        assert_eq!(app.min_log_level, None);
+        assert_eq!(shown(&app).len(), app.logs.len());
+    }
+
+    #[test]
+    fn test_log_chips_hide_categories_but_keep_counting() {
+        let mut app = test_app();
+        assert_eq!(app.log_chips.count_of(LogCategory::Sql), 1);
+        assert_eq!(app.log_chips.count_of(LogCategory::Error), 2);
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("1 SQL 1"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('1')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('3')));
+        app.add_log(LogLine::new(
+            "web",
+            r#"  Event Load (0.4ms)  SELECT "events".* FROM "events" WHERE "level" = 'ERROR'"#,
+        ));
+        assert_eq!(app.log_chips.count_of(LogCategory::Sql), 2);
+        assert_eq!(app.log_chips.count_of(LogCategory::Error), 2);
+        assert!(
+            app.filtered_logs()
+                .iter()
+                .all(|log| !matches!(log.category, LogCategory::Sql | LogCategory::Error))
+        );
+
+        assert!(matches!(
+            app.last_command_result.take(),
+            Some(command::ExecutionResult::Success(message)) if message == "Errors lines hidden"
+        ));
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("SQL 2"), "{}", screen);
+        assert!(screen.contains("Hiding: SQL, Errors"), "{}", screen);
+        assert!(!screen.contains("User Load"), "{}", screen);
+        assert!(screen.contains("Started GET"), "{}", screen);
+
+        // Chips combine with the process filter, and `c` shows everything again
+        app.filter_process = Some(ProcessFilter::only("worker"));
+        assert!(app.filtered_logs().is_empty());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('c')));
+        assert!(!app.log_chips.is_filtering());
+        assert_eq!(app.filtered_logs().len(), app.logs.len());
+    }
+
+    #[test]
+    fn test_newest_first_order_flips_logs_and_scroll_keys() {
+        let mut app = test_app().with_log_order(LogOrder::NewestFirst);
+        for n in 1..=60 {
+            app.add_log(LogLine::new("web", format!("tick {}", n)));
+        }
+
+        let screen = render_app(&app, 120, 30);
+        let newest = screen.find("tick 60").unwrap();
+        assert!(newest < screen.find("tick 59").unwrap(), "{}", screen);
+
+        // Down moves into older lines, and back up to the top follows again
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        assert!(!app.auto_scroll);
+        assert!(!render_app(&app, 120, 30).contains("tick 60"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert!(app.auto_scroll);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('o')));
+        assert_eq!(app.log_order, LogOrder::OldestFirst);
+        let screen = render_app(&app, 120, 30);
+        assert!(
+            screen.find("tick 58").unwrap() < screen.find("tick 59").unwrap(),
+            "{}",
+            screen
+        );
+    }
+
+    #[test]
+    fn test_the_wheel_scrolls_the_logs_like_the_arrow_keys() {
+        let mut app = test_app();
+        for n in 1..=60 {
+            app.add_log(LogLine::new("web", format!("tick {}", n)));
+        }
+        assert!(render_app(&app, 120, 30).contains("tick 60"));
+
+        assert!(handle_mouse_event(
+            &mut app,
+            mouse(MouseEventKind::ScrollUp, 40, 15)
+        ));
+        assert!(!app.auto_scroll);
+        assert!(!render_app(&app, 120, 30).contains("tick 60"));
+
+        handle_mouse_event(&mut app, mouse(MouseEventKind::ScrollDown, 40, 15));
+        assert!(app.auto_scroll);
+        assert!(!handle_mouse_event(
+            &mut app,
+            mouse(MouseEventKind::Moved, 40, 15)
+        ));
+    }
+
+    #[test]
+    fn test_multi_megabyte_line_is_truncated_and_stays_fast() {
+        let mut app = test_app();
+        let values = "(1, 'signup', '2024-01-01 00:00:00'), ".repeat(90_000);
+        let content = format!(
+            r#"  Event Bulk Insert (850.2ms)  INSERT INTO "events" (id, name, created_at) VALUES {}(2, 'x', NULL)"#,
+            values
+        );
+        assert!(content.len() > 3 * 1024 * 1024);
+
+        let start = Instant::now();
+        app.add_log(LogLine::new("web", content));
+        app.search_queries
+            .insert(ViewMode::Logs, "insert".to_string());
+        let screen = render_app(&app, 160, 40);
+        let elapsed = start.elapsed();
+
+        let stored = app.logs.last().unwrap();
+        assert!(stored.content.len() < 17 * 1024);
+        assert!(
+            stored.content.ends_with(" … truncated (3.3MB)"),
+            "{}",
+            &stored.content[16_000..]
+        );
+        assert!(screen.contains("Bulk Insert"), "{}", screen);
+        // Debug builds take a few milliseconds; this only catches a return to
+        // scanning the whole line
+        assert!(elapsed < Duration::from_millis(500), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_logs_annotate_requests_and_exceptions_until_turned_off() {
+        let mut app = test_app();
+        // The exception is grouped once its backtrace ends
+        app.add_log(LogLine::new("web", "Started GET \"/users\" for 127.0.0.1"));
+        let completed = |screen: &str| {
+            screen
+                .lines()
+                .find(|line| line.contains("Completed 200 OK"))
+                .unwrap()
+                .to_string()
+        };
+
+        let screen = render_app(&app, 160, 40);
+        let line = completed(&screen);
+        assert!(
+            line.trim_end_matches(['│', ' '])
+                .ends_with("● 52ms 1 query"),
+            "{}",
+            line
+        );
+        assert!(screen.contains("×1 seen"), "{}", screen);
+
+        app.command_input = "/annotations off".to_string();
+        app.execute_command();
+        let screen = render_app(&app, 160, 40);
+        assert!(!completed(&screen).contains("●"), "{}", screen);
+        assert!(!screen.contains("×1 seen"), "{}", screen);
+    }
+
+    #[test]
+    fn test_tool_colored_links_render_and_open_reports_missing_links() {
+        let mut app = test_app().with_log_colors(LogColors::Tool);
+        app.command_input = "/open".into();
+        app.execute_command();
+        assert!(
+            matches!(
+                &app.last_command_result,
+                Some(command::ExecutionResult::Error(message)) if message.contains("No links")
+            ),
+            "{:?}",
+            app.last_command_result
+        );
+
+        let (content, markup) = crate::process::parse_ansi(
+            "\u{1b}[31m✘\u{1b}[0m \u{1b}]8;;file:///app/src/main.ts\u{7}src/main.ts:4\u{1b}]8;;\u{7}",
+        );
+        app.add_log(LogLine {
+            markup: markup.map(Box::new),
+            ..LogLine::new("vite", content)
+        });
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("✘ src/main.ts:4"), "{}", screen);
+        assert!(!screen.contains("\u{1b}"), "{}", screen);
+    }
+}
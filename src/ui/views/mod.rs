@@ -1,11 +1,14 @@
 pub mod database_health_view;
 pub mod exception_detail_view;
 pub mod exceptions_view;
+pub mod jobs_view;
 /// View modules - Each major view in its own file
 pub mod logs_view;
 pub mod query_analysis_view;
 pub mod request_detail_view;
+pub mod table_detail_view;
 pub mod test_results_view;
+pub mod timeline_view;
 
 use ratatui::Frame;
 
@@ -1,33 +1,98 @@
-use ratatui::{Frame, layout::Rect, style::Style, widgets::Paragraph};
+use std::sync::Arc;
 
-use crate::context::RequestContextTracker;
-use crate::ui::theme::Theme;
+use ratatui::{Frame, layout::Rect, style::Style, text::Line, widgets::Paragraph};
 
+use crate::context::{
+    AggregatedNPlusOne, BackgroundQueries, CompletedRequest, RequestContextTracker,
+};
+use crate::privacy::Privacy;
+use crate::query::{NPlusOneSource, RequestContext};
+use crate::rails::routes::RouteTable;
+use crate::search::SearchQuery;
+use crate::stats::StatsCollector;
+use crate::ui::click_map::{ClickMap, ClickTarget};
+use crate::ui::components::EmptyState;
+use crate::ui::components::request_row::{request_header, request_row};
+use crate::ui::formatting::format_ms;
+use crate::ui::theme::{Icons, Theme};
+
+/// Templates listed in the slowest views table
+const SLOWEST_VIEWS: usize = 5;
+
+/// Key prefixes listed in the cache table
+const CACHE_PREFIXES: usize = 5;
+
+/// Turbo Streams listed, by broadcast volume
+const TURBO_STREAMS: usize = 5;
+
+/// Slowest background queries listed under their entry
+const SLOWEST_BACKGROUND: usize = 3;
+
+/// Repeated queries listed in the N+1 section, by total time
+pub const N_PLUS_ONE_LISTED: usize = 5;
+
+/// Paths listed under each repeated query before the rest are counted
+const N_PLUS_ONE_PATHS: usize = 3;
+
+/// Render the query analysis view
+///
+/// A non-empty `search_query` narrows the request list to paths or
+/// controllers containing it. `selected_in_flight` marks the in-flight
+/// request Enter would follow. Requests are annotated with the name of the
+/// route they match in `routes`, once read, and paths are masked while
+/// `privacy` is on. The N+1 section at the top lists the repeated queries
+/// costing the most across requests while `n_plus_one_expanded`, marking
+/// `selected_n_plus_one`. Its rows, the in-flight requests and the listed
+/// completed requests are recorded in `click_map` where they were drawn.
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
     context_tracker: &RequestContextTracker,
+    stats_collector: &StatsCollector,
+    search_query: &str,
+    selected_in_flight: Option<usize>,
+    n_plus_one_expanded: bool,
+    selected_n_plus_one: Option<usize>,
+    routes: Option<&RouteTable>,
+    privacy: &Privacy,
+    click_map: &mut ClickMap,
     _spinner_frame: usize,
     fade_progress: Option<f32>,
 ) {
+    if context_tracker.is_empty() {
+        EmptyState::new(Icons::query(), "No requests yet")
+            .with_hint("Waiting for Rails request logs - make an HTTP request to your app")
+            .with_hint("Each request shows once its 'Started' and 'Completed' lines are logged")
+            .with_tip(
+                "Rails must log to STDOUT: set RAILS_LOG_TO_STDOUT=1, or point Lograge at STDOUT",
+            )
+            .render(f, area, "Query Analysis", fade_progress);
+        return;
+    }
+
     let requests = context_tracker.get_recent_requests();
     let current_requests = context_tracker.get_current_requests();
-    let n_plus_ones = context_tracker.get_all_n_plus_one_issues();
+    let n_plus_ones = context_tracker.n_plus_one_count();
+    let background = context_tracker.background_queries();
+    let width = area.width.saturating_sub(2) as usize;
+
+    let block = Theme::block("Query Analysis", fade_progress);
+    let inner = block.inner(area);
 
     if requests.is_empty() {
-        let block = Theme::block("Query Analysis", fade_progress);
-        let debug_text = format!(
-            "Waiting for completed requests...\n\n\
-            Active requests: {}\n\
-            Completed requests: {}\n\n\
-            Note: Requests appear here after Rails logs show:\n\
-            1. 'Started GET /path' (request start)\n\
-            2. SQL queries during request\n\
-            3. 'Completed 200' (request end)",
-            current_requests.len(),
-            requests.len()
-        );
-        let empty = Paragraph::new(debug_text)
+        let mut text = vec![
+            "Waiting for the first request to complete...".to_string(),
+            String::new(),
+        ];
+        for (line, target) in in_flight_lines(&current_requests, selected_in_flight, privacy) {
+            if let Some(target) = target {
+                click_map.add_line(inner, text.len(), target);
+            }
+            text.push(line);
+        }
+        text.extend(background_lines(&background, privacy, width));
+        let empty = Paragraph::new(text.join("\n"))
             .style(Style::default().fg(Theme::text_muted()))
             .block(block);
         f.render_widget(empty, area);
@@ -37,31 +102,438 @@ pub fn render(
     // Show summary and list of recent requests
     let mut text = vec![
         format!("📊 Recent requests: {}", requests.len()),
-        format!("⚠️  Detected N+1 issues: {}", n_plus_ones.len()),
+        format!("⚠️  Detected N+1 issues: {}", n_plus_ones),
         format!("🔄 Active requests: {}", current_requests.len()),
         String::new(),
-        "Recent Requests:".to_string(),
     ];
+    let n_plus_one = n_plus_one_lines(
+        &context_tracker.get_aggregated_n_plus_one(),
+        n_plus_one_expanded,
+        selected_n_plus_one,
+        privacy,
+        width,
+    );
+    let in_flight = in_flight_lines(&current_requests, selected_in_flight, privacy);
+    for section in [n_plus_one, in_flight] {
+        if section.is_empty() {
+            continue;
+        }
+        for (line, target) in section {
+            if let Some(target) = target {
+                click_map.add_line(inner, text.len(), target);
+            }
+            text.push(line);
+        }
+        text.push(String::new());
+    }
+    let background = background_lines(&background, privacy, width);
+    if !background.is_empty() {
+        text.extend(background);
+        text.push(String::new());
+    }
 
-    // Show last 10 requests
-    for (i, req) in requests.iter().rev().take(10).enumerate() {
-        let path = req.context.path.as_deref().unwrap_or("<unknown>");
-        let status = req.status.unwrap_or(0);
-        let queries = req.context.query_count();
-        let duration = req.total_duration.unwrap_or(0.0);
+    let search = SearchQuery::new(search_query);
+    let listed = if search.is_empty() {
+        text.push("Recent Requests:".to_string());
+        requests
+    } else {
+        let matching = context_tracker.get_recent_requests_matching(&search);
+        text.push(format!(
+            "Requests matching '{}': {}",
+            search_query,
+            matching.len()
+        ));
+        Arc::new(matching)
+    };
 
-        let status_icon = if status >= 500 { "❌" }
-                         else if status >= 400 { "⚠️" }
-                         else if status >= 300 { "↪️" }
-                         else { "✅" };
+    // Show last 10 requests, in columns sized to the inner width
+    let mut lines: Vec<Line> = text.into_iter().map(Line::from).collect();
+    lines.push(request_header(width));
+    for (index, req) in listed.iter().enumerate().rev().take(10) {
+        click_map.add_line(inner, lines.len(), ClickTarget::Request(index));
+        lines.push(request_row(req, route_name(routes, req), privacy, width));
+    }
+    let mut text = Vec::new();
 
+    let view_stats = context_tracker.get_view_stats();
+    if !view_stats.is_empty() {
+        text.push(String::new());
+        text.push("Slowest Views (by total render time):".to_string());
         text.push(format!(
-            "  {}. {} {} - {} queries ({:.1}ms)",
-            i + 1, status_icon, path, queries, duration
+            "  {:<40} {:>7} {:>9} {:>9} {:>10}",
+            "Template", "Renders", "Avg", "Max", "Total"
         ));
+        for stats in view_stats.iter().take(SLOWEST_VIEWS) {
+            text.push(format!(
+                "  {:<40} {:>7} {:>7.1}ms {:>7.1}ms {:>8.1}ms",
+                truncate_start(&stats.template, 40),
+                stats.renders,
+                stats.avg_duration(),
+                stats.max_duration,
+                stats.total_duration
+            ));
+        }
     }
 
-    let block = Theme::block("Query Analysis", fade_progress);
-    let para = Paragraph::new(text.join("\n")).block(block);
+    let prefixes = stats_collector.get_stats().cache_prefixes();
+    if !prefixes.is_empty() {
+        text.push(String::new());
+        text.push("Cache (by key prefix):".to_string());
+        text.push(format!(
+            "  {:<24} {:>7} {:>7} {:>7} {:>7} {:>8}",
+            "Prefix", "Hits", "Misses", "Writes", "Deletes", "Hit rate"
+        ));
+        for (prefix, counts) in prefixes.iter().take(CACHE_PREFIXES) {
+            let hit_rate = counts
+                .hit_rate()
+                .map_or_else(|| "-".to_string(), |rate| format!("{:.0}%", rate));
+            text.push(format!(
+                "  {:<24} {:>7} {:>7} {:>7} {:>7} {:>8}",
+                truncate_start(prefix, 24),
+                counts.hits,
+                counts.misses,
+                counts.writes,
+                counts.deletes,
+                hit_rate
+            ));
+        }
+    }
+
+    let streams = context_tracker.top_turbo_streams(TURBO_STREAMS);
+    if !streams.is_empty() {
+        text.push(String::new());
+        text.push("Turbo Streams (by broadcasts):".to_string());
+        text.push(format!(
+            "  {:<32} {:<16} {:>10} {:>9} {:<10}",
+            "Stream", "Model", "Broadcasts", "Delivered", "Last"
+        ));
+        for stream in &streams {
+            let marker = if stream.is_bursting() { "⚠" } else { " " };
+            text.push(format!(
+                "{} {:<32} {:<16} {:>10} {:>9} {:<10}",
+                marker,
+                truncate_start(&privacy.text(&stream.label), 32),
+                stream.model.as_deref().unwrap_or("-"),
+                stream.broadcasts,
+                stream.transmissions,
+                stream.last_action.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+
+    lines.extend(text.into_iter().map(Line::from));
+    let para = Paragraph::new(lines).block(block);
     f.render_widget(para, area);
 }
+
+/// Name of the route `request` went to, if it matched a named one
+fn route_name<'a>(routes: Option<&'a RouteTable>, request: &CompletedRequest) -> Option<&'a str> {
+    let context = &request.context;
+    routes?.name_for(context.method.as_deref()?, context.path.as_deref()?)
+}
+
+/// The "N+1 Queries" section: each repeated query with how often it ran, the
+/// time it took and the paths it ran on; only a count while collapsed.
+/// Each query's first line is where a click selects it.
+fn n_plus_one_lines(
+    issues: &[AggregatedNPlusOne],
+    expanded: bool,
+    selected: Option<usize>,
+    privacy: &Privacy,
+    width: usize,
+) -> Vec<(String, Option<ClickTarget>)> {
+    if issues.is_empty() {
+        return Vec::new();
+    }
+    if !expanded {
+        return vec![(
+            format!("N+1 Queries: {} repeated (n to expand)", issues.len()),
+            None,
+        )];
+    }
+
+    let mut lines = vec![(
+        "N+1 Queries (by total time; ↑↓ select, Enter for a request, n to collapse):".to_string(),
+        None,
+    )];
+    for (i, issue) in issues.iter().take(N_PLUS_ONE_LISTED).enumerate() {
+        let marker = if selected == Some(i) { "▶" } else { " " };
+        let source = match issue.source {
+            NPlusOneSource::Bullet => " (Bullet)",
+            NPlusOneSource::Heuristic => "",
+        };
+        let stats = format!(
+            "{:>5}× in {:>3} req {:>9}",
+            issue.occurrences,
+            issue.requests,
+            format_ms(issue.total_duration)
+        );
+        let room = width.saturating_sub(stats.chars().count() + source.len() + 6);
+        let sql: String = privacy
+            .sql(&issue.fingerprint.normalized)
+            .chars()
+            .take(room)
+            .collect();
+        lines.push((
+            format!("  {} {}  {}{}", marker, stats, sql, source),
+            Some(ClickTarget::NPlusOne(i)),
+        ));
+
+        let mut paths: Vec<String> = issue
+            .paths
+            .iter()
+            .take(N_PLUS_ONE_PATHS)
+            .map(|path| privacy.text(path).to_string())
+            .collect();
+        if issue.paths.len() > N_PLUS_ONE_PATHS {
+            paths.push(format!("+{} more", issue.paths.len() - N_PLUS_ONE_PATHS));
+        }
+        if !paths.is_empty() {
+            lines.push((format!("      on {}", paths.join(", ")), None));
+        }
+    }
+    lines
+}
+
+/// The "In Flight" section, oldest (longest running) first, each request
+/// with where a click selects it
+fn in_flight_lines(
+    current: &[RequestContext],
+    selected: Option<usize>,
+    privacy: &Privacy,
+) -> Vec<(String, Option<ClickTarget>)> {
+    if current.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![("In Flight (↑↓ select, Enter to follow):".to_string(), None)];
+    for (i, context) in current.iter().enumerate() {
+        let marker = if selected == Some(i) { "▶" } else { " " };
+        let controller = context
+            .controller
+            .as_deref()
+            .map(|c| format!(" [{}]", c))
+            .unwrap_or_default();
+        let line = format!(
+            "  {} ⏳ {}{} - {} queries so far ({} elapsed)",
+            marker,
+            privacy.text(context.path.as_deref().unwrap_or("<unknown>")),
+            controller,
+            context.query_count(),
+            format_ms(context.start_time.elapsed().as_secs_f64() * 1000.0)
+        );
+        lines.push((line, Some(ClickTarget::InFlight(i))));
+    }
+    lines
+}
+
+/// The "Background / console" entry and its slowest recent queries, once
+/// any SQL ran outside a request
+fn background_lines(
+    background: &BackgroundQueries,
+    privacy: &Privacy,
+    width: usize,
+) -> Vec<String> {
+    if background.queries == 0 {
+        return Vec::new();
+    }
+
+    let mut lines = vec![format!("🖥️  {}", background.summary())];
+    for query in background.slowest(SLOWEST_BACKGROUND) {
+        let duration = format!("{:>9}", format_ms(query.duration));
+        let room = width.saturating_sub(duration.chars().count() + 5);
+        let sql: String = privacy
+            .sql(query.raw_query.trim())
+            .chars()
+            .take(room)
+            .collect();
+        lines.push(format!("    {} {}", duration, sql));
+    }
+    lines
+}
+
+/// Keep the end of `text`, which names the template, when it is too long
+fn truncate_start(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(len - (width - 1)).collect();
+    format!("…{}", tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_in_flight_request_is_followed_until_it_completes() {
+        let mut app = test_app();
+        let log = |app: &mut App, content: &str| {
+            app.add_log(LogLine::new("web", content.to_string()));
+        };
+        log(&mut app, r#"Started POST "/reports" for 127.0.0.1"#);
+        log(&mut app, "Processing by ReportsController#create as HTML");
+        log(
+            &mut app,
+            r#"  Order Load (250.0ms)  SELECT "orders".* FROM "orders""#,
+        );
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("In Flight"), "{}", screen);
+        assert!(screen.contains("/reports"));
+
+        // Up from the first completed request moves into In Flight
+        app.select_previous_request();
+        assert_eq!(app.selected_in_flight, Some(0));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("▶ ⏳"), "{}", screen);
+        app.view_selected_request();
+        let ViewMode::LiveRequest(id) = app.view_mode else {
+            panic!("expected the live view, got {:?}", app.view_mode);
+        };
+
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("● LIVE /reports"), "{}", screen);
+        assert!(screen.contains("Queries: 1"));
+
+        log(
+            &mut app,
+            r#"  LineItem Load (120.0ms)  SELECT "line_items".* FROM "line_items""#,
+        );
+        app.follow_live_request();
+        assert_eq!(app.view_mode, ViewMode::LiveRequest(id));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Queries: 2"), "{}", screen);
+        assert!(screen.contains("line_items"));
+
+        log(
+            &mut app,
+            "Completed 200 OK in 31000ms (ActiveRecord: 370.0ms)",
+        );
+        app.follow_live_request();
+        assert!(matches!(app.view_mode, ViewMode::RequestDetail(_)));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("POST /reports"), "{}", screen);
+
+        app.view_mode = ViewMode::LiveRequest(id + 100);
+        assert!(render_app(&app, 120, 40).contains("no longer tracked"));
+    }
+
+    #[test]
+    fn test_n_plus_one_section_selects_and_opens_a_request() {
+        let mut app = test_app();
+        let log = |app: &mut App, content: &str| {
+            app.add_log(LogLine::new("web", content.to_string()));
+        };
+        for path in ["/posts", "/posts/1", "/tags"] {
+            log(
+                &mut app,
+                &format!(r#"Started GET "{}" for 127.0.0.1"#, path),
+            );
+            if path != "/tags" {
+                for id in 0..3 {
+                    log(
+                        &mut app,
+                        &format!(
+                            r#"  Comment Load (2.0ms)  SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = {}"#,
+                            id
+                        ),
+                    );
+                }
+            }
+            log(&mut app, "Completed 200 OK in 20ms");
+        }
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 140, 40);
+        assert!(screen.contains("N+1 Queries (by total time"), "{}", screen);
+        assert!(screen.contains("6× in   2 req"), "{}", screen);
+        assert!(screen.contains("on /posts, /posts/1"), "{}", screen);
+
+        // Up from the first completed request, with nothing in flight
+        app.select_previous_request();
+        assert_eq!(app.selected_n_plus_one, Some(0));
+        assert!(render_app(&app, 140, 40).contains("▶     6×"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        // The latest request it was found in
+        assert_eq!(app.view_mode, ViewMode::RequestDetail(2));
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('n')));
+        let screen = render_app(&app, 140, 40);
+        assert!(
+            screen.contains("N+1 Queries: 1 repeated (n to expand)"),
+            "{}",
+            screen
+        );
+        app.select_previous_request();
+        assert_eq!(app.selected_n_plus_one, None);
+    }
+
+    #[test]
+    fn test_turbo_stream_burst_is_logged_and_streams_are_listed() {
+        let mut app = test_app();
+        let broadcast = r#"[ActionCable] Broadcasting to Z2lkOi8vYmxvZy9Qb3N0LzE: "<turbo-stream action=\"replace\" target=\"post_1\"><template></template></turbo-stream>""#;
+        let lines = std::iter::once(r#"Started PATCH "/posts/1" for 127.0.0.1"#)
+            .chain(std::iter::repeat_n(broadcast, 51))
+            .chain(std::iter::once("Completed 200 OK in 80ms"));
+        for content in lines {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        app.check_turbo_bursts();
+        app.check_turbo_bursts();
+        let warnings: Vec<_> = app
+            .logs
+            .iter()
+            .filter(|log| log.content.starts_with("[turbo]"))
+            .map(|log| log.content.clone())
+            .collect();
+        assert_eq!(
+            warnings,
+            ["[turbo] Post/1 broadcast ×51 in 10s during PATCH /posts/1 — after_commit loop?"]
+        );
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 120, 50);
+        assert!(
+            screen.contains("Turbo Streams (by broadcasts)"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("⚠ Post/1"), "{}", screen);
+        assert!(screen.contains("replace"), "{}", screen);
+    }
+
+    #[test]
+    fn test_console_queries_listed_as_background_and_still_feed_db_health() {
+        let mut app = test_app();
+        app.add_log(LogLine::new(
+            "web",
+            r#"Started GET "/orders" for 127.0.0.1"#,
+        ));
+        for content in [
+            "shop(dev)> Order.where(state: 'open').count",
+            r#"  Order Count (180.4ms)  SELECT COUNT(*) FROM "orders" WHERE "orders"."state" = 'open'"#,
+        ] {
+            app.add_log(LogLine::new("console", content.to_string()));
+        }
+
+        assert_eq!(
+            app.context_tracker.get_current_requests()[0].query_count(),
+            0
+        );
+        assert_eq!(app.db_health.get_slow_queries().len(), 1);
+        app.set_view(ViewMode::QueryAnalysis);
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains("Background / console — 1 queries, 1 slow (console: console)"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("180.4ms Order Count"), "{}", screen);
+    }
+}
@@ -1,10 +1,979 @@
 /// Request Detail view - Detailed query timeline for a request
-use ratatui::Frame;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
 
+use crate::context::CompletedRequest;
+use crate::privacy::Privacy;
+use crate::query::{NPlusOneIssue, QueryInfo, QueryType, RequestContext, strip_log_prefix};
+use crate::ui::components::request_fields::split_request_fields;
+use crate::ui::formatting::format_ms;
+use crate::ui::theme::Theme;
+
+/// Slowest views listed under the timeline
+const DETAIL_VIEWS: usize = 10;
+
+/// Cells of the duration bar, which is full for the request's slowest query
+const BAR_WIDTH: usize = 12;
+
+/// Partial blocks for the end of a duration bar, in eighths
+const BAR_EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Columns before a query's SQL: index, icon, duration and bar
+const QUERY_INDENT: usize = 4 + 1 + 1 + 1 + 8 + 1 + BAR_WIDTH + 2;
+
+/// Render a completed request: a summary on top and, below it, its queries
+/// in the order they ran, scrolled down `scroll` lines
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Request Detail (↑↓ scroll) ──────────────────────────────────────────┐
+/// │ GET /users/7 [UsersController#show]  200  #4f2a9c1e                  │
+/// │ Duration: 52.0ms  │  SQL: 9.5ms (18%)  │  App: 42.5ms                │
+/// │ Queries: 5 - 3 SELECT, 1 INSERT, 1 BEGIN                             │
+/// │                                                                      │
+/// │ Timeline (5 queries, in order):                                      │
+/// │    1 ◆   1.2ms ██▌           User Load  SELECT "users".* FROM ...    │
+/// │    2 ◆   0.4ms ▊             Post Load  SELECT "posts".* FROM ...    │
+/// │      ⚠ Possible N+1 query detected (3 times). Eager load ...         │
+/// └──────────────────────────────────────────────────────────────────────┘
+/// ```
+///
+/// BEGIN and COMMIT are grayed out; N+1 callouts sit under the first of
+/// the repeated queries. The path, queries and fields are masked while
+/// `privacy` is on.
 pub fn render(
-    _f: &mut Frame,
-    _area: ratatui::layout::Rect,
-    // ... other parameters
+    f: &mut Frame,
+    area: Rect,
+    request: Option<&CompletedRequest>,
+    show_extra: bool,
+    scroll: usize,
+    privacy: &Privacy,
+    fade_progress: Option<f32>,
 ) {
-    // Full implementation using component builders
+    let block = Theme::block("Request Detail (↑↓ scroll)", fade_progress);
+    let Some(request) = request else {
+        let none = Paragraph::new("No request selected")
+            .style(Style::default().fg(Theme::text_muted()))
+            .block(block);
+        f.render_widget(none, area);
+        return;
+    };
+
+    let fade = fade_progress.unwrap_or(1.0);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let summary = summary_lines(request, privacy, fade);
+    let [summary_area, timeline_area] = Layout::vertical([
+        Constraint::Length(summary.len() as u16 + 1),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+    f.render_widget(Paragraph::new(summary), summary_area);
+    f.render_widget(
+        Paragraph::new(timeline_lines(request, show_extra, privacy, fade))
+            .scroll((scroll.min(u16::MAX as usize) as u16, 0)),
+        timeline_area,
+    );
+}
+
+/// The summary kept above the timeline: the request, where its time went
+/// and what kinds of queries it ran
+pub fn summary_lines(
+    request: &CompletedRequest,
+    privacy: &Privacy,
+    fade: f32,
+) -> Vec<Line<'static>> {
+    let context = &request.context;
+    let faded = |color: Color| Style::default().fg(Theme::apply_fade_to_color(color, fade));
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let muted = faded(Theme::text_muted());
+
+    let mut title = Vec::new();
+    if let Some(method) = &context.method {
+        title.push(Span::styled(
+            format!("{} ", method),
+            faded(Theme::method_color(method)).add_modifier(Modifier::BOLD),
+        ));
+    }
+    title.push(Span::styled(
+        privacy
+            .text(context.path.as_deref().unwrap_or("<unknown>"))
+            .into_owned(),
+        bold,
+    ));
+    if let Some(controller) = &context.controller {
+        title.push(Span::raw(format!(" [{}]", controller)));
+    }
+    if let Some(status) = request.status {
+        title.push(Span::styled(
+            format!("  {}", status),
+            faded(Theme::status_code_color(status)).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(id) = context.short_request_id() {
+        title.push(Span::styled(format!("  #{}", id), muted));
+    }
+
+    // SQL and app time only split a duration Rails logged
+    let sql = context.total_query_time();
+    let mut timing = vec![Span::styled("Duration: ", bold)];
+    match request.total_duration {
+        Some(total) => {
+            let share = if total > 0.0 {
+                (sql / total * 100.0).min(100.0)
+            } else {
+                0.0
+            };
+            timing.extend([
+                Span::styled(format_ms(total), faded(Theme::duration_color(total))),
+                Span::raw("  │  "),
+                Span::styled("SQL: ", bold),
+                Span::raw(format!("{} ({:.0}%)", format_ms(sql), share)),
+                Span::raw("  │  "),
+                Span::styled("App: ", bold),
+                Span::raw(format_ms((total - sql).max(0.0))),
+            ]);
+        }
+        None => timing.extend([
+            Span::styled("not logged", muted),
+            Span::raw("  │  "),
+            Span::styled("SQL: ", bold),
+            Span::raw(format_ms(sql)),
+        ]),
+    }
+
+    let mut lines = vec![
+        Line::from(title),
+        Line::from(timing),
+        Line::from(vec![
+            Span::styled("Queries: ", bold),
+            Span::raw(query_breakdown(context)),
+        ]),
+    ];
+
+    // Queueing happens before Rails starts the clock, so it's on top of Duration
+    if let Some(queue) = context.queue_time {
+        let source = if queue.estimated {
+            "estimated from thread saturation"
+        } else {
+            "logged"
+        };
+        lines.push(Line::raw(format!(
+            "Queueing: {} ({}, not in Duration)",
+            format_ms(queue.ms),
+            source
+        )));
+    }
+    let cache = context.cache;
+    if !cache.is_empty() {
+        lines.push(Line::raw(format!(
+            "Cache: {} reads ({} hits, {} misses), {} writes, {} deletes",
+            cache.reads(),
+            cache.hits,
+            cache.misses,
+            cache.writes,
+            cache.deletes
+        )));
+    }
+    for (label, value) in split_request_fields(&request.extra).known {
+        lines.push(Line::raw(format!("{}: {}", label, privacy.text(&value))));
+    }
+    if !request.n_plus_one_issues.is_empty() {
+        lines.push(Line::styled(
+            format!(
+                "N+1 queries ({}): flagged ⚠ in the timeline",
+                request.n_plus_one_issues.len()
+            ),
+            faded(Theme::warning()),
+        ));
+    }
+    lines
+}
+
+/// `5 - 3 SELECT, 1 INSERT, 1 BEGIN`: the count, then by type
+fn query_breakdown(context: &RequestContext) -> String {
+    let count = context.query_count();
+    let mut by_type: Vec<(QueryType, usize)> = Vec::new();
+    for query in &context.queries {
+        match by_type
+            .iter_mut()
+            .find(|(kind, _)| *kind == query.query_type)
+        {
+            Some((_, n)) => *n += 1,
+            None => by_type.push((query.query_type.clone(), 1)),
+        }
+    }
+    by_type.sort_by_key(|(kind, _)| type_order(kind));
+    let kinds: Vec<String> = by_type
+        .iter()
+        .map(|(kind, n)| format!("{} {}", n, type_label(kind)))
+        .collect();
+    if kinds.is_empty() {
+        count.to_string()
+    } else {
+        format!("{} - {}", count, kinds.join(", "))
+    }
+}
+
+/// The scrolled part: every query in the order it ran with its binds and
+/// any N+1 callout, then the slowest views and the extra fields
+pub fn timeline_lines(
+    request: &CompletedRequest,
+    show_extra: bool,
+    privacy: &Privacy,
+    fade: f32,
+) -> Vec<Line<'static>> {
+    let context = &request.context;
+    let faded = |color: Color| Style::default().fg(Theme::apply_fade_to_color(color, fade));
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let muted = faded(Theme::text_muted());
+    let mut lines = Vec::new();
+
+    let slowest = context
+        .queries
+        .iter()
+        .map(|query| query.duration)
+        .fold(0.0, f64::max);
+    if context.queries.is_empty() {
+        lines.push(Line::styled("No queries", muted));
+    } else {
+        lines.push(Line::styled(
+            format!("Timeline ({} queries, in order):", context.query_count()),
+            bold,
+        ));
+    }
+
+    let mut called_out: Vec<&NPlusOneIssue> = Vec::new();
+    for (index, query) in context.queries.iter().enumerate() {
+        let issue = request
+            .n_plus_one_issues
+            .iter()
+            .find(|issue| issue.fingerprint == query.fingerprint);
+        lines.push(query_line(
+            index,
+            query,
+            slowest,
+            issue.is_some(),
+            privacy,
+            fade,
+        ));
+
+        // Binds line up under the query, names padded to the longest
+        let width = query
+            .binds
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (name, value) in &query.binds {
+            lines.push(Line::styled(
+                format!(
+                    "{:indent$}{:<width$} = {}",
+                    "",
+                    name,
+                    privacy.bind_value(value),
+                    indent = QUERY_INDENT,
+                    width = width
+                ),
+                muted,
+            ));
+        }
+
+        if let Some(issue) = issue
+            && !called_out.iter().any(|seen| std::ptr::eq(*seen, issue))
+        {
+            called_out.push(issue);
+            lines.extend(callout_lines(issue, privacy, fade));
+        }
+    }
+
+    // Bullet can report an association whose queries weren't logged
+    let unplaced: Vec<&NPlusOneIssue> = request
+        .n_plus_one_issues
+        .iter()
+        .filter(|issue| !called_out.iter().any(|seen| std::ptr::eq(*seen, *issue)))
+        .collect();
+    if !unplaced.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("N+1 queries not in the timeline:", bold));
+        for issue in unplaced {
+            lines.extend(callout_lines(issue, privacy, fade));
+        }
+    }
+
+    let views = context.views_by_duration();
+    if !views.is_empty() {
+        // Durations include nested partials, so they aren't summed
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("Views ({} rendered, slowest first):", views.len()),
+            bold,
+        ));
+        for view in views.iter().take(DETAIL_VIEWS) {
+            let times = view
+                .count
+                .map(|count| format!(" ×{}", count))
+                .unwrap_or_default();
+            lines.push(Line::raw(format!(
+                "  {:>8}  {}{}",
+                format_ms(view.duration),
+                view.template,
+                times
+            )));
+        }
+        if views.len() > DETAIL_VIEWS {
+            lines.push(Line::styled(
+                format!("  … {} more", views.len() - DETAIL_VIEWS),
+                muted,
+            ));
+        }
+    }
+
+    let other = split_request_fields(&request.extra).other;
+    if !other.is_empty() {
+        lines.push(Line::raw(""));
+        if show_extra {
+            lines.push(Line::raw(format!("▾ Extra fields ({})", other.len())));
+            for (key, value) in other {
+                lines.push(Line::raw(format!("  {} = {}", key, privacy.text(value))));
+            }
+        } else {
+            lines.push(Line::raw(format!(
+                "▸ Extra fields ({}) - press e to expand",
+                other.len()
+            )));
+        }
+    }
+
+    lines
+}
+
+/// `   1 ◆   1.2ms ██▌           User Load  SELECT ...`
+fn query_line(
+    index: usize,
+    query: &QueryInfo,
+    slowest: f64,
+    repeated: bool,
+    privacy: &Privacy,
+    fade: f32,
+) -> Line<'static> {
+    let faded = |color: Color| Style::default().fg(Theme::apply_fade_to_color(color, fade));
+    let muted = faded(Theme::text_muted());
+    let (icon, color) = type_icon(&query.query_type);
+    let sql = privacy.sql(strip_log_prefix(&query.raw_query)).into_owned();
+
+    // Transaction boundaries are bookkeeping: grayed out whole
+    if matches!(query.query_type, QueryType::Begin | QueryType::Commit) {
+        return Line::styled(
+            format!(
+                "{:>4} {} {:>8} {:bar$}  {}",
+                index + 1,
+                icon,
+                format_ms(query.duration),
+                "",
+                sql,
+                bar = BAR_WIDTH
+            ),
+            muted,
+        );
+    }
+
+    let mut spans = vec![
+        Span::styled(format!("{:>4} ", index + 1), muted),
+        Span::styled(icon, faded(color)),
+        Span::raw(" "),
+        Span::styled(
+            format!("{:>8} ", format_ms(query.duration)),
+            faded(Theme::duration_color(query.duration)),
+        ),
+        Span::styled(
+            format!(
+                "{:<width$}",
+                duration_bar(query.duration, slowest, BAR_WIDTH),
+                width = BAR_WIDTH
+            ),
+            faded(Theme::duration_color(query.duration)),
+        ),
+        Span::raw("  "),
+    ];
+    if repeated {
+        spans.push(Span::styled("⚠ ", faded(Theme::warning())));
+    }
+    if let Some(name) = &query.name {
+        spans.push(Span::styled(format!("{}  ", name), muted));
+    }
+    spans.push(Span::raw(sql));
+    if query.truncated.is_some() {
+        spans.push(Span::styled(" (truncated)", muted));
+    }
+    Line::from(spans)
+}
+
+/// The issue's suggestion, indented under the query it's about
+fn callout_lines(issue: &NPlusOneIssue, privacy: &Privacy, fade: f32) -> Vec<Line<'static>> {
+    let warning = Style::default().fg(Theme::apply_fade_to_color(Theme::warning(), fade));
+    issue
+        .suggestion
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let marker = if i == 0 { "⚠ " } else { "  " };
+            Line::styled(
+                format!(
+                    "{:indent$}{}{}",
+                    "",
+                    marker,
+                    privacy.text(line.trim_start()),
+                    indent = 6
+                ),
+                warning,
+            )
+        })
+        .collect()
+}
+
+/// A bar `duration / slowest` of `width` cells, in eighths; any query that
+/// took time gets at least a sliver
+pub fn duration_bar(duration: f64, slowest: f64, width: usize) -> String {
+    if duration <= 0.0 || slowest <= 0.0 {
+        return String::new();
+    }
+    let eighths = ((duration / slowest).min(1.0) * (width * 8) as f64).round() as usize;
+    let eighths = eighths.max(1);
+    let mut bar = "█".repeat(eighths / 8);
+    if let Some(partial) = (eighths % 8).checked_sub(1) {
+        bar.push(BAR_EIGHTHS[partial]);
+    }
+    bar
+}
+
+/// Icon and color for a kind of query
+fn type_icon(kind: &QueryType) -> (&'static str, Color) {
+    match kind {
+        QueryType::Select => ("◆", Theme::info()),
+        QueryType::Insert => ("+", Theme::success()),
+        QueryType::Update => ("✎", Theme::warning()),
+        QueryType::Delete => ("✗", Theme::danger()),
+        QueryType::Begin => ("┌", Theme::text_muted()),
+        QueryType::Commit => ("└", Theme::text_muted()),
+        QueryType::Rollback => ("↺", Theme::danger()),
+        QueryType::Other => ("·", Theme::text_secondary()),
+    }
+}
+
+fn type_label(kind: &QueryType) -> &'static str {
+    match kind {
+        QueryType::Select => "SELECT",
+        QueryType::Insert => "INSERT",
+        QueryType::Update => "UPDATE",
+        QueryType::Delete => "DELETE",
+        QueryType::Begin => "BEGIN",
+        QueryType::Commit => "COMMIT",
+        QueryType::Rollback => "ROLLBACK",
+        QueryType::Other => "other",
+    }
+}
+
+fn type_order(kind: &QueryType) -> usize {
+    match kind {
+        QueryType::Select => 0,
+        QueryType::Insert => 1,
+        QueryType::Update => 2,
+        QueryType::Delete => 3,
+        QueryType::Begin => 4,
+        QueryType::Commit => 5,
+        QueryType::Rollback => 6,
+        QueryType::Other => 7,
+    }
+}
+
+/// Lines above the query list in the live view, borders included
+const LIVE_HEADER_LINES: usize = 8;
+
+/// Render a request that is still in flight, as it stands this frame
+///
+/// Queries are listed newest last and cut to the most recent that fit, so the
+/// list follows the request as it runs. Long queries are clipped at the border.
+/// The path and queries are masked while `privacy` is on.
+pub fn render_live(
+    f: &mut Frame,
+    area: Rect,
+    context: &RequestContext,
+    privacy: &Privacy,
+    fade_progress: Option<f32>,
+) {
+    let fade = fade_progress.unwrap_or(1.0);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+
+    let controller = context
+        .controller
+        .as_deref()
+        .map(|c| format!(" [{}]", c))
+        .unwrap_or_default();
+    let request_id = context
+        .short_request_id()
+        .map(|id| format!(" #{}", id))
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                "● LIVE ",
+                Style::default()
+                    .fg(Theme::apply_fade_to_color(Theme::warning(), fade))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                "{}{}",
+                privacy.text(context.path.as_deref().unwrap_or("<unknown>")),
+                controller
+            )),
+            Span::styled(request_id, muted),
+        ]),
+        Line::from(vec![
+            Span::styled("Elapsed: ", bold),
+            Span::raw(format_ms(
+                context.start_time.elapsed().as_secs_f64() * 1000.0,
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Queries: ", bold),
+            Span::raw(format!(
+                "{} ({} in SQL)",
+                context.query_count(),
+                format_ms(context.total_query_time())
+            )),
+            Span::raw("  │  "),
+            Span::styled("Views rendered: ", bold),
+            Span::raw(context.views.len().to_string()),
+        ]),
+        Line::from(Span::styled(
+            "Switches to the completed view, with N+1 results, when the request finishes",
+            muted,
+        )),
+        Line::from(""),
+        Line::from(Span::styled("Queries (newest last):", bold)),
+    ];
+
+    let room = (area.height as usize).saturating_sub(LIVE_HEADER_LINES);
+    // When they don't all fit, one line goes to the count of those left out
+    let skipped = if context.queries.len() > room {
+        context.queries.len() - room.saturating_sub(1)
+    } else {
+        0
+    };
+    if skipped > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("  … {} earlier", skipped),
+            muted,
+        )));
+    }
+    if context.queries.is_empty() {
+        lines.push(Line::from(Span::styled("  (none yet)", muted)));
+    }
+    for query in context.queries.iter().skip(skipped) {
+        lines.push(Line::from(format!(
+            "  {:>8}  {}",
+            format_ms(query.duration),
+            privacy.sql(&query.raw_query)
+        )));
+    }
+
+    let paragraph =
+        Paragraph::new(lines).block(Theme::block("Live Request (following)", fade_progress));
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryFingerprint;
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+    use ratatui::{Terminal, backend::TestBackend, buffer::Buffer};
+    use std::collections::HashMap;
+    use std::time::Instant;
+
+    fn query(raw_query: &str, duration: f64) -> QueryInfo {
+        QueryInfo {
+            raw_query: raw_query.to_string(),
+            fingerprint: QueryFingerprint::new(raw_query),
+            duration,
+            rows: None,
+            query_type: QueryType::from_sql(raw_query),
+            binds: Vec::new(),
+            name: None,
+            truncated: None,
+        }
+    }
+
+    fn comments(post_id: usize) -> QueryInfo {
+        query(
+            &format!(
+                r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = {}"#,
+                post_id
+            ),
+            1.0,
+        )
+    }
+
+    /// `GET /posts`: posts, three comment loads, and a transaction
+    fn request() -> CompletedRequest {
+        let mut context = RequestContext::new(Some("/posts".to_string()));
+        context.method = Some("GET".to_string());
+        for query in [
+            query(r#"SELECT "posts".* FROM "posts""#, 4.0),
+            comments(1),
+            comments(2),
+            comments(3),
+            query("BEGIN", 0.1),
+            query(r#"INSERT INTO "visits" ("path") VALUES ('/posts')"#, 0.9),
+            query("COMMIT", 2.0),
+        ] {
+            context.add_query(query);
+        }
+        let repeated = comments(1);
+        CompletedRequest {
+            context,
+            n_plus_one_issues: vec![NPlusOneIssue {
+                fingerprint: repeated.fingerprint,
+                count: 3,
+                total_duration: 3.0,
+                sample_query: repeated.raw_query,
+                suggestion: "Possible N+1 query detected (3 times).\n  Post.includes(:comments)"
+                    .to_string(),
+                source: Default::default(),
+            }],
+            total_duration: Some(40.0),
+            status: Some(200),
+            extra: HashMap::new(),
+            completed_at: Instant::now(),
+            duplicates: None,
+        }
+    }
+
+    fn draw(request: &CompletedRequest, scroll: usize, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(100, height)).unwrap();
+        terminal
+            .draw(|f| {
+                render(
+                    f,
+                    f.area(),
+                    Some(request),
+                    false,
+                    scroll,
+                    &Privacy::default(),
+                    None,
+                )
+            })
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn rows(buffer: &Buffer) -> Vec<String> {
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    fn row_of(rows: &[String], text: &str) -> usize {
+        rows.iter()
+            .position(|row| row.contains(text))
+            .unwrap_or_else(|| panic!("{:?} not on screen:\n{}", text, rows.join("\n")))
+    }
+
+    #[test]
+    fn test_summary_splits_sql_and_app_time_and_counts_query_types() {
+        let rows = rows(&draw(&request(), 0, 24));
+        let screen = rows.join("\n");
+        assert!(screen.contains("GET /posts  200"), "{}", screen);
+        assert!(
+            screen.contains("Duration: 40.0ms  │  SQL: 10.0ms (25%)  │  App: 30.0ms"),
+            "{}",
+            screen
+        );
+        assert!(
+            screen.contains("Queries: 7 - 4 SELECT, 1 INSERT, 1 BEGIN, 1 COMMIT"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("N+1 queries (1)"), "{}", screen);
+    }
+
+    #[test]
+    fn test_queries_are_listed_in_order_with_n_plus_one_callouts_under_them() {
+        let buffer = draw(&request(), 0, 24);
+        let rows = rows(&buffer);
+
+        let posts = row_of(&rows, r#"   1 ◆    4.0ms ████████████  SELECT "posts""#);
+        let first_comment = row_of(&rows, r#"= 1"#);
+        let callout = row_of(&rows, "⚠ Possible N+1 query detected (3 times).");
+        assert_eq!(row_of(&rows, "Post.includes(:comments)"), callout + 1);
+        let second_comment = row_of(&rows, r#"= 2"#);
+        let insert = row_of(&rows, r#"INSERT INTO "visits""#);
+        assert!(posts < first_comment && first_comment < callout);
+        assert!(callout < second_comment && second_comment < insert);
+        // Called out once, with the repeats only flagged
+        assert_eq!(
+            rows.iter()
+                .filter(|row| row.contains("Possible N+1"))
+                .count(),
+            1
+        );
+        assert!(rows[second_comment].contains("⚠ SELECT"));
+
+        // Bars are scaled to the slowest query and colored by duration
+        assert!(
+            rows[first_comment].contains(" 1.0ms ███  "),
+            "{}",
+            rows[first_comment]
+        );
+        let bar_x = rows[posts].chars().position(|c| c == '█').unwrap() as u16;
+        assert_eq!(buffer[(bar_x, posts as u16)].fg, Theme::duration_color(4.0));
+
+        // Transaction boundaries are grayed out, bar and all
+        for text in ["  BEGIN", "  COMMIT"] {
+            let row = row_of(&rows, text) as u16;
+            let line = &rows[row as usize];
+            let x = line[..line.find(text).unwrap()].chars().count() as u16;
+            assert_eq!(buffer[(x, row)].fg, Theme::text_muted());
+            assert!(!rows[row as usize].contains('█'));
+        }
+    }
+
+    #[test]
+    fn test_scrolling_moves_the_timeline_under_a_fixed_summary() {
+        let rows = rows(&draw(&request(), 3, 14));
+        let screen = rows.join("\n");
+        assert!(screen.contains("Duration: 40.0ms"), "{}", screen);
+        assert!(!screen.contains("Timeline (7 queries"), "{}", screen);
+        assert!(!screen.contains(r#"SELECT "posts""#), "{}", screen);
+        assert!(screen.contains("Possible N+1"), "{}", screen);
+    }
+
+    #[test]
+    fn test_duration_bars_are_drawn_in_eighths() {
+        assert_eq!(duration_bar(10.0, 10.0, 4), "████");
+        assert_eq!(duration_bar(5.0, 10.0, 4), "██");
+        assert_eq!(duration_bar(3.0, 10.0, 4), "█▎");
+        assert_eq!(duration_bar(0.001, 10.0, 4), "▏");
+        assert_eq!(duration_bar(0.0, 10.0, 4), "");
+    }
+
+    #[test]
+    fn test_rendered_views_show_in_request_detail_and_query_analysis() {
+        let mut app = test_app();
+        for content in [
+            r#"Started GET "/posts" for 127.0.0.1"#,
+            "  Rendered collection of posts/_post.html.erb [25 times] (Duration: 18.5ms | Allocations: 4000)",
+            "  Rendered posts/index.html.erb within layouts/application (Duration: 21.0ms | Allocations: 5000)",
+            "Completed 200 OK in 40ms (Views: 22.0ms | ActiveRecord: 3.0ms | Allocations: 9000)",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        app.view_mode = ViewMode::RequestDetail(1);
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Views (2 rendered"), "{}", screen);
+        assert!(screen.contains("posts/_post.html.erb ×25"));
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Slowest Views"), "{}", screen);
+        assert!(screen.contains("posts/index.html.erb"));
+    }
+
+    #[test]
+    fn test_bullet_report_shows_in_request_detail_only_when_the_gem_is_present() {
+        let lines = [
+            r#"Started GET "/posts" for 127.0.0.1"#,
+            r#"SELECT "posts".* FROM "posts""#,
+            r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = 1"#,
+            r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = 2"#,
+            r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = 3"#,
+            "Completed 200 OK in 20ms (Views: 10.0ms | ActiveRecord: 1.0ms)",
+            "user: alice",
+            "GET /posts",
+            "USE eager loading detected",
+            "  Post => [:comments]",
+            "  Add to your query: .includes([:comments])",
+        ];
+        let detail = |gems: InstrumentationGems| {
+            let mut app = test_app().with_instrumentation(gems);
+            for content in lines {
+                app.add_log(LogLine::new("web", content.to_string()));
+            }
+            app.view_mode = ViewMode::RequestDetail(1);
+            render_app(&app, 120, 40)
+        };
+
+        let screen = detail(InstrumentationGems {
+            bullet: true,
+            ..InstrumentationGems::default()
+        });
+        assert!(screen.contains("N+1 queries (1)"), "{}", screen);
+        assert!(screen.contains("Post => [:comments]"), "{}", screen);
+        assert!(!screen.contains("Possible N+1"), "{}", screen);
+
+        let screen = detail(InstrumentationGems::default());
+        assert!(screen.contains("Possible N+1"), "{}", screen);
+        assert!(!screen.contains("Bullet"), "{}", screen);
+    }
+
+    #[test]
+    fn test_request_detail_lists_binds_under_each_query() {
+        let mut app = test_app();
+        for content in [
+            r#"Started GET "/users/7" for 127.0.0.1"#,
+            r#"  User Load (0.4ms)  SELECT "users".* FROM "users" WHERE "users"."id" = $1 AND "users"."email" = $2 LIMIT $3  [["id", 7], ["email", "jane@example.com"], ["LIMIT", 1]]"#,
+            "Completed 200 OK in 12ms (Views: 5.0ms | ActiveRecord: 0.4ms)",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+        let idx = app
+            .context_tracker
+            .get_recent_requests()
+            .iter()
+            .position(|req| req.context.path.as_deref() == Some("/users/7"))
+            .unwrap();
+        app.view_mode = ViewMode::RequestDetail(idx);
+
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains(r#"= $2 LIMIT $3 "#) && !screen.contains("[[\"id\""),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("id    = 7"), "{}", screen);
+        assert!(
+            screen.contains(r#"email = "jane@example.com""#),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("LIMIT = 1"), "{}", screen);
+
+        app.privacy.set_enabled(true);
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains(r#"email = "•••""#), "{}", screen);
+        assert!(screen.contains("id    = 7"), "{}", screen);
+    }
+
+    #[test]
+    fn test_request_detail_scrolls_with_the_arrow_and_page_keys() {
+        let mut app = test_app();
+        let mut lines = vec![r#"Started GET "/orders" for 127.0.0.1"#.to_string()];
+        lines.extend((1..=40).map(|id| {
+            format!(
+                r#"  Order Load (0.5ms)  SELECT "orders".* FROM "orders" WHERE "orders"."id" = {}"#,
+                id
+            )
+        }));
+        lines.push("Completed 200 OK in 30ms".to_string());
+        for content in lines {
+            app.add_log(LogLine::new("web", content));
+        }
+        app.selected_request = 1;
+        app.view_selected_request();
+        assert_eq!(app.view_mode, ViewMode::RequestDetail(1));
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains(r#""orders"."id" = 1"#), "{}", screen);
+        assert!(!screen.contains(r#""orders"."id" = 40"#), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::PageDown));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.request_detail_scroll, 11);
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Duration: 30.0ms"), "{}", screen);
+        assert!(!screen.contains(r#""orders"."id" = 1 "#), "{}", screen);
+
+        // Stops with the last query still on screen
+        for _ in 0..10 {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::PageDown));
+        }
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains(r#""orders"."id" = 40"#), "{}", screen);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert!(render_app(&app, 120, 40).contains(r#""orders"."id" = 40"#));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Home));
+        assert_eq!(app.request_detail_scroll, 0);
+
+        // Another request opens at the top
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::PageDown));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        app.view_selected_request();
+        assert_eq!(app.request_detail_scroll, 0);
+    }
+
+    #[test]
+    fn test_cache_hit_rate_in_header_and_request_detail() {
+        let mut app = test_app();
+        for content in [
+            r#"Started GET "/products" for 127.0.0.1"#,
+            "Cache read: views/products/1 (hit)",
+            "Cache read: views/products/2 (miss)",
+            "Cache read: views/products/3 (miss)",
+            "Cache write: views/products/2",
+            "Completed 200 OK in 12ms",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        let screen = render_app(&app, 160, 40);
+        assert!(screen.contains("cache 33% hit"), "{}", screen);
+
+        app.view_mode = ViewMode::RequestDetail(1);
+        let screen = render_app(&app, 120, 40);
+        assert!(
+            screen.contains("Cache: 3 reads (1 hits, 2 misses), 1 writes, 0 deletes"),
+            "{}",
+            screen
+        );
+
+        app.view_mode = ViewMode::QueryAnalysis;
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Cache (by key prefix)"), "{}", screen);
+    }
+
+    #[test]
+    fn test_request_queueing_in_header_and_request_detail() {
+        let mut app = test_app();
+        for content in [
+            "source=rack-timeout id=4f2a wait=320ms timeout=15000ms state=ready",
+            r#"Started GET "/products" for 127.0.0.1"#,
+            "Completed 200 OK in 12ms",
+        ] {
+            app.add_log(LogLine::new("web", content.to_string()));
+        }
+
+        let screen = render_app(&app, 160, 40);
+        assert!(
+            screen.contains("Queueing 320.0ms avg - at capacity"),
+            "{}",
+            screen
+        );
+
+        app.view_mode = ViewMode::RequestDetail(1);
+        let screen = render_app(&app, 120, 40);
+        assert!(
+            screen.contains("Queueing: 320.0ms (logged, not in Duration)"),
+            "{}",
+            screen
+        );
+
+        // The request without a queueing signal shows nothing
+        app.view_mode = ViewMode::RequestDetail(0);
+        assert!(!render_app(&app, 120, 40).contains("Queueing:"));
+    }
 }
@@ -0,0 +1,142 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+};
+
+use crate::database::{DatabaseHealth, TableDetail};
+use crate::privacy::Privacy;
+use crate::ui::formatting::format_ms;
+use crate::ui::theme::Theme;
+
+/// Render one table's slow queries, index coverage and issues
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    db_health: &DatabaseHealth,
+    table: &str,
+    privacy: &Privacy,
+    fade_progress: Option<f32>,
+) {
+    let detail = db_health.get_table_detail(table);
+    let paragraph = Paragraph::new(detail_lines(&detail, privacy))
+        .block(Theme::block(
+            format!("Table Detail: {}", detail.name),
+            fade_progress,
+        ))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn detail_lines(detail: &TableDetail, privacy: &Privacy) -> Vec<Line<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Slow queries: ", bold),
+            Span::raw(format!(
+                "{} ({} executions)",
+                detail.slow_queries.len(),
+                detail.executions()
+            )),
+            Span::raw("  │  "),
+            Span::styled("Total: ", bold),
+            Span::raw(format_ms(detail.total_slow_time())),
+            Span::raw("  │  "),
+            Span::styled("Avg: ", bold),
+            Span::raw(format_ms(detail.avg_slow_time())),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Filtered columns:", bold)),
+    ];
+
+    if detail.filtered_columns.is_empty() {
+        lines.push(Line::from("  (none)"));
+    } else if !detail.in_schema {
+        let names: Vec<&str> = detail
+            .filtered_columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        lines.push(Line::from(format!(
+            "  {} (table not in db/schema.rb, index status unknown)",
+            names.join(", ")
+        )));
+    } else {
+        for column in &detail.filtered_columns {
+            let (mark, label, color) = if column.indexed == Some(true) {
+                ("✓", "indexed", Theme::success())
+            } else {
+                ("✗", "no index", Theme::warning())
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {} ", column.name)),
+                Span::styled(format!("{} {}", mark, label), Style::default().fg(color)),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("Issues ({}):", detail.issues.len()),
+        bold,
+    )));
+    if detail.issues.is_empty() {
+        lines.push(Line::from("  (none)"));
+    }
+    for issue in &detail.issues {
+        lines.push(Line::from(format!("  • {}", issue.title)));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Slow queries (slowest first):",
+        bold,
+    )));
+    for query in &detail.slow_queries {
+        lines.push(Line::from(format!(
+            "  • {} (×{})  {}",
+            format_ms(query.duration),
+            query.execution_count,
+            privacy.sql(&query.query)
+        )));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_top_table_drills_down_to_table_detail() {
+        let app_db = std::sync::Arc::new(DatabaseHealth::new());
+        app_db.analyze_query(
+            r#"SELECT * FROM "users" WHERE "users"."email" = 'a'"#,
+            300.0,
+        );
+        app_db.analyze_query(r#"SELECT * FROM "orders" WHERE "orders"."id" = 1"#, 200.0);
+        app_db.analyze_query(r#"SELECT * FROM "orders" WHERE "orders"."id" = 2"#, 200.0);
+        let mut app = test_app();
+        app.db_health = app_db;
+        app.view_mode = ViewMode::DatabaseHealth;
+
+        let screen = render_app(&app, 120, 30);
+        assert!(screen.contains("Top Tables"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.view_mode, ViewMode::TableDetail("users".to_string()));
+
+        let screen = render_app(&app, 120, 30);
+        assert!(screen.contains("Table Detail: users"), "{}", screen);
+        assert!(screen.contains("Total: 300.0ms"));
+        assert!(screen.contains("index status unknown"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.view_mode, ViewMode::DatabaseHealth);
+    }
+}
@@ -1,25 +1,106 @@
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::Style,
-    widgets::{Cell, Row, Table},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Cell, Paragraph, Row, Table},
 };
 
-use crate::test::TestTracker;
-use crate::ui::theme::Theme;
+use crate::search::SearchQuery;
+use crate::test::{DebuggerPause, TestResult, TestStatus, TestTracker};
+use crate::ui::components::EmptyState;
+use crate::ui::formatting::{format_duration, format_ms};
+use crate::ui::theme::{Icons, Theme};
 
+/// Backtrace frames shown under an expanded failure
+const BACKTRACE_SHOWN: usize = 5;
+
+/// Render the test results view
+///
+/// The latest run's failures are listed under the totals, `selected_failure`
+/// highlighted and `expanded_failure` showing its message and backtrace. A
+/// non-empty `search_query` adds a list of test results whose names match.
+/// While a breakpoint has the current run stopped, a banner says where.
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     f: &mut Frame,
     area: Rect,
     test_tracker: &TestTracker,
+    search_query: &str,
+    selected_failure: usize,
+    expanded_failure: Option<usize>,
+    spinner_frame: usize,
+    fade_progress: Option<f32>,
+) {
+    let area = match test_tracker.debugger_pause() {
+        Some(pause) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            render_pause_banner(f, chunks[0], &pause);
+            chunks[1]
+        }
+        None => area,
+    };
+    render_results(
+        f,
+        area,
+        test_tracker,
+        search_query,
+        selected_failure,
+        expanded_failure,
+        spinner_frame,
+        fade_progress,
+    );
+}
+
+/// "⏸ Paused in debugger at app/models/user.rb:27 (2m 31s)"
+fn render_pause_banner(f: &mut Frame, area: Rect, pause: &DebuggerPause) {
+    let location = pause
+        .location()
+        .map(|location| format!(" at {}", location))
+        .unwrap_or_default();
+    let banner = format!(
+        "⏸ Paused in debugger{} ({}) - not counted in test durations",
+        location,
+        format_duration((pause.elapsed_ms() / 1000.0) as u64)
+    );
+    f.render_widget(
+        Paragraph::new(banner).style(
+            Style::default()
+                .fg(Theme::warning())
+                .add_modifier(Modifier::BOLD),
+        ),
+        area,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_results(
+    f: &mut Frame,
+    area: Rect,
+    test_tracker: &TestTracker,
+    search_query: &str,
+    selected_failure: usize,
+    expanded_failure: Option<usize>,
     _spinner_frame: usize,
     fade_progress: Option<f32>,
 ) {
+    if test_tracker.is_empty() {
+        EmptyState::new(Icons::test(), "No test runs yet")
+            .with_hint("Waiting for RSpec, Minitest or Test::Unit output from any process")
+            .with_hint("Results show as a run prints them; totals once it finishes")
+            .with_tip("add a watcher such as `test: bundle exec guard` to your Procfile")
+            .render(f, area, "Test Results", fade_progress);
+        return;
+    }
+
     let stats = test_tracker.get_stats();
 
     if stats.total_runs == 0 {
         let block = Theme::block("Test Results", fade_progress);
-        let empty = ratatui::widgets::Paragraph::new("Waiting for test results...")
+        let empty = ratatui::widgets::Paragraph::new("Waiting for the first test run to finish...")
             .style(Style::default().fg(Theme::text_muted()))
             .block(block);
         f.render_widget(empty, area);
@@ -51,22 +132,45 @@ pub fn render(
     ];
 
     // Add debugger status (only show if active)
-    if test_tracker.is_debugger_active() {
-        if let Some(info) = test_tracker.get_debugger_info() {
-            let debugger_text = format!(
-                "{:?} @ {}:{}",
-                info.debugger_type,
-                info.file_path.as_deref().unwrap_or("unknown"),
-                info.line_number
-                    .map(|n| n.to_string())
-                    .unwrap_or_else(|| "?".to_string())
-            );
-            rows.push(
-                Row::new(vec![Cell::from("⚡ Debugger"), Cell::from(debugger_text)])
-                    .style(Style::default().fg(Theme::warning())),
-            );
-        }
+    if test_tracker.is_debugger_active()
+        && let Some(info) = test_tracker.get_debugger_info()
+    {
+        let debugger_text = format!(
+            "{:?} @ {}:{}",
+            info.debugger_type,
+            info.file_path.as_deref().unwrap_or("unknown"),
+            info.line_number
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        );
+        rows.push(
+            Row::new(vec![Cell::from("⚡ Debugger"), Cell::from(debugger_text)])
+                .style(Style::default().fg(Theme::warning())),
+        );
+    }
+
+    let failures: Vec<TestResult> = test_tracker
+        .latest_run()
+        .map(|run| run.failed_tests().into_iter().cloned().collect())
+        .unwrap_or_default();
+    let search = SearchQuery::new(search_query);
+
+    let mut constraints = vec![Constraint::Length(rows.len() as u16 + 2)];
+    if !failures.is_empty() {
+        constraints.push(Constraint::Min(0));
     }
+    if !search.is_empty() {
+        constraints.push(Constraint::Min(0));
+    }
+    let chunks = if constraints.len() == 1 {
+        vec![area]
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area)
+            .to_vec()
+    };
 
     let table = Table::new(
         rows,
@@ -77,5 +181,245 @@ pub fn render(
     )
     .block(Theme::block("Test Results", fade_progress));
 
+    f.render_widget(table, chunks[0]);
+
+    let mut next = 1;
+    if !failures.is_empty() {
+        render_failures(
+            f,
+            chunks[next],
+            &failures,
+            selected_failure,
+            expanded_failure,
+            fade_progress,
+        );
+        next += 1;
+    }
+    if !search.is_empty() {
+        render_matching_tests(f, chunks[next], test_tracker, &search, fade_progress);
+    }
+}
+
+/// The latest run's failures, one line each with where the example is; the
+/// expanded one shows its message and the top of its backtrace beneath
+fn render_failures(
+    f: &mut Frame,
+    area: Rect,
+    failures: &[TestResult],
+    selected: usize,
+    expanded: Option<usize>,
+    fade_progress: Option<f32>,
+) {
+    let selected = selected.min(failures.len() - 1);
+    let mut lines = Vec::new();
+    let mut selected_line = 0;
+    for (index, failure) in failures.iter().enumerate() {
+        if index == selected {
+            selected_line = lines.len();
+        }
+        lines.push(failure_line(
+            failure,
+            index == selected,
+            expanded == Some(index),
+        ));
+        if expanded == Some(index) {
+            lines.extend(failure_details(failure));
+        }
+    }
+
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = (selected_line + 1).saturating_sub(height);
+    let paragraph = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(Theme::block(
+            format!(
+                "Failed Tests ({}) - ↑/↓ Navigate, Enter Show Message",
+                failures.len()
+            ),
+            fade_progress,
+        ));
+    f.render_widget(paragraph, area);
+}
+
+/// `▸ ✗ User validates email  ./spec/models/user_spec.rb:42`
+fn failure_line(failure: &TestResult, selected: bool, expanded: bool) -> Line<'static> {
+    let marker = if expanded { "▾" } else { "▸" };
+    let mut name = Style::default().fg(Theme::danger());
+    if selected {
+        name = name.bg(Theme::surface()).add_modifier(Modifier::BOLD);
+    }
+    let mut spans = vec![
+        Span::styled(
+            format!("{} ", marker),
+            Style::default().fg(Theme::text_muted()),
+        ),
+        Span::styled(format!("✗ {}", failure.test_name), name),
+    ];
+    if let Some(file) = &failure.file_path {
+        let location = match failure.line_number {
+            Some(line) => format!("{}:{}", file, line),
+            None => file.clone(),
+        };
+        spans.push(Span::styled(
+            format!("  {}", location),
+            Style::default().fg(Theme::text_muted()),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// The failure message, diff lines colored, then the first backtrace frames
+fn failure_details(failure: &TestResult) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = match &failure.failure_message {
+        Some(message) => message
+            .lines()
+            .map(|line| {
+                Line::from(Span::styled(
+                    format!("    {}", line),
+                    Style::default().fg(message_color(line)),
+                ))
+            })
+            .collect(),
+        None => vec![Line::from(Span::styled(
+            "    No failure message was printed",
+            Style::default().fg(Theme::text_muted()),
+        ))],
+    };
+    let backtrace = failure.backtrace.as_deref().unwrap_or_default();
+    lines.extend(backtrace.iter().take(BACKTRACE_SHOWN).map(|frame| {
+        Line::from(Span::styled(
+            format!("    # {}", frame),
+            Style::default().fg(Theme::text_muted()),
+        ))
+    }));
+    if backtrace.len() > BACKTRACE_SHOWN {
+        lines.push(Line::from(Span::styled(
+            format!("    # ... {} more", backtrace.len() - BACKTRACE_SHOWN),
+            Style::default().fg(Theme::text_muted()),
+        )));
+    }
+    lines
+}
+
+/// Removed and added lines of a diff in red and green
+fn message_color(line: &str) -> Color {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('-') && !trimmed.starts_with("---") {
+        Theme::danger()
+    } else if trimmed.starts_with('+') && !trimmed.starts_with("+++") {
+        Theme::success()
+    } else {
+        Theme::text_primary()
+    }
+}
+
+fn render_matching_tests(
+    f: &mut Frame,
+    area: Rect,
+    test_tracker: &TestTracker,
+    search: &SearchQuery,
+    fade_progress: Option<f32>,
+) {
+    let results = test_tracker.get_test_results_matching(search);
+
+    let rows: Vec<Row> = results
+        .iter()
+        .map(|result| {
+            let (status, color) = match result.status {
+                TestStatus::Passed => ("passed", Theme::success()),
+                TestStatus::Failed => ("failed", Theme::danger()),
+                TestStatus::Pending => ("pending", Theme::warning()),
+                TestStatus::Skipped => ("skipped", Theme::text_muted()),
+            };
+
+            Row::new(vec![
+                Cell::from(result.test_name.clone()),
+                Cell::from(status),
+                Cell::from(result.duration.map(format_ms).unwrap_or_default()),
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        &[
+            ratatui::layout::Constraint::Min(20),
+            ratatui::layout::Constraint::Length(8),
+            ratatui::layout::Constraint::Length(10),
+        ],
+    )
+    .block(Theme::block(
+        format!("Matching Tests ({})", results.len()),
+        fade_progress,
+    ));
+
     f.render_widget(table, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_results_show_where_a_run_is_paused_in_the_debugger() {
+        let mut app = test_app();
+        app.test_tracker
+            .start_test_run(crate::test::TestFramework::RSpec);
+        app.test_tracker
+            .parse_line("From: /app/app/models/user.rb:27 User#full_name:");
+        app.test_tracker.parse_line("[1] pry(#<User>)>");
+        app.view_mode = ViewMode::TestResults;
+        app.active_tab_index = 3;
+
+        let screen = render_app(&app, 120, 30);
+        assert!(
+            screen.contains("Paused in debugger at /app/app/models/user.rb:27 (0s)"),
+            "{}",
+            screen
+        );
+
+        app.test_tracker.parse_line(".");
+        assert!(!render_app(&app, 120, 30).contains("Paused in debugger"));
+    }
+
+    #[test]
+    fn test_results_list_failures_and_expand_their_messages() {
+        let mut app = test_app();
+        let log = std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures/rspec/documentation.log"),
+        )
+        .unwrap();
+        for line in log.lines() {
+            app.test_tracker.parse_line(line);
+        }
+        app.view_mode = ViewMode::TestResults;
+        app.active_tab_index = 3;
+
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("Failed Tests (2)"), "{}", screen);
+        assert!(
+            screen.contains("✗ User validations validates email  ./spec/models/user_spec.rb:14")
+        );
+        assert!(!screen.contains("Failure/Error"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.expanded_test_failure, Some(1));
+        let screen = render_app(&app, 120, 40);
+        assert!(
+            screen.contains("▾ ✗ Order#total sums the line item prices"),
+            "{}",
+            screen
+        );
+        assert!(screen.contains("Failure/Error: items.sum(&:price)"));
+        assert!(screen.contains("undefined method `price' for nil:NilClass"));
+        assert!(screen.contains("# ./app/models/order.rb:22:in `sum'"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.expanded_test_failure, None);
+        assert!(!render_app(&app, 120, 40).contains("Failure/Error"));
+    }
+}
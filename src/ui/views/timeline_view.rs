@@ -0,0 +1,381 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeDelta};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::clock::format_approx;
+use crate::stats::{StatsCollector, TIMELINE_BUCKET, TimelineBucket};
+use crate::time_format::DisplayZone;
+use crate::ui::components::EmptyState;
+use crate::ui::theme::{Icons, Theme};
+
+/// Bucket widths `+` / `-` step through, narrowest first
+pub const ZOOM_LEVELS: [TimeDelta; 4] = [
+    TimeDelta::seconds(10),
+    TimeDelta::minutes(1),
+    TimeDelta::minutes(5),
+    TimeDelta::minutes(15),
+];
+
+/// Rows under the bars: axis, time labels, selected bucket, legend
+const FOOTER_ROWS: u16 = 4;
+
+/// Columns between time labels on the axis
+const LABEL_EVERY: usize = 12;
+
+const BAR_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Bar cells of buckets the machine slept through
+const ASLEEP: char = '░';
+
+/// Render the session timeline
+///
+/// # Layout
+///
+/// ```text
+/// ┌ Timeline (10s buckets) ──────────────────────────┐
+/// │     ●        ↻                     ✗             │  markers
+/// │   ▂▅█▃     ▁▂▁  ▃▄                 ▆▇            │  request volume
+/// │──────────────────────────────────────────────────│
+/// │14:30:00    14:32:00    14:34:00    14:36:00      │
+/// │14:36:10–14:36:20  12 requests (3 errors) · 1 crash│
+/// │✗ crash  ● exception  ↻ restart  ◆ deploy  ░ asleep│
+/// └──────────────────────────────────────────────────┘
+/// ```
+///
+/// One column per bucket, the newest at `anchor` on the right until the
+/// selection moves further back than fits.
+#[allow(clippy::too_many_arguments)]
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    stats: &StatsCollector,
+    width: TimeDelta,
+    anchor: DateTime<Local>,
+    selected: DateTime<Local>,
+    zone: DisplayZone,
+    fade_progress: Option<f32>,
+) {
+    let title = format!("Timeline ({} buckets)", zoom_label(width));
+    if stats.timeline_is_empty() {
+        EmptyState::new(Icons::info(), "Nothing on the timeline yet")
+            .with_hint("Waiting for requests, exceptions, crashes, restarts, deploys or test runs")
+            .with_hint("Each one is charted in the bucket it happened in")
+            .with_tip("`+` / `-` zoom once there's something to see")
+            .render(f, area, &title, fade_progress);
+        return;
+    }
+
+    let fade = fade_progress.unwrap_or(1.0);
+    let block = Theme::block(title, fade_progress);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+
+    let count = inner.width as usize;
+    let span = width * (count as i32 - 1);
+    let end = if selected + span < anchor {
+        selected + span
+    } else {
+        anchor
+    };
+    let buckets = stats.timeline_window(end, width, count);
+
+    let muted = Style::default().fg(Theme::apply_fade_to_color(Theme::text_muted(), fade));
+    let bar_rows = inner.height.saturating_sub(FOOTER_ROWS + 1).max(1) as usize;
+    let peak = buckets.iter().map(|(_, b)| b.requests).max().unwrap_or(0);
+    let selected_column = buckets.iter().position(|(start, _)| *start == selected);
+
+    let mut lines = Vec::new();
+    lines.push(Line::from(
+        buckets
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bucket))| {
+                let (symbol, color) = marker(bucket).unwrap_or((" ", Theme::text_muted()));
+                let style = Style::default().fg(Theme::apply_fade_to_color(color, fade));
+                Span::styled(symbol, highlight(style, Some(i) == selected_column))
+            })
+            .collect::<Vec<_>>(),
+    ));
+
+    for row in (0..bar_rows).rev() {
+        lines.push(Line::from(
+            buckets
+                .iter()
+                .enumerate()
+                .map(|(i, (_, bucket))| {
+                    let color = if bucket.errors > 0 {
+                        Theme::danger()
+                    } else {
+                        Theme::primary()
+                    };
+                    let style = Style::default().fg(Theme::apply_fade_to_color(color, fade));
+                    // Time spent asleep is shaded rather than left looking quiet
+                    if bucket.suspended > 0 && bucket.requests == 0 {
+                        return Span::styled(
+                            ASLEEP.to_string(),
+                            highlight(muted, Some(i) == selected_column),
+                        );
+                    }
+                    let cell = bar_cell(bucket.requests, peak, bar_rows, row);
+                    Span::styled(
+                        cell.to_string(),
+                        highlight(style, Some(i) == selected_column),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        ));
+    }
+
+    lines.push(Line::styled("─".repeat(count), muted));
+
+    let mut labels = String::new();
+    for (i, (start, _)) in buckets.iter().enumerate().step_by(LABEL_EVERY) {
+        let label = zone.clock(*start);
+        if i + label.chars().count() <= count {
+            labels.push_str(&" ".repeat(i - labels.chars().count()));
+            labels.push_str(&label);
+        }
+    }
+    lines.push(Line::styled(labels, muted));
+
+    let bucket = buckets
+        .iter()
+        .find(|(start, _)| *start == selected)
+        .map(|(_, bucket)| *bucket)
+        .unwrap_or_default();
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!(
+                "{}–{}  ",
+                zone.clock(selected),
+                zone.clock(selected + width)
+            ),
+            Style::default()
+                .fg(Theme::apply_fade_to_color(Theme::text_primary(), fade))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(describe(&bucket)),
+    ]));
+    lines.push(Line::styled(
+        "✗ crash  ● exception  ↻ restart  ◆ deploy  ✓ test run  ░ asleep  · Enter shows its logs",
+        muted,
+    ));
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// "10s", "1m", "15m"
+fn zoom_label(width: TimeDelta) -> String {
+    if width.num_seconds() % 60 == 0 {
+        format!("{}m", width.num_minutes())
+    } else {
+        format!("{}s", width.num_seconds())
+    }
+}
+
+/// The most serious event in a bucket, as a marker and its color
+fn marker(bucket: &TimelineBucket) -> Option<(&'static str, ratatui::style::Color)> {
+    if bucket.crashes > 0 {
+        Some(("✗", Theme::danger()))
+    } else if bucket.exceptions > 0 {
+        Some(("●", Theme::warning()))
+    } else if bucket.restarts > 0 {
+        Some(("↻", Theme::info()))
+    } else if bucket.deploys > 0 {
+        Some(("◆", Theme::accent()))
+    } else if bucket.test_runs > 0 {
+        Some(("✓", Theme::success()))
+    } else {
+        None
+    }
+}
+
+/// Row `row` (0 at the bottom) of a bar `value / peak` of `rows` high
+fn bar_cell(value: u32, peak: u32, rows: usize, row: usize) -> char {
+    if value == 0 || peak == 0 {
+        return ' ';
+    }
+    // In eighths of a row; any request shows at least the lowest block
+    let height = ((value as usize * rows * 8).div_ceil(peak as usize)).max(1);
+    let filled = height.saturating_sub(row * 8);
+    match filled {
+        0 => ' ',
+        n if n >= 8 => BAR_LEVELS[7],
+        n => BAR_LEVELS[n - 1],
+    }
+}
+
+fn highlight(style: Style, selected: bool) -> Style {
+    if selected {
+        style.bg(Theme::surface()).add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    }
+}
+
+/// "12 requests (3 errors) · 1 exception · 1 restart"
+fn describe(bucket: &TimelineBucket) -> String {
+    if bucket.is_empty() {
+        return "Nothing recorded".to_string();
+    }
+    let count = |n: u32, one: &str, many: &str| {
+        (n > 0).then(|| format!("{} {}", n, if n == 1 { one } else { many }))
+    };
+    let requests = count(bucket.requests, "request", "requests").map(|requests| {
+        match count(bucket.errors, "error", "errors") {
+            Some(errors) => format!("{} ({})", requests, errors),
+            None => requests,
+        }
+    });
+    [
+        requests,
+        count(bucket.exceptions, "exception", "exceptions"),
+        count(bucket.crashes, "crash", "crashes"),
+        count(bucket.restarts, "restart", "restarts"),
+        count(bucket.deploys, "deploy", "deploys"),
+        count(bucket.test_runs, "test run", "test runs"),
+        (bucket.suspended > 0).then(|| {
+            let asleep = TIMELINE_BUCKET.num_seconds() as u64 * u64::from(bucket.suspended);
+            format!(
+                "system suspended {}",
+                format_approx(Duration::from_secs(asleep))
+            )
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::test_support::*;
+    use crate::ui::*;
+
+    #[test]
+    fn test_timeline_zooms_pans_and_opens_a_bucket_in_logs() {
+        let mut app = test_app();
+        app.logs.clear();
+        let width = views::timeline_view::ZOOM_LEVELS[0];
+        let bucket =
+            crate::stats::bucket_start(chrono::Local::now() - chrono::TimeDelta::minutes(3), width);
+        app.add_log(LogLine {
+            time: bucket + chrono::TimeDelta::seconds(2),
+            ..LogLine::new("web", "Completed 500 Internal Server Error in 12ms")
+        });
+
+        app.view_mode = ViewMode::Timeline;
+        app.active_tab_index = 5;
+        app.timeline_cursor = Some(bucket + chrono::TimeDelta::seconds(5));
+        let screen = render_app(&app, 120, 30);
+        assert!(screen.contains("Timeline (10s buckets)"), "{}", screen);
+        assert!(screen.contains("1 request (1 error)"), "{}", screen);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('-')));
+        assert!(render_app(&app, 120, 30).contains("Timeline (1m buckets)"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('+')));
+        assert_eq!(app.timeline_width(), width);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Right));
+        assert_eq!(app.timeline_selected(), bucket + width);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Left));
+        assert_eq!(app.timeline_selected(), bucket);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.view_mode, ViewMode::Logs);
+        assert_eq!(app.active_tab_index, 0);
+        assert_eq!(
+            app.time_range,
+            Some(TimeRange::between(bucket, bucket + width))
+        );
+        let shown: Vec<&str> = app
+            .filtered_logs()
+            .iter()
+            .map(|log| log.content.as_str())
+            .collect();
+        assert_eq!(shown, ["Completed 500 Internal Server Error in 12ms"]);
+        assert!(app.last_command_result.as_ref().unwrap().is_success());
+
+        // End goes back to following the newest bucket
+        app.view_mode = ViewMode::Timeline;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::End));
+        assert!(app.timeline_cursor.is_none());
+    }
+
+    #[test]
+    fn test_suspension_is_logged_marked_on_the_timeline_and_uptimes_annotated() {
+        use crate::clock::MockClock;
+
+        let clock = std::sync::Arc::new(MockClock::new());
+        let mut app = test_app().with_clock(clock.clone());
+        app.update_processes(std::sync::Arc::new(vec![ProcessInfo {
+            name: "web".into(),
+            command: "bin/rails s".into(),
+            status: crate::process::ProcessStatus::Running,
+            start_time: Some(clock.now()),
+            pid: Some(42),
+            last_exit: None,
+            restarts: 0,
+            crash_count: 0,
+            spawn_error: None,
+        }]));
+        app.check_suspend();
+        for _ in 0..65 {
+            clock.advance(Duration::from_secs(1));
+            app.check_suspend();
+        }
+        app.show_status = true;
+        assert!(render_app(&app, 120, 40).contains(" 1m 5s "));
+
+        // The lid closes for 7h; the monotonic clock doesn't see it
+        clock.sleep(Duration::from_secs(7 * 3600));
+        clock.advance(Duration::from_millis(100));
+        app.check_suspend();
+
+        let notes: Vec<&str> = app
+            .logs
+            .iter()
+            .filter(|log| log.content.starts_with("System suspended"))
+            .map(|log| log.content.as_str())
+            .collect();
+        assert_eq!(notes.len(), 1);
+        assert!(
+            notes[0].starts_with("System suspended ~7h ("),
+            "{}",
+            notes[0]
+        );
+        let screen = render_app(&app, 120, 40);
+        assert!(screen.contains("~1m 5s"), "{}", screen);
+
+        let asleep: u32 = app
+            .stats_collector
+            .timeline_window(clock.wall(), chrono::TimeDelta::hours(1), 8)
+            .iter()
+            .map(|(_, bucket)| bucket.suspended)
+            .sum();
+        // Longer than the timeline keeps, so all of it is asleep
+        assert_eq!(asleep as usize, crate::stats::MAX_TIMELINE_BUCKETS);
+
+        // Ticks after waking up are ordinary again
+        clock.advance(Duration::from_secs(1));
+        app.check_suspend();
+        assert_eq!(
+            app.logs
+                .iter()
+                .filter(|log| log.content.starts_with("System suspended"))
+                .count(),
+            1
+        );
+    }
+}
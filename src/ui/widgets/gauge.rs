@@ -81,22 +81,20 @@ impl<'a> Widget for Gauge<'a> {
             return;
         }
 
-        let filled_width = (gauge_area.width as u16 * self.percent) / 100;
+        let filled_width = (gauge_area.width * self.percent) / 100;
 
         // Render filled portion with gradient
         for i in 0..filled_width {
             let p = (i * 100) / gauge_area.width;
             let color = self.get_gradient_color(p);
             let cell = &mut buf[(gauge_area.x + i, gauge_area.y)];
-            cell.set_symbol(symbols::block::FULL)
-                .set_fg(color);
+            cell.set_symbol(symbols::block::FULL).set_fg(color);
         }
 
         // Render empty portion
         for i in filled_width..gauge_area.width {
             let cell = &mut buf[(gauge_area.x + i, gauge_area.y)];
-            cell.set_symbol(" ")
-                .set_style(self.gauge_style);
+            cell.set_symbol(" ").set_style(self.gauge_style);
         }
 
         // Render label
@@ -1,10 +1,12 @@
 /// Reusable UI widget components
 pub mod gauge;
+pub mod sortable_table;
 pub mod sparkline;
 pub mod spinner;
 pub mod visual_bar;
 
 pub use gauge::Gauge;
-pub use sparkline::Sparkline;
+pub use sortable_table::{Column, SortChange, SortKey, SortState, SortableTable};
+pub use sparkline::{Aggregation, Sparkline};
 pub use spinner::Spinner;
 pub use visual_bar::VisualBar;
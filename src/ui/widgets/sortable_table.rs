@@ -0,0 +1,474 @@
+//! Table whose rows sort by any of its columns
+//!
+//! Each column knows how to draw its cell and what to sort by. The column
+//! sorted on is marked ▲ / ▼ in the header; ties fall back to an optional
+//! secondary column, then to the order the rows came in.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Row, Table, TableState},
+};
+
+/// What a row sorts by in one column
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKey {
+    Number(f64),
+    /// Compared ignoring case
+    Text(String),
+}
+
+impl SortKey {
+    fn compare(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a
+                .chars()
+                .flat_map(char::to_lowercase)
+                .cmp(b.chars().flat_map(char::to_lowercase)),
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// The column rows are sorted by, and which way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortState {
+    pub column: usize,
+    pub descending: bool,
+}
+
+impl SortState {
+    pub const fn new(column: usize, descending: bool) -> Self {
+        Self { column, descending }
+    }
+}
+
+/// A key press changing the sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortChange {
+    /// `>`: the column to the right
+    Next,
+    /// `<`: the column to the left
+    Previous,
+    /// A number key: that column, or the other way round if already on it
+    Column(usize),
+}
+
+type CellFn<'a, T> = Box<dyn Fn(&T) -> Line<'a> + 'a>;
+type KeyFn<'a, T> = Box<dyn Fn(&T) -> SortKey + 'a>;
+
+/// One column of a [`SortableTable`]
+pub struct Column<'a, T> {
+    title: &'a str,
+    width: Constraint,
+    alignment: Alignment,
+    descending: bool,
+    cell: CellFn<'a, T>,
+    key: KeyFn<'a, T>,
+}
+
+impl<'a, T> Column<'a, T> {
+    /// A left-aligned column, sorted ascending when chosen
+    pub fn new(
+        title: &'a str,
+        width: Constraint,
+        cell: impl Fn(&T) -> Line<'a> + 'a,
+        key: impl Fn(&T) -> SortKey + 'a,
+    ) -> Self {
+        Self {
+            title,
+            width,
+            alignment: Alignment::Left,
+            descending: false,
+            cell: Box::new(cell),
+            key: Box::new(key),
+        }
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sort largest first when the column is chosen, as for counts and durations
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+}
+
+/// A table of `T` rows sorted by one of its columns
+pub struct SortableTable<'a, T> {
+    columns: Vec<Column<'a, T>>,
+    sort: SortState,
+    secondary: Option<usize>,
+    block: Option<Block<'a>>,
+    header_style: Style,
+    highlight_style: Style,
+}
+
+impl<'a, T> SortableTable<'a, T> {
+    /// A `sort` past the last column sorts by the first
+    pub fn new(columns: Vec<Column<'a, T>>, sort: SortState) -> Self {
+        let sort = if sort.column < columns.len() {
+            sort
+        } else {
+            SortState::new(0, columns.first().is_some_and(|c| c.descending))
+        };
+        Self {
+            columns,
+            sort,
+            secondary: None,
+            block: None,
+            header_style: Style::default().add_modifier(Modifier::BOLD),
+            highlight_style: Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Column breaking ties in the sorted one, in its own default direction
+    pub fn secondary(mut self, column: usize) -> Self {
+        self.secondary = (column < self.columns.len()).then_some(column);
+        self
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    pub fn sort_state(&self) -> SortState {
+        self.sort
+    }
+
+    /// The sort after `change`; a newly chosen column starts in its default direction
+    pub fn apply(&self, change: SortChange) -> SortState {
+        let count = self.columns.len();
+        let column = match change {
+            _ if count == 0 => return self.sort,
+            SortChange::Next => (self.sort.column + 1) % count,
+            SortChange::Previous => (self.sort.column + count - 1) % count,
+            SortChange::Column(column) if column >= count => return self.sort,
+            SortChange::Column(column) if column == self.sort.column => {
+                return SortState::new(column, !self.sort.descending);
+            }
+            SortChange::Column(column) => column,
+        };
+        SortState::new(column, self.columns[column].descending)
+    }
+
+    /// `rows` in display order
+    pub fn sorted<'r>(&self, rows: &'r [T]) -> Vec<&'r T> {
+        self.order(rows).into_iter().map(|i| &rows[i]).collect()
+    }
+
+    /// Indices into `rows` in display order; keys are read once per row
+    fn order(&self, rows: &[T]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        let Some(primary) = self.columns.get(self.sort.column) else {
+            return order;
+        };
+        let secondary = self
+            .secondary
+            .filter(|column| *column != self.sort.column)
+            .map(|column| &self.columns[column]);
+        let keys: Vec<(SortKey, Option<SortKey>)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    (primary.key)(row),
+                    secondary.map(|column| (column.key)(row)),
+                )
+            })
+            .collect();
+
+        let directed = |ordering: Ordering, descending: bool| {
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        order.sort_by(|&a, &b| {
+            directed(keys[a].0.compare(&keys[b].0), self.sort.descending).then_with(|| {
+                match (&keys[a].1, &keys[b].1, secondary) {
+                    (Some(x), Some(y), Some(column)) => directed(x.compare(y), column.descending),
+                    _ => Ordering::Equal,
+                }
+            })
+        });
+        order
+    }
+
+    /// Draw `rows` sorted, highlighting the `selected`th in display order
+    ///
+    /// Returns where each row on screen was drawn, by its position in
+    /// display order, for telling which one a click landed on.
+    pub fn render(
+        self,
+        f: &mut Frame,
+        area: Rect,
+        rows: &[T],
+        selected: Option<usize>,
+    ) -> Vec<(usize, Rect)> {
+        let header = Row::new(self.columns.iter().enumerate().map(|(i, column)| {
+            let title = if i == self.sort.column {
+                let arrow = if self.sort.descending { "▼" } else { "▲" };
+                format!("{} {}", column.title, arrow)
+            } else {
+                column.title.to_string()
+            };
+            Cell::from(Line::from(title).alignment(column.alignment))
+        }))
+        .style(self.header_style);
+
+        let body: Vec<Row> = self
+            .order(rows)
+            .into_iter()
+            .enumerate()
+            .map(|(position, index)| {
+                let row = Row::new(self.columns.iter().map(|column| {
+                    Cell::from((column.cell)(&rows[index]).alignment(column.alignment))
+                }));
+                if Some(position) == selected {
+                    row.style(self.highlight_style)
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let widths: Vec<Constraint> = self.columns.iter().map(|column| column.width).collect();
+        let mut table = Table::new(body, widths).header(header);
+        let inner = match &self.block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        if let Some(block) = self.block {
+            table = table.block(block);
+        }
+
+        let mut state = TableState::default();
+        state.select(selected);
+        f.render_stateful_widget(table, area, &mut state);
+
+        // One line per row under the one-line header, from the offset the
+        // table scrolled to to keep the selection in view
+        let body_rows = inner.height.saturating_sub(1) as usize;
+        (state.offset()..rows.len())
+            .take(body_rows)
+            .enumerate()
+            .map(|(line, position)| {
+                let y = inner.y + 1 + line as u16;
+                (position, Rect::new(inner.x, y, inner.width, 1))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    struct Job {
+        name: &'static str,
+        count: usize,
+        age: u64,
+    }
+
+    const JOBS: [Job; 4] = [
+        Job {
+            name: "beta",
+            count: 3,
+            age: 20,
+        },
+        Job {
+            name: "alpha",
+            count: 7,
+            age: 50,
+        },
+        Job {
+            name: "delta",
+            count: 3,
+            age: 5,
+        },
+        Job {
+            name: "Gamma",
+            count: 1,
+            age: 10,
+        },
+    ];
+
+    fn table<'a>(sort: SortState) -> SortableTable<'a, Job> {
+        SortableTable::new(
+            vec![
+                Column::new(
+                    "Name",
+                    Constraint::Length(8),
+                    |job: &Job| Line::from(job.name),
+                    |job| SortKey::Text(job.name.to_string()),
+                ),
+                Column::new(
+                    "Count",
+                    Constraint::Length(8),
+                    |job: &Job| Line::from(job.count.to_string()),
+                    |job| SortKey::Number(job.count as f64),
+                )
+                .alignment(Alignment::Right)
+                .descending(),
+                Column::new(
+                    "Age",
+                    Constraint::Length(6),
+                    |job: &Job| Line::from(job.age.to_string()),
+                    |job| SortKey::Number(job.age as f64),
+                ),
+            ],
+            sort,
+        )
+        .secondary(2)
+    }
+
+    fn draw(sort: SortState) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(24, 5)).unwrap();
+        terminal
+            .draw(|f| {
+                table(sort).render(f, f.area(), &JOBS, None);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn names(sort: SortState) -> Vec<&'static str> {
+        table(sort).sorted(&JOBS).iter().map(|j| j.name).collect()
+    }
+
+    #[test]
+    fn test_header_marks_the_sorted_column() {
+        let lines = draw(SortState::new(1, true));
+        assert_eq!(lines[0], "Name      Count ▼ Age");
+
+        let lines = draw(SortState::new(0, false));
+        assert_eq!(lines[0], "Name ▲      Count Age");
+    }
+
+    #[test]
+    fn test_rows_follow_the_sort() {
+        let lines = draw(SortState::new(1, true));
+        assert_eq!(lines[1], "alpha           7 50");
+        // Equal counts: the secondary column (age, ascending) decides
+        assert_eq!(lines[2], "delta           3 5");
+        assert_eq!(lines[3], "beta            3 20");
+        assert_eq!(lines[4], "Gamma           1 10");
+
+        assert_eq!(
+            names(SortState::new(0, false)),
+            vec!["alpha", "beta", "delta", "Gamma"]
+        );
+    }
+
+    #[test]
+    fn test_rows_on_screen_are_reported_where_they_were_drawn() {
+        let mut terminal = Terminal::new(TestBackend::new(24, 5)).unwrap();
+        let mut rows = Vec::new();
+        terminal
+            .draw(|f| rows = table(SortState::new(1, true)).render(f, f.area(), &JOBS, None))
+            .unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], (0, Rect::new(0, 1, 24, 1)));
+        assert_eq!(rows[3], (3, Rect::new(0, 4, 24, 1)));
+
+        // Two rows fit inside the border; selecting the last scrolls to it
+        let mut terminal = Terminal::new(TestBackend::new(24, 5)).unwrap();
+        terminal
+            .draw(|f| {
+                rows = table(SortState::new(1, true))
+                    .block(Block::bordered())
+                    .render(f, f.area(), &JOBS, Some(3))
+            })
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![(2, Rect::new(1, 2, 22, 1)), (3, Rect::new(1, 3, 22, 1))]
+        );
+    }
+
+    #[test]
+    fn test_changing_the_sort() {
+        let sort = SortState::new(1, true);
+        let current = table(sort);
+
+        let next = current.apply(SortChange::Next);
+        assert_eq!(next, SortState::new(2, false));
+        assert_eq!(names(next), vec!["delta", "Gamma", "beta", "alpha"]);
+
+        // Wraps round, each column starting in its own direction
+        assert_eq!(
+            current.apply(SortChange::Previous),
+            SortState::new(0, false)
+        );
+        assert_eq!(
+            table(next).apply(SortChange::Next),
+            SortState::new(0, false)
+        );
+
+        // The sorted column's number reverses it; another's picks it
+        let reversed = current.apply(SortChange::Column(1));
+        assert_eq!(reversed, SortState::new(1, false));
+        assert_eq!(names(reversed), vec!["Gamma", "delta", "beta", "alpha"]);
+        assert_eq!(
+            current.apply(SortChange::Column(0)),
+            SortState::new(0, false)
+        );
+        assert_eq!(current.apply(SortChange::Column(9)), sort);
+    }
+
+    #[test]
+    fn test_equal_keys_keep_their_order() {
+        let table = SortableTable::new(
+            vec![Column::new(
+                "Count",
+                Constraint::Length(8),
+                |job: &Job| Line::from(job.count.to_string()),
+                |job| SortKey::Number(job.count as f64),
+            )],
+            SortState::new(0, true),
+        );
+        let names: Vec<_> = table.sorted(&JOBS).iter().map(|j| j.name).collect();
+        assert_eq!(names, vec!["alpha", "beta", "delta", "Gamma"]);
+    }
+
+    #[test]
+    fn test_out_of_range_sort_falls_back_to_the_first_column() {
+        assert_eq!(
+            table(SortState::new(7, true)).sort_state(),
+            SortState::new(0, false)
+        );
+    }
+}
@@ -1,37 +1,138 @@
-/// Sparkline widget for displaying trend data
+//! Sparkline widget for displaying trend data
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+/// How values that fall into the same column are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Avg,
+    /// Keeps spikes visible however many values share a column
+    Max,
+}
 
 /// Sparkline widget - displays a mini chart using Unicode characters
+///
+/// Bars are scaled between the lowest and highest value, which the optional
+/// labels show either side of the chart.
 pub struct Sparkline<'a> {
     values: &'a [f64],
+    width: Option<usize>,
+    aggregation: Aggregation,
+    label_format: Option<fn(f64) -> String>,
+    style: Style,
+    highlight: Option<Color>,
 }
 
 impl<'a> Sparkline<'a> {
     const CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
+    /// Narrowest chart worth keeping labels for; below this they are dropped
+    const MIN_LABELLED_WIDTH: usize = 3;
+
     /// Create a new sparkline from values
     pub fn new(values: &'a [f64]) -> Self {
-        Self { values }
+        Self {
+            values,
+            width: None,
+            aggregation: Aggregation::default(),
+            label_format: None,
+            style: Style::default(),
+            highlight: None,
+        }
+    }
+
+    /// Fit into `width` columns, labels included
+    ///
+    /// Without a width there is one column per value.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// Show the lowest and highest value either side of the chart
+    pub fn labels(mut self, format: fn(f64) -> String) -> Self {
+        self.label_format = Some(format);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Draw the most recent column in `color`
+    pub fn highlight_last(mut self, color: Color) -> Self {
+        self.highlight = Some(color);
+        self
     }
 
-    /// Render the sparkline as a string
+    /// Render the bars as a string, without labels
     pub fn render(&self) -> String {
-        if self.values.is_empty() {
-            return String::new();
+        self.layout().1
+    }
+
+    /// Labels and bars, styled
+    pub fn line(&self) -> Line<'static> {
+        let (labels, bars) = self.layout();
+        let mut spans = Vec::new();
+
+        if let Some((min, _)) = &labels {
+            spans.push(Span::styled(format!("{} ", min), self.style));
+        }
+        match self.highlight {
+            Some(color) if !bars.is_empty() => {
+                let split = bars.char_indices().last().map_or(0, |(i, _)| i);
+                spans.push(Span::styled(bars[..split].to_string(), self.style));
+                spans.push(Span::styled(
+                    bars[split..].to_string(),
+                    self.style.fg(color),
+                ));
+            }
+            _ => spans.push(Span::styled(bars, self.style)),
+        }
+        if let Some((_, max)) = labels {
+            spans.push(Span::styled(format!(" {}", max), self.style));
         }
 
-        let max = self.values.iter().fold(0.0f64, |a, &b| a.max(b));
+        Line::from(spans)
+    }
 
-        if max == 0.0 {
-            return Self::CHARS[0].to_string().repeat(self.values.len());
+    /// Min/max labels (if shown and there's room) and the bars
+    fn layout(&self) -> (Option<(String, String)>, String) {
+        if self.values.is_empty() {
+            return (None, String::new());
         }
 
-        self.values
-            .iter()
-            .map(|&v| {
-                let index = ((v / max) * (Self::CHARS.len() - 1) as f64) as usize;
-                Self::CHARS[index.min(Self::CHARS.len() - 1)]
-            })
-            .collect()
+        let (min, max) = value_range(self.values);
+        let labels = self.label_format.map(|format| (format(min), format(max)));
+        let Some(width) = self.width else {
+            return (labels, bars(self.values, min, max));
+        };
+
+        let labelled_width = labels.as_ref().and_then(|(min, max)| {
+            width
+                .checked_sub(min.chars().count() + max.chars().count() + 2)
+                .filter(|&chart| chart >= Self::MIN_LABELLED_WIDTH)
+        });
+        match labelled_width {
+            Some(chart) => (
+                labels,
+                bars(&bucket(self.values, chart, self.aggregation), min, max),
+            ),
+            None => (
+                None,
+                bars(&bucket(self.values, width, self.aggregation), min, max),
+            ),
+        }
     }
 }
 
@@ -41,6 +142,49 @@ impl<'a> std::fmt::Display for Sparkline<'a> {
     }
 }
 
+/// Combine `values` into at most `width` evenly sized buckets, oldest first
+///
+/// Fewer values than columns are returned as they are rather than stretched.
+pub fn bucket(values: &[f64], width: usize, aggregation: Aggregation) -> Vec<f64> {
+    if values.len() <= width {
+        return values.to_vec();
+    }
+
+    (0..width)
+        .map(|i| {
+            let chunk = &values[i * values.len() / width..(i + 1) * values.len() / width];
+            match aggregation {
+                Aggregation::Avg => chunk.iter().sum::<f64>() / chunk.len() as f64,
+                Aggregation::Max => chunk.iter().copied().fold(f64::MIN, f64::max),
+            }
+        })
+        .collect()
+}
+
+fn value_range(values: &[f64]) -> (f64, f64) {
+    values.iter().fold((f64::MAX, f64::MIN), |(min, max), &v| {
+        (min.min(v), max.max(v))
+    })
+}
+
+/// One bar per value, scaled from `min` (lowest bar) to `max` (full bar)
+fn bars(values: &[f64], min: f64, max: f64) -> String {
+    let range = max - min;
+    let top = (Sparkline::CHARS.len() - 1) as f64;
+    values
+        .iter()
+        .map(|&v| {
+            // A flat series has no range to scale by; draw it along the bottom
+            let level = if range > 0.0 {
+                ((v - min) / range * top).round() as usize
+            } else {
+                0
+            };
+            Sparkline::CHARS[level.min(Sparkline::CHARS.len() - 1)]
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +193,13 @@ mod tests {
     fn test_sparkline_empty() {
         let sparkline = Sparkline::new(&[]);
         assert_eq!(sparkline.render(), "");
+        assert_eq!(
+            Sparkline::new(&[])
+                .width(10)
+                .labels(|v| v.to_string())
+                .render(),
+            ""
+        );
     }
 
     #[test]
@@ -57,12 +208,89 @@ mod tests {
         assert_eq!(sparkline.render(), "▁▁▁");
     }
 
+    #[test]
+    fn test_sparkline_constant_values() {
+        let sparkline = Sparkline::new(&[42.0; 50]).width(10);
+        assert_eq!(sparkline.render(), "▁".repeat(10));
+    }
+
     #[test]
     fn test_sparkline_values() {
         let sparkline = Sparkline::new(&[1.0, 2.0, 3.0, 2.0, 1.0]);
         let result = sparkline.render();
         assert_eq!(result.chars().count(), 5);
         // Middle value should be highest character
-        assert!(result.chars().nth(2).unwrap() > result.chars().nth(0).unwrap());
+        assert!(result.chars().nth(2).unwrap() > result.chars().next().unwrap());
+    }
+
+    #[test]
+    fn test_bucket_avg_and_max() {
+        let values: Vec<f64> = (0..10).map(f64::from).collect();
+        assert_eq!(
+            bucket(&values, 5, Aggregation::Avg),
+            [0.5, 2.5, 4.5, 6.5, 8.5]
+        );
+        assert_eq!(
+            bucket(&values, 5, Aggregation::Max),
+            [1.0, 3.0, 5.0, 7.0, 9.0]
+        );
+    }
+
+    #[test]
+    fn test_bucket_uneven_split_covers_every_value() {
+        let values = [1.0; 100];
+        let buckets = bucket(&values, 7, Aggregation::Avg);
+        assert_eq!(buckets.len(), 7);
+        assert!(buckets.iter().all(|&v| v == 1.0));
+
+        // Max keeps a lone spike whichever bucket it lands in
+        let mut spiky = vec![1.0; 100];
+        spiky[57] = 500.0;
+        let buckets = bucket(&spiky, 7, Aggregation::Max);
+        assert_eq!(buckets.iter().filter(|&&v| v == 500.0).count(), 1);
+    }
+
+    #[test]
+    fn test_bucket_short_input_is_not_stretched() {
+        assert_eq!(bucket(&[3.0, 4.0], 10, Aggregation::Avg), [3.0, 4.0]);
+        assert!(bucket(&[3.0, 4.0], 0, Aggregation::Avg).is_empty());
+    }
+
+    #[test]
+    fn test_sparkline_fills_width_with_labels() {
+        let values: Vec<f64> = (0..100).map(f64::from).collect();
+        let sparkline = Sparkline::new(&values)
+            .width(20)
+            .labels(|v| format!("{:.0}ms", v));
+
+        let text: String = sparkline
+            .line()
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(text.chars().count(), 20);
+        assert!(text.starts_with("0ms ▁"));
+        assert!(text.ends_with("█ 99ms"));
+        assert_eq!(sparkline.render().chars().count(), 20 - "0ms  99ms".len());
+    }
+
+    #[test]
+    fn test_labels_dropped_when_too_narrow() {
+        let values = [1.0, 1000.0, 5.0, 7.0, 9.0];
+        let sparkline = Sparkline::new(&values)
+            .width(12)
+            .labels(|v| format!("{:.0}ms", v));
+        assert_eq!(sparkline.render().chars().count(), 5);
+        assert_eq!(sparkline.line().spans.len(), 1);
+    }
+
+    #[test]
+    fn test_highlight_last_column() {
+        let sparkline = Sparkline::new(&[1.0, 2.0, 3.0]).highlight_last(Color::Red);
+        let line = sparkline.line();
+        assert_eq!(line.spans.len(), 2);
+        assert_eq!(line.spans[1].content, "█");
+        assert_eq!(line.spans[1].style.fg, Some(Color::Red));
     }
 }
@@ -1,4 +1,4 @@
-/// Visual bar widget for proportional display
+//! Visual bar widget for proportional display
 
 /// VisualBar widget - displays a proportional bar
 pub struct VisualBar {
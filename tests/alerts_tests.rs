@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use caboose::alerts::{
+    AlertEngine, AlertEventKind, AlertMetric, AlertRule, AlertSeverity, AlertSources, Comparator,
+};
+use caboose::context::RequestContextTracker;
+use caboose::database::DatabaseHealth;
+use caboose::exception::ExceptionTracker;
+use caboose::parser::{HttpRequest, LogEvent};
+use caboose::stats::StatsCollector;
+
+struct Trackers {
+    stats: StatsCollector,
+    requests: RequestContextTracker,
+    db_health: DatabaseHealth,
+    exceptions: ExceptionTracker,
+}
+
+impl Trackers {
+    fn new() -> Self {
+        Self {
+            stats: StatsCollector::new(),
+            requests: RequestContextTracker::new(),
+            db_health: DatabaseHealth::new(),
+            exceptions: ExceptionTracker::new(),
+        }
+    }
+
+    fn sources(&self) -> AlertSources<'_> {
+        AlertSources {
+            stats: &self.stats,
+            requests: &self.requests,
+            db_health: &self.db_health,
+            exceptions: &self.exceptions,
+        }
+    }
+
+    /// A started and a completed line for one request
+    fn complete_request(&self, controller: &str, duration: f64) {
+        for (path, status) in [("/", None), ("", Some(200))] {
+            self.requests
+                .process_log_event(&LogEvent::HttpRequest(HttpRequest {
+                    method: "GET".into(),
+                    path: path.into(),
+                    status,
+                    duration: status.map(|_| duration),
+                    controller: Some(controller.into()),
+                    action: Some("index".into()),
+                    extra: HashMap::new(),
+                }));
+        }
+    }
+}
+
+#[test]
+fn parses_rules_with_defaults_and_options() {
+    let rule = AlertRule::parse("error_rate > 5%").unwrap();
+    assert_eq!(rule.metric, AlertMetric::ErrorRate);
+    assert_eq!(rule.comparator, Comparator::Gt);
+    assert_eq!(rule.threshold, 5.0);
+    assert_eq!(rule.window, Duration::from_secs(60));
+    assert_eq!(rule.severity, AlertSeverity::Warning);
+
+    let rule = AlertRule::parse("p95_response_time >= 1.5s over 5m as critical").unwrap();
+    assert_eq!(rule.threshold, 1500.0);
+    assert_eq!(rule.window, Duration::from_secs(300));
+    assert_eq!(rule.severity, AlertSeverity::Critical);
+    assert_eq!(rule.to_string(), "p95_response_time >= 1500ms over 5m");
+
+    let rule = AlertRule::parse("health_score < 70").unwrap();
+    assert_eq!(rule.window, Duration::ZERO);
+    assert_eq!(rule.to_string(), "health_score < 70");
+}
+
+#[test]
+fn rejects_malformed_rules_with_reasons() {
+    let err = AlertRule::parse("latency > 5").unwrap_err();
+    assert!(err.contains("Unknown alert metric 'latency'"));
+    assert!(err.contains("error_rate"));
+
+    assert!(
+        AlertRule::parse("error_rate => 5")
+            .unwrap_err()
+            .contains("Unknown comparison")
+    );
+    assert!(
+        AlertRule::parse("error_rate > lots")
+            .unwrap_err()
+            .contains("Invalid threshold")
+    );
+    assert!(
+        AlertRule::parse("error_rate > 5 as loud")
+            .unwrap_err()
+            .contains("Unknown severity")
+    );
+    assert!(
+        AlertRule::parse("error_rate > 5 over 2h")
+            .unwrap_err()
+            .contains("longer than")
+    );
+    assert!(AlertRule::parse("error_rate > 5 over").is_err());
+    assert!(AlertRule::parse("error_rate >").is_err());
+}
+
+#[test]
+fn error_rate_rule_fires_and_resolves() {
+    let trackers = Trackers::new();
+    let mut engine = AlertEngine::new(vec![
+        AlertRule::parse("error_rate > 20% over 1m as critical").unwrap(),
+    ]);
+    let now = Instant::now();
+
+    trackers.stats.record_request(200, 10.0);
+    assert!(engine.evaluate(now, &trackers.sources()).is_empty());
+    assert!(engine.firing().is_empty());
+
+    trackers.stats.record_request(500, 10.0);
+    let events = engine.evaluate(now, &trackers.sources());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, AlertEventKind::Fired);
+    assert!(
+        events[0]
+            .message()
+            .starts_with("[alert] CRITICAL error_rate > 20%")
+    );
+    assert!(events[0].message().contains("now 50.0%"));
+    assert_eq!(engine.firing().len(), 1);
+
+    // Still breached: no repeat event
+    assert!(engine.evaluate(now, &trackers.sources()).is_empty());
+
+    // Once the requests age out of the window there's no data and it resolves
+    let later = now + Duration::from_secs(125);
+    let events = engine.evaluate(later, &trackers.sources());
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].kind,
+        AlertEventKind::Resolved {
+            after: Duration::from_secs(125)
+        }
+    );
+    assert!(events[0].message().contains("after 2m 5s"));
+    assert!(engine.firing().is_empty());
+}
+
+#[test]
+fn endpoint_rule_names_the_slow_endpoint() {
+    let trackers = Trackers::new();
+    trackers.complete_request("UsersController", 40.0);
+    trackers.complete_request("ReportsController", 2400.0);
+
+    let mut engine = AlertEngine::new(vec![AlertRule::parse("endpoint_p95 > 1s over 5m").unwrap()]);
+    let events = engine.evaluate(Instant::now(), &trackers.sources());
+
+    assert_eq!(events.len(), 1);
+    let reading = events[0].reading.as_ref().unwrap();
+    assert_eq!(reading.value, 2400.0);
+    assert_eq!(reading.subject.as_deref(), Some("ReportsController#index"));
+}
+
+#[test]
+fn health_score_must_stay_low_for_the_window() {
+    let trackers = Trackers::new();
+    for _ in 0..20 {
+        trackers
+            .db_health
+            .analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 250.0);
+    }
+    assert!(trackers.db_health.calculate_health_score() < 99);
+
+    let mut engine = AlertEngine::new(vec![
+        AlertRule::parse("health_score < 99 over 30s").unwrap(),
+    ]);
+    let now = Instant::now();
+
+    assert!(engine.evaluate(now, &trackers.sources()).is_empty());
+    assert!(
+        engine
+            .evaluate(now + Duration::from_secs(10), &trackers.sources())
+            .is_empty()
+    );
+    let events = engine.evaluate(now + Duration::from_secs(30), &trackers.sources());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, AlertEventKind::Fired);
+}
+
+#[test]
+fn metrics_without_data_do_not_fire() {
+    let trackers = Trackers::new();
+    let mut engine = AlertEngine::new(vec![
+        AlertRule::parse("avg_response_time < 100ms").unwrap(),
+        AlertRule::parse("health_score < 90").unwrap(),
+    ]);
+
+    assert!(
+        engine
+            .evaluate(Instant::now(), &trackers.sources())
+            .is_empty()
+    );
+    assert!(engine.statuses().all(|(_, state)| state.reading.is_none()));
+}
@@ -0,0 +1,171 @@
+use caboose::process::{
+    AnsiColor, AnsiStyle, Hyperlink, LineTruncator, LogLine, LogMarkup, StyledRun, parse_ansi,
+};
+use caboose::ui::command::commands::recent_link;
+
+fn styles(raw: &str) -> (String, Vec<StyledRun>) {
+    let (text, markup) = parse_ansi(raw);
+    (text, markup.map(|m| m.styles).unwrap_or_default())
+}
+
+fn fg(color: AnsiColor) -> AnsiStyle {
+    AnsiStyle {
+        fg: Some(color),
+        ..AnsiStyle::default()
+    }
+}
+
+#[test]
+fn plain_lines_have_no_markup() {
+    assert_eq!(
+        parse_ansi("Started GET \"/\"\tok"),
+        ("Started GET \"/\"\tok".to_string(), None)
+    );
+}
+
+#[test]
+fn sgr_colors_become_runs_over_the_clean_text() {
+    let (text, runs) = styles("\u{1b}[32m✓\u{1b}[39m ready in \u{1b}[1;33m312\u{1b}[0m ms");
+    assert_eq!(text, "✓ ready in 312 ms");
+    assert_eq!(
+        runs,
+        vec![
+            StyledRun {
+                start: 0,
+                end: 3,
+                style: fg(AnsiColor::Indexed(2)),
+            },
+            StyledRun {
+                start: 13,
+                end: 16,
+                style: AnsiStyle {
+                    bold: true,
+                    ..fg(AnsiColor::Indexed(3))
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn reads_bright_256_and_truecolor_forms() {
+    for (raw, color) in [
+        ("\u{1b}[91mx", AnsiColor::Indexed(9)),
+        ("\u{1b}[38;5;208mx", AnsiColor::Indexed(208)),
+        ("\u{1b}[38:5:208mx", AnsiColor::Indexed(208)),
+        ("\u{1b}[38;2;255;128;0mx", AnsiColor::Rgb(255, 128, 0)),
+        ("\u{1b}[38:2::255:128:0mx", AnsiColor::Rgb(255, 128, 0)),
+        // Black is a colour too, not a colour-space id
+        ("\u{1b}[38;2;0;5;6;1mx", AnsiColor::Rgb(0, 5, 6)),
+    ] {
+        let (text, runs) = styles(raw);
+        assert_eq!(text, "x", "{:?}", raw);
+        assert_eq!(runs[0].style.fg, Some(color), "{:?}", raw);
+    }
+}
+
+#[test]
+fn osc8_links_are_extracted_with_either_terminator() {
+    let raw = "at \u{1b}]8;;file:///app/src/App.vue\u{7}src/App.vue:12\u{1b}]8;;\u{7} and \
+               \u{1b}]8;id=2;https://nextjs.org/docs\u{1b}\\docs\u{1b}]8;;\u{1b}\\";
+    let (text, markup) = parse_ansi(raw);
+    assert_eq!(text, "at src/App.vue:12 and docs");
+    assert_eq!(
+        markup.unwrap().links,
+        vec![
+            Hyperlink {
+                start: 3,
+                end: 17,
+                url: "file:///app/src/App.vue".to_string(),
+            },
+            Hyperlink {
+                start: 22,
+                end: 26,
+                url: "https://nextjs.org/docs".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn cursor_moves_keep_alignment_and_clears_are_dropped() {
+    // Forward moves and absolute columns become spaces
+    assert_eq!(parse_ansi("name\u{1b}[4Csize").0, "name    size");
+    assert_eq!(parse_ansi("name\u{1b}[11Gsize").0, "name      size");
+    // Clears, cursor up, titles, charset switches and \r are dropped
+    assert_eq!(
+        parse_ansi("\u{1b}[2K\u{1b}[1A\r\u{1b}]0;vite\u{7}\u{1b}(Bdone\u{1b}[?25h").0,
+        "done"
+    );
+    // A huge move can't blow up the line
+    assert!(parse_ansi("a\u{1b}[99999999Cb").0.len() < 300);
+}
+
+#[test]
+fn malformed_escapes_never_panic() {
+    let raw = "\u{1b}[38;2;300;-1;x;m\u{1b}]8;;http://x\u{7}é\u{1b}[38;5m\u{1b}[;;;m\u{1b}[9999999999999999999mz\u{1b}";
+    for end in (0..=raw.len()).filter(|i| raw.is_char_boundary(*i)) {
+        let (text, markup) = parse_ansi(&raw[..end]);
+        if let Some(markup) = markup {
+            for run in &markup.styles {
+                assert!(run.end <= text.len() && text.is_char_boundary(run.start));
+            }
+        }
+    }
+    // Unterminated sequences swallow the rest of the line rather than leak into it
+    assert_eq!(parse_ansi("ok\u{1b}]8;;http://x").0, "ok");
+    assert_eq!(parse_ansi("ok\u{1b}[12").0, "ok");
+}
+
+fn log(raw: &str) -> LogLine {
+    let (content, markup) = parse_ansi(raw);
+    LogLine {
+        markup: markup.map(Box::new),
+        ..LogLine::new("vite", content)
+    }
+}
+
+#[test]
+fn cutting_a_line_clips_its_markup() {
+    let mut line = log(&format!(
+        "\u{1b}[31m{}\u{1b}[0m\u{1b}]8;;http://x\u{7}{}\u{1b}]8;;\u{7}",
+        "a".repeat(8),
+        "b".repeat(8)
+    ));
+    LineTruncator::new(10).apply(&mut line);
+
+    let markup = line.markup.as_deref().unwrap();
+    assert_eq!(markup.styles[0].end, 8);
+    assert_eq!((markup.links[0].start, markup.links[0].end), (8, 10));
+
+    let mut line = log("\u{1b}[31maaaaaaaaaaaaaaa\u{1b}]8;;http://x\u{7}bb\u{1b}]8;;\u{7}");
+    LineTruncator::new(10).apply(&mut line);
+    assert_eq!(
+        line.markup.as_deref(),
+        Some(&LogMarkup {
+            styles: vec![StyledRun {
+                start: 0,
+                end: 10,
+                style: fg(AnsiColor::Indexed(1)),
+            }],
+            links: Vec::new(),
+        })
+    );
+}
+
+#[test]
+fn recent_links_count_back_from_the_newest() {
+    let logs = vec![
+        log("\u{1b}]8;;http://one\u{7}one\u{1b}]8;;\u{7}"),
+        log("plain"),
+        log(
+            "\u{1b}]8;;http://two\u{7}two\u{1b}]8;;\u{7} \u{1b}]8;;http://three\u{7}3\u{1b}]8;;\u{7}",
+        ),
+    ];
+
+    assert_eq!(recent_link(&logs, 1), Some("http://three"));
+    assert_eq!(recent_link(&logs, 2), Some("http://two"));
+    assert_eq!(recent_link(&logs, 3), Some("http://one"));
+    assert_eq!(recent_link(&logs, 4), None);
+    assert_eq!(recent_link(&logs, 0), None);
+}
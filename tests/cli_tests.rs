@@ -14,10 +14,94 @@ fn parses_dev_with_process() {
 fn parses_logs_and_stop() {
     let cli = Cli::parse_from(["caboose", "logs", "worker"]);
     match cli.command {
-        Some(Commands::Logs { process }) => assert_eq!(process, "worker"),
+        Some(Commands::Logs {
+            process,
+            follow,
+            lines,
+        }) => assert_eq!((process.as_str(), follow, lines), ("worker", false, 100)),
         _ => panic!("Expected logs command"),
     }
 
+    let cli = Cli::parse_from(["caboose", "logs", "web", "-f", "-n", "20"]);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Logs {
+            follow: true,
+            lines: 20,
+            ..
+        })
+    ));
+
+    let cli = Cli::parse_from(["caboose", "stop"]);
+    assert!(matches!(cli.command, Some(Commands::Stop { .. })));
+}
+
+#[test]
+fn parses_global_project_flag_and_recent() {
+    let cli = Cli::parse_from(["caboose", "dev", "--project", "/srv/app"]);
+    assert_eq!(cli.project, Some("/srv/app".into()));
+
+    let cli = Cli::parse_from(["caboose", "--project", "../shop", "recent", "2"]);
+    assert_eq!(cli.project, Some("../shop".into()));
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Recent { number: Some(2) })
+    ));
+}
+
+#[test]
+fn parses_write_procfile_flags() {
+    let cli = Cli::parse_from(["caboose", "--write-procfile", "--force"]);
+    assert!(cli.write_procfile);
+    assert!(cli.force);
+
+    // --force only makes sense together with --write-procfile
+    assert!(Cli::try_parse_from(["caboose", "dev", "--force"]).is_err());
+}
+
+#[test]
+fn parses_open_with_a_session_file() {
+    let cli = Cli::parse_from(["caboose", "open", "handoff.json.gz"]);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Open { file }) if file == std::path::Path::new("handoff.json.gz")
+    ));
+
+    assert!(Cli::try_parse_from(["caboose", "open"]).is_err());
+}
+
+#[test]
+fn parses_headless_flags() {
+    let cli = Cli::parse_from(["caboose", "--no-tui", "--startup-json", "--json-logs"]);
+    assert!(cli.no_tui && cli.startup_json && cli.json_logs);
+
+    // JSON output only exists without the TUI
+    assert!(Cli::try_parse_from(["caboose", "--startup-json"]).is_err());
+    assert!(Cli::try_parse_from(["caboose", "dev", "--json-logs"]).is_err());
+}
+
+#[test]
+fn parses_stop_with_grace_and_force() {
     let cli = Cli::parse_from(["caboose", "stop"]);
-    assert!(matches!(cli.command, Some(Commands::Stop)));
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Stop {
+            grace: 5,
+            force: false
+        })
+    ));
+    let cli = Cli::parse_from(["caboose", "stop", "--grace", "10"]);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Stop {
+            grace: 10,
+            force: false
+        })
+    ));
+    let cli = Cli::parse_from(["caboose", "stop", "--force"]);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Stop { force: true, .. })
+    ));
+    assert!(!cli.force);
 }
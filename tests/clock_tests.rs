@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use caboose::clock::{Clock, MockClock, SUSPEND_THRESHOLD, Suspensions, format_approx};
+use chrono::TimeDelta;
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Run the loop for `secs`, ticking every second
+fn stay_awake(clock: &MockClock, suspensions: &mut Suspensions, secs: u64) {
+    for _ in 0..secs {
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(suspensions.tick(clock.now(), clock.wall()), None);
+    }
+}
+
+#[test]
+fn ticks_close_together_are_not_a_suspension() {
+    let clock = MockClock::new();
+    let mut suspensions = Suspensions::default();
+    assert_eq!(suspensions.tick(clock.now(), clock.wall()), None);
+    for _ in 0..10 {
+        clock.advance(TICK);
+        assert_eq!(suspensions.tick(clock.now(), clock.wall()), None);
+    }
+    // A slow tick (a long draw, a blocking call) isn't one either
+    clock.advance(SUSPEND_THRESHOLD - TICK);
+    assert_eq!(suspensions.tick(clock.now(), clock.wall()), None);
+    assert!(suspensions.all().is_empty());
+}
+
+#[test]
+fn a_sleep_the_monotonic_clock_missed_is_found_by_the_wall_clock() {
+    let clock = MockClock::new();
+    let mut suspensions = Suspensions::default();
+    let started = clock.now();
+    suspensions.tick(clock.now(), clock.wall());
+    stay_awake(&clock, &mut suspensions, 600);
+
+    let before = clock.wall();
+    clock.sleep(Duration::from_secs(7 * 3600 + 10 * 60));
+    clock.advance(TICK);
+    let suspension = suspensions.tick(clock.now(), clock.wall()).unwrap();
+
+    assert_eq!(suspension.from, before);
+    assert_eq!(suspension.to, clock.wall());
+    assert_eq!(suspension.monotonic, TICK);
+    assert!(
+        suspension.message().starts_with("System suspended ~7h ("),
+        "{}",
+        suspension.message()
+    );
+    assert_eq!(suspensions.latest(), Some(&suspension));
+
+    // The monotonic clock already left the sleep out; the uptime is only marked
+    let uptime = suspensions.uptime(started, clock.now());
+    assert!(uptime.spans_suspension);
+    assert_eq!(uptime.awake, Duration::from_secs(600));
+    assert_eq!(uptime.label(), "~10m 0s");
+
+    // Started after waking up, nothing to mark
+    let since_resume = clock.now();
+    stay_awake(&clock, &mut suspensions, 5);
+    let uptime = suspensions.uptime(since_resume, clock.now());
+    assert!(!uptime.spans_suspension);
+    assert_eq!(uptime.label(), "5s");
+}
+
+#[test]
+fn a_sleep_the_monotonic_clock_counted_is_left_out_of_uptimes() {
+    let clock = MockClock::new();
+    let mut suspensions = Suspensions::default();
+    let started = clock.now();
+    suspensions.tick(clock.now(), clock.wall());
+    stay_awake(&clock, &mut suspensions, 120);
+
+    // Where the monotonic clock keeps counting through a sleep
+    clock.advance(Duration::from_secs(2 * 3600));
+    let suspension = suspensions.tick(clock.now(), clock.wall()).unwrap();
+    assert_eq!(suspension.duration, Duration::from_secs(2 * 3600));
+    assert_eq!(suspension.monotonic, Duration::from_secs(2 * 3600));
+
+    let uptime = suspensions.uptime(started, clock.now());
+    assert_eq!(uptime.awake, Duration::from_secs(120));
+    assert_eq!(uptime.label(), "~2m 0s");
+}
+
+#[test]
+fn approximate_durations_round_to_their_largest_unit() {
+    assert_eq!(format_approx(Duration::from_secs(40)), "~40s");
+    assert_eq!(format_approx(Duration::from_secs(45 * 60 + 20)), "~45m");
+    assert_eq!(
+        format_approx(Duration::from_secs(7 * 3600 + 40 * 60)),
+        "~8h"
+    );
+    assert_eq!(
+        format_approx(
+            (TimeDelta::hours(6) + TimeDelta::minutes(59))
+                .to_std()
+                .unwrap()
+        ),
+        "~7h"
+    );
+}
@@ -1,7 +1,20 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use caboose::config::{CabooseConfig, Procfile, load_env};
+use caboose::config::{
+    CabooseConfig, ConfigWatcher, Procfile, ProcfileEntry, ProcfileFinding, TemplateValues,
+    WorkerMode, check_procfile, generate_procfile_entries, load_env, render_procfile,
+    write_procfile,
+};
+use caboose::diagnostics::Limits;
+use caboose::frontend::{FrontendApp, FrontendFramework, PackageManager};
+use caboose::parser::InstrumentationGems;
+use caboose::process::{RestartRule, SetupStep};
+use caboose::rails::{JobConfig, RailsApp};
+use caboose::stats::DbShareThresholds;
+use caboose::ui::layout::CompactThresholds;
 
 fn temp_path(name: &str) -> PathBuf {
     let mut dir = std::env::temp_dir();
@@ -33,7 +46,7 @@ fn load_env_parses_values() {
     let env = load_env(&path).unwrap();
     assert_eq!(env.get("FOO"), Some(&"bar".to_string()));
     assert_eq!(env.get("NUMBER"), Some(&"123".to_string()));
-    assert!(env.get("INVALID").is_none());
+    assert!(!env.contains_key("INVALID"));
 
     let _ = fs::remove_file(path);
 }
@@ -54,3 +67,674 @@ fn caboose_config_create_example_has_sections() {
     assert!(example.contains("[rails]"));
     assert!(example.contains("process_name"));
 }
+
+fn rails_app(background_job: Option<&str>) -> RailsApp {
+    RailsApp {
+        detected: true,
+        database: Some("postgresql".into()),
+        background_job: background_job.map(String::from),
+        asset_pipeline: None,
+        jobs: JobConfig::default(),
+        instrumentation: InstrumentationGems::default(),
+    }
+}
+
+fn no_rails() -> RailsApp {
+    RailsApp {
+        detected: false,
+        database: None,
+        background_job: None,
+        asset_pipeline: None,
+        jobs: JobConfig::default(),
+        instrumentation: InstrumentationGems::default(),
+    }
+}
+
+fn vite_frontend() -> FrontendApp {
+    FrontendApp {
+        detected: true,
+        framework: Some(FrontendFramework::Vite),
+        path: "client".into(),
+        package_manager: PackageManager::Pnpm,
+    }
+}
+
+fn no_frontend() -> FrontendApp {
+    FrontendApp {
+        detected: false,
+        framework: None,
+        path: String::new(),
+        package_manager: PackageManager::Npm,
+    }
+}
+
+fn names(entries: &[ProcfileEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.name.as_str()).collect()
+}
+
+#[test]
+fn generates_rails_only_entries_with_reasons() {
+    let entries = generate_procfile_entries(
+        &rails_app(Some("sidekiq")),
+        &no_frontend(),
+        &CabooseConfig::default(),
+    );
+
+    assert_eq!(names(&entries), ["web", "worker"]);
+    assert_eq!(entries[0].command, "bundle exec rails server -p 3000");
+    assert_eq!(entries[1].command, "bundle exec sidekiq");
+    assert_eq!(entries[1].reason, "detected sidekiq in Gemfile");
+}
+
+#[test]
+fn generates_frontend_only_entry() {
+    let entries =
+        generate_procfile_entries(&no_rails(), &vite_frontend(), &CabooseConfig::default());
+
+    assert_eq!(names(&entries), ["frontend"]);
+    assert_eq!(entries[0].command, "cd client && pnpm run dev");
+    assert_eq!(entries[0].reason, "detected Vite frontend in client");
+}
+
+#[test]
+fn generates_combined_entries_honoring_config() {
+    let mut config = CabooseConfig::default();
+    config.rails.port = Some(4000);
+    config.frontend.process_name = Some("ui".into());
+
+    let entries = generate_procfile_entries(&rails_app(None), &vite_frontend(), &config);
+    assert_eq!(names(&entries), ["web", "ui"]);
+    assert_eq!(entries[0].command, "bundle exec rails server -p 4000");
+    assert!(entries[0].reason.contains("4000"));
+
+    // The annotated file and the in-memory Procfile describe the same processes
+    let annotated = render_procfile(&entries, true);
+    assert!(annotated.contains("# detected Vite frontend in client\nui: cd client"));
+    let from_file = Procfile::parse_content(&annotated).unwrap();
+    let in_memory = Procfile::from_entries(&entries).unwrap();
+    let commands = |p: &Procfile| {
+        p.processes
+            .iter()
+            .map(|c| (c.name.clone(), c.command.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(commands(&from_file), commands(&in_memory));
+}
+
+#[test]
+fn procfile_check_reports_entries_only_detection_has() {
+    let generated = generate_procfile_entries(
+        &rails_app(Some("sidekiq")),
+        &vite_frontend(),
+        &CabooseConfig::default(),
+    );
+    let procfile =
+        Procfile::parse_content("web: bin/rails s\njs: cd client && npm run dev -- --host\n")
+            .unwrap();
+
+    let findings = check_procfile(
+        &procfile.processes,
+        &generated,
+        &CabooseConfig::default(),
+        &temp_path("procfile_missing"),
+    );
+    assert_eq!(findings.len(), 2, "{:?}", findings);
+    assert!(matches!(&findings[0], ProcfileFinding::Missing(entry) if entry.name == "worker"));
+    assert_eq!(
+        findings[0].to_string(),
+        "detected sidekiq in Gemfile, but the Procfile has no worker entry (`worker: bundle exec sidekiq`)"
+    );
+    // bin/rails isn't in the (empty) project directory
+    assert!(matches!(&findings[1], ProcfileFinding::Stale { name, .. } if name == "web"));
+
+    // Under another name, with extra flags, sidekiq still counts as the worker
+    let procfile = Procfile::parse_content(
+        "web: bundle exec rails server\njobs: bundle exec sidekiq -C config/sidekiq.yml\nfrontend: cd client && yarn dev\n",
+    )
+    .unwrap();
+    let findings = check_procfile(
+        &procfile.processes,
+        &generated,
+        &CabooseConfig::default(),
+        &temp_path("procfile_covered"),
+    );
+    assert_eq!(findings, []);
+}
+
+#[test]
+fn procfile_check_reports_port_conflicts_and_stale_tools() {
+    let dir = temp_path("procfile_stale");
+    fs::create_dir_all(dir.join("bin")).unwrap();
+    fs::write(dir.join("bin/vite"), "").unwrap();
+
+    let mut config = CabooseConfig::default();
+    config.rails.port = Some(4000);
+    // No vite_rails in the Gemfile any more, so nothing generates vite
+    let generated = generate_procfile_entries(&rails_app(None), &no_frontend(), &config);
+    let procfile = Procfile::parse_content(
+        "web: bundle exec rails server -p 3000\nvite: bin/vite dev\nworker: bundle exec sidekiq\n",
+    )
+    .unwrap();
+
+    let findings = check_procfile(&procfile.processes, &generated, &config, &dir);
+    assert_eq!(
+        findings,
+        [
+            ProcfileFinding::PortConflict {
+                name: "web".into(),
+                port: 3000,
+                configured: 4000,
+                setting: "[rails] port",
+            },
+            ProcfileFinding::Stale {
+                name: "vite".into(),
+                command: "bin/vite dev".into(),
+                problem: "the Gemfile has no vite_rails".into(),
+            },
+            ProcfileFinding::Stale {
+                name: "worker".into(),
+                command: "bundle exec sidekiq".into(),
+                problem: "the Gemfile has no sidekiq".into(),
+            },
+        ]
+    );
+    assert_eq!(
+        findings[0].to_string(),
+        "'web' listens on 3000, but [rails] port is 4000"
+    );
+
+    // Without a detected Rails app there's nothing to call stale
+    let findings = check_procfile(&procfile.processes, &[], &CabooseConfig::default(), &dir);
+    assert_eq!(findings, []);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn write_procfile_refuses_to_overwrite_without_force() {
+    let dir = temp_path("write_procfile");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Procfile.dev");
+    let entries = vec![ProcfileEntry::new(
+        "web",
+        "bundle exec rails server -p 3000",
+        "Rails app detected",
+    )];
+
+    write_procfile(&path, &entries, false).unwrap();
+    assert!(
+        fs::read_to_string(&path)
+            .unwrap()
+            .contains("# Rails app detected\nweb: bundle exec rails server -p 3000")
+    );
+
+    let err = write_procfile(&path, &entries, false).unwrap_err();
+    assert!(err.contains("--force"));
+    write_procfile(&path, &entries, true).unwrap();
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn rails_workers_mode_reads_from_toml() {
+    let config: CabooseConfig = toml::from_str("[rails]\nworkers = \"single\"\n").unwrap();
+    assert_eq!(config.rails.workers, WorkerMode::Single);
+
+    let config: CabooseConfig = toml::from_str("[rails]\nport = 3000\n").unwrap();
+    assert_eq!(config.rails.workers, WorkerMode::PerGroup);
+}
+
+#[test]
+fn session_persist_is_off_unless_asked_for() {
+    let config: CabooseConfig = toml::from_str("").unwrap();
+    assert!(!config.session.persist);
+
+    let config: CabooseConfig = toml::from_str("[session]\npersist = true\n").unwrap();
+    assert!(config.session.persist);
+}
+
+#[test]
+fn stats_thresholds_default_and_override() {
+    let config = CabooseConfig::default();
+    assert_eq!(
+        config.stats.db_share_thresholds(),
+        DbShareThresholds::default()
+    );
+
+    let config: CabooseConfig = toml::from_str("[stats]\ndb_share_danger = 90\n").unwrap();
+    let thresholds = config.stats.db_share_thresholds();
+    assert_eq!(thresholds.warning, 50.0);
+    assert_eq!(thresholds.danger, 90.0);
+    assert_eq!(config.stats.cache_hit_rate_warning(), 80.0);
+
+    let config: CabooseConfig = toml::from_str("[stats]\ncache_hit_rate_warning = 60\n").unwrap();
+    assert_eq!(config.stats.cache_hit_rate_warning(), 60.0);
+}
+
+#[test]
+fn ui_compact_thresholds_default_and_override() {
+    let config = CabooseConfig::default();
+    assert_eq!(config.ui.compact_thresholds(), CompactThresholds::default());
+
+    let config: CabooseConfig = toml::from_str("[ui]\ncompact_height = 30\n").unwrap();
+    let thresholds = config.ui.compact_thresholds();
+    assert_eq!(thresholds.height, 30);
+    assert_eq!(thresholds.width, 80);
+}
+
+#[test]
+fn process_override_allow_daemonize_defaults_off() {
+    let config: CabooseConfig = toml::from_str(
+        "[processes.web]\nallow_daemonize = true\n\n[processes.worker]\ncommand = \"bin/jobs\"\n",
+    )
+    .unwrap();
+    assert!(config.processes["web"].allow_daemonize);
+    assert!(!config.processes["worker"].allow_daemonize);
+}
+
+#[test]
+fn process_override_can_adopt_an_external_process() {
+    let config: CabooseConfig = toml::from_str(
+        "[processes.web]\nmanaged = false\nport = 3001\nlog_file = \"log/development.log\"\n\n[processes.worker]\ncommand = \"bin/jobs\"\n",
+    )
+    .unwrap();
+    let web = &config.processes["web"];
+    assert!(!web.managed());
+    assert_eq!(web.port, Some(3001));
+    assert_eq!(
+        web.log_file.as_deref(),
+        Some(std::path::Path::new("log/development.log"))
+    );
+    assert!(config.processes["worker"].managed());
+    assert_eq!(config.processes["worker"].port, None);
+}
+
+#[test]
+fn process_override_restart_policy_fills_in_defaults() {
+    let config: CabooseConfig = toml::from_str(
+        "[processes.web]\nrestart = \"on-failure\"\n\n[processes.worker]\nrestart = \"on-failure\"\nmax_restarts = 2\nbackoff_ms = 250\n\n[processes.css]\ncommand = \"bin/css\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.processes["web"].restart_rule(),
+        Some(RestartRule::default())
+    );
+    assert_eq!(
+        config.processes["worker"].restart_rule(),
+        Some(RestartRule {
+            max_restarts: 2,
+            backoff: Duration::from_millis(250),
+        })
+    );
+    assert_eq!(config.processes["css"].restart_rule(), None);
+    assert!(toml::from_str::<CabooseConfig>("[processes.web]\nrestart = \"sometimes\"\n").is_err());
+}
+
+#[test]
+fn logs_dedupe_streams_defaults_off() {
+    assert!(!CabooseConfig::default().logs.dedupe_streams);
+
+    let config: CabooseConfig = toml::from_str("[logs]\ndedupe_streams = true\n").unwrap();
+    assert!(config.logs.dedupe_streams);
+}
+
+#[test]
+fn queries_ignore_limit_offset_maps_to_fingerprint_options() {
+    let defaults = CabooseConfig::default().queries.fingerprint_options();
+    assert!(!defaults.ignore_limit_offset);
+
+    let config: CabooseConfig = toml::from_str("[queries]\nignore_limit_offset = true\n").unwrap();
+    assert!(config.queries.fingerprint_options().ignore_limit_offset);
+}
+
+#[test]
+fn limits_default_override_and_fall_back_when_out_of_range() {
+    let (limits, warnings) = CabooseConfig::default().limits.limits();
+    assert_eq!(limits, Limits::default());
+    assert!(warnings.is_empty());
+
+    let config: CabooseConfig = toml::from_str(
+        "[limits]\nmax_logs = 5000\nmax_slow_queries = 0\nmax_exceptions = 99999999\n",
+    )
+    .unwrap();
+    let (limits, warnings) = config.limits.limits();
+    assert_eq!(limits.max_logs, 5000);
+    assert_eq!(limits.max_slow_queries, Limits::default().max_slow_queries);
+    assert_eq!(limits.max_exceptions, Limits::default().max_exceptions);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("max_slow_queries"));
+}
+
+#[test]
+fn config_watcher_reports_edits() {
+    let dir = temp_path("watcher");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(".caboose.toml");
+    fs::write(&path, "[limits]\nmax_logs = 2000\n").unwrap();
+
+    let mut watcher = ConfigWatcher::new(&dir);
+    assert!(watcher.check().is_none());
+
+    fs::write(&path, "[limits]\nmax_logs = 3000\n").unwrap();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    let reloaded = watcher.check().unwrap().unwrap();
+    assert_eq!(reloaded.limits.max_logs, Some(3000));
+    assert!(watcher.check().is_none());
+
+    fs::write(&path, "[limits\n").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later + std::time::Duration::from_secs(5))
+        .unwrap();
+    assert!(watcher.check().unwrap().is_err());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn alerts_rules_parse_and_report_every_bad_rule() {
+    assert!(CabooseConfig::default().alerts.rules().unwrap().is_empty());
+
+    let config: CabooseConfig = toml::from_str(
+        "[alerts]\nrules = [\"error_rate > 5% over 2m as critical\", \"health_score < 70\"]\n",
+    )
+    .unwrap();
+    let rules = config.alerts.rules().unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].to_string(), "error_rate > 5% over 2m");
+
+    let config: CabooseConfig = toml::from_str(
+        "[alerts]\nrules = [\"latency > 5\", \"error_rate > 5%\", \"exceptions >= many\"]\n",
+    )
+    .unwrap();
+    let errors = config.alerts.rules().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].contains("latency"));
+    assert!(errors[1].contains("many"));
+}
+
+#[test]
+fn logs_max_line_length_defaults_and_falls_back() {
+    let (truncator, warnings) = CabooseConfig::default().logs.line_truncator();
+    assert_eq!(truncator.max_len(), 16 * 1024);
+    assert!(warnings.is_empty());
+
+    let config: CabooseConfig = toml::from_str("[logs]\nmax_line_length = 4096\n").unwrap();
+    assert_eq!(config.logs.line_truncator().0.max_len(), 4096);
+
+    let config: CabooseConfig = toml::from_str("[logs]\nmax_line_length = 0\n").unwrap();
+    let (truncator, warnings) = config.logs.line_truncator();
+    assert_eq!(truncator.max_len(), 16 * 1024);
+    assert!(warnings[0].contains("max_line_length"));
+}
+
+#[test]
+fn logs_order_reads_newest_or_oldest() {
+    use caboose::ui::components::LogOrder;
+
+    assert_eq!(CabooseConfig::default().logs.order, LogOrder::OldestFirst);
+    let config: CabooseConfig = toml::from_str("[logs]\norder = \"newest\"\n").unwrap();
+    assert_eq!(config.logs.order, LogOrder::NewestFirst);
+    assert!(toml::from_str::<CabooseConfig>("[logs]\norder = \"sideways\"\n").is_err());
+}
+
+#[test]
+fn logs_colors_read_theme_or_tool() {
+    use caboose::ui::components::LogColors;
+
+    assert_eq!(CabooseConfig::default().logs.colors, LogColors::Themed);
+    let config: CabooseConfig = toml::from_str("[logs]\ncolors = \"tool\"\n").unwrap();
+    assert_eq!(config.logs.colors, LogColors::Tool);
+    assert!(toml::from_str::<CabooseConfig>("[logs]\ncolors = \"rainbow\"\n").is_err());
+}
+
+#[test]
+fn queueing_settings_default_and_override() {
+    let config = CabooseConfig::default();
+    assert_eq!(config.stats.queue_time_warning(), 100.0);
+    assert!(config.rails.request_capacity() > 0);
+
+    let config: CabooseConfig =
+        toml::from_str("[rails]\nmax_threads = 3\n\n[stats]\nqueue_time_warning = 250\n").unwrap();
+    assert_eq!(config.rails.request_capacity(), 3);
+    assert_eq!(config.stats.queue_time_warning(), 250.0);
+}
+
+#[test]
+fn ui_timezone_reads_zone_names_and_warns_on_unknown_ones() {
+    use caboose::time_format::DisplayZone;
+
+    assert_eq!(
+        CabooseConfig::default().ui.display_zone(),
+        (DisplayZone::Local, None)
+    );
+    let config: CabooseConfig = toml::from_str("[ui]\ntimezone = \"utc\"\n").unwrap();
+    assert_eq!(config.ui.display_zone(), (DisplayZone::Utc, None));
+
+    let config: CabooseConfig = toml::from_str("[ui]\ntimezone = \"Moon/Base\"\n").unwrap();
+    let (zone, warning) = config.ui.display_zone();
+    assert_eq!(zone, DisplayZone::Local);
+    assert!(warning.unwrap().contains("Moon/Base"));
+}
+
+#[test]
+fn burst_settings_default_and_override() {
+    use std::time::Duration;
+
+    let config = CabooseConfig::default();
+    assert_eq!(config.stats.burst_threshold(), 20);
+    assert_eq!(config.stats.burst_window(), Duration::from_secs(120));
+
+    let config: CabooseConfig =
+        toml::from_str("[stats]\nburst_threshold = 50\nburst_window_secs = 30\n").unwrap();
+    assert_eq!(config.stats.burst_threshold(), 50);
+    assert_eq!(config.stats.burst_window(), Duration::from_secs(30));
+
+    assert_eq!(CabooseConfig::default().stats.turbo_burst_threshold(), 50);
+    let config: CabooseConfig =
+        toml::from_str("[stats]\nturbo_burst_threshold = 5\nturbo_burst_window_secs = 2\n")
+            .unwrap();
+    assert_eq!(config.stats.turbo_burst_threshold(), 5);
+    assert_eq!(config.stats.turbo_burst_window(), Duration::from_secs(2));
+}
+
+#[test]
+fn duplicate_settings_default_and_override() {
+    use std::time::Duration;
+
+    let (settings, warnings) = CabooseConfig::default().stats.duplicate_settings();
+    assert_eq!(settings.window, Duration::from_secs(1));
+    assert_eq!(settings.min_count, 2);
+    assert_eq!(settings.methods, ["POST", "PUT", "PATCH", "DELETE"]);
+    assert!(settings.exclude.is_empty());
+    assert!(warnings.is_empty());
+
+    let config: CabooseConfig = toml::from_str(
+        "[stats]\nduplicate_window_ms = 300\nduplicate_min_count = 3\n\
+         duplicate_methods = [\"post\", \"GET\"]\n\
+         duplicate_exclude = [\"/api/*/status\", \"poll/**\"]\n",
+    )
+    .unwrap();
+    let (settings, warnings) = config.stats.duplicate_settings();
+    assert_eq!(settings.window, Duration::from_millis(300));
+    assert_eq!(settings.min_count, 3);
+    assert_eq!(settings.methods, ["POST", "GET"]);
+    assert_eq!(settings.exclude.len(), 1);
+    assert_eq!(settings.exclude[0].as_str(), "/api/*/status");
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("'poll/**'"), "{}", warnings[0]);
+}
+
+#[test]
+fn exception_grouping_default_and_override() {
+    use caboose::exception::ExceptionGrouping;
+
+    assert_eq!(
+        CabooseConfig::default().exceptions.grouping(),
+        ExceptionGrouping::default()
+    );
+    let config: CabooseConfig = toml::from_str(
+        "[exceptions]\nfingerprint_with_location = true\nfingerprint_line_numbers = false\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.exceptions.grouping(),
+        ExceptionGrouping {
+            with_location: true,
+            line_numbers: false,
+        }
+    );
+}
+
+#[test]
+fn disk_settings_default_and_override() {
+    use caboose::disk::DiskLimits;
+
+    let config = CabooseConfig::default();
+    assert!(config.disk.enabled());
+    assert_eq!(config.disk.limits(), DiskLimits::default());
+
+    let config: CabooseConfig =
+        toml::from_str("[disk]\nenabled = false\nmax_file_mb = 512\nmin_free_mb = 100\n").unwrap();
+    assert!(!config.disk.enabled());
+    assert_eq!(
+        config.disk.limits(),
+        DiskLimits {
+            max_file_size: 512 * 1024 * 1024,
+            min_free_space: 100 * 1024 * 1024,
+        }
+    );
+}
+
+#[test]
+fn privacy_is_off_by_default_and_configurable() {
+    assert!(!CabooseConfig::default().privacy.privacy().is_enabled());
+
+    let config: CabooseConfig =
+        toml::from_str("[privacy]\nenabled = true\nbranch_patterns = [\"customer/*\"]\n").unwrap();
+    let privacy = config.privacy.privacy();
+    assert!(privacy.is_enabled());
+    assert_eq!(privacy.branch("customer/acme"), caboose::privacy::MASK);
+    assert_eq!(privacy.branch("main"), "main");
+}
+
+fn template_values() -> TemplateValues {
+    TemplateValues::new()
+        .with_rails_port(4000)
+        .with_frontend(Some(5173), "client")
+        .with_project_root(Path::new("/src/shop"))
+}
+
+fn env_map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn template_substitutes_detected_values() {
+    let rendered = template_values()
+        .render(
+            "cd {project.root}/{frontend.path} && PORT={frontend.port} API=http://localhost:{rails.port} {env.EXTRA}",
+            &env_map(&[("EXTRA", "--open")]),
+        )
+        .unwrap();
+
+    assert_eq!(
+        rendered,
+        "cd /src/shop/client && PORT=5173 API=http://localhost:4000 --open"
+    );
+}
+
+#[test]
+fn template_escapes_double_braces() {
+    let rendered = template_values()
+        .render(r#"{{"port": {rails.port}}} }"#, &HashMap::new())
+        .unwrap();
+
+    assert_eq!(rendered, r#"{"port": 4000} }"#);
+}
+
+#[test]
+fn template_env_expands_nested_env_references() {
+    let overrides = env_map(&[
+        ("API_HOST", "localhost:{rails.port}"),
+        ("VITE_API_URL", "http://{env.API_HOST}/api"),
+        ("VITE_CABLE_URL", "ws://{env.API_HOST}/cable?db={env.DB}"),
+    ]);
+    let base = env_map(&[("DB", "{not-expanded}")]);
+
+    let rendered = template_values().render_env(&overrides, &base).unwrap();
+
+    assert_eq!(rendered["VITE_API_URL"], "http://localhost:4000/api");
+    assert_eq!(
+        rendered["VITE_CABLE_URL"],
+        "ws://localhost:4000/cable?db={not-expanded}"
+    );
+    assert_eq!(rendered["DB"], "{not-expanded}");
+}
+
+#[test]
+fn template_errors() {
+    let err = template_values()
+        .render("{rails.prot}", &HashMap::new())
+        .unwrap_err();
+    assert!(err.contains("unknown placeholder {rails.prot}"), "{}", err);
+    assert!(err.contains("{frontend.path}"), "{}", err);
+
+    let err = template_values()
+        .render("{rails.port", &HashMap::new())
+        .unwrap_err();
+    assert!(err.contains("unclosed {"), "{}", err);
+
+    let err = template_values()
+        .render("{env.CABOOSE_SURELY_UNSET_VAR}", &HashMap::new())
+        .unwrap_err();
+    assert_eq!(err, "{env.CABOOSE_SURELY_UNSET_VAR} is not set");
+
+    let err = TemplateValues::new()
+        .render("{frontend.port}", &HashMap::new())
+        .unwrap_err();
+    assert!(err.contains("no frontend detected"), "{}", err);
+
+    let looped = env_map(&[("A", "{env.B}"), ("B", "{env.A}")]);
+    let err = template_values()
+        .render_env(&looped, &HashMap::new())
+        .unwrap_err();
+    assert_eq!(err, "A: {env.*} loop: A -> B -> A");
+}
+
+#[test]
+fn setup_commands_take_plain_lines_or_tables() {
+    let config: CabooseConfig = toml::from_str(
+        r#"
+[setup]
+timeout_secs = 60
+commands = [
+  "bundle exec rails db:prepare",
+  { command = "yarn install", unless_file = "tmp/caboose-setup-done", timeout_secs = 600 },
+]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.setup.steps(),
+        vec![
+            SetupStep::new("bundle exec rails db:prepare").with_timeout(Duration::from_secs(60)),
+            SetupStep::new("yarn install")
+                .with_unless_file("tmp/caboose-setup-done")
+                .with_timeout(Duration::from_secs(600)),
+        ]
+    );
+    assert!(CabooseConfig::default().setup.steps().is_empty());
+    assert!(CabooseConfig::create_example().contains("[setup]"));
+}
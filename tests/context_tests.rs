@@ -1,9 +1,24 @@
-use caboose::context::RequestContextTracker;
-use caboose::parser::{HttpRequest, LogEvent, SqlQuery};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use caboose::context::{
+    BurstDetector, BurstEvent, BurstKind, DuplicateDetector, DuplicateSettings, Duplicates,
+    QueueEstimator, RequestContextTracker, RequestTarget, TurboStreams, describe_stream,
+    is_console_prompt, normalize_path,
+};
+use caboose::diagnostics::{Limits, StoreKind};
+use caboose::parser::{
+    HttpRequest, LogEvent, RailsLogParser, SqlQuery, TurboDelivery, TurboStream,
+};
+use caboose::path_glob::PathGlob;
+use caboose::rails::routes::{Route, RouteTable};
+use caboose::search::SearchQuery;
 
 #[test]
 fn tracker_collects_requests_and_queries() {
     let tracker = RequestContextTracker::new();
+    assert!(tracker.is_empty());
 
     tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
         method: "GET".into(),
@@ -12,13 +27,19 @@ fn tracker_collects_requests_and_queries() {
         duration: None,
         controller: None,
         action: None,
+        extra: HashMap::new(),
     }));
+    // In flight is enough
+    assert!(!tracker.is_empty());
 
     tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
         query: r#"SELECT "users".* FROM "users" WHERE "users"."id" = 1"#.into(),
         duration: Some(5.0),
         rows: Some(1),
         name: Some("User Load".into()),
+        binds: Vec::new(),
+        truncated: None,
+        request_id: None,
     }));
 
     tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
@@ -26,6 +47,9 @@ fn tracker_collects_requests_and_queries() {
         duration: Some(5.0),
         rows: Some(1),
         name: Some("User Load".into()),
+        binds: Vec::new(),
+        truncated: None,
+        request_id: None,
     }));
 
     tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
@@ -33,6 +57,9 @@ fn tracker_collects_requests_and_queries() {
         duration: Some(5.0),
         rows: Some(1),
         name: Some("User Load".into()),
+        binds: Vec::new(),
+        truncated: None,
+        request_id: None,
     }));
 
     tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
@@ -42,6 +69,7 @@ fn tracker_collects_requests_and_queries() {
         duration: Some(30.0),
         controller: None,
         action: None,
+        extra: HashMap::new(),
     }));
 
     let completed = tracker.get_recent_requests();
@@ -49,3 +77,1108 @@ fn tracker_collects_requests_and_queries() {
     assert_eq!(completed[0].context.query_count(), 3);
     assert_eq!(completed[0].n_plus_one_issues.len(), 1);
 }
+
+#[test]
+fn trims_completed_requests_to_newest() {
+    let tracker = RequestContextTracker::new();
+    for i in 0..4 {
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: format!("/users/{}", i),
+            status: None,
+            duration: None,
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: String::new(),
+            status: Some(200),
+            duration: Some(10.0),
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+    }
+
+    let usage = tracker.store_usage();
+    let completed = usage
+        .iter()
+        .find(|u| u.kind == StoreKind::CompletedRequests)
+        .unwrap();
+    assert_eq!(completed.items, 4);
+    assert!(tracker.approx_bytes() >= completed.bytes);
+
+    assert_eq!(tracker.trim(StoreKind::CompletedRequests, 1), 3);
+    let remaining = tracker.get_recent_requests();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].context.path.as_deref(), Some("/users/3"));
+}
+
+#[test]
+fn filters_requests_by_path_or_controller() {
+    let tracker = RequestContextTracker::new();
+    for (path, controller) in [
+        ("/users", "UsersController#index"),
+        ("/posts", "PostsController#index"),
+    ] {
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: path.into(),
+            status: None,
+            duration: None,
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+        tracker.process_log_event(&LogEvent::Info(format!("Processing: {}", controller)));
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: String::new(),
+            path: String::new(),
+            status: Some(200),
+            duration: Some(10.0),
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+    }
+
+    let by_path = tracker.get_recent_requests_matching(&SearchQuery::new("/POSTS"));
+    assert_eq!(by_path.len(), 1);
+    assert_eq!(
+        by_path[0].context.controller.as_deref(),
+        Some("PostsController#index")
+    );
+
+    let by_controller = tracker.get_recent_requests_matching(&SearchQuery::new("userscontroller"));
+    assert_eq!(by_controller.len(), 1);
+    assert_eq!(by_controller[0].context.path.as_deref(), Some("/users"));
+
+    assert_eq!(
+        tracker
+            .get_recent_requests_matching(&SearchQuery::default())
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn completed_requests_keep_extra_fields_from_completion_line() {
+    let tracker = RequestContextTracker::new();
+
+    for line in [
+        r#"Started GET "/reports" for 127.0.0.1"#,
+        "Completed 200 OK in 52ms (Views: 40.1ms | ActiveRecord: 9.5ms | Allocations: 1200)",
+    ] {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            tracker.process_log_event(&event);
+        }
+    }
+
+    let completed = tracker.get_recent_requests();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].extra["db"], "9.5");
+    assert_eq!(completed[0].extra["allocations"], "1200");
+}
+
+#[test]
+fn completed_request_limit_applies_and_resizes() {
+    let tracker = RequestContextTracker::new().with_limits(Limits {
+        max_completed_requests: 3,
+        ..Limits::default()
+    });
+    for i in 0..5 {
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: format!("/users/{}", i),
+            status: None,
+            duration: None,
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: String::new(),
+            status: Some(200),
+            duration: Some(10.0),
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+    }
+    assert_eq!(tracker.get_recent_requests().len(), 3);
+
+    tracker.set_limits(Limits {
+        max_completed_requests: 1,
+        ..Limits::default()
+    });
+    let remaining = tracker.get_recent_requests();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].context.path.as_deref(), Some("/users/4"));
+}
+
+#[test]
+fn attaches_renders_to_request_and_aggregates_per_template() {
+    let tracker = RequestContextTracker::new();
+    for _ in 0..2 {
+        for line in [
+            r#"Started GET "/users" for 127.0.0.1"#,
+            "Rendered collection of users/_row.html.erb [20 times] (Duration: 10.0ms | Allocations: 900)",
+            "Rendered users/index.html.erb within layouts/application (Duration: 14.0ms | Allocations: 1200)",
+            "Rendered layout layouts/application.html.erb (Duration: 16.0ms | Allocations: 1500)",
+            "Completed 200 OK in 30ms (Views: 16.0ms | ActiveRecord: 2.0ms | Allocations: 3000)",
+        ] {
+            if let Some(event) = RailsLogParser::parse_line(line) {
+                tracker.process_log_event(&event);
+            }
+        }
+    }
+
+    let requests = tracker.get_recent_requests();
+    assert_eq!(requests.len(), 2);
+    let views: Vec<&str> = requests[0]
+        .context
+        .views_by_duration()
+        .iter()
+        .map(|view| view.template.as_str())
+        .collect();
+    assert_eq!(
+        views,
+        [
+            "layouts/application.html.erb",
+            "users/index.html.erb",
+            "users/_row.html.erb"
+        ]
+    );
+
+    let stats = tracker.get_view_stats();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[0].template, "layouts/application.html.erb");
+    let row = stats
+        .iter()
+        .find(|s| s.template == "users/_row.html.erb")
+        .unwrap();
+    assert_eq!(row.renders, 2);
+    assert_eq!(row.items, 40);
+    assert_eq!(row.total_duration, 20.0);
+    assert_eq!(row.avg_duration(), 10.0);
+
+    assert_eq!(tracker.trim(StoreKind::ViewStats, 1), 2);
+    assert_eq!(tracker.get_view_stats().len(), 1);
+}
+
+#[test]
+fn in_flight_requests_keep_their_id_through_completion() {
+    let tracker = RequestContextTracker::new();
+    let start = |path: &str| {
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: path.into(),
+            status: None,
+            duration: None,
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        }));
+    };
+    start("/reports");
+    start("/users");
+
+    let current = tracker.get_current_requests();
+    let (report_id, users_id) = (current[0].id, current[1].id);
+    assert_ne!(report_id, users_id);
+    assert!(tracker.completed_position(report_id).is_none());
+
+    tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
+        query: r#"SELECT "users".* FROM "users""#.into(),
+        duration: Some(2.0),
+        rows: None,
+        name: None,
+        binds: Vec::new(),
+        truncated: None,
+        request_id: None,
+    }));
+    assert_eq!(tracker.get_in_flight(users_id).unwrap().query_count(), 1);
+    assert_eq!(tracker.get_in_flight(report_id).unwrap().query_count(), 0);
+
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: "GET".into(),
+        path: String::new(),
+        status: Some(200),
+        duration: Some(40.0),
+        controller: None,
+        action: None,
+        extra: HashMap::new(),
+    }));
+    assert!(tracker.get_in_flight(report_id).is_none());
+    assert_eq!(tracker.completed_position(report_id), Some(0));
+    assert!(tracker.get_in_flight(users_id).is_some());
+}
+
+#[test]
+fn cache_activity_is_attributed_to_the_active_request() {
+    let tracker = RequestContextTracker::new();
+    for line in [
+        r#"Started GET "/users" for 127.0.0.1"#,
+        "Cache read: views/users/1 (hit)",
+        "Cache read: views/users/2 (miss)",
+        "Cache write: views/users/2",
+        "Completed 200 OK in 20ms",
+    ] {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            tracker.process_log_event(&event);
+        }
+    }
+
+    let cache = tracker.get_recent_requests()[0].context.cache;
+    assert_eq!((cache.hits, cache.misses, cache.writes), (1, 1, 1));
+}
+
+fn start(tracker: &RequestContextTracker, path: &str, extra: HashMap<String, String>) {
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: "GET".into(),
+        path: path.into(),
+        status: None,
+        duration: None,
+        controller: None,
+        action: None,
+        extra,
+    }));
+}
+
+fn complete(tracker: &RequestContextTracker) {
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: String::new(),
+        path: String::new(),
+        status: Some(200),
+        duration: Some(20.0),
+        controller: None,
+        action: None,
+        extra: HashMap::new(),
+    }));
+}
+
+fn with_request_id(id: &str) -> HashMap<String, String> {
+    HashMap::from([("request_id".to_string(), id.to_string())])
+}
+
+#[test]
+fn requests_are_found_by_request_id_prefix() {
+    let tracker = RequestContextTracker::new();
+    start(&tracker, "/orders", with_request_id("ab12cd34-0000"));
+    complete(&tracker);
+    start(&tracker, "/users", with_request_id("AB98ef01-0000"));
+    complete(&tracker);
+    start(&tracker, "/live", with_request_id("ff00aa11-0000"));
+
+    // Unique prefix, any case
+    let found = tracker.find_by_request_id("AB12");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].request_id, "ab12cd34-0000");
+    assert_eq!(found[0].label, "GET /orders");
+    assert_eq!(found[0].target, RequestTarget::Completed(0));
+
+    // Ambiguous: newest first
+    let found = tracker.find_by_request_id("ab");
+    let ids: Vec<_> = found.iter().map(|m| m.request_id.as_str()).collect();
+    assert_eq!(ids, ["AB98ef01-0000", "ab12cd34-0000"]);
+
+    // In flight, and the full id picks one even when it prefixes no other
+    let found = tracker.find_by_request_id("ff00aa11-0000");
+    assert_eq!(found.len(), 1);
+    assert!(matches!(found[0].target, RequestTarget::InFlight(_)));
+
+    assert!(tracker.find_by_request_id("zz").is_empty());
+    assert!(tracker.find_by_request_id(" ").is_empty());
+}
+
+#[test]
+fn exceptions_link_to_the_request_in_flight_or_that_just_failed() {
+    let tracker = RequestContextTracker::new();
+    assert!(
+        tracker
+            .request_for_exception(None, Instant::now())
+            .is_none()
+    );
+
+    start(&tracker, "/orders/14", with_request_id("ab12cd34-0000"));
+    let found = tracker.request_for_exception(None, Instant::now()).unwrap();
+    assert_eq!(found.label, "GET /orders/14");
+    assert!(matches!(found.target, RequestTarget::InFlight(_)));
+
+    // Rails logs the exception after `Completed 500`
+    tracker.process_log_event(
+        &RailsLogParser::parse_line("Completed 500 Internal Server Error in 5ms").unwrap(),
+    );
+    let found = tracker.request_for_exception(None, Instant::now()).unwrap();
+    assert_eq!(found.request_id, "ab12cd34-0000");
+    assert_eq!(found.target, RequestTarget::Completed(0));
+    assert!(
+        tracker
+            .request_for_exception(None, Instant::now() + Duration::from_secs(5))
+            .is_none()
+    );
+
+    // A request that succeeded raised nothing
+    start(&tracker, "/users", HashMap::new());
+    complete(&tracker);
+    assert!(
+        tracker
+            .request_for_exception(None, Instant::now())
+            .is_none()
+    );
+}
+
+#[test]
+fn exceptions_tagged_with_a_request_id_link_to_that_request() {
+    let tracker = RequestContextTracker::new();
+    for line in [
+        r#"[req-b] Started POST "/orders/7" for 127.0.0.1"#,
+        r#"[req-a] Started GET "/reports" for 127.0.0.1"#,
+        "[req-b] Completed 500 Internal Server Error in 5ms",
+    ] {
+        tracker.process_log_event(&RailsLogParser::parse_line(line).unwrap());
+    }
+
+    let found = tracker
+        .request_for_exception(Some("req-b"), Instant::now())
+        .unwrap();
+    assert_eq!(found.label, "POST /orders/7");
+    assert_eq!(found.target, RequestTarget::Completed(0));
+    let found = tracker
+        .request_for_exception(Some("req-a"), Instant::now())
+        .unwrap();
+    assert!(matches!(found.target, RequestTarget::InFlight(_)));
+    assert!(
+        tracker
+            .request_for_exception(Some("req-gone"), Instant::now())
+            .is_none()
+    );
+
+    // Untagged, the request that just failed wins over one still in flight
+    let found = tracker.request_for_exception(None, Instant::now()).unwrap();
+    assert_eq!(found.request_id, "req-b");
+}
+
+#[test]
+fn queries_of_overlapping_requests_go_to_the_request_with_their_id() {
+    let tracker = RequestContextTracker::new();
+    for line in [
+        r#"[req-a] Started GET "/users" for 127.0.0.1"#,
+        r#"[req-a]   User Load (0.4ms)  SELECT "users".* FROM "users""#,
+        r#"[req-b] Started GET "/orders" for 127.0.0.1"#,
+        r#"[req-a]   Post Load (0.3ms)  SELECT "posts".* FROM "posts""#,
+        r#"[req-b]   Order Load (0.5ms)  SELECT "orders".* FROM "orders""#,
+        "[req-b] Completed 200 OK in 12ms",
+        r#"[req-a]   Tag Load (0.2ms)  SELECT "tags".* FROM "tags""#,
+        "[req-a] Completed 200 OK in 30ms",
+    ] {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            tracker.process_log_event(&event);
+        }
+    }
+
+    let recent = tracker.get_recent_requests();
+    let tables = |index: usize| -> Vec<String> {
+        recent[index]
+            .context
+            .queries
+            .iter()
+            .map(|query| query.raw_query.split('"').nth(1).unwrap_or("").to_string())
+            .collect()
+    };
+    // `/orders` finished first, with its own duration
+    assert_eq!(recent[0].context.path.as_deref(), Some("/orders"));
+    assert_eq!(recent[0].total_duration, Some(12.0));
+    assert_eq!(tables(0), ["orders"]);
+    assert_eq!(recent[1].context.path.as_deref(), Some("/users"));
+    assert_eq!(recent[1].total_duration, Some(30.0));
+    assert_eq!(tables(1), ["users", "posts", "tags"]);
+    assert!(tracker.get_current_requests().is_empty());
+}
+
+#[test]
+fn lines_tagged_with_an_unknown_id_leave_other_requests_alone() {
+    let tracker = RequestContextTracker::new();
+    start(&tracker, "/orders", with_request_id("req-a"));
+    for line in [
+        // `req-gone` started before the tracker was attached
+        r#"[req-gone]   User Load (0.4ms)  SELECT "users".* FROM "users""#,
+        "[req-gone] Completed 200 OK in 12ms",
+    ] {
+        tracker.process_log_event(&RailsLogParser::parse_line(line).unwrap());
+    }
+
+    let current = tracker.get_current_requests();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0].request_id.as_deref(), Some("req-a"));
+    assert!(current[0].queries.is_empty());
+    assert!(tracker.get_recent_requests().is_empty());
+    assert_eq!(tracker.background_queries().queries, 1);
+}
+
+#[test]
+fn untagged_lines_fall_back_to_the_latest_and_oldest_request() {
+    let tracker = RequestContextTracker::new();
+    start(&tracker, "/users", HashMap::new());
+    start(&tracker, "/orders", HashMap::new());
+    tracker.process_log_event(&sql(r#"SELECT "orders".* FROM "orders""#, 1.0));
+    complete(&tracker);
+    complete(&tracker);
+
+    let recent = tracker.get_recent_requests();
+    assert_eq!(recent[0].context.path.as_deref(), Some("/users"));
+    assert!(recent[0].context.queries.is_empty());
+    assert_eq!(recent[1].context.queries.len(), 1);
+}
+
+#[test]
+fn request_ids_from_the_completion_line_and_within_the_limit_are_kept() {
+    let tracker = RequestContextTracker::new().with_limits(Limits {
+        max_completed_requests: 2,
+        ..Limits::default()
+    });
+    for i in 0..4 {
+        start(&tracker, &format!("/users/{}", i), HashMap::new());
+        tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+            method: String::new(),
+            path: String::new(),
+            status: Some(200),
+            duration: Some(20.0),
+            controller: None,
+            action: None,
+            extra: with_request_id(&format!("req-{}", i)),
+        }));
+    }
+
+    let recent = tracker.get_recent_requests();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[1].context.request_id.as_deref(), Some("req-3"));
+    assert_eq!(recent[1].context.short_request_id(), Some("req-3"));
+    assert_eq!(tracker.find_by_request_id("req-2").len(), 1);
+    assert!(tracker.find_by_request_id("req-1").is_empty());
+}
+
+#[test]
+fn logged_queue_time_goes_to_the_next_request() {
+    let tracker = RequestContextTracker::new();
+    start(&tracker, "/before", HashMap::new());
+    complete(&tracker);
+
+    tracker.process_log_event(&LogEvent::RequestQueueing(180.0));
+    start(&tracker, "/users", HashMap::new());
+    complete(&tracker);
+    start(
+        &tracker,
+        "/posts",
+        HashMap::from([("queue_time".to_string(), "240".to_string())]),
+    );
+    complete(&tracker);
+
+    let queue_times: Vec<_> = tracker
+        .get_recent_requests()
+        .iter()
+        .map(|req| {
+            req.context
+                .queue_time
+                .map(|queue| (queue.ms, queue.estimated))
+        })
+        .collect();
+    assert_eq!(
+        queue_times,
+        [None, Some((180.0, false)), Some((240.0, false))]
+    );
+
+    let (average, count) = tracker
+        .average_queue_time(Instant::now(), Duration::from_secs(60))
+        .unwrap();
+    assert_eq!((average, count), (210.0, 2));
+    assert_eq!(tracker.queueing_warning(Instant::now()), Some(210.0));
+    assert_eq!(
+        RequestContextTracker::new()
+            .with_queue_time_warning(500.0)
+            .queueing_warning(Instant::now()),
+        None
+    );
+}
+
+#[test]
+fn queueing_is_estimated_only_when_every_thread_was_busy() {
+    let mut estimator = QueueEstimator::new(2);
+    let t0 = Instant::now();
+    let ms = |millis| t0 + Duration::from_millis(millis);
+
+    assert_eq!(estimator.started(ms(0)), None);
+    assert_eq!(estimator.started(ms(10)), None);
+    // Both threads busy; one frees and the next request starts right away
+    estimator.completed(ms(300), 2);
+    assert_eq!(estimator.started(ms(301)), Some(291.0));
+
+    // A start well after a completion didn't wait for that thread
+    estimator.completed(ms(400), 2);
+    assert_eq!(estimator.started(ms(450)), None);
+
+    // Nor does one after a completion with a thread to spare
+    estimator.completed(ms(500), 1);
+    assert_eq!(estimator.started(ms(501)), None);
+}
+
+#[test]
+fn requests_without_a_queueing_signal_have_none() {
+    let tracker = RequestContextTracker::new().with_request_capacity(1);
+    start(&tracker, "/users", HashMap::new());
+    std::thread::sleep(Duration::from_millis(2));
+    complete(&tracker);
+    start(&tracker, "/posts", HashMap::new());
+    complete(&tracker);
+
+    let requests = tracker.get_recent_requests();
+    assert!(requests[0].context.queue_time.is_none());
+    // Saturated single thread: back-to-back start is estimated
+    let queue = requests[1].context.queue_time.unwrap();
+    assert!(queue.estimated && queue.ms >= 2.0, "{:?}", queue);
+
+    // Once the logs report queueing, estimates stop
+    tracker.process_log_event(&LogEvent::RequestQueueing(5.0));
+    start(&tracker, "/a", HashMap::new());
+    complete(&tracker);
+    start(&tracker, "/b", HashMap::new());
+    complete(&tracker);
+    let requests = tracker.get_recent_requests();
+    assert_eq!(requests[2].context.queue_time.unwrap().ms, 5.0);
+    assert!(requests[3].context.queue_time.is_none());
+    assert!(
+        RequestContextTracker::new()
+            .average_queue_time(Instant::now(), Duration::from_secs(60))
+            .is_none()
+    );
+}
+
+#[test]
+fn burst_paths_are_normalized() {
+    assert_eq!(normalize_path("/users/42?tab=posts"), "/users/:id");
+    assert_eq!(
+        normalize_path("/orders/3f2b8c1e-9a4d-4e6f-8b1a-2c3d4e5f6a7b/items/7"),
+        "/orders/:id/items/:id"
+    );
+    assert_eq!(
+        normalize_path("/assets/app-0123456789abcdef0123.js"),
+        "/assets/app-0123456789abcdef0123.js"
+    );
+    assert_eq!(normalize_path("/blobs/0123456789abcdef"), "/blobs/:id");
+    assert_eq!(normalize_path("/api/v2/users"), "/api/v2/users");
+}
+
+#[test]
+fn burst_fires_once_past_the_threshold_and_clears_at_half() {
+    let start = Instant::now();
+    let mut detector = BurstDetector::new(4, Duration::from_secs(60));
+    for i in 0..5 {
+        detector.record(start, 404, &format!("/users/{}", i));
+    }
+    detector.record(start, 200, "/users/1");
+    detector.record(start, 500, "/users/1");
+
+    let events = detector.evaluate(start);
+    assert_eq!(events.len(), 1);
+    let BurstEvent::Started(burst) = &events[0] else {
+        panic!("expected a burst to start: {:?}", events);
+    };
+    assert_eq!(burst.kind, BurstKind::NotFound);
+    assert_eq!(burst.count, 5);
+    assert_eq!(
+        burst.message(),
+        "/users/:id returning 404 ×5 in 1m — route missing?"
+    );
+
+    // Still bursting, no repeat warning
+    detector.record(start + Duration::from_secs(40), 404, "/users/9");
+    detector.record(start + Duration::from_secs(40), 404, "/users/9");
+    detector.record(start + Duration::from_secs(40), 404, "/users/9");
+    assert!(
+        detector
+            .evaluate(start + Duration::from_secs(45))
+            .is_empty()
+    );
+
+    // The first five age out: 3 left is under the threshold but above half
+    assert!(
+        detector
+            .evaluate(start + Duration::from_secs(70))
+            .is_empty()
+    );
+    assert_eq!(detector.active()[0].count, 3);
+
+    let events = detector.evaluate(start + Duration::from_secs(110));
+    assert!(
+        matches!(&events[..], [BurstEvent::Cleared { burst, .. }] if burst.path == "/users/:id"),
+        "{:?}",
+        events
+    );
+    assert!(detector.active().is_empty());
+}
+
+#[test]
+fn bursts_count_paths_under_their_route_pattern() {
+    let now = Instant::now();
+    let mut detector = BurstDetector::new(2, Duration::from_secs(60));
+    detector.set_routes(Arc::new(RouteTable::new(vec![Route {
+        name: Some("post".to_string()),
+        verb: "GET".to_string(),
+        path: "/posts/:slug(.:format)".to_string(),
+        action: "posts#show".to_string(),
+    }])));
+    // Slugs aren't ids, but they're one route
+    detector.record(now, 500, "/posts/hello-world");
+    detector.record(now, 500, "/posts/second-post");
+    detector.record(now, 500, "/posts/third-post.json");
+    detector.record(now, 404, "/missing/1");
+
+    let events = detector.evaluate(now);
+    let paths: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            BurstEvent::Started(burst) => Some(burst.path.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(paths, ["/posts/:slug"]);
+}
+
+#[test]
+fn server_error_bursts_read_differently() {
+    let now = Instant::now();
+    let mut detector = BurstDetector::new(2, Duration::from_secs(120));
+    for status in [500, 502, 503] {
+        detector.record(now, status, "/checkout");
+    }
+
+    let events = detector.evaluate(now);
+    let [BurstEvent::Started(burst)] = &events[..] else {
+        panic!("expected a burst to start: {:?}", events);
+    };
+    assert_eq!(burst.kind, BurstKind::ServerError);
+    assert!(
+        burst
+            .message()
+            .starts_with("/checkout returning 5xx ×3 in 2m")
+    );
+    assert!(!burst.message().contains("route missing"));
+}
+
+#[test]
+fn routing_errors_complete_their_request_as_404() {
+    let tracker = RequestContextTracker::new().with_burst_detection(2, Duration::from_secs(60));
+    for id in 1..=3 {
+        for line in [
+            format!(r#"Started GET "/api/v2/users/{}" for 127.0.0.1"#, id),
+            format!(
+                r#"ActionController::RoutingError (No route matches [GET] "/api/v2/users/{}"):"#,
+                id
+            ),
+        ] {
+            tracker.process_log_event(&RailsLogParser::parse_line(&line).unwrap());
+        }
+    }
+
+    assert!(tracker.get_current_requests().is_empty());
+    let completed = tracker.get_recent_requests();
+    assert_eq!(completed.len(), 3);
+    assert!(completed.iter().all(|req| req.status == Some(404)));
+
+    let events = tracker.poll_bursts(Instant::now());
+    assert_eq!(events.len(), 1);
+    assert!(
+        events[0]
+            .message()
+            .starts_with("[burst] /api/v2/users/:id returning 404 ×3"),
+        "{}",
+        events[0].message()
+    );
+    assert_eq!(tracker.active_bursts().len(), 1);
+}
+
+#[test]
+fn repeated_posts_within_the_window_are_one_duplicate_run() {
+    let mut detector = DuplicateDetector::new(DuplicateSettings::default());
+    let t0 = Instant::now();
+    let ms = Duration::from_millis;
+
+    assert!(detector.record(t0, 1, "POST", "/orders", None).is_none());
+    let group = detector
+        .record(t0 + ms(90), 2, "POST", "/orders", None)
+        .unwrap();
+    assert_eq!(group.ids, [1, 2]);
+    let group = detector
+        .record(t0 + ms(180), 3, "post", "/orders", None)
+        .unwrap();
+    assert_eq!(group.ids, [1, 2, 3]);
+    assert_eq!(group.duplicates().badge(), "×3 in 180ms");
+
+    // Still open while a repeat could follow, then reported once
+    assert!(detector.poll(t0 + ms(900)).is_empty());
+    let finished = detector.poll(t0 + ms(1_500));
+    assert_eq!(finished.len(), 1);
+    assert_eq!(
+        finished[0].message(),
+        "[duplicate] POST /orders ×3 in 180ms — double submit or retry loop?"
+    );
+    assert!(detector.poll(t0 + ms(3_000)).is_empty());
+
+    // Far enough apart, they're separate requests
+    assert!(
+        detector
+            .record(t0 + ms(5_000), 4, "POST", "/orders", None)
+            .is_none()
+    );
+    assert!(
+        detector
+            .record(t0 + ms(6_500), 5, "POST", "/orders", None)
+            .is_none()
+    );
+}
+
+#[test]
+fn duplicates_skip_gets_excluded_paths_and_different_params() {
+    let settings = DuplicateSettings {
+        exclude: vec![PathGlob::parse("/api/*/status").unwrap()],
+        ..DuplicateSettings::default()
+    };
+    let mut detector = DuplicateDetector::new(settings);
+    let t0 = Instant::now();
+    let ms = Duration::from_millis;
+
+    for (id, method, path, params) in [
+        (1, "GET", "/orders", None),
+        (2, "GET", "/orders", None),
+        (3, "POST", "/api/jobs/status", None),
+        (4, "POST", "/api/jobs/status?n=2", None),
+        (5, "POST", "/orders", Some(1)),
+        (6, "POST", "/orders", Some(2)),
+        (7, "PATCH", "/orders/1", None),
+        (8, "PATCH", "/orders/2", None),
+    ] {
+        assert!(
+            detector
+                .record(t0 + ms(id * 10), id, method, path, params)
+                .is_none(),
+            "{} {}",
+            method,
+            path
+        );
+    }
+    assert!(detector.poll(t0 + ms(5_000)).is_empty());
+}
+
+#[test]
+fn duplicate_badges_show_more_than_a_second_in_seconds() {
+    let duplicates = Duplicates {
+        count: 4,
+        span: Duration::from_millis(2_350),
+    };
+    assert_eq!(duplicates.badge(), "×4 in 2.4s");
+}
+
+fn post_orders(tracker: &RequestContextTracker, params: &str) {
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: "POST".into(),
+        path: "/orders".into(),
+        status: None,
+        duration: None,
+        controller: None,
+        action: None,
+        extra: HashMap::new(),
+    }));
+    tracker.process_log_event(&LogEvent::RequestParams(params.into()));
+    complete(tracker);
+}
+
+#[test]
+fn tracker_marks_every_request_in_a_duplicate_run() {
+    let tracker =
+        RequestContextTracker::new().with_duplicate_detection(DuplicateSettings::default());
+    post_orders(&tracker, r#"{"sku"=>"A-1"}"#);
+    assert!(tracker.get_recent_requests()[0].duplicates.is_none());
+    post_orders(&tracker, r#"{"sku"=>"A-1"}"#);
+    post_orders(&tracker, r#"{"sku"=>"A-1"}"#);
+    post_orders(&tracker, r#"{"sku"=>"B-2"}"#);
+
+    let counts: Vec<Option<usize>> = tracker
+        .get_recent_requests()
+        .iter()
+        .map(|req| req.duplicates.map(|duplicates| duplicates.count))
+        .collect();
+    assert_eq!(counts, [Some(3), Some(3), Some(3), None]);
+
+    let finished = tracker.poll_duplicates(Instant::now() + Duration::from_secs(2));
+    assert_eq!(finished.len(), 1);
+    assert_eq!(finished[0].ids.len(), 3);
+}
+
+fn sql(query: &str, duration: f64) -> LogEvent {
+    LogEvent::SqlQuery(SqlQuery {
+        query: query.into(),
+        duration: Some(duration),
+        rows: None,
+        name: None,
+        binds: Vec::new(),
+        truncated: None,
+        request_id: None,
+    })
+}
+
+#[test]
+fn queries_outside_any_request_are_background_queries() {
+    let tracker = RequestContextTracker::new();
+    tracker.process_log_event(&sql(r#"SELECT "jobs".* FROM "jobs""#, 2.0));
+    tracker.process_log_event(&sql(r#"UPDATE "users" SET "name" = 'x'"#, 250.0));
+
+    start(&tracker, "/users", HashMap::new());
+    tracker.process_log_event(&sql(r#"SELECT "users".* FROM "users""#, 1.0));
+    complete(&tracker);
+
+    let background = tracker.background_queries();
+    assert_eq!((background.queries, background.slow), (2, 1));
+    assert_eq!(
+        background.summary(),
+        "Background / console — 2 queries, 1 slow"
+    );
+    assert_eq!(
+        background.slowest(1)[0].raw_query,
+        r#"UPDATE "users" SET "name" = 'x'"#
+    );
+    assert_eq!(tracker.get_recent_requests()[0].context.query_count(), 1);
+}
+
+#[test]
+fn console_queries_skip_the_active_request() {
+    let tracker = RequestContextTracker::new();
+    assert!(tracker.mark_console("console"));
+    assert!(!tracker.mark_console("console"));
+
+    start(&tracker, "/orders", HashMap::new());
+    tracker.process_log_event_from("console", &sql(r#"SELECT "orders".* FROM "orders""#, 120.0));
+    tracker.process_log_event_from("web", &sql(r#"SELECT "users".* FROM "users""#, 1.0));
+    complete(&tracker);
+
+    assert_eq!(tracker.get_recent_requests()[0].context.query_count(), 1);
+    let background = tracker.background_queries();
+    assert_eq!((background.queries, background.slow), (1, 1));
+    assert!(background.summary().ends_with("(console: console)"));
+
+    // Saved sessions keep them
+    let restored = RequestContextTracker::new();
+    restored.restore(tracker.snapshot());
+    assert_eq!(restored.background_queries().queries, 1);
+    assert!(restored.is_console("console"));
+}
+
+#[test]
+fn recognizes_console_prompts() {
+    for prompt in [
+        "irb(main):001:0> User.count",
+        "irb(main):002> ",
+        "[1] pry(main)> Order.last",
+        "shop(dev)> User.first",
+        "my-app(development):003> 1 + 1",
+    ] {
+        assert!(is_console_prompt(prompt), "{}", prompt);
+    }
+    for line in [
+        r#"Started GET "/users" for 127.0.0.1"#,
+        "  User Load (0.4ms)  SELECT \"users\".* FROM \"users\" WHERE (id > 3)",
+        "=> #<User id: 1>",
+    ] {
+        assert!(!is_console_prompt(line), "{}", line);
+    }
+}
+
+fn turbo(stream: &str, delivery: TurboDelivery) -> TurboStream {
+    TurboStream {
+        stream: stream.to_string(),
+        action: Some("replace".to_string()),
+        delivery,
+    }
+}
+
+#[test]
+fn turbo_stream_names_decode_global_ids() {
+    assert_eq!(
+        describe_stream("Z2lkOi8vYmxvZy9Qb3N0LzE:comments"),
+        ("Post/1:comments".to_string(), Some("Post".to_string()))
+    );
+    assert_eq!(
+        describe_stream("Z2lkOi8vYmxvZy9Db21tZW50LzQy"),
+        ("Comment/42".to_string(), Some("Comment".to_string()))
+    );
+    assert_eq!(describe_stream("posts"), ("posts".to_string(), None));
+    // Looks like a global id, isn't one
+    assert_eq!(
+        describe_stream("Z2lkOi8v!"),
+        ("Z2lkOi8v!".to_string(), None)
+    );
+}
+
+#[test]
+fn turbo_burst_fires_once_past_the_threshold_and_rearms_at_half() {
+    let mut streams = TurboStreams::new(5, Duration::from_secs(10));
+    let start = Instant::now();
+    let post = turbo("Z2lkOi8vYmxvZy9Qb3N0LzE", TurboDelivery::Broadcast);
+    let request = || Some("PATCH /posts/1".to_string());
+
+    for i in 0..5 {
+        let at = start + Duration::from_millis(i * 100);
+        assert!(streams.record(at, &post, request()).is_none());
+    }
+    let burst = streams
+        .record(start + Duration::from_millis(500), &post, request())
+        .expect("sixth broadcast within the window is a burst");
+    assert_eq!(burst.count, 6);
+    assert_eq!(
+        burst.message(),
+        "[turbo] Post/1 broadcast ×6 in 10s during PATCH /posts/1 — after_commit loop?"
+    );
+    // Still bursting: no second warning
+    assert!(
+        streams
+            .record(start + Duration::from_millis(600), &post, None)
+            .is_none()
+    );
+    assert!(streams.top(1)[0].is_bursting());
+
+    // Quiet long enough to fall to half, then a new burst warns again
+    let later = start + Duration::from_secs(30);
+    assert!(streams.record(later, &post, None).is_none());
+    assert!(!streams.top(1)[0].is_bursting());
+    let burst = (1..=5)
+        .find_map(|i| streams.record(later + Duration::from_millis(i * 10), &post, None))
+        .expect("burst re-armed");
+    assert_eq!(burst.request, None);
+}
+
+#[test]
+fn turbo_transmissions_stand_in_until_broadcasts_show_up() {
+    let mut streams = TurboStreams::new(2, Duration::from_secs(10));
+    let now = Instant::now();
+    let sent = turbo("posts", TurboDelivery::Transmission);
+    let broadcast = turbo("posts", TurboDelivery::Broadcast);
+
+    assert!(streams.record(now, &sent, None).is_none());
+    assert!(streams.record(now, &sent, None).is_none());
+    assert!(streams.record(now, &broadcast, None).is_none());
+    // Transmissions of a stream whose broadcasts are logged don't add up
+    for _ in 0..10 {
+        assert!(streams.record(now, &sent, None).is_none());
+    }
+    let top = streams.top(5);
+    assert_eq!(top[0].broadcasts, 1);
+    assert_eq!(top[0].transmissions, 12);
+    assert_eq!(top[0].volume(), 1);
+}
+
+#[test]
+fn tracker_lists_turbo_streams_by_volume_and_names_the_request() {
+    let tracker =
+        RequestContextTracker::new().with_turbo_burst_detection(2, Duration::from_secs(10));
+    let lines = [
+        r#"Started POST "/boards/7/cards" for 127.0.0.1"#,
+        r#"[ActionCable] Broadcasting to Z2lkOi8vYmxvZy9Cb2FyZC83: "<turbo-stream action=\"prepend\" target=\"cards\"></turbo-stream>""#,
+        r#"[ActionCable] Broadcasting to Z2lkOi8vYmxvZy9Cb2FyZC83: "<turbo-stream action=\"prepend\" target=\"cards\"></turbo-stream>""#,
+        r#"[ActionCable] Broadcasting to Z2lkOi8vYmxvZy9Cb2FyZC83: "<turbo-stream action=\"prepend\" target=\"cards\"></turbo-stream>""#,
+        "Completed 302 Found in 21ms",
+        r#"[ActionCable] Broadcasting to posts: "<turbo-stream action=\"remove\" target=\"post_2\"></turbo-stream>""#,
+    ];
+    for line in lines {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            tracker.process_log_event(&event);
+        }
+    }
+
+    let bursts = tracker.poll_turbo_bursts();
+    assert_eq!(bursts.len(), 1);
+    assert_eq!(bursts[0].label, "Board/7");
+    assert_eq!(bursts[0].request.as_deref(), Some("POST /boards/7/cards"));
+    assert!(tracker.poll_turbo_bursts().is_empty());
+
+    let top = tracker.top_turbo_streams(5);
+    let summary: Vec<_> = top
+        .iter()
+        .map(|stream| {
+            (
+                stream.label.as_str(),
+                stream.model.as_deref(),
+                stream.broadcasts,
+            )
+        })
+        .collect();
+    assert_eq!(summary, [("Board/7", Some("Board"), 3), ("posts", None, 1)]);
+    assert_eq!(top[1].last_action.as_deref(), Some("remove"));
+}
+
+/// Complete a request to `path` that loads `table` rows one at a time,
+/// `repeats` times at `ms` each
+fn request_repeating(
+    tracker: &RequestContextTracker,
+    path: &str,
+    table: &str,
+    repeats: usize,
+    ms: f64,
+) {
+    // The Completed line has no path
+    let request = |path: &str, status, duration| {
+        LogEvent::HttpRequest(HttpRequest {
+            method: "GET".into(),
+            path: path.into(),
+            status,
+            duration,
+            controller: None,
+            action: None,
+            extra: HashMap::new(),
+        })
+    };
+    tracker.process_log_event(&request(path, None, None));
+    for id in 0..repeats {
+        tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
+            query: format!(r#"SELECT "{table}".* FROM "{table}" WHERE "{table}"."id" = {id}"#),
+            duration: Some(ms),
+            rows: Some(1),
+            name: None,
+            binds: Vec::new(),
+            truncated: None,
+            request_id: None,
+        }));
+    }
+    tracker.process_log_event(&request("", Some(200), Some(50.0)));
+}
+
+#[test]
+fn n_plus_one_issues_are_grouped_by_query_across_requests() {
+    let tracker = RequestContextTracker::new();
+    request_repeating(&tracker, "/posts?page=2", "comments", 3, 1.0);
+    request_repeating(&tracker, "/users", "accounts", 10, 2.0);
+    request_repeating(&tracker, "/posts/1", "comments", 4, 1.0);
+    request_repeating(&tracker, "/posts", "comments", 5, 1.0);
+    // Twice isn't an N+1
+    request_repeating(&tracker, "/tags", "tags", 2, 50.0);
+
+    let aggregated = tracker.get_aggregated_n_plus_one();
+    assert_eq!(aggregated.len(), 2);
+
+    // 20ms wasted on accounts beats 12ms on comments, though comments ran more often
+    let accounts = &aggregated[0];
+    assert!(accounts.fingerprint.normalized.contains("accounts"));
+    assert_eq!((accounts.requests, accounts.occurrences), (1, 10));
+    assert!((accounts.total_duration - 20.0).abs() < 1e-9);
+    assert_eq!(accounts.paths, ["/users"]);
+    assert_eq!(accounts.latest_request, 1);
+
+    let comments = &aggregated[1];
+    assert_eq!((comments.requests, comments.occurrences), (3, 12));
+    assert!((comments.total_duration - 12.0).abs() < 1e-9);
+    // Query strings dropped, each path once
+    assert_eq!(comments.paths, ["/posts", "/posts/1"]);
+    assert_eq!(comments.latest_request, 3);
+    assert!(comments.sample_query.contains(r#""comments"."id" = 0"#));
+}
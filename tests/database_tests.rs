@@ -1,12 +1,24 @@
-use caboose::database::{DatabaseHealth, IssueType};
+use caboose::database::schema::parse_schema;
+use std::time::{Duration, Instant};
+
+use caboose::database::{
+    DatabaseHealth, IssueType, SCORE_HISTORY_LEN, SCORE_SAMPLE_INTERVAL, ScoreHistory, ScoreRange,
+    ScoreSample, SqlDialect,
+};
+use caboose::diagnostics::{Limits, StoreKind};
+use caboose::search::SearchQuery;
+use caboose::time_format::DisplayZone;
+use chrono::{DateTime, Local, TimeZone, Utc};
 
 #[test]
 fn tracks_slow_queries_and_tables() {
     let db = DatabaseHealth::new();
     let q = r#"SELECT * FROM "users" WHERE "users"."id" = 1"#;
+    assert!(db.is_empty());
 
     db.analyze_query(q, 120.0);
     db.analyze_query(q, 130.0);
+    assert!(!db.is_empty());
 
     let slow = db.get_slow_queries();
     assert_eq!(slow.len(), 1);
@@ -18,6 +30,27 @@ fn tracks_slow_queries_and_tables() {
     assert_eq!(stats.select_star_count, 2);
 }
 
+#[test]
+fn groups_slow_queries_by_fingerprint() {
+    let db = DatabaseHealth::new();
+    db.analyze_query(
+        r#"SELECT * FROM "posts" WHERE "posts"."id" IN (1, 2)"#,
+        120.0,
+    );
+    db.analyze_query(
+        r#"SELECT * FROM "posts" WHERE "posts"."id" IN (3, 4, 5)"#,
+        180.0,
+    );
+    db.analyze_query(r#"SELECT * FROM "posts" WHERE "posts"."id" IN (6)"#, 110.0);
+
+    let slow = db.get_slow_queries();
+    assert_eq!(slow.len(), 1);
+    assert_eq!(slow[0].execution_count, 3);
+    assert_eq!(slow[0].duration, 180.0);
+    // The slowest instance is kept as the example
+    assert!(slow[0].query.contains("(3, 4, 5)"));
+}
+
 #[test]
 fn generates_issues_and_health_score() {
     let db = DatabaseHealth::new();
@@ -38,6 +71,76 @@ fn generates_issues_and_health_score() {
     assert!(score < 100);
 }
 
+#[test]
+fn sql_dialect_comes_from_the_adapter() {
+    assert_eq!(
+        SqlDialect::from_adapter(Some("postgresql")),
+        SqlDialect::Postgres
+    );
+    assert_eq!(SqlDialect::from_adapter(Some("mysql2")), SqlDialect::MySql);
+    assert_eq!(SqlDialect::from_adapter(Some("trilogy")), SqlDialect::MySql);
+    assert_eq!(
+        SqlDialect::from_adapter(Some("sqlite3")),
+        SqlDialect::Sqlite
+    );
+    assert_eq!(
+        SqlDialect::from_adapter(Some("oracle_enhanced")),
+        SqlDialect::Generic
+    );
+    assert_eq!(SqlDialect::from_adapter(None), SqlDialect::Generic);
+
+    assert_eq!(
+        SqlDialect::MySql.explain_statement("SELECT 1"),
+        "EXPLAIN FORMAT=TREE SELECT 1"
+    );
+    assert_eq!(
+        SqlDialect::Sqlite.explain_statement("SELECT 1"),
+        "EXPLAIN QUERY PLAN SELECT 1"
+    );
+    assert_eq!(
+        SqlDialect::Generic.explain_statement("SELECT 1"),
+        "EXPLAIN SELECT 1"
+    );
+}
+
+#[test]
+fn missing_index_migration_follows_the_dialect() {
+    let migration = |dialect| {
+        let db = DatabaseHealth::new().with_dialect(dialect);
+        for _ in 0..6 {
+            db.analyze_query(
+                "SELECT `line_items`.* FROM `line_items` WHERE `line_items`.`order_id` = 1",
+                120.0,
+            );
+        }
+        db.get_issues()
+            .iter()
+            .find(|i| i.issue_type == IssueType::MissingIndex)
+            .and_then(|i| i.migration_code.clone())
+            .expect("missing index issue")
+    };
+
+    let postgres = migration(SqlDialect::Postgres);
+    assert!(
+        postgres.contains("disable_ddl_transaction!"),
+        "{}",
+        postgres
+    );
+    assert!(postgres.contains("add_index :line_items, :order_id, algorithm: :concurrently"));
+
+    let mysql = migration(SqlDialect::MySql);
+    assert!(mysql.contains("add_index :line_items, :order_id, algorithm: :inplace"));
+    assert!(mysql.contains("rails g migration AddIndexToLineItems order_id:index"));
+
+    let sqlite = migration(SqlDialect::Sqlite);
+    assert!(
+        sqlite.contains("add_index :line_items, :order_id\n"),
+        "{}",
+        sqlite
+    );
+    assert!(!sqlite.contains("algorithm"));
+}
+
 #[test]
 fn perfect_health_when_no_issues() {
     let db = DatabaseHealth::new();
@@ -45,3 +148,424 @@ fn perfect_health_when_no_issues() {
     db.analyze_query("SELECT name FROM users", 10.0);
     assert_eq!(db.calculate_health_score(), 100);
 }
+
+#[test]
+fn select_star_issue_ignores_exists_and_count_queries() {
+    let db = DatabaseHealth::new();
+    db.analyze_query(r#"SELECT COUNT(*) FROM "users""#, 1.0);
+    db.analyze_query(
+        r#"SELECT 1 AS one FROM "users" WHERE EXISTS (SELECT * FROM "posts" WHERE "posts"."user_id" = "users"."id") LIMIT 1"#,
+        1.0,
+    );
+    db.analyze_query(r#"SELECT "users".* FROM "users""#, 1.0);
+    assert_eq!(db.get_stats().select_star_count, 0);
+
+    db.analyze_query("SELECT * FROM users", 1.0);
+    assert_eq!(db.get_stats().select_star_count, 1);
+}
+
+#[test]
+fn trims_slow_queries_and_tables() {
+    let db = DatabaseHealth::new();
+    for i in 0..5 {
+        db.analyze_query(&format!(r#"SELECT name FROM "table_{}""#, i), 150.0);
+    }
+    db.analyze_query(r#"SELECT name FROM "table_4""#, 200.0);
+
+    let usage = db.store_usage();
+    let slow = usage
+        .iter()
+        .find(|u| u.kind == StoreKind::SlowQueries)
+        .unwrap();
+    assert_eq!(slow.items, 5);
+    assert!(slow.bytes > 0);
+    assert_eq!(
+        db.approx_bytes(),
+        usage.iter().map(|u| u.bytes).sum::<usize>()
+    );
+
+    assert_eq!(db.trim(StoreKind::SlowQueries, 2), 3);
+    assert_eq!(db.get_slow_queries().len(), 2);
+
+    // Tables keep the most accessed entries
+    assert_eq!(db.trim(StoreKind::TablesAccessed, 1), 4);
+    let stats = db.get_stats();
+    assert_eq!(stats.tables_accessed.len(), 1);
+    assert_eq!(stats.tables_accessed.get("table_4"), Some(&2));
+
+    // Stores the analyzer doesn't own are left alone
+    assert_eq!(db.trim(StoreKind::LogBuffer, 0), 0);
+}
+
+#[test]
+fn slow_query_limit_applies_and_resizes() {
+    let db = DatabaseHealth::new().with_limits(Limits {
+        max_slow_queries: 3,
+        ..Limits::default()
+    });
+    for i in 0..5 {
+        db.analyze_query(&format!(r#"SELECT name FROM "table_{}""#, i), 150.0);
+    }
+    assert_eq!(db.get_slow_queries().len(), 3);
+
+    db.set_limits(Limits {
+        max_slow_queries: 1,
+        ..Limits::default()
+    });
+    let slow = db.get_slow_queries();
+    assert_eq!(slow.len(), 1);
+    assert!(slow[0].query.contains("table_4"));
+
+    let usage = db.store_usage();
+    let slow_usage = usage
+        .iter()
+        .find(|u| u.kind == StoreKind::SlowQueries)
+        .unwrap();
+    assert_eq!(slow_usage.limit, Some(1));
+}
+
+#[test]
+fn filters_slow_queries_by_sql_fragment() {
+    let db = DatabaseHealth::new();
+    db.analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 150.0);
+    db.analyze_query(r#"SELECT title FROM "posts" ORDER BY created_at"#, 300.0);
+
+    let matches = db.get_slow_queries_matching(&SearchQuery::new("order by"));
+    assert_eq!(matches.len(), 1);
+    assert!(matches[0].query.contains("posts"));
+
+    // An empty query keeps every slow query, slowest first
+    let all = db.get_slow_queries_matching(&SearchQuery::default());
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].duration, 300.0);
+}
+
+#[test]
+fn extracts_primary_table_name() {
+    let cases = [
+        (r#"SELECT * FROM "users" WHERE "users"."id" = 1"#, "users"),
+        (r#"SELECT * FROM "public"."users" WHERE id = 1"#, "users"),
+        ("SELECT * FROM public.users", "users"),
+        ("SELECT * FROM `shop`.`orders` LIMIT 1", "orders"),
+        (
+            "SELECT `users`.* FROM`users` WHERE `users`.`from` = 1",
+            "users",
+        ),
+        (
+            "SELECT * FROM `odd``name` WHERE `odd``name`.`id` = 1",
+            "odd`name",
+        ),
+        (r#"SELECT * FROM "odd""name" LIMIT 1"#, r#"odd"name"#),
+        (
+            "INSERT INTO `users` (`created_at`) VALUES ('it''s from x')",
+            "users",
+        ),
+        (
+            r#"SELECT "posts".* FROM "posts" INNER JOIN "comments" ON "comments"."post_id" = "posts"."id""#,
+            "posts",
+        ),
+        (
+            "select a.* from accounts a inner join users u on u.id = a.user_id",
+            "accounts",
+        ),
+        (r#"SELECT COUNT(*) FROM"users""#, "users"),
+        ("SELECT * FROM posts, comments", "posts"),
+        (
+            r#"UPDATE "users" SET "name" = 'x' WHERE "users"."id" = 1"#,
+            "users",
+        ),
+        (
+            r#"INSERT INTO "public"."events" ("name") VALUES ('from x')"#,
+            "events",
+        ),
+        (
+            r#"DELETE FROM "sessions" WHERE "sessions"."id" = 1"#,
+            "sessions",
+        ),
+        (
+            r#"SELECT EXTRACT(YEAR FROM "created_at") FROM "orders""#,
+            "orders",
+        ),
+        (
+            r#"SELECT COUNT(*) FROM (SELECT 1 FROM "posts" LIMIT 5) subquery"#,
+            "posts",
+        ),
+    ];
+    for (query, table) in cases {
+        assert_eq!(
+            DatabaseHealth::extract_table_name(query).as_deref(),
+            Some(table),
+            "{}",
+            query
+        );
+    }
+    assert_eq!(DatabaseHealth::extract_table_name("BEGIN"), None);
+}
+
+#[test]
+fn extracts_where_columns() {
+    let query = r#"SELECT * FROM "users" WHERE "users"."email" = $1 AND "users"."deleted_at" IS NULL AND status IN ('a', 'b') ORDER BY "users"."id" LIMIT 1"#;
+    assert_eq!(
+        DatabaseHealth::where_columns(query),
+        ["email", "deleted_at", "status"]
+    );
+    assert!(DatabaseHealth::where_columns(r#"SELECT * FROM "users""#).is_empty());
+}
+
+#[test]
+fn parses_indexes_from_schema_rb() {
+    let schema = parse_schema(
+        r#"
+ActiveRecord::Schema[7.1].define(version: 2024_01_01_000000) do
+  create_table "users", force: :cascade do |t|
+    t.string "email", null: false
+    t.string "status"
+    t.index ["email"], name: "index_users_on_email", unique: true
+    t.index ["status", "created_at"], name: "index_users_on_status_and_created_at"
+  end
+
+  create_table "tags", id: false, force: :cascade do |t|
+    t.string "name"
+  end
+
+  add_index "tags", "name", name: "index_tags_on_name"
+end
+"#,
+    );
+
+    let users = &schema["users"];
+    assert!(users.has_primary_key);
+    assert_eq!(users.indexes.len(), 2);
+    assert_eq!(users.indexes[0].name, "index_users_on_email");
+    assert!(users.indexes[0].is_unique);
+    assert_eq!(users.indexes[1].columns, ["status", "created_at"]);
+
+    let tags = &schema["tags"];
+    assert!(!tags.has_primary_key);
+    assert_eq!(tags.indexes[0].columns, ["name"]);
+}
+
+#[test]
+fn parses_columns_and_foreign_keys_from_schema_rb() {
+    let schema = parse_schema(
+        r#"
+ActiveRecord::Schema[7.1].define(version: 2024_01_01_000000) do
+  create_table "comments", force: :cascade do |t|
+    t.bigint "post_id", null: false
+    t.bigint "author_id"
+    t.text "body"
+    t.datetime "created_at", null: false
+    t.index ["post_id"], name: "index_comments_on_post_id"
+  end
+
+  add_foreign_key "comments", "posts"
+  add_foreign_key "comments", "public.users", column: "author_id"
+end
+"#,
+    );
+
+    let comments = &schema["comments"];
+    assert_eq!(
+        comments.columns,
+        ["post_id", "author_id", "body", "created_at"]
+    );
+    let keys: Vec<_> = comments
+        .foreign_keys
+        .iter()
+        .map(|fk| {
+            (
+                fk.column.as_str(),
+                fk.references_table.as_str(),
+                fk.has_index,
+            )
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        [("post_id", "posts", true), ("author_id", "users", false)]
+    );
+}
+
+#[test]
+fn table_detail_combines_queries_schema_and_issues() {
+    let schema = parse_schema(
+        r#"
+  create_table "users" do |t|
+    t.index ["email"], name: "index_users_on_email"
+  end
+"#,
+    );
+    let db = DatabaseHealth::new().with_schema(schema);
+    db.analyze_query(
+        r#"SELECT * FROM "public"."users" WHERE "users"."email" = 'a' AND "users"."status" = 'x'"#,
+        600.0,
+    );
+    db.analyze_query(
+        r#"SELECT * FROM "public"."users" WHERE "users"."email" = 'b' AND "users"."status" = 'y'"#,
+        200.0,
+    );
+    db.analyze_query(r#"SELECT * FROM "posts" WHERE "posts"."id" = 1"#, 150.0);
+
+    let detail = db.get_table_detail("users");
+    assert_eq!(detail.access_count, 2);
+    assert_eq!(detail.slow_queries.len(), 1);
+    assert_eq!(detail.executions(), 2);
+    assert_eq!(detail.total_slow_time(), 800.0);
+    assert_eq!(detail.avg_slow_time(), 400.0);
+    assert!(detail.in_schema);
+    let indexed: Vec<_> = detail
+        .filtered_columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.indexed))
+        .collect();
+    assert_eq!(indexed, [("email", Some(true)), ("status", Some(false))]);
+    assert!(
+        detail
+            .issues
+            .iter()
+            .any(|i| i.title.contains("on table 'users'"))
+    );
+
+    let posts = db.get_table_detail("posts");
+    assert!(!posts.in_schema);
+    assert_eq!(posts.filtered_columns[0].indexed, None);
+    assert!(posts.issues.is_empty());
+}
+
+#[test]
+fn records_health_score_history() {
+    let db = DatabaseHealth::new();
+    assert!(db.get_score_history().is_empty());
+
+    db.analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 1.0);
+    db.record_health_score();
+    for _ in 0..11 {
+        db.analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 120.0);
+    }
+    db.record_health_score();
+
+    let history = db.get_score_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0], 100.0);
+    assert!(history[1] < history[0]);
+
+    for _ in 0..SCORE_HISTORY_LEN {
+        db.record_health_score();
+    }
+    assert_eq!(db.get_score_history().len(), SCORE_HISTORY_LEN);
+}
+
+/// A sample `minutes` past 14:00 UTC
+fn sample(minutes: u32, score: u32, issues: usize, slow_queries: usize) -> ScoreSample {
+    let time: DateTime<Local> = Utc
+        .with_ymd_and_hms(2025, 3, 9, 14, minutes, 0)
+        .unwrap()
+        .into();
+    ScoreSample {
+        time,
+        score,
+        issues,
+        slow_queries,
+    }
+}
+
+#[test]
+fn score_history_keeps_the_latest_samples_in_its_ring_buffer() {
+    let mut history = ScoreHistory::new();
+    assert_eq!(history.range(), None);
+
+    let start = Instant::now();
+    for i in 0..SCORE_HISTORY_LEN + 5 {
+        let at = start + SCORE_SAMPLE_INTERVAL * i as u32;
+        history.record(sample(0, 100 - (i % 50) as u32, 0, 0), at);
+    }
+    assert_eq!(history.samples().count(), SCORE_HISTORY_LEN);
+    let scores = history.scores();
+    assert_eq!(scores.len(), SCORE_HISTORY_LEN);
+    // The first five were pushed out
+    assert_eq!(history.samples().next().unwrap().score, 95);
+    assert_eq!(scores[0], 95.0);
+    assert_eq!(
+        history.range(),
+        Some(ScoreRange {
+            min: 51,
+            max: 100,
+            current: 100 - ((SCORE_HISTORY_LEN + 4) % 50) as u32,
+        })
+    );
+
+    // After a long pause the trend starts over; the samples are kept
+    let later = start + SCORE_SAMPLE_INTERVAL * (3 * SCORE_HISTORY_LEN) as u32;
+    history.record(sample(1, 80, 0, 0), later);
+    assert_eq!(history.scores(), vec![80.0]);
+    assert_eq!(history.samples().count(), SCORE_HISTORY_LEN);
+}
+
+#[test]
+fn worst_score_drops_say_what_appeared() {
+    let mut history = ScoreHistory::new();
+    let start = Instant::now();
+    for (i, sample) in [
+        sample(30, 100, 0, 0),
+        sample(31, 96, 1, 0),
+        sample(32, 84, 2, 3),
+        sample(33, 90, 2, 3),
+        sample(34, 89, 2, 3),
+        sample(35, 85, 3, 4),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        history.record(sample, start + Duration::from_secs(60 * i as u64));
+    }
+
+    let drops = history.worst_drops(3);
+    let summary: Vec<(u32, u32, u32)> = drops
+        .iter()
+        .map(|drop| (drop.from, drop.to, drop.points()))
+        .collect();
+    // Equal drops: the latest first
+    assert_eq!(summary, vec![(96, 84, 12), (89, 85, 4), (100, 96, 4)]);
+    assert_eq!(
+        drops[0].describe(DisplayZone::Utc),
+        "Score dropped 12 points at 14:32:00 when 3 new slow queries appeared"
+    );
+    assert_eq!(
+        drops[1].describe(DisplayZone::Utc),
+        "Score dropped 4 points at 14:35:00 when 1 new slow query appeared"
+    );
+    assert_eq!(
+        drops[2].describe(DisplayZone::Utc),
+        "Score dropped 4 points at 14:31:00 when 1 new issue appeared"
+    );
+    assert_eq!(history.worst_drops(10).len(), 4);
+
+    let mut flat = ScoreHistory::new();
+    flat.record(sample(0, 90, 0, 0), start);
+    flat.record(sample(1, 95, 0, 0), start);
+    assert!(flat.worst_drops(3).is_empty());
+}
+
+#[test]
+fn recorded_scores_keep_the_issues_behind_them() {
+    let db = DatabaseHealth::new();
+    db.analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 1.0);
+    db.record_health_score();
+    for _ in 0..11 {
+        db.analyze_query(r#"SELECT name FROM "users" WHERE "users"."id" = 1"#, 120.0);
+    }
+    db.record_health_score();
+
+    let drops = db.get_score_drops(3);
+    assert_eq!(drops.len(), 1);
+    assert_eq!(drops[0].from, 100);
+    assert_eq!(drops[0].new_slow_queries, 1);
+    let range = db.get_score_range().unwrap();
+    assert_eq!((range.max, range.current), (100, drops[0].to));
+
+    // Saved and restored with the session
+    let restored = DatabaseHealth::new();
+    restored.restore(db.snapshot());
+    assert_eq!(restored.get_score_history(), db.get_score_history());
+    assert_eq!(restored.get_score_drops(3), drops);
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use caboose::disk::{
+    DiskLimits, DiskUsage, DiskWarning, LogFile, spawn_disk_watcher, truncate_log,
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("caboose_disk_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn sized_file(path: &PathBuf, size: u64) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::File::create(path).unwrap().set_len(size).unwrap();
+}
+
+#[test]
+fn scan_measures_logs_and_tmp_cache() {
+    let root = temp_dir("scan");
+    sized_file(&root.join("log/development.log"), 5_000);
+    sized_file(&root.join("log/test.log"), 300);
+    sized_file(&root.join("log/notes.txt"), 9_000);
+    sized_file(&root.join("tmp/cache/assets/a.cache"), 700);
+    sized_file(&root.join("tmp/cache/b.cache"), 50);
+
+    let usage = DiskUsage::scan(&root);
+    let logs: Vec<(&str, u64)> = usage
+        .log_files
+        .iter()
+        .map(|file| (file.name.as_str(), file.size))
+        .collect();
+    assert_eq!(
+        logs,
+        [("log/development.log", 5_000), ("log/test.log", 300)]
+    );
+    assert_eq!(usage.tmp_cache, Some(750));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn scan_of_a_project_without_log_or_tmp_is_empty() {
+    let root = temp_dir("empty");
+
+    let usage = DiskUsage::scan(&root);
+    assert!(usage.log_files.is_empty());
+    assert_eq!(usage.tmp_cache, None);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn warnings_list_large_logs_first_then_cache_and_space() {
+    let log = |name: &str, size| LogFile {
+        path: PathBuf::from(name),
+        name: name.to_string(),
+        size,
+    };
+    let usage = DiskUsage {
+        log_files: vec![
+            log("log/development.log", 2_000),
+            log("log/test.log", 10),
+            log("log/sidekiq.log", 9_000),
+        ],
+        tmp_cache: Some(1_500),
+        free_space: Some(100),
+    };
+    let limits = DiskLimits {
+        max_file_size: 1_000,
+        min_free_space: 500,
+    };
+
+    assert_eq!(
+        usage.warnings(limits),
+        [
+            DiskWarning::LargeLog(log("log/sidekiq.log", 9_000)),
+            DiskWarning::LargeLog(log("log/development.log", 2_000)),
+            DiskWarning::LargeCache { size: 1_500 },
+            DiskWarning::LowSpace { free: 100 },
+        ]
+    );
+    let roomy = DiskLimits {
+        max_file_size: 10_000,
+        min_free_space: 100,
+    };
+    assert!(usage.warnings(roomy).is_empty());
+}
+
+#[test]
+fn truncate_keeps_the_file_for_a_writer_that_has_it_open() {
+    let root = temp_dir("truncate");
+    let path = root.join("development.log");
+    fs::write(&path, "x".repeat(4096)).unwrap();
+
+    // Ruby's Logger opens its file for appending
+    let mut writer = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    assert_eq!(truncate_log(&path), Ok(4096));
+
+    writer.write_all(b"Started GET \"/\"\n").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "Started GET \"/\"\n");
+
+    let err = truncate_log(&root.join("missing.log")).unwrap_err();
+    assert!(err.contains("missing.log"), "{}", err);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn watcher_publishes_warnings_from_its_first_scan() {
+    let root = temp_dir("watch");
+    sized_file(&root.join("log/development.log"), 2_048);
+    let limits = DiskLimits {
+        max_file_size: 1_024,
+        min_free_space: 0,
+    };
+
+    let mut rx = spawn_disk_watcher(root.clone(), limits, Duration::from_secs(60));
+    tokio::time::timeout(Duration::from_secs(5), rx.changed())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let warnings = rx.borrow().clone();
+    assert!(matches!(
+        warnings.as_slice(),
+        [DiskWarning::LargeLog(file)] if file.name == "log/development.log" && file.size == 2_048
+    ));
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -1,11 +1,19 @@
-use caboose::exception::{ExceptionSeverity, ExceptionTracker};
+use caboose::diagnostics::{Limits, StoreKind};
+use caboose::exception::{
+    DEFAULT_SEEN_WITHIN, ExceptionFilter, ExceptionGrouping, ExceptionRate, ExceptionSeverity,
+    ExceptionTracker, RATE_MINUTES, project_location,
+};
+use caboose::search::SearchQuery;
+use std::time::{Duration, Instant};
 
 #[test]
 fn parses_exception_and_backtrace() {
     let tracker = ExceptionTracker::new();
+    assert!(tracker.is_empty());
     tracker.parse_line("NoMethodError: undefined method `foo' for nil:NilClass");
     tracker.parse_line("  app/models/user.rb:12:in `block in find'");
     tracker.parse_line("irrelevant line to end backtrace");
+    assert!(!tracker.is_empty());
 
     let stats = tracker.get_stats();
     assert_eq!(stats.total_exceptions, 1);
@@ -40,3 +48,457 @@ fn groups_similar_exceptions() {
     );
     assert!(tracker.get_exception_rate() >= 2.0);
 }
+
+#[test]
+fn trimming_samples_keeps_aggregate_stats() {
+    let tracker = ExceptionTracker::new();
+    for name in ["FooError", "BarError", "BazError"] {
+        tracker.parse_line(&format!("{}: something went wrong", name));
+        tracker.parse_line("  app/models/user.rb:12:in `find'");
+        tracker.parse_line("");
+    }
+
+    let before = tracker.approx_bytes();
+    assert!(before > 0);
+
+    assert_eq!(tracker.trim(StoreKind::Exceptions, 1), 2);
+    assert_eq!(tracker.trim(StoreKind::ExceptionGroups, 2), 1);
+    assert!(tracker.approx_bytes() < before);
+
+    assert_eq!(tracker.get_recent_exceptions(10).len(), 1);
+    assert_eq!(tracker.get_grouped_exceptions().len(), 2);
+    assert_eq!(tracker.get_stats().total_exceptions, 3);
+}
+
+#[test]
+fn filters_groups_by_type_or_message() {
+    let tracker = ExceptionTracker::new();
+    tracker.parse_line("ArgumentError: Couldn't find User with 'id'=42");
+    tracker.parse_line("");
+    tracker.parse_line("NoMethodError: undefined method `name' for nil");
+    tracker.parse_line("");
+
+    let by_type = tracker.get_grouped_exceptions_matching(&SearchQuery::new("nomethod"));
+    assert_eq!(by_type.len(), 1);
+    assert_eq!(by_type[0].exception_type, "NoMethodError");
+
+    let by_message = tracker.get_grouped_exceptions_matching(&SearchQuery::new("find user"));
+    assert_eq!(by_message.len(), 1);
+    assert_eq!(by_message[0].exception_type, "ArgumentError");
+
+    assert!(
+        tracker
+            .get_grouped_exceptions_matching(&SearchQuery::new("timeout"))
+            .is_empty()
+    );
+}
+
+fn raise(tracker: &ExceptionTracker, line: &str) {
+    tracker.parse_line(line);
+    tracker.parse_line("");
+}
+
+fn listed(tracker: &ExceptionTracker, filter: &ExceptionFilter, now: Instant) -> Vec<String> {
+    let mut types: Vec<String> = tracker
+        .get_grouped_exceptions_filtered(&SearchQuery::new(""), filter, now)
+        .into_iter()
+        .map(|group| group.exception_type)
+        .collect();
+    types.sort();
+    types
+}
+
+#[test]
+fn dismissed_groups_stay_hidden_until_they_happen_again() {
+    let tracker = ExceptionTracker::new();
+    raise(
+        &tracker,
+        r#"ActionController::RoutingError (No route matches [GET] "/favicon.ico"):"#,
+    );
+    let not_found = tracker.last_fingerprint().unwrap();
+    raise(&tracker, "NoMethodError (undefined method `name' for nil):");
+
+    assert!(tracker.dismiss(&not_found));
+    assert!(!tracker.dismiss("NoSuchError:nothing"));
+    let filter = ExceptionFilter::default();
+    assert_eq!(listed(&tracker, &filter, Instant::now()), ["NoMethodError"]);
+    assert_eq!(tracker.dismissed_count(), 1);
+    let with_dismissed = ExceptionFilter {
+        show_dismissed: true,
+        ..filter
+    };
+    assert_eq!(listed(&tracker, &with_dismissed, Instant::now()).len(), 2);
+
+    // Another NoMethodError doesn't bring it back; the next routing error does
+    raise(&tracker, "NoMethodError (undefined method `name' for nil):");
+    assert_eq!(listed(&tracker, &filter, Instant::now()), ["NoMethodError"]);
+    raise(
+        &tracker,
+        r#"ActionController::RoutingError (No route matches [GET] "/favicon.ico"):"#,
+    );
+    assert_eq!(
+        listed(&tracker, &filter, Instant::now()),
+        ["ActionController::RoutingError", "NoMethodError"]
+    );
+    assert_eq!(tracker.group_count(&not_found), Some(2));
+    assert_eq!(tracker.dismissed_count(), 0);
+
+    // Kept across a saved session
+    tracker.dismiss(&not_found);
+    let restored = ExceptionTracker::new();
+    restored.restore(
+        serde_json::from_str(&serde_json::to_string(&tracker.snapshot()).unwrap()).unwrap(),
+    );
+    assert_eq!(
+        listed(&restored, &filter, Instant::now()),
+        ["NoMethodError"]
+    );
+}
+
+fn raise_in(tracker: &ExceptionTracker, request: &str, line: &str) {
+    tracker.parse_line(line);
+    tracker.set_current_request_context(Some(request.to_string()), None);
+    tracker.parse_line("");
+}
+
+#[test]
+fn exception_endpoints_normalize_ids_in_the_path() {
+    let tracker = ExceptionTracker::new();
+    tracker.parse_line("NoMethodError (undefined method `total' for nil):");
+    tracker.set_current_request_context(
+        Some("PATCH /orders/123/items/9f8e7d6c-5b4a-4321-8fed-cba987654321?page=2".into()),
+        Some("ab12cd34".into()),
+    );
+    tracker.parse_line("");
+    let exception = &tracker.get_recent_exceptions(1)[0];
+    assert_eq!(exception.request_id.as_deref(), Some("ab12cd34"));
+    assert_eq!(
+        exception.endpoint().as_deref(),
+        Some("PATCH /orders/:id/items/:id")
+    );
+
+    // Between exceptions there's nothing to attribute
+    tracker.set_current_request_context(Some("GET /".into()), None);
+    assert_eq!(tracker.get_stats().total_exceptions, 1);
+}
+
+#[test]
+fn exception_groups_count_occurrences_per_endpoint() {
+    let tracker = ExceptionTracker::new();
+    for request in [
+        "POST /orders/14",
+        "POST /orders/15",
+        "GET /orders/15",
+        "POST /orders/16",
+    ] {
+        raise_in(
+            &tracker,
+            request,
+            "NoMethodError (undefined method `total' for nil):",
+        );
+    }
+    raise(
+        &tracker,
+        "NoMethodError (undefined method `total' for nil):",
+    );
+
+    let group = &tracker.get_grouped_exceptions()[0];
+    assert_eq!(group.count, 5);
+    assert_eq!(
+        group.top_endpoints(),
+        [("POST /orders/:id", 3), ("GET /orders/:id", 1)]
+    );
+
+    // Regrouping splits the tallies with the groups and merges them back
+    tracker.parse_line("NoMethodError (undefined method `total' for nil):");
+    tracker.parse_line("  app/controllers/invoices_controller.rb:9:in `show'");
+    tracker.set_current_request_context(Some("GET /invoices/3".into()), None);
+    tracker.parse_line("");
+    tracker.set_grouping(ExceptionGrouping {
+        with_location: true,
+        ..ExceptionGrouping::default()
+    });
+    let groups = tracker.get_grouped_exceptions();
+    let invoices = groups.iter().find(|g| g.location.is_some()).unwrap();
+    assert_eq!(invoices.top_endpoints(), [("GET /invoices/:id", 1)]);
+    let rest = groups.iter().find(|g| g.location.is_none()).unwrap();
+    assert_eq!(rest.endpoints.values().sum::<usize>(), 4);
+    tracker.set_grouping(ExceptionGrouping::default());
+    let group = &tracker.get_grouped_exceptions()[0];
+    assert_eq!(group.endpoints.values().sum::<usize>(), 5);
+    assert_eq!(group.top_endpoints()[0], ("POST /orders/:id", 3));
+}
+
+#[test]
+fn filter_narrows_by_severity_and_recency() {
+    let tracker = ExceptionTracker::new();
+    raise(&tracker, "SystemStackError: stack level too deep");
+    raise(&tracker, "NoMethodError: undefined method `name' for nil");
+    raise(&tracker, "ActionController::RoutingError: No route matches");
+    raise(&tracker, "Timeout::Error: execution expired");
+    let now = Instant::now();
+
+    let mut filter = ExceptionFilter::default();
+    assert_eq!(listed(&tracker, &filter, now).len(), 4);
+    let mut steps = Vec::new();
+    for _ in 0..4 {
+        filter.min_severity = filter.next_severity();
+        steps.push(listed(&tracker, &filter, now).len());
+    }
+    assert_eq!(steps, [3, 2, 1, 4]);
+
+    filter.min_severity = Some(ExceptionSeverity::High);
+    assert_eq!(filter.describe(), "high and up");
+    assert_eq!(
+        listed(&tracker, &filter, now),
+        ["NoMethodError", "SystemStackError"]
+    );
+
+    filter.min_severity = None;
+    filter.seen_within = Some(DEFAULT_SEEN_WITHIN);
+    assert_eq!(filter.describe(), "seen in the last 15m");
+    assert_eq!(listed(&tracker, &filter, now).len(), 4);
+    let later = now + DEFAULT_SEEN_WITHIN + Duration::from_secs(1);
+    assert!(listed(&tracker, &filter, later).is_empty());
+}
+
+#[test]
+fn exception_limit_applies_and_resizes() {
+    let tracker = ExceptionTracker::new().with_limits(Limits {
+        max_exceptions: 2,
+        ..Limits::default()
+    });
+    for name in ["FooError", "BarError", "BazError"] {
+        tracker.parse_line(&format!("{}: something went wrong", name));
+        tracker.parse_line("");
+    }
+    assert_eq!(tracker.get_recent_exceptions(10).len(), 2);
+
+    tracker.set_limits(Limits {
+        max_exceptions: 1,
+        ..Limits::default()
+    });
+    let recent = tracker.get_recent_exceptions(10);
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].exception_type, "BazError");
+    // Groups are capped separately and keep every type
+    assert_eq!(tracker.get_grouped_exceptions().len(), 3);
+}
+
+/// The same `NoMethodError` raised from two controllers, then from a gem only
+fn raise_not_found(tracker: &ExceptionTracker) {
+    for frames in [
+        &[
+            "/usr/local/bundle/gems/activerecord-7.1.3/lib/active_record/core.rb:253:in `find'",
+            "app/controllers/orders_controller.rb:14:in `show'",
+        ][..],
+        &["app/controllers/invoices_controller.rb:9:in `show'"][..],
+        &["app/controllers/orders_controller.rb:14:in `show'"][..],
+        &["/usr/local/bundle/gems/activerecord-7.1.3/lib/active_record/core.rb:253:in `find'"][..],
+    ] {
+        tracker.parse_line("NoMethodError (undefined method `total' for nil):");
+        for frame in frames {
+            tracker.parse_line(&format!("  {}", frame));
+        }
+        tracker.parse_line("Completed 404 Not Found in 5ms");
+    }
+}
+
+fn counts_by_location(tracker: &ExceptionTracker) -> Vec<(Option<String>, usize)> {
+    let mut counts: Vec<(Option<String>, usize)> = tracker
+        .get_grouped_exceptions()
+        .iter()
+        .map(|group| (group.location.clone(), group.count))
+        .collect();
+    counts.sort();
+    counts
+}
+
+fn by_location() -> ExceptionGrouping {
+    ExceptionGrouping {
+        with_location: true,
+        ..ExceptionGrouping::default()
+    }
+}
+
+#[test]
+fn exceptions_group_by_type_and_message_by_default() {
+    let tracker = ExceptionTracker::new();
+    raise_not_found(&tracker);
+    assert_eq!(counts_by_location(&tracker), [(None, 4)]);
+}
+
+#[test]
+fn grouping_by_location_splits_call_sites() {
+    let tracker = ExceptionTracker::new().with_grouping(by_location());
+    raise_not_found(&tracker);
+    assert_eq!(
+        counts_by_location(&tracker),
+        [
+            // No project frame: grouped as before
+            (None, 1),
+            (
+                Some("app/controllers/invoices_controller.rb:9".to_string()),
+                1
+            ),
+            (
+                Some("app/controllers/orders_controller.rb:14".to_string()),
+                2
+            ),
+        ]
+    );
+    assert_eq!(tracker.get_stats().unique_exceptions, 3);
+
+    let backtrace = vec![
+        "/usr/lib/ruby/3.3.0/set.rb:10:in `each'".to_string(),
+        "  lib/billing/charge.rb:31:in `call'".to_string(),
+    ];
+    assert_eq!(
+        project_location(&backtrace, false).as_deref(),
+        Some("lib/billing/charge.rb")
+    );
+}
+
+#[test]
+fn changing_grouping_regroups_what_was_seen() {
+    let tracker = ExceptionTracker::new();
+    raise_not_found(&tracker);
+    assert!(!tracker.set_grouping(ExceptionGrouping::default()));
+
+    // Split using the samples still kept
+    assert!(tracker.set_grouping(by_location()));
+    assert_eq!(counts_by_location(&tracker).len(), 3);
+    assert_eq!(tracker.get_stats().unique_exceptions, 3);
+    assert_eq!(
+        tracker
+            .get_grouped_exceptions()
+            .iter()
+            .map(|group| group.count)
+            .sum::<usize>(),
+        4
+    );
+
+    // New exceptions join the regrouped groups
+    tracker.parse_line("NoMethodError (undefined method `total' for nil):");
+    tracker.parse_line("  app/controllers/invoices_controller.rb:9:in `show'");
+    tracker.parse_line("done");
+    assert!(counts_by_location(&tracker).contains(&(
+        Some("app/controllers/invoices_controller.rb:9".to_string()),
+        2
+    )));
+
+    // And back: one group with every occurrence
+    assert!(tracker.set_grouping(ExceptionGrouping::default()));
+    assert_eq!(counts_by_location(&tracker), [(None, 5)]);
+    assert_eq!(tracker.get_stats().unique_exceptions, 1);
+}
+
+#[test]
+fn regrouping_keeps_counts_older_than_the_kept_samples() {
+    let tracker = ExceptionTracker::new().with_limits(Limits {
+        max_exceptions: 1,
+        ..Limits::default()
+    });
+    raise_not_found(&tracker);
+
+    // Only the last (gem-only) exception is kept; the three before it stay
+    // with the group's sample, the first one raised
+    tracker.set_grouping(by_location());
+    assert_eq!(
+        counts_by_location(&tracker),
+        [
+            (None, 1),
+            (
+                Some("app/controllers/orders_controller.rb:14".to_string()),
+                3
+            ),
+        ]
+    );
+}
+
+#[test]
+fn line_numbers_can_be_left_out_of_the_location() {
+    let tracker = ExceptionTracker::new().with_grouping(ExceptionGrouping {
+        with_location: true,
+        line_numbers: false,
+    });
+    for line in [3, 8] {
+        tracker.parse_line("NoMethodError: undefined method `total' for nil");
+        tracker.parse_line(&format!("  app/models/order.rb:{}:in `sum'", line));
+        tracker.parse_line("done");
+    }
+    assert_eq!(
+        counts_by_location(&tracker),
+        [(Some("app/models/order.rb".to_string()), 2)]
+    );
+}
+
+#[test]
+fn rate_weights_exceptions_by_severity() {
+    let origin = Instant::now();
+    let mut rate = ExceptionRate::new(origin);
+    let at = origin + Duration::from_secs(5);
+    rate.record(&ExceptionSeverity::Critical, at);
+    rate.record(&ExceptionSeverity::High, at);
+    rate.record(&ExceptionSeverity::Low, at);
+
+    assert_eq!(rate.per_minute(at), 8.0);
+    assert_eq!(rate.count_per_minute(at), 3.0);
+    assert_eq!(rate.history(at).last(), Some(&8));
+}
+
+#[test]
+fn rate_prorates_the_previous_minute() {
+    let origin = Instant::now();
+    let mut rate = ExceptionRate::new(origin);
+    rate.record(&ExceptionSeverity::High, origin + Duration::from_secs(30));
+    rate.record(&ExceptionSeverity::Medium, origin + Duration::from_secs(60));
+
+    // 15s into the second minute, three quarters of the first still count
+    let now = origin + Duration::from_secs(75);
+    assert_eq!(rate.per_minute(now), 1.0 + 2.0 * 0.75);
+    assert_eq!(rate.history(now)[RATE_MINUTES - 2..], [2, 1]);
+
+    // Two minutes on, neither bucket is in the last minute
+    assert_eq!(rate.per_minute(origin + Duration::from_secs(180)), 0.0);
+}
+
+#[test]
+fn rate_history_rolls_over_after_ten_minutes() {
+    let origin = Instant::now();
+    let mut rate = ExceptionRate::new(origin);
+    rate.record(&ExceptionSeverity::Critical, origin);
+    rate.record(
+        &ExceptionSeverity::Low,
+        origin + Duration::from_secs(4 * 60),
+    );
+
+    let now = origin + Duration::from_secs(9 * 60);
+    let history = rate.history(now);
+    assert_eq!(history.len(), RATE_MINUTES);
+    assert_eq!(history[0], 5);
+    assert_eq!(history[4], 1);
+
+    let later = origin + Duration::from_secs(11 * 60);
+    rate.record(&ExceptionSeverity::High, later);
+    let history = rate.history(later);
+    assert_eq!(history.iter().sum::<u32>(), 1 + 2);
+    assert_eq!(history[RATE_MINUTES - 1], 2);
+    assert_eq!(history[RATE_MINUTES - 1 - 7], 1);
+}
+
+#[test]
+fn tracker_counts_every_exception_towards_the_rate() {
+    let tracker = ExceptionTracker::new();
+    tracker.parse_line("SystemStackError: stack level too deep");
+    tracker.parse_line("NoMethodError: undefined method `foo' for nil:NilClass");
+    tracker.parse_line("NoMethodError: undefined method `foo' for nil:NilClass");
+    tracker.parse_line("irrelevant line to end backtrace");
+
+    let now = Instant::now();
+    assert_eq!(tracker.get_exception_rate(), 3.0);
+    assert_eq!(tracker.weighted_rate_at(now), 5.0 + 2.0 + 2.0);
+
+    tracker.clear_stats();
+    assert_eq!(tracker.weighted_rate_at(Instant::now()), 0.0);
+}
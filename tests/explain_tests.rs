@@ -1,3 +1,4 @@
+use caboose::database::SqlDialect;
 use caboose::explain::{ExplainExecutor, WarningSeverity};
 
 #[test]
@@ -12,3 +13,161 @@ fn explain_executor_simulates_plan_with_warnings() {
     let severities: Vec<_> = plan.warnings.iter().map(|w| w.severity.clone()).collect();
     assert!(severities.contains(&WarningSeverity::Warning));
 }
+
+fn messages(plan: &caboose::explain::ExplainPlan) -> Vec<(WarningSeverity, String)> {
+    plan.warnings
+        .iter()
+        .map(|w| (w.severity.clone(), w.message.clone()))
+        .collect()
+}
+
+#[test]
+fn postgres_plan_reads_cost_range_and_rows() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::Postgres);
+    let plan = exec.parse_plan(
+        "Sort  (cost=1520.34..1545.34 rows=10000 width=40)\n  \
+         Sort Key: created_at\n  \
+         ->  Seq Scan on orders  (cost=0.00..180.00 rows=10000 width=40)",
+    );
+
+    assert_eq!(plan.cost, Some(1545.34));
+    assert_eq!(plan.rows, Some(10000));
+    assert!(plan.has_seq_scan());
+    assert_eq!(
+        messages(&plan),
+        [
+            (
+                WarningSeverity::Warning,
+                "Sequential scan detected - consider adding an index".to_string()
+            ),
+            (
+                WarningSeverity::Critical,
+                "Very high query cost: 1545.34".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn mysql_tree_plan_flags_table_scans_temporary_tables_and_filesorts() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::MySql);
+    assert_eq!(
+        exec.explain_statement("SELECT * FROM orders"),
+        "EXPLAIN FORMAT=TREE SELECT * FROM orders"
+    );
+
+    let plan = exec.parse_plan(
+        "-> Sort: orders.total DESC  (cost=2150.50 rows=20000)\n    \
+         -> Table scan on <temporary>\n        \
+         -> Aggregate using temporary table\n            \
+         -> Table scan on orders  (cost=2150.50 rows=20000)",
+    );
+
+    assert_eq!(plan.cost, Some(2150.5));
+    assert_eq!(plan.rows, Some(20000));
+    assert!(plan.has_seq_scan());
+    assert!(!plan.has_index_scan());
+    assert_eq!(
+        messages(&plan),
+        [
+            (
+                WarningSeverity::Warning,
+                "Full table scan on orders - consider adding an index".to_string()
+            ),
+            (
+                WarningSeverity::Warning,
+                "Temporary table used - GROUP BY or DISTINCT can't use an index".to_string()
+            ),
+            (
+                WarningSeverity::Info,
+                "Filesort - ORDER BY isn't served by an index".to_string()
+            ),
+            (
+                WarningSeverity::Critical,
+                "Very high query cost: 2150.50".to_string()
+            ),
+            (
+                WarningSeverity::Warning,
+                "Large result set estimated: 20000 rows".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn mysql_index_lookup_has_no_warnings() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::MySql);
+    let plan = exec.parse_plan(
+        "-> Index lookup on users using index_users_on_email (email='a@b.c')  (cost=0.35 rows=1)",
+    );
+
+    assert_eq!(plan.cost, Some(0.35));
+    assert_eq!(plan.rows, Some(1));
+    assert!(plan.has_index_scan());
+    assert!(!plan.has_seq_scan());
+    assert!(plan.warnings.is_empty());
+    assert!(plan.suggest_indexes().is_empty());
+}
+
+#[test]
+fn sqlite_query_plan_flags_scans_automatic_indexes_and_temp_btrees() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::Sqlite);
+    assert_eq!(
+        exec.explain_statement("SELECT * FROM posts"),
+        "EXPLAIN QUERY PLAN SELECT * FROM posts"
+    );
+
+    let plan = exec.parse_plan(
+        "QUERY PLAN\n\
+         |--SCAN posts\n\
+         |--SEARCH comments USING AUTOMATIC COVERING INDEX (post_id=?)\n\
+         `--USE TEMP B-TREE FOR ORDER BY",
+    );
+
+    assert_eq!(plan.cost, None);
+    assert_eq!(plan.rows, None);
+    assert!(plan.has_seq_scan());
+    assert_eq!(
+        messages(&plan),
+        [
+            (
+                WarningSeverity::Warning,
+                "Full table scan on posts - consider adding an index".to_string()
+            ),
+            (
+                WarningSeverity::Warning,
+                "Automatic index built on comments for this query - add a permanent one"
+                    .to_string()
+            ),
+            (
+                WarningSeverity::Info,
+                "Temporary B-tree for ORDER BY - no index covers it".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn sqlite_reads_older_row_format_and_index_searches() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::Sqlite);
+    let plan = exec.parse_plan(
+        "0|0|0|SEARCH TABLE users USING INDEX index_users_on_email (email=?)\n\
+         0|1|1|SCAN TABLE roles USING COVERING INDEX index_roles_on_name",
+    );
+
+    assert!(plan.has_index_scan());
+    assert!(!plan.has_seq_scan());
+    assert!(plan.warnings.is_empty());
+}
+
+#[test]
+fn unknown_dialect_falls_back_to_generic_reading() {
+    let exec = ExplainExecutor::new(None).with_dialect(SqlDialect::from_adapter(Some("sqlserver")));
+    let plan =
+        exec.parse_plan("Index Scan using users_pkey on users  (cost=0.29..8.30 rows=1 width=40)");
+
+    assert_eq!(plan.dialect, SqlDialect::Generic);
+    assert_eq!(plan.cost, Some(8.3));
+    assert!(plan.has_index_scan());
+    assert!(plan.warnings.is_empty());
+}
@@ -1,7 +1,12 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use caboose::frontend::{FrontendApp, FrontendFramework, PackageManager};
+use caboose::frontend::{
+    BuildChange, BuildErrors, BuildState, BuildTool, FrontendApp, FrontendFramework,
+    FrontendLogEvent, FrontendLogParser, FrontendTracker, PackageManager, ScanCap, ScanLimits,
+    scan_package_dirs,
+};
+use std::time::{Duration, Instant};
 
 fn temp_dir(name: &str) -> PathBuf {
     let mut dir = std::env::temp_dir();
@@ -48,3 +53,320 @@ fn package_manager_detection_defaults_to_npm() {
 
     let _ = fs::remove_dir_all(root);
 }
+
+/// A Vite app in `dir`, created along with its parents
+fn vite_app(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("package.json"), r#"{"name":"demo"}"#).unwrap();
+    fs::write(dir.join("vite.config.ts"), "export default {}").unwrap();
+}
+
+#[test]
+fn detection_skips_vendored_and_ignored_packages() {
+    let root = temp_dir("vendored");
+    // Each of these would be picked up if searched
+    vite_app(&root.join("node_modules/some-plugin"));
+    vite_app(&root.join("vendor/assets/widget"));
+    vite_app(&root.join("tmp/extracted"));
+    vite_app(&root.join("web"));
+    vite_app(&root.join(".yarn/cache/pkg"));
+    vite_app(&root.join("apps/shop"));
+    fs::write(root.join(".gitignore"), "/web\n").unwrap();
+
+    let (app, trace) = FrontendApp::detect_traced(&root, None, &ScanLimits::default());
+    assert!(app.detected, "{:?}", trace);
+    assert_eq!(app.path, "apps/shop");
+    assert!(
+        trace
+            .steps
+            .contains(&"web: ignored by .gitignore".to_string()),
+        "{:?}",
+        trace.steps
+    );
+    assert_eq!(trace.capped, None);
+
+    let (dirs, _) = scan_package_dirs(&root, &ScanLimits::default());
+    assert_eq!(dirs, vec![PathBuf::from("apps/shop")]);
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn detection_search_stops_at_its_depth_entry_and_time_caps() {
+    let root = temp_dir("caps");
+    // Too deep to be searched
+    let mut deep = root.join("packages");
+    for level in 0..8 {
+        deep = deep.join(format!("level{}", level));
+    }
+    vite_app(&deep);
+    // Wide enough to run past a small entry cap
+    for i in 0..200 {
+        fs::create_dir_all(root.join("docs").join(format!("page{}", i))).unwrap();
+    }
+
+    let (app, trace) = FrontendApp::detect_traced(&root, None, &ScanLimits::default());
+    assert!(!app.detected);
+    assert_eq!(trace.capped, None);
+
+    let limits = ScanLimits {
+        max_entries: 50,
+        ..ScanLimits::default()
+    };
+    let (_, trace) = scan_package_dirs(&root, &limits);
+    assert_eq!(trace.visited, 50);
+    assert_eq!(trace.capped, Some(ScanCap::Entries(50)));
+    assert!(trace.capped.unwrap().warning().contains("[frontend] path"),);
+
+    let limits = ScanLimits {
+        time_budget: Duration::ZERO,
+        ..ScanLimits::default()
+    };
+    let (_, trace) = scan_package_dirs(&root, &limits);
+    assert_eq!(trace.capped, Some(ScanCap::Time(Duration::ZERO)));
+
+    // Deep enough once the depth cap allows it
+    let limits = ScanLimits {
+        max_depth: 10,
+        ..ScanLimits::default()
+    };
+    let (app, _) = FrontendApp::detect_traced(&root, None, &limits);
+    assert!(app.detected);
+
+    let _ = fs::remove_dir_all(root);
+}
+
+/// Replay `tests/fixtures/build_errors/<name>.log` as `frontend`'s output
+fn replay_build(name: &str) -> BuildErrors {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/build_errors")
+        .join(format!("{}.log", name));
+    let mut errors = BuildErrors::new();
+    for line in fs::read_to_string(path).unwrap().lines() {
+        let event = FrontendLogParser::parse_line(line);
+        errors.observe("frontend", line, event.as_ref());
+    }
+    errors
+}
+
+#[test]
+fn parses_vite_dev_server_errors_with_their_code_frame() {
+    let errors = replay_build("vite_dev");
+    let failure = errors.latest().unwrap();
+    assert_eq!(failure.errors.len(), 1);
+    let error = &failure.errors[0];
+    assert_eq!(error.tool, BuildTool::Vite);
+    assert_eq!(
+        error.location().as_deref(),
+        Some("/home/dev/shop/frontend/src/App.tsx:12:8")
+    );
+    assert_eq!(error.message, r#"Expected ";" but found "x""#);
+    assert_eq!(error.code_frame.len(), 6);
+    assert_eq!(error.code_frame[2], "  12 |    let y x");
+    assert!(!failure.complete);
+}
+
+#[test]
+fn parses_esbuild_errors_until_the_build_fails() {
+    let errors = replay_build("vite_build");
+    let failure = errors.latest().unwrap();
+    assert!(failure.complete);
+    // esbuild repeats the error after "error during build:"; it isn't a second one
+    assert_eq!(failure.errors.len(), 1);
+    let error = &failure.errors[0];
+    assert_eq!(error.tool, BuildTool::Esbuild);
+    assert_eq!(error.location().as_deref(), Some("src/App.tsx:12:8"));
+    assert_eq!(error.message, r#"Expected ";" but found "x""#);
+    assert_eq!(
+        error.code_frame,
+        vec![
+            "      12 │   let y x",
+            "         │         ^",
+            "         ╵         ;"
+        ]
+    );
+}
+
+#[test]
+fn parses_every_webpack_error_of_a_build() {
+    let errors = replay_build("webpack");
+    let failure = errors.latest().unwrap();
+    assert!(failure.complete);
+    assert_eq!(failure.errors.len(), 2);
+
+    let parse = &failure.errors[0];
+    assert_eq!(parse.location().as_deref(), Some("src/App.jsx:12:8"));
+    assert_eq!(
+        parse.message,
+        "Module parse failed: Unexpected token (12:8)"
+    );
+    assert_eq!(parse.code_frame.len(), 5);
+    assert_eq!(parse.code_frame[2], ">   let y x");
+
+    let types = &failure.errors[1];
+    assert_eq!(
+        types.location().as_deref(),
+        Some("/home/dev/shop/frontend/src/api.ts:7:3")
+    );
+    assert_eq!(
+        types.message,
+        "TS2322: Type 'string' is not assignable to type 'number'."
+    );
+    assert_eq!(types.code_frame.len(), 5);
+}
+
+#[test]
+fn parses_tsc_watch_errors_in_both_location_styles() {
+    let errors = replay_build("tsc_watch");
+    let failure = errors.latest().unwrap();
+    assert!(failure.complete);
+    assert_eq!(failure.errors.len(), 2);
+    assert_eq!(
+        failure.errors[0].location().as_deref(),
+        Some("src/api.ts:7:3")
+    );
+    assert_eq!(
+        failure.errors[0].code_frame,
+        vec!["7   return value;", "    ~~~~~~~~~~~~~"]
+    );
+    assert_eq!(
+        failure.errors[1].location().as_deref(),
+        Some("src/App.tsx:12:8")
+    );
+    assert_eq!(failure.errors[1].message, "TS1005: ';' expected.");
+}
+
+#[test]
+fn a_successful_compile_clears_the_failure() {
+    let mut errors = replay_build("tsc_watch");
+    let line = "[4:13:02 PM] Found 0 errors. Watching for file changes.";
+    let event = FrontendLogParser::parse_line(line);
+    assert!(matches!(
+        event,
+        Some(FrontendLogEvent::CompileSuccess { .. })
+    ));
+    // Another process compiling says nothing about this one
+    assert_eq!(errors.observe("admin", line, event.as_ref()), None);
+    assert_eq!(
+        errors.observe("frontend", line, event.as_ref()),
+        Some(BuildChange::Fixed)
+    );
+    assert!(errors.latest().is_none());
+
+    let mut errors = replay_build("vite_dev");
+    let line = "4:12:30 PM [vite] hmr update /src/App.tsx";
+    let event = FrontendLogParser::parse_line(line);
+    assert_eq!(
+        errors.observe("frontend", line, event.as_ref()),
+        Some(BuildChange::Fixed)
+    );
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn an_error_after_a_failed_build_starts_a_new_failure() {
+    let mut errors = replay_build("tsc_watch");
+    let line = "src/App.tsx:3:1 - error TS2304: Cannot find name 'foo'.";
+    let event = FrontendLogParser::parse_line(line);
+    assert_eq!(
+        errors.observe("frontend", line, event.as_ref()),
+        Some(BuildChange::Failed)
+    );
+    assert_eq!(errors.latest().unwrap().errors.len(), 1);
+}
+
+/// Replay `tests/fixtures/frontend/<name>.log` into a tracker, a second apart
+fn replay_dev_server(name: &str, start: Instant) -> (FrontendTracker, Instant) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/frontend")
+        .join(format!("{}.log", name));
+    let mut tracker = FrontendTracker::new("frontend");
+    let mut now = start;
+    for line in fs::read_to_string(path).unwrap().lines() {
+        observe(&mut tracker, line, now);
+        now += Duration::from_secs(1);
+    }
+    (tracker, now)
+}
+
+fn observe(tracker: &mut FrontendTracker, line: &str, now: Instant) {
+    tracker.observe(line, FrontendLogParser::parse_line(line).as_ref(), now);
+}
+
+#[test]
+fn tracks_a_vite_dev_server() {
+    let start = Instant::now();
+    let (mut tracker, now) = replay_dev_server("vite_dev", start);
+    assert_eq!(tracker.tool(), "vite");
+    assert_eq!(tracker.port(), Some(5173));
+    assert_eq!(tracker.url(), Some("http://localhost:5173/"));
+    assert_eq!(tracker.last_build_time(), Some(Duration::from_millis(312)));
+    assert_eq!(tracker.hot_updates_per_minute(now), 2);
+    assert_eq!(tracker.last_hot_update(), Some("/src/components/Cart.tsx"));
+
+    // The esbuild line under vite's error is part of it, not a second error
+    assert_eq!(tracker.state(), BuildState::Failed);
+    assert_eq!(tracker.errors(), ["Transform failed with 1 error:"]);
+    assert_eq!(tracker.summary(), "vite ✘ build failed");
+
+    // Hot-replacing the fix clears it; updates age out after a minute
+    observe(
+        &mut tracker,
+        "4:12:30 PM [vite] hmr update /src/App.tsx",
+        now,
+    );
+    assert_eq!(tracker.summary(), "vite ✓ 312ms");
+    assert_eq!(tracker.hot_updates_per_minute(now), 3);
+    assert_eq!(
+        tracker.hot_updates_per_minute(now + Duration::from_secs(61)),
+        0
+    );
+    assert_eq!(tracker.errors().len(), 1);
+}
+
+#[test]
+fn tracks_a_next_dev_server() {
+    let (mut tracker, now) = replay_dev_server("next_dev", Instant::now());
+    assert_eq!(tracker.tool(), "next");
+    assert_eq!(tracker.port(), Some(3000));
+    assert_eq!(tracker.url(), Some("http://localhost:3000"));
+    assert_eq!(tracker.last_build_time(), Some(Duration::from_millis(214)));
+    assert_eq!(tracker.hot_updates_per_minute(now), 0);
+
+    // Failed while compiling /orders/[id]
+    assert_eq!(tracker.state(), BuildState::Failed);
+    assert_eq!(tracker.errors(), ["./src/app/orders/[id]/page.tsx:3:1"]);
+
+    observe(
+        &mut tracker,
+        " ✓ Compiled /orders/[id] in 390ms (530 modules)",
+        now,
+    );
+    assert_eq!(tracker.summary(), "next ✓ 390ms");
+    // (2300 + 1840 + 214 + 390) / 4
+    assert_eq!(
+        tracker.average_build_time(),
+        Some(Duration::from_millis(1186))
+    );
+
+    // An error the app raises between compiles isn't a failed build
+    observe(&mut tracker, " ⨯ Error: Order 7 not found", now);
+    assert_eq!(tracker.state(), BuildState::Succeeded);
+    assert_eq!(tracker.errors().len(), 1);
+}
+
+#[test]
+fn build_durations_are_read_in_milliseconds_or_seconds() {
+    for (line, ms) in [
+        ("  VITE v5.0.10  ready in 312 ms", 312.0),
+        (" ✓ Compiled /items in 80ms (120 modules)", 80.0),
+        ("✓ built in 1.23s", 1230.0),
+    ] {
+        let Some(FrontendLogEvent::CompileSuccess { duration }) =
+            FrontendLogParser::parse_line(line)
+        else {
+            panic!("{}", line);
+        };
+        assert!((duration - ms).abs() < 1e-6, "{}: {}", line, duration);
+    }
+}
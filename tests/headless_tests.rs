@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use caboose::headless::{
+    EXIT_DETECTION_FAILED, EXIT_HEALTH_FAILED, EXIT_SETUP_FAILED, LogOutput, StartupFailure,
+    StartupReport, StartupStatus,
+};
+use caboose::process::{LogLine, LogStream, ProcessExit};
+use caboose::rails::RailsHealthIssue;
+use serde_json::Value;
+
+/// A copy of a fixture project (runs write `.caboose/` into it) plus a home
+/// directory, so recent projects aren't recorded in the real one
+struct Sandbox {
+    root: PathBuf,
+}
+
+impl Sandbox {
+    fn new(name: &str) -> Self {
+        let root =
+            std::env::temp_dir().join(format!("caboose_headless_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("project")).unwrap();
+        fs::create_dir_all(root.join("home")).unwrap();
+        Self { root }
+    }
+
+    fn from_fixture(name: &str, fixture: &str) -> Self {
+        let sandbox = Self::new(name);
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/projects")
+            .join(fixture);
+        for entry in fs::read_dir(source).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), sandbox.project().join(entry.file_name())).unwrap();
+        }
+        sandbox
+    }
+
+    fn project(&self) -> PathBuf {
+        self.root.join("project")
+    }
+
+    fn caboose(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_caboose"));
+        command
+            .arg("--project")
+            .arg(self.project())
+            .args(args)
+            .env("HOME", self.root.join("home"))
+            .env("XDG_DATA_HOME", self.root.join("home"))
+            .env_remove("CABOOSE_PROJECT");
+        command
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        self.caboose(args).output().unwrap()
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn json_lines(output: &Output) -> Vec<Value> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {:?}", e, line)))
+        .collect()
+}
+
+#[test]
+fn startup_json_describes_processes_then_logs_stream_as_events() {
+    let sandbox = Sandbox::from_fixture("running", "headless");
+    let output = sandbox.run(&["--no-tui", "--startup-json", "--json-logs"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+
+    let lines = json_lines(&output);
+    let report = &lines[0];
+    assert_eq!(report["schema"], 1);
+    assert_eq!(report["status"], "running");
+    assert_eq!(report["rails"], Value::Null);
+    assert_eq!(report["frontend"], Value::Null);
+    assert_eq!(report["health"], serde_json::json!([]));
+    assert_eq!(report["error"], Value::Null);
+
+    let processes = report["processes"].as_array().unwrap();
+    assert_eq!(processes.len(), 2);
+    assert_eq!(processes[0]["name"], "hello");
+    assert_eq!(processes[0]["command"], "echo hello from headless");
+    assert_eq!(processes[0]["port"], Value::Null);
+    assert_eq!(processes[1]["name"], "web");
+    assert_eq!(processes[1]["port"], 4000);
+    // Env names are listed, values never are
+    assert_eq!(
+        processes[0]["env"],
+        serde_json::json!(["RAILS_ENV", "SECRET_KEY_BASE"])
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("not-for-output"));
+
+    let events = &lines[1..];
+    let logs: Vec<&Value> = events.iter().filter(|e| e["event"] == "log").collect();
+    assert!(
+        logs.iter()
+            .any(|e| e["process"] == "hello" && e["content"] == "hello from headless"),
+        "{:?}",
+        logs
+    );
+    for log in &logs {
+        assert!(
+            log["stream"].is_string() && log["time"].is_string(),
+            "{}",
+            log
+        );
+    }
+    let web_exit = events
+        .iter()
+        .find(|e| e["event"] == "exit" && e["process"] == "web")
+        .expect("an exit event for web");
+    assert_eq!(web_exit["code"], 3);
+    assert_eq!(web_exit["success"], false);
+
+    // The human startup messages went to stderr
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Starting 2 processes"));
+}
+
+#[test]
+fn text_output_prefixes_lines_with_the_process() {
+    let sandbox = Sandbox::from_fixture("text", "headless");
+    let output = sandbox.run(&["--no-tui"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Starting 2 processes"), "{}", stdout);
+    assert!(
+        stdout.contains("hello   | hello from headless"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("caboose | web exited with code 3"),
+        "{}",
+        stdout
+    );
+}
+
+/// Startup, from launch to the report, with a long Procfile
+const MANY_PROCESSES_STARTUP: Duration = Duration::from_secs(3);
+
+#[test]
+fn many_processes_start_quickly_and_one_broken_entry_doesnt_stop_the_rest() {
+    let sandbox = Sandbox::new("many");
+    let mut procfile: String = (0..10).map(|i| format!("worker{}: sleep 1\n", i)).collect();
+    procfile.push_str("broken: caboose-no-such-program --serve\n");
+    fs::write(sandbox.project().join("Procfile"), procfile).unwrap();
+
+    let started = Instant::now();
+    let mut child = sandbox
+        .caboose(&["--no-tui", "--startup-json", "--json-logs"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut first = String::new();
+    stdout.read_line(&mut first).unwrap();
+    let elapsed = started.elapsed();
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    let status = child.wait().unwrap();
+
+    assert!(
+        elapsed < MANY_PROCESSES_STARTUP,
+        "startup took {:?}",
+        elapsed
+    );
+    assert_eq!(status.code(), Some(0));
+
+    let report: Value = serde_json::from_str(&first).unwrap();
+    let processes = report["processes"].as_array().unwrap();
+    assert_eq!(processes.len(), 11);
+    let broken = processes.iter().find(|p| p["name"] == "broken").unwrap();
+    assert!(broken["error"].is_string(), "{}", broken);
+    assert!(
+        processes
+            .iter()
+            .filter(|p| p["name"] != "broken")
+            .all(|p| p["error"].is_null())
+    );
+
+    let events: Vec<Value> = rest
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let exits = events.iter().filter(|e| e["event"] == "exit").count();
+    assert_eq!(exits, 10, "{:?}", events);
+    assert!(events.iter().any(|e| {
+        e["process"] == "broken"
+            && e["content"]
+                .as_str()
+                .unwrap()
+                .starts_with("Failed to start")
+    }));
+}
+
+#[test]
+fn nothing_detected_exits_with_code_2() {
+    let sandbox = Sandbox::new("empty");
+    let output = sandbox.run(&["--no-tui", "--startup-json"]);
+    assert_eq!(output.status.code(), Some(EXIT_DETECTION_FAILED));
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["status"], "detection_failed");
+    assert_eq!(lines[0]["processes"], serde_json::json!([]));
+    assert!(lines[0]["error"].as_str().unwrap().contains("No Procfile"));
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_bundle_check_exits_with_code_3() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new("health");
+    let project = sandbox.project();
+    fs::create_dir_all(project.join("config")).unwrap();
+    fs::write(project.join("Gemfile"), "gem 'rails'\ngem 'pg'\n").unwrap();
+    fs::write(project.join("config/application.rb"), "module App end\n").unwrap();
+    fs::write(
+        project.join("config/database.yml"),
+        "development:\n  adapter: postgresql\n",
+    )
+    .unwrap();
+
+    // A `bundle` that always reports missing gems
+    let bin = sandbox.root.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let bundle = bin.join("bundle");
+    fs::write(
+        &bundle,
+        "#!/bin/sh\necho 'Could not find rails-7.1.3 in locally installed gems' >&2\nexit 1\n",
+    )
+    .unwrap();
+    fs::set_permissions(&bundle, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let output = sandbox
+        .caboose(&["--no-tui", "--startup-json"])
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(EXIT_HEALTH_FAILED),
+        "{:?}",
+        output
+    );
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    let report = &lines[0];
+    assert_eq!(report["status"], "health_failed");
+    assert_eq!(report["rails"]["database"], "postgresql");
+    assert_eq!(report["health"][0]["kind"], "bundle_outdated");
+    assert_eq!(report["health"][0]["fatal"], true);
+    assert_eq!(
+        report["health"][0]["message"],
+        "Could not find rails-7.1.3 in locally installed gems"
+    );
+}
+
+#[test]
+fn two_processes_on_one_port_fail_before_either_starts() {
+    let sandbox = Sandbox::new("ports");
+    fs::write(
+        sandbox.project().join("Procfile"),
+        "web: bin/rails server\nfrontend: cd frontend && npx vite --port 3000\n",
+    )
+    .unwrap();
+
+    let output = sandbox.run(&["--no-tui", "--startup-json"]);
+    assert_eq!(
+        output.status.code(),
+        Some(EXIT_HEALTH_FAILED),
+        "{:?}",
+        output
+    );
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1, "nothing should have started: {:?}", lines);
+    assert_eq!(lines[0]["status"], "health_failed");
+    assert_eq!(lines[0]["processes"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        lines[0]["error"],
+        "Port 3000 is claimed by 'web' (the rails server default) and 'frontend' (`--port 3000` in its command)"
+    );
+}
+
+#[test]
+fn piped_stdout_without_no_tui_falls_back_to_plain_logs_and_leaves_nothing_running() {
+    let sandbox = Sandbox::new("piped");
+    fs::write(
+        sandbox.project().join("Procfile"),
+        "web: sh -c 'echo $$ > web.pid; echo serving; sleep 1'\n",
+    )
+    .unwrap();
+
+    // No --no-tui, but stdout is a pipe and TERM is dumb
+    let output = sandbox.caboose(&[]).env("TERM", "dumb").output().unwrap();
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Can't show the TUI: stdout isn't a terminal")
+            && stderr.contains("--no-tui"),
+        "{}",
+        stderr
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("web     | serving"), "{}", stdout);
+
+    let pid = fs::read_to_string(sandbox.project().join("web.pid")).unwrap();
+    let alive = Command::new("kill")
+        .args(["-0", pid.trim()])
+        .stderr(Stdio::null())
+        .status()
+        .unwrap()
+        .success();
+    assert!(!alive, "web (pid {}) is still running", pid.trim());
+}
+
+#[test]
+fn statuses_map_to_stable_exit_codes() {
+    assert_eq!(StartupStatus::Running.exit_code(), 0);
+    assert_eq!(StartupStatus::DetectionFailed.exit_code(), 2);
+    assert_eq!(StartupStatus::HealthFailed.exit_code(), 3);
+    assert_eq!(StartupStatus::SetupFailed.exit_code(), 4);
+
+    let failure = StartupFailure::health("bundle install needed");
+    let report = StartupReport::new("/srv/app")
+        .with_health(&[
+            RailsHealthIssue::PendingMigrations(vec!["down 20240101 Add users".into()]),
+            RailsHealthIssue::BundleOutdated("gems missing\nmore detail".into()),
+        ])
+        .failed(&failure);
+    let json: Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["status"], "health_failed");
+    assert_eq!(json["error"], "bundle install needed");
+    assert_eq!(
+        json["health"],
+        serde_json::json!([
+            {"kind": "pending_migrations", "message": "1 pending migration(s)", "fatal": false},
+            {"kind": "bundle_outdated", "message": "gems missing", "fatal": true},
+        ])
+    );
+}
+
+#[test]
+fn processes_report_their_port() {
+    let mut report = StartupReport::new("/srv/app");
+    let env = HashMap::from([("PORT".to_string(), "5000".to_string())]);
+    report.add_process("web", "bundle exec rails server", &HashMap::new());
+    report.add_process("api", "bin/api", &env);
+    let ports: Vec<Option<u16>> = report.processes.iter().map(|p| p.port).collect();
+    assert_eq!(ports, [Some(3000), Some(5000)]);
+    assert_eq!(report.processes[1].env, ["PORT"]);
+}
+
+fn log_line(process: &str, content: &str) -> LogLine {
+    LogLine {
+        stream: LogStream::Stderr,
+        ..LogLine::new(process, content)
+    }
+}
+
+#[test]
+fn log_output_formats() {
+    let text = LogOutput::text(["web", "worker"]);
+    assert_eq!(
+        text.log_line(&log_line("web", "Listening")),
+        "web    | Listening"
+    );
+
+    let json: Value =
+        serde_json::from_str(&LogOutput::Json.log_line(&log_line("web", "Listening"))).unwrap();
+    assert_eq!(json["event"], "log");
+    assert_eq!(json["stream"], "stderr");
+    assert_eq!(json["content"], "Listening");
+
+    let exit = ProcessExit {
+        success: false,
+        code: None,
+        at: Instant::now(),
+    };
+    assert_eq!(
+        LogOutput::text(["caboose"]).process_exit("worker", Some(&exit)),
+        "caboose | worker was killed"
+    );
+    let json: Value =
+        serde_json::from_str(&LogOutput::Json.process_exit("worker", Some(&exit))).unwrap();
+    assert_eq!(json["code"], Value::Null);
+    assert_eq!(json["success"], false);
+}
+
+#[test]
+fn setup_runs_before_processes_and_a_failure_stops_startup() {
+    let sandbox = Sandbox::from_fixture("setup", "headless");
+    let project = sandbox.project();
+    fs::write(
+        project.join(".caboose.toml"),
+        r#"
+[setup]
+commands = [
+  "echo preparing the database",
+  { command = "sh -c 'echo installed > installs'", unless_file = "tmp/setup-done" },
+]
+"#,
+    )
+    .unwrap();
+
+    let output = sandbox.run(&["--no-tui", "--startup-json"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("preparing the database"), "{}", stderr);
+    assert!(project.join("tmp/setup-done").exists());
+    fs::remove_file(project.join("installs")).unwrap();
+
+    // The stamp skips the second command from now on
+    let output = sandbox.run(&["--no-tui", "--startup-json"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert!(!project.join("installs").exists());
+
+    fs::write(
+        project.join(".caboose.toml"),
+        "[setup]\ncommands = [\"sh -c 'echo no database; exit 1'\"]\n",
+    )
+    .unwrap();
+    let output = sandbox.run(&["--no-tui", "--startup-json"]);
+    assert_eq!(
+        output.status.code(),
+        Some(EXIT_SETUP_FAILED),
+        "{:?}",
+        output
+    );
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1, "nothing should have started: {:?}", lines);
+    assert_eq!(lines[0]["status"], "setup_failed");
+    assert!(
+        lines[0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("exited with code 1")
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no database"), "{}", stderr);
+
+    // --skip-setup starts the processes anyway
+    let output = sandbox.run(&["--no-tui", "--startup-json", "--skip-setup"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fs;
+
+use caboose::context::RequestContextTracker;
+use caboose::parser::{
+    AgentError, BulletWarning, HttpRequest, InstrumentationEvent, InstrumentationGems,
+    InstrumentationParser, LogEvent, SqlQuery,
+};
+use caboose::query::NPlusOneSource;
+use caboose::rails::RailsApp;
+
+/// Bullet's report as `Bullet.rails_logger` writes it to log/development.log
+const BULLET_REPORT: &str = "\
+W, [2024-05-14T10:30:45.123456 #4242]  WARN -- : user: alice
+GET /posts?page=2
+USE eager loading detected
+  Post => [:comments, :author]
+  Add to your query: .includes([:comments, :author])
+Call stack
+  /app/app/views/posts/index.html.erb:5:in `block in _app_views_posts_index_html_erb'
+  /app/app/controllers/posts_controller.rb:4:in `index'";
+
+const MINI_PROFILER_LINE: &str = "I, [2024-05-14T10:30:45.200000 #4242]  INFO -- : [MiniProfiler] GET /posts?page=2 total: 182.3ms sql: 41.2ms (12 queries) render: 96.0ms";
+
+const SCOUT_ERRORS: &str = "\
+[2024-05-14T10:30:40 +0000 web.1 (4242)] INFO : Scout Agent v5.3.8 Initialized
+[2024-05-14T10:30:40 +0000 web.1 (4242)] ERROR : [Scout] Invalid key: agent will not report (ScoutApm::Config key is missing)
+[2024-05-14T10:31:40 +0000 web.1 (4242)] ERROR : [Scout] Invalid key: agent will not report (ScoutApm::Config key is missing)";
+
+const SKYLIGHT_ERROR: &str = "[SKYLIGHT] [6.0.4] Unable to start Instrumenter due to a configuration error: authentication token required";
+
+fn only(gems: InstrumentationGems) -> InstrumentationParser {
+    InstrumentationParser::new(gems)
+}
+
+fn events(parser: &mut InstrumentationParser, lines: &str) -> Vec<InstrumentationEvent> {
+    lines
+        .lines()
+        .filter_map(|line| parser.parse_line(line))
+        .collect()
+}
+
+fn bullet() -> InstrumentationGems {
+    InstrumentationGems {
+        bullet: true,
+        ..InstrumentationGems::default()
+    }
+}
+
+#[test]
+fn gems_are_read_from_the_gemfile() {
+    let gems = InstrumentationGems::from_gemfile(
+        "source 'https://rubygems.org'\n\
+         gem \"rails\", \"~> 7.1\"\n\
+         gem 'scout_apm'\n\
+         # gem 'skylight'\n\
+         group :development do\n  gem 'bullet'\n  gem \"rack-mini-profiler\", require: false\nend\n",
+    );
+    assert_eq!(
+        gems,
+        InstrumentationGems {
+            bullet: true,
+            rack_mini_profiler: true,
+            scout_apm: true,
+            skylight: false,
+        }
+    );
+    assert_eq!(gems.names(), ["bullet", "rack-mini-profiler", "scout_apm"]);
+    // Gems whose names start the same don't count
+    assert!(InstrumentationGems::from_gemfile("gem 'bullet_train'\n").is_empty());
+}
+
+#[test]
+fn rails_detection_records_instrumentation_gems() {
+    let root = std::env::temp_dir().join(format!("caboose_instrumentation_{}", std::process::id()));
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::write(root.join("Gemfile"), "gem 'rails'\ngem 'skylight'\n").unwrap();
+    fs::write(root.join("config/application.rb"), "module App end").unwrap();
+
+    let app = RailsApp::detect_in_path(&root);
+    assert!(app.instrumentation.skylight);
+    assert!(!app.instrumentation.bullet);
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn bullet_reports_become_warnings() {
+    let mut parser = only(bullet());
+    assert_eq!(
+        events(&mut parser, BULLET_REPORT),
+        vec![InstrumentationEvent::Bullet(BulletWarning {
+            method: Some("GET".to_string()),
+            path: Some("/posts?page=2".to_string()),
+            model: "Post".to_string(),
+            associations: vec!["comments".to_string(), "author".to_string()],
+        })]
+    );
+
+    // Unused eager loading isn't an N+1
+    let avoid = "GET /posts\nAVOID eager loading detected\n  Post => [:tags]\n";
+    assert!(events(&mut parser, avoid).is_empty());
+
+    // Skipped when the gem isn't in the Gemfile
+    assert!(events(&mut only(InstrumentationGems::default()), BULLET_REPORT).is_empty());
+}
+
+fn tracker_with_comment_queries() -> RequestContextTracker {
+    let tracker = RequestContextTracker::new();
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: "GET".into(),
+        path: "/posts?page=2".into(),
+        status: None,
+        duration: None,
+        controller: None,
+        action: None,
+        extra: HashMap::new(),
+    }));
+    for id in 1..=3 {
+        tracker.process_log_event(&LogEvent::SqlQuery(SqlQuery {
+            query: format!(
+                r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = {}"#,
+                id
+            ),
+            duration: Some(1.5),
+            rows: None,
+            name: Some("Comment Load".into()),
+            binds: Vec::new(),
+            truncated: None,
+            request_id: None,
+        }));
+    }
+    tracker.process_log_event(&LogEvent::HttpRequest(HttpRequest {
+        method: String::new(),
+        path: String::new(),
+        status: Some(200),
+        duration: Some(30.0),
+        controller: None,
+        action: None,
+        extra: HashMap::new(),
+    }));
+    tracker
+}
+
+#[test]
+fn bullet_warnings_supersede_heuristic_n_plus_ones() {
+    let tracker = tracker_with_comment_queries();
+    let before = &tracker.get_recent_requests()[0].n_plus_one_issues;
+    assert_eq!(before.len(), 1);
+    assert_eq!(before[0].source, NPlusOneSource::Heuristic);
+
+    let mut parser = only(bullet());
+    for event in events(&mut parser, BULLET_REPORT) {
+        let InstrumentationEvent::Bullet(warning) = event else {
+            panic!("expected a Bullet warning");
+        };
+        assert!(tracker.record_bullet_warning(&warning));
+    }
+
+    let issues = &tracker.get_recent_requests()[0].n_plus_one_issues;
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].source, NPlusOneSource::Bullet);
+    // The repeated query is kept from the heuristic's grouping
+    assert_eq!(issues[0].count, 3);
+    assert!(
+        issues[0]
+            .suggestion
+            .contains(".includes([:comments, :author])"),
+        "{}",
+        issues[0].suggestion
+    );
+
+    // A report for a request that never completed has nowhere to go
+    let stray = BulletWarning {
+        method: Some("GET".to_string()),
+        path: Some("/elsewhere".to_string()),
+        model: "User".to_string(),
+        associations: vec!["roles".to_string()],
+    };
+    assert!(!tracker.record_bullet_warning(&stray));
+}
+
+#[test]
+fn mini_profiler_timings_enrich_the_request() {
+    let profiler = InstrumentationGems {
+        rack_mini_profiler: true,
+        ..InstrumentationGems::default()
+    };
+    let mut parser = only(profiler);
+    let Some(InstrumentationEvent::Profiler(timings)) = parser.parse_line(MINI_PROFILER_LINE)
+    else {
+        panic!("expected profiler timings");
+    };
+    assert_eq!(timings.path.as_deref(), Some("/posts?page=2"));
+    assert_eq!(timings.timings.get("sql"), Some(&41.2));
+    assert_eq!(timings.timings.len(), 3);
+
+    let tracker = tracker_with_comment_queries();
+    assert!(tracker.record_profiler_timings(&timings));
+    let extra = &tracker.get_recent_requests()[0].extra;
+    assert_eq!(
+        extra.get("profiler_total").map(String::as_str),
+        Some("182.3")
+    );
+    assert_eq!(
+        extra.get("profiler_render").map(String::as_str),
+        Some("96.0")
+    );
+
+    assert!(
+        only(InstrumentationGems::default())
+            .parse_line(MINI_PROFILER_LINE)
+            .is_none()
+    );
+}
+
+#[test]
+fn agent_errors_warn_once_per_gem() {
+    let apm = InstrumentationGems {
+        scout_apm: true,
+        skylight: true,
+        ..InstrumentationGems::default()
+    };
+    let mut parser = only(apm);
+    let warnings = events(
+        &mut parser,
+        &format!("{}\n{}", SCOUT_ERRORS, SKYLIGHT_ERROR),
+    );
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+
+    let InstrumentationEvent::AgentError(AgentError { gem, message }) = &warnings[0] else {
+        panic!("expected an agent error");
+    };
+    assert_eq!(*gem, "scout_apm");
+    assert!(message.contains("Invalid key"), "{}", message);
+
+    let InstrumentationEvent::AgentError(skylight) = &warnings[1] else {
+        panic!("expected an agent error");
+    };
+    assert_eq!(skylight.gem, "skylight");
+    assert!(skylight.warning().starts_with("skylight: Unable to start"));
+
+    // Without the gems, the same lines are left alone
+    assert!(events(&mut only(InstrumentationGems::default()), SCOUT_ERRORS).is_empty());
+    assert!(events(&mut only(bullet()), SKYLIGHT_ERROR).is_empty());
+}
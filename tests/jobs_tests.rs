@@ -0,0 +1,176 @@
+use std::time::Instant;
+
+use caboose::jobs::{JobEventKind, JobSource, JobTracker, parse_job_line};
+
+fn feed(tracker: &JobTracker, lines: &[&str]) {
+    for line in lines {
+        tracker.parse_line(line);
+    }
+}
+
+#[test]
+fn parses_active_job_lines() {
+    let event = parse_job_line(
+        "[ActiveJob] [ReportJob] [5f2c9a1e-0b7d-4c55-9e21-6d8f0a4b2c10] Performing ReportJob (Job ID: 5f2c9a1e-0b7d-4c55-9e21-6d8f0a4b2c10) from Sidekiq(reports) enqueued at 2024-01-15T10:30:45.123Z with arguments: 42",
+    )
+    .unwrap();
+    assert_eq!(event.kind, JobEventKind::Started);
+    assert_eq!(event.source, JobSource::ActiveJob);
+    assert_eq!(event.class.as_deref(), Some("ReportJob"));
+    assert_eq!(
+        event.job_id.as_deref(),
+        Some("5f2c9a1e-0b7d-4c55-9e21-6d8f0a4b2c10")
+    );
+    assert_eq!(event.queue.as_deref(), Some("reports"));
+
+    let event = parse_job_line(
+        "I, [2024-01-15T10:30:45.275 #123]  INFO -- : [ActiveJob] [ReportJob] [abc] Performed ReportJob (Job ID: abc) from Async(default) in 152.3ms",
+    )
+    .unwrap();
+    assert_eq!(event.kind, JobEventKind::Performed);
+    assert_eq!(event.duration_ms, Some(152.3));
+
+    let event = parse_job_line(
+        "[ActiveJob] [ReportJob] [abc] Error performing ReportJob (Job ID: abc) from Async(default) in 12.5ms: RuntimeError (report failed):",
+    )
+    .unwrap();
+    assert_eq!(event.kind, JobEventKind::Failed);
+    assert_eq!(event.duration_ms, Some(12.5));
+    assert_eq!(event.error.as_deref(), Some("RuntimeError: report failed"));
+
+    let event = parse_job_line(
+        "[ActiveJob] [ReportJob] [abc] Retrying ReportJob (Job ID: abc) after 1 attempts in 3 seconds, due to a RuntimeError (report failed).",
+    )
+    .unwrap();
+    assert_eq!(event.kind, JobEventKind::Retrying);
+    assert_eq!(event.error.as_deref(), Some("RuntimeError: report failed"));
+
+    assert!(parse_job_line(r#"Started GET "/jobs" for 127.0.0.1"#).is_none());
+    assert!(parse_job_line("Performing maintenance on the cache").is_none());
+}
+
+#[test]
+fn active_job_runs_are_counted_per_class() {
+    let tracker = JobTracker::new();
+    assert!(tracker.is_empty());
+    feed(
+        &tracker,
+        &[
+            "[ActiveJob] [ReportJob] [a1] Performing ReportJob (Job ID: a1) from Async(reports)",
+            "[ActiveJob] [ReportJob] [a1] Performed ReportJob (Job ID: a1) from Async(reports) in 100.0ms",
+            "[ActiveJob] [ReportJob] [a2] Performing ReportJob (Job ID: a2) from Async(reports)",
+            "[ActiveJob] [ReportJob] [a2] Error performing ReportJob (Job ID: a2) from Async(reports) in 50.0ms: RuntimeError (report failed):",
+            "[ActiveJob] [ReportJob] [a2] Retrying ReportJob (Job ID: a2) after 1 attempts in 3 seconds, due to a RuntimeError (report failed).",
+            "[ActiveJob] [MailerJob] [m1] Performing MailerJob (Job ID: m1) from Async(mailers)",
+        ],
+    );
+
+    let classes = tracker.get_class_stats();
+    assert_eq!(classes[0].class, "ReportJob");
+    assert_eq!(classes[0].queue.as_deref(), Some("reports"));
+    assert_eq!((classes[0].runs, classes[0].failures), (2, 1));
+    assert_eq!(classes[0].retries, 1);
+    assert_eq!(classes[0].avg_duration_ms(), Some(75.0));
+    assert_eq!(
+        classes[0].last_error.as_deref(),
+        Some("RuntimeError: report failed")
+    );
+    assert_eq!(classes[1].class, "MailerJob");
+    assert_eq!(classes[1].runs, 0);
+    assert_eq!(tracker.running(), 1);
+
+    let failures = tracker.recent_failures(10);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].job_id.as_deref(), Some("a2"));
+}
+
+#[test]
+fn sidekiq_runs_failures_and_retries_are_counted() {
+    let tracker = JobTracker::new();
+    feed(
+        &tracker,
+        &[
+            "2024-01-15T10:30:45.123Z pid=12345 tid=2xz class=HardWorker jid=b4a577edbccf1d805744efa9 INFO: start",
+            "2024-01-15T10:30:45.275Z pid=12345 tid=2xz class=HardWorker jid=b4a577edbccf1d805744efa9 elapsed=0.152 INFO: done",
+            "2024-01-15T10:30:46.000Z pid=12345 tid=2y1 class=HardWorker jid=c0ffee00c0ffee00c0ffee00 INFO: start",
+            "2024-01-15T10:30:46.010Z pid=12345 tid=2y1 class=HardWorker jid=c0ffee00c0ffee00c0ffee00 elapsed=0.01 INFO: fail",
+            r#"2024-01-15T10:30:46.011Z pid=12345 tid=2y1 WARN: {"context":"Job raised exception","job":{"retry":true,"queue":"default","class":"HardWorker","args":[1],"jid":"c0ffee00c0ffee00c0ffee00"}}"#,
+            "2024-01-15T10:30:46.011Z pid=12345 tid=2y1 WARN: ActiveRecord::RecordNotFound: Couldn't find User with 'id'=1",
+            "2024-01-15T10:30:46.012Z pid=12345 tid=2y1 WARN: /app/app/workers/hard_worker.rb:5:in `perform'",
+            // Sidekiq retries it under the same jid
+            "2024-01-15T10:31:10.000Z pid=12345 tid=2y2 class=HardWorker jid=c0ffee00c0ffee00c0ffee00 INFO: start",
+            "2024-01-15T10:31:10.050Z pid=12345 tid=2y2 class=HardWorker jid=c0ffee00c0ffee00c0ffee00 elapsed=0.05 INFO: done",
+        ],
+    );
+
+    let classes = tracker.get_class_stats();
+    assert_eq!(classes.len(), 1);
+    let stats = &classes[0];
+    assert_eq!((stats.runs, stats.failures, stats.retries), (3, 1, 1));
+    assert!((stats.avg_duration_ms().unwrap() - 70.666).abs() < 0.01);
+    let error = "ActiveRecord::RecordNotFound: Couldn't find User with 'id'=1";
+    assert_eq!(stats.last_error.as_deref(), Some(error));
+    assert_eq!(tracker.recent_failures(10)[0].error.as_deref(), Some(error));
+    assert_eq!(tracker.running(), 0);
+
+    // A warning with no failure before it isn't a job line
+    assert!(
+        !tracker.parse_line("2024-01-15T10:32:00.000Z pid=12345 tid=2y3 WARN: Redis: reconnecting")
+    );
+}
+
+#[test]
+fn sidekiq_5_lines_and_error_payloads_are_understood() {
+    let tracker = JobTracker::new();
+    feed(
+        &tracker,
+        &[
+            "2018-01-15T10:30:45.123Z 12345 TID-ouy7z HardWorker JID-b4a577edbccf1d805744efa9 INFO: start",
+            "2018-01-15T10:30:45.275Z 12345 TID-ouy7z HardWorker JID-b4a577edbccf1d805744efa9 INFO: fail: 0.152 sec",
+            r#"2018-01-15T10:30:45.276Z 12345 TID-ouy7z WARN: {"context":"Job raised exception","job":{"class":"HardWorker","jid":"b4a577edbccf1d805744efa9","error_message":"boom","error_class":"RuntimeError","retry_count":0}}"#,
+        ],
+    );
+
+    let stats = &tracker.get_class_stats()[0];
+    assert_eq!(stats.class, "HardWorker");
+    assert_eq!((stats.runs, stats.failures), (1, 1));
+    assert_eq!(stats.avg_duration_ms(), Some(152.0));
+    assert_eq!(stats.last_error.as_deref(), Some("RuntimeError: boom"));
+}
+
+#[test]
+fn active_job_run_by_sidekiq_counts_once() {
+    let tracker = JobTracker::new();
+    feed(
+        &tracker,
+        &[
+            "2024-01-15T10:30:45.100Z pid=1 tid=a class=ReportJob jid=aaa111 INFO: start",
+            "[ActiveJob] [ReportJob] [r1] Performing ReportJob (Job ID: r1) from Sidekiq(default)",
+            "[ActiveJob] [ReportJob] [r1] Performed ReportJob (Job ID: r1) from Sidekiq(default) in 40.0ms",
+            "2024-01-15T10:30:45.150Z pid=1 tid=a class=ReportJob jid=aaa111 elapsed=0.05 INFO: done",
+        ],
+    );
+
+    let stats = &tracker.get_class_stats()[0];
+    assert_eq!(stats.runs, 1);
+    assert_eq!(stats.avg_duration_ms(), Some(40.0));
+    assert_eq!(tracker.running(), 0);
+}
+
+#[test]
+fn recent_failures_are_newest_first_and_bounded() {
+    let tracker = JobTracker::new();
+    for i in 0..60 {
+        tracker.parse_line(&format!(
+            "[ActiveJob] [SyncJob] [s{i}] Error performing SyncJob (Job ID: s{i}) from Async(default) in 1.0ms: Timeout::Error (attempt {i}):"
+        ));
+    }
+    let failures = tracker.recent_failures(100);
+    assert_eq!(failures.len(), caboose::jobs::MAX_RECENT_FAILURES);
+    assert_eq!(
+        failures[0].error.as_deref(),
+        Some("Timeout::Error: attempt 59")
+    );
+    assert!(failures[0].at <= Instant::now());
+    assert_eq!(tracker.get_class_stats()[0].failures, 60);
+}
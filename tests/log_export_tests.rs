@@ -0,0 +1,173 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+
+use caboose::log_export::{JsonLogRecord, ParsedLine, export_json, write_json_lines};
+use caboose::privacy::Privacy;
+use caboose::process::{LogCategory, LogLevel, LogLine, LogStream};
+use caboose::time_format::DisplayZone;
+use chrono::{Local, TimeZone, Utc};
+
+fn log_line(process: &str, content: &str) -> LogLine {
+    LogLine {
+        time: Utc
+            .with_ymd_and_hms(2025, 3, 9, 14, 32, 5)
+            .unwrap()
+            .with_timezone(&Local),
+        stream: LogStream::Stdout,
+        level: LogLevel::detect(content, LogCategory::Other),
+        ..LogLine::new(process, content)
+    }
+}
+
+fn export(logs: &[LogLine], privacy: &Privacy) -> Vec<String> {
+    let mut out = Vec::new();
+    let written = write_json_lines(&mut out, logs, DisplayZone::Utc, privacy).unwrap();
+    assert_eq!(written, logs.len());
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.ends_with('\n'));
+    text.lines().map(String::from).collect()
+}
+
+#[test]
+fn lines_round_trip_with_their_parsed_requests_and_queries() {
+    let logs = [
+        log_line(
+            "web",
+            r#"Started GET "/users/42" for 127.0.0.1 at 2025-03-09 14:32:05"#,
+        ),
+        log_line(
+            "web",
+            r#"  User Load (1.2ms)  SELECT "users".* FROM "users" WHERE "users"."id" = 42"#,
+        ),
+        log_line(
+            "web",
+            "Completed 200 OK in 52ms (Views: 40.1ms | ActiveRecord: 9.5ms)",
+        ),
+        log_line("worker", "WARN: Redis is slow"),
+    ];
+    let lines = export(&logs, &Privacy::default());
+    let records: Vec<JsonLogRecord> = lines
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(records[0].process, "web");
+    assert_eq!(records[0].time.to_rfc3339(), "2025-03-09T14:32:05+00:00");
+    assert_eq!(records[0].content, logs[0].content);
+    assert_eq!(
+        records[0].parsed,
+        Some(ParsedLine::HttpRequest {
+            method: Some("GET".to_string()),
+            path: Some("/users/42".to_string()),
+            status: None,
+            duration_ms: None,
+        })
+    );
+    assert_eq!(
+        records[1].parsed,
+        Some(ParsedLine::Sql {
+            name: Some("User Load".to_string()),
+            duration_ms: Some(1.2),
+            // Fingerprinted the way Query Analysis groups it
+            fingerprint: r#"user load (?) select "users".* from "users" where "users"."id" = ?"#
+                .to_string(),
+        })
+    );
+    assert_eq!(
+        records[2].parsed,
+        Some(ParsedLine::HttpRequest {
+            method: None,
+            path: None,
+            status: Some(200),
+            duration_ms: Some(52.0),
+        })
+    );
+    assert_eq!(records[3].process, "worker");
+    assert_eq!(records[3].level, Some(LogLevel::Warn));
+    assert_eq!(records[3].parsed, None);
+
+    // Writing the records back gives the same lines
+    for (record, line) in records.iter().zip(&lines) {
+        assert_eq!(&serde_json::to_string(record).unwrap(), line);
+    }
+}
+
+#[test]
+fn the_schema_keeps_to_its_field_names() {
+    let mut logs = [
+        log_line("web", "Completed 500 Internal Server Error in 12ms"),
+        log_line(
+            "web",
+            r#"  Post Count (0.4ms)  SELECT COUNT(*) FROM "posts""#,
+        ),
+        log_line("frontend", "ready in 312 ms"),
+    ];
+    logs[0].level = Some(LogLevel::Error);
+    let values: Vec<serde_json::Value> = export(&logs, &Privacy::default())
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let keys = |value: &serde_json::Value| -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    assert_eq!(
+        keys(&values[0]),
+        ["content", "level", "parsed", "process", "time"]
+    );
+    assert_eq!(values[0]["level"], "error");
+    assert_eq!(
+        values[0]["parsed"],
+        serde_json::json!({"type": "http_request", "status": 500, "duration_ms": 12.0})
+    );
+    assert_eq!(values[1]["parsed"]["type"], "sql");
+    assert_eq!(values[1]["parsed"]["duration_ms"], 0.4);
+    // No level and nothing parsed: the fields are left out
+    assert_eq!(keys(&values[2]), ["content", "process", "time"]);
+}
+
+#[test]
+fn privacy_mode_masks_the_exported_content() {
+    let logs = [log_line(
+        "web",
+        r#"Started GET "/users/jane@example.com" for 127.0.0.1"#,
+    )];
+    let record: JsonLogRecord =
+        serde_json::from_str(&export(&logs, &Privacy::new(true, Vec::new()))[0]).unwrap();
+    assert!(
+        !record.content.contains("jane@example.com"),
+        "{}",
+        record.content
+    );
+    let Some(ParsedLine::HttpRequest { path, .. }) = record.parsed else {
+        panic!("not a request: {:?}", record.parsed);
+    };
+    assert!(!path.unwrap().contains("jane@example.com"));
+}
+
+#[test]
+fn large_exports_stream_to_the_file() {
+    let dir = std::env::temp_dir().join(format!("caboose_json_export_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("logs.jsonl");
+    let logs: Vec<LogLine> = (0..100_000)
+        .map(|i| log_line("web", &format!("line {} of the export", i)))
+        .collect();
+
+    let written = export_json(&path, &logs, DisplayZone::Local, &Privacy::default()).unwrap();
+    assert_eq!(written, 100_000);
+    let file = BufReader::new(fs::File::open(&path).unwrap());
+    let mut count = 0;
+    for (i, line) in file.lines().enumerate() {
+        let record: JsonLogRecord = serde_json::from_str(&line.unwrap()).unwrap();
+        assert_eq!(record.content, format!("line {} of the export", i));
+        count += 1;
+    }
+    assert_eq!(count, 100_000);
+
+    let err = export_json(&dir, &logs, DisplayZone::Local, &Privacy::default()).unwrap_err();
+    assert!(err.starts_with("Failed to create"), "{}", err);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,181 @@
+use caboose::process::{LogCategory, LogLevel};
+
+fn level(line: &str) -> Option<LogLevel> {
+    LogLevel::detect(line, LogCategory::Other)
+}
+
+#[test]
+fn reads_the_level_from_rails_logger_prefixes() {
+    // Rails 6/7 default formatter
+    assert_eq!(
+        level(
+            "D, [2024-01-15T10:30:45.043111 #6322] DEBUG -- : Rendering layout layouts/application.html.erb"
+        ),
+        Some(LogLevel::Debug)
+    );
+    assert_eq!(
+        level(
+            r#"I, [2024-01-15T10:30:45.043111 #6322]  INFO -- : [req-abc] Started GET "/" for 127.0.0.1"#
+        ),
+        Some(LogLevel::Info)
+    );
+    assert_eq!(
+        level(
+            "W, [2024-01-15T10:30:45.043111 #6322]  WARN -- : DEPRECATION WARNING: `to_s(:db)` is deprecated"
+        ),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level("E, [2024-01-15T10:30:45.043111 #6322] ERROR -- : Could not connect to Redis"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        level("F, [2024-01-15T10:30:45.043111 #6322] FATAL -- : ActionController::RoutingError"),
+        Some(LogLevel::Error)
+    );
+    // The prefix wins over words in the message
+    assert_eq!(
+        level("I, [2024-01-15T10:30:45.043111 #6322]  INFO -- : retrying after ERROR"),
+        Some(LogLevel::Info)
+    );
+
+    // Bracketed and semantic_logger prefixes
+    assert_eq!(
+        level("[WARN 2018-07-01 11:55:04 65048] : Slow request"),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level("2024-01-15 10:30:45.123456 W [12345:puma srv tp 001] Rails -- Cache miss"),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level(
+            "2024-01-15 10:30:45.123456 D [12345:puma srv tp 001] (0.4ms) ActiveRecord -- User Load"
+        ),
+        Some(LogLevel::Debug)
+    );
+}
+
+#[test]
+fn plain_severity_words_set_the_level() {
+    assert_eq!(level("WARN: Redis reconnecting"), Some(LogLevel::Warn));
+    assert_eq!(
+        level("2024-01-15T10:30:46.011Z pid=12345 tid=2y1 WARN: Job raised exception"),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level(r#"ERROR:  relation "widgets" does not exist"#),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(level("npm ERR! code ELIFECYCLE"), Some(LogLevel::Error));
+    assert_eq!(level("[warning] stale lockfile"), Some(LogLevel::Warn));
+    assert_eq!(
+        level("time=10:30 level=error msg=boom"),
+        Some(LogLevel::Error)
+    );
+
+    // Words that only contain a level aren't one
+    assert_eq!(level("ERRORS_TO_IGNORE loaded"), None);
+    assert_eq!(level("Compiled with no warnings"), None);
+    assert_eq!(level("Listening on http://127.0.0.1:3000"), None);
+}
+
+#[test]
+fn frontend_markers_set_the_level() {
+    // Vite / esbuild
+    assert_eq!(
+        level(r#"✘ [ERROR] Could not resolve "react-dom/client""#),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        level(r#"▲ [WARNING] Duplicate key "name" in object literal"#),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level("10:30:45 AM [vite] Internal server error: Failed to resolve import"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        level("(!) Some chunks are larger than 500 kB after minification."),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        LogLevel::detect(
+            "10:30:45 AM [vite] hmr update /src/App.tsx",
+            LogCategory::Frontend
+        ),
+        Some(LogLevel::Info)
+    );
+
+    // Next.js 13+
+    assert_eq!(
+        level(" ⨯ ./app/page.tsx:4:1 Module not found"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        level(" ⚠ Fast Refresh had to perform a full reload"),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level(" ✓ Compiled in 312ms (512 modules)"),
+        Some(LogLevel::Info)
+    );
+
+    // Next.js 12
+    assert_eq!(
+        level("error - ./pages/index.js:3:0 Module not found"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        level("warn  - Fast Refresh had to perform a full reload."),
+        Some(LogLevel::Warn)
+    );
+    assert_eq!(
+        level("event - compiled client and server successfully in 1.2s"),
+        Some(LogLevel::Info)
+    );
+    assert_eq!(level("warning: unused variable `x`"), Some(LogLevel::Warn));
+}
+
+#[test]
+fn the_category_decides_when_the_text_does_not() {
+    // Rails logs queries at debug, whatever their literals say
+    assert_eq!(
+        LogLevel::detect(
+            r#"  Event Load (0.4ms)  SELECT "events".* FROM "events" WHERE "level" = 'ERROR'"#,
+            LogCategory::Sql
+        ),
+        Some(LogLevel::Debug)
+    );
+    assert_eq!(
+        LogLevel::detect(
+            "NoMethodError (undefined method `name' for nil:NilClass):",
+            LogCategory::Error
+        ),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(
+        LogLevel::detect("Completed 200 OK in 52ms", LogCategory::Http),
+        Some(LogLevel::Info)
+    );
+    assert_eq!(
+        LogLevel::detect("hello from puts", LogCategory::Other),
+        None
+    );
+}
+
+#[test]
+fn a_minimum_level_admits_lines_at_it_and_above() {
+    assert!(LogLevel::Warn.admits(Some(LogLevel::Error)));
+    assert!(LogLevel::Warn.admits(Some(LogLevel::Warn)));
+    assert!(!LogLevel::Warn.admits(Some(LogLevel::Info)));
+    // Lines with no level count as info
+    assert!(!LogLevel::Warn.admits(None));
+    assert!(LogLevel::Info.admits(None));
+    assert!(!LogLevel::Info.admits(Some(LogLevel::Debug)));
+
+    assert_eq!(LogLevel::parse("WARNING"), Some(LogLevel::Warn));
+    assert_eq!(LogLevel::parse("fatal"), Some(LogLevel::Error));
+    assert_eq!(LogLevel::parse("verbose"), None);
+    assert!(LogLevel::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::Path;
+
+use caboose::memory::{MAX_TABLE_ROWS, Measure, MemoryReports, ReportChange, ReportKind};
+use caboose::time_format::DisplayZone;
+use chrono::Local;
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/memory")
+        .join(format!("{}.log", name));
+    fs::read_to_string(path).unwrap()
+}
+
+/// Feed `lines` as `process`'s output, returning the changes seen
+fn replay<'a>(
+    reports: &mut MemoryReports,
+    process: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<ReportChange> {
+    lines
+        .into_iter()
+        .filter_map(|line| reports.observe(process, line, Local::now()))
+        .collect()
+}
+
+#[test]
+fn memory_profiler_report_is_read_into_totals_and_tables() {
+    let mut reports = MemoryReports::new();
+    let log = fixture("memory_profiler");
+    let changes = replay(&mut reports, "web", log.lines());
+    assert_eq!(changes, [ReportChange::Started, ReportChange::Finished]);
+
+    let report = reports.latest().unwrap();
+    assert_eq!(report.kind, ReportKind::MemoryProfiler);
+    assert!(report.complete);
+    let allocated = report.total("allocated").unwrap();
+    assert_eq!(allocated.bytes, 30_185_764);
+    assert_eq!(allocated.objects, Some(291_567));
+    assert_eq!(report.total("retained").unwrap().bytes, 1_864_296);
+
+    let titles: Vec<&str> = report.tables.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        [
+            "allocated memory by gem",
+            "allocated memory by file",
+            "allocated objects by gem",
+            "retained memory by gem",
+            "retained memory by file",
+        ]
+    );
+    let by_gem = report.table("allocated memory by gem").unwrap();
+    assert_eq!(by_gem.rows.len(), 6);
+    assert_eq!(by_gem.rows[0].amount, 11_873_240);
+    assert_eq!(by_gem.rows[0].name, "activesupport-7.1.3");
+    assert_eq!(
+        report.table("allocated objects by gem").unwrap().measure,
+        Measure::Objects
+    );
+    assert_eq!(
+        report.table("retained memory by file").unwrap().rows[1].name,
+        "/app/app/models/product.rb"
+    );
+}
+
+#[test]
+fn scaled_sizes_and_reports_without_a_closing_line() {
+    let mut reports = MemoryReports::new();
+    let log = fixture("memory_profiler_scaled");
+    assert_eq!(
+        replay(&mut reports, "worker", log.lines()),
+        [ReportChange::Started]
+    );
+
+    // Still readable while the process may print more of it
+    let report = reports.latest().unwrap();
+    assert!(!report.complete);
+    assert_eq!(report.total("allocated").unwrap().bytes, 48_210_000);
+    let retained = report.table("retained memory by gem").unwrap();
+    assert_eq!(retained.rows[1].amount, 460_200);
+    assert_eq!(retained.rows[1].name, "activesupport-7.1.3");
+    assert_eq!(
+        report.table("allocated memory by gem").unwrap().rows[2].amount,
+        412_500
+    );
+}
+
+#[test]
+fn derailed_bundle_mem_keeps_the_gemfile_requires() {
+    let mut reports = MemoryReports::new();
+    let log = fixture("derailed_bundle_mem");
+    replay(&mut reports, "web", log.lines());
+    assert_eq!(
+        replay(&mut reports, "web", ["$ bin/dev"]),
+        [ReportChange::Finished]
+    );
+
+    let report = reports.get("web").unwrap();
+    assert_eq!(report.kind, ReportKind::DerailedBundleMem);
+    assert_eq!(
+        report.total("required").unwrap().bytes,
+        (84.2148_f64 * 1024.0 * 1024.0).round() as u64
+    );
+    let names: Vec<&str> = report.tables[0]
+        .rows
+        .iter()
+        .map(|row| row.name.as_str())
+        .collect();
+    assert_eq!(names, ["rails/all", "sprockets/railtie", "mail", "pg"]);
+}
+
+#[test]
+fn other_processes_lines_dont_cut_a_report_short() {
+    let mut reports = MemoryReports::new();
+    let log = fixture("memory_profiler");
+    for (index, line) in log.lines().enumerate() {
+        reports.observe("worker", line, Local::now());
+        reports.observe(
+            "frontend",
+            &format!("[vite] hmr update #{}", index),
+            Local::now(),
+        );
+        reports.observe("web", r#"Started GET "/" for 127.0.0.1"#, Local::now());
+    }
+
+    let report = reports.get("worker").unwrap();
+    assert!(report.complete);
+    assert_eq!(report.tables.len(), 5);
+    assert!(reports.get("web").is_none());
+
+    // A line of the reporting process that isn't part of the report ends it early
+    let mut reports = MemoryReports::new();
+    let mut lines = log.lines().skip(2);
+    for line in lines.by_ref().take(9) {
+        reports.observe("worker", line, Local::now());
+    }
+    assert_eq!(
+        reports.observe("worker", "Performing ReportJob", Local::now()),
+        Some(ReportChange::Finished)
+    );
+    assert_eq!(reports.latest().unwrap().tables.len(), 1);
+    assert_eq!(
+        reports.observe("worker", lines.next().unwrap(), Local::now()),
+        None
+    );
+}
+
+#[test]
+fn tables_keep_the_top_rows_and_export_as_text() {
+    let mut reports = MemoryReports::new();
+    let mut lines = vec![
+        "Total allocated: 5000 bytes (50 objects)".to_string(),
+        "allocated memory by class".to_string(),
+        "-----------------------------------".to_string(),
+    ];
+    lines.extend((0..20).map(|i| format!("  {}  Class{}", 200 - i, i)));
+    replay(&mut reports, "console", lines.iter().map(String::as_str));
+
+    let report = reports.latest().unwrap();
+    assert_eq!(report.tables[0].rows.len(), MAX_TABLE_ROWS);
+    assert_eq!(report.summary(), "4.88 KB allocated");
+
+    let text = report.to_text(DisplayZone::Utc);
+    assert!(
+        text.starts_with("memory_profiler report from console at "),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("Total 4.88 KB allocated (50 objects)"),
+        "{}",
+        text
+    );
+    assert!(text.contains("\nallocated memory by class\n"), "{}", text);
+    assert!(text.contains("    200.00 B  Class0\n"), "{}", text);
+    assert!(!text.contains("Class10"), "{}", text);
+}
@@ -0,0 +1,169 @@
+//! Golden-file tests for log parsing
+//!
+//! Every `tests/fixtures/logs/<name>.log` is replayed line by line through
+//! `RailsLogParser::parse_tagged`, `ExceptionTracker` and `TestTracker`. The
+//! resulting snapshot is compared with `<name>.expected.json`; events list
+//! their `tags` only when the line had any.
+//!
+//! After an intentional parser change, regenerate the golden files with:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test parser_golden
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use caboose::exception::ExceptionTracker;
+use caboose::parser::RailsLogParser;
+use caboose::test::TestTracker;
+use serde_json::{Value, json};
+
+/// Fields holding serialized `Instant`s, which differ on every run
+const INSTANT_FIELDS: &[&str] = &[
+    "timestamp",
+    "started_at",
+    "completed_at",
+    "first_seen",
+    "last_seen",
+    "occurrences",
+];
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/logs")
+}
+
+fn fixture_logs() -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+        .expect("fixtures directory should exist")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    logs.sort();
+    logs
+}
+
+fn expected_path(log: &Path) -> PathBuf {
+    log.with_extension("expected.json")
+}
+
+/// Replace `Instant` ages with a stable placeholder
+fn normalize_instants(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if INSTANT_FIELDS.contains(&key.as_str()) {
+                    match field {
+                        Value::Number(_) => *field = json!("<instant>"),
+                        Value::Array(items) => {
+                            for item in items.iter_mut() {
+                                *item = json!("<instant>");
+                            }
+                        }
+                        _ => {}
+                    }
+                } else {
+                    normalize_instants(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_instants),
+        _ => {}
+    }
+}
+
+fn snapshot(content: &str) -> Value {
+    let exception_tracker = ExceptionTracker::new();
+    let test_tracker = TestTracker::new();
+    let mut events = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        if let Some(tagged) = RailsLogParser::parse_tagged(line) {
+            let mut entry = json!({ "line": idx + 1, "event": tagged.event });
+            if !tagged.tags.is_empty() {
+                entry["tags"] = json!(tagged.tags);
+            }
+            events.push(entry);
+        }
+        exception_tracker.parse_line(line);
+        test_tracker.parse_line(line);
+    }
+
+    // A blank line ends any exception still collecting its backtrace
+    exception_tracker.parse_line("");
+
+    let mut exceptions = exception_tracker.get_recent_exceptions(usize::MAX);
+    exceptions.reverse();
+
+    let mut test_runs = test_tracker.get_recent_runs().to_vec();
+    test_runs.extend(test_tracker.get_current_run());
+
+    let mut value = json!({
+        "events": events,
+        "exceptions": exceptions,
+        "test_runs": test_runs,
+        "debugger": test_tracker.get_debugger_info(),
+    });
+    normalize_instants(&mut value);
+    value
+}
+
+#[test]
+fn parser_output_matches_golden_files() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+    let logs = fixture_logs();
+    assert!(!logs.is_empty(), "no fixtures found in tests/fixtures/logs");
+
+    let mut mismatches = Vec::new();
+
+    for log in &logs {
+        let content = fs::read_to_string(log).unwrap();
+        let actual = snapshot(&content);
+        let rendered = format!("{}\n", serde_json::to_string_pretty(&actual).unwrap());
+        let golden = expected_path(log);
+        let name = log.file_name().unwrap().to_string_lossy().to_string();
+
+        if update {
+            fs::write(&golden, rendered).unwrap();
+            continue;
+        }
+
+        let Ok(expected) = fs::read_to_string(&golden) else {
+            mismatches.push(format!("{}: missing {}", name, golden.display()));
+            continue;
+        };
+        let expected: Value = serde_json::from_str(&expected).unwrap();
+
+        if expected != actual {
+            mismatches.push(format!(
+                "{}: output differs from golden file\n--- expected\n{}\n+++ actual\n{}",
+                name,
+                serde_json::to_string_pretty(&expected).unwrap(),
+                rendered
+            ));
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{}\n\nRun `UPDATE_GOLDEN=1 cargo test --test parser_golden` if the change is intended.",
+        mismatches.join("\n\n")
+    );
+}
+
+#[test]
+fn normalizer_replaces_instant_fields_only() {
+    let mut value = json!({
+        "timestamp": 12,
+        "occurrences": [1, 2],
+        "completed_at": null,
+        "nested": [{ "started_at": 5, "duration": 5.0 }],
+    });
+    normalize_instants(&mut value);
+
+    assert_eq!(value["timestamp"], "<instant>");
+    assert_eq!(value["occurrences"], json!(["<instant>", "<instant>"]));
+    assert_eq!(value["completed_at"], Value::Null);
+    assert_eq!(value["nested"][0]["started_at"], "<instant>");
+    assert_eq!(value["nested"][0]["duration"], 5.0);
+}
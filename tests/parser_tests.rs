@@ -1,4 +1,7 @@
-use caboose::parser::{LogEvent, RailsLogParser};
+use caboose::parser::{
+    CacheEvent, CacheOperation, LogEvent, ParseCoverage, REQUEST_ID_KEY, RailsLogParser,
+    SHAPE_LENGTH, UNPARSED_SAMPLES, ViewRender, is_noise, line_shape, request_id_tag,
+};
 
 #[test]
 fn parses_http_start_and_completion() {
@@ -22,6 +25,36 @@ fn parses_http_start_and_completion() {
     }
 }
 
+#[test]
+fn parses_completions_with_multi_word_status_text() {
+    for (line, status) in [
+        ("Completed 500 Internal Server Error in 312ms", 500),
+        ("Completed 404 Not Found in 3ms (ActiveRecord: 0.3ms)", 404),
+        ("Completed 422 Unprocessable Entity in 8.5ms", 422),
+        ("Completed 303 See Other in 4ms", 303),
+    ] {
+        match RailsLogParser::parse_line(line) {
+            Some(LogEvent::HttpRequest(req)) => {
+                assert_eq!(req.status, Some(status), "{}", line);
+                assert!(req.duration.is_some(), "{}", line);
+            }
+            other => panic!("Expected HTTP completion for {}, got {:?}", line, other),
+        }
+    }
+}
+
+#[test]
+fn parses_routing_errors() {
+    let line = r#"F, [2024-01-15T10:30:45.043111 #6322] FATAL -- : ActionController::RoutingError (No route matches [GET] "/api/v2/users"):"#;
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::RouteNotFound { method, path }) => {
+            assert_eq!(method, "GET");
+            assert_eq!(path, "/api/v2/users");
+        }
+        other => panic!("Expected a routing error, got {:?}", other),
+    }
+}
+
 #[test]
 fn parses_sql_and_error_lines() {
     let sql = RailsLogParser::parse_line(r#"User Load (0.5ms)  SELECT "users".* FROM "users""#);
@@ -37,9 +70,455 @@ fn parses_sql_and_error_lines() {
     assert!(matches!(error, Some(LogEvent::Error(_))));
 }
 
+#[test]
+fn sql_binds_are_split_from_the_query() {
+    let line = r#"  User Load (0.4ms)  SELECT "users".* FROM "users" WHERE "users"."id" = $1 LIMIT $2  [["id", 1], ["LIMIT", 11]] /*application='Blog'*/"#;
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::SqlQuery(q)) => {
+            assert!(
+                q.query.ends_with(r#"WHERE "users"."id" = $1 LIMIT $2"#),
+                "{}",
+                q.query
+            );
+            assert_eq!(
+                q.binds,
+                vec![
+                    ("id".to_string(), "1".to_string()),
+                    ("LIMIT".to_string(), "11".to_string()),
+                ]
+            );
+        }
+        other => panic!("Expected SQL event, got {:?}", other),
+    }
+
+    // A line cut off inside the array parses as it did before binds were split
+    let cut = r#"  User Load (0.4ms)  SELECT "users".* FROM "users" WHERE "users"."id" = $1  [["id", 1], ["LIM"#;
+    match RailsLogParser::parse_line(cut) {
+        Some(LogEvent::SqlQuery(q)) => {
+            assert!(q.query.ends_with(r#"[["id", 1], ["LIM"#), "{}", q.query);
+            assert!(q.binds.is_empty());
+        }
+        other => panic!("Expected SQL event, got {:?}", other),
+    }
+}
+
 #[test]
 fn highlights_sql_keywords() {
     let highlighted = RailsLogParser::highlight_sql("SELECT * FROM users WHERE id = 1");
     assert!(highlighted.contains("[KW]SELECT[/KW]"));
     assert!(highlighted.contains("[KW]FROM[/KW]"));
 }
+
+#[test]
+fn parses_lograge_line_with_many_custom_fields() {
+    let line = concat!(
+        "method=GET path=/api/orders format=json controller=Api::OrdersController ",
+        "action=index status=200 duration=87.42 view=12.30 db=40.05 allocations=28090 ",
+        "bytes=10432 content_type=application/json request_id=8f2c-11 host=localhost ",
+        "remote_ip=127.0.0.1 user_id=42 tenant=acme params_count=3 cache=miss ",
+        r#"user_agent="Mozilla/5.0 (X11; Linux)" region=eu-west-1 sidekiq.jid=abc123 "#,
+        "feature.checkout_v2=true"
+    );
+
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::HttpRequest(req)) => {
+            assert_eq!(req.method, "GET");
+            assert_eq!(req.path, "/api/orders");
+            assert_eq!(req.status, Some(200));
+            assert_eq!(req.duration, Some(87.42));
+            assert_eq!(req.controller.as_deref(), Some("Api::OrdersController"));
+            assert_eq!(req.action.as_deref(), Some("index"));
+
+            assert_eq!(req.extra.len(), 17);
+            assert_eq!(req.extra["bytes"], "10432");
+            assert_eq!(req.extra["content_type"], "application/json");
+            assert_eq!(req.extra["user_agent"], "Mozilla/5.0 (X11; Linux)");
+            assert_eq!(req.extra["feature.checkout_v2"], "true");
+            assert!(!req.extra.contains_key("method"));
+        }
+        _ => panic!("Expected Lograge request"),
+    }
+}
+
+#[test]
+fn lograge_core_fields_ignore_similar_custom_keys() {
+    // `api_path` and `http_status` must not be mistaken for `path` and `status`
+    let line = "api_path=/v2 http_status=503 method=POST path=/orders status=201 duration=5 \
+                broken= =value note=\"unterminated";
+
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::HttpRequest(req)) => {
+            assert_eq!(req.path, "/orders");
+            assert_eq!(req.status, Some(201));
+            assert_eq!(req.duration, Some(5.0));
+            assert_eq!(req.extra["api_path"], "/v2");
+            assert_eq!(req.extra["http_status"], "503");
+            assert_eq!(req.extra["broken"], "");
+            assert_eq!(req.extra["note"], "unterminated");
+        }
+        _ => panic!("Expected Lograge request"),
+    }
+}
+
+#[test]
+fn completed_line_breakdown_becomes_extra_fields() {
+    let done = RailsLogParser::parse_line(
+        "Completed 200 OK in 104ms (Views: 90.8ms | ActiveRecord: 0.4ms | Allocations: 2809)",
+    );
+    match done {
+        Some(LogEvent::HttpRequest(req)) => {
+            assert_eq!(req.status, Some(200));
+            assert_eq!(req.extra["view"], "90.8");
+            assert_eq!(req.extra["db"], "0.4");
+            assert_eq!(req.extra["allocations"], "2809");
+        }
+        _ => panic!("Expected HTTP completion"),
+    }
+}
+
+fn view_render(line: &str) -> ViewRender {
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::ViewRender(view)) => view,
+        other => panic!("Expected view render for {:?}, got {:?}", line, other),
+    }
+}
+
+#[test]
+fn parses_rendered_lines() {
+    let partial =
+        view_render("  Rendered users/_row.html.erb (Duration: 1.2ms | Allocations: 312)");
+    assert_eq!(
+        partial,
+        ViewRender {
+            template: "users/_row.html.erb".into(),
+            duration: 1.2,
+            allocations: Some(312),
+            count: None,
+        }
+    );
+
+    let within = view_render(
+        "I, [2024-01-15T10:30:45.070666 #6322]  INFO -- :   Rendered posts/index.html.erb within layouts/application (Duration: 12.3ms | Allocations: 4521)",
+    );
+    assert_eq!(within.template, "posts/index.html.erb");
+    assert_eq!(within.duration, 12.3);
+
+    let layout = view_render(
+        "Rendered layout layouts/application.html.erb (Duration: 8.4ms | GC: 0.2ms | Allocations: 3120)",
+    );
+    assert_eq!(layout.template, "layouts/application.html.erb");
+    assert_eq!(layout.allocations, Some(3120));
+
+    // Rails 5 logs only the duration
+    let rails5 = view_render("Rendered users/_row.html.erb (0.9ms)");
+    assert_eq!(rails5.duration, 0.9);
+    assert_eq!(rails5.allocations, None);
+}
+
+#[test]
+fn parses_collection_renders_with_item_counts() {
+    let collection = view_render(
+        "Rendered collection of users/_row.html.erb [50 times] (Duration: 30.1ms | Allocations: 9000)",
+    );
+    assert_eq!(collection.template, "users/_row.html.erb");
+    assert_eq!(collection.count, Some(50));
+    assert_eq!(collection.items(), 50);
+    assert_eq!(collection.duration, 30.1);
+
+    let cached = view_render(
+        "Rendered collection of posts/_post.html.erb [8 / 10 cache hits] (Duration: 4.0ms | Allocations: 800)",
+    );
+    assert_eq!(cached.count, Some(10));
+
+    // "Rendering" announces a render that hasn't finished yet
+    assert!(RailsLogParser::parse_line("Rendering layout layouts/application.html.erb").is_none());
+}
+
+fn cache_event(line: &str) -> CacheEvent {
+    match RailsLogParser::parse_line(line) {
+        Some(LogEvent::Cache(cache)) => cache,
+        other => panic!("Expected cache event for {:?}, got {:?}", line, other),
+    }
+}
+
+#[test]
+fn parses_cache_lines() {
+    let hit = cache_event("Cache read: views/users/1-20240115 (hit)");
+    assert_eq!(hit.operation, CacheOperation::Hit);
+    assert_eq!(hit.key, "views/users/1-20240115");
+    assert_eq!(hit.prefix(), "views");
+
+    let miss = cache_event(
+        "D, [2024-01-15T10:30:45.043111 #6322] DEBUG -- : Cache read: products/42 ({:expires_in=>300}) (miss)",
+    );
+    assert_eq!(miss.operation, CacheOperation::Miss);
+    assert_eq!(miss.key, "products/42");
+
+    assert_eq!(
+        cache_event("Cache fetch_hit: settings").operation,
+        CacheOperation::Hit
+    );
+    assert_eq!(
+        cache_event("Cache generate: settings").operation,
+        CacheOperation::Miss
+    );
+    assert_eq!(
+        cache_event("Cache write: views/posts/7").operation,
+        CacheOperation::Write
+    );
+    let delete = cache_event("Cache delete: session/abc");
+    assert_eq!(delete.operation, CacheOperation::Delete);
+    assert_eq!(delete.prefix(), "session");
+
+    // Reads with no outcome and other operations aren't counted
+    assert!(!matches!(
+        RailsLogParser::parse_line("Cache read: views/users/1"),
+        Some(LogEvent::Cache(_))
+    ));
+    assert!(!matches!(
+        RailsLogParser::parse_line("Cache exist?: views/users/1"),
+        Some(LogEvent::Cache(_))
+    ));
+}
+
+#[test]
+fn cache_outcome_is_not_read_from_the_key() {
+    // The key itself ends in "(hit)", but the read's outcome is a miss
+    let miss = cache_event("Cache read: search/q=(hit) (miss)");
+    assert_eq!(miss.operation, CacheOperation::Miss);
+    assert_eq!(miss.key, "search/q=(hit)");
+
+    // A key containing "(miss)" with no separate outcome is not a read result
+    assert!(!matches!(
+        RailsLogParser::parse_line("Cache read: search/(miss)"),
+        Some(LogEvent::Cache(_))
+    ));
+    assert!(!matches!(
+        RailsLogParser::parse_line("Cache read: search/term (hit)x"),
+        Some(LogEvent::Cache(_))
+    ));
+
+    // Keys that look like SQL are still cache lines
+    assert_eq!(
+        cache_event("Cache write: reports/SELECT-totals").operation,
+        CacheOperation::Write
+    );
+}
+
+#[test]
+fn parses_request_queueing_lines() {
+    let queueing = |line: &str| match RailsLogParser::parse_line(line) {
+        Some(LogEvent::RequestQueueing(ms)) => Some(ms),
+        _ => None,
+    };
+
+    assert_eq!(
+        queueing("source=rack-timeout id=4f2a wait=23ms timeout=15000ms state=ready"),
+        Some(23.0)
+    );
+    // Later states repeat the wait; only `ready` counts
+    assert_eq!(
+        queueing(
+            "source=rack-timeout id=4f2a wait=23ms timeout=15000ms service=40ms state=completed"
+        ),
+        None
+    );
+    assert_eq!(queueing("[request_queueing] 142.5ms"), Some(142.5));
+    assert_eq!(queueing("request_queueing=8ms"), Some(8.0));
+    assert_eq!(queueing("Completed 200 OK in 12ms"), None);
+}
+
+#[test]
+fn parses_request_parameters_lines() {
+    let event = RailsLogParser::parse_line(
+        r#"  Parameters: {"authenticity_token"=>"[FILTERED]", "order"=>{"sku"=>"A-1"}}"#,
+    );
+    let Some(LogEvent::RequestParams(params)) = event else {
+        panic!("expected parameters, got {:?}", event);
+    };
+    assert_eq!(
+        params,
+        r#"{"authenticity_token"=>"[FILTERED]", "order"=>{"sku"=>"A-1"}}"#
+    );
+}
+
+#[test]
+fn strips_nested_tags_and_keeps_them() {
+    let tagged = RailsLogParser::parse_tagged(
+        r#"I, [2024-01-15T10:30:45.043111 #6322]  INFO -- : [Payments] [req-abc] Started GET "/pay" for 127.0.0.1"#,
+    )
+    .unwrap();
+    assert_eq!(tagged.tags, ["Payments", "req-abc"]);
+    match tagged.event {
+        LogEvent::HttpRequest(req) => assert_eq!(req.path, "/pay"),
+        other => panic!("Expected HTTP start, got {:?}", other),
+    }
+
+    // Bind arrays and untagged lines are left alone
+    assert!(RailsLogParser::tags(r#"[["id", 1]] Started GET "/""#).is_empty());
+    assert!(RailsLogParser::tags("[] Started GET /").is_empty());
+    assert!(RailsLogParser::tags(r#"Started GET "/""#).is_empty());
+    assert_eq!(
+        RailsLogParser::message("[a] [b]   User Load (0.4ms)  SELECT 1"),
+        "  User Load (0.4ms)  SELECT 1"
+    );
+}
+
+#[test]
+fn request_id_tags_become_the_request_id_field() {
+    let tagged = RailsLogParser::parse_tagged(
+        r#"[Payments] [1f0c9a2e-7b3d-4c55-9e21-6d8f0a4b2c10] Started GET "/pay" for 127.0.0.1"#,
+    )
+    .unwrap();
+    match tagged.event {
+        LogEvent::HttpRequest(req) => assert_eq!(
+            req.extra.get(REQUEST_ID_KEY).map(String::as_str),
+            Some("1f0c9a2e-7b3d-4c55-9e21-6d8f0a4b2c10")
+        ),
+        other => panic!("Expected HTTP start, got {:?}", other),
+    }
+    let tagged = RailsLogParser::parse_line(
+        r#"[req-42]   User Load (0.4ms)  SELECT "users".* FROM "users""#,
+    );
+    match tagged {
+        Some(LogEvent::SqlQuery(query)) => assert_eq!(query.request_id.as_deref(), Some("req-42")),
+        other => panic!("Expected SQL query, got {:?}", other),
+    }
+
+    let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        request_id_tag(&tags(&["Billing", "req-77"])),
+        Some("req-77")
+    );
+    assert_eq!(
+        request_id_tag(&tags(&["0123456789abcdef0123"])),
+        Some("0123456789abcdef0123")
+    );
+    assert_eq!(
+        request_id_tag(&tags(&["Billing", "ActiveJob", "12345"])),
+        None
+    );
+
+    // A logged field wins over the tag
+    let tagged = RailsLogParser::parse_tagged(
+        "[req-tag] method=GET path=/a format=html status=200 duration=5.0 request_id=field-id",
+    )
+    .unwrap();
+    match tagged.event {
+        LogEvent::HttpRequest(req) => assert_eq!(req.extra[REQUEST_ID_KEY], "field-id"),
+        other => panic!("Expected Lograge request, got {:?}", other),
+    }
+}
+
+#[test]
+fn strips_semantic_logger_prefix() {
+    let line = r#"2024-01-15 10:30:45.123456 I [12345:puma srv tp 001] [Billing] [req-77] (1.2ms) InvoicesController -- Completed 200 OK in 26ms"#;
+    let tagged = RailsLogParser::parse_tagged(line).unwrap();
+    assert_eq!(tagged.tags, ["Billing", "req-77"]);
+    match tagged.event {
+        LogEvent::HttpRequest(req) => assert_eq!(req.status, Some(200)),
+        other => panic!("Expected completion, got {:?}", other),
+    }
+
+    assert_eq!(
+        RailsLogParser::message(
+            r#"2024-01-15 10:30:45.123456 I [12345:puma srv tp 001] Rails -- Started GET "/""#
+        ),
+        r#"Started GET "/""#
+    );
+}
+
+fn error_line_fixture(name: &str) -> Vec<String> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/error_lines")
+        .join(name);
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn benign_lines_mentioning_errors_are_not_errors() {
+    let misclassified: Vec<String> = error_line_fixture("benign.log")
+        .into_iter()
+        .filter(|line| matches!(RailsLogParser::parse_line(line), Some(LogEvent::Error(_))))
+        .collect();
+    assert!(misclassified.is_empty(), "{:#?}", misclassified);
+}
+
+#[test]
+fn genuine_error_lines_still_classify() {
+    let missed: Vec<String> = error_line_fixture("errors.log")
+        .into_iter()
+        .filter(|line| !matches!(RailsLogParser::parse_line(line), Some(LogEvent::Error(_))))
+        .collect();
+    assert!(missed.is_empty(), "{:#?}", missed);
+}
+
+#[test]
+fn parse_coverage_counts_lines_per_process() {
+    let mut coverage = ParseCoverage::new();
+    assert_eq!(coverage.percent(), None);
+
+    coverage.record("web", "Completed 200 OK in 5ms", true);
+    coverage.record("web", "Puma starting in single mode...", false);
+    coverage.record("web", "", false);
+    coverage.record("worker", "Sidekiq booted", false);
+
+    assert_eq!(coverage.lines(), 4);
+    assert_eq!(coverage.unparsed(), 2);
+    assert_eq!(coverage.percent(), Some(50));
+    let web = coverage
+        .processes()
+        .find(|(name, _)| *name == "web")
+        .unwrap()
+        .1;
+    assert_eq!((web.lines, web.unparsed), (3, 1));
+}
+
+#[test]
+fn parse_coverage_keeps_a_bounded_set_of_distinct_shapes() {
+    let mut coverage = ParseCoverage::new();
+    for i in 0..10 {
+        coverage.record("web", &format!("[{}] heartbeat {}", i, i * 7), false);
+    }
+    for i in 0..(UNPARSED_SAMPLES * 2) {
+        let word: String = std::iter::repeat_n('a', i + 1).collect();
+        coverage.record("worker", &format!("job {}", word), false);
+    }
+
+    let samples = coverage.samples();
+    assert_eq!(samples.len(), UNPARSED_SAMPLES);
+    assert_eq!(samples[0].shape, "[0] heartbeat 0");
+    assert_eq!(samples[0].count, 10);
+    assert_eq!(coverage.unparsed(), 10 + UNPARSED_SAMPLES as u64 * 2);
+
+    let report = coverage.report();
+    assert!(report.starts_with("Parse coverage: 0% ("), "{}", report);
+    assert!(
+        report.contains("    10x  [web] [0] heartbeat 0\n"),
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn line_shapes_are_short_and_redacted() {
+    let shape = line_shape(&format!(
+        "connecting with password=hunter2 as dev@example.com {}",
+        "x".repeat(200)
+    ));
+    assert!(
+        shape.starts_with("connecting with password=••• as ••• x"),
+        "{}",
+        shape
+    );
+    assert_eq!(shape.chars().count(), SHAPE_LENGTH);
+    assert!(!shape.contains("hunter2"));
+
+    assert!(is_noise("   "));
+    assert!(is_noise("─────── * ───────"));
+    assert!(!is_noise("=== ok ==="));
+}
@@ -0,0 +1,50 @@
+use caboose::path_glob::{PathGlob, matches_any};
+
+fn glob(pattern: &str) -> PathGlob {
+    PathGlob::parse(pattern).unwrap()
+}
+
+#[test]
+fn star_matches_within_one_segment() {
+    let status = glob("/api/*/status");
+    assert!(status.matches("/api/jobs/status"));
+    assert!(status.matches("/api/jobs/status/"));
+    assert!(!status.matches("/api/jobs/42/status"));
+    assert!(!status.matches("/api/status"));
+
+    let prefixed = glob("/v*/users");
+    assert!(prefixed.matches("/v2/users"));
+    assert!(!prefixed.matches("/admin/users"));
+}
+
+#[test]
+fn double_star_matches_any_number_of_segments() {
+    let poll = glob("/poll/**");
+    assert!(poll.matches("/poll"));
+    assert!(poll.matches("/poll/42/events"));
+    assert!(!poll.matches("/polling"));
+
+    assert!(glob("/**/health").matches("/internal/k8s/health"));
+    assert!(glob("/**/health").matches("/health"));
+}
+
+#[test]
+fn query_strings_are_ignored() {
+    assert!(glob("/poll/*").matches("/poll/42?since=3"));
+    assert!(!glob("/poll/*").matches("/poll/42/events?since=3"));
+}
+
+#[test]
+fn patterns_must_be_paths() {
+    let error = PathGlob::parse("api/*").unwrap_err();
+    assert!(error.contains("'api/*'"), "{}", error);
+
+    let (globs, errors) = PathGlob::parse_all(["/a/*", "b", " /c/** "]);
+    assert_eq!(
+        globs.iter().map(PathGlob::as_str).collect::<Vec<_>>(),
+        ["/a/*", "/c/**"]
+    );
+    assert_eq!(errors.len(), 1);
+    assert!(matches_any(&globs, "/c/d/e"));
+    assert!(!matches_any(&globs, "/b"));
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use caboose::context::RequestContextTracker;
+use caboose::database::{DatabaseHealth, TableInfo};
+use caboose::exception::ExceptionTracker;
+use caboose::parser::{LogEvent, RailsLogParser};
+use caboose::persistence::{PERSISTED_FORMAT_VERSION, PERSISTED_SESSION_FILE, PersistedAnalytics};
+use caboose::session::SessionSources;
+use caboose::stats::StatsCollector;
+use caboose::test::TestTracker;
+use chrono::Local;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "caboose_persistence_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[derive(Default)]
+struct Trackers {
+    stats: StatsCollector,
+    requests: RequestContextTracker,
+    exceptions: ExceptionTracker,
+    tests: TestTracker,
+    database: DatabaseHealth,
+}
+
+impl Trackers {
+    fn sources(&self) -> SessionSources<'_> {
+        SessionSources {
+            processes: &[],
+            logs: &[],
+            stats: &self.stats,
+            requests: &self.requests,
+            exceptions: &self.exceptions,
+            tests: &self.tests,
+            database: &self.database,
+        }
+    }
+
+    /// Feed a log line to the trackers the way the UI does
+    fn record(&self, line: &str) {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            match &event {
+                LogEvent::HttpRequest(req) => {
+                    if let (Some(status), Some(duration)) = (req.status, req.duration) {
+                        self.stats.record_request(status, duration);
+                    }
+                }
+                LogEvent::SqlQuery(query) => {
+                    if let Some(duration) = query.duration {
+                        self.stats.record_sql_query(duration);
+                        self.database.analyze_query(&query.query, duration);
+                    }
+                }
+                _ => {}
+            }
+            self.requests.process_log_event(&event);
+        }
+        self.exceptions.parse_line(line);
+    }
+}
+
+const SESSION_LOG: &[&str] = &[
+    r#"Started GET "/users/42" for 127.0.0.1"#,
+    "Processing by UsersController#show as HTML",
+    r#"  User Load (250.3ms)  SELECT "users".* FROM "users" WHERE "users"."email" = 'a@b.c'"#,
+    "Completed 200 OK in 312ms",
+    "NoMethodError: undefined method `name' for nil:NilClass",
+    "  app/controllers/users_controller.rb:12:in `show'",
+    "end of backtrace",
+    "NoMethodError: undefined method `name' for nil:NilClass",
+    "  app/controllers/users_controller.rb:12:in `show'",
+    "end of backtrace",
+    "ArgumentError: wrong number of arguments (given 2, expected 1)",
+    "  app/controllers/users_controller.rb:20:in `update'",
+    "end of backtrace",
+];
+
+fn table(name: &str) -> (String, TableInfo) {
+    let table = TableInfo {
+        name: name.to_string(),
+        estimated_rows: 0,
+        has_primary_key: true,
+        columns: Vec::new(),
+        indexes: Vec::new(),
+        foreign_keys: Vec::new(),
+    };
+    (name.to_string(), table)
+}
+
+fn recorded_session() -> Trackers {
+    let trackers = Trackers::default();
+    for line in SESSION_LOG {
+        trackers.record(line);
+    }
+    trackers.database.record_health_score();
+    trackers
+}
+
+#[test]
+fn exception_groups_and_health_score_survive_a_restart() {
+    let dir = temp_dir("restart");
+    let path = dir.join(PERSISTED_SESSION_FILE);
+    let before = recorded_session();
+    let mut analytics = PersistedAnalytics::capture(&before.sources());
+    assert!(!analytics.is_empty());
+    // Caboose was stopped for ten minutes after saving
+    analytics.saved_at = Local::now() - chrono::Duration::minutes(10);
+    analytics.save(&path).unwrap();
+
+    let after = Trackers::default();
+    let loaded = PersistedAnalytics::load(&path).unwrap().unwrap();
+    assert_eq!(
+        loaded.summary(),
+        "2 exception groups, 1 request, 0 test runs"
+    );
+    loaded.restore_into(&after.sources());
+
+    let groups = after.exceptions.get_grouped_exceptions();
+    assert_eq!(groups.len(), 2);
+    let no_method = groups
+        .iter()
+        .find(|group| group.exception_type == "NoMethodError")
+        .unwrap();
+    assert_eq!(no_method.count, 2);
+    assert!(no_method.last_seen.elapsed() >= Duration::from_secs(600));
+    assert!(no_method.last_seen.elapsed() < Duration::from_secs(660));
+
+    assert_eq!(
+        after.database.calculate_health_score(),
+        before.database.calculate_health_score()
+    );
+    assert_eq!(
+        after.database.get_score_history(),
+        before.database.get_score_history()
+    );
+    assert_eq!(after.database.get_slow_queries().len(), 1);
+    assert_eq!(after.database.get_stats().total_queries, 1);
+    assert_eq!(after.stats.get_stats().total_requests, 1);
+    assert_eq!(after.requests.snapshot().completed.len(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_running_projects_schema_is_kept() {
+    let saved = recorded_session();
+    saved.database.set_schema(HashMap::from([table("users")]));
+    let analytics = PersistedAnalytics::capture(&saved.sources());
+
+    let live = Trackers::default();
+    live.database.set_schema(HashMap::from([table("orders")]));
+    analytics.restore_into(&live.sources());
+
+    let tables = live.database.snapshot().tables;
+    assert!(tables.contains_key("orders"));
+    assert!(!tables.contains_key("users"));
+    assert_eq!(live.database.get_slow_queries().len(), 1);
+}
+
+#[test]
+fn missing_files_have_nothing_to_resume_and_newer_formats_are_refused() {
+    let dir = temp_dir("missing");
+    assert!(
+        PersistedAnalytics::load(&dir.join(PERSISTED_SESSION_FILE))
+            .unwrap()
+            .is_none()
+    );
+
+    let newer = format!(
+        r#"{{"version":{},"saved_at":"2026-01-01T12:00:00+00:00"}}"#,
+        PERSISTED_FORMAT_VERSION + 1
+    );
+    let err = PersistedAnalytics::from_json(newer.as_bytes(), Local::now()).unwrap_err();
+    assert!(err.contains("format version"), "{}", err);
+    assert!(PersistedAnalytics::from_json(b"not json", Local::now()).is_err());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn a_run_that_logged_nothing_is_empty() {
+    let trackers = Trackers::default();
+    assert!(PersistedAnalytics::capture(&trackers.sources()).is_empty());
+}
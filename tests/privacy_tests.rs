@@ -0,0 +1,157 @@
+use caboose::privacy::{MASK, Privacy, redact_secrets};
+use caboose::query::mask_string_literals;
+
+fn on() -> Privacy {
+    Privacy::new(true, vec!["customer/*".to_string(), "*-acme".to_string()])
+}
+
+#[test]
+fn string_literals_are_masked_keeping_identifiers_and_comments() {
+    assert_eq!(
+        mask_string_literals(
+            r#"SELECT "users".* FROM "users" WHERE "users"."name" = 'O''Brien' AND id = 4"#,
+            "?"
+        ),
+        r#"SELECT "users".* FROM "users" WHERE "users"."name" = '?' AND id = 4"#
+    );
+    assert_eq!(
+        mask_string_literals(
+            "SELECT `id` FROM `users` WHERE `email` = 'a@b.co' /*controller:users,note:don't*/",
+            "?"
+        ),
+        "SELECT `id` FROM `users` WHERE `email` = '?' /*controller:users,note:don't*/"
+    );
+    // A literal cut off at the end is masked to the end, with no closing quote
+    assert_eq!(
+        mask_string_literals("WHERE name = 'Jane Do", "?"),
+        "WHERE name = '?"
+    );
+}
+
+#[test]
+fn queries_mask_literals_and_tokens() {
+    assert_eq!(
+        on().sql(r#"UPDATE "users" SET "email" = 'jane@example.com' WHERE "id" = 7"#),
+        format!(r#"UPDATE "users" SET "email" = '{MASK}' WHERE "id" = 7"#)
+    );
+    // Postgres binds come with their values in a trailing list
+    assert_eq!(
+        on().sql(r#"SELECT 1 FROM "invites" WHERE "token" = $1  [["token", "3f2504e0-4f89-11d3-9a0c-0305e82c3301"]]"#),
+        format!(r#"SELECT 1 FROM "invites" WHERE "token" = $1  [["token", "{MASK}"]]"#)
+    );
+}
+
+#[test]
+fn string_bind_values_are_masked_whole() {
+    let privacy = on();
+
+    assert_eq!(
+        privacy.sql(r#"SELECT 1 FROM "users" WHERE "name" = $1 AND "id" = $2  [["name", "Jane O'Brien"], ["id", 7]]"#),
+        format!(r#"SELECT 1 FROM "users" WHERE "name" = $1 AND "id" = $2  [["name", "{MASK}"], ["id", 7]]"#)
+    );
+    assert_eq!(privacy.bind_value(r#""Jane""#), format!(r#""{MASK}""#));
+    assert_eq!(privacy.bind_value("7"), "7");
+    assert_eq!(Privacy::default().bind_value(r#""Jane""#), r#""Jane""#);
+}
+
+#[test]
+fn paths_and_params_mask_emails_and_uuids() {
+    let privacy = on();
+
+    assert_eq!(
+        privacy.text("/users/jane.doe+test@example.co.uk/edit"),
+        format!("/users/{MASK}/edit")
+    );
+    assert_eq!(
+        privacy.text("/unsubscribe?email=jane%40example.com&list=2"),
+        format!("/unsubscribe?email={MASK}&list=2")
+    );
+    assert_eq!(
+        privacy.text("/orders/3F2504E0-4F89-11D3-9A0C-0305E82C3301/items/12"),
+        format!("/orders/{MASK}/items/12")
+    );
+    assert_eq!(
+        privacy.text(r#"Parameters: {"user"=>{"email"=>"jane@example.com", "age"=>"30"}}"#),
+        format!(r#"Parameters: {{"user"=>{{"email"=>"{MASK}", "age"=>"30"}}}}"#)
+    );
+    assert_eq!(privacy.text("/users/42/posts"), "/users/42/posts");
+}
+
+#[test]
+fn log_lines_are_masked_as_sql_only_when_they_log_a_query() {
+    let privacy = on();
+
+    assert_eq!(
+        privacy.log_line(r#"  User Load (0.4ms)  SELECT "users".* FROM "users" WHERE "users"."name" = 'Jane' LIMIT 1"#),
+        format!(r#"  User Load (0.4ms)  SELECT "users".* FROM "users" WHERE "users"."name" = '{MASK}' LIMIT 1"#)
+    );
+    // Apostrophes in prose aren't literals
+    assert_eq!(
+        privacy.log_line("Can't verify CSRF token authenticity for jane@example.com"),
+        format!("Can't verify CSRF token authenticity for {MASK}")
+    );
+}
+
+#[test]
+fn request_ids_survive_masking() {
+    let privacy = on();
+    let id = "1f0c9a2e-7b3d-4c55-9e21-6d8f0a4b2c10";
+    let record = "6d8f0a4b-7b3d-4c55-9e21-1f0c9a2e2c10";
+
+    assert_eq!(
+        privacy.log_line(&format!(
+            "[{id}] Started GET \"/docs/{record}\" for 127.0.0.1"
+        )),
+        format!("[{id}] Started GET \"/docs/{MASK}\" for 127.0.0.1")
+    );
+    assert_eq!(
+        privacy.log_line(&format!(
+            "[{id}]   User Load (0.4ms)  SELECT 1 WHERE email = 'a@b.io'"
+        )),
+        format!("[{id}]   User Load (0.4ms)  SELECT 1 WHERE email = '{MASK}'")
+    );
+    assert_eq!(
+        privacy.log_line(&format!("[{id}] Rendering layout for {record}")),
+        format!("[{id}] Rendering layout for {MASK}")
+    );
+}
+
+#[test]
+fn branches_are_hidden_when_they_match_a_pattern() {
+    let privacy = on();
+
+    assert_eq!(privacy.branch("customer/big-bank-export"), MASK);
+    assert_eq!(privacy.branch("fix-login-acme"), MASK);
+    assert_eq!(privacy.branch("main"), "main");
+    assert_eq!(
+        Privacy::new(true, Vec::new()).branch("customer/x"),
+        "customer/x"
+    );
+}
+
+#[test]
+fn nothing_is_masked_while_off() {
+    let mut privacy = on();
+    privacy.set_enabled(false);
+
+    assert!(!privacy.is_enabled());
+    assert_eq!(privacy.sql("WHERE a = 'secret'"), "WHERE a = 'secret'");
+    assert_eq!(privacy.text("/u/jane@example.com"), "/u/jane@example.com");
+    assert_eq!(privacy.branch("customer/x"), "customer/x");
+}
+
+#[test]
+fn redact_secrets_masks_credentials_even_with_privacy_off() {
+    assert_eq!(
+        redact_secrets(r#"auth ok token=abc123 api_key: "k-9 9" user=jo@example.com"#),
+        format!(r#"auth ok token={m} api_key: {m} user={m}"#, m = MASK)
+    );
+    assert_eq!(
+        redact_secrets(r#"{"password":"s3cret","name":"Jo"} Authorization: Bearer eyJhb.c-d"#),
+        format!(
+            r#"{{"password":{m},"name":"Jo"}} Authorization: {m}"#,
+            m = MASK
+        )
+    );
+    assert_eq!(redact_secrets("Booted in 2.3s"), "Booted in 2.3s");
+}
@@ -1,4 +1,22 @@
-use caboose::process::ProcessManager;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use caboose::frontend::FrontendLogParser;
+use caboose::parser::RailsLogParser;
+use caboose::process::{
+    BootTimes, DEDUPE_WINDOW, DEFAULT_BOOT_BUDGET, FINISHED_TASK_TTL, LineTruncator, LogCategory,
+    LogFileFollower, LogLine, LogStream, MAX_RESTART_BACKOFF, MAX_SEND_LEN, MIN_BOOT_BUDGET,
+    PortClaim, PortSource, ProcessFilter, ProcessInfo, ProcessLogFiles, ProcessManager,
+    ProcessSpawn, ProcessStatus, ProjectHealth, RESTART_REQUESTS_FILE, Readiness, RestartRule,
+    STATE_FILE, STATE_WRITE_INTERVAL, STOP_REQUEST_FILE, STOP_REQUEST_WAIT, SessionState,
+    SetupError, SetupStep, StopOutcome, StreamDeduper, daemonize_warning, format_ps, gave_up_note,
+    is_process_alive, is_ready_line, last_lines, listening_inodes, logged_processes,
+    parse_lsof_pids, port_collisions, port_from_command, port_intent, process_log_path,
+    project_health, read_backlog, request_restart, request_run, request_stop, restart_note,
+    rolled_over_path, running_session, shell_join, split_args, stop_processes, stop_targets,
+    take_restart_requests, take_run_requests, task_name,
+};
+use chrono::{DateTime, Local};
 
 #[test]
 fn spawn_process_rejects_empty_command() {
@@ -7,3 +25,1700 @@ fn spawn_process_rejects_empty_command() {
     let err = manager.spawn_process("web".into(), "".into(), std::collections::HashMap::new());
     assert!(err.is_err());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn spawn_all_starts_processes_together_and_reports_failures() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = std::sync::Arc::new(ProcessManager::new(tx));
+    let mut spawns: Vec<ProcessSpawn> = (0..10)
+        .map(|i| ProcessSpawn {
+            name: format!("worker{}", i),
+            command: "sleep 2".to_string(),
+            env: HashMap::new(),
+        })
+        .collect();
+    spawns.insert(
+        3,
+        ProcessSpawn {
+            name: "broken".to_string(),
+            command: "caboose-no-such-program --serve".to_string(),
+            env: HashMap::new(),
+        },
+    );
+
+    let started = Instant::now();
+    let failures = manager.spawn_all(spawns).await;
+    let elapsed = started.elapsed();
+
+    assert!(elapsed < Duration::from_secs(2), "took {:?}", elapsed);
+    assert_eq!(failures.len(), 1, "{:?}", failures);
+    assert_eq!(failures[0].0, "broken");
+
+    let processes = manager.get_processes();
+    assert_eq!(processes.len(), 11);
+    let broken = manager.get_process("broken").unwrap();
+    assert_eq!(broken.status, ProcessStatus::Crashed);
+    assert_eq!(broken.spawn_error.as_deref(), Some(failures[0].1.as_str()));
+    assert!(
+        processes
+            .iter()
+            .filter(|info| info.name != "broken")
+            .all(|info| info.pid.is_some() && info.spawn_error.is_none())
+    );
+
+    let line = rx.recv().await.unwrap();
+    assert_eq!(line.process_name, "broken");
+    assert!(
+        line.content.starts_with("Failed to start: "),
+        "{}",
+        line.content
+    );
+    manager.stop_all();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn adopt_process_finds_the_listener_and_is_never_stopped() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+
+    manager
+        .adopt_process("web".into(), "bin/rails server".into(), Some(port), None)
+        .unwrap();
+
+    let web = manager.get_process("web").unwrap();
+    assert_eq!(web.status, ProcessStatus::External);
+    assert!(manager.is_adopted("web"));
+    let note = rx.recv().await.unwrap();
+    assert!(
+        note.content
+            .starts_with("Adopted external process: running on port"),
+        "{}",
+        note.content
+    );
+
+    manager.stop_all();
+    assert_eq!(
+        manager.get_process("web").unwrap().status,
+        ProcessStatus::External
+    );
+    drop(listener);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn adopt_process_needs_a_port() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+
+    let err = manager
+        .adopt_process("web".into(), "bin/dev".into(), None, None)
+        .unwrap_err();
+    assert!(err.contains("set port in [processes.web]"), "{}", err);
+    assert!(manager.get_process("web").unwrap().spawn_error.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_line_types_into_the_pty_and_echoes_it_marked() {
+    if std::env::var("NO_PTY").is_ok() {
+        return;
+    }
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = std::sync::Arc::new(ProcessManager::new(tx));
+    manager
+        .spawn_process("console".into(), "cat".into(), HashMap::new())
+        .unwrap();
+
+    let senders: Vec<_> = (0..4)
+        .map(|i| {
+            let manager = manager.clone();
+            std::thread::spawn(move || {
+                manager.send_line("console", &format!("line{}", i).repeat(50))
+            })
+        })
+        .collect();
+    for sender in senders {
+        sender.join().unwrap().unwrap();
+    }
+
+    let mut sent = 0;
+    let mut read_back = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while (sent < 4 || read_back.len() < 4) && Instant::now() < deadline {
+        let Ok(Some(line)) = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await else {
+            continue;
+        };
+        if line.content.starts_with("[SENT] ") {
+            sent += 1;
+        } else if !line.content.is_empty() {
+            read_back.push(line.content);
+        }
+    }
+    assert_eq!(sent, 4);
+    // Each line arrives whole: sends never interleave
+    for content in &read_back {
+        let first = &content[..5];
+        assert_eq!(*content, first.repeat(50), "{}", content);
+    }
+    manager.stop_all();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn send_line_refuses_long_text_and_missing_or_stopped_processes() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+
+    let err = manager.send_line("web", "y").unwrap_err();
+    assert_eq!(err, "No process named 'web'");
+
+    manager
+        .spawn_process("quick".into(), "true".into(), HashMap::new())
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while manager.get_process("quick").unwrap().status == ProcessStatus::Running
+        && Instant::now() < deadline
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let err = manager.send_line("quick", "y").unwrap_err();
+    assert_eq!(err, "quick isn't running");
+
+    let err = manager
+        .send_line("quick", &"x".repeat(MAX_SEND_LEN + 1))
+        .unwrap_err();
+    assert!(err.starts_with("Too long to send"), "{}", err);
+}
+
+#[test]
+fn log_file_follower_reads_appended_lines_only() {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("caboose_follow_{}.log", std::process::id()));
+    std::fs::write(&path, "old line\n").unwrap();
+    let mut follower = LogFileFollower::new(&path);
+    assert!(follower.read_lines().is_empty());
+
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    write!(file, "Started GET \"/\"\nCompleted 200").unwrap();
+    assert_eq!(follower.read_lines(), vec!["Started GET \"/\""]);
+
+    // The rest of a line still being written arrives with its newline
+    writeln!(file, " OK in 5ms").unwrap();
+    assert_eq!(follower.read_lines(), vec!["Completed 200 OK in 5ms"]);
+
+    // Truncated (rails log:clear): read again from the start
+    std::fs::write(&path, "fresh\n").unwrap();
+    assert_eq!(follower.read_lines(), vec!["fresh"]);
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(follower.read_lines().is_empty());
+}
+
+#[test]
+fn port_from_command_reads_flags_env_and_rails_default() {
+    let no_env = HashMap::new();
+    assert_eq!(
+        port_from_command("bundle exec rails server -p 4000 -d", &no_env),
+        Some(4000)
+    );
+    assert_eq!(
+        port_from_command("npm run dev -- --port=5173", &no_env),
+        Some(5173)
+    );
+    assert_eq!(
+        port_from_command("PORT=8080 node server.js", &no_env),
+        Some(8080)
+    );
+    assert_eq!(port_from_command("bin/rails s -d", &no_env), Some(3000));
+    assert_eq!(port_from_command("bundle exec sidekiq", &no_env), None);
+
+    let env = HashMap::from([("PORT".to_string(), "9292".to_string())]);
+    assert_eq!(port_from_command("bundle exec puma", &env), Some(9292));
+}
+
+#[test]
+fn port_intent_reads_real_dev_server_commands() {
+    let no_env = HashMap::new();
+    let flag = |flag: &str| Some(PortSource::Flag(flag.to_string()));
+    let cases: &[(&str, Option<u16>, Option<PortSource>)] = &[
+        // puma, directly or through rails
+        (
+            "bundle exec puma -p 3000 -C config/puma.rb",
+            Some(3000),
+            flag("-p 3000"),
+        ),
+        (
+            "bundle exec puma -b tcp://0.0.0.0:3001",
+            Some(3001),
+            flag("-b tcp://0.0.0.0:3001"),
+        ),
+        (
+            "bundle exec puma --bind=ssl://127.0.0.1:3443?key=k.pem&cert=c.pem",
+            Some(3443),
+            flag("--bind=ssl://127.0.0.1:3443?key=k.pem&cert=c.pem"),
+        ),
+        (
+            "bin/rails server -b 0.0.0.0 -p 4000",
+            Some(4000),
+            flag("-p 4000"),
+        ),
+        (
+            "bin/rails s -b 0.0.0.0",
+            Some(3000),
+            Some(PortSource::RailsDefault),
+        ),
+        // vite
+        ("bin/vite dev --port 3036", Some(3036), flag("--port 3036")),
+        (
+            "cd client && npx vite --host --port=5174",
+            Some(5174),
+            flag("--port=5174"),
+        ),
+        // next
+        (
+            "cd web && npx next dev -p 3001",
+            Some(3001),
+            flag("-p 3001"),
+        ),
+        (
+            "yarn --cwd web next dev --port \"3002\"",
+            Some(3002),
+            flag("--port \"3002\""),
+        ),
+        // ng serve
+        (
+            "cd angular && npx ng serve --port 4201 --open",
+            Some(4201),
+            flag("--port 4201"),
+        ),
+        (
+            "npm run start -- --port=4200",
+            Some(4200),
+            flag("--port=4200"),
+        ),
+        // webpack-dev-server
+        (
+            "npx webpack-dev-server --config webpack.dev.js --port 8081",
+            Some(8081),
+            flag("--port 8081"),
+        ),
+        (
+            "npx webpack serve --mode development --port=8080",
+            Some(8080),
+            flag("--port=8080"),
+        ),
+        // PORT set in the command
+        (
+            "cd frontend && BROWSER=none PORT=3000 npm start",
+            Some(3000),
+            Some(PortSource::Assignment("PORT=3000".to_string())),
+        ),
+        // Not ports: other flags' values, and commands without one
+        ("mkdir -p tmp/pids && bundle exec sidekiq", None, None),
+        ("npx ng serve", None, None),
+        ("bin/rails tailwindcss:watch", None, None),
+    ];
+    for (command, port, source) in cases {
+        let intent = port_intent(command, &no_env);
+        assert_eq!(intent.as_ref().map(|(port, _)| *port), *port, "{}", command);
+        assert_eq!(intent.map(|(_, source)| source), *source, "{}", command);
+    }
+
+    let env = HashMap::from([("PORT".to_string(), "5000".to_string())]);
+    assert_eq!(
+        port_intent("bundle exec rails s -p $PORT", &env),
+        Some((5000, PortSource::Flag("-p $PORT".to_string())))
+    );
+    assert_eq!(port_intent("next dev", &env), Some((5000, PortSource::Env)));
+    assert_eq!(
+        port_intent("bundle exec rails s -p $PORT", &no_env),
+        Some((3000, PortSource::RailsDefault))
+    );
+}
+
+#[test]
+fn port_collisions_name_both_processes_and_their_sources() {
+    let no_env = HashMap::new();
+    let claims: Vec<PortClaim> = [
+        ("web", "bundle exec rails server"),
+        ("frontend", "cd frontend && npx vite --port 3000"),
+        ("worker", "bundle exec sidekiq"),
+        ("docs", "PORT=4000 npm run docs"),
+    ]
+    .iter()
+    .filter_map(|(name, command)| PortClaim::for_process(name, command, &no_env, None))
+    .collect();
+
+    assert_eq!(
+        port_collisions(&claims),
+        vec![
+            "Port 3000 is claimed by 'web' (the rails server default) and 'frontend' (`--port 3000` in its command)"
+                .to_string()
+        ]
+    );
+
+    // A configured port stands in for the command's
+    let claims = vec![
+        PortClaim::for_process("web", "bin/rails s -p 3000", &no_env, Some(3100)).unwrap(),
+        PortClaim::for_process("admin", "bin/rails s -p 3100", &no_env, None).unwrap(),
+        PortClaim::for_process("api", "bin/rails s", &no_env, Some(3100)).unwrap(),
+    ];
+    assert_eq!(
+        port_collisions(&claims),
+        vec![
+            "Port 3100 is claimed by 'web' (port in [processes.web]), 'admin' (`-p 3100` in its command) and 'api' (port in [processes.api])"
+                .to_string()
+        ]
+    );
+
+    let apart = [
+        PortClaim::for_process("web", "bin/rails s", &no_env, None).unwrap(),
+        PortClaim::for_process("js", "vite --port 3036", &no_env, None).unwrap(),
+    ];
+    assert!(port_collisions(&apart).is_empty());
+}
+
+#[test]
+fn listening_inodes_matches_port_and_listen_state() {
+    let proc_net_tcp = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 48213 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0BB8 0100007F:D5A2 01 00000000:00000000 00:00000000 00000000  1000        0 48999 1 0000000000000000 20 4 30 10 -1
+   2: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 51234 1 0000000000000000 100 0 0 10 0
+";
+
+    // 0x0BB8 = 3000; the established connection on the same port is ignored
+    assert_eq!(listening_inodes(proc_net_tcp, 3000), [48213]);
+    assert_eq!(listening_inodes(proc_net_tcp, 8080), [51234]);
+    assert!(listening_inodes(proc_net_tcp, 5173).is_empty());
+}
+
+#[test]
+fn parse_lsof_pids_skips_noise() {
+    assert_eq!(
+        parse_lsof_pids("4242\n\n4243\nlsof: WARNING\n"),
+        [4242, 4243]
+    );
+}
+
+#[test]
+fn daemonize_warning_names_process_and_fix() {
+    let warning = daemonize_warning("web", 3000, Some(4242));
+    assert!(warning.contains("'web' appears to have daemonized"));
+    assert!(warning.contains("pid 4242 on port 3000"));
+    assert!(warning.contains("allow_daemonize = true"));
+}
+
+fn line(content: &str, stream: LogStream, timestamp: Instant) -> LogLine {
+    LogLine {
+        timestamp,
+        stream,
+        ..LogLine::new("frontend", content)
+    }
+}
+
+/// Feed `(content, stream, ms since start)` through a deduper, collecting what it sends
+fn dedupe(input: &[(&str, LogStream, u64)]) -> Vec<(String, LogStream)> {
+    let start = Instant::now();
+    let mut deduper = StreamDeduper::new(DEDUPE_WINDOW);
+    let mut sent = Vec::new();
+    for (content, stream, ms) in input {
+        let at = start + Duration::from_millis(*ms);
+        sent.extend(deduper.push(line(content, *stream, at), at));
+    }
+    sent.extend(deduper.close_stream());
+    sent.extend(deduper.close_stream());
+    assert!(deduper.is_finished());
+    sent.into_iter().map(|l| (l.content, l.stream)).collect()
+}
+
+#[test]
+fn stream_dedupe_delivers_dual_stream_lines_once() {
+    use LogStream::{Both, Stderr, Stdout};
+    let sent = dedupe(&[
+        ("compiling...", Stdout, 0),
+        ("compiling...", Stderr, 2),
+        ("webpack compiled", Stderr, 10),
+        ("webpack compiled", Stdout, 11),
+        ("only stdout", Stdout, 12),
+    ]);
+    assert_eq!(
+        sent,
+        [
+            ("compiling...".to_string(), Both),
+            ("webpack compiled".to_string(), Both),
+            ("only stdout".to_string(), Stdout),
+        ]
+    );
+}
+
+#[test]
+fn stream_dedupe_keeps_repeats_on_the_same_stream() {
+    use LogStream::{Both, Stderr, Stdout};
+    let sent = dedupe(&[
+        ("GET /health 200", Stdout, 0),
+        ("GET /health 200", Stdout, 1),
+        ("GET /health 200", Stderr, 2),
+    ]);
+    assert_eq!(
+        sent,
+        [
+            ("GET /health 200".to_string(), Both),
+            ("GET /health 200".to_string(), Stdout),
+        ]
+    );
+}
+
+#[test]
+fn stream_dedupe_does_not_merge_copies_outside_the_window() {
+    use LogStream::{Stderr, Stdout};
+    let late = DEDUPE_WINDOW.as_millis() as u64 + 5;
+    let sent = dedupe(&[("retrying", Stdout, 0), ("retrying", Stderr, late)]);
+    assert_eq!(
+        sent,
+        [
+            ("retrying".to_string(), Stdout),
+            ("retrying".to_string(), Stderr),
+        ]
+    );
+}
+
+fn running(name: &str, start_time: Instant) -> ProcessInfo {
+    ProcessInfo {
+        name: name.to_string(),
+        command: "bin/rails server".to_string(),
+        status: ProcessStatus::Running,
+        start_time: Some(start_time),
+        pid: Some(1),
+        last_exit: None,
+        restarts: 0,
+        crash_count: 0,
+        spawn_error: None,
+    }
+}
+
+#[test]
+fn recognizes_ready_lines() {
+    assert!(is_ready_line("* Listening on http://127.0.0.1:3000"));
+    assert!(is_ready_line("Use Ctrl-C to stop"));
+    assert!(is_ready_line("  VITE v5.0.0  ready in 312 ms"));
+    assert!(is_ready_line(
+        "webpack 5.89.0 compiled successfully in 2301 ms"
+    ));
+    assert!(!is_ready_line("Started GET \"/\" for 127.0.0.1"));
+}
+
+#[test]
+fn boot_times_record_first_ready_line_per_run() {
+    let mut boots = BootTimes::new();
+    let first = Instant::now();
+    boots.track(&[running("web", first)]);
+
+    boots.observe("web", "=> Booting Puma", first + Duration::from_millis(500));
+    boots.observe(
+        "web",
+        "* Listening on http://127.0.0.1:3000",
+        first + Duration::from_secs(3),
+    );
+    // Only the first ready line of a run counts
+    boots.observe(
+        "web",
+        "* Listening on http://[::1]:3000",
+        first + Duration::from_secs(4),
+    );
+    // Lines from processes that aren't tracked are ignored
+    boots.observe("css", "Listening on 0.0.0.0", first);
+    assert_eq!(boots.history("web"), [3.0]);
+    assert!(boots.history("css").is_empty());
+
+    // A new start time is a new run; the same start time is not
+    boots.track(&[running("web", first)]);
+    let second = first + Duration::from_secs(60);
+    boots.track(&[running("web", second)]);
+    boots.observe(
+        "web",
+        "Use Ctrl-C to stop",
+        second + Duration::from_millis(1500),
+    );
+    assert_eq!(boots.history("web"), [3.0, 1.5]);
+}
+
+#[test]
+fn boot_times_report_readiness_against_a_budget() {
+    let mut boots = BootTimes::new();
+    let start = Instant::now();
+    let mut css = running("css", start);
+    css.command = "bin/rails tailwindcss:watch".to_string();
+    let processes = [running("web", start), css];
+    boots.track(&processes);
+
+    // A server with no recorded boots gets the default budget; a watcher that
+    // isn't known to print a ready line isn't held to one
+    let readiness = boots.readiness(&processes, start + Duration::from_secs(5));
+    assert_eq!(
+        readiness["web"],
+        Readiness::Booting {
+            elapsed: Duration::from_secs(5),
+            budget: DEFAULT_BOOT_BUDGET,
+        }
+    );
+    assert_eq!(readiness["css"], Readiness::Unknown);
+
+    boots.observe(
+        "web",
+        "* Listening on http://127.0.0.1:3000",
+        start + Duration::from_secs(2),
+    );
+    let readiness = boots.readiness(&processes, start + Duration::from_secs(5));
+    assert_eq!(readiness["web"], Readiness::Ready);
+
+    // Once it has booted, the budget follows its boot times
+    assert_eq!(boots.boot_budget("web"), MIN_BOOT_BUDGET);
+    let restart = start + Duration::from_secs(60);
+    boots.track(&[running("web", restart)]);
+    boots.observe(
+        "web",
+        "Use Ctrl-C to stop",
+        restart + Duration::from_secs(20),
+    );
+    assert_eq!(boots.boot_budget("web"), Duration::from_secs(60));
+}
+
+fn with_status(name: &str, status: ProcessStatus) -> ProcessInfo {
+    ProcessInfo {
+        status,
+        ..running(name, Instant::now())
+    }
+}
+
+fn booting(secs: u64) -> Readiness {
+    Readiness::Booting {
+        elapsed: Duration::from_secs(secs),
+        budget: Duration::from_secs(90),
+    }
+}
+
+fn readiness(states: &[(&str, Readiness)]) -> HashMap<String, Readiness> {
+    states
+        .iter()
+        .map(|(name, state)| (name.to_string(), *state))
+        .collect()
+}
+
+#[test]
+fn project_health_without_processes() {
+    assert_eq!(
+        project_health(&[], &HashMap::new()),
+        ProjectHealth::NoProcesses
+    );
+}
+
+#[test]
+fn project_health_all_running_and_ready_is_all_go() {
+    let processes = [
+        with_status("web", ProcessStatus::Running),
+        with_status("worker", ProcessStatus::Running),
+        with_status("css", ProcessStatus::Running),
+    ];
+    let states = readiness(&[
+        ("web", Readiness::Ready),
+        ("worker", Readiness::Ready),
+        ("css", Readiness::Unknown),
+    ]);
+
+    let health = project_health(&processes, &states);
+    assert_eq!(health, ProjectHealth::AllGo);
+    assert_eq!(health.summary(), "All systems go");
+}
+
+#[test]
+fn project_health_detached_counts_as_up() {
+    let processes = [
+        with_status("web", ProcessStatus::Detached),
+        with_status("worker", ProcessStatus::Running),
+    ];
+    let states = readiness(&[("worker", Readiness::Ready)]);
+
+    assert_eq!(project_health(&processes, &states), ProjectHealth::AllGo);
+}
+
+#[test]
+fn project_health_booting_within_budget_is_starting() {
+    let processes = [
+        with_status("web", ProcessStatus::Running),
+        with_status("worker", ProcessStatus::Running),
+    ];
+    let states = readiness(&[("web", booting(10)), ("worker", Readiness::Ready)]);
+
+    let health = project_health(&processes, &states);
+    assert_eq!(health, ProjectHealth::Starting(vec!["web".to_string()]));
+    assert_eq!(health.summary(), "Starting: web");
+}
+
+#[test]
+fn project_health_not_ready_past_budget_is_slow() {
+    let processes = [
+        with_status("web", ProcessStatus::Running),
+        with_status("frontend", ProcessStatus::Running),
+        with_status("worker", ProcessStatus::Stopped),
+    ];
+    let states = readiness(&[("web", booting(120)), ("frontend", booting(10))]);
+
+    let health = project_health(&processes, &states);
+    assert_eq!(health, ProjectHealth::SlowBoot(vec!["web".to_string()]));
+    assert_eq!(health.summary(), "Not ready: web");
+}
+
+#[test]
+fn project_health_clean_stop_outranks_starting() {
+    let processes = [
+        with_status("web", ProcessStatus::Running),
+        with_status("worker", ProcessStatus::Stopped),
+    ];
+    let states = readiness(&[("web", booting(10))]);
+
+    let health = project_health(&processes, &states);
+    assert_eq!(health, ProjectHealth::Stopped(vec!["worker".to_string()]));
+    assert_eq!(health.summary(), "Stopped: worker");
+}
+
+#[test]
+fn project_health_crash_outranks_everything_and_names_each_process() {
+    let processes = [
+        with_status("worker", ProcessStatus::Crashed),
+        with_status("web", ProcessStatus::Running),
+        with_status("frontend", ProcessStatus::Crashed),
+        with_status("css", ProcessStatus::Stopped),
+    ];
+    let states = readiness(&[("web", booting(300))]);
+
+    let health = project_health(&processes, &states);
+    assert_eq!(
+        health,
+        ProjectHealth::Down(vec!["frontend".to_string(), "worker".to_string()])
+    );
+    assert_eq!(health.summary(), "Crashed: frontend, worker");
+}
+
+#[test]
+fn project_health_ignores_stale_readiness_of_processes_not_running() {
+    // Readiness left over from a run that has since stopped cleanly
+    let processes = [with_status("web", ProcessStatus::Detached)];
+    let states = readiness(&[("web", booting(300))]);
+
+    assert_eq!(project_health(&processes, &states), ProjectHealth::AllGo);
+}
+
+#[test]
+fn long_lines_are_cut_with_a_size_marker() {
+    // Byte 27 falls inside the first 'é', which must not be split
+    let mut truncator = LineTruncator::new(27);
+
+    let mut short = line("short line", LogStream::Combined, Instant::now());
+    truncator.apply(&mut short);
+    assert_eq!(short.content, "short line");
+    assert!(short.truncated.is_none());
+
+    let original = format!("INSERT INTO t VALUES {}", "(1, 'é'),".repeat(250_000));
+    let mut long = line(&original, LogStream::Combined, Instant::now());
+    truncator.apply(&mut long);
+
+    let truncation = long.truncated.clone().unwrap();
+    assert_eq!(truncation.original_len, original.len());
+    assert_eq!(truncation.kept_len, 26);
+    assert_eq!(long.kept_content(), &original[..truncation.kept_len]);
+    assert!(
+        long.content.ends_with(" … truncated (2.4MB)"),
+        "{}",
+        long.content
+    );
+    // Without a spill file the full text is gone
+    assert_eq!(long.full_content(), long.content);
+}
+
+#[test]
+fn spilled_lines_keep_their_full_text_until_dropped() {
+    let dir = std::env::temp_dir().join(format!("caboose_spill_{}", std::process::id()));
+    let mut truncator = LineTruncator::new(8).with_spill_dir(dir.clone());
+
+    let original = "SELECT * FROM users WHERE id IN (1, 2, 3, 4, 5)";
+    let mut long = line(original, LogStream::Combined, Instant::now());
+    truncator.apply(&mut long);
+
+    assert_eq!(long.kept_content(), "SELECT *");
+    assert_eq!(long.full_content(), original);
+    assert!(dir.exists());
+
+    drop(truncator);
+    assert!(!dir.exists());
+    assert_eq!(long.full_content(), long.content);
+}
+
+/// Category of `line` as the Logs view decides it, without an exception tracker
+fn category(line: &str) -> LogCategory {
+    LogCategory::classify(
+        RailsLogParser::parse_line(line).as_ref(),
+        false,
+        FrontendLogParser::parse_line(line).as_ref(),
+    )
+}
+
+#[test]
+fn sql_mentioning_error_in_a_literal_stays_sql() {
+    assert_eq!(
+        category(
+            r#"  Event Load (0.4ms)  SELECT "events".* FROM "events" WHERE "events"."level" = 'ERROR'"#
+        ),
+        LogCategory::Sql
+    );
+    assert_eq!(
+        category(
+            r#"  Log Create (1.1ms)  INSERT INTO "logs" ("message") VALUES ('Exception: ERROR')"#
+        ),
+        LogCategory::Sql
+    );
+}
+
+#[test]
+fn log_lines_fall_into_one_category_each() {
+    assert_eq!(
+        category(r#"Started GET "/users" for 127.0.0.1 at 2024-01-01 00:00:00 +0000"#),
+        LogCategory::Http
+    );
+    assert_eq!(
+        category("Completed 200 OK in 12ms (Views: 3.0ms | ActiveRecord: 1.2ms)"),
+        LogCategory::Http
+    );
+    assert_eq!(
+        category(r#"ActionController::RoutingError (No route matches [GET] "/missing"):"#),
+        LogCategory::Error
+    );
+    assert_eq!(
+        category("  ➜  Local:   http://localhost:5173/"),
+        LogCategory::Frontend
+    );
+    assert_eq!(
+        category("[vite] hmr update /src/App.tsx"),
+        LogCategory::Frontend
+    );
+    assert_eq!(
+        category("Puma starting in single mode..."),
+        LogCategory::Other
+    );
+
+    // Whatever the exception tracker took is an error, unless it was SQL
+    assert_eq!(LogCategory::classify(None, true, None), LogCategory::Error);
+    let sql = RailsLogParser::parse_line(r#"  User Load (0.2ms)  SELECT "users".* FROM "users""#);
+    assert_eq!(
+        LogCategory::classify(sql.as_ref(), true, None),
+        LogCategory::Sql
+    );
+}
+
+#[test]
+fn process_filter_takes_sets_exclusions_and_globs() {
+    let both = ProcessFilter::parse("web, worker").unwrap();
+    assert!(both.matches("web") && both.matches("worker"));
+    assert!(!both.matches("frontend"));
+    assert_eq!(both.to_string(), "web,worker");
+
+    let except = ProcessFilter::parse("!frontend").unwrap();
+    assert!(except.matches("web"));
+    assert!(!except.matches("frontend"));
+
+    let glob = ProcessFilter::parse("web*").unwrap();
+    assert!(glob.matches("web") && glob.matches("web-2"));
+    assert!(!glob.matches("worker"));
+
+    assert!(ProcessFilter::parse("").is_err());
+    assert!(ProcessFilter::parse(" , ").is_err());
+    assert!(ProcessFilter::parse("web,!").is_err());
+}
+
+#[test]
+fn process_filter_exclusions_win_over_inclusions() {
+    for spec in ["web*,!web-2", "!web-2,web*"] {
+        let filter = ProcessFilter::parse(spec).unwrap();
+        assert!(filter.matches("web-1"), "{}", spec);
+        assert!(!filter.matches("web-2"), "{}", spec);
+        assert!(!filter.matches("worker"), "{}", spec);
+    }
+
+    // Naming a process and excluding it shows nothing of it
+    let filter = ProcessFilter::parse("web,!web").unwrap();
+    assert!(!filter.matches("web"));
+    assert!(!filter.matches("worker"));
+}
+
+#[test]
+fn setup_step_streams_output_and_reports_how_it_ended() {
+    let root = std::env::temp_dir().join(format!("caboose_setup_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let env = HashMap::from([("GREETING".to_string(), "hi".to_string())]);
+
+    let mut lines = Vec::new();
+    SetupStep::new("echo $GREETING; echo oops >&2; pwd")
+        .run(&root, &env, |line| lines.push(line.to_string()))
+        .unwrap();
+    lines.sort();
+    let root_name = root.canonicalize().unwrap().display().to_string();
+    let mut expected = vec!["hi".to_string(), "oops".into(), root_name];
+    expected.sort();
+    assert_eq!(lines, expected);
+
+    let failed = SetupStep::new("sh -c 'exit 7'").run(&root, &env, |_| {});
+    assert_eq!(
+        failed,
+        Err(SetupError::Failed {
+            command: "sh -c 'exit 7'".into(),
+            code: Some(7)
+        })
+    );
+    assert!(
+        failed
+            .unwrap_err()
+            .to_string()
+            .contains("exited with code 7")
+    );
+
+    let started = Instant::now();
+    let slow = SetupStep::new("sleep 5")
+        .with_timeout(Duration::from_millis(200))
+        .run(&root, &env, |_| {});
+    assert!(
+        matches!(slow, Err(SetupError::TimedOut { .. })),
+        "{:?}",
+        slow
+    );
+    assert!(started.elapsed() < Duration::from_secs(4));
+
+    // Skipped while the stamp exists, which is written on request
+    let step = SetupStep::new("true").with_unless_file("tmp/setup-done");
+    assert!(!step.is_done(&root));
+    step.touch_stamp(&root).unwrap();
+    assert!(step.is_done(&root));
+    assert!(!SetupStep::new("true").is_done(&root));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn boot_times_leave_a_suspension_out_of_an_interrupted_boot() {
+    let mut boots = BootTimes::new();
+    let start = Instant::now();
+    let processes = [running("web", start), running("api", start)];
+    boots.track(&processes);
+    boots.observe(
+        "api",
+        "* Listening on http://127.0.0.1:4000",
+        start + Duration::from_secs(2),
+    );
+
+    // The monotonic clock counted a 2h sleep halfway through web's boot
+    boots.resume(Duration::from_secs(7200));
+    let woke = start + Duration::from_secs(7205);
+    let readiness = boots.readiness(&processes, woke);
+    assert_eq!(
+        readiness["web"],
+        Readiness::Booting {
+            elapsed: Duration::from_secs(5),
+            budget: DEFAULT_BOOT_BUDGET,
+        }
+    );
+    assert_eq!(readiness["api"], Readiness::Ready);
+
+    boots.observe(
+        "web",
+        "* Listening on http://127.0.0.1:3000",
+        woke + Duration::from_secs(3),
+    );
+    assert_eq!(boots.history("web"), [8.0]);
+    assert_eq!(boots.history("api"), [2.0]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recheck_liveness_calls_out_a_ready_process_that_stopped_listening() {
+    // A port nothing listens on
+    let port = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager
+        .spawn_process(
+            "web".into(),
+            "sleep 5".into(),
+            HashMap::from([("PORT".to_string(), port.to_string())]),
+        )
+        .unwrap();
+    manager
+        .spawn_process("worker".into(), "sleep 5".into(), HashMap::new())
+        .unwrap();
+
+    // Still booting, it isn't expected to listen yet
+    let booting = HashMap::from([("web".to_string(), booting(3))]);
+    assert!(manager.recheck_liveness(&booting).is_empty());
+
+    let ready = HashMap::from([
+        ("web".to_string(), Readiness::Ready),
+        ("worker".to_string(), Readiness::Ready),
+    ]);
+    assert_eq!(manager.recheck_liveness(&ready), ["web"]);
+    let note = rx.recv().await.unwrap();
+    assert_eq!(note.process_name, "web");
+    assert_eq!(
+        note.content,
+        format!(
+            "No longer listening on port {} since the system was suspended",
+            port
+        )
+    );
+    // Only said, not acted on: it may still come back
+    assert_eq!(
+        manager.get_process("web").unwrap().status,
+        ProcessStatus::Running
+    );
+    manager.stop_all();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recheck_liveness_reaps_a_child_that_exited_while_asleep() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager
+        .spawn_process("web".into(), "sleep 0".into(), HashMap::new())
+        .unwrap();
+    manager
+        .spawn_process("worker".into(), "sleep 5".into(), HashMap::new())
+        .unwrap();
+    // Give the short-lived child time to exit, but check before its monitor
+    // can be relied on to have noticed
+    std::thread::sleep(Duration::from_millis(50));
+
+    let gone = manager.recheck_liveness(&HashMap::new());
+    if gone.is_empty() {
+        // Its monitor got there first; nothing left for the recheck
+        assert_ne!(
+            manager.get_process("web").unwrap().status,
+            ProcessStatus::Running
+        );
+    } else {
+        assert_eq!(gone, ["web"]);
+        let note = rx.recv().await.unwrap();
+        assert_eq!(
+            (note.process_name.as_str(), note.content.as_str()),
+            ("web", "Exited while the system was suspended")
+        );
+    }
+    manager.stop_all();
+}
+
+fn state_fixture() -> String {
+    std::fs::read_to_string("tests/fixtures/state/state.json").unwrap()
+}
+
+#[test]
+fn ps_lists_the_session_processes_and_flags_dead_pids() {
+    let state: SessionState = serde_json::from_str(&state_fixture()).unwrap();
+    let now = DateTime::parse_from_rfc3339("2024-05-14T14:02:03+00:00")
+        .unwrap()
+        .with_timezone(&Local);
+
+    // css's pid is gone though the session still has it running
+    let table = format_ps(&state, now, |pid| pid != 4244);
+    assert_eq!(
+        table,
+        std::fs::read_to_string("tests/fixtures/state/ps.txt").unwrap()
+    );
+}
+
+#[test]
+fn running_session_removes_a_stale_state_file() {
+    let dir = std::env::temp_dir().join(format!("caboose_state_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(STATE_FILE);
+    assert_eq!(running_session(&path, |_| true), None);
+
+    std::fs::write(&path, state_fixture()).unwrap();
+    let state = running_session(&path, |pid| pid == 4100).unwrap();
+    assert_eq!(state.processes.len(), 3);
+    assert!(path.exists());
+
+    // The Caboose that wrote it is gone
+    assert_eq!(running_session(&path, |pid| pid != 4100), None);
+    assert!(!path.exists());
+
+    // So is a file that can't be read
+    std::fs::write(&path, "{ not json").unwrap();
+    assert_eq!(running_session(&path, |_| true), None);
+    assert!(!path.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn state_file_follows_the_processes_until_stop_all() {
+    let dir = std::env::temp_dir().join(format!("caboose_state_live_{}", std::process::id()));
+    let path = dir.join(STATE_FILE);
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager.keep_state_file(path.clone());
+    tokio::time::sleep(STATE_WRITE_INTERVAL).await;
+    let state = running_session(&path, is_process_alive).unwrap();
+    assert_eq!(state.pid, std::process::id());
+    assert!(state.processes.is_empty());
+
+    manager
+        .adopt_process("web".into(), "bin/rails server".into(), Some(port), None)
+        .unwrap();
+    tokio::time::sleep(STATE_WRITE_INTERVAL * 3).await;
+    let state = running_session(&path, is_process_alive).unwrap();
+    assert_eq!(state.processes.len(), 1);
+    let web = &state.processes[0];
+    assert_eq!(
+        (web.name.as_str(), web.command.as_str(), &web.status),
+        ("web", "bin/rails server", &ProcessStatus::External)
+    );
+    assert_eq!(web.pid, manager.get_process("web").unwrap().pid);
+
+    manager.stop_all();
+    assert!(!path.exists());
+    drop(listener);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A dummy child for `caboose stop` to stop, run through `sh -c`
+fn dummy(script: &str) -> (std::process::Child, u32) {
+    let child = std::process::Command::new("sh")
+        .args(["-c", script])
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    // Let the shell set up its traps before it's signalled
+    std::thread::sleep(Duration::from_millis(200));
+    (child, pid)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn caboose_stop_tells_the_session_so_nothing_crashes_or_restarts() {
+    let dir = std::env::temp_dir().join(format!("caboose_stop_request_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(STOP_REQUEST_FILE);
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let rule = RestartRule {
+        max_restarts: 3,
+        backoff: Duration::from_millis(100),
+    };
+    let manager = std::sync::Arc::new(
+        ProcessManager::new(tx).with_restart_rules([("web".to_string(), rule)]),
+    );
+    manager.follow_restart_policies();
+    manager.follow_stop_request(path.clone());
+    manager
+        .spawn_process("web".into(), "sleep 30".into(), HashMap::new())
+        .unwrap();
+    let pid = wait_for_status(&manager, "web", ProcessStatus::Running)
+        .await
+        .pid
+        .unwrap();
+
+    // As `caboose stop` does it, from another process
+    let stopping = path.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        request_stop(&stopping, STOP_REQUEST_WAIT).unwrap();
+        stop_processes(&[("web".to_string(), pid)], Duration::from_secs(2))
+    })
+    .await
+    .unwrap();
+    assert_eq!(results[0].outcome, StopOutcome::Stopped);
+    assert!(!path.exists());
+
+    // Past the backoff a crash would have been restarted after
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let web = manager.get_process("web").unwrap();
+    let pending = manager.is_restart_pending("web");
+    manager.stop_all();
+    assert_eq!(web.status, ProcessStatus::Stopped);
+    assert_eq!((web.restarts, web.crash_count, pending), (0, 0, false));
+    while let Ok(line) = rx.try_recv() {
+        assert!(!line.content.starts_with("web exited"), "{}", line.content);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stop_targets_skip_adopted_and_pidless_processes() {
+    let state: SessionState = serde_json::from_str(&state_fixture()).unwrap();
+    assert_eq!(
+        stop_targets(&state),
+        vec![("css".to_string(), 4244), ("web".to_string(), 4242)]
+    );
+
+    let mut state = state;
+    state.processes[0].status = ProcessStatus::External;
+    assert_eq!(stop_targets(&state), vec![("web".to_string(), 4242)]);
+}
+
+#[test]
+fn stop_terminates_then_kills_what_ignores_sigterm() {
+    let (mut polite, polite_pid) = dummy("exec sleep 30");
+    let (mut stubborn, stubborn_pid) = dummy("trap '' TERM; exec sleep 30");
+    let (mut gone, gone_pid) = dummy("exit 0");
+    gone.wait().unwrap();
+
+    let targets = vec![
+        ("web".to_string(), polite_pid),
+        ("worker".to_string(), stubborn_pid),
+        ("css".to_string(), gone_pid),
+    ];
+    let started = Instant::now();
+    let results = stop_processes(&targets, Duration::from_millis(500));
+    assert!(started.elapsed() >= Duration::from_millis(500));
+    let outcomes: Vec<_> = results.iter().map(|result| &result.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            &StopOutcome::Stopped,
+            &StopOutcome::Killed,
+            &StopOutcome::NotRunning
+        ]
+    );
+    assert_eq!(results[1].describe(), "worker: killed after timeout");
+
+    polite.wait().unwrap();
+    stubborn.wait().unwrap();
+}
+
+#[test]
+fn stop_without_a_grace_period_kills_right_away() {
+    let (mut child, pid) = dummy("trap '' TERM; exec sleep 30");
+    let started = Instant::now();
+    let results = stop_processes(&[("web".to_string(), pid)], Duration::ZERO);
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(results[0].outcome, StopOutcome::ForceKilled);
+    assert_eq!(results[0].describe(), "web: killed");
+    child.wait().unwrap();
+}
+
+/// Wait up to 5s for `name` to get a pid and reach `status`
+async fn wait_for_status(
+    manager: &ProcessManager,
+    name: &str,
+    status: ProcessStatus,
+) -> ProcessInfo {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let info = manager.get_process(name).unwrap();
+        if (info.status == status && info.pid.is_some()) || Instant::now() >= deadline {
+            return info;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restart_process_starts_a_new_run_and_leaves_the_others() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    let command = "sh -c 'echo run $$; while true; do sleep 1; done'";
+    for name in ["web", "css"] {
+        manager
+            .spawn_process(name.into(), command.into(), HashMap::new())
+            .unwrap();
+    }
+    let first_run = next_web_line(&mut rx).await;
+    let web = manager.get_process("web").unwrap();
+    let css = wait_for_status(&manager, "css", ProcessStatus::Running).await;
+
+    manager.restart_process("web").unwrap();
+    assert_eq!(next_web_line(&mut rx).await.as_deref(), Some("Restarting"));
+    let second_run = next_web_line(&mut rx).await;
+    // Long enough for the old run's exit to be noticed
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let restarted = manager.get_process("web").unwrap();
+    let untouched = manager.get_process("css").unwrap();
+    manager.stop_all();
+
+    // Both runs log to the same process
+    assert!(
+        first_run
+            .as_deref()
+            .is_some_and(|line| line.starts_with("run "))
+    );
+    assert!(
+        second_run
+            .as_deref()
+            .is_some_and(|line| line.starts_with("run "))
+    );
+    assert_ne!(first_run, second_run);
+    // The old run's exit wasn't taken for a crash of the new one
+    assert_eq!(restarted.status, ProcessStatus::Running);
+    assert_eq!(restarted.restarts, 1);
+    assert!(restarted.pid.is_some() && restarted.pid != web.pid);
+    assert!(restarted.start_time > web.start_time);
+    assert_eq!((untouched.pid, untouched.restarts), (css.pid, 0));
+}
+
+/// The next `run <pid>` or `Restarting` line logged by "web", within 5s
+async fn next_web_line(rx: &mut tokio::sync::mpsc::UnboundedReceiver<LogLine>) -> Option<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Ok(Some(line)) = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await
+            && line.process_name == "web"
+            && (line.content.starts_with("run ") || line.content == "Restarting")
+        {
+            return Some(line.content);
+        }
+    }
+    None
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restarting_a_crashed_process_clears_the_crash() {
+    let dir = std::env::temp_dir().join(format!("caboose_restart_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let flag = dir.join("crashed-once");
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    // Crashes the first time only
+    let command = format!(
+        "sh -c 'if [ -e {0} ]; then while true; do sleep 1; done; else touch {0}; exit 3; fi'",
+        flag.display()
+    );
+    manager
+        .spawn_process("worker".into(), command, HashMap::new())
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while manager.get_process("worker").unwrap().status != ProcessStatus::Crashed {
+        assert!(Instant::now() < deadline, "never crashed");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    manager.restart_process("worker").unwrap();
+    let worker = wait_for_status(&manager, "worker", ProcessStatus::Running).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let worker_now = manager.get_process("worker").unwrap();
+    manager.stop_all();
+
+    assert_eq!(worker_now.status, ProcessStatus::Running);
+    assert_eq!(worker_now.pid, worker.pid);
+    assert_eq!(worker_now.restarts, 1);
+    assert_eq!(worker_now.last_exit.map(|exit| exit.code), Some(Some(3)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stop_process_stops_one_and_refuses_adopted_ones() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager
+        .spawn_process(
+            "web".into(),
+            "sh -c 'while true; do sleep 1; done'".into(),
+            HashMap::new(),
+        )
+        .unwrap();
+    let web = wait_for_status(&manager, "web", ProcessStatus::Running).await;
+
+    manager.stop_process("web").unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let stopped = manager.get_process("web").unwrap();
+    // Killed, but not a crash
+    assert_eq!(
+        (stopped.status, stopped.pid),
+        (ProcessStatus::Stopped, None)
+    );
+    assert!(!is_process_alive(web.pid.unwrap()));
+
+    assert_eq!(
+        manager.restart_process("nope"),
+        Err("No process named 'nope'".to_string())
+    );
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    manager
+        .adopt_process("rails".into(), "bin/rails server".into(), Some(port), None)
+        .unwrap();
+    assert!(manager.restart_process("rails").is_err());
+    assert!(manager.stop_process("rails").is_err());
+    manager.stop_all();
+}
+
+#[test]
+fn restart_requests_are_checked_taken_in_order_and_removed() {
+    let dir = std::env::temp_dir().join(format!("caboose_requests_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(RESTART_REQUESTS_FILE);
+    let mut state: SessionState = serde_json::from_str(&state_fixture()).unwrap();
+    state.processes[0].status = ProcessStatus::External;
+
+    assert!(take_restart_requests(&path).is_empty());
+    request_restart(&path, &state, "worker").unwrap();
+    request_restart(&path, &state, "web").unwrap();
+    request_restart(&path, &state, "worker").unwrap();
+    assert_eq!(
+        request_restart(&path, &state, "nope"),
+        Err("No process named 'nope' (the session has: css, web, worker)".to_string())
+    );
+    assert!(request_restart(&path, &state, "css").is_err());
+
+    assert_eq!(take_restart_requests(&path), ["worker", "web"]);
+    assert!(!path.exists());
+    assert!(take_restart_requests(&path).is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn restart_backoff_doubles_up_to_a_cap() {
+    let rule = RestartRule {
+        max_restarts: 50,
+        backoff: Duration::from_millis(1000),
+    };
+    let delays: Vec<u64> = (1..=4).map(|n| rule.delay(n).as_millis() as u64).collect();
+    assert_eq!(delays, [1000, 2000, 4000, 8000]);
+    assert_eq!(rule.delay(7), MAX_RESTART_BACKOFF);
+    assert_eq!(rule.delay(50), MAX_RESTART_BACKOFF);
+    assert_eq!(
+        restart_note("web", Some(1), 2, &RestartRule::default()),
+        "web exited with code 1, restarting (attempt 2/5)"
+    );
+    assert_eq!(
+        gave_up_note("web", None, &RestartRule::default()),
+        "web was killed, not restarting after 5 attempts"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn crashing_process_is_restarted_with_backoff_until_the_last_attempt() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let rule = RestartRule {
+        max_restarts: 3,
+        backoff: Duration::from_millis(200),
+    };
+    let manager = std::sync::Arc::new(
+        ProcessManager::new(tx).with_restart_rules([("job".to_string(), rule)]),
+    );
+    manager.follow_restart_policies();
+    manager
+        .spawn_process("job".into(), "false".into(), HashMap::new())
+        .unwrap();
+
+    // One note per crash, the last one giving up
+    let mut notes = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while notes.len() < 4 && Instant::now() < deadline {
+        if let Ok(Some(line)) = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await
+            && line.process_name == "job"
+            && line.content.starts_with("job exited")
+        {
+            notes.push((line.content, line.timestamp));
+        }
+    }
+    let job = manager.get_process("job").unwrap();
+    let pending = manager.is_restart_pending("job");
+    manager.stop_all();
+
+    let messages: Vec<&str> = notes.iter().map(|(content, _)| content.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "job exited with code 1, restarting (attempt 1/3)",
+            "job exited with code 1, restarting (attempt 2/3)",
+            "job exited with code 1, restarting (attempt 3/3)",
+            "job exited with code 1, not restarting after 3 attempts",
+        ]
+    );
+    // Each attempt waits twice as long as the one before
+    for (n, pair) in notes.windows(2).enumerate() {
+        let waited = pair[1].1.duration_since(pair[0].1);
+        assert!(
+            waited >= rule.delay(n as u32 + 1),
+            "attempt {} came after {:?}",
+            n + 1,
+            waited
+        );
+    }
+    assert_eq!(job.status, ProcessStatus::Crashed);
+    assert_eq!((job.crash_count, job.restarts), (4, 3));
+    assert_eq!(job.last_exit.map(|exit| exit.code), Some(Some(1)));
+    assert!(!pending);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stopping_a_process_cancels_its_crash_restart() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let rule = RestartRule {
+        max_restarts: 3,
+        backoff: Duration::from_millis(300),
+    };
+    let manager = std::sync::Arc::new(
+        ProcessManager::new(tx).with_restart_rules([("job".to_string(), rule)]),
+    );
+    manager.follow_restart_policies();
+    manager
+        .spawn_process("job".into(), "false".into(), HashMap::new())
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !manager.is_restart_pending("job") && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(manager.is_restart_pending("job"));
+
+    manager.stop_process("job").unwrap();
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    let job = manager.get_process("job").unwrap();
+    manager.stop_all();
+    assert_eq!((job.status, job.restarts), (ProcessStatus::Stopped, 0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn start_process_starts_a_stopped_one_and_refuses_a_running_one() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager
+        .spawn_process(
+            "worker".into(),
+            "sh -c 'while true; do sleep 1; done'".into(),
+            HashMap::new(),
+        )
+        .unwrap();
+    let first = wait_for_status(&manager, "worker", ProcessStatus::Running).await;
+    let while_running = manager.start_process("worker");
+
+    manager.stop_process("worker").unwrap();
+    let started = manager.start_process("worker");
+    let second = wait_for_status(&manager, "worker", ProcessStatus::Running).await;
+    let unknown = manager.start_process("nope");
+    manager.stop_all();
+
+    assert_eq!(while_running, Err("worker is already running".to_string()));
+    assert_eq!(started, Ok(()));
+    assert_eq!(second.status, ProcessStatus::Running);
+    assert!(second.pid.is_some() && second.pid != first.pid);
+    assert_eq!(unknown, Err("No process named 'nope'".to_string()));
+}
+
+#[test]
+fn last_lines_keeps_the_end_of_the_log() {
+    let text = "one\ntwo\nthree\n";
+    assert_eq!(last_lines(text, 2), ["two", "three"]);
+    assert_eq!(last_lines(text, 10), ["one", "two", "three"]);
+    assert!(last_lines(text, 0).is_empty());
+    assert!(last_lines("", 5).is_empty());
+}
+
+#[test]
+fn process_log_files_roll_over_and_the_backlog_reaches_into_the_old_file() {
+    let dir = std::env::temp_dir().join(format!("caboose_log_files_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    // Room for two 6-byte lines per file
+    let mut files = ProcessLogFiles::new(&dir).with_max_bytes(12);
+    for line in ["line1", "line2", "line3", "line4", "line5"] {
+        files.append("web", line).unwrap();
+    }
+    files.append("worker", "job").unwrap();
+
+    let path = process_log_path(&dir, "web");
+    let current = std::fs::read_to_string(&path).unwrap();
+    let rolled = std::fs::read_to_string(rolled_over_path(&path)).unwrap();
+    // One rollover is kept; line1 and line2 went with the one before
+    assert_eq!(
+        (rolled.as_str(), current.as_str()),
+        ("line3\nline4\n", "line5\n")
+    );
+    assert_eq!(
+        read_backlog(&path, 2).unwrap(),
+        ["line4".to_string(), "line5".to_string()]
+    );
+    assert_eq!(read_backlog(&path, 10).unwrap().len(), 3);
+    assert_eq!(logged_processes(&dir), ["web", "worker"]);
+    // A name can't reach outside the directory
+    assert_eq!(process_log_path(&dir, "../web"), dir.join(".._web.log"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn process_output_is_kept_in_its_log_file() {
+    let dir = std::env::temp_dir().join(format!("caboose_tee_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx).with_log_files(ProcessLogFiles::new(&dir));
+    manager
+        .spawn_process(
+            "web".into(),
+            "sh -c 'echo booted; while true; do sleep 1; done'".into(),
+            HashMap::new(),
+        )
+        .unwrap();
+    // Lines still reach the channel, after the file
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut seen = false;
+    while !seen && Instant::now() < deadline {
+        if let Ok(Some(line)) = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await {
+            seen = line.content == "booted";
+        }
+    }
+    manager.stop_all();
+
+    assert!(seen);
+    let kept = std::fs::read_to_string(process_log_path(&dir, "web")).unwrap();
+    assert!(kept.lines().any(|line| line == "booted"), "{}", kept);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn split_args_follows_shell_quoting() {
+    assert_eq!(
+        split_args(r#"rails runner "puts 'hi'""#).unwrap(),
+        argv(&["rails", "runner", "puts 'hi'"])
+    );
+    assert_eq!(
+        split_args(r#"  echo 'a  b' c\ d "q\"x" '' "#).unwrap(),
+        argv(&["echo", "a  b", "c d", "q\"x", ""])
+    );
+    assert_eq!(
+        split_args("--name='two words'x").unwrap(),
+        argv(&["--name=two wordsx"])
+    );
+    assert!(split_args("").unwrap().is_empty());
+    assert!(split_args("echo 'open").is_err());
+    assert!(split_args(r#"echo "open"#).is_err());
+}
+
+#[test]
+fn shell_join_quotes_only_what_needs_it_and_splits_back() {
+    let args = argv(&[
+        "bin/rails",
+        "runner",
+        "puts 'it''s'",
+        "",
+        "&&",
+        "a;b",
+        "$HOME",
+    ]);
+    let joined = shell_join(&args);
+    assert!(joined.starts_with("bin/rails runner 'puts "), "{}", joined);
+    assert!(joined.contains(" && 'a;b' '$HOME'"), "{}", joined);
+    assert_eq!(split_args(&joined).unwrap(), args);
+}
+
+#[test]
+fn task_names_come_from_what_runs() {
+    for (command, name) in [
+        ("rails db:migrate", "task:db-migrate"),
+        ("bin/rails routes", "task:routes"),
+        ("bundle exec rspec spec/models", "task:rspec"),
+        ("yarn build", "task:build"),
+        ("npm run lint -- --fix", "task:lint"),
+        ("./script/Seed_Data.sh", "task:seed-data-sh"),
+        ("rails", "task:rails"),
+    ] {
+        assert_eq!(
+            task_name(&split_args(command).unwrap()),
+            name,
+            "{}",
+            command
+        );
+    }
+}
+
+#[test]
+fn run_requests_are_taken_once_in_order() {
+    let path = std::env::temp_dir().join(format!("caboose_run_requests_{}", std::process::id()));
+    assert!(take_run_requests(&path).is_empty());
+
+    request_run(&path, &argv(&["rails", "db:migrate"])).unwrap();
+    request_run(&path, &argv(&["rails", "runner", "puts 'hi'"])).unwrap();
+    assert!(request_run(&path, &[]).is_err());
+
+    assert_eq!(
+        take_run_requests(&path),
+        vec![
+            argv(&["rails", "db:migrate"]),
+            argv(&["rails", "runner", "puts 'hi'"])
+        ]
+    );
+    assert!(!path.exists());
+    assert!(take_run_requests(&path).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_tasks_are_run_through_the_shell_for_their_quotes() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager = ProcessManager::new(tx);
+    manager
+        .spawn_process("web".into(), r#"echo "a  b""#.into(), HashMap::new())
+        .unwrap();
+    let task = manager.run_task(&argv(&["echo", "a  b"])).unwrap();
+
+    // Split on whitespace, the quotes are echoed; through the shell, not
+    let mut expected = vec![
+        (task, "a  b".to_string()),
+        ("web".to_string(), r#""a b""#.to_string()),
+    ];
+    while !expected.is_empty()
+        && let Ok(Some(line)) = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await
+    {
+        expected.retain(|(name, content)| {
+            (name, content.as_str()) != (&line.process_name, line.content.trim())
+        });
+    }
+    manager.stop_all();
+    assert!(expected.is_empty(), "not logged: {:?}", expected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn finished_tasks_show_their_exit_then_are_removed() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let manager =
+        ProcessManager::new(tx).with_task_env(HashMap::from([("GREETING".into(), "hi".into())]));
+
+    let name = manager
+        .run_task(&argv(&["printenv", "GREETING", "&&", "exit", "3"]))
+        .unwrap();
+    assert_eq!(name, "task:printenv");
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while manager.get_process(&name).unwrap().status == ProcessStatus::Running
+        && Instant::now() < deadline
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let mut lines = Vec::new();
+    while let Ok(Some(line)) = tokio::time::timeout(Duration::from_secs(1), rx.recv()).await {
+        assert_eq!(line.process_name, name);
+        lines.push(line.content);
+    }
+    assert_eq!(
+        lines.first().map(String::as_str),
+        Some("Running printenv GREETING && exit 3")
+    );
+    assert!(lines.iter().any(|line| line.trim() == "hi"), "{:?}", lines);
+    assert!(
+        lines
+            .last()
+            .unwrap()
+            .starts_with("Failed: exited with code 3 after "),
+        "{:?}",
+        lines
+    );
+
+    let exit = manager.get_process(&name).unwrap().last_exit.unwrap();
+    assert_eq!((exit.success, exit.code), (false, Some(3)));
+    assert!(manager.remove_finished_tasks(Instant::now()).is_empty());
+    assert_eq!(
+        manager.remove_finished_tasks(exit.at + FINISHED_TASK_TTL),
+        vec![name.clone()]
+    );
+    assert!(manager.get_process(&name).is_none());
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::PathBuf;
+
+use caboose::project::{ProjectRoot, RecentProjects};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let uniq = format!(
+        "caboose_project_{}_{}",
+        name,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    dir.push(uniq);
+    dir
+}
+
+#[test]
+fn explicit_path_resolves_to_canonical_root() {
+    let root = temp_dir("explicit");
+    fs::create_dir_all(root.join("nested")).unwrap();
+
+    let project = ProjectRoot::resolve(Some(&root.join("nested/.."))).unwrap();
+    assert_eq!(project.path(), root.canonicalize().unwrap());
+    assert_eq!(project.join("Procfile"), project.path().join("Procfile"));
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn rejects_missing_or_file_paths() {
+    let root = temp_dir("invalid");
+    assert!(ProjectRoot::new(&root).is_err());
+
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("Gemfile"), "").unwrap();
+    assert!(ProjectRoot::new(root.join("Gemfile")).is_err());
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn data_dir_is_created_with_gitignore() {
+    let root = temp_dir("data");
+    fs::create_dir_all(&root).unwrap();
+
+    let project = ProjectRoot::new(&root).unwrap();
+    let data_dir = project.ensure_data_dir().unwrap();
+    assert_eq!(data_dir, project.path().join(".caboose"));
+    assert_eq!(
+        fs::read_to_string(data_dir.join(".gitignore")).unwrap(),
+        "*\n"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn recent_projects_dedupe_and_persist() {
+    let root = temp_dir("recent");
+    fs::create_dir_all(root.join("a")).unwrap();
+    fs::create_dir_all(root.join("b")).unwrap();
+    let a = ProjectRoot::new(root.join("a")).unwrap();
+    let b = ProjectRoot::new(root.join("b")).unwrap();
+
+    let mut recent = RecentProjects::default();
+    recent.record(&a);
+    recent.record(&b);
+    recent.record(&a);
+
+    assert_eq!(recent.projects.len(), 2);
+    assert_eq!(recent.get(1).unwrap().path, a.path());
+    assert_eq!(recent.get(2).unwrap().path, b.path());
+    assert!(recent.get(0).is_none());
+
+    let file = root.join("data/recent_projects.json");
+    recent.save_to(&file).unwrap();
+    let loaded = RecentProjects::load_from(&file);
+    assert_eq!(loaded.projects, recent.projects);
+
+    let _ = fs::remove_dir_all(root);
+}
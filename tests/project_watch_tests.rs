@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use caboose::rails::routes::parse_routes;
+use caboose::rails::watch::{ProjectChange, ProjectFile, read_change, spawn_project_watcher};
+
+fn temp_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("caboose_watch_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("db")).unwrap();
+    fs::create_dir_all(dir.join("config")).unwrap();
+    dir
+}
+
+const SCHEMA: &str = r#"ActiveRecord::Schema[7.1].define(version: 2024_05_01_000000) do
+  create_table "users", force: :cascade do |t|
+    t.string "email"
+    t.index ["email"], name: "index_users_on_email", unique: true
+  end
+
+  create_table "posts", force: :cascade do |t|
+    t.bigint "user_id"
+  end
+end
+"#;
+
+#[test]
+fn routes_summary_counts_definitions_and_mounts() {
+    let routes = parse_routes(
+        r#"Rails.application.routes.draw do
+  mount Sidekiq::Web => "/sidekiq"
+  mount Blazer::Engine, at: "/blazer"
+  root "home#index"
+  resources :users do
+    resources :posts, only: [:index]
+  end
+  get "up" => "rails/health#show", as: :rails_health_check
+  # get "commented" => "out#route"
+  post("/webhooks", to: "webhooks#create")
+end
+"#,
+    );
+
+    assert_eq!(routes.definitions, 5);
+    assert_eq!(routes.mounts, ["/sidekiq", "/blazer"]);
+}
+
+#[test]
+fn read_change_parses_each_file() {
+    let root = temp_project("read");
+    fs::write(root.join("db/schema.rb"), SCHEMA).unwrap();
+    fs::write(
+        root.join("config/routes.rb"),
+        "  get \"up\" => \"health#show\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("config/database.yml"),
+        "development:\n  adapter: mysql2\n",
+    )
+    .unwrap();
+
+    let schema = read_change(&root, ProjectFile::Schema);
+    assert!(matches!(&schema, ProjectChange::Schema(tables) if tables.len() == 2));
+    assert_eq!(schema.message(), "schema.rb changed — reloaded 2 tables");
+
+    let routes = read_change(&root, ProjectFile::Routes);
+    assert_eq!(routes.message(), "routes.rb changed — 1 route definition");
+
+    let database = read_change(&root, ProjectFile::DatabaseConfig);
+    assert!(matches!(
+        &database,
+        ProjectChange::DatabaseConfig { adapter } if adapter.as_deref() == Some("mysql")
+    ));
+
+    fs::remove_file(root.join("config/routes.rb")).unwrap();
+    let removed = read_change(&root, ProjectFile::Routes);
+    assert!(matches!(
+        removed,
+        ProjectChange::Removed(ProjectFile::Routes)
+    ));
+    assert_eq!(
+        removed.message(),
+        "routes.rb removed — keeping what was read from it"
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn read_change_waits_for_a_file_replaced_by_an_atomic_write() {
+    let root = temp_project("atomic");
+    let schema = root.join("db/schema.rb");
+
+    let writer = {
+        let schema = schema.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(150));
+            let tmp = schema.with_extension("rb.tmp");
+            fs::write(&tmp, SCHEMA).unwrap();
+            fs::rename(&tmp, &schema).unwrap();
+        })
+    };
+
+    // The file is missing when the change is read, and back shortly after
+    let change = read_change(&root, ProjectFile::Schema);
+    writer.join().unwrap();
+    assert!(matches!(change, ProjectChange::Schema(tables) if tables.len() == 2));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn watcher_reports_a_burst_of_writes_once() {
+    let root = temp_project("burst");
+    fs::write(root.join("db/schema.rb"), "").unwrap();
+
+    let mut rx = spawn_project_watcher(root.clone(), Duration::from_millis(200)).unwrap();
+    for _ in 0..3 {
+        fs::write(root.join("db/schema.rb"), SCHEMA).unwrap();
+        fs::write(root.join("db/seeds.rb"), "# not watched").unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let change = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(change, ProjectChange::Schema(tables) if tables.len() == 2));
+
+    // Nothing else: the burst was one change, and seeds.rb isn't watched
+    let more = tokio::time::timeout(Duration::from_millis(500), rx.recv()).await;
+    assert!(more.is_err(), "{:?}", more);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn watcher_needs_a_db_or_config_directory() {
+    let root = std::env::temp_dir().join(format!("caboose_watch_none_{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let err = runtime
+        .block_on(async { spawn_project_watcher(root.clone(), Duration::from_millis(10)) })
+        .unwrap_err();
+    assert!(err.contains("no db/ or config/"), "{}", err);
+
+    fs::remove_dir_all(&root).unwrap();
+}
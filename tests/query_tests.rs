@@ -1,6 +1,14 @@
+use std::collections::HashMap;
+
+use caboose::context::RequestContextTracker;
+use caboose::database::SqlDialect;
+use caboose::database::schema::parse_schema;
+use caboose::parser::RailsLogParser;
+use caboose::privacy::Privacy;
 use caboose::query::{
-    NPlusOneDetector, PerformanceIssue, QueryAnalyzer, QueryFingerprint, QueryInfo, QueryType,
-    RequestContext,
+    Confidence, FingerprintOptions, MAX_FINGERPRINT_INPUT, NPlusOneDetector, PerformanceIssue,
+    QueryAnalyzer, QueryFingerprint, QueryInfo, QueryType, RequestContext, infer_eager_load,
+    requests_to_sql, selects_star, singularize, split_binds, statement_to_sql, substitute_binds,
 };
 
 fn sample_select(duration: f64) -> QueryInfo {
@@ -12,6 +20,9 @@ fn sample_select(duration: f64) -> QueryInfo {
         duration,
         rows: None,
         query_type: QueryType::Select,
+        binds: Vec::new(),
+        name: None,
+        truncated: None,
     }
 }
 
@@ -20,8 +31,234 @@ fn fingerprint_normalizes_values() {
     let fp = QueryFingerprint::new("SELECT * FROM users WHERE id = 123 AND name = 'John'");
     assert_eq!(
         fp.normalized,
-        "SELECT * FROM users WHERE id = ? AND name = ?"
+        "select * from users where id = ? and name = ?"
+    );
+}
+
+fn fingerprint(sql: &str) -> String {
+    QueryFingerprint::new(sql).normalized
+}
+
+#[test]
+fn fingerprint_collapses_value_lists() {
+    assert_eq!(
+        fingerprint(r#"SELECT "posts".* FROM "posts" WHERE "posts"."user_id" IN (1, 2, 3)"#),
+        r#"select "posts".* from "posts" where "posts"."user_id" in (?)"#
+    );
+    assert_eq!(
+        fingerprint("INSERT INTO tags (name, slug) VALUES ('a', 'a'), ('b', 'b'), ('c', 'c')"),
+        "insert into tags (name, slug) values (?)"
+    );
+    assert_eq!(
+        fingerprint("SELECT * FROM items WHERE tags && ARRAY['x', 'y']"),
+        "select * from items where tags && array[?]"
+    );
+    assert_eq!(
+        fingerprint("SELECT * FROM users WHERE id = ANY('{1,2,3}'::bigint[])"),
+        "select * from users where id = any(?::bigint[])"
+    );
+}
+
+#[test]
+fn fingerprints_that_must_collide() {
+    let pairs = [
+        (
+            "SELECT * FROM users WHERE id IN (1, 2, 3)",
+            "SELECT * FROM users WHERE id IN (4, 5, 6, 7, 8, 9)",
+        ),
+        (
+            "SELECT * FROM users WHERE id IN (1)",
+            "SELECT * FROM users WHERE id IN ($1, $2)",
+        ),
+        (
+            "SELECT * FROM users WHERE id = 1",
+            "select  *  from users\n  where id = 42",
+        ),
+        (
+            "SELECT * FROM users WHERE id IN ( 1,2 )",
+            "SELECT * FROM users WHERE id IN (3, -4)",
+        ),
+        (
+            "INSERT INTO tags (name) VALUES ('a')",
+            "INSERT INTO tags (name) VALUES ('b'), ('c'), ('it''s')",
+        ),
+        (
+            "SELECT * FROM users WHERE id = ANY(ARRAY[1, 2])",
+            "SELECT * FROM users WHERE id = ANY(ARRAY[3, 4, 5])",
+        ),
+        (
+            "SELECT * FROM users WHERE id = ANY('{1,2}')",
+            "SELECT * FROM users WHERE id = ANY('{3}')",
+        ),
+        (
+            "SELECT * FROM users WHERE id = 1 /*controller:users,action:show*/",
+            "SELECT * FROM users WHERE id = 2 /*controller:admin,action:index*/",
+        ),
+        (
+            "SELECT * FROM users WHERE score > 1.5",
+            "SELECT * FROM users WHERE score > 20",
+        ),
+    ];
+
+    for (a, b) in pairs {
+        assert_eq!(fingerprint(a), fingerprint(b), "{a:?} vs {b:?}");
+    }
+}
+
+#[test]
+fn fingerprints_that_must_not_collide() {
+    let pairs = [
+        (
+            "SELECT * FROM users WHERE id IN (1, 2)",
+            "SELECT * FROM posts WHERE id IN (1, 2)",
+        ),
+        (
+            "SELECT * FROM users WHERE id IN (1, 2)",
+            "SELECT * FROM users WHERE account_id IN (1, 2)",
+        ),
+        (
+            r#"SELECT * FROM "Users" WHERE id = 1"#,
+            r#"SELECT * FROM "users" WHERE id = 1"#,
+        ),
+        (
+            "SELECT * FROM users WHERE id IN (1, 2)",
+            "SELECT * FROM users WHERE id IN (SELECT user_id FROM posts)",
+        ),
+        (
+            "SELECT * FROM users WHERE id = 1",
+            "SELECT * FROM users WHERE id = 1 LIMIT 1",
+        ),
+        (
+            "SELECT * FROM users WHERE age - 1 > 2",
+            "SELECT * FROM users WHERE age > 2",
+        ),
+        (
+            "INSERT INTO tags (name) VALUES ('a')",
+            "INSERT INTO tags (name, slug) VALUES ('a', 'a')",
+        ),
+        (
+            "SELECT table1.id FROM table1",
+            "SELECT table2.id FROM table2",
+        ),
+    ];
+
+    for (a, b) in pairs {
+        assert_ne!(fingerprint(a), fingerprint(b), "{a:?} vs {b:?}");
+    }
+}
+
+#[test]
+fn fingerprint_can_ignore_limit_and_offset() {
+    let options = FingerprintOptions {
+        ignore_limit_offset: true,
+    };
+    let paged = QueryFingerprint::with_options(
+        "SELECT * FROM posts ORDER BY created_at DESC LIMIT 25 OFFSET 50",
+        options,
+    );
+    let unpaged =
+        QueryFingerprint::with_options("SELECT * FROM posts ORDER BY created_at DESC", options);
+
+    assert_eq!(paged, unpaged);
+    assert_eq!(
+        paged.normalized,
+        "select * from posts order by created_at desc"
+    );
+}
+
+#[test]
+fn fingerprints_ignore_bind_values() {
+    let pairs = [
+        (
+            r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1 LIMIT $2  [["id", 1], ["LIMIT", 1]]"#,
+            r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1 LIMIT $2  [["id", 42], ["LIMIT", 1]]"#,
+        ),
+        // One bind per IN element: the list collapses, and so must its binds
+        (
+            r#"SELECT "tags".* FROM "tags" WHERE "tags"."id" IN ($1, $2)  [["id", 1], ["id", 2]]"#,
+            r#"SELECT "tags".* FROM "tags" WHERE "tags"."id" IN ($1, $2, $3)  [["id", 4], ["id", 5], ["id", 6]]"#,
+        ),
+        (
+            r#"INSERT INTO "events" ("name", "payload") VALUES (?, ?)  [["name", "signup"], ["payload", "{\"plan\":\"pro\", \"seats\":[1, 2]}"]]"#,
+            r#"INSERT INTO "events" ("name", "payload") VALUES (?, ?)  [["name", "login"], ["payload", nil]]"#,
+        ),
+    ];
+    for (a, b) in pairs {
+        assert_eq!(fingerprint(a), fingerprint(b), "\n{}\n{}", a, b);
+    }
+    assert_eq!(
+        fingerprint(r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1  [["id", 1]]"#),
+        r#"select "users".* from "users" where "users"."id" = ?"#
+    );
+}
+
+#[test]
+fn split_binds_parses_the_trailing_array() {
+    let (query, binds) = split_binds(
+        r#"SELECT "users".* FROM "users" WHERE "users"."email" = $1 AND "users"."id" = $2  [["email", "a@b.co"], [nil, 7], ["LIMIT", 1]]"#,
     );
+    assert_eq!(
+        query,
+        r#"SELECT "users".* FROM "users" WHERE "users"."email" = $1 AND "users"."id" = $2"#
+    );
+    assert_eq!(
+        binds,
+        vec![
+            ("email".to_string(), r#""a@b.co""#.to_string()),
+            ("nil".to_string(), "7".to_string()),
+            ("LIMIT".to_string(), "1".to_string()),
+        ]
+    );
+
+    // Values may hold brackets, commas and escaped quotes
+    let (_, binds) = split_binds(
+        r#"UPDATE "posts" SET "tags" = $1, "title" = $2  [["tags", "[\"a\", \"b]\"]"], ["title", "Say \"hi\", [sic]"]]"#,
+    );
+    assert_eq!(binds[0].1, r#""[\"a\", \"b]\"]""#);
+    assert_eq!(binds[1].1, r#""Say \"hi\", [sic]""#);
+}
+
+#[test]
+fn split_binds_leaves_queries_without_a_whole_array_alone() {
+    for sql in [
+        r#"SELECT "users".* FROM "users""#,
+        // Cut off mid-array, as a truncated line is
+        r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1  [["id", 1], ["LIM"#,
+        r#"SELECT "users".* FROM "users" WHERE "users"."name" = $1  [["name", "unterminated]]"#,
+        // Not a bind array: something follows it, or its items aren't pairs
+        r#"SELECT * FROM t WHERE a = ANY(ARRAY  [[1, 2]]) AND b = 1"#,
+        r#"SELECT '  [[1]]'"#,
+    ] {
+        assert_eq!(split_binds(sql), (sql, Vec::new()), "{}", sql);
+    }
+}
+
+#[test]
+fn fingerprint_handles_long_in_lists() {
+    let ids: Vec<String> = (0..20_000).map(|id| id.to_string()).collect();
+    let sql = format!("SELECT * FROM users WHERE id IN ({})", ids.join(", "));
+
+    let start = std::time::Instant::now();
+    let fp = QueryFingerprint::new(&sql);
+
+    assert_eq!(fp.normalized, "select * from users where id in (?)");
+    // Linear in the query length; a generous bound that only catches blowups
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
+
+#[test]
+fn fingerprint_caps_its_input() {
+    let head = "INSERT INTO events (id, name) VALUES ";
+    let rows = "(1, 'a'), ".repeat(MAX_FINGERPRINT_INPUT / 10 + 100_000);
+    let first = format!("{}{}(2, 'b')", head, rows);
+    let second = format!("{}{}(3, 'c'), (4, 'd')", head, rows);
+
+    let start = std::time::Instant::now();
+    let fp = QueryFingerprint::new(&first);
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+
+    assert!(fp.normalized.len() < 200, "{}", fp.normalized);
+    assert_eq!(fp.normalized, QueryFingerprint::new(&second).normalized);
 }
 
 #[test]
@@ -30,6 +267,15 @@ fn query_type_detection() {
     assert_eq!(QueryType::from_sql("UPDATE x"), QueryType::Update);
     assert_eq!(QueryType::from_sql("COMMIT"), QueryType::Commit);
     assert_eq!(QueryType::from_sql("ALTER TABLE"), QueryType::Other);
+    // As Rails logs them, named and timed
+    assert_eq!(
+        QueryType::from_sql(r#"  Comment Load (2.0ms)  SELECT "comments".* FROM "comments""#),
+        QueryType::Select
+    );
+    assert_eq!(
+        QueryType::from_sql("TRANSACTION (0.2ms)  BEGIN"),
+        QueryType::Begin
+    );
 }
 
 #[test]
@@ -39,13 +285,186 @@ fn n_plus_one_detector_flags_repeated_selects() {
     ctx.add_query(sample_select(3.0));
     ctx.add_query(sample_select(4.0));
 
-    let issues = NPlusOneDetector::detect(&ctx);
+    let issues = NPlusOneDetector::detect(&ctx, &HashMap::new());
     assert_eq!(issues.len(), 1);
     let issue = &issues[0];
     assert_eq!(issue.count, 3);
     assert!(issue.suggestion.contains("includes"));
 }
 
+fn select(sql: &str) -> QueryInfo {
+    QueryInfo {
+        raw_query: sql.to_string(),
+        fingerprint: QueryFingerprint::new(sql),
+        duration: 1.0,
+        rows: None,
+        query_type: QueryType::Select,
+        binds: Vec::new(),
+        name: None,
+        truncated: None,
+    }
+}
+
+const BLOG_SCHEMA: &str = r#"
+ActiveRecord::Schema[7.1].define(version: 2024_01_01_000000) do
+  create_table "posts", force: :cascade do |t|
+    t.string "title"
+    t.bigint "author_id"
+    t.index ["author_id"], name: "index_posts_on_author_id"
+  end
+
+  create_table "comments", force: :cascade do |t|
+    t.bigint "post_id", null: false
+    t.text "body"
+  end
+
+  create_table "users", force: :cascade do |t|
+    t.string "email"
+  end
+
+  add_foreign_key "comments", "posts"
+  add_foreign_key "posts", "users", column: "author_id"
+end
+"#;
+
+#[test]
+fn n_plus_one_suggestion_names_the_parent_model_and_association() {
+    let mut ctx = RequestContext::new(Some("/posts".into()));
+    ctx.add_query(select(r#"SELECT "posts".* FROM "posts" LIMIT 10"#));
+    for id in 1..=4 {
+        ctx.add_query(select(&format!(
+            r#"SELECT "comments".* FROM "comments" WHERE "comments"."post_id" = {}"#,
+            id
+        )));
+    }
+
+    let issues = NPlusOneDetector::detect(&ctx, &HashMap::new());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        issues[0].suggestion,
+        "Possible N+1 query detected (4 times). Eager load the association:\n  \
+         Post.includes(:comments)\n  Confidence: medium (comments.post_id)"
+    );
+
+    let schema = parse_schema(BLOG_SCHEMA);
+    let issues = NPlusOneDetector::detect(&ctx, &schema);
+    assert!(
+        issues[0]
+            .suggestion
+            .ends_with("Confidence: high (comments.post_id)"),
+        "{}",
+        issues[0].suggestion
+    );
+}
+
+#[test]
+fn n_plus_one_suggestion_falls_back_without_a_parent_query() {
+    let mut ctx = RequestContext::new(Some("/users".into()));
+    ctx.add_query(sample_select(2.0));
+    ctx.add_query(sample_select(3.0));
+    ctx.add_query(sample_select(4.0));
+
+    let issues = NPlusOneDetector::detect(&ctx, &HashMap::new());
+    assert_eq!(
+        issues[0].suggestion,
+        "Possible N+1 query detected (3 times). Eager load the association that reads \
+         users with .includes() or .preload()"
+    );
+}
+
+#[test]
+fn eager_load_for_belongs_to_lookups_by_id() {
+    let query = r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1 LIMIT $2"#;
+
+    let guessed = infer_eager_load("posts", query, &HashMap::new()).unwrap();
+    assert_eq!(guessed.code(), "Post.includes(:user)");
+    assert_eq!(guessed.confidence, Confidence::Medium);
+
+    // schema.rb names the real key: posts.author_id references users
+    let schema = parse_schema(BLOG_SCHEMA);
+    let confirmed = infer_eager_load("posts", query, &schema).unwrap();
+    assert_eq!(confirmed.code(), "Post.includes(:author)");
+    assert_eq!(confirmed.confidence, Confidence::High);
+    assert_eq!(confirmed.evidence, "posts.author_id in schema.rb");
+
+    let missing = infer_eager_load("comments", query, &schema).unwrap();
+    assert_eq!(missing.confidence, Confidence::Low);
+}
+
+#[test]
+fn eager_load_through_join_tables() {
+    let through = infer_eager_load(
+        "posts",
+        r#"SELECT "tags".* FROM "tags" INNER JOIN "taggings" ON "tags"."id" = "taggings"."tag_id" WHERE "taggings"."post_id" = $1"#,
+        &HashMap::new(),
+    )
+    .unwrap();
+    assert_eq!(through.code(), "Post.includes(:tags)");
+    assert_eq!(through.through.as_deref(), Some("taggings"));
+
+    let habtm = infer_eager_load(
+        "posts",
+        r#"SELECT "tags".* FROM "tags" INNER JOIN "posts_tags" ON "tags"."id" = "posts_tags"."tag_id" WHERE "posts_tags"."post_id" = $1"#,
+        &HashMap::new(),
+    )
+    .unwrap();
+    assert_eq!(habtm.code(), "Post.includes(:tags)");
+    assert_eq!(habtm.through, None);
+}
+
+#[test]
+fn eager_load_for_namespaced_tables() {
+    // Blog::Post with table_name_prefix "blog_"
+    let namespaced = infer_eager_load(
+        "blog_posts",
+        r#"SELECT "blog_comments".* FROM "blog_comments" WHERE "blog_comments"."post_id" = $1"#,
+        &HashMap::new(),
+    )
+    .unwrap();
+    assert_eq!(namespaced.code(), "Blog::Post.includes(:comments)");
+
+    // A plain BlogPost model keys its children by blog_post_id
+    let prefixed = infer_eager_load(
+        "blog_posts",
+        r#"SELECT "blog_comments".* FROM "blog_comments" WHERE "blog_comments"."blog_post_id" = $1"#,
+        &HashMap::new(),
+    )
+    .unwrap();
+    assert_eq!(prefixed.code(), "BlogPost.includes(:blog_comments)");
+
+    // A key that matches nothing known is not guessed at
+    assert!(
+        infer_eager_load(
+            "blog_posts",
+            r#"SELECT "notes".* FROM "notes" WHERE "notes"."owner_id" = $1"#,
+            &HashMap::new(),
+        )
+        .is_none()
+    );
+
+    // Non-ASCII names are compared, not cut mid-character
+    for (parent, child) in [("blog_posts", "blo日s"), ("blo日s", "blog_posts")] {
+        let query = format!(r#"SELECT "{child}".* FROM "{child}" WHERE "{child}"."owner_id" = $1"#);
+        assert!(infer_eager_load(parent, &query, &HashMap::new()).is_none());
+    }
+}
+
+#[test]
+fn singularizes_common_table_names() {
+    for (plural, singular) in [
+        ("posts", "post"),
+        ("categories", "category"),
+        ("addresses", "address"),
+        ("line_items", "line_item"),
+        ("people", "person"),
+        ("boxes", "box"),
+        ("order_statuses", "order_status"),
+        ("houses", "house"),
+    ] {
+        assert_eq!(singularize(plural), singular, "{}", plural);
+    }
+}
+
 #[test]
 fn query_analyzer_flags_select_star_and_slow_queries() {
     let info = QueryInfo {
@@ -54,6 +473,9 @@ fn query_analyzer_flags_select_star_and_slow_queries() {
         duration: 120.0,
         rows: Some(200),
         query_type: QueryType::Select,
+        binds: Vec::new(),
+        name: None,
+        truncated: None,
     };
 
     let recs = QueryAnalyzer::analyze(&info);
@@ -75,3 +497,186 @@ fn query_analyzer_flags_select_star_and_slow_queries() {
         .expect("missing slow query recommendation");
     assert!(slow.suggestion.contains("indexes"));
 }
+
+#[test]
+fn query_analyzer_suggests_indexes_for_backtick_quoted_mysql_queries() {
+    let sql = "SELECT `users`.`id` FROM `users` WHERE `users`.`email` = 'a@b.c'";
+    let info = QueryInfo {
+        raw_query: sql.to_string(),
+        fingerprint: QueryFingerprint::new(sql),
+        duration: 150.0,
+        rows: None,
+        query_type: QueryType::Select,
+        binds: Vec::new(),
+        name: None,
+        truncated: None,
+    };
+
+    let migration = |recs: Vec<caboose::query::QueryRecommendation>| {
+        recs.into_iter()
+            .find_map(|r| r.migration_code)
+            .expect("missing migration")
+    };
+    let generic = migration(QueryAnalyzer::analyze(&info));
+    assert!(
+        generic.contains("add_index :users, :email\n"),
+        "{}",
+        generic
+    );
+    assert!(generic.contains("AddIndexToUsers email:index"));
+
+    let mysql = migration(QueryAnalyzer::analyze_for(&info, SqlDialect::MySql));
+    assert!(mysql.contains("add_index :users, :email, algorithm: :inplace"));
+}
+
+#[test]
+fn select_star_matches_top_level_select_list_only() {
+    assert!(selects_star("SELECT * FROM users"));
+    assert!(selects_star("select * from users where id = 1"));
+    assert!(selects_star("SELECT DISTINCT * FROM users"));
+    assert!(selects_star("SELECT *, 1 AS flag FROM users"));
+    assert!(selects_star(
+        r#"SELECT * FROM (SELECT "users"."id" FROM "users") AS sub"#
+    ));
+
+    // Rails-generated queries that must not be flagged
+    assert!(!selects_star(r#"SELECT COUNT(*) FROM "posts""#));
+    assert!(!selects_star(
+        r#"SELECT 1 AS one FROM "users" WHERE EXISTS (SELECT * FROM "posts" WHERE "posts"."user_id" = "users"."id") LIMIT $1"#
+    ));
+    assert!(!selects_star(
+        r#"SELECT "users".* FROM "users" WHERE "users"."id" = $1 LIMIT $2"#
+    ));
+    assert!(!selects_star(
+        "SELECT id FROM notes WHERE body = 'use SELECT * sparingly'"
+    ));
+    assert!(!selects_star("SELECT id /* SELECT * FROM x */ FROM notes"));
+    assert!(!selects_star(
+        "SELECT price * quantity AS total FROM line_items"
+    ));
+}
+
+#[test]
+fn query_analyzer_ignores_count_star() {
+    let sql = r#"SELECT COUNT(*) FROM "comments" WHERE "comments"."article_id" = $1"#;
+    let info = QueryInfo {
+        raw_query: sql.to_string(),
+        fingerprint: QueryFingerprint::new(sql),
+        duration: 1.0,
+        rows: None,
+        query_type: QueryType::Select,
+        binds: Vec::new(),
+        name: None,
+        truncated: None,
+    };
+
+    let recs = QueryAnalyzer::analyze(&info);
+    assert!(
+        !recs
+            .iter()
+            .any(|r| r.issue_type == PerformanceIssue::SelectStar)
+    );
+}
+
+#[test]
+fn exported_sql_fills_in_binds_and_keeps_transactions() {
+    let tracker = RequestContextTracker::new();
+    let log = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/logs/rails6.log"),
+    )
+    .unwrap();
+    for line in log.lines() {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            tracker.process_log_event(&event);
+        }
+    }
+
+    let sql = requests_to_sql(&tracker.get_recent_requests(), &Privacy::default());
+    assert!(
+        sql.starts_with("-- 6 statements from 2 requests, exported by Caboose\n\n"),
+        "{}",
+        sql
+    );
+    assert!(
+        sql.contains(
+            r#"-- GET /posts -> PostsController#index, 200 in 37.0ms
+-- Post Load (1.2ms)
+SELECT "posts".* FROM "posts" ORDER BY "posts"."created_at" DESC LIMIT 20;
+"#
+        ),
+        "{}",
+        sql
+    );
+    assert!(
+        sql.ends_with(
+            r#"-- POST /posts -> PostsController#create, 302 in 6.0ms
+-- TRANSACTION (0.1ms)
+BEGIN;
+-- Post Create (0.8ms)
+INSERT INTO "posts" ("title", "created_at", "updated_at") VALUES ('Hello', '2024-01-15 10:30:46', '2024-01-15 10:30:46') RETURNING "id";
+-- TRANSACTION (0.4ms)
+COMMIT;
+"#
+        ),
+        "{}",
+        sql
+    );
+}
+
+#[test]
+fn binds_become_sql_literals_outside_quotes() {
+    let binds = |values: &[&str]| -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|value| ("x".to_string(), value.to_string()))
+            .collect()
+    };
+
+    // MySQL / SQLite placeholders, one bind each in turn
+    let (sql, unbound) = substitute_binds(
+        "SELECT * FROM `users` WHERE `name` = ? AND `note` = '?' AND `deleted_at` IS ? LIMIT ?",
+        &binds(&[r#""O'Brien \"Jr\"""#, "nil", "1"]),
+    );
+    assert_eq!(
+        sql,
+        r#"SELECT * FROM `users` WHERE `name` = 'O''Brien "Jr"' AND `note` = '?' AND `deleted_at` IS NULL LIMIT 1"#
+    );
+    assert_eq!(unbound, 0);
+
+    // `$n` may repeat or go past the binds logged
+    let (sql, unbound) = substitute_binds(
+        r#"SELECT "$1" FROM t WHERE a = $1 OR b = $1 OR c = $12 OR data ? 'key'"#,
+        &binds(&["7"]),
+    );
+    assert_eq!(
+        sql,
+        r#"SELECT "$1" FROM t WHERE a = 7 OR b = 7 OR c = $12 OR data ? 'key'"#
+    );
+    assert_eq!(unbound, 1);
+
+    // Without binds the statement is left as logged
+    assert_eq!(substitute_binds("SELECT ?", &[]), ("SELECT ?".into(), 0));
+}
+
+#[test]
+fn truncated_and_private_statements_are_marked_not_emitted() {
+    let mut query = sample_select(2.5);
+    query.raw_query =
+        r#"INSERT INTO "events" ("payload") VALUES ('{"email":"jane@example.com"'"#.to_string();
+    query.name = Some("Event Create".to_string());
+    query.truncated = Some(2_400_000);
+    let sql = statement_to_sql(&query, &Privacy::default());
+    let lines: Vec<&str> = sql.lines().collect();
+    assert_eq!(lines[0], "-- Event Create (2.5ms)");
+    assert!(lines[1].starts_with("-- TRUNCATED: the logged line was 2400000 bytes"));
+    assert_eq!(lines[2], format!("-- {}", query.raw_query));
+    assert_eq!(lines.len(), 3);
+
+    let mut query = sample_select(1.0);
+    query.raw_query = r#"SELECT * FROM "users" WHERE "email" = $1"#.to_string();
+    query.binds = vec![("email".to_string(), r#""jane@example.com""#.to_string())];
+    assert_eq!(
+        statement_to_sql(&query, &Privacy::new(true, Vec::new())),
+        "-- (1.0ms)\nSELECT * FROM \"users\" WHERE \"email\" = '•••';\n"
+    );
+}
@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::PathBuf;
 
+use caboose::config::WorkerMode;
+use caboose::database::SqlDialect;
 use caboose::rails::RailsApp;
 
 fn temp_dir(name: &str) -> PathBuf {
@@ -29,9 +31,161 @@ fn detects_rails_app_and_features() {
     let app = RailsApp::detect_in_path(&root);
     assert!(app.detected);
     assert_eq!(app.database.as_deref(), Some("postgresql"));
+    assert_eq!(app.sql_dialect(), SqlDialect::Postgres);
     assert_eq!(app.background_job.as_deref(), Some("sidekiq"));
     assert_eq!(app.asset_pipeline.as_deref(), Some("vite"));
     assert!(app.generate_procfile(None).contains("bundle exec sidekiq"));
 
     let _ = fs::remove_dir_all(root);
 }
+
+fn job_app(name: &str, gem: &str, configs: &[(&str, &str)]) -> PathBuf {
+    let root = temp_dir(name);
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::write(root.join("Gemfile"), format!("gem 'rails'\ngem '{}'", gem)).unwrap();
+    fs::write(root.join("config/application.rb"), "module App end").unwrap();
+    for (path, content) in configs {
+        fs::write(root.join(path), content).unwrap();
+    }
+    root
+}
+
+const SIDEKIQ_CAPSULES: &str = r#"---
+:concurrency: 5
+:queues:
+  - critical
+  - default
+  - [low, 2]
+
+:capsules:
+  :mailers:
+    :queues: [mailers]
+    :concurrency: <%= ENV.fetch("MAILER_THREADS", 2) %>
+"#;
+
+#[test]
+fn sidekiq_capsules_become_separate_workers() {
+    let root = job_app(
+        "sidekiq_groups",
+        "sidekiq",
+        &[("config/sidekiq.yml", SIDEKIQ_CAPSULES)],
+    );
+
+    let app = RailsApp::detect_in_path(&root);
+    assert!(app.jobs.warning.is_none());
+    assert_eq!(app.jobs.groups.len(), 2);
+
+    let entries = app.procfile_entries(None, WorkerMode::PerGroup);
+    let workers: Vec<(&str, &str)> = entries
+        .iter()
+        .filter(|e| e.name.starts_with("worker"))
+        .map(|e| (e.name.as_str(), e.command.as_str()))
+        .collect();
+    assert_eq!(
+        workers,
+        [
+            (
+                "worker_critical",
+                "bundle exec sidekiq -q critical -q default -q low,2 -c 5"
+            ),
+            ("worker_mailers", "bundle exec sidekiq -q mailers -c 2"),
+        ]
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn single_worker_mode_keeps_one_entry() {
+    let root = job_app(
+        "sidekiq_single",
+        "sidekiq",
+        &[("config/sidekiq.yml", SIDEKIQ_CAPSULES)],
+    );
+
+    let app = RailsApp::detect_in_path(&root);
+    let entries = app.procfile_entries(None, WorkerMode::Single);
+    let workers: Vec<_> = entries
+        .iter()
+        .filter(|e| e.name.starts_with("worker"))
+        .collect();
+    assert_eq!(workers.len(), 1);
+    assert_eq!(workers[0].name, "worker");
+    assert_eq!(workers[0].command, "bundle exec sidekiq");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn malformed_job_config_falls_back_with_warning() {
+    let root = job_app(
+        "sidekiq_malformed",
+        "sidekiq",
+        &[(
+            "config/sidekiq.yml",
+            ":queues:\n  - critical\n   bad: [indent\n",
+        )],
+    );
+
+    let app = RailsApp::detect_in_path(&root);
+    let warning = app.jobs.warning.as_deref().unwrap();
+    assert!(warning.contains("config/sidekiq.yml"));
+
+    let entries = app.procfile_entries(None, WorkerMode::PerGroup);
+    assert_eq!(entries[1].name, "worker");
+    assert_eq!(entries[1].command, "bundle exec sidekiq");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn solid_queue_reports_groups_and_recurring_tasks() {
+    let queue_yml = r#"
+default: &default
+  dispatchers:
+    - polling_interval: 1
+      batch_size: 500
+  workers:
+    - queues: "*"
+      threads: 3
+      processes: <%= ENV.fetch("JOB_CONCURRENCY", 1) %>
+    - queues: [critical, mailers]
+      threads: 1
+
+development:
+  <<: *default
+"#;
+    let recurring_yml = r#"
+development:
+  cleanup:
+    class: CleanupJob
+    schedule: every hour
+  digest:
+    command: "DigestMailer.deliver"
+    schedule: at 5am every day
+"#;
+    let root = job_app(
+        "solid_queue",
+        "solid_queue",
+        &[
+            ("config/queue.yml", queue_yml),
+            ("config/recurring.yml", recurring_yml),
+        ],
+    );
+
+    let app = RailsApp::detect_in_path(&root);
+    assert!(app.jobs.warning.is_none());
+    assert_eq!(app.jobs.groups.len(), 2);
+    assert_eq!(app.jobs.groups[0].processes, Some(1));
+    assert_eq!(app.jobs.groups[1].queues, ["critical", "mailers"]);
+    assert_eq!(app.jobs.recurring_tasks, 2);
+
+    // The supervisor forks each group itself, so there is still one entry
+    let entries = app.procfile_entries(None, WorkerMode::PerGroup);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].command, "bundle exec rake solid_queue:start");
+    assert!(entries[1].reason.contains("2 worker group(s)"));
+    assert!(entries[1].reason.contains("2 recurring task(s)"));
+
+    let _ = fs::remove_dir_all(root);
+}
@@ -0,0 +1,157 @@
+use std::fs;
+
+use caboose::rails::routes::{ROUTES_CACHE_FILE, Route, RouteTable, parse_expanded_routes};
+
+/// `rails routes --expanded` from a small app (Rails 7.1 adds `Source Location`)
+const EXPANDED: &str = "\
+--[ Route 1 ]-------------------------------------------------------------------
+Prefix            | sidekiq_web
+Verb              |
+URI               | /sidekiq
+Controller#Action | Sidekiq::Web
+Source Location   | config/routes.rb:2
+--[ Route 2 ]-------------------------------------------------------------------
+Prefix            | root
+Verb              | GET
+URI               | /
+Controller#Action | home#index
+Source Location   | config/routes.rb:3
+--[ Route 3 ]-------------------------------------------------------------------
+Prefix            | new_user
+Verb              | GET
+URI               | /users/new(.:format)
+Controller#Action | users#new
+Source Location   | config/routes.rb:4
+--[ Route 4 ]-------------------------------------------------------------------
+Prefix            | user
+Verb              | GET
+URI               | /users/:id(.:format)
+Controller#Action | users#show
+Source Location   | config/routes.rb:4
+--[ Route 5 ]-------------------------------------------------------------------
+Prefix            |
+Verb              | PATCH
+URI               | /users/:id(.:format)
+Controller#Action | users#update
+Source Location   | config/routes.rb:4
+--[ Route 6 ]-------------------------------------------------------------------
+Prefix            | post
+Verb              | GET|POST
+URI               | /posts/:slug(/:page)(.:format)
+Controller#Action | posts#show
+Source Location   | config/routes.rb:5
+--[ Route 7 ]-------------------------------------------------------------------
+Prefix            | files
+Verb              | GET
+URI               | /files/*path(.:format)
+Controller#Action | files#show
+Source Location   | config/routes.rb:6
+
+Routes for Sidekiq::Web:
+--[ Route 1 ]-------------------------------------------------------------------
+Prefix            | dashboard
+Verb              | GET
+URI               | /dashboard
+Controller#Action | sidekiq/dashboard#index
+";
+
+fn table() -> RouteTable {
+    RouteTable::new(parse_expanded_routes(EXPANDED))
+}
+
+#[test]
+fn expanded_output_parses_into_routes() {
+    let routes = parse_expanded_routes(EXPANDED);
+
+    // The engine's own routes are left out
+    assert_eq!(routes.len(), 7);
+    assert_eq!(
+        routes[3],
+        Route {
+            name: Some("user".to_string()),
+            verb: "GET".to_string(),
+            path: "/users/:id(.:format)".to_string(),
+            action: "users#show".to_string(),
+        }
+    );
+    assert_eq!(routes[0].verb, "");
+    assert_eq!(routes[0].action, "Sidekiq::Web");
+    assert_eq!(routes[4].name, None);
+    assert_eq!(routes[3].pattern(), "/users/:id");
+}
+
+#[test]
+fn requests_match_the_first_route_with_their_verb_and_path() {
+    let table = table();
+    let action = |method: &str, path: &str| table.find(method, path).map(|r| r.action.as_str());
+
+    assert_eq!(action("GET", "/"), Some("home#index"));
+    // Declared before `:id`, so `new` isn't an id
+    assert_eq!(action("GET", "/users/new"), Some("users#new"));
+    assert_eq!(action("GET", "/users/42?tab=posts"), Some("users#show"));
+    assert_eq!(action("GET", "/users/42.json"), Some("users#show"));
+    assert_eq!(action("PATCH", "/users/42"), Some("users#update"));
+    assert_eq!(action("DELETE", "/users/42"), None);
+    assert_eq!(action("POST", "/posts/hello-world/2"), Some("posts#show"));
+    assert_eq!(action("GET", "/files/docs/a/b.pdf"), Some("files#show"));
+    assert_eq!(action("GET", "/sidekiq"), Some("Sidekiq::Web"));
+    assert_eq!(action("GET", "/users/42/posts"), None);
+}
+
+#[test]
+fn unnamed_routes_share_the_name_of_their_path() {
+    let table = table();
+
+    assert_eq!(table.name_for("GET", "/users/7"), Some("user"));
+    assert_eq!(table.name_for("PATCH", "/users/7"), Some("user"));
+    assert_eq!(table.name_for("GET", "/missing"), None);
+}
+
+#[test]
+fn paths_normalize_to_their_route_pattern() {
+    let table = table();
+
+    assert_eq!(table.pattern_for("/users/42?tab=1"), Some("/users/:id"));
+    assert_eq!(
+        table.pattern_for("/posts/hello-world"),
+        Some("/posts/:slug(/:page)")
+    );
+    assert_eq!(table.pattern_for("/nowhere"), None);
+}
+
+#[test]
+fn filter_matches_any_column_ignoring_case() {
+    let table = table();
+    let actions = |query: &str| {
+        table
+            .filter(query)
+            .map(|route| route.action.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(actions("PATCH"), ["users#update"]);
+    assert_eq!(actions("new_user"), ["users#new"]);
+    assert_eq!(actions("sidekiq"), ["Sidekiq::Web"]);
+    assert_eq!(actions("").len(), 7);
+}
+
+#[test]
+fn routes_cache_round_trips() {
+    let dir = std::env::temp_dir().join(format!("caboose_routes_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    assert!(RouteTable::load(&dir).is_none());
+
+    table().save(&dir).unwrap();
+    let loaded = RouteTable::load(&dir).unwrap();
+    assert_eq!(loaded.routes(), table().routes());
+    assert_eq!(
+        loaded.find("GET", "/users/1").map(|r| r.action.as_str()),
+        Some("users#show")
+    );
+
+    // An unreadable cache counts as none
+    fs::write(dir.join(ROUTES_CACHE_FILE), "not json").unwrap();
+    assert!(RouteTable::load(&dir).is_none());
+    let _ = fs::remove_dir_all(&dir);
+}
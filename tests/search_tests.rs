@@ -0,0 +1,103 @@
+use caboose::process::{LogLine, LogStream};
+use caboose::search::{SearchFilter, SearchQuery};
+
+#[test]
+fn matches_case_insensitively() {
+    let query = SearchQuery::new("  Users ");
+    assert_eq!(query.as_str(), "users");
+    assert!(query.matches("GET /USERS/1"));
+    assert!(query.matches("UsersController#show"));
+    assert!(!query.matches("GET /posts"));
+}
+
+#[test]
+fn empty_query_matches_everything() {
+    let query = SearchQuery::new("");
+    assert!(query.is_empty());
+    assert!(query.matches(""));
+    assert!(query.matches_any(Vec::<&str>::new()));
+}
+
+#[test]
+fn matches_non_ascii_queries() {
+    let query = SearchQuery::new("ÜBER");
+    assert!(query.matches("Route /über-uns"));
+    assert!(query.matches_any(["nope", "Über"]));
+    assert!(!query.matches_any(["nope", "uber"]));
+}
+
+fn log_line(content: &str) -> LogLine {
+    LogLine {
+        stream: LogStream::Stdout,
+        ..LogLine::new("web", content)
+    }
+}
+
+#[test]
+fn regex_searches_match_log_lines() {
+    let filter = SearchFilter::new(r"re:Completed (5\d\d)", false);
+    assert!(filter.is_regex());
+    assert!(filter.error().is_none());
+    assert!(filter.matches(&log_line("Completed 500 Internal Server Error in 12ms")));
+    assert!(filter.matches(&log_line("completed 503 Service Unavailable")));
+    assert!(!filter.matches(&log_line("Completed 200 OK in 52ms")));
+
+    // Without `re:` the same text is searched for as typed
+    let plain = SearchFilter::new(r"(5\d\d)", false);
+    assert!(!plain.is_regex());
+    assert!(!plain.matches_str("Completed 500"));
+    assert!(plain.matches_str(r"pattern (5\d\d)"));
+}
+
+#[test]
+fn invalid_regexes_match_nothing_and_say_why() {
+    let filter = SearchFilter::new("re:Completed (5", false);
+    assert_eq!(filter.error(), Some("unclosed group"));
+    assert!(!filter.is_empty());
+    assert!(!filter.matches_str("Completed (5"));
+    assert!(filter.highlight_ranges("Completed (5").is_empty());
+
+    let filter = SearchFilter::new(r"re:\w{1000}{1000}", false);
+    assert!(filter.error().is_some());
+
+    // An empty pattern is no search at all
+    let filter = SearchFilter::new("re:", false);
+    assert!(filter.is_empty());
+    assert!(filter.matches_str("anything"));
+}
+
+#[test]
+fn case_folding_covers_unicode_unless_case_sensitive() {
+    let filter = SearchFilter::new("ÜBER", false);
+    assert!(filter.matches_str("Route /über-uns"));
+    assert!(SearchFilter::new("re:σ+", false).matches_str("ΣΥΣ"));
+    assert!(SearchFilter::new("Straße", false).matches_str("STRAßE"));
+
+    let filter = SearchFilter::new("ÜBER", true);
+    assert!(filter.case_sensitive());
+    assert!(!filter.matches_str("Route /über-uns"));
+    assert!(filter.matches_str("Route /ÜBER"));
+    assert!(!SearchFilter::new("re:error", true).matches_str("ERROR"));
+}
+
+#[test]
+fn highlight_ranges_are_byte_offsets_of_each_match() {
+    let filter = SearchFilter::new("user", false);
+    assert_eq!(
+        filter.highlight_ranges(r#"User Load  SELECT "users".*"#),
+        vec![0..4, 19..23]
+    );
+
+    // Offsets count bytes, so multi-byte characters before a match move it
+    let filter = SearchFilter::new("über", false);
+    assert_eq!(filter.highlight_ranges("→ ÜBER über"), vec![4..9, 10..15]);
+
+    // Empty matches have nothing to highlight
+    let filter = SearchFilter::new(r"re:\d*", false);
+    assert_eq!(filter.highlight_ranges("in 52ms"), vec![3..5]);
+    assert!(
+        SearchFilter::new("", false)
+            .highlight_ranges("text")
+            .is_empty()
+    );
+}
@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use caboose::context::RequestContextTracker;
+use caboose::database::{DatabaseHealth, SqlDialect};
+use caboose::exception::ExceptionTracker;
+use caboose::parser::{LogEvent, RailsLogParser};
+use caboose::privacy::{MASK, Privacy};
+use caboose::process::{LogLine, LogStream, ProcessExit, ProcessInfo, ProcessStatus};
+use caboose::session::{
+    SESSION_FORMAT_VERSION, SessionArchive, SessionMetadata, SessionSources, default_file_name,
+};
+use caboose::stats::StatsCollector;
+use caboose::test::{TestFramework, TestResult, TestStatus, TestTracker};
+use caboose::time_format::DisplayZone;
+use chrono::{Local, TimeZone};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("caboose_session_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[derive(Default)]
+struct Trackers {
+    stats: StatsCollector,
+    requests: RequestContextTracker,
+    exceptions: ExceptionTracker,
+    tests: TestTracker,
+    database: DatabaseHealth,
+}
+
+impl Trackers {
+    fn sources<'a>(
+        &'a self,
+        processes: &'a [ProcessInfo],
+        logs: &'a [LogLine],
+    ) -> SessionSources<'a> {
+        SessionSources {
+            processes,
+            logs,
+            stats: &self.stats,
+            requests: &self.requests,
+            exceptions: &self.exceptions,
+            tests: &self.tests,
+            database: &self.database,
+        }
+    }
+
+    /// Feed a log line to the trackers the way the UI does
+    fn record(&self, line: &str) {
+        if let Some(event) = RailsLogParser::parse_line(line) {
+            match &event {
+                LogEvent::HttpRequest(req) => {
+                    if let (Some(status), Some(duration)) = (req.status, req.duration) {
+                        self.stats.record_request(status, duration);
+                    }
+                }
+                LogEvent::SqlQuery(query) => {
+                    if let Some(duration) = query.duration {
+                        self.stats.record_sql_query(duration);
+                        self.database.analyze_query(&query.query, duration);
+                    }
+                }
+                _ => {}
+            }
+            self.requests.process_log_event(&event);
+        }
+        self.exceptions.parse_line(line);
+    }
+}
+
+const SESSION_LOG: &[&str] = &[
+    r#"Started GET "/users/42" for 127.0.0.1"#,
+    "Processing by UsersController#show as HTML",
+    r#"  User Load (250.3ms)  SELECT "users".* FROM "users" WHERE "users"."email" = 'a@b.c'"#,
+    "Completed 200 OK in 312ms",
+    "NoMethodError: undefined method `name' for nil:NilClass",
+    "  app/controllers/users_controller.rb:12:in `show'",
+    "irrelevant line to end backtrace",
+];
+
+fn log_line(seq: u64, content: &str) -> LogLine {
+    LogLine {
+        timestamp: Instant::now() - Duration::from_secs(30),
+        seq,
+        stream: LogStream::Stdout,
+        ..LogLine::new("web", content)
+    }
+}
+
+fn session() -> (Trackers, Vec<ProcessInfo>, Vec<LogLine>) {
+    let trackers = Trackers {
+        database: DatabaseHealth::new().with_dialect(SqlDialect::MySql),
+        ..Trackers::default()
+    };
+    for line in SESSION_LOG {
+        trackers.record(line);
+    }
+    trackers.database.record_health_score();
+    trackers.tests.start_test_run(TestFramework::RSpec);
+    trackers.tests.add_test_result(TestResult {
+        test_name: "User#name fails".into(),
+        file_path: Some("spec/models/user_spec.rb".into()),
+        line_number: Some(8),
+        status: TestStatus::Failed,
+        duration: Some(12.0),
+        failure_message: Some("expected nil".into()),
+        backtrace: None,
+        timestamp: Instant::now(),
+    });
+    trackers.tests.complete_test_run(Some(12.0));
+
+    let processes = vec![ProcessInfo {
+        name: "web".to_string(),
+        command: "bin/rails server".to_string(),
+        status: ProcessStatus::Crashed,
+        start_time: None,
+        pid: None,
+        last_exit: Some(ProcessExit {
+            success: false,
+            code: Some(1),
+            at: Instant::now() - Duration::from_secs(5),
+        }),
+        restarts: 2,
+        crash_count: 0,
+        spawn_error: None,
+    }];
+    let logs = SESSION_LOG
+        .iter()
+        .enumerate()
+        .map(|(seq, content)| log_line(seq as u64, content))
+        .collect();
+    (trackers, processes, logs)
+}
+
+#[test]
+fn saved_session_restores_every_tracker() {
+    let dir = temp_dir("round_trip");
+    let path = dir.join("handoff.json.gz");
+    let (trackers, processes, logs) = session();
+
+    let archive = SessionArchive::capture(
+        &trackers.sources(&processes, &logs),
+        SessionMetadata::new(Some(Path::new("/code/shop"))),
+    );
+    archive.save(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap()[..2], [0x1f, 0x8b]);
+
+    let mut loaded = SessionArchive::load(&path).unwrap();
+    assert_eq!(loaded.version, SESSION_FORMAT_VERSION);
+    assert_eq!(loaded.metadata, archive.metadata);
+    assert_eq!(loaded.metadata.project.as_deref(), Some("/code/shop"));
+
+    let logs = std::mem::take(&mut loaded.logs);
+    let processes = std::mem::take(&mut loaded.processes);
+    assert_eq!(logs.len(), SESSION_LOG.len());
+    assert_eq!(logs[2].content, SESSION_LOG[2]);
+    assert_eq!(logs[2].stream, LogStream::Stdout);
+    // Ages survive the trip, so "how long ago" reads the same
+    assert!(logs[0].timestamp.elapsed() >= Duration::from_secs(30));
+    assert_eq!(processes[0].status, ProcessStatus::Crashed);
+    assert_eq!(processes[0].restarts, 2);
+    assert_eq!(processes[0].last_exit.as_ref().unwrap().code, Some(1));
+
+    let restored = Trackers::default();
+    loaded.restore_into(&restored.sources(&[], &[]));
+
+    let requests = restored.requests.get_recent_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].status, Some(200));
+    assert_eq!(requests[0].context.method.as_deref(), Some("GET"));
+    assert_eq!(requests[0].context.query_count(), 1);
+
+    let groups = restored.exceptions.get_grouped_exceptions();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].exception_type, "NoMethodError");
+    assert_eq!(restored.exceptions.get_stats().total_exceptions, 1);
+
+    let runs = restored.tests.get_recent_runs();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].failed_tests()[0].test_name, "User#name fails");
+
+    assert_eq!(restored.database.dialect(), SqlDialect::MySql);
+    assert_eq!(restored.database.get_slow_queries().len(), 1);
+    assert_eq!(
+        restored.database.get_score_history(),
+        trackers.database.get_score_history()
+    );
+    assert_eq!(restored.stats.get_stats().total_requests, 1);
+    assert_eq!(restored.stats.get_stats().sql_queries, 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn privacy_masks_what_a_saved_session_shows() {
+    let (trackers, processes, logs) = session();
+    let mut archive = SessionArchive::capture(
+        &trackers.sources(&processes, &logs),
+        SessionMetadata::new(None),
+    );
+
+    Privacy::default().archive(&mut archive);
+    assert_eq!(archive.logs[2].content, SESSION_LOG[2]);
+
+    Privacy::new(true, Vec::new()).archive(&mut archive);
+    assert!(archive.logs[2].content.ends_with(&format!("= '{}'", MASK)));
+    // Lines that aren't queries keep their apostrophes
+    assert_eq!(archive.logs[4].content, SESSION_LOG[4]);
+    let query = &archive.requests.completed[0].context.queries[0].raw_query;
+    assert!(query.ends_with(&format!("= '{}'", MASK)), "{}", query);
+    assert!(!archive.database.slow_queries[0].query.contains("a@b.c"));
+}
+
+#[test]
+fn newer_format_is_refused_and_missing_sections_load_empty() {
+    let saved_at = Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let newer = format!(
+        r#"{{"version": {}, "metadata": {{"caboose_version": "9.0.0", "project": null, "saved_at": "{}"}}}}"#,
+        SESSION_FORMAT_VERSION + 1,
+        saved_at.to_rfc3339()
+    );
+    let err = SessionArchive::from_json(newer.as_bytes()).unwrap_err();
+    assert!(err.contains("upgrade"), "{}", err);
+
+    let err = SessionArchive::from_json(br#"{"logs": []}"#).unwrap_err();
+    assert!(err.contains("no format version"), "{}", err);
+
+    // Plain JSON with only the required fields
+    let minimal = newer.replace(
+        &format!(r#""version": {}"#, SESSION_FORMAT_VERSION + 1),
+        &format!(r#""version": {}"#, SESSION_FORMAT_VERSION),
+    );
+    let dir = temp_dir("minimal");
+    let path = dir.join("minimal.json");
+    fs::write(&path, minimal).unwrap();
+    let archive = SessionArchive::load(&path).unwrap();
+    assert_eq!(archive.metadata.saved_at, saved_at);
+    assert!(archive.logs.is_empty());
+    assert!(archive.requests.completed.is_empty());
+
+    let err = SessionArchive::load(&dir.join("missing.json.gz")).unwrap_err();
+    assert!(err.contains("missing.json.gz"), "{}", err);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn default_file_name_is_timestamped() {
+    let now = Local.with_ymd_and_hms(2025, 3, 9, 14, 5, 7).unwrap();
+    assert_eq!(
+        default_file_name(now, DisplayZone::Local),
+        "caboose_session_20250309_140507.json.gz"
+    );
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use caboose::context::RequestContextTracker;
+use caboose::database::DatabaseHealth;
+use caboose::diagnostics::StoreKind;
+use caboose::exception::{ExceptionGrouping, ExceptionTracker};
+use caboose::parser::RailsLogParser;
+use caboose::shared::{Shared, SnapshotCache};
+use caboose::test::{TestFramework, TestResult, TestStatus, TestTracker};
+
+/// How long the stress test hammers the trackers
+const STRESS_FOR: Duration = Duration::from_secs(3);
+
+/// Slowest a frame's reads may be while ingestion runs flat out
+const MAX_FRAME: Duration = Duration::from_millis(500);
+
+#[test]
+fn snapshots_are_reused_until_a_write() {
+    let shared = Shared::new(vec![1, 2]);
+    let first = shared.snapshot();
+    assert!(Arc::ptr_eq(&first, &shared.snapshot()));
+
+    shared.write().push(3);
+    let second = shared.snapshot();
+    assert_eq!(*second, vec![1, 2, 3]);
+    // Readers holding the old snapshot keep it unchanged
+    assert_eq!(*first, vec![1, 2]);
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert_eq!(shared.generation(), 1);
+}
+
+#[test]
+fn derived_views_follow_writes() {
+    let shared = Shared::new(vec![3, 1, 2]);
+    let sorted = SnapshotCache::default();
+    let build = |values: &Vec<i32>| {
+        let mut values = values.clone();
+        values.sort();
+        values
+    };
+
+    let view = shared.derive(&sorted, build);
+    assert_eq!(*view, vec![1, 2, 3]);
+    assert!(Arc::ptr_eq(&view, &shared.derive(&sorted, build)));
+
+    shared.write().push(0);
+    assert_eq!(*shared.derive(&sorted, build), vec![0, 1, 2, 3]);
+
+    sorted.clear();
+    assert!(!Arc::ptr_eq(&view, &shared.derive(&sorted, build)));
+}
+
+#[test]
+fn tracker_getters_share_snapshots_until_something_changes() {
+    let requests = RequestContextTracker::new();
+    let exceptions = ExceptionTracker::new();
+    let db = DatabaseHealth::new();
+    let tests = TestTracker::new();
+    ingest(&requests, &db, &tests, &exceptions, 1);
+
+    assert!(Arc::ptr_eq(
+        &requests.get_recent_requests(),
+        &requests.get_recent_requests()
+    ));
+    assert!(Arc::ptr_eq(
+        &exceptions.get_grouped_exceptions(),
+        &exceptions.get_grouped_exceptions()
+    ));
+    assert!(Arc::ptr_eq(&db.get_issues(), &db.get_issues()));
+    assert!(Arc::ptr_eq(&tests.get_stats(), &tests.get_stats()));
+
+    let before = requests.get_recent_requests();
+    ingest(&requests, &db, &tests, &exceptions, 2);
+    let after = requests.get_recent_requests();
+    assert_eq!(after.len(), before.len() + 1);
+}
+
+/// One request, slow query, exception and test run
+fn ingest(
+    requests: &RequestContextTracker,
+    db: &DatabaseHealth,
+    tests: &TestTracker,
+    exceptions: &ExceptionTracker,
+    n: usize,
+) {
+    let sql = format!(
+        r#"SELECT "users".* FROM "users" WHERE "users"."email" = 'user{}@example.com'"#,
+        n
+    );
+    for line in [
+        format!(
+            r#"Started GET "/users/{}" for 127.0.0.1 at 2024-01-01 00:00:00"#,
+            n
+        ),
+        "Processing by UsersController#show as HTML".to_string(),
+        format!("  User Load (150.2ms)  {}", sql),
+        "Completed 200 OK in 180ms (Views: 1.0ms | ActiveRecord: 150.2ms)".to_string(),
+    ] {
+        if let Some(event) = RailsLogParser::parse_line(&line) {
+            requests.process_log_event(&event);
+        }
+    }
+    db.analyze_query(&sql, 150.2);
+
+    exceptions.parse_line(&format!(
+        "NoMethodError: undefined method `name{}' for nil:NilClass",
+        n % 7
+    ));
+    exceptions.parse_line(&format!("  app/models/user.rb:{}:in `name'", n % 3));
+    exceptions.parse_line("Completed 500 Internal Server Error in 5ms");
+
+    tests.start_test_run(TestFramework::RSpec);
+    tests.add_test_result(TestResult {
+        test_name: format!("user {}", n),
+        file_path: None,
+        line_number: None,
+        status: TestStatus::Passed,
+        duration: Some(120.0),
+        failure_message: None,
+        backtrace: None,
+        timestamp: Instant::now(),
+    });
+    tests.complete_test_run(Some(130.0));
+}
+
+#[test]
+fn trackers_survive_concurrent_ingest_render_and_trimming() {
+    let requests = Arc::new(RequestContextTracker::new());
+    let db = Arc::new(DatabaseHealth::new());
+    let tests = Arc::new(TestTracker::new());
+    let exceptions = Arc::new(ExceptionTracker::new());
+    let deadline = Instant::now() + STRESS_FOR;
+
+    let ingester = {
+        let (requests, db, tests, exceptions) = (
+            requests.clone(),
+            db.clone(),
+            tests.clone(),
+            exceptions.clone(),
+        );
+        thread::spawn(move || {
+            let mut n = 0;
+            while Instant::now() < deadline {
+                ingest(&requests, &db, &tests, &exceptions, n);
+                n += 1;
+            }
+            n
+        })
+    };
+
+    // What the UI reads each frame
+    let renderer = {
+        let (requests, db, tests, exceptions) = (
+            requests.clone(),
+            db.clone(),
+            tests.clone(),
+            exceptions.clone(),
+        );
+        thread::spawn(move || {
+            let mut slowest = Duration::ZERO;
+            let mut frames = 0;
+            while Instant::now() < deadline {
+                let started = Instant::now();
+                let _ = requests.get_recent_requests().len();
+                let _ = requests.get_current_requests().len();
+                let _ = requests.n_plus_one_count();
+                let _ = exceptions.get_grouped_exceptions().len();
+                let _ = exceptions.get_stats().total_exceptions;
+                let _ = exceptions.weighted_rate_at(Instant::now());
+                let _ = db.get_stats().total_queries;
+                let _ = db.get_slow_queries().len();
+                let _ = db.calculate_health_score();
+                let _ = tests.get_stats().total_runs;
+                let _ = tests.get_recent_runs().len();
+                slowest = slowest.max(started.elapsed());
+                frames += 1;
+            }
+            (frames, slowest)
+        })
+    };
+
+    // Memory pressure trimming and `/clear`, which take several locks at once
+    let housekeeper = {
+        let (requests, db, tests, exceptions) = (
+            requests.clone(),
+            db.clone(),
+            tests.clone(),
+            exceptions.clone(),
+        );
+        thread::spawn(move || {
+            let mut round = 0;
+            while Instant::now() < deadline {
+                let _ = requests.store_usage();
+                let _ = db.store_usage();
+                let _ = tests.store_usage();
+                let _ = exceptions.store_usage();
+                requests.trim(StoreKind::CompletedRequests, 50);
+                db.trim(StoreKind::SlowQueries, 10);
+                db.trim(StoreKind::TablesAccessed, 5);
+                tests.trim(StoreKind::TestRuns, 3);
+                exceptions.trim(StoreKind::ExceptionGroups, 3);
+                exceptions.set_grouping(ExceptionGrouping {
+                    with_location: round % 2 == 0,
+                    line_numbers: true,
+                });
+                if round % 10 == 0 {
+                    exceptions.clear_stats();
+                }
+                round += 1;
+                thread::sleep(Duration::from_millis(5));
+            }
+        })
+    };
+
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+        let ingested = ingester.join().unwrap();
+        let rendered = renderer.join().unwrap();
+        housekeeper.join().unwrap();
+        let _ = done.send((ingested, rendered));
+    });
+    let (ingested, (frames, slowest)) = finished
+        .recv_timeout(STRESS_FOR + Duration::from_secs(10))
+        .expect("trackers deadlocked");
+
+    assert!(ingested > 0 && frames > 0);
+    assert!(
+        slowest < MAX_FRAME,
+        "slowest frame took {:?} ({} frames, {} ingested)",
+        slowest,
+        frames,
+        ingested
+    );
+}
@@ -1,13 +1,25 @@
-use caboose::stats::{PerformanceStats, StatsCollector};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use caboose::diagnostics::StoreKind;
+use caboose::parser::{CacheEvent, CacheOperation};
+use caboose::shared::SnapshotCache;
+use caboose::stats::{
+    DB_SHARE_WINDOW, DbShare, DbShareLevel, DbShareThresholds, HeaderStats, MAX_TIMELINE_BUCKETS,
+    PerformanceStats, StatsCollector, TIMELINE_BUCKET, Timeline, TimelineEvent,
+};
+use chrono::{Local, TimeDelta, TimeZone};
 
 #[test]
 fn performance_stats_calculations() {
-    let mut stats = PerformanceStats::default();
-    stats.total_requests = 2;
-    stats.total_duration = 50.0;
-    stats.error_count = 1;
-    stats.sql_queries = 2;
-    stats.total_sql_duration = 10.0;
+    let stats = PerformanceStats {
+        total_requests: 2,
+        total_duration: 50.0,
+        error_count: 1,
+        sql_queries: 2,
+        total_sql_duration: 10.0,
+        ..Default::default()
+    };
 
     assert_eq!(stats.avg_response_time(), 25.0);
     assert_eq!(stats.error_rate(), 50.0);
@@ -28,3 +40,312 @@ fn stats_collector_records_requests_and_sql() {
     assert_eq!(stats.sql_queries, 1);
     assert_eq!(stats.avg_response_time(), 15.0);
 }
+
+#[test]
+fn header_stats_rebuild_only_when_the_stats_change() {
+    let collector = StatsCollector::new();
+    let cache = SnapshotCache::<HeaderStats>::default();
+    collector.record_request(200, 10.0);
+    collector.record_request(500, 30.0);
+
+    let header = collector.header_stats(&cache);
+    assert_eq!(header.total_requests, 2);
+    assert_eq!(header.avg_response_time, 20.0);
+    assert_eq!(header.error_rate, 50.0);
+    assert_eq!(header.response_times, vec![10.0, 20.0]);
+
+    // Idle frames get the same view-model back
+    let generation = collector.generation();
+    assert!(Arc::ptr_eq(&header, &collector.header_stats(&cache)));
+    assert_eq!(collector.generation(), generation);
+
+    collector.record_sql_query(4.0);
+    assert!(collector.generation() > generation);
+    let rebuilt = collector.header_stats(&cache);
+    assert!(!Arc::ptr_eq(&header, &rebuilt));
+    assert_eq!(rebuilt.sql_queries, 1);
+}
+
+#[test]
+fn trims_response_time_history() {
+    let collector = StatsCollector::new();
+    for i in 0..20 {
+        collector.record_request(200, i as f64);
+    }
+
+    let usage = collector.store_usage();
+    assert_eq!(usage[0].kind, StoreKind::ResponseTimeHistory);
+    assert_eq!(usage[0].items, 20);
+
+    let newest = collector.get_response_time_history()[15..].to_vec();
+    assert_eq!(collector.trim(StoreKind::ResponseTimeHistory, 5), 15);
+    assert_eq!(collector.get_response_time_history(), newest);
+    assert_eq!(collector.get_stats().total_requests, 20);
+}
+
+#[test]
+fn db_share_uses_logged_db_time_or_pending_sql() {
+    let collector = StatsCollector::new();
+
+    // Logged ActiveRecord time wins over SQL lines seen in between
+    collector.record_sql_query(99.0);
+    collector.record_request_with_db_time(200, 100.0, Some(30.0));
+
+    // Without a logged DB time, SQL since the previous request is attributed
+    collector.record_sql_query(20.0);
+    collector.record_sql_query(30.0);
+    collector.record_request(200, 100.0);
+
+    let share = collector.db_share().unwrap();
+    assert_eq!(share.requests, 2);
+    assert_eq!(share.db_ms, 80.0);
+    assert_eq!(share.app_ms, 120.0);
+    assert_eq!(share.db_percent(), 40.0);
+}
+
+#[test]
+fn db_share_ignores_requests_outside_the_window() {
+    let collector = StatsCollector::new();
+    collector.record_request_with_db_time(200, 50.0, Some(25.0));
+
+    let stats = collector.get_stats();
+    let later = Instant::now() + DB_SHARE_WINDOW + Duration::from_secs(1);
+    assert!(stats.db_share_at(later, DB_SHARE_WINDOW).is_none());
+    assert!(stats.db_share_at(Instant::now(), DB_SHARE_WINDOW).is_some());
+}
+
+#[test]
+fn db_share_handles_empty_windows_without_nan() {
+    let collector = StatsCollector::new();
+    assert!(collector.db_share().is_none());
+
+    // Zero-duration requests give 0%, not NaN
+    collector.record_request_with_db_time(200, 0.0, Some(0.0));
+    let share = collector.db_share().unwrap();
+    assert_eq!(share.db_percent(), 0.0);
+
+    // DB time larger than the request is clamped
+    collector.record_request_with_db_time(200, 10.0, Some(50.0));
+    let share = collector.db_share().unwrap();
+    assert_eq!(share.db_percent(), 100.0);
+}
+
+#[test]
+fn db_share_thresholds_pick_levels() {
+    let thresholds = DbShareThresholds {
+        warning: 40.0,
+        danger: 70.0,
+    };
+    let share = |db_ms: f64| DbShare {
+        requests: 1,
+        db_ms,
+        app_ms: 100.0 - db_ms,
+    };
+
+    assert_eq!(thresholds.level(&share(10.0)), DbShareLevel::Normal);
+    assert_eq!(thresholds.level(&share(40.0)), DbShareLevel::Warning);
+    assert_eq!(thresholds.level(&share(85.0)), DbShareLevel::Danger);
+
+    let collector = StatsCollector::new().with_db_share_thresholds(thresholds);
+    assert_eq!(collector.db_share_thresholds(), thresholds);
+}
+
+#[test]
+fn requests_at_summarises_the_window() {
+    let collector = StatsCollector::new();
+    assert!(
+        collector
+            .requests_at(Instant::now(), Duration::from_secs(60))
+            .is_none()
+    );
+
+    for duration in 1..=20 {
+        collector.record_request(200, f64::from(duration) * 10.0);
+    }
+    collector.record_request(503, 500.0);
+    collector.record_request(404, 5.0);
+
+    let window = collector
+        .requests_at(Instant::now(), Duration::from_secs(60))
+        .unwrap();
+    assert_eq!(window.requests, 22);
+    assert_eq!(window.errors, 2);
+    assert!((window.error_rate() - 100.0 * 2.0 / 22.0).abs() < 1e-9);
+    assert_eq!(window.p95_duration, 200.0);
+
+    let later = Instant::now() + Duration::from_secs(61);
+    assert!(
+        collector
+            .requests_at(later, Duration::from_secs(60))
+            .is_none()
+    );
+}
+
+#[test]
+fn records_cache_activity_per_prefix() {
+    let collector = StatsCollector::new();
+    assert_eq!(collector.get_stats().cache.hit_rate(), None);
+
+    for (operation, key) in [
+        (CacheOperation::Hit, "views/users/1"),
+        (CacheOperation::Hit, "views/users/2"),
+        (CacheOperation::Miss, "views/users/3"),
+        (CacheOperation::Miss, "products/1"),
+        (CacheOperation::Write, "views/users/3"),
+        (CacheOperation::Delete, "products/1"),
+    ] {
+        collector.record_cache(&CacheEvent {
+            operation,
+            key: key.to_string(),
+        });
+    }
+
+    let stats = collector.get_stats();
+    assert_eq!(stats.cache.reads(), 4);
+    assert_eq!(stats.cache.writes, 1);
+    assert_eq!(stats.cache.deletes, 1);
+    assert_eq!(stats.cache.hit_rate(), Some(50.0));
+
+    let prefixes = stats.cache_prefixes();
+    assert_eq!(prefixes[0].0, "views");
+    assert_eq!(prefixes[0].1.hits, 2);
+    assert_eq!(prefixes[1].0, "products");
+    assert_eq!(prefixes[1].1.hit_rate(), Some(0.0));
+
+    assert_eq!(collector.cache_hit_rate_warning(), 80.0);
+    assert_eq!(
+        StatsCollector::new()
+            .with_cache_hit_rate_warning(60.0)
+            .cache_hit_rate_warning(),
+        60.0
+    );
+}
+
+#[test]
+fn timeline_counts_events_into_ten_second_buckets() {
+    let start = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+    let collector = StatsCollector::new();
+    collector.record_timeline(start, TimelineEvent::Request { status: 200 });
+    collector.record_timeline(
+        start + TimeDelta::seconds(3),
+        TimelineEvent::Request { status: 500 },
+    );
+    collector.record_timeline(start + TimeDelta::seconds(12), TimelineEvent::Exception);
+    collector.record_timeline(start + TimeDelta::seconds(25), TimelineEvent::Restart);
+
+    let window = collector.timeline_window(start + TimeDelta::seconds(25), TIMELINE_BUCKET, 3);
+    let starts: Vec<_> = window.iter().map(|(at, _)| *at).collect();
+    assert_eq!(
+        starts,
+        vec![
+            start,
+            start + TimeDelta::seconds(10),
+            start + TimeDelta::seconds(20)
+        ]
+    );
+    assert_eq!((window[0].1.requests, window[0].1.errors), (2, 1));
+    assert_eq!(window[1].1.exceptions, 1);
+    assert_eq!(window[2].1.restarts, 1);
+    assert_eq!(
+        collector.timeline_latest(),
+        Some(start + TimeDelta::seconds(20))
+    );
+}
+
+#[test]
+fn timeline_windows_sum_whole_buckets_when_zoomed_out() {
+    let start = Local.timestamp_opt(1_700_000_040, 0).unwrap(); // a minute boundary
+    let mut timeline = Timeline::default();
+    for second in (0..120).step_by(10) {
+        timeline.record(
+            start + TimeDelta::seconds(second),
+            TimelineEvent::Request { status: 200 },
+        );
+    }
+    timeline.record(start + TimeDelta::seconds(70), TimelineEvent::Deploy);
+
+    // Mid-minute ends still land in the minute holding them
+    let window = timeline.window(start + TimeDelta::seconds(95), TimeDelta::minutes(1), 3);
+    assert_eq!(window[0].0, start - TimeDelta::minutes(1));
+    assert!(window[0].1.is_empty());
+    assert_eq!(window[1].1.requests, 6);
+    assert_eq!((window[2].1.requests, window[2].1.deploys), (6, 1));
+}
+
+#[test]
+fn timeline_keeps_only_the_newest_buckets() {
+    let start = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+    let mut timeline = Timeline::default();
+    timeline.record(start, TimelineEvent::Crash);
+    let last = start + TIMELINE_BUCKET * (MAX_TIMELINE_BUCKETS as i32 + 5);
+    timeline.record(last, TimelineEvent::TestRun);
+
+    assert_eq!(timeline.len(), MAX_TIMELINE_BUCKETS);
+    assert_eq!(
+        timeline.earliest(),
+        Some(last - TIMELINE_BUCKET * (MAX_TIMELINE_BUCKETS as i32 - 1))
+    );
+    // Too old to fit any more
+    timeline.record(start, TimelineEvent::Crash);
+    assert!(
+        timeline
+            .window(start, TIMELINE_BUCKET, 1)
+            .iter()
+            .all(|(_, bucket)| bucket.is_empty())
+    );
+}
+
+#[test]
+fn timeline_survives_a_saved_session() {
+    let start = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+    let collector = StatsCollector::new();
+    collector.record_timeline(start, TimelineEvent::Request { status: 200 });
+
+    let json = serde_json::to_string(&collector.get_stats()).unwrap();
+    let restored = StatsCollector::new();
+    restored.restore(serde_json::from_str(&json).unwrap());
+    assert_eq!(
+        restored.timeline_window(start, TIMELINE_BUCKET, 1)[0]
+            .1
+            .requests,
+        1
+    );
+
+    // Sessions saved before the timeline existed still load
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("timeline");
+    let old: PerformanceStats = serde_json::from_value(value).unwrap();
+    assert!(old.timeline.is_empty());
+}
+
+#[test]
+fn timeline_marks_a_suspension_as_asleep() {
+    let start = Local.timestamp_opt(1_700_000_040, 0).unwrap(); // a minute boundary
+    let collector = StatsCollector::new();
+    collector.record_timeline(start, TimelineEvent::Request { status: 200 });
+    let woke = start + TimeDelta::minutes(3);
+    collector.record_suspension(start + TimeDelta::seconds(5), woke);
+    collector.record_timeline(woke, TimelineEvent::Request { status: 200 });
+
+    let window = collector.timeline_window(woke, TIMELINE_BUCKET, 19);
+    assert_eq!(window[0].0, start);
+    assert_eq!((window[0].1.requests, window[0].1.suspended), (1, 1));
+    assert!(
+        window[1..18]
+            .iter()
+            .all(|(_, bucket)| bucket.suspended == 1 && bucket.requests == 0 && !bucket.is_empty())
+    );
+    assert_eq!((window[18].1.requests, window[18].1.suspended), (1, 1));
+
+    // Zoomed out, a minute counts the buckets of it spent asleep
+    let minutes = collector.timeline_window(woke, TimeDelta::minutes(1), 4);
+    let asleep: Vec<u32> = minutes.iter().map(|(_, bucket)| bucket.suspended).collect();
+    assert_eq!(asleep, [6, 6, 6, 1]);
+
+    // A suspension longer than the timeline keeps only its newest part
+    let mut timeline = Timeline::default();
+    let later = woke + TimeDelta::days(2);
+    timeline.record_suspension(woke, later);
+    assert_eq!(timeline.len(), MAX_TIMELINE_BUCKETS);
+    assert_eq!(timeline.latest(), Some(later));
+}
@@ -1,9 +1,16 @@
-use caboose::test::{DebuggerType, TestFramework, TestResult, TestStatus, TestTracker};
+use std::fs;
+use std::path::Path;
+
+use caboose::diagnostics::Limits;
+use caboose::search::SearchQuery;
+use caboose::test::{DebuggerType, TestFramework, TestResult, TestRun, TestStatus, TestTracker};
 
 #[test]
 fn test_run_success_rate_and_results() {
     let tracker = TestTracker::new();
+    assert!(tracker.is_empty());
     tracker.start_test_run(TestFramework::RSpec);
+    assert!(!tracker.is_empty());
     tracker.add_test_result(TestResult {
         test_name: "passes".into(),
         file_path: None,
@@ -57,3 +64,260 @@ fn detects_debugger_activation() {
     assert_eq!(info.file_path.as_deref(), Some("/app/foo.rb"));
     assert_eq!(info.line_number, Some(42));
 }
+
+fn result(name: &str, status: TestStatus) -> TestResult {
+    TestResult {
+        test_name: name.into(),
+        file_path: None,
+        line_number: None,
+        status,
+        duration: Some(10.0),
+        failure_message: None,
+        backtrace: None,
+        timestamp: std::time::Instant::now(),
+    }
+}
+
+#[test]
+fn filters_test_results_by_name_across_runs() {
+    let tracker = TestTracker::new();
+    tracker.start_test_run(TestFramework::RSpec);
+    tracker.add_test_result(result("User validates email", TestStatus::Passed));
+    tracker.add_test_result(result("Post has a title", TestStatus::Passed));
+    tracker.complete_test_run(Some(20.0));
+
+    // The run in progress is searched too, and listed first
+    tracker.start_test_run(TestFramework::RSpec);
+    tracker.add_test_result(result("User rejects blank email", TestStatus::Failed));
+
+    let matches = tracker.get_test_results_matching(&SearchQuery::new("EMAIL"));
+    let names: Vec<_> = matches.iter().map(|r| r.test_name.as_str()).collect();
+    assert_eq!(names, ["User rejects blank email", "User validates email"]);
+}
+
+#[test]
+fn run_and_slowest_test_limits_apply_and_resize() {
+    let tracker = TestTracker::new().with_limits(Limits {
+        max_test_runs: 2,
+        max_slowest_tests: 3,
+        ..Limits::default()
+    });
+    for run in 0..3 {
+        tracker.start_test_run(TestFramework::RSpec);
+        for test in 0..2 {
+            let mut slow = result(&format!("slow {} {}", run, test), TestStatus::Passed);
+            slow.duration = Some(200.0 + (run * 10 + test) as f64);
+            tracker.add_test_result(slow);
+        }
+        tracker.complete_test_run(None);
+    }
+    assert_eq!(tracker.get_recent_runs().len(), 2);
+    assert_eq!(tracker.get_stats().slowest_tests.len(), 3);
+
+    tracker.set_limits(Limits {
+        max_test_runs: 1,
+        max_slowest_tests: 1,
+        ..Limits::default()
+    });
+    assert_eq!(tracker.get_recent_runs().len(), 1);
+    let slowest = tracker.get_stats().slowest_tests.clone();
+    assert_eq!(slowest.len(), 1);
+    assert_eq!(slowest[0].test_name, "slow 2 1");
+}
+
+#[test]
+fn debugger_pause_is_left_out_of_run_and_test_durations() {
+    let tracker = TestTracker::new();
+    tracker.start_test_run(TestFramework::RSpec);
+    tracker.parse_line("From: /app/app/models/user.rb:27 User#full_name:");
+    tracker.parse_line("[1] pry(#<User>)>");
+
+    let pause = tracker.debugger_pause().expect("run should be paused");
+    assert_eq!(
+        pause.location().as_deref(),
+        Some("/app/app/models/user.rb:27")
+    );
+    std::thread::sleep(std::time::Duration::from_millis(60));
+
+    tracker.parse_line("[1] pry(#<User>)> continue");
+    assert!(tracker.debugger_pause().is_none());
+    assert!(!tracker.is_debugger_active());
+
+    let mut slow = result("stops at a breakpoint", TestStatus::Passed);
+    slow.duration = Some(200.0);
+    tracker.add_test_result(slow);
+    let mut next = result("runs after it", TestStatus::Passed);
+    next.duration = Some(200.0);
+    tracker.add_test_result(next);
+    tracker.complete_test_run(Some(1000.0));
+
+    let run = tracker.get_recent_runs().last().cloned().unwrap();
+    assert!(run.paused_ms >= 60.0);
+    assert!(run.test_results[0].duration.unwrap() <= 140.0);
+    // Only the test the breakpoint interrupted loses the paused time
+    assert_eq!(run.test_results[1].duration, Some(200.0));
+    assert_eq!(run.duration, Some(1000.0 - run.paused_ms));
+}
+
+#[test]
+fn next_test_output_resumes_a_paused_run() {
+    let tracker = TestTracker::new();
+    tracker.start_test_run(TestFramework::RSpec);
+    tracker.parse_line("[22, 31] in /app/app/models/order.rb");
+    tracker.parse_line("=> 27:     total = line_items.sum(&:price)");
+    tracker.parse_line("(byebug)");
+    assert_eq!(
+        tracker.debugger_pause().unwrap().location().as_deref(),
+        Some("/app/app/models/order.rb:27")
+    );
+
+    tracker.parse_line("..F");
+    assert!(tracker.debugger_pause().is_none());
+    assert!(tracker.get_current_run().unwrap().paused_ms > 0.0);
+}
+
+#[test]
+fn debugger_outside_a_run_only_sets_the_indicator() {
+    let tracker = TestTracker::new();
+    tracker.parse_line("[1] pry(main)>");
+
+    assert!(tracker.is_debugger_active());
+    assert!(tracker.debugger_pause().is_none());
+    assert!(tracker.get_current_run().is_none());
+}
+
+/// Feed a captured `tests/fixtures/rspec` run and return it once finished
+fn replay_rspec(name: &str) -> TestRun {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/rspec")
+        .join(name);
+    let tracker = TestTracker::new();
+    for line in fs::read_to_string(path).unwrap().lines() {
+        tracker.parse_line(line);
+    }
+    assert!(
+        !tracker.is_running(),
+        "the trailing seed line starts nothing"
+    );
+    let runs = tracker.get_recent_runs();
+    assert_eq!(runs.len(), 1);
+    runs[0].clone()
+}
+
+#[test]
+fn documentation_formatter_failures_get_their_message_and_location() {
+    let run = replay_rspec("documentation.log");
+    assert_eq!(run.framework, TestFramework::RSpec);
+    assert_eq!(
+        (run.total_tests, run.passed, run.failed, run.pending),
+        (7, 4, 2, 1)
+    );
+    assert_eq!(run.duration, Some(423.18));
+
+    let failed = run.failed_tests();
+    let names: Vec<_> = failed.iter().map(|t| t.test_name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "User validations validates email",
+            "Order#total sums the line item prices"
+        ]
+    );
+
+    let email = failed[0];
+    assert_eq!(
+        email.file_path.as_deref(),
+        Some("./spec/models/user_spec.rb")
+    );
+    assert_eq!(email.line_number, Some(14));
+    assert_eq!(
+        email.failure_message.as_deref(),
+        Some(
+            "Failure/Error: expect(user).to be_valid\n\n  expected #<User id: nil, name: \"Ada\", email: \"ada@\"> to be valid, but got errors: Email is invalid"
+        )
+    );
+
+    // The example's own frame, not the app code it failed in
+    let total = failed[1];
+    assert_eq!(
+        total.file_path.as_deref(),
+        Some("./spec/models/order_spec.rb")
+    );
+    assert_eq!(total.line_number, Some(9));
+    assert_eq!(total.backtrace.as_ref().unwrap().len(), 3);
+    assert!(
+        total
+            .failure_message
+            .as_deref()
+            .unwrap()
+            .ends_with("NoMethodError:\n  undefined method `price' for nil:NilClass")
+    );
+
+    let pending: Vec<_> = run
+        .test_results
+        .iter()
+        .filter(|t| t.status == TestStatus::Pending)
+        .collect();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        pending[0].test_name,
+        "User#full_name handles a missing last name"
+    );
+    assert_eq!(
+        pending[0].failure_message.as_deref(),
+        Some("Not yet implemented")
+    );
+    assert_eq!(pending[0].line_number, Some(31));
+}
+
+#[test]
+fn progress_formatter_failures_keep_the_expected_and_actual_diff() {
+    let run = replay_rspec("progress.log");
+    assert_eq!(
+        (run.total_tests, run.passed, run.failed, run.pending),
+        (10, 8, 1, 1)
+    );
+    assert_eq!(run.duration, Some(1050.0));
+
+    let failed = run.failed_tests();
+    assert_eq!(failed.len(), 1);
+    let email = failed[0];
+    assert_eq!(email.test_name, "User validates email");
+    assert_eq!(
+        email.file_path.as_deref(),
+        Some("./spec/models/user_spec.rb")
+    );
+    assert_eq!(email.line_number, Some(42));
+    assert_eq!(
+        email.backtrace.as_deref(),
+        Some(
+            &[
+                "./spec/models/user_spec.rb:42:in `block (2 levels) in <top (required)>'"
+                    .to_string()
+            ][..]
+        )
+    );
+    let message = email.failure_message.as_deref().unwrap();
+    assert!(message.starts_with("Failure/Error: expect(user.email)"));
+    assert!(message.contains("  expected: \"ada@example.com\"\n       got: \"ADA@example.com\""));
+    assert!(
+        message.ends_with("Diff:\n  @@ -1 +1 @@\n  -\"ada@example.com\"\n  +\"ADA@example.com\"")
+    );
+}
+
+#[test]
+fn the_summary_alone_fills_in_the_counts() {
+    let tracker = TestTracker::new();
+    tracker.parse_line("Randomized with seed 1234");
+    tracker.parse_line("Finished in 1 minute 2.5 seconds (files took 3 seconds to load)");
+    tracker.parse_line("10 examples, 2 failures, 1 pending");
+
+    let run = tracker.get_recent_runs().last().cloned().unwrap();
+    assert_eq!(run.framework, TestFramework::RSpec);
+    assert_eq!(
+        (run.total_tests, run.passed, run.failed, run.pending),
+        (10, 7, 2, 1)
+    );
+    assert_eq!(run.duration, Some(62_500.0));
+    assert_eq!(tracker.get_stats().total_failed, 2);
+}
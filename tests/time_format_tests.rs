@@ -0,0 +1,121 @@
+use caboose::privacy::Privacy;
+use caboose::process::{LogLine, LogStream};
+use caboose::time_format::DisplayZone;
+use caboose::time_range::parse_time_spec;
+use caboose::ui::command::commands::{default_export_file_name, export_line};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+        .unwrap()
+        .with_timezone(&Local)
+}
+
+fn berlin() -> DisplayZone {
+    DisplayZone::parse("Europe/Berlin").unwrap()
+}
+
+#[test]
+fn parses_local_utc_and_iana_names() {
+    assert_eq!(DisplayZone::parse("local"), Ok(DisplayZone::Local));
+    assert_eq!(DisplayZone::parse("UTC"), Ok(DisplayZone::Utc));
+    assert_eq!(DisplayZone::parse(" utc "), Ok(DisplayZone::Utc));
+    assert_eq!(berlin().to_string(), "Europe/Berlin");
+
+    let err = DisplayZone::parse("Mars/Olympus").unwrap_err();
+    assert!(err.contains("Mars/Olympus"), "{}", err);
+}
+
+#[test]
+fn invalid_config_zone_falls_back_to_local_with_a_warning() {
+    assert_eq!(DisplayZone::from_config(None), (DisplayZone::Local, None));
+    assert_eq!(
+        DisplayZone::from_config(Some("Asia/Tokyo")),
+        (DisplayZone::parse("Asia/Tokyo").unwrap(), None)
+    );
+
+    let (zone, warning) = DisplayZone::from_config(Some("Europe/Atlantis"));
+    assert_eq!(zone, DisplayZone::Local);
+    let warning = warning.unwrap();
+    assert!(warning.contains("[ui] timezone"), "{}", warning);
+    assert!(warning.contains("Europe/Atlantis"), "{}", warning);
+}
+
+#[test]
+fn formats_in_the_zone_with_its_abbreviation() {
+    let winter = utc(2024, 1, 15, 12, 0);
+    let summer = utc(2024, 7, 15, 12, 0);
+
+    assert_eq!(DisplayZone::Utc.clock(winter), "12:00:00");
+    assert_eq!(
+        DisplayZone::Utc.date_time(winter),
+        "2024-01-15 12:00:00 UTC"
+    );
+    assert_eq!(berlin().date_time(winter), "2024-01-15 13:00:00 CET");
+    assert_eq!(berlin().date_time(summer), "2024-07-15 14:00:00 CEST");
+    assert_eq!(berlin().file_stamp(summer), "20240715_140000");
+    // Local time keeps the plain format
+    assert_eq!(
+        DisplayZone::Local.date_time(winter),
+        winter.format("%Y-%m-%d %H:%M:%S").to_string()
+    );
+}
+
+#[test]
+fn wall_clock_times_across_dst_boundaries() {
+    let zone = berlin();
+    let resolve = |month, day, h, min| {
+        zone.resolve(
+            NaiveDate::from_ymd_opt(2024, month, day).unwrap(),
+            NaiveTime::from_hms_opt(h, min, 0).unwrap(),
+        )
+    };
+
+    // 31 March 2024: clocks jump from 02:00 to 03:00, so 02:30 never happens
+    assert_eq!(resolve(3, 31, 1, 59), Some(utc(2024, 3, 31, 0, 59)));
+    assert_eq!(resolve(3, 31, 2, 30), None);
+    assert_eq!(resolve(3, 31, 3, 0), Some(utc(2024, 3, 31, 1, 0)));
+
+    // 27 October 2024: 02:30 happens twice; the first (CEST) one is used
+    assert_eq!(resolve(10, 27, 2, 30), Some(utc(2024, 10, 27, 0, 30)));
+    assert_eq!(resolve(10, 27, 3, 0), Some(utc(2024, 10, 27, 2, 0)));
+}
+
+#[test]
+fn around_reads_typed_times_in_the_display_zone() {
+    // 23:30 UTC is already the next day in Berlin
+    let now = utc(2024, 5, 14, 23, 30);
+
+    assert_eq!(
+        parse_time_spec("01:15", now, berlin()),
+        Ok(utc(2024, 5, 14, 23, 15))
+    );
+    assert_eq!(
+        parse_time_spec("23:00", now, DisplayZone::Utc),
+        Ok(utc(2024, 5, 14, 23, 0))
+    );
+    assert!(
+        parse_time_spec("02:30", utc(2024, 3, 31, 12, 0), berlin())
+            .unwrap_err()
+            .contains("does not exist")
+    );
+}
+
+#[test]
+fn exports_stamp_lines_and_file_names_in_the_zone() {
+    let time = utc(2024, 1, 15, 12, 0);
+    let log = LogLine {
+        time,
+        stream: LogStream::Stdout,
+        ..LogLine::new("web", r#"Started GET "/" for 127.0.0.1"#)
+    };
+
+    assert_eq!(
+        export_line(&log, DisplayZone::Utc, &Privacy::default()),
+        r#"2024-01-15 12:00:00 UTC [web] Started GET "/" for 127.0.0.1"#
+    );
+    assert_eq!(
+        default_export_file_name(time, berlin()),
+        "caboose_logs_20240115_130000.txt"
+    );
+}
@@ -0,0 +1,128 @@
+use caboose::process::LogLine;
+use caboose::time_format::DisplayZone;
+use caboose::time_range::{
+    DEFAULT_WINDOW, TimeRange, first_at_or_after, parse_time_spec, parse_window,
+};
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone};
+
+fn at(hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+    Local
+        .from_local_datetime(
+            &NaiveDate::from_ymd_opt(2024, 5, 14)
+                .unwrap()
+                .and_hms_opt(hour, minute, second)
+                .unwrap(),
+        )
+        .unwrap()
+}
+
+fn line(time: DateTime<Local>) -> LogLine {
+    LogLine {
+        time,
+        ..LogLine::new("web", time.format("%H:%M:%S").to_string())
+    }
+}
+
+#[test]
+fn parses_clock_times_as_the_most_recent_occurrence() {
+    let now = at(15, 0, 0);
+    assert_eq!(
+        parse_time_spec("14:32", now, DisplayZone::Local),
+        Ok(at(14, 32, 0))
+    );
+    assert_eq!(
+        parse_time_spec("14:32:15", now, DisplayZone::Local),
+        Ok(at(14, 32, 15))
+    );
+
+    // Later than now means yesterday
+    let yesterday = parse_time_spec("23:50", now, DisplayZone::Local).unwrap();
+    assert_eq!(yesterday, at(23, 50, 0) - TimeDelta::days(1));
+
+    assert!(parse_time_spec("25:00", now, DisplayZone::Local).is_err());
+    assert!(parse_time_spec("lunch", now, DisplayZone::Local).is_err());
+}
+
+#[test]
+fn parses_relative_times_and_windows() {
+    let now = at(15, 0, 0);
+    assert_eq!(
+        parse_time_spec("-5m", now, DisplayZone::Local),
+        Ok(at(14, 55, 0))
+    );
+    assert_eq!(
+        parse_time_spec("-90s", now, DisplayZone::Local),
+        Ok(at(14, 58, 30))
+    );
+    assert_eq!(
+        parse_time_spec("-1h", now, DisplayZone::Local),
+        Ok(at(14, 0, 0))
+    );
+    assert!(parse_time_spec("-5x", now, DisplayZone::Local).is_err());
+
+    assert_eq!(parse_window("±30s"), Ok(TimeDelta::seconds(30)));
+    assert_eq!(parse_window("+-2m"), Ok(TimeDelta::minutes(2)));
+    assert_eq!(parse_window("45"), Ok(TimeDelta::seconds(45)));
+    assert!(parse_window("±").is_err());
+    assert!(parse_window("48h").is_err());
+}
+
+#[test]
+fn parse_uses_default_window_and_rejects_extra_args() {
+    let now = at(15, 0, 0);
+    let range = TimeRange::parse(&["14:32".to_string()], now, DisplayZone::Local).unwrap();
+    assert_eq!(range.start, at(14, 32, 0) - DEFAULT_WINDOW);
+    assert_eq!(range.label(DisplayZone::Local), "14:31:30–14:32:30");
+
+    let args: Vec<String> = ["14:32", "±1m", "x"].map(String::from).into();
+    assert!(TimeRange::parse(&args, now, DisplayZone::Local).is_err());
+}
+
+#[test]
+fn slices_time_ordered_buffer_by_binary_search() {
+    let logs: Vec<LogLine> = (0..10).map(|m| line(at(14, 30 + m, 0))).collect();
+
+    let range = TimeRange::around(at(14, 33, 30), TimeDelta::seconds(60));
+    let inside: Vec<&str> = range
+        .slice(&logs)
+        .iter()
+        .map(|l| l.content.as_str())
+        .collect();
+    assert_eq!(inside, ["14:33:00", "14:34:00"]);
+    assert_eq!(first_at_or_after(&logs, at(14, 33, 30)), 4);
+}
+
+#[test]
+fn slice_handles_ranges_outside_the_retained_buffer() {
+    let logs: Vec<LogLine> = (0..5).map(|m| line(at(14, 30 + m, 0))).collect();
+
+    // Entirely before the oldest retained line
+    let before = TimeRange::around(at(14, 0, 0), TimeDelta::minutes(5));
+    assert!(before.slice(&logs).is_empty());
+
+    // Starts before the buffer: everything up to the end of the window
+    let overlapping = TimeRange::around(at(14, 29, 0), TimeDelta::minutes(2));
+    assert_eq!(overlapping.slice(&logs).len(), 2);
+
+    // After the newest line
+    let after = TimeRange::around(at(16, 0, 0), TimeDelta::minutes(1));
+    assert!(after.slice(&logs).is_empty());
+    assert_eq!(first_at_or_after(&logs, at(16, 0, 0)), logs.len());
+}
+
+#[test]
+fn between_starts_at_its_start_and_notes_a_short_buffer() {
+    let range = TimeRange::between(at(14, 32, 0), at(14, 32, 10));
+    assert_eq!(range.center, at(14, 32, 0));
+    assert!(range.contains(at(14, 32, 10)));
+
+    let logs = vec![line(at(14, 32, 5))];
+    assert_eq!(
+        range.showing_message(&logs, DisplayZone::Local),
+        "Showing logs 14:32:00–14:32:10 - buffer starts at 14:32:05"
+    );
+    assert_eq!(
+        range.showing_message(&[], DisplayZone::Local),
+        "Showing logs 14:32:00–14:32:10"
+    );
+}